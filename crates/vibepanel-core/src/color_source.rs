@@ -0,0 +1,228 @@
+//! Colors from a wallpaper color scheme generator.
+//!
+//! With `theme.source = "pywal"` or `"matugen"`, the generator's JSON output
+//! is read when the config is loaded and fills in the mode, bar and widget
+//! backgrounds, accent and state colors, like a theme preset. Keys set in the
+//! config still win. The bar watches the file and reloads the config when it
+//! changes, so the colors follow the wallpaper.
+//!
+//! - pywal: `~/.cache/wal/colors.json`, written by `wal`
+//! - matugen: the output of `matugen image <wallpaper> --json hex`, by
+//!   default read from `~/.cache/matugen/colors.json`
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::theme::{is_dark_color, parse_color};
+
+/// Known valid values for theme.source.
+pub const VALID_THEME_SOURCES: &[&str] = &["pywal", "matugen"];
+
+/// Colors read from a generator's output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceColors {
+    /// Theme mode, "dark" or "light".
+    pub mode: &'static str,
+    /// Bar background color.
+    pub bar_background: String,
+    /// Widget background color.
+    pub widget_background: String,
+    /// Accent color.
+    pub accent: String,
+    /// Success state color, if the scheme has one.
+    pub success: Option<String>,
+    /// Warning state color, if the scheme has one.
+    pub warning: Option<String>,
+    /// Urgent state color.
+    pub urgent: String,
+}
+
+/// The file `source` is read from: `path` with `~` expanded, or the
+/// generator's default location.
+pub fn source_path(source: &str, path: Option<&str>) -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok();
+    let cache = std::env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| PathBuf::from(home).join(".cache")));
+
+    match path {
+        Some(path) => Some(expand_home(path)),
+        None => match source {
+            "pywal" => cache.map(|dir| dir.join("wal/colors.json")),
+            "matugen" => cache.map(|dir| dir.join("matugen/colors.json")),
+            _ => None,
+        },
+    }
+}
+
+/// `path` with a leading `~` replaced by `$HOME`.
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{}{}", home, rest))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Read and map the colors of `source` from `path`.
+///
+/// `light` picks the light scheme for generators that write both.
+pub fn load(source: &str, path: &std::path::Path, light: bool) -> Result<SourceColors, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let json: Value = serde_json::from_str(&text)
+        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+    match source {
+        "pywal" => parse_pywal(&json),
+        "matugen" => parse_matugen(&json, light),
+        other => Err(format!("unknown theme source '{}'", other)),
+    }
+    .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Map pywal's `colors.json`: the special background for the bar, `color0`
+/// for widgets, `color4` as the accent and the red, green and yellow
+/// terminal colors as states.
+fn parse_pywal(json: &Value) -> Result<SourceColors, String> {
+    let background = color(json, &["special", "background"])?;
+    Ok(SourceColors {
+        mode: if is_dark_color(&background) {
+            "dark"
+        } else {
+            "light"
+        },
+        widget_background: color(json, &["colors", "color0"])?,
+        accent: color(json, &["colors", "color4"])?,
+        success: Some(color(json, &["colors", "color2"])?),
+        warning: Some(color(json, &["colors", "color3"])?),
+        urgent: color(json, &["colors", "color1"])?,
+        bar_background: background,
+    })
+}
+
+/// Map matugen's Material colors: `surface` for the bar,
+/// `surface_container` for widgets, `primary` as the accent and `error` as
+/// urgent. Material has no success or warning roles.
+///
+/// Accepts both layouts matugen has written, `colors.<scheme>.<role>` and
+/// `colors.<role>.<scheme>`.
+fn parse_matugen(json: &Value, light: bool) -> Result<SourceColors, String> {
+    let scheme = if light { "light" } else { "dark" };
+    let role = |name: &str| {
+        color(json, &["colors", scheme, name]).or_else(|_| color(json, &["colors", name, scheme]))
+    };
+    Ok(SourceColors {
+        mode: scheme,
+        bar_background: role("surface")?,
+        widget_background: role("surface_container")?,
+        accent: role("primary")?,
+        success: None,
+        warning: None,
+        urgent: role("error")?,
+    })
+}
+
+/// The color at `keys`, checked to parse.
+fn color(json: &Value, keys: &[&str]) -> Result<String, String> {
+    let value = keys
+        .iter()
+        .try_fold(json, |value, key| value.get(key))
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing color {}", keys.join(".")))?;
+    if parse_color(value).is_none() {
+        return Err(format!("invalid color {} '{}'", keys.join("."), value));
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pywal() {
+        let json: Value = serde_json::from_str(
+            r##"{
+                "wallpaper": "/home/user/wall.png",
+                "alpha": "100",
+                "special": { "background": "#0f1419", "foreground": "#c5c8c6", "cursor": "#c5c8c6" },
+                "colors": {
+                    "color0": "#1d2127", "color1": "#cc6666", "color2": "#b5bd68",
+                    "color3": "#f0c674", "color4": "#81a2be", "color5": "#b294bb",
+                    "color6": "#8abeb7", "color7": "#c5c8c6"
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let colors = parse_pywal(&json).unwrap();
+        assert_eq!(colors.mode, "dark");
+        assert_eq!(colors.bar_background, "#0f1419");
+        assert_eq!(colors.widget_background, "#1d2127");
+        assert_eq!(colors.accent, "#81a2be");
+        assert_eq!(colors.success.as_deref(), Some("#b5bd68"));
+        assert_eq!(colors.urgent, "#cc6666");
+
+        let json: Value =
+            serde_json::from_str(r##"{"special": {"background": "#eeeeee"}}"##).unwrap();
+        assert_eq!(
+            parse_pywal(&json).unwrap_err(),
+            "missing color colors.color0"
+        );
+    }
+
+    #[test]
+    fn test_parse_matugen_layouts() {
+        let by_scheme: Value = serde_json::from_str(
+            r##"{"colors": {
+                "dark": { "surface": "#141218", "surface_container": "#211f26",
+                          "primary": "#d0bcff", "error": "#f2b8b5" },
+                "light": { "surface": "#fef7ff", "surface_container": "#f3edf7",
+                           "primary": "#6750a4", "error": "#b3261e" }
+            }}"##,
+        )
+        .unwrap();
+        let by_role: Value = serde_json::from_str(
+            r##"{"colors": {
+                "surface": { "dark": "#141218", "light": "#fef7ff" },
+                "surface_container": { "dark": "#211f26", "light": "#f3edf7" },
+                "primary": { "dark": "#d0bcff", "light": "#6750a4" },
+                "error": { "dark": "#f2b8b5", "light": "#b3261e" }
+            }}"##,
+        )
+        .unwrap();
+
+        for json in [&by_scheme, &by_role] {
+            let dark = parse_matugen(json, false).unwrap();
+            assert_eq!(dark.mode, "dark");
+            assert_eq!(dark.bar_background, "#141218");
+            assert_eq!(dark.accent, "#d0bcff");
+            assert_eq!(dark.success, None);
+
+            let light = parse_matugen(json, true).unwrap();
+            assert_eq!(light.mode, "light");
+            assert_eq!(light.widget_background, "#f3edf7");
+            assert_eq!(light.urgent, "#b3261e");
+        }
+    }
+
+    #[test]
+    fn test_source_path() {
+        assert_eq!(
+            source_path("pywal", Some("/tmp/colors.json")),
+            Some(PathBuf::from("/tmp/colors.json"))
+        );
+        if let Ok(home) = std::env::var("HOME") {
+            assert_eq!(
+                source_path("matugen", Some("~/m.json")),
+                Some(PathBuf::from(home).join("m.json"))
+            );
+        }
+        assert!(source_path("pywal", None).is_none_or(|path| path.ends_with("wal/colors.json")));
+        assert_eq!(source_path("other", None), None);
+    }
+}
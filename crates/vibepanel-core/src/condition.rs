@@ -0,0 +1,220 @@
+//! Widget visibility conditions (`show_if`).
+//!
+//! A condition tests values published by the bar's services, e.g.
+//! `updates.count > 0` or `battery.present && !media.playing`:
+//!
+//! - A term is a variable compared to a number (`>`, `>=`, `<`, `<=`, `==`,
+//!   `!=`), or a bare variable, which is true when non-zero.
+//! - `!` in front of a term negates it.
+//! - Terms combine with `&&` and `||`; `&&` binds tighter. There are no
+//!   parentheses.
+//!
+//! Booleans are 1 or 0. A value that isn't known yet (a battery percentage
+//! before UPower answers) makes its term false before any negation.
+
+/// Variables a condition can refer to.
+pub const VARIABLES: &[&str] = &[
+    "battery.present",
+    "battery.percent",
+    "battery.charging",
+    "updates.count",
+    "media.active",
+    "media.playing",
+    "network.connected",
+    "network.wifi",
+    "bluetooth.powered",
+    "bluetooth.connected",
+];
+
+/// Comparison operator of a term.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    /// Operators by symbol, two-character ones first so `>=` isn't read as `>`.
+    const SYMBOLS: [(&'static str, Op); 6] = [
+        (">=", Op::Ge),
+        ("<=", Op::Le),
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+    ];
+
+    fn apply(self, left: f64, right: f64) -> bool {
+        match self {
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+        }
+    }
+}
+
+/// A single (possibly negated) test of one variable.
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    variable: String,
+    comparison: Option<(Op, f64)>,
+}
+
+impl Term {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (negated, text) = match text.strip_prefix('!') {
+            Some(rest) if !rest.starts_with('=') => (true, rest.trim()),
+            _ => (false, text),
+        };
+
+        let (variable, comparison) = match Op::SYMBOLS
+            .iter()
+            .find_map(|(symbol, op)| text.split_once(symbol).map(|(l, r)| (l, r, *op)))
+        {
+            Some((left, right, op)) => {
+                let right = right.trim();
+                let number = right
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' is not a number", right))?;
+                (left.trim(), Some((op, number)))
+            }
+            None => (text, None),
+        };
+
+        if variable.is_empty() {
+            return Err("missing a variable".to_string());
+        }
+        if !VARIABLES.contains(&variable) {
+            return Err(format!(
+                "unknown variable '{}', expected one of: {}",
+                variable,
+                VARIABLES.join(", ")
+            ));
+        }
+
+        Ok(Self {
+            negated,
+            variable: variable.to_string(),
+            comparison,
+        })
+    }
+
+    fn evaluate(&self, value: Option<f64>) -> bool {
+        let result = match (value, self.comparison) {
+            (None, _) => false,
+            (Some(value), None) => value != 0.0,
+            (Some(value), Some((op, number))) => op.apply(value, number),
+        };
+        result != self.negated
+    }
+}
+
+/// A parsed `show_if` condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Alternatives (`||`), each a list of terms that must all hold (`&&`).
+    any: Vec<Vec<Term>>,
+}
+
+impl Condition {
+    /// Parse a condition, reporting what's wrong with it on failure.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.trim().is_empty() {
+            return Err("empty condition".to_string());
+        }
+        let any = text
+            .split("||")
+            .map(|alternative| alternative.split("&&").map(Term::parse).collect())
+            .collect::<Result<_, _>>()?;
+        Ok(Self { any })
+    }
+
+    /// Variables the condition refers to, without duplicates.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut variables: Vec<&str> = Vec::new();
+        for term in self.any.iter().flatten() {
+            if !variables.contains(&term.variable.as_str()) {
+                variables.push(&term.variable);
+            }
+        }
+        variables
+    }
+
+    /// Evaluate the condition, looking variables up with `value`.
+    pub fn evaluate(&self, value: impl Fn(&str) -> Option<f64>) -> bool {
+        self.any.iter().any(|terms| {
+            terms
+                .iter()
+                .all(|term| term.evaluate(value(&term.variable)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(name: &str) -> Option<f64> {
+        match name {
+            "battery.present" => Some(1.0),
+            "battery.percent" => Some(42.0),
+            "updates.count" => Some(0.0),
+            "media.playing" => Some(0.0),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_evaluate() {
+        let eval = |text: &str| Condition::parse(text).unwrap().evaluate(values);
+
+        assert!(eval("battery.present"));
+        assert!(!eval("updates.count > 0"));
+        assert!(eval("updates.count == 0"));
+        assert!(eval("battery.percent <= 42"));
+        assert!(!eval("battery.percent < 42"));
+        assert!(eval("battery.present && !media.playing"));
+        assert!(eval("updates.count >= 1 || battery.percent != 0"));
+        assert!(!eval("updates.count > 0 || media.playing"));
+    }
+
+    #[test]
+    fn test_unknown_values_are_false() {
+        let condition = Condition::parse("network.connected").unwrap();
+        assert!(!condition.evaluate(values));
+        let condition = Condition::parse("!network.connected").unwrap();
+        assert!(condition.evaluate(values));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Condition::parse("").is_err());
+        assert!(
+            Condition::parse("battery.precent")
+                .unwrap_err()
+                .contains("unknown variable")
+        );
+        assert!(
+            Condition::parse("updates.count > lots")
+                .unwrap_err()
+                .contains("not a number")
+        );
+        assert!(Condition::parse("battery.present &&").is_err());
+    }
+
+    #[test]
+    fn test_variables() {
+        let condition =
+            Condition::parse("updates.count>0 && !media.active || updates.count < 5").unwrap();
+        assert_eq!(condition.variables(), vec!["updates.count", "media.active"]);
+    }
+}
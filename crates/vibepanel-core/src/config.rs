@@ -11,17 +11,45 @@ use std::env;
 use std::path::{Path, PathBuf};
 use toml::Table;
 
+use crate::color_source::{self, SourceColors, VALID_THEME_SOURCES};
+use crate::condition::Condition;
 use crate::error::{Error, Result};
+use crate::substitution;
+use crate::theme::THEME_PRESETS;
 
 /// Known valid values for advanced.compositor.
-const VALID_COMPOSITORS: &[&str] = &["auto", "mango", "hyprland", "niri"];
+const VALID_COMPOSITORS: &[&str] = &[
+    "auto", "mango", "dwl", "hyprland", "niri", "river", "wayfire", "cosmic", "kwin", "generic",
+];
 
 /// Known valid values for theme.mode.
 const VALID_THEME_MODES: &[&str] = &["auto", "dark", "light", "gtk"];
 
+/// Known valid values for bar.position.
+const VALID_BAR_POSITIONS: &[&str] = &["top", "bottom"];
+
+/// Known valid values for bar.mode.
+const VALID_BAR_MODES: &[&str] = &["normal", "text"];
+
+/// Known valid values for theme background image modes.
+const VALID_BACKGROUND_MODES: &[&str] = &["cover", "contain", "tile", "stretch"];
+
 /// Known valid values for osd.position.
 const VALID_OSD_POSITIONS: &[&str] = &["bottom", "left", "right", "top"];
 
+/// Accepted range for night_light temperatures (Kelvin).
+const NIGHT_LIGHT_TEMPERATURE_RANGE: std::ops::RangeInclusive<u32> = 1000..=25000;
+
+/// Accepted range for bluetooth.reconnect_timeout (seconds).
+const BLUETOOTH_RECONNECT_TIMEOUT_RANGE: std::ops::RangeInclusive<u32> = 1..=120;
+
+/// Widgets whose named instances ("cpu:server1") read their options from
+/// their own `[widgets."<type>:<name>"]` section.
+const OWN_SECTION_WIDGETS: &[&str] = &["custom", "cpu", "memory", "temperature"];
+
+/// Highest output volume the bar will set, in percent (100 is unamplified).
+pub const MAX_VOLUME: u32 = 150;
+
 /// Embedded default configuration TOML, compiled into the binary.
 pub const DEFAULT_CONFIG_TOML: &str = include_str!("../../../config.toml");
 
@@ -47,17 +75,71 @@ pub struct Config {
     /// Widget configuration (left, center, right sections).
     pub widgets: WidgetsConfig,
 
+    /// Additional bars (`[[bars]]`), e.g. a dock on the other screen edge.
+    pub bars: Vec<ExtraBarConfig>,
+
     /// Theme configuration (colors, typography, icons).
     pub theme: ThemeConfig,
 
     /// On-screen display configuration.
     pub osd: OsdConfig,
 
+    /// Audio behavior (jack handling).
+    pub audio: AudioConfig,
+
+    /// Bluetooth behavior (reconnecting the last audio device).
+    pub bluetooth: BluetoothConfig,
+
+    /// Night light (color temperature) scheduling.
+    pub night_light: NightLightConfig,
+
+    /// Per-workspace settings (accent colors).
+    pub workspace: WorkspaceConfig,
+
     /// Advanced configuration options.
     pub advanced: AdvancedConfig,
 }
 
 impl Config {
+    /// Configuration for the bar on `output`, with its
+    /// `[bar.output."<connector>"]` override applied.
+    pub fn for_output(&self, output: &str) -> Config {
+        let mut config = self.clone();
+        config.bar = self.bar.for_output(output);
+        if let Some(over) = self.bar.output.get(output) {
+            for (section, placements) in [
+                (&mut config.widgets.left, &over.left),
+                (&mut config.widgets.center, &over.center),
+                (&mut config.widgets.right, &over.right),
+            ] {
+                if let Some(placements) = placements {
+                    *section = placements.clone();
+                }
+            }
+        }
+        config
+    }
+
+    /// Configuration for an additional bar from `[[bars]]`.
+    ///
+    /// `[bar.output."<connector>"]` overrides belong to the main bar and
+    /// are dropped.
+    pub fn for_bar(&self, bar: &ExtraBarConfig) -> Config {
+        let mut config = self.clone();
+        if let Some(size) = bar.size {
+            config.bar.size = size;
+        }
+        if let Some(ref position) = bar.position {
+            config.bar.position = position.clone();
+        }
+        config.bar.outputs = bar.outputs.clone();
+        config.bar.output.clear();
+        config.widgets.left = bar.left.clone();
+        config.widgets.center = bar.center.clone();
+        config.widgets.right = bar.right.clone();
+        config
+    }
+
     /// Load configuration from an embedded default TOML string.
     pub fn from_default_toml() -> Result<Self> {
         let config: Config = toml::from_str(DEFAULT_CONFIG_TOML)?;
@@ -90,13 +172,59 @@ impl Config {
     /// Load configuration from a TOML string, merging with embedded defaults.
     ///
     /// This parses both the default config and user config as TOML tables,
-    /// deep-merges them (user values win), then deserializes the result.
+    /// expands `${VAR}` (and, if enabled, `$(cmd)`) placeholders in the user
+    /// config's strings, deep-merges them (user values win), then
+    /// deserializes the result.
     fn load_with_defaults(user_toml: &str) -> Result<Self> {
         // This should never fail since it's embedded and tested
         let mut base: Table = toml::from_str(DEFAULT_CONFIG_TOML)
             .expect("embedded DEFAULT_CONFIG_TOML should always be valid");
 
-        let user: Table = toml::from_str(user_toml)?;
+        let mut user: Table = toml::from_str(user_toml)?;
+        let commands = user
+            .get("advanced")
+            .and_then(|advanced| advanced.get("config_commands"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        substitution::expand_table(&mut user, commands).map_err(Error::ConfigSubstitution)?;
+
+        // A preset sits between the defaults and the user's own keys
+        if let Some(preset) = user
+            .get("theme")
+            .and_then(|theme| theme.get("preset"))
+            .and_then(|v| v.as_str())
+            .and_then(crate::theme::theme_preset)
+        {
+            deep_merge_toml(
+                &mut base,
+                theme_colors_table(ThemeColors {
+                    mode: preset.mode,
+                    bar_background: preset.bar_background,
+                    widget_background: preset.widget_background,
+                    accent: preset.accent,
+                    success: Some(preset.success),
+                    warning: Some(preset.warning),
+                    urgent: preset.urgent,
+                }),
+            );
+        }
+
+        // Then wallpaper colors; a missing or broken file (the generator may
+        // not have run yet) leaves the colors as they are
+        if let Some(colors) = load_source_colors(&user) {
+            deep_merge_toml(
+                &mut base,
+                theme_colors_table(ThemeColors {
+                    mode: colors.mode,
+                    bar_background: &colors.bar_background,
+                    widget_background: &colors.widget_background,
+                    accent: &colors.accent,
+                    success: colors.success.as_deref(),
+                    warning: colors.warning.as_deref(),
+                    urgent: &colors.urgent,
+                }),
+            );
+        }
 
         deep_merge_toml(&mut base, user);
 
@@ -186,6 +314,12 @@ impl Config {
         paths
     }
 
+    /// File the `theme.source` colors are read from, if a source is set.
+    pub fn theme_source_path(&self) -> Option<PathBuf> {
+        let source = self.theme.source.as_deref()?;
+        color_source::source_path(source, self.theme.source_path.as_deref())
+    }
+
     /// Validate the configuration, returning errors for invalid values.
     ///
     /// This performs strict validation - any invalid value causes an error.
@@ -210,22 +344,66 @@ impl Config {
             ));
         }
 
-        // Validate theme.accent: must be "gtk", "none", or a valid hex color (if specified)
+        // Validate theme.preset
+        if let Some(ref preset) = self.theme.preset
+            && crate::theme::theme_preset(preset).is_none()
+        {
+            let names: Vec<&str> = THEME_PRESETS.iter().map(|p| p.name).collect();
+            errors.push(format!(
+                "theme.preset: unknown preset '{}', expected one of: {}",
+                preset,
+                names.join(", ")
+            ));
+        }
+
+        // Validate theme.source
+        if let Some(ref source) = self.theme.source
+            && !VALID_THEME_SOURCES.contains(&source.as_str())
+        {
+            errors.push(format!(
+                "theme.source: invalid value '{}', expected one of: {}",
+                source,
+                VALID_THEME_SOURCES.join(", ")
+            ));
+        }
+
+        // Validate theme.accent: must be "gtk", "none", "wallpaper", or a valid color (if specified)
         if let Some(ref accent) = self.theme.accent
             && accent != "gtk"
             && accent != "none"
+            && accent != "wallpaper"
+            && crate::theme::parse_color(accent).is_none()
         {
-            // Must be a hex color
-            let is_valid_hex = accent.starts_with('#') && {
-                let hex = accent.trim_start_matches('#');
-                (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
-            };
-            if !is_valid_hex {
-                errors.push(format!(
-                    "theme.accent: invalid value '{}', expected 'gtk', 'none', or a hex color like '#3584e4'",
-                    accent
-                ));
-            }
+            errors.push(format!(
+                "theme.accent: invalid value '{}', expected 'gtk', 'none', 'wallpaper', or a color like '#3584e4', 'rgb(53, 132, 228)' or 'royalblue'",
+                accent
+            ));
+        }
+
+        // Validate theme backgrounds
+        if let Some(ref background) = self.theme.bar_background {
+            background.validate("theme.bar_background", &mut errors);
+        }
+        if let Some(ref background) = self.theme.widget_background {
+            background.validate("theme.widget_background", &mut errors);
+        }
+
+        // Validate bar.position
+        if !VALID_BAR_POSITIONS.contains(&self.bar.position.as_str()) {
+            errors.push(format!(
+                "bar.position: invalid value '{}', expected one of: {}",
+                self.bar.position,
+                VALID_BAR_POSITIONS.join(", ")
+            ));
+        }
+
+        // Validate bar.mode
+        if !VALID_BAR_MODES.contains(&self.bar.mode.as_str()) {
+            errors.push(format!(
+                "bar.mode: invalid value '{}', expected one of: {}",
+                self.bar.mode,
+                VALID_BAR_MODES.join(", ")
+            ));
         }
 
         // Validate osd.position
@@ -237,6 +415,142 @@ impl Config {
             ));
         }
 
+        // Validate night_light
+        let night_light = &self.night_light;
+        for (key, value) in [
+            ("day_temperature", night_light.day_temperature),
+            ("night_temperature", night_light.night_temperature),
+        ] {
+            if !NIGHT_LIGHT_TEMPERATURE_RANGE.contains(&value) {
+                errors.push(format!(
+                    "night_light.{}: invalid value '{}', must be between {} and {} K",
+                    key,
+                    value,
+                    NIGHT_LIGHT_TEMPERATURE_RANGE.start(),
+                    NIGHT_LIGHT_TEMPERATURE_RANGE.end()
+                ));
+            }
+        }
+        match (night_light.latitude, night_light.longitude) {
+            (Some(latitude), Some(longitude)) => {
+                if !(-90.0..=90.0).contains(&latitude) {
+                    errors.push(format!(
+                        "night_light.latitude: invalid value '{}', must be between -90 and 90",
+                        latitude
+                    ));
+                }
+                if !(-180.0..=180.0).contains(&longitude) {
+                    errors.push(format!(
+                        "night_light.longitude: invalid value '{}', must be between -180 and 180",
+                        longitude
+                    ));
+                }
+            }
+            (None, None) => {}
+            _ => {
+                errors.push("night_light: latitude and longitude must be set together".to_string())
+            }
+        }
+
+        // Validate bluetooth.reconnect_timeout
+        if !BLUETOOTH_RECONNECT_TIMEOUT_RANGE.contains(&self.bluetooth.reconnect_timeout) {
+            errors.push(format!(
+                "bluetooth.reconnect_timeout: invalid value '{}', must be between {} and {} seconds",
+                self.bluetooth.reconnect_timeout,
+                BLUETOOTH_RECONNECT_TIMEOUT_RANGE.start(),
+                BLUETOOTH_RECONNECT_TIMEOUT_RANGE.end()
+            ));
+        }
+
+        // Validate workspace colors
+        let mut workspace_colors: Vec<_> = self.workspace.colors.iter().collect();
+        workspace_colors.sort();
+        for (workspace, color) in workspace_colors {
+            if crate::theme::parse_color(color).is_none() {
+                errors.push(format!(
+                    "workspace.colors.{}: invalid color '{}'",
+                    workspace, color
+                ));
+            }
+        }
+
+        // Validate audio volumes
+        for (key, value) in [
+            ("max_volume", self.audio.max_volume),
+            ("safe_listening_volume", self.audio.safe_listening_volume),
+        ] {
+            if !(1..=MAX_VOLUME).contains(&value) {
+                errors.push(format!(
+                    "audio.{}: invalid value '{}', must be between 1 and {}",
+                    key, value, MAX_VOLUME
+                ));
+            }
+        }
+
+        // Validate widget visibility conditions
+        let mut widget_names: Vec<_> = self.widgets.widget_configs.keys().collect();
+        widget_names.sort();
+        for name in widget_names {
+            let Some(show_if) = self.widgets.widget_configs[name].options.get("show_if") else {
+                continue;
+            };
+            let result = show_if
+                .as_str()
+                .ok_or_else(|| "must be a string".to_string())
+                .and_then(|text| Condition::parse(text).map(|_| ()));
+            if let Err(e) = result {
+                errors.push(format!("widgets.{}.show_if: {}", name, e));
+            }
+        }
+
+        // Validate per-output overrides
+        let mut outputs: Vec<_> = self.bar.output.iter().collect();
+        outputs.sort_by(|a, b| a.0.cmp(b.0));
+        for (output, over) in outputs {
+            if let Some(ref position) = over.position
+                && !VALID_BAR_POSITIONS.contains(&position.as_str())
+            {
+                errors.push(format!(
+                    "bar.output.\"{}\".position: invalid value '{}', expected one of: {}",
+                    output,
+                    position,
+                    VALID_BAR_POSITIONS.join(", ")
+                ));
+            }
+            if over.size == Some(0) {
+                errors.push(format!(
+                    "bar.output.\"{}\".size: must be greater than 0",
+                    output
+                ));
+            }
+        }
+
+        // Validate additional bars
+        let mut bar_names = std::collections::HashSet::new();
+        for (index, bar) in self.bars.iter().enumerate() {
+            if bar.name.is_empty() {
+                errors.push(format!("bars[{}].name: must be set", index));
+            } else if !bar_names.insert(bar.name.as_str()) {
+                errors.push(format!(
+                    "bars[{}].name: '{}' is already used by another bar",
+                    index, bar.name
+                ));
+            }
+            if let Some(ref position) = bar.position
+                && !VALID_BAR_POSITIONS.contains(&position.as_str())
+            {
+                errors.push(format!(
+                    "bars[{}].position: invalid value '{}', expected one of: {}",
+                    index,
+                    position,
+                    VALID_BAR_POSITIONS.join(", ")
+                ));
+            }
+            if bar.size == Some(0) {
+                errors.push(format!("bars[{}].size: must be greater than 0", index));
+            }
+        }
+
         // Validate numeric ranges
         if self.bar.size == 0 {
             errors.push("bar.size: must be greater than 0".to_string());
@@ -277,13 +591,38 @@ impl Config {
 
         // Check for widget configs that aren't referenced in any placement array
         let unreferenced = self.widgets.unreferenced_configs();
-        for name in unreferenced {
+        let output_sections = self.bar.output.values().flat_map(|over| {
+            [&over.left, &over.center, &over.right]
+                .into_iter()
+                .flatten()
+                .flatten()
+        });
+        let bar_sections = self
+            .bars
+            .iter()
+            .flat_map(|bar| [&bar.left, &bar.center, &bar.right].into_iter().flatten());
+        let in_outputs: std::collections::HashSet<&str> = output_sections
+            .chain(bar_sections)
+            .flat_map(|placement| placement.widget_names())
+            .collect();
+        for name in unreferenced
+            .into_iter()
+            .filter(|name| !in_outputs.contains(name.as_str()))
+        {
             warnings.push(format!(
                 "widgets.{}: config defined but widget not used in any section (possible typo?)",
                 name
             ));
         }
 
+        if self.theme.bar_background.is_some() && self.bar.background_opacity <= 0.0 {
+            warnings.push(
+                "theme.bar_background: has no effect while bar.background_opacity is 0; \
+                 raise it to show the bar background"
+                    .to_string(),
+            );
+        }
+
         // Check for spacer widgets in center section (they have no effect there)
         for placement in &self.widgets.center {
             for name in placement.widget_names() {
@@ -307,8 +646,28 @@ impl Config {
         let mut lines = Vec::new();
 
         lines.push("Bar Configuration:".to_string());
+        lines.push(format!("  position: {}", self.bar.position));
+        lines.push(format!("  mode: {}", self.bar.mode));
+        if self.bar.on_demand {
+            lines.push("  on_demand: true".to_string());
+        }
+        if self.bar.hide_on_fullscreen {
+            lines.push("  hide_on_fullscreen: true".to_string());
+        }
         lines.push(format!("  size: {}px", self.bar.size));
         lines.push(format!("  spacing: {}px", self.bar.spacing));
+        for (section, spacing) in [
+            ("left", self.bar.section_spacing.left),
+            ("center", self.bar.section_spacing.center),
+            ("right", self.bar.section_spacing.right),
+        ] {
+            if let Some(spacing) = spacing {
+                lines.push(format!("  {} spacing: {}px", section, spacing));
+            }
+        }
+        if self.bar.group_spacing > 0 {
+            lines.push(format!("  group_spacing: {}px", self.bar.group_spacing));
+        }
         lines.push(format!("  screen_margin: {}px", self.bar.screen_margin));
         lines.push(format!(
             "  background_opacity: {}",
@@ -320,6 +679,46 @@ impl Config {
         if !self.bar.outputs.is_empty() {
             lines.push(format!("  outputs: {:?}", self.bar.outputs));
         }
+        let mut outputs: Vec<_> = self.bar.output.iter().collect();
+        outputs.sort_by(|a, b| a.0.cmp(b.0));
+        for (output, over) in outputs {
+            let mut parts = Vec::new();
+            if !over.enabled {
+                parts.push("disabled".to_string());
+            }
+            if let Some(size) = over.size {
+                parts.push(format!("size {}px", size));
+            }
+            if let Some(ref position) = over.position {
+                parts.push(position.clone());
+            }
+            for (name, section) in [
+                ("left", &over.left),
+                ("center", &over.center),
+                ("right", &over.right),
+            ] {
+                if let Some(section) = section {
+                    parts.push(format!("{}: {} widget(s)", name, count_widgets(section)));
+                }
+            }
+            lines.push(format!("  output {}: {}", output, parts.join(", ")));
+        }
+        for bar in &self.bars {
+            let mut parts = Vec::new();
+            if let Some(ref position) = bar.position {
+                parts.push(position.clone());
+            }
+            if let Some(size) = bar.size {
+                parts.push(format!("size {}px", size));
+            }
+            if !bar.outputs.is_empty() {
+                parts.push(format!("outputs {:?}", bar.outputs));
+            }
+            let widgets =
+                count_widgets(&bar.left) + count_widgets(&bar.center) + count_widgets(&bar.right);
+            parts.push(format!("{} widget(s)", widgets));
+            lines.push(format!("  bar {}: {}", bar.name, parts.join(", ")));
+        }
 
         lines.push("\nWidgets:".to_string());
         lines.push(format!(
@@ -355,6 +754,12 @@ impl Config {
 
         lines.push("\nTheme:".to_string());
         lines.push(format!("  mode: {}", self.theme.mode));
+        if let Some(ref preset) = self.theme.preset {
+            lines.push(format!("  preset: {}", preset));
+        }
+        if let Some(ref source) = self.theme.source {
+            lines.push(format!("  source: {}", source));
+        }
         lines.push(format!(
             "  accent: {}",
             self.theme.accent.as_deref().unwrap_or("(auto)")
@@ -365,6 +770,37 @@ impl Config {
         ));
         lines.push(format!("  icon_theme: {}", self.theme.icons.theme));
         lines.push(format!("  icon_weight: {}", self.theme.icons.weight));
+        if self.theme.icons.theme == "nerd" {
+            lines.push(format!("  icon_font: {}", self.theme.icons.font));
+        }
+        if !self.theme.icons.overrides.is_empty() {
+            lines.push(format!(
+                "  icon_overrides: {}",
+                self.theme.icons.overrides.len()
+            ));
+        }
+        if !self.theme.icons.map.is_empty() {
+            lines.push(format!("  icon_map: {}", self.theme.icons.map.len()));
+        }
+        for (key, background) in [
+            ("bar_background", &self.theme.bar_background),
+            ("widget_background", &self.theme.widget_background),
+        ] {
+            match background {
+                Some(b) if !b.gradient.is_empty() => lines.push(format!(
+                    "  {}: gradient [{}] at {}deg",
+                    key,
+                    b.gradient.join(", "),
+                    b.angle
+                )),
+                Some(BackgroundConfig {
+                    image: Some(image),
+                    mode,
+                    ..
+                }) => lines.push(format!("  {}: image {} ({})", key, image, mode)),
+                _ => {}
+            }
+        }
 
         lines.push("\nAdvanced:".to_string());
         lines.push(format!("  compositor: {}", self.advanced.compositor));
@@ -374,11 +810,73 @@ impl Config {
             "  enabled: {}, position: {}, timeout: {}ms",
             self.osd.enabled, self.osd.position, self.osd.timeout_ms
         ));
+        if self.osd.suppress_fullscreen || !self.osd.suppress_apps.is_empty() {
+            lines.push(format!(
+                "  suppress: fullscreen={}, apps=[{}], notify={}",
+                self.osd.suppress_fullscreen,
+                self.osd.suppress_apps.join(", "),
+                self.osd.notify_when_suppressed
+            ));
+        }
+
+        let audio = &self.audio;
+        if *audio != AudioConfig::default() {
+            lines.push("\nAudio:".to_string());
+            if audio.mute_on_unplug {
+                lines.push("  mute_on_unplug: true".to_string());
+            }
+            lines.push(format!(
+                "  max_volume: {}%, warn_overdrive: {}",
+                audio.max_volume, audio.warn_overdrive
+            ));
+            if audio.safe_listening_minutes > 0 {
+                lines.push(format!(
+                    "  safe_listening: {} min at {}%",
+                    audio.safe_listening_minutes, audio.safe_listening_volume
+                ));
+            }
+            if audio.mic_mute_key || !audio.mic_mute_led {
+                lines.push(format!(
+                    "  mic_mute_key: {}, mic_mute_led: {}",
+                    audio.mic_mute_key, audio.mic_mute_led
+                ));
+            }
+        }
+
+        if self.bluetooth.auto_reconnect {
+            lines.push("\nBluetooth:".to_string());
+            lines.push(format!(
+                "  auto_reconnect: true, timeout: {}s",
+                self.bluetooth.reconnect_timeout
+            ));
+        }
 
         lines.join("\n")
     }
 }
 
+/// Read the colors of the user's `theme.source`, if set.
+///
+/// The user's own `theme.mode = "light"` picks the light scheme of
+/// generators that write both.
+fn load_source_colors(user: &Table) -> Option<SourceColors> {
+    let theme = user.get("theme")?;
+    let source = theme.get("source")?.as_str()?;
+    if !VALID_THEME_SOURCES.contains(&source) {
+        return None;
+    }
+    let path =
+        color_source::source_path(source, theme.get("source_path").and_then(|v| v.as_str()))?;
+    let light = theme.get("mode").and_then(|v| v.as_str()) == Some("light");
+    match color_source::load(source, &path, light) {
+        Ok(colors) => Some(colors),
+        Err(e) => {
+            tracing::warn!("theme.source: {}", e);
+            None
+        }
+    }
+}
+
 /// Deep merge two TOML tables, with `overlay` values taking precedence.
 ///
 /// For nested tables, recursively merges. For arrays and other values,
@@ -398,16 +896,86 @@ fn deep_merge_toml(base: &mut Table, overlay: Table) {
     }
 }
 
+/// Colors set by a theme preset or `theme.source`.
+struct ThemeColors<'a> {
+    mode: &'a str,
+    bar_background: &'a str,
+    widget_background: &'a str,
+    accent: &'a str,
+    success: Option<&'a str>,
+    warning: Option<&'a str>,
+    urgent: &'a str,
+}
+
+/// The keys `colors` sets, as a table to merge under the user's own.
+fn theme_colors_table(colors: ThemeColors) -> Table {
+    let mut states = Table::new();
+    if let Some(success) = colors.success {
+        states.insert("success".into(), success.into());
+    }
+    if let Some(warning) = colors.warning {
+        states.insert("warning".into(), warning.into());
+    }
+    states.insert("urgent".into(), colors.urgent.into());
+
+    let mut theme = Table::new();
+    theme.insert("mode".into(), colors.mode.into());
+    theme.insert("accent".into(), colors.accent.into());
+    theme.insert("states".into(), states.into());
+
+    let mut bar = Table::new();
+    bar.insert("background_color".into(), colors.bar_background.into());
+
+    let mut widgets = Table::new();
+    widgets.insert("background_color".into(), colors.widget_background.into());
+
+    let mut table = Table::new();
+    table.insert("theme".into(), theme.into());
+    table.insert("bar".into(), bar.into());
+    table.insert("widgets".into(), widgets.into());
+    table
+}
+
 /// Bar-level configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct BarConfig {
+    /// Screen edge the bar is anchored to: "top" or "bottom".
+    /// Popovers, quick settings and tooltips open away from this edge.
+    /// Default: "top"
+    pub position: String,
+
+    /// Rendering mode: "normal" or "text".
+    /// "text" replaces every icon with a text label and widens spacing and
+    /// contrast, for screen readers, braille displays and minimal setups.
+    /// Default: "normal"
+    pub mode: String,
+
+    /// Overlay on demand: the bar reserves no screen space and stays hidden
+    /// until the pointer touches its screen edge or `vibepanel bar toggle`
+    /// is run, then hides again when the pointer leaves it.
+    /// Default: false
+    pub on_demand: bool,
+
+    /// Hide the bar on an output while its focused window is fullscreen,
+    /// and bring it back when fullscreen ends.
+    /// Default: false
+    pub hide_on_fullscreen: bool,
+
     /// Base height of the bar in pixels.
     pub size: u32,
 
     /// Spacing between widgets in pixels.
     pub spacing: u32,
 
+    /// Per-section spacing between widgets in pixels, overriding `spacing`.
+    /// Example: `section_spacing = { left = 4, right = 12 }`
+    pub section_spacing: SectionSpacing,
+
+    /// Extra gap between the widgets inside a group island in pixels.
+    /// Default: 0
+    pub group_spacing: u32,
+
     /// Distance from screen edge to bar window in pixels.
     pub screen_margin: u32,
 
@@ -422,7 +990,7 @@ pub struct BarConfig {
     pub border_radius: u32,
 
     /// Vertical offset between widgets and their popovers/quick settings (in pixels).
-    /// This creates a gap between the bar and any popover or panel that opens below it.
+    /// This creates a gap between the bar and any popover or panel that opens from it.
     /// Default: 1
     pub popover_offset: u32,
 
@@ -438,13 +1006,23 @@ pub struct BarConfig {
     /// Bar background opacity (0.0 = fully transparent, 1.0 = fully opaque).
     /// Default: 0.0 (transparent bar for "islands" look).
     pub background_opacity: f64,
+
+    /// Per-output overrides, keyed by connector name.
+    /// Example: `[bar.output."HDMI-A-1"]` with `size = 24` and `right = ["clock"]`
+    pub output: HashMap<String, BarOutputConfig>,
 }
 
 impl Default for BarConfig {
     fn default() -> Self {
         Self {
+            position: "top".to_string(),
+            mode: "normal".to_string(),
+            on_demand: false,
+            hide_on_fullscreen: false,
             size: 32,
             spacing: 8,
+            section_spacing: SectionSpacing::default(),
+            group_spacing: 0,
             screen_margin: 0,
             inset: 8,
             padding: 4,
@@ -453,10 +1031,157 @@ impl Default for BarConfig {
             outputs: Vec::new(),
             background_color: None,
             background_opacity: 0.0,
+            output: HashMap::new(),
+        }
+    }
+}
+
+impl BarConfig {
+    /// Whether the bar is anchored to the bottom screen edge.
+    pub fn is_bottom(&self) -> bool {
+        self.position == "bottom"
+    }
+
+    /// Whether the bar renders text labels instead of icons.
+    pub fn is_text_mode(&self) -> bool {
+        self.mode == "text"
+    }
+
+    /// Whether `output` gets a bar (`enabled = false` in its override
+    /// turns it off).
+    pub fn is_output_enabled(&self, output: &str) -> bool {
+        self.output.get(output).is_none_or(|o| o.enabled)
+    }
+
+    /// Bar settings for `output`, with its override applied.
+    pub fn for_output(&self, output: &str) -> BarConfig {
+        let mut bar = self.clone();
+        if let Some(over) = self.output.get(output) {
+            if let Some(size) = over.size {
+                bar.size = size;
+            }
+            if let Some(ref position) = over.position {
+                bar.position = position.clone();
+            }
+        }
+        bar
+    }
+
+    /// Spacing between widgets in pixels, widened by half in text mode so
+    /// adjacent labels stay distinguishable.
+    pub fn widget_spacing(&self) -> u32 {
+        self.widen_spacing(self.spacing)
+    }
+
+    /// Spacing between widgets in `section` ("left", "center" or "right"),
+    /// falling back to `spacing` and widened in text mode like it.
+    pub fn section_widget_spacing(&self, section: &str) -> u32 {
+        let spacing = match section {
+            "left" => self.section_spacing.left,
+            "center" => self.section_spacing.center,
+            "right" => self.section_spacing.right,
+            _ => None,
+        };
+        self.widen_spacing(spacing.unwrap_or(self.spacing))
+    }
+
+    fn widen_spacing(&self, spacing: u32) -> u32 {
+        if self.is_text_mode() {
+            spacing + spacing / 2
+        } else {
+            spacing
+        }
+    }
+}
+
+/// Spacing overrides for the bar sections (`[bar.section_spacing]`).
+///
+/// Unset sections use `bar.spacing`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SectionSpacing {
+    /// Spacing between widgets in the left section in pixels.
+    pub left: Option<u32>,
+
+    /// Spacing between widgets in the center section in pixels.
+    pub center: Option<u32>,
+
+    /// Spacing between widgets in the right section in pixels.
+    pub right: Option<u32>,
+}
+
+/// Overrides for the bar on one output (`[bar.output."<connector>"]`).
+///
+/// Unset fields fall back to `[bar]` and `[widgets]`. A section set here
+/// replaces the matching `[widgets]` section on that output; per-widget
+/// options stay shared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BarOutputConfig {
+    /// Whether this output gets a bar.
+    /// Default: true
+    pub enabled: bool,
+
+    /// Base height of the bar in pixels.
+    pub size: Option<u32>,
+
+    /// Screen edge the bar is anchored to: "top" or "bottom".
+    pub position: Option<String>,
+
+    /// Widgets in the left section.
+    pub left: Option<Vec<WidgetPlacement>>,
+
+    /// Widgets in the center section.
+    pub center: Option<Vec<WidgetPlacement>>,
+
+    /// Widgets in the right section.
+    pub right: Option<Vec<WidgetPlacement>>,
+}
+
+impl Default for BarOutputConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            size: None,
+            position: None,
+            left: None,
+            center: None,
+            right: None,
         }
     }
 }
 
+/// An additional bar (`[[bars]]`), e.g. a dock alongside the main bar.
+///
+/// Unset fields fall back to `[bar]`. The widget sections start empty;
+/// options in `[widgets.<name>]` are shared with the main bar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExtraBarConfig {
+    /// Unique name, used in logs and in the bar window's CSS class
+    /// (`extra-bar-<name>`).
+    pub name: String,
+
+    /// Base height of the bar in pixels.
+    pub size: Option<u32>,
+
+    /// Screen edge the bar is anchored to: "top" or "bottom".
+    pub position: Option<String>,
+
+    /// Output allow-list for this bar.
+    /// If empty, the bar is created on all monitors.
+    pub outputs: Vec<String>,
+
+    /// Widgets in the left section.
+    pub left: Vec<WidgetPlacement>,
+
+    /// Widgets in the center section.
+    pub center: Vec<WidgetPlacement>,
+
+    /// Widgets in the right section.
+    pub right: Vec<WidgetPlacement>,
+}
+
 /// Widget section configuration.
 ///
 /// Widget placement is defined using simple name strings or groups of names.
@@ -567,14 +1292,28 @@ impl WidgetsConfig {
     /// Supports inline spacer width syntax like "spacer:50".
     /// This is intentionally special-cased: the inline value is parsed and injected
     /// into the resolved entry as `options["width"]`.
+    ///
+    /// Remote module slots use the same syntax ("remote:weather"), with the inline
+    /// value injected as `options["id"]`.
+    ///
+    /// Custom command widgets ("custom:uptime") also get `options["id"]`, but
+    /// read their options from their own `[widgets."custom:uptime"]` section so
+    /// several can be configured side by side. Stats widgets do the same
+    /// ("cpu:server1"), e.g. to show the local and a remote machine's CPU.
     fn resolve_widget(&self, name: &str) -> Option<WidgetEntry> {
         let (base_name, inline_arg) = Self::parse_inline_arg(name);
 
-        if self.is_disabled(base_name) {
+        let options_name = if OWN_SECTION_WIDGETS.contains(&base_name) && inline_arg.is_some() {
+            name
+        } else {
+            base_name
+        };
+
+        if self.is_disabled(options_name) {
             return None;
         }
 
-        let mut entry = if let Some(opts) = self.get_options(base_name) {
+        let mut entry = if let Some(opts) = self.get_options(options_name) {
             WidgetEntry::with_options(base_name, opts)
         } else {
             WidgetEntry::new(base_name)
@@ -599,6 +1338,14 @@ impl WidgetsConfig {
             }
         }
 
+        if (base_name == "remote" || base_name == "custom")
+            && let Some(arg) = inline_arg
+        {
+            entry
+                .options
+                .insert("id".to_string(), toml::Value::String(arg.to_string()));
+        }
+
         Some(entry)
     }
 
@@ -607,7 +1354,10 @@ impl WidgetsConfig {
     pub fn resolve_placement(&self, placement: &WidgetPlacement) -> Option<WidgetOrGroup> {
         match placement {
             WidgetPlacement::Single(name) => self.resolve_widget(name).map(WidgetOrGroup::Single),
-            WidgetPlacement::Group { group } => {
+            WidgetPlacement::Group {
+                group,
+                shared_popover,
+            } => {
                 let resolved: Vec<WidgetEntry> = group
                     .iter()
                     .filter_map(|name| self.resolve_widget(name))
@@ -616,7 +1366,10 @@ impl WidgetsConfig {
                 if resolved.is_empty() {
                     None
                 } else {
-                    Some(WidgetOrGroup::Group { group: resolved })
+                    Some(WidgetOrGroup::Group {
+                        group: resolved,
+                        shared_popover: *shared_popover,
+                    })
                 }
             }
         }
@@ -733,9 +1486,10 @@ impl WidgetsConfig {
 /// right = [
 ///   "clock",                              # single widget
 ///   { group = ["battery", "volume"] },    # grouped widgets sharing one island
+///   { group = ["cpu", "memory"], shared_popover = true },  # one popover for the group
 /// ]
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum WidgetPlacement {
     /// A group of widgets sharing one island.
@@ -743,6 +1497,10 @@ pub enum WidgetPlacement {
     Group {
         /// The widget names in this group.
         group: Vec<String>,
+        /// Open one popover combining every member's popover, wherever
+        /// the group is clicked, instead of each member's own.
+        #[serde(default)]
+        shared_popover: bool,
     },
     /// A single widget name.
     Single(String),
@@ -753,7 +1511,7 @@ impl WidgetPlacement {
     pub fn widget_count(&self) -> usize {
         match self {
             WidgetPlacement::Single(_) => 1,
-            WidgetPlacement::Group { group } => group.len(),
+            WidgetPlacement::Group { group, .. } => group.len(),
         }
     }
 
@@ -761,7 +1519,7 @@ impl WidgetPlacement {
     pub fn widget_names(&self) -> Vec<&str> {
         match self {
             WidgetPlacement::Single(name) => vec![name.as_str()],
-            WidgetPlacement::Group { group } => group.iter().map(|s| s.as_str()).collect(),
+            WidgetPlacement::Group { group, .. } => group.iter().map(|s| s.as_str()).collect(),
         }
     }
 
@@ -769,7 +1527,7 @@ impl WidgetPlacement {
     pub fn display_names(&self) -> Vec<String> {
         match self {
             WidgetPlacement::Single(name) => vec![name.clone()],
-            WidgetPlacement::Group { group } => {
+            WidgetPlacement::Group { group, .. } => {
                 vec![format!("[group: {}]", group.join(", "))]
             }
         }
@@ -779,7 +1537,8 @@ impl WidgetPlacement {
 /// Per-widget configuration options.
 ///
 /// Each widget can have a `[widgets.<name>]` table with widget-specific options.
-/// The `disabled` field is common to all widgets; other fields are widget-specific.
+/// The `disabled` field is common to all widgets, as is `show_if` (a
+/// [`Condition`] kept in `options`); other fields are widget-specific.
 ///
 /// # Example
 ///
@@ -798,25 +1557,77 @@ pub struct WidgetOptions {
     #[serde(default)]
     pub disabled: bool,
 
-    /// Background color override for this widget (hex like "#f5c2e7").
+    /// Background color override for this widget (e.g. "#f5c2e7", "rgba(245, 194, 231, 0.8)").
+    /// Alpha scales the widget background opacity.
     /// If invalid or not set, uses the theme's default widget background.
     #[serde(default)]
     pub background_color: Option<String>,
 
+    /// Margin around this widget's island (or its item in a group), in
+    /// pixels or as a CSS shorthand like "0 4px". Replaces the section
+    /// spacing on the sides it sets.
+    #[serde(default)]
+    pub margin: Option<BoxSpacing>,
+
+    /// Padding inside this widget's island (or its item in a group), in
+    /// pixels or as a CSS shorthand like "2px 12px".
+    #[serde(default)]
+    pub padding: Option<BoxSpacing>,
+
     /// Widget-specific options (format, show_icon, etc.).
     #[serde(flatten)]
     pub options: HashMap<String, toml::Value>,
 }
 
-/// A resolved widget entry with name and options, ready for the widget factory.
-///
-/// This is the internal representation used after resolving placements
-/// against per-widget configuration tables.
-#[derive(Debug, Clone)]
-pub struct WidgetEntry {
-    /// Widget type name (e.g., "clock", "battery", "workspaces").
-    pub name: String,
-
+/// A per-widget `margin` or `padding`: one pixel value for every side, or
+/// a CSS shorthand of one to four lengths.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BoxSpacing {
+    /// The same number of pixels on every side.
+    Pixels(i64),
+    /// CSS shorthand, e.g. "2px 8px" (bare numbers are pixels).
+    Css(String),
+}
+
+impl BoxSpacing {
+    /// The CSS value for this spacing, or `None` if it isn't one to four
+    /// pixel lengths (or has negative lengths when `allow_negative` is false).
+    pub fn to_css(&self, allow_negative: bool) -> Option<String> {
+        let lengths: Vec<f64> = match self {
+            BoxSpacing::Pixels(px) => vec![*px as f64],
+            BoxSpacing::Css(css) => css
+                .split_whitespace()
+                .map(|part| part.strip_suffix("px").unwrap_or(part).parse::<f64>().ok())
+                .collect::<Option<_>>()?,
+        };
+        let valid = (1..=4).contains(&lengths.len())
+            && lengths
+                .iter()
+                .all(|len| len.is_finite() && (allow_negative || *len >= 0.0));
+        if !valid {
+            return None;
+        }
+
+        Some(
+            lengths
+                .iter()
+                .map(|len| format!("{}px", len))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+/// A resolved widget entry with name and options, ready for the widget factory.
+///
+/// This is the internal representation used after resolving placements
+/// against per-widget configuration tables.
+#[derive(Debug, Clone)]
+pub struct WidgetEntry {
+    /// Widget type name (e.g., "clock", "battery", "workspaces").
+    pub name: String,
+
     /// Merged widget-specific options from `[widgets.<name>]`.
     pub options: HashMap<String, toml::Value>,
 }
@@ -848,7 +1659,11 @@ pub enum WidgetOrGroup {
     /// A single widget with its own island.
     Single(WidgetEntry),
     /// A group of widgets sharing one island.
-    Group { group: Vec<WidgetEntry> },
+    Group {
+        group: Vec<WidgetEntry>,
+        /// Whether clicking the group opens one combined popover.
+        shared_popover: bool,
+    },
 }
 
 impl WidgetOrGroup {
@@ -856,7 +1671,7 @@ impl WidgetOrGroup {
     pub fn widget_count(&self) -> usize {
         match self {
             WidgetOrGroup::Single(_) => 1,
-            WidgetOrGroup::Group { group } => group.len(),
+            WidgetOrGroup::Group { group, .. } => group.len(),
         }
     }
 
@@ -864,7 +1679,7 @@ impl WidgetOrGroup {
     pub fn display_names(&self) -> Vec<String> {
         match self {
             WidgetOrGroup::Single(entry) => vec![entry.name.clone()],
-            WidgetOrGroup::Group { group } => {
+            WidgetOrGroup::Group { group, .. } => {
                 let names: Vec<_> = group.iter().map(|e| e.name.clone()).collect();
                 vec![format!("[group: {}]", names.join(", "))]
             }
@@ -886,13 +1701,26 @@ fn format_widget_section(items: &[WidgetPlacement]) -> Vec<String> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct ThemeIconsConfig {
-    /// Icon backend: "material" for bundled Material Symbols, or "gtk" for
-    /// the system GTK icon theme.
+    /// Icon backend: "material" for bundled Material Symbols, "gtk" for
+    /// the system GTK icon theme, or "nerd" for an installed Nerd Font.
     pub theme: String,
 
     /// Icon stroke weight for Material Symbols (100-700). Lower = thinner strokes.
     /// Only applies when theme = "material". Default: 400.
     pub weight: u16,
+
+    /// Font family used for icons when theme = "nerd".
+    /// Default: "Symbols Nerd Font".
+    pub font: String,
+
+    /// Image files (SVG or PNG) replacing single icons, by logical icon name,
+    /// e.g. `"battery-full" = "~/icons/battery.svg"`. Apply to every backend
+    /// except text mode.
+    pub overrides: HashMap<String, String>,
+
+    /// Icon names by logical icon name (or app id), merged over the built-in
+    /// mapping, e.g. `"power-draw-symbolic" = { material = "bolt" }`.
+    pub map: HashMap<String, IconMapping>,
 }
 
 impl Default for ThemeIconsConfig {
@@ -900,10 +1728,27 @@ impl Default for ThemeIconsConfig {
         Self {
             theme: "material".to_string(),
             weight: 400,
+            font: "Symbols Nerd Font".to_string(),
+            overrides: HashMap::new(),
+            map: HashMap::new(),
         }
     }
 }
 
+/// What one logical icon name maps to in each backend (`[theme.icons.map]`).
+///
+/// Unset fields keep the built-in mapping for that backend.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IconMapping {
+    /// Material Symbols glyph name, e.g. "electric_bolt".
+    pub material: Option<String>,
+    /// GTK icon names, tried in order.
+    pub gtk: Vec<String>,
+    /// Nerd Font glyph, e.g. "\u{f0e7}".
+    pub nerd: Option<String>,
+}
+
 /// Theme configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -915,15 +1760,41 @@ pub struct ThemeConfig {
     /// - "gtk": derive colors from GTK theme where possible
     pub mode: String,
 
+    /// Built-in color scheme, e.g. "catppuccin-mocha" or "nord".
+    /// Sets the mode, bar and widget backgrounds, accent and state colors;
+    /// any of those set explicitly still take precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+
+    /// Wallpaper color scheme generator to take colors from: "pywal" or
+    /// "matugen". Overrides the preset; keys set explicitly still win.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Generator output file, when not at the generator's default location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+
     /// Accent color configuration: "gtk", "none", or a hex color like "#3584e4".
     /// - "gtk": use the GTK theme's accent color (don't override @accent_color)
     /// - "none": monochrome mode (no colored accents)
-    /// - "#rrggbb": use this specific color as the accent
+    /// - "wallpaper": the dominant color of the current wallpaper
+    /// - a color ("#rrggbb", "rgb(...)", "hsl(...)" or a CSS name): use it as the accent
     ///
     /// When not specified, defaults to "gtk" if mode is "gtk", otherwise "#adabe0".
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub accent: Option<String>,
 
+    /// Image sampled by `accent = "wallpaper"`. When not set, the wallpaper
+    /// hyprpaper or swww is showing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallpaper: Option<String>,
+
+    /// Color sampled from the wallpaper, filled in by the bar at runtime.
+    /// Never read from the config file.
+    #[serde(skip)]
+    pub wallpaper_accent: Option<String>,
+
     /// State colors (success, warning, urgent).
     pub states: ThemeStates,
 
@@ -932,16 +1803,33 @@ pub struct ThemeConfig {
 
     /// Icon theme configuration.
     pub icons: ThemeIconsConfig,
+
+    /// Gradient or image drawn as the bar background.
+    /// Only visible when `bar.background_opacity` is above 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bar_background: Option<BackgroundConfig>,
+
+    /// Gradient or image drawn as every widget's background,
+    /// on top of the widget background color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget_background: Option<BackgroundConfig>,
 }
 
 impl Default for ThemeConfig {
     fn default() -> Self {
         Self {
             mode: "auto".to_string(),
+            preset: None,
+            source: None,
+            source_path: None,
             accent: None,
+            wallpaper: None,
+            wallpaper_accent: None,
             states: ThemeStates::default(),
             typography: ThemeTypography::default(),
             icons: ThemeIconsConfig::default(),
+            bar_background: None,
+            widget_background: None,
         }
     }
 }
@@ -986,6 +1874,92 @@ impl Default for ThemeTypography {
     }
 }
 
+/// Gradient or image background for the bar or widgets.
+///
+/// Exactly one of `gradient` or `image` must be set.
+///
+/// # Example
+///
+/// ```toml
+/// [theme]
+/// bar_background = { gradient = ["#1a1a2e", "#16213e"], angle = 90 }
+/// widget_background = { image = "~/Pictures/paper.png", mode = "tile" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackgroundConfig {
+    /// Linear gradient color stops (at least two colors).
+    pub gradient: Vec<String>,
+
+    /// Gradient angle in degrees, following CSS: 0 points up, 90 points right.
+    /// Default: 180 (top to bottom)
+    pub angle: f64,
+
+    /// Image file path (`~` is expanded to the home directory).
+    pub image: Option<String>,
+
+    /// How the image fills the area: "cover", "contain", "tile", "stretch".
+    /// Default: "cover"
+    pub mode: String,
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        Self {
+            gradient: Vec::new(),
+            angle: 180.0,
+            image: None,
+            mode: "cover".to_string(),
+        }
+    }
+}
+
+impl BackgroundConfig {
+    /// Collect validation errors, prefixing each with `key` (e.g. "theme.bar_background").
+    fn validate(&self, key: &str, errors: &mut Vec<String>) {
+        match (self.gradient.is_empty(), &self.image) {
+            (true, None) => errors.push(format!("{}: set either `gradient` or `image`", key)),
+            (false, Some(_)) => errors.push(format!(
+                "{}: `gradient` and `image` can't be used together",
+                key
+            )),
+            _ => {}
+        }
+
+        if self.gradient.len() == 1 {
+            errors.push(format!("{}.gradient: needs at least two colors", key));
+        }
+        for color in &self.gradient {
+            if crate::theme::parse_color(color).is_none() {
+                errors.push(format!("{}.gradient: invalid color '{}'", key, color));
+            }
+        }
+
+        if let Some(ref image) = self.image
+            && !image.starts_with('/')
+            && !image.starts_with('~')
+        {
+            errors.push(format!(
+                "{}.image: '{}' must be an absolute path or start with ~",
+                key, image
+            ));
+        }
+
+        if !self.angle.is_finite() {
+            errors.push(format!("{}.angle: must be a number of degrees", key));
+        }
+
+        if !VALID_BACKGROUND_MODES.contains(&self.mode.as_str()) {
+            errors.push(format!(
+                "{}.mode: invalid value '{}', expected one of: {}",
+                key,
+                self.mode,
+                VALID_BACKGROUND_MODES.join(", ")
+            ));
+        }
+    }
+}
+
 /// On-screen display configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -998,6 +1972,16 @@ pub struct OsdConfig {
 
     /// How long the OSD stays visible (milliseconds).
     pub timeout_ms: u32,
+
+    /// Don't show the OSD while the focused window is fullscreen.
+    pub suppress_fullscreen: bool,
+
+    /// Don't show the OSD while any of these apps is running. Matched
+    /// against the focused window's app_id and running process names.
+    pub suppress_apps: Vec<String>,
+
+    /// Send a notification with the new value when the OSD is suppressed.
+    pub notify_when_suppressed: bool,
 }
 
 impl Default for OsdConfig {
@@ -1006,6 +1990,151 @@ impl Default for OsdConfig {
             enabled: true,
             position: "bottom".to_string(),
             timeout_ms: 1500,
+            suppress_fullscreen: false,
+            suppress_apps: Vec::new(),
+            notify_when_suppressed: false,
+        }
+    }
+}
+
+/// Night light configuration.
+///
+/// Color temperature follows the sun: it ramps from `day_temperature` to
+/// `night_temperature` over `transition_minutes` centered on sunset, and
+/// back around sunrise. Without coordinates, the location comes from
+/// GeoClue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NightLightConfig {
+    /// Whether the schedule runs.
+    pub enabled: bool,
+
+    /// Latitude in degrees (north positive).
+    pub latitude: Option<f64>,
+
+    /// Longitude in degrees (east positive).
+    pub longitude: Option<f64>,
+
+    /// Color temperature during the day (Kelvin).
+    pub day_temperature: u32,
+
+    /// Color temperature at night (Kelvin).
+    pub night_temperature: u32,
+
+    /// Length of the sunrise and sunset ramps (minutes).
+    pub transition_minutes: u32,
+
+    /// Command that applies a temperature; `{temperature}` is replaced
+    /// with the value in Kelvin.
+    pub command: String,
+}
+
+impl Default for NightLightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            latitude: None,
+            longitude: None,
+            day_temperature: 6500,
+            night_temperature: 4000,
+            transition_minutes: 45,
+            command: "hyprctl hyprsunset temperature {temperature}".to_string(),
+        }
+    }
+}
+
+/// Per-workspace configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Accent color per workspace, keyed by workspace name or number
+    /// (`1 = "#ff0000"`). The accent follows the focused workspace;
+    /// workspaces without a color use `theme.accent`.
+    pub colors: HashMap<String, String>,
+}
+
+impl WorkspaceConfig {
+    /// Accent color for a workspace, matched by name first, then by number.
+    pub fn color_for(&self, id: i32, name: &str) -> Option<&str> {
+        self.colors
+            .get(name)
+            .or_else(|| self.colors.get(&id.to_string()))
+            .map(String::as_str)
+    }
+}
+
+/// Audio configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioConfig {
+    /// Mute output when headphones are unplugged, so audio doesn't
+    /// suddenly play from the speakers.
+    pub mute_on_unplug: bool,
+
+    /// Highest output volume in percent (1-150). Applies to the Quick
+    /// Settings slider and `vibepanel volume`, and volume raised above it by
+    /// other tools is turned back down.
+    pub max_volume: u32,
+
+    /// Show a warning OSD when the volume is raised above 100%.
+    pub warn_overdrive: bool,
+
+    /// Warn after headphones have been the output at or above
+    /// `safe_listening_volume` for this many minutes, repeating while they
+    /// stay loud. 0 disables the warning.
+    pub safe_listening_minutes: u32,
+
+    /// Headphone volume in percent that counts as loud for
+    /// `safe_listening_minutes`.
+    pub safe_listening_volume: u32,
+
+    /// Toggle the microphone when a mic-mute key is pressed. Keys are read
+    /// from `/dev/input`, which needs membership in the `input` group. Leave
+    /// off if the compositor already binds the key.
+    pub mic_mute_key: bool,
+
+    /// Keep the `*::micmute` LED in step with the microphone mute state.
+    pub mic_mute_led: bool,
+}
+
+impl AudioConfig {
+    /// Clamp a volume percentage to `max_volume`.
+    pub fn clamp_volume(&self, percent: u32) -> u32 {
+        percent.min(self.max_volume.min(MAX_VOLUME))
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            mute_on_unplug: false,
+            max_volume: MAX_VOLUME,
+            warn_overdrive: false,
+            safe_listening_minutes: 0,
+            safe_listening_volume: 80,
+            mic_mute_key: false,
+            mic_mute_led: true,
+        }
+    }
+}
+
+/// Bluetooth configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BluetoothConfig {
+    /// Reconnect the most recently connected audio device when the bar
+    /// starts or the adapter is powered on.
+    pub auto_reconnect: bool,
+
+    /// How long a reconnect attempt may take before it is given up (seconds).
+    pub reconnect_timeout: u32,
+}
+
+impl Default for BluetoothConfig {
+    fn default() -> Self {
+        Self {
+            auto_reconnect: false,
+            reconnect_timeout: 10,
         }
     }
 }
@@ -1017,7 +2146,12 @@ impl Default for OsdConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AdvancedConfig {
-    /// Compositor to connect to: "auto", "mango", "hyprland", "niri".
+    /// Compositor to connect to: "auto", "mango", "dwl", "hyprland", "niri",
+    /// "river", "wayfire", "cosmic", "kwin", "generic" (ext-workspace and
+    /// wlr-foreign-toplevel, e.g. labwc).
+    ///
+    /// "dwl" uses the ipc patch's protocol when available, otherwise dwl's
+    /// status output on stdin (`dwl -s vibepanel`).
     ///
     /// In most cases, "auto" will correctly detect your compositor.
     /// Only change this if auto-detection fails or you want to force
@@ -1035,6 +2169,15 @@ pub struct AdvancedConfig {
     ///
     /// Default: false (use standard GTK/CSS font rendering)
     pub pango_font_rendering: bool,
+
+    /// Run `$(command)` placeholders in config strings when loading the
+    /// config. `${VAR}` placeholders are always expanded.
+    ///
+    /// Off by default, as commands in the config (e.g. a custom widget's
+    /// `exec`) may use `$(...)` themselves; write `$$(` to keep one as is.
+    ///
+    /// Default: false
+    pub config_commands: bool,
 }
 
 impl Default for AdvancedConfig {
@@ -1042,6 +2185,7 @@ impl Default for AdvancedConfig {
         Self {
             compositor: "auto".to_string(),
             pango_font_rendering: false,
+            config_commands: false,
         }
     }
 }
@@ -1063,6 +2207,7 @@ mod tests {
         assert_eq!(config.theme.typography.font_family, "monospace");
         assert_eq!(config.theme.icons.theme, "material");
         assert_eq!(config.theme.icons.weight, 400);
+        assert_eq!(config.theme.icons.font, "Symbols Nerd Font");
     }
 
     #[test]
@@ -1147,6 +2292,36 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_load_with_defaults_substitution() {
+        let user_toml = r##"
+            [theme]
+            accent = "${VIBEPANEL_TEST_UNSET_ACCENT:-#ff8800}"
+
+            [widgets.clock]
+            format = "$(printf %s '%H:%M')"
+        "##;
+        let config = Config::load_with_defaults(user_toml).unwrap();
+        assert_eq!(config.theme.accent.as_deref(), Some("#ff8800"));
+        // Commands only run when enabled
+        assert_eq!(
+            config.widgets.get_options("clock").unwrap().options["format"].as_str(),
+            Some("$(printf %s '%H:%M')")
+        );
+
+        let user_toml = format!("{}\n[advanced]\nconfig_commands = true\n", user_toml);
+        let config = Config::load_with_defaults(&user_toml).unwrap();
+        assert_eq!(
+            config.widgets.get_options("clock").unwrap().options["format"].as_str(),
+            Some("%H:%M")
+        );
+
+        let result = Config::load_with_defaults(
+            "[advanced]\nconfig_commands = true\n[bar]\nposition = \"$(exit 1)\"",
+        );
+        assert!(matches!(result, Err(Error::ConfigSubstitution(_))));
+    }
+
     #[test]
     fn test_load_with_defaults_override_widgets() {
         // User can override widgets completely (new format: just names)
@@ -1196,6 +2371,46 @@ mod tests {
         assert_eq!(config.bar.background_opacity, 0.0);
     }
 
+    #[test]
+    fn test_icon_overrides() {
+        let user_toml = r#"
+            [theme.icons.overrides]
+            "battery-full" = "~/icons/battery.svg"
+            notifications = "/usr/share/icons/bell.png"
+        "#;
+
+        let config = Config::load_with_defaults(user_toml).unwrap();
+        let overrides = &config.theme.icons.overrides;
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides["battery-full"], "~/icons/battery.svg");
+        assert_eq!(config.theme.icons.theme, "material");
+        assert!(Config::default().theme.icons.overrides.is_empty());
+    }
+
+    #[test]
+    fn test_icon_map() {
+        let user_toml = r#"
+            [theme.icons.map]
+            "power-draw-symbolic" = { material = "bolt", nerd = "\uf0e7" }
+            "com.example.Tool" = { gtk = ["utilities-terminal", "application-x-executable"] }
+        "#;
+
+        let config = Config::load_with_defaults(user_toml).unwrap();
+        let map = &config.theme.icons.map;
+        assert_eq!(map.len(), 2);
+        let power = &map["power-draw-symbolic"];
+        assert_eq!(power.material.as_deref(), Some("bolt"));
+        assert_eq!(power.nerd.as_deref(), Some("\u{f0e7}"));
+        assert!(power.gtk.is_empty());
+        assert_eq!(map["com.example.Tool"].gtk[0], "utilities-terminal");
+
+        let bad = r#"
+            [theme.icons.map]
+            wifi = { materail = "wifi" }
+        "#;
+        assert!(Config::load_with_defaults(bad).is_err());
+    }
+
     #[test]
     fn test_load_with_defaults_empty_config() {
         // Completely empty config should use all defaults
@@ -1375,6 +2590,516 @@ mod tests {
         assert!(msg.contains("theme.mode"));
     }
 
+    #[test]
+    fn test_load_with_defaults_theme_preset() {
+        let config = Config::load_with_defaults(
+            r##"
+            [theme]
+            preset = "nord"
+
+            [theme.states]
+            urgent = "#ff0000"
+
+            [widgets]
+            background_color = "#000000"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.theme.preset.as_deref(), Some("nord"));
+        assert_eq!(config.theme.mode, "dark");
+        assert_eq!(config.theme.accent.as_deref(), Some("#88c0d0"));
+        assert_eq!(config.theme.states.success, "#a3be8c");
+        assert_eq!(config.bar.background_color.as_deref(), Some("#2e3440"));
+        // Keys set by the user win over the preset
+        assert_eq!(config.theme.states.urgent, "#ff0000");
+        assert_eq!(config.widgets.background_color.as_deref(), Some("#000000"));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_load_with_defaults_theme_source() {
+        let dir = std::env::temp_dir().join(format!("vibepanel-wal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("colors.json");
+        std::fs::write(
+            &path,
+            r##"{
+                "special": { "background": "#fafafa" },
+                "colors": { "color0": "#eeeeee", "color1": "#aa0000", "color2": "#00aa00",
+                            "color3": "#aaaa00", "color4": "#0000aa" }
+            }"##,
+        )
+        .unwrap();
+
+        let config = Config::load_with_defaults(&format!(
+            "[theme]\npreset = \"nord\"\nsource = \"pywal\"\nsource_path = \"{}\"\n\n[theme.states]\nurgent = \"#ff0000\"",
+            path.display()
+        ))
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The source overrides the preset, the user's keys override both
+        assert_eq!(config.theme.mode, "light");
+        assert_eq!(config.theme.accent.as_deref(), Some("#0000aa"));
+        assert_eq!(config.bar.background_color.as_deref(), Some("#fafafa"));
+        assert_eq!(config.widgets.background_color.as_deref(), Some("#eeeeee"));
+        assert_eq!(config.theme.states.success, "#00aa00");
+        assert_eq!(config.theme.states.urgent, "#ff0000");
+
+        // A missing file keeps the preset's colors
+        let config = Config::load_with_defaults(
+            "[theme]\npreset = \"nord\"\nsource = \"pywal\"\nsource_path = \"/nonexistent/colors.json\"",
+        )
+        .unwrap();
+        assert_eq!(config.theme.accent.as_deref(), Some("#88c0d0"));
+        assert!(config.validate().is_ok());
+
+        let config = Config::load_with_defaults("[theme]\nsource = \"wallust\"").unwrap();
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("theme.source")
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_theme_preset() {
+        let config = Config::load_with_defaults("[theme]\npreset = \"solarized\"").unwrap();
+        assert_eq!(
+            config.theme.accent,
+            Config::load_with_defaults("").unwrap().theme.accent
+        );
+
+        let msg = config.validate().unwrap_err().to_string();
+        assert!(msg.contains("theme.preset"));
+        assert!(msg.contains("catppuccin-mocha"));
+    }
+
+    #[test]
+    fn test_osd_suppression_options() {
+        let config: Config = toml::from_str(
+            r#"
+            [osd]
+            suppress_fullscreen = true
+            suppress_apps = ["steam", "gamescope"]
+            notify_when_suppressed = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.osd.suppress_fullscreen);
+        assert_eq!(config.osd.suppress_apps, vec!["steam", "gamescope"]);
+        assert!(config.osd.notify_when_suppressed);
+        assert!(!Config::default().osd.suppress_fullscreen);
+    }
+
+    #[test]
+    fn test_validate_theme_backgrounds() {
+        let config: Config = toml::from_str(
+            r##"
+            [theme]
+            bar_background = { gradient = ["#1a1a2e", "rgb(22, 33, 62)"], angle = 90 }
+            widget_background = { image = "~/Pictures/paper.png", mode = "cover" }
+            "##,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let config: Config = toml::from_str(
+            r##"
+            [theme]
+            bar_background = { gradient = ["#1a1a2e"], image = "paper.png", mode = "fill" }
+            widget_background = { gradient = ["#1a1a2e", "notacolor"] }
+            "##,
+        )
+        .unwrap();
+        let Err(Error::ConfigValidation(errors)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("can't be used together")));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("needs at least two colors"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("must be an absolute path"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("theme.bar_background.mode"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("invalid color 'notacolor'"))
+        );
+    }
+
+    #[test]
+    fn test_validate_night_light() {
+        let config: Config = toml::from_str(
+            r#"
+            [night_light]
+            enabled = true
+            latitude = 52.52
+            longitude = 13.405
+            night_temperature = 3500
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.night_light.night_temperature, 3500);
+        assert_eq!(config.night_light.transition_minutes, 45);
+
+        let mut config = Config::default();
+        config.night_light.latitude = Some(52.52);
+        config.night_light.night_temperature = 500;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("night_light.night_temperature"));
+        assert!(err.contains("latitude and longitude must be set together"));
+    }
+
+    #[test]
+    fn test_output_overrides() {
+        let config: Config = toml::from_str(
+            r#"
+            [bar]
+            size = 32
+
+            [widgets]
+            left = ["workspaces"]
+            right = ["clock", "battery"]
+
+            [bar.output."HDMI-A-1"]
+            size = 24
+            position = "bottom"
+            right = ["clock"]
+
+            [bar.output."DP-2"]
+            enabled = false
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let tv = config.for_output("HDMI-A-1");
+        assert_eq!(tv.bar.size, 24);
+        assert!(tv.bar.is_bottom());
+        assert_eq!(tv.widgets.left.len(), 1);
+        assert_eq!(
+            tv.widgets.right,
+            vec![WidgetPlacement::Single("clock".into())]
+        );
+
+        let main = config.for_output("DP-1");
+        assert_eq!(main.bar.size, 32);
+        assert_eq!(main.widgets.right.len(), 2);
+
+        assert!(config.bar.is_output_enabled("DP-1"));
+        assert!(!config.bar.is_output_enabled("DP-2"));
+    }
+
+    #[test]
+    fn test_validate_output_overrides() {
+        let mut config = Config::default();
+        config.bar.output.insert(
+            "DP-1".to_string(),
+            BarOutputConfig {
+                size: Some(0),
+                position: Some("left".to_string()),
+                ..Default::default()
+            },
+        );
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("bar.output.\"DP-1\".position"));
+        assert!(err.contains("bar.output.\"DP-1\".size"));
+    }
+
+    #[test]
+    fn test_extra_bars() {
+        let config: Config = toml::from_str(
+            r#"
+            [bar]
+            size = 32
+            outputs = ["DP-1"]
+
+            [widgets]
+            right = ["clock", "battery"]
+
+            [bar.output."DP-1"]
+            size = 28
+
+            [[bars]]
+            name = "dock"
+            position = "bottom"
+            size = 48
+            center = ["media"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert!(config.warnings().is_empty());
+
+        let dock = config.for_bar(&config.bars[0]);
+        assert_eq!(dock.bar.size, 48);
+        assert!(dock.bar.is_bottom());
+        assert!(dock.bar.outputs.is_empty());
+        assert!(dock.bar.output.is_empty());
+        assert!(dock.widgets.right.is_empty());
+        assert_eq!(
+            dock.widgets.center,
+            vec![WidgetPlacement::Single("media".into())]
+        );
+    }
+
+    #[test]
+    fn test_validate_extra_bars() {
+        let config = Config {
+            bars: vec![
+                ExtraBarConfig {
+                    name: "dock".to_string(),
+                    size: Some(0),
+                    position: Some("left".to_string()),
+                    ..Default::default()
+                },
+                ExtraBarConfig {
+                    name: "dock".to_string(),
+                    ..Default::default()
+                },
+                ExtraBarConfig::default(),
+            ],
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("bars[0].position"));
+        assert!(err.contains("bars[0].size"));
+        assert!(err.contains("bars[1].name: 'dock' is already used"));
+        assert!(err.contains("bars[2].name: must be set"));
+    }
+
+    #[test]
+    fn test_validate_show_if() {
+        let config: Config = toml::from_str(
+            r#"
+            [widgets]
+            right = ["updates", "battery", "media"]
+
+            [widgets.updates]
+            show_if = "updates.count > 0"
+
+            [widgets.battery]
+            show_if = "battery.precent < 20"
+
+            [widgets.media]
+            show_if = 1
+            "#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(!err.contains("widgets.updates"));
+        assert!(err.contains("widgets.battery.show_if: unknown variable 'battery.precent'"));
+        assert!(err.contains("widgets.media.show_if: must be a string"));
+    }
+
+    #[test]
+    fn test_validate_bluetooth() {
+        let config: Config = toml::from_str(
+            r#"
+            [bluetooth]
+            auto_reconnect = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.bluetooth.reconnect_timeout, 10);
+
+        let mut config = Config::default();
+        config.bluetooth.reconnect_timeout = 0;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("bluetooth.reconnect_timeout"));
+    }
+
+    #[test]
+    fn test_workspace_colors() {
+        let config: Config = toml::from_str(
+            r##"
+            [workspace.colors]
+            1 = "#ff0000"
+            web = "royalblue"
+            "##,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.workspace.color_for(1, "1"), Some("#ff0000"));
+        assert_eq!(config.workspace.color_for(2, "web"), Some("royalblue"));
+        assert_eq!(config.workspace.color_for(3, "3"), None);
+
+        let mut config = Config::default();
+        config
+            .workspace
+            .colors
+            .insert("2".to_string(), "not-a-color".to_string());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("workspace.colors.2: invalid color 'not-a-color'"));
+    }
+
+    #[test]
+    fn test_audio_config() {
+        assert!(!Config::default().audio.mute_on_unplug);
+
+        let config: Config = toml::from_str(
+            r#"
+            [audio]
+            mute_on_unplug = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.audio.mute_on_unplug);
+        assert!(config.summary().contains("mute_on_unplug: true"));
+    }
+
+    #[test]
+    fn test_audio_mic_mute_config() {
+        let defaults = AudioConfig::default();
+        assert!(!defaults.mic_mute_key);
+        assert!(defaults.mic_mute_led);
+
+        let config: Config = toml::from_str(
+            r#"
+            [audio]
+            mic_mute_key = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.audio.mic_mute_key);
+        assert!(config.audio.mic_mute_led);
+        assert!(
+            config
+                .summary()
+                .contains("mic_mute_key: true, mic_mute_led: true")
+        );
+    }
+
+    #[test]
+    fn test_audio_max_volume() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [audio]
+            max_volume = 80
+            safe_listening_minutes = 60
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.audio.clamp_volume(95), 80);
+        assert_eq!(config.audio.clamp_volume(40), 40);
+        assert!(config.summary().contains("safe_listening: 60 min at 80%"));
+
+        config.audio.max_volume = 200;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("audio.max_volume"));
+    }
+
+    #[test]
+    fn test_validate_bar_position() {
+        let mut config = Config::default();
+        config.bar.position = "bottom".to_string();
+        assert!(config.validate().is_ok());
+        assert!(config.bar.is_bottom());
+
+        config.bar.position = "left".to_string();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("bar.position"));
+    }
+
+    #[test]
+    fn test_validate_bar_mode() {
+        let mut config = Config::default();
+        assert!(!config.bar.is_text_mode());
+        assert_eq!(config.bar.widget_spacing(), config.bar.spacing);
+
+        config.bar.mode = "text".to_string();
+        config.bar.spacing = 8;
+        assert!(config.validate().is_ok());
+        assert!(config.bar.is_text_mode());
+        assert_eq!(config.bar.widget_spacing(), 12);
+
+        config.bar.mode = "braille".to_string();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("bar.mode"));
+    }
+
+    #[test]
+    fn test_section_spacing() {
+        let mut config = Config::load_with_defaults(
+            r#"
+            [bar]
+            spacing = 6
+            group_spacing = 4
+            section_spacing = { left = 2, right = 10 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.bar.group_spacing, 4);
+        assert_eq!(config.bar.section_widget_spacing("left"), 2);
+        assert_eq!(config.bar.section_widget_spacing("center"), 6);
+        assert_eq!(config.bar.section_widget_spacing("right"), 10);
+
+        config.bar.mode = "text".to_string();
+        assert_eq!(config.bar.section_widget_spacing("right"), 15);
+
+        let summary = config.summary();
+        assert!(summary.contains("left spacing: 2px"));
+        assert!(summary.contains("group_spacing: 4px"));
+        assert!(!summary.contains("center spacing"));
+
+        let err = Config::load_with_defaults("[bar.section_spacing]\nmiddle = 4\n").unwrap_err();
+        assert!(err.to_string().contains("middle"));
+    }
+
+    #[test]
+    fn test_widget_box_spacing() {
+        let config = Config::load_with_defaults(
+            r#"
+            [widgets.clock]
+            margin = 4
+            padding = "2px 12"
+            format = "%H:%M"
+            "#,
+        )
+        .unwrap();
+        let clock = &config.widgets.widget_configs["clock"];
+        assert_eq!(clock.margin, Some(BoxSpacing::Pixels(4)));
+        assert_eq!(clock.padding, Some(BoxSpacing::Css("2px 12".to_string())));
+        assert!(!clock.options.contains_key("margin"));
+        assert!(clock.options.contains_key("format"));
+
+        assert_eq!(BoxSpacing::Pixels(4).to_css(false).as_deref(), Some("4px"));
+        assert_eq!(
+            BoxSpacing::Css("2px 12 0".to_string())
+                .to_css(false)
+                .as_deref(),
+            Some("2px 12px 0px")
+        );
+        assert_eq!(
+            BoxSpacing::Css("0 -4px".to_string())
+                .to_css(true)
+                .as_deref(),
+            Some("0px -4px")
+        );
+        assert_eq!(BoxSpacing::Css("0 -4px".to_string()).to_css(false), None);
+        assert_eq!(BoxSpacing::Css("1em".to_string()).to_css(true), None);
+        assert_eq!(BoxSpacing::Css("1 2 3 4 5".to_string()).to_css(true), None);
+        assert_eq!(BoxSpacing::Css(String::new()).to_css(true), None);
+    }
+
     #[test]
     fn test_validate_invalid_osd_position() {
         let mut config = Config::default();
@@ -1466,7 +3191,7 @@ mod tests {
 
         // Second: group of 2 widgets
         match &config.widgets.right[1] {
-            WidgetPlacement::Group { group } => {
+            WidgetPlacement::Group { group, .. } => {
                 assert_eq!(group.len(), 2);
                 assert_eq!(group[0], "battery");
                 assert_eq!(group[1], "volume");
@@ -1526,10 +3251,41 @@ mod tests {
 
         let group = WidgetPlacement::Group {
             group: vec!["battery".to_string(), "volume".to_string()],
+            shared_popover: false,
         };
         assert_eq!(group.widget_count(), 2);
     }
 
+    #[test]
+    fn test_widget_group_shared_popover() {
+        let toml = r#"
+            [widgets]
+            right = [
+                { group = ["cpu", "memory", "temperature"], shared_popover = true },
+                { group = ["battery", "clock"] },
+            ]
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let resolved = config.widgets.resolved_right();
+        assert_eq!(resolved.len(), 2);
+
+        match &resolved[0] {
+            WidgetOrGroup::Group {
+                group,
+                shared_popover,
+            } => {
+                assert_eq!(group.len(), 3);
+                assert!(shared_popover);
+            }
+            WidgetOrGroup::Single(_) => panic!("expected group"),
+        }
+        match &resolved[1] {
+            WidgetOrGroup::Group { shared_popover, .. } => assert!(!shared_popover),
+            WidgetOrGroup::Single(_) => panic!("expected group"),
+        }
+    }
+
     #[test]
     fn test_empty_widget_group() {
         let toml = r#"
@@ -1543,7 +3299,7 @@ mod tests {
         assert_eq!(config.widgets.right.len(), 1);
 
         match &config.widgets.right[0] {
-            WidgetPlacement::Group { group } => {
+            WidgetPlacement::Group { group, .. } => {
                 assert!(group.is_empty());
             }
             WidgetPlacement::Single(_) => panic!("expected group"),
@@ -1659,6 +3415,7 @@ mod tests {
         // Spacer in a group should still be detected
         let section = vec![WidgetPlacement::Group {
             group: vec!["clock".to_string(), "spacer".to_string()],
+            shared_popover: false,
         }];
         let config = WidgetsConfig::default();
         assert!(config.section_has_expander(&section));
@@ -1753,4 +3510,91 @@ mod tests {
         assert_eq!(entry.name, "spacer");
         assert!(!entry.options.contains_key("width"));
     }
+
+    #[test]
+    fn test_resolve_widget_remote_inline_id_injects_option() {
+        let config = WidgetsConfig::default();
+        let entry = config.resolve_widget("remote:weather").unwrap();
+
+        assert_eq!(entry.name, "remote");
+        assert_eq!(
+            entry.options.get("id"),
+            Some(&toml::Value::String("weather".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_resolve_widget_custom_uses_own_section() {
+        let mut config = WidgetsConfig::default();
+        let mut options = HashMap::new();
+        options.insert(
+            "exec".to_string(),
+            toml::Value::String("uptime -p".to_string()),
+        );
+        config.widget_configs.insert(
+            "custom:uptime".to_string(),
+            WidgetOptions {
+                options,
+                ..Default::default()
+            },
+        );
+        config.widget_configs.insert(
+            "custom:disabled".to_string(),
+            WidgetOptions {
+                disabled: true,
+                ..Default::default()
+            },
+        );
+
+        let entry = config.resolve_widget("custom:uptime").unwrap();
+        assert_eq!(entry.name, "custom");
+        assert_eq!(
+            entry.options.get("id"),
+            Some(&toml::Value::String("uptime".to_string()))
+        );
+        assert_eq!(
+            entry.options.get("exec"),
+            Some(&toml::Value::String("uptime -p".to_string()))
+        );
+
+        assert!(config.resolve_widget("custom:disabled").is_none());
+    }
+
+    #[test]
+    fn test_resolve_widget_stats_instance_uses_own_section() {
+        let mut config = WidgetsConfig::default();
+        let mut options = HashMap::new();
+        options.insert(
+            "source".to_string(),
+            toml::Value::String("ssh:server1".to_string()),
+        );
+        config.widget_configs.insert(
+            "cpu:server1".to_string(),
+            WidgetOptions {
+                options,
+                ..Default::default()
+            },
+        );
+
+        let entry = config.resolve_widget("cpu:server1").unwrap();
+        assert_eq!(entry.name, "cpu");
+        assert_eq!(
+            entry.options.get("source"),
+            Some(&toml::Value::String("ssh:server1".to_string()))
+        );
+        assert!(!entry.options.contains_key("id"));
+
+        // The plain widget keeps reading [widgets.cpu]
+        let entry = config.resolve_widget("cpu").unwrap();
+        assert!(!entry.options.contains_key("source"));
+    }
+
+    #[test]
+    fn test_resolve_widget_remote_without_inline_id() {
+        let config = WidgetsConfig::default();
+        let entry = config.resolve_widget("remote").unwrap();
+
+        assert_eq!(entry.name, "remote");
+        assert!(!entry.options.contains_key("id"));
+    }
 }
@@ -20,6 +20,10 @@ pub enum Error {
     #[error("failed to parse config: {0}")]
     ConfigParse(#[from] toml::de::Error),
 
+    /// Expanding `${VAR}` / `$(cmd)` placeholders failed.
+    #[error("config substitution failed:\n{}", .0.join("\n"))]
+    ConfigSubstitution(Vec<String>),
+
     /// Configuration validation failed.
     #[error("config validation failed:\n{}", .0.join("\n"))]
     ConfigValidation(Vec<String>),
@@ -1,16 +1,31 @@
 //! Core types, configuration, and utilities for vibepanel bar.
 //!
 //! This crate provides:
-//! - Configuration parsing from TOML
-//! - Theme palette generation
+//! - Configuration parsing from TOML, with `${VAR}` / `$(cmd)` substitution
+//! - Theme palette generation, presets, pywal / matugen colors and wallpaper accents
+//! - Widget visibility conditions (`show_if`)
 //! - Logging setup
+//! - Persistent key-value state store
+//! - Night light sun schedule
+//! - System proxy settings
 //! - Shared types used across the bar
 
+pub mod color_source;
+pub mod condition;
 pub mod config;
 pub mod error;
 pub mod logging;
+pub mod night_light;
+pub mod proxy;
+pub mod state;
+pub mod substitution;
 pub mod theme;
+pub mod wallpaper;
 
 pub use config::{Config, ConfigLoadResult, DEFAULT_CONFIG_TOML};
 pub use error::{Error, Result};
-pub use theme::{AccentSource, SurfaceStyles, ThemePalette, ThemeSizes, parse_hex_color};
+pub use state::StateStore;
+pub use theme::{
+    AccentSource, ContrastWarning, Rgba, SurfaceStyles, THEME_PRESETS, ThemePalette, ThemePreset,
+    ThemeSizes, extra_bar_css_class, output_css_class, parse_color, parse_hex_color, theme_preset,
+};
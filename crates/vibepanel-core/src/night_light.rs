@@ -0,0 +1,217 @@
+//! Night light schedule: sunrise/sunset times and color temperature ramps.
+//!
+//! Sun times use the standard sunrise equation (accurate to a minute or two,
+//! which is plenty for dimming a screen). All times are Unix timestamps in
+//! seconds; longitude is positive east of Greenwich.
+//!
+//! Color temperature ramps linearly from day to night over a transition
+//! window centered on sunset, and back over a window centered on sunrise.
+
+use crate::config::NightLightConfig;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Julian date of the Unix epoch.
+const UNIX_EPOCH_JULIAN: f64 = 2_440_587.5;
+
+/// Julian date of J2000.0.
+const J2000: f64 = 2_451_545.0;
+
+/// Sun altitude at sunrise/sunset, accounting for refraction and the
+/// sun's radius (degrees).
+const SUNRISE_ALTITUDE: f64 = -0.833;
+
+/// Axial tilt of the earth (degrees).
+const OBLIQUITY: f64 = 23.4397;
+
+/// Daylight on a given day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Daylight {
+    /// The sun rises and sets (Unix timestamps).
+    Normal { sunrise: i64, sunset: i64 },
+    /// The sun never sets.
+    PolarDay,
+    /// The sun never rises.
+    PolarNight,
+}
+
+/// Compute sunrise and sunset on `day` (days since the Unix epoch, as a
+/// solar date at the given longitude).
+pub fn sun_times(day: i64, latitude: f64, longitude: f64) -> Daylight {
+    // Days since J2000 at noon UTC, then mean solar noon at this longitude
+    let n = (day as f64 + UNIX_EPOCH_JULIAN + 0.5 - J2000 + 0.0008).round();
+    let mean_noon = n - longitude / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0);
+    let m = anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let lambda = ecliptic_longitude.to_radians();
+    let transit = J2000 + mean_noon + 0.0053 * m.sin() - 0.0069 * (2.0 * lambda).sin();
+
+    let declination = (lambda.sin() * OBLIQUITY.to_radians().sin()).asin();
+    let phi = latitude.to_radians();
+    let cos_hour_angle = (SUNRISE_ALTITUDE.to_radians().sin() - phi.sin() * declination.sin())
+        / (phi.cos() * declination.cos());
+
+    if cos_hour_angle > 1.0 {
+        return Daylight::PolarNight;
+    }
+    if cos_hour_angle < -1.0 {
+        return Daylight::PolarDay;
+    }
+
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    let to_unix = |julian: f64| ((julian - UNIX_EPOCH_JULIAN) * SECONDS_PER_DAY as f64) as i64;
+    Daylight::Normal {
+        sunrise: to_unix(transit - half_day),
+        sunset: to_unix(transit + half_day),
+    }
+}
+
+/// Day/night color temperature schedule for a location.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Length of the sunrise/sunset ramps (seconds).
+    pub transition: i64,
+    /// Color temperature during the day (Kelvin).
+    pub day_temperature: u32,
+    /// Color temperature at night (Kelvin).
+    pub night_temperature: u32,
+}
+
+impl Schedule {
+    /// Build a schedule from the config and a resolved location.
+    pub fn new(config: &NightLightConfig, latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            transition: i64::from(config.transition_minutes) * 60,
+            day_temperature: config.day_temperature,
+            night_temperature: config.night_temperature,
+        }
+    }
+
+    /// Solar date (days since the Unix epoch) containing `now`.
+    fn solar_day(&self, now: i64) -> i64 {
+        let offset = (self.longitude / 360.0 * SECONDS_PER_DAY as f64) as i64;
+        (now + offset).div_euclid(SECONDS_PER_DAY)
+    }
+
+    /// How far into night `now` is: 0.0 is full day, 1.0 full night.
+    pub fn night_factor(&self, now: i64) -> f64 {
+        let (sunrise, sunset) = match sun_times(self.solar_day(now), self.latitude, self.longitude)
+        {
+            Daylight::Normal { sunrise, sunset } => (sunrise, sunset),
+            Daylight::PolarDay => return 0.0,
+            Daylight::PolarNight => return 1.0,
+        };
+
+        let half = self.transition / 2;
+        let ramp = |start: i64| (now - start) as f64 / self.transition.max(1) as f64;
+        if now < sunrise - half {
+            1.0
+        } else if now < sunrise + half {
+            1.0 - ramp(sunrise - half)
+        } else if now < sunset - half {
+            0.0
+        } else if now < sunset + half {
+            ramp(sunset - half)
+        } else {
+            1.0
+        }
+    }
+
+    /// Color temperature at `now`, rounded to 50 K so small steps in the
+    /// ramp don't each trigger an update.
+    pub fn temperature_at(&self, now: i64) -> u32 {
+        let day = f64::from(self.day_temperature);
+        let night = f64::from(self.night_temperature);
+        let temperature = day + (night - day) * self.night_factor(now);
+        ((temperature / 50.0).round() * 50.0) as u32
+    }
+
+    /// Start of the next sunrise or sunset ramp after `now`, if the sun
+    /// rises or sets in the next few days.
+    pub fn next_transition(&self, now: i64) -> Option<i64> {
+        let half = self.transition / 2;
+        let today = self.solar_day(now);
+        (today..today + 3)
+            .filter_map(|day| match sun_times(day, self.latitude, self.longitude) {
+                Daylight::Normal { sunrise, sunset } => Some([sunrise - half, sunset - half]),
+                _ => None,
+            })
+            .flatten()
+            .find(|&start| start > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-06-21 00:00 UTC
+    const JUNE_21_2024: i64 = 1_718_928_000;
+
+    fn berlin() -> Schedule {
+        Schedule {
+            latitude: 52.52,
+            longitude: 13.405,
+            transition: 3600,
+            day_temperature: 6500,
+            night_temperature: 4000,
+        }
+    }
+
+    #[test]
+    fn test_sun_times_berlin_midsummer() {
+        let day = JUNE_21_2024 / SECONDS_PER_DAY;
+        let Daylight::Normal { sunrise, sunset } = sun_times(day, 52.52, 13.405) else {
+            panic!("expected sunrise and sunset");
+        };
+        // Sunrise ~02:43 UTC, sunset ~19:33 UTC
+        let minutes = |t: i64| (t - JUNE_21_2024) / 60;
+        assert!((minutes(sunrise) - 163).abs() <= 5, "{}", minutes(sunrise));
+        assert!((minutes(sunset) - 1173).abs() <= 5, "{}", minutes(sunset));
+    }
+
+    #[test]
+    fn test_polar_day_and_night() {
+        let day = JUNE_21_2024 / SECONDS_PER_DAY;
+        assert_eq!(sun_times(day, 78.22, 15.65), Daylight::PolarDay);
+        assert_eq!(sun_times(day, -78.0, 166.0), Daylight::PolarNight);
+    }
+
+    #[test]
+    fn test_temperature_ramps_around_sunset() {
+        let schedule = berlin();
+        let day = JUNE_21_2024 / SECONDS_PER_DAY;
+        let Daylight::Normal { sunset, .. } = sun_times(day, 52.52, 13.405) else {
+            panic!("expected sunrise and sunset");
+        };
+
+        assert_eq!(schedule.temperature_at(JUNE_21_2024 + 12 * 3600), 6500);
+        assert_eq!(schedule.temperature_at(sunset), 5250);
+        assert_eq!(schedule.temperature_at(sunset + 1800), 4000);
+        assert_eq!(schedule.temperature_at(JUNE_21_2024 + 23 * 3600), 4000);
+    }
+
+    #[test]
+    fn test_next_transition() {
+        let schedule = berlin();
+        let noon = JUNE_21_2024 + 12 * 3600;
+        let next = schedule.next_transition(noon).unwrap();
+        // The sunset ramp starts half an hour before sunset
+        let day = JUNE_21_2024 / SECONDS_PER_DAY;
+        let Daylight::Normal { sunset, .. } = sun_times(day, 52.52, 13.405) else {
+            panic!("expected sunrise and sunset");
+        };
+        assert_eq!(next, sunset - 1800);
+
+        // After sunset, the next change is tomorrow's sunrise ramp
+        let after = schedule.next_transition(next + 1).unwrap();
+        assert!(after > sunset && after - sunset < 12 * 3600);
+    }
+}
@@ -0,0 +1,324 @@
+//! Persistent key-value state store.
+//!
+//! Stores small pieces of runtime state (DND, timer remaining, pinned
+//! quick settings, collapsed groups, ...) as a JSON object at
+//! `$XDG_STATE_HOME/vibepanel/state.json` so they survive restarts.
+//!
+//! Values are typed on access via serde. Writes are debounced: changes made
+//! within the debounce window are coalesced into a single write from a
+//! background thread. Call [`StateStore::flush`] before exiting.
+//!
+//! A read-only store (see [`StateStore::open_read_only`]) starts from the
+//! file but keeps changes in memory, for processes like a preview bar that
+//! must not overwrite the running bar's state.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use tracing::{debug, warn};
+
+/// Default delay between a change and the write that persists it.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Returns the path to the state file.
+///
+/// Location: `$XDG_STATE_HOME/vibepanel/state.json`
+/// Default: `~/.local/state/vibepanel/state.json`
+pub fn default_state_path() -> PathBuf {
+    let state_home = std::env::var("XDG_STATE_HOME")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            format!("{}/.local/state", home)
+        });
+    PathBuf::from(state_home)
+        .join("vibepanel")
+        .join("state.json")
+}
+
+/// Key-value store persisted as a JSON object.
+///
+/// Cheap to clone; clones share the same underlying state.
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    path: PathBuf,
+    debounce: Duration,
+    values: Mutex<Map<String, Value>>,
+    /// Set while changes are waiting to be written.
+    write_pending: AtomicBool,
+    /// Never write to `path`.
+    read_only: bool,
+}
+
+static GLOBAL: OnceLock<StateStore> = OnceLock::new();
+
+impl StateStore {
+    /// Open the store at `path` with the default debounce.
+    ///
+    /// A missing or unreadable file yields an empty store.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self::open_with_debounce(path, DEFAULT_DEBOUNCE)
+    }
+
+    /// Open the store at `path`, coalescing writes within `debounce`.
+    pub fn open_with_debounce(path: impl Into<PathBuf>, debounce: Duration) -> Self {
+        Self::new(path.into(), debounce, false)
+    }
+
+    /// Open the store at `path` without ever writing it back.
+    ///
+    /// Changes are visible through this store but only kept in memory.
+    pub fn open_read_only(path: impl Into<PathBuf>) -> Self {
+        Self::new(path.into(), DEFAULT_DEBOUNCE, true)
+    }
+
+    fn new(path: PathBuf, debounce: Duration, read_only: bool) -> Self {
+        let values = load_values(&path);
+        Self {
+            inner: Arc::new(Inner {
+                path,
+                debounce,
+                values: Mutex::new(values),
+                write_pending: AtomicBool::new(false),
+                read_only,
+            }),
+        }
+    }
+
+    /// Get the process-wide store, opened at [`default_state_path`] unless
+    /// [`StateStore::init_global`] installed another one first.
+    pub fn global() -> &'static StateStore {
+        GLOBAL.get_or_init(|| StateStore::open(default_state_path()))
+    }
+
+    /// Install `store` as the process-wide store.
+    ///
+    /// Must be called before the first [`StateStore::global`]; returns
+    /// `false` (and changes nothing) afterwards.
+    pub fn init_global(store: StateStore) -> bool {
+        GLOBAL.set(store).is_ok()
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Get the value stored under `key`.
+    ///
+    /// Returns `None` if the key is missing or doesn't deserialize as `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.inner.values().get(key)?.clone();
+        match serde_json::from_value(value) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Ignoring state key '{}' with unexpected type: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Store `value` under `key` and schedule a write.
+    pub fn set<T: Serialize + ?Sized>(&self, key: &str, value: &T) {
+        let value = match serde_json::to_value(value) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Failed to serialize state key '{}': {}", key, e);
+                return;
+            }
+        };
+        {
+            let mut values = self.inner.values();
+            if values.get(key) == Some(&value) {
+                return;
+            }
+            values.insert(key.to_string(), value);
+        }
+        self.schedule_write();
+    }
+
+    /// Remove `key` and schedule a write if it was present.
+    pub fn remove(&self, key: &str) {
+        if self.inner.values().remove(key).is_some() {
+            self.schedule_write();
+        }
+    }
+
+    /// Write pending changes immediately.
+    pub fn flush(&self) {
+        if self.inner.write_pending.swap(false, Ordering::SeqCst) {
+            self.inner.write();
+        }
+    }
+
+    fn schedule_write(&self) {
+        if self.inner.read_only {
+            return;
+        }
+        if self.inner.write_pending.swap(true, Ordering::SeqCst) {
+            // A write is already scheduled and will include this change
+            return;
+        }
+        let inner = Arc::clone(&self.inner);
+        std::thread::spawn(move || {
+            std::thread::sleep(inner.debounce);
+            if inner.write_pending.swap(false, Ordering::SeqCst) {
+                inner.write();
+            }
+        });
+    }
+}
+
+impl Inner {
+    fn values(&self) -> MutexGuard<'_, Map<String, Value>> {
+        // A panic while holding the lock can't leave the map half-updated
+        self.values.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Serialize the current values and atomically replace the file.
+    fn write(&self) {
+        let json = match serde_json::to_string_pretty(&*self.values()) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize state: {}", e);
+                return;
+            }
+        };
+
+        if let Some(parent) = self.path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            warn!("Failed to create state directory {:?}: {}", parent, e);
+            return;
+        }
+
+        let tmp_path = self.path.with_extension("json.tmp");
+        let result =
+            std::fs::write(&tmp_path, json).and_then(|()| std::fs::rename(&tmp_path, &self.path));
+        match result {
+            Ok(()) => debug!("Saved state to {:?}", self.path),
+            Err(e) => warn!("Failed to save state to {:?}: {}", self.path, e),
+        }
+    }
+}
+
+/// Read the state file into a JSON object.
+fn load_values(path: &Path) -> Map<String, Value> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("No state file found at {:?}, using defaults", path);
+            return Map::new();
+        }
+        Err(e) => {
+            warn!("Failed to read state file {:?}: {}", path, e);
+            return Map::new();
+        }
+    };
+
+    match serde_json::from_str::<Value>(&contents) {
+        Ok(Value::Object(values)) => {
+            debug!("Loaded state from {:?}", path);
+            values
+        }
+        Ok(_) => {
+            warn!("State file {:?} is not a JSON object, ignoring", path);
+            Map::new()
+        }
+        Err(e) => {
+            warn!("Failed to parse state file {:?}: {}", path, e);
+            Map::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_state_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "vibepanel-state-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("state.json")
+    }
+
+    #[test]
+    fn test_typed_roundtrip() {
+        let path = temp_state_path("roundtrip");
+        let store = StateStore::open(&path);
+        assert_eq!(store.get::<bool>("dnd"), None);
+
+        store.set("dnd", &true);
+        store.set("pinned", &vec!["wifi", "audio"]);
+        store.flush();
+
+        let reopened = StateStore::open(&path);
+        assert_eq!(reopened.get::<bool>("dnd"), Some(true));
+        assert_eq!(
+            reopened.get::<Vec<String>>("pinned"),
+            Some(vec!["wifi".to_string(), "audio".to_string()])
+        );
+        // Wrong type reads as missing
+        assert_eq!(reopened.get::<u32>("dnd"), None);
+
+        reopened.remove("dnd");
+        reopened.flush();
+        assert_eq!(StateStore::open(&path).get::<bool>("dnd"), None);
+    }
+
+    #[test]
+    fn test_debounced_write() {
+        let path = temp_state_path("debounce");
+        let store = StateStore::open_with_debounce(&path, Duration::from_millis(50));
+        store.set("timer.remaining", &90u64);
+        store.set("timer.remaining", &60u64);
+        assert!(!path.exists());
+
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(
+            StateStore::open(&path).get::<u64>("timer.remaining"),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn test_read_only_store_never_writes() {
+        let path = temp_state_path("read-only");
+        let store = StateStore::open(&path);
+        store.set("dnd", &true);
+        store.flush();
+
+        let preview = StateStore::open_read_only(&path);
+        assert_eq!(preview.get::<bool>("dnd"), Some(true));
+        preview.set("dnd", &false);
+        preview.remove("pinned");
+        preview.flush();
+        assert_eq!(preview.get::<bool>("dnd"), Some(false));
+        assert_eq!(StateStore::open(&path).get::<bool>("dnd"), Some(true));
+    }
+
+    #[test]
+    fn test_invalid_file_yields_empty_store() {
+        let path = temp_state_path("invalid");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "[1, 2, 3]").unwrap();
+
+        let store = StateStore::open(&path);
+        assert_eq!(store.get::<bool>("dnd"), None);
+    }
+}
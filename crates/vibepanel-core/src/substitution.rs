@@ -0,0 +1,377 @@
+//! Placeholders in config string values, expanded when the config is loaded.
+//!
+//! - `${NAME}` is the environment variable `NAME`; `${NAME:-fallback}` uses
+//!   `fallback` when it's unset or empty.
+//! - `$(command)` is the output of `sh -c command` with trailing newlines
+//!   removed. Commands only run with `advanced.config_commands = true`, and
+//!   never in keys that hold a command themselves ([`COMMAND_KEYS`]): there
+//!   `$(...)` is left for the shell that runs the command later.
+//! - `$${` and `$$(` are a literal `${` and `$(`.
+//!
+//! Values such as custom widget commands contain shell syntax of their own,
+//! so a `${...}` that isn't a plain variable name (`${file%.txt}`), or names
+//! an unset variable without a fallback, is left as written.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use toml::{Table, Value};
+
+/// How long a `$(command)` may run before it's killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Keys whose values are commands run by the bar (custom widget `exec` and
+/// click actions, terminals, backup and night light commands).
+pub const COMMAND_KEYS: &[&str] = &[
+    "exec",
+    "command",
+    "backup_command",
+    "terminal",
+    "on_click",
+    "on_click_middle",
+    "on_click_right",
+    "on_scroll_up",
+    "on_scroll_down",
+];
+
+/// Looks up an environment variable.
+type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Runs a `$(command)`, if commands are enabled.
+type Run<'a> = Option<&'a dyn Fn(&str) -> Result<String, String>>;
+
+/// Expand placeholders in every string value of `table`, recursively.
+///
+/// Keys are left alone. Returns the failures as `"<key path>: <reason>"`.
+pub fn expand_table(table: &mut Table, commands: bool) -> Result<(), Vec<String>> {
+    let env = |name: &str| std::env::var(name).ok();
+    let run: Run = if commands { Some(&run_command) } else { None };
+
+    let mut errors = Vec::new();
+    for (key, value) in table.iter_mut() {
+        expand_value(value, key, key, &env, run, &mut errors);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn expand_value(
+    value: &mut Value,
+    key: &str,
+    path: &str,
+    env: Env,
+    run: Run,
+    errors: &mut Vec<String>,
+) {
+    match value {
+        Value::String(text) => {
+            let run = if COMMAND_KEYS.contains(&key) {
+                None
+            } else {
+                run
+            };
+            match expand(text, env, run) {
+                Ok(expanded) => *text = expanded,
+                Err(e) => errors.push(format!("{}: {}", path, e)),
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                expand_value(item, key, path, env, run, errors);
+            }
+        }
+        Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                expand_value(value, key, &format!("{}.{}", path, key), env, run, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Expand the placeholders in `text`, looking variables up with `env` and
+/// running commands with `run` (commands are left as written without it).
+pub fn expand(text: &str, env: Env, run: Run) -> Result<String, String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(escaped) = after.strip_prefix('$')
+            && (escaped.starts_with('{') || escaped.starts_with('('))
+        {
+            expanded.push('$');
+            expanded.push_str(&escaped[..1]);
+            rest = &escaped[1..];
+            continue;
+        }
+
+        if let Some(body) = after.strip_prefix('{')
+            && let Some(end) = body.find('}')
+            && let Some(value) = variable(&body[..end], env)
+        {
+            expanded.push_str(&value);
+            rest = &body[end + 1..];
+            continue;
+        }
+
+        if let Some(run) = run
+            && let Some(body) = after.strip_prefix('(')
+        {
+            let end =
+                closing_paren(body).ok_or_else(|| format!("unclosed '$(' in \"{}\"", text))?;
+            expanded.push_str(&run(&body[..end])?);
+            rest = &body[end + 1..];
+            continue;
+        }
+
+        expanded.push('$');
+        rest = after;
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// Value of a `${...}` body, or `None` to leave it as written.
+fn variable(body: &str, env: Env) -> Option<String> {
+    let (name, fallback) = match body.split_once(":-") {
+        Some((name, fallback)) => (name, Some(fallback)),
+        None => (body, None),
+    };
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return None;
+    }
+
+    match (env(name), fallback) {
+        (Some(value), Some(fallback)) if value.is_empty() => Some(fallback.to_string()),
+        (Some(value), _) => Some(value),
+        (None, fallback) => fallback.map(str::to_string),
+    }
+}
+
+/// Index of the `)` closing a `$(`, allowing nested parentheses.
+fn closing_paren(body: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in body.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Run a `$(command)` and return its output without trailing newlines.
+fn run_command(command: &str) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run `{}`: {}", command, e))?;
+
+    // Read the pipes while waiting: a command whose output fills a pipe
+    // would otherwise never exit
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + COMMAND_TIMEOUT;
+    let timed_out = || {
+        format!(
+            "`{}` timed out after {}s",
+            command,
+            COMMAND_TIMEOUT.as_secs()
+        )
+    };
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timed_out());
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(e) => return Err(format!("failed to run `{}`: {}", command, e)),
+        }
+    };
+
+    // A background process left behind may keep the pipes open
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    let stdout = stdout.recv_timeout(remaining()).map_err(|_| timed_out())?;
+    let stderr = stderr.recv_timeout(remaining()).map_err(|_| timed_out())?;
+
+    if !status.success() {
+        let reason = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| status.to_string());
+        return Err(format!("`{}` failed: {}", command, reason));
+    }
+
+    Ok(stdout.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Read `pipe` to the end on a thread of its own; the output arrives on the
+/// returned channel.
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+        let _ = sender.send(output);
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "OUTPUT" => Some("DP-2".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn run(command: &str) -> Result<String, String> {
+        match command {
+            "hostname" => Ok("desk".to_string()),
+            "echo $(printf x)" => Ok("x".to_string()),
+            _ => Err(format!("`{}` failed", command)),
+        }
+    }
+
+    fn expand_env(text: &str) -> String {
+        expand(text, &env, None).unwrap()
+    }
+
+    #[test]
+    fn test_expand_variables() {
+        assert_eq!(expand_env("${OUTPUT}"), "DP-2");
+        assert_eq!(expand_env("main on ${OUTPUT}!"), "main on DP-2!");
+        assert_eq!(expand_env("${MISSING:-eDP-1}"), "eDP-1");
+        assert_eq!(expand_env("${EMPTY:-eDP-1}"), "eDP-1");
+        assert_eq!(expand_env("[${EMPTY}]"), "[]");
+        assert_eq!(expand_env("${OUTPUT:-eDP-1}"), "DP-2");
+    }
+
+    #[test]
+    fn test_expand_leaves_shell_syntax() {
+        assert_eq!(expand_env("${MISSING}"), "${MISSING}");
+        assert_eq!(expand_env("${f%.txt} ${OUTPUT}"), "${f%.txt} DP-2");
+        assert_eq!(expand_env("cost: $5, ${unclosed"), "cost: $5, ${unclosed");
+        assert_eq!(expand_env("echo $(date)"), "echo $(date)");
+        assert_eq!(expand_env("kill $$"), "kill $$");
+    }
+
+    #[test]
+    fn test_expand_escapes() {
+        assert_eq!(expand_env("$${OUTPUT}"), "${OUTPUT}");
+        assert_eq!(
+            expand("$$(hostname) $(hostname)", &env, Some(&run)).unwrap(),
+            "$(hostname) desk"
+        );
+    }
+
+    #[test]
+    fn test_expand_commands() {
+        assert_eq!(
+            expand("bar-$(hostname)", &env, Some(&run)).unwrap(),
+            "bar-desk"
+        );
+        assert_eq!(
+            expand("$(echo $(printf x))", &env, Some(&run)).unwrap(),
+            "x"
+        );
+        assert!(expand("$(false)", &env, Some(&run)).is_err());
+        assert!(
+            expand("$(hostname", &env, Some(&run))
+                .unwrap_err()
+                .contains("unclosed")
+        );
+    }
+
+    #[test]
+    fn test_expand_table() {
+        let mut table: Table = toml::from_str(
+            r#"
+            [bar]
+            outputs = ["${VIBEPANEL_TEST_UNSET:-eDP-1}", "HDMI-A-1"]
+            size = 32
+
+            [widgets.clock]
+            format = "$(printf '%s' 12:00)"
+            "#,
+        )
+        .unwrap();
+
+        // Commands stay as written unless enabled
+        expand_table(&mut table, false).unwrap();
+        assert_eq!(table["bar"]["outputs"][0].as_str(), Some("eDP-1"));
+        assert_eq!(
+            table["widgets"]["clock"]["format"].as_str(),
+            Some("$(printf '%s' 12:00)")
+        );
+
+        expand_table(&mut table, true).unwrap();
+        assert_eq!(table["widgets"]["clock"]["format"].as_str(), Some("12:00"));
+
+        let mut table: Table = toml::from_str(
+            r#"
+            [widgets.uptime]
+            exec = "echo $(cut -d' ' -f1 /proc/uptime)"
+            on_click = ["notify-send", "$(date)"]
+            label = "$(printf up)"
+            "#,
+        )
+        .unwrap();
+        expand_table(&mut table, true).unwrap();
+        // Command-valued keys keep `$(...)` for their own shell
+        assert_eq!(
+            table["widgets"]["uptime"]["exec"].as_str(),
+            Some("echo $(cut -d' ' -f1 /proc/uptime)")
+        );
+        assert_eq!(
+            table["widgets"]["uptime"]["on_click"][1].as_str(),
+            Some("$(date)")
+        );
+        assert_eq!(table["widgets"]["uptime"]["label"].as_str(), Some("up"));
+
+        let mut table: Table = toml::from_str("[theme]\naccent = \"$(exit 3)\"").unwrap();
+        let errors = expand_table(&mut table, true).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("theme.accent: "));
+    }
+
+    #[test]
+    fn test_run_command_reads_large_output() {
+        // More than a pipe buffer holds
+        let output = run_command("head -c 200000 /dev/zero | tr '\\0' x").unwrap();
+        assert_eq!(output.len(), 200_000);
+        assert!(run_command("exit 3").is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //! It parses config, computes derived values, and generates CSS variables.
 
 use crate::Config;
+use crate::config::BackgroundConfig;
 
 // Overlay opacities: base values for card backgrounds.
 // Dark mode uses lower opacity (0.06) since white overlays on dark are more visible.
@@ -43,6 +44,10 @@ const FOREGROUND_MUTED_OPACITY: f64 = 0.6;
 const FOREGROUND_DISABLED_OPACITY: f64 = 0.4;
 const FOREGROUND_FAINT_OPACITY: f64 = 0.3;
 
+// WCAG 2.1 minimum contrast ratios: normal text (AA) and UI graphics/icons
+pub const MIN_TEXT_CONTRAST: f64 = 4.5;
+pub const MIN_UI_CONTRAST: f64 = 3.0;
+
 // Toast critical background blend weight
 const TOAST_CRITICAL_URGENT_WEIGHT: f64 = 0.35;
 
@@ -84,6 +89,119 @@ pub enum AccentSource {
     Custom(String),
 }
 
+/// A built-in color scheme, selected with `theme.preset`.
+///
+/// Presets fill in the mode, bar and widget backgrounds, accent and state
+/// colors; any of those keys set in the user config still wins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemePreset {
+    /// Name used in `theme.preset`, e.g. "catppuccin-mocha".
+    pub name: &'static str,
+    /// Theme mode, "dark" or "light".
+    pub mode: &'static str,
+    /// Bar background color.
+    pub bar_background: &'static str,
+    /// Widget background color.
+    pub widget_background: &'static str,
+    /// Accent color.
+    pub accent: &'static str,
+    /// Success state color.
+    pub success: &'static str,
+    /// Warning state color.
+    pub warning: &'static str,
+    /// Urgent state color.
+    pub urgent: &'static str,
+}
+
+/// Built-in theme presets, using each scheme's own palette.
+pub const THEME_PRESETS: &[ThemePreset] = &[
+    ThemePreset {
+        name: "catppuccin-mocha",
+        mode: "dark",
+        bar_background: "#11111b",
+        widget_background: "#1e1e2e",
+        accent: "#cba6f7",
+        success: "#a6e3a1",
+        warning: "#f9e2af",
+        urgent: "#f38ba8",
+    },
+    ThemePreset {
+        name: "catppuccin-macchiato",
+        mode: "dark",
+        bar_background: "#181926",
+        widget_background: "#24273a",
+        accent: "#c6a0f6",
+        success: "#a6da95",
+        warning: "#eed49f",
+        urgent: "#ed8796",
+    },
+    ThemePreset {
+        name: "catppuccin-frappe",
+        mode: "dark",
+        bar_background: "#232634",
+        widget_background: "#303446",
+        accent: "#ca9ee6",
+        success: "#a6d189",
+        warning: "#e5c890",
+        urgent: "#e78284",
+    },
+    ThemePreset {
+        name: "catppuccin-latte",
+        mode: "light",
+        bar_background: "#dce0e8",
+        widget_background: "#eff1f5",
+        accent: "#8839ef",
+        success: "#40a02b",
+        warning: "#df8e1d",
+        urgent: "#d20f39",
+    },
+    ThemePreset {
+        name: "nord",
+        mode: "dark",
+        bar_background: "#2e3440",
+        widget_background: "#3b4252",
+        accent: "#88c0d0",
+        success: "#a3be8c",
+        warning: "#ebcb8b",
+        urgent: "#bf616a",
+    },
+    ThemePreset {
+        name: "gruvbox-dark",
+        mode: "dark",
+        bar_background: "#1d2021",
+        widget_background: "#282828",
+        accent: "#fe8019",
+        success: "#b8bb26",
+        warning: "#fabd2f",
+        urgent: "#fb4934",
+    },
+    ThemePreset {
+        name: "gruvbox-light",
+        mode: "light",
+        bar_background: "#f2e5bc",
+        widget_background: "#fbf1c7",
+        accent: "#af3a03",
+        success: "#79740e",
+        warning: "#b57614",
+        urgent: "#9d0006",
+    },
+    ThemePreset {
+        name: "dracula",
+        mode: "dark",
+        bar_background: "#21222c",
+        widget_background: "#282a36",
+        accent: "#bd93f9",
+        success: "#50fa7b",
+        warning: "#f1fa8c",
+        urgent: "#ff5555",
+    },
+];
+
+/// The built-in preset called `name`.
+pub fn theme_preset(name: &str) -> Option<&'static ThemePreset> {
+    THEME_PRESETS.iter().find(|preset| preset.name == name)
+}
+
 /// Parse a hex color string to RGB tuple. Returns None if invalid.
 pub fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
     let color = color.trim().trim_start_matches('#');
@@ -106,6 +224,421 @@ pub fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
     Some((r, g, b))
 }
 
+/// An sRGB color with alpha, as configured by the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// Alpha from 0.0 (transparent) to 1.0 (opaque).
+    pub a: f64,
+}
+
+impl Rgba {
+    /// The color without its alpha.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
+
+    /// Format as CSS: hex when opaque, `rgba()` otherwise.
+    pub fn to_css(&self) -> String {
+        if self.a >= 1.0 {
+            rgb_to_hex(self.r, self.g, self.b)
+        } else {
+            rgba_str(self.r, self.g, self.b, self.a)
+        }
+    }
+}
+
+/// Parse a configured color. Returns None if invalid.
+///
+/// Accepts hex (`#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`), `rgb()`/`rgba()`,
+/// `hsl()`/`hsla()` (comma or space separated, optional `/ alpha`), CSS named
+/// colors and `transparent`. GTK color references like `@accent_color` are
+/// not colors here and return None.
+pub fn parse_color(color: &str) -> Option<Rgba> {
+    let color = color.trim();
+
+    if color.contains('(') {
+        return parse_color_function(color);
+    }
+    if color.eq_ignore_ascii_case("transparent") {
+        return Some(Rgba {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0.0,
+        });
+    }
+    if let Some(&(_, value)) = NAMED_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(color))
+    {
+        return Some(Rgba {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+            a: 1.0,
+        });
+    }
+
+    let hex = color.trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    // Expand shorthand (e.g., "fff8" -> "ffffff88")
+    let hex = if hex.len() == 3 || hex.len() == 4 {
+        hex.chars().flat_map(|c| [c, c]).collect::<String>()
+    } else {
+        hex.to_string()
+    };
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let a = match hex.len() {
+        6 => 1.0,
+        8 => channel(6)? as f64 / 255.0,
+        _ => return None,
+    };
+
+    Some(Rgba {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+        a,
+    })
+}
+
+/// Parse `rgb()`, `rgba()`, `hsl()` or `hsla()` notation.
+fn parse_color_function(color: &str) -> Option<Rgba> {
+    let (name, args) = color.split_once('(')?;
+    let args = args.strip_suffix(')')?;
+
+    // Modern syntax puts alpha after a slash: "rgb(255 0 0 / 50%)"
+    let (channels, alpha) = match args.split_once('/') {
+        Some((channels, alpha)) => (channels, Some(alpha.trim())),
+        None => (args, None),
+    };
+    let mut parts: Vec<&str> = channels
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let alpha = match alpha {
+        Some(alpha) => Some(alpha),
+        None if parts.len() == 4 => parts.pop(),
+        None => None,
+    };
+    if parts.len() != 3 {
+        return None;
+    }
+    let a = match alpha {
+        Some(alpha) => parse_unit_value(alpha)?,
+        None => 1.0,
+    };
+
+    let (r, g, b) = match name.trim().to_ascii_lowercase().as_str() {
+        "rgb" | "rgba" => {
+            let channel = |part: &str| -> Option<u8> {
+                let value = match part.strip_suffix('%') {
+                    Some(percent) => parse_number(percent)? / 100.0 * 255.0,
+                    None => parse_number(part)?,
+                };
+                Some(value.round().clamp(0.0, 255.0) as u8)
+            };
+            (channel(parts[0])?, channel(parts[1])?, channel(parts[2])?)
+        }
+        "hsl" | "hsla" => {
+            let hue = parse_number(parts[0].trim_end_matches("deg"))?;
+            let percent = |part: &str| -> Option<f64> {
+                let value = parse_number(part.strip_suffix('%').unwrap_or(part))?;
+                Some((value / 100.0).clamp(0.0, 1.0))
+            };
+            hsl_to_rgb(hue, percent(parts[1])?, percent(parts[2])?)
+        }
+        _ => return None,
+    };
+
+    Some(Rgba { r, g, b, a })
+}
+
+/// Parse a finite number.
+fn parse_number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Parse an alpha value: a number from 0 to 1 or a percentage.
+fn parse_unit_value(value: &str) -> Option<f64> {
+    let value = match value.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(value)?,
+    };
+    Some(value.clamp(0.0, 1.0))
+}
+
+/// Convert HSL (hue in degrees, saturation and lightness 0.0-1.0) to RGB.
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Normalize a configured color to CSS, passing through anything that isn't
+/// a color we understand (e.g. GTK references like `@error_color`).
+fn normalize_color(color: &str) -> String {
+    parse_color(color)
+        .map(|rgba| rgba.to_css())
+        .unwrap_or_else(|| color.to_string())
+}
+
+/// `name` with the characters CSS class names can't hold replaced by `-`.
+fn css_class_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// CSS class of the bar window on an output, e.g. `output-DP-1`.
+pub fn output_css_class(output: &str) -> String {
+    format!("output-{}", css_class_name(output))
+}
+
+/// CSS class of the windows of a `[[bars]]` bar, e.g. `extra-bar-dock`.
+pub fn extra_bar_css_class(name: &str) -> String {
+    format!("extra-bar-{}", css_class_name(name))
+}
+
+/// CSS `background-image` value for a configured background.
+///
+/// Gradient colors have their alpha scaled by `opacity`. Returns None if the
+/// background has neither a usable gradient nor an image.
+fn background_image(background: &BackgroundConfig, opacity: f64) -> Option<String> {
+    if let Some(ref image) = background.image {
+        return Some(format!("url(\"{}\")", file_url(image)));
+    }
+
+    let stops: Vec<String> = background
+        .gradient
+        .iter()
+        .filter_map(|color| parse_color(color))
+        .map(|color| {
+            Rgba {
+                a: color.a * opacity.clamp(0.0, 1.0),
+                ..color
+            }
+            .to_css()
+        })
+        .collect();
+    if stops.len() < 2 {
+        return None;
+    }
+
+    Some(format!(
+        "linear-gradient({}deg, {})",
+        background.angle,
+        stops.join(", ")
+    ))
+}
+
+/// `background-size` / `background-repeat` / `background-position` for a background mode.
+fn background_layout(background: &BackgroundConfig) -> &'static str {
+    match background.mode.as_str() {
+        "contain" => {
+            "background-size: contain;\n    background-repeat: no-repeat;\n    background-position: center;"
+        }
+        "tile" => "background-size: auto;\n    background-repeat: repeat;",
+        "stretch" => "background-size: 100% 100%;\n    background-repeat: no-repeat;",
+        // "cover"
+        _ => {
+            "background-size: cover;\n    background-repeat: no-repeat;\n    background-position: center;"
+        }
+    }
+}
+
+/// Build a `file://` URL for an image path, expanding a leading `~`.
+///
+/// CSS loaded from a string has no base location, so relative `url()`s
+/// can't be resolved; bytes outside the URL-safe set are percent-encoded.
+fn file_url(path: &str) -> String {
+    let path = match (path.strip_prefix('~'), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home, rest)
+        }
+        _ => path.to_string(),
+    };
+
+    let mut url = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+/// CSS named colors.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
 /// Calculate relative luminance per WCAG formula (0.0 = black, 1.0 = white).
 pub fn relative_luminance(r: u8, g: u8, b: u8) -> f64 {
     fn channel(c: u8) -> f64 {
@@ -127,18 +660,18 @@ pub fn is_dark_color(color: &str) -> bool {
 
 /// Return true if the color is considered dark, with custom threshold.
 pub fn is_dark_color_with_threshold(color: &str, threshold: f64) -> bool {
-    match parse_hex_color(color) {
-        Some((r, g, b)) => relative_luminance(r, g, b) < threshold,
+    match parse_color(color) {
+        Some(Rgba { r, g, b, .. }) => relative_luminance(r, g, b) < threshold,
         None => true, // Default to dark if parsing fails
     }
 }
 
-/// Blend two hex colors together.
+/// Blend two colors together, ignoring their alpha.
 ///
 /// `weight1` is the weight for color1 (0.0 to 1.0), color2 gets (1 - weight1).
 pub fn blend_colors(color1: &str, color2: &str, weight1: f64) -> Option<(u8, u8, u8)> {
-    let rgb1 = parse_hex_color(color1)?;
-    let rgb2 = parse_hex_color(color2)?;
+    let rgb1 = parse_color(color1)?.rgb();
+    let rgb2 = parse_color(color2)?.rgb();
 
     let weight2 = 1.0 - weight1;
     let r = (rgb1.0 as f64 * weight1 + rgb2.0 as f64 * weight2) as u8;
@@ -158,6 +691,46 @@ pub fn rgba_str(r: u8, g: u8, b: u8, a: f64) -> String {
     format!("rgba({}, {}, {}, {:.2})", r, g, b, a)
 }
 
+/// Calculate the WCAG contrast ratio between two colors (1.0 to 21.0).
+pub fn contrast_ratio(color1: (u8, u8, u8), color2: (u8, u8, u8)) -> f64 {
+    let l1 = relative_luminance(color1.0, color1.1, color1.2);
+    let l2 = relative_luminance(color2.0, color2.1, color2.2);
+    let (lighter, darker) = if l1 >= l2 { (l1, l2) } else { (l2, l1) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// A foreground/background pair whose contrast is below the WCAG minimum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastWarning {
+    /// Name of the foreground color, e.g. "accent".
+    pub foreground: String,
+    /// Resolved foreground color as hex.
+    pub foreground_color: String,
+    /// Name of the background color, e.g. "widget background".
+    pub background: String,
+    /// Resolved background color as hex.
+    pub background_color: String,
+    /// Computed contrast ratio.
+    pub ratio: f64,
+    /// Minimum ratio required for this pair.
+    pub minimum: f64,
+}
+
+impl std::fmt::Display for ContrastWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}) on {} ({}): contrast {:.1}:1, needs {:.1}:1",
+            self.foreground,
+            self.foreground_color,
+            self.background,
+            self.background_color,
+            self.ratio,
+            self.minimum
+        )
+    }
+}
+
 /// Computed sizes based on bar height.
 #[derive(Debug, Clone)]
 pub struct ThemeSizes {
@@ -281,6 +854,7 @@ pub struct ThemePalette {
     widget_radius_percent: u32,
     bar_size: u32,
     bar_padding: u32,
+    bar_bottom: bool,
 }
 
 impl ThemePalette {
@@ -292,10 +866,30 @@ impl ThemePalette {
         palette
     }
 
+    /// CSS that swaps the accent for `color` (e.g. the focused workspace's
+    /// color), meant to be loaded above the theme CSS.
+    pub fn accent_override_css(color: &str) -> String {
+        let color = normalize_color(color);
+        format!(
+            r#"
+:root {{
+    --color-accent-primary: {color};
+    --color-accent-subtle: color-mix(in srgb, {color} 20%, transparent);
+}}
+"#
+        )
+    }
+
     /// Generate the :root CSS variable block.
     pub fn css_vars_block(&self) -> String {
         // For GTK accent mode, we reference @accent_color in CSS.
         // For custom/none modes, we use computed values.
+        let inner_padding = if self.bar_opacity > 0.0 {
+            self.bar_padding
+        } else {
+            0
+        };
+
         let (accent_primary_css, accent_subtle_css) = match &self.accent_source {
             AccentSource::Gtk => (
                 // Reference GTK's accent color
@@ -457,14 +1051,19 @@ impl ThemePalette {
             radius_pill = self.radius_pill,
             radius_factor = (self.widget_radius_percent as f64 / 50.0).min(1.0),
             bar_height = self.sizes.bar_height,
-            // Visual padding always applies (widgets offset from edge),
-            // but exclusive zone only includes it when bar is visible (handled in bar.rs)
-            bar_padding_y = self.bar_padding,
-            // Bottom padding is 0 in islands mode (opacity=0) to keep exclusive zone tight
-            bar_padding_y_bottom = if self.bar_opacity > 0.0 {
+            // Visual padding always applies on the screen edge side (widgets offset
+            // from edge), but exclusive zone only includes it when bar is visible
+            // (handled in bar.rs). The inner side is 0 in islands mode (opacity=0)
+            // to keep the exclusive zone tight.
+            bar_padding_y = if self.bar_bottom {
+                inner_padding
+            } else {
+                self.bar_padding
+            },
+            bar_padding_y_bottom = if self.bar_bottom {
                 self.bar_padding
             } else {
-                0
+                inner_padding
             },
             widget_height = self.sizes.widget_height,
             widget_padding_x = self.sizes.widget_padding_x,
@@ -519,7 +1118,8 @@ impl ThemePalette {
     /// Generate per-widget CSS overrides from `[widgets.xxx]` config sections.
     ///
     /// Generates rules like `.widget.clock, .clock-popover { --widget-background-color: #f5c2e7; }`.
-    /// Widget names are normalized to CSS conventions (underscores → hyphens).
+    /// Widget names are normalized to CSS conventions (underscores and the
+    /// colon in `custom:<name>` → hyphens).
     pub fn generate_per_widget_css(config: &Config) -> String {
         let mut css = String::new();
 
@@ -527,21 +1127,31 @@ impl ThemePalette {
             let mut rules = Vec::new();
 
             if let Some(ref color) = options.background_color {
-                if let Some((r, g, b)) = parse_hex_color(color) {
-                    let normalized = format!("#{:02x}{:02x}{:02x}", r, g, b);
-                    rules.push(format!("--widget-background-color: {};", normalized));
+                if let Some(rgba) = parse_color(color) {
+                    rules.push(format!(
+                        "--widget-background-color: {};",
+                        rgb_to_hex(rgba.r, rgba.g, rgba.b)
+                    ));
+                    // Alpha in the color scales the global widget opacity
+                    if rgba.a < 1.0 {
+                        let opacity = config.widgets.background_opacity * rgba.a;
+                        rules.push(format!(
+                            "--widget-background-opacity: {}%;",
+                            (opacity * 100.0).round() as u32
+                        ));
+                    }
                 } else {
                     tracing::warn!(
-                        "Invalid background_color '{}' for widget '{}' - expected hex color",
+                        "Invalid background_color '{}' for widget '{}' - expected a color",
                         color,
                         widget_name
                     );
                 }
             }
 
+            let css_name = widget_name.replace(['_', ':'], "-");
             if !rules.is_empty() {
                 let rules_str = rules.join("\n    ");
-                let css_name = widget_name.replace('_', "-");
                 css.push_str(&format!(
                     r#"
 .widget.{css_name},
@@ -554,11 +1164,314 @@ impl ThemePalette {
                     rules = rules_str
                 ));
             }
+
+            // Margin and padding apply to the island, or to the widget's
+            // item when it sits in a group, but never to its popover
+            let mut box_rules = Vec::new();
+            for (property, spacing, allow_negative) in [
+                ("margin", &options.margin, true),
+                ("padding", &options.padding, false),
+            ] {
+                let Some(spacing) = spacing else {
+                    continue;
+                };
+                match spacing.to_css(allow_negative) {
+                    Some(value) => box_rules.push(format!("{}: {};", property, value)),
+                    None => tracing::warn!(
+                        "Invalid {} {:?} for widget '{}' - expected one to four pixel lengths",
+                        property,
+                        spacing,
+                        widget_name
+                    ),
+                }
+            }
+
+            if !box_rules.is_empty() {
+                css.push_str(&format!(
+                    r#"
+.widget.{css_name}:not(.widget-group),
+.widget-group > .content > .widget-item.{css_name} {{
+    {rules}
+}}
+"#,
+                    css_name = css_name,
+                    rules = box_rules.join("\n    ")
+                ));
+            }
+        }
+
+        css
+    }
+
+    /// Generate size overrides for `[bar.output."<connector>"]` sections.
+    ///
+    /// The `:root` block sizes everything from `bar.size`; outputs that
+    /// override the size or position get the size variables redefined on
+    /// their bar window (see [`output_css_class`]).
+    pub fn generate_per_output_css(config: &Config) -> String {
+        let mut css = String::new();
+
+        let mut outputs: Vec<_> = config.bar.output.iter().collect();
+        outputs.sort_by_key(|(name, _)| name.as_str());
+
+        for (name, output) in outputs {
+            if output.size.is_none() && output.position.is_none() {
+                continue;
+            }
+
+            let palette = Self::from_config(&config.for_output(name));
+            css.push_str(&format!(
+                r#"
+.bar-window.{class} {{
+{vars}
+}}
+"#,
+                class = output_css_class(name),
+                vars = palette.size_css_vars()
+            ));
         }
 
         css
     }
 
+    /// Generate the size variables of each `[[bars]]` bar, scoped to its
+    /// windows (see [`extra_bar_css_class`]).
+    pub fn generate_extra_bar_css(config: &Config) -> String {
+        let mut css = String::new();
+
+        for bar in &config.bars {
+            let palette = Self::from_config(&config.for_bar(bar));
+            css.push_str(&format!(
+                r#"
+.bar-window.{class} {{
+{vars}
+}}
+"#,
+                class = extra_bar_css_class(&bar.name),
+                vars = palette.size_css_vars()
+            ));
+        }
+
+        css
+    }
+
+    /// Size-dependent variables of the `:root` block, for per-output and
+    /// per-bar overrides.
+    ///
+    /// `--font-size` is repeated because a `calc()` at `:root` is resolved
+    /// there and wouldn't follow a redefined `--widget-height`.
+    fn size_css_vars(&self) -> String {
+        let inner_padding = if self.bar_opacity > 0.0 {
+            self.bar_padding
+        } else {
+            0
+        };
+        let (padding_y, padding_y_bottom) = if self.bar_bottom {
+            (inner_padding, self.bar_padding)
+        } else {
+            (self.bar_padding, inner_padding)
+        };
+        let radius_widget = if self.widget_radius_percent >= 50 {
+            "9999px".to_string()
+        } else {
+            format!("{}px", self.widget_border_radius)
+        };
+
+        [
+            format!("--radius-widget: {};", radius_widget),
+            format!("--radius-widget-lg: calc({} * 2);", radius_widget),
+            format!("--radius-pill: {}px;", self.radius_pill),
+            format!("--radius-card: {}px;", self.widget_border_radius),
+            format!("--bar-height: {}px;", self.sizes.bar_height),
+            format!("--bar-padding-y: {}px;", padding_y),
+            format!("--bar-padding-y-bottom: {}px;", padding_y_bottom),
+            format!("--widget-height: {}px;", self.sizes.widget_height),
+            format!("--widget-padding-x: {}px;", self.sizes.widget_padding_x),
+            format!("--widget-padding-y: {}px;", self.sizes.widget_padding_y),
+            format!("--spacing-internal: {}px;", self.sizes.internal_spacing),
+            format!(
+                "--spacing-widget-edge: {}px;",
+                self.sizes.widget_content_edge
+            ),
+            format!("--spacing-widget-gap: {}px;", self.sizes.widget_content_gap),
+            "--font-size: calc(var(--widget-height) * var(--font-scale));".to_string(),
+            format!("--font-size-text-icon: {}px;", self.sizes.text_icon_size),
+            format!("--pixmap-icon-size: {}px;", self.sizes.pixmap_icon_size),
+            format!("--icon-size: {}px;", self.sizes.text_icon_size),
+        ]
+        .iter()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    /// Generate CSS for `theme.bar_background` / `theme.widget_background`.
+    ///
+    /// Gradients and images are drawn as `background-image` on top of the
+    /// background color. Gradient colors are faded by the matching
+    /// `background_opacity`; images are drawn as-is. The bar background is
+    /// skipped while the bar itself is transparent (islands mode).
+    ///
+    /// Must come after the widget CSS so the hover overlay can be layered
+    /// over the widget background.
+    pub fn generate_background_css(config: &Config) -> String {
+        let mut css = String::new();
+
+        if let Some(ref background) = config.theme.bar_background
+            && config.bar.background_opacity > 0.0
+            && let Some(image) = background_image(background, config.bar.background_opacity)
+        {
+            css.push_str(&format!(
+                r#"
+sectioned-bar.bar {{
+    background-image: {image};
+    {layout}
+}}
+"#,
+                image = image,
+                layout = background_layout(background),
+            ));
+        }
+
+        if let Some(ref background) = config.theme.widget_background
+            && let Some(image) = background_image(background, config.widgets.background_opacity)
+        {
+            css.push_str(&format!(
+                r#"
+.widget {{
+    background-image: {image};
+    {layout}
+}}
+
+.widget.clickable:not(.widget-group):hover {{
+    background-image: linear-gradient(var(--color-card-overlay-hover), var(--color-card-overlay-hover)), {image};
+}}
+"#,
+                image = image,
+                layout = background_layout(background),
+            ));
+        }
+
+        css
+    }
+
+    /// Check the resolved theme colors for combinations below WCAG contrast minimums.
+    ///
+    /// Text colors are checked against every opaque widget background (the global
+    /// one and per-widget `background_color` overrides). The accent and state
+    /// colors are only checked when set in the config. Colors that can't be
+    /// resolved here are skipped: GTK theme references and backgrounds that
+    /// show the wallpaper through.
+    pub fn lint_contrast(config: &Config) -> Vec<ContrastWarning> {
+        let palette = Self::from_config(config);
+
+        let mut backgrounds = Vec::new();
+        if let Some(rgb) = parse_color(&palette.widget_background)
+            .and_then(|color| palette.effective_widget_background(color, palette.widget_opacity))
+        {
+            backgrounds.push(("widget background".to_string(), rgb));
+        }
+        let mut overrides: Vec<_> = config.widgets.widget_configs.iter().collect();
+        overrides.sort_by(|a, b| a.0.cmp(b.0));
+        for (widget_name, options) in overrides {
+            if let Some(rgb) = options
+                .background_color
+                .as_deref()
+                .and_then(parse_color)
+                .and_then(|color| {
+                    let opacity = config.widgets.background_opacity * color.a;
+                    palette.effective_widget_background(color, opacity)
+                })
+            {
+                backgrounds.push((format!("widgets.{} background", widget_name), rgb));
+            }
+        }
+
+        let text_base = if palette.is_dark_mode {
+            "#ffffff"
+        } else {
+            "#000000"
+        };
+        let defaults = crate::config::ThemeStates::default();
+        let mut foregrounds: Vec<(&str, &str, f64)> = Vec::new();
+        if let AccentSource::Custom(color) = &palette.accent_source
+            && config.theme.accent.is_some()
+        {
+            foregrounds.push(("accent", color, MIN_UI_CONTRAST));
+        }
+        let states = &config.theme.states;
+        for (name, color, configured, default) in [
+            (
+                "success",
+                &palette.state_success,
+                &states.success,
+                &defaults.success,
+            ),
+            (
+                "warning",
+                &palette.state_warning,
+                &states.warning,
+                &defaults.warning,
+            ),
+            (
+                "urgent",
+                &palette.state_urgent,
+                &states.urgent,
+                &defaults.urgent,
+            ),
+        ] {
+            if configured != default {
+                foregrounds.push((name, color, MIN_UI_CONTRAST));
+            }
+        }
+
+        let mut warnings = Vec::new();
+        for (background, bg) in &backgrounds {
+            let bg_hex = rgb_to_hex(bg.0, bg.1, bg.2);
+            let mut check = |name: &str, fg: (u8, u8, u8), minimum: f64| {
+                let ratio = contrast_ratio(fg, *bg);
+                if ratio < minimum {
+                    warnings.push(ContrastWarning {
+                        foreground: name.to_string(),
+                        foreground_color: rgb_to_hex(fg.0, fg.1, fg.2),
+                        background: background.clone(),
+                        background_color: bg_hex.clone(),
+                        ratio,
+                        minimum,
+                    });
+                }
+            };
+
+            if let Some(fg) = parse_color(&palette.foreground_primary) {
+                check("text", fg.rgb(), MIN_TEXT_CONTRAST);
+            }
+            if let Some(fg) = blend_colors(text_base, &bg_hex, FOREGROUND_MUTED_OPACITY) {
+                check("muted text", fg, MIN_TEXT_CONTRAST);
+            }
+            for (name, color, minimum) in &foregrounds {
+                if let Some(fg) = parse_color(color) {
+                    check(name, fg.rgb(), *minimum);
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Resolve the color a widget background actually renders as, if knowable.
+    ///
+    /// Translucent widgets blend with the bar; if the bar is translucent too the
+    /// wallpaper shows through and the result can't be determined.
+    fn effective_widget_background(&self, color: Rgba, opacity: f64) -> Option<(u8, u8, u8)> {
+        if opacity >= 1.0 {
+            Some(color.rgb())
+        } else if self.bar_opacity >= 1.0 {
+            blend_colors(&color.to_css(), &self.bar_background, opacity)
+        } else {
+            None
+        }
+    }
+
     fn parse_config(&mut self, config: &Config) {
         // Check if GTK mode is requested
         self.is_gtk_mode = config.theme.mode == "gtk";
@@ -598,6 +1511,17 @@ impl ThemePalette {
         self.bar_opacity = config.bar.background_opacity;
         self.widget_opacity = config.widgets.background_opacity;
 
+        // Normalize configured backgrounds to hex, folding any alpha into the
+        // opacity. GTK references like @window_bg_color are kept as-is.
+        if let Some(color) = parse_color(&self.bar_background) {
+            self.bar_background = rgb_to_hex(color.r, color.g, color.b);
+            self.bar_opacity *= color.a;
+        }
+        if let Some(color) = parse_color(&self.widget_background) {
+            self.widget_background = rgb_to_hex(color.r, color.g, color.b);
+            self.widget_opacity *= color.a;
+        }
+
         // Resolve is_dark_mode
         // For GTK mode, we assume dark for overlay calculations since we can't query GTK's actual colors at build time
         self.is_dark_mode = match config.theme.mode.as_str() {
@@ -619,7 +1543,15 @@ impl ThemePalette {
         self.accent_source = match accent_str {
             "gtk" => AccentSource::Gtk,
             "none" => AccentSource::None,
-            color => AccentSource::Custom(color.to_string()),
+            // Until the wallpaper has been sampled, use the default accent
+            "wallpaper" => AccentSource::Custom(normalize_color(
+                config
+                    .theme
+                    .wallpaper_accent
+                    .as_deref()
+                    .unwrap_or("#adabe0"),
+            )),
+            color => AccentSource::Custom(normalize_color(color)),
         };
 
         // Set accent colors based on source
@@ -643,9 +1575,9 @@ impl ThemePalette {
         }
 
         // State colors
-        self.state_success = config.theme.states.success.clone();
-        self.state_warning = config.theme.states.warning.clone();
-        self.state_urgent = config.theme.states.urgent.clone();
+        self.state_success = normalize_color(&config.theme.states.success);
+        self.state_warning = normalize_color(&config.theme.states.warning);
+        self.state_urgent = normalize_color(&config.theme.states.urgent);
 
         // Typography - use "inherit" for empty font_family to use system font
         self.font_family = if config.theme.typography.font_family.is_empty() {
@@ -661,6 +1593,7 @@ impl ThemePalette {
         // Bar size
         self.bar_size = config.bar.size;
         self.bar_padding = config.bar.padding;
+        self.bar_bottom = config.bar.is_bottom();
     }
 
     fn compute_derived_values(&mut self) {
@@ -896,6 +1829,7 @@ impl Default for ThemePalette {
             widget_radius_percent: 40,
             bar_size: 32,
             bar_padding: 4,
+            bar_bottom: false,
         }
     }
 }
@@ -920,6 +1854,89 @@ mod tests {
         assert_eq!(parse_hex_color("#ff"), None);
     }
 
+    #[test]
+    fn test_parse_color_formats() {
+        let rgba = |r, g, b, a| Some(Rgba { r, g, b, a });
+        assert_eq!(parse_color("#3584e4"), rgba(0x35, 0x84, 0xe4, 1.0));
+        assert_eq!(parse_color("#fff"), rgba(255, 255, 255, 1.0));
+        assert_eq!(parse_color("#00000080"), rgba(0, 0, 0, 128.0 / 255.0));
+        assert_eq!(parse_color("rgb(53, 132, 228)"), rgba(53, 132, 228, 1.0));
+        assert_eq!(parse_color("rgba(0, 0, 0, 0.5)"), rgba(0, 0, 0, 0.5));
+        assert_eq!(parse_color("rgb(100% 0% 0% / 25%)"), rgba(255, 0, 0, 0.25));
+        assert_eq!(parse_color("hsl(0, 100%, 50%)"), rgba(255, 0, 0, 1.0));
+        assert_eq!(parse_color("hsl(120deg 100% 25%)"), rgba(0, 128, 0, 1.0));
+        assert_eq!(
+            parse_color("hsla(240, 100%, 50%, 0.5)"),
+            rgba(0, 0, 255, 0.5)
+        );
+        assert_eq!(parse_color("RebeccaPurple"), rgba(0x66, 0x33, 0x99, 1.0));
+        assert_eq!(parse_color("transparent"), rgba(0, 0, 0, 0.0));
+    }
+
+    #[test]
+    fn test_parse_color_invalid() {
+        assert_eq!(parse_color("@accent_color"), None);
+        assert_eq!(parse_color("notacolor"), None);
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("rgb(1, 2)"), None);
+        assert_eq!(parse_color("rgb(1, 2, x)"), None);
+        assert_eq!(parse_color("cmyk(1, 2, 3, 4)"), None);
+        assert_eq!(parse_color("rgb(NaN, 0, 0)"), None);
+    }
+
+    #[test]
+    fn test_theme_presets_valid() {
+        for preset in THEME_PRESETS {
+            assert!(matches!(preset.mode, "dark" | "light"), "{}", preset.name);
+            for color in [
+                preset.bar_background,
+                preset.widget_background,
+                preset.accent,
+                preset.success,
+                preset.warning,
+                preset.urgent,
+            ] {
+                assert!(
+                    parse_hex_color(color).is_some(),
+                    "{}: {}",
+                    preset.name,
+                    color
+                );
+            }
+        }
+        assert_eq!(theme_preset("dracula").unwrap().accent, "#bd93f9");
+        assert!(theme_preset("solarized").is_none());
+    }
+
+    #[test]
+    fn test_color_formats_normalized_in_palette() {
+        let mut config = Config::default();
+        config.theme.accent = Some("hsl(240, 100%, 50%)".to_string());
+        config.theme.states.urgent = "tomato".to_string();
+        config.widgets.background_color = Some("rgba(0, 0, 0, 0.5)".to_string());
+        config.widgets.widget_configs.insert(
+            "clock".to_string(),
+            crate::config::WidgetOptions {
+                background_color: Some("#ff000080".to_string()),
+                ..Default::default()
+            },
+        );
+        let palette = ThemePalette::from_config(&config);
+
+        assert_eq!(
+            palette.accent_source,
+            AccentSource::Custom("#0000ff".to_string())
+        );
+        assert_eq!(palette.state_urgent, "#ff6347");
+        // Background alpha folds into the widget opacity
+        assert_eq!(palette.widget_background, "#000000");
+        assert!((palette.widget_opacity - 0.5).abs() < 0.001);
+
+        let css = ThemePalette::generate_per_widget_css(&config);
+        assert!(css.contains("--widget-background-color: #ff0000;"));
+        assert!(css.contains("--widget-background-opacity: 50%;"));
+    }
+
     #[test]
     fn test_relative_luminance() {
         // Black should be 0
@@ -1049,6 +2066,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generate_per_widget_css_normalizes_custom_names() {
+        use crate::config::WidgetOptions;
+
+        let mut config = Config::default();
+        config.widgets.widget_configs.insert(
+            "custom:uptime".to_string(),
+            WidgetOptions {
+                background_color: Some("#00ff00".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let css = ThemePalette::generate_per_widget_css(&config);
+
+        assert!(
+            css.contains(".widget.custom-uptime"),
+            "should turn the colon into a hyphen"
+        );
+    }
+
+    #[test]
+    fn test_generate_per_widget_css_margin_and_padding() {
+        use crate::config::{BoxSpacing, WidgetOptions};
+
+        let mut config = Config::default();
+        config.widgets.widget_configs.insert(
+            "cpu".to_string(),
+            WidgetOptions {
+                margin: Some(BoxSpacing::Css("0 -2px".to_string())),
+                padding: Some(BoxSpacing::Pixels(6)),
+                ..Default::default()
+            },
+        );
+        config.widgets.widget_configs.insert(
+            "memory".to_string(),
+            WidgetOptions {
+                padding: Some(BoxSpacing::Pixels(-6)),
+                ..Default::default()
+            },
+        );
+
+        let css = ThemePalette::generate_per_widget_css(&config);
+
+        assert!(css.contains(".widget.cpu:not(.widget-group)"));
+        assert!(css.contains(".widget-group > .content > .widget-item.cpu"));
+        assert!(css.contains("margin: 0px -2px;"));
+        assert!(css.contains("padding: 6px;"));
+        assert!(
+            !css.contains(".cpu-popover"),
+            "margin and padding should not reach the popover"
+        );
+        // Negative padding is invalid and dropped
+        assert!(!css.contains("memory"));
+    }
+
     #[test]
     fn test_generate_per_widget_css_empty_without_overrides() {
         let config = Config::default();
@@ -1061,6 +2134,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generate_background_css_gradient() {
+        let mut config: Config = toml::from_str(
+            r##"
+            [theme]
+            bar_background = { gradient = ["#1a1a2e", "#16213e"], angle = 90 }
+            "##,
+        )
+        .unwrap();
+
+        // Transparent bar (islands mode) draws no bar background
+        assert!(ThemePalette::generate_background_css(&config).is_empty());
+
+        config.bar.background_opacity = 1.0;
+        let css = ThemePalette::generate_background_css(&config);
+        assert!(css.contains("sectioned-bar.bar"));
+        assert!(css.contains("linear-gradient(90deg, #1a1a2e, #16213e)"));
+
+        // Opacity fades the gradient stops
+        config.bar.background_opacity = 0.5;
+        let css = ThemePalette::generate_background_css(&config);
+        assert!(
+            css.contains("linear-gradient(90deg, rgba(26, 26, 46, 0.50), rgba(22, 33, 62, 0.50))")
+        );
+    }
+
+    #[test]
+    fn test_generate_background_css_image() {
+        let config: Config = toml::from_str(
+            r#"
+            [theme]
+            widget_background = { image = "/usr/share/backgrounds/my paper.png", mode = "tile" }
+            "#,
+        )
+        .unwrap();
+        let css = ThemePalette::generate_background_css(&config);
+        assert!(css.contains(r#"url("file:///usr/share/backgrounds/my%20paper.png")"#));
+        assert!(css.contains("background-repeat: repeat;"));
+        // Hover overlay is layered over the image
+        assert!(css.contains(".widget.clickable:not(.widget-group):hover"));
+    }
+
+    #[test]
+    fn test_bar_padding_follows_position() {
+        let mut config = Config::default();
+        config.bar.padding = 6;
+        let css = ThemePalette::from_config(&config).css_vars_block();
+        assert!(css.contains("--bar-padding-y: 6px;"));
+        assert!(css.contains("--bar-padding-y-bottom: 0px;"));
+
+        config.bar.position = "bottom".to_string();
+        let css = ThemePalette::from_config(&config).css_vars_block();
+        assert!(css.contains("--bar-padding-y: 0px;"));
+        assert!(css.contains("--bar-padding-y-bottom: 6px;"));
+    }
+
+    #[test]
+    fn test_per_output_css() {
+        let mut config = Config::default();
+        assert!(ThemePalette::generate_per_output_css(&config).is_empty());
+
+        config.bar.output.insert(
+            "HDMI-A-1".to_string(),
+            crate::config::BarOutputConfig {
+                size: Some(20),
+                position: Some("bottom".to_string()),
+                ..Default::default()
+            },
+        );
+        // Layout-only overrides don't need size variables
+        config.bar.output.insert(
+            "DP-1".to_string(),
+            crate::config::BarOutputConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        );
+
+        let css = ThemePalette::generate_per_output_css(&config);
+        assert!(css.contains(".bar-window.output-HDMI-A-1 {"));
+        assert!(!css.contains("output-DP-1"));
+        assert!(css.contains("--bar-height: 20px;"));
+        assert!(css.contains("--bar-padding-y: 0px;"));
+        assert!(css.contains("--font-size: calc(var(--widget-height) * var(--font-scale));"));
+    }
+
+    #[test]
+    fn test_output_css_class() {
+        assert_eq!(output_css_class("DP-1"), "output-DP-1");
+        assert_eq!(output_css_class("HDMI A.1"), "output-HDMI-A-1");
+        assert_eq!(extra_bar_css_class("my dock"), "extra-bar-my-dock");
+    }
+
+    #[test]
+    fn test_extra_bar_css() {
+        let mut config = Config::default();
+        assert!(ThemePalette::generate_extra_bar_css(&config).is_empty());
+
+        config.bars.push(crate::config::ExtraBarConfig {
+            name: "dock".to_string(),
+            size: Some(48),
+            ..Default::default()
+        });
+        let css = ThemePalette::generate_extra_bar_css(&config);
+        assert!(css.contains(".bar-window.extra-bar-dock {"));
+        assert!(css.contains("--bar-height: 48px;"));
+    }
+
     #[test]
     fn test_theme_sizes_computed_from_bar_size() {
         let mut config = Config::default();
@@ -1073,6 +2254,15 @@ mod tests {
         assert!(palette.sizes.font_size > 0);
     }
 
+    #[test]
+    fn test_accent_override_css() {
+        let css = ThemePalette::accent_override_css("red");
+        assert!(css.contains("--color-accent-primary: #ff0000;"));
+        assert!(
+            css.contains("--color-accent-subtle: color-mix(in srgb, #ff0000 20%, transparent);")
+        );
+    }
+
     #[test]
     fn test_accent_default_is_custom() {
         // Default accent = None with mode = "auto" means use "#adabe0" as custom hex color
@@ -1115,6 +2305,16 @@ mod tests {
         assert!(css.contains("--color-accent-primary: #ff0000"));
     }
 
+    #[test]
+    fn test_accent_wallpaper() {
+        let mut config = Config::default();
+        config.theme.accent = Some("wallpaper".to_string());
+        assert_eq!(ThemePalette::from_config(&config).accent_primary, "#adabe0");
+
+        config.theme.wallpaper_accent = Some("#3a7bd5".to_string());
+        assert_eq!(ThemePalette::from_config(&config).accent_primary, "#3a7bd5");
+    }
+
     #[test]
     fn test_accent_none_monochrome() {
         // When accent = "none", use monochrome mode
@@ -1247,4 +2447,73 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio((255, 255, 255), (0, 0, 0)) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio((17, 18, 23), (17, 18, 23)) - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_lint_contrast_defaults() {
+        let mut config = Config::default();
+        assert!(ThemePalette::lint_contrast(&config).is_empty());
+
+        config.theme.mode = "light".to_string();
+        assert!(ThemePalette::lint_contrast(&config).is_empty());
+
+        // GTK colors are resolved at runtime and can't be checked
+        config.theme.mode = "gtk".to_string();
+        config.theme.accent = Some("#ffffff".to_string());
+        assert!(ThemePalette::lint_contrast(&config).is_empty());
+    }
+
+    #[test]
+    fn test_lint_contrast_low_contrast_colors() {
+        let mut config = Config::default();
+        config.theme.mode = "light".to_string();
+        config.theme.accent = Some("#f0f0f0".to_string());
+        config.widgets.widget_configs.insert(
+            "clock".to_string(),
+            crate::config::WidgetOptions {
+                background_color: Some("#555555".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let warnings = ThemePalette::lint_contrast(&config);
+        let pairs: Vec<(&str, &str)> = warnings
+            .iter()
+            .map(|w| (w.foreground.as_str(), w.background.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("accent", "widget background"),
+                ("text", "widgets.clock background"),
+                ("muted text", "widgets.clock background"),
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "accent (#f0f0f0) on widget background (#ffffff): contrast 1.1:1, needs 3.0:1"
+        );
+    }
+
+    #[test]
+    fn test_lint_contrast_translucent_widgets() {
+        let mut config = Config::default();
+        config.theme.accent = Some("#222222".to_string());
+        config.widgets.background_opacity = 0.5;
+
+        // Wallpaper shows through: nothing to check against
+        assert!(ThemePalette::lint_contrast(&config).is_empty());
+
+        // Opaque bar: widgets blend with it
+        config.bar.background_opacity = 1.0;
+        let warnings = ThemePalette::lint_contrast(&config);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].background_color, "#15161b");
+    }
 }
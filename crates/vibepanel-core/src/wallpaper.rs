@@ -0,0 +1,162 @@
+//! Accent color sampled from the wallpaper.
+//!
+//! With `theme.accent = "wallpaper"`, the bar finds the current wallpaper
+//! (`theme.wallpaper`, or what hyprpaper or swww report), scales it down and
+//! picks its dominant color as the accent. This module holds the parts that
+//! don't need GTK: reading the wallpaper tools' output and choosing the color.
+
+use std::path::PathBuf;
+
+use crate::color_source::expand_home;
+use crate::theme::rgb_to_hex;
+
+/// Number of hue buckets pixels are sorted into.
+const HUE_BUCKETS: usize = 24;
+
+/// Pixels less saturated than this count as gray and are skipped.
+const MIN_SATURATION: f64 = 0.2;
+
+/// Pixels darker than this are skipped; their hue is mostly noise.
+const MIN_VALUE: f64 = 0.2;
+
+/// The configured wallpaper path with `~` expanded.
+pub fn wallpaper_path(path: &str) -> PathBuf {
+    expand_home(path)
+}
+
+/// First wallpaper in `hyprctl hyprpaper listactive` output, whose lines
+/// read `<monitor> = <path>`.
+pub fn parse_hyprpaper_active(output: &str) -> Option<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(_, path)| path.trim())
+        .find(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// First image in `swww query` output, whose lines read
+/// `<monitor>: <size>, scale: 1, currently displaying: image: <path>`.
+/// Monitors showing a plain color are skipped.
+pub fn parse_swww_query(output: &str) -> Option<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once("currently displaying: image:"))
+        .map(|(_, path)| path.trim())
+        .find(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Dominant color of an image as "#rrggbb".
+///
+/// `pixels` is packed RGB or RGBA (`channels` = 3 or 4) without row padding.
+/// Pixels are grouped by hue, weighted by saturation and brightness, and the
+/// average of the heaviest group wins, so a small vivid area beats a large
+/// washed-out one. Gray, dark and transparent pixels are skipped; `None`
+/// when nothing is left (a grayscale wallpaper).
+pub fn dominant_color(pixels: &[u8], channels: usize) -> Option<String> {
+    if channels < 3 {
+        return None;
+    }
+
+    // Per bucket: total weight and weighted red, green and blue
+    let mut buckets = [[0.0f64; 4]; HUE_BUCKETS];
+    for pixel in pixels.chunks_exact(channels) {
+        if channels > 3 && pixel[3] < 128 {
+            continue;
+        }
+        let (r, g, b) = (pixel[0], pixel[1], pixel[2]);
+        let max = r.max(g).max(b) as f64 / 255.0;
+        let min = r.min(g).min(b) as f64 / 255.0;
+        if max < MIN_VALUE {
+            continue;
+        }
+        let saturation = (max - min) / max;
+        if saturation < MIN_SATURATION {
+            continue;
+        }
+
+        let bucket = &mut buckets[hue_bucket(r, g, b)];
+        let weight = saturation * max;
+        bucket[0] += weight;
+        bucket[1] += weight * r as f64;
+        bucket[2] += weight * g as f64;
+        bucket[3] += weight * b as f64;
+    }
+
+    let [weight, r, g, b] = buckets
+        .into_iter()
+        .max_by(|a, b| a[0].total_cmp(&b[0]))
+        .filter(|bucket| bucket[0] > 0.0)?;
+    Some(rgb_to_hex(
+        (r / weight).round() as u8,
+        (g / weight).round() as u8,
+        (b / weight).round() as u8,
+    ))
+}
+
+/// Hue bucket of a (saturated) pixel.
+fn hue_bucket(r: u8, g: u8, b: u8) -> usize {
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    } * 60.0;
+    (hue / 360.0 * HUE_BUCKETS as f64) as usize % HUE_BUCKETS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[([u8; 3], usize)]) -> Vec<u8> {
+        pixels
+            .iter()
+            .flat_map(|(rgb, count)| std::iter::repeat_n(*rgb, *count))
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn test_dominant_color() {
+        // Mostly gray with a blue area and a little red
+        let pixels = image(&[
+            ([128, 128, 128], 500),
+            ([10, 10, 10], 200),
+            ([40, 90, 200], 100),
+            ([50, 100, 210], 100),
+            ([220, 40, 40], 50),
+        ]);
+        assert_eq!(dominant_color(&pixels, 3).as_deref(), Some("#2d5fcd"));
+
+        // Gray and black only
+        let pixels = image(&[([128, 128, 128], 10), ([0, 0, 0], 10)]);
+        assert_eq!(dominant_color(&pixels, 3), None);
+
+        // Transparent pixels are skipped
+        let pixels = [255, 0, 0, 0, 0, 200, 0, 255];
+        assert_eq!(dominant_color(&pixels, 4).as_deref(), Some("#00c800"));
+    }
+
+    #[test]
+    fn test_parse_wallpaper_tools() {
+        assert_eq!(
+            parse_hyprpaper_active("DP-1 = /home/user/walls/lake.png\neDP-1 = /tmp/b.jpg\n"),
+            Some(PathBuf::from("/home/user/walls/lake.png"))
+        );
+        assert_eq!(parse_hyprpaper_active("no wallpapers active"), None);
+
+        let swww = "eDP-1: 1920x1080, scale: 1, currently displaying: color: 000000\n\
+                    DP-2: 2560x1440, scale: 1, currently displaying: image: /home/user/walls/city.jpg\n";
+        assert_eq!(
+            parse_swww_query(swww),
+            Some(PathBuf::from("/home/user/walls/city.jpg"))
+        );
+        assert_eq!(parse_swww_query(""), None);
+    }
+}
@@ -29,7 +29,10 @@ fn test_load_real_config() {
 
     // Verify advanced config has valid compositor
     assert!(
-        ["auto", "mango", "hyprland", "niri"].contains(&config.advanced.compositor.as_str()),
+        [
+            "auto", "mango", "hyprland", "niri", "river", "wayfire", "cosmic", "kwin", "generic",
+        ]
+        .contains(&config.advanced.compositor.as_str()),
         "Compositor should be valid"
     );
 
@@ -8,28 +8,43 @@ use std::path::PathBuf;
 use tracing::{debug, info, warn};
 
 use vibepanel_core::config::{WidgetEntry, WidgetOrGroup};
-use vibepanel_core::{Config, ThemePalette};
+use vibepanel_core::{Config, ThemePalette, output_css_class};
 
 use crate::sectioned_bar::SectionedBar;
+use crate::services::screen_regions::ScreenRegions;
 use crate::styles::class;
-use crate::widgets::{self, BarState, QuickSettingsConfig, WidgetConfig, WidgetFactory};
+use crate::widgets::{
+    self, BarState, BuiltWidget, QuickSettingsConfig, WidgetConfig, WidgetFactory, show_if,
+};
+
+/// A bar item that hasn't been built yet.
+///
+/// The bar maps with a placeholder in the item's slot, so it shows its final
+/// layout right away; [`hydrate_widget`] later swaps in the real widget.
+pub struct PendingWidget {
+    item: WidgetOrGroup,
+    placeholder: gtk4::Box,
+}
 
 /// Create and configure the bar window with layer-shell.
 ///
+/// The window is mapped as a skeleton: layout-only widgets (spacers) are
+/// built right away, every other item gets a placeholder and is returned as
+/// a [`PendingWidget`] for the caller to hydrate, so the bar doesn't wait on
+/// widget and service setup before it appears.
+///
 /// The `state` parameter is used to store widget handles, keeping them alive
-/// for the lifetime of the bar. The `output_id` is the monitor connector name
-/// used for per-monitor widget filtering.
+/// for the lifetime of the bar.
 pub fn create_bar_window(
     app: &Application,
     config: &Config,
     monitor: &gtk4::gdk::Monitor,
-    output_id: &str,
     state: &mut BarState,
-) -> ApplicationWindow {
+) -> (ApplicationWindow, Vec<PendingWidget>) {
     // Window height determines the exclusive zone (via auto_exclusive_zone_enable).
     // - When bar is visible (opacity > 0): include padding on both sides
     // - When bar is transparent (opacity = 0): exclusive zone = size only
-    //   The top padding offsets widgets visually but bottom padding is 0 via CSS
+    //   The screen-edge padding offsets widgets visually but the inner padding is 0 via CSS
     let bar_height = if config.bar.background_opacity > 0.0 {
         config.bar.size as i32 + 2 * config.bar.padding as i32
     } else {
@@ -46,6 +61,18 @@ pub fn create_bar_window(
         .build();
 
     window.add_css_class(class::BAR_WINDOW);
+    // Scopes the `[bar.output."<connector>"]` size overrides to this window
+    if let Some(connector) = monitor.connector()
+        && config.bar.output.contains_key(connector.as_str())
+    {
+        window.add_css_class(&output_css_class(&connector));
+    }
+    if config.bar.is_text_mode() {
+        window.add_css_class(class::BAR_TEXT_MODE);
+    }
+    if crate::preview::is_enabled() {
+        window.add_css_class(class::BAR_PREVIEW);
+    }
 
     // Initialize layer-shell
     window.init_layer_shell();
@@ -55,14 +82,22 @@ pub fn create_bar_window(
     window.set_monitor(Some(monitor));
     debug!("Bar bound to monitor: {:?}", monitor.connector());
 
-    // Anchor to top edge, stretch horizontally
-    window.set_anchor(Edge::Top, true);
+    // Anchor to the configured edge, stretch horizontally
+    let at_bottom = config.bar.is_bottom();
+    window.set_anchor(Edge::Top, !at_bottom);
     window.set_anchor(Edge::Left, true);
     window.set_anchor(Edge::Right, true);
-    window.set_anchor(Edge::Bottom, false);
+    window.set_anchor(Edge::Bottom, at_bottom);
 
-    // Reserve space (exclusive zone) so other windows don't overlap
-    window.auto_exclusive_zone_enable();
+    if config.bar.on_demand {
+        // Overlay windows instead of pushing them aside; BarManager shows
+        // the bar when the pointer touches its edge
+        window.set_layer(Layer::Overlay);
+        window.set_exclusive_zone(0);
+    } else {
+        // Reserve space (exclusive zone) so other windows don't overlap
+        window.auto_exclusive_zone_enable();
+    }
 
     // Bar doesn't need keyboard input
     window.set_keyboard_mode(KeyboardMode::None);
@@ -73,12 +108,13 @@ pub fn create_bar_window(
     // bar content instead.
     let margin = config.bar.screen_margin as i32;
     window.set_margin(Edge::Top, 0);
+    window.set_margin(Edge::Bottom, 0);
     window.set_margin(Edge::Left, 0);
     window.set_margin(Edge::Right, 0);
 
     // Create the bar container using SectionedBar for proper left/center/right layout
     let bar_box = SectionedBar::new(
-        config.bar.spacing as i32,
+        config.bar.widget_spacing() as i32,
         config.bar.inset as i32,
         config.widgets.left_has_expander(),
         config.widgets.right_has_expander(),
@@ -88,19 +124,22 @@ pub fn create_bar_window(
     bar_box.set_vexpand(true);
 
     // Wrap bar_box in an outer container so we can inset the
-    // visible bar from the top, left, and right edges while
+    // visible bar from the screen, left, and right edges while
     // keeping the window and exclusive zone full-width.
     let outer_box = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
     outer_box.add_css_class(class::BAR_SHELL);
     outer_box.set_hexpand(true);
     outer_box.set_vexpand(true);
 
-    // Top spacer: empty area above the bar content.
-    if margin > 0 {
+    // Spacer: empty area between the screen edge and the bar content.
+    let spacer = (margin > 0).then(|| {
         let spacer = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
         spacer.set_size_request(-1, margin);
         spacer.add_css_class(class::BAR_MARGIN_SPACER);
-        outer_box.append(&spacer);
+        spacer
+    });
+    if !at_bottom && let Some(ref spacer) = spacer {
+        outer_box.append(spacer);
     }
 
     // Inner horizontal box adds left/right padding via CSS.
@@ -108,9 +147,18 @@ pub fn create_bar_window(
     inner_box.add_css_class(class::BAR_SHELL_INNER);
     inner_box.set_hexpand(true);
     inner_box.set_vexpand(false);
+    if crate::preview::is_enabled() {
+        let label = gtk4::Label::new(Some("Preview"));
+        label.add_css_class(class::BAR_PREVIEW_LABEL);
+        label.set_valign(gtk4::Align::Center);
+        inner_box.append(&label);
+    }
     inner_box.append(&bar_box);
 
     outer_box.append(&inner_box);
+    if at_bottom && let Some(ref spacer) = spacer {
+        outer_box.append(spacer);
+    }
 
     // Find quick_settings config from widget entries to configure the window.
     // Get options from [widgets.quick_settings] if defined.
@@ -126,21 +174,24 @@ pub fn create_bar_window(
     // Create handle for this bar's Quick Settings window.
     // The window itself is created lazily on first open and destroyed on close.
     let qs_handle = crate::widgets::QuickSettingsWindowHandle::new(app.clone(), qs_cards_config);
+    state.set_quick_settings(qs_handle.clone());
+
+    let mut pending = Vec::new();
 
     // Create left section
-    let left_section = create_section("left", config, state, &qs_handle, Some(output_id));
+    let left_section = create_section("left", config, state, &mut pending);
     bar_box.set_start_widget(Some(&left_section));
 
     // Create center section only if there are center widgets
     // Without a center widget, the layout manager uses linear allocation
     let has_center_content = !config.widgets.resolved_center().is_empty();
     if has_center_content {
-        let center_section = create_center_section(config, state, &qs_handle, Some(output_id));
+        let center_section = create_center_section(config, state, &mut pending);
         bar_box.set_center_widget(Some(&center_section));
     }
 
     // Create right section
-    let right_section = create_section("right", config, state, &qs_handle, Some(output_id));
+    let right_section = create_section("right", config, state, &mut pending);
     bar_box.set_end_widget(Some(&right_section));
 
     window.set_child(Some(&outer_box));
@@ -151,6 +202,16 @@ pub fn create_bar_window(
     let target_geometry = monitor.geometry();
     let target_width = target_geometry.width();
 
+    // Toasts, the OSD and Quick Settings are placed in the area the bar's
+    // exclusive zone leaves free. Computed from this bar's config (the same
+    // as `calculate_bar_exclusive_zone`) so per-output sizes are honored.
+    let bar_zone = if config.bar.on_demand {
+        0
+    } else {
+        bar_height + 2 * config.bar.screen_margin as i32
+    };
+    ScreenRegions::global().set_work_area(target_width, target_geometry.height() - bar_zone);
+
     window.connect_map(move |win| {
         win.set_default_size(target_width, bar_height);
         debug!(
@@ -159,43 +220,162 @@ pub fn create_bar_window(
         );
     });
 
-    window.set_visible(true);
+    // An on-demand bar stays hidden until summoned
+    window.set_visible(!config.bar.on_demand);
 
     info!(
-        "Bar window created: size={}px, margin={}px, monitor={:?}, widgets={}",
+        "Bar window created: position={}, size={}px, margin={}px, monitor={:?}, pending={}",
+        config.bar.position,
         config.bar.size,
         config.bar.screen_margin,
         monitor.connector(),
-        state.handle_count()
+        pending.len()
     );
 
+    (window, pending)
+}
+
+/// Thickness of the strip that summons an on-demand bar, in pixels.
+const HOT_EDGE_SIZE: i32 = 2;
+
+/// Create the invisible strip along the bar's screen edge that summons an
+/// on-demand bar (`bar.on_demand`).
+///
+/// `on_touch` runs when the pointer enters the strip.
+pub fn create_hot_edge<F>(
+    app: &Application,
+    config: &Config,
+    monitor: &gtk4::gdk::Monitor,
+    on_touch: F,
+) -> ApplicationWindow
+where
+    F: Fn() + 'static,
+{
+    let window = ApplicationWindow::builder()
+        .application(app)
+        .title("vibepanel-hot-edge")
+        .decorated(false)
+        .resizable(false)
+        .default_height(HOT_EDGE_SIZE)
+        .build();
+    window.add_css_class(class::BAR_HOT_EDGE);
+
+    window.init_layer_shell();
+    window.set_layer(Layer::Overlay);
+    window.set_monitor(Some(monitor));
+    let at_bottom = config.bar.is_bottom();
+    window.set_anchor(Edge::Top, !at_bottom);
+    window.set_anchor(Edge::Left, true);
+    window.set_anchor(Edge::Right, true);
+    window.set_anchor(Edge::Bottom, at_bottom);
+    // Sit on the very screen edge, even past other panels' exclusive zones
+    window.set_exclusive_zone(-1);
+    window.set_keyboard_mode(KeyboardMode::None);
+
+    let motion = gtk4::EventControllerMotion::new();
+    motion.connect_enter(move |_, _, _| on_touch());
+    window.add_controller(motion);
+
+    window.set_visible(true);
     window
 }
 
+/// Build a pending item and swap it in for its placeholder.
+///
+/// The `output_id` is the monitor connector name used for per-monitor widget
+/// filtering. Returns the built widgets with their config names, for the
+/// caller to record in the bar's state. An item that builds nothing just
+/// loses its placeholder.
+pub fn hydrate_widget(
+    pending: PendingWidget,
+    qs_handle: Option<&crate::widgets::QuickSettingsWindowHandle>,
+    output_id: Option<&str>,
+) -> Vec<(String, BuiltWidget)> {
+    let PendingWidget { item, placeholder } = pending;
+    let built = build_widget_or_group(&item, qs_handle, output_id);
+
+    if let Some(section) = placeholder.parent().and_downcast::<gtk4::Box>() {
+        if let Some((root, _)) = &built {
+            section.insert_child_after(root, Some(&placeholder));
+        }
+        section.remove(&placeholder);
+    }
+
+    built.map(|(_, widgets)| widgets).unwrap_or_default()
+}
+
+/// Add an item to a section: layout-only widgets are built right away, the
+/// rest get a placeholder and are queued in `pending`.
+fn add_item(
+    item: &WidgetOrGroup,
+    section: &gtk4::Box,
+    state: &mut BarState,
+    pending: &mut Vec<PendingWidget>,
+) {
+    if let WidgetOrGroup::Single(entry) = item
+        && entry.name == "spacer"
+    {
+        if let Some((root, widgets)) = build_widget_or_group(item, None, None) {
+            section.append(&root);
+            for (name, built) in widgets {
+                state.add_widget(&name, &built.widget);
+                state.add_handle(built.handle);
+            }
+        }
+        return;
+    }
+
+    let placeholder = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+    placeholder.add_css_class(class::WIDGET);
+    placeholder.add_css_class(class::WIDGET_PLACEHOLDER);
+    // Same per-widget class as the real widget, so its CSS variables apply
+    let first_entry = match item {
+        WidgetOrGroup::Single(entry) => Some(entry),
+        WidgetOrGroup::Group { group, .. } => group.first(),
+    };
+    if let Some(entry) = first_entry {
+        placeholder.add_css_class(&entry.name.replace('_', "-"));
+    }
+    section.append(&placeholder);
+
+    pending.push(PendingWidget {
+        item: item.clone(),
+        placeholder,
+    });
+}
+
 /// Build a single widget or a group of widgets sharing one island.
 ///
-/// Returns the number of widgets built (for counting purposes).
+/// Returns the root widget to place in the section along with each built
+/// widget and its config name, or `None` if nothing was built.
 fn build_widget_or_group(
     item: &WidgetOrGroup,
-    container: &gtk4::Box,
-    state: &mut BarState,
-    qs_handle: &crate::widgets::QuickSettingsWindowHandle,
+    qs_handle: Option<&crate::widgets::QuickSettingsWindowHandle>,
     output_id: Option<&str>,
-) -> usize {
+) -> Option<(gtk4::Widget, Vec<(String, BuiltWidget)>)> {
     match item {
         WidgetOrGroup::Single(entry) => {
             // Single widget with its own island
-            if let Some(built) = WidgetFactory::build(entry, Some(qs_handle), output_id) {
-                container.append(&built.widget);
-                state.add_handle(built.handle);
-                1
-            } else {
-                0
-            }
+            let built = WidgetFactory::build(entry, qs_handle, output_id)?;
+            let root = match show_if::condition_for(entry) {
+                // Wrap the island so the condition doesn't fight widgets that
+                // hide their own root (e.g. battery without a battery)
+                Some(condition) => {
+                    let wrapper = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+                    wrapper.append(&built.widget);
+                    show_if::bind(wrapper.upcast_ref(), condition);
+                    wrapper.upcast()
+                }
+                None => built.widget.clone(),
+            };
+            Some((root, vec![(entry.name.clone(), built)]))
         }
-        WidgetOrGroup::Group { group } => {
+        WidgetOrGroup::Group {
+            group,
+            shared_popover,
+        } => {
             if group.is_empty() {
-                return 0;
+                return None;
             }
 
             // Create a shared island container for the group
@@ -216,24 +396,61 @@ fn build_widget_or_group(
             content.set_valign(gtk4::Align::Fill);
             island.append(&content);
 
-            let mut count = 0;
+            let mut widgets = Vec::new();
+            let mut conditional = false;
             for entry in group {
-                if let Some(built) = WidgetFactory::build(entry, Some(qs_handle), output_id) {
+                if let Some(built) = WidgetFactory::build(entry, qs_handle, output_id) {
                     // Remove the .widget class from this widget since it's inside a group
                     built.widget.remove_css_class(class::WIDGET);
                     content.append(&built.widget);
-                    state.add_handle(built.handle);
-                    count += 1;
+                    // Group members can't be wrapped without breaking the
+                    // group's CSS, so the condition applies to the widget itself
+                    if let Some(condition) = show_if::condition_for(entry) {
+                        show_if::bind(&built.widget, condition);
+                        conditional = true;
+                    }
+                    widgets.push((entry.name.clone(), built));
                 }
             }
 
-            // Only append the island if we built at least one widget
-            if count > 0 {
-                container.append(&island);
-                debug!("Created widget group with {} widget(s)", count);
+            // Only place the island if we built at least one widget
+            if widgets.is_empty() {
+                return None;
             }
 
-            count
+            // Hide the island while every member is hidden
+            if conditional {
+                let sync_island = {
+                    let island = island.downgrade();
+                    let content = content.downgrade();
+                    move || {
+                        if let (Some(island), Some(content)) = (island.upgrade(), content.upgrade())
+                        {
+                            let mut child = content.first_child();
+                            let mut any_visible = false;
+                            while let Some(widget) = child {
+                                any_visible |= widget.is_visible();
+                                child = widget.next_sibling();
+                            }
+                            island.set_visible(any_visible);
+                        }
+                    }
+                };
+                sync_island();
+                for (_, built) in &widgets {
+                    let sync_island = sync_island.clone();
+                    built.widget.connect_visible_notify(move |_| sync_island());
+                }
+            }
+            if *shared_popover {
+                let sections = widgets
+                    .iter()
+                    .filter_map(|(_, built)| built.popover_section.clone())
+                    .collect();
+                widgets::bind_shared_popover(&island, sections);
+            }
+            debug!("Created widget group with {} widget(s)", widgets.len());
+            Some((island.upcast(), widgets))
         }
     }
 }
@@ -242,8 +459,7 @@ fn create_section(
     position: &str,
     config: &Config,
     state: &mut BarState,
-    qs_handle: &crate::widgets::QuickSettingsWindowHandle,
-    output_id: Option<&str>,
+    pending: &mut Vec<PendingWidget>,
 ) -> gtk4::Box {
     let section = gtk4::Box::new(
         gtk4::Orientation::Horizontal,
@@ -265,15 +481,14 @@ fn create_section(
         _ => return section,
     };
 
-    // Build widgets from resolved entries
-    let mut widget_count = 0;
     for item in &resolved {
-        widget_count += build_widget_or_group(item, &section, state, qs_handle, output_id);
+        add_item(item, &section, state, pending);
     }
 
     debug!(
-        "Created {} section with {} widget(s)",
-        position, widget_count
+        "Created {} section with {} item(s)",
+        position,
+        resolved.len()
     );
     section
 }
@@ -282,18 +497,20 @@ fn create_section(
 fn create_center_section(
     config: &Config,
     state: &mut BarState,
-    qs_handle: &crate::widgets::QuickSettingsWindowHandle,
-    output_id: Option<&str>,
+    pending: &mut Vec<PendingWidget>,
 ) -> gtk4::Box {
-    let section = gtk4::Box::new(gtk4::Orientation::Horizontal, config.bar.spacing as i32);
+    let section = gtk4::Box::new(
+        gtk4::Orientation::Horizontal,
+        config.bar.section_widget_spacing("center") as i32,
+    );
     section.add_css_class(class::BAR_SECTION_CENTER);
 
-    let mut widget_count = 0;
-    for item in &config.widgets.resolved_center() {
-        widget_count += build_widget_or_group(item, &section, state, qs_handle, output_id);
+    let resolved = config.widgets.resolved_center();
+    for item in &resolved {
+        add_item(item, &section, state, pending);
     }
 
-    debug!("Created center section with {} widget(s)", widget_count);
+    debug!("Created center section with {} item(s)", resolved.len());
     section
 }
 
@@ -448,17 +665,31 @@ fn generate_css(config: &Config, palette: &ThemePalette) -> String {
     // Get CSS variables from theme palette
     let css_vars = palette.css_vars_block();
 
-    // Per-widget CSS overrides (background_color, etc. from [widgets.xxx] sections)
+    // Per-widget CSS overrides (background_color, margin, etc. from
+    // [widgets.xxx] sections)
     let per_widget_css = ThemePalette::generate_per_widget_css(config);
 
+    // Per-output size overrides ([bar.output."<connector>"] sections) and
+    // the sizes of [[bars]] bars
+    let per_output_css = format!(
+        "{}{}",
+        ThemePalette::generate_per_output_css(config),
+        ThemePalette::generate_extra_bar_css(config)
+    );
+
     // Utility CSS shared across widgets and surfaces
     let utility_css = widgets::css::utility_css();
 
     // Widget-specific CSS
     let widget_css = widgets::css::widget_css(config);
 
+    // Gradient/image backgrounds (after widget CSS so they layer under hover overlays)
+    let background_css = ThemePalette::generate_background_css(config);
+
+    // Per-widget CSS goes after the widget CSS so margin and padding
+    // overrides win over the section and group rules they tie with
     format!(
-        "{}\n{}\n{}\n{}",
-        css_vars, per_widget_css, utility_css, widget_css
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        css_vars, per_output_css, utility_css, widget_css, per_widget_css, background_css
     )
 }
@@ -0,0 +1,177 @@
+//! Keyboard focus mode for a bar (`vibepanel ipc focus-bar`).
+//!
+//! The bar normally takes no keyboard input. Focus mode asks the compositor
+//! for the keyboard and highlights one widget at a time:
+//! - Left/Right (or Tab/Shift+Tab, h/l) move between clickable widgets
+//! - Enter or Space runs the highlighted widget's default action (what a
+//!   left click does, see [`DEFAULT_ACTION`]) and leaves focus mode, so an
+//!   opened popover gets the keyboard
+//! - Escape, or the bar losing the keyboard, leaves focus mode
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::gdk::Key;
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, EventControllerKey};
+use gtk4_layer_shell::{KeyboardMode, LayerShell};
+use tracing::debug;
+
+use crate::styles::{class, state};
+use crate::widgets::DEFAULT_ACTION;
+use crate::widgets::layer_shell_popover::popover_keyboard_mode;
+
+/// Keyboard focus mode of one bar window.
+pub struct BarFocus {
+    window: ApplicationWindow,
+    /// Highlighted widget while focus mode is on.
+    current: RefCell<Option<gtk4::Widget>>,
+}
+
+impl BarFocus {
+    /// Set up focus mode for `window`; it starts off.
+    pub fn new(window: &ApplicationWindow) -> Rc<Self> {
+        let focus = Rc::new(Self {
+            window: window.clone(),
+            current: RefCell::new(None),
+        });
+
+        let keys = EventControllerKey::new();
+        let focus_weak = Rc::downgrade(&focus);
+        keys.connect_key_pressed(move |_, key, _, _| match focus_weak.upgrade() {
+            Some(focus) if focus.is_active() => focus.on_key(key),
+            _ => glib::Propagation::Proceed,
+        });
+        window.add_controller(keys);
+
+        let focus_weak = Rc::downgrade(&focus);
+        window.connect_is_active_notify(move |window| {
+            if !window.is_active()
+                && let Some(focus) = focus_weak.upgrade()
+            {
+                focus.leave();
+            }
+        });
+
+        focus
+    }
+
+    /// Whether focus mode is on.
+    pub fn is_active(&self) -> bool {
+        self.current.borrow().is_some()
+    }
+
+    /// Take the keyboard and highlight the first widget.
+    ///
+    /// Fails if the bar has no clickable widgets.
+    pub fn enter(&self) -> Result<(), String> {
+        if self.is_active() {
+            return Ok(());
+        }
+        let first = self
+            .targets()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "the bar has no clickable widgets".to_string())?;
+
+        debug!("Bar focus mode on");
+        self.window.set_keyboard_mode(popover_keyboard_mode());
+        self.window.present();
+        self.highlight(Some(first));
+        Ok(())
+    }
+
+    /// Give the keyboard back and drop the highlight.
+    pub fn leave(&self) {
+        if !self.is_active() {
+            return;
+        }
+        debug!("Bar focus mode off");
+        self.highlight(None);
+        self.window.set_keyboard_mode(KeyboardMode::None);
+    }
+
+    fn on_key(&self, key: Key) -> glib::Propagation {
+        match key {
+            Key::Left | Key::h | Key::ISO_Left_Tab => self.step(-1),
+            Key::Right | Key::l | Key::Tab => self.step(1),
+            Key::Return | Key::KP_Enter | Key::space => self.activate(),
+            Key::Escape => self.leave(),
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    }
+
+    /// Move the highlight `delta` widgets along, wrapping around.
+    fn step(&self, delta: isize) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            self.leave();
+            return;
+        }
+        let current = self.current.borrow().clone();
+        let index = current
+            .and_then(|current| targets.iter().position(|w| *w == current))
+            .map_or(0, |index| {
+                (index as isize + delta).rem_euclid(targets.len() as isize) as usize
+            });
+        self.highlight(Some(targets[index].clone()));
+    }
+
+    /// Leave focus mode and run the highlighted widget's default action.
+    fn activate(&self) {
+        let Some(widget) = self.current.borrow().clone() else {
+            return;
+        };
+        self.leave();
+        if let Err(e) = widget.activate_action(DEFAULT_ACTION, None) {
+            debug!("Bar focus: widget has no default action: {}", e);
+        }
+    }
+
+    fn highlight(&self, widget: Option<gtk4::Widget>) {
+        if let Some(previous) = self.current.replace(widget.clone()) {
+            previous.remove_css_class(state::KEYBOARD_FOCUS);
+        }
+        if let Some(widget) = widget {
+            widget.add_css_class(state::KEYBOARD_FOCUS);
+        }
+    }
+
+    /// Clickable widgets and shared-popover groups on the bar, left to right.
+    fn targets(&self) -> Vec<gtk4::Widget> {
+        let mut targets = Vec::new();
+        if let Some(child) = self.window.child() {
+            collect_targets(&child, &mut targets);
+        }
+        targets.sort_by_key(|widget| {
+            widget
+                .compute_point(&self.window, &gtk4::graphene::Point::zero())
+                .map(|point| (point.x() as i32, point.y() as i32))
+                .unwrap_or_default()
+        });
+        targets
+    }
+}
+
+/// Add the visible, clickable bar widgets under `widget` to `targets`.
+///
+/// A target's children aren't searched: a group with a shared popover is
+/// one target.
+fn collect_targets(widget: &gtk4::Widget, targets: &mut Vec<gtk4::Widget>) {
+    if !widget.is_visible() {
+        return;
+    }
+    if widget.has_css_class(state::CLICKABLE)
+        && (widget.has_css_class(class::WIDGET_ITEM) || widget.has_css_class(class::SHARED_POPOVER))
+    {
+        targets.push(widget.clone());
+        return;
+    }
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        collect_targets(&current, targets);
+        child = current.next_sibling();
+    }
+}
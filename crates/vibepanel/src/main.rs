@@ -3,14 +3,20 @@
 //! This is the main entry point for the vibepanel bar application.
 
 mod bar;
+mod bar_focus;
 pub mod layout_math;
 pub mod popover_tracker;
+mod preview;
 mod sectioned_bar;
 mod services;
+mod setup;
 pub mod styles;
+mod supervisor;
+#[cfg(test)]
+mod test_harness;
 mod widgets;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 use clap::{Parser, Subcommand};
@@ -19,11 +25,14 @@ use gtk4::prelude::*;
 use tracing::{debug, error, info, warn};
 
 use services::bar_manager;
-use vibepanel_core::{Config, ThemePalette, logging};
+use vibepanel_core::{Config, StateStore, ThemePalette, logging};
 
+use crate::services::actions::{ActionKind, Actions};
 use crate::services::bar_manager::BarManager;
 use crate::services::compositor::CompositorManager;
 use crate::services::config_manager::ConfigManager;
+use crate::services::leak_check::LeakCheck;
+use crate::services::mock::{MockServices, Scenario};
 
 /// vibepanel - A modern Wayland status bar
 #[derive(Parser, Debug)]
@@ -45,6 +54,37 @@ struct Args {
     #[arg(long)]
     check_config: bool,
 
+    /// Check theme colors for WCAG contrast problems and exit
+    /// (returns non-zero if any combination is hard to read)
+    #[arg(long)]
+    lint_theme: bool,
+
+    /// Log live widget, popover and subscription counts after popovers
+    /// close and bars are removed (for debugging leaks)
+    #[arg(long)]
+    leak_check: bool,
+
+    /// Replace the battery, network, Bluetooth and audio services with
+    /// scripted fakes from a scenario file (for widget and theme development)
+    #[arg(long, value_name = "SCENARIO")]
+    mock_services: Option<PathBuf>,
+
+    /// Exit when the Wayland compositor goes away instead of waiting for it
+    /// to come back and restarting the bar
+    #[arg(long)]
+    no_reconnect: bool,
+
+    /// Refuse power actions, click commands, links and control commands
+    /// that change the bar, and don't reload the config file (for shared or
+    /// public machines)
+    #[arg(long)]
+    kiosk: bool,
+
+    /// Try a config in a second, marked bar next to the running one
+    /// (without the control socket, notification daemon or OSD)
+    #[arg(long, value_name = "CONFIG", conflicts_with = "config")]
+    preview: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -75,6 +115,155 @@ enum Command {
         #[command(subcommand)]
         action: MediaAction,
     },
+    /// Run a command and track it in the jobs widget
+    Run {
+        /// Name shown in the jobs widget (defaults to the command line)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Command to run
+        #[arg(trailing_var_arg = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Control notifications
+    Notify {
+        #[command(subcommand)]
+        action: NotifyAction,
+    },
+    /// Control the bar windows
+    Bar {
+        #[command(subcommand)]
+        action: BarAction,
+    },
+    /// Control the running bar over its IPC socket
+    Ipc {
+        #[command(subcommand)]
+        action: IpcAction,
+    },
+    /// Report the health of the D-Bus services used by the running bar
+    Doctor,
+    /// Bundle the config, style.css, widget state and theme images into a
+    /// tarball for another machine
+    ExportSetup {
+        /// Bundle to write
+        #[arg(default_value = "vibepanel-setup.tar.gz")]
+        output: PathBuf,
+    },
+    /// Install a bundle written by export-setup
+    ImportSetup {
+        /// Bundle to install
+        bundle: PathBuf,
+        /// Replace existing files
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IpcAction {
+    /// Reload the config file and style.css
+    Reload,
+    /// Show or hide a widget on all bars
+    ToggleWidget {
+        /// Widget name as used in the config (e.g. "cpu")
+        widget: String,
+    },
+    /// Show an OSD
+    ShowOsd {
+        #[command(subcommand)]
+        osd: OsdAction,
+    },
+    /// Open Quick Settings
+    OpenQuickSettings {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Open or close the notification center
+    ToggleNotificationCenter {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Open or close the command palette
+    ToggleCommandPalette {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Show or hide the bar
+    ToggleBar {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Give the bar the keyboard: Left/Right pick a widget, Enter clicks
+    /// it, Escape gives the keyboard back
+    FocusBar {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Mute or unmute the default microphone (bind the mic-mute key to this)
+    ToggleMicMute,
+    /// Force night light on or off until the next sunrise/sunset, or
+    /// return to the schedule
+    NightLight {
+        #[arg(value_enum)]
+        mode: NightLightArg,
+    },
+    /// Print the latest state of every service
+    State,
+    /// Send a raw JSON command and print the response
+    Send {
+        /// JSON command, e.g. '{"command":"reload"}'
+        json: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BarAction {
+    /// Show or hide the bar (summons an on-demand bar)
+    Toggle {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum NotifyAction {
+    /// Open or close the notification center panel
+    Panel {
+        /// Monitor connector name (defaults to the focused monitor)
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum NightLightArg {
+    On,
+    Off,
+    Auto,
+}
+
+#[derive(Subcommand, Debug)]
+enum OsdAction {
+    /// Volume OSD
+    Volume {
+        /// Volume percentage
+        percent: u32,
+        /// Show as muted
+        #[arg(long)]
+        muted: bool,
+    },
+    /// Brightness OSD
+    Brightness {
+        /// Brightness percentage
+        percent: u32,
+    },
+    /// "Volume unavailable" OSD
+    VolumeUnavailable,
 }
 
 #[derive(Subcommand, Debug)]
@@ -105,7 +294,7 @@ enum BrightnessAction {
 enum VolumeAction {
     /// Get current volume percentage
     Get,
-    /// Set volume to a specific percentage (0-150)
+    /// Set volume to a specific percentage (0-150, up to audio.max_volume)
     Set {
         /// Volume percentage (0-150, values above 100 are overdrive)
         #[arg(value_parser = clap::value_parser!(u32).range(0..=150))]
@@ -151,14 +340,15 @@ fn main() -> ExitCode {
     // Initialize logging
     logging::init(args.verbose);
 
-    // Handle subcommands (these don't need config or GTK)
+    // Handle subcommands (these don't need GTK)
     if let Some(command) = args.command {
-        return handle_command(command);
+        return handle_command(command, args.config.as_deref());
     }
 
     // Load configuration using XDG lookup chain
-    // If --config is specified, it must exist and be valid (no fallback)
-    let load_result = match Config::find_and_load(args.config.as_deref()) {
+    // If --config or --preview is specified, it must exist and be valid (no fallback)
+    let config_path = args.preview.as_deref().or(args.config.as_deref());
+    let load_result = match Config::find_and_load(config_path) {
         Ok(result) => result,
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -192,12 +382,35 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
+    // --lint-theme: check theme contrast and exit
+    if args.lint_theme {
+        let warnings = ThemePalette::lint_contrast(&config);
+        if config.theme.mode == "gtk" {
+            println!("Note: colors from the GTK theme are resolved at runtime and not checked");
+        }
+        if warnings.is_empty() {
+            println!("No contrast problems found");
+            return ExitCode::SUCCESS;
+        }
+        for warning in &warnings {
+            println!("warning: {}", warning);
+        }
+        return ExitCode::FAILURE;
+    }
+
     // --print-example-config: print the example config with comments
     if args.print_example_config {
         print!("{}", vibepanel_core::config::DEFAULT_CONFIG_TOML);
         return ExitCode::SUCCESS;
     }
 
+    // Run the bar under a supervisor that restarts it after a compositor
+    // crash or restart (GTK exits as soon as the display goes away). A
+    // preview just exits with the compositor.
+    if !args.no_reconnect && args.preview.is_none() && !supervisor::is_supervised() {
+        return supervisor::run();
+    }
+
     info!("Configuration loaded successfully");
     info!("Bar size: {}px", config.bar.size);
     info!(
@@ -206,18 +419,90 @@ fn main() -> ExitCode {
         config.widgets.center.len(),
         config.widgets.right.len()
     );
+    for warning in ThemePalette::lint_contrast(&config) {
+        warn!("Theme contrast: {}", warning);
+    }
+
+    if args.leak_check {
+        LeakCheck::global().enable();
+    }
+
+    if args.kiosk {
+        Actions::global().enable_kiosk();
+    }
+
+    if args.preview.is_some() {
+        info!("Preview mode: running next to the normal bar");
+        preview::enable();
+        // Read the running bar's state but leave state.json to it
+        StateStore::init_global(StateStore::open_read_only(
+            vibepanel_core::state::default_state_path(),
+        ));
+    }
+
+    if let Some(ref path) = args.mock_services {
+        match Scenario::load(path) {
+            Ok(scenario) => MockServices::global().enable(scenario),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
 
     // Run the GTK application
     run_gtk_app(config, load_result.source)
 }
 
 /// Handle CLI subcommands (brightness, volume, etc.)
-fn handle_command(command: Command) -> ExitCode {
+fn handle_command(command: Command, config_path: Option<&Path>) -> ExitCode {
     match command {
         Command::Brightness { action } => handle_brightness_command(action),
-        Command::Volume { action } => handle_volume_command(action),
+        Command::Volume { action } => handle_volume_command(action, config_path),
         Command::Inhibit { reason, command } => handle_inhibit_command(&reason, &command),
         Command::Media { action } => handle_media_command(action),
+        Command::Run { name, command } => handle_run_command(name.as_deref(), &command),
+        Command::Notify { action } => match action {
+            NotifyAction::Panel { output } => {
+                handle_ipc_command(IpcAction::ToggleNotificationCenter { output })
+            }
+        },
+        Command::Bar { action } => match action {
+            BarAction::Toggle { output } => handle_ipc_command(IpcAction::ToggleBar { output }),
+        },
+        Command::Ipc { action } => handle_ipc_command(action),
+        Command::Doctor => handle_doctor_command(),
+        Command::ExportSetup { output } => match setup::export(config_path, &output) {
+            Ok(files) => {
+                for file in files {
+                    println!("  {}", file.display());
+                }
+                println!("Wrote {}", output.display());
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Command::ImportSetup { bundle, force } => {
+            match setup::import(&bundle, config_path, force) {
+                Ok(files) => {
+                    for file in files {
+                        println!("  {}", file.display());
+                    }
+                    println!(
+                        "Imported {}; restart vibepanel to load it",
+                        bundle.display()
+                    );
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
     }
 }
 
@@ -274,7 +559,10 @@ fn handle_brightness_command(action: BrightnessAction) -> ExitCode {
 }
 
 /// Handle volume subcommands using PulseAudio.
-fn handle_volume_command(action: VolumeAction) -> ExitCode {
+///
+/// The config is only read for `audio.max_volume`; a missing or broken
+/// config falls back to the default limit.
+fn handle_volume_command(action: VolumeAction, config_path: Option<&Path>) -> ExitCode {
     use crate::services::audio::AudioCli;
     use crate::services::osd_ipc::{notify_volume, notify_volume_unavailable};
 
@@ -293,6 +581,10 @@ fn handle_volume_command(action: VolumeAction) -> ExitCode {
             return ExitCode::FAILURE;
         }
     };
+    match Config::find_and_load(config_path) {
+        Ok(result) => cli.configure(&result.config.audio),
+        Err(e) => debug!("Using default volume limit: {}", e),
+    }
 
     match action {
         VolumeAction::Get => {
@@ -300,6 +592,7 @@ fn handle_volume_command(action: VolumeAction) -> ExitCode {
             ExitCode::SUCCESS
         }
         VolumeAction::Set { percent } => {
+            let percent = percent.min(cli.max_volume());
             match cli.set_volume(percent) {
                 Ok(()) => {
                     notify_volume(percent, cli.is_muted());
@@ -319,7 +612,7 @@ fn handle_volume_command(action: VolumeAction) -> ExitCode {
         }
         VolumeAction::Inc { amount } => {
             let current = cli.get_volume();
-            let new_value = (current + amount).min(150);
+            let new_value = (current + amount).min(cli.max_volume());
             match cli.set_volume(new_value) {
                 Ok(()) => {
                     notify_volume(new_value, cli.is_muted());
@@ -431,6 +724,275 @@ fn handle_inhibit_command(reason: &str, command: &[String]) -> ExitCode {
     // _inhibitor is dropped here, releasing the lock
 }
 
+/// Handle run subcommand - run a command and report it to the jobs widget.
+fn handle_run_command(name: Option<&str>, command: &[String]) -> ExitCode {
+    use crate::services::job_ipc::{JobMessage, notify_job};
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Command as ProcessCommand;
+
+    if command.is_empty() {
+        eprintln!("Error: no command specified");
+        return ExitCode::FAILURE;
+    }
+
+    let program = &command[0];
+    let args = &command[1..];
+
+    let mut child = match ProcessCommand::new(program).args(args).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Error: failed to run command '{}': {}", program, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Ctrl-C goes to the whole foreground process group; let the child
+    // handle it and stay alive to report its exit status. Set after spawn
+    // so the child doesn't inherit the ignored disposition.
+    // SAFETY: Setting a signal disposition to SIG_IGN has no handler to race with.
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_IGN);
+        libc::signal(libc::SIGQUIT, libc::SIG_IGN);
+    }
+
+    let id = std::process::id();
+    let name = name.map(String::from).unwrap_or_else(|| command.join(" "));
+    notify_job(&JobMessage::Started { id, name });
+
+    let code = match child.wait() {
+        // Report signals shell-style (128 + signal number)
+        Ok(status) => status
+            .code()
+            .or_else(|| status.signal().map(|sig| 128 + sig))
+            .unwrap_or(1),
+        Err(e) => {
+            eprintln!("Error: failed to wait for command '{}': {}", program, e);
+            1
+        }
+    };
+
+    notify_job(&JobMessage::Finished { id, code });
+
+    ExitCode::from(code.clamp(0, 255) as u8)
+}
+
+/// Handle ipc subcommands by sending them to the running bar.
+fn handle_ipc_command(action: IpcAction) -> ExitCode {
+    use crate::services::control_ipc::{ControlRequest, send_raw, send_request};
+    use crate::services::osd_ipc::OsdMessage;
+
+    let result = match action {
+        IpcAction::Reload => send_request(&ControlRequest::Reload),
+        IpcAction::ToggleWidget { widget } => {
+            send_request(&ControlRequest::ToggleWidget { widget })
+        }
+        IpcAction::ShowOsd { osd } => {
+            let msg = match osd {
+                OsdAction::Volume { percent, muted } => OsdMessage::Volume { percent, muted },
+                OsdAction::Brightness { percent } => OsdMessage::Brightness { percent },
+                OsdAction::VolumeUnavailable => OsdMessage::VolumeUnavailable,
+            };
+            send_request(&ControlRequest::ShowOsd(msg))
+        }
+        IpcAction::OpenQuickSettings { output } => {
+            send_request(&ControlRequest::OpenQuickSettings { output })
+        }
+        IpcAction::ToggleNotificationCenter { output } => {
+            send_request(&ControlRequest::ToggleNotificationCenter { output })
+        }
+        IpcAction::ToggleCommandPalette { output } => {
+            send_request(&ControlRequest::ToggleCommandPalette { output })
+        }
+        IpcAction::ToggleBar { output } => send_request(&ControlRequest::ToggleBar { output }),
+        IpcAction::FocusBar { output } => send_request(&ControlRequest::FocusBar { output }),
+        IpcAction::ToggleMicMute => send_request(&ControlRequest::ToggleMicMute),
+        IpcAction::NightLight { mode } => {
+            let night = match mode {
+                NightLightArg::On => Some(true),
+                NightLightArg::Off => Some(false),
+                NightLightArg::Auto => None,
+            };
+            send_request(&ControlRequest::NightLight { night })
+        }
+        IpcAction::State => send_request(&ControlRequest::State),
+        IpcAction::Send { json } => send_raw(&json),
+    };
+
+    match result {
+        Ok(response) if response.ok => {
+            for (topic, value) in response.state.iter().flatten() {
+                println!("{}: {}", topic, value);
+            }
+            ExitCode::SUCCESS
+        }
+        Ok(response) => {
+            eprintln!(
+                "Error: {}",
+                response.error.as_deref().unwrap_or("command failed")
+            );
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("Error: could not reach vibepanel (is it running?): {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print per-service D-Bus health reported by the running bar.
+///
+/// Fails if any service is unresponsive.
+fn handle_doctor_command() -> ExitCode {
+    use crate::services::control_ipc::{ControlRequest, send_request};
+    use crate::services::dbus_watchdog::HealthStatus;
+
+    let response = match send_request(&ControlRequest::Health) {
+        Ok(response) if response.ok => response,
+        Ok(response) => {
+            eprintln!(
+                "Error: {}",
+                response.error.as_deref().unwrap_or("command failed")
+            );
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("Error: could not reach vibepanel (is it running?): {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let services = response.health.unwrap_or_default();
+    if services.is_empty() {
+        println!("No D-Bus calls made yet");
+        return ExitCode::SUCCESS;
+    }
+
+    println!(
+        "{:<16} {:<12} {:>6} {:>8} {:>8}  LAST ERROR",
+        "SERVICE", "STATUS", "CALLS", "FAILED", "TIMEOUTS"
+    );
+    for service in &services {
+        let status = match service.status {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Degraded => "degraded",
+            HealthStatus::Unresponsive => "unresponsive",
+        };
+        println!(
+            "{:<16} {:<12} {:>6} {:>8} {:>8}  {}",
+            service.service,
+            status,
+            service.calls,
+            service.failures,
+            service.timeouts,
+            service.last_error.as_deref().unwrap_or("-")
+        );
+    }
+
+    if services
+        .iter()
+        .any(|s| s.status == HealthStatus::Unresponsive)
+    {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Handle a command received on the control socket.
+fn handle_control_request(
+    request: services::control_ipc::ControlRequest,
+    reply: services::control_ipc::ControlReply,
+) {
+    use crate::services::control_ipc::{ControlRequest, ControlResponse};
+    use crate::services::event_bus::EventBus;
+    use crate::services::osd_ipc::OsdMessage;
+
+    if request.is_mutation()
+        && let Err(e) = Actions::global().permit(ActionKind::Control, &format!("{:?}", request))
+    {
+        return reply.send(ControlResponse::error(e));
+    }
+
+    let response = match request {
+        ControlRequest::Reload => {
+            // Answered once the config is loaded off the main loop
+            ConfigManager::global()
+                .reload(move |result| reply.send(ControlResponse::from_result(result)));
+            return;
+        }
+        ControlRequest::ToggleWidget { widget } => {
+            if BarManager::global().toggle_widget(&widget) > 0 {
+                ControlResponse::ok()
+            } else {
+                ControlResponse::error(format!("no widget named '{}' on any bar", widget))
+            }
+        }
+        ControlRequest::ShowOsd(msg) => {
+            let bus = EventBus::global();
+            if bus.subscriber_count::<OsdMessage>() == 0 {
+                return reply.send(ControlResponse::error("OSD is disabled in the config"));
+            }
+            bus.publish(&msg);
+            ControlResponse::ok()
+        }
+        ControlRequest::OpenQuickSettings { output } => ControlResponse::from_result(
+            BarManager::global().open_quick_settings(output.as_deref()),
+        ),
+        ControlRequest::ToggleNotificationCenter { output } => ControlResponse::from_result(
+            BarManager::global().toggle_notification_center(output.as_deref()),
+        ),
+        ControlRequest::ToggleCommandPalette { output } => ControlResponse::from_result(
+            BarManager::global().toggle_command_palette(output.as_deref()),
+        ),
+        ControlRequest::ToggleBar { output } => {
+            ControlResponse::from_result(BarManager::global().toggle_bar(output.as_deref()))
+        }
+        ControlRequest::FocusBar { output } => {
+            ControlResponse::from_result(BarManager::global().focus_bar(output.as_deref()))
+        }
+        ControlRequest::ToggleMicMute => {
+            let audio = services::audio::AudioService::global();
+            if audio.current().mic_muted.is_none() {
+                return reply.send(ControlResponse::error("no microphone"));
+            }
+            audio.toggle_mic_mute();
+            ControlResponse::ok()
+        }
+        ControlRequest::NightLight { night } => ControlResponse::from_result(
+            services::night_light::NightLightService::global().set_override(night),
+        ),
+        ControlRequest::State => {
+            let state = EventBus::global()
+                .dump_state()
+                .into_iter()
+                .map(|(topic, value)| (topic.to_string(), value))
+                .collect();
+            ControlResponse {
+                state: Some(state),
+                ..ControlResponse::ok()
+            }
+        }
+        ControlRequest::Health => ControlResponse {
+            health: Some(services::dbus_watchdog::health()),
+            ..ControlResponse::ok()
+        },
+        ControlRequest::Module(request) => {
+            let Some(connection) = reply.connection().cloned() else {
+                return;
+            };
+            ControlResponse::from_result(
+                services::remote_modules::RemoteModulesService::global()
+                    .handle_request(&connection, request),
+            )
+        }
+        ControlRequest::Job(msg) => {
+            services::jobs::JobsService::global().handle_message(msg);
+            ControlResponse::ok()
+        }
+    };
+    reply.send(response);
+}
+
 /// Handle media subcommands using MPRIS D-Bus.
 fn handle_media_command(action: MediaAction) -> ExitCode {
     use crate::services::media::MediaCli;
@@ -533,7 +1095,10 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
         services::icons::IconsService::init_global(
             &config_for_activate.theme.icons.theme,
             config_for_activate.theme.icons.weight,
+            &config_for_activate.theme.icons.font,
         );
+        services::icons::IconsService::global()
+            .set_text_mode(config_for_activate.bar.is_text_mode());
         debug!(
             "Icons service initialized with theme: {}, weight: {}",
             config_for_activate.theme.icons.theme, config_for_activate.theme.icons.weight
@@ -541,6 +1106,11 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
 
         // Initialize theming-related services with theme-derived styles
         let palette = ThemePalette::from_config(&config_for_activate);
+        services::icons::IconsService::global().set_overrides(
+            &config_for_activate.theme.icons.overrides,
+            palette.sizes.text_icon_size,
+        );
+        services::icons::IconsService::global().set_map(&config_for_activate.theme.icons.map);
         let surface_styles = palette.surface_styles();
         services::surfaces::SurfaceStyleManager::init_global_with_config(
             surface_styles.clone(),
@@ -553,6 +1123,31 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
         services::tooltip::TooltipManager::init_global(surface_styles);
         debug!("Tooltip manager initialized with theme styles");
 
+        // Start the night light schedule (no-op unless enabled)
+        services::night_light::NightLightService::global()
+            .configure(&config_for_activate.night_light);
+
+        // Accent color from the wallpaper (no-op unless `theme.accent = "wallpaper"`)
+        services::wallpaper_accent::WallpaperAccentService::global()
+            .configure(&config_for_activate.theme);
+
+        // Accent color per focused workspace (no-op without `[workspace.colors]`)
+        services::workspace_accent::WorkspaceAccentService::global()
+            .configure(&config_for_activate.workspace);
+
+        // Scripted service snapshots (no-op unless --mock-services)
+        MockServices::global().start();
+
+        // Jack handling settings (mute on unplug)
+        services::audio::AudioService::global().configure(&config_for_activate.audio);
+        services::mute_keys::MuteKeysService::global().configure(&config_for_activate.audio);
+
+        // Reconnect the last audio device (only starts BlueZ when enabled)
+        if config_for_activate.bluetooth.auto_reconnect {
+            services::bluetooth::BluetoothService::global()
+                .configure(&config_for_activate.bluetooth);
+        }
+
         // Initialize idle inhibitor service (uses D-Bus ScreenSaver API)
         let _ = services::idle_inhibitor::IdleInhibitorService::global();
         debug!("Idle inhibitor service initialized");
@@ -569,13 +1164,11 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
         // Initialize bar manager and sync bars to current monitors
         let bar_manager = BarManager::global();
         bar_manager.init(app);
+        bar_manager.restore_session(supervisor::is_restored());
         bar_manager.sync_monitors(&display, &config_for_activate);
 
-        info!(
-            "Bar(s) created: {} bar(s) with {} widget handle(s)",
-            bar_manager.bar_count(),
-            bar_manager.handle_count()
-        );
+        // Widgets are built after the bars map, see BarManager::start_hydration
+        info!("Bar(s) created: {} bar(s)", bar_manager.bar_count());
 
         // Connect monitor change signals for hot-plug support.
         // We capture the display directly so sync_monitors is called unconditionally,
@@ -618,7 +1211,8 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
         }
 
         // Create OSD overlay if enabled and keep it alive on the application
-        if config_for_activate.osd.enabled {
+        // (a preview leaves the OSD to the running bar)
+        if config_for_activate.osd.enabled && !preview::is_enabled() {
             let overlay = crate::widgets::OsdOverlay::new(app, &config_for_activate.osd);
             // Attach to the application so the Rc stays alive for the
             // lifetime of the app.
@@ -630,8 +1224,28 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
             debug!("OSD overlay disabled via configuration");
         }
 
-        // Start config file watcher for live reload
-        ConfigManager::global().start_watching();
+        // Listen for `vibepanel ipc` commands, external modules and
+        // `vibepanel run` jobs, and keep the server alive on the application
+        // (the socket belongs to the running bar in preview)
+        if !preview::is_enabled() {
+            let server =
+                services::control_ipc::ControlIpcServer::new(handle_control_request, |client| {
+                    services::remote_modules::RemoteModulesService::global()
+                        .handle_disconnect(client)
+                });
+            match server {
+                Some(server) => unsafe {
+                    app.set_data("vibepanel-control-ipc", server);
+                },
+                None => warn!("Control IPC socket not available (non-fatal)"),
+            }
+        }
+
+        // Start config file watcher for live reload (the config is
+        // read-only in kiosk mode)
+        if !Actions::global().is_kiosk() {
+            ConfigManager::global().start_watching();
+        }
     });
 
     app.connect_startup(|_| {
@@ -642,6 +1256,10 @@ fn run_gtk_app(config: Config, config_source: Option<PathBuf>) -> ExitCode {
         info!("GTK application shutting down");
         // Stop config watcher
         ConfigManager::global().stop_watching();
+        // Persist any debounced state changes
+        StateStore::global().flush();
+        // Don't leave the notification LED lit
+        services::notification_led::NotificationLedService::global().shutdown();
     });
 
     // Run the application with empty args (we already parsed with clap)
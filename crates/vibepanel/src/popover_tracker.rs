@@ -2,10 +2,18 @@
 //!
 //! Uses unique IDs rather than pointer equality because `Rc<dyn Dismissible>` casting
 //! creates new fat pointers that break pointer comparison.
+//!
+//! Also remembers per-popover view state (expanded sections, scroll positions)
+//! for the session. Popover content is rebuilt on every open, so without this
+//! reopening a panel would always reset the view.
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
+use gtk4::ScrolledWindow;
+use gtk4::prelude::*;
+
 use crate::widgets::layer_shell_popover::Dismissible;
 
 thread_local! {
@@ -18,6 +26,10 @@ pub type PopoverId = u64;
 pub struct PopoverTracker {
     active: RefCell<Option<(PopoverId, Rc<dyn Dismissible>)>>,
     next_id: Cell<PopoverId>,
+    /// Expanded state of collapsible sections, by key.
+    expanded: RefCell<HashMap<String, bool>>,
+    /// Vertical scroll positions of scrolled lists, by key.
+    scroll_positions: RefCell<HashMap<String, f64>>,
 }
 
 impl Default for PopoverTracker {
@@ -25,6 +37,8 @@ impl Default for PopoverTracker {
         Self {
             active: RefCell::new(None),
             next_id: Cell::new(1),
+            expanded: RefCell::new(HashMap::new()),
+            scroll_positions: RefCell::new(HashMap::new()),
         }
     }
 }
@@ -88,4 +102,91 @@ impl PopoverTracker {
             dismissible.dismiss();
         }
     }
+
+    /// Whether a popover is currently open.
+    pub fn has_active(&self) -> bool {
+        self.active
+            .borrow()
+            .as_ref()
+            .is_some_and(|(_, dismissible)| dismissible.is_visible())
+    }
+
+    /// Whether the section `key` was left expanded this session.
+    pub fn is_expanded(&self, key: &str) -> bool {
+        self.expanded.borrow().get(key).copied().unwrap_or(false)
+    }
+
+    /// Remember whether the section `key` is expanded.
+    pub fn set_expanded(&self, key: &str, expanded: bool) {
+        self.expanded.borrow_mut().insert(key.to_string(), expanded);
+    }
+
+    /// Last remembered scroll position for the list `key`.
+    pub fn scroll_position(&self, key: &str) -> Option<f64> {
+        self.scroll_positions.borrow().get(key).copied()
+    }
+
+    /// Remember the scroll position of the list `key`.
+    pub fn set_scroll_position(&self, key: &str, position: f64) {
+        self.scroll_positions
+            .borrow_mut()
+            .insert(key.to_string(), position);
+    }
+}
+
+/// Keep a scrolled window's vertical position across popover rebuilds.
+///
+/// Restores the position remembered under `key` once the content is tall
+/// enough to reach it (lists are often populated after the popover opens),
+/// and records every position the user scrolls to.
+pub fn remember_scroll_position(scrolled: &ScrolledWindow, key: &'static str) {
+    let adjustment = scrolled.vadjustment();
+    // Set once restored or once the user scrolls, whichever comes first
+    let settled = Rc::new(Cell::new(false));
+    let restoring = Rc::new(Cell::new(false));
+
+    match PopoverTracker::global().scroll_position(key) {
+        Some(target) if target > 0.0 => {
+            let settled = settled.clone();
+            let restoring = restoring.clone();
+            adjustment.connect_changed(move |adj| {
+                if settled.get() || adj.upper() - adj.page_size() < target {
+                    return;
+                }
+                settled.set(true);
+                restoring.set(true);
+                adj.set_value(target);
+                restoring.set(false);
+            });
+        }
+        _ => settled.set(true),
+    }
+
+    adjustment.connect_value_changed(move |adj| {
+        if restoring.get() {
+            return;
+        }
+        settled.set(true);
+        PopoverTracker::global().set_scroll_position(key, adj.value());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_state_defaults_and_updates() {
+        let tracker = PopoverTracker::default();
+        assert!(!tracker.is_expanded("qs.wifi"));
+        assert_eq!(tracker.scroll_position("qs.wifi.list"), None);
+
+        tracker.set_expanded("qs.wifi", true);
+        tracker.set_scroll_position("qs.wifi.list", 120.0);
+        assert!(tracker.is_expanded("qs.wifi"));
+        assert_eq!(tracker.scroll_position("qs.wifi.list"), Some(120.0));
+
+        tracker.set_expanded("qs.wifi", false);
+        assert!(!tracker.is_expanded("qs.wifi"));
+    }
 }
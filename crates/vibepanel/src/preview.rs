@@ -0,0 +1,29 @@
+//! `vibepanel --preview <config>`: trying a config next to the running bar.
+//!
+//! The preview is an ordinary bar process with a few things left out so it
+//! doesn't get in the way of the user's own bar:
+//! - Its bars are marked with a "Preview" label and an accent outline
+//! - It isn't supervised, so closing it doesn't restart anything
+//! - It doesn't open the control socket, so `vibepanel ipc`, external
+//!   modules and `vibepanel run` keep talking to the running bar
+//! - It reads `state.json` but keeps its own changes in memory, so hiding
+//!   widgets or toggling DND in the preview doesn't change the running bar's
+//!   saved state
+//! - It doesn't claim `org.freedesktop.Notifications` or show an OSD
+//!
+//! The preview bar reserves its own exclusive zone, so the compositor stacks
+//! it next to the running bar instead of on top of it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn preview mode on, before the GTK application starts.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether this process is a preview bar.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
@@ -5,43 +5,126 @@
 //!
 //! ## Services
 //!
+//! - **actions**: Single dispatch point for power, exec, link and control actions (`--kiosk`)
+//! - **event_bus**: Typed event bus carrying every service's snapshots
+//! - **worker**: Shared worker threads for blocking I/O off the main loop
+//! - **widget_settings**: Per-widget settings merged for a shared service
+//! - **dbus_watchdog**: Timeouts, retries and per-service health for D-Bus calls
+//! - **leak_check**: Debug tracking of objects that outlive their bar or popover
+//! - **mock**: Scripted battery, network, Bluetooth and audio state (`--mock-services`)
 //! - **battery**: UPower-backed battery state monitoring
 //! - **config_manager**: Configuration hot-reload with file watching
+//! - **control_ipc**: Control socket for scripting the running bar (`vibepanel ipc`)
+//! - **stream_server**: Line-based Unix socket server behind the control socket
 //! - **icons**: Icon theme management (Material Symbols font, icon name mapping)
 //! - **tooltip**: Styled GTK tooltips
 //! - **surfaces**: Shared surface styling for popovers, menus, overlays
+//! - **screen_regions**: Keeps Quick Settings, toasts and the OSD from overlapping
 //! - **compositor**: Pluggable compositor backend abstraction
 //! - **workspaces**: Workspace state monitoring
+//! - **workspace_accent**: Accent color that follows the focused workspace
+//! - **wallpaper_accent**: Accent color sampled from the wallpaper
 //! - **window_title**: Focused window title monitoring
+//! - **toplevel**: Open windows via wlr-foreign-toplevel-management (taskbar)
+//! - **audio_windows**: Windows producing sound, from stream and window PIDs
 //! - **tray**: StatusNotifierItem host for system tray icons
 //! - **vpn**: VPN connection management via NetworkManager
 //! - **idle_inhibitor**: System idle/sleep prevention
+//! - **inhibitors**: logind inhibitor lock queries for power actions
+//! - **power_profile**: Active power profile via power-profiles-daemon
 //! - **state**: Persistent state storage (DND, VPN last used, notification history)
 //! - **system**: CPU, memory, and system resource monitoring
+//! - **remote_stats**: CPU, memory and temperatures of other hosts over SSH
 //! - **media**: MPRIS media player control and monitoring
+//! - **remote_modules**: External out-of-process widget modules over IPC
+//! - **custom**: User commands run on an interval or followed line by line
+//! - **jobs**: Long-running commands started via `vibepanel run`
+//! - **backup**: Last backup age from borg, restic or timeshift
+//! - **calendar_events**: Calendar popover events from Evolution Data Server and .ics files
+//! - **syncthing**: Folder sync status via Syncthing's REST API
+//! - **tailscale**: Tailnet state, exit node and peers via the `tailscale` CLI
+//! - **timer**: Pomodoro / focus timer with completion notifications
+//! - **git**: Uncommitted and unpushed work in configured repositories
+//! - **ci**: Latest GitHub Actions / GitLab pipeline status per repository
+//! - **url**: Percent-encoding shared by the HTTP-based services
+//! - **hosts**: TCP / SSH reachability of configured remote hosts
+//! - **privacy**: Microphone, camera and screen-share usage from PipeWire and /dev/video
+//! - **temperature**: hwmon and thermal zone temperature sensors
+//! - **power_draw**: CPU package and DRAM power from RAPL energy counters
+//! - **sysfs**: Shared helpers for reading sysfs attribute directories
+//! - **notification_led**: Notification LED lit while urgent notifications are unseen
+//! - **leds**: Shared LED lookup and logind-backed brightness writes
+//! - **mute_keys**: Mic-mute / camera keys from /dev/input and the micmute LED
+//! - **night_light**: Sunrise/sunset color temperature schedule
+//! - **geoclue**: Shared GeoClue location client (night light, weather)
+//! - **weather**: Current conditions and forecast from a weather provider
+//! - **proxy**: System proxy toggle (GNOME proxy settings and environment.d)
+//! - **touchpad**: Touchpad toggle (compositor IPC or KWin input devices)
 
+pub mod actions;
 pub mod audio;
+pub mod audio_windows;
+pub mod backup;
 pub mod bar_manager;
 pub mod battery;
 pub mod bluetooth;
 pub mod brightness;
+pub mod calendar_events;
 pub mod callbacks;
+pub mod ci;
 pub mod compositor;
 pub mod config_manager;
+pub mod control_ipc;
+pub mod custom;
+pub mod dbus_watchdog;
+pub mod event_bus;
+pub mod geoclue;
+pub mod git;
+pub mod hosts;
 pub mod icons;
 pub mod idle_inhibitor;
+pub mod inhibitors;
+pub mod job_ipc;
+pub mod jobs;
+pub mod leak_check;
+pub mod leds;
 pub mod media;
 pub mod media_ipc;
+pub mod mock;
+pub mod module_ipc;
+pub mod mute_keys;
 pub mod network;
+pub mod night_light;
 pub mod notification;
+pub mod notification_led;
 pub mod osd_ipc;
+pub mod power_draw;
 pub mod power_profile;
+pub mod privacy;
+pub mod proxy;
+pub mod remote_modules;
+pub mod remote_stats;
+pub mod screen_regions;
 pub mod state;
+pub mod stream_server;
 pub mod surfaces;
+pub mod syncthing;
+pub mod sysfs;
 pub mod system;
+pub mod tailscale;
+pub mod temperature;
+pub mod timer;
 pub mod tooltip;
+pub mod toplevel;
+pub mod touchpad;
 pub mod tray;
 pub mod updates;
+pub mod url;
 pub mod vpn;
+pub mod wallpaper_accent;
+pub mod weather;
+pub mod widget_settings;
 pub mod window_title;
+pub mod worker;
 pub mod workspace;
+pub mod workspace_accent;
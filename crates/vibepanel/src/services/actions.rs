@@ -0,0 +1,160 @@
+//! Actions - the one place user-triggered side effects are allowed or refused.
+//!
+//! Power menu entries, commands run on click (custom and clock widgets, SSH
+//! and upgrade terminals), links opened from the bar, control commands that
+//! change the running bar and changes to system settings (network
+//! connections, Wi-Fi secrets, proxy) all go through [`Actions`] instead of
+//! spawning processes or applying changes themselves.
+//!
+//! With `vibepanel --kiosk` every such action is refused and logged, for bars
+//! on shared or public machines. Widgets don't need to know about kiosk mode:
+//! their buttons stay, but nothing they trigger runs.
+
+use std::cell::Cell;
+use std::fmt;
+use std::process::Command;
+use std::rc::Rc;
+
+use gtk4::glib;
+use tracing::{debug, info};
+
+/// Kind of a user-triggered action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    /// Shutdown, reboot, suspend, logout and the other power menu entries.
+    Power,
+    /// A command from the config or the bar, run on click.
+    Exec,
+    /// Opening a link in the default browser.
+    OpenUrl,
+    /// A control socket command that changes the running bar.
+    Control,
+    /// A change to (or read of) system settings: network connections,
+    /// saved Wi-Fi secrets, the proxy.
+    Settings,
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ActionKind::Power => "power action",
+            ActionKind::Exec => "command",
+            ActionKind::OpenUrl => "link",
+            ActionKind::Control => "control command",
+            ActionKind::Settings => "settings change",
+        })
+    }
+}
+
+/// Shared, process-wide action dispatcher.
+pub struct Actions {
+    kiosk: Cell<bool>,
+}
+
+impl Actions {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            kiosk: Cell::new(false),
+        })
+    }
+
+    /// Get the global Actions singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<Actions> = Actions::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Refuse every action from now on.
+    pub fn enable_kiosk(&self) {
+        info!(
+            "Kiosk mode enabled: power, command, link, control and settings actions are disabled"
+        );
+        self.kiosk.set(true);
+    }
+
+    /// Whether kiosk mode is on.
+    pub fn is_kiosk(&self) -> bool {
+        self.kiosk.get()
+    }
+
+    /// Check whether an action may run. `what` describes it for the log.
+    pub fn permit(&self, kind: ActionKind, what: &str) -> Result<(), String> {
+        if self.kiosk.get() {
+            info!("Kiosk mode: refusing {} '{}'", kind, what);
+            return Err(format!("{} disabled in kiosk mode", kind));
+        }
+        debug!("Running {} '{}'", kind, what);
+        Ok(())
+    }
+
+    /// Run a command line (split like a shell would, without a shell),
+    /// detached from the bar.
+    pub fn spawn_command_line(&self, kind: ActionKind, command_line: &str) -> Result<(), String> {
+        self.permit(kind, command_line)?;
+        glib::spawn_command_line_async(command_line).map_err(|e| e.to_string())
+    }
+
+    /// Run `command` if actions of `kind` are allowed, returning the child.
+    pub fn spawn(
+        &self,
+        kind: ActionKind,
+        command: &mut Command,
+    ) -> Result<std::process::Child, String> {
+        self.permit(kind, &format!("{:?}", command))?;
+        command.spawn().map_err(|e| e.to_string())
+    }
+
+    /// Open a URL with `xdg-open`.
+    pub fn open_url(&self, url: &str) -> Result<(), String> {
+        self.permit(ActionKind::OpenUrl, url)?;
+        let command_line = format!("xdg-open '{}'", url.replace('\'', "'\\''"));
+        glib::spawn_command_line_async(&command_line).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_allowed_by_default() {
+        let actions = Actions::new();
+        assert!(!actions.is_kiosk());
+        assert!(actions.permit(ActionKind::Power, "reboot").is_ok());
+        assert!(actions.permit(ActionKind::Control, "reload").is_ok());
+    }
+
+    #[test]
+    fn test_kiosk_refuses_every_kind() {
+        let actions = Actions::new();
+        actions.enable_kiosk();
+        for kind in [
+            ActionKind::Power,
+            ActionKind::Exec,
+            ActionKind::OpenUrl,
+            ActionKind::Control,
+            ActionKind::Settings,
+        ] {
+            assert!(actions.permit(kind, "anything").is_err());
+        }
+        assert_eq!(
+            actions.permit(ActionKind::Exec, "foot").unwrap_err(),
+            "command disabled in kiosk mode"
+        );
+    }
+
+    #[test]
+    fn test_kiosk_refuses_before_spawning() {
+        let actions = Actions::new();
+        actions.enable_kiosk();
+        let mut command = Command::new("/nonexistent/vibepanel-test");
+        // Refused by the policy, not by a failed spawn
+        assert_eq!(
+            actions.spawn(ActionKind::Power, &mut command).unwrap_err(),
+            "power action disabled in kiosk mode"
+        );
+    }
+}
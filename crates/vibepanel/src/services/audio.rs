@@ -3,8 +3,14 @@
 //! Provides a GTK/GLib-friendly, callback-based API for:
 //! - Monitoring default sink volume and mute state
 //! - Monitoring default source (mic) mute state
-//! - Enumerating available sinks for quick settings
+//! - Enumerating available sinks for quick settings, with their active port
+//! - Detecting headphone jack plug/unplug (published as `JackEvent`), with
+//!   optional mute on unplug
 //! - Setting volume/mute with efficient handling of rapid changes
+//! - Enforcing the `audio.max_volume` limit and publishing `VolumeWarning`s
+//!   for overdrive and long loud headphone listening
+//! - Tracking which processes are playing sound (uncorked, unmuted sink
+//!   inputs), so widgets can mark the windows producing audio
 //!
 //! Uses `libpulse-binding` for native PulseAudio protocol access, which
 //! works seamlessly with PipeWire's `pipewire-pulse` compatibility layer
@@ -25,10 +31,18 @@ use std::time::{Duration, Instant};
 
 use gtk4::glib;
 use tracing::{debug, error, info, warn};
+use vibepanel_core::config::{AudioConfig, MAX_VOLUME};
 
 use libpulse_binding as pulse;
 
 use super::callbacks::Callbacks;
+use super::event_bus::EventBus;
+use super::mock::MockServices;
+
+// Thread-local singleton storage for AudioService
+thread_local! {
+    static AUDIO_INSTANCE: RefCell<Option<Rc<AudioService>>> = const { RefCell::new(None) };
+}
 
 /// Duration (in ms) after connecting to PulseAudio during which the OSD
 /// should stay quiet. PulseAudio/PipeWire emits a flurry of updates as
@@ -43,6 +57,115 @@ use pulse::mainloop::threaded::Mainloop;
 use pulse::proplist::Proplist;
 use pulse::volume::Volume;
 
+/// What an output port is connected to, guessed from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    Speaker,
+    Headphones,
+    Headset,
+    Hdmi,
+    LineOut,
+    Digital,
+    Other,
+}
+
+impl PortKind {
+    /// Classify a port from its PulseAudio name (e.g. `analog-output-headphones`,
+    /// `hdmi-output-0`, `[Out] Speaker`), falling back to its description.
+    pub fn from_port(name: &str, description: &str) -> Self {
+        let classify = |text: &str| {
+            let text = text.to_ascii_lowercase();
+            if text.contains("headset") || text.contains("handsfree") {
+                Some(Self::Headset)
+            } else if text.contains("headphone") {
+                Some(Self::Headphones)
+            } else if text.contains("hdmi") || text.contains("displayport") {
+                Some(Self::Hdmi)
+            } else if text.contains("speaker") {
+                Some(Self::Speaker)
+            } else if text.contains("lineout")
+                || text.contains("line-out")
+                || text.contains("line out")
+            {
+                Some(Self::LineOut)
+            } else if text.contains("iec958") || text.contains("spdif") || text.contains("s/pdif") {
+                Some(Self::Digital)
+            } else {
+                None
+            }
+        };
+        classify(name)
+            .or_else(|| classify(description))
+            .unwrap_or(Self::Other)
+    }
+
+    /// Whether this port sits behind a jack that can be plugged and unplugged.
+    pub fn is_jack(self) -> bool {
+        matches!(self, Self::Headphones | Self::Headset | Self::LineOut)
+    }
+
+    /// Icon for the port.
+    pub fn icon_name(self) -> &'static str {
+        match self {
+            Self::Headphones | Self::LineOut => "audio-headphones-symbolic",
+            Self::Headset => "audio-headset-symbolic",
+            _ => "audio-speakers-symbolic",
+        }
+    }
+}
+
+/// The active port of a sink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkPort {
+    /// Internal PulseAudio port name.
+    pub name: String,
+    /// Human-readable description.
+    pub description: String,
+    pub kind: PortKind,
+}
+
+impl SinkPort {
+    /// Short label for display ("Headphones", "HDMI", ...).
+    pub fn label(&self) -> &str {
+        match self.kind {
+            PortKind::Speaker => "Speakers",
+            PortKind::Headphones => "Headphones",
+            PortKind::Headset => "Headset",
+            PortKind::Hdmi => "HDMI",
+            PortKind::LineOut => "Line out",
+            PortKind::Digital => "Digital output",
+            PortKind::Other => &self.description,
+        }
+    }
+}
+
+/// A headphone jack was plugged in or unplugged.
+///
+/// Published on the `EventBus` (use `subscribe_no_replay`; this is a
+/// one-shot event, not state).
+#[derive(Debug, Clone)]
+pub struct JackEvent {
+    pub plugged: bool,
+    /// Description of the sink the jack belongs to.
+    pub sink_description: String,
+    /// Active port of the default sink after the change.
+    pub port: Option<SinkPort>,
+    /// Whether output is muted after the change (including mute on unplug).
+    pub muted: bool,
+}
+
+/// A volume level worth warning about (see the `[audio]` config).
+///
+/// Published on the `EventBus` (use `subscribe_no_replay`; this is a
+/// one-shot event, not state).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeWarning {
+    /// The volume was raised above 100%.
+    Overdrive { volume: u32 },
+    /// Headphones have been loud for this many minutes.
+    LoudListening { minutes: u32 },
+}
+
 /// Information about an audio sink (output device).
 #[derive(Debug, Clone)]
 pub struct SinkInfoSnapshot {
@@ -57,6 +180,74 @@ pub struct SinkInfoSnapshot {
     /// `Some(false)` means the port is not available (e.g., headphones unplugged).
     /// `Some(true)` means the port is available.
     pub port_available: Option<bool>,
+    /// The port currently in use (e.g. speakers or headphones).
+    pub active_port: Option<SinkPort>,
+    /// Whether something is plugged into one of the sink's jacks.
+    /// `None` if the sink has no jack-detected headphone/line-out ports.
+    pub jack_plugged: Option<bool>,
+}
+
+impl SinkInfoSnapshot {
+    fn from_info(info: &SinkInfo, default_name: Option<&str>) -> Self {
+        let name = info
+            .name
+            .as_ref()
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        let description = info
+            .description
+            .as_ref()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| name.clone());
+        let is_default = default_name == Some(name.as_str());
+
+        // Check active port availability (for jack detection, e.g., headphones)
+        // PortAvailable::Unknown means no jack detection support - treat as available
+        // PortAvailable::No means port not available (e.g., headphones unplugged)
+        // PortAvailable::Yes means port is available (e.g., headphones plugged in)
+        let port_available = info.active_port.as_ref().map(|port| match port.available {
+            PortAvailable::No => false,
+            PortAvailable::Yes | PortAvailable::Unknown => true,
+        });
+
+        let active_port = info.active_port.as_ref().map(|port| {
+            let name = port.name.as_deref().unwrap_or_default().to_string();
+            let description = port.description.as_deref().unwrap_or(&name).to_string();
+            SinkPort {
+                kind: PortKind::from_port(&name, &description),
+                name,
+                description,
+            }
+        });
+
+        // Only ports with jack detection tell us whether something is plugged in
+        let jack_ports: Vec<_> = info
+            .ports
+            .iter()
+            .filter(|port| port.available != PortAvailable::Unknown)
+            .filter(|port| {
+                PortKind::from_port(
+                    port.name.as_deref().unwrap_or_default(),
+                    port.description.as_deref().unwrap_or_default(),
+                )
+                .is_jack()
+            })
+            .collect();
+        let jack_plugged = (!jack_ports.is_empty()).then(|| {
+            jack_ports
+                .iter()
+                .any(|port| port.available == PortAvailable::Yes)
+        });
+
+        Self {
+            name,
+            description,
+            is_default,
+            port_available,
+            active_port,
+            jack_plugged,
+        }
+    }
 }
 
 /// Information about an audio source (input device).
@@ -102,6 +293,8 @@ pub struct AudioSnapshot {
     pub control_available: bool,
     /// Whether mic volume/mute controls are currently functional.
     pub mic_control_available: bool,
+    /// Process IDs of applications currently playing sound, sorted.
+    pub playing_pids: Vec<u32>,
 }
 
 impl Default for AudioSnapshot {
@@ -118,6 +311,7 @@ impl Default for AudioSnapshot {
             available: false,
             control_available: true, // Optimistic default; updated when sink info arrives
             mic_control_available: true,
+            playing_pids: Vec::new(),
         }
     }
 }
@@ -147,8 +341,8 @@ impl AudioSnapshot {
 enum AudioCommand {
     /// Set volume as a percentage (0–150).
     SetVolume(u32),
-    /// Set volume relative to current (e.g., +5 or -5).
-    SetVolumeRelative(i32),
+    /// Set volume relative to current (e.g., +5 or -5), up to a maximum.
+    SetVolumeRelative(i32, u32),
     /// Set mute state for the default sink.
     SetMuted(bool),
     /// Toggle mute state for the default sink.
@@ -185,6 +379,7 @@ struct AudioStateUpdate {
     available: bool,
     control_available: bool,
     mic_control_available: bool,
+    playing_pids: Vec<u32>,
 }
 
 /// Shared, process-wide audio service.
@@ -199,6 +394,16 @@ pub struct AudioService {
     ready_at: Cell<Option<Instant>>,
     /// Sender for commands to the Pulse worker thread.
     command_tx: Sender<AudioCommand>,
+    /// Mute output when headphones are unplugged.
+    mute_on_unplug: Cell<bool>,
+    /// Volume limit in percent.
+    max_volume: Cell<u32>,
+    /// Warn when the volume is raised above 100%.
+    warn_overdrive: Cell<bool>,
+    /// Loud headphone listening warning as (volume, minutes), if enabled.
+    safe_listening: Cell<Option<(u32, u32)>>,
+    /// Repeating safe-listening warning, running while listening is loud.
+    loud_timer: RefCell<Option<glib::SourceId>>,
 }
 
 impl AudioService {
@@ -212,8 +417,19 @@ impl AudioService {
             ready: Cell::new(false),
             ready_at: Cell::new(None),
             command_tx,
+            mute_on_unplug: Cell::new(false),
+            max_volume: Cell::new(MAX_VOLUME),
+            warn_overdrive: Cell::new(false),
+            safe_listening: Cell::new(None),
+            loud_timer: RefCell::new(None),
         });
 
+        if MockServices::global().is_enabled() {
+            // Commands fail silently once the receiver is gone
+            debug!("AudioService: mocked, skipping PulseAudio");
+            return service;
+        }
+
         // State updates come back via glib::idle_add_once() - no polling needed.
         thread::spawn(move || {
             pulse_worker_thread(command_rx);
@@ -224,11 +440,17 @@ impl AudioService {
 
     /// Get the global AudioService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<AudioService> = AudioService::new();
-        }
+        AUDIO_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(AudioService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        AUDIO_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked when audio state changes.
@@ -247,11 +469,48 @@ impl AudioService {
         }
     }
 
+    /// Apply `[audio]` settings.
+    pub fn configure(&self, config: &AudioConfig) {
+        self.mute_on_unplug.set(config.mute_on_unplug);
+        self.max_volume.set(config.clamp_volume(MAX_VOLUME));
+        self.warn_overdrive.set(config.warn_overdrive);
+        self.safe_listening.set(
+            (config.safe_listening_minutes > 0)
+                .then_some((config.safe_listening_volume, config.safe_listening_minutes)),
+        );
+
+        // Apply the new limits to the current state
+        if let Some(id) = self.loud_timer.take() {
+            id.remove();
+        }
+        if self.ready.get() {
+            let snapshot = self.current();
+            self.enforce_max_volume(&snapshot);
+            self.update_loud_timer(&snapshot);
+        }
+    }
+
+    /// Volume limit in percent (`audio.max_volume`).
+    pub fn max_volume(&self) -> u32 {
+        self.max_volume.get()
+    }
+
     /// Get the current audio snapshot.
     pub fn current(&self) -> AudioSnapshot {
         self.current.borrow().clone()
     }
 
+    /// Replace the snapshot with a scripted one and notify listeners
+    /// (`--mock-services`).
+    pub fn publish_mock(&self, snapshot: AudioSnapshot) {
+        *self.current.borrow_mut() = snapshot.clone();
+        if !self.ready.get() {
+            self.ready.set(true);
+            self.ready_at.set(Some(Instant::now()));
+        }
+        self.callbacks.notify(&snapshot);
+    }
+
     /// Get the current volume percentage.
     #[allow(dead_code)]
     pub fn volume(&self) -> u32 {
@@ -288,21 +547,23 @@ impl AudioService {
 
     /// Set volume as a percentage (0–150).
     ///
-    /// Values are clamped to [0, 150]. This method is efficient for rapid
-    /// calls (e.g., holding volume keys).
+    /// Values are clamped to [0, max_volume]. This method is efficient for
+    /// rapid calls (e.g., holding volume keys).
     pub fn set_volume(&self, percent: u32) {
-        let percent = percent.clamp(0, 150);
+        let percent = percent.min(self.max_volume.get());
         let _ = self.command_tx.send(AudioCommand::SetVolume(percent));
     }
 
     /// Adjust volume by a relative amount (e.g., +5 or -5 percentage points).
     #[allow(dead_code)]
     pub fn set_volume_relative(&self, delta: i32) {
-        let _ = self.command_tx.send(AudioCommand::SetVolumeRelative(delta));
+        let _ = self.command_tx.send(AudioCommand::SetVolumeRelative(
+            delta,
+            self.max_volume.get(),
+        ));
     }
 
     /// Set the mute state for the default sink.
-    #[allow(dead_code)]
     pub fn set_muted(&self, muted: bool) {
         let _ = self.command_tx.send(AudioCommand::SetMuted(muted));
     }
@@ -319,7 +580,6 @@ impl AudioService {
     }
 
     /// Toggle the mute state for the default source (mic).
-    #[allow(dead_code)]
     pub fn toggle_mic_mute(&self) {
         let _ = self.command_tx.send(AudioCommand::ToggleMicMute);
     }
@@ -387,6 +647,7 @@ impl AudioService {
             available: update.available,
             control_available: update.control_available,
             mic_control_available: update.mic_control_available,
+            playing_pids: update.playing_pids,
         };
 
         // Check if anything actually changed.
@@ -401,6 +662,7 @@ impl AudioService {
                 && current.available == new_snapshot.available
                 && current.control_available == new_snapshot.control_available
                 && current.mic_control_available == new_snapshot.mic_control_available
+                && current.playing_pids == new_snapshot.playing_pids
                 && current.sinks.len() == new_snapshot.sinks.len()
                 && current.sources.len() == new_snapshot.sources.len()
             {
@@ -414,6 +676,8 @@ impl AudioService {
                             a.name == b.name
                                 && a.is_default == b.is_default
                                 && a.port_available == b.port_available
+                                && a.active_port == b.active_port
+                                && a.jack_plugged == b.jack_plugged
                         });
                 let sources_equal =
                     current
@@ -431,6 +695,18 @@ impl AudioService {
             }
         }
 
+        // Jacks are only compared once the initial device flurry is over
+        let jack_change = if self.in_initial_settle() {
+            None
+        } else {
+            jack_change(&self.current.borrow().sinks, &new_snapshot.sinks)
+        };
+        let overdrive = self.warn_overdrive.get()
+            && !self.in_initial_settle()
+            && !new_snapshot.muted
+            && self.current.borrow().volume <= 100
+            && (101..=self.max_volume.get()).contains(&new_snapshot.volume);
+
         // Update state and mark as ready.
         *self.current.borrow_mut() = new_snapshot.clone();
         if !self.ready.get() {
@@ -440,7 +716,106 @@ impl AudioService {
         }
 
         self.callbacks.notify(&new_snapshot);
+
+        self.enforce_max_volume(&new_snapshot);
+        self.update_loud_timer(&new_snapshot);
+        if overdrive {
+            EventBus::global().publish(&VolumeWarning::Overdrive {
+                volume: new_snapshot.volume,
+            });
+        }
+
+        if let Some((sink, plugged)) = jack_change {
+            let mute = !plugged && self.mute_on_unplug.get() && !new_snapshot.muted;
+            info!(
+                "AudioService: jack {} on '{}'{}",
+                if plugged { "plugged in" } else { "unplugged" },
+                sink.description,
+                if mute { ", muting" } else { "" }
+            );
+            if mute {
+                self.set_muted(true);
+            }
+
+            let port = new_snapshot
+                .sinks
+                .iter()
+                .find(|s| s.is_default)
+                .and_then(|s| s.active_port.clone());
+            EventBus::global().publish(&JackEvent {
+                plugged,
+                sink_description: sink.description,
+                port,
+                muted: new_snapshot.muted || mute,
+            });
+        }
+    }
+
+    /// Turn the volume back down if another tool raised it above the limit.
+    fn enforce_max_volume(&self, snapshot: &AudioSnapshot) {
+        let max = self.max_volume.get();
+        if snapshot.available && snapshot.control_available && snapshot.volume > max {
+            info!(
+                "AudioService: volume {}% above limit, lowering to {}%",
+                snapshot.volume, max
+            );
+            self.set_volume(max);
+        }
     }
+
+    /// Start or stop the safe-listening warning timer.
+    ///
+    /// The warning repeats every `safe_listening_minutes` while headphones
+    /// stay the output at or above `safe_listening_volume`.
+    fn update_loud_timer(&self, snapshot: &AudioSnapshot) {
+        let Some((threshold, minutes)) = self.safe_listening.get() else {
+            return;
+        };
+        let mut timer = self.loud_timer.borrow_mut();
+        if !is_loud_listening(snapshot, threshold) {
+            if let Some(id) = timer.take() {
+                id.remove();
+            }
+        } else if timer.is_none() {
+            debug!(
+                "AudioService: loud headphone listening, warning in {} min",
+                minutes
+            );
+            let elapsed = Cell::new(0);
+            *timer = Some(glib::timeout_add_seconds_local(minutes * 60, move || {
+                elapsed.set(elapsed.get() + minutes);
+                EventBus::global().publish(&VolumeWarning::LoudListening {
+                    minutes: elapsed.get(),
+                });
+                glib::ControlFlow::Continue
+            }));
+        }
+    }
+}
+
+/// Whether the default sink is unmuted headphones at or above `threshold`.
+fn is_loud_listening(snapshot: &AudioSnapshot, threshold: u32) -> bool {
+    !snapshot.muted
+        && snapshot.volume >= threshold
+        && snapshot
+            .sinks
+            .iter()
+            .filter(|sink| sink.is_default)
+            .filter_map(|sink| sink.active_port.as_ref())
+            .any(|port| matches!(port.kind, PortKind::Headphones | PortKind::Headset))
+}
+
+/// The first sink whose jack was plugged in or unplugged between two sink
+/// lists, with its new state.
+fn jack_change(
+    old: &[SinkInfoSnapshot],
+    new: &[SinkInfoSnapshot],
+) -> Option<(SinkInfoSnapshot, bool)> {
+    new.iter().find_map(|sink| {
+        let before = old.iter().find(|s| s.name == sink.name)?.jack_plugged?;
+        let after = sink.jack_plugged?;
+        (before != after).then(|| (sink.clone(), after))
+    })
 }
 
 impl Drop for AudioService {
@@ -491,6 +866,8 @@ struct PulseWorkerState {
     /// Number of consecutive times the reported volume failed to track
     /// our requested volume. Reset to 0 when volume changes are observed.
     stuck_attempts: u8,
+    /// Process IDs of applications currently playing sound.
+    playing_pids: Vec<u32>,
 }
 
 /// Main function for the PulseAudio worker thread.
@@ -686,12 +1063,19 @@ fn setup_subscriptions(
                     Arc::clone(&state_for_cb),
                 );
             }
+            Facility::SinkInput => {
+                // A stream started, stopped, was corked or muted.
+                fetch_sink_inputs_inner(Arc::clone(&context_for_cb), Arc::clone(&state_for_cb));
+            }
             _ => {}
         }
     })));
 
-    // Subscribe to sink, source, and server events.
-    let mask = InterestMaskSet::SINK | InterestMaskSet::SOURCE | InterestMaskSet::SERVER;
+    // Subscribe to sink, source, server and stream events.
+    let mask = InterestMaskSet::SINK
+        | InterestMaskSet::SOURCE
+        | InterestMaskSet::SERVER
+        | InterestMaskSet::SINK_INPUT;
     ctx.subscribe(mask, |_success| {});
 
     ml.unlock();
@@ -712,9 +1096,9 @@ fn handle_command(
                 percent,
             );
         }
-        AudioCommand::SetVolumeRelative(delta) => {
+        AudioCommand::SetVolumeRelative(delta, max) => {
             let current = state.lock().unwrap_or_else(|e| e.into_inner()).volume;
-            let new_volume = (current as i32 + delta).clamp(0, 150) as u32;
+            let new_volume = (current as i32 + delta).clamp(0, max as i32) as u32;
             set_sink_volume(
                 Arc::clone(&mainloop),
                 Arc::clone(&context),
@@ -845,8 +1229,9 @@ fn fetch_full_state(
         // We're inside a callback, so the mainloop is already locked.
         // Use the context directly without locking the mainloop.
 
-        // Fetch sinks
+        // Fetch sinks and the streams playing on them
         fetch_sinks_inner(Arc::clone(&context_for_cb), Arc::clone(&state_for_cb));
+        fetch_sink_inputs_inner(Arc::clone(&context_for_cb), Arc::clone(&state_for_cb));
 
         // Fetch default sink details
         if let Some(sink_name) = default_sink_name {
@@ -940,42 +1325,16 @@ fn fetch_sinks_inner(context: Arc<Mutex<Context>>, state: Arc<Mutex<PulseWorkerS
     introspect.get_sink_info_list(move |result| {
         match result {
             ListResult::Item(info) => {
-                let name = info
-                    .name
-                    .as_ref()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                let description = info
-                    .description
-                    .as_ref()
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| name.clone());
-
                 let default_name = state_for_cb
                     .lock()
                     .unwrap_or_else(|e| e.into_inner())
                     .default_sink_name
                     .clone();
-                let is_default = default_name.as_ref().map(|n| n == &name).unwrap_or(false);
-
-                // Check active port availability (for jack detection, e.g., headphones)
-                // PortAvailable::Unknown means no jack detection support - treat as available
-                // PortAvailable::No means port not available (e.g., headphones unplugged)
-                // PortAvailable::Yes means port is available (e.g., headphones plugged in)
-                let port_available = info.active_port.as_ref().map(|port| match port.available {
-                    PortAvailable::No => false,
-                    PortAvailable::Yes | PortAvailable::Unknown => true,
-                });
 
                 collected_for_cb
                     .lock()
                     .unwrap_or_else(|e| e.into_inner())
-                    .push(SinkInfoSnapshot {
-                        name,
-                        description,
-                        is_default,
-                        port_available,
-                    });
+                    .push(SinkInfoSnapshot::from_info(info, default_name.as_deref()));
             }
             ListResult::End => {
                 // All sinks collected; update state.
@@ -995,6 +1354,51 @@ fn fetch_sinks_inner(context: Arc<Mutex<Context>>, state: Arc<Mutex<PulseWorkerS
     });
 }
 
+/// Collect the PIDs of applications with a playing stream.
+///
+/// Called from within a callback (mainloop already locked).
+fn fetch_sink_inputs_inner(context: Arc<Mutex<Context>>, state: Arc<Mutex<PulseWorkerState>>) {
+    let ctx = context.lock().unwrap_or_else(|e| e.into_inner());
+    let introspect = ctx.introspect();
+
+    let collected_pids = Arc::new(Mutex::new(Vec::new()));
+    let collected_for_cb = Arc::clone(&collected_pids);
+    let state_for_cb = Arc::clone(&state);
+
+    introspect.get_sink_input_info_list(move |result| match result {
+        ListResult::Item(info) => {
+            // Paused players keep their stream open, corked
+            if info.corked || info.mute {
+                return;
+            }
+            let pid = info
+                .proplist
+                .get_str(pulse::proplist::properties::APPLICATION_PROCESS_ID)
+                .and_then(|pid| pid.parse::<u32>().ok());
+            if let Some(pid) = pid {
+                collected_for_cb
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(pid);
+            }
+        }
+        ListResult::End => {
+            let mut pids =
+                std::mem::take(&mut *collected_for_cb.lock().unwrap_or_else(|e| e.into_inner()));
+            pids.sort_unstable();
+            pids.dedup();
+            let mut st = state_for_cb.lock().unwrap_or_else(|e| e.into_inner());
+            if st.playing_pids != pids {
+                st.playing_pids = pids;
+                send_state_update(&st);
+            }
+        }
+        ListResult::Error => {
+            warn!("AudioService: error fetching sink input list");
+        }
+    });
+}
+
 /// Inner version called from within a callback (mainloop already locked).
 fn fetch_sink_by_name_inner(
     context: Arc<Mutex<Context>>,
@@ -1061,15 +1465,32 @@ fn fetch_sink_by_index_from_callback(
 
     introspect.get_sink_info_by_index(index, move |result| {
         if let ListResult::Item(info) = result {
-            // Only update if this is the default sink.
-            let is_default = {
-                let st = state_for_cb.lock().unwrap_or_else(|e| e.into_inner());
-                st.default_sink_index == Some(info.index)
-                    || st.default_sink_name.as_deref() == info.name.as_ref().map(|s| s.as_ref())
+            // Keep the sink list's port state current (jack plug/unplug
+            // only shows up as a change event on the sink).
+            let (is_default, list_changed) = {
+                let mut st = state_for_cb.lock().unwrap_or_else(|e| e.into_inner());
+                let is_default = st.default_sink_index == Some(info.index)
+                    || st.default_sink_name.as_deref() == info.name.as_ref().map(|s| s.as_ref());
+
+                let updated = SinkInfoSnapshot::from_info(info, st.default_sink_name.as_deref());
+                let list_changed = match st.sinks.iter_mut().find(|s| s.name == updated.name) {
+                    Some(sink)
+                        if sink.active_port != updated.active_port
+                            || sink.jack_plugged != updated.jack_plugged
+                            || sink.port_available != updated.port_available =>
+                    {
+                        *sink = updated;
+                        true
+                    }
+                    _ => false,
+                };
+                (is_default, list_changed)
             };
 
             if is_default {
                 update_sink_state(&state_for_cb, info);
+            }
+            if is_default || list_changed {
                 send_state_update(&state_for_cb.lock().unwrap_or_else(|e| e.into_inner()));
             }
         }
@@ -1613,6 +2034,7 @@ fn build_state_update(state: &PulseWorkerState) -> AudioStateUpdate {
         available: state.available,
         control_available: state.control_available,
         mic_control_available: state.mic_control_available,
+        playing_pids: state.playing_pids.clone(),
     }
 }
 
@@ -1650,6 +2072,8 @@ pub struct AudioCli {
     channel_count: u8,
     /// Whether volume control is currently available (sink not suspended).
     control_available: bool,
+    /// Volume limit in percent.
+    max_volume: u32,
 }
 
 impl AudioCli {
@@ -1707,6 +2131,7 @@ impl AudioCli {
             sink_index: None,
             channel_count: 2,         // Default to stereo, updated by refresh_state
             control_available: false, // Conservative default, updated by refresh_state
+            max_volume: MAX_VOLUME,
         };
 
         // Fetch initial state.
@@ -1725,7 +2150,17 @@ impl AudioCli {
         self.muted
     }
 
-    /// Set volume to a specific percentage (0-150).
+    /// Volume limit in percent.
+    pub fn max_volume(&self) -> u32 {
+        self.max_volume
+    }
+
+    /// Limit volume changes to `audio.max_volume`.
+    pub fn configure(&mut self, config: &AudioConfig) {
+        self.max_volume = config.clamp_volume(MAX_VOLUME);
+    }
+
+    /// Set volume to a specific percentage (0-150), clamped to the limit.
     pub fn set_volume(&mut self, percent: u32) -> Result<(), String> {
         let sink_index = self.sink_index.ok_or_else(|| {
             "no default sink found (is PulseAudio/pipewire-pulse running?)".to_string()
@@ -1743,7 +2178,7 @@ impl AudioCli {
             return Err("audio device not ready (try playing audio first)".to_string());
         }
 
-        let percent = percent.clamp(0, 150);
+        let percent = percent.min(self.max_volume);
 
         let mut introspect = self.context.introspect();
 
@@ -1932,3 +2367,78 @@ impl Drop for AudioCli {
         self.context.disconnect();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(name: &str, jack_plugged: Option<bool>) -> SinkInfoSnapshot {
+        SinkInfoSnapshot {
+            name: name.to_string(),
+            description: name.to_string(),
+            is_default: false,
+            port_available: Some(true),
+            active_port: None,
+            jack_plugged,
+        }
+    }
+
+    #[test]
+    fn test_port_kind_from_port() {
+        let kind = |name: &str| PortKind::from_port(name, "");
+        assert_eq!(kind("analog-output-headphones"), PortKind::Headphones);
+        assert_eq!(kind("analog-output-speaker"), PortKind::Speaker);
+        assert_eq!(kind("[Out] Headphones"), PortKind::Headphones);
+        assert_eq!(kind("hdmi-output-1"), PortKind::Hdmi);
+        assert_eq!(kind("iec958-stereo-output"), PortKind::Digital);
+        assert_eq!(kind("headset-output"), PortKind::Headset);
+        // Falls back to the description
+        assert_eq!(
+            PortKind::from_port("analog-output", "Line Out"),
+            PortKind::LineOut
+        );
+        assert_eq!(
+            PortKind::from_port("analog-output", "Analog Output"),
+            PortKind::Other
+        );
+    }
+
+    #[test]
+    fn test_jack_change() {
+        let old = vec![sink("hdmi", None), sink("analog", Some(false))];
+        let new = vec![sink("hdmi", None), sink("analog", Some(true))];
+        let (changed, plugged) = jack_change(&old, &new).unwrap();
+        assert_eq!(changed.name, "analog");
+        assert!(plugged);
+
+        assert!(jack_change(&new, &new).is_none());
+        // New sinks aren't jack events
+        assert!(jack_change(&old[..1], &new).is_none());
+    }
+
+    #[test]
+    fn test_is_loud_listening() {
+        let mut headphones = sink("analog", Some(true));
+        headphones.is_default = true;
+        headphones.active_port = Some(SinkPort {
+            name: "analog-output-headphones".to_string(),
+            description: "Headphones".to_string(),
+            kind: PortKind::Headphones,
+        });
+        let mut snapshot = AudioSnapshot {
+            volume: 85,
+            sinks: vec![sink("hdmi", None), headphones],
+            ..Default::default()
+        };
+        assert!(is_loud_listening(&snapshot, 80));
+        assert!(!is_loud_listening(&snapshot, 90));
+
+        snapshot.muted = true;
+        assert!(!is_loud_listening(&snapshot, 80));
+
+        // Speakers never count
+        snapshot.muted = false;
+        snapshot.sinks[1].active_port.as_mut().unwrap().kind = PortKind::Speaker;
+        assert!(!is_loud_listening(&snapshot, 80));
+    }
+}
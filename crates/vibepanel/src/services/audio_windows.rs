@@ -0,0 +1,279 @@
+//! AudioWindowsService - which windows are producing sound.
+//!
+//! Cross-references the PIDs of playing PulseAudio streams (from
+//! `AudioService`) with the PIDs of open windows (from the compositor
+//! backend). Streams are often opened by a helper process (browser content
+//! or audio service processes), so a stream's process ancestry is walked
+//! until it reaches a window's process.
+//!
+//! A process with several windows can't be narrowed down further, so all of
+//! its windows are reported. Only compositors whose IPC exposes window PIDs
+//! (Hyprland and Niri) are supported; elsewhere the snapshot stays empty.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::fs;
+use std::rc::Rc;
+
+use gtk4::glib;
+use tracing::debug;
+
+use super::audio::{AudioService, AudioSnapshot};
+use super::callbacks::{CallbackId, Callbacks};
+use super::compositor::{CompositorManager, WindowProcess};
+use super::event_bus::EventBus;
+use super::toplevel::{ToplevelService, ToplevelSnapshot};
+
+// Thread-local singleton storage for AudioWindowsService
+thread_local! {
+    static AUDIO_WINDOWS_INSTANCE: RefCell<Option<Rc<AudioWindowsService>>> = const { RefCell::new(None) };
+}
+
+/// How many parent processes to walk up from a stream's process.
+const MAX_ANCESTRY_DEPTH: usize = 8;
+
+/// Canonical snapshot of the windows producing sound.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioWindowsSnapshot {
+    /// Windows whose process (or one of its children) is playing sound.
+    pub windows: Vec<WindowProcess>,
+}
+
+impl AudioWindowsSnapshot {
+    /// Whether a window on workspace `id` is playing sound.
+    pub fn workspace_playing(&self, id: i32) -> bool {
+        self.windows.iter().any(|w| w.workspace_id == Some(id))
+    }
+
+    /// Whether the window with this app ID and title is playing sound.
+    pub fn window_playing(&self, app_id: &str, title: &str) -> bool {
+        self.windows
+            .iter()
+            .any(|w| w.app_id == app_id && w.title == title)
+    }
+}
+
+/// Shared, process-wide audio windows service.
+pub struct AudioWindowsService {
+    snapshot: RefCell<AudioWindowsSnapshot>,
+    callbacks: Callbacks<AudioWindowsSnapshot>,
+    /// PIDs with a playing stream, from the last audio update.
+    playing_pids: RefCell<Vec<u32>>,
+    /// Whether a refresh is already scheduled for this main loop iteration.
+    refresh_pending: Cell<bool>,
+}
+
+impl AudioWindowsService {
+    fn new() -> Rc<Self> {
+        let service = Rc::new(Self {
+            snapshot: RefCell::new(AudioWindowsSnapshot::default()),
+            callbacks: Callbacks::new(),
+            playing_pids: RefCell::new(Vec::new()),
+            refresh_pending: Cell::new(false),
+        });
+
+        // The first caller is usually a bar being built; these subscriptions
+        // belong to the service, not to that bar's scope
+        let this_weak = Rc::downgrade(&service);
+        EventBus::global().unscoped(|| {
+            AudioService::global().connect(move |snapshot: &AudioSnapshot| {
+                if let Some(this) = this_weak.upgrade()
+                    && *this.playing_pids.borrow() != snapshot.playing_pids
+                {
+                    *this.playing_pids.borrow_mut() = snapshot.playing_pids.clone();
+                    this.schedule_refresh();
+                }
+            });
+
+            // Windows opening, closing, retitling or moving between workspaces
+            let this_weak = Rc::downgrade(&service);
+            ToplevelService::global().connect(move |_: &ToplevelSnapshot| {
+                if let Some(this) = this_weak.upgrade() {
+                    this.schedule_refresh();
+                }
+            });
+            let this_weak = Rc::downgrade(&service);
+            CompositorManager::global().register_workspace_callback(move |_| {
+                if let Some(this) = this_weak.upgrade() {
+                    this.schedule_refresh();
+                }
+            });
+        });
+
+        service
+    }
+
+    /// Get the global AudioWindowsService singleton.
+    pub fn global() -> Rc<Self> {
+        AUDIO_WINDOWS_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(AudioWindowsService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        AUDIO_WINDOWS_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&AudioWindowsSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        self.callbacks
+            .notify_single(id, &self.snapshot.borrow().clone());
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Coalesce bursts of audio and window events into one refresh.
+    fn schedule_refresh(self: &Rc<Self>) {
+        if self.refresh_pending.replace(true) {
+            return;
+        }
+        let this_weak = Rc::downgrade(self);
+        glib::idle_add_local_once(move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh_pending.set(false);
+                this.refresh();
+            }
+        });
+    }
+
+    fn refresh(&self) {
+        let playing_pids = self.playing_pids.borrow().clone();
+        // Nothing playing: no need to ask the compositor
+        let windows = if playing_pids.is_empty() {
+            Vec::new()
+        } else {
+            let windows = CompositorManager::global().list_windows();
+            playing_windows(&windows, &playing_pids, parent_pid)
+        };
+
+        let snapshot = AudioWindowsSnapshot { windows };
+        if *self.snapshot.borrow() == snapshot {
+            return;
+        }
+        debug!(
+            "AudioWindowsService: {} windows playing audio",
+            snapshot.windows.len()
+        );
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+/// Windows owned by a playing process or one of its ancestors.
+fn playing_windows(
+    windows: &[WindowProcess],
+    playing_pids: &[u32],
+    parent_of: impl Fn(u32) -> Option<u32>,
+) -> Vec<WindowProcess> {
+    let window_pids: HashSet<u32> = windows.iter().map(|w| w.pid).collect();
+
+    let mut owners = HashSet::new();
+    for &pid in playing_pids {
+        let mut pid = pid;
+        for _ in 0..MAX_ANCESTRY_DEPTH {
+            if window_pids.contains(&pid) {
+                owners.insert(pid);
+                break;
+            }
+            match parent_of(pid) {
+                Some(parent) if parent > 1 => pid = parent,
+                _ => break,
+            }
+        }
+    }
+
+    windows
+        .iter()
+        .filter(|w| owners.contains(&w.pid))
+        .cloned()
+        .collect()
+}
+
+/// Parent PID from `/proc/<pid>/stat`.
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat_ppid(&stat)
+}
+
+/// Parse the parent PID out of a `/proc/<pid>/stat` line.
+///
+/// The command name is in parentheses and may itself contain spaces or
+/// parentheses, so fields are counted from the last `)`.
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    // Fields after the name: state, ppid, ...
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn window(pid: u32, app_id: &str, workspace_id: i32) -> WindowProcess {
+        WindowProcess {
+            pid,
+            title: app_id.to_string(),
+            app_id: app_id.to_string(),
+            workspace_id: Some(workspace_id),
+        }
+    }
+
+    #[test]
+    fn test_parse_stat_ppid() {
+        assert_eq!(
+            parse_stat_ppid("4242 (firefox) S 1200 4242 4242 0 -1 4194560"),
+            Some(1200)
+        );
+        assert_eq!(
+            parse_stat_ppid("77 (Web Content (2)) S 4242 77 77 0 -1"),
+            Some(4242)
+        );
+        assert_eq!(parse_stat_ppid("garbage"), None);
+    }
+
+    #[test]
+    fn test_playing_windows_walks_ancestry() {
+        let windows = vec![
+            window(100, "firefox", 1),
+            window(200, "kitty", 2),
+            window(300, "mpv", 3),
+        ];
+        // 150 is a firefox content process, 250 a shell in kitty
+        let parents: HashMap<u32, u32> = [(150, 100), (250, 200), (100, 1), (200, 1)].into();
+        let parent_of = |pid: u32| parents.get(&pid).copied();
+
+        let playing = playing_windows(&windows, &[150, 300], parent_of);
+        assert_eq!(
+            playing,
+            vec![window(100, "firefox", 1), window(300, "mpv", 3)]
+        );
+
+        // Streams from processes without a window mark nothing
+        assert!(playing_windows(&windows, &[999], parent_of).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_lookups() {
+        let snapshot = AudioWindowsSnapshot {
+            windows: vec![window(100, "firefox", 1)],
+        };
+        assert!(snapshot.workspace_playing(1));
+        assert!(!snapshot.workspace_playing(2));
+        assert!(snapshot.window_playing("firefox", "firefox"));
+        assert!(!snapshot.window_playing("firefox", "Other tab"));
+    }
+}
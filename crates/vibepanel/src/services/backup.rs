@@ -0,0 +1,554 @@
+//! BackupService - age of the last successful backup.
+//!
+//! This service provides:
+//! - Pluggable checkers for borg, restic and timeshift
+//! - Periodic checks in a background thread (tools may hit the network)
+//! - An overdue flag once the last backup is older than a threshold
+//! - A "run backup now" action tracked by the jobs monitor (`vibepanel run`)
+//!
+//! Checkers shell out to the backup tool and parse its listing:
+//! - borg: `borg list --last 1 --json <repository>`
+//! - restic: `restic snapshots --latest 1 --json [-r <repository>]`
+//! - timeshift: snapshot names from `repository` (a snapshot directory such
+//!   as `/timeshift/snapshots`), or `timeshift --list` which requires root
+//!
+//! Credentials come from the environment as usual for each tool
+//! (`BORG_PASSCOMMAND`, `RESTIC_PASSWORD_COMMAND`, ...).
+
+use std::cell::{Cell, RefCell};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use gtk4::glib::{self, SourceId};
+use serde::Deserialize;
+use tracing::{debug, error, info, warn};
+
+use super::actions::{ActionKind, Actions};
+use super::callbacks::Callbacks;
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings};
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for BackupService
+thread_local! {
+    static BACKUP_INSTANCE: RefCell<Option<Rc<BackupService>>> = const { RefCell::new(None) };
+}
+
+/// Default check interval in seconds (15 minutes).
+pub const DEFAULT_CHECK_INTERVAL: u64 = 900;
+
+/// Minimum check interval to avoid hammering remote repositories (1 minute).
+const MIN_CHECK_INTERVAL: u64 = 60;
+
+/// Default age after which a backup is considered overdue (hours).
+pub const DEFAULT_OVERDUE_HOURS: u64 = 24;
+
+/// Supported backup tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupTool {
+    /// BorgBackup.
+    Borg,
+    /// restic.
+    Restic,
+    /// Timeshift system snapshots.
+    Timeshift,
+}
+
+impl BackupTool {
+    /// Parse a tool name from config.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "borg" => Some(Self::Borg),
+            "restic" => Some(Self::Restic),
+            "timeshift" => Some(Self::Timeshift),
+            _ => None,
+        }
+    }
+
+    /// Display name of the tool.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Borg => "borg",
+            Self::Restic => "restic",
+            Self::Timeshift => "timeshift",
+        }
+    }
+
+    /// Find the time of the most recent backup.
+    fn last_backup(&self, repository: Option<&str>) -> Result<Option<SystemTime>, String> {
+        match self {
+            Self::Borg => {
+                let repository = repository.ok_or("borg requires a `repository`")?;
+                let output = run_tool("borg", &["list", "--last", "1", "--json", repository])?;
+                parse_borg_list(&output)
+            }
+            Self::Restic => {
+                let mut args = vec!["snapshots", "--latest", "1", "--json"];
+                if let Some(repository) = repository {
+                    args.extend(["-r", repository]);
+                }
+                let output = run_tool("restic", &args)?;
+                parse_restic_snapshots(&output)
+            }
+            Self::Timeshift => match repository {
+                Some(dir) => {
+                    let entries = std::fs::read_dir(dir)
+                        .map_err(|e| format!("Failed to read {}: {}", dir, e))?;
+                    let names: Vec<String> = entries
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                        .collect();
+                    Ok(latest_timeshift_snapshot(names.iter().map(String::as_str)))
+                }
+                None => {
+                    let output = run_tool("timeshift", &["--list"])?;
+                    Ok(latest_timeshift_snapshot(
+                        output.lines().flat_map(str::split_whitespace),
+                    ))
+                }
+            },
+        }
+    }
+}
+
+/// Settings for the backup checker (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupSettings {
+    /// Backup tool to query.
+    pub tool: BackupTool,
+    /// Repository (borg/restic) or snapshot directory (timeshift).
+    pub repository: Option<String>,
+    /// Age after which the backup is overdue.
+    pub overdue_after: Duration,
+    /// How often to check (seconds).
+    pub check_interval: u64,
+    /// Shell command for "run backup now".
+    pub backup_command: Option<String>,
+}
+
+impl MergeSettings for BackupSettings {
+    /// One backup is tracked: the newest widget's, checked at the shortest
+    /// interval.
+    fn merge(&mut self, other: &Self) {
+        let check_interval = self.check_interval.min(other.check_interval);
+        *self = other.clone();
+        self.check_interval = check_interval;
+    }
+}
+
+/// Canonical snapshot of backup state.
+#[derive(Debug, Clone, Default)]
+pub struct BackupSnapshot {
+    /// Whether a backup tool is configured.
+    pub available: bool,
+    /// Configured tool.
+    pub tool: Option<BackupTool>,
+    /// Whether a check is currently in progress.
+    pub checking: bool,
+    /// Whether a backup started from the widget is running.
+    pub running: bool,
+    /// Time of the most recent backup, if any exist.
+    pub last_backup: Option<SystemTime>,
+    /// Whether the last backup is older than the threshold (or missing).
+    pub overdue: bool,
+    /// Last error message, if any.
+    pub error: Option<String>,
+    /// Whether "run backup now" is available.
+    pub can_run: bool,
+}
+
+/// Shared, process-wide backup service.
+pub struct BackupService {
+    snapshot: RefCell<BackupSnapshot>,
+    callbacks: Callbacks<BackupSnapshot>,
+    /// Settings of each backup widget.
+    registered: WidgetSettings<BackupSettings>,
+    /// Merged settings the checks run with.
+    settings: RefCell<Option<BackupSettings>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent concurrent checks.
+    check_in_progress: Cell<bool>,
+}
+
+impl BackupService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(BackupSnapshot::default()),
+            callbacks: Callbacks::new(),
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global BackupService singleton.
+    pub fn global() -> Rc<Self> {
+        BACKUP_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(BackupService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        BACKUP_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&BackupSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> BackupSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: BackupSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        self.notify();
+    }
+
+    /// Register a backup widget and (re)start periodic checks.
+    pub fn configure(self: &Rc<Self>, settings: BackupSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's settings; checks stop with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Restart checks with the merged settings of all widgets.
+    fn apply_settings(self: &Rc<Self>) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let Some(settings) = settings else {
+            debug!("BackupService: no widgets left, stopping checks");
+            *self.settings.borrow_mut() = None;
+            *self.snapshot.borrow_mut() = BackupSnapshot::default();
+            self.notify();
+            return;
+        };
+
+        info!(
+            "BackupService: checking {} every {}s",
+            settings.tool.name(),
+            settings.check_interval
+        );
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.available = true;
+            snapshot.tool = Some(settings.tool);
+            snapshot.can_run = settings.backup_command.is_some();
+        }
+
+        let interval = settings.check_interval.max(MIN_CHECK_INTERVAL);
+        *self.settings.borrow_mut() = Some(settings);
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Trigger an immediate check.
+    pub fn refresh(&self) {
+        if self.check_in_progress.get() {
+            debug!("BackupService: check already in progress, skipping");
+            return;
+        }
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+
+        self.check_in_progress.set(true);
+        self.snapshot.borrow_mut().checking = true;
+        self.notify();
+
+        WorkerPool::global().spawn(
+            move || settings.tool.last_backup(settings.repository.as_deref()),
+            |result| {
+                BackupService::global()
+                    .apply_check_result(result.unwrap_or_else(|e| Err(e.to_string())));
+            },
+        );
+    }
+
+    /// Start the configured backup command, tracked by the jobs monitor.
+    ///
+    /// The command runs via `vibepanel run` so it shows up in the jobs
+    /// widget; a check runs once it exits.
+    pub fn run_backup_now(&self) {
+        if self.snapshot.borrow().running {
+            return;
+        }
+        let Some(command) = self
+            .settings
+            .borrow()
+            .as_ref()
+            .and_then(|s| s.backup_command.clone())
+        else {
+            return;
+        };
+
+        let exe = match std::env::current_exe() {
+            Ok(exe) => exe,
+            Err(e) => {
+                error!("BackupService: cannot locate vibepanel executable: {}", e);
+                return;
+            }
+        };
+
+        let mut backup = Command::new(exe);
+        backup
+            .args(["run", "--name", "Backup", "--", "sh", "-c", &command])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        // Through the dispatcher so kiosk mode refuses it
+        let mut child = match Actions::global().spawn(ActionKind::Exec, &mut backup) {
+            Ok(child) => child,
+            Err(e) => {
+                error!("BackupService: failed to start backup: {}", e);
+                return;
+            }
+        };
+
+        info!("BackupService: backup started");
+        self.snapshot.borrow_mut().running = true;
+        self.notify();
+
+        std::thread::spawn(move || {
+            if let Err(e) = child.wait() {
+                error!("BackupService: failed to wait for backup: {}", e);
+            }
+            glib::idle_add_once(|| {
+                let service = BackupService::global();
+                service.snapshot.borrow_mut().running = false;
+                service.notify();
+                service.refresh();
+            });
+        });
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply the result of a background check.
+    fn apply_check_result(&self, result: Result<Option<SystemTime>, String>) {
+        self.check_in_progress.set(false);
+
+        let Some(overdue_after) = self.settings.borrow().as_ref().map(|s| s.overdue_after) else {
+            // Every widget went away during the check
+            return;
+        };
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.checking = false;
+            match result {
+                Ok(last_backup) => {
+                    snapshot.error = None;
+                    snapshot.last_backup = last_backup;
+                    snapshot.overdue = is_overdue(last_backup, overdue_after);
+                    debug!("BackupService: last backup {:?}", last_backup);
+                }
+                Err(err) => {
+                    warn!("BackupService: check failed: {}", err);
+                    snapshot.error = Some(err);
+                    // Keep the previous backup time on error
+                }
+            }
+        }
+        self.notify();
+    }
+}
+
+impl Drop for BackupService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Whether a backup taken at `last_backup` is overdue.
+///
+/// A missing backup is always overdue.
+pub fn is_overdue(last_backup: Option<SystemTime>, overdue_after: Duration) -> bool {
+    match last_backup {
+        Some(time) => time.elapsed().unwrap_or_default() > overdue_after,
+        None => true,
+    }
+}
+
+/// Run a backup tool and return its stdout.
+fn run_tool(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().last().unwrap_or("").trim();
+        return Err(format!("{} failed: {}", program, message));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[derive(Deserialize)]
+struct BorgList {
+    archives: Vec<BorgArchive>,
+}
+
+#[derive(Deserialize)]
+struct BorgArchive {
+    /// Local time without offset, e.g. "2024-01-15T03:00:12.000000".
+    time: String,
+}
+
+/// Parse `borg list --json` output.
+fn parse_borg_list(output: &str) -> Result<Option<SystemTime>, String> {
+    let list: BorgList =
+        serde_json::from_str(output).map_err(|e| format!("Unexpected borg output: {}", e))?;
+
+    Ok(list
+        .archives
+        .iter()
+        .filter_map(|archive| {
+            let naive =
+                NaiveDateTime::parse_from_str(&archive.time, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+            Local.from_local_datetime(&naive).earliest()
+        })
+        .max()
+        .map(SystemTime::from))
+}
+
+#[derive(Deserialize)]
+struct ResticSnapshot {
+    /// RFC 3339 timestamp, e.g. "2024-01-15T03:00:12.123456789+01:00".
+    time: String,
+}
+
+/// Parse `restic snapshots --json` output.
+fn parse_restic_snapshots(output: &str) -> Result<Option<SystemTime>, String> {
+    let snapshots: Vec<ResticSnapshot> =
+        serde_json::from_str(output).map_err(|e| format!("Unexpected restic output: {}", e))?;
+
+    Ok(snapshots
+        .iter()
+        .filter_map(|snapshot| DateTime::parse_from_rfc3339(&snapshot.time).ok())
+        .max()
+        .map(SystemTime::from))
+}
+
+/// Find the newest timeshift snapshot name (`YYYY-MM-DD_HH-MM-SS`, local time).
+fn latest_timeshift_snapshot<'a>(names: impl Iterator<Item = &'a str>) -> Option<SystemTime> {
+    names
+        .filter_map(|name| NaiveDateTime::parse_from_str(name, "%Y-%m-%d_%H-%M-%S").ok())
+        .filter_map(|naive| Local.from_local_datetime(&naive).earliest())
+        .max()
+        .map(SystemTime::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_borg_list() {
+        let output = r#"{
+            "archives": [
+                {"archive": "host-2024-01-15", "name": "host-2024-01-15",
+                 "start": "2024-01-15T03:00:12.000000", "time": "2024-01-15T03:00:12.000000"}
+            ],
+            "encryption": {"mode": "repokey"}
+        }"#;
+        let time = parse_borg_list(output).unwrap().unwrap();
+        let expected = Local
+            .with_ymd_and_hms(2024, 1, 15, 3, 0, 12)
+            .earliest()
+            .unwrap();
+        assert_eq!(time, SystemTime::from(expected));
+
+        assert_eq!(parse_borg_list(r#"{"archives": []}"#).unwrap(), None);
+        assert!(parse_borg_list("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_restic_snapshots() {
+        let output = r#"[
+            {"time": "2024-01-14T03:00:00.5+01:00", "id": "a"},
+            {"time": "2024-01-15T03:00:00.123456789+01:00", "id": "b"}
+        ]"#;
+        let time = parse_restic_snapshots(output).unwrap().unwrap();
+        let expected = DateTime::parse_from_rfc3339("2024-01-15T02:00:00.123456789Z").unwrap();
+        assert_eq!(time, SystemTime::from(expected));
+
+        assert_eq!(parse_restic_snapshots("[]").unwrap(), None);
+    }
+
+    #[test]
+    fn test_latest_timeshift_snapshot() {
+        let listing = "Num     Name                 Tags  Description\n\
+                       0    >  2024-01-14_03-00-01  D\n\
+                       1    >  2024-01-15_03-00-01  O\n";
+        let time = latest_timeshift_snapshot(listing.lines().flat_map(str::split_whitespace));
+        let expected = Local
+            .with_ymd_and_hms(2024, 1, 15, 3, 0, 1)
+            .earliest()
+            .unwrap();
+        assert_eq!(time, Some(SystemTime::from(expected)));
+    }
+
+    #[test]
+    fn test_is_overdue() {
+        let day = Duration::from_secs(24 * 3600);
+        assert!(is_overdue(None, day));
+        assert!(!is_overdue(Some(SystemTime::now()), day));
+        assert!(is_overdue(Some(SystemTime::now() - 2 * day), day));
+    }
+
+    #[test]
+    fn test_backup_tool_from_name() {
+        assert_eq!(BackupTool::from_name("Restic"), Some(BackupTool::Restic));
+        assert_eq!(BackupTool::from_name("rsync"), None);
+    }
+}
@@ -14,6 +14,28 @@
 //!   disconnected monitors, respects `bar.outputs` allow-list.
 //! - `reconfigure_all()`: Destroys all bars and recreates them with new config.
 //!
+//! Besides the main bar, each `[[bars]]` entry gets a bar on the monitors in
+//! its own `outputs` allow-list. These are keyed `<connector>#<name>`; the
+//! main bar is keyed by the connector alone.
+//!
+//! Bars map as a skeleton of placeholders and build their widgets afterwards,
+//! one per main loop iteration, so a bar shows up at once on login instead
+//! of after every widget and service has been set up.
+//!
+//! With `bar.on_demand` a bar starts hidden and reserves no space. An
+//! invisible strip along its screen edge reveals it when the pointer touches
+//! it, and it hides again shortly after the pointer leaves (unless a popover
+//! is open). `vibepanel bar toggle` shows or hides any bar.
+//!
+//! With `bar.hide_on_fullscreen` a bar hides while the focused window on its
+//! output is fullscreen. Only the focused window's state is reported by the
+//! compositor backends, so an output is remembered as fullscreen until a
+//! window on it is focused and isn't.
+//!
+//! Widgets hidden with `vibepanel ipc toggle-widget` are tracked by name, so
+//! they stay hidden when bars are rebuilt, and are kept in the state store so
+//! a bar restarted after a compositor crash comes back the way it was.
+//!
 //! This allows live reload of structural changes like:
 //! - Bar size, layout, margins
 //! - Widget list changes
@@ -22,27 +44,101 @@
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use gtk4::glib::SignalHandlerId;
+use gtk4::glib::{self, SignalHandlerId, SourceId};
 use gtk4::prelude::*;
 use gtk4::{Application, ApplicationWindow};
 use tracing::{debug, info};
 
-use vibepanel_core::Config;
+use vibepanel_core::{Config, StateStore, extra_bar_css_class};
 
-use crate::bar;
+use crate::bar::{self, PendingWidget};
+use crate::bar_focus::BarFocus;
+use crate::popover_tracker::PopoverTracker;
+use crate::services::leak_check::LeakCheck;
+use crate::services::state;
 use crate::services::surfaces::SurfaceStyleManager;
-use crate::widgets::BarState;
+use crate::services::window_title::{WindowTitleService, WindowTitleSnapshot};
+use crate::widgets::{BarState, CommandPalette, NotificationCenter};
+
+/// How long an on-demand bar stays after the pointer leaves it.
+const CONCEAL_DELAY: Duration = Duration::from_millis(600);
 
 /// State for a single bar instance on a specific monitor.
 struct BarInstance {
     /// The monitor this bar is displayed on.
-    #[allow(dead_code)]
     monitor: gtk4::gdk::Monitor,
+    /// Key of that monitor (see [`monitor_key`]).
+    output: String,
     /// The bar window.
     window: ApplicationWindow,
     /// Widget handles for this bar (timers, callbacks, etc.).
     state: BarState,
+    /// Idle source building this bar's pending widgets, until all are built.
+    hydration: Option<SourceId>,
+    /// Edge strip that summons the bar, with `bar.on_demand`.
+    hot_edge: Option<ApplicationWindow>,
+    /// Pending hide of an on-demand bar the pointer has left.
+    conceal_timer: RefCell<Option<SourceId>>,
+    /// Hide while the output's focused window is fullscreen.
+    hide_on_fullscreen: bool,
+    /// Keyboard focus mode (`vibepanel ipc focus-bar`).
+    focus: Rc<BarFocus>,
+}
+
+impl BarInstance {
+    /// Show the bar, dropping any pending hide.
+    ///
+    /// The edge strip is hidden meanwhile so it doesn't swallow clicks on
+    /// the bar's outermost pixels.
+    fn reveal(&self) {
+        self.cancel_conceal();
+        if !self.window.is_visible() {
+            debug!("Revealing bar on {:?}", self.monitor.connector());
+            self.window.set_visible(true);
+        }
+        if let Some(ref hot_edge) = self.hot_edge {
+            hot_edge.set_visible(false);
+        }
+    }
+
+    /// Hide the bar, dropping any pending hide.
+    fn conceal(&self) {
+        self.cancel_conceal();
+        if self.window.is_visible() {
+            debug!("Concealing bar on {:?}", self.monitor.connector());
+            self.window.set_visible(false);
+        }
+        if let Some(ref hot_edge) = self.hot_edge {
+            hot_edge.set_visible(true);
+        }
+    }
+
+    /// Hide the bar for a fullscreen window, or bring it back afterwards.
+    ///
+    /// An on-demand bar isn't brought back: it waits to be summoned.
+    fn apply_fullscreen(&self, fullscreen: bool) {
+        if !self.hide_on_fullscreen {
+            return;
+        }
+        if fullscreen {
+            debug!(
+                "Hiding bar on {:?} for a fullscreen window",
+                self.monitor.connector()
+            );
+            self.cancel_conceal();
+            self.window.set_visible(false);
+        } else if self.hot_edge.is_none() {
+            self.window.set_visible(true);
+        }
+    }
+
+    fn cancel_conceal(&self) {
+        if let Some(source) = self.conceal_timer.borrow_mut().take() {
+            source.remove();
+        }
+    }
 }
 
 /// Manages bar window lifecycle across multiple monitors.
@@ -53,8 +149,14 @@ struct BarInstance {
 pub struct BarManager {
     /// Reference to the GTK application.
     app: RefCell<Option<Application>>,
-    /// Bar instances keyed by monitor connector name.
+    /// Bar instances keyed by [`bar_key`].
     bars: RefCell<HashMap<String, BarInstance>>,
+    /// Signal handlers of the current wait for monitors to become ready.
+    ready_handlers: RefCell<Vec<(gtk4::gdk::Monitor, SignalHandlerId)>>,
+    /// Config names of widgets hidden via [`Self::toggle_widget`].
+    hidden_widgets: RefCell<HashSet<String>>,
+    /// Outputs whose focused window was last seen fullscreen.
+    fullscreen_outputs: RefCell<HashSet<String>>,
 }
 
 // Thread-local singleton storage
@@ -83,12 +185,24 @@ fn monitor_key(monitor: &gtk4::gdk::Monitor, index: u32) -> String {
     }
 }
 
+/// Key of a bar: the monitor key for the main bar, `<monitor>#<name>` for
+/// a `[[bars]]` bar.
+fn bar_key(output: &str, extra_bar: Option<&str>) -> String {
+    match extra_bar {
+        Some(name) => format!("{}#{}", output, name),
+        None => output.to_string(),
+    }
+}
+
 impl BarManager {
     /// Create a new BarManager.
     fn new() -> Rc<Self> {
         Rc::new(Self {
             app: RefCell::new(None),
             bars: RefCell::new(HashMap::new()),
+            ready_handlers: RefCell::new(Vec::new()),
+            hidden_widgets: RefCell::new(HashSet::new()),
+            fullscreen_outputs: RefCell::new(HashSet::new()),
         })
     }
 
@@ -111,60 +225,245 @@ impl BarManager {
     /// `sync_monitors()` to create initial bar windows.
     pub fn init(&self, app: &Application) {
         *self.app.borrow_mut() = Some(app.clone());
+        WindowTitleService::global()
+            .connect(|snapshot| BarManager::global().on_focused_window(snapshot));
         debug!("BarManager initialized with app");
     }
 
+    /// Track the fullscreen state of each output from the focused window,
+    /// hiding or restoring its bar when it changes.
+    fn on_focused_window(&self, snapshot: &WindowTitleSnapshot) {
+        let Some(output) = snapshot.output.as_deref() else {
+            return;
+        };
+        let changed = {
+            let mut fullscreen_outputs = self.fullscreen_outputs.borrow_mut();
+            if snapshot.fullscreen {
+                fullscreen_outputs.insert(output.to_string())
+            } else {
+                fullscreen_outputs.remove(output)
+            }
+        };
+        if changed {
+            for instance in self.bars.borrow().values() {
+                if instance.output == output {
+                    instance.apply_fullscreen(snapshot.fullscreen);
+                }
+            }
+        }
+    }
+
+    /// Restore the widgets hidden in the previous session, or forget them.
+    ///
+    /// Call before the first bars are created. `restored` is set when the
+    /// bar was restarted after the compositor went away; a normal start
+    /// clears the saved session so toggles don't outlive it.
+    pub fn restore_session(&self, restored: bool) {
+        let mut persisted = state::load();
+        if restored {
+            let hidden = &persisted.session.hidden_widgets;
+            info!("Restoring session: {} hidden widget(s)", hidden.len());
+            *self.hidden_widgets.borrow_mut() = hidden.iter().cloned().collect();
+        } else if !persisted.session.hidden_widgets.is_empty() {
+            persisted.session = state::SessionState::default();
+            state::save(&persisted);
+        }
+    }
+
     /// Create a bar for a specific monitor.
     ///
-    /// Returns the monitor key used to identify this bar, or None if creation
+    /// `extra_bar` names the `[[bars]]` entry `config` was resolved for, or
+    /// is None for the main bar.
+    ///
+    /// Returns the key used to identify this bar, or None if creation
     /// failed (e.g., app not initialized).
     pub fn create_bar_for_monitor(
         &self,
         monitor: &gtk4::gdk::Monitor,
         monitor_index: u32,
         config: &Config,
+        extra_bar: Option<&str>,
     ) -> Option<String> {
         let app = self.app.borrow();
         let app_ref = app.as_ref()?;
-        let key = monitor_key(monitor, monitor_index);
+        let output = monitor_key(monitor, monitor_index);
+        let key = bar_key(&output, extra_bar);
 
         // Avoid duplicating bars if called redundantly
         if self.bars.borrow().contains_key(&key) {
-            debug!("Bar already exists for monitor key={}", key);
+            debug!("Bar already exists for key={}", key);
             return Some(key);
         }
 
+        let started = Instant::now();
         let mut state = BarState::new();
-        let window = bar::create_bar_window(app_ref, config, monitor, &key, &mut state);
+        let (window, pending) = {
+            // Service subscriptions made by this bar's widgets are dropped with it
+            let _scope = state.enter_scope();
+            bar::create_bar_window(app_ref, config, monitor, &mut state)
+        };
+        LeakCheck::global().track_object("bar window", &window);
+        if let Some(name) = extra_bar {
+            window.add_css_class(&extra_bar_css_class(name));
+        }
+
+        let hot_edge = config.bar.on_demand.then(|| {
+            let motion = gtk4::EventControllerMotion::new();
+            let enter_key = key.clone();
+            motion.connect_enter(move |_, _, _| {
+                if let Some(instance) = BarManager::global().bars.borrow().get(&enter_key) {
+                    instance.cancel_conceal();
+                }
+            });
+            let leave_key = key.clone();
+            motion.connect_leave(move |_| BarManager::global().schedule_conceal(&leave_key));
+            window.add_controller(motion);
+
+            let touch_key = key.clone();
+            bar::create_hot_edge(app_ref, config, monitor, move || {
+                if let Some(instance) = BarManager::global().bars.borrow().get(&touch_key) {
+                    instance.reveal();
+                }
+            })
+        });
 
         // Apply Pango font attributes to all labels if enabled in config.
         SurfaceStyleManager::global().apply_pango_attrs_all(&window);
 
         let instance = BarInstance {
             monitor: monitor.clone(),
+            output: output.clone(),
             window: window.clone(),
             state,
+            hydration: None,
+            hot_edge,
+            conceal_timer: RefCell::new(None),
+            hide_on_fullscreen: config.bar.hide_on_fullscreen,
+            focus: BarFocus::new(&window),
         };
+        if self.fullscreen_outputs.borrow().contains(&output) {
+            instance.apply_fullscreen(true);
+        }
 
         self.bars.borrow_mut().insert(key.clone(), instance);
 
         info!(
-            "Created bar for monitor key={} connector={:?}",
+            "Created bar for monitor key={} connector={:?} in {:?}",
             key,
-            monitor.connector()
+            monitor.connector(),
+            started.elapsed()
         );
 
+        self.start_hydration(&key, pending);
+
         Some(key)
     }
 
-    /// Remove a bar by its monitor key.
+    /// Build a bar's pending widgets in the background, one per main loop
+    /// iteration, so the bar stays responsive and its placeholders fill in
+    /// from left to right.
+    fn start_hydration(&self, key: &str, pending: Vec<PendingWidget>) {
+        if pending.is_empty() {
+            return;
+        }
+
+        let key = key.to_string();
+        let started = Instant::now();
+        let mut pending = pending.into_iter();
+        let source = glib::idle_add_local({
+            let key = key.clone();
+            move || {
+                let manager = BarManager::global();
+                if let Some(item) = pending.next() {
+                    manager.hydrate_widget(&key, item);
+                }
+                if !pending.as_slice().is_empty() {
+                    return glib::ControlFlow::Continue;
+                }
+
+                // The source is done: forget it so remove_bar doesn't remove it again
+                if let Some(instance) = manager.bars.borrow_mut().get_mut(&key) {
+                    instance.hydration = None;
+                    info!(
+                        "Hydrated bar for monitor key={} in {:?} ({} handles)",
+                        key,
+                        started.elapsed(),
+                        instance.state.handle_count()
+                    );
+                }
+                glib::ControlFlow::Break
+            }
+        });
+
+        if let Some(instance) = self.bars.borrow_mut().get_mut(&key) {
+            instance.hydration = Some(source);
+        }
+    }
+
+    /// Build one pending widget of the bar for `key` and record it in the
+    /// bar's state.
+    fn hydrate_widget(&self, key: &str, pending: PendingWidget) {
+        // Don't hold the borrow while building: widgets may reach back into
+        // the manager while they set up.
+        let (scope, qs_handle, output) = {
+            let bars = self.bars.borrow();
+            let Some(instance) = bars.get(key) else {
+                return;
+            };
+            (
+                instance.state.enter_scope(),
+                instance.state.quick_settings().cloned(),
+                instance.output.clone(),
+            )
+        };
+        let built = bar::hydrate_widget(pending, qs_handle.as_ref(), Some(&output));
+        drop(scope);
+
+        for (_, built) in &built {
+            SurfaceStyleManager::global().apply_pango_attrs_all(&built.widget);
+        }
+
+        let mut bars = self.bars.borrow_mut();
+        let Some(instance) = bars.get_mut(key) else {
+            return;
+        };
+        let hidden = self.hidden_widgets.borrow();
+        for (name, built) in built {
+            if hidden.contains(&name) {
+                built.widget.set_visible(false);
+            }
+            instance.state.add_widget(&name, &built.widget);
+            instance.state.add_handle(built.handle);
+        }
+    }
+
+    /// Remove a bar by its key.
     ///
     /// Closes the window and drops the BarState, cleaning up timers/callbacks.
     pub fn remove_bar(&self, key: &str) {
-        if let Some(instance) = self.bars.borrow_mut().remove(key) {
-            debug!("Removing bar for key={}", key);
+        let removed = self.bars.borrow_mut().remove(key);
+        if let Some(mut instance) = removed {
+            // Widgets still waiting to be built are dropped with the source
+            if let Some(source) = instance.hydration.take() {
+                source.remove();
+            }
+            instance.cancel_conceal();
+            if let Some(hot_edge) = instance.hot_edge.take() {
+                hot_edge.close();
+            }
+            debug!(
+                "Removing bar for key={} ({} handles, {} subscriptions)",
+                key,
+                instance.state.handle_count(),
+                instance.state.subscription_count()
+            );
             instance.window.close();
-            // BarState is dropped here, cleaning up widget handles
+            // Window children hold the last references to the widgets
+            instance.window.set_child(None::<&gtk4::Widget>);
+            // BarState is dropped here, cleaning up widget handles and
+            // unsubscribing the bar's service callbacks
+            drop(instance);
+            LeakCheck::global().report("bar removed");
         }
     }
 
@@ -174,6 +473,8 @@ impl BarManager {
     /// - Creates bars for new monitors (respecting `bar.outputs` allow-list)
     /// - Removes bars for disconnected monitors
     /// - Removes bars for monitors no longer in the allow-list
+    /// - Applies `[bar.output."<connector>"]` overrides, skipping disabled outputs
+    /// - Does the same for each `[[bars]]` bar, with its own allow-list
     ///
     /// Call this on initial activation and when monitors change.
     pub fn sync_monitors(&self, display: &gtk4::gdk::Display, config: &Config) {
@@ -192,14 +493,31 @@ impl BarManager {
             // Check bar.outputs allow-list (empty = all monitors)
             if !config.bar.outputs.is_empty() && !config.bar.outputs.contains(&key) {
                 debug!("Skipping monitor {} (not in bar.outputs)", key);
-                continue;
+            } else if !config.bar.is_output_enabled(&key) {
+                debug!("Skipping monitor {} (disabled in bar.output)", key);
+            } else {
+                seen_keys.insert(key.clone());
+
+                // Create bar if it doesn't exist
+                if !self.bars.borrow().contains_key(&key) {
+                    self.create_bar_for_monitor(&monitor, i, &config.for_output(&key), None);
+                }
             }
 
-            seen_keys.insert(key.clone());
-
-            // Create bar if it doesn't exist
-            if !self.bars.borrow().contains_key(&key) {
-                self.create_bar_for_monitor(&monitor, i, config);
+            for extra in &config.bars {
+                if !extra.outputs.is_empty() && !extra.outputs.contains(&key) {
+                    continue;
+                }
+                let extra_key = bar_key(&key, Some(&extra.name));
+                seen_keys.insert(extra_key.clone());
+                if !self.bars.borrow().contains_key(&extra_key) {
+                    self.create_bar_for_monitor(
+                        &monitor,
+                        i,
+                        &config.for_bar(extra),
+                        Some(&extra.name),
+                    );
+                }
             }
         }
 
@@ -252,6 +570,189 @@ impl BarManager {
             .sum()
     }
 
+    /// Get the total number of service subscriptions across all bars.
+    pub fn subscription_count(&self) -> usize {
+        self.bars
+            .borrow()
+            .values()
+            .map(|instance| instance.state.subscription_count())
+            .sum()
+    }
+
+    /// Toggle the visibility of every widget named `name` on every bar.
+    ///
+    /// Returns the number of widgets toggled. The widget stays hidden on
+    /// bars built later and across a restart after a compositor crash.
+    pub fn toggle_widget(&self, name: &str) -> usize {
+        let count = self
+            .bars
+            .borrow()
+            .values()
+            .map(|instance| instance.state.toggle_widget(name))
+            .sum();
+        if count == 0 {
+            return 0;
+        }
+
+        let hidden_widgets = {
+            let mut hidden = self.hidden_widgets.borrow_mut();
+            if !hidden.remove(name) {
+                hidden.insert(name.to_string());
+            }
+            let mut names: Vec<String> = hidden.iter().cloned().collect();
+            names.sort();
+            names
+        };
+        let mut persisted = state::load();
+        persisted.session.hidden_widgets = hidden_widgets;
+        state::save(&persisted);
+        // Write right away: a compositor crash ends the process without
+        // running the shutdown handler that flushes debounced state
+        StateStore::global().flush();
+        count
+    }
+
+    /// Show or hide the bar for `output`, resolved like
+    /// [`Self::open_quick_settings`].
+    ///
+    /// An on-demand bar revealed this way stays until toggled again or until
+    /// the pointer passes over it and leaves.
+    pub fn toggle_bar(&self, output: Option<&str>) -> Result<(), String> {
+        let bars = self.bars.borrow();
+        let instance = Self::bar_for_output(&bars, output)?;
+        if instance.window.is_visible() {
+            instance.conceal();
+        } else {
+            instance.reveal();
+        }
+        Ok(())
+    }
+
+    /// Give the bar for `output`, resolved like
+    /// [`Self::open_quick_settings`], the keyboard for navigating its
+    /// widgets. An on-demand bar is revealed first.
+    pub fn focus_bar(&self, output: Option<&str>) -> Result<(), String> {
+        let bars = self.bars.borrow();
+        let instance = Self::bar_for_output(&bars, output)?;
+        if !instance.window.is_visible() {
+            instance.reveal();
+        }
+        instance.focus.enter()
+    }
+
+    /// Hide the on-demand bar `key` after [`CONCEAL_DELAY`], unless the
+    /// pointer comes back first.
+    fn schedule_conceal(&self, key: &str) {
+        let bars = self.bars.borrow();
+        let Some(instance) = bars.get(key) else {
+            return;
+        };
+        if instance.hot_edge.is_none() || instance.conceal_timer.borrow().is_some() {
+            return;
+        }
+
+        let key = key.to_string();
+        let source = glib::timeout_add_local_once(CONCEAL_DELAY, move || {
+            let manager = BarManager::global();
+            let bars = manager.bars.borrow();
+            let Some(instance) = bars.get(&key) else {
+                return;
+            };
+            // This source is done: forget it so cancel_conceal doesn't remove it
+            instance.conceal_timer.borrow_mut().take();
+            if PopoverTracker::global().has_active() {
+                // Keep the bar while one of its popovers is open
+                drop(bars);
+                manager.schedule_conceal(&key);
+                return;
+            }
+            instance.conceal();
+        });
+        *instance.conceal_timer.borrow_mut() = Some(source);
+    }
+
+    /// Open Quick Settings on the bar for `output`.
+    ///
+    /// Without an output, uses the monitor of the focused window, falling
+    /// back to the first bar.
+    pub fn open_quick_settings(&self, output: Option<&str>) -> Result<(), String> {
+        let bars = self.bars.borrow();
+        let instance = Self::bar_for_output(&bars, output)?;
+        instance.state.open_quick_settings()
+    }
+
+    /// Open or close the notification center on the monitor of a bar,
+    /// resolved like [`Self::open_quick_settings`].
+    pub fn toggle_notification_center(&self, output: Option<&str>) -> Result<(), String> {
+        let (app, monitor) = {
+            let bars = self.bars.borrow();
+            let instance = Self::bar_for_output(&bars, output)?;
+            let app = instance
+                .window
+                .application()
+                .ok_or_else(|| "bar window has no application".to_string())?;
+            (app, instance.monitor.clone())
+        };
+        NotificationCenter::global().toggle(&app, Some(&monitor));
+        Ok(())
+    }
+
+    /// Open or close the command palette on the monitor of a bar, resolved
+    /// like [`Self::open_quick_settings`].
+    pub fn toggle_command_palette(&self, output: Option<&str>) -> Result<(), String> {
+        let (app, monitor, output, widget_names) = {
+            let bars = self.bars.borrow();
+            let instance = Self::bar_for_output(&bars, output)?;
+            let app = instance
+                .window
+                .application()
+                .ok_or_else(|| "bar window has no application".to_string())?;
+            let output = instance.monitor.connector().map(|c| c.to_string());
+            (
+                app,
+                instance.monitor.clone(),
+                output,
+                instance.state.widget_names(),
+            )
+        };
+        CommandPalette::global().toggle(&app, &monitor, output, widget_names);
+        Ok(())
+    }
+
+    /// The bar on `output`, or on the focused window's monitor (falling back
+    /// to the first bar) when no output is given.
+    fn bar_for_output<'a>(
+        bars: &'a HashMap<String, BarInstance>,
+        output: Option<&str>,
+    ) -> Result<&'a BarInstance, String> {
+        let key = match output {
+            Some(output) => output.to_string(),
+            None => {
+                let focused = WindowTitleService::global().snapshot().output;
+                match focused.filter(|key| bars.values().any(|instance| instance.output == *key)) {
+                    Some(key) => key,
+                    None => bars
+                        .keys()
+                        .min()
+                        .cloned()
+                        .ok_or_else(|| "no bars are running".to_string())?,
+                }
+            }
+        };
+        // Prefer the main bar, but an output may only have `[[bars]]` bars
+        bars.get(&key)
+            .or_else(|| bars.values().find(|instance| instance.output == key))
+            .ok_or_else(|| format!("no bar on output '{}'", key))
+    }
+
+    /// Disconnect the handlers of a pending wait for monitors to become
+    /// ready, if any.
+    fn cancel_ready_wait(&self) {
+        for (monitor, handler) in self.ready_handlers.borrow_mut().drain(..) {
+            monitor.disconnect(handler);
+        }
+    }
+
     /// Get the number of active bars.
     pub fn bar_count(&self) -> usize {
         self.bars.borrow().len()
@@ -313,6 +814,12 @@ fn monitor_id(monitor: &gtk4::gdk::Monitor) -> usize {
 /// initialized before syncing, avoiding the need for arbitrary delays.
 pub fn sync_monitors_when_ready(display: &gtk4::gdk::Display, config: &vibepanel_core::Config) {
     let monitors = display.monitors();
+    let manager = BarManager::global();
+
+    // This change supersedes any earlier wait. Without this, every hotplug
+    // event for a monitor that never becomes ready (unplugged mid-setup, or
+    // both change signals firing) would leave its handlers connected.
+    manager.cancel_ready_wait();
 
     // Find monitors that aren't fully ready yet, tracking them by identity
     let mut pending_monitors: Vec<gtk4::gdk::Monitor> = Vec::new();
@@ -333,7 +840,6 @@ pub fn sync_monitors_when_ready(display: &gtk4::gdk::Display, config: &vibepanel
     if pending_monitors.is_empty() {
         // All monitors are ready, sync immediately
         info!("All monitors ready, syncing bars...");
-        let manager = BarManager::global();
         manager.sync_monitors(display, config);
         manager.show_all();
     } else {
@@ -346,14 +852,11 @@ pub fn sync_monitors_when_ready(display: &gtk4::gdk::Display, config: &vibepanel
         let display = display.clone();
         let config = config.clone();
         let pending_set = Rc::new(RefCell::new(pending_set));
-        let signal_handlers: Rc<RefCell<Vec<(gtk4::gdk::Monitor, SignalHandlerId)>>> =
-            Rc::new(RefCell::new(Vec::new()));
 
         for monitor in pending_monitors {
             let display = display.clone();
             let config = config.clone();
             let pending_set = pending_set.clone();
-            let signal_handlers = signal_handlers.clone();
 
             // Closure to check if this monitor is now ready and trigger sync if all are done.
             // Using a HashSet ensures that even if multiple signals fire for the same monitor,
@@ -362,7 +865,6 @@ pub fn sync_monitors_when_ready(display: &gtk4::gdk::Display, config: &vibepanel
                 let display = display.clone();
                 let config = config.clone();
                 let pending_set = pending_set.clone();
-                let signal_handlers = signal_handlers.clone();
                 move |mon: &gtk4::gdk::Monitor| {
                     if monitor_is_ready(mon) {
                         let mut pending = pending_set.borrow_mut();
@@ -387,9 +889,7 @@ pub fn sync_monitors_when_ready(display: &gtk4::gdk::Display, config: &vibepanel
                                 manager.show_all();
 
                                 // Disconnect all signal handlers to avoid reference cycles
-                                for (mon, handler) in signal_handlers.borrow_mut().drain(..) {
-                                    mon.disconnect(handler);
-                                }
+                                manager.cancel_ready_wait();
                             }
                         }
                     }
@@ -409,7 +909,7 @@ pub fn sync_monitors_when_ready(display: &gtk4::gdk::Display, config: &vibepanel
                 check_ready_geometry(mon);
             });
 
-            let mut handlers = signal_handlers.borrow_mut();
+            let mut handlers = manager.ready_handlers.borrow_mut();
             handlers.push((monitor.clone(), handler_connector));
             handlers.push((monitor.clone(), handler_geometry));
         }
@@ -1,11 +1,15 @@
 //! BatteryService - shared, event-driven battery state via UPower.
 //!
 //! - Asynchronously connects to the system DBus and UPower DisplayDevice
+//!   (the combined state of all laptop batteries, or a UPS on desktops)
+//! - Enumerates individual UPower devices and follows `DeviceAdded` /
+//!   `DeviceRemoved`, so each battery, UPS and wireless peripheral is listed
 //! - Reads cached properties for initial state
 //! - Listens for `PropertiesChanged` ("g-properties-changed") updates
 //! - Notifies listeners on the GLib main loop with a canonical snapshot.
 
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use std::rc::Rc;
@@ -16,12 +20,24 @@ use gtk4::prelude::*;
 use tracing::{debug, error, warn};
 
 use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+use super::mock::MockServices;
+
+// Thread-local singleton storage for BatteryService
+thread_local! {
+    static BATTERY_INSTANCE: RefCell<Option<Rc<BatteryService>>> = const { RefCell::new(None) };
+}
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "upower";
 
 /// Path to the kernel's power supply sysfs directory.
 const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
 
 /// DBus constants for the UPower DisplayDevice.
 const UPOWER_NAME: &str = "org.freedesktop.UPower";
+const UPOWER_PATH: &str = "/org/freedesktop/UPower";
+const UPOWER_IFACE: &str = "org.freedesktop.UPower";
 const DISPLAY_PATH: &str = "/org/freedesktop/UPower/devices/DisplayDevice";
 const DEVICE_IFACE: &str = "org.freedesktop.UPower.Device";
 
@@ -29,13 +45,116 @@ const DEVICE_IFACE: &str = "org.freedesktop.UPower.Device";
 /// See: https://upower.freedesktop.org/docs/Device.html#Device:state
 /// Note: UPower returns State as u32, TimeToEmpty/TimeToFull as i64.
 pub const STATE_CHARGING: u32 = 1;
+pub const STATE_DISCHARGING: u32 = 2;
 pub const STATE_FULLY_CHARGED: u32 = 4;
 
+/// UPower device type codes of interest.
+/// See: https://upower.freedesktop.org/docs/Device.html#Device:Type
+pub const KIND_LINE_POWER: u32 = 1;
+pub const KIND_BATTERY: u32 = 2;
+pub const KIND_UPS: u32 = 3;
+pub const KIND_MONITOR: u32 = 4;
+
+/// A single UPower device with a battery.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatteryDevice {
+    /// UPower object path.
+    pub path: String,
+    /// Raw UPower device type code.
+    pub kind: u32,
+    /// Model name reported by the device (may be empty).
+    pub model: String,
+    /// Whether the device powers the computer (laptop battery or UPS).
+    pub power_supply: bool,
+    /// Percentage in range 0.0-100.0.
+    pub percent: f64,
+    /// Raw UPower state code, if known.
+    pub state: Option<u32>,
+}
+
+impl BatteryDevice {
+    /// Display name: the model, or the device type when there is none.
+    pub fn name(&self) -> String {
+        let model = self.model.trim();
+        if model.is_empty() {
+            kind_label(self.kind).to_string()
+        } else {
+            model.to_string()
+        }
+    }
+
+    fn from_proxy(path: &str, proxy: &gio::DBusProxy) -> Option<Self> {
+        let kind = proxy.cached_property("Type")?.get::<u32>()?;
+        let present = proxy
+            .cached_property("IsPresent")
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(true);
+        // Line power and monitors have no battery; empty bays aren't listed
+        if matches!(kind, KIND_LINE_POWER | KIND_MONITOR) || !present {
+            return None;
+        }
+
+        Some(Self {
+            path: path.to_string(),
+            kind,
+            model: proxy
+                .cached_property("Model")
+                .and_then(|v| v.get::<String>())
+                .unwrap_or_default(),
+            power_supply: proxy
+                .cached_property("PowerSupply")
+                .and_then(|v| v.get::<bool>())
+                .unwrap_or(false),
+            percent: proxy.cached_property("Percentage")?.get::<f64>()?,
+            state: proxy
+                .cached_property("State")
+                .and_then(|v| v.get::<u32>())
+                .filter(|&state| state != 0),
+        })
+    }
+}
+
+/// Human-readable name for a UPower device type code.
+pub fn kind_label(kind: u32) -> &'static str {
+    match kind {
+        KIND_BATTERY => "Battery",
+        KIND_UPS => "UPS",
+        5 => "Mouse",
+        6 => "Keyboard",
+        8 => "Phone",
+        9 => "Media player",
+        10 => "Tablet",
+        12 => "Controller",
+        13 => "Pen",
+        14 => "Touchpad",
+        17 => "Headset",
+        18 => "Speakers",
+        19 => "Headphones",
+        26 => "Wearable",
+        _ => "Device",
+    }
+}
+
+/// Order devices for display: the computer's own batteries, then UPSes,
+/// then peripherals, each by name.
+fn sort_devices(devices: &mut [BatteryDevice]) {
+    devices.sort_by_key(|device| {
+        let group = match (device.power_supply, device.kind) {
+            (true, KIND_BATTERY) => 0,
+            (_, KIND_UPS) => 1,
+            _ => 2,
+        };
+        (group, device.name().to_lowercase(), device.path.clone())
+    });
+}
+
 /// Canonical snapshot of battery state.
 #[derive(Debug, Clone)]
 pub struct BatterySnapshot {
     /// Whether the UPower service is available.
     pub available: bool,
+    /// Whether there is a system battery or UPS to show in the bar.
+    pub present: bool,
     /// Percentage in range 0.0-100.0 if known.
     pub percent: Option<f64>,
     /// Raw UPower state code, if known (u32 from DBus).
@@ -46,55 +165,80 @@ pub struct BatterySnapshot {
     pub time_to_empty: Option<i64>,
     /// Seconds until full, if known (i64 from DBus).
     pub time_to_full: Option<i64>,
+    /// Individual batteries, UPSes and peripherals, in display order.
+    pub devices: Vec<BatteryDevice>,
 }
 
 impl BatterySnapshot {
     pub fn unknown() -> Self {
         Self {
             available: false,
+            present: false,
             percent: None,
             state: None,
             energy_rate: None,
             time_to_empty: None,
             time_to_full: None,
+            devices: Vec::new(),
         }
     }
+
+    /// Whether the device list says more than the combined state does:
+    /// several batteries, a UPS or any peripheral.
+    pub fn has_device_details(&self) -> bool {
+        self.devices.len() > 1
+            || self
+                .devices
+                .iter()
+                .any(|device| !(device.power_supply && device.kind == KIND_BATTERY))
+    }
 }
 
 /// Shared, process-wide battery service.
 pub struct BatteryService {
+    /// Whether sysfs showed a system battery at startup.
+    system_battery: bool,
     proxy: RefCell<Option<gio::DBusProxy>>,
+    /// UPower root object, for enumerating devices.
+    upower_proxy: RefCell<Option<gio::DBusProxy>>,
+    /// Per-device proxies by object path.
+    device_proxies: RefCell<BTreeMap<String, gio::DBusProxy>>,
     snapshot: RefCell<BatterySnapshot>,
     callbacks: Callbacks<BatterySnapshot>,
 }
 
 impl BatteryService {
     fn new() -> Rc<Self> {
-        let has_battery = Self::has_battery_device();
+        let mocked = MockServices::global().is_enabled();
+        let has_battery = !mocked && Self::has_battery_device();
 
         // Set available = true immediately if we detected a battery device, so
-        // that synchronous checks (e.g., widget factory) see the correct state
-        // before the async D-Bus initialization completes.
-        let initial_snapshot = if has_battery {
-            BatterySnapshot {
-                available: true,
-                ..BatterySnapshot::unknown()
-            }
-        } else {
-            BatterySnapshot::unknown()
+        // that the widget shows up before the async D-Bus initialization
+        // completes. Without one, UPower may still report a UPS.
+        let initial_snapshot = BatterySnapshot {
+            available: has_battery,
+            present: has_battery,
+            ..BatterySnapshot::unknown()
         };
 
         let service = Rc::new(Self {
+            system_battery: has_battery,
             proxy: RefCell::new(None),
+            upower_proxy: RefCell::new(None),
+            device_proxies: RefCell::new(BTreeMap::new()),
             snapshot: RefCell::new(initial_snapshot),
             callbacks: Callbacks::new(),
         });
 
-        if has_battery {
-            Self::init_dbus(&service);
-        } else {
-            warn!("BatteryService: no battery device found; service disabled");
+        if mocked {
+            debug!("BatteryService: mocked, skipping UPower");
+            return service;
+        }
+        if !has_battery {
+            debug!("BatteryService: no system battery in sysfs; relying on UPower");
         }
+        Self::init_dbus(&service);
+        Self::init_devices(&service);
 
         service
     }
@@ -151,11 +295,17 @@ impl BatteryService {
 
     /// Get the global BatteryService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<BatteryService> = BatteryService::new();
-        }
+        BATTERY_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(BatteryService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        BATTERY_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked whenever the battery snapshot changes.
@@ -176,6 +326,13 @@ impl BatteryService {
         self.snapshot.borrow().clone()
     }
 
+    /// Replace the snapshot with a scripted one and notify listeners
+    /// (`--mock-services` and widget tests).
+    pub fn publish_mock(&self, snapshot: BatterySnapshot) {
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+
     fn init_dbus(this: &Rc<Self>) {
         let this_weak = Rc::downgrade(this);
 
@@ -241,7 +398,10 @@ impl BatteryService {
         if !snapshot.available {
             return; // Already unavailable
         }
-        *snapshot = BatterySnapshot::unknown();
+        *snapshot = BatterySnapshot {
+            present: self.system_battery,
+            ..BatterySnapshot::unknown()
+        };
         let snapshot_clone = snapshot.clone();
         drop(snapshot);
         self.callbacks.notify(&snapshot_clone);
@@ -272,23 +432,31 @@ impl BatteryService {
         let energy_rate = variant_f64(proxy.cached_property("EnergyRate"));
         let time_to_empty = variant_i64(proxy.cached_property("TimeToEmpty"));
         let time_to_full = variant_i64(proxy.cached_property("TimeToFull"));
+        let present = proxy
+            .cached_property("IsPresent")
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false)
+            || self.system_battery;
 
         let percent = match (energy, full) {
             (Some(e), Some(f)) if f > 0.0 => Some(((e / f) * 100.0).clamp(0.0, 100.0)),
             _ => percentage_prop,
         };
 
+        let mut snapshot = self.snapshot.borrow_mut();
         let new_snapshot = BatterySnapshot {
             available: true,
+            present,
             percent,
             state,
             energy_rate,
             time_to_empty,
             time_to_full,
+            devices: snapshot.devices.clone(),
         };
 
-        let mut snapshot = self.snapshot.borrow_mut();
         if snapshot.available == new_snapshot.available
+            && snapshot.present == new_snapshot.present
             && snapshot.percent == new_snapshot.percent
             && snapshot.state == new_snapshot.state
             && snapshot.energy_rate == new_snapshot.energy_rate
@@ -302,4 +470,235 @@ impl BatteryService {
         drop(snapshot); // Release borrow before notify
         self.callbacks.notify(&self.snapshot.borrow());
     }
+
+    /// Connect to the UPower root object and track its devices.
+    fn init_devices(this: &Rc<Self>) {
+        let this_weak = Rc::downgrade(this);
+        gio::DBusProxy::for_bus(
+            gio::BusType::System,
+            gio::DBusProxyFlags::NONE,
+            None::<&gio::DBusInterfaceInfo>,
+            UPOWER_NAME,
+            UPOWER_PATH,
+            UPOWER_IFACE,
+            None::<&gio::Cancellable>,
+            move |res| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                let proxy = match res {
+                    Ok(p) => p,
+                    Err(e) => {
+                        warn!("Failed to create UPower root DBusProxy: {}", e);
+                        return;
+                    }
+                };
+
+                let this_weak = Rc::downgrade(&this);
+                proxy.connect_local("g-signal", false, move |values| {
+                    let this = this_weak.upgrade()?;
+                    let signal_name = values
+                        .get(2)
+                        .and_then(|v| v.get::<&str>().ok())
+                        .unwrap_or("");
+                    let path = values
+                        .get(3)
+                        .and_then(|v| v.get::<glib::Variant>().ok())
+                        .and_then(|params| params.try_child_value(0))
+                        .and_then(|v| v.get::<String>())?;
+                    match signal_name {
+                        "DeviceAdded" => Self::add_device(&this, &path),
+                        "DeviceRemoved" => this.remove_device(&path),
+                        _ => {}
+                    }
+                    None
+                });
+
+                // Object paths don't survive a UPower restart: start over.
+                let this_weak = Rc::downgrade(&this);
+                proxy.connect_local("notify::g-name-owner", false, move |values| {
+                    let this = this_weak.upgrade()?;
+                    this.device_proxies.borrow_mut().clear();
+                    this.update_devices();
+                    let proxy = values[0].get::<gio::DBusProxy>().ok();
+                    if proxy.and_then(|p| p.name_owner()).is_some() {
+                        Self::enumerate_devices(&this);
+                    }
+                    None
+                });
+
+                this.upower_proxy.replace(Some(proxy));
+                Self::enumerate_devices(&this);
+            },
+        );
+    }
+
+    fn enumerate_devices(this: &Rc<Self>) {
+        let Some(proxy) = this.upower_proxy.borrow().clone() else {
+            return;
+        };
+
+        let this_weak = Rc::downgrade(this);
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &proxy,
+            "EnumerateDevices",
+            None,
+            CallPolicy::QUERY,
+            move |res| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                let result = match res {
+                    Ok(result) => result,
+                    Err(e) => {
+                        warn!("UPower EnumerateDevices failed: {}", e);
+                        return;
+                    }
+                };
+
+                // Result is (ao,) - array of object paths in a tuple
+                let paths: Vec<String> = result
+                    .child_value(0)
+                    .iter()
+                    .filter_map(|v| v.get::<String>())
+                    .collect();
+                debug!("BatteryService: {} UPower devices", paths.len());
+                for path in paths {
+                    Self::add_device(&this, &path);
+                }
+            },
+        );
+    }
+
+    fn add_device(this: &Rc<Self>, path: &str) {
+        if this.device_proxies.borrow().contains_key(path) {
+            return;
+        }
+
+        let this_weak = Rc::downgrade(this);
+        let device_path = path.to_string();
+        gio::DBusProxy::for_bus(
+            gio::BusType::System,
+            gio::DBusProxyFlags::NONE,
+            None::<&gio::DBusInterfaceInfo>,
+            UPOWER_NAME,
+            path,
+            DEVICE_IFACE,
+            None::<&gio::Cancellable>,
+            move |res| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                let proxy = match res {
+                    Ok(p) => p,
+                    Err(e) => {
+                        debug!(
+                            "Failed to create UPower device proxy {}: {}",
+                            device_path, e
+                        );
+                        return;
+                    }
+                };
+                // Removed again before the proxy was ready
+                if proxy.cached_property("Type").is_none() {
+                    return;
+                }
+
+                let this_weak = Rc::downgrade(&this);
+                proxy.connect_local("g-properties-changed", false, move |_| {
+                    if let Some(this) = this_weak.upgrade() {
+                        this.update_devices();
+                    }
+                    None
+                });
+
+                this.device_proxies.borrow_mut().insert(device_path, proxy);
+                this.update_devices();
+            },
+        );
+    }
+
+    fn remove_device(&self, path: &str) {
+        if self.device_proxies.borrow_mut().remove(path).is_some() {
+            self.update_devices();
+        }
+    }
+
+    /// Rebuild the device list from the device proxies.
+    fn update_devices(&self) {
+        let mut devices: Vec<BatteryDevice> = self
+            .device_proxies
+            .borrow()
+            .iter()
+            .filter_map(|(path, proxy)| BatteryDevice::from_proxy(path, proxy))
+            .collect();
+        sort_devices(&mut devices);
+
+        let mut snapshot = self.snapshot.borrow_mut();
+        if snapshot.devices == devices {
+            return;
+        }
+        snapshot.devices = devices;
+        drop(snapshot); // Release borrow before notify
+        self.callbacks.notify(&self.snapshot.borrow());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(path: &str, kind: u32, model: &str, power_supply: bool) -> BatteryDevice {
+        BatteryDevice {
+            path: path.to_string(),
+            kind,
+            model: model.to_string(),
+            power_supply,
+            percent: 50.0,
+            state: None,
+        }
+    }
+
+    #[test]
+    fn test_device_name() {
+        assert_eq!(device("/a", 5, "MX Master 3", false).name(), "MX Master 3");
+        assert_eq!(device("/a", 17, "  ", false).name(), "Headset");
+        assert_eq!(device("/a", KIND_UPS, "", true).name(), "UPS");
+        assert_eq!(device("/a", 99, "", false).name(), "Device");
+    }
+
+    #[test]
+    fn test_sort_devices() {
+        let mut devices = vec![
+            device("/mouse", 5, "Mouse", false),
+            device("/ups", KIND_UPS, "Back-UPS", true),
+            device("/bat1", KIND_BATTERY, "BAT1", true),
+            device("/keyboard", 6, "Keyboard", false),
+            device("/bat0", KIND_BATTERY, "BAT0", true),
+        ];
+        sort_devices(&mut devices);
+        let paths: Vec<&str> = devices.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, vec!["/bat0", "/bat1", "/ups", "/keyboard", "/mouse"]);
+    }
+
+    #[test]
+    fn test_has_device_details() {
+        let mut snapshot = BatterySnapshot::unknown();
+        assert!(!snapshot.has_device_details());
+
+        snapshot.devices = vec![device("/bat0", KIND_BATTERY, "BAT0", true)];
+        assert!(!snapshot.has_device_details());
+
+        snapshot
+            .devices
+            .push(device("/bat1", KIND_BATTERY, "BAT1", true));
+        assert!(snapshot.has_device_details());
+
+        snapshot.devices = vec![device("/ups", KIND_UPS, "", true)];
+        assert!(snapshot.has_device_details());
+
+        snapshot.devices = vec![device("/mouse", 5, "", false)];
+        assert!(snapshot.has_device_details());
+    }
 }
@@ -6,15 +6,28 @@
 //!   - Debounced updates on adapter/device property changes
 //!   - Simple control API: power, scan, connect/disconnect, pair, forget
 //!   - BlueZ Agent for handling pairing authentication (PIN, passkey, confirmation)
+//!   - Optional reconnect of the last audio device at startup and power on
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use gtk4::gio::{self, BusType, DBusCallFlags, DBusProxy, DBusProxyFlags, prelude::*};
+use gtk4::gio::{self, BusType, DBusProxy, DBusProxyFlags, prelude::*};
 use gtk4::glib::{self, Variant};
 use tracing::{debug, error};
+use vibepanel_core::config::BluetoothConfig;
 
 use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+use super::mock::MockServices;
+use super::state;
+
+// Thread-local singleton storage for BluetoothService
+thread_local! {
+    static BLUETOOTH_INSTANCE: RefCell<Option<Rc<BluetoothService>>> = const { RefCell::new(None) };
+}
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "bluez";
 
 // BlueZ D-Bus constants
 const BLUEZ_SERVICE: &str = "org.bluez";
@@ -206,6 +219,16 @@ pub struct BluetoothDevice {
     pub icon: Option<String>,
 }
 
+impl BluetoothDevice {
+    /// Whether this is headphones, a headset, speakers or similar
+    /// (BlueZ names the icon after the device class).
+    pub fn is_audio(&self) -> bool {
+        self.icon
+            .as_deref()
+            .is_some_and(|icon| icon.starts_with("audio-"))
+    }
+}
+
 /// Canonical snapshot of Bluetooth state.
 #[derive(Debug, Clone)]
 pub struct BluetoothSnapshot {
@@ -229,7 +252,7 @@ pub struct BluetoothSnapshot {
 }
 
 impl BluetoothSnapshot {
-    fn empty() -> Self {
+    pub fn empty() -> Self {
         Self {
             has_adapter: false,
             powered: false,
@@ -267,6 +290,12 @@ pub struct BluetoothService {
     pending_auth: RefCell<Option<PendingAuth>>,
     /// Timeout source ID for auth request expiry.
     auth_timeout_source: RefCell<Option<glib::SourceId>>,
+    /// Reconnect timeout in seconds, when `bluetooth.auto_reconnect` is on.
+    auto_reconnect: Cell<Option<u32>>,
+    /// Address of the most recently connected audio device (persisted).
+    last_audio_device: RefCell<Option<String>>,
+    /// Whether the adapter was powered at the last update (None before the first).
+    was_powered: Cell<Option<bool>>,
 }
 
 impl BluetoothService {
@@ -283,19 +312,33 @@ impl BluetoothService {
             agent_registration_id: RefCell::new(None),
             pending_auth: RefCell::new(None),
             auth_timeout_source: RefCell::new(None),
+            auto_reconnect: Cell::new(None),
+            last_audio_device: RefCell::new(state::load().bluetooth.last_audio_device),
+            was_powered: Cell::new(None),
         });
 
+        if MockServices::global().is_enabled() {
+            debug!("BluetoothService: mocked, skipping BlueZ");
+            return service;
+        }
+
         Self::init_dbus(&service);
         service
     }
 
     /// Global singleton instance.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<BluetoothService> = BluetoothService::new();
-        }
+        BLUETOOTH_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(BluetoothService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        BLUETOOTH_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked whenever the Bluetooth snapshot changes.
@@ -315,6 +358,18 @@ impl BluetoothService {
         self.snapshot.borrow().clone()
     }
 
+    /// Replace the snapshot with a scripted one and notify listeners
+    /// (`--mock-services`).
+    pub fn publish_mock(&self, snapshot: BluetoothSnapshot) {
+        self.update_snapshot(|current| *current = snapshot);
+    }
+
+    /// Apply `[bluetooth]` settings.
+    pub fn configure(&self, config: &BluetoothConfig) {
+        self.auto_reconnect
+            .set(config.auto_reconnect.then_some(config.reconnect_timeout));
+    }
+
     /// Mutate the snapshot and notify callbacks.
     fn update_snapshot(&self, f: impl FnOnce(&mut BluetoothSnapshot)) {
         let mut snapshot = self.snapshot.borrow_mut();
@@ -531,12 +586,12 @@ impl BluetoothService {
                     "KeyboardDisplay".to_variant(),
                 ]);
 
-                proxy.call(
+                dbus_watchdog::call_proxy(
+                    WATCHDOG_SERVICE,
+                    &proxy,
                     "RegisterAgent",
                     Some(&args),
-                    DBusCallFlags::NONE,
-                    5000,
-                    None::<&gio::Cancellable>,
+                    CallPolicy::ACTION,
                     move |res| {
                         if let Err(e) = res {
                             // AlreadyExists is fine (agent already registered)
@@ -879,17 +934,17 @@ impl BluetoothService {
             let args = Variant::tuple_from_iter([agent_path.to_variant()]);
 
             // Fire-and-forget: we don't wait for the result since BlueZ may be unavailable
-            connection.call(
-                Some(BLUEZ_SERVICE),
+            dbus_watchdog::call(
+                WATCHDOG_SERVICE,
+                &connection,
+                BLUEZ_SERVICE,
                 "/org/bluez",
                 AGENT_MANAGER_IFACE,
                 "UnregisterAgent",
                 Some(&args),
                 None,
-                DBusCallFlags::NONE,
-                1000, // Short timeout - don't block if BlueZ is gone
-                None::<&gio::Cancellable>,
-                |_| {}, // Ignore result
+                CallPolicy::QUICK, // Short timeout - don't block if BlueZ is gone
+                |_| {},            // Ignore result
             );
         }
 
@@ -970,12 +1025,12 @@ impl BluetoothService {
         // Get managed objects to enumerate devices
         if let Some(om) = object_manager {
             let this_weak = Rc::downgrade(self);
-            om.call(
+            dbus_watchdog::call_proxy(
+                WATCHDOG_SERVICE,
+                &om,
                 "GetManagedObjects",
                 None,
-                DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
+                CallPolicy::QUERY,
                 move |res| {
                     let this = match this_weak.upgrade() {
                         Some(s) => s,
@@ -1030,7 +1085,7 @@ impl BluetoothService {
                             .unwrap_or(true);
                         let should_clear = match device {
                             Some(d) => d.paired || pairing_cleared, // Clear when paired or pairing ended
-                            None => true, // Clear if device disappeared
+                            None => true,                           // Clear if device disappeared
                         };
                         if should_clear {
                             debug!(
@@ -1043,6 +1098,7 @@ impl BluetoothService {
                     let snapshot_clone = snapshot.clone();
                     drop(snapshot);
                     this.callbacks.notify(&snapshot_clone);
+                    this.track_audio_device(&snapshot_clone);
                 },
             );
         } else {
@@ -1056,6 +1112,88 @@ impl BluetoothService {
         }
     }
 
+    /// Remember the connected audio device, and reconnect the last one when
+    /// the adapter comes up (at startup or power on) if configured.
+    fn track_audio_device(&self, snapshot: &BluetoothSnapshot) {
+        let connected_audio = snapshot
+            .devices
+            .iter()
+            .find(|d| d.connected && d.is_audio());
+        if let Some(device) = connected_audio
+            && self.last_audio_device.borrow().as_deref() != Some(device.address.as_str())
+        {
+            debug!(
+                "BluetoothService: remembering audio device {}",
+                device.address
+            );
+            *self.last_audio_device.borrow_mut() = Some(device.address.clone());
+            let mut persisted = state::load();
+            persisted.bluetooth.last_audio_device = Some(device.address.clone());
+            state::save(&persisted);
+        }
+
+        let powered = snapshot.has_adapter && snapshot.powered;
+        let was_powered = self.was_powered.replace(Some(powered));
+        if !powered || was_powered == Some(true) || connected_audio.is_some() {
+            return;
+        }
+        let Some(timeout_secs) = self.auto_reconnect.get() else {
+            return;
+        };
+        let Some(address) = self.last_audio_device.borrow().clone() else {
+            return;
+        };
+        let Some(device) = snapshot
+            .devices
+            .iter()
+            .find(|d| d.address == address && d.paired)
+        else {
+            return;
+        };
+        let Some(connection) = self.connection.borrow().clone() else {
+            return;
+        };
+
+        debug!(
+            "BluetoothService: reconnecting {} ({})",
+            device.name, address
+        );
+        let policy = CallPolicy {
+            timeout_ms: timeout_secs as i32 * 1000,
+            retries: 0,
+        };
+        DBusProxy::new(
+            &connection,
+            DBusProxyFlags::NONE,
+            None,
+            Some(BLUEZ_SERVICE),
+            &device.path,
+            DEVICE_IFACE,
+            None::<&gio::Cancellable>,
+            move |res| match res {
+                Ok(proxy) => {
+                    dbus_watchdog::call_proxy(
+                        WATCHDOG_SERVICE,
+                        &proxy,
+                        "Connect",
+                        None,
+                        policy,
+                        move |res| {
+                            // The device is often just off or out of range,
+                            // so this isn't worth more than a debug line
+                            if let Err(e) = res {
+                                debug!("BluetoothService: reconnecting {} failed: {}", address, e);
+                            }
+                        },
+                    );
+                }
+                Err(e) => {
+                    debug!("BluetoothService: failed to create device proxy: {}", e);
+                }
+            },
+        );
+    }
+
     fn ensure_adapter_from_managed_objects(self: &Rc<Self>, result: &Variant) {
         let adapter_paths = self.find_adapter_paths(result);
         let desired_path = adapter_paths.into_iter().next();
@@ -1310,12 +1448,12 @@ impl BluetoothService {
             glib::Variant::from_variant(&enabled.to_variant()),
         ]);
 
-        adapter.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &adapter,
             "org.freedesktop.DBus.Properties.Set",
             Some(&variant),
-            DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
             |res| {
                 if let Err(e) = res {
                     error!("BluetoothService: set_powered failed: {}", e);
@@ -1336,12 +1474,12 @@ impl BluetoothService {
         }
 
         // Start discovery - BlueZ will emit PropertiesChanged when Discovering changes
-        adapter.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &adapter,
             "StartDiscovery",
             None,
-            DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
             move |res| {
                 if let Err(e) = res {
                     error!("BluetoothService: StartDiscovery failed: {}", e);
@@ -1357,12 +1495,12 @@ impl BluetoothService {
             if let Some(this) = this_weak.upgrade()
                 && let Some(adapter) = this.adapter.borrow().clone()
             {
-                adapter.call(
+                dbus_watchdog::call_proxy(
+                    WATCHDOG_SERVICE,
+                    &adapter,
                     "StopDiscovery",
                     None,
-                    DBusCallFlags::NONE,
-                    5000,
-                    None::<&gio::Cancellable>,
+                    CallPolicy::ACTION,
                     |res| {
                         if let Err(e) = res {
                             // This can fail if discovery was already stopped - that's fine
@@ -1405,12 +1543,12 @@ impl BluetoothService {
             move |res| {
                 match res {
                     Ok(proxy) => {
-                        proxy.call(
+                        dbus_watchdog::call_proxy(
+                            WATCHDOG_SERVICE,
+                            &proxy,
                             "Connect",
                             None,
-                            DBusCallFlags::NONE,
-                            30000, // Bluetooth connections can take time
-                            None::<&gio::Cancellable>,
+                            CallPolicy::SLOW, // Bluetooth connections can take time
                             |res| {
                                 if let Err(e) = res {
                                     error!("BluetoothService: Connect failed: {}", e);
@@ -1441,12 +1579,12 @@ impl BluetoothService {
             None::<&gio::Cancellable>,
             move |res| match res {
                 Ok(proxy) => {
-                    proxy.call(
+                    dbus_watchdog::call_proxy(
+                        WATCHDOG_SERVICE,
+                        &proxy,
                         "Disconnect",
                         None,
-                        DBusCallFlags::NONE,
-                        5000,
-                        None::<&gio::Cancellable>,
+                        CallPolicy::ACTION,
                         |res| {
                             if let Err(e) = res {
                                 error!("BluetoothService: Disconnect failed: {}", e);
@@ -1490,12 +1628,12 @@ impl BluetoothService {
                         let pairing_path_for_callback = pairing_path.clone();
                         let this_weak_inner = Rc::downgrade(&this);
 
-                        proxy.call(
+                        dbus_watchdog::call_proxy(
+                            WATCHDOG_SERVICE,
+                            &proxy,
                             "Pair",
                             None,
-                            DBusCallFlags::NONE,
-                            30000, // Pairing can take time
-                            None::<&gio::Cancellable>,
+                            CallPolicy::SLOW, // Pairing can take time
                             move |res| {
                                 let Some(this) = this_weak_inner.upgrade() else {
                                     return;
@@ -1563,12 +1701,12 @@ impl BluetoothService {
                                     glib::Variant::from_variant(&true.to_variant()),
                                 ]);
 
-                                proxy_for_trust.call(
+                                dbus_watchdog::call_proxy(
+                                    WATCHDOG_SERVICE,
+                                    &proxy_for_trust,
                                     "org.freedesktop.DBus.Properties.Set",
                                     Some(&trusted_variant),
-                                    DBusCallFlags::NONE,
-                                    5000,
-                                    None::<&gio::Cancellable>,
+                                    CallPolicy::ACTION,
                                     |res| {
                                         if let Err(e) = res {
                                             error!(
@@ -1579,15 +1717,18 @@ impl BluetoothService {
                                     },
                                 );
 
-                                proxy_for_connect.call(
+                                dbus_watchdog::call_proxy(
+                                    WATCHDOG_SERVICE,
+                                    &proxy_for_connect,
                                     "Connect",
                                     None,
-                                    DBusCallFlags::NONE,
-                                    30000, // Bluetooth connections can take time
-                                    None::<&gio::Cancellable>,
+                                    CallPolicy::SLOW, // Bluetooth connections can take time
                                     |res| {
                                         if let Err(e) = res {
-                                            error!("BluetoothService: Connect after pair failed: {}", e);
+                                            error!(
+                                                "BluetoothService: Connect after pair failed: {}",
+                                                e
+                                            );
                                         }
                                     },
                                 );
@@ -1615,12 +1756,12 @@ impl BluetoothService {
         let obj_path = glib::variant::ObjectPath::try_from(path.as_str()).unwrap();
         let variant = Variant::tuple_from_iter([obj_path.to_variant()]);
 
-        adapter.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &adapter,
             "RemoveDevice",
             Some(&variant),
-            DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
             |res| {
                 if let Err(e) = res {
                     error!("BluetoothService: Forget failed: {}", e);
@@ -1672,12 +1813,12 @@ impl BluetoothService {
                 None::<&gio::Cancellable>,
                 |res| {
                     if let Ok(proxy) = res {
-                        proxy.call(
+                        dbus_watchdog::call_proxy(
+                            WATCHDOG_SERVICE,
+                            &proxy,
                             "CancelPairing",
                             None,
-                            DBusCallFlags::NONE,
-                            5000,
-                            None::<&gio::Cancellable>,
+                            CallPolicy::ACTION,
                             |_| {},
                         );
                     }
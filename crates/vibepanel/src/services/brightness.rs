@@ -5,12 +5,17 @@
 //! - Falls back to direct sysfs writes if logind is unavailable
 //! - Provides a GTK/GLib-friendly, callback-based API
 //!
-//! Uses libudev to monitor backlight device changes via the GLib main loop.
-//! This is fully event-driven - no polling required.
+//! Uses libudev to monitor backlight device changes via the GLib main loop,
+//! and also waits for the kernel's `sysfs_notify` on `actual_brightness`,
+//! which fires even without a udev daemon relaying events. Either way,
+//! changes made by other tools (brightnessctl, compositor keybindings,
+//! firmware hotkeys) are picked up. This is fully event-driven - no polling
+//! required. (inotify is not an option: sysfs attributes don't generate
+//! inotify events.)
 
 use std::cell::{Cell, RefCell};
 use std::fs;
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
 use std::os::unix::io::AsRawFd;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -23,6 +28,10 @@ use gtk4::glib;
 use tracing::{debug, error, warn};
 
 use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "logind";
 
 /// Logind D-Bus constants.
 const LOGIND_BUS_NAME: &str = "org.freedesktop.login1";
@@ -77,6 +86,8 @@ struct BacklightDevice {
     name: String,
     /// Path to the `brightness` file.
     brightness_path: PathBuf,
+    /// Path to the `actual_brightness` file, which the kernel notifies on change.
+    actual_brightness_path: PathBuf,
     /// Maximum raw brightness value read from sysfs.
     max_brightness_raw: u32,
 }
@@ -101,6 +112,10 @@ pub struct BrightnessService {
     udev_monitor: RefCell<Option<UdevMonitorState>>,
     /// GLib source ID for the udev fd watcher.
     udev_source_id: RefCell<Option<glib::SourceId>>,
+    /// Open `actual_brightness` file polled for kernel change notifications.
+    sysfs_watch: RefCell<Option<fs::File>>,
+    /// GLib source ID for the `actual_brightness` fd watcher.
+    sysfs_source_id: RefCell<Option<glib::SourceId>>,
     /// Whether we're currently in a throttle period (have fired recently).
     throttle_active: Cell<bool>,
     /// Whether another event arrived during the throttle period.
@@ -124,6 +139,8 @@ impl BrightnessService {
             ready: Cell::new(false),
             udev_monitor: RefCell::new(None),
             udev_source_id: RefCell::new(None),
+            sysfs_watch: RefCell::new(None),
+            sysfs_source_id: RefCell::new(None),
             throttle_active: Cell::new(false),
             pending_read: Cell::new(false),
         });
@@ -136,6 +153,7 @@ impl BrightnessService {
             service.read_brightness();
             service.ready.set(true);
             service.start_udev_monitoring();
+            service.start_sysfs_notify_watch();
             debug!("BrightnessService initialized (device found)");
         } else {
             warn!("BrightnessService: no backlight device found; service disabled");
@@ -212,8 +230,8 @@ impl BrightnessService {
         } else {
             self.set_via_sysfs(raw);
         }
-        // The file monitor (or fallback polling loop) will detect the change
-        // and emit callbacks if needed.
+        // The udev / sysfs watchers will detect the change and emit
+        // callbacks if needed.
     }
 
     /// Initialize logind D-Bus connection and discover session path.
@@ -260,16 +278,16 @@ impl BrightnessService {
                 fn pick_logind_session(connection: &gio::DBusConnection) -> Option<String> {
                     // First try GetSessionByPID for this process.
                     let pid = std::process::id();
-                    if let Ok(result) = connection.call_sync(
-                        Some(LOGIND_BUS_NAME),
+                    if let Ok(result) = dbus_watchdog::call_sync(
+                        WATCHDOG_SERVICE,
+                        connection,
+                        LOGIND_BUS_NAME,
                         "/org/freedesktop/login1",
                         "org.freedesktop.login1.Manager",
                         "GetSessionByPID",
                         Some(&(pid,).to_variant()),
                         Some(glib::VariantTy::new("(o)").unwrap()),
-                        gio::DBusCallFlags::NONE,
-                        5000,
-                        None::<&gio::Cancellable>,
+                        CallPolicy::QUERY,
                     ) && let Some(session_path) = result.child_value(0).get::<String>()
                     {
                         return Some(session_path);
@@ -277,19 +295,18 @@ impl BrightnessService {
 
                     // Fall back to ListSessions and pick a graphical one (seat0),
                     // mirroring BrightnessCli::get_active_graphical_session.
-                    let result = connection
-                        .call_sync(
-                            Some(LOGIND_BUS_NAME),
-                            "/org/freedesktop/login1",
-                            "org.freedesktop.login1.Manager",
-                            "ListSessions",
-                            None,
-                            Some(glib::VariantTy::new("(a(susso))").unwrap()),
-                            gio::DBusCallFlags::NONE,
-                            5000,
-                            None::<&gio::Cancellable>,
-                        )
-                        .ok()?;
+                    let result = dbus_watchdog::call_sync(
+                        WATCHDOG_SERVICE,
+                        connection,
+                        LOGIND_BUS_NAME,
+                        "/org/freedesktop/login1",
+                        "org.freedesktop.login1.Manager",
+                        "ListSessions",
+                        None,
+                        Some(glib::VariantTy::new("(a(susso))").unwrap()),
+                        CallPolicy::QUERY,
+                    )
+                    .ok()?;
 
                     // Result is (array of (session_id, uid, user_name, seat_id, object_path),)
                     let sessions = result.child_value(0);
@@ -424,6 +441,7 @@ impl BrightnessService {
             return Some(BacklightDevice {
                 name,
                 brightness_path,
+                actual_brightness_path: device.join("actual_brightness"),
                 max_brightness_raw,
             });
         }
@@ -516,6 +534,74 @@ impl BrightnessService {
         debug!("BrightnessService: udev monitoring started for backlight subsystem");
     }
 
+    /// Watch `actual_brightness` for the kernel's change notification.
+    ///
+    /// The backlight core calls `sysfs_notify` on this attribute whenever the
+    /// brightness is set, which wakes `poll()` with `POLLPRI | POLLERR`. The
+    /// file has to be re-read after each wake-up to re-arm the notification.
+    fn start_sysfs_notify_watch(self: &Rc<Self>) {
+        let Some(device) = &self.device else {
+            return;
+        };
+
+        let mut file = match fs::File::open(&device.actual_brightness_path) {
+            Ok(file) => file,
+            Err(e) => {
+                debug!(
+                    "BrightnessService: can't watch {}: {}",
+                    device.actual_brightness_path.display(),
+                    e
+                );
+                return;
+            }
+        };
+        // Arm the notification
+        let _ = Self::drain_sysfs_file(&mut file);
+
+        let fd = file.as_raw_fd();
+        *self.sysfs_watch.borrow_mut() = Some(file);
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::unix_fd_add_local(
+            fd,
+            glib::IOCondition::PRI | glib::IOCondition::ERR,
+            move |_fd, _cond| {
+                let Some(this) = this_weak.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+
+                let drained = match this.sysfs_watch.borrow_mut().as_mut() {
+                    Some(file) => Self::drain_sysfs_file(file),
+                    None => return glib::ControlFlow::Break,
+                };
+                if let Err(e) = drained {
+                    // Without re-reading, poll() would keep firing
+                    warn!(
+                        "BrightnessService: stopped watching actual_brightness: {}",
+                        e
+                    );
+                    this.sysfs_watch.borrow_mut().take();
+                    this.sysfs_source_id.borrow_mut().take();
+                    return glib::ControlFlow::Break;
+                }
+
+                this.schedule_debounced_read();
+                glib::ControlFlow::Continue
+            },
+        );
+
+        *self.sysfs_source_id.borrow_mut() = Some(source_id);
+        debug!("BrightnessService: watching actual_brightness for changes");
+    }
+
+    /// Re-read a sysfs attribute from the start, acknowledging a notification.
+    fn drain_sysfs_file(file: &mut fs::File) -> std::io::Result<()> {
+        file.seek(SeekFrom::Start(0))?;
+        let mut buf = String::new();
+        file.read_to_string(&mut buf)?;
+        Ok(())
+    }
+
     fn schedule_debounced_read(self: &Rc<Self>) {
         // Throttle pattern: fire immediately on leading edge, then wait.
         // If more events arrive during the wait, do one final read at the end.
@@ -628,16 +714,16 @@ impl BrightnessService {
         let params = ("backlight", device_name, raw_brightness).to_variant();
         let device_name_owned = device_name.to_string();
 
-        connection.call(
-            Some(LOGIND_BUS_NAME),
+        dbus_watchdog::call(
+            WATCHDOG_SERVICE,
+            &connection,
+            LOGIND_BUS_NAME,
             &session_path,
             LOGIND_SESSION_INTERFACE,
             "SetBrightness",
             Some(&params),
-            None::<&glib::VariantTy>,
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            None,
+            CallPolicy::ACTION,
             move |res| {
                 if let Err(e) = res {
                     error!(
@@ -682,12 +768,28 @@ impl Drop for BrightnessService {
         // Drop the udev monitor socket.
         self.udev_monitor.borrow_mut().take();
 
+        // Stop watching actual_brightness.
+        if let Some(source_id) = self.sysfs_source_id.borrow_mut().take() {
+            source_id.remove();
+        }
+        self.sysfs_watch.borrow_mut().take();
+
         // Clear D-Bus connection and session path.
         self.dbus_connection.borrow_mut().take();
         self.logind_session_path.borrow_mut().take();
     }
 }
 
+/// Find the logind session to call `SetBrightness` on: the session of this
+/// process, or else an active graphical session.
+///
+/// Also used for LEDs (see `NotificationLedService`), which logind handles
+/// the same way as backlights.
+pub(crate) fn logind_session_path(connection: &gio::DBusConnection) -> Option<String> {
+    BrightnessCli::get_session_for_pid(connection, std::process::id())
+        .or_else(|| BrightnessCli::get_active_graphical_session(connection))
+}
+
 // CLI interface - synchronous, standalone (no GTK main loop required)
 
 /// Synchronous brightness control for CLI usage.
@@ -749,47 +851,42 @@ impl BrightnessCli {
     /// Connect to system D-Bus and get a session path (lazy, on-demand).
     fn get_dbus_session() -> Option<(gio::DBusConnection, String)> {
         let connection = gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>).ok()?;
-
-        let session_path = Self::get_session_for_pid(&connection, std::process::id())
-            .or_else(|| Self::get_active_graphical_session(&connection))?;
-
+        let session_path = logind_session_path(&connection)?;
         Some((connection, session_path))
     }
 
     /// Get the session path for a specific PID.
     fn get_session_for_pid(connection: &gio::DBusConnection, pid: u32) -> Option<String> {
-        let result = connection
-            .call_sync(
-                Some(LOGIND_BUS_NAME),
-                "/org/freedesktop/login1",
-                "org.freedesktop.login1.Manager",
-                "GetSessionByPID",
-                Some(&(pid,).to_variant()),
-                Some(glib::VariantTy::new("(o)").unwrap()),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .ok()?;
+        let result = dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            LOGIND_BUS_NAME,
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+            "GetSessionByPID",
+            Some(&(pid,).to_variant()),
+            Some(glib::VariantTy::new("(o)").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .ok()?;
 
         result.child_value(0).get::<String>()
     }
 
     /// Find an active graphical session (seat0) to use for brightness control.
     fn get_active_graphical_session(connection: &gio::DBusConnection) -> Option<String> {
-        let result = connection
-            .call_sync(
-                Some(LOGIND_BUS_NAME),
-                "/org/freedesktop/login1",
-                "org.freedesktop.login1.Manager",
-                "ListSessions",
-                None,
-                Some(glib::VariantTy::new("(a(susso))").unwrap()),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .ok()?;
+        let result = dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            LOGIND_BUS_NAME,
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+            "ListSessions",
+            None,
+            Some(glib::VariantTy::new("(a(susso))").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .ok()?;
 
         // Result is (array of (session_id, uid, user_name, seat_id, object_path),)
         let sessions = result.child_value(0);
@@ -824,20 +921,19 @@ impl BrightnessCli {
         session_path: &str,
         raw: u32,
     ) -> Result<(), String> {
-        connection
-            .call_sync(
-                Some(LOGIND_BUS_NAME),
-                session_path,
-                LOGIND_SESSION_INTERFACE,
-                "SetBrightness",
-                Some(&("backlight", self.device_name.as_str(), raw).to_variant()),
-                None,
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .map(|_| ())
-            .map_err(|e| format!("logind SetBrightness failed: {}", e))
+        dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            LOGIND_BUS_NAME,
+            session_path,
+            LOGIND_SESSION_INTERFACE,
+            "SetBrightness",
+            Some(&("backlight", self.device_name.as_str(), raw).to_variant()),
+            None,
+            CallPolicy::ACTION,
+        )
+        .map(|_| ())
+        .map_err(|e| format!("logind SetBrightness failed: {}", e))
     }
 
     /// Set brightness via direct sysfs write (fallback).
@@ -0,0 +1,818 @@
+//! CalendarEventsService - events for the clock's calendar popover.
+//!
+//! This service provides:
+//! - Events from Evolution Data Server (the calendars set up in GNOME
+//!   Online Accounts, Evolution or GNOME Calendar) over the session bus
+//! - Events from local `.ics` files, or every `.ics` file in a directory
+//! - Periodic reloads in a worker thread, plus `refresh()` on demand
+//!
+//! Only what the popover needs is parsed: summary, location, start and end,
+//! and simple recurrence (`FREQ`, `INTERVAL`, `COUNT` and `UNTIL`; `BY*`
+//! rules and exceptions are ignored). Times with a `TZID` are treated as
+//! local time.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use chrono::{Days, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use gtk4::gio::{self, BusType};
+use gtk4::glib::{self, SourceId, Variant, VariantTy};
+use gtk4::prelude::*;
+use tracing::{debug, info, warn};
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::dbus_watchdog::{self, CallPolicy};
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for CalendarEventsService
+thread_local! {
+    static CALENDAR_EVENTS_INSTANCE: RefCell<Option<Rc<CalendarEventsService>>> = const { RefCell::new(None) };
+}
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "eds";
+
+/// Reload interval in seconds (15 minutes).
+const REFRESH_INTERVAL_SECS: u32 = 900;
+
+/// EDS source registry (lists configured calendars).
+const EDS_SOURCES_SERVICE: &str = "org.gnome.evolution.dataserver.Sources5";
+const EDS_SOURCES_PATH: &str = "/org/gnome/evolution/dataserver/SourceManager";
+const EDS_SOURCE_IFACE: &str = "org.gnome.evolution.dataserver.Source";
+
+/// EDS calendar factory (opens a calendar backend by source UID).
+const EDS_CALENDAR_SERVICE: &str = "org.gnome.evolution.dataserver.Calendar8";
+const EDS_CALENDAR_FACTORY_PATH: &str = "/org/gnome/evolution/dataserver/CalendarFactory";
+const EDS_CALENDAR_FACTORY_IFACE: &str = "org.gnome.evolution.dataserver.CalendarFactory";
+const EDS_CALENDAR_IFACE: &str = "org.gnome.evolution.dataserver.Calendar";
+
+/// D-Bus call policy. Opening a remote calendar may sync it first.
+const EDS_POLICY: CallPolicy = CallPolicy {
+    timeout_ms: 10_000,
+    retries: 0,
+};
+
+/// Days before and after today fetched from EDS.
+const EDS_WINDOW_DAYS: u64 = 366;
+
+/// Where events are read from (from the clock widget config).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarSources {
+    /// Read calendars from Evolution Data Server.
+    pub eds: bool,
+    /// `.ics` files, or directories of them.
+    pub ics: Vec<PathBuf>,
+}
+
+impl CalendarSources {
+    /// Whether no source is configured.
+    pub fn is_empty(&self) -> bool {
+        !self.eds && self.ics.is_empty()
+    }
+}
+
+/// Start or end of an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventTime {
+    /// All-day (`VALUE=DATE`).
+    Date(NaiveDate),
+    /// Local date and time.
+    DateTime(NaiveDateTime),
+}
+
+impl EventTime {
+    /// Day the time falls on.
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Self::Date(date) => *date,
+            Self::DateTime(datetime) => datetime.date(),
+        }
+    }
+
+    /// Shift by whole days, keeping the kind.
+    fn add_days(self, days: u64) -> Option<Self> {
+        match self {
+            Self::Date(date) => date.checked_add_days(Days::new(days)).map(Self::Date),
+            Self::DateTime(dt) => dt.checked_add_days(Days::new(days)).map(Self::DateTime),
+        }
+    }
+
+    /// Shift by whole months (the day is clamped to the month's length).
+    fn add_months(self, months: u32) -> Option<Self> {
+        match self {
+            Self::Date(date) => date.checked_add_months(Months::new(months)).map(Self::Date),
+            Self::DateTime(dt) => dt
+                .checked_add_months(Months::new(months))
+                .map(Self::DateTime),
+        }
+    }
+}
+
+/// Recurrence frequency (`RRULE` `FREQ`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Simple recurrence rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    /// Total number of occurrences.
+    count: Option<u32>,
+    /// Last day an occurrence may start on.
+    until: Option<NaiveDate>,
+}
+
+/// A calendar event, or one occurrence of a recurring event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub location: Option<String>,
+    pub start: EventTime,
+    /// Exclusive end, if given.
+    pub end: Option<EventTime>,
+    recurrence: Option<Recurrence>,
+}
+
+impl CalendarEvent {
+    /// Whether this is an all-day event.
+    pub fn all_day(&self) -> bool {
+        matches!(self.start, EventTime::Date(_))
+    }
+
+    /// First day after the event (at least the day after it starts).
+    fn end_date(&self) -> NaiveDate {
+        let start = self.start.date();
+        let end = match self.end {
+            Some(EventTime::Date(date)) => date,
+            // Ending at midnight doesn't touch the next day
+            Some(EventTime::DateTime(dt)) if dt.time() == NaiveTime::MIN => dt.date(),
+            Some(EventTime::DateTime(dt)) => dt.date() + Duration::days(1),
+            None => start,
+        };
+        end.max(start + Duration::days(1))
+    }
+
+    /// Whether the event is on `day`.
+    pub fn is_on(&self, day: NaiveDate) -> bool {
+        self.start.date() <= day && day < self.end_date()
+    }
+
+    /// Whether the event hasn't ended by `now`. All-day events last all day.
+    pub fn is_upcoming(&self, now: NaiveDateTime) -> bool {
+        match self.end.unwrap_or(self.start) {
+            EventTime::DateTime(end) if !self.all_day() => end > now,
+            _ => self.end_date() > now.date(),
+        }
+    }
+
+    /// This event, shifted to occurrence `index` of its recurrence.
+    fn occurrence(&self, index: u32) -> Option<Self> {
+        let rule = self.recurrence?;
+        let steps = index.checked_mul(rule.interval)?;
+        let shift = |time: EventTime| match rule.frequency {
+            Frequency::Daily => time.add_days(u64::from(steps)),
+            Frequency::Weekly => time.add_days(u64::from(steps) * 7),
+            Frequency::Monthly => time.add_months(steps),
+            Frequency::Yearly => time.add_months(steps.checked_mul(12)?),
+        };
+        Some(Self {
+            summary: self.summary.clone(),
+            location: self.location.clone(),
+            start: shift(self.start)?,
+            end: match self.end {
+                Some(end) => Some(shift(end)?),
+                None => None,
+            },
+            recurrence: None,
+        })
+    }
+}
+
+/// Occurrences of `events` on any day in `from..to`, sorted by start.
+///
+/// All-day events sort before timed events on the same day.
+pub fn events_between(
+    events: &[CalendarEvent],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<CalendarEvent> {
+    let overlaps = |event: &CalendarEvent| event.start.date() < to && event.end_date() > from;
+    let mut result = Vec::new();
+
+    for event in events {
+        let Some(rule) = event.recurrence else {
+            if overlaps(event) {
+                result.push(event.clone());
+            }
+            continue;
+        };
+
+        // Daily and weekly rules can jump straight to the window
+        let span = (event.end_date() - event.start.date()).num_days().max(0) as u64;
+        let step_days = match rule.frequency {
+            Frequency::Daily => Some(u64::from(rule.interval)),
+            Frequency::Weekly => Some(u64::from(rule.interval) * 7),
+            _ => None,
+        };
+        let mut index = match step_days {
+            Some(step) => {
+                let gap = (from - event.start.date()).num_days().max(0) as u64;
+                (gap.saturating_sub(span) / step.max(1)) as u32
+            }
+            None => 0,
+        };
+
+        while rule.count.is_none_or(|count| index < count) {
+            let Some(occurrence) = event.occurrence(index) else {
+                break;
+            };
+            let start = occurrence.start.date();
+            if start >= to || rule.until.is_some_and(|until| start > until) {
+                break;
+            }
+            if overlaps(&occurrence) {
+                result.push(occurrence);
+            }
+            index += 1;
+        }
+    }
+
+    result.sort_by(|a, b| {
+        (a.start.date(), !a.all_day(), a.start).cmp(&(b.start.date(), !b.all_day(), b.start))
+    });
+    result
+}
+
+/// Canonical snapshot of calendar events.
+#[derive(Debug, Clone, Default)]
+pub struct CalendarEventsSnapshot {
+    /// Events from all sources; recurring events are not expanded (see
+    /// [`events_between`]).
+    pub events: Vec<CalendarEvent>,
+}
+
+/// Shared, process-wide calendar events service.
+pub struct CalendarEventsService {
+    snapshot: RefCell<CalendarEventsSnapshot>,
+    callbacks: Callbacks<CalendarEventsSnapshot>,
+    sources: RefCell<CalendarSources>,
+    timer_source: RefCell<Option<SourceId>>,
+}
+
+impl CalendarEventsService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(CalendarEventsSnapshot::default()),
+            callbacks: Callbacks::new(),
+            sources: RefCell::new(CalendarSources::default()),
+            timer_source: RefCell::new(None),
+        })
+    }
+
+    /// Get the global CalendarEventsService singleton.
+    pub fn global() -> Rc<Self> {
+        CALENDAR_EVENTS_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(CalendarEventsService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        CALENDAR_EVENTS_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&CalendarEventsSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify_single(id, &snapshot);
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Apply sources and (re)start periodic reloads.
+    ///
+    /// Only one configuration is active; re-applying identical sources
+    /// (e.g. one clock per monitor) is a no-op.
+    pub fn configure(self: &Rc<Self>, sources: CalendarSources) {
+        if *self.sources.borrow() == sources {
+            return;
+        }
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+
+        let enabled = !sources.is_empty();
+        *self.sources.borrow_mut() = sources;
+        self.snapshot.borrow_mut().events.clear();
+        self.notify();
+
+        if !enabled {
+            return;
+        }
+        info!(
+            "CalendarEventsService: reloading events every {}s",
+            REFRESH_INTERVAL_SECS
+        );
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(REFRESH_INTERVAL_SECS, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Reload events from all sources in the background.
+    pub fn refresh(&self) {
+        let sources = self.sources.borrow().clone();
+        if sources.is_empty() {
+            return;
+        }
+
+        WorkerPool::global().spawn(
+            move || {
+                let events = load_events(&sources);
+                (sources, events)
+            },
+            |result| {
+                let Ok((sources, events)) = result else {
+                    return;
+                };
+                let service = CalendarEventsService::global();
+                // Drop results for sources that were reconfigured meanwhile
+                if *service.sources.borrow() != sources {
+                    return;
+                }
+                debug!("CalendarEventsService: loaded {} events", events.len());
+                service.snapshot.borrow_mut().events = events;
+                service.notify();
+            },
+        );
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+/// Load events from every configured source.
+///
+/// Runs in a worker thread. A failing source is logged and skipped.
+fn load_events(sources: &CalendarSources) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+
+    for path in &sources.ics {
+        match ics_files(path) {
+            Ok(files) => {
+                for file in files {
+                    match std::fs::read_to_string(&file) {
+                        Ok(text) => events.extend(parse_ics(&text)),
+                        Err(e) => warn!("Calendar: failed to read {}: {}", file.display(), e),
+                    }
+                }
+            }
+            Err(e) => warn!("Calendar: failed to read {}: {}", path.display(), e),
+        }
+    }
+
+    if sources.eds {
+        match load_eds_events() {
+            Ok(eds_events) => events.extend(eds_events),
+            Err(e) => warn!(
+                "Calendar: failed to load Evolution Data Server events: {}",
+                e
+            ),
+        }
+    }
+
+    events
+}
+
+/// The `.ics` files at `path`: the file itself, or those in a directory.
+fn ics_files(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|file| {
+            file.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Fetch events near today from every enabled EDS calendar.
+fn load_eds_events() -> Result<Vec<CalendarEvent>, String> {
+    let bus = gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>)
+        .map_err(|e| format!("session bus: {}", e))?;
+
+    let call = |service: &str, path: &str, iface: &str, method: &str, args: Option<&Variant>| {
+        dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            &bus,
+            service,
+            path,
+            iface,
+            method,
+            args,
+            None,
+            EDS_POLICY,
+        )
+        .map_err(|e| format!("{}.{}: {}", iface, method, e))
+    };
+
+    let objects = call(
+        EDS_SOURCES_SERVICE,
+        EDS_SOURCES_PATH,
+        "org.freedesktop.DBus.ObjectManager",
+        "GetManagedObjects",
+        None,
+    )?;
+    let uids = eds_calendar_uids(&objects.child_value(0));
+    debug!("Calendar: {} EDS calendars", uids.len());
+
+    let today = Local::now().date_naive();
+    let from = today - Days::new(EDS_WINDOW_DAYS);
+    let to = today + Days::new(EDS_WINDOW_DAYS);
+    let sexp = format!(
+        "(occur-in-time-range? (make-time \"{}\") (make-time \"{}\"))",
+        from.format("%Y%m%dT000000Z"),
+        to.format("%Y%m%dT000000Z")
+    );
+
+    let mut events = Vec::new();
+    for uid in uids {
+        let opened = call(
+            EDS_CALENDAR_SERVICE,
+            EDS_CALENDAR_FACTORY_PATH,
+            EDS_CALENDAR_FACTORY_IFACE,
+            "OpenCalendar",
+            Some(&(uid.as_str(),).to_variant()),
+        );
+        let (object_path, bus_name) = match opened.map(|reply| reply.get::<(String, String)>()) {
+            Ok(Some(reply)) => reply,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Calendar: cannot open EDS calendar {}: {}", uid, e);
+                continue;
+            }
+        };
+
+        let objects =
+            call(&bus_name, &object_path, EDS_CALENDAR_IFACE, "Open", None).and_then(|_| {
+                call(
+                    &bus_name,
+                    &object_path,
+                    EDS_CALENDAR_IFACE,
+                    "GetObjectList",
+                    Some(&(sexp.as_str(),).to_variant()),
+                )
+            });
+        match objects.map(|reply| reply.get::<(Vec<String>,)>()) {
+            Ok(Some((objects,))) => {
+                for object in objects {
+                    events.extend(parse_ics(&object));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Calendar: cannot read EDS calendar {}: {}", uid, e),
+        }
+    }
+
+    Ok(events)
+}
+
+/// UIDs of enabled calendar sources in a `GetManagedObjects` reply.
+fn eds_calendar_uids(objects: &Variant) -> Vec<String> {
+    let mut uids = Vec::new();
+    for object in objects.iter() {
+        let interfaces = object.child_value(1);
+        for interface in interfaces.iter() {
+            if interface.child_value(0).str() != Some(EDS_SOURCE_IFACE) {
+                continue;
+            }
+            let props = interface.child_value(1);
+            if !props.is_type(VariantTy::VARDICT) {
+                continue;
+            }
+            let props = glib::VariantDict::new(Some(&props));
+            let (Ok(Some(uid)), Ok(Some(data))) = (
+                props.lookup::<String>("UID"),
+                props.lookup::<String>("Data"),
+            ) else {
+                continue;
+            };
+
+            let key_file = glib::KeyFile::new();
+            if key_file
+                .load_from_data(&data, glib::KeyFileFlags::NONE)
+                .is_err()
+                || !key_file.has_group("Calendar")
+            {
+                continue;
+            }
+            if key_file.boolean("Data Source", "Enabled").unwrap_or(true) {
+                uids.push(uid);
+            }
+        }
+    }
+    uids
+}
+
+/// Parse the `VEVENT`s in iCalendar text. Cancelled and malformed events
+/// are skipped.
+pub fn parse_ics(text: &str) -> Vec<CalendarEvent> {
+    // Unfold continuation lines (starting with a space or tab)
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    // Nesting below the current VEVENT (e.g. VALARM)
+    let mut event: Option<IcsEvent> = None;
+    let mut depth = 0;
+
+    for line in &lines {
+        let Some((name_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = name_params.split(';');
+        let name = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let params: Vec<&str> = parts.collect();
+
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(IcsEvent::default());
+                depth = 0;
+            }
+            ("BEGIN", Some(_)) => depth += 1,
+            ("END", Some(_)) if depth > 0 => depth -= 1,
+            ("END", Some(_)) => {
+                if let Some(parsed) = event.take().and_then(IcsEvent::build) {
+                    events.push(parsed);
+                }
+            }
+            (_, Some(current)) if depth == 0 => current.set(&name, &params, value),
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Properties collected while parsing one `VEVENT`.
+#[derive(Default)]
+struct IcsEvent {
+    summary: Option<String>,
+    location: Option<String>,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    recurrence: Option<Recurrence>,
+    cancelled: bool,
+}
+
+impl IcsEvent {
+    fn set(&mut self, name: &str, params: &[&str], value: &str) {
+        match name {
+            "SUMMARY" => self.summary = Some(unescape_text(value)),
+            "LOCATION" => self.location = Some(unescape_text(value)).filter(|l| !l.is_empty()),
+            "DTSTART" => self.start = parse_time(params, value),
+            "DTEND" => self.end = parse_time(params, value),
+            "RRULE" => self.recurrence = parse_rrule(value),
+            "STATUS" => self.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+
+    fn build(self) -> Option<CalendarEvent> {
+        if self.cancelled {
+            return None;
+        }
+        Some(CalendarEvent {
+            summary: self
+                .summary
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "(No title)".to_string()),
+            location: self.location,
+            start: self.start?,
+            end: self.end,
+            recurrence: self.recurrence,
+        })
+    }
+}
+
+/// Parse a `DTSTART` / `DTEND` value. UTC times are converted to local time;
+/// floating and `TZID` times are taken as local.
+fn parse_time(params: &[&str], value: &str) -> Option<EventTime> {
+    let value = value.trim();
+    let is_date = params.iter().any(|p| p.eq_ignore_ascii_case("VALUE=DATE")) || value.len() == 8;
+    if is_date {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(EventTime::Date);
+    }
+
+    let (value, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let datetime = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Some(EventTime::DateTime(if utc {
+        Utc.from_utc_datetime(&datetime)
+            .with_timezone(&Local)
+            .naive_local()
+    } else {
+        datetime
+    }))
+}
+
+/// Parse the supported parts of an `RRULE` value.
+fn parse_rrule(value: &str) -> Option<Recurrence> {
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut count = None;
+    let mut until = None;
+
+    for part in value.split(';') {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Some(Frequency::Daily),
+                    "WEEKLY" => Some(Frequency::Weekly),
+                    "MONTHLY" => Some(Frequency::Monthly),
+                    "YEARLY" => Some(Frequency::Yearly),
+                    _ => None,
+                }
+            }
+            "INTERVAL" => interval = value.parse().unwrap_or(1).max(1),
+            "COUNT" => count = value.parse().ok(),
+            "UNTIL" => until = parse_time(&[], value).map(|time| time.date()),
+            _ => {}
+        }
+    }
+
+    Some(Recurrence {
+        frequency: frequency?,
+        interval,
+        count,
+        until,
+    })
+}
+
+/// Undo iCalendar text escaping; newlines become spaces.
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push(' '),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        date(year, month, day).and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:Team sync\\, weekly\r
+LOCATION:Room 4\r
+DTSTART;TZID=Europe/Stockholm:20261012T093000\r
+DTEND;TZID=Europe/Stockholm:20261012T100000\r
+RRULE:FREQ=WEEKLY;COUNT=3\r
+BEGIN:VALARM\r
+SUMMARY:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Conference with a very long\r
+  title\r
+DTSTART;VALUE=DATE:20261016\r
+DTEND;VALUE=DATE:20261018\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Cancelled\r
+STATUS:CANCELLED\r
+DTSTART:20261016T120000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_ics() {
+        let events = parse_ics(ICS);
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(events[0].summary, "Team sync, weekly");
+        assert_eq!(events[0].location.as_deref(), Some("Room 4"));
+        assert_eq!(
+            events[0].start,
+            EventTime::DateTime(at(2026, 10, 12, 9, 30))
+        );
+        assert!(events[0].recurrence.is_some());
+
+        assert_eq!(events[1].summary, "Conference with a very long title");
+        assert!(events[1].all_day());
+        assert!(events[1].is_on(date(2026, 10, 17)));
+        assert!(!events[1].is_on(date(2026, 10, 18)));
+    }
+
+    #[test]
+    fn test_events_between() {
+        let events = parse_ics(ICS);
+
+        let day = |d| events_between(&events, d, d + Days::new(1));
+        let summaries = |d| -> Vec<String> { day(d).into_iter().map(|e| e.summary).collect() };
+        assert_eq!(
+            summaries(date(2026, 10, 19)),
+            vec!["Team sync, weekly".to_string()]
+        );
+        // All-day events come first
+        assert_eq!(
+            summaries(date(2026, 10, 16)),
+            vec!["Conference with a very long title"]
+        );
+        assert_eq!(
+            day(date(2026, 10, 26))[0].start,
+            EventTime::DateTime(at(2026, 10, 26, 9, 30))
+        );
+        // COUNT=3 ends the series
+        assert!(day(date(2026, 11, 2)).is_empty());
+
+        let month = events_between(&events, date(2026, 10, 1), date(2026, 11, 1));
+        assert_eq!(month.len(), 4);
+    }
+
+    #[test]
+    fn test_is_upcoming() {
+        let events = parse_ics(ICS);
+        let sync = events_between(&events, date(2026, 10, 12), date(2026, 10, 13));
+        assert!(sync[0].is_upcoming(at(2026, 10, 12, 9, 45)));
+        assert!(!sync[0].is_upcoming(at(2026, 10, 12, 10, 0)));
+        assert!(events[1].is_upcoming(at(2026, 10, 17, 23, 0)));
+    }
+
+    #[test]
+    fn test_parse_rrule() {
+        let rule = parse_rrule("FREQ=DAILY;INTERVAL=2;UNTIL=20261020T000000Z").unwrap();
+        assert_eq!(rule.frequency, Frequency::Daily);
+        assert_eq!(rule.interval, 2);
+        assert!(rule.until.is_some());
+        assert!(parse_rrule("FREQ=SECONDLY").is_none());
+    }
+}
@@ -1,7 +1,16 @@
-//! Generic callback registry for service snapshot updates.
+//! Per-service handle onto the typed event bus.
 //!
-//! This module provides `Callbacks<T>`, a reusable helper for the common
-//! snapshot+callback pattern used across most services in the bar.
+//! `Callbacks<T>` is the publishing side used by services for the common
+//! snapshot+callback pattern. Each `Callbacks` has its own
+//! [`InstanceTopic`] on the global [`EventBus`](super::event_bus::EventBus):
+//! its callbacks only receive what it notifies, even when another registry
+//! carries the same `T`. Every snapshot is also published on the shared
+//! topic for `T`, so it is visible to bus subscribers (hooks, conditions,
+//! state dumps) without per-service wiring.
+//!
+//! Registrations made through a `Callbacks<T>` are removed when it is dropped,
+//! or earlier by the [`SubscriptionScope`](super::event_bus::SubscriptionScope)
+//! that was entered when they were made.
 //!
 //! ## Usage
 //!
@@ -33,66 +42,54 @@
 //! ```
 
 use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use super::event_bus::{EventBus, InstanceTopic, SubscriptionId};
 
 /// Unique identifier for a registered callback.
 ///
 /// Used to unregister callbacks when they are no longer needed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct CallbackId(u64);
-
-/// Global counter for generating unique callback IDs.
-static NEXT_CALLBACK_ID: AtomicU64 = AtomicU64::new(1);
-
-impl CallbackId {
-    /// Generate a new unique callback ID.
-    fn new() -> Self {
-        Self(NEXT_CALLBACK_ID.fetch_add(1, Ordering::Relaxed))
-    }
-}
+pub type CallbackId = SubscriptionId;
 
-/// Entry in the callback registry, pairing an ID with a callback.
-struct CallbackEntry<T> {
-    id: CallbackId,
-    callback: Rc<dyn Fn(&T)>,
-}
-
-/// Type alias for the callback storage to reduce complexity.
-type CallbackList<T> = Vec<CallbackEntry<T>>;
-
-/// A registry of callbacks that receive snapshot updates.
+/// A service's registrations on its own event bus topic for `T`.
 ///
 /// This is the standard pattern used by services to notify widgets of state changes.
-/// Callbacks are stored as `Rc<dyn Fn(&T)>` to allow cloning for async notification.
-///
 /// Each callback is assigned a unique `CallbackId` which can be used to unregister
 /// it when no longer needed (e.g., when a widget is destroyed).
 pub struct Callbacks<T> {
-    inner: RefCell<CallbackList<T>>,
+    /// Topic this registry notifies on.
+    topic: InstanceTopic,
+    /// Subscriptions made through this handle.
+    ids: RefCell<Vec<CallbackId>>,
+    _marker: PhantomData<fn(&T)>,
 }
 
-impl<T> Callbacks<T> {
+impl<T: Clone + Debug + 'static> Callbacks<T> {
     /// Create a new empty callback registry.
     pub fn new() -> Self {
         Self {
-            inner: RefCell::new(Vec::new()),
+            topic: InstanceTopic::new(),
+            ids: RefCell::new(Vec::new()),
+            _marker: PhantomData,
         }
     }
 
     /// Register a callback to be invoked on snapshot updates.
     ///
-    /// The callback is wrapped in `Rc` for efficient cloning during notification.
+    /// Unlike `EventBus::subscribe`, the last value is not replayed; services
+    /// notify with their current snapshot themselves.
     /// Returns a `CallbackId` that can be used to unregister the callback.
     pub fn register<F>(&self, callback: F) -> CallbackId
     where
         F: Fn(&T) + 'static,
     {
-        let id = CallbackId::new();
-        self.inner.borrow_mut().push(CallbackEntry {
-            id,
-            callback: Rc::new(callback),
-        });
+        let bus = EventBus::global();
+        let id = bus.subscribe_instance(self.topic, callback);
+        let mut ids = self.ids.borrow_mut();
+        // Forget registrations removed elsewhere (e.g. by a dropped scope)
+        ids.retain(|own| bus.is_subscribed(*own));
+        ids.push(id);
         id
     }
 
@@ -100,26 +97,23 @@ impl<T> Callbacks<T> {
     ///
     /// Returns `true` if the callback was found and removed, `false` otherwise.
     pub fn unregister(&self, id: CallbackId) -> bool {
-        let mut inner = self.inner.borrow_mut();
-        let len_before = inner.len();
-        inner.retain(|entry| entry.id != id);
-        inner.len() < len_before
+        let mut ids = self.ids.borrow_mut();
+        let len_before = ids.len();
+        ids.retain(|own| *own != id);
+        if ids.len() == len_before {
+            return false;
+        }
+        drop(ids);
+        EventBus::global().unsubscribe(id)
     }
 
-    /// Notify all registered callbacks with the given snapshot.
+    /// Publish a snapshot to this registry's callbacks, then to all bus
+    /// subscribers of `T`.
     ///
-    /// Callbacks are cloned before iteration to avoid holding the borrow
-    /// during invocation, which prevents panics if callbacks re-enter the service.
+    /// Callbacks are invoked outside of any internal borrow, which prevents
+    /// panics if callbacks re-enter the service.
     pub fn notify(&self, snapshot: &T) {
-        let callbacks: Vec<_> = self
-            .inner
-            .borrow()
-            .iter()
-            .map(|entry| entry.callback.clone())
-            .collect();
-        for cb in callbacks {
-            cb(snapshot);
-        }
+        EventBus::global().publish_instance(self.topic, snapshot);
     }
 
     /// Notify a single callback by its ID with the given snapshot.
@@ -129,44 +123,44 @@ impl<T> Callbacks<T> {
     ///
     /// Returns `true` if the callback was found and invoked, `false` otherwise.
     pub fn notify_single(&self, id: CallbackId, snapshot: &T) -> bool {
-        let callback = self
-            .inner
-            .borrow()
-            .iter()
-            .find(|entry| entry.id == id)
-            .map(|entry| entry.callback.clone());
-
-        if let Some(cb) = callback {
-            cb(snapshot);
-            true
-        } else {
-            false
-        }
+        EventBus::global().deliver_to_instance(self.topic, id, snapshot)
     }
 
     /// Returns true if no callbacks are registered.
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
-        self.inner.borrow().is_empty()
+        self.ids.borrow().is_empty()
     }
 
     /// Returns the number of registered callbacks.
     #[allow(dead_code)]
     pub fn len(&self) -> usize {
-        self.inner.borrow().len()
+        self.ids.borrow().len()
     }
 }
 
-impl<T> Default for Callbacks<T> {
+impl<T: Clone + Debug + 'static> Default for Callbacks<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<T> Drop for Callbacks<T> {
+    fn drop(&mut self) {
+        // The bus may already be gone during thread-local teardown
+        if let Some(bus) = EventBus::try_global() {
+            for id in self.ids.get_mut().drain(..) {
+                bus.unsubscribe(id);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::cell::Cell;
+    use std::rc::Rc;
 
     #[test]
     fn test_callbacks_register_and_notify() {
@@ -269,6 +263,26 @@ mod tests {
         assert_ne!(id1, id3);
     }
 
+    #[test]
+    fn test_registries_with_same_type_are_separate() {
+        let first: Callbacks<i32> = Callbacks::new();
+        let second: Callbacks<i32> = Callbacks::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let seen_clone = seen.clone();
+        let id = first.register(move |value| seen_clone.borrow_mut().push(("first", *value)));
+        let seen_clone = seen.clone();
+        second.register(move |value| seen_clone.borrow_mut().push(("second", *value)));
+
+        first.notify(&1);
+        second.notify(&2);
+        assert!(!second.notify_single(id, &3));
+
+        assert_eq!(*seen.borrow(), vec![("first", 1), ("second", 2)]);
+        // Bus subscribers of the type still see every snapshot
+        assert_eq!(EventBus::global().last::<i32>(), Some(2));
+    }
+
     #[test]
     fn test_notify_single() {
         let callbacks: Callbacks<i32> = Callbacks::new();
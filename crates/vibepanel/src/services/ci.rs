@@ -0,0 +1,702 @@
+//! CiService - latest CI run per repository from GitHub Actions and GitLab.
+//!
+//! This service provides:
+//! - The state of the most recent workflow run / pipeline per repository
+//! - A link to the run for opening in the browser
+//! - Polling in a background thread with exponential backoff on errors and
+//!   rate limiting (honouring `Retry-After` and `X-RateLimit-Reset`)
+//!
+//! Repositories are given as `github:owner/repo` or `gitlab:group/project`,
+//! optionally followed by `@branch`. Tokens are read from a TOML secrets file
+//! so they don't end up in the main config:
+//!
+//! ```toml
+//! github_token = "ghp_..."
+//! gitlab_token = "glpat-..."
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use gtk4::glib::{self, SourceId};
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use super::callbacks::Callbacks;
+use super::url::encode_component;
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings, merge_unique};
+
+// Thread-local singleton storage for CiService
+thread_local! {
+    static CI_INSTANCE: RefCell<Option<Rc<CiService>>> = const { RefCell::new(None) };
+}
+
+/// Default poll interval in seconds.
+pub const DEFAULT_POLL_INTERVAL: u64 = 300;
+
+/// Minimum poll interval in seconds (keeps unauthenticated use under limits).
+const MIN_POLL_INTERVAL: u64 = 60;
+
+/// Upper bound for the backoff delay in seconds.
+const MAX_BACKOFF_SECS: u64 = 3600;
+
+/// Default GitLab instance.
+pub const DEFAULT_GITLAB_URL: &str = "https://gitlab.com";
+
+/// Request timeout in seconds.
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// CI provider hosting a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiProvider {
+    /// GitHub Actions.
+    GitHub,
+    /// GitLab CI/CD.
+    GitLab,
+}
+
+/// A repository to watch, parsed from `provider:path[@branch]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiRepo {
+    /// Hosting provider.
+    pub provider: CiProvider,
+    /// `owner/repo` (GitHub) or `group/project` (GitLab).
+    pub path: String,
+    /// Branch to filter runs by, if any.
+    pub branch: Option<String>,
+}
+
+impl CiRepo {
+    /// Parse a repository spec like `github:owner/repo@main`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (provider, rest) = spec.trim().split_once(':')?;
+        let provider = match provider.to_lowercase().as_str() {
+            "github" | "gh" => CiProvider::GitHub,
+            "gitlab" | "gl" => CiProvider::GitLab,
+            _ => return None,
+        };
+        let (path, branch) = match rest.split_once('@') {
+            Some((path, branch)) if !branch.is_empty() => (path, Some(branch.to_string())),
+            _ => (rest.trim_end_matches('@'), None),
+        };
+        if !path.contains('/') || path.starts_with('/') || path.ends_with('/') {
+            return None;
+        }
+        Some(Self {
+            provider,
+            path: path.to_string(),
+            branch,
+        })
+    }
+
+    /// Display name, e.g. "owner/repo" or "owner/repo@main".
+    pub fn display_name(&self) -> String {
+        match self.branch {
+            Some(ref branch) => format!("{}@{}", self.path, branch),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Outcome of the latest run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiState {
+    /// Completed successfully.
+    Success,
+    /// Failed.
+    Failure,
+    /// Queued or in progress.
+    Running,
+    /// Cancelled or skipped.
+    Cancelled,
+    /// No runs found.
+    None,
+}
+
+impl CiState {
+    /// Map a GitHub run `status`/`conclusion` pair.
+    fn from_github(status: &str, conclusion: Option<&str>) -> Self {
+        if status != "completed" {
+            return Self::Running;
+        }
+        match conclusion {
+            Some("success") | Some("neutral") => Self::Success,
+            Some("cancelled") | Some("skipped") | Some("stale") => Self::Cancelled,
+            _ => Self::Failure,
+        }
+    }
+
+    /// Map a GitLab pipeline `status`.
+    fn from_gitlab(status: &str) -> Self {
+        match status {
+            "success" => Self::Success,
+            "failed" => Self::Failure,
+            "canceled" | "skipped" => Self::Cancelled,
+            _ => Self::Running,
+        }
+    }
+
+    /// Human-readable label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Success => "Passing",
+            Self::Failure => "Failing",
+            Self::Running => "Running",
+            Self::Cancelled => "Cancelled",
+            Self::None => "No runs",
+        }
+    }
+}
+
+/// Latest CI status for one repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiRepoStatus {
+    /// The watched repository.
+    pub repo: CiRepo,
+    /// State of the latest run (`None` until the first successful fetch).
+    pub state: Option<CiState>,
+    /// Workflow or pipeline name, if reported.
+    pub name: Option<String>,
+    /// Link to the run in the browser.
+    pub url: Option<String>,
+    /// Error from the last fetch, if any.
+    pub error: Option<String>,
+}
+
+/// Settings for the CI poller (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiSettings {
+    /// Repositories to watch.
+    pub repos: Vec<CiRepo>,
+    /// TOML file holding `github_token` / `gitlab_token`.
+    pub secrets_file: Option<PathBuf>,
+    /// GitLab instance URL, without trailing slash.
+    pub gitlab_url: String,
+    /// Base poll interval (seconds).
+    pub poll_interval: u64,
+}
+
+impl MergeSettings for CiSettings {
+    /// Watch every widget's repositories at the shortest interval; the
+    /// secrets file and GitLab URL of the newest widget win.
+    fn merge(&mut self, other: &Self) {
+        merge_unique(&mut self.repos, &other.repos);
+        self.secrets_file = other.secrets_file.clone();
+        self.gitlab_url = other.gitlab_url.clone();
+        self.poll_interval = self.poll_interval.min(other.poll_interval);
+    }
+}
+
+/// Canonical snapshot of CI state.
+#[derive(Debug, Clone, Default)]
+pub struct CiSnapshot {
+    /// Whether any repositories are configured.
+    pub available: bool,
+    /// Per-repository status, in config order.
+    pub repos: Vec<CiRepoStatus>,
+    /// When the next poll is scheduled, if backing off.
+    pub backoff_until: Option<SystemTime>,
+}
+
+impl CiSnapshot {
+    /// Number of repositories whose latest run failed.
+    pub fn failing(&self) -> usize {
+        self.repos
+            .iter()
+            .filter(|r| r.state == Some(CiState::Failure))
+            .count()
+    }
+
+    /// Number of repositories with a run in progress.
+    pub fn running(&self) -> usize {
+        self.repos
+            .iter()
+            .filter(|r| r.state == Some(CiState::Running))
+            .count()
+    }
+}
+
+/// Tokens loaded from the secrets file.
+#[derive(Debug, Default, Deserialize)]
+struct CiSecrets {
+    github_token: Option<String>,
+    gitlab_token: Option<String>,
+}
+
+/// Why a fetch failed.
+#[derive(Debug)]
+enum FetchError {
+    /// Rate limited; retry after the given delay if known.
+    RateLimited(Option<Duration>),
+    /// Any other failure.
+    Other(String),
+}
+
+/// Shared, process-wide CI service.
+pub struct CiService {
+    snapshot: RefCell<CiSnapshot>,
+    callbacks: Callbacks<CiSnapshot>,
+    /// Settings of each CI widget.
+    registered: WidgetSettings<CiSettings>,
+    /// Merged settings the polls run with.
+    settings: RefCell<Option<CiSettings>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Consecutive failed polls, drives the backoff.
+    failures: Cell<u32>,
+    /// Prevent overlapping polls.
+    poll_in_progress: Cell<bool>,
+}
+
+impl CiService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(CiSnapshot::default()),
+            callbacks: Callbacks::new(),
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            timer_source: RefCell::new(None),
+            failures: Cell::new(0),
+            poll_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global CiService singleton.
+    pub fn global() -> Rc<Self> {
+        CI_INSTANCE.with(|cell| cell.borrow_mut().get_or_insert_with(CiService::new).clone())
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        CI_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&CiSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> CiSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: CiSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        self.notify();
+    }
+
+    /// Register a CI widget's repositories and poll them.
+    pub fn configure(self: &Rc<Self>, settings: CiSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's repositories; polling stops with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Restart polling with the merged settings of all widgets.
+    fn apply_settings(&self) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        let Some(settings) = settings else {
+            debug!("CiService: no widgets left, stopping polls");
+            if let Some(source_id) = self.timer_source.borrow_mut().take() {
+                source_id.remove();
+            }
+            *self.settings.borrow_mut() = None;
+            *self.snapshot.borrow_mut() = CiSnapshot::default();
+            self.notify();
+            return;
+        };
+
+        info!(
+            "CiService: watching {} repositories every {}s",
+            settings.repos.len(),
+            settings.poll_interval
+        );
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let previous = std::mem::take(&mut snapshot.repos);
+            snapshot.available = !settings.repos.is_empty();
+            // Keep the known state of repositories that are still watched
+            snapshot.repos = settings
+                .repos
+                .iter()
+                .map(|repo| {
+                    previous
+                        .iter()
+                        .find(|status| status.repo == *repo)
+                        .cloned()
+                        .unwrap_or_else(|| CiRepoStatus {
+                            repo: repo.clone(),
+                            state: None,
+                            name: None,
+                            url: None,
+                            error: None,
+                        })
+                })
+                .collect();
+            snapshot.backoff_until = None;
+        }
+        *self.settings.borrow_mut() = Some(settings);
+        self.failures.set(0);
+        self.notify();
+
+        self.poll();
+    }
+
+    /// Poll all repositories now, cancelling any pending scheduled poll.
+    pub fn poll(&self) {
+        if self.poll_in_progress.get() {
+            return;
+        }
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+
+        self.poll_in_progress.set(true);
+        std::thread::spawn(move || {
+            let secrets = load_secrets(settings.secrets_file.as_ref());
+            let results: Vec<(CiRepo, Result<CiRepoStatus, FetchError>)> = settings
+                .repos
+                .iter()
+                .map(|repo| (repo.clone(), fetch_repo_status(repo, &settings, &secrets)))
+                .collect();
+            glib::idle_add_once(move || {
+                CiService::global().apply_poll_results(results);
+            });
+        });
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply poll results and schedule the next poll.
+    fn apply_poll_results(&self, results: Vec<(CiRepo, Result<CiRepoStatus, FetchError>)>) {
+        self.poll_in_progress.set(false);
+
+        let mut rate_limit_delay: Option<Duration> = None;
+        let mut any_error = false;
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            for (repo, result) in results {
+                // Results for repositories removed meanwhile are dropped
+                let Some(status) = snapshot.repos.iter_mut().find(|s| s.repo == repo) else {
+                    continue;
+                };
+                match result {
+                    Ok(new_status) => *status = new_status,
+                    Err(FetchError::RateLimited(delay)) => {
+                        any_error = true;
+                        let delay = delay.unwrap_or_default();
+                        rate_limit_delay = Some(rate_limit_delay.map_or(delay, |d| d.max(delay)));
+                        status.error = Some("Rate limited".to_string());
+                    }
+                    Err(FetchError::Other(err)) => {
+                        any_error = true;
+                        if status.error.as_deref() != Some(err.as_str()) {
+                            warn!("CiService: {}: {}", status.repo.display_name(), err);
+                        }
+                        // Keep the last known state on transient errors
+                        status.error = Some(err);
+                    }
+                }
+            }
+        }
+
+        let base = self
+            .settings
+            .borrow()
+            .as_ref()
+            .map(|s| s.poll_interval)
+            .unwrap_or(DEFAULT_POLL_INTERVAL)
+            .max(MIN_POLL_INTERVAL);
+
+        let failures = if any_error {
+            self.failures.get().saturating_add(1)
+        } else {
+            0
+        };
+        self.failures.set(failures);
+
+        let mut delay = Duration::from_secs(backoff_secs(base, failures));
+        if let Some(reset) = rate_limit_delay {
+            delay = delay.max(reset);
+        }
+        if failures > 0 {
+            debug!("CiService: backing off for {}s", delay.as_secs());
+        }
+
+        self.snapshot.borrow_mut().backoff_until =
+            (failures > 0).then(|| SystemTime::now() + delay);
+        self.schedule_poll(delay);
+        self.notify();
+    }
+
+    /// Schedule the next poll after `delay`.
+    fn schedule_poll(&self, delay: Duration) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let secs = delay.as_secs().clamp(1, u32::MAX as u64) as u32;
+        let source_id = glib::timeout_add_seconds_local_once(secs, || {
+            let service = CiService::global();
+            // The one-shot source is gone once it fires
+            service.timer_source.borrow_mut().take();
+            service.poll();
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+    }
+}
+
+impl Drop for CiService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Poll delay after `failures` consecutive failed polls.
+fn backoff_secs(base: u64, failures: u32) -> u64 {
+    if failures == 0 {
+        return base;
+    }
+    base.saturating_mul(1 << failures.min(10))
+        .min(MAX_BACKOFF_SECS)
+        .max(base)
+}
+
+/// Load tokens from the secrets file (missing file means no tokens).
+fn load_secrets(path: Option<&PathBuf>) -> CiSecrets {
+    let Some(path) = path else {
+        return CiSecrets::default();
+    };
+    match std::fs::read_to_string(path) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+            warn!("CiService: failed to parse {}: {}", path.display(), e);
+            CiSecrets::default()
+        }),
+        Err(e) => {
+            warn!("CiService: failed to read {}: {}", path.display(), e);
+            CiSecrets::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitHubRuns {
+    workflow_runs: Vec<GitHubRun>,
+}
+
+#[derive(Deserialize)]
+struct GitHubRun {
+    name: Option<String>,
+    status: String,
+    conclusion: Option<String>,
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipeline {
+    status: String,
+    web_url: String,
+    #[serde(rename = "ref")]
+    git_ref: Option<String>,
+}
+
+/// Fetch the latest run for one repository.
+fn fetch_repo_status(
+    repo: &CiRepo,
+    settings: &CiSettings,
+    secrets: &CiSecrets,
+) -> Result<CiRepoStatus, FetchError> {
+    let (state, name, url) = match repo.provider {
+        CiProvider::GitHub => {
+            let mut url = format!(
+                "https://api.github.com/repos/{}/actions/runs?per_page=1",
+                repo.path
+            );
+            if let Some(ref branch) = repo.branch {
+                url.push_str(&format!("&branch={}", encode_component(branch)));
+            }
+            let mut request = minreq::get(&url)
+                .with_header("Accept", "application/vnd.github+json")
+                .with_header("User-Agent", "vibepanel");
+            if let Some(ref token) = secrets.github_token {
+                request = request.with_header("Authorization", format!("Bearer {}", token));
+            }
+            let runs: GitHubRuns = send_json(request)?;
+            match runs.workflow_runs.into_iter().next() {
+                Some(run) => (
+                    CiState::from_github(&run.status, run.conclusion.as_deref()),
+                    run.name,
+                    Some(run.html_url),
+                ),
+                None => (CiState::None, None, None),
+            }
+        }
+        CiProvider::GitLab => {
+            let mut url = format!(
+                "{}/api/v4/projects/{}/pipelines?per_page=1",
+                settings.gitlab_url,
+                encode_component(&repo.path)
+            );
+            if let Some(ref branch) = repo.branch {
+                url.push_str(&format!("&ref={}", encode_component(branch)));
+            }
+            let mut request = minreq::get(&url).with_header("User-Agent", "vibepanel");
+            if let Some(ref token) = secrets.gitlab_token {
+                request = request.with_header("PRIVATE-TOKEN", token.as_str());
+            }
+            let pipelines: Vec<GitLabPipeline> = send_json(request)?;
+            match pipelines.into_iter().next() {
+                Some(pipeline) => (
+                    CiState::from_gitlab(&pipeline.status),
+                    pipeline.git_ref,
+                    Some(pipeline.web_url),
+                ),
+                None => (CiState::None, None, None),
+            }
+        }
+    };
+
+    Ok(CiRepoStatus {
+        repo: repo.clone(),
+        state: Some(state),
+        name,
+        url,
+        error: None,
+    })
+}
+
+/// Send a request and decode the JSON body, classifying rate limits.
+fn send_json<T: serde::de::DeserializeOwned>(request: minreq::Request) -> Result<T, FetchError> {
+    let response = request
+        .with_timeout(REQUEST_TIMEOUT_SECS)
+        .send()
+        .map_err(|e| FetchError::Other(format!("Request failed: {}", e)))?;
+
+    // minreq lowercases header names
+    let header = |name: &str| response.headers.get(name).map(String::as_str);
+    let rate_limited = response.status_code == 429
+        || (response.status_code == 403 && header("x-ratelimit-remaining") == Some("0"));
+    if rate_limited {
+        return Err(FetchError::RateLimited(rate_limit_delay(
+            header("retry-after"),
+            header("x-ratelimit-reset"),
+            SystemTime::now(),
+        )));
+    }
+
+    match response.status_code {
+        200..=299 => {}
+        401 | 403 => return Err(FetchError::Other("Access denied (check token)".to_string())),
+        404 => return Err(FetchError::Other("Repository not found".to_string())),
+        code => return Err(FetchError::Other(format!("HTTP {}", code))),
+    }
+
+    serde_json::from_slice(response.as_bytes())
+        .map_err(|e| FetchError::Other(format!("Unexpected response: {}", e)))
+}
+
+/// Delay until a rate limit resets, from `Retry-After` (seconds) or
+/// `X-RateLimit-Reset` (Unix timestamp).
+fn rate_limit_delay(
+    retry_after: Option<&str>,
+    reset: Option<&str>,
+    now: SystemTime,
+) -> Option<Duration> {
+    if let Some(secs) = retry_after.and_then(|v| v.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+    let reset = reset.and_then(|v| v.trim().parse::<u64>().ok())?;
+    let reset = UNIX_EPOCH + Duration::from_secs(reset);
+    Some(reset.duration_since(now).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo_spec() {
+        assert_eq!(
+            CiRepo::parse("github:owner/repo@main"),
+            Some(CiRepo {
+                provider: CiProvider::GitHub,
+                path: "owner/repo".to_string(),
+                branch: Some("main".to_string()),
+            })
+        );
+        let gitlab = CiRepo::parse("gitlab:group/sub/project").unwrap();
+        assert_eq!(gitlab.provider, CiProvider::GitLab);
+        assert_eq!(gitlab.branch, None);
+
+        assert!(CiRepo::parse("owner/repo").is_none());
+        assert!(CiRepo::parse("bitbucket:owner/repo").is_none());
+        assert!(CiRepo::parse("github:repo").is_none());
+    }
+
+    #[test]
+    fn test_ci_state_mapping() {
+        assert_eq!(
+            CiState::from_github("completed", Some("success")),
+            CiState::Success
+        );
+        assert_eq!(
+            CiState::from_github("completed", Some("timed_out")),
+            CiState::Failure
+        );
+        assert_eq!(CiState::from_github("in_progress", None), CiState::Running);
+        assert_eq!(CiState::from_gitlab("failed"), CiState::Failure);
+        assert_eq!(CiState::from_gitlab("pending"), CiState::Running);
+    }
+
+    #[test]
+    fn test_backoff_secs() {
+        assert_eq!(backoff_secs(300, 0), 300);
+        assert_eq!(backoff_secs(300, 1), 600);
+        assert_eq!(backoff_secs(300, 2), 1200);
+        assert_eq!(backoff_secs(300, 8), MAX_BACKOFF_SECS);
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        assert_eq!(
+            rate_limit_delay(Some("30"), None, now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limit_delay(None, Some("1600"), now),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(rate_limit_delay(None, None, now), None);
+    }
+}
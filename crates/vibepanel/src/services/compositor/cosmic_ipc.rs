@@ -0,0 +1,53 @@
+//! Wayland protocol bindings for COSMIC's toplevel info protocol.
+//!
+//! This module provides Rust bindings for `zcosmic_toplevel_info_v1`, which
+//! extends `ext_foreign_toplevel_handle_v1` with outputs, state and the
+//! `ext_workspace_handle_v1` workspaces a window is on. The older
+//! `cosmic-workspace-unstable-v1` protocol is only included because the
+//! toplevel info protocol still refers to its workspace handles.
+//!
+//! The bindings are generated from the protocol XML files at compile time.
+
+#![allow(dead_code, non_camel_case_types, unused_unsafe, unused_variables)]
+#![allow(non_upper_case_globals, non_snake_case, unused_imports)]
+#![allow(missing_docs, clippy::all)]
+
+/// `cosmic-workspace-unstable-v1`.
+pub mod workspace {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/cosmic-workspace-unstable-v1.xml");
+    }
+
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/cosmic-workspace-unstable-v1.xml");
+}
+
+/// `cosmic-toplevel-info-unstable-v1`.
+pub mod toplevel_info {
+    use super::workspace::*;
+    use wayland_client;
+    use wayland_client::protocol::*;
+    use wayland_protocols::ext::foreign_toplevel_list::v1::client::*;
+    use wayland_protocols::ext::workspace::v1::client::*;
+
+    pub mod __interfaces {
+        use super::super::workspace::__interfaces::*;
+        use wayland_client::protocol::__interfaces::*;
+        use wayland_protocols::ext::foreign_toplevel_list::v1::client::__interfaces::*;
+        use wayland_protocols::ext::workspace::v1::client::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/cosmic-toplevel-info-unstable-v1.xml");
+    }
+
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/cosmic-toplevel-info-unstable-v1.xml");
+}
+
+// Re-export the protocol types with convenient names
+pub use toplevel_info::zcosmic_toplevel_handle_v1::{self, State, ZcosmicToplevelHandleV1};
+pub use toplevel_info::zcosmic_toplevel_info_v1::{self, ZcosmicToplevelInfoV1};
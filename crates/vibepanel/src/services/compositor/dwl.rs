@@ -0,0 +1,418 @@
+//! DWL compositor backend reading dwl's status output.
+//!
+//! Unpatched dwl has no IPC, but prints its state to stdout after every
+//! change, and `dwl -s <command>` runs the startup command with that output
+//! on its stdin. Starting the bar with `dwl -s vibepanel` (or piping dwl's
+//! output into it) feeds this backend. dwl builds with the ipc patch are
+//! handled by the MangoWC/DWL backend instead, which can also switch tags.
+//!
+//! # Status format
+//!
+//! One line per field and output, printed as a block for every output:
+//!
+//! ```text
+//! eDP-1 title Mozilla Firefox
+//! eDP-1 appid firefox
+//! eDP-1 fullscreen 0
+//! eDP-1 floating 0
+//! eDP-1 selmon 1
+//! eDP-1 tags 5 1 1 0
+//! eDP-1 layout []=
+//! ```
+//!
+//! `tags` holds four bitmasks: occupied tags, selected tags, tags of the
+//! focused window and urgent tags. There is no way to send commands back, so
+//! switching workspaces from the bar isn't supported.
+
+use std::io::{self, BufRead, BufReader};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread;
+
+use parking_lot::RwLock;
+use tracing::{debug, error, trace, warn};
+
+use super::{
+    CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
+    WorkspaceSnapshot,
+};
+
+/// Number of tags in a default dwl build.
+const DEFAULT_TAG_COUNT: u32 = 9;
+
+/// Whether stdin is a pipe, as when started with `dwl -s vibepanel`.
+pub fn stdin_is_pipe() -> bool {
+    let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+    // SAFETY: fstat fills in `stat` for a valid fd and we only read it when
+    // the call succeeded.
+    unsafe {
+        libc::fstat(libc::STDIN_FILENO, stat.as_mut_ptr()) == 0
+            && stat.assume_init().st_mode & libc::S_IFMT == libc::S_IFIFO
+    }
+}
+
+/// Last reported state of one output.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct OutputStatus {
+    title: String,
+    appid: String,
+    fullscreen: bool,
+    selected_monitor: bool,
+    occupied_tags: u32,
+    selected_tags: u32,
+    focused_client_tags: u32,
+    urgent_tags: u32,
+}
+
+/// State of all outputs, built up from status lines.
+#[derive(Debug, Default)]
+struct DwlStatus {
+    /// Outputs by name, in the order they were first reported.
+    outputs: Vec<(String, OutputStatus)>,
+}
+
+/// 1-based workspace IDs of the bits set in a tag mask.
+fn tags(mask: u32) -> impl Iterator<Item = i32> {
+    (0..32)
+        .filter(move |bit| mask & (1 << bit) != 0)
+        .map(|bit| bit + 1)
+}
+
+impl DwlStatus {
+    /// Apply one status line. Unknown fields are ignored.
+    fn apply_line(&mut self, line: &str) {
+        let mut parts = line.splitn(3, ' ');
+        let (Some(output), Some(field)) = (parts.next(), parts.next()) else {
+            return;
+        };
+        let value = parts.next().unwrap_or("");
+
+        let index = match self.outputs.iter().position(|(name, _)| name == output) {
+            Some(index) => index,
+            None => {
+                self.outputs
+                    .push((output.to_string(), OutputStatus::default()));
+                self.outputs.len() - 1
+            }
+        };
+        let status = &mut self.outputs[index].1;
+
+        match field {
+            "title" => status.title = value.to_string(),
+            "appid" => status.appid = value.to_string(),
+            "fullscreen" => status.fullscreen = value.trim() == "1",
+            "selmon" => {
+                let selected = value.trim() == "1";
+                status.selected_monitor = selected;
+                if selected {
+                    for (name, other) in &mut self.outputs {
+                        if name != output {
+                            other.selected_monitor = false;
+                        }
+                    }
+                }
+            }
+            "tags" => {
+                let masks: Vec<u32> = value
+                    .split_whitespace()
+                    .filter_map(|mask| mask.parse().ok())
+                    .collect();
+                if let [occupied, selected, focused_client, urgent] = masks[..] {
+                    status.occupied_tags = occupied;
+                    status.selected_tags = selected;
+                    status.focused_client_tags = focused_client;
+                    status.urgent_tags = urgent;
+                } else {
+                    warn!("DwlBackend: malformed tags line '{}'", line);
+                }
+            }
+            _ => trace!("DwlBackend: ignoring '{}'", line),
+        }
+    }
+
+    /// The selected output, or the first one if none is marked yet.
+    fn selected_output(&self) -> Option<&(String, OutputStatus)> {
+        self.outputs
+            .iter()
+            .find(|(_, status)| status.selected_monitor)
+            .or_else(|| self.outputs.first())
+    }
+
+    /// Number of tags to show: the default, or more if the masks use more.
+    fn tag_count(&self) -> u32 {
+        let mask = self.outputs.iter().fold(0, |mask, (_, status)| {
+            mask | status.occupied_tags | status.selected_tags | status.urgent_tags
+        });
+        DEFAULT_TAG_COUNT.max(32 - mask.leading_zeros())
+    }
+
+    fn snapshot(&self) -> WorkspaceSnapshot {
+        let mut snapshot = WorkspaceSnapshot::default();
+        let selected = self.selected_output().map(|(name, _)| name.as_str());
+
+        for (name, status) in &self.outputs {
+            let per_output = snapshot.per_output.entry(name.clone()).or_default();
+            per_output.active_workspace = tags(status.selected_tags).collect();
+            per_output.occupied_workspaces = tags(status.occupied_tags).collect();
+
+            if selected == Some(name.as_str()) {
+                snapshot.active_workspace = per_output.active_workspace.clone();
+            }
+            snapshot
+                .occupied_workspaces
+                .extend(tags(status.occupied_tags));
+            snapshot.urgent_workspaces.extend(tags(status.urgent_tags));
+        }
+
+        snapshot
+    }
+
+    fn focused_window(&self) -> Option<WindowInfo> {
+        let (name, status) = self.selected_output()?;
+        Some(WindowInfo {
+            title: status.title.clone(),
+            app_id: status.appid.clone(),
+            workspace_id: tags(status.focused_client_tags).next(),
+            output: Some(name.clone()),
+            fullscreen: status.fullscreen,
+        })
+    }
+}
+
+#[derive(Default)]
+struct SharedState {
+    snapshot: RwLock<WorkspaceSnapshot>,
+    focused_window: RwLock<Option<WindowInfo>>,
+    tag_count: AtomicU32,
+}
+
+/// DWL backend fed by dwl's status output on stdin.
+pub struct DwlBackend {
+    #[allow(dead_code)] // For future filtering support
+    allowed_outputs: Vec<String>,
+    running: Arc<AtomicBool>,
+    shared: Arc<SharedState>,
+}
+
+impl DwlBackend {
+    /// Create a new DWL status backend.
+    pub fn new(outputs: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_outputs: outputs.unwrap_or_default(),
+            running: Arc::new(AtomicBool::new(false)),
+            shared: Arc::new(SharedState {
+                tag_count: AtomicU32::new(DEFAULT_TAG_COUNT),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Read status lines until stdin closes or the backend stops. Callbacks
+    /// run once all lines that arrived together are applied, so a status
+    /// block is reported as a whole.
+    fn read_loop(
+        running: Arc<AtomicBool>,
+        shared: Arc<SharedState>,
+        on_workspace_update: WorkspaceCallback,
+        on_window_update: WindowCallback,
+    ) {
+        let mut reader = BufReader::new(io::stdin().lock());
+        let mut status = DwlStatus::default();
+        let mut line = String::new();
+
+        while running.load(Ordering::SeqCst) {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => {
+                    warn!("DwlBackend: dwl status stream closed");
+                    break;
+                }
+                Ok(_) => status.apply_line(line.trim_end_matches('\n')),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    error!("DwlBackend: failed to read dwl status: {}", e);
+                    break;
+                }
+            }
+            if !reader.buffer().is_empty() {
+                continue;
+            }
+
+            shared
+                .tag_count
+                .store(status.tag_count(), Ordering::Relaxed);
+
+            let snapshot = status.snapshot();
+            let snapshot_changed = *shared.snapshot.read() != snapshot;
+            if snapshot_changed {
+                *shared.snapshot.write() = snapshot.clone();
+                on_workspace_update(snapshot);
+            }
+
+            let window = status.focused_window();
+            let window_changed = {
+                let current = shared.focused_window.read();
+                current
+                    .as_ref()
+                    .map(|w| (&w.title, &w.app_id, w.fullscreen, &w.output))
+                    != window
+                        .as_ref()
+                        .map(|w| (&w.title, &w.app_id, w.fullscreen, &w.output))
+            };
+            if window_changed {
+                *shared.focused_window.write() = window.clone();
+                on_window_update(window.unwrap_or_default());
+            }
+        }
+
+        running.store(false, Ordering::SeqCst);
+    }
+}
+
+impl CompositorBackend for DwlBackend {
+    fn start(&self, on_workspace_update: WorkspaceCallback, on_window_update: WindowCallback) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            warn!("DwlBackend already running");
+            return;
+        }
+
+        // SAFETY: isatty only inspects the fd.
+        if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+            error!(
+                "DwlBackend: stdin is a terminal, not dwl's status output; \
+                 start the bar with `dwl -s vibepanel`"
+            );
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        }
+
+        debug!("Starting DwlBackend on dwl's status output");
+
+        let running = self.running.clone();
+        let shared = self.shared.clone();
+        // The thread blocks reading stdin, so it isn't joined on stop; it
+        // exits after the next line or when dwl closes the stream.
+        let spawned = thread::Builder::new()
+            .name("dwl-status".into())
+            .spawn(move || {
+                Self::read_loop(running, shared, on_workspace_update, on_window_update);
+            });
+        if let Err(e) = spawned {
+            error!("DwlBackend: failed to start status reader: {}", e);
+            self.running.store(false, Ordering::SeqCst);
+        }
+    }
+
+    fn stop(&self) {
+        if self.running.swap(false, Ordering::SeqCst) {
+            debug!("DwlBackend stopped");
+        }
+    }
+
+    fn list_workspaces(&self) -> Vec<WorkspaceMeta> {
+        let count = self.shared.tag_count.load(Ordering::Relaxed);
+        (1..=count as i32)
+            .map(|id| WorkspaceMeta {
+                id,
+                name: id.to_string(),
+                output: None, // dwl tags are global
+            })
+            .collect()
+    }
+
+    fn get_workspace_snapshot(&self) -> WorkspaceSnapshot {
+        self.shared.snapshot.read().clone()
+    }
+
+    fn get_focused_window(&self) -> Option<WindowInfo> {
+        self.shared.focused_window.read().clone()
+    }
+
+    fn switch_workspace(&self, workspace_id: i32) {
+        debug!(
+            "DwlBackend: can't switch to tag {}, dwl's status output is read-only \
+             (the dwl ipc patch adds switching)",
+            workspace_id
+        );
+    }
+
+    fn name(&self) -> &'static str {
+        "DWL"
+    }
+}
+
+impl Drop for DwlBackend {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const STATUS: &str = "\
+eDP-1 title Mozilla Firefox
+eDP-1 appid firefox
+eDP-1 fullscreen 0
+eDP-1 floating 0
+eDP-1 selmon 1
+eDP-1 tags 5 1 1 0
+eDP-1 layout []=
+HDMI-A-1 title
+HDMI-A-1 appid
+HDMI-A-1 fullscreen 0
+HDMI-A-1 floating 0
+HDMI-A-1 selmon 0
+HDMI-A-1 tags 8 6 0 256
+HDMI-A-1 layout ><>";
+
+    fn status(lines: &str) -> DwlStatus {
+        let mut status = DwlStatus::default();
+        for line in lines.lines() {
+            status.apply_line(line);
+        }
+        status
+    }
+
+    #[test]
+    fn test_snapshot_from_status() {
+        let snapshot = status(STATUS).snapshot();
+
+        assert_eq!(snapshot.active_workspace, HashSet::from([1]));
+        assert_eq!(snapshot.occupied_workspaces, HashSet::from([1, 3, 4]));
+        assert_eq!(snapshot.urgent_workspaces, HashSet::from([9]));
+        let hdmi = &snapshot.per_output["HDMI-A-1"];
+        assert_eq!(hdmi.active_workspace, HashSet::from([2, 3]));
+        assert_eq!(hdmi.occupied_workspaces, HashSet::from([4]));
+    }
+
+    #[test]
+    fn test_focused_window_follows_selmon() {
+        let mut dwl = status(STATUS);
+        let window = dwl.focused_window().unwrap();
+        assert_eq!(window.title, "Mozilla Firefox");
+        assert_eq!(window.app_id, "firefox");
+        assert_eq!(window.workspace_id, Some(1));
+        assert_eq!(window.output.as_deref(), Some("eDP-1"));
+
+        // Focus moves to the empty output
+        dwl.apply_line("HDMI-A-1 selmon 1");
+        let window = dwl.focused_window().unwrap();
+        assert!(window.is_empty());
+        assert_eq!(window.output.as_deref(), Some("HDMI-A-1"));
+        assert_eq!(dwl.snapshot().active_workspace, HashSet::from([2, 3]));
+    }
+
+    #[test]
+    fn test_tag_count_grows_with_masks() {
+        let mut dwl = status(STATUS);
+        assert_eq!(dwl.tag_count(), 9);
+        dwl.apply_line("eDP-1 tags 2048 1 1 0");
+        assert_eq!(dwl.tag_count(), 12);
+        // Malformed lines leave the state alone
+        dwl.apply_line("eDP-1 tags 1 2");
+        assert_eq!(dwl.snapshot().occupied_workspaces, HashSet::from([12, 4]));
+    }
+}
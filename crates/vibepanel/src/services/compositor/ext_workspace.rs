@@ -0,0 +1,613 @@
+//! Shared ext-workspace-v1 tracking for the generic and COSMIC backends.
+//!
+//! Both backends list workspaces through `ext-workspace-v1` and only differ in
+//! how they learn about windows. [`WorkspaceTracker`] keeps the groups and
+//! workspaces, resolves them into snapshots and activates queued switches.
+//! Backends delegate the protocol's `Dispatch` impls to it and implement
+//! [`WorkspaceHandler`] to publish once the compositor finishes a batch of
+//! changes.
+//!
+//! Workspaces are numbered from 1 within each group, ordered by coordinates
+//! when the compositor sends them.
+
+use tracing::{debug, warn};
+use wayland_backend::client::ObjectId;
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, event_created_child};
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_group_handle_v1::{
+    self, ExtWorkspaceGroupHandleV1,
+};
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_handle_v1::{
+    self, ExtWorkspaceHandleV1, State as WorkspaceState,
+};
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_manager_v1::{
+    self, ExtWorkspaceManagerV1,
+};
+
+use super::{PerOutputState, WorkspaceMeta, WorkspaceSnapshot};
+
+/// Global advertised for ext-workspace-v1.
+pub const WORKSPACE_MANAGER_INTERFACE: &str = "ext_workspace_manager_v1";
+
+/// State for a tracked workspace group.
+#[derive(Debug)]
+struct TrackedGroup {
+    handle: ExtWorkspaceGroupHandleV1,
+    /// Outputs the group's workspaces are shown on.
+    outputs: Vec<ObjectId>,
+}
+
+/// State for a tracked workspace.
+#[derive(Debug)]
+struct TrackedWorkspace {
+    handle: ExtWorkspaceHandleV1,
+    name: String,
+    /// Position in the compositor's workspace layout (may be empty).
+    coordinates: Vec<u32>,
+    state: WorkspaceState,
+    /// Group the workspace belongs to, if any.
+    group: Option<ObjectId>,
+}
+
+/// A visible workspace, resolved for the snapshot.
+#[derive(Debug, Clone)]
+pub(super) struct WorkspaceLayout {
+    pub(super) name: String,
+    pub(super) state: WorkspaceState,
+    /// Number of windows on the workspace, if the backend knows it.
+    pub(super) windows: Option<u32>,
+}
+
+/// The workspaces of one group, resolved to output names.
+#[derive(Debug, Clone, Default)]
+pub(super) struct GroupLayout {
+    /// Names of the outputs the group is shown on.
+    pub(super) outputs: Vec<String>,
+    /// Visible workspaces, in display order.
+    pub(super) workspaces: Vec<WorkspaceLayout>,
+}
+
+/// Hooks a backend's Wayland state provides to the delegated dispatchers.
+pub(super) trait WorkspaceHandler {
+    /// The backend's workspace tracker.
+    fn workspace_tracker(&mut self) -> &mut WorkspaceTracker;
+
+    /// Called on the manager's `done` event, once a batch of changes is applied.
+    fn workspaces_done(&mut self);
+
+    /// Called after a workspace is removed, to drop references to it.
+    fn workspace_removed(&mut self, _workspace: &ObjectId) {}
+}
+
+/// Groups and workspaces announced through ext-workspace-v1.
+#[derive(Debug, Default)]
+pub(super) struct WorkspaceTracker {
+    /// ext-workspace manager global.
+    manager: Option<ExtWorkspaceManagerV1>,
+    /// Workspace groups, in announcement order.
+    groups: Vec<TrackedGroup>,
+    /// Workspaces, in announcement order.
+    workspaces: Vec<TrackedWorkspace>,
+}
+
+impl WorkspaceTracker {
+    /// Bind the ext-workspace manager global.
+    pub(super) fn bind<D>(
+        &mut self,
+        registry: &WlRegistry,
+        name: u32,
+        version: u32,
+        qh: &QueueHandle<D>,
+    ) where
+        D: Dispatch<ExtWorkspaceManagerV1, ()> + 'static,
+    {
+        debug!("Found ext-workspace manager v{}", version);
+        self.manager = Some(registry.bind(name, version.min(1), qh, ()));
+    }
+
+    /// Whether the compositor advertised the manager global.
+    pub(super) fn is_bound(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Number of workspaces announced so far.
+    pub(super) fn workspace_count(&self) -> usize {
+        self.workspaces.len()
+    }
+
+    /// Visible workspaces of a group (or the ungrouped ones), in display order.
+    fn group_workspaces(&self, group: Option<&ObjectId>) -> Vec<&TrackedWorkspace> {
+        let mut workspaces: Vec<&TrackedWorkspace> = self
+            .workspaces
+            .iter()
+            .filter(|ws| ws.group.as_ref() == group && !ws.state.contains(WorkspaceState::Hidden))
+            .collect();
+        // Stable, so workspaces without coordinates keep announcement order
+        workspaces.sort_by(|a, b| a.coordinates.cmp(&b.coordinates));
+        workspaces
+    }
+
+    /// Groups to show, with ungrouped workspaces as a final output-less group.
+    fn group_ids(&self) -> Vec<Option<ObjectId>> {
+        let mut ids: Vec<Option<ObjectId>> = self
+            .groups
+            .iter()
+            .map(|group| Some(group.handle.id()))
+            .collect();
+        if self.workspaces.iter().any(|ws| ws.group.is_none()) {
+            ids.push(None);
+        }
+        ids
+    }
+
+    /// Resolve every group to output names and workspace states.
+    ///
+    /// `window_count` gives the number of windows on a workspace, or `None`
+    /// when the backend can't tell which workspace a window is on.
+    pub(super) fn layouts(
+        &self,
+        output_name: impl Fn(&ObjectId) -> String,
+        window_count: impl Fn(&ObjectId) -> Option<u32>,
+    ) -> Vec<GroupLayout> {
+        self.group_ids()
+            .iter()
+            .map(|id| {
+                let outputs = self
+                    .groups
+                    .iter()
+                    .find(|group| Some(group.handle.id()) == *id)
+                    .map(|group| group.outputs.iter().map(&output_name).collect())
+                    .unwrap_or_default();
+                let workspaces = self
+                    .group_workspaces(id.as_ref())
+                    .into_iter()
+                    .map(|ws| WorkspaceLayout {
+                        name: ws.name.clone(),
+                        state: ws.state,
+                        windows: window_count(&ws.handle.id()),
+                    })
+                    .collect();
+                GroupLayout {
+                    outputs,
+                    workspaces,
+                }
+            })
+            .collect()
+    }
+
+    /// Workspace ID (1-based within its group) of a workspace handle.
+    pub(super) fn workspace_id(&self, workspace: &ObjectId) -> Option<i32> {
+        let group = self
+            .workspaces
+            .iter()
+            .find(|ws| ws.handle.id() == *workspace)?
+            .group
+            .clone();
+        self.group_workspaces(group.as_ref())
+            .iter()
+            .position(|ws| ws.handle.id() == *workspace)
+            .map(|index| index as i32 + 1)
+    }
+
+    /// Activate a workspace by ID.
+    ///
+    /// The index counts within the group shown on `focused_output`, falling
+    /// back to the first group when nothing is focused.
+    pub(super) fn activate(&self, workspace_id: i32, focused_output: Option<&ObjectId>) {
+        let Some(manager) = &self.manager else {
+            warn!("ext-workspace manager not available, dropping workspace switch");
+            return;
+        };
+
+        let focused_group = focused_output.and_then(|output| {
+            self.groups
+                .iter()
+                .find(|group| group.outputs.contains(output))
+                .map(|group| group.handle.id())
+        });
+        let group = focused_group.or_else(|| self.group_ids().into_iter().next().flatten());
+        let workspaces = self.group_workspaces(group.as_ref());
+
+        let Some(workspace) = usize::try_from(workspace_id - 1)
+            .ok()
+            .and_then(|index| workspaces.get(index))
+        else {
+            debug!("No workspace {} in the focused group", workspace_id);
+            return;
+        };
+        debug!("Activating workspace '{}'", workspace.name);
+        workspace.handle.activate();
+        manager.commit();
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceManagerV1, (), D> for WorkspaceTracker
+where
+    D: Dispatch<ExtWorkspaceManagerV1, ()>
+        + Dispatch<ExtWorkspaceGroupHandleV1, ()>
+        + Dispatch<ExtWorkspaceHandleV1, ()>
+        + WorkspaceHandler
+        + 'static,
+{
+    fn event(
+        state: &mut D,
+        _manager: &ExtWorkspaceManagerV1,
+        event: ext_workspace_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        match event {
+            ext_workspace_manager_v1::Event::WorkspaceGroup { workspace_group } => {
+                state.workspace_tracker().groups.push(TrackedGroup {
+                    handle: workspace_group,
+                    outputs: Vec::new(),
+                });
+            }
+            ext_workspace_manager_v1::Event::Workspace { workspace } => {
+                state.workspace_tracker().workspaces.push(TrackedWorkspace {
+                    handle: workspace,
+                    name: String::new(),
+                    coordinates: Vec::new(),
+                    state: WorkspaceState::empty(),
+                    group: None,
+                });
+            }
+            ext_workspace_manager_v1::Event::Done => {
+                state.workspaces_done();
+            }
+            ext_workspace_manager_v1::Event::Finished => {
+                warn!("Compositor finished the ext-workspace manager");
+                state.workspace_tracker().manager = None;
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(D, ExtWorkspaceManagerV1, [
+        ext_workspace_manager_v1::EVT_WORKSPACE_GROUP_OPCODE => (ExtWorkspaceGroupHandleV1, ()),
+        ext_workspace_manager_v1::EVT_WORKSPACE_OPCODE => (ExtWorkspaceHandleV1, ()),
+    ]);
+}
+
+impl<D> Dispatch<ExtWorkspaceGroupHandleV1, (), D> for WorkspaceTracker
+where
+    D: Dispatch<ExtWorkspaceGroupHandleV1, ()> + WorkspaceHandler + 'static,
+{
+    fn event(
+        state: &mut D,
+        handle: &ExtWorkspaceGroupHandleV1,
+        event: ext_workspace_group_handle_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        let tracker = state.workspace_tracker();
+        let group_id = handle.id();
+
+        // Changes are applied on the manager's `done` event
+        match event {
+            ext_workspace_group_handle_v1::Event::OutputEnter { output } => {
+                if let Some(group) = tracker
+                    .groups
+                    .iter_mut()
+                    .find(|g| g.handle.id() == group_id)
+                    && !group.outputs.contains(&output.id())
+                {
+                    group.outputs.push(output.id());
+                }
+            }
+            ext_workspace_group_handle_v1::Event::OutputLeave { output } => {
+                if let Some(group) = tracker
+                    .groups
+                    .iter_mut()
+                    .find(|g| g.handle.id() == group_id)
+                {
+                    group.outputs.retain(|o| *o != output.id());
+                }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceEnter { workspace } => {
+                if let Some(ws) = tracker
+                    .workspaces
+                    .iter_mut()
+                    .find(|ws| ws.handle.id() == workspace.id())
+                {
+                    ws.group = Some(group_id);
+                }
+            }
+            ext_workspace_group_handle_v1::Event::WorkspaceLeave { workspace } => {
+                if let Some(ws) = tracker.workspaces.iter_mut().find(|ws| {
+                    ws.handle.id() == workspace.id() && ws.group.as_ref() == Some(&group_id)
+                }) {
+                    ws.group = None;
+                }
+            }
+            ext_workspace_group_handle_v1::Event::Removed => {
+                tracker.groups.retain(|g| g.handle.id() != group_id);
+                handle.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceHandleV1, (), D> for WorkspaceTracker
+where
+    D: Dispatch<ExtWorkspaceHandleV1, ()> + WorkspaceHandler + 'static,
+{
+    fn event(
+        state: &mut D,
+        handle: &ExtWorkspaceHandleV1,
+        event: ext_workspace_handle_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        let id = handle.id();
+
+        if let ext_workspace_handle_v1::Event::Removed = event {
+            state
+                .workspace_tracker()
+                .workspaces
+                .retain(|ws| ws.handle.id() != id);
+            state.workspace_removed(&id);
+            handle.destroy();
+            return;
+        }
+
+        let Some(ws) = state
+            .workspace_tracker()
+            .workspaces
+            .iter_mut()
+            .find(|ws| ws.handle.id() == id)
+        else {
+            return;
+        };
+        // Changes are applied on the manager's `done` event
+        match event {
+            ext_workspace_handle_v1::Event::Name { name } => {
+                ws.name = name;
+            }
+            ext_workspace_handle_v1::Event::Coordinates { coordinates } => {
+                ws.coordinates = parse_coordinates(&coordinates);
+            }
+            ext_workspace_handle_v1::Event::State { state: ws_state } => {
+                ws.state = ws_state.into_result().unwrap_or(WorkspaceState::empty());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse a `coordinates` array of native-endian u32 values.
+///
+/// Toplevel `state` arrays use the same encoding.
+pub(super) fn parse_coordinates(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// Decode a toplevel `state` array into `(activated, fullscreen)`, given the
+/// protocol's values for those two states.
+pub(super) fn parse_toplevel_states(bytes: &[u8], activated: u32, fullscreen: u32) -> (bool, bool) {
+    let states = parse_coordinates(bytes);
+    (states.contains(&activated), states.contains(&fullscreen))
+}
+
+/// Build a workspace snapshot from each group's workspaces.
+///
+/// The global active workspace and window counts are those of the focused
+/// output, or of the first group when no output is focused. Workspaces
+/// without a window count are all treated as occupied.
+pub(super) fn build_snapshot(
+    layouts: &[GroupLayout],
+    focused_output: Option<&str>,
+) -> WorkspaceSnapshot {
+    let mut snapshot = WorkspaceSnapshot::default();
+    let mut focused_seen = false;
+
+    for layout in layouts {
+        let mut state = PerOutputState::default();
+        for (id, ws) in (1..).zip(&layout.workspaces) {
+            if ws.state.contains(WorkspaceState::Active) {
+                state.active_workspace.insert(id);
+            }
+            match ws.windows {
+                None => {
+                    state.occupied_workspaces.insert(id);
+                }
+                Some(0) => {}
+                Some(windows) => {
+                    state.occupied_workspaces.insert(id);
+                    state.window_counts.insert(id, windows);
+                }
+            }
+            if ws.state.contains(WorkspaceState::Urgent) {
+                snapshot.urgent_workspaces.insert(id);
+            }
+        }
+
+        snapshot
+            .occupied_workspaces
+            .extend(&state.occupied_workspaces);
+
+        let focused = match focused_output {
+            Some(name) => layout.outputs.iter().any(|output| output == name),
+            None => true,
+        };
+        if focused && !focused_seen {
+            focused_seen = true;
+            snapshot.active_workspace = state.active_workspace.clone();
+            snapshot.window_counts = state.window_counts.clone();
+        }
+
+        for output in &layout.outputs {
+            snapshot.per_output.insert(output.clone(), state.clone());
+        }
+    }
+
+    snapshot
+}
+
+/// Workspace metadata for every group, one entry per output it's shown on.
+pub(super) fn workspace_metas(layouts: &[GroupLayout]) -> Vec<WorkspaceMeta> {
+    let mut metas = Vec::new();
+    for layout in layouts {
+        let outputs: Vec<Option<String>> = if layout.outputs.is_empty() {
+            vec![None]
+        } else {
+            layout.outputs.iter().cloned().map(Some).collect()
+        };
+        for output in outputs {
+            metas.extend((1..).zip(&layout.workspaces).map(|(id, ws)| WorkspaceMeta {
+                id,
+                name: if ws.name.is_empty() {
+                    id.to_string()
+                } else {
+                    ws.name.clone()
+                },
+                output: output.clone(),
+            }));
+        }
+    }
+    metas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(state: WorkspaceState, windows: Option<u32>) -> WorkspaceLayout {
+        WorkspaceLayout {
+            name: String::new(),
+            state,
+            windows,
+        }
+    }
+
+    fn layout(outputs: &[&str], workspaces: Vec<WorkspaceLayout>) -> GroupLayout {
+        GroupLayout {
+            outputs: outputs.iter().map(|o| o.to_string()).collect(),
+            workspaces,
+        }
+    }
+
+    #[test]
+    fn test_parse_toplevel_states() {
+        let bytes =
+            |states: &[u32]| -> Vec<u8> { states.iter().flat_map(|s| s.to_ne_bytes()).collect() };
+        assert_eq!(parse_toplevel_states(&[], 2, 3), (false, false));
+        assert_eq!(parse_toplevel_states(&bytes(&[0, 2]), 2, 3), (true, false));
+        assert_eq!(parse_toplevel_states(&bytes(&[3]), 2, 3), (false, true));
+        assert_eq!(parse_toplevel_states(&bytes(&[3, 2]), 2, 3), (true, true));
+    }
+
+    #[test]
+    fn test_build_snapshot_with_window_counts() {
+        let empty = WorkspaceState::empty();
+        let layouts = [
+            layout(
+                &["DP-1"],
+                vec![
+                    workspace(WorkspaceState::Active, Some(2)),
+                    workspace(empty, Some(0)),
+                    workspace(empty, Some(1)),
+                ],
+            ),
+            layout(
+                &["HDMI-A-1"],
+                vec![
+                    workspace(empty, Some(0)),
+                    workspace(WorkspaceState::Active | WorkspaceState::Urgent, Some(4)),
+                ],
+            ),
+        ];
+
+        let snapshot = build_snapshot(&layouts, Some("HDMI-A-1"));
+        assert_eq!(snapshot.active_workspace, [2].into());
+        assert_eq!(snapshot.window_counts, [(2, 4)].into());
+        assert_eq!(snapshot.occupied_workspaces, [1, 2, 3].into());
+        assert_eq!(snapshot.urgent_workspaces, [2].into());
+
+        let dp1 = &snapshot.per_output["DP-1"];
+        assert_eq!(dp1.active_workspace, [1].into());
+        assert_eq!(dp1.occupied_workspaces, [1, 3].into());
+        assert_eq!(dp1.window_counts, [(1, 2), (3, 1)].into());
+        assert_eq!(
+            snapshot.per_output["HDMI-A-1"].occupied_workspaces,
+            [2].into()
+        );
+
+        // Without a focused output, the first group is the global one
+        let snapshot = build_snapshot(&layouts, None);
+        assert_eq!(snapshot.active_workspace, [1].into());
+        assert_eq!(snapshot.window_counts, [(1, 2), (3, 1)].into());
+    }
+
+    #[test]
+    fn test_build_snapshot_without_window_counts() {
+        let empty = WorkspaceState::empty();
+        let layouts = [
+            layout(
+                &["DP-1"],
+                vec![
+                    workspace(WorkspaceState::Active, None),
+                    workspace(empty, None),
+                    workspace(empty, None),
+                ],
+            ),
+            layout(
+                &["DP-2"],
+                vec![
+                    workspace(empty, None),
+                    workspace(WorkspaceState::Active | WorkspaceState::Urgent, None),
+                ],
+            ),
+        ];
+
+        let snapshot = build_snapshot(&layouts, Some("DP-2"));
+        assert_eq!(snapshot.active_workspace, [2].into());
+        assert_eq!(snapshot.occupied_workspaces, [1, 2, 3].into());
+        assert_eq!(snapshot.urgent_workspaces, [2].into());
+        assert_eq!(snapshot.per_output["DP-1"].active_workspace, [1].into());
+        assert_eq!(
+            snapshot.per_output["DP-2"].occupied_workspaces,
+            [1, 2].into()
+        );
+        assert!(snapshot.window_counts.is_empty());
+    }
+
+    #[test]
+    fn test_workspace_metas() {
+        let empty = WorkspaceState::empty();
+        let mut named = workspace(empty, None);
+        named.name = "Web".to_string();
+        let layouts = [
+            layout(&["DP-1", "DP-2"], vec![named, workspace(empty, None)]),
+            layout(&[], vec![workspace(empty, Some(3))]),
+        ];
+
+        let metas = workspace_metas(&layouts);
+        assert_eq!(metas.len(), 5);
+        assert_eq!(metas[0].name, "Web");
+        assert_eq!(
+            metas[1],
+            WorkspaceMeta {
+                id: 2,
+                name: "2".to_string(),
+                output: Some("DP-1".to_string()),
+            }
+        );
+        assert_eq!(metas[2].output.as_deref(), Some("DP-2"));
+        assert_eq!(
+            metas[4],
+            WorkspaceMeta {
+                id: 1,
+                name: "1".to_string(),
+                output: None,
+            }
+        );
+    }
+}
@@ -4,18 +4,38 @@
 
 use std::env;
 use tracing::{debug, info};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{Connection, Dispatch, EventQueue, QueueHandle};
 
-use super::{CompositorBackend, HyprlandBackend, MangoBackend, NiriBackend};
+use super::{
+    CompositorBackend, CosmicBackend, DwlBackend, GenericBackend, HyprlandBackend, KwinBackend,
+    MangoBackend, NiriBackend, RiverBackend, WayfireBackend, cosmic, dwl, ext_workspace, generic,
+    kwin, mango, river,
+};
 
 /// Backend kind enum for configuration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BackendKind {
     /// MangoWC / DWL (uses dwl-ipc-unstable-v2 or mmsg fallback).
     MangoDwl,
+    /// DWL: the ipc protocol if the compositor has the patch, otherwise
+    /// dwl's status output on stdin.
+    Dwl,
     /// Hyprland compositor.
     Hyprland,
     /// Niri compositor.
     Niri,
+    /// River compositor.
+    River,
+    /// Wayfire compositor.
+    Wayfire,
+    /// COSMIC compositor (cosmic-comp).
+    Cosmic,
+    /// KDE Plasma's KWin compositor.
+    Kwin,
+    /// Any compositor with ext-workspace and/or wlr-foreign-toplevel-management
+    /// (labwc, ...).
+    Generic,
     /// Auto-detect from environment.
     Auto,
 }
@@ -25,21 +45,99 @@ impl BackendKind {
     #[allow(dead_code)] // Used by tests and for config parsing
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
-            "mango" | "mangowc" | "dwl" => BackendKind::MangoDwl,
+            "mango" | "mangowc" => BackendKind::MangoDwl,
+            "dwl" => BackendKind::Dwl,
             "hyprland" => BackendKind::Hyprland,
             "niri" => BackendKind::Niri,
+            "river" => BackendKind::River,
+            "wayfire" => BackendKind::Wayfire,
+            "cosmic" | "cosmic-comp" => BackendKind::Cosmic,
+            "kwin" | "kde" | "plasma" => BackendKind::Kwin,
+            "generic" | "wlroots" => BackendKind::Generic,
             "auto" | "" => BackendKind::Auto,
             _ => BackendKind::Auto, // Unknown defaults to auto-detect
         }
     }
 }
 
+/// Registry state for collecting the globals the compositor advertises.
+struct GlobalProbe {
+    interfaces: Vec<String>,
+}
+
+impl Dispatch<WlRegistry, ()> for GlobalProbe {
+    fn event(
+        state: &mut Self,
+        _registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global { interface, .. } = event {
+            state.interfaces.push(interface);
+        }
+    }
+}
+
+/// Interface names of the globals advertised by the running compositor.
+///
+/// Returns None if the Wayland display can't be reached.
+fn advertised_globals() -> Option<Vec<String>> {
+    let connection = Connection::connect_to_env().ok()?;
+    let mut event_queue: EventQueue<GlobalProbe> = connection.new_event_queue();
+    let _registry = connection.display().get_registry(&event_queue.handle(), ());
+    let mut probe = GlobalProbe {
+        interfaces: Vec::new(),
+    };
+    event_queue.roundtrip(&mut probe).ok()?;
+    Some(probe.interfaces)
+}
+
+/// Pick a protocol-based backend from the advertised globals.
+fn detect_from_globals(interfaces: &[String]) -> Option<BackendKind> {
+    let has = |name: &str| interfaces.iter().any(|interface| interface == name);
+
+    if has(river::STATUS_MANAGER_INTERFACE) {
+        Some(BackendKind::River)
+    } else if has(mango::IPC_MANAGER_INTERFACE) {
+        Some(BackendKind::MangoDwl)
+    } else if has(cosmic::TOPLEVEL_INFO_INTERFACE)
+        && has(ext_workspace::WORKSPACE_MANAGER_INTERFACE)
+    {
+        Some(BackendKind::Cosmic)
+    } else if has(kwin::VIRTUAL_DESKTOP_MANAGEMENT_INTERFACE) {
+        Some(BackendKind::Kwin)
+    } else if has(ext_workspace::WORKSPACE_MANAGER_INTERFACE)
+        || has(generic::TOPLEVEL_MANAGER_INTERFACE)
+    {
+        Some(BackendKind::Generic)
+    } else {
+        None
+    }
+}
+
+/// Whether an `XDG_CURRENT_DESKTOP` value names a KDE Plasma session.
+fn is_kde_session(desktops: &str) -> bool {
+    desktops
+        .split(':')
+        .any(|desktop| desktop.eq_ignore_ascii_case("KDE"))
+}
+
 /// Detect the compositor backend from environment variables.
 ///
 /// Detection order:
 /// 1. HYPRLAND_INSTANCE_SIGNATURE → Hyprland
 /// 2. NIRI_SOCKET → Niri
-/// 3. Default → MangoWC/DWL
+/// 3. WAYFIRE_SOCKET → Wayfire
+/// 4. XDG_CURRENT_DESKTOP containing KDE → KWin
+/// 5. River status protocol advertised by the compositor → River
+/// 6. DWL IPC protocol advertised → MangoWC/DWL
+/// 7. COSMIC toplevel info and ext-workspace advertised → COSMIC
+/// 8. Plasma virtual desktops advertised → KWin
+/// 9. ext-workspace or wlr-foreign-toplevel advertised → Generic
+/// 10. Status output piped to stdin (`dwl -s vibepanel`) → DWL
+/// 11. Default → MangoWC/DWL
 pub fn detect_backend() -> BackendKind {
     // Check for Hyprland
     if env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
@@ -53,6 +151,36 @@ pub fn detect_backend() -> BackendKind {
         return BackendKind::Niri;
     }
 
+    // Check for Wayfire (set by its ipc plugin)
+    if env::var("WAYFIRE_SOCKET").is_ok() {
+        debug!("Detected Wayfire via WAYFIRE_SOCKET");
+        return BackendKind::Wayfire;
+    }
+
+    // Check for a KDE Plasma session (KWin only advertises the Plasma
+    // protocols to privileged clients, so the globals may be hidden)
+    if env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktops| is_kde_session(&desktops)) {
+        debug!("Detected KWin via XDG_CURRENT_DESKTOP");
+        return BackendKind::Kwin;
+    }
+
+    // The rest have no environment variable of their own, so look at the
+    // protocols the compositor advertises
+    if let Some(kind) = advertised_globals()
+        .as_deref()
+        .and_then(detect_from_globals)
+    {
+        debug!("Detected {:?} via advertised Wayland protocols", kind);
+        return kind;
+    }
+
+    // Unpatched dwl has no IPC, but passes its status output to the
+    // startup command's stdin
+    if dwl::stdin_is_pipe() {
+        debug!("Detected DWL via status output on stdin");
+        return BackendKind::Dwl;
+    }
+
     // Default to MangoWC/DWL
     debug!("No specific compositor detected, defaulting to MangoWC/DWL");
     BackendKind::MangoDwl
@@ -84,6 +212,24 @@ pub fn create_backend(
         BackendKind::MangoDwl => Box::new(MangoBackend::new(outputs)),
         BackendKind::Hyprland => Box::new(HyprlandBackend::new(outputs)),
         BackendKind::Niri => Box::new(NiriBackend::new(outputs)),
+        BackendKind::River => Box::new(RiverBackend::new(outputs)),
+        BackendKind::Wayfire => Box::new(WayfireBackend::new(outputs)),
+        BackendKind::Cosmic => Box::new(CosmicBackend::new(outputs)),
+        BackendKind::Kwin => Box::new(KwinBackend::new(outputs)),
+        BackendKind::Generic => Box::new(GenericBackend::new(outputs)),
+        BackendKind::Dwl => {
+            // Prefer the ipc patch's protocol, which can also switch tags
+            let has_ipc = advertised_globals().is_some_and(|interfaces| {
+                interfaces
+                    .iter()
+                    .any(|interface| interface == mango::IPC_MANAGER_INTERFACE)
+            });
+            if has_ipc {
+                Box::new(MangoBackend::new(outputs))
+            } else {
+                Box::new(DwlBackend::new(outputs))
+            }
+        }
         BackendKind::Auto => {
             // Should never reach here after resolution, but handle gracefully
             Box::new(MangoBackend::new(outputs))
@@ -98,14 +244,71 @@ mod tests {
     #[test]
     fn test_backend_kind_from_str() {
         assert_eq!(BackendKind::from_str("mango"), BackendKind::MangoDwl);
-        assert_eq!(BackendKind::from_str("dwl"), BackendKind::MangoDwl);
+        assert_eq!(BackendKind::from_str("dwl"), BackendKind::Dwl);
         assert_eq!(BackendKind::from_str("MangoWC"), BackendKind::MangoDwl);
         assert_eq!(BackendKind::from_str("hyprland"), BackendKind::Hyprland);
         assert_eq!(BackendKind::from_str("HYPRLAND"), BackendKind::Hyprland);
         assert_eq!(BackendKind::from_str("niri"), BackendKind::Niri);
         assert_eq!(BackendKind::from_str("Niri"), BackendKind::Niri);
+        assert_eq!(BackendKind::from_str("river"), BackendKind::River);
+        assert_eq!(BackendKind::from_str("Wayfire"), BackendKind::Wayfire);
+        assert_eq!(BackendKind::from_str("COSMIC"), BackendKind::Cosmic);
+        assert_eq!(BackendKind::from_str("cosmic-comp"), BackendKind::Cosmic);
+        assert_eq!(BackendKind::from_str("KWin"), BackendKind::Kwin);
+        assert_eq!(BackendKind::from_str("plasma"), BackendKind::Kwin);
+        assert_eq!(BackendKind::from_str("generic"), BackendKind::Generic);
+        assert_eq!(BackendKind::from_str("wlroots"), BackendKind::Generic);
         assert_eq!(BackendKind::from_str("auto"), BackendKind::Auto);
         assert_eq!(BackendKind::from_str(""), BackendKind::Auto);
         assert_eq!(BackendKind::from_str("unknown"), BackendKind::Auto);
     }
+
+    #[test]
+    fn test_detect_from_globals() {
+        let globals =
+            |names: &[&str]| -> Vec<String> { names.iter().map(|name| name.to_string()).collect() };
+
+        assert_eq!(detect_from_globals(&globals(&["wl_output"])), None);
+        assert_eq!(
+            detect_from_globals(&globals(&[
+                "zwlr_foreign_toplevel_manager_v1",
+                "zriver_status_manager_v1",
+            ])),
+            Some(BackendKind::River)
+        );
+        assert_eq!(
+            detect_from_globals(&globals(&[
+                "zwlr_foreign_toplevel_manager_v1",
+                "zdwl_ipc_manager_v2",
+            ])),
+            Some(BackendKind::MangoDwl)
+        );
+        assert_eq!(
+            detect_from_globals(&globals(&[
+                "ext_workspace_manager_v1",
+                "zcosmic_toplevel_info_v1",
+            ])),
+            Some(BackendKind::Cosmic)
+        );
+        assert_eq!(
+            detect_from_globals(&globals(&[
+                "ext_workspace_manager_v1",
+                "org_kde_plasma_virtual_desktop_management",
+            ])),
+            Some(BackendKind::Kwin)
+        );
+        assert_eq!(
+            detect_from_globals(&globals(&["ext_workspace_manager_v1"])),
+            Some(BackendKind::Generic)
+        );
+    }
+
+    #[test]
+    fn test_is_kde_session() {
+        assert!(is_kde_session("KDE"));
+        assert!(is_kde_session("ubuntu:kde"));
+        assert!(!is_kde_session("GNOME"));
+        assert!(!is_kde_session("KDEx"));
+        assert!(!is_kde_session(""));
+    }
 }
@@ -0,0 +1,29 @@
+//! Wayland protocol bindings for wlr-foreign-toplevel-management.
+//!
+//! This module provides Rust bindings for the `zwlr_foreign_toplevel_manager_v1`
+//! and `zwlr_foreign_toplevel_handle_v1` Wayland protocol interfaces, which
+//! list open windows and let clients activate or close them.
+//!
+//! The bindings are generated from the protocol XML file at compile time.
+
+#![allow(dead_code, non_camel_case_types, unused_unsafe, unused_variables)]
+#![allow(non_upper_case_globals, non_snake_case, unused_imports)]
+#![allow(missing_docs, clippy::all)]
+
+use wayland_client;
+use wayland_client::protocol::*;
+
+pub mod __interfaces {
+    use wayland_client::protocol::__interfaces::*;
+    wayland_scanner::generate_interfaces!(
+        "protocols/wlr-foreign-toplevel-management-unstable-v1.xml"
+    );
+}
+
+use self::__interfaces::*;
+
+wayland_scanner::generate_client_code!("protocols/wlr-foreign-toplevel-management-unstable-v1.xml");
+
+// Re-export the protocol types with convenient names
+pub use zwlr_foreign_toplevel_handle_v1::{State, ZwlrForeignToplevelHandleV1};
+pub use zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1;
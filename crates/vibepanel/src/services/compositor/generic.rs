@@ -0,0 +1,589 @@
+//! Generic backend for compositors without a dedicated backend.
+//!
+//! Used for labwc and other compositors that implement the standard
+//! workspace and toplevel protocols. Workspaces come from `ext-workspace-v1`
+//! and the focused window from `wlr-foreign-toplevel-management`; if either
+//! protocol is missing, the matching widget simply stays empty. Like the
+//! River backend, it uses its own Wayland connection and dispatches events
+//! via glib's main loop.
+//!
+//! # Protocol
+//!
+//! - `ext_workspace_group_handle_v1`: the outputs a set of workspaces is shown on
+//! - `ext_workspace_handle_v1`: name, coordinates and active/urgent/hidden state
+//! - `zwlr_foreign_toplevel_handle_v1`: title, app ID, outputs and activated state
+//!
+//! Workspace tracking is shared with the COSMIC backend (see
+//! [`super::ext_workspace`]). Neither protocol says which workspace a window
+//! is on, so every listed workspace counts as occupied and window counts
+//! aren't reported.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::fd::AsFd;
+use std::os::unix::io::AsRawFd;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use gtk4::glib;
+use parking_lot::RwLock;
+use tracing::{debug, error, trace, warn};
+use wayland_backend::client::ObjectId;
+use wayland_client::protocol::wl_output::{self, WlOutput};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle, delegate_dispatch, event_created_child,
+};
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_group_handle_v1::ExtWorkspaceGroupHandleV1;
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_handle_v1::ExtWorkspaceHandleV1;
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_manager_v1::ExtWorkspaceManagerV1;
+
+use super::ext_workspace::{
+    WORKSPACE_MANAGER_INTERFACE, WorkspaceHandler, WorkspaceTracker, build_snapshot,
+    parse_toplevel_states, workspace_metas,
+};
+use super::foreign_toplevel::{
+    State as ToplevelState, ZwlrForeignToplevelHandleV1, ZwlrForeignToplevelManagerV1,
+    zwlr_foreign_toplevel_handle_v1, zwlr_foreign_toplevel_manager_v1,
+};
+use super::switch_queue::SwitchQueue;
+use super::{
+    CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
+    WorkspaceSnapshot, output_name,
+};
+
+/// Global advertised for wlr-foreign-toplevel-management.
+pub const TOPLEVEL_MANAGER_INTERFACE: &str = "zwlr_foreign_toplevel_manager_v1";
+
+/// State for a tracked output.
+#[derive(Debug)]
+struct TrackedOutput {
+    /// Registry name of the wl_output global.
+    global_name: u32,
+    /// Output name (from wl_output, if available).
+    name: Option<String>,
+}
+
+/// State for a tracked toplevel.
+#[derive(Debug)]
+struct TrackedToplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    title: String,
+    app_id: String,
+    outputs: Vec<ObjectId>,
+    activated: bool,
+    fullscreen: bool,
+}
+
+/// Thread-safe shared state that can be updated from callbacks.
+#[derive(Debug, Default)]
+struct SharedState {
+    /// Current workspace snapshot.
+    snapshot: RwLock<WorkspaceSnapshot>,
+    /// Current workspace list.
+    workspaces: RwLock<Vec<WorkspaceMeta>>,
+    /// Current focused window info.
+    focused_window: RwLock<Option<WindowInfo>>,
+    /// Workspace switches waiting for the main loop.
+    switches: SwitchQueue,
+    /// Set when the backend is stopped.
+    stopped: AtomicBool,
+}
+
+/// Main-thread-only Wayland state.
+struct WaylandState {
+    /// Workspace groups and workspaces.
+    workspace: WorkspaceTracker,
+    /// Foreign toplevel manager global.
+    toplevel_manager: Option<ZwlrForeignToplevelManagerV1>,
+    /// Tracked outputs by wl_output ObjectId.
+    outputs: HashMap<ObjectId, TrackedOutput>,
+    /// Open windows, in announcement order.
+    toplevels: Vec<TrackedToplevel>,
+    /// Workspace update callback.
+    on_workspace_update: Option<WorkspaceCallback>,
+    /// Window update callback.
+    on_window_update: Option<WindowCallback>,
+    /// Shared state for cross-thread access.
+    shared: Arc<SharedState>,
+}
+
+impl WaylandState {
+    fn new(shared: Arc<SharedState>) -> Self {
+        Self {
+            workspace: WorkspaceTracker::default(),
+            toplevel_manager: None,
+            outputs: HashMap::new(),
+            toplevels: Vec::new(),
+            on_workspace_update: None,
+            on_window_update: None,
+            shared,
+        }
+    }
+
+    /// Name of an output, falling back to a stable per-object name.
+    fn output_name(&self, id: &ObjectId) -> String {
+        output_name(self.outputs.get(id).and_then(|o| o.name.as_deref()), id)
+    }
+
+    /// The window with keyboard focus.
+    fn focused_toplevel(&self) -> Option<&TrackedToplevel> {
+        self.toplevels.iter().find(|t| t.activated)
+    }
+
+    /// The output of the focused window, used as the focused output.
+    fn focused_output(&self) -> Option<&ObjectId> {
+        self.focused_toplevel().and_then(|t| t.outputs.first())
+    }
+
+    /// Activate the workspace queued by `switch_workspace`.
+    fn process_pending_switch(&self) {
+        if let Some(workspace_id) = self.shared.switches.take() {
+            self.workspace.activate(workspace_id, self.focused_output());
+        }
+    }
+
+    /// Rebuild the workspace snapshot and list, and notify listeners.
+    fn publish_workspaces(&self) {
+        let layouts = self.workspace.layouts(|id| self.output_name(id), |_| None);
+        let focused = self.focused_output().map(|id| self.output_name(id));
+        let snapshot = build_snapshot(&layouts, focused.as_deref());
+
+        *self.shared.workspaces.write() = workspace_metas(&layouts);
+        *self.shared.snapshot.write() = snapshot.clone();
+        if let Some(cb) = &self.on_workspace_update {
+            cb(snapshot);
+        }
+    }
+
+    /// Rebuild the focused window info and notify listeners.
+    fn publish_window(&self) {
+        let window_info = match self.focused_toplevel() {
+            Some(toplevel) => {
+                let output = toplevel.outputs.first().map(|id| self.output_name(id));
+                let workspace_id = output.as_ref().and_then(|name| {
+                    let snapshot = self.shared.snapshot.read();
+                    let active = &snapshot.per_output.get(name)?.active_workspace;
+                    active.iter().min().copied()
+                });
+                WindowInfo {
+                    title: toplevel.title.clone(),
+                    app_id: toplevel.app_id.clone(),
+                    workspace_id,
+                    output,
+                    fullscreen: toplevel.fullscreen,
+                }
+            }
+            None => WindowInfo::default(),
+        };
+
+        *self.shared.focused_window.write() = Some(window_info.clone());
+        if let Some(cb) = &self.on_window_update {
+            cb(window_info);
+        }
+    }
+}
+
+impl WorkspaceHandler for WaylandState {
+    fn workspace_tracker(&mut self) -> &mut WorkspaceTracker {
+        &mut self.workspace
+    }
+
+    fn workspaces_done(&mut self) {
+        self.publish_workspaces();
+        self.publish_window();
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => {
+                trace!("Global: {} v{} (name={})", interface, version, name);
+
+                match interface.as_str() {
+                    WORKSPACE_MANAGER_INTERFACE => {
+                        state.workspace.bind(registry, name, version, qh);
+                    }
+                    TOPLEVEL_MANAGER_INTERFACE => {
+                        debug!("Found foreign toplevel manager v{}", version);
+                        state.toplevel_manager = Some(registry.bind(name, version.min(3), qh, ()));
+                    }
+                    "wl_output" => {
+                        // v4 is needed for the connector name
+                        let wl_output: WlOutput = registry.bind(name, version.min(4), qh, name);
+                        state.outputs.insert(
+                            wl_output.id(),
+                            TrackedOutput {
+                                global_name: name,
+                                name: None,
+                            },
+                        );
+                    }
+                    _ => {}
+                }
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                let before = state.outputs.len();
+                state.outputs.retain(|_, output| output.global_name != name);
+                if state.outputs.len() != before {
+                    debug!("Output removed (name={})", name);
+                    state.publish_workspaces();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlOutput, u32> for WaylandState {
+    fn event(
+        state: &mut Self,
+        output: &WlOutput,
+        event: wl_output::Event,
+        _name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event
+            && let Some(tracked) = state.outputs.get_mut(&output.id())
+        {
+            tracked.name = Some(name);
+        }
+    }
+}
+
+delegate_dispatch!(WaylandState: [ExtWorkspaceManagerV1: ()] => WorkspaceTracker);
+delegate_dispatch!(WaylandState: [ExtWorkspaceGroupHandleV1: ()] => WorkspaceTracker);
+delegate_dispatch!(WaylandState: [ExtWorkspaceHandleV1: ()] => WorkspaceTracker);
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _manager: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                state.toplevels.push(TrackedToplevel {
+                    handle: toplevel,
+                    title: String::new(),
+                    app_id: String::new(),
+                    outputs: Vec::new(),
+                    activated: false,
+                    fullscreen: false,
+                });
+            }
+            zwlr_foreign_toplevel_manager_v1::Event::Finished => {
+                warn!("Compositor finished the foreign toplevel manager");
+                state.toplevel_manager = None;
+            }
+        }
+    }
+
+    event_created_child!(WaylandState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let id = handle.id();
+
+        if let zwlr_foreign_toplevel_handle_v1::Event::Closed = event {
+            let was_focused = state
+                .toplevels
+                .iter()
+                .any(|t| t.handle.id() == id && t.activated);
+            state.toplevels.retain(|t| t.handle.id() != id);
+            handle.destroy();
+            if was_focused {
+                state.publish_workspaces();
+                state.publish_window();
+            }
+            return;
+        }
+
+        let Some(toplevel) = state.toplevels.iter_mut().find(|t| t.handle.id() == id) else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                toplevel.title = title;
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                toplevel.app_id = app_id;
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output }
+                if !toplevel.outputs.contains(&output.id()) =>
+            {
+                toplevel.outputs.push(output.id());
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                toplevel.outputs.retain(|o| *o != output.id());
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::State { state: states } => {
+                (toplevel.activated, toplevel.fullscreen) = parse_toplevel_states(
+                    &states,
+                    ToplevelState::Activated as u32,
+                    ToplevelState::Fullscreen as u32,
+                );
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Done => {
+                if toplevel.activated {
+                    // Focus may have moved to another output
+                    state.publish_workspaces();
+                    state.publish_window();
+                } else if state.focused_toplevel().is_none() {
+                    state.publish_window();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Generic backend using ext-workspace and wlr-foreign-toplevel-management.
+pub struct GenericBackend {
+    /// Output allow-list (empty = all outputs).
+    #[allow(dead_code)]
+    allowed_outputs: RwLock<Vec<String>>,
+    /// Shared state accessible from any thread.
+    shared: Arc<SharedState>,
+    /// Whether the backend is running.
+    running: AtomicBool,
+    /// glib source IDs for cleanup.
+    source_ids: Mutex<Vec<glib::SourceId>>,
+}
+
+impl GenericBackend {
+    /// Create a new generic backend.
+    pub fn new(outputs: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_outputs: RwLock::new(outputs.unwrap_or_default()),
+            shared: Arc::new(SharedState::default()),
+            running: AtomicBool::new(false),
+            source_ids: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl CompositorBackend for GenericBackend {
+    fn start(&self, on_workspace_update: WorkspaceCallback, on_window_update: WindowCallback) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            warn!("GenericBackend already running");
+            return;
+        }
+
+        debug!("Starting GenericBackend");
+
+        let Ok(connection) = Connection::connect_to_env() else {
+            error!("Failed to connect to Wayland display");
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        let event_queue: EventQueue<WaylandState> = connection.new_event_queue();
+        let qh = event_queue.handle();
+
+        let mut state = WaylandState::new(self.shared.clone());
+        state.on_workspace_update = Some(on_workspace_update);
+        state.on_window_update = Some(on_window_update);
+
+        let _registry = connection.display().get_registry(&qh, ());
+
+        let event_queue = Rc::new(RefCell::new(event_queue));
+        let state = Rc::new(RefCell::new(state));
+
+        {
+            let mut eq = event_queue.borrow_mut();
+            let mut st = state.borrow_mut();
+
+            // Roundtrip to bind globals
+            if let Err(e) = eq.roundtrip(&mut *st) {
+                error!("Wayland roundtrip failed: {}", e);
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            match (st.workspace.is_bound(), &st.toplevel_manager) {
+                (false, None) => {
+                    error!(
+                        "Neither {} nor {} is available - no workspace or window information",
+                        WORKSPACE_MANAGER_INTERFACE, TOPLEVEL_MANAGER_INTERFACE
+                    );
+                    self.running.store(false, Ordering::SeqCst);
+                    return;
+                }
+                (false, Some(_)) => {
+                    warn!(
+                        "{} not found - workspaces are unavailable",
+                        WORKSPACE_MANAGER_INTERFACE
+                    );
+                }
+                (true, None) => {
+                    warn!(
+                        "{} not found - window titles are unavailable",
+                        TOPLEVEL_MANAGER_INTERFACE
+                    );
+                }
+                (true, Some(_)) => {}
+            }
+
+            // Another roundtrip for output names and the initial workspaces and windows
+            if let Err(e) = eq.roundtrip(&mut *st) {
+                error!("Wayland roundtrip failed: {}", e);
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            debug!(
+                "Generic backend ready: {} outputs, {} workspaces, {} windows",
+                st.outputs.len(),
+                st.workspace.workspace_count(),
+                st.toplevels.len()
+            );
+        }
+
+        // Watch the eventfd to send queued switches without polling.
+        let state_for_wake = state.clone();
+        let event_queue_for_wake = event_queue.clone();
+        let shared_for_wake = self.shared.clone();
+
+        let Some(wake_source_id) = self.shared.switches.watch(move || {
+            if shared_for_wake.stopped.load(Ordering::Relaxed) {
+                return glib::ControlFlow::Break;
+            }
+
+            state_for_wake.borrow().process_pending_switch();
+            let _ = event_queue_for_wake.borrow().flush();
+
+            glib::ControlFlow::Continue
+        }) else {
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        let eq_fd = event_queue.borrow().as_fd().as_raw_fd();
+        let shared_for_loop = self.shared.clone();
+        let event_queue_for_fd = event_queue.clone();
+        let state_for_fd = state.clone();
+
+        let fd_source_id =
+            glib::unix_fd_add_local(eq_fd, glib::IOCondition::IN, move |_fd, _condition| {
+                let mut eq = event_queue_for_fd.borrow_mut();
+                let mut st = state_for_fd.borrow_mut();
+
+                if let Err(e) = eq.dispatch_pending(&mut *st) {
+                    error!("Wayland dispatch error: {}", e);
+                    return glib::ControlFlow::Break;
+                }
+
+                if let Some(guard) = eq.prepare_read() {
+                    match guard.read() {
+                        Ok(_) => {
+                            let _ = eq.dispatch_pending(&mut *st);
+                        }
+                        Err(wayland_client::backend::WaylandError::Io(io_err)) => {
+                            if io_err.kind() != std::io::ErrorKind::WouldBlock {
+                                error!("Wayland read error: {}", io_err);
+                            }
+                        }
+                        Err(e) => {
+                            error!("Wayland error: {}", e);
+                        }
+                    }
+                }
+
+                let _ = eq.flush();
+
+                if shared_for_loop.stopped.load(Ordering::Relaxed) {
+                    return glib::ControlFlow::Break;
+                }
+
+                glib::ControlFlow::Continue
+            });
+
+        self.source_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend([fd_source_id, wake_source_id]);
+
+        debug!("GenericBackend started");
+    }
+
+    fn stop(&self) {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        debug!("Stopping GenericBackend");
+
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        self.shared.switches.wake();
+
+        for source_id in self
+            .source_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+        {
+            source_id.remove();
+        }
+
+        self.shared.switches.close();
+
+        debug!("GenericBackend stopped");
+    }
+
+    fn list_workspaces(&self) -> Vec<WorkspaceMeta> {
+        self.shared.workspaces.read().clone()
+    }
+
+    fn get_workspace_snapshot(&self) -> WorkspaceSnapshot {
+        self.shared.snapshot.read().clone()
+    }
+
+    fn get_focused_window(&self) -> Option<WindowInfo> {
+        self.shared.focused_window.read().clone()
+    }
+
+    fn switch_workspace(&self, workspace_id: i32) {
+        debug!("Requesting switch to workspace {}", workspace_id);
+        self.shared.switches.request(workspace_id);
+    }
+
+    fn name(&self) -> &'static str {
+        "Generic"
+    }
+}
+
+impl Drop for GenericBackend {
+    fn drop(&mut self) {
+        // Signal stop but don't call stop() directly (may already be stopped)
+        self.running.store(false, Ordering::SeqCst);
+        self.shared.stopped.store(true, Ordering::SeqCst);
+    }
+}
@@ -20,7 +20,7 @@ use serde_json::Value;
 use tracing::{debug, error, trace, warn};
 
 use super::{
-    CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
+    CompositorBackend, WindowCallback, WindowInfo, WindowProcess, WorkspaceCallback, WorkspaceMeta,
     WorkspaceSnapshot,
 };
 
@@ -404,12 +404,18 @@ impl HyprlandBackend {
                 .get("monitor")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
+            // Older Hyprland reports a bool, newer an int mode (0 = none)
+            let fullscreen = active_window
+                .get("fullscreen")
+                .map(|v| v.as_bool().unwrap_or_else(|| v.as_i64().unwrap_or(0) != 0))
+                .unwrap_or(false);
 
             let new_focused = WindowInfo {
                 title,
                 app_id,
                 workspace_id,
                 output,
+                fullscreen,
             };
 
             let mut focused = self.focused_window.write();
@@ -498,6 +504,10 @@ impl HyprlandBackend {
                 // Query the window info from Hyprland
                 window_changed = self.refresh_active_window();
             }
+            "fullscreen" => {
+                // fullscreen>>0|1 (for the active window)
+                window_changed = self.refresh_active_window();
+            }
             "focusedmon" => {
                 // focusedmon>>MONNAME,WORKSPACENAME
                 // Update focused monitor and global active workspace
@@ -734,6 +744,40 @@ impl CompositorBackend for HyprlandBackend {
         self.focused_window.read().clone()
     }
 
+    fn list_windows(&self) -> Vec<WindowProcess> {
+        let Some(clients) = self.query_json("clients") else {
+            return Vec::new();
+        };
+        let Some(clients) = clients.as_array() else {
+            return Vec::new();
+        };
+
+        clients
+            .iter()
+            .filter_map(|client| {
+                let pid = client.get("pid").and_then(|v| v.as_i64())?;
+                Some(WindowProcess {
+                    pid: u32::try_from(pid).ok()?,
+                    title: client
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    app_id: client
+                        .get("class")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    workspace_id: client
+                        .get("workspace")
+                        .and_then(|ws| ws.get("id"))
+                        .and_then(|v| v.as_i64())
+                        .map(|id| id as i32),
+                })
+            })
+            .collect()
+    }
+
     fn switch_workspace(&self, workspace_id: i32) {
         let _ = self.send_command(&format!("dispatch workspace {}", workspace_id));
     }
@@ -743,11 +787,46 @@ impl CompositorBackend for HyprlandBackend {
         let _ = self.send_command("dispatch exit");
     }
 
+    fn list_touchpads(&self) -> Vec<String> {
+        let Some(devices) = self.query_json("devices") else {
+            return Vec::new();
+        };
+        touchpad_names(&devices)
+    }
+
+    fn set_input_device_enabled(&self, name: &str, enabled: bool) -> bool {
+        debug!("Setting Hyprland device {} enabled={}", name, enabled);
+        let command = format!("keyword device[{}]:enabled {}", name, enabled);
+        match self.send_command(&command) {
+            Some(response) if response.trim() == "ok" => true,
+            Some(response) => {
+                warn!("Hyprland rejected '{}': {}", command, response.trim());
+                false
+            }
+            None => false,
+        }
+    }
+
     fn name(&self) -> &'static str {
         "Hyprland"
     }
 }
 
+/// Names of the touchpads in a `devices` query response.
+///
+/// Hyprland lists touchpads among the mice; libinput names them after the
+/// hardware, which almost always includes "touchpad".
+fn touchpad_names(devices: &Value) -> Vec<String> {
+    devices["mice"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|mouse| mouse["name"].as_str())
+        .filter(|name| name.to_lowercase().contains("touchpad"))
+        .map(str::to_string)
+        .collect()
+}
+
 impl Drop for HyprlandBackend {
     fn drop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
@@ -761,5 +840,6 @@ impl PartialEq for WindowInfo {
             && self.app_id == other.app_id
             && self.workspace_id == other.workspace_id
             && self.output == other.output
+            && self.fullscreen == other.fullscreen
     }
 }
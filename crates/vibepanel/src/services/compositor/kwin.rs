@@ -0,0 +1,1170 @@
+//! KDE Plasma (KWin) backend using Plasma's window management protocols.
+//!
+//! KWin lists its virtual desktops through `org_kde_plasma_virtual_desktop_management`
+//! and open windows through `org_kde_plasma_window_management`, which carries
+//! each window's title, app ID, state flags, geometry and the desktops it's
+//! on. Like the COSMIC backend, it uses its own Wayland connection and
+//! dispatches events via glib's main loop.
+//!
+//! # Protocol
+//!
+//! - `org_kde_plasma_virtual_desktop`: name and active state of a desktop
+//! - `org_kde_plasma_window`: title, app ID, PID, state flags, geometry and desktops
+//! - `zxdg_output_v1`: logical position and size of each output
+//!
+//! # Workspaces
+//!
+//! Virtual desktops are shared by all outputs and numbered from 1 in KWin's
+//! order. When the desktops are laid out in several rows, the grid size is
+//! reported in `WorkspaceSnapshot::grid`. Windows are assigned to the output
+//! containing their center, for per-output occupancy and window counts.
+//!
+//! # Privileged protocols
+//!
+//! KWin only advertises the window management globals to clients started
+//! from a `.desktop` file listing them in `X-KDE-Wayland-Interfaces`; see the
+//! README for the entry vibepanel needs.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::fd::AsFd;
+use std::os::unix::io::AsRawFd;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use gtk4::glib;
+use parking_lot::RwLock;
+use tracing::{debug, error, trace, warn};
+use wayland_backend::client::ObjectId;
+use wayland_client::protocol::wl_output::{self, WlOutput};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+use wayland_protocols::xdg::xdg_output::zv1::client::zxdg_output_manager_v1::ZxdgOutputManagerV1;
+use wayland_protocols::xdg::xdg_output::zv1::client::zxdg_output_v1::{self, ZxdgOutputV1};
+
+use super::kwin_ipc::{
+    OrgKdePlasmaVirtualDesktop, OrgKdePlasmaVirtualDesktopManagement, OrgKdePlasmaWindow,
+    OrgKdePlasmaWindowManagement, State as WindowState, org_kde_plasma_virtual_desktop,
+    org_kde_plasma_virtual_desktop_management, org_kde_plasma_window,
+    org_kde_plasma_window_management,
+};
+use super::switch_queue::SwitchQueue;
+use super::{
+    CompositorBackend, PerOutputState, WindowCallback, WindowInfo, WindowProcess,
+    WorkspaceCallback, WorkspaceGrid, WorkspaceMeta, WorkspaceSnapshot, output_name,
+};
+
+/// Global advertised for Plasma window management.
+pub const WINDOW_MANAGEMENT_INTERFACE: &str = "org_kde_plasma_window_management";
+
+/// Global advertised for Plasma virtual desktop management.
+pub const VIRTUAL_DESKTOP_MANAGEMENT_INTERFACE: &str = "org_kde_plasma_virtual_desktop_management";
+
+/// First window management version that reports the desktops a window is on.
+const WINDOW_MANAGEMENT_MIN_VERSION: u32 = 8;
+
+/// First window management version that announces windows by UUID.
+const WINDOW_UUID_VERSION: u32 = 13;
+
+/// A rectangle in the compositor's logical coordinate space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Rect {
+    /// Whether the point lies within the rectangle.
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+
+    /// Center point of the rectangle.
+    fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+/// State for a tracked output.
+#[derive(Debug)]
+struct TrackedOutput {
+    /// Registry name of the wl_output global.
+    global_name: u32,
+    wl_output: WlOutput,
+    /// Output name (from wl_output, if available).
+    name: Option<String>,
+    /// xdg-output object, once requested.
+    xdg_output: Option<ZxdgOutputV1>,
+    /// Logical area of the output (from xdg-output).
+    logical: Rect,
+}
+
+/// State for a tracked virtual desktop.
+#[derive(Debug)]
+struct TrackedDesktop {
+    handle: OrgKdePlasmaVirtualDesktop,
+    /// KWin's unique desktop ID.
+    desktop_id: String,
+    name: String,
+    active: bool,
+}
+
+/// State for a tracked window.
+#[derive(Debug)]
+struct TrackedWindow {
+    handle: OrgKdePlasmaWindow,
+    title: String,
+    app_id: String,
+    pid: Option<u32>,
+    /// Bitfield of `WindowState` flags.
+    state: u32,
+    geometry: Option<Rect>,
+    /// IDs of the desktops the window is on (empty when on all of them).
+    desktops: Vec<String>,
+    /// Set once the compositor has sent the window's initial state.
+    ready: bool,
+}
+
+impl TrackedWindow {
+    /// Whether a state flag is set.
+    fn has(&self, flag: WindowState) -> bool {
+        self.state & flag as u32 != 0
+    }
+
+    /// Whether the window shows up in taskbars (panels and the desktop don't).
+    fn is_listed(&self) -> bool {
+        self.ready && !self.has(WindowState::Skiptaskbar)
+    }
+}
+
+/// A virtual desktop, resolved for the snapshot.
+#[derive(Debug, Clone)]
+struct DesktopLayout {
+    name: String,
+    active: bool,
+}
+
+/// A window, resolved to workspace IDs and an output name for the snapshot.
+#[derive(Debug, Clone, Default)]
+struct WindowLayout {
+    /// Workspaces the window is on (empty when on all of them).
+    workspaces: Vec<i32>,
+    output: Option<String>,
+    urgent: bool,
+}
+
+/// Thread-safe shared state that can be updated from callbacks.
+#[derive(Debug, Default)]
+struct SharedState {
+    /// Current workspace snapshot.
+    snapshot: RwLock<WorkspaceSnapshot>,
+    /// Current workspace list.
+    workspaces: RwLock<Vec<WorkspaceMeta>>,
+    /// Current focused window info.
+    focused_window: RwLock<Option<WindowInfo>>,
+    /// Open windows with their client PIDs.
+    windows: RwLock<Vec<WindowProcess>>,
+    /// Workspace switches waiting for the main loop.
+    switches: SwitchQueue,
+    /// Set when the backend is stopped.
+    stopped: AtomicBool,
+}
+
+/// Main-thread-only Wayland state.
+struct WaylandState {
+    /// Plasma window management global.
+    window_management: Option<OrgKdePlasmaWindowManagement>,
+    /// Plasma virtual desktop management global.
+    desktop_management: Option<OrgKdePlasmaVirtualDesktopManagement>,
+    /// xdg-output manager global.
+    xdg_output_manager: Option<ZxdgOutputManagerV1>,
+    /// Tracked outputs by wl_output ObjectId.
+    outputs: HashMap<ObjectId, TrackedOutput>,
+    /// Virtual desktops, in KWin's order.
+    desktops: Vec<TrackedDesktop>,
+    /// Number of rows the desktops are laid out in.
+    rows: u32,
+    /// Open windows, in announcement order.
+    windows: Vec<TrackedWindow>,
+    /// Workspace update callback.
+    on_workspace_update: Option<WorkspaceCallback>,
+    /// Window update callback.
+    on_window_update: Option<WindowCallback>,
+    /// Shared state for cross-thread access.
+    shared: Arc<SharedState>,
+}
+
+impl WaylandState {
+    fn new(shared: Arc<SharedState>) -> Self {
+        Self {
+            window_management: None,
+            desktop_management: None,
+            xdg_output_manager: None,
+            outputs: HashMap::new(),
+            desktops: Vec::new(),
+            rows: 1,
+            windows: Vec::new(),
+            on_workspace_update: None,
+            on_window_update: None,
+            shared,
+        }
+    }
+
+    /// Request the xdg-output object of every output that lacks one.
+    fn request_xdg_outputs(&mut self, qh: &QueueHandle<Self>) {
+        let Some(manager) = &self.xdg_output_manager else {
+            return;
+        };
+        for (id, output) in &mut self.outputs {
+            if output.xdg_output.is_none() {
+                output.xdg_output = Some(manager.get_xdg_output(&output.wl_output, qh, id.clone()));
+            }
+        }
+    }
+
+    /// Name and logical area of every output, falling back to a stable
+    /// per-object name.
+    fn output_areas(&self) -> Vec<(String, Rect)> {
+        self.outputs
+            .iter()
+            .map(|(id, output)| (output_name(output.name.as_deref(), id), output.logical))
+            .collect()
+    }
+
+    /// Workspace ID (1-based position) of a desktop.
+    fn workspace_id(&self, desktop_id: &str) -> Option<i32> {
+        self.desktops
+            .iter()
+            .position(|desktop| desktop.desktop_id == desktop_id)
+            .map(|index| index as i32 + 1)
+    }
+
+    /// Resolve a window to workspace IDs and an output.
+    fn window_layout(&self, window: &TrackedWindow, outputs: &[(String, Rect)]) -> WindowLayout {
+        WindowLayout {
+            workspaces: window
+                .desktops
+                .iter()
+                .filter_map(|id| self.workspace_id(id))
+                .collect(),
+            output: window
+                .geometry
+                .and_then(|geometry| output_at(outputs, geometry)),
+            urgent: window.has(WindowState::DemandsAttention),
+        }
+    }
+
+    /// The window with keyboard focus.
+    fn focused_window(&self) -> Option<&TrackedWindow> {
+        self.windows
+            .iter()
+            .find(|w| w.is_listed() && w.has(WindowState::Active))
+    }
+
+    /// Activate the desktop queued by `switch_workspace`.
+    fn process_pending_switch(&self) {
+        let Some(workspace_id) = self.shared.switches.take() else {
+            return;
+        };
+
+        let Some(desktop) = usize::try_from(workspace_id - 1)
+            .ok()
+            .and_then(|index| self.desktops.get(index))
+        else {
+            debug!("No virtual desktop {}", workspace_id);
+            return;
+        };
+        debug!("Activating virtual desktop '{}'", desktop.name);
+        desktop.handle.request_activate();
+    }
+
+    /// Rebuild the workspace snapshot, workspace list and window list, and
+    /// notify listeners.
+    fn publish_workspaces(&self) {
+        let desktops: Vec<DesktopLayout> = self
+            .desktops
+            .iter()
+            .map(|desktop| DesktopLayout {
+                name: desktop.name.clone(),
+                active: desktop.active,
+            })
+            .collect();
+        let outputs = self.output_areas();
+        let listed: Vec<&TrackedWindow> = self.windows.iter().filter(|w| w.is_listed()).collect();
+        let windows: Vec<WindowLayout> = listed
+            .iter()
+            .map(|window| self.window_layout(window, &outputs))
+            .collect();
+        let output_names: Vec<String> = outputs.into_iter().map(|(name, _)| name).collect();
+        let snapshot = build_snapshot(&desktops, &windows, &output_names, self.rows);
+
+        *self.shared.windows.write() = listed
+            .iter()
+            .zip(&windows)
+            .filter_map(|(window, layout)| {
+                Some(WindowProcess {
+                    pid: window.pid?,
+                    title: window.title.clone(),
+                    app_id: window.app_id.clone(),
+                    workspace_id: layout.workspaces.first().copied(),
+                })
+            })
+            .collect();
+        *self.shared.workspaces.write() = workspace_metas(&desktops);
+        *self.shared.snapshot.write() = snapshot.clone();
+        if let Some(cb) = &self.on_workspace_update {
+            cb(snapshot);
+        }
+    }
+
+    /// Rebuild the focused window info and notify listeners.
+    fn publish_window(&self) {
+        let window_info = match self.focused_window() {
+            Some(window) => {
+                let layout = self.window_layout(window, &self.output_areas());
+                WindowInfo {
+                    title: window.title.clone(),
+                    app_id: window.app_id.clone(),
+                    workspace_id: layout.workspaces.first().copied(),
+                    output: layout.output,
+                    fullscreen: window.has(WindowState::Fullscreen),
+                }
+            }
+            None => WindowInfo::default(),
+        };
+
+        *self.shared.focused_window.write() = Some(window_info.clone());
+        if let Some(cb) = &self.on_window_update {
+            cb(window_info);
+        }
+    }
+
+    /// Publish both workspaces and the focused window.
+    fn publish(&self) {
+        self.publish_workspaces();
+        self.publish_window();
+    }
+}
+
+/// Name of the output containing the center of `geometry`.
+fn output_at(outputs: &[(String, Rect)], geometry: Rect) -> Option<String> {
+    let (x, y) = geometry.center();
+    outputs
+        .iter()
+        .find(|(_, area)| area.contains(x, y))
+        .map(|(name, _)| name.clone())
+}
+
+/// Grid size for `count` desktops laid out in `rows` rows, filled row by row.
+///
+/// None for a single row, which the workspaces widget shows as a flat list.
+fn desktop_grid(count: usize, rows: u32) -> Option<WorkspaceGrid> {
+    if rows <= 1 || count == 0 {
+        return None;
+    }
+    let rows = rows.min(count as u32);
+    Some(WorkspaceGrid {
+        columns: (count as u32).div_ceil(rows),
+        rows,
+    })
+}
+
+/// Build a workspace snapshot from the desktops and the windows on them.
+///
+/// Desktops are shared by all outputs, so every output has the same active
+/// workspace; occupancy and window counts only include the windows on it.
+fn build_snapshot(
+    desktops: &[DesktopLayout],
+    windows: &[WindowLayout],
+    outputs: &[String],
+    rows: u32,
+) -> WorkspaceSnapshot {
+    let mut snapshot = WorkspaceSnapshot {
+        grid: desktop_grid(desktops.len(), rows),
+        ..Default::default()
+    };
+    for (id, desktop) in (1..).zip(desktops) {
+        if desktop.active {
+            snapshot.active_workspace.insert(id);
+        }
+    }
+
+    let mut per_output: HashMap<&str, PerOutputState> = outputs
+        .iter()
+        .map(|name| {
+            let state = PerOutputState {
+                active_workspace: snapshot.active_workspace.clone(),
+                ..Default::default()
+            };
+            (name.as_str(), state)
+        })
+        .collect();
+
+    for window in windows {
+        for &id in &window.workspaces {
+            snapshot.occupied_workspaces.insert(id);
+            *snapshot.window_counts.entry(id).or_insert(0) += 1;
+            if window.urgent {
+                snapshot.urgent_workspaces.insert(id);
+            }
+            if let Some(state) = window
+                .output
+                .as_deref()
+                .and_then(|output| per_output.get_mut(output))
+            {
+                state.occupied_workspaces.insert(id);
+                *state.window_counts.entry(id).or_insert(0) += 1;
+            }
+        }
+    }
+
+    snapshot.per_output = per_output
+        .into_iter()
+        .map(|(name, state)| (name.to_string(), state))
+        .collect();
+    snapshot
+}
+
+/// Workspace metadata for the desktops, shared by all outputs.
+fn workspace_metas(desktops: &[DesktopLayout]) -> Vec<WorkspaceMeta> {
+    (1..)
+        .zip(desktops)
+        .map(|(id, desktop)| WorkspaceMeta {
+            id,
+            name: if desktop.name.is_empty() {
+                id.to_string()
+            } else {
+                desktop.name.clone()
+            },
+            output: None,
+        })
+        .collect()
+}
+
+impl Dispatch<WlRegistry, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => {
+                trace!("Global: {} v{} (name={})", interface, version, name);
+
+                match interface.as_str() {
+                    WINDOW_MANAGEMENT_INTERFACE if version >= WINDOW_MANAGEMENT_MIN_VERSION => {
+                        debug!("Found Plasma window management v{}", version);
+                        state.window_management =
+                            Some(registry.bind(name, version.min(15), qh, ()));
+                    }
+                    WINDOW_MANAGEMENT_INTERFACE => {
+                        warn!(
+                            "Plasma window management v{} is too old (need v{})",
+                            version, WINDOW_MANAGEMENT_MIN_VERSION
+                        );
+                    }
+                    VIRTUAL_DESKTOP_MANAGEMENT_INTERFACE => {
+                        debug!("Found Plasma virtual desktop management v{}", version);
+                        state.desktop_management =
+                            Some(registry.bind(name, version.min(2), qh, ()));
+                    }
+                    "zxdg_output_manager_v1" => {
+                        state.xdg_output_manager =
+                            Some(registry.bind(name, version.min(3), qh, ()));
+                        state.request_xdg_outputs(qh);
+                    }
+                    "wl_output" => {
+                        // v4 is needed for the connector name
+                        let wl_output: WlOutput = registry.bind(name, version.min(4), qh, name);
+                        state.outputs.insert(
+                            wl_output.id(),
+                            TrackedOutput {
+                                global_name: name,
+                                wl_output: wl_output.clone(),
+                                name: None,
+                                xdg_output: None,
+                                logical: Rect::default(),
+                            },
+                        );
+                        state.request_xdg_outputs(qh);
+                    }
+                    _ => {}
+                }
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                let before = state.outputs.len();
+                state.outputs.retain(|_, output| {
+                    let keep = output.global_name != name;
+                    if !keep && let Some(xdg_output) = &output.xdg_output {
+                        xdg_output.destroy();
+                    }
+                    keep
+                });
+                if state.outputs.len() != before {
+                    debug!("Output removed (name={})", name);
+                    state.publish();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlOutput, u32> for WaylandState {
+    fn event(
+        state: &mut Self,
+        output: &WlOutput,
+        event: wl_output::Event,
+        _name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_output::Event::Name { name } => {
+                if let Some(tracked) = state.outputs.get_mut(&output.id()) {
+                    tracked.name = Some(name);
+                }
+            }
+            wl_output::Event::Done => {
+                // Windows may now fall on a different output
+                state.publish();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZxdgOutputManagerV1, ()> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _manager: &ZxdgOutputManagerV1,
+        _event: <ZxdgOutputManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // No events
+    }
+}
+
+impl Dispatch<ZxdgOutputV1, ObjectId> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _xdg_output: &ZxdgOutputV1,
+        event: zxdg_output_v1::Event,
+        output_id: &ObjectId,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let Some(output) = state.outputs.get_mut(output_id) else {
+            return;
+        };
+        // Applied on the wl_output's `done` event
+        match event {
+            zxdg_output_v1::Event::LogicalPosition { x, y } => {
+                output.logical.x = x;
+                output.logical.y = y;
+            }
+            zxdg_output_v1::Event::LogicalSize { width, height } => {
+                output.logical.width = width;
+                output.logical.height = height;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<OrgKdePlasmaVirtualDesktopManagement, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        manager: &OrgKdePlasmaVirtualDesktopManagement,
+        event: org_kde_plasma_virtual_desktop_management::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            org_kde_plasma_virtual_desktop_management::Event::DesktopCreated {
+                desktop_id,
+                position,
+            } => {
+                let index = (position as usize).min(state.desktops.len());
+                state.desktops.insert(
+                    index,
+                    TrackedDesktop {
+                        handle: manager.get_virtual_desktop(desktop_id.clone(), qh, ()),
+                        desktop_id,
+                        name: String::new(),
+                        active: false,
+                    },
+                );
+            }
+            org_kde_plasma_virtual_desktop_management::Event::DesktopRemoved { desktop_id } => {
+                state
+                    .desktops
+                    .retain(|desktop| desktop.desktop_id != desktop_id);
+                for window in &mut state.windows {
+                    window.desktops.retain(|id| *id != desktop_id);
+                }
+                state.publish();
+            }
+            org_kde_plasma_virtual_desktop_management::Event::Rows { rows } => {
+                state.rows = rows.max(1);
+            }
+            org_kde_plasma_virtual_desktop_management::Event::Done => {
+                state.publish_workspaces();
+            }
+        }
+    }
+}
+
+impl Dispatch<OrgKdePlasmaVirtualDesktop, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        handle: &OrgKdePlasmaVirtualDesktop,
+        event: org_kde_plasma_virtual_desktop::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let id = handle.id();
+        // Desktops removed through the manager are gone from the list already
+        let Some(desktop) = state.desktops.iter_mut().find(|d| d.handle.id() == id) else {
+            return;
+        };
+
+        // Changes are applied on `done`
+        match event {
+            org_kde_plasma_virtual_desktop::Event::Name { name } => {
+                desktop.name = name;
+            }
+            org_kde_plasma_virtual_desktop::Event::Activated => {
+                desktop.active = true;
+            }
+            org_kde_plasma_virtual_desktop::Event::Deactivated => {
+                desktop.active = false;
+            }
+            org_kde_plasma_virtual_desktop::Event::Done => {
+                state.publish();
+            }
+            org_kde_plasma_virtual_desktop::Event::Removed => {
+                state.desktops.retain(|d| d.handle.id() != id);
+                state.publish();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<OrgKdePlasmaWindowManagement, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        manager: &OrgKdePlasmaWindowManagement,
+        event: org_kde_plasma_window_management::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let handle = match event {
+            // Clients binding v13 or newer get `window_with_uuid` instead
+            org_kde_plasma_window_management::Event::Window { id }
+                if manager.version() < WINDOW_UUID_VERSION =>
+            {
+                manager.get_window(id, qh, ())
+            }
+            org_kde_plasma_window_management::Event::WindowWithUuid { uuid, .. } => {
+                manager.get_window_by_uuid(uuid, qh, ())
+            }
+            _ => return,
+        };
+        state.windows.push(TrackedWindow {
+            handle,
+            title: String::new(),
+            app_id: String::new(),
+            pid: None,
+            state: 0,
+            geometry: None,
+            desktops: Vec::new(),
+            ready: false,
+        });
+    }
+}
+
+impl Dispatch<OrgKdePlasmaWindow, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        handle: &OrgKdePlasmaWindow,
+        event: org_kde_plasma_window::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let id = handle.id();
+
+        if let org_kde_plasma_window::Event::Unmapped = event {
+            if let Some(index) = state.windows.iter().position(|w| w.handle.id() == id) {
+                let window = state.windows.remove(index);
+                if window.is_listed() {
+                    // Window counts and possibly the focus changed
+                    state.publish();
+                }
+            }
+            handle.destroy();
+            return;
+        }
+
+        let Some(index) = state.windows.iter().position(|w| w.handle.id() == id) else {
+            return;
+        };
+        let outputs = state.output_areas();
+        let window = &mut state.windows[index];
+        let focused = window.has(WindowState::Active);
+
+        // Windows have no `done` event, so each change is published as it
+        // arrives once the initial state is in
+        let changed = match event {
+            org_kde_plasma_window::Event::TitleChanged { title } => {
+                window.title = title;
+                Changed::Window
+            }
+            org_kde_plasma_window::Event::AppIdChanged { app_id } => {
+                window.app_id = app_id;
+                Changed::Window
+            }
+            org_kde_plasma_window::Event::PidChanged { pid } => {
+                window.pid = Some(pid);
+                Changed::Nothing
+            }
+            org_kde_plasma_window::Event::StateChanged { flags } => {
+                window.state = flags;
+                Changed::Everything
+            }
+            org_kde_plasma_window::Event::Geometry {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let before = window.geometry.and_then(|g| output_at(&outputs, g));
+                let geometry = Rect {
+                    x,
+                    y,
+                    width: width as i32,
+                    height: height as i32,
+                };
+                window.geometry = Some(geometry);
+                // Only matters when the window moves to another output
+                if output_at(&outputs, geometry) != before {
+                    Changed::Everything
+                } else {
+                    Changed::Nothing
+                }
+            }
+            org_kde_plasma_window::Event::VirtualDesktopEntered { id }
+                if !window.desktops.contains(&id) =>
+            {
+                window.desktops.push(id);
+                Changed::Everything
+            }
+            org_kde_plasma_window::Event::VirtualDesktopLeft { is } => {
+                window.desktops.retain(|id| *id != is);
+                Changed::Everything
+            }
+            org_kde_plasma_window::Event::InitialState => {
+                window.ready = true;
+                Changed::Everything
+            }
+            _ => Changed::Nothing,
+        };
+
+        if !window.ready {
+            return;
+        }
+        match changed {
+            Changed::Everything => state.publish(),
+            Changed::Window if focused => state.publish_window(),
+            _ => {}
+        }
+    }
+}
+
+/// What a window event changed, to decide what to publish.
+enum Changed {
+    Nothing,
+    /// Title or app ID, which only matter for the focused window.
+    Window,
+    /// Workspaces, outputs or the focus.
+    Everything,
+}
+
+/// KDE Plasma backend using Plasma's window management protocols.
+pub struct KwinBackend {
+    /// Output allow-list (empty = all outputs).
+    #[allow(dead_code)]
+    allowed_outputs: RwLock<Vec<String>>,
+    /// Shared state accessible from any thread.
+    shared: Arc<SharedState>,
+    /// Whether the backend is running.
+    running: AtomicBool,
+    /// glib source IDs for cleanup.
+    source_ids: Mutex<Vec<glib::SourceId>>,
+}
+
+impl KwinBackend {
+    /// Create a new KWin backend.
+    pub fn new(outputs: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_outputs: RwLock::new(outputs.unwrap_or_default()),
+            shared: Arc::new(SharedState::default()),
+            running: AtomicBool::new(false),
+            source_ids: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl CompositorBackend for KwinBackend {
+    fn start(&self, on_workspace_update: WorkspaceCallback, on_window_update: WindowCallback) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            warn!("KwinBackend already running");
+            return;
+        }
+
+        debug!("Starting KwinBackend");
+
+        let Ok(connection) = Connection::connect_to_env() else {
+            error!("Failed to connect to Wayland display");
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        let event_queue: EventQueue<WaylandState> = connection.new_event_queue();
+        let qh = event_queue.handle();
+
+        let mut state = WaylandState::new(self.shared.clone());
+        state.on_workspace_update = Some(on_workspace_update);
+        state.on_window_update = Some(on_window_update);
+
+        let _registry = connection.display().get_registry(&qh, ());
+
+        let event_queue = Rc::new(RefCell::new(event_queue));
+        let state = Rc::new(RefCell::new(state));
+
+        {
+            let mut eq = event_queue.borrow_mut();
+            let mut st = state.borrow_mut();
+
+            // Roundtrip to bind globals
+            if let Err(e) = eq.roundtrip(&mut *st) {
+                error!("Wayland roundtrip failed: {}", e);
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            if st.desktop_management.is_none() && st.window_management.is_none() {
+                error!(
+                    "{} and {} not available - is this KWin, and was vibepanel started \
+                     from a .desktop file that allows them (X-KDE-Wayland-Interfaces)?",
+                    VIRTUAL_DESKTOP_MANAGEMENT_INTERFACE, WINDOW_MANAGEMENT_INTERFACE
+                );
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+            if st.window_management.is_none() {
+                warn!(
+                    "{} not found - window titles and occupied workspaces are unavailable",
+                    WINDOW_MANAGEMENT_INTERFACE
+                );
+            }
+            if st.xdg_output_manager.is_none() {
+                warn!("zxdg_output_manager_v1 not found - windows can't be matched to outputs");
+            }
+
+            // Another roundtrip for output names and the initial desktops and
+            // windows, and a third for the state of the windows just announced
+            for _ in 0..2 {
+                if let Err(e) = eq.roundtrip(&mut *st) {
+                    error!("Wayland roundtrip failed: {}", e);
+                    self.running.store(false, Ordering::SeqCst);
+                    return;
+                }
+            }
+
+            debug!(
+                "KWin backend ready: {} outputs, {} desktops, {} windows",
+                st.outputs.len(),
+                st.desktops.len(),
+                st.windows.len()
+            );
+        }
+
+        // Watch the eventfd to send queued switches without polling.
+        let state_for_wake = state.clone();
+        let event_queue_for_wake = event_queue.clone();
+        let shared_for_wake = self.shared.clone();
+
+        let Some(wake_source_id) = self.shared.switches.watch(move || {
+            if shared_for_wake.stopped.load(Ordering::Relaxed) {
+                return glib::ControlFlow::Break;
+            }
+
+            state_for_wake.borrow().process_pending_switch();
+            let _ = event_queue_for_wake.borrow().flush();
+
+            glib::ControlFlow::Continue
+        }) else {
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        let eq_fd = event_queue.borrow().as_fd().as_raw_fd();
+        let shared_for_loop = self.shared.clone();
+        let event_queue_for_fd = event_queue.clone();
+        let state_for_fd = state.clone();
+
+        let fd_source_id =
+            glib::unix_fd_add_local(eq_fd, glib::IOCondition::IN, move |_fd, _condition| {
+                let mut eq = event_queue_for_fd.borrow_mut();
+                let mut st = state_for_fd.borrow_mut();
+
+                if let Err(e) = eq.dispatch_pending(&mut *st) {
+                    error!("Wayland dispatch error: {}", e);
+                    return glib::ControlFlow::Break;
+                }
+
+                if let Some(guard) = eq.prepare_read() {
+                    match guard.read() {
+                        Ok(_) => {
+                            let _ = eq.dispatch_pending(&mut *st);
+                        }
+                        Err(wayland_client::backend::WaylandError::Io(io_err)) => {
+                            if io_err.kind() != std::io::ErrorKind::WouldBlock {
+                                error!("Wayland read error: {}", io_err);
+                            }
+                        }
+                        Err(e) => {
+                            error!("Wayland error: {}", e);
+                        }
+                    }
+                }
+
+                let _ = eq.flush();
+
+                if shared_for_loop.stopped.load(Ordering::Relaxed) {
+                    return glib::ControlFlow::Break;
+                }
+
+                glib::ControlFlow::Continue
+            });
+
+        self.source_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend([fd_source_id, wake_source_id]);
+
+        debug!("KwinBackend started");
+    }
+
+    fn stop(&self) {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        debug!("Stopping KwinBackend");
+
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        self.shared.switches.wake();
+
+        for source_id in self
+            .source_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+        {
+            source_id.remove();
+        }
+
+        self.shared.switches.close();
+
+        debug!("KwinBackend stopped");
+    }
+
+    fn list_workspaces(&self) -> Vec<WorkspaceMeta> {
+        self.shared.workspaces.read().clone()
+    }
+
+    fn get_workspace_snapshot(&self) -> WorkspaceSnapshot {
+        self.shared.snapshot.read().clone()
+    }
+
+    fn get_focused_window(&self) -> Option<WindowInfo> {
+        self.shared.focused_window.read().clone()
+    }
+
+    fn list_windows(&self) -> Vec<WindowProcess> {
+        self.shared.windows.read().clone()
+    }
+
+    fn switch_workspace(&self, workspace_id: i32) {
+        debug!("Requesting switch to workspace {}", workspace_id);
+        self.shared.switches.request(workspace_id);
+    }
+
+    fn name(&self) -> &'static str {
+        "KWin"
+    }
+}
+
+impl Drop for KwinBackend {
+    fn drop(&mut self) {
+        // Signal stop but don't call stop() directly (may already be stopped)
+        self.running.store(false, Ordering::SeqCst);
+        self.shared.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desktop(active: bool) -> DesktopLayout {
+        DesktopLayout {
+            name: String::new(),
+            active,
+        }
+    }
+
+    fn window(workspaces: &[i32], output: Option<&str>) -> WindowLayout {
+        WindowLayout {
+            workspaces: workspaces.to_vec(),
+            output: output.map(str::to_string),
+            urgent: false,
+        }
+    }
+
+    #[test]
+    fn test_output_at_window_center() {
+        let outputs = [
+            (
+                "eDP-1".to_string(),
+                Rect {
+                    x: 0,
+                    y: 0,
+                    width: 1920,
+                    height: 1080,
+                },
+            ),
+            (
+                "DP-1".to_string(),
+                Rect {
+                    x: 1920,
+                    y: 0,
+                    width: 2560,
+                    height: 1440,
+                },
+            ),
+        ];
+        let geometry = |x, width| Rect {
+            x,
+            y: 100,
+            width,
+            height: 600,
+        };
+
+        assert_eq!(
+            output_at(&outputs, geometry(100, 800)).as_deref(),
+            Some("eDP-1")
+        );
+        // Mostly on the second output
+        assert_eq!(
+            output_at(&outputs, geometry(1800, 800)).as_deref(),
+            Some("DP-1")
+        );
+        assert_eq!(output_at(&outputs, geometry(-2000, 800)), None);
+    }
+
+    #[test]
+    fn test_desktop_grid() {
+        assert_eq!(desktop_grid(4, 1), None);
+        assert_eq!(desktop_grid(0, 2), None);
+        assert_eq!(
+            desktop_grid(6, 2),
+            Some(WorkspaceGrid {
+                columns: 3,
+                rows: 2
+            })
+        );
+        assert_eq!(
+            desktop_grid(5, 2),
+            Some(WorkspaceGrid {
+                columns: 3,
+                rows: 2
+            })
+        );
+        // More rows than desktops
+        assert_eq!(
+            desktop_grid(2, 4),
+            Some(WorkspaceGrid {
+                columns: 1,
+                rows: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_snapshot_per_output() {
+        let desktops = [desktop(false), desktop(true), desktop(false)];
+        let mut urgent = window(&[3], Some("DP-1"));
+        urgent.urgent = true;
+        let windows = [
+            window(&[1], Some("eDP-1")),
+            window(&[1], Some("DP-1")),
+            window(&[2], Some("eDP-1")),
+            urgent,
+            // On all desktops
+            window(&[], Some("eDP-1")),
+        ];
+        let outputs = ["eDP-1".to_string(), "DP-1".to_string()];
+
+        let snapshot = build_snapshot(&desktops, &windows, &outputs, 1);
+        assert_eq!(snapshot.active_workspace, [2].into());
+        assert_eq!(snapshot.occupied_workspaces, [1, 2, 3].into());
+        assert_eq!(snapshot.window_counts, [(1, 2), (2, 1), (3, 1)].into());
+        assert_eq!(snapshot.urgent_workspaces, [3].into());
+        assert_eq!(snapshot.grid, None);
+
+        let edp = &snapshot.per_output["eDP-1"];
+        assert_eq!(edp.active_workspace, [2].into());
+        assert_eq!(edp.occupied_workspaces, [1, 2].into());
+        assert_eq!(edp.window_counts, [(1, 1), (2, 1)].into());
+        let dp = &snapshot.per_output["DP-1"];
+        assert_eq!(dp.active_workspace, [2].into());
+        assert_eq!(dp.window_counts, [(1, 1), (3, 1)].into());
+    }
+
+    #[test]
+    fn test_workspace_metas() {
+        let mut named = desktop(true);
+        named.name = "Mail".to_string();
+        let metas = workspace_metas(&[named, desktop(false)]);
+        assert_eq!(metas.len(), 2);
+        assert_eq!(metas[0].name, "Mail");
+        assert_eq!(
+            metas[1],
+            WorkspaceMeta {
+                id: 2,
+                name: "2".to_string(),
+                output: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Wayland protocol bindings for KDE Plasma's window management protocols.
+//!
+//! This module provides Rust bindings for `org_kde_plasma_window_management`
+//! (open windows with their state, geometry and virtual desktops) and
+//! `org_kde_plasma_virtual_desktop_management` (KWin's virtual desktops).
+//!
+//! The bindings are generated from the protocol XML files at compile time.
+
+#![allow(dead_code, non_camel_case_types, unused_unsafe, unused_variables)]
+#![allow(non_upper_case_globals, non_snake_case, unused_imports)]
+#![allow(missing_docs, clippy::all)]
+
+/// `plasma-window-management`.
+pub mod window_management {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/plasma-window-management.xml");
+    }
+
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/plasma-window-management.xml");
+}
+
+/// `plasma-virtual-desktop`.
+pub mod virtual_desktop {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/plasma-virtual-desktop.xml");
+    }
+
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/plasma-virtual-desktop.xml");
+}
+
+// Re-export the protocol types with convenient names
+pub use virtual_desktop::org_kde_plasma_virtual_desktop::{self, OrgKdePlasmaVirtualDesktop};
+pub use virtual_desktop::org_kde_plasma_virtual_desktop_management::{
+    self, OrgKdePlasmaVirtualDesktopManagement,
+};
+pub use window_management::org_kde_plasma_window::{self, OrgKdePlasmaWindow};
+pub use window_management::org_kde_plasma_window_management::{
+    self, OrgKdePlasmaWindowManagement, State,
+};
@@ -32,8 +32,8 @@ use tracing::{debug, info};
 use vibepanel_core::config::AdvancedConfig;
 
 use super::{
-    BackendKind, CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
-    WorkspaceSnapshot, factory,
+    BackendKind, CompositorBackend, WindowCallback, WindowInfo, WindowProcess, WorkspaceCallback,
+    WorkspaceMeta, WorkspaceSnapshot, factory,
 };
 use crate::services::callbacks::{CallbackId, Callbacks};
 
@@ -84,6 +84,22 @@ impl CompositorManager {
         });
     }
 
+    /// Install a fresh manager without a backend, so tests can feed it
+    /// updates.
+    #[cfg(test)]
+    pub fn init_for_test() {
+        COMPOSITOR_MANAGER.with(|cell| {
+            cell.replace(Some(Rc::new(Self {
+                backend: RefCell::new(None),
+                workspace_callbacks: Callbacks::new(),
+                window_callbacks: Callbacks::new(),
+                last_workspace_snapshot: RefCell::new(None),
+                last_window_info: RefCell::new(None),
+                started: RefCell::new(false),
+            })))
+        });
+    }
+
     /// Get the global CompositorManager singleton.
     ///
     /// This must be called from the GTK main thread.
@@ -157,6 +173,15 @@ impl CompositorManager {
         self.last_window_info.borrow().clone()
     }
 
+    /// List open windows with their process IDs (empty if unsupported).
+    pub fn list_windows(&self) -> Vec<WindowProcess> {
+        if let Some(ref backend) = *self.backend.borrow() {
+            backend.list_windows()
+        } else {
+            Vec::new()
+        }
+    }
+
     /// Switch to a workspace.
     pub fn switch_workspace(&self, workspace_id: i32) {
         if let Some(ref backend) = *self.backend.borrow() {
@@ -164,6 +189,13 @@ impl CompositorManager {
         }
     }
 
+    /// Show or hide a workspace alongside the visible ones.
+    pub fn toggle_workspace(&self, workspace_id: i32) {
+        if let Some(ref backend) = *self.backend.borrow() {
+            backend.toggle_workspace(workspace_id);
+        }
+    }
+
     /// Request the compositor to quit/exit.
     ///
     /// Used for logout functionality. Sends a quit command to the compositor
@@ -174,6 +206,26 @@ impl CompositorManager {
         }
     }
 
+    /// Names of the touchpads the compositor can enable and disable.
+    pub fn list_touchpads(&self) -> Vec<String> {
+        if let Some(ref backend) = *self.backend.borrow() {
+            backend.list_touchpads()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Enable or disable an input device by name via the compositor's IPC.
+    ///
+    /// Returns whether the compositor accepted the change.
+    pub fn set_input_device_enabled(&self, name: &str, enabled: bool) -> bool {
+        if let Some(ref backend) = *self.backend.borrow() {
+            backend.set_input_device_enabled(name, enabled)
+        } else {
+            false
+        }
+    }
+
     /// Get the backend name (e.g., "Hyprland", "Niri", "MangoWC").
     pub fn backend_name(&self) -> &'static str {
         if let Some(ref backend) = *self.backend.borrow() {
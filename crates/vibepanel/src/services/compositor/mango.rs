@@ -34,12 +34,15 @@ use super::dwl_ipc::{
 };
 use super::{
     CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
-    WorkspaceSnapshot,
+    WorkspaceSnapshot, output_name,
 };
 
 /// Default number of workspaces/tags for DWL.
 const DEFAULT_WORKSPACE_COUNT: u32 = 9;
 
+/// Global advertised by MangoWC and DWL for IPC.
+pub const IPC_MANAGER_INTERFACE: &str = "zdwl_ipc_manager_v2";
+
 /// Per-output state accumulated during a frame.
 #[derive(Debug, Clone, Default)]
 struct OutputFrameState {
@@ -51,6 +54,8 @@ struct OutputFrameState {
     title: Option<String>,
     /// Window app_id update.
     appid: Option<String>,
+    /// Focused window fullscreen update.
+    fullscreen: Option<bool>,
 }
 
 impl OutputFrameState {
@@ -59,6 +64,7 @@ impl OutputFrameState {
         self.tags.clear();
         self.title = None;
         self.appid = None;
+        self.fullscreen = None;
     }
 }
 
@@ -78,6 +84,8 @@ struct TrackedOutput {
     last_title: String,
     /// Last known app_id.
     last_appid: String,
+    /// Whether the focused window is fullscreen.
+    last_fullscreen: bool,
 }
 
 /// Thread-safe shared state that can be updated from callbacks.
@@ -169,6 +177,7 @@ impl WaylandState {
                     frame_state: OutputFrameState::default(),
                     last_title: String::new(),
                     last_appid: String::new(),
+                    last_fullscreen: false,
                 },
             );
         }
@@ -199,7 +208,14 @@ impl WaylandState {
     /// Apply buffered frame state for an output.
     fn apply_frame(&mut self, output_id: &ObjectId) {
         // First, extract all the data we need from the output
-        let (output_name, is_focused_output, frame_tags, frame_title, frame_appid) = {
+        let (
+            output_name,
+            is_focused_output,
+            frame_tags,
+            frame_title,
+            frame_appid,
+            frame_fullscreen,
+        ) = {
             let Some(output) = self.outputs.get_mut(output_id) else {
                 return;
             };
@@ -207,10 +223,7 @@ impl WaylandState {
             let frame = &mut output.frame_state;
 
             // Get output name for per-output tracking
-            let output_name = output
-                .name
-                .clone()
-                .unwrap_or_else(|| format!("output-{:?}", output_id));
+            let output_name = output_name(output.name.as_deref(), output_id);
 
             // Handle active output change
             if let Some(active) = frame.active
@@ -225,11 +238,12 @@ impl WaylandState {
             let tags = frame.tags.clone();
             let title = frame.title.take();
             let appid = frame.appid.take();
+            let fullscreen = frame.fullscreen.take();
 
             // Clear frame state for next frame
             frame.clear();
 
-            (output_name, is_focused, tags, title, appid)
+            (output_name, is_focused, tags, title, appid, fullscreen)
         };
 
         // Get or create per-output state
@@ -299,6 +313,13 @@ impl WaylandState {
             }
             window_changed = true;
         }
+        if let Some(fullscreen) = frame_fullscreen
+            && let Some(output) = self.outputs.get_mut(output_id)
+            && output.last_fullscreen != fullscreen
+        {
+            output.last_fullscreen = fullscreen;
+            window_changed = true;
+        }
 
         // Update shared state
         *self.shared.snapshot.write() = self.snapshot.clone();
@@ -320,6 +341,7 @@ impl WaylandState {
                     // We pick an arbitrary one since WindowInfo only holds a single workspace_id.
                     workspace_id: self.snapshot.active_workspace.iter().next().copied(),
                     output: Some(output_name.clone()),
+                    fullscreen: output.last_fullscreen,
                 }
             } else {
                 return;
@@ -390,7 +412,7 @@ impl Dispatch<WlRegistry, ()> for WaylandState {
             } => {
                 trace!("Global: {} v{} (name={})", interface, version, name);
 
-                if interface == "zdwl_ipc_manager_v2" {
+                if interface == IPC_MANAGER_INTERFACE {
                     debug!("Found DWL IPC manager v{}", version);
                     let manager: ZdwlIpcManagerV2 = registry.bind(name, version.min(2), qh, ());
                     state.manager = Some(manager);
@@ -417,6 +439,7 @@ impl Dispatch<WlRegistry, ()> for WaylandState {
                                 frame_state: OutputFrameState::default(),
                                 last_title: String::new(),
                                 last_appid: String::new(),
+                                last_fullscreen: false,
                             },
                         );
                     } else {
@@ -499,7 +522,9 @@ impl Dispatch<ZdwlIpcOutputV2, ObjectId> for WaylandState {
             zdwl_ipc_output_v2::Event::ToggleVisibility => {}
             zdwl_ipc_output_v2::Event::Layout { layout: _ } => {}
             zdwl_ipc_output_v2::Event::LayoutSymbol { layout: _ } => {}
-            zdwl_ipc_output_v2::Event::Fullscreen { is_fullscreen: _ } => {}
+            zdwl_ipc_output_v2::Event::Fullscreen { is_fullscreen } => {
+                tracked.frame_state.fullscreen = Some(is_fullscreen != 0);
+            }
             zdwl_ipc_output_v2::Event::Floating { is_floating: _ } => {}
             _ => {}
         }
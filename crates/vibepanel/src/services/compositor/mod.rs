@@ -1,9 +1,15 @@
 //! Compositor backend abstraction for workspace and window title tracking.
 //!
 //! This module provides a pluggable backend system for different Wayland compositors:
-//! - MangoWC / DWL (via `mmsg` CLI tool)
+//! - MangoWC / DWL (via the dwl-ipc-unstable-v2 Wayland protocol)
+//! - DWL without the ipc patch (via its status output on stdin)
 //! - Niri (via socket IPC with JSON protocol)
 //! - Hyprland (via socket IPC with JSON protocol)
+//! - River (via the river-status and river-control Wayland protocols)
+//! - Wayfire (via the IPC plugin's socket with JSON protocol)
+//! - COSMIC (via the ext-workspace and COSMIC toplevel info Wayland protocols)
+//! - KDE Plasma (via the Plasma window management and virtual desktop Wayland protocols)
+//! - Anything else (via the ext-workspace and wlr-foreign-toplevel Wayland protocols)
 //!
 //! The backend trait feeds both:
 //! - `WorkspaceService` (workspace/tag state)
@@ -14,17 +20,44 @@
 //! Services should use `CompositorManager::global()` to get a shared backend instance,
 //! then register callbacks via `register_workspace_callback` and `register_window_callback`.
 
+mod cosmic;
+pub mod cosmic_ipc;
+mod dwl;
 pub mod dwl_ipc;
+mod ext_workspace;
 mod factory;
+pub mod foreign_toplevel;
+mod generic;
 mod hyprland;
+mod kwin;
+pub mod kwin_ipc;
 mod manager;
 mod mango;
 mod niri;
+mod river;
+pub mod river_ipc;
+mod switch_queue;
 pub mod types;
+mod wayfire;
 
+pub use cosmic::CosmicBackend;
+pub use dwl::DwlBackend;
 pub use factory::BackendKind;
+pub use generic::GenericBackend;
 pub use hyprland::HyprlandBackend;
+pub use kwin::KwinBackend;
 pub use manager::CompositorManager;
 pub use mango::MangoBackend;
 pub use niri::NiriBackend;
+pub use river::RiverBackend;
 pub use types::*;
+pub use wayfire::WayfireBackend;
+
+use wayland_backend::client::ObjectId;
+
+/// Name of a Wayland output, falling back to a stable per-object name for
+/// outputs that haven't announced one.
+fn output_name(name: Option<&str>, id: &ObjectId) -> String {
+    name.map(str::to_string)
+        .unwrap_or_else(|| format!("output-{:?}", id))
+}
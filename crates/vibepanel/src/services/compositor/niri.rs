@@ -22,7 +22,7 @@ use serde_json::Value;
 use tracing::{debug, error, trace, warn};
 
 use super::{
-    CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
+    CompositorBackend, WindowCallback, WindowInfo, WindowProcess, WorkspaceCallback, WorkspaceMeta,
     WorkspaceSnapshot,
 };
 
@@ -75,6 +75,16 @@ struct WindowData {
     app_id: String,
     workspace_id: Option<u64>,
     is_focused: bool,
+    /// Client process ID (None for windows Niri can't attribute).
+    pid: Option<u32>,
+}
+
+/// Read a window's `pid` (null for clients Niri can't attribute).
+fn window_pid(window: &Value) -> Option<u32> {
+    window
+        .get("pid")
+        .and_then(|v| v.as_i64())
+        .and_then(|pid| u32::try_from(pid).ok())
 }
 
 impl NiriBackend {
@@ -300,6 +310,7 @@ impl NiriBackend {
                     .get("is_focused")
                     .and_then(|v| v.as_bool())
                     .unwrap_or(false),
+                pid: window_pid(win),
             };
 
             win_cache.insert(win_id, data);
@@ -351,6 +362,8 @@ impl NiriBackend {
                     app_id: win.app_id.clone(),
                     workspace_id: active_ws_id.and_then(|id| id_map.get(&id).copied()),
                     output: Some(out_name.clone()),
+                    // niri's IPC doesn't report fullscreen state
+                    fullscreen: false,
                 })
                 .unwrap_or_else(|| WindowInfo {
                     output: Some(out_name.clone()),
@@ -387,6 +400,7 @@ impl NiriBackend {
                 app_id: win.app_id.clone(),
                 workspace_id: workspace_idx,
                 output,
+                fullscreen: false,
             });
             break;
         }
@@ -427,6 +441,7 @@ impl NiriBackend {
             app_id,
             workspace_id,
             is_focused,
+            pid: window_pid(window),
         };
 
         shared.windows.write().insert(win_id, data);
@@ -600,6 +615,7 @@ impl NiriBackend {
                             app_id: win.app_id.clone(),
                             workspace_id: workspace_idx,
                             output: Some(output.clone()),
+                            fullscreen: false,
                         })
                     } else {
                         None
@@ -847,6 +863,22 @@ impl CompositorBackend for NiriBackend {
         self.shared.focused_window.read().clone()
     }
 
+    fn list_windows(&self) -> Vec<WindowProcess> {
+        let win_cache = self.shared.windows.read();
+        let id_map = self.shared.id_to_idx.read();
+        win_cache
+            .values()
+            .filter_map(|win| {
+                Some(WindowProcess {
+                    pid: win.pid?,
+                    title: win.title.clone(),
+                    app_id: win.app_id.clone(),
+                    workspace_id: win.workspace_id.and_then(|id| id_map.get(&id).copied()),
+                })
+            })
+            .collect()
+    }
+
     fn switch_workspace(&self, workspace_id: i32) {
         let request = serde_json::json!({
             "Action": {
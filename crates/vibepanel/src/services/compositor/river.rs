@@ -0,0 +1,774 @@
+//! River compositor backend using native Wayland protocols.
+//!
+//! Tag and focus state come from River's `zriver_status_manager_v1` protocol,
+//! and tag switching sends the same commands as `riverctl` over
+//! `zriver_control_v1`. Like the MangoWC backend, it uses its own Wayland
+//! connection and dispatches events via glib's main loop.
+//!
+//! # Protocol
+//!
+//! - `zriver_output_status_v1`: focused, occupied and urgent tags per output
+//! - `zriver_seat_status_v1`: focused output and the focused view's title
+//! - `zriver_control_v1`: `set-focused-tags` / `toggle-focused-tags`
+//!
+//! River has 32 tags; the first 9 are shown as workspaces. The status
+//! protocol doesn't expose app IDs, so `WindowInfo::app_id` stays empty.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::fd::AsFd;
+use std::os::unix::io::AsRawFd;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use gtk4::glib;
+use parking_lot::RwLock;
+use tracing::{debug, error, trace, warn};
+use wayland_backend::client::ObjectId;
+use wayland_client::protocol::wl_output::{self, WlOutput};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::protocol::wl_seat::{self, WlSeat};
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+
+use super::river_ipc::{
+    ZriverCommandCallbackV1, ZriverControlV1, ZriverOutputStatusV1, ZriverSeatStatusV1,
+    ZriverStatusManagerV1, zriver_command_callback_v1, zriver_control_v1, zriver_output_status_v1,
+    zriver_seat_status_v1, zriver_status_manager_v1,
+};
+use super::switch_queue::SwitchQueue;
+use super::{
+    CompositorBackend, WindowCallback, WindowInfo, WorkspaceCallback, WorkspaceMeta,
+    WorkspaceSnapshot, output_name,
+};
+
+/// Number of tags shown as workspaces.
+const WORKSPACE_COUNT: i32 = 9;
+
+/// Global advertised by River for status information.
+pub const STATUS_MANAGER_INTERFACE: &str = "zriver_status_manager_v1";
+
+/// Tag state of one output, as reported by river-status.
+#[derive(Debug, Clone, Default)]
+struct OutputTags {
+    /// Bitfield of the tags shown on the output.
+    focused: u32,
+    /// Bitfield of the tags with an urgent view.
+    urgent: u32,
+    /// Tag bitfield of each view on the output.
+    views: Vec<u32>,
+}
+
+/// State for a tracked output.
+#[derive(Debug)]
+struct TrackedOutput {
+    /// The wl_output this tracks.
+    wl_output: WlOutput,
+    /// Registry name of the wl_output global.
+    global_name: u32,
+    /// River status object, once the status manager is bound.
+    status: Option<ZriverOutputStatusV1>,
+    /// Output name (from wl_output, if available).
+    name: Option<String>,
+    /// Latest tag state.
+    tags: OutputTags,
+}
+
+/// Thread-safe shared state that can be updated from callbacks.
+#[derive(Debug, Default)]
+struct SharedState {
+    /// Current workspace snapshot.
+    snapshot: RwLock<WorkspaceSnapshot>,
+    /// Current focused window info.
+    focused_window: RwLock<Option<WindowInfo>>,
+    /// Commands waiting for the main loop, as argument lists.
+    commands: SwitchQueue<Vec<String>>,
+    /// Set when the backend is stopped.
+    stopped: AtomicBool,
+}
+
+/// Main-thread-only Wayland state.
+struct WaylandState {
+    /// River status manager global.
+    status_manager: Option<ZriverStatusManagerV1>,
+    /// River control global.
+    control: Option<ZriverControlV1>,
+    /// Seat used for status and commands.
+    seat: Option<WlSeat>,
+    /// Seat status object, once the status manager and seat are bound.
+    seat_status: Option<ZriverSeatStatusV1>,
+    /// Tracked outputs by wl_output ObjectId.
+    outputs: HashMap<ObjectId, TrackedOutput>,
+    /// Currently focused output ID.
+    focused_output: Option<ObjectId>,
+    /// Title of the focused view (empty when nothing is focused).
+    focused_title: String,
+    /// Workspace update callback.
+    on_workspace_update: Option<WorkspaceCallback>,
+    /// Window update callback.
+    on_window_update: Option<WindowCallback>,
+    /// Shared state for cross-thread access.
+    shared: Arc<SharedState>,
+}
+
+impl WaylandState {
+    fn new(shared: Arc<SharedState>) -> Self {
+        Self {
+            status_manager: None,
+            control: None,
+            seat: None,
+            seat_status: None,
+            outputs: HashMap::new(),
+            focused_output: None,
+            focused_title: String::new(),
+            on_workspace_update: None,
+            on_window_update: None,
+            shared,
+        }
+    }
+
+    /// Create status objects for outputs and the seat that don't have one yet.
+    fn ensure_status_objects(&mut self, qh: &QueueHandle<Self>) {
+        let Some(manager) = &self.status_manager else {
+            return;
+        };
+
+        for (id, output) in &mut self.outputs {
+            if output.status.is_none() {
+                debug!("Creating River output status for wl_output {:?}", id);
+                output.status =
+                    Some(manager.get_river_output_status(&output.wl_output, qh, id.clone()));
+            }
+        }
+
+        if self.seat_status.is_none()
+            && let Some(seat) = &self.seat
+        {
+            debug!("Creating River seat status");
+            self.seat_status = Some(manager.get_river_seat_status(seat, qh, ()));
+        }
+    }
+
+    /// Send any commands queued by `switch_workspace` and friends.
+    fn process_pending_commands(&self, qh: &QueueHandle<Self>) {
+        let commands = self.shared.commands.drain();
+        if commands.is_empty() {
+            return;
+        }
+        let (Some(control), Some(seat)) = (&self.control, &self.seat) else {
+            warn!("River control or seat not available, dropping commands");
+            return;
+        };
+
+        for args in commands {
+            debug!("Running River command: {}", args.join(" "));
+            for arg in &args {
+                control.add_argument(arg.clone());
+            }
+            control.run_command(seat, qh, args.join(" "));
+        }
+    }
+
+    /// Name of an output, falling back to a stable per-object name.
+    fn output_name(&self, id: &ObjectId) -> String {
+        output_name(self.outputs.get(id).and_then(|o| o.name.as_deref()), id)
+    }
+
+    /// Rebuild the workspace snapshot and notify listeners.
+    fn publish_workspaces(&self) {
+        let outputs: Vec<(String, &OutputTags)> = self
+            .outputs
+            .iter()
+            .map(|(id, output)| (self.output_name(id), &output.tags))
+            .collect();
+        let focused = self.focused_output.as_ref().map(|id| self.output_name(id));
+        let snapshot = build_snapshot(
+            outputs.iter().map(|(name, tags)| (name.as_str(), *tags)),
+            focused.as_deref(),
+        );
+
+        *self.shared.snapshot.write() = snapshot.clone();
+        if let Some(cb) = &self.on_workspace_update {
+            cb(snapshot);
+        }
+    }
+
+    /// Rebuild the focused window info and notify listeners.
+    fn publish_window(&self) {
+        let output = self.focused_output.as_ref();
+        let workspace_id = output
+            .and_then(|id| self.outputs.get(id))
+            .and_then(|output| tag_ids(output.tags.focused).next());
+        let window_info = WindowInfo {
+            title: self.focused_title.clone(),
+            app_id: String::new(),
+            workspace_id,
+            output: output.map(|id| self.output_name(id)),
+            fullscreen: false,
+        };
+
+        *self.shared.focused_window.write() = Some(window_info.clone());
+        if let Some(cb) = &self.on_window_update {
+            cb(window_info);
+        }
+    }
+}
+
+/// 1-based workspace IDs of the tags set in `mask`.
+fn tag_ids(mask: u32) -> impl Iterator<Item = i32> {
+    (0..32)
+        .filter(move |bit| mask & (1 << bit) != 0)
+        .map(|bit| bit + 1)
+}
+
+/// Parse a `view_tags` array of native-endian u32 bitfields.
+fn parse_view_tags(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// Build a workspace snapshot from each output's tag state.
+fn build_snapshot<'a>(
+    outputs: impl Iterator<Item = (&'a str, &'a OutputTags)>,
+    focused_output: Option<&str>,
+) -> WorkspaceSnapshot {
+    let mut snapshot = WorkspaceSnapshot::default();
+
+    for (name, tags) in outputs {
+        let per_output = snapshot.per_output.entry(name.to_string()).or_default();
+        per_output.active_workspace.extend(tag_ids(tags.focused));
+        for &view in &tags.views {
+            for id in tag_ids(view) {
+                *per_output.window_counts.entry(id).or_insert(0) += 1;
+                per_output.occupied_workspaces.insert(id);
+            }
+        }
+
+        for (&id, &count) in &per_output.window_counts {
+            *snapshot.window_counts.entry(id).or_insert(0) += count;
+        }
+        snapshot
+            .occupied_workspaces
+            .extend(per_output.occupied_workspaces.iter().copied());
+        // Urgent is global (any output can trigger urgency)
+        snapshot.urgent_workspaces.extend(tag_ids(tags.urgent));
+        if focused_output == Some(name) {
+            snapshot.active_workspace = per_output.active_workspace.clone();
+        }
+    }
+
+    snapshot
+}
+
+/// Command arguments for showing only `workspace_id`'s tag, or toggling it.
+fn tag_command(command: &str, workspace_id: i32) -> Option<Vec<String>> {
+    if !(1..=32).contains(&workspace_id) {
+        return None;
+    }
+    let mask = 1u32 << (workspace_id - 1);
+    Some(vec![command.to_string(), mask.to_string()])
+}
+
+impl Dispatch<WlRegistry, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } => {
+                trace!("Global: {} v{} (name={})", interface, version, name);
+
+                match interface.as_str() {
+                    STATUS_MANAGER_INTERFACE => {
+                        debug!("Found River status manager v{}", version);
+                        state.status_manager = Some(registry.bind(name, version.min(4), qh, ()));
+                    }
+                    "zriver_control_v1" => {
+                        debug!("Found River control v{}", version);
+                        state.control = Some(registry.bind(name, version.min(1), qh, ()));
+                    }
+                    "wl_seat" if state.seat.is_none() => {
+                        state.seat = Some(registry.bind(name, version.min(1), qh, ()));
+                    }
+                    "wl_output" => {
+                        let wl_output: WlOutput = registry.bind(name, version.min(4), qh, name);
+                        state.outputs.insert(
+                            wl_output.id(),
+                            TrackedOutput {
+                                wl_output,
+                                global_name: name,
+                                status: None,
+                                name: None,
+                                tags: OutputTags::default(),
+                            },
+                        );
+                    }
+                    _ => return,
+                }
+                state.ensure_status_objects(qh);
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                let before = state.outputs.len();
+                state.outputs.retain(|_, output| {
+                    if output.global_name != name {
+                        return true;
+                    }
+                    if let Some(status) = &output.status {
+                        status.destroy();
+                    }
+                    false
+                });
+                if state.outputs.len() != before {
+                    debug!("River output removed (name={})", name);
+                    state.publish_workspaces();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZriverStatusManagerV1, ()> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _manager: &ZriverStatusManagerV1,
+        _event: zriver_status_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // No events
+    }
+}
+
+impl Dispatch<ZriverOutputStatusV1, ObjectId> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _status: &ZriverOutputStatusV1,
+        event: zriver_output_status_v1::Event,
+        output_id: &ObjectId,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let Some(tracked) = state.outputs.get_mut(output_id) else {
+            trace!("Event for unknown output {:?}", output_id);
+            return;
+        };
+
+        match event {
+            zriver_output_status_v1::Event::FocusedTags { tags } => {
+                trace!("Focused tags on {:?}: 0x{:x}", output_id, tags);
+                tracked.tags.focused = tags;
+            }
+            zriver_output_status_v1::Event::ViewTags { tags } => {
+                tracked.tags.views = parse_view_tags(&tags);
+            }
+            zriver_output_status_v1::Event::UrgentTags { tags } => {
+                tracked.tags.urgent = tags;
+            }
+            _ => return,
+        }
+
+        state.publish_workspaces();
+        if state.focused_output.as_ref() == Some(output_id) {
+            state.publish_window();
+        }
+    }
+}
+
+impl Dispatch<ZriverSeatStatusV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _status: &ZriverSeatStatusV1,
+        event: zriver_seat_status_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zriver_seat_status_v1::Event::FocusedOutput { output } => {
+                state.focused_output = Some(output.id());
+                state.publish_workspaces();
+            }
+            zriver_seat_status_v1::Event::UnfocusedOutput { output } => {
+                if state.focused_output == Some(output.id()) {
+                    state.focused_output = None;
+                }
+                return;
+            }
+            zriver_seat_status_v1::Event::FocusedView { title } => {
+                state.focused_title = title;
+            }
+            _ => return,
+        }
+        state.publish_window();
+    }
+}
+
+impl Dispatch<ZriverControlV1, ()> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _control: &ZriverControlV1,
+        _event: zriver_control_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // No events
+    }
+}
+
+impl Dispatch<ZriverCommandCallbackV1, String> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _callback: &ZriverCommandCallbackV1,
+        event: zriver_command_callback_v1::Event,
+        command: &String,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zriver_command_callback_v1::Event::Success { output: _ } => {
+                trace!("River command '{}' succeeded", command);
+            }
+            zriver_command_callback_v1::Event::Failure { failure_message } => {
+                warn!("River command '{}' failed: {}", command, failure_message);
+            }
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _seat: &WlSeat,
+        _event: wl_seat::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // Capabilities and name aren't needed
+    }
+}
+
+impl Dispatch<WlOutput, u32> for WaylandState {
+    fn event(
+        state: &mut Self,
+        output: &WlOutput,
+        event: wl_output::Event,
+        _name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event
+            && let Some(tracked) = state.outputs.get_mut(&output.id())
+        {
+            tracked.name = Some(name);
+        }
+    }
+}
+
+/// River backend using native Wayland protocols.
+pub struct RiverBackend {
+    /// Output allow-list (empty = all outputs).
+    #[allow(dead_code)]
+    allowed_outputs: RwLock<Vec<String>>,
+    /// Shared state accessible from any thread.
+    shared: Arc<SharedState>,
+    /// Whether the backend is running.
+    running: AtomicBool,
+    /// glib source IDs for cleanup.
+    source_ids: Mutex<Vec<glib::SourceId>>,
+}
+
+impl RiverBackend {
+    /// Create a new River backend.
+    pub fn new(outputs: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_outputs: RwLock::new(outputs.unwrap_or_default()),
+            shared: Arc::new(SharedState::default()),
+            running: AtomicBool::new(false),
+            source_ids: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue a command for the main loop to send.
+    fn queue_command(&self, args: Vec<String>) {
+        self.shared.commands.push(args);
+    }
+}
+
+impl CompositorBackend for RiverBackend {
+    fn start(&self, on_workspace_update: WorkspaceCallback, on_window_update: WindowCallback) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            warn!("RiverBackend already running");
+            return;
+        }
+
+        debug!("Starting RiverBackend");
+
+        let Ok(connection) = Connection::connect_to_env() else {
+            error!("Failed to connect to Wayland display");
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        let event_queue: EventQueue<WaylandState> = connection.new_event_queue();
+        let qh = event_queue.handle();
+
+        let mut state = WaylandState::new(self.shared.clone());
+        state.on_workspace_update = Some(on_workspace_update);
+        state.on_window_update = Some(on_window_update);
+
+        let _registry = connection.display().get_registry(&qh, ());
+
+        let event_queue = Rc::new(RefCell::new(event_queue));
+        let state = Rc::new(RefCell::new(state));
+
+        {
+            let mut eq = event_queue.borrow_mut();
+            let mut st = state.borrow_mut();
+
+            // Roundtrip to bind globals
+            if let Err(e) = eq.roundtrip(&mut *st) {
+                error!("Wayland roundtrip failed: {}", e);
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            if st.status_manager.is_none() {
+                error!("River status manager not found - is this River?");
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+            if st.control.is_none() {
+                warn!("River control not found - workspace switching is unavailable");
+            }
+
+            // Another roundtrip for output names and initial status events
+            if let Err(e) = eq.roundtrip(&mut *st) {
+                error!("Wayland roundtrip failed: {}", e);
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            debug!("River status ready: {} outputs", st.outputs.len());
+        }
+
+        // Watch the eventfd to send queued commands without polling.
+        let state_for_wake = state.clone();
+        let event_queue_for_wake = event_queue.clone();
+        let shared_for_wake = self.shared.clone();
+
+        let Some(wake_source_id) = self.shared.commands.watch(move || {
+            if shared_for_wake.stopped.load(Ordering::Relaxed) {
+                return glib::ControlFlow::Break;
+            }
+
+            let eq = event_queue_for_wake.borrow();
+            state_for_wake
+                .borrow()
+                .process_pending_commands(&eq.handle());
+            let _ = eq.flush();
+
+            glib::ControlFlow::Continue
+        }) else {
+            self.running.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        let eq_fd = event_queue.borrow().as_fd().as_raw_fd();
+        let shared_for_loop = self.shared.clone();
+        let event_queue_for_fd = event_queue.clone();
+        let state_for_fd = state.clone();
+
+        let fd_source_id =
+            glib::unix_fd_add_local(eq_fd, glib::IOCondition::IN, move |_fd, _condition| {
+                let mut eq = event_queue_for_fd.borrow_mut();
+                let mut st = state_for_fd.borrow_mut();
+
+                if let Err(e) = eq.dispatch_pending(&mut *st) {
+                    error!("Wayland dispatch error: {}", e);
+                    return glib::ControlFlow::Break;
+                }
+
+                if let Some(guard) = eq.prepare_read() {
+                    match guard.read() {
+                        Ok(_) => {
+                            let _ = eq.dispatch_pending(&mut *st);
+                        }
+                        Err(wayland_client::backend::WaylandError::Io(io_err)) => {
+                            if io_err.kind() != std::io::ErrorKind::WouldBlock {
+                                error!("Wayland read error: {}", io_err);
+                            }
+                        }
+                        Err(e) => {
+                            error!("Wayland error: {}", e);
+                        }
+                    }
+                }
+
+                let _ = eq.flush();
+
+                if shared_for_loop.stopped.load(Ordering::Relaxed) {
+                    return glib::ControlFlow::Break;
+                }
+
+                glib::ControlFlow::Continue
+            });
+
+        self.source_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend([fd_source_id, wake_source_id]);
+
+        debug!("RiverBackend started");
+    }
+
+    fn stop(&self) {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        debug!("Stopping RiverBackend");
+
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        self.shared.commands.wake();
+
+        for source_id in self
+            .source_ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+        {
+            source_id.remove();
+        }
+
+        self.shared.commands.close();
+
+        debug!("RiverBackend stopped");
+    }
+
+    fn list_workspaces(&self) -> Vec<WorkspaceMeta> {
+        (1..=WORKSPACE_COUNT)
+            .map(|id| WorkspaceMeta {
+                id,
+                name: id.to_string(),
+                output: None, // River tags are global
+            })
+            .collect()
+    }
+
+    fn get_workspace_snapshot(&self) -> WorkspaceSnapshot {
+        self.shared.snapshot.read().clone()
+    }
+
+    fn get_focused_window(&self) -> Option<WindowInfo> {
+        self.shared.focused_window.read().clone()
+    }
+
+    fn switch_workspace(&self, workspace_id: i32) {
+        debug!("Requesting switch to tag {}", workspace_id);
+        if let Some(args) = tag_command("set-focused-tags", workspace_id) {
+            self.queue_command(args);
+        }
+    }
+
+    fn toggle_workspace(&self, workspace_id: i32) {
+        debug!("Requesting toggle of tag {}", workspace_id);
+        if let Some(args) = tag_command("toggle-focused-tags", workspace_id) {
+            self.queue_command(args);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "River"
+    }
+
+    fn quit_compositor(&self) {
+        debug!("Requesting compositor quit");
+        self.queue_command(vec!["exit".to_string()]);
+    }
+}
+
+impl Drop for RiverBackend {
+    fn drop(&mut self) {
+        // Signal stop but don't call stop() directly (may already be stopped)
+        self.running.store(false, Ordering::SeqCst);
+        self.shared.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_ids() {
+        assert_eq!(tag_ids(0).count(), 0);
+        assert_eq!(tag_ids(0b101).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(tag_ids(1 << 31).collect::<Vec<_>>(), vec![32]);
+    }
+
+    #[test]
+    fn test_parse_view_tags() {
+        let bytes: Vec<u8> = [1u32, 0b110]
+            .iter()
+            .flat_map(|tags| tags.to_ne_bytes())
+            .collect();
+        assert_eq!(parse_view_tags(&bytes), vec![1, 0b110]);
+        assert!(parse_view_tags(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_build_snapshot() {
+        let left = OutputTags {
+            focused: 0b1,
+            urgent: 0,
+            views: vec![0b1, 0b1, 0b100],
+        };
+        let right = OutputTags {
+            focused: 0b10,
+            urgent: 0b1000,
+            views: vec![0b11],
+        };
+        let snapshot = build_snapshot(
+            [("DP-1", &left), ("DP-2", &right)].into_iter(),
+            Some("DP-2"),
+        );
+
+        assert_eq!(snapshot.active_workspace, [2].into());
+        assert_eq!(snapshot.occupied_workspaces, [1, 2, 3].into());
+        assert_eq!(snapshot.urgent_workspaces, [4].into());
+        assert_eq!(snapshot.window_counts[&1], 3);
+        assert_eq!(snapshot.per_output["DP-1"].window_counts[&1], 2);
+        assert_eq!(snapshot.per_output["DP-1"].active_workspace, [1].into());
+    }
+
+    #[test]
+    fn test_tag_command() {
+        assert_eq!(
+            tag_command("set-focused-tags", 3),
+            Some(vec!["set-focused-tags".to_string(), "4".to_string()])
+        );
+        assert_eq!(
+            tag_command("toggle-focused-tags", 32),
+            Some(vec![
+                "toggle-focused-tags".to_string(),
+                "2147483648".to_string()
+            ])
+        );
+        assert!(tag_command("set-focused-tags", 0).is_none());
+        assert!(tag_command("set-focused-tags", 33).is_none());
+    }
+}
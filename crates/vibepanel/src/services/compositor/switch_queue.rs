@@ -0,0 +1,121 @@
+//! Workspace switches handed from any thread to a Wayland backend's main loop.
+//!
+//! Wayland backends own their proxies on the GTK main loop, while
+//! `switch_workspace` may be called from anywhere. [`SwitchQueue`] stores
+//! the switch and wakes an eventfd watched on the main loop, which sends
+//! it. Shared by the generic, COSMIC, KWin and River backends; River queues
+//! its control commands instead of workspace ids.
+
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::Mutex;
+
+use gtk4::glib;
+use tracing::error;
+
+/// Workspace switches requested from any thread, sent from the main loop.
+///
+/// `request` stores the switch and writes an eventfd; the watch installed by
+/// `watch` hands it to the Wayland state, which owns the workspace handles.
+#[derive(Debug)]
+pub(super) struct SwitchQueue<T = i32> {
+    /// Requests to send on the next wake, oldest first.
+    pending: Mutex<Vec<T>>,
+    /// Eventfd used to wake the main-loop watcher.
+    wake_fd: Mutex<Option<OwnedFd>>,
+}
+
+impl<T> Default for SwitchQueue<T> {
+    fn default() -> Self {
+        Self {
+            pending: Mutex::new(Vec::new()),
+            wake_fd: Mutex::new(None),
+        }
+    }
+}
+
+impl<T> SwitchQueue<T> {
+    /// Queue a switch (e.g. to a workspace id), replacing any unsent ones.
+    pub(super) fn request(&self, request: T) {
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = vec![request];
+        self.wake();
+    }
+
+    /// Queue `request` after any unsent ones.
+    pub(super) fn push(&self, request: T) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(request);
+        self.wake();
+    }
+
+    /// Take the latest queued request, dropping older ones.
+    pub(super) fn take(&self) -> Option<T> {
+        self.drain().pop()
+    }
+
+    /// Take all queued requests, oldest first.
+    pub(super) fn drain(&self) -> Vec<T> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Wake the eventfd watcher.
+    pub(super) fn wake(&self) {
+        if let Some(wake_fd) = self
+            .wake_fd
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            // SAFETY: wake_fd is valid (held by Mutex), writing 8-byte u64 with correct alignment.
+            let val: u64 = 1;
+            unsafe {
+                libc::write(
+                    wake_fd.as_raw_fd(),
+                    &val as *const u64 as *const libc::c_void,
+                    8,
+                );
+            }
+        }
+    }
+
+    /// Create the eventfd and call `on_wake` on the main loop whenever it fires.
+    ///
+    /// `on_wake` returns whether to keep watching. Returns `None` if the
+    /// eventfd couldn't be created.
+    pub(super) fn watch(
+        &self,
+        mut on_wake: impl FnMut() -> glib::ControlFlow + 'static,
+    ) -> Option<glib::SourceId> {
+        // SAFETY: eventfd() is a safe syscall that returns a valid fd or -1 on error.
+        let wake_fd_raw: RawFd =
+            unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if wake_fd_raw < 0 {
+            error!(
+                "Failed to create eventfd: {}",
+                std::io::Error::last_os_error()
+            );
+            return None;
+        }
+        // SAFETY: wake_fd_raw >= 0 (checked above), so it's a valid fd. OwnedFd takes ownership.
+        let wake_fd = unsafe { OwnedFd::from_raw_fd(wake_fd_raw) };
+        *self.wake_fd.lock().unwrap_or_else(|e| e.into_inner()) = Some(wake_fd);
+
+        Some(glib::unix_fd_add_local(
+            wake_fd_raw,
+            glib::IOCondition::IN,
+            move |fd, _condition| {
+                // SAFETY: fd is a valid eventfd from glib callback. Reading 8 bytes (u64 counter)
+                // into correctly-sized buffer. Return value ignored - we just need to reset it.
+                let mut buf = [0u8; 8];
+                unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, 8) };
+                on_wake()
+            },
+        ))
+    }
+
+    /// Close the eventfd once its watch is removed.
+    pub(super) fn close(&self) {
+        *self.wake_fd.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
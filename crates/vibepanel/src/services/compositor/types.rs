@@ -44,7 +44,7 @@ pub struct WorkspaceMeta {
 /// This contains workspace state specific to a single output/monitor,
 /// used for compositors where workspace state varies per-output (like
 /// MangoWC's per-output window counts, or Niri's per-monitor workspaces).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct PerOutputState {
     /// Active workspace IDs on this output.
     /// Most compositors have a single active workspace, but MangoWC/DWL
@@ -56,11 +56,23 @@ pub struct PerOutputState {
     pub window_counts: HashMap<i32, u32>,
 }
 
+/// Size of a workspace grid, for compositors that lay workspaces out in
+/// rows and columns (like Wayfire).
+///
+/// Workspace IDs count row by row from the top-left cell, starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkspaceGrid {
+    /// Number of workspaces per row.
+    pub columns: u32,
+    /// Number of rows.
+    pub rows: u32,
+}
+
 /// Point-in-time snapshot of workspace state.
 ///
 /// This represents the current state across all workspaces,
 /// updated atomically when the compositor signals changes.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct WorkspaceSnapshot {
     /// Currently active/focused workspace IDs.
     /// Most compositors have a single active workspace, but MangoWC/DWL
@@ -76,6 +88,9 @@ pub struct WorkspaceSnapshot {
     /// Per-output workspace state for multi-monitor setups.
     /// Key is the output/monitor connector name (e.g., "eDP-1", "DP-1").
     pub per_output: HashMap<String, PerOutputState>,
+    /// Workspace grid size, for compositors with a grid layout.
+    /// None for compositors with a flat list of workspaces.
+    pub grid: Option<WorkspaceGrid>,
 }
 
 /// Information about a focused window.
@@ -91,6 +106,8 @@ pub struct WindowInfo {
     pub workspace_id: Option<i32>,
     /// Output/monitor name the window is on (None if unavailable).
     pub output: Option<String>,
+    /// Whether the window is fullscreen (false if the backend can't tell).
+    pub fullscreen: bool,
 }
 
 impl WindowInfo {
@@ -101,6 +118,19 @@ impl WindowInfo {
     }
 }
 
+/// An open window and the process that owns it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowProcess {
+    /// Process ID of the window's client.
+    pub pid: u32,
+    /// Window title (may be empty).
+    pub title: String,
+    /// Application ID (e.g., "firefox", "org.gnome.Nautilus").
+    pub app_id: String,
+    /// Workspace ID the window is on (None if unavailable).
+    pub workspace_id: Option<i32>,
+}
+
 /// Callback type for workspace state updates.
 pub type WorkspaceCallback = Arc<dyn Fn(WorkspaceSnapshot) + Send + Sync>;
 
@@ -164,12 +194,28 @@ pub trait CompositorBackend: Send + Sync {
     /// no window is focused or state is unknown.
     fn get_focused_window(&self) -> Option<WindowInfo>;
 
+    /// List open windows with the process IDs of their clients.
+    ///
+    /// Used to find the windows producing audio. Default implementation
+    /// returns nothing, for compositors whose IPC doesn't expose PIDs.
+    fn list_windows(&self) -> Vec<WindowProcess> {
+        Vec::new()
+    }
+
     /// Switch to a workspace.
     ///
     /// Requests the compositor to activate the specified workspace.
     /// This is typically called in response to user interaction.
     fn switch_workspace(&self, workspace_id: i32);
 
+    /// Show or hide a workspace alongside the visible ones.
+    ///
+    /// For compositors that can view several tags at once. Default
+    /// implementation switches to the workspace.
+    fn toggle_workspace(&self, workspace_id: i32) {
+        self.switch_workspace(workspace_id);
+    }
+
     /// Get the backend's name for debugging.
     fn name(&self) -> &'static str;
 
@@ -181,6 +227,22 @@ pub trait CompositorBackend: Send + Sync {
     fn quit_compositor(&self) {
         // Default no-op
     }
+
+    /// Names of the touchpads the compositor can enable and disable.
+    ///
+    /// Default implementation returns nothing, for compositors whose IPC
+    /// can't toggle input devices.
+    fn list_touchpads(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Enable or disable an input device by name.
+    ///
+    /// Returns whether the compositor accepted the change. Default
+    /// implementation is unsupported.
+    fn set_input_device_enabled(&self, _name: &str, _enabled: bool) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,811 @@
+//! Wayfire compositor backend using the IPC plugin's socket.
+//!
+//! This backend communicates with Wayfire via the Unix socket at
+//! $WAYFIRE_SOCKET, which the `ipc` plugin provides. The `ipc-rules` plugin
+//! must be enabled for window and output queries, and `vswitch` for
+//! switching workspaces.
+//!
+//! Protocol: every message is a JSON object prefixed with its length as a
+//! 32-bit little-endian integer. Requests look like
+//! `{"method": "window-rules/list-views", "data": {}}`. After a
+//! `window-rules/events/watch` request, the same connection streams events.
+//!
+//! # Workspace grid
+//!
+//! Wayfire arranges each output's workspaces in a grid. Cell (x, y) is
+//! shown as workspace `y * columns + x + 1`, and the grid size is reported
+//! in `WorkspaceSnapshot::grid` so the workspaces widget can lay out the
+//! cells as they are on screen. A view is on the cell containing its
+//! center; view geometry is relative to the output's current workspace.
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use serde_json::{Value, json};
+use tracing::{debug, error, trace, warn};
+
+use super::{
+    CompositorBackend, WindowCallback, WindowInfo, WindowProcess, WorkspaceCallback, WorkspaceGrid,
+    WorkspaceMeta, WorkspaceSnapshot,
+};
+
+const RECONNECT_INITIAL_MS: u64 = 1000;
+const RECONNECT_MAX_MS: u64 = 30000;
+const RECONNECT_MULTIPLIER: f64 = 1.5;
+
+/// Environment variable Wayfire's IPC plugin sets to its socket path.
+const SOCKET_ENV: &str = "WAYFIRE_SOCKET";
+
+/// Wayfire's default workspace grid, used until outputs are known.
+const DEFAULT_GRID: WorkspaceGrid = WorkspaceGrid {
+    columns: 3,
+    rows: 3,
+};
+
+/// Events subscribed to on the event stream.
+const WATCHED_EVENTS: &[&str] = &[
+    "view-mapped",
+    "view-unmapped",
+    "view-focused",
+    "view-title-changed",
+    "view-app-id-changed",
+    "view-fullscreen",
+    "view-geometry-changed",
+    "view-set-output",
+    "view-workspace-changed",
+    "wset-workspace-changed",
+    "output-gain-focus",
+    "output-added",
+    "output-removed",
+];
+
+/// An output and the state of its workspace grid.
+#[derive(Debug, Clone, PartialEq)]
+struct OutputData {
+    id: u64,
+    name: String,
+    width: i32,
+    height: i32,
+    /// Current workspace cell.
+    x: i32,
+    y: i32,
+    grid: WorkspaceGrid,
+}
+
+impl OutputData {
+    /// Workspace ID of cell (x, y), clamped to the grid.
+    fn workspace_id(&self, x: i32, y: i32) -> i32 {
+        let columns = self.grid.columns as i32;
+        let x = x.clamp(0, columns - 1);
+        let y = y.clamp(0, self.grid.rows as i32 - 1);
+        y * columns + x + 1
+    }
+
+    /// Workspace ID of the current cell.
+    fn current_workspace(&self) -> i32 {
+        self.workspace_id(self.x, self.y)
+    }
+
+    /// Workspace ID of the cell containing a view's center.
+    fn view_workspace(&self, view: &ViewData) -> i32 {
+        let center_x = view.x + view.width / 2;
+        let center_y = view.y + view.height / 2;
+        self.workspace_id(
+            self.x + center_x.div_euclid(self.width.max(1)),
+            self.y + center_y.div_euclid(self.height.max(1)),
+        )
+    }
+}
+
+/// A mapped toplevel view.
+#[derive(Debug, Clone)]
+struct ViewData {
+    id: u64,
+    pid: Option<u32>,
+    title: String,
+    app_id: String,
+    output_id: Option<u64>,
+    /// Geometry relative to the output's current workspace.
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    fullscreen: bool,
+}
+
+fn int_field(value: &Value, key: &str) -> i32 {
+    value.get(key).and_then(|v| v.as_i64()).unwrap_or(0) as i32
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Parse an output from `list-outputs` or an event.
+fn parse_output(output: &Value) -> Option<OutputData> {
+    let id = output.get("id")?.as_u64()?;
+    let geometry = output.get("geometry")?;
+    let workspace = output.get("workspace")?;
+    let grid = WorkspaceGrid {
+        columns: int_field(workspace, "grid_width").max(1) as u32,
+        rows: int_field(workspace, "grid_height").max(1) as u32,
+    };
+    Some(OutputData {
+        id,
+        name: str_field(output, "name"),
+        width: int_field(geometry, "width"),
+        height: int_field(geometry, "height"),
+        x: int_field(workspace, "x"),
+        y: int_field(workspace, "y"),
+        grid,
+    })
+}
+
+/// Parse a view from `list-views` or an event, skipping anything that isn't
+/// a mapped toplevel (panels, backgrounds, popups).
+fn parse_view(view: &Value) -> Option<ViewData> {
+    let id = view.get("id")?.as_u64()?;
+    let role = view
+        .get("role")
+        .or_else(|| view.get("type"))
+        .and_then(|v| v.as_str());
+    let mapped = view.get("mapped").and_then(|v| v.as_bool()).unwrap_or(true);
+    if role != Some("toplevel") || !mapped {
+        return None;
+    }
+
+    let geometry = view.get("geometry").cloned().unwrap_or_default();
+    Some(ViewData {
+        id,
+        pid: view
+            .get("pid")
+            .and_then(|v| v.as_i64())
+            .and_then(|pid| u32::try_from(pid).ok()),
+        title: str_field(view, "title"),
+        app_id: str_field(view, "app-id"),
+        output_id: view.get("output-id").and_then(|v| v.as_u64()),
+        x: int_field(&geometry, "x"),
+        y: int_field(&geometry, "y"),
+        width: int_field(&geometry, "width"),
+        height: int_field(&geometry, "height"),
+        fullscreen: view
+            .get("fullscreen")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+/// Build a workspace snapshot from the outputs and the views on them.
+fn build_snapshot<'a>(
+    outputs: &[OutputData],
+    views: impl Iterator<Item = &'a ViewData>,
+    focused_output: Option<u64>,
+) -> WorkspaceSnapshot {
+    let mut snapshot = WorkspaceSnapshot::default();
+    let focused = outputs
+        .iter()
+        .find(|output| Some(output.id) == focused_output)
+        .or_else(|| outputs.first());
+    snapshot.grid = focused.map(|output| output.grid);
+
+    for output in outputs {
+        let per_output = snapshot.per_output.entry(output.name.clone()).or_default();
+        per_output
+            .active_workspace
+            .insert(output.current_workspace());
+        if focused.is_some_and(|focused| focused.id == output.id) {
+            snapshot.active_workspace = per_output.active_workspace.clone();
+        }
+    }
+
+    for view in views {
+        let Some(output) = outputs
+            .iter()
+            .find(|output| Some(output.id) == view.output_id)
+        else {
+            continue;
+        };
+        let id = output.view_workspace(view);
+        let per_output = snapshot.per_output.entry(output.name.clone()).or_default();
+        *per_output.window_counts.entry(id).or_insert(0) += 1;
+        per_output.occupied_workspaces.insert(id);
+        *snapshot.window_counts.entry(id).or_insert(0) += 1;
+        snapshot.occupied_workspaces.insert(id);
+    }
+
+    snapshot
+}
+
+/// Frame a JSON message with its little-endian length.
+fn encode_message(message: &Value) -> Vec<u8> {
+    let body = message.to_string().into_bytes();
+    let mut bytes = (body.len() as u32).to_le_bytes().to_vec();
+    bytes.extend(body);
+    bytes
+}
+
+/// Fill `buf`, waiting through read timeouts once data has started to
+/// arrive. Returns `Ok(false)` if the read timed out before any data.
+fn read_full(stream: &mut UnixStream, buf: &mut [u8], allow_idle: bool) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match stream.read(&mut buf[filled..]) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e)
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut =>
+            {
+                if filled == 0 && allow_idle {
+                    return Ok(false);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+/// Read one message. Returns `Ok(None)` if the read timed out before one
+/// started.
+fn read_message(stream: &mut UnixStream) -> io::Result<Option<Value>> {
+    let mut header = [0u8; 4];
+    if !read_full(stream, &mut header, true)? {
+        return Ok(None);
+    }
+    let mut body = vec![0u8; u32::from_le_bytes(header) as usize];
+    read_full(stream, &mut body, false)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[derive(Default)]
+struct SharedState {
+    outputs: RwLock<Vec<OutputData>>,
+    views: RwLock<HashMap<u64, ViewData>>,
+    focused_output: RwLock<Option<u64>>,
+    focused_view: RwLock<Option<u64>>,
+    snapshot: RwLock<WorkspaceSnapshot>,
+}
+
+impl SharedState {
+    /// Rebuild the workspace snapshot. Returns true if it changed.
+    fn rebuild_snapshot(&self) -> bool {
+        let snapshot = build_snapshot(
+            &self.outputs.read(),
+            self.views.read().values(),
+            *self.focused_output.read(),
+        );
+        let mut current = self.snapshot.write();
+        let changed = *current != snapshot;
+        *current = snapshot;
+        changed
+    }
+
+    /// The focused view, or empty info on the focused output if none.
+    fn focused_window(&self) -> WindowInfo {
+        let outputs = self.outputs.read();
+        let views = self.views.read();
+        let focused_output = *self.focused_output.read();
+
+        let Some(view) = self.focused_view.read().and_then(|id| views.get(&id)) else {
+            return WindowInfo {
+                output: outputs
+                    .iter()
+                    .find(|output| Some(output.id) == focused_output)
+                    .map(|output| output.name.clone()),
+                ..Default::default()
+            };
+        };
+        let output = outputs
+            .iter()
+            .find(|output| Some(output.id) == view.output_id);
+        WindowInfo {
+            title: view.title.clone(),
+            app_id: view.app_id.clone(),
+            workspace_id: output.map(|output| output.view_workspace(view)),
+            output: output.map(|output| output.name.clone()),
+            fullscreen: view.fullscreen,
+        }
+    }
+}
+
+pub struct WayfireBackend {
+    #[allow(dead_code)] // For future filtering support
+    allowed_outputs: Vec<String>,
+    running: Arc<AtomicBool>,
+    event_thread: Mutex<Option<JoinHandle<()>>>,
+    socket_path: RwLock<Option<String>>,
+    shared: Arc<SharedState>,
+}
+
+impl WayfireBackend {
+    pub fn new(outputs: Option<Vec<String>>) -> Self {
+        Self {
+            allowed_outputs: outputs.unwrap_or_default(),
+            running: Arc::new(AtomicBool::new(false)),
+            event_thread: Mutex::new(None),
+            socket_path: RwLock::new(None),
+            shared: Arc::new(SharedState::default()),
+        }
+    }
+
+    /// Send a request to Wayfire and get the response.
+    fn send_request(socket_path: &str, method: &str, data: Value) -> Option<Value> {
+        let mut stream = match UnixStream::connect(socket_path) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Failed to connect to Wayfire socket: {}", e);
+                return None;
+            }
+        };
+
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(2)));
+
+        let request = json!({ "method": method, "data": data });
+        if let Err(e) = stream.write_all(&encode_message(&request)) {
+            error!("Failed to send request to Wayfire: {}", e);
+            return None;
+        }
+
+        let response = match read_message(&mut stream) {
+            Ok(Some(response)) => response,
+            Ok(None) => {
+                error!("Timed out waiting for Wayfire response to {}", method);
+                return None;
+            }
+            Err(e) => {
+                error!("Failed to read Wayfire response: {}", e);
+                return None;
+            }
+        };
+
+        if let Some(message) = response.get("error").and_then(|v| v.as_str()) {
+            warn!("Wayfire request {} failed: {}", method, message);
+            return None;
+        }
+        Some(response)
+    }
+
+    /// Re-read all outputs and views.
+    fn fetch_state(socket_path: &str, shared: &SharedState) {
+        if let Some(outputs) =
+            Self::send_request(socket_path, "window-rules/list-outputs", json!({}))
+                .as_ref()
+                .and_then(|v| v.as_array())
+        {
+            *shared.outputs.write() = outputs.iter().filter_map(parse_output).collect();
+        }
+
+        if let Some(views) = Self::send_request(socket_path, "window-rules/list-views", json!({}))
+            .as_ref()
+            .and_then(|v| v.as_array())
+        {
+            *shared.views.write() = views
+                .iter()
+                .filter_map(parse_view)
+                .map(|view| (view.id, view))
+                .collect();
+        }
+
+        if let Some(response) =
+            Self::send_request(socket_path, "window-rules/get-focused-output", json!({}))
+        {
+            *shared.focused_output.write() = response
+                .get("info")
+                .and_then(|info| info.get("id"))
+                .and_then(|v| v.as_u64());
+        }
+
+        if let Some(response) =
+            Self::send_request(socket_path, "window-rules/get-focused-view", json!({}))
+        {
+            *shared.focused_view.write() = response
+                .get("info")
+                .and_then(parse_view)
+                .map(|view| view.id);
+        }
+
+        shared.rebuild_snapshot();
+    }
+
+    /// Handle an event from the stream.
+    ///
+    /// Returns `(workspace_changed, window_changed)`.
+    fn handle_event(socket_path: &str, shared: &SharedState, event: &Value) -> (bool, bool) {
+        let Some(name) = event.get("event").and_then(|v| v.as_str()) else {
+            return (false, false);
+        };
+        trace!("Wayfire event: {}", name);
+
+        let view = event.get("view");
+        let view_id = view
+            .and_then(|view| view.get("id"))
+            .and_then(|v| v.as_u64());
+        let is_focused = view_id.is_some() && *shared.focused_view.read() == view_id;
+
+        match name {
+            "view-focused" => {
+                let view = view.and_then(parse_view);
+                *shared.focused_view.write() = view.as_ref().map(|view| view.id);
+                if let Some(view) = view {
+                    shared.views.write().insert(view.id, view);
+                }
+                (shared.rebuild_snapshot(), true)
+            }
+            "view-unmapped" => {
+                if let Some(id) = view_id {
+                    shared.views.write().remove(&id);
+                }
+                if is_focused {
+                    *shared.focused_view.write() = None;
+                }
+                (shared.rebuild_snapshot(), is_focused)
+            }
+            "view-mapped"
+            | "view-title-changed"
+            | "view-app-id-changed"
+            | "view-fullscreen"
+            | "view-geometry-changed"
+            | "view-set-output"
+            | "view-workspace-changed" => {
+                if let Some(view) = view.and_then(parse_view) {
+                    shared.views.write().insert(view.id, view);
+                }
+                (shared.rebuild_snapshot(), is_focused)
+            }
+            // Switching workspaces moves every view, so re-read everything
+            "wset-workspace-changed" | "output-gain-focus" | "output-added" | "output-removed" => {
+                Self::fetch_state(socket_path, shared);
+                (true, true)
+            }
+            _ => (false, false),
+        }
+    }
+
+    /// Run the event loop (in background thread).
+    fn event_loop(
+        running: Arc<AtomicBool>,
+        shared: Arc<SharedState>,
+        socket_path: String,
+        callbacks: (WorkspaceCallback, WindowCallback),
+    ) {
+        let (ws_cb, win_cb) = callbacks;
+
+        // Exponential backoff state
+        let mut backoff_ms = RECONNECT_INITIAL_MS;
+
+        while running.load(Ordering::SeqCst) {
+            let watch = json!({
+                "method": "window-rules/events/watch",
+                "data": { "events": WATCHED_EVENTS },
+            });
+            let stream = UnixStream::connect(&socket_path).and_then(|mut stream| {
+                stream.write_all(&encode_message(&watch))?;
+                Ok(stream)
+            });
+            let mut stream = match stream {
+                Ok(s) => {
+                    // Reset backoff on successful connection
+                    backoff_ms = RECONNECT_INITIAL_MS;
+                    s
+                }
+                Err(e) => {
+                    if running.load(Ordering::SeqCst) {
+                        warn!(
+                            "Failed to watch Wayfire events: {}. Retrying in {}ms",
+                            e, backoff_ms
+                        );
+                        thread::sleep(Duration::from_millis(backoff_ms));
+                        // Exponential backoff with cap
+                        backoff_ms = ((backoff_ms as f64) * RECONNECT_MULTIPLIER)
+                            .min(RECONNECT_MAX_MS as f64)
+                            as u64;
+                    }
+                    continue;
+                }
+            };
+
+            // Fetch state after subscribing so no change falls in between
+            Self::fetch_state(&socket_path, &shared);
+            ws_cb(shared.snapshot.read().clone());
+            win_cb(shared.focused_window());
+
+            // Set read timeout for graceful shutdown
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+
+            while running.load(Ordering::SeqCst) {
+                let event = match read_message(&mut stream) {
+                    Ok(Some(event)) => event,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        if running.load(Ordering::SeqCst) {
+                            error!("Error reading from Wayfire socket: {}", e);
+                        }
+                        break;
+                    }
+                };
+
+                let (ws_changed, win_changed) = Self::handle_event(&socket_path, &shared, &event);
+                if ws_changed {
+                    ws_cb(shared.snapshot.read().clone());
+                }
+                if win_changed {
+                    win_cb(shared.focused_window());
+                }
+            }
+        }
+
+        debug!("Wayfire event loop exiting");
+    }
+}
+
+impl CompositorBackend for WayfireBackend {
+    fn start(&self, on_workspace_update: WorkspaceCallback, on_window_update: WindowCallback) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            warn!("WayfireBackend already running");
+            return;
+        }
+
+        debug!("Starting WayfireBackend");
+
+        // Store the socket path on `self` first so switch_workspace() works
+        let socket_path = match env::var(SOCKET_ENV) {
+            Ok(p) => p,
+            Err(_) => {
+                warn!("{} not set - is Wayfire's ipc plugin enabled?", SOCKET_ENV);
+                self.running.store(false, Ordering::SeqCst);
+                return;
+            }
+        };
+        *self.socket_path.write() = Some(socket_path.clone());
+
+        let running = Arc::clone(&self.running);
+        let shared = Arc::clone(&self.shared);
+        let callbacks = (on_workspace_update, on_window_update);
+
+        let handle = thread::Builder::new()
+            .name("wayfire-event-loop".into())
+            .spawn(move || {
+                Self::event_loop(running, shared, socket_path, callbacks);
+            })
+            .ok();
+
+        *self.event_thread.lock().unwrap_or_else(|e| e.into_inner()) = handle;
+
+        debug!("WayfireBackend started");
+    }
+
+    fn stop(&self) {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        debug!("Stopping WayfireBackend");
+
+        if let Some(handle) = self
+            .event_thread
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            let _ = handle.join();
+        }
+
+        debug!("WayfireBackend stopped");
+    }
+
+    fn list_workspaces(&self) -> Vec<WorkspaceMeta> {
+        let grid = self.shared.snapshot.read().grid.unwrap_or(DEFAULT_GRID);
+        (1..=(grid.columns * grid.rows) as i32)
+            .map(|id| WorkspaceMeta {
+                id,
+                name: id.to_string(),
+                // Every output has its own grid, but they share numbering
+                output: None,
+            })
+            .collect()
+    }
+
+    fn get_workspace_snapshot(&self) -> WorkspaceSnapshot {
+        self.shared.snapshot.read().clone()
+    }
+
+    fn get_focused_window(&self) -> Option<WindowInfo> {
+        Some(self.shared.focused_window())
+    }
+
+    fn list_windows(&self) -> Vec<WindowProcess> {
+        let outputs = self.shared.outputs.read();
+        self.shared
+            .views
+            .read()
+            .values()
+            .filter_map(|view| {
+                Some(WindowProcess {
+                    pid: view.pid?,
+                    title: view.title.clone(),
+                    app_id: view.app_id.clone(),
+                    workspace_id: outputs
+                        .iter()
+                        .find(|output| Some(output.id) == view.output_id)
+                        .map(|output| output.view_workspace(view)),
+                })
+            })
+            .collect()
+    }
+
+    fn switch_workspace(&self, workspace_id: i32) {
+        let Some(socket_path) = self.socket_path.read().clone() else {
+            return;
+        };
+        let output = {
+            let outputs = self.shared.outputs.read();
+            let focused_output = *self.shared.focused_output.read();
+            outputs
+                .iter()
+                .find(|output| Some(output.id) == focused_output)
+                .or_else(|| outputs.first())
+                .cloned()
+        };
+        let Some(output) = output else {
+            return;
+        };
+
+        let columns = output.grid.columns as i32;
+        if !(1..=columns * output.grid.rows as i32).contains(&workspace_id) {
+            return;
+        }
+        let index = workspace_id - 1;
+        debug!("Requesting switch to workspace {}", workspace_id);
+        let _ = Self::send_request(
+            &socket_path,
+            "vswitch/set-workspace",
+            json!({ "x": index % columns, "y": index / columns, "output-id": output.id }),
+        );
+    }
+
+    fn name(&self) -> &'static str {
+        "Wayfire"
+    }
+}
+
+impl Drop for WayfireBackend {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(id: u64, name: &str, x: i32, y: i32) -> OutputData {
+        OutputData {
+            id,
+            name: name.to_string(),
+            width: 1920,
+            height: 1080,
+            x,
+            y,
+            grid: WorkspaceGrid {
+                columns: 3,
+                rows: 2,
+            },
+        }
+    }
+
+    fn view(id: u64, output_id: u64, x: i32, y: i32) -> ViewData {
+        ViewData {
+            id,
+            pid: Some(100 + id as u32),
+            title: format!("view {}", id),
+            app_id: "foot".to_string(),
+            output_id: Some(output_id),
+            x,
+            y,
+            width: 800,
+            height: 600,
+            fullscreen: false,
+        }
+    }
+
+    #[test]
+    fn test_workspace_ids() {
+        let out = output(1, "DP-1", 1, 1);
+        assert_eq!(out.current_workspace(), 5);
+        assert_eq!(out.workspace_id(0, 0), 1);
+        assert_eq!(out.workspace_id(2, 0), 3);
+        // Out-of-grid cells clamp to the edge
+        assert_eq!(out.workspace_id(5, -1), 3);
+
+        // On the current workspace, one to the right, one up-left
+        assert_eq!(out.view_workspace(&view(1, 1, 100, 100)), 5);
+        assert_eq!(out.view_workspace(&view(2, 1, 2000, 100)), 6);
+        assert_eq!(out.view_workspace(&view(3, 1, -1500, -900)), 1);
+    }
+
+    #[test]
+    fn test_parse_output_and_view() {
+        let out = parse_output(&json!({
+            "id": 2,
+            "name": "HDMI-A-1",
+            "geometry": { "x": 0, "y": 0, "width": 2560, "height": 1440 },
+            "workspace": { "x": 2, "y": 0, "grid_width": 4, "grid_height": 1 },
+        }))
+        .unwrap();
+        assert_eq!(out.name, "HDMI-A-1");
+        assert_eq!(out.current_workspace(), 3);
+        assert_eq!(
+            out.grid,
+            WorkspaceGrid {
+                columns: 4,
+                rows: 1
+            }
+        );
+
+        let parsed = parse_view(&json!({
+            "id": 7,
+            "pid": 4242,
+            "title": "notes.md",
+            "app-id": "org.gnome.TextEditor",
+            "role": "toplevel",
+            "mapped": true,
+            "output-id": 2,
+            "fullscreen": true,
+            "geometry": { "x": 10, "y": 20, "width": 300, "height": 200 },
+        }))
+        .unwrap();
+        assert_eq!(parsed.pid, Some(4242));
+        assert_eq!(parsed.app_id, "org.gnome.TextEditor");
+        assert!(parsed.fullscreen);
+
+        assert!(parse_view(&json!({ "id": 8, "role": "desktop-environment" })).is_none());
+        assert!(parse_view(&json!({ "id": 9, "role": "toplevel", "mapped": false })).is_none());
+    }
+
+    #[test]
+    fn test_build_snapshot() {
+        let outputs = [output(1, "DP-1", 0, 0), output(2, "DP-2", 2, 1)];
+        let views = [
+            view(1, 1, 100, 100),
+            view(2, 1, 200, 200),
+            view(3, 1, 2000, 100),
+            view(4, 2, 100, 100),
+        ];
+        let snapshot = build_snapshot(&outputs, views.iter(), Some(2));
+
+        assert_eq!(snapshot.active_workspace, [6].into());
+        assert_eq!(snapshot.occupied_workspaces, [1, 2, 6].into());
+        assert_eq!(snapshot.window_counts[&1], 2);
+        assert_eq!(snapshot.per_output["DP-1"].active_workspace, [1].into());
+        assert_eq!(snapshot.per_output["DP-2"].window_counts[&6], 1);
+        assert_eq!(
+            snapshot.grid,
+            Some(WorkspaceGrid {
+                columns: 3,
+                rows: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_message() {
+        let bytes = encode_message(&json!({ "method": "a" }));
+        assert_eq!(&bytes[..4], &14u32.to_le_bytes());
+        assert_eq!(&bytes[4..], br#"{"method":"a"}"#);
+    }
+}
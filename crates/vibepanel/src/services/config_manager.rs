@@ -6,22 +6,27 @@
 //! ## Architecture
 //!
 //! - A file watcher thread monitors `config.toml` for modifications.
-//! - On change, the new config is parsed and validated.
+//! - On change, the new config is parsed and validated (on that thread, or a
+//!   worker for `vibepanel ipc reload`, since `$(command)` placeholders can
+//!   take seconds).
 //! - If valid, changes are dispatched to the GTK main thread via glib::idle_add_once.
 //! - The main thread applies changes by calling `reconfigure` on each subsystem.
 //!
 //! ## Supported Live Reload
 //!
-//! - `icons.*`: Switches icon backend (Material ↔ GTK themes) and weight
+//! - `icons.*`: Switches icon backend (Material ↔ GTK ↔ Nerd Font), weight and
+//!   font, and updates per-icon overrides and the icon name map
 //! - `theme.*`: Updates colors, palette, CSS variables
 //! - Structural changes (widget list, layout, bar size, margins) trigger a full
 //!   bar rebuild with a brief visual flicker.
+//! - The `theme.source` colors file (pywal, matugen) is watched too; when the
+//!   generator rewrites it, the config is reloaded to pick up the new colors.
 
 use std::cell::RefCell;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -32,16 +37,23 @@ use tracing::{debug, error, info, warn};
 use vibepanel_core::{Config, ThemePalette, ThemeSizes};
 
 use super::callbacks::{CallbackId, Callbacks};
+use super::worker::WorkerPool;
 
 /// Debounce interval (in ms) for file change events. Editors often trigger
 /// multiple events for a single save; this batches them into one reload.
 const FILE_CHANGE_DEBOUNCE_MS: u64 = 300;
 
 use crate::bar;
+use crate::services::audio::AudioService;
 use crate::services::bar_manager::BarManager;
+use crate::services::bluetooth::BluetoothService;
 use crate::services::icons::IconsService;
+use crate::services::mute_keys::MuteKeysService;
+use crate::services::night_light::NightLightService;
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::services::tooltip::TooltipManager;
+use crate::services::wallpaper_accent::WallpaperAccentService;
+use crate::services::workspace_accent::WorkspaceAccentService;
 
 /// Messages sent from the file watcher thread to the GTK main thread.
 #[derive(Debug)]
@@ -74,6 +86,8 @@ pub struct ConfigManager {
     config_path: RefCell<Option<PathBuf>>,
     /// Shutdown flag for the file watcher thread.
     shutdown_flag: Arc<AtomicBool>,
+    /// The `theme.source` colors file, shared with the file watcher thread.
+    source_path: Arc<Mutex<Option<PathBuf>>>,
     /// Callbacks for theme/style changes (border radius, colors, etc.)
     /// that don't trigger a full bar rebuild.
     theme_callbacks: Callbacks<()>,
@@ -88,6 +102,7 @@ impl ConfigManager {
     /// Create a new ConfigManager with the given initial config.
     fn new(config: Config, config_path: Option<PathBuf>) -> Rc<Self> {
         Rc::new(Self {
+            source_path: Arc::new(Mutex::new(config.theme_source_path())),
             config: RefCell::new(config),
             config_path: RefCell::new(config_path),
             shutdown_flag: Arc::new(AtomicBool::new(false)),
@@ -169,6 +184,11 @@ impl ConfigManager {
         self.config.borrow().bar.padding
     }
 
+    /// Whether the bar is anchored to the bottom screen edge.
+    pub fn bar_at_bottom(&self) -> bool {
+        self.config.borrow().bar.is_bottom()
+    }
+
     /// Get the bar screen margin from the current configuration.
     pub fn screen_margin(&self) -> u32 {
         self.config.borrow().bar.screen_margin
@@ -240,15 +260,20 @@ impl ConfigManager {
         // Clone path for the watcher thread
         let watch_path = path.clone();
         let shutdown_flag = self.shutdown_flag.clone();
+        let source_path = self.source_path.clone();
 
         // Spawn file watcher thread
         thread::spawn(move || {
-            Self::run_file_watcher(watch_path, shutdown_flag);
+            Self::run_file_watcher(watch_path, source_path, shutdown_flag);
         });
     }
 
     /// Run the file watcher loop (called on a background thread).
-    fn run_file_watcher(path: PathBuf, shutdown_flag: Arc<AtomicBool>) {
+    fn run_file_watcher(
+        path: PathBuf,
+        source_path: Arc<Mutex<Option<PathBuf>>>,
+        shutdown_flag: Arc<AtomicBool>,
+    ) {
         // Debounce events to avoid multiple reloads for a single save
         let debounce_duration = Duration::from_millis(FILE_CHANGE_DEBOUNCE_MS);
 
@@ -264,13 +289,25 @@ impl ConfigManager {
         // Also watch for style.css in the same directory
         let style_css_path = path_for_handler.parent().map(|p| p.join("style.css"));
 
+        // The theme.source file, as the watcher reports it
+        let watched_source: Arc<Mutex<Option<PathBuf>>> = Arc::new(Mutex::new(None));
+        let source_for_handler = watched_source.clone();
+
         let mut debouncer =
             match new_debouncer(debounce_duration, move |res: DebounceEventResult| {
                 match res {
                     Ok(events) => {
                         // Check if any event is for our config file
                         let config_changed = events.iter().any(|e| e.path == path_for_handler);
-                        if config_changed {
+                        let source_changed = source_for_handler
+                            .lock()
+                            .unwrap()
+                            .as_ref()
+                            .is_some_and(|source| events.iter().any(|e| e.path == *source));
+                        if source_changed {
+                            debug!("Theme source colors change detected");
+                        }
+                        if config_changed || source_changed {
                             debug!("Config file change detected");
                             Self::reload_and_send(&path_for_handler);
                         }
@@ -317,8 +354,33 @@ impl ConfigManager {
         info!("File watcher started, watching: {}", watch_dir.display());
 
         // Keep the thread alive until shutdown is signaled
-        // Use shorter sleep intervals to allow responsive shutdown
+        // Use shorter sleep intervals to allow responsive shutdown. Each
+        // round also follows theme.source to the directory it's in now.
+        let mut source_dir: Option<PathBuf> = None;
         while !shutdown_flag.load(Ordering::Relaxed) {
+            let wanted = source_path.lock().unwrap().clone().and_then(|source| {
+                let dir = source.parent()?.canonicalize().ok()?;
+                Some((dir.clone(), dir.join(source.file_name()?)))
+            });
+            let wanted_dir = wanted.as_ref().map(|(dir, _)| dir.clone());
+            if wanted_dir != source_dir {
+                if let Some(ref old) = source_dir
+                    && old != watch_dir
+                {
+                    let _ = debouncer.watcher().unwatch(old);
+                }
+                if let Some(ref dir) = wanted_dir
+                    && dir != watch_dir
+                {
+                    match debouncer.watcher().watch(dir, RecursiveMode::NonRecursive) {
+                        Ok(()) => info!("Watching theme source colors in: {}", dir.display()),
+                        Err(e) => warn!("Failed to watch theme source directory: {}", e),
+                    }
+                }
+                source_dir = wanted_dir;
+            }
+            *watched_source.lock().unwrap() = wanted.map(|(_, file)| file);
+
             thread::sleep(Duration::from_millis(500));
         }
 
@@ -348,6 +410,47 @@ impl ConfigManager {
         }
     }
 
+    /// Reload the config file and user style.css now (IPC `reload`).
+    ///
+    /// The file is loaded on a worker thread (`$(command)` placeholders may
+    /// take seconds) and applied on the main loop; `on_done` then gets an
+    /// error if it failed to load or validate, in which case the current
+    /// config stays in effect.
+    pub fn reload<F>(&self, on_done: F)
+    where
+        F: FnOnce(Result<(), String>) + 'static,
+    {
+        let path = self.config_path.borrow().clone();
+        let Some(path) = path else {
+            on_done(Err("no config file loaded (using defaults)".to_string()));
+            return;
+        };
+
+        let load_path = path.clone();
+        WorkerPool::global().spawn_long(
+            "config-reload",
+            move || -> Result<Config, String> {
+                let new_config = Config::load(&load_path)
+                    .map_err(|e| format!("Failed to reload config: {}", e))?;
+                new_config
+                    .validate()
+                    .map_err(|e| format!("Config validation failed: {}", e))?;
+                Ok(new_config)
+            },
+            move |result| {
+                let new_config = match result {
+                    Ok(Ok(new_config)) => new_config,
+                    Ok(Err(e)) => return on_done(Err(e)),
+                    Err(_) => return on_done(Err("Config reload panicked".to_string())),
+                };
+                info!("Config reloaded on request from: {}", path.display());
+                ConfigManager::global().apply_config(new_config);
+                bar::reload_user_css();
+                on_done(Ok(()));
+            },
+        );
+    }
+
     /// Handle a config message from the file watcher.
     /// Called via glib::idle_add_once from send_config_message.
     pub(crate) fn handle_config_message(&self, msg: ConfigMessage) {
@@ -371,25 +474,41 @@ impl ConfigManager {
     ///
     /// This is the central "fan-out" function that coordinates updates across
     /// all services and widgets when the config changes.
-    fn apply_config(&self, new_config: Config) {
+    fn apply_config(&self, mut new_config: Config) {
         let old_config = self.config.borrow().clone();
 
+        // Follow (or stop following) the wallpaper before the palette is built
+        WallpaperAccentService::global().configure(&new_config.theme);
+        new_config.theme.wallpaper_accent = WallpaperAccentService::global().color();
+
         info!("Applying new configuration...");
 
-        // Update icons theme and/or weight
+        // Update icons theme, weight and/or font
         if old_config.theme.icons.theme != new_config.theme.icons.theme
             || old_config.theme.icons.weight != new_config.theme.icons.weight
+            || old_config.theme.icons.font != new_config.theme.icons.font
         {
             info!(
-                "Icon config changed: theme {} -> {}, weight {} -> {}",
+                "Icon config changed: theme {} -> {}, weight {} -> {}, font {} -> {}",
                 old_config.theme.icons.theme,
                 new_config.theme.icons.theme,
                 old_config.theme.icons.weight,
-                new_config.theme.icons.weight
+                new_config.theme.icons.weight,
+                old_config.theme.icons.font,
+                new_config.theme.icons.font
+            );
+            IconsService::global().reconfigure(
+                &new_config.theme.icons.theme,
+                new_config.theme.icons.weight,
+                &new_config.theme.icons.font,
             );
-            IconsService::global()
-                .reconfigure(&new_config.theme.icons.theme, new_config.theme.icons.weight);
         }
+        IconsService::global().set_text_mode(new_config.bar.is_text_mode());
+        IconsService::global().set_overrides(
+            &new_config.theme.icons.overrides,
+            ThemePalette::from_config(&new_config).sizes.text_icon_size,
+        );
+        IconsService::global().set_map(&new_config.theme.icons.map);
 
         // Determine what changed
         let theme_changed = config_theme_changed(&old_config, &new_config);
@@ -403,6 +522,10 @@ impl ConfigManager {
             let palette = ThemePalette::from_config(&new_config);
             let surface_styles = palette.surface_styles();
 
+            for warning in ThemePalette::lint_contrast(&new_config) {
+                warn!("Theme contrast: {}", warning);
+            }
+
             // Update surface style manager
             SurfaceStyleManager::global().reconfigure(
                 surface_styles.clone(),
@@ -418,7 +541,16 @@ impl ConfigManager {
             debug!("Theme styles updated");
         }
 
+        NightLightService::global().configure(&new_config.night_light);
+        WorkspaceAccentService::global().configure(&new_config.workspace);
+        AudioService::global().configure(&new_config.audio);
+        MuteKeysService::global().configure(&new_config.audio);
+        if new_config.bluetooth != old_config.bluetooth {
+            BluetoothService::global().configure(&new_config.bluetooth);
+        }
+
         // Store the new config BEFORE rebuilding/notifying, so widgets see new values
+        *self.source_path.lock().unwrap() = new_config.theme_source_path();
         *self.config.borrow_mut() = new_config.clone();
 
         if structure_changed {
@@ -439,6 +571,13 @@ impl ConfigManager {
         info!("Configuration applied successfully");
     }
 
+    /// Rebuild the theme with the wallpaper's new dominant color
+    /// (`theme.accent = "wallpaper"`).
+    pub fn refresh_wallpaper_accent(&self) {
+        let config = self.config.borrow().clone();
+        self.apply_config(config);
+    }
+
     /// Stop watching the config file.
     pub fn stop_watching(&self) {
         // Signal the watcher thread to shut down
@@ -459,21 +598,26 @@ fn per_widget_styles_changed(old: &Config, new: &Config) -> bool {
 fn config_theme_changed(old: &Config, new: &Config) -> bool {
     old.theme.mode != new.theme.mode
         || old.theme.accent != new.theme.accent
+        || old.theme.wallpaper_accent != new.theme.wallpaper_accent
         || old.bar.background_color != new.bar.background_color
         || old.bar.background_opacity != new.bar.background_opacity
         || old.widgets.background_color != new.widgets.background_color
         || old.widgets.background_opacity != new.widgets.background_opacity
+        || old.theme.bar_background != new.theme.bar_background
+        || old.theme.widget_background != new.theme.widget_background
         || old.theme.states.success != new.theme.states.success
         || old.theme.states.warning != new.theme.states.warning
         || old.theme.states.urgent != new.theme.states.urgent
         || old.theme.typography.font_family != new.theme.typography.font_family
         || old.bar.border_radius != new.bar.border_radius
+        // bar.position decides which side gets the bar padding
+        || old.bar.position != new.bar.position
         || old.widgets.border_radius != new.widgets.border_radius
         // bar.size affects computed font sizes in ThemeSizes/SurfaceStyles
         || old.bar.size != new.bar.size
         // advanced.pango_font_rendering affects how fonts are applied
         || old.advanced.pango_font_rendering != new.advanced.pango_font_rendering
-        // Per-widget style overrides (background_color, etc.)
+        // Per-widget style overrides (background_color, margin, padding)
         || per_widget_styles_changed(old, new)
 }
 
@@ -484,6 +628,35 @@ fn config_structure_changed(old: &Config, new: &Config) -> bool {
         return true;
     }
 
+    if old.bar.position != new.bar.position {
+        debug!(
+            "bar.position changed ({} -> {})",
+            old.bar.position, new.bar.position
+        );
+        return true;
+    }
+
+    if old.bar.hide_on_fullscreen != new.bar.hide_on_fullscreen {
+        debug!(
+            "bar.hide_on_fullscreen changed ({} -> {})",
+            old.bar.hide_on_fullscreen, new.bar.hide_on_fullscreen
+        );
+        return true;
+    }
+
+    if old.bar.on_demand != new.bar.on_demand {
+        debug!(
+            "bar.on_demand changed ({} -> {})",
+            old.bar.on_demand, new.bar.on_demand
+        );
+        return true;
+    }
+
+    if old.bar.mode != new.bar.mode {
+        debug!("bar.mode changed ({} -> {})", old.bar.mode, new.bar.mode);
+        return true;
+    }
+
     if old.bar.screen_margin != new.bar.screen_margin {
         debug!(
             "bar.screen_margin changed ({} -> {})",
@@ -500,6 +673,19 @@ fn config_structure_changed(old: &Config, new: &Config) -> bool {
         return true;
     }
 
+    if old.bar.section_spacing != new.bar.section_spacing {
+        debug!("bar.section_spacing changed");
+        return true;
+    }
+
+    if old.bar.group_spacing != new.bar.group_spacing {
+        debug!(
+            "bar.group_spacing changed ({} -> {})",
+            old.bar.group_spacing, new.bar.group_spacing
+        );
+        return true;
+    }
+
     if old.bar.inset != new.bar.inset {
         debug!("bar.inset changed ({} -> {})", old.bar.inset, new.bar.inset);
         return true;
@@ -513,6 +699,16 @@ fn config_structure_changed(old: &Config, new: &Config) -> bool {
         return true;
     }
 
+    if old.bar.output != new.bar.output {
+        debug!("bar.output overrides changed");
+        return true;
+    }
+
+    if old.bars != new.bars {
+        debug!("[[bars]] changed");
+        return true;
+    }
+
     // Widget list changes
     let old_widgets = widget_names(old);
     let new_widgets = widget_names(new);
@@ -546,8 +742,12 @@ fn widget_names(config: &Config) -> Vec<String> {
             WidgetPlacement::Single(name) => {
                 vec![format!("{}:{}", prefix, name)]
             }
-            WidgetPlacement::Group { group } => {
-                vec![format!("{}:group:[{}]", prefix, group.join(", "))]
+            WidgetPlacement::Group {
+                group,
+                shared_popover,
+            } => {
+                let shared = if *shared_popover { ":shared" } else { "" };
+                vec![format!("{}:group{}:[{}]", prefix, shared, group.join(", "))]
             }
         }
     }
@@ -606,6 +806,70 @@ mod tests {
         assert!(config_theme_changed(&old, &new));
     }
 
+    #[test]
+    fn test_bar_position_change_rebuilds_bar() {
+        let old = Config::default();
+        let mut new = Config::default();
+
+        new.bar.position = "bottom".to_string();
+        assert!(config_structure_changed(&old, &new));
+        assert!(config_theme_changed(&old, &new));
+    }
+
+    #[test]
+    fn test_bar_mode_change_rebuilds_bar() {
+        let old = Config::default();
+        let mut new = Config::default();
+
+        new.bar.mode = "text".to_string();
+        assert!(config_structure_changed(&old, &new));
+    }
+
+    #[test]
+    fn test_bar_on_demand_change_rebuilds_bar() {
+        let old = Config::default();
+        let mut new = Config::default();
+
+        new.bar.on_demand = true;
+        assert!(config_structure_changed(&old, &new));
+    }
+
+    #[test]
+    fn test_bar_hide_on_fullscreen_change_rebuilds_bar() {
+        let old = Config::default();
+        let mut new = Config::default();
+
+        new.bar.hide_on_fullscreen = true;
+        assert!(config_structure_changed(&old, &new));
+    }
+
+    #[test]
+    fn test_bar_output_override_change_rebuilds_bar() {
+        let old = Config::default();
+        let mut new = Config::default();
+
+        new.bar.output.insert(
+            "HDMI-A-1".to_string(),
+            vibepanel_core::config::BarOutputConfig {
+                size: Some(20),
+                ..Default::default()
+            },
+        );
+        assert!(config_structure_changed(&old, &new));
+    }
+
+    #[test]
+    fn test_extra_bars_change_rebuilds_bar() {
+        let old = Config::default();
+        let mut new = Config::default();
+
+        new.bars.push(vibepanel_core::config::ExtraBarConfig {
+            name: "dock".to_string(),
+            ..Default::default()
+        });
+        assert!(config_structure_changed(&old, &new));
+    }
+
     #[test]
     fn test_widget_names() {
         use vibepanel_core::config::WidgetPlacement;
@@ -619,9 +883,18 @@ mod tests {
             .widgets
             .right
             .push(WidgetPlacement::Single("clock".to_string()));
+        config.widgets.right.push(WidgetPlacement::Group {
+            group: vec!["cpu".to_string(), "memory".to_string()],
+            shared_popover: true,
+        });
 
         let names = widget_names(&config);
         assert!(names.iter().any(|n| n == "left:workspaces"));
         assert!(names.iter().any(|n| n == "right:clock"));
+        assert!(
+            names
+                .iter()
+                .any(|n| n == "right:group:shared:[cpu, memory]")
+        );
     }
 }
@@ -0,0 +1,421 @@
+//! IPC control socket for scripting the running bar.
+//!
+//! Uses a Unix stream socket in `$XDG_RUNTIME_DIR/vibepanel.sock`.
+//! A client connects, writes one JSON command terminated by a newline and
+//! reads one JSON response line back. `vibepanel ipc ...` is the CLI client;
+//! keybinds and scripts can also talk to the socket directly (e.g. with
+//! `socat`).
+//!
+//! Commands (tagged by `command`):
+//! - `{"command":"reload"}` – reload the config file and user style.css
+//! - `{"command":"toggle-widget","widget":"cpu"}` – show/hide a widget on all bars
+//! - `{"command":"show-osd","osd":"volume","percent":42,"muted":false}`
+//! - `{"command":"show-osd","osd":"brightness","percent":70}`
+//! - `{"command":"show-osd","osd":"volume-unavailable"}`
+//! - `{"command":"open-quick-settings","output":"DP-1"}` – `output` is
+//!   optional and defaults to the monitor of the focused window
+//! - `{"command":"toggle-notification-center","output":"DP-1"}` – open or
+//!   close the notification center; `output` works as above
+//! - `{"command":"toggle-command-palette","output":"DP-1"}` – open or close
+//!   the command palette; `output` works as above
+//! - `{"command":"toggle-bar","output":"DP-1"}` – show or hide a bar (with
+//!   `bar.on_demand`, summon it like touching its screen edge); `output`
+//!   works as above
+//! - `{"command":"toggle-mic-mute"}` – mute or unmute the default microphone
+//!   (for binding a mic-mute key in the compositor)
+//! - `{"command":"night-light","night":true}` – force night light on
+//!   (`false` off) until the next sunrise/sunset; omit `night` to return to
+//!   the schedule
+//! - `{"command":"state"}` – dump the latest value of every service snapshot
+//! - `{"command":"health"}` – report the health of every D-Bus service
+//! - `{"command":"module","action":"update",...}` – external modules (see
+//!   `module_ipc`)
+//! - `{"command":"job","action":"start",...}` – `vibepanel run` (see
+//!   `job_ipc`)
+//!
+//! Responses: `{"ok":true}` or `{"ok":false,"error":"..."}`, plus a `state`
+//! object for the `state` command and a `health` list for `health`.
+//!
+//! A connection may send several commands; each gets its response line in
+//! order. External modules keep their connection open and also receive
+//! their widgets' events on it. The socket is served by a
+//! [`StreamServer`] on the GTK main loop.
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::dbus_watchdog::ServiceHealth;
+use super::job_ipc::JobMessage;
+use super::module_ipc::ModuleRequest;
+use super::osd_ipc::OsdMessage;
+use super::stream_server::{ClientId, Connection, StreamServer};
+
+/// How long the CLI waits for the bar to respond.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long the CLI waits for `reload`, which may run `$(command)`
+/// placeholders in the config.
+const RELOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Get the socket path for the control IPC.
+///
+/// Returns `$XDG_RUNTIME_DIR/vibepanel.sock` or falls back to
+/// `/tmp/vibepanel.sock`.
+pub fn socket_path() -> PathBuf {
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        PathBuf::from(runtime_dir).join("vibepanel.sock")
+    } else {
+        PathBuf::from("/tmp/vibepanel.sock")
+    }
+}
+
+/// Commands accepted by the running bar.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum ControlRequest {
+    /// Reload the config file and user stylesheet.
+    Reload,
+    /// Toggle the visibility of every widget with this name.
+    ToggleWidget { widget: String },
+    /// Show an OSD.
+    ShowOsd(OsdMessage),
+    /// Open Quick Settings on a bar.
+    OpenQuickSettings {
+        /// Monitor connector name (e.g. "DP-1"); defaults to the focused one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// Open or close the notification center panel.
+    ToggleNotificationCenter {
+        /// Monitor connector name (e.g. "DP-1"); defaults to the focused one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// Open or close the command palette.
+    ToggleCommandPalette {
+        /// Monitor connector name (e.g. "DP-1"); defaults to the focused one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// Show or hide a bar.
+    ToggleBar {
+        /// Monitor connector name (e.g. "DP-1"); defaults to the focused one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// Give a bar keyboard focus for navigating its widgets.
+    FocusBar {
+        /// Monitor connector name (e.g. "DP-1"); defaults to the focused one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    /// Mute or unmute the default microphone.
+    ToggleMicMute,
+    /// Force night light on (`true`) or off (`false`) until the next
+    /// sunrise/sunset, or return to the schedule (`null`/omitted).
+    NightLight {
+        #[serde(default)]
+        night: Option<bool>,
+    },
+    /// Dump the latest value of every event bus topic.
+    State,
+    /// Report call statistics and health of every D-Bus service.
+    Health,
+    /// Update or hide a remote widget slot from an external module.
+    Module(ModuleRequest),
+    /// Report a job started or finished by `vibepanel run`.
+    Job(JobMessage),
+}
+
+/// Reply to a [`ControlRequest`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Event bus state, by snapshot type (only for `state`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<String, String>>,
+    /// D-Bus service health (only for `health`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Vec<ServiceHealth>>,
+}
+
+impl ControlResponse {
+    /// A successful response.
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            error: None,
+            state: None,
+            health: None,
+        }
+    }
+
+    /// A failed response with the given message.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(message.into()),
+            state: None,
+            health: None,
+        }
+    }
+
+    /// Successful if `result` is `Ok`, otherwise carries its error.
+    pub fn from_result(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self::ok(),
+            Err(e) => Self::error(e),
+        }
+    }
+}
+
+impl ControlRequest {
+    /// Parse a single line of the wire format.
+    pub fn from_wire(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line.trim()).map_err(|e| format!("invalid command: {}", e))
+    }
+
+    /// Serialize to wire format (including the trailing newline).
+    pub fn to_wire(&self) -> String {
+        let mut s = serde_json::to_string(self).unwrap_or_default();
+        s.push('\n');
+        s
+    }
+
+    /// Whether the command changes the running bar (config, widgets or
+    /// night light), rather than showing a panel or reporting state.
+    ///
+    /// These are refused in kiosk mode.
+    pub fn is_mutation(&self) -> bool {
+        matches!(
+            self,
+            Self::Reload | Self::ToggleWidget { .. } | Self::NightLight { .. }
+        )
+    }
+}
+
+impl ControlResponse {
+    /// Serialize to wire format (including the trailing newline).
+    pub fn to_wire(&self) -> String {
+        let mut s = serde_json::to_string(self).unwrap_or_default();
+        s.push('\n');
+        s
+    }
+}
+
+/// Send a raw command line to the running bar and wait for its response.
+pub fn send_raw(line: &str) -> io::Result<ControlResponse> {
+    send_line(line, RESPONSE_TIMEOUT)
+}
+
+/// Send a command to the running bar and wait for its response.
+pub fn send_request(request: &ControlRequest) -> io::Result<ControlResponse> {
+    let timeout = match request {
+        ControlRequest::Reload => RELOAD_TIMEOUT,
+        _ => RESPONSE_TIMEOUT,
+    };
+    send_line(&request.to_wire(), timeout)
+}
+
+fn send_line(line: &str, timeout: Duration) -> io::Result<ControlResponse> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.write_all(line.trim_end().as_bytes())?;
+    stream.write_all(b"\n")?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    serde_json::from_str(response.trim()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Send a command without waiting for the response (best-effort).
+///
+/// Used for fire-and-forget notifications like the volume OSD, where the
+/// CLI shouldn't block or fail if the bar isn't running.
+pub fn notify(request: &ControlRequest) -> io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    stream.write_all(request.to_wire().as_bytes())
+}
+
+/// The pending response to one request.
+///
+/// Handlers answer right away or keep it until the result is known (e.g.
+/// `reload` loading the config off the main loop); the client's next
+/// command waits until then. Dropping it unanswered sends an error.
+pub struct ControlReply {
+    connection: Option<Connection>,
+}
+
+impl ControlReply {
+    /// The connection the request came in on, for sending it events later
+    /// (external modules keep theirs open).
+    pub fn connection(&self) -> Option<&Connection> {
+        self.connection.as_ref()
+    }
+
+    /// Write `response` back to the client.
+    pub fn send(mut self, response: ControlResponse) {
+        if let Some(connection) = self.connection.take() {
+            connection.reply(&response.to_wire());
+        }
+    }
+}
+
+impl Drop for ControlReply {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            connection.reply(&ControlResponse::error("no response").to_wire());
+        }
+    }
+}
+
+/// Server accepting control connections on the GTK main loop.
+pub struct ControlIpcServer {
+    /// The line server on the control socket (stops when dropped).
+    _server: Rc<StreamServer>,
+}
+
+impl ControlIpcServer {
+    /// Bind the control socket and start accepting connections.
+    ///
+    /// `on_request` is invoked on the main loop for every command, with
+    /// the [`ControlReply`] that answers the client. `on_disconnect` is
+    /// invoked when a client connection closes.
+    pub fn new<R, D>(on_request: R, on_disconnect: D) -> Option<Self>
+    where
+        R: Fn(ControlRequest, ControlReply) + 'static,
+        D: Fn(ClientId) + 'static,
+    {
+        let server = StreamServer::bind(
+            "Control IPC",
+            socket_path(),
+            move |connection, line| {
+                let reply = ControlReply {
+                    connection: Some(connection),
+                };
+                match ControlRequest::from_wire(&line) {
+                    Ok(request) => {
+                        debug!("Control IPC: received {:?}", request);
+                        on_request(request, reply);
+                    }
+                    Err(e) => reply.send(ControlResponse::error(e)),
+                }
+            },
+            on_disconnect,
+        )?;
+        Some(Self { _server: server })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"reload"}"#),
+            Ok(ControlRequest::Reload)
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"toggle-widget","widget":"cpu"}"#),
+            Ok(ControlRequest::ToggleWidget {
+                widget: "cpu".to_string()
+            })
+        );
+        assert_eq!(
+            ControlRequest::from_wire(
+                r#"{"command":"show-osd","osd":"volume","percent":42,"muted":true}"#
+            ),
+            Ok(ControlRequest::ShowOsd(OsdMessage::Volume {
+                percent: 42,
+                muted: true
+            }))
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"open-quick-settings"}"#),
+            Ok(ControlRequest::OpenQuickSettings { output: None })
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"toggle-notification-center"}"#),
+            Ok(ControlRequest::ToggleNotificationCenter { output: None })
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"toggle-command-palette"}"#),
+            Ok(ControlRequest::ToggleCommandPalette { output: None })
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"toggle-bar","output":"DP-1"}"#),
+            Ok(ControlRequest::ToggleBar {
+                output: Some("DP-1".to_string())
+            })
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"focus-bar"}"#),
+            Ok(ControlRequest::FocusBar { output: None })
+        );
+        assert_eq!(
+            ControlRequest::from_wire(r#"{"command":"toggle-mic-mute"}"#),
+            Ok(ControlRequest::ToggleMicMute)
+        );
+        assert!(ControlRequest::from_wire(r#"{"command":"explode"}"#).is_err());
+        assert!(ControlRequest::from_wire("reload").is_err());
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        let cases = vec![
+            ControlRequest::State,
+            ControlRequest::Health,
+            ControlRequest::ShowOsd(OsdMessage::Brightness { percent: 70 }),
+            ControlRequest::ShowOsd(OsdMessage::VolumeUnavailable),
+            ControlRequest::OpenQuickSettings {
+                output: Some("DP-1".to_string()),
+            },
+            ControlRequest::ToggleNotificationCenter {
+                output: Some("HDMI-A-1".to_string()),
+            },
+            ControlRequest::ToggleCommandPalette {
+                output: Some("eDP-1".to_string()),
+            },
+        ];
+
+        for request in cases {
+            let wire = request.to_wire();
+            assert!(wire.ends_with('\n'));
+            assert_eq!(ControlRequest::from_wire(&wire), Ok(request));
+        }
+    }
+
+    #[test]
+    fn test_is_mutation() {
+        assert!(ControlRequest::Reload.is_mutation());
+        assert!(
+            ControlRequest::ToggleWidget {
+                widget: "clock".to_string()
+            }
+            .is_mutation()
+        );
+        assert!(ControlRequest::NightLight { night: None }.is_mutation());
+        assert!(!ControlRequest::State.is_mutation());
+        assert!(!ControlRequest::OpenQuickSettings { output: None }.is_mutation());
+    }
+
+    #[test]
+    fn test_response_wire_format() {
+        assert_eq!(ControlResponse::ok().to_wire(), "{\"ok\":true}\n");
+        assert_eq!(
+            ControlResponse::error("no widget named 'cpu'").to_wire(),
+            "{\"ok\":false,\"error\":\"no widget named 'cpu'\"}\n"
+        );
+    }
+}
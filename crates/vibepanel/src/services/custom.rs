@@ -0,0 +1,702 @@
+//! CustomService - user commands rendered by `custom` widgets.
+//!
+//! Every `custom:<name>` widget runs a shell command and shows its output:
+//! - Interval mode runs the command every `interval` seconds on a thread of
+//!   its own and parses its whole stdout; a run that takes longer than
+//!   `timeout` seconds is killed with its process group
+//! - Follow mode keeps one long-running process and treats every stdout line
+//!   as an update; it is restarted a few seconds after it exits
+//!
+//! Output is plain text (text, tooltip and CSS classes on the first three
+//! lines) or a JSON object with `text`, `tooltip`, `class` and `percentage`.
+//! Commands are shared by id, so a widget shown on several monitors runs its
+//! command once; it is stopped when the last widget using it goes away.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::Read;
+use std::io::{BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use gtk4::glib::{self, SourceId};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for CustomService
+thread_local! {
+    static CUSTOM_INSTANCE: RefCell<Option<Rc<CustomService>>> = const { RefCell::new(None) };
+}
+
+/// Default interval between runs in interval mode (seconds).
+pub const DEFAULT_INTERVAL: u64 = 10;
+
+/// Default time an interval-mode run may take before it's killed (seconds).
+pub const DEFAULT_TIMEOUT: u64 = 30;
+
+/// Delay before restarting a follow-mode command that exited (seconds).
+const RESTART_DELAY_SECS: u32 = 5;
+
+/// How command output is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Text, tooltip and classes on the first three lines.
+    #[default]
+    Text,
+    /// A JSON object (`text`, `tooltip`, `class`, `percentage`).
+    Json,
+}
+
+impl OutputFormat {
+    /// Parse an `output` option value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// When the command runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// Run every N seconds.
+    Interval(u64),
+    /// Run once and read updates from stdout, one per line.
+    Follow,
+}
+
+/// Settings for one custom command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomSettings {
+    /// Shell command line (run with `sh -c`).
+    pub exec: String,
+    pub mode: RunMode,
+    pub format: OutputFormat,
+    /// Seconds an interval-mode run may take.
+    pub timeout: u64,
+}
+
+/// Parsed command output.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomOutput {
+    /// Label text; the widget hides while it's empty.
+    pub text: String,
+    pub tooltip: Option<String>,
+    /// Extra CSS classes for the widget.
+    pub classes: Vec<String>,
+    /// Percentage (0-100) used to pick an icon.
+    pub percentage: Option<u8>,
+    /// Badge on the widget icon (JSON output only).
+    pub badge: Option<CustomBadge>,
+}
+
+/// Badge requested by the command output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomBadge {
+    /// A count, e.g. unread mail or failed units.
+    Count(usize),
+    /// A dot without text.
+    Dot,
+}
+
+/// Snapshot of a single custom command.
+#[derive(Debug, Clone, Default)]
+pub struct CustomSnapshot {
+    /// Command id (matches the widget's `id` option).
+    pub id: String,
+    /// Last successful output.
+    pub output: Option<CustomOutput>,
+    /// Error from the last run, if it failed.
+    pub error: Option<String>,
+}
+
+/// Internal per-command state.
+struct Script {
+    settings: CustomSettings,
+    snapshot: CustomSnapshot,
+    /// Number of widgets using this command.
+    users: usize,
+    /// Bumped on every (re)start so results from a stopped run are dropped.
+    generation: u64,
+    /// Interval timer, or the pending restart in follow mode.
+    timer: Option<SourceId>,
+    in_progress: bool,
+    /// Process group of the running follow-mode command.
+    child_pid: Option<u32>,
+}
+
+impl Script {
+    fn stop(&mut self) {
+        if let Some(source_id) = self.timer.take() {
+            source_id.remove();
+        }
+        if let Some(pid) = self.child_pid.take() {
+            // The command runs in its own process group so pipelines and
+            // subshells go down with it.
+            // SAFETY: kill(2) has no memory-safety preconditions.
+            unsafe {
+                libc::kill(-(pid as libc::pid_t), libc::SIGTERM);
+            }
+        }
+        self.in_progress = false;
+    }
+}
+
+/// Shared, process-wide custom command service.
+pub struct CustomService {
+    scripts: RefCell<HashMap<String, Script>>,
+    callbacks: Callbacks<CustomSnapshot>,
+    next_generation: Cell<u64>,
+}
+
+impl CustomService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            scripts: RefCell::new(HashMap::new()),
+            callbacks: Callbacks::new(),
+            next_generation: Cell::new(1),
+        })
+    }
+
+    /// Get the global CustomService singleton.
+    pub fn global() -> Rc<Self> {
+        CUSTOM_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(CustomService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        CUSTOM_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback for command updates.
+    ///
+    /// The callback receives snapshots for every command; widgets filter by
+    /// id. It is immediately invoked with the current state of all commands.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&CustomSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        let snapshots: Vec<_> = self
+            .scripts
+            .borrow()
+            .values()
+            .map(|script| script.snapshot.clone())
+            .collect();
+        for snapshot in &snapshots {
+            self.callbacks.notify_single(id, snapshot);
+        }
+        id
+    }
+
+    /// Unregister a previously connected callback.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Replace a command's snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: CustomSnapshot) {
+        if let Some(script) = self.scripts.borrow_mut().get_mut(&snapshot.id) {
+            script.snapshot = snapshot.clone();
+        }
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Start (or join) the command for `id`.
+    ///
+    /// Each call must be paired with a `release()`. If the command is already
+    /// running with different settings, it is restarted with the new ones.
+    pub fn start(&self, id: &str, settings: CustomSettings) {
+        {
+            let mut scripts = self.scripts.borrow_mut();
+            if let Some(script) = scripts.get_mut(id) {
+                script.users += 1;
+                if script.settings == settings {
+                    return;
+                }
+                debug!("CustomService: settings for '{}' changed, restarting", id);
+                script.stop();
+                script.settings = settings;
+            } else {
+                scripts.insert(
+                    id.to_string(),
+                    Script {
+                        settings,
+                        snapshot: CustomSnapshot {
+                            id: id.to_string(),
+                            ..Default::default()
+                        },
+                        users: 1,
+                        generation: 0,
+                        timer: None,
+                        in_progress: false,
+                        child_pid: None,
+                    },
+                );
+            }
+        }
+        self.launch(id);
+    }
+
+    /// Drop one user of the command for `id`, stopping it after the last.
+    pub fn release(&self, id: &str) {
+        let mut scripts = self.scripts.borrow_mut();
+        let Some(script) = scripts.get_mut(id) else {
+            return;
+        };
+        script.users = script.users.saturating_sub(1);
+        if script.users == 0 {
+            script.stop();
+            scripts.remove(id);
+            debug!("CustomService: stopped '{}'", id);
+        }
+    }
+
+    /// Run an interval-mode command now (e.g. after a click action).
+    pub fn refresh(&self, id: &str) {
+        let interval = self
+            .scripts
+            .borrow()
+            .get(id)
+            .is_some_and(|script| matches!(script.settings.mode, RunMode::Interval(_)));
+        if interval {
+            self.run_once(id);
+        }
+    }
+
+    fn launch(&self, id: &str) {
+        let mode = {
+            let mut scripts = self.scripts.borrow_mut();
+            let Some(script) = scripts.get_mut(id) else {
+                return;
+            };
+            script.generation = self.next_generation.replace(self.next_generation.get() + 1);
+            script.settings.mode
+        };
+
+        match mode {
+            RunMode::Interval(seconds) => {
+                let timer_id = id.to_string();
+                let source_id = glib::timeout_add_seconds_local(seconds.max(1) as u32, move || {
+                    CustomService::global().run_once(&timer_id);
+                    glib::ControlFlow::Continue
+                });
+                if let Some(script) = self.scripts.borrow_mut().get_mut(id) {
+                    script.timer = Some(source_id);
+                }
+                self.run_once(id);
+            }
+            RunMode::Follow => self.spawn_follow(id),
+        }
+    }
+
+    /// Run the command once on a thread of its own (interval mode).
+    fn run_once(&self, id: &str) {
+        let (exec, format, timeout, generation) = {
+            let mut scripts = self.scripts.borrow_mut();
+            let Some(script) = scripts.get_mut(id) else {
+                return;
+            };
+            if script.in_progress {
+                return;
+            }
+            script.in_progress = true;
+            (
+                script.settings.exec.clone(),
+                script.settings.format,
+                Duration::from_secs(script.settings.timeout.max(1)),
+                script.generation,
+            )
+        };
+
+        // User scripts may hang, so they don't hold a shared worker
+        let id = id.to_string();
+        WorkerPool::global().spawn_long(
+            "custom",
+            move || run_command(&exec, timeout).and_then(|stdout| parse_output(&stdout, format)),
+            move |result| {
+                CustomService::global().apply_result(
+                    &id,
+                    generation,
+                    result.unwrap_or_else(|e| Err(e.to_string())),
+                );
+            },
+        );
+    }
+
+    /// Start the long-running command and follow its stdout (follow mode).
+    fn spawn_follow(&self, id: &str) {
+        let (exec, format, generation) = {
+            let scripts = self.scripts.borrow();
+            let Some(script) = scripts.get(id) else {
+                return;
+            };
+            (
+                script.settings.exec.clone(),
+                script.settings.format,
+                script.generation,
+            )
+        };
+
+        let child = Command::new("sh")
+            .args(["-c", &exec])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                self.apply_result(id, generation, Err(format!("failed to run: {}", e)));
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        if let Some(script) = self.scripts.borrow_mut().get_mut(id) {
+            script.child_pid = Some(child.id());
+        }
+        debug!("CustomService: following '{}' (pid {})", id, child.id());
+
+        let id = id.to_string();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let result = parse_output(&line, format);
+                let id = id.clone();
+                glib::idle_add_once(move || {
+                    CustomService::global().apply_result(&id, generation, result);
+                });
+            }
+
+            let status = match child.wait() {
+                Ok(status) => status.to_string(),
+                Err(e) => e.to_string(),
+            };
+            glib::idle_add_once(move || {
+                CustomService::global().follow_exited(&id, generation, status);
+            });
+        });
+    }
+
+    /// Schedule a restart after a follow-mode command exited on its own.
+    fn follow_exited(&self, id: &str, generation: u64, status: String) {
+        let mut scripts = self.scripts.borrow_mut();
+        let Some(script) = scripts.get_mut(id) else {
+            return;
+        };
+        if script.generation != generation {
+            return;
+        }
+        script.child_pid = None;
+        warn!(
+            "custom:{}: command exited ({}), restarting in {}s",
+            id, status, RESTART_DELAY_SECS
+        );
+
+        let restart_id = id.to_string();
+        script.timer = Some(glib::timeout_add_seconds_local_once(
+            RESTART_DELAY_SECS,
+            move || {
+                let service = CustomService::global();
+                let current = {
+                    let mut scripts = service.scripts.borrow_mut();
+                    scripts.get_mut(&restart_id).map(|script| {
+                        script.timer = None;
+                        script.generation
+                    })
+                };
+                if current == Some(generation) {
+                    service.spawn_follow(&restart_id);
+                }
+            },
+        ));
+    }
+
+    /// Apply a parsed run (or line) to the command's snapshot.
+    fn apply_result(&self, id: &str, generation: u64, result: Result<CustomOutput, String>) {
+        let snapshot = {
+            let mut scripts = self.scripts.borrow_mut();
+            let Some(script) = scripts.get_mut(id) else {
+                return;
+            };
+            if script.generation != generation {
+                return;
+            }
+            script.in_progress = false;
+
+            match result {
+                Ok(output) => {
+                    if script.snapshot.error.is_none()
+                        && script.snapshot.output.as_ref() == Some(&output)
+                    {
+                        return;
+                    }
+                    script.snapshot.output = Some(output);
+                    script.snapshot.error = None;
+                }
+                Err(error) => {
+                    if script.snapshot.error.as_ref() == Some(&error) {
+                        return;
+                    }
+                    warn!("custom:{}: {}", id, error);
+                    // Keep showing the last output; the widget marks it stale
+                    script.snapshot.error = Some(error);
+                }
+            }
+            script.snapshot.clone()
+        };
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+impl Drop for CustomService {
+    fn drop(&mut self) {
+        for script in self.scripts.get_mut().values_mut() {
+            script.stop();
+        }
+    }
+}
+
+/// Run `exec` with `sh -c` and return its stdout, killing its process
+/// group if it runs longer than `timeout`.
+fn run_command(exec: &str, timeout: Duration) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .args(["-c", exec])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("failed to run: {}", e))?;
+
+    // Read the pipes while waiting, so a command with lots of output can't
+    // block on a full pipe
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let timed_out = |pid: u32| {
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+        format!("command timed out after {}s", timeout.as_secs())
+    };
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let error = timed_out(child.id());
+                let _ = child.wait();
+                return Err(error);
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("failed to run: {}", e)),
+        }
+    };
+
+    // Background processes the command left behind may hold the pipes open
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    let stdout = stdout
+        .recv_timeout(remaining())
+        .map_err(|_| timed_out(child.id()))?;
+    let stderr = stderr
+        .recv_timeout(remaining())
+        .map_err(|_| timed_out(child.id()))?;
+
+    if !status.success() {
+        let stderr = stderr.trim();
+        return Err(if stderr.is_empty() {
+            format!("command failed ({})", status)
+        } else {
+            format!("command failed ({}): {}", status, stderr)
+        });
+    }
+    Ok(stdout)
+}
+
+/// Read `pipe` to the end on a thread of its own; the output arrives on the
+/// returned channel.
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        let _ = sender.send(String::from_utf8_lossy(&output).into_owned());
+    });
+    receiver
+}
+
+/// JSON output schema.
+#[derive(Deserialize)]
+struct JsonOutput {
+    #[serde(default)]
+    text: String,
+    tooltip: Option<String>,
+    #[serde(default)]
+    class: JsonClass,
+    percentage: Option<f64>,
+    #[serde(default)]
+    badge: JsonBadge,
+}
+
+/// `badge` may be a count or a flag for a plain dot.
+#[derive(Deserialize, Default)]
+#[serde(untagged)]
+enum JsonBadge {
+    #[default]
+    None,
+    Flag(bool),
+    Count(u64),
+}
+
+/// `class` may be a single class or a list.
+#[derive(Deserialize, Default)]
+#[serde(untagged)]
+enum JsonClass {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Parse command output in the given format.
+pub fn parse_output(stdout: &str, format: OutputFormat) -> Result<CustomOutput, String> {
+    match format {
+        OutputFormat::Text => {
+            let mut lines = stdout.lines().map(str::trim_end);
+            let text = lines.next().unwrap_or_default().to_string();
+            let tooltip = lines
+                .next()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string);
+            let classes = lines
+                .next()
+                .map(|line| line.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default();
+            Ok(CustomOutput {
+                text,
+                tooltip,
+                classes,
+                percentage: None,
+                badge: None,
+            })
+        }
+        OutputFormat::Json => {
+            let stdout = stdout.trim();
+            if stdout.is_empty() {
+                return Ok(CustomOutput::default());
+            }
+            let json: JsonOutput =
+                serde_json::from_str(stdout).map_err(|e| format!("invalid JSON output: {}", e))?;
+            let classes = match json.class {
+                JsonClass::None => Vec::new(),
+                JsonClass::One(class) => class.split_whitespace().map(str::to_string).collect(),
+                JsonClass::Many(classes) => classes,
+            };
+            Ok(CustomOutput {
+                text: json.text,
+                tooltip: json.tooltip.filter(|tooltip| !tooltip.is_empty()),
+                classes,
+                percentage: json
+                    .percentage
+                    .filter(|p| p.is_finite())
+                    .map(|p| p.round().clamp(0.0, 100.0) as u8),
+                badge: match json.badge {
+                    JsonBadge::None | JsonBadge::Flag(false) | JsonBadge::Count(0) => None,
+                    JsonBadge::Flag(true) => Some(CustomBadge::Dot),
+                    JsonBadge::Count(count) => Some(CustomBadge::Count(count as usize)),
+                },
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_command() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(run_command("echo 42", timeout).unwrap(), "42\n");
+        // More than a pipe buffer holds
+        let output = run_command("head -c 200000 /dev/zero | tr '\\0' x", timeout).unwrap();
+        assert_eq!(output.len(), 200_000);
+        assert_eq!(
+            run_command("echo oops >&2; exit 2", timeout).unwrap_err(),
+            "command failed (exit status: 2): oops"
+        );
+    }
+
+    #[test]
+    fn test_run_command_timeout_kills_process_group() {
+        let started = Instant::now();
+        let error = run_command("sleep 30 & sleep 30", Duration::from_secs(1)).unwrap_err();
+        assert_eq!(error, "command timed out after 1s");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_parse_text_output() {
+        let output = parse_output("42 GB\nDisk usage\nwarning low\n", OutputFormat::Text).unwrap();
+        assert_eq!(output.text, "42 GB");
+        assert_eq!(output.tooltip.as_deref(), Some("Disk usage"));
+        assert_eq!(output.classes, vec!["warning", "low"]);
+
+        let output = parse_output("", OutputFormat::Text).unwrap();
+        assert_eq!(output, CustomOutput::default());
+    }
+
+    #[test]
+    fn test_parse_json_output() {
+        let output = parse_output(
+            r#"{"text": "3", "tooltip": "3 unread", "class": ["unread", "mail"], "percentage": 42.6}"#,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(output.text, "3");
+        assert_eq!(output.tooltip.as_deref(), Some("3 unread"));
+        assert_eq!(output.classes, vec!["unread", "mail"]);
+        assert_eq!(output.percentage, Some(43));
+        assert_eq!(output.badge, None);
+
+        let output = parse_output(
+            r#"{"class": "idle", "percentage": 250}"#,
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(output.text, "");
+        assert_eq!(output.classes, vec!["idle"]);
+        assert_eq!(output.percentage, Some(100));
+
+        let badge = |json| parse_output(json, OutputFormat::Json).unwrap().badge;
+        assert_eq!(badge(r#"{"badge": 3}"#), Some(CustomBadge::Count(3)));
+        assert_eq!(badge(r#"{"badge": true}"#), Some(CustomBadge::Dot));
+        assert_eq!(badge(r#"{"badge": 0}"#), None);
+        assert_eq!(badge(r#"{"badge": false}"#), None);
+
+        assert!(parse_output("not json", OutputFormat::Json).is_err());
+    }
+}
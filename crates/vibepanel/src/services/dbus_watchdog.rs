@@ -0,0 +1,524 @@
+//! Central timeouts, retries and health tracking for D-Bus calls.
+//!
+//! Every D-Bus method call goes through [`call`] / [`call_proxy`] (async) or
+//! [`call_sync`] / [`call_proxy_sync`] (blocking). Each call names the
+//! service it talks to ("bluez", "networkmanager", ...) and a [`CallPolicy`]
+//! that sets its timeout and how often it may be retried:
+//! - Queries time out quickly and are retried with exponential backoff when
+//!   the service doesn't answer
+//! - Actions aren't retried, since they may not be idempotent
+//! - Slow actions (pairing, VPN activation) get a long timeout
+//!
+//! Blocking calls are never retried, so a retry can't multiply the time the
+//! caller is blocked.
+//!
+//! A service that times out several times in a row is marked unresponsive,
+//! and calls to it fail immediately until a backoff delay has passed, so a
+//! hung daemon can't keep stalling snapshot updates. The first call after the
+//! delay goes through as a probe; any answer marks the service healthy again.
+//!
+//! Per-service health is reported by `vibepanel doctor`.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use gtk4::gio;
+use gtk4::glib::{self, Variant, VariantTy};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Consecutive timeouts after which a service is marked unresponsive.
+const UNRESPONSIVE_AFTER: u32 = 3;
+
+/// First delay during which calls to an unresponsive service fail fast.
+const BLOCK_INITIAL: Duration = Duration::from_secs(2);
+
+/// Upper bound for the fail-fast delay.
+const BLOCK_MAX: Duration = Duration::from_secs(60);
+
+/// Delay before the first retry of a query; doubled for each further retry.
+const RETRY_INITIAL: Duration = Duration::from_millis(250);
+
+/// Timeout and retry behaviour of a D-Bus call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallPolicy {
+    /// Timeout for each attempt, in milliseconds.
+    pub timeout_ms: i32,
+    /// How many times a timed-out call is retried (async calls only).
+    pub retries: u32,
+}
+
+impl CallPolicy {
+    /// Idempotent reads (properties, object lists): short timeout, retried.
+    pub const QUERY: Self = Self {
+        timeout_ms: 3000,
+        retries: 2,
+    };
+
+    /// State-changing calls: not retried.
+    pub const ACTION: Self = Self {
+        timeout_ms: 5000,
+        retries: 0,
+    };
+
+    /// Calls that legitimately take long (pairing, auth dialogs, scans).
+    pub const SLOW: Self = Self {
+        timeout_ms: 30_000,
+        retries: 0,
+    };
+
+    /// Best-effort calls during shutdown or polling: short and not retried.
+    pub const QUICK: Self = Self {
+        timeout_ms: 1000,
+        retries: 0,
+    };
+}
+
+/// Health of a D-Bus service, derived from recent calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HealthStatus {
+    /// The last call was answered.
+    Healthy,
+    /// The last call failed or timed out.
+    Degraded,
+    /// Several calls in a row timed out; calls fail fast for a while.
+    Unresponsive,
+}
+
+/// Call statistics and health of one service, as shown by `vibepanel doctor`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceHealth {
+    pub service: String,
+    pub status: HealthStatus,
+    /// Calls that reached the service (fast failures aren't counted).
+    pub calls: u64,
+    /// Calls that returned an error, including timeouts.
+    pub failures: u64,
+    /// Calls that timed out.
+    pub timeouts: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// How a call ended, for health tracking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    /// The service answered successfully.
+    Answered,
+    /// The service answered with an error.
+    Failed(String),
+    /// The service didn't answer in time.
+    TimedOut(String),
+}
+
+/// Running call statistics of one service.
+#[derive(Debug, Clone, Default)]
+struct ServiceRecord {
+    calls: u64,
+    failures: u64,
+    timeouts: u64,
+    consecutive_timeouts: u32,
+    last_failed: bool,
+    last_error: Option<String>,
+    /// Calls fail fast until this instant.
+    blocked_until: Option<Instant>,
+    /// Next fail-fast delay.
+    block_delay: Option<Duration>,
+}
+
+impl ServiceRecord {
+    /// Whether a call may go out now.
+    fn admit(&self, now: Instant) -> bool {
+        self.blocked_until.is_none_or(|until| now >= until)
+    }
+
+    /// Account for a finished call.
+    ///
+    /// Returns true when this call made the service unresponsive.
+    fn record(&mut self, outcome: Outcome, now: Instant) -> bool {
+        self.calls += 1;
+        match outcome {
+            Outcome::Answered => {
+                self.consecutive_timeouts = 0;
+                self.last_failed = false;
+                self.blocked_until = None;
+                self.block_delay = None;
+                false
+            }
+            Outcome::Failed(error) => {
+                // The service answered, so it isn't hung
+                self.failures += 1;
+                self.consecutive_timeouts = 0;
+                self.last_failed = true;
+                self.last_error = Some(error);
+                self.blocked_until = None;
+                self.block_delay = None;
+                false
+            }
+            Outcome::TimedOut(error) => {
+                self.failures += 1;
+                self.timeouts += 1;
+                self.consecutive_timeouts += 1;
+                self.last_failed = true;
+                self.last_error = Some(error);
+                if self.consecutive_timeouts < UNRESPONSIVE_AFTER {
+                    return false;
+                }
+                let delay = self.block_delay.unwrap_or(BLOCK_INITIAL);
+                self.blocked_until = Some(now + delay);
+                self.block_delay = Some((delay * 2).min(BLOCK_MAX));
+                true
+            }
+        }
+    }
+
+    fn status(&self) -> HealthStatus {
+        if self.consecutive_timeouts >= UNRESPONSIVE_AFTER {
+            HealthStatus::Unresponsive
+        } else if self.last_failed {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+/// Call statistics by service name.
+static RECORDS: Mutex<BTreeMap<&'static str, ServiceRecord>> = Mutex::new(BTreeMap::new());
+
+fn with_record<T>(service: &'static str, f: impl FnOnce(&mut ServiceRecord) -> T) -> T {
+    let mut records = RECORDS.lock().unwrap_or_else(|e| e.into_inner());
+    f(records.entry(service).or_default())
+}
+
+/// Health of every service that has been called, sorted by name.
+pub fn health() -> Vec<ServiceHealth> {
+    RECORDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(service, record)| ServiceHealth {
+            service: service.to_string(),
+            status: record.status(),
+            calls: record.calls,
+            failures: record.failures,
+            timeouts: record.timeouts,
+            last_error: record.last_error.clone(),
+        })
+        .collect()
+}
+
+/// Whether an error means the service didn't answer in time.
+fn is_timeout(error: &glib::Error) -> bool {
+    error.matches(gio::IOErrorEnum::TimedOut)
+        || error.matches(gio::DBusError::NoReply)
+        || error.matches(gio::DBusError::Timeout)
+        || error.matches(gio::DBusError::TimedOut)
+}
+
+/// Fail fast if `service` is marked unresponsive.
+fn admit(service: &'static str) -> Result<(), glib::Error> {
+    if with_record(service, |record| record.admit(Instant::now())) {
+        Ok(())
+    } else {
+        Err(glib::Error::new(
+            gio::IOErrorEnum::TimedOut,
+            &format!("{} is not responding", service),
+        ))
+    }
+}
+
+/// Record the result of a call that reached `service`.
+fn record<T>(service: &'static str, result: &Result<T, glib::Error>) {
+    let outcome = match result {
+        Ok(_) => Outcome::Answered,
+        // Cancelled calls say nothing about the service
+        Err(e) if e.matches(gio::IOErrorEnum::Cancelled) => return,
+        Err(e) if is_timeout(e) => Outcome::TimedOut(e.to_string()),
+        Err(e) => Outcome::Failed(e.to_string()),
+    };
+    if with_record(service, |record| record.record(outcome, Instant::now())) {
+        warn!(
+            "D-Bus service '{}' is not responding, failing calls to it for a while",
+            service
+        );
+    }
+}
+
+/// Callback receiving the result of one attempt.
+type Reply<T> = Box<dyn FnOnce(Result<T, glib::Error>)>;
+
+/// Run an async call under the watchdog.
+///
+/// `start` issues one attempt with the given timeout in milliseconds and
+/// passes its result on. Timed-out attempts are retried per `policy`.
+pub fn run<T, S, F>(service: &'static str, policy: CallPolicy, start: S, callback: F)
+where
+    T: 'static,
+    S: Fn(i32, Reply<T>) + 'static,
+    F: FnOnce(Result<T, glib::Error>) + 'static,
+{
+    attempt(service, policy, 0, Rc::new(start), Box::new(callback));
+}
+
+fn attempt<T: 'static>(
+    service: &'static str,
+    policy: CallPolicy,
+    attempt_index: u32,
+    start: Rc<dyn Fn(i32, Reply<T>)>,
+    callback: Reply<T>,
+) {
+    if let Err(e) = admit(service) {
+        callback(Err(e));
+        return;
+    }
+
+    let start_again = start.clone();
+    start(
+        policy.timeout_ms,
+        Box::new(move |result| {
+            record(service, &result);
+            match result {
+                Err(e) if is_timeout(&e) && attempt_index < policy.retries => {
+                    let delay = RETRY_INITIAL * 2u32.pow(attempt_index);
+                    debug!(
+                        "D-Bus call to '{}' timed out, retrying in {:?}",
+                        service, delay
+                    );
+                    glib::timeout_add_local_once(delay, move || {
+                        attempt(service, policy, attempt_index + 1, start_again, callback);
+                    });
+                }
+                result => callback(result),
+            }
+        }),
+    );
+}
+
+/// Run a blocking call under the watchdog.
+///
+/// `call` is made once with the policy's timeout in milliseconds.
+pub fn run_sync<T>(
+    service: &'static str,
+    policy: CallPolicy,
+    call: impl FnOnce(i32) -> Result<T, glib::Error>,
+) -> Result<T, glib::Error> {
+    admit(service)?;
+    let result = call(policy.timeout_ms);
+    record(service, &result);
+    result
+}
+
+/// Call a method on a bus connection asynchronously.
+#[allow(clippy::too_many_arguments)]
+pub fn call<F>(
+    service: &'static str,
+    connection: &gio::DBusConnection,
+    bus_name: &str,
+    path: &str,
+    interface: &str,
+    method: &str,
+    params: Option<&Variant>,
+    reply_type: Option<&VariantTy>,
+    policy: CallPolicy,
+    callback: F,
+) where
+    F: FnOnce(Result<Variant, glib::Error>) + 'static,
+{
+    let connection = connection.clone();
+    let (bus_name, path, interface, method) = (
+        bus_name.to_string(),
+        path.to_string(),
+        interface.to_string(),
+        method.to_string(),
+    );
+    let params = params.cloned();
+    let reply_type = reply_type.map(VariantTy::to_owned);
+    run(
+        service,
+        policy,
+        move |timeout_ms, reply| {
+            connection.call(
+                Some(&bus_name),
+                &path,
+                &interface,
+                &method,
+                params.as_ref(),
+                reply_type.as_deref(),
+                gio::DBusCallFlags::NONE,
+                timeout_ms,
+                None::<&gio::Cancellable>,
+                reply,
+            );
+        },
+        callback,
+    );
+}
+
+/// Call a method on a proxy asynchronously.
+pub fn call_proxy<F>(
+    service: &'static str,
+    proxy: &gio::DBusProxy,
+    method: &str,
+    params: Option<&Variant>,
+    policy: CallPolicy,
+    callback: F,
+) where
+    F: FnOnce(Result<Variant, glib::Error>) + 'static,
+{
+    let proxy = proxy.clone();
+    let method = method.to_string();
+    let params = params.cloned();
+    run(
+        service,
+        policy,
+        move |timeout_ms, reply| {
+            proxy.call(
+                &method,
+                params.as_ref(),
+                gio::DBusCallFlags::NONE,
+                timeout_ms,
+                None::<&gio::Cancellable>,
+                reply,
+            );
+        },
+        callback,
+    );
+}
+
+/// Call a method on a bus connection, blocking until it returns.
+#[allow(clippy::too_many_arguments)]
+pub fn call_sync(
+    service: &'static str,
+    connection: &gio::DBusConnection,
+    bus_name: &str,
+    path: &str,
+    interface: &str,
+    method: &str,
+    params: Option<&Variant>,
+    reply_type: Option<&VariantTy>,
+    policy: CallPolicy,
+) -> Result<Variant, glib::Error> {
+    run_sync(service, policy, |timeout_ms| {
+        connection.call_sync(
+            Some(bus_name),
+            path,
+            interface,
+            method,
+            params,
+            reply_type,
+            gio::DBusCallFlags::NONE,
+            timeout_ms,
+            None::<&gio::Cancellable>,
+        )
+    })
+}
+
+/// Call a method on a proxy, blocking until it returns.
+pub fn call_proxy_sync(
+    service: &'static str,
+    proxy: &gio::DBusProxy,
+    method: &str,
+    params: Option<&Variant>,
+    policy: CallPolicy,
+) -> Result<Variant, glib::Error> {
+    run_sync(service, policy, |timeout_ms| {
+        proxy.call_sync(
+            method,
+            params,
+            gio::DBusCallFlags::NONE,
+            timeout_ms,
+            None::<&gio::Cancellable>,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timed_out() -> Outcome {
+        Outcome::TimedOut("Timeout was reached".to_string())
+    }
+
+    #[test]
+    fn test_record_status() {
+        let now = Instant::now();
+        let mut record = ServiceRecord::default();
+        assert_eq!(record.status(), HealthStatus::Healthy);
+
+        record.record(Outcome::Failed("UnknownMethod".to_string()), now);
+        assert_eq!(record.status(), HealthStatus::Degraded);
+        assert_eq!(record.last_error.as_deref(), Some("UnknownMethod"));
+
+        record.record(Outcome::Answered, now);
+        assert_eq!(record.status(), HealthStatus::Healthy);
+        assert_eq!((record.calls, record.failures, record.timeouts), (2, 1, 0));
+    }
+
+    #[test]
+    fn test_unresponsive_after_consecutive_timeouts() {
+        let now = Instant::now();
+        let mut record = ServiceRecord::default();
+
+        assert!(!record.record(timed_out(), now));
+        assert!(!record.record(timed_out(), now));
+        assert_eq!(record.status(), HealthStatus::Degraded);
+        assert!(record.admit(now));
+
+        assert!(record.record(timed_out(), now));
+        assert_eq!(record.status(), HealthStatus::Unresponsive);
+        assert!(!record.admit(now));
+        assert!(record.admit(now + BLOCK_INITIAL));
+
+        // Any answer clears the block
+        record.record(Outcome::Failed("AccessDenied".to_string()), now);
+        assert_eq!(record.status(), HealthStatus::Degraded);
+        assert!(record.admit(now));
+    }
+
+    #[test]
+    fn test_block_delay_backs_off() {
+        let now = Instant::now();
+        let mut record = ServiceRecord::default();
+        for _ in 0..UNRESPONSIVE_AFTER {
+            record.record(timed_out(), now);
+        }
+        assert_eq!(record.blocked_until, Some(now + BLOCK_INITIAL));
+
+        // A failed probe blocks for twice as long
+        record.record(timed_out(), now);
+        assert_eq!(record.blocked_until, Some(now + BLOCK_INITIAL * 2));
+
+        for _ in 0..10 {
+            record.record(timed_out(), now);
+        }
+        assert_eq!(record.blocked_until, Some(now + BLOCK_MAX));
+
+        record.record(Outcome::Answered, now);
+        assert_eq!(record.status(), HealthStatus::Healthy);
+        assert_eq!(record.blocked_until, None);
+    }
+
+    #[test]
+    fn test_health_status_serialization() {
+        let health = ServiceHealth {
+            service: "bluez".to_string(),
+            status: HealthStatus::Unresponsive,
+            calls: 4,
+            failures: 3,
+            timeouts: 3,
+            last_error: None,
+        };
+        let json = serde_json::to_string(&health).unwrap();
+        assert!(json.contains(r#""status":"unresponsive""#));
+        assert!(!json.contains("last_error"));
+        assert_eq!(
+            serde_json::from_str::<ServiceHealth>(&json).unwrap(),
+            health
+        );
+    }
+}
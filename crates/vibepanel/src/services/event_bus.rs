@@ -0,0 +1,839 @@
+//! Typed event bus for service state.
+//!
+//! Every service publishes its snapshot type on the bus; consumers subscribe
+//! by type instead of reaching into each service's callback list. This gives
+//! all state a single observation point, which cross-cutting features (hooks,
+//! conditions, the IPC state dump) use to see every service uniformly.
+//!
+//! Features:
+//! - **Last-value replay**: `subscribe` immediately receives the latest
+//!   published value of its type, if any
+//! - **Filters**: `subscribe_filtered` only delivers values matching a predicate
+//! - **Weak subscriptions**: `subscribe_weak` ties a subscription to an `Rc`
+//!   owner and drops it automatically once the owner is gone
+//! - **Wildcard observers**: `subscribe_all` sees every event of every type
+//! - **Instance topics**: `subscribe_instance` only receives values published
+//!   on one [`InstanceTopic`], so publishers sharing a type (two services
+//!   with the same payload, or several instances of one service) don't see
+//!   each other's values; `publish_instance` also publishes on the shared
+//!   topic for the type
+//! - **Scopes**: subscriptions made while a `SubscriptionScope` is entered
+//!   are removed when the scope is dropped (used to tie widget callbacks to
+//!   the bar or popover that created them)
+//!
+//! Each event type is expected to have a single publisher (usually the
+//! service that owns the snapshot). The bus is main-thread only; background
+//! threads hand results back with `glib::idle_add_once` before publishing.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! let bus = EventBus::global();
+//! let id = bus.subscribe(|snapshot: &BatterySnapshot| {
+//!     println!("battery: {:?}", snapshot.percent);
+//! });
+//! bus.publish(&battery_snapshot);
+//! bus.unsubscribe(id);
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Unique identifier for a bus subscription.
+///
+/// Used to unsubscribe when the subscriber is no longer interested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Global counter for generating unique subscription IDs.
+static NEXT_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+
+impl SubscriptionId {
+    /// Generate a new unique subscription ID.
+    pub(super) fn new() -> Self {
+        Self(NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A publisher's own topic for values of its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstanceTopic(u64);
+
+/// Global counter for generating unique instance topics.
+static NEXT_INSTANCE_TOPIC: AtomicU64 = AtomicU64::new(1);
+
+impl InstanceTopic {
+    /// Generate a new unique instance topic.
+    pub fn new() -> Self {
+        Self(NEXT_INSTANCE_TOPIC.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for InstanceTopic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Key of a topic: the event type, and the instance for instance topics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TopicKey {
+    type_id: TypeId,
+    instance: Option<InstanceTopic>,
+}
+
+impl TopicKey {
+    fn of<T: 'static>(instance: Option<InstanceTopic>) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            instance,
+        }
+    }
+}
+
+/// Whether a subscriber wants further events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    Keep,
+    /// The subscriber's owner is gone; remove it.
+    Drop,
+}
+
+/// Type-erased subscriber callback.
+type Handler<T> = Rc<dyn Fn(&T) -> Delivery>;
+
+/// Wildcard observer callback: event type name and value.
+type WildcardHandler = Rc<dyn Fn(&'static str, &dyn Any)>;
+
+/// Subscribers and last value for one event type.
+struct Topic<T> {
+    last: Option<T>,
+    subscribers: Vec<(SubscriptionId, Handler<T>)>,
+}
+
+/// Object-safe view of a `Topic<T>` for storage in the type map.
+trait AnyTopic {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn type_name(&self) -> &'static str;
+    fn describe_last(&self) -> Option<String>;
+    fn remove(&mut self, id: SubscriptionId) -> bool;
+    fn contains(&self, id: SubscriptionId) -> bool;
+    fn subscriber_count(&self) -> usize;
+}
+
+impl<T: Debug + 'static> AnyTopic for Topic<T> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_name(&self) -> &'static str {
+        short_type_name::<T>()
+    }
+
+    fn describe_last(&self) -> Option<String> {
+        self.last.as_ref().map(|value| format!("{:?}", value))
+    }
+
+    fn remove(&mut self, id: SubscriptionId) -> bool {
+        let len_before = self.subscribers.len();
+        self.subscribers.retain(|(sub_id, _)| *sub_id != id);
+        self.subscribers.len() < len_before
+    }
+
+    fn contains(&self, id: SubscriptionId) -> bool {
+        self.subscribers.iter().any(|(sub_id, _)| *sub_id == id)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+}
+
+thread_local! {
+    static INSTANCE: Rc<EventBus> = EventBus::new();
+}
+
+/// Subscription IDs recorded by a scope.
+type ScopeIds = Rc<RefCell<Vec<SubscriptionId>>>;
+
+/// Process-wide (main thread) typed event bus.
+pub struct EventBus {
+    topics: RefCell<HashMap<TopicKey, Box<dyn AnyTopic>>>,
+    wildcard: RefCell<Vec<(SubscriptionId, WildcardHandler)>>,
+    /// Entered scopes, innermost last.
+    scopes: RefCell<Vec<ScopeIds>>,
+}
+
+impl EventBus {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            topics: RefCell::new(HashMap::new()),
+            wildcard: RefCell::new(Vec::new()),
+            scopes: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Get the global EventBus singleton.
+    pub fn global() -> Rc<Self> {
+        INSTANCE.with(|bus| bus.clone())
+    }
+
+    /// Like `global`, but `None` once the thread's bus has been destroyed.
+    pub fn try_global() -> Option<Rc<Self>> {
+        INSTANCE.try_with(|bus| bus.clone()).ok()
+    }
+
+    /// Run `f` with the topic for `T` (or its instance topic), creating it
+    /// if needed.
+    fn with_topic<T, R>(
+        &self,
+        instance: Option<InstanceTopic>,
+        f: impl FnOnce(&mut Topic<T>) -> R,
+    ) -> R
+    where
+        T: Clone + Debug + 'static,
+    {
+        let mut topics = self.topics.borrow_mut();
+        let topic = topics
+            .entry(TopicKey::of::<T>(instance))
+            .or_insert_with(|| {
+                Box::new(Topic::<T> {
+                    last: None,
+                    subscribers: Vec::new(),
+                })
+            });
+        let topic = topic
+            .as_any_mut()
+            .downcast_mut::<Topic<T>>()
+            .expect("event bus topic keyed by its own TypeId");
+        f(topic)
+    }
+
+    fn add_subscriber<T>(
+        &self,
+        instance: Option<InstanceTopic>,
+        handler: Handler<T>,
+        replay: bool,
+    ) -> SubscriptionId
+    where
+        T: Clone + Debug + 'static,
+    {
+        let id = SubscriptionId::new();
+        self.record_in_scope(id);
+        let last = self.with_topic(instance, |topic: &mut Topic<T>| {
+            topic.subscribers.push((id, handler.clone()));
+            if replay { topic.last.clone() } else { None }
+        });
+        // Replay outside the borrow so the handler may use the bus
+        if let Some(value) = last
+            && handler(&value) == Delivery::Drop
+        {
+            self.unsubscribe(id);
+        }
+        id
+    }
+
+    /// Subscribe to events of type `T`.
+    ///
+    /// The callback is invoked immediately with the last published value,
+    /// if there is one.
+    pub fn subscribe<T, F>(&self, callback: F) -> SubscriptionId
+    where
+        T: Clone + Debug + 'static,
+        F: Fn(&T) + 'static,
+    {
+        self.add_subscriber(
+            None,
+            Rc::new(move |value: &T| {
+                callback(value);
+                Delivery::Keep
+            }),
+            true,
+        )
+    }
+
+    /// Subscribe to events of type `T` without replaying the last value.
+    pub fn subscribe_no_replay<T, F>(&self, callback: F) -> SubscriptionId
+    where
+        T: Clone + Debug + 'static,
+        F: Fn(&T) + 'static,
+    {
+        self.add_subscriber(
+            None,
+            Rc::new(move |value: &T| {
+                callback(value);
+                Delivery::Keep
+            }),
+            false,
+        )
+    }
+
+    /// Subscribe to values of type `T` published on `topic` only.
+    ///
+    /// The last value is not replayed.
+    pub fn subscribe_instance<T, F>(&self, topic: InstanceTopic, callback: F) -> SubscriptionId
+    where
+        T: Clone + Debug + 'static,
+        F: Fn(&T) + 'static,
+    {
+        self.add_subscriber(
+            Some(topic),
+            Rc::new(move |value: &T| {
+                callback(value);
+                Delivery::Keep
+            }),
+            false,
+        )
+    }
+
+    /// Subscribe to events of type `T` for which `filter` returns `true`.
+    pub fn subscribe_filtered<T, P, F>(&self, filter: P, callback: F) -> SubscriptionId
+    where
+        T: Clone + Debug + 'static,
+        P: Fn(&T) -> bool + 'static,
+        F: Fn(&T) + 'static,
+    {
+        self.add_subscriber(
+            None,
+            Rc::new(move |value: &T| {
+                if filter(value) {
+                    callback(value);
+                }
+                Delivery::Keep
+            }),
+            true,
+        )
+    }
+
+    /// Subscribe to events of type `T` for as long as `owner` is alive.
+    ///
+    /// The callback receives the owner; the subscription is removed on the
+    /// first event after the last strong reference to the owner is dropped.
+    pub fn subscribe_weak<T, O, F>(&self, owner: &Rc<O>, callback: F) -> SubscriptionId
+    where
+        T: Clone + Debug + 'static,
+        O: 'static,
+        F: Fn(&O, &T) + 'static,
+    {
+        let owner: Weak<O> = Rc::downgrade(owner);
+        self.add_subscriber(
+            None,
+            Rc::new(move |value: &T| match owner.upgrade() {
+                Some(owner) => {
+                    callback(&owner, value);
+                    Delivery::Keep
+                }
+                None => Delivery::Drop,
+            }),
+            true,
+        )
+    }
+
+    /// Observe every event of every type.
+    ///
+    /// The callback receives the event's short type name and the value.
+    pub fn subscribe_all<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&'static str, &dyn Any) + 'static,
+    {
+        let id = SubscriptionId::new();
+        self.record_in_scope(id);
+        self.wildcard.borrow_mut().push((id, Rc::new(callback)));
+        id
+    }
+
+    /// Create an empty scope on this bus.
+    pub fn scope(self: &Rc<Self>) -> SubscriptionScope {
+        SubscriptionScope {
+            bus: self.clone(),
+            ids: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Run `f` with no scope entered.
+    ///
+    /// Services that subscribe to other services on first use call this so
+    /// their subscriptions don't end up owned by whichever bar happened to
+    /// create them.
+    pub fn unscoped<R>(&self, f: impl FnOnce() -> R) -> R {
+        let saved = self.scopes.take();
+        let result = f();
+        *self.scopes.borrow_mut() = saved;
+        result
+    }
+
+    fn record_in_scope(&self, id: SubscriptionId) {
+        if let Some(scope) = self.scopes.borrow().last() {
+            scope.borrow_mut().push(id);
+        }
+    }
+
+    /// Remove a subscription.
+    ///
+    /// Returns `true` if the subscription was found and removed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut wildcard = self.wildcard.borrow_mut();
+        let len_before = wildcard.len();
+        wildcard.retain(|(sub_id, _)| *sub_id != id);
+        if wildcard.len() < len_before {
+            return true;
+        }
+        drop(wildcard);
+
+        self.topics
+            .borrow_mut()
+            .values_mut()
+            .any(|topic| topic.remove(id))
+    }
+
+    /// Whether a subscription is still registered.
+    pub fn is_subscribed(&self, id: SubscriptionId) -> bool {
+        let wildcard = self.wildcard.borrow();
+        let topics = self.topics.borrow();
+        wildcard.iter().any(|(sub_id, _)| *sub_id == id)
+            || topics.values().any(|topic| topic.contains(id))
+    }
+
+    /// Publish a value to all subscribers of its type.
+    ///
+    /// The value is stored as the topic's last value for replay. Subscribers
+    /// are invoked outside of any internal borrow, so they may re-enter the
+    /// bus (subscribe, publish, unsubscribe).
+    pub fn publish<T>(&self, value: &T)
+    where
+        T: Clone + Debug + 'static,
+    {
+        let handlers: Vec<(SubscriptionId, Handler<T>)> =
+            self.with_topic(None, |topic: &mut Topic<T>| {
+                topic.last = Some(value.clone());
+                topic.subscribers.clone()
+            });
+        self.invoke(handlers, value);
+
+        let wildcard: Vec<WildcardHandler> = self
+            .wildcard
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        let type_name = short_type_name::<T>();
+        for handler in wildcard {
+            handler(type_name, value);
+        }
+    }
+
+    /// Publish a value to the subscribers of `topic`, then to all
+    /// subscribers of its type like [`publish`](Self::publish).
+    pub fn publish_instance<T>(&self, topic: InstanceTopic, value: &T)
+    where
+        T: Clone + Debug + 'static,
+    {
+        let handlers: Vec<(SubscriptionId, Handler<T>)> = self
+            .with_topic(Some(topic), |topic: &mut Topic<T>| {
+                topic.subscribers.clone()
+            });
+        self.invoke(handlers, value);
+        self.publish(value);
+    }
+
+    fn invoke<T>(&self, handlers: Vec<(SubscriptionId, Handler<T>)>, value: &T) {
+        for (id, handler) in handlers {
+            if handler(value) == Delivery::Drop {
+                self.unsubscribe(id);
+            }
+        }
+    }
+
+    /// Deliver a value to a single subscriber of its type.
+    ///
+    /// Does not update the last value. Returns `true` if the subscriber was
+    /// found.
+    pub fn deliver_to<T>(&self, id: SubscriptionId, value: &T) -> bool
+    where
+        T: Clone + Debug + 'static,
+    {
+        self.deliver(None, id, value)
+    }
+
+    /// Deliver a value to a single subscriber of `topic`.
+    ///
+    /// Returns `true` if the subscriber was found.
+    pub fn deliver_to_instance<T>(
+        &self,
+        topic: InstanceTopic,
+        id: SubscriptionId,
+        value: &T,
+    ) -> bool
+    where
+        T: Clone + Debug + 'static,
+    {
+        self.deliver(Some(topic), id, value)
+    }
+
+    fn deliver<T>(&self, instance: Option<InstanceTopic>, id: SubscriptionId, value: &T) -> bool
+    where
+        T: Clone + Debug + 'static,
+    {
+        let handler = self.with_topic(instance, |topic: &mut Topic<T>| {
+            topic
+                .subscribers
+                .iter()
+                .find(|(sub_id, _)| *sub_id == id)
+                .map(|(_, handler)| handler.clone())
+        });
+        match handler {
+            Some(handler) => {
+                if handler(value) == Delivery::Drop {
+                    self.unsubscribe(id);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The last published value of type `T`, if any.
+    pub fn last<T>(&self) -> Option<T>
+    where
+        T: Clone + Debug + 'static,
+    {
+        self.with_topic(None, |topic: &mut Topic<T>| topic.last.clone())
+    }
+
+    /// Number of subscribers for type `T`.
+    pub fn subscriber_count<T: 'static>(&self) -> usize {
+        self.topics
+            .borrow()
+            .get(&TopicKey::of::<T>(None))
+            .map(|topic| topic.subscriber_count())
+            .unwrap_or(0)
+    }
+
+    /// Number of subscribers across all types and instance topics, including
+    /// wildcard observers.
+    pub fn total_subscriber_count(&self) -> usize {
+        let typed: usize = self
+            .topics
+            .borrow()
+            .values()
+            .map(|topic| topic.subscriber_count())
+            .sum();
+        typed + self.wildcard.borrow().len()
+    }
+
+    /// Debug dump of the last value of every topic, sorted by type name.
+    pub fn dump_state(&self) -> Vec<(&'static str, String)> {
+        let mut state: Vec<(&'static str, String)> = self
+            .topics
+            .borrow()
+            .values()
+            .filter_map(|topic| Some((topic.type_name(), topic.describe_last()?)))
+            .collect();
+        state.sort_by_key(|(name, _)| *name);
+        state
+    }
+}
+
+/// A group of subscriptions removed together when the scope is dropped.
+///
+/// Enter the scope while building something (a bar, a popover) and every
+/// subscription made in the meantime is recorded, including ones made by
+/// service `connect` methods.
+///
+/// Services can be created for the first time while a scope is entered, so
+/// subscriptions a service makes for itself must go through
+/// [`EventBus::unscoped`]; otherwise they would be dropped with whichever
+/// bar happened to create the service.
+pub struct SubscriptionScope {
+    bus: Rc<EventBus>,
+    ids: ScopeIds,
+}
+
+impl SubscriptionScope {
+    /// Record subscriptions in this scope until the guard is dropped.
+    pub fn enter(&self) -> ScopeGuard {
+        self.bus.scopes.borrow_mut().push(self.ids.clone());
+        ScopeGuard {
+            bus: self.bus.clone(),
+        }
+    }
+
+    /// Number of subscriptions recorded.
+    pub fn len(&self) -> usize {
+        self.ids.borrow().len()
+    }
+
+    /// Returns true if no subscriptions were recorded.
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.ids.borrow().is_empty()
+    }
+}
+
+impl Drop for SubscriptionScope {
+    fn drop(&mut self) {
+        let ids: Vec<SubscriptionId> = self.ids.borrow_mut().drain(..).collect();
+        for id in ids {
+            self.bus.unsubscribe(id);
+        }
+    }
+}
+
+/// Keeps a scope entered; see [`SubscriptionScope::enter`].
+#[must_use = "the scope is left as soon as the guard is dropped"]
+pub struct ScopeGuard {
+    bus: Rc<EventBus>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        self.bus.scopes.borrow_mut().pop();
+    }
+}
+
+/// Type name without its module path, e.g. "BatterySnapshot".
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    // Keep generic arguments intact: only strip the path before the first '<'
+    let base_end = name.find('<').unwrap_or(name.len());
+    let start = name[..base_end].rfind("::").map(|i| i + 2).unwrap_or(0);
+    &name[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Level(u32);
+
+    #[test]
+    fn test_subscribe_replays_last_value() {
+        let bus = EventBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        bus.publish(&Level(1));
+        let seen_clone = seen.clone();
+        bus.subscribe(move |level: &Level| seen_clone.borrow_mut().push(level.0));
+        bus.publish(&Level(2));
+
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+        assert_eq!(bus.last::<Level>(), Some(Level(2)));
+    }
+
+    #[test]
+    fn test_filtered_subscription() {
+        let bus = EventBus::new();
+        let count = Rc::new(Cell::new(0));
+
+        let count_clone = count.clone();
+        bus.subscribe_filtered(
+            |level: &Level| level.0 < 20,
+            move |_| count_clone.set(count_clone.get() + 1),
+        );
+        bus.publish(&Level(50));
+        bus.publish(&Level(10));
+
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn test_weak_subscription_dropped_with_owner() {
+        let bus = EventBus::new();
+        let owner = Rc::new(Cell::new(0));
+
+        bus.subscribe_weak(&owner, |owner: &Cell<u32>, level: &Level| {
+            owner.set(level.0);
+        });
+        bus.publish(&Level(7));
+        assert_eq!(owner.get(), 7);
+        assert_eq!(bus.subscriber_count::<Level>(), 1);
+
+        drop(owner);
+        bus.publish(&Level(8));
+        assert_eq!(bus.subscriber_count::<Level>(), 0);
+    }
+
+    #[test]
+    fn test_unsubscribe_and_wildcard() {
+        let bus = EventBus::new();
+        let names = Rc::new(RefCell::new(Vec::new()));
+
+        let names_clone = names.clone();
+        let id = bus.subscribe_all(move |name, _| names_clone.borrow_mut().push(name));
+        bus.publish(&Level(1));
+        bus.publish(&true);
+        assert!(bus.unsubscribe(id));
+        assert!(!bus.unsubscribe(id));
+        bus.publish(&Level(2));
+
+        assert_eq!(*names.borrow(), vec!["Level", "bool"]);
+        assert_eq!(
+            bus.dump_state(),
+            vec![
+                ("Level", "Level(2)".to_string()),
+                ("bool", "true".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reentrant_publish() {
+        let bus = EventBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let bus_clone = bus.clone();
+        bus.subscribe_no_replay(move |level: &Level| {
+            if level.0 == 1 {
+                bus_clone.publish(&format!("level {}", level.0));
+            }
+        });
+        let seen_clone = seen.clone();
+        bus.subscribe(move |msg: &String| seen_clone.borrow_mut().push(msg.clone()));
+        bus.publish(&Level(1));
+
+        assert_eq!(*seen.borrow(), vec!["level 1".to_string()]);
+    }
+
+    #[test]
+    fn test_scope_removes_subscriptions_on_drop() {
+        let bus = EventBus::new();
+        let outer = bus.scope();
+        let inner = bus.scope();
+
+        let outer_guard = outer.enter();
+        let outer_id = bus.subscribe_no_replay(|_: &Level| {});
+        {
+            let _inner_guard = inner.enter();
+            bus.subscribe_no_replay(|_: &Level| {});
+            bus.subscribe_all(|_, _| {});
+            // Service-owned subscriptions stay out of the scope
+            bus.unscoped(|| bus.subscribe_no_replay(|_: &bool| {}));
+        }
+        drop(outer_guard);
+        bus.subscribe_no_replay(|_: &Level| {});
+
+        assert_eq!((outer.len(), inner.len()), (1, 2));
+        assert_eq!(bus.total_subscriber_count(), 5);
+
+        drop(inner);
+        assert_eq!(bus.total_subscriber_count(), 3);
+        assert!(bus.is_subscribed(outer_id));
+
+        drop(outer);
+        assert!(!bus.is_subscribed(outer_id));
+        assert_eq!(bus.subscriber_count::<Level>(), 1);
+        assert_eq!(bus.subscriber_count::<bool>(), 1);
+    }
+
+    #[test]
+    fn test_instance_topics_are_separate() {
+        let bus = EventBus::new();
+        let (first, second) = (InstanceTopic::new(), InstanceTopic::new());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        for (name, topic) in [("first", first), ("second", second)] {
+            let seen = seen.clone();
+            bus.subscribe_instance(topic, move |level: &Level| {
+                seen.borrow_mut().push((name, level.0))
+            });
+        }
+        let seen_clone = seen.clone();
+        bus.subscribe_no_replay(move |level: &Level| {
+            seen_clone.borrow_mut().push(("all", level.0))
+        });
+
+        bus.publish_instance(first, &Level(1));
+        bus.publish_instance(second, &Level(2));
+        bus.publish(&Level(3));
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                ("first", 1),
+                ("all", 1),
+                ("second", 2),
+                ("all", 2),
+                ("all", 3)
+            ]
+        );
+        assert_eq!(bus.last::<Level>(), Some(Level(3)));
+        assert_eq!(bus.subscriber_count::<Level>(), 1);
+        assert_eq!(bus.total_subscriber_count(), 3);
+        assert_eq!(bus.dump_state(), vec![("Level", "Level(3)".to_string())]);
+    }
+
+    /// A service created lazily while a bar is built, which follows
+    /// another service on its own.
+    struct Follower {
+        seen: Cell<u32>,
+    }
+
+    impl Follower {
+        fn new(bus: &Rc<EventBus>) -> Rc<Self> {
+            let follower = Rc::new(Self { seen: Cell::new(0) });
+            let weak = Rc::downgrade(&follower);
+            bus.unscoped(|| {
+                bus.subscribe_no_replay(move |level: &Level| {
+                    if let Some(this) = weak.upgrade() {
+                        this.seen.set(level.0);
+                    }
+                })
+            });
+            follower
+        }
+    }
+
+    #[test]
+    fn test_service_keeps_updates_after_bar_removed() {
+        let bus = EventBus::new();
+        let bar = bus.scope();
+        let widget_seen = Rc::new(Cell::new(0));
+
+        let follower = {
+            let _guard = bar.enter();
+            // First use of the service happens while the bar is built
+            let follower = Follower::new(&bus);
+            let widget_seen = widget_seen.clone();
+            bus.subscribe_no_replay(move |level: &Level| widget_seen.set(level.0));
+            follower
+        };
+        assert_eq!(bar.len(), 1);
+
+        // Monitor unplugged: the bar and its widget subscriptions go away
+        drop(bar);
+        bus.publish(&Level(3));
+        assert_eq!(follower.seen.get(), 3);
+        assert_eq!(widget_seen.get(), 0);
+    }
+
+    #[test]
+    fn test_service_keeps_updates_after_bar_reconfigured() {
+        let bus = EventBus::new();
+        let first = bus.scope();
+        let follower = {
+            let _guard = first.enter();
+            Follower::new(&bus)
+        };
+
+        // Config reload: a new bar is built before the old one is dropped
+        let second = bus.scope();
+        {
+            let _guard = second.enter();
+            bus.subscribe_no_replay(|_: &Level| {});
+        }
+        drop(first);
+        bus.publish(&Level(4));
+        assert_eq!(follower.seen.get(), 4);
+
+        drop(second);
+        bus.publish(&Level(5));
+        assert_eq!(follower.seen.get(), 5);
+        assert_eq!(bus.subscriber_count::<Level>(), 1);
+    }
+}
@@ -0,0 +1,231 @@
+//! GeoClueService - approximate location from GeoClue.
+//!
+//! Shared by services that fall back to the user's location when none is
+//! configured (night light, weather). The GeoClue client is started on the
+//! first `connect()` at city accuracy and follows location updates; GeoClue
+//! hands out one client per D-Bus connection, so there is one per process.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk4::gio::{self, BusType};
+use gtk4::prelude::*;
+use tracing::{debug, warn};
+
+use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "geoclue";
+
+const GEOCLUE_BUS_NAME: &str = "org.freedesktop.GeoClue2";
+const GEOCLUE_MANAGER_PATH: &str = "/org/freedesktop/GeoClue2/Manager";
+const GEOCLUE_MANAGER_IFACE: &str = "org.freedesktop.GeoClue2.Manager";
+const GEOCLUE_CLIENT_IFACE: &str = "org.freedesktop.GeoClue2.Client";
+const GEOCLUE_LOCATION_IFACE: &str = "org.freedesktop.GeoClue2.Location";
+const PROPERTIES_IFACE: &str = "org.freedesktop.DBus.Properties";
+
+/// GeoClue accuracy level for city-level location.
+const GEOCLUE_ACCURACY_CITY: u32 = 4;
+
+/// Desktop ID reported to GeoClue (used for its app authorization).
+const DESKTOP_ID: &str = "io.github.vibepanel";
+
+/// Canonical snapshot of the GeoClue location.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GeoClueSnapshot {
+    /// `(latitude, longitude)` once GeoClue reported one.
+    pub location: Option<(f64, f64)>,
+}
+
+/// Shared, process-wide GeoClue client.
+pub struct GeoClueService {
+    snapshot: Cell<GeoClueSnapshot>,
+    callbacks: Callbacks<GeoClueSnapshot>,
+    started: Cell<bool>,
+    /// Signal subscription (kept alive while the client runs).
+    subscription: RefCell<Option<gio::SignalSubscription>>,
+}
+
+impl GeoClueService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: Cell::new(GeoClueSnapshot::default()),
+            callbacks: Callbacks::new(),
+            started: Cell::new(false),
+            subscription: RefCell::new(None),
+        })
+    }
+
+    /// Get the global GeoClueService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<GeoClueService> = GeoClueService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the location changes,
+    /// starting the GeoClue client if it isn't running yet.
+    pub fn connect<F>(self: &Rc<Self>, callback: F)
+    where
+        F: Fn(&GeoClueSnapshot) + 'static,
+    {
+        if !self.started.replace(true) {
+            self.start();
+        }
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        self.callbacks.notify(&self.snapshot.get());
+    }
+
+    /// Ask GeoClue for the location and follow its updates.
+    fn start(self: &Rc<Self>) {
+        let this_weak = Rc::downgrade(self);
+        gio::bus_get(BusType::System, None::<&gio::Cancellable>, move |res| {
+            let connection = match res {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!("GeoClueService: failed to get system bus: {}", e);
+                    return;
+                }
+            };
+            let connection_for_client = connection.clone();
+            dbus_watchdog::call(
+                WATCHDOG_SERVICE,
+                &connection,
+                GEOCLUE_BUS_NAME,
+                GEOCLUE_MANAGER_PATH,
+                GEOCLUE_MANAGER_IFACE,
+                "GetClient",
+                None,
+                None,
+                CallPolicy::ACTION,
+                move |res| {
+                    let client_path = match res {
+                        Ok(reply) => reply.child_value(0).str().map(str::to_string),
+                        Err(e) => {
+                            warn!(
+                                "GeoClueService: GeoClue unavailable, configure coordinates instead: {}",
+                                e
+                            );
+                            return;
+                        }
+                    };
+                    if let (Some(this), Some(client_path)) = (this_weak.upgrade(), client_path) {
+                        this.start_client(&connection_for_client, &client_path);
+                    }
+                },
+            );
+        });
+    }
+
+    fn start_client(self: &Rc<Self>, connection: &gio::DBusConnection, client_path: &str) {
+        // GeoClue requires a desktop ID and accuracy before Start
+        for (property, value) in [
+            ("DesktopId", DESKTOP_ID.to_variant()),
+            ("RequestedAccuracyLevel", GEOCLUE_ACCURACY_CITY.to_variant()),
+        ] {
+            dbus_watchdog::call(
+                WATCHDOG_SERVICE,
+                connection,
+                GEOCLUE_BUS_NAME,
+                client_path,
+                PROPERTIES_IFACE,
+                "Set",
+                Some(&(GEOCLUE_CLIENT_IFACE, property, value).to_variant()),
+                None,
+                CallPolicy::ACTION,
+                move |res| {
+                    if let Err(e) = res {
+                        warn!("GeoClueService: failed to set {}: {}", property, e);
+                    }
+                },
+            );
+        }
+
+        let this_weak = Rc::downgrade(self);
+        let connection_for_signal = connection.clone();
+        let subscription = connection.subscribe_to_signal(
+            Some(GEOCLUE_BUS_NAME),
+            Some(GEOCLUE_CLIENT_IFACE),
+            Some("LocationUpdated"),
+            Some(client_path),
+            None,
+            gio::DBusSignalFlags::NONE,
+            move |signal| {
+                let Some(location_path) =
+                    signal.parameters.child_value(1).str().map(str::to_string)
+                else {
+                    return;
+                };
+                if let Some(this) = this_weak.upgrade() {
+                    this.read_location(&connection_for_signal, &location_path);
+                }
+            },
+        );
+        *self.subscription.borrow_mut() = Some(subscription);
+
+        dbus_watchdog::call(
+            WATCHDOG_SERVICE,
+            connection,
+            GEOCLUE_BUS_NAME,
+            client_path,
+            GEOCLUE_CLIENT_IFACE,
+            "Start",
+            None,
+            None,
+            CallPolicy::ACTION,
+            |res| {
+                if let Err(e) = res {
+                    warn!("GeoClueService: failed to start client: {}", e);
+                }
+            },
+        );
+    }
+
+    fn read_location(self: &Rc<Self>, connection: &gio::DBusConnection, path: &str) {
+        let this_weak = Rc::downgrade(self);
+        dbus_watchdog::call(
+            WATCHDOG_SERVICE,
+            connection,
+            GEOCLUE_BUS_NAME,
+            path,
+            PROPERTIES_IFACE,
+            "GetAll",
+            Some(&(GEOCLUE_LOCATION_IFACE,).to_variant()),
+            None,
+            CallPolicy::QUERY,
+            move |res| {
+                let properties = match res {
+                    Ok(reply) => reply.child_value(0),
+                    Err(e) => {
+                        warn!("GeoClueService: failed to read location: {}", e);
+                        return;
+                    }
+                };
+                let coordinate = |name: &str| {
+                    properties
+                        .lookup_value(name, None)
+                        .and_then(|value| value.get::<f64>())
+                };
+                let (Some(latitude), Some(longitude)) =
+                    (coordinate("Latitude"), coordinate("Longitude"))
+                else {
+                    return;
+                };
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                debug!("GeoClueService: location {:.2}, {:.2}", latitude, longitude);
+                let snapshot = GeoClueSnapshot {
+                    location: Some((latitude, longitude)),
+                };
+                if this.snapshot.replace(snapshot) != snapshot {
+                    this.callbacks.notify(&snapshot);
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! GitService - working tree and push state for configured repositories.
+//!
+//! This service provides:
+//! - Dirty state (modified, staged and untracked files) per repository
+//! - Ahead/behind counts of the current branch against its upstream
+//! - Local branches with unpushed commits or without an upstream
+//! - Periodic checks in a background thread via `git` subprocesses
+//!
+//! Ahead/behind counts are relative to the last fetch; the service never
+//! touches the network.
+
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+use gtk4::glib::{self, SourceId};
+use tracing::{debug, info};
+
+use super::callbacks::Callbacks;
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings, merge_unique};
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for GitService
+thread_local! {
+    static GIT_INSTANCE: RefCell<Option<Rc<GitService>>> = const { RefCell::new(None) };
+}
+
+/// Default check interval in seconds.
+pub const DEFAULT_CHECK_INTERVAL: u64 = 60;
+
+/// Minimum check interval in seconds.
+const MIN_CHECK_INTERVAL: u64 = 5;
+
+/// Settings for the repository checker (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitSettings {
+    /// Repository working tree paths.
+    pub repos: Vec<PathBuf>,
+    /// How often to check (seconds).
+    pub check_interval: u64,
+}
+
+impl MergeSettings for GitSettings {
+    /// Watch every widget's repositories at the shortest interval.
+    fn merge(&mut self, other: &Self) {
+        merge_unique(&mut self.repos, &other.repos);
+        self.check_interval = self.check_interval.min(other.check_interval);
+    }
+}
+
+/// State of a single repository.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoStatus {
+    /// Working tree path.
+    pub path: PathBuf,
+    /// Display name (last path component).
+    pub name: String,
+    /// Current branch, or `None` when detached.
+    pub branch: Option<String>,
+    /// Number of modified, staged or conflicted files.
+    pub changed: usize,
+    /// Number of untracked files.
+    pub untracked: usize,
+    /// Commits on the current branch not on its upstream.
+    pub ahead: usize,
+    /// Commits on the upstream not on the current branch.
+    pub behind: usize,
+    /// Other local branches with unpushed commits or no upstream.
+    pub unpushed_branches: Vec<String>,
+    /// Error message if the repository couldn't be read.
+    pub error: Option<String>,
+}
+
+impl RepoStatus {
+    /// Whether the working tree has uncommitted changes.
+    pub fn is_dirty(&self) -> bool {
+        self.changed > 0 || self.untracked > 0
+    }
+
+    /// Whether the repository has work that would be lost with the machine.
+    pub fn needs_attention(&self) -> bool {
+        self.error.is_none()
+            && (self.is_dirty() || self.ahead > 0 || !self.unpushed_branches.is_empty())
+    }
+}
+
+/// Canonical snapshot of repository state.
+#[derive(Debug, Clone, Default)]
+pub struct GitSnapshot {
+    /// Whether any repositories are configured.
+    pub available: bool,
+    /// Whether a check is currently in progress.
+    pub checking: bool,
+    /// Repository states, in config order.
+    pub repos: Vec<RepoStatus>,
+}
+
+impl GitSnapshot {
+    /// Repositories with uncommitted or unpushed work.
+    pub fn attention_repos(&self) -> impl Iterator<Item = &RepoStatus> {
+        self.repos.iter().filter(|repo| repo.needs_attention())
+    }
+}
+
+/// Shared, process-wide git status service.
+pub struct GitService {
+    snapshot: RefCell<GitSnapshot>,
+    callbacks: Callbacks<GitSnapshot>,
+    /// Settings of each git widget.
+    registered: WidgetSettings<GitSettings>,
+    /// Merged settings the checks run with.
+    settings: RefCell<Option<GitSettings>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent concurrent checks.
+    check_in_progress: Cell<bool>,
+}
+
+impl GitService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(GitSnapshot::default()),
+            callbacks: Callbacks::new(),
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global GitService singleton.
+    pub fn global() -> Rc<Self> {
+        GIT_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(GitService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        GIT_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&GitSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> GitSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: GitSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        self.notify();
+    }
+
+    /// Start watching a git widget's repositories.
+    pub fn configure(self: &Rc<Self>, settings: GitSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's repositories; checks stop with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Restart checks with the merged settings of all widgets.
+    fn apply_settings(self: &Rc<Self>) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let Some(settings) = settings else {
+            debug!("GitService: no widgets left, stopping checks");
+            *self.settings.borrow_mut() = None;
+            *self.snapshot.borrow_mut() = GitSnapshot::default();
+            self.notify();
+            return;
+        };
+
+        info!(
+            "GitService: watching {} repositories every {}s",
+            settings.repos.len(),
+            settings.check_interval
+        );
+
+        let interval = settings.check_interval.max(MIN_CHECK_INTERVAL);
+        self.snapshot.borrow_mut().available = !settings.repos.is_empty();
+        *self.settings.borrow_mut() = Some(settings);
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Trigger an immediate check of all repositories.
+    pub fn refresh(&self) {
+        if self.check_in_progress.get() {
+            debug!("GitService: check already in progress, skipping");
+            return;
+        }
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+
+        self.check_in_progress.set(true);
+        self.snapshot.borrow_mut().checking = true;
+        self.notify();
+
+        WorkerPool::global().spawn(
+            move || -> Vec<RepoStatus> { settings.repos.iter().map(|p| check_repo(p)).collect() },
+            |repos| GitService::global().apply_check_result(repos.ok()),
+        );
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply the result of a background check (`None` if it panicked).
+    fn apply_check_result(&self, repos: Option<Vec<RepoStatus>>) {
+        self.check_in_progress.set(false);
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.checking = false;
+            if let Some(mut repos) = repos {
+                // Drop repositories whose widgets went away during the check
+                let settings = self.settings.borrow();
+                repos.retain(|repo| {
+                    settings
+                        .as_ref()
+                        .is_some_and(|settings| settings.repos.contains(&repo.path))
+                });
+                snapshot.repos = repos;
+            }
+        }
+        self.notify();
+    }
+}
+
+impl Drop for GitService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Check a single repository.
+fn check_repo(path: &Path) -> RepoStatus {
+    let mut status = RepoStatus {
+        path: path.to_path_buf(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string()),
+        ..Default::default()
+    };
+
+    match run_git(path, &["status", "--porcelain=v2", "--branch"]) {
+        Ok(output) => parse_status(&output, &mut status),
+        Err(err) => {
+            status.error = Some(err);
+            return status;
+        }
+    }
+
+    match run_git(
+        path,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)\t%(upstream:short)\t%(upstream:track)",
+            "refs/heads",
+        ],
+    ) {
+        Ok(output) => {
+            status.unpushed_branches = parse_unpushed_branches(&output)
+                .into_iter()
+                .filter(|branch| status.branch.as_deref() != Some(branch.as_str()))
+                .collect();
+        }
+        Err(err) => status.error = Some(err),
+    }
+
+    status
+}
+
+/// Run a git command in `path` and return its stdout.
+fn run_git(path: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .stdin(Stdio::null())
+        // Never block on credential or editor prompts
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().next().unwrap_or("").trim();
+        return Err(message
+            .strip_prefix("fatal: ")
+            .unwrap_or(message)
+            .to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `git status --porcelain=v2 --branch` output into `status`.
+fn parse_status(output: &str, status: &mut RepoStatus) {
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            status.branch = (head != "(detached)").then(|| head.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        } else if line.starts_with("1 ") || line.starts_with("2 ") || line.starts_with("u ") {
+            status.changed += 1;
+        }
+    }
+}
+
+/// Parse `git for-each-ref` output (name, upstream, track separated by tabs).
+///
+/// Returns branches without an upstream or ahead of it.
+fn parse_unpushed_branches(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.trim();
+            let upstream = fields.next().unwrap_or("").trim();
+            let track = fields.next().unwrap_or("").trim();
+            let unpushed = upstream.is_empty() || track.contains("ahead");
+            (!name.is_empty() && unpushed).then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let output = "# branch.oid 1234567890abcdef\n\
+                      # branch.head main\n\
+                      # branch.upstream origin/main\n\
+                      # branch.ab +2 -1\n\
+                      1 .M N... 100644 100644 100644 abc def src/main.rs\n\
+                      2 R. N... 100644 100644 100644 abc def R100 new.rs\told.rs\n\
+                      ? notes.txt\n";
+        let mut status = RepoStatus::default();
+        parse_status(output, &mut status);
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(status.changed, 2);
+        assert_eq!(status.untracked, 1);
+        assert!(status.needs_attention());
+    }
+
+    #[test]
+    fn test_parse_status_clean_detached() {
+        let mut status = RepoStatus::default();
+        parse_status("# branch.oid abc\n# branch.head (detached)\n", &mut status);
+
+        assert_eq!(status.branch, None);
+        assert!(!status.is_dirty());
+        assert!(!status.needs_attention());
+    }
+
+    #[test]
+    fn test_parse_unpushed_branches() {
+        let output = "main\torigin/main\t\n\
+                      feature\torigin/feature\t[ahead 3]\n\
+                      stale\torigin/stale\t[behind 2]\n\
+                      local-only\t\t\n";
+        assert_eq!(
+            parse_unpushed_branches(output),
+            vec!["feature".to_string(), "local-only".to_string()]
+        );
+    }
+}
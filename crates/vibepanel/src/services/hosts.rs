@@ -0,0 +1,497 @@
+//! HostsService - reachability checks for configured remote hosts.
+//!
+//! This service provides:
+//! - Periodic TCP connect probes (optionally verifying an SSH banner)
+//! - Up/down state and connect latency per host
+//!
+//! All hosts are probed concurrently in a background thread so a single
+//! unreachable host doesn't delay the others beyond the probe timeout.
+
+use std::cell::{Cell, RefCell};
+use std::io::{BufRead, BufReader};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use gtk4::glib::{self, SourceId};
+use tracing::{debug, info};
+
+use super::callbacks::Callbacks;
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings, merge_unique};
+
+// Thread-local singleton storage for HostsService
+thread_local! {
+    static HOSTS_INSTANCE: RefCell<Option<Rc<HostsService>>> = const { RefCell::new(None) };
+}
+
+/// Default check interval in seconds.
+pub const DEFAULT_CHECK_INTERVAL: u64 = 60;
+
+/// Default probe timeout in seconds.
+pub const DEFAULT_TIMEOUT: u64 = 3;
+
+/// Default port probed when none is given.
+pub const DEFAULT_PORT: u16 = 22;
+
+/// How a host is probed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeKind {
+    /// Plain TCP connect.
+    Tcp,
+    /// TCP connect plus an `SSH-` protocol banner.
+    Ssh,
+}
+
+impl ProbeKind {
+    /// Parse a probe name from config.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "tcp" => Some(Self::Tcp),
+            "ssh" => Some(Self::Ssh),
+            _ => None,
+        }
+    }
+}
+
+/// A host to check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostSpec {
+    /// Display name.
+    pub name: String,
+    /// Hostname or IP address.
+    pub host: String,
+    /// Port to probe.
+    pub port: u16,
+    /// SSH user, if any.
+    pub user: Option<String>,
+}
+
+impl HostSpec {
+    /// Parse a `[user@]host[:port]` string.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let (user, rest) = match spec.split_once('@') {
+            Some((user, rest)) if !user.is_empty() => (Some(user.to_string()), rest),
+            Some(_) => return None,
+            None => (None, spec),
+        };
+        // Bracketed IPv6 ("[::1]:22") or host[:port]
+        let (host, port) = if let Some(inner) = rest.strip_prefix('[') {
+            let (host, after) = inner.split_once(']')?;
+            let port = match after.strip_prefix(':') {
+                Some(port) => port.parse().ok()?,
+                None if after.is_empty() => DEFAULT_PORT,
+                None => return None,
+            };
+            (host, port)
+        } else {
+            match rest.rsplit_once(':') {
+                Some((host, port)) if !host.contains(':') => (host, port.parse().ok()?),
+                _ => (rest, DEFAULT_PORT),
+            }
+        };
+        if host.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: host.to_string(),
+            host: host.to_string(),
+            port,
+            user,
+        })
+    }
+
+    /// SSH destination, e.g. "user@host".
+    pub fn ssh_destination(&self) -> String {
+        match self.user {
+            Some(ref user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// Reachability of a host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostState {
+    /// Not checked yet.
+    Unknown,
+    /// The probe succeeded.
+    Up,
+    /// The probe failed.
+    Down,
+}
+
+/// Latest probe result for one host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostStatus {
+    /// The checked host.
+    pub spec: HostSpec,
+    /// Reachability.
+    pub state: HostState,
+    /// Connect latency when up.
+    pub latency: Option<Duration>,
+    /// Failure reason when down.
+    pub error: Option<String>,
+}
+
+/// Settings for the host checker (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostsSettings {
+    /// Hosts to check.
+    pub hosts: Vec<HostSpec>,
+    /// How hosts are probed.
+    pub probe: ProbeKind,
+    /// Probe timeout.
+    pub timeout: Duration,
+    /// How often to check (seconds).
+    pub check_interval: u64,
+}
+
+impl MergeSettings for HostsSettings {
+    /// Check every widget's hosts at the shortest interval and longest
+    /// timeout; the probe of the newest widget wins.
+    fn merge(&mut self, other: &Self) {
+        merge_unique(&mut self.hosts, &other.hosts);
+        self.probe = other.probe;
+        self.timeout = self.timeout.max(other.timeout);
+        self.check_interval = self.check_interval.min(other.check_interval);
+    }
+}
+
+/// Canonical snapshot of host reachability.
+#[derive(Debug, Clone, Default)]
+pub struct HostsSnapshot {
+    /// Per-host status, in config order.
+    pub hosts: Vec<HostStatus>,
+}
+
+impl HostsSnapshot {
+    /// Number of hosts that are down.
+    pub fn down_count(&self) -> usize {
+        self.hosts
+            .iter()
+            .filter(|h| h.state == HostState::Down)
+            .count()
+    }
+}
+
+/// Shared, process-wide hosts service.
+pub struct HostsService {
+    snapshot: RefCell<HostsSnapshot>,
+    callbacks: Callbacks<HostsSnapshot>,
+    /// Settings of each hosts widget.
+    registered: WidgetSettings<HostsSettings>,
+    /// Merged settings the checks run with.
+    settings: RefCell<Option<HostsSettings>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent overlapping checks.
+    check_in_progress: Cell<bool>,
+}
+
+impl HostsService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(HostsSnapshot::default()),
+            callbacks: Callbacks::new(),
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global HostsService singleton.
+    pub fn global() -> Rc<Self> {
+        HOSTS_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(HostsService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        HOSTS_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&HostsSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> HostsSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: HostsSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        self.notify();
+    }
+
+    /// Register a hosts widget and start probing its hosts.
+    pub fn configure(self: &Rc<Self>, settings: HostsSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's hosts; probing stops with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Restart checks with the merged settings of all widgets.
+    fn apply_settings(self: &Rc<Self>) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let Some(settings) = settings else {
+            debug!("HostsService: no widgets left, stopping checks");
+            *self.settings.borrow_mut() = None;
+            self.snapshot.borrow_mut().hosts.clear();
+            self.notify();
+            return;
+        };
+
+        info!(
+            "HostsService: checking {} hosts every {}s",
+            settings.hosts.len(),
+            settings.check_interval
+        );
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let previous = std::mem::take(&mut snapshot.hosts);
+            // Keep the known state of hosts that are still checked
+            snapshot.hosts = settings
+                .hosts
+                .iter()
+                .map(|spec| {
+                    previous
+                        .iter()
+                        .find(|status| status.spec == *spec)
+                        .cloned()
+                        .unwrap_or_else(|| HostStatus {
+                            spec: spec.clone(),
+                            state: HostState::Unknown,
+                            latency: None,
+                            error: None,
+                        })
+                })
+                .collect();
+        }
+        let interval = settings.check_interval.max(1);
+        *self.settings.borrow_mut() = Some(settings);
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.notify();
+        self.refresh();
+    }
+
+    /// Trigger an immediate check of all hosts.
+    pub fn refresh(&self) {
+        if self.check_in_progress.get() {
+            return;
+        }
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+
+        self.check_in_progress.set(true);
+        std::thread::spawn(move || {
+            let results: Vec<HostStatus> = std::thread::scope(|scope| {
+                let handles: Vec<_> = settings
+                    .hosts
+                    .iter()
+                    .map(|spec| scope.spawn(|| probe_host(spec, settings.probe, settings.timeout)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .zip(&settings.hosts)
+                    .map(|(handle, spec)| {
+                        handle.join().unwrap_or_else(|_| HostStatus {
+                            spec: spec.clone(),
+                            state: HostState::Down,
+                            latency: None,
+                            error: Some("Probe failed".to_string()),
+                        })
+                    })
+                    .collect()
+            });
+            glib::idle_add_once(move || {
+                HostsService::global().apply_results(results);
+            });
+        });
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply the results of a background check.
+    fn apply_results(&self, results: Vec<HostStatus>) {
+        self.check_in_progress.set(false);
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            for result in results {
+                // Results for hosts removed by a reconfigure are dropped
+                if let Some(status) = snapshot.hosts.iter_mut().find(|h| h.spec == result.spec) {
+                    if status.state != result.state {
+                        debug!("HostsService: {} is {:?}", result.spec.name, result.state);
+                    }
+                    *status = result;
+                }
+            }
+        }
+        self.notify();
+    }
+}
+
+impl Drop for HostsService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Probe a single host.
+fn probe_host(spec: &HostSpec, probe: ProbeKind, timeout: Duration) -> HostStatus {
+    let started = Instant::now();
+    let result = connect(spec, timeout).and_then(|stream| match probe {
+        ProbeKind::Tcp => Ok(()),
+        ProbeKind::Ssh => check_ssh_banner(stream, timeout),
+    });
+
+    match result {
+        Ok(()) => HostStatus {
+            spec: spec.clone(),
+            state: HostState::Up,
+            latency: Some(started.elapsed()),
+            error: None,
+        },
+        Err(err) => HostStatus {
+            spec: spec.clone(),
+            state: HostState::Down,
+            latency: None,
+            error: Some(err),
+        },
+    }
+}
+
+/// Resolve and connect to the host, trying each address in turn.
+fn connect(spec: &HostSpec, timeout: Duration) -> Result<TcpStream, String> {
+    let addrs = (spec.host.as_str(), spec.port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Cannot resolve {}: {}", spec.host, e))?;
+
+    let mut last_error = format!("No addresses for {}", spec.host);
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+/// Read the server's identification line and check it's SSH.
+fn check_ssh_banner(stream: TcpStream, timeout: Duration) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    // Servers may send other lines before the version string (RFC 4253 4.2)
+    for _ in 0..5 {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) if is_ssh_banner(&line) => return Ok(()),
+            Ok(_) => continue,
+            Err(e) => return Err(format!("No SSH banner: {}", e)),
+        }
+    }
+    Err("No SSH banner".to_string())
+}
+
+/// Whether a line is an SSH protocol version exchange line.
+fn is_ssh_banner(line: &str) -> bool {
+    line.starts_with("SSH-2.0-") || line.starts_with("SSH-1.99-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_host_spec() {
+        assert_eq!(
+            HostSpec::parse("admin@nas.lan:2222"),
+            Some(HostSpec {
+                name: "nas.lan".to_string(),
+                host: "nas.lan".to_string(),
+                port: 2222,
+                user: Some("admin".to_string()),
+            })
+        );
+
+        let plain = HostSpec::parse("build01").unwrap();
+        assert_eq!(plain.port, DEFAULT_PORT);
+        assert_eq!(plain.ssh_destination(), "build01");
+
+        let v6 = HostSpec::parse("[fd00::1]:8022").unwrap();
+        assert_eq!((v6.host.as_str(), v6.port), ("fd00::1", 8022));
+
+        assert!(HostSpec::parse("host:notaport").is_none());
+        assert!(HostSpec::parse("@host").is_none());
+    }
+
+    #[test]
+    fn test_is_ssh_banner() {
+        assert!(is_ssh_banner("SSH-2.0-OpenSSH_9.6\r\n"));
+        assert!(!is_ssh_banner("HTTP/1.1 400 Bad Request\r\n"));
+    }
+
+    #[test]
+    fn test_probe_local_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let spec = HostSpec::parse(&format!("127.0.0.1:{}", port)).unwrap();
+
+        let status = probe_host(&spec, ProbeKind::Tcp, Duration::from_secs(1));
+        assert_eq!(status.state, HostState::Up);
+
+        drop(listener);
+        let status = probe_host(&spec, ProbeKind::Tcp, Duration::from_secs(1));
+        assert_eq!(status.state, HostState::Down);
+    }
+}
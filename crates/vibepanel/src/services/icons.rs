@@ -11,29 +11,48 @@
 //!   icons as `Gtk.Image` widgets. Logical icon names are mapped to GTK
 //!   symbolic icon names.
 //!
+//! - **Nerd Font**: Uses an installed Nerd Font (configurable family) and
+//!   maps logical icon names to its private-use codepoints. Nothing is
+//!   bundled; the font has to be installed system-wide.
+//!
 //! - **Text fallback**: When neither Material nor GTK backends are available,
 //!   displays the logical icon name as plain text.
 //!
+//! Single icons can be replaced by image files (`theme.icons.overrides`),
+//! whatever the backend: an overridden name is shown as a `Gtk.Image`
+//! rendered from the file at the icon size and the widget's scale factor.
+//!
+//! The logical name → Material / GTK / Nerd Font mapping can be extended
+//! from the config (`theme.icons.map`). A user entry wins over the built-in
+//! mapping for the backends it sets; GTK names in it also apply to app ids
+//! (taskbar, window title, notifications) and tray icon names.
+//!
 //! Widgets use `IconHandle` to display and update icons without knowing
 //! the underlying theme implementation. The service supports live theme
 //! switching via `reconfigure()`.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::{Rc, Weak};
 
+use gtk4::gdk_pixbuf::Pixbuf;
 use gtk4::gio::{AppInfo, DesktopAppInfo, prelude::*};
 use gtk4::prelude::*;
 use gtk4::{IconTheme, Image, Label};
 use pango::prelude::FontMapExt;
 use tracing::{debug, info, warn};
+use vibepanel_core::config::IconMapping;
 
 use crate::styles::icon;
+use crate::widgets::{expand_home, update};
 
 /// Font family name for Material Symbols (must match the TTF metadata).
 const MATERIAL_FONT_FAMILY: &str = "Material Symbols Rounded";
 
+/// Nerd Font family used when `theme.icons.font` names none.
+const NERD_FONT_FAMILY: &str = "Symbols Nerd Font";
+
 /// Relative path to the Material Symbols font file from the project root.
 const MATERIAL_FONT_FILE: &str = "assets/fonts/MaterialSymbolsRounded.ttf";
 
@@ -94,10 +113,10 @@ fn register_font_with_pango(font_path: &std::path::Path) -> bool {
     }
 }
 
-/// Maps logical icon names to Material Symbols glyph names.
+/// Logical icon names and their Material Symbols glyph names.
 ///
 /// Material Symbols uses ligatures: setting the label text to "battery_full"
-/// renders the battery_full glyph. This mapping converts our canonical names
+/// renders the battery_full glyph. This table converts our canonical names
 /// (e.g., "battery-full") to Material's naming convention.
 ///
 /// Battery icons (8 levels for granular display):
@@ -105,165 +124,369 @@ fn register_font_with_pango(font_path: &std::path::Path) -> bool {
 ///   - battery-medium-low, battery-low, battery-critical
 ///   - Plus "-charging" variants for each level
 ///   - battery-missing for unknown state
+const MATERIAL_SYMBOLS: &[(&str, &str)] = &[
+    // Battery (discharging) - 8 levels for granular display
+    ("battery-full", "battery_full"),
+    ("battery-high", "battery_6_bar"),
+    ("battery-medium-high", "battery_5_bar"),
+    ("battery-medium", "battery_4_bar"),
+    ("battery-medium-low", "battery_3_bar"),
+    ("battery-low", "battery_2_bar"),
+    ("battery-critical", "battery_1_bar"),
+    ("battery-missing", "battery_unknown"),
+    // Battery (charging) - matching 8 levels
+    ("battery-full-charging", "battery_charging_full"),
+    ("battery-high-charging", "battery_charging_90"),
+    ("battery-medium-high-charging", "battery_charging_80"),
+    ("battery-medium-charging", "battery_charging_60"),
+    ("battery-medium-low-charging", "battery_charging_50"),
+    ("battery-low-charging", "battery_charging_30"),
+    ("battery-critical-charging", "battery_charging_20"),
+    // Notifications
+    ("notifications", "notifications"),
+    ("notifications-disabled", "notifications_off"),
+    ("notifications-active", "notifications_active"),
+    // Brightness (for OSD)
+    ("display-brightness-off-symbolic", "brightness_empty"),
+    ("display-brightness-low-symbolic", "brightness_empty"),
+    ("display-brightness-medium-symbolic", "brightness_medium"),
+    ("display-brightness-high-symbolic", "brightness_high"),
+    ("display-brightness-symbolic", "brightness_medium"),
+    // Audio volume (for OSD and quick settings)
+    ("audio-volume-muted-symbolic", "volume_off"),
+    ("audio-volume-low-symbolic", "volume_down"),
+    ("audio-volume-medium-symbolic", "volume_down"),
+    ("audio-volume-high-symbolic", "volume_up"),
+    ("audio-volume-muted", "volume_off"),
+    ("audio-volume-low", "volume_down"),
+    ("audio-volume-medium", "volume_down"),
+    ("audio-volume-high", "volume_up"),
+    // Microphone sensitivity (for quick settings mic slider)
+    ("microphone-sensitivity-muted-symbolic", "mic_off"),
+    ("microphone-sensitivity-low-symbolic", "mic"),
+    ("microphone-sensitivity-medium-symbolic", "mic"),
+    ("microphone-sensitivity-high-symbolic", "mic"),
+    ("audio-input-microphone-symbolic", "mic"),
+    ("audio-input-microphone-muted-symbolic", "mic_off"),
+    // Selection indicators (for sink list, etc.)
+    ("object-select-symbolic", "check"),
+    ("radio-symbolic", "radio_button_unchecked"),
+    ("radio-checked-symbolic", "radio_button_checked"),
+    // Wi-Fi signal strength (for quick settings network list)
+    // Material Symbols wifi line: wifi_1_bar, wifi_2_bar, wifi (3 bar)
+    // Note: no wifi_0_bar or wifi_4_bar, wifi_off for disabled
+    ("network-wireless-signal-excellent-symbolic", "wifi"),
+    ("network-wireless-signal-good-symbolic", "wifi"),
+    ("network-wireless-signal-ok-symbolic", "wifi_2_bar"),
+    ("network-wireless-signal-weak-symbolic", "wifi_1_bar"),
+    ("network-wireless-signal-none-symbolic", "wifi_1_bar"),
+    ("network-wireless-offline-symbolic", "wifi_off"),
+    // Wired networking
+    ("network-wired", "lan"),
+    ("network-wired-symbolic", "lan"),
+    ("network-offline-symbolic", "settings_ethernet"),
+    // Simplified Wi-Fi names
+    ("wifi-off", "wifi_off"),
+    ("wifi", "wifi"),
+    // Bluetooth icons
+    ("bluetooth-symbolic", "bluetooth"),
+    ("bluetooth-active-symbolic", "bluetooth_connected"),
+    ("bluetooth-disabled-symbolic", "bluetooth_disabled"),
+    // Bluetooth device type icons (from BlueZ)
+    ("audio-headphones", "headphones"),
+    ("audio-headphones-symbolic", "headphones"),
+    ("audio-headset", "headset_mic"),
+    ("audio-headset-symbolic", "headset_mic"),
+    ("audio-card", "speaker"),
+    ("audio-card-symbolic", "speaker"),
+    ("audio-speakers", "speaker"),
+    ("audio-speakers-symbolic", "speaker"),
+    ("input-keyboard", "keyboard"),
+    ("input-keyboard-symbolic", "keyboard"),
+    ("input-mouse", "mouse"),
+    ("input-mouse-symbolic", "mouse"),
+    ("input-gaming", "sports_esports"),
+    ("input-gaming-symbolic", "sports_esports"),
+    ("phone", "smartphone"),
+    ("phone-symbolic", "smartphone"),
+    ("computer", "computer"),
+    ("computer-symbolic", "computer"),
+    // VPN icons
+    ("network-vpn", "vpn_key"),
+    ("network-vpn-symbolic", "vpn_key"),
+    ("network-vpn-acquiring-symbolic", "vpn_key"),
+    ("network-vpn-connected-symbolic", "vpn_lock"),
+    ("network-vpn-disconnected-symbolic", "vpn_key_off"),
+    // Proxy icon
+    ("network-proxy-symbolic", "shield"),
+    // Touchpad icon
+    ("input-touchpad-symbolic", "touchpad_mouse"),
+    // Idle inhibitor / night light icons
+    ("night-light-symbolic", "coffee"),
+    ("preferences-system-time-symbolic", "coffee"),
+    ("caffeine-cup-full-symbolic", "coffee"),
+    ("caffeine-cup-empty-symbolic", "coffee"),
+    // Focus timer
+    ("alarm-symbolic", "timer"),
+    // UI action icons (chevrons, menus, close buttons)
+    ("pan-down-symbolic", "keyboard_arrow_down"),
+    ("pan-up-symbolic", "keyboard_arrow_up"),
+    ("pan-left-symbolic", "keyboard_arrow_left"),
+    ("pan-right-symbolic", "keyboard_arrow_right"),
+    ("open-menu-symbolic", "more_vert"),
+    ("view-more-symbolic", "more_horiz"),
+    ("window-close-symbolic", "close"),
+    ("user-trash-symbolic", "delete"),
+    ("edit-copy-symbolic", "content_copy"),
+    // Version control
+    ("vcs-branch-symbolic", "commit"),
+    // Software updates
+    ("software-update-available", "download"),
+    ("software-update-urgent", "download"),
+    // Power menu icons
+    ("system-shutdown-symbolic", "power_settings_new"),
+    ("system-reboot-symbolic", "restart_alt"),
+    ("system-suspend-symbolic", "bedtime"),
+    ("system-lock-screen-symbolic", "lock"),
+    ("system-log-out-symbolic", "logout"),
+    // Power profiles
+    ("power-profile-performance-symbolic", "bolt"),
+    ("power-profile-balanced-symbolic", "balance"),
+    ("power-profile-power-saver-symbolic", "eco"),
+    // Media playback controls
+    ("media-playback-start", "play_arrow"),
+    ("media-playback-pause", "pause"),
+    ("media-playback-stop", "stop"),
+    ("media-skip-backward", "skip_previous"),
+    ("media-skip-forward", "skip_next"),
+    ("media-seek-backward", "fast_rewind"),
+    ("media-seek-forward", "fast_forward"),
+    ("media-playlist-repeat", "repeat"),
+    ("media-playlist-shuffle", "shuffle"),
+    ("media-playback-start-symbolic", "play_arrow"),
+    ("media-playback-pause-symbolic", "pause"),
+    ("media-playback-stop-symbolic", "stop"),
+    ("media-skip-backward-symbolic", "skip_previous"),
+    ("media-skip-forward-symbolic", "skip_next"),
+    ("media-seek-backward-symbolic", "fast_rewind"),
+    ("media-seek-forward-symbolic", "fast_forward"),
+    ("media-rewind-30-symbolic", "replay_30"),
+    ("media-forward-30-symbolic", "forward_30"),
+    ("media-playlist-repeat-symbolic", "repeat"),
+    ("media-playlist-shuffle-symbolic", "shuffle"),
+    // Pop-out / open external window
+    ("window-new-symbolic", "open_in_new"),
+    ("view-fullscreen-symbolic", "fullscreen"),
+    // Track rating (love button)
+    ("starred-symbolic", "favorite"),
+    ("non-starred-symbolic", "favorite"),
+    // Loading / progress spinner
+    ("process-working-symbolic", "progress_activity"),
+    // Temperature sensors
+    ("device_thermostat", "device_thermostat"),
+    // Power draw (RAPL)
+    ("power-draw-symbolic", "electric_bolt"),
+    // Privacy indicators
+    ("camera-web-symbolic", "videocam"),
+    ("screen-shared-symbolic", "screen_share"),
+    // Job status
+    ("emblem-ok-symbolic", "check_circle"),
+    ("dialog-error-symbolic", "error"),
+    // Syncthing status
+    ("emblem-synchronizing-symbolic", "sync"),
+    ("sync-problem-symbolic", "sync_problem"),
+    ("sync-disabled-symbolic", "sync_disabled"),
+    // Weather
+    ("weather-clear-symbolic", "sunny"),
+    ("weather-clear-night-symbolic", "clear_night"),
+    ("weather-few-clouds-symbolic", "partly_cloudy_day"),
+    ("weather-few-clouds-night-symbolic", "partly_cloudy_night"),
+    ("weather-overcast-symbolic", "cloud"),
+    ("weather-fog-symbolic", "foggy"),
+    ("weather-showers-scattered-symbolic", "rainy"),
+    ("weather-showers-symbolic", "rainy"),
+    ("weather-snow-symbolic", "weather_snowy"),
+    ("weather-storm-symbolic", "thunderstorm"),
+];
+
+/// Maps logical icon names to Material Symbols glyph names.
+///
+/// Unknown names pass through unchanged, which allows Material ligature
+/// names directly.
 pub fn material_symbol_name(icon_name: &str) -> &str {
+    MATERIAL_SYMBOLS
+        .iter()
+        .find(|(name, _)| *name == icon_name)
+        .map_or(icon_name, |(_, symbol)| symbol)
+}
+
+/// Maps logical icon names to Nerd Font glyphs.
+///
+/// Nerd Fonts patch icon sets into private-use codepoints. Glyphs come from
+/// the Font Awesome (`nf-fa-*`), Material Design (`nf-md-*`) and Weather
+/// (`nf-weather-*`) sets, which every Nerd Font ships.
+pub fn nerd_font_glyph(icon_name: &str) -> &str {
     match icon_name {
-        // Battery (discharging) - 8 levels for granular display
-        "battery-full" => "battery_full",
-        "battery-high" => "battery_6_bar",
-        "battery-medium-high" => "battery_5_bar",
-        "battery-medium" => "battery_4_bar",
-        "battery-medium-low" => "battery_3_bar",
-        "battery-low" => "battery_2_bar",
-        "battery-critical" => "battery_1_bar",
-        "battery-missing" => "battery_unknown",
-
-        // Battery (charging) - matching 8 levels
-        "battery-full-charging" => "battery_charging_full",
-        "battery-high-charging" => "battery_charging_90",
-        "battery-medium-high-charging" => "battery_charging_80",
-        "battery-medium-charging" => "battery_charging_60",
-        "battery-medium-low-charging" => "battery_charging_50",
-        "battery-low-charging" => "battery_charging_30",
-        "battery-critical-charging" => "battery_charging_20",
+        // Battery (discharging): nf-md-battery_*
+        "battery-full" => "\u{f0079}",
+        "battery-high" => "\u{f0081}",
+        "battery-medium-high" => "\u{f007f}",
+        "battery-medium" => "\u{f007e}",
+        "battery-medium-low" => "\u{f007c}",
+        "battery-low" => "\u{f007b}",
+        "battery-critical" => "\u{f007a}",
+        "battery-missing" => "\u{f0091}",
+
+        // Battery (charging): nf-md-battery_charging_*
+        "battery-full-charging" => "\u{f0085}",
+        "battery-high-charging" => "\u{f008a}",
+        "battery-medium-high-charging" => "\u{f0089}",
+        "battery-medium-charging" => "\u{f0088}",
+        "battery-medium-low-charging" => "\u{f0087}",
+        "battery-low-charging" | "battery-critical-charging" => "\u{f0086}",
 
         // Notifications
-        "notifications" => "notifications",
-        "notifications-disabled" => "notifications_off",
-        "notifications-active" => "notifications_active",
-
-        // Brightness (for OSD)
-        "display-brightness-off-symbolic" => "brightness_empty",
-        "display-brightness-low-symbolic" => "brightness_empty",
-        "display-brightness-medium-symbolic" => "brightness_medium",
-        "display-brightness-high-symbolic" => "brightness_high",
-        "display-brightness-symbolic" => "brightness_medium",
-
-        // Audio volume (for OSD and quick settings)
-        "audio-volume-muted-symbolic" => "volume_off",
-        "audio-volume-low-symbolic" => "volume_down",
-        "audio-volume-medium-symbolic" => "volume_down",
-        "audio-volume-high-symbolic" => "volume_up",
-        "audio-volume-muted" => "volume_off",
-        "audio-volume-low" => "volume_down",
-        "audio-volume-medium" => "volume_down",
-        "audio-volume-high" => "volume_up",
-
-        // Microphone sensitivity (for quick settings mic slider)
-        "microphone-sensitivity-muted-symbolic" => "mic_off",
-        "microphone-sensitivity-low-symbolic" => "mic",
-        "microphone-sensitivity-medium-symbolic" => "mic",
-        "microphone-sensitivity-high-symbolic" => "mic",
-        "audio-input-microphone-symbolic" => "mic",
-        "audio-input-microphone-muted-symbolic" => "mic_off",
-
-        // Selection indicators (for sink list, etc.)
-        "object-select-symbolic" => "check",
-        "radio-symbolic" => "radio_button_unchecked",
-        "radio-checked-symbolic" => "radio_button_checked",
-
-        // Wi-Fi signal strength (for quick settings network list)
-        // Material Symbols wifi line: wifi_1_bar, wifi_2_bar, wifi (3 bar)
-        // Note: no wifi_0_bar or wifi_4_bar, wifi_off for disabled
-        "network-wireless-signal-excellent-symbolic" => "wifi",
-        "network-wireless-signal-good-symbolic" => "wifi",
-        "network-wireless-signal-ok-symbolic" => "wifi_2_bar",
-        "network-wireless-signal-weak-symbolic" => "wifi_1_bar",
-        "network-wireless-signal-none-symbolic" => "wifi_1_bar",
-        "network-wireless-offline-symbolic" => "wifi_off",
-
-        // Wired networking
-        "network-wired" => "lan",
-        "network-wired-symbolic" => "lan",
-        "network-offline-symbolic" => "settings_ethernet",
-
-        // Simplified Wi-Fi names
-        "wifi-off" => "wifi_off",
-        "wifi" => "wifi",
-
-        // Bluetooth icons
-        "bluetooth-symbolic" => "bluetooth",
-        "bluetooth-active-symbolic" => "bluetooth_connected",
-        "bluetooth-disabled-symbolic" => "bluetooth_disabled",
-
-        // Bluetooth device type icons (from BlueZ)
-        "audio-headphones" => "headphones",
-        "audio-headphones-symbolic" => "headphones",
-        "audio-headset" => "headset_mic",
-        "audio-headset-symbolic" => "headset_mic",
-        "audio-card" => "speaker",
-        "audio-card-symbolic" => "speaker",
-        "audio-speakers" => "speaker",
-        "audio-speakers-symbolic" => "speaker",
-        "input-keyboard" => "keyboard",
-        "input-keyboard-symbolic" => "keyboard",
-        "input-mouse" => "mouse",
-        "input-mouse-symbolic" => "mouse",
-        "input-gaming" => "sports_esports",
-        "input-gaming-symbolic" => "sports_esports",
-        "phone" => "smartphone",
-        "phone-symbolic" => "smartphone",
-        "computer" => "computer",
-        "computer-symbolic" => "computer",
-
-        // VPN icons
-        "network-vpn" => "vpn_key",
-        "network-vpn-symbolic" => "vpn_key",
-        "network-vpn-acquiring-symbolic" => "vpn_key",
-        "network-vpn-connected-symbolic" => "vpn_lock",
-        "network-vpn-disconnected-symbolic" => "vpn_key_off",
-
-        // Idle inhibitor / night light icons
-        "night-light-symbolic" => "coffee",
-        "preferences-system-time-symbolic" => "coffee",
-
-        // UI action icons (chevrons, menus, close buttons)
-        "pan-down-symbolic" => "keyboard_arrow_down",
-        "pan-up-symbolic" => "keyboard_arrow_up",
-        "pan-left-symbolic" => "keyboard_arrow_left",
-        "pan-right-symbolic" => "keyboard_arrow_right",
-        "open-menu-symbolic" => "more_vert",
-        "view-more-symbolic" => "more_horiz",
-        "window-close-symbolic" => "close",
-        "user-trash-symbolic" => "delete",
-
-        // Software updates
-        "software-update-available" => "download",
-        "software-update-urgent" => "download",
-
-        // Power menu icons
-        "system-shutdown-symbolic" => "power_settings_new",
-        "system-reboot-symbolic" => "restart_alt",
-        "system-suspend-symbolic" => "bedtime",
-        "system-lock-screen-symbolic" => "lock",
-        "system-log-out-symbolic" => "logout",
-
-        // Media playback controls
-        "media-playback-start" => "play_arrow",
-        "media-playback-pause" => "pause",
-        "media-playback-stop" => "stop",
-        "media-skip-backward" => "skip_previous",
-        "media-skip-forward" => "skip_next",
-        "media-seek-backward" => "fast_rewind",
-        "media-seek-forward" => "fast_forward",
-        "media-playlist-repeat" => "repeat",
-        "media-playlist-shuffle" => "shuffle",
-        "media-playback-start-symbolic" => "play_arrow",
-        "media-playback-pause-symbolic" => "pause",
-        "media-playback-stop-symbolic" => "stop",
-        "media-skip-backward-symbolic" => "skip_previous",
-        "media-skip-forward-symbolic" => "skip_next",
-        "media-seek-backward-symbolic" => "fast_rewind",
-        "media-seek-forward-symbolic" => "fast_forward",
-        "media-playlist-repeat-symbolic" => "repeat",
-        "media-playlist-shuffle-symbolic" => "shuffle",
-        // Pop-out / open external window
-        "window-new-symbolic" => "open_in_new",
-        "view-fullscreen-symbolic" => "fullscreen",
-
-        // Loading / progress spinner
-        "process-working-symbolic" => "progress_activity",
-
-        // Fallback: pass through unchanged (allows Material ligature names directly)
+        "notifications" | "notifications-active" => "\u{f0f3}",
+        "notifications-disabled" => "\u{f1f6}",
+
+        // Brightness: nf-md-brightness_*
+        "display-brightness-off-symbolic" => "\u{f00da}",
+        "display-brightness-low-symbolic" => "\u{f00de}",
+        "display-brightness-medium-symbolic" => "\u{f00df}",
+        "display-brightness-high-symbolic" | "display-brightness-symbolic" => "\u{f00e0}",
+
+        // Volume: nf-md-volume_*
+        "audio-volume-muted-symbolic" | "audio-volume-muted" => "\u{f0581}",
+        "audio-volume-low-symbolic" | "audio-volume-low" => "\u{f057f}",
+        "audio-volume-medium-symbolic" | "audio-volume-medium" => "\u{f0580}",
+        "audio-volume-high-symbolic" | "audio-volume-high" => "\u{f057e}",
+
+        // Microphone
+        "microphone-sensitivity-muted-symbolic" | "audio-input-microphone-muted-symbolic" => {
+            "\u{f131}"
+        }
+        "microphone-sensitivity-low-symbolic"
+        | "microphone-sensitivity-medium-symbolic"
+        | "microphone-sensitivity-high-symbolic"
+        | "audio-input-microphone-symbolic" => "\u{f130}",
+
+        // Selection
+        "object-select-symbolic" => "\u{f00c}",
+        "radio-symbolic" => "\u{f10c}",
+        "radio-checked-symbolic" => "\u{f192}",
+
+        // Network: nf-md-wifi_strength_*, nf-md-ethernet*
+        "network-wireless-signal-excellent-symbolic" | "wifi" => "\u{f0928}",
+        "network-wireless-signal-good-symbolic" => "\u{f0925}",
+        "network-wireless-signal-ok-symbolic" => "\u{f0922}",
+        "network-wireless-signal-weak-symbolic" => "\u{f091f}",
+        "network-wireless-signal-none-symbolic" => "\u{f092f}",
+        "network-wireless-offline-symbolic" | "wifi-off" => "\u{f05aa}",
+        "network-wired" | "network-wired-symbolic" => "\u{f0200}",
+        "network-offline-symbolic" => "\u{f0202}",
+
+        // Bluetooth: nf-md-bluetooth*
+        "bluetooth-symbolic" => "\u{f00af}",
+        "bluetooth-active-symbolic" => "\u{f00b1}",
+        "bluetooth-disabled-symbolic" => "\u{f00b2}",
+
+        // Devices
+        "audio-headphones"
+        | "audio-headphones-symbolic"
+        | "audio-headset"
+        | "audio-headset-symbolic" => "\u{f025}",
+        "audio-card" | "audio-card-symbolic" | "memory" | "memory_alt" => "\u{f2db}",
+        "audio-speakers" | "audio-speakers-symbolic" => "\u{f028}",
+        "input-keyboard" | "input-keyboard-symbolic" => "\u{f11c}",
+        "input-mouse" | "input-mouse-symbolic" => "\u{f037d}",
+        "input-gaming" | "input-gaming-symbolic" => "\u{f11b}",
+        "input-touchpad-symbolic" => "\u{f25a}",
+        "phone" | "phone-symbolic" => "\u{f10b}",
+        "computer" | "computer-symbolic" | "screen-shared-symbolic" => "\u{f108}",
+        "camera-web-symbolic" => "\u{f03d}",
+
+        // VPN and proxy
+        "network-vpn"
+        | "network-vpn-symbolic"
+        | "network-vpn-acquiring-symbolic"
+        | "network-vpn-connected-symbolic" => "\u{f0582}",
+        "network-vpn-disconnected-symbolic" => "\u{f09c}",
+        "network-proxy-symbolic" => "\u{f0ac}",
+
+        // Quick settings toggles
+        "night-light-symbolic" | "system-suspend-symbolic" => "\u{f186}",
+        "preferences-system-time-symbolic" => "\u{f017}",
+        "caffeine-cup-full-symbolic" | "caffeine-cup-empty-symbolic" => "\u{f0f4}",
+        "alarm-symbolic" => "\u{f0020}",
+
+        // Navigation and menus
+        "pan-down-symbolic" | "keyboard_arrow_down" => "\u{f078}",
+        "pan-up-symbolic" => "\u{f077}",
+        "pan-left-symbolic" => "\u{f053}",
+        "pan-right-symbolic" => "\u{f054}",
+        "open-menu-symbolic" => "\u{f0c9}",
+        "view-more-symbolic" => "\u{f142}",
+        "window-close-symbolic" => "\u{f00d}",
+        "window-new-symbolic" => "\u{f2d0}",
+        "view-fullscreen-symbolic" => "\u{f065}",
+        "open_in_new" => "\u{f08e}",
+
+        // Actions
+        "user-trash-symbolic" => "\u{f1f8}",
+        "edit-copy-symbolic" => "\u{f0c5}",
+        "vcs-branch-symbolic" => "\u{f126}",
+        "software-update-available" => "\u{f019}",
+        "software-update-urgent" | "dialog-error-symbolic" => "\u{f06a}",
+        "backup" => "\u{f0c2}",
+
+        // Power
+        "system-shutdown-symbolic" => "\u{f011}",
+        "system-reboot-symbolic" | "emblem-synchronizing-symbolic" => "\u{f021}",
+        "system-lock-screen-symbolic" => "\u{f023}",
+        "system-log-out-symbolic" => "\u{f08b}",
+        "power-profile-performance-symbolic" => "\u{f0e7}",
+        "power-profile-balanced-symbolic" => "\u{f24e}",
+        "power-profile-power-saver-symbolic" => "\u{f06c}",
+
+        // Media
+        "media-playback-start" | "media-playback-start-symbolic" | "play_arrow" => "\u{f04b}",
+        "media-playback-pause" | "media-playback-pause-symbolic" => "\u{f04c}",
+        "media-playback-stop" | "media-playback-stop-symbolic" => "\u{f04d}",
+        "media-skip-backward" | "media-skip-backward-symbolic" | "skip_previous" => "\u{f048}",
+        "media-skip-forward" | "media-skip-forward-symbolic" | "skip_next" => "\u{f051}",
+        "media-seek-backward" | "media-seek-backward-symbolic" | "media-rewind-30-symbolic" => {
+            "\u{f04a}"
+        }
+        "media-seek-forward" | "media-seek-forward-symbolic" | "media-forward-30-symbolic" => {
+            "\u{f04e}"
+        }
+        "media-playlist-repeat" | "media-playlist-repeat-symbolic" => "\u{f01e}",
+        "media-playlist-shuffle" | "media-playlist-shuffle-symbolic" => "\u{f074}",
+        "album" => "\u{f001}",
+        "starred-symbolic" => "\u{f004}",
+        "non-starred-symbolic" => "\u{f08a}",
+
+        // Status
+        "process-working-symbolic" => "\u{f110}",
+        "device_thermostat" => "\u{f2c9}",
+        "power-draw-symbolic" => "\u{f0e7}",
+        "emblem-ok-symbolic" => "\u{f058}",
+        "sync-problem-symbolic" => "\u{f071}",
+        "sync-disabled-symbolic" => "\u{f05e}",
+
+        // Weather: nf-weather-*
+        "weather-clear-symbolic" => "\u{e30d}",
+        "weather-clear-night-symbolic" => "\u{e32b}",
+        "weather-few-clouds-symbolic" => "\u{e302}",
+        "weather-few-clouds-night-symbolic" => "\u{e37e}",
+        "weather-overcast-symbolic" => "\u{e312}",
+        "weather-fog-symbolic" => "\u{e313}",
+        "weather-showers-scattered-symbolic" => "\u{e319}",
+        "weather-showers-symbolic" => "\u{e318}",
+        "weather-snow-symbolic" => "\u{e31a}",
+        "weather-storm-symbolic" => "\u{e31d}",
+
+        // Fallback: pass through unchanged (allows glyphs directly, e.g. in custom widgets)
         _ => icon_name,
     }
 }
@@ -618,6 +841,12 @@ pub fn gtk_icon_candidates(logical: &str) -> &'static [&'static str] {
             "network-vpn",
         ],
 
+        // Proxy icon
+        "network-proxy-symbolic" => &["network-proxy-symbolic", "network-server-symbolic"],
+
+        // Touchpad icon
+        "input-touchpad-symbolic" => &["input-touchpad-symbolic", "input-mouse-symbolic"],
+
         // Idle inhibitor / night light icons
         "night-light-symbolic" => &[
             "night-light-symbolic",
@@ -629,6 +858,23 @@ pub fn gtk_icon_candidates(logical: &str) -> &'static [&'static str] {
             "night-light-symbolic",
             "alarm-symbolic",
         ],
+        "caffeine-cup-full-symbolic" => &[
+            "caffeine-cup-full-symbolic",
+            "night-light-symbolic",
+            "preferences-system-time-symbolic",
+        ],
+        "caffeine-cup-empty-symbolic" => &[
+            "caffeine-cup-empty-symbolic",
+            "night-light-disabled-symbolic",
+            "preferences-system-time-symbolic",
+        ],
+
+        // Focus timer
+        "alarm-symbolic" => &[
+            "alarm-symbolic",
+            "preferences-system-time-symbolic",
+            "appointment-soon-symbolic",
+        ],
 
         // Software updates
         "software-update-available" => &[
@@ -644,6 +890,79 @@ pub fn gtk_icon_candidates(logical: &str) -> &'static [&'static str] {
             "system-software-update-symbolic",
         ],
 
+        // Job status
+        "emblem-ok-symbolic" => &[
+            "emblem-ok-symbolic",
+            "object-select-symbolic",
+            "emblem-default-symbolic",
+        ],
+        "dialog-error-symbolic" => &[
+            "dialog-error-symbolic",
+            "dialog-warning-symbolic",
+            "dialog-error",
+        ],
+
+        // Version control
+        "vcs-branch-symbolic" => &["vcs-branch-symbolic", "media-record-symbolic"],
+
+        // Copy to clipboard
+        "edit-copy-symbolic" => &["edit-copy-symbolic", "edit-copy"],
+
+        // Syncthing status
+        "emblem-synchronizing-symbolic" => {
+            &["emblem-synchronizing-symbolic", "view-refresh-symbolic"]
+        }
+        "sync-problem-symbolic" => &["emblem-important-symbolic", "dialog-warning-symbolic"],
+        "sync-disabled-symbolic" => &["network-offline-symbolic", "emblem-unreadable"],
+
+        // Temperature sensors
+        "device_thermostat" => &[
+            "sensors-temperature-symbolic",
+            "temperature-symbolic",
+            "weather-clear-symbolic",
+        ],
+
+        // Power draw (RAPL)
+        "power-draw-symbolic" => &[
+            "power-draw-symbolic",
+            "power-profile-performance-symbolic",
+            "battery-full-charging-symbolic",
+        ],
+
+        // Power profiles
+        "power-profile-performance-symbolic" => &[
+            "power-profile-performance-symbolic",
+            "speedometer-symbolic",
+            "power-profile-balanced-symbolic",
+        ],
+        "power-profile-balanced-symbolic" => {
+            &["power-profile-balanced-symbolic", "emblem-system-symbolic"]
+        }
+        "power-profile-power-saver-symbolic" => &[
+            "power-profile-power-saver-symbolic",
+            "battery-profile-powersave-symbolic",
+            "power-profile-balanced-symbolic",
+        ],
+
+        // Privacy indicators
+        "camera-web-symbolic" => &[
+            "camera-web-symbolic",
+            "camera-video-symbolic",
+            "camera-photo-symbolic",
+        ],
+        "screen-shared-symbolic" => &[
+            "screen-shared-symbolic",
+            "video-display-symbolic",
+            "preferences-desktop-display-symbolic",
+        ],
+
+        // Backup status
+        "backup" => &[
+            "drive-harddisk-symbolic",
+            "document-save-symbolic",
+            "folder-download-symbolic",
+        ],
+
         // Power menu icons
         "system-shutdown-symbolic" => &[
             "system-shutdown-symbolic",
@@ -692,6 +1011,8 @@ pub fn gtk_icon_candidates(logical: &str) -> &'static [&'static str] {
         "media-skip-forward-symbolic" => &["media-skip-forward-symbolic", "media-skip-forward"],
         "media-seek-backward-symbolic" => &["media-seek-backward-symbolic", "media-seek-backward"],
         "media-seek-forward-symbolic" => &["media-seek-forward-symbolic", "media-seek-forward"],
+        "media-rewind-30-symbolic" => &["media-seek-backward-symbolic", "media-seek-backward"],
+        "media-forward-30-symbolic" => &["media-seek-forward-symbolic", "media-seek-forward"],
         "media-playlist-repeat-symbolic" => {
             &["media-playlist-repeat-symbolic", "media-playlist-repeat"]
         }
@@ -705,6 +1026,9 @@ pub fn gtk_icon_candidates(logical: &str) -> &'static [&'static str] {
             "view-fullscreen-symbolic",
         ],
         "view-fullscreen-symbolic" => &["view-fullscreen-symbolic", "view-fullscreen"],
+        // Track rating (love button)
+        "starred-symbolic" => &["starred-symbolic", "emblem-favorite-symbolic"],
+        "non-starred-symbolic" => &["non-starred-symbolic", "starred-symbolic"],
 
         // Loading / progress spinner
         "process-working-symbolic" => &[
@@ -757,8 +1081,9 @@ fn resolve_gtk_icon_single(theme: &IconTheme, name: &str) -> String {
 /// Get the resolved GTK icon name for a logical icon name.
 ///
 /// Uses the global IconsService's icon theme for resolution. Tries each
-/// candidate from `gtk_icon_candidates()` in order, falling back to
-/// "image-missing" if none are found.
+/// candidate from the user's `theme.icons.map` entry, else from
+/// `gtk_icon_candidates()`, in order, falling back to "image-missing" if
+/// none are found.
 pub fn gtk_icon_name(logical: &str) -> String {
     let candidates = gtk_icon_candidates(logical);
 
@@ -767,6 +1092,13 @@ pub fn gtk_icon_name(logical: &str) -> String {
         if let Some(service) = opt.as_ref()
             && let Some(ref theme) = *service.icon_theme.borrow()
         {
+            // User candidates replace the built-in ones
+            if let Some(mapping) = service.map.borrow().get(logical)
+                && !mapping.gtk.is_empty()
+            {
+                let user: Vec<&str> = mapping.gtk.iter().map(String::as_str).collect();
+                return resolve_gtk_icon(theme, &user);
+            }
             // If we have candidates from the mapping, use them
             if !candidates.is_empty() {
                 return resolve_gtk_icon(theme, candidates);
@@ -779,6 +1111,23 @@ pub fn gtk_icon_name(logical: &str) -> String {
     })
 }
 
+/// The user's `theme.icons.map` entry for `name`, if any.
+fn user_icon_mapping(name: &str) -> Option<IconMapping> {
+    ICONS_INSTANCE.with(|cell| {
+        cell.borrow()
+            .as_ref()
+            .and_then(|service| service.map.borrow().get(name).cloned())
+    })
+}
+
+/// GTK icon name for an app id or tray icon name the user mapped in
+/// `theme.icons.map`, or `None` if it has no GTK names there.
+pub fn mapped_gtk_icon_name(name: &str) -> Option<String> {
+    user_icon_mapping(name)
+        .filter(|mapping| !mapping.gtk.is_empty())
+        .map(|_| gtk_icon_name(name))
+}
+
 /// Normalize an app_id by trimming whitespace and stripping leading @: characters.
 fn normalize_app_id(app_id: &str) -> String {
     app_id
@@ -915,7 +1264,8 @@ pub fn get_desktop_appinfo_for_app_id(app_id: &str) -> Option<DesktopAppInfo> {
 
 /// Return the best-known themed icon name for a compositor app_id.
 ///
-/// Uses `DesktopAppInfo` to resolve the corresponding desktop entry and
+/// Uses GTK names from the user's `theme.icons.map` entry for the app_id if
+/// there is one, else `DesktopAppInfo` to resolve the corresponding desktop entry and
 /// caches results keyed by the raw app_id string.
 ///
 /// Returns an empty string if no icon could be found.
@@ -924,6 +1274,11 @@ pub fn get_app_icon_name(app_id: &str) -> String {
         return String::new();
     }
 
+    // A user mapping wins over the desktop entry (not cached: it can be reloaded)
+    if let Some(icon_name) = mapped_gtk_icon_name(app_id) {
+        return icon_name;
+    }
+
     // Check cache first
     let cached = APP_ICON_NAME_CACHE.with(|cell| cell.borrow().get(app_id).cloned());
     if let Some(result) = cached {
@@ -1001,6 +1356,10 @@ enum IconBackendKind {
     Material,
     /// GTK icon theme (Adwaita, Breeze, etc.)
     Gtk,
+    /// Nerd Font glyphs (codepoint-based icons)
+    Nerd,
+    /// Image file from `theme.icons.overrides`
+    File,
     /// Plain text fallback
     Text,
 }
@@ -1010,10 +1369,14 @@ enum IconBackendKind {
 /// Each variant holds a specific widget type optimized for the backend:
 /// - `MaterialLabel`: A Label with Material Symbols font (ligature-based icons)
 /// - `GtkImage`: A GTK Image using the system icon theme
+/// - `NerdLabel`: A Label with the configured Nerd Font
+/// - `FileImage`: A GTK Image rendered from an override file
 /// - `TextLabel`: A plain Label showing the logical icon name as text
 enum IconBackend {
     MaterialLabel(Label),
     GtkImage(Image),
+    NerdLabel(Label),
+    FileImage(Image),
     TextLabel(Label),
 }
 
@@ -1023,6 +1386,8 @@ impl IconBackend {
         match self {
             IconBackend::MaterialLabel(_) => IconBackendKind::Material,
             IconBackend::GtkImage(_) => IconBackendKind::Gtk,
+            IconBackend::NerdLabel(_) => IconBackendKind::Nerd,
+            IconBackend::FileImage(_) => IconBackendKind::File,
             IconBackend::TextLabel(_) => IconBackendKind::Text,
         }
     }
@@ -1032,6 +1397,8 @@ impl IconBackend {
         match self {
             IconBackend::MaterialLabel(label) => label.clone().upcast(),
             IconBackend::GtkImage(image) => image.clone().upcast(),
+            IconBackend::NerdLabel(label) => label.clone().upcast(),
+            IconBackend::FileImage(image) => image.clone().upcast(),
             IconBackend::TextLabel(label) => label.clone().upcast(),
         }
     }
@@ -1042,11 +1409,23 @@ impl Clone for IconBackend {
         match self {
             IconBackend::MaterialLabel(label) => IconBackend::MaterialLabel(label.clone()),
             IconBackend::GtkImage(image) => IconBackend::GtkImage(image.clone()),
+            IconBackend::NerdLabel(label) => IconBackend::NerdLabel(label.clone()),
+            IconBackend::FileImage(image) => IconBackend::FileImage(image.clone()),
             IconBackend::TextLabel(label) => IconBackend::TextLabel(label.clone()),
         }
     }
 }
 
+/// Per-icon image files (`theme.icons.overrides`), shared by the service and
+/// every icon handle.
+#[derive(Debug, Default)]
+struct IconOverrides {
+    /// Image file by logical icon name, with `~` expanded.
+    paths: HashMap<String, PathBuf>,
+    /// Icon size in logical pixels the files are rendered at.
+    size: u32,
+}
+
 /// Internal state shared by IconHandle clones and tracked by IconsService.
 ///
 /// This allows the service to reapply icons when the theme changes at runtime.
@@ -1057,6 +1436,10 @@ struct IconHandleInner {
     root: gtk4::Box,
     /// The concrete backend widget (Label or Image).
     backend: RefCell<IconBackend>,
+    /// Backend kind of the icon theme; overridden names use `File` instead.
+    theme_kind: Cell<IconBackendKind>,
+    /// Per-icon override files, shared with the service.
+    overrides: Rc<RefCell<IconOverrides>>,
     /// The last logical icon name set via `set_icon`.
     /// Stored so we can reapply after a theme change.
     logical_name: RefCell<String>,
@@ -1067,21 +1450,47 @@ struct IconHandleInner {
 }
 
 impl IconHandleInner {
-    /// Update the displayed icon using the current backend.
+    /// Backend kind for `name`: a file image if it's overridden (except in
+    /// text mode), else the icon theme's.
+    fn kind_for(&self, name: &str) -> IconBackendKind {
+        let theme_kind = self.theme_kind.get();
+        if theme_kind != IconBackendKind::Text && self.overrides.borrow().paths.contains_key(name) {
+            IconBackendKind::File
+        } else {
+            theme_kind
+        }
+    }
+
+    /// Update the displayed icon, switching between the theme backend and a
+    /// file image as needed.
     fn apply_icon(&self, name: &str) {
         *self.logical_name.borrow_mut() = name.to_string();
+        self.set_backend_kind(self.kind_for(name));
 
         match &*self.backend.borrow() {
             IconBackend::MaterialLabel(label) => {
-                let glyph = material_symbol_name(name);
+                let mapped = user_icon_mapping(name).and_then(|m| m.material);
+                let glyph = mapped
+                    .as_deref()
+                    .unwrap_or_else(|| material_symbol_name(name));
                 label.set_label(glyph);
             }
             IconBackend::GtkImage(image) => {
                 let gtk_name = gtk_icon_name(name);
                 image.set_icon_name(Some(&gtk_name));
             }
+            IconBackend::NerdLabel(label) => {
+                let mapped = user_icon_mapping(name).and_then(|m| m.nerd);
+                label.set_label(mapped.as_deref().unwrap_or_else(|| nerd_font_glyph(name)));
+            }
+            IconBackend::FileImage(image) => {
+                let overrides = self.overrides.borrow();
+                if let Some(path) = overrides.paths.get(name) {
+                    load_icon_file(image, path, overrides.size);
+                }
+            }
             IconBackend::TextLabel(label) => {
-                label.set_label(name);
+                label.set_label(&text_icon_label(name));
             }
         }
     }
@@ -1094,15 +1503,22 @@ impl IconHandleInner {
         }
     }
 
-    /// Rebuild the backend widget if the backend kind has changed.
+    /// Switch to the theme backend `theme_kind` and reapply the icon.
     ///
     /// This is called during theme reconfiguration to swap between Material
-    /// (Label with ligature font) and GTK (Image) backends.
-    fn rebuild_backend(&self, new_kind: IconBackendKind) {
-        let current_kind = self.backend.borrow().kind();
-        if current_kind == new_kind {
-            // Same backend kind, just reapply the icon (handles GTK theme changes)
-            self.reapply();
+    /// (Label with ligature font) and GTK (Image) backends, and when the
+    /// overrides change.
+    fn rebuild_backend(&self, theme_kind: IconBackendKind) {
+        self.theme_kind.set(theme_kind);
+        let name = self.logical_name.borrow().clone();
+        self.set_backend_kind(self.kind_for(&name));
+        // Same backend kind just reapplies the icon (handles GTK theme changes)
+        self.reapply();
+    }
+
+    /// Replace the backend widget if its kind isn't `new_kind`.
+    fn set_backend_kind(&self, new_kind: IconBackendKind) {
+        if self.backend.borrow().kind() == new_kind {
             return;
         }
 
@@ -1126,9 +1542,6 @@ impl IconHandleInner {
 
         // Update the backend
         *self.backend.borrow_mut() = new_backend;
-
-        // Reapply the current icon
-        self.reapply();
     }
 }
 
@@ -1137,6 +1550,8 @@ impl IconHandleInner {
 /// The underlying widget type varies based on the configured icon theme:
 /// - Material theme: `gtk4::Label` with Material Symbols font
 /// - GTK theme: `gtk4::Image` using the system icon theme  
+/// - Nerd theme: `gtk4::Label` with the configured Nerd Font
+/// - Overridden icon: `gtk4::Image` rendered from the override file
 /// - Fallback: `gtk4::Label` showing the icon name as text
 ///
 /// Call `set_icon` to change the displayed icon. The handle supports live
@@ -1168,11 +1583,11 @@ impl IconHandle {
     /// tracks the class so it survives theme switches (when the backend widget
     /// is recreated).
     pub fn add_css_class(&self, class: &str) {
-        self.inner.backend.borrow().widget().add_css_class(class);
         self.inner
             .dynamic_classes
             .borrow_mut()
             .insert(class.to_string());
+        update::set_class(&self.inner.backend.borrow().widget(), class, true);
     }
 
     /// Remove a CSS class from the backend widget.
@@ -1180,8 +1595,8 @@ impl IconHandle {
     /// This removes the class from both the current widget and the tracked
     /// set, so it won't be reapplied on theme switches.
     pub fn remove_css_class(&self, class: &str) {
-        self.inner.backend.borrow().widget().remove_css_class(class);
         self.inner.dynamic_classes.borrow_mut().remove(class);
+        update::set_class(&self.inner.backend.borrow().widget(), class, false);
     }
 
     /// Update the displayed icon by logical name.
@@ -1191,6 +1606,9 @@ impl IconHandle {
     /// appropriate backend representation (Material glyph, GTK icon name, or
     /// plain text).
     ///
+    /// Setting the icon that is already displayed is a no-op, so polling
+    /// widgets can call this on every update without causing redraws.
+    ///
     /// # Examples
     ///
     /// ```ignore
@@ -1199,6 +1617,9 @@ impl IconHandle {
     /// icon_handle.set_icon("battery-missing");
     /// ```
     pub fn set_icon(&self, name: &str) {
+        if *self.inner.logical_name.borrow() == name {
+            return;
+        }
         self.inner.apply_icon(name);
     }
 }
@@ -1213,16 +1634,20 @@ impl IconHandle {
 /// The backend is chosen based on the configured theme name:
 ///
 /// - "material" → Material Symbols font (ligature-based icons)
+/// - "nerd" → an installed Nerd Font (codepoint-based icons)
 /// - Any other value (e.g. "gtk") → system GTK icon theme
 ///
 /// If the Material font can't be loaded, the service automatically falls back
 /// to the GTK backend. If GTK icons aren't available either, it falls back to
-/// plain text display.
+/// plain text display. Text mode (`set_text_mode`) uses plain text for every
+/// icon regardless of the theme.
 pub struct IconsService {
     /// The configured icon theme name (e.g., "material", "Adwaita").
     theme: RefCell<String>,
     /// Font weight for Material Symbols (100-700, default 400).
     weight: RefCell<u16>,
+    /// Font family for the Nerd Font backend.
+    font: RefCell<String>,
     /// Whether the Material Symbols font was successfully loaded.
     material_ready: RefCell<bool>,
     /// Whether we've attempted to load the font CSS.
@@ -1233,19 +1658,33 @@ pub struct IconsService {
     handles: RefCell<Vec<Weak<IconHandleInner>>>,
     /// CSS provider for Material Symbols (stored for replacement on weight change).
     material_css_provider: RefCell<Option<gtk4::CssProvider>>,
+    /// CSS provider for the Nerd Font (stored for replacement on font change).
+    nerd_css_provider: RefCell<Option<gtk4::CssProvider>>,
+    /// Text-only mode (`bar.mode = "text"`): every icon is a text label.
+    text_mode: RefCell<bool>,
+    /// Per-icon override files, shared with every handle.
+    overrides: Rc<RefCell<IconOverrides>>,
+    /// User icon name mapping (`theme.icons.map`), over the built-in one.
+    map: RefCell<HashMap<String, IconMapping>>,
 }
 
 impl IconsService {
-    /// Create a new IconsService with the given theme name and font weight.
-    fn new(theme: String, weight: u16) -> Rc<Self> {
+    /// Create a new IconsService with the given theme name, font weight and
+    /// Nerd Font family.
+    fn new(theme: String, weight: u16, font: String) -> Rc<Self> {
         let service = Rc::new(Self {
             theme: RefCell::new(theme.clone()),
             weight: RefCell::new(weight),
+            font: RefCell::new(font),
             material_ready: RefCell::new(false),
             css_loaded: RefCell::new(false),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         });
 
         IconsService::setup_backends(&service, &theme);
@@ -1285,6 +1724,8 @@ impl IconsService {
         // Initialize Material if configured
         if is_material_theme(theme) {
             service.ensure_material_css();
+        } else if is_nerd_theme(theme) {
+            service.load_nerd_css();
         }
     }
 
@@ -1296,28 +1737,38 @@ impl IconsService {
         ICONS_INSTANCE.with(|cell| {
             let mut opt = cell.borrow_mut();
             if opt.is_none() {
-                *opt = Some(IconsService::new("material".to_string(), 400));
+                *opt = Some(IconsService::new(
+                    "material".to_string(),
+                    400,
+                    NERD_FONT_FAMILY.to_string(),
+                ));
             }
             opt.as_ref().unwrap().clone()
         })
     }
 
-    /// Initialize the global IconsService with a specific theme and font weight.
+    /// Initialize the global IconsService with a specific theme, font weight
+    /// and Nerd Font family.
     ///
     /// Must be called before `global()` is first accessed, typically
     /// during application startup after loading config.
-    pub fn init_global(theme: &str, weight: u16) {
+    pub fn init_global(theme: &str, weight: u16, font: &str) {
         ICONS_INSTANCE.with(|cell| {
             let mut opt = cell.borrow_mut();
             if opt.is_some() {
                 warn!("IconsService already initialized, ignoring init_global call");
                 return;
             }
-            *opt = Some(IconsService::new(theme.to_string(), weight));
+            *opt = Some(IconsService::new(
+                theme.to_string(),
+                weight,
+                font.to_string(),
+            ));
         });
     }
 
-    /// Reconfigure the icon service with a new theme and/or font weight.
+    /// Reconfigure the icon service with a new theme, font weight and/or
+    /// Nerd Font family.
     ///
     /// This updates the backend and reapplies all existing icons to reflect
     /// the new theme. Use this for live config reload.
@@ -1325,18 +1776,20 @@ impl IconsService {
     /// # Arguments
     ///
     /// * `new_theme` - The new theme name ("material" for Material Symbols,
-    ///   or a GTK theme name like "Adwaita", "Breeze", etc.)
+    ///   "nerd" for a Nerd Font, or a GTK theme name like "Adwaita", "Breeze", etc.)
     /// * `new_weight` - The font weight for Material Symbols (100-700)
-    pub fn reconfigure(&self, new_theme: &str, new_weight: u16) {
+    /// * `new_font` - The font family for the Nerd Font backend
+    pub fn reconfigure(&self, new_theme: &str, new_weight: u16, new_font: &str) {
         let old_theme = self.theme.borrow().clone();
         let old_weight = *self.weight.borrow();
         let theme_changed = old_theme != new_theme;
         let weight_changed = old_weight != new_weight;
+        let font_changed = *self.font.borrow() != new_font;
 
-        if !theme_changed && !weight_changed {
+        if !theme_changed && !weight_changed && !font_changed {
             debug!(
-                "Icon theme and weight unchanged ({}, {}), skipping reconfigure",
-                new_theme, new_weight
+                "Icon theme, weight and font unchanged ({}, {}, {}), skipping reconfigure",
+                new_theme, new_weight, new_font
             );
             return;
         }
@@ -1351,9 +1804,18 @@ impl IconsService {
             );
         }
 
-        // Update theme name and weight
+        if font_changed {
+            info!(
+                "Reconfiguring icon font: {} -> {}",
+                self.font.borrow(),
+                new_font
+            );
+        }
+
+        // Update theme name, weight and font
         *self.theme.borrow_mut() = new_theme.to_string();
         *self.weight.borrow_mut() = new_weight;
+        *self.font.borrow_mut() = new_font.to_string();
 
         // Reload Material CSS if switching to Material or if weight changed while using Material
         let switching_to_material = is_material_theme(new_theme) && !is_material_theme(&old_theme);
@@ -1363,17 +1825,73 @@ impl IconsService {
             self.ensure_material_css();
         }
 
+        // Load the Nerd Font CSS if switching to it or if the font changed while using it
+        let switching_to_nerd = is_nerd_theme(new_theme) && !is_nerd_theme(&old_theme);
+        if is_nerd_theme(new_theme) && (switching_to_nerd || font_changed) {
+            self.load_nerd_css();
+        }
+
         // Rebuild all icons with the new theme/weight.
         // With Pango's add_font_file(), fonts are immediately available,
         // so we no longer need to defer this with idle_add_local_once.
         self.reapply_all_icons();
     }
 
+    /// Switch text-only mode on or off.
+    ///
+    /// In text mode every icon handle shows a text label instead of a glyph
+    /// or image, regardless of the icon theme. Existing handles are rebuilt.
+    pub fn set_text_mode(&self, enabled: bool) {
+        if *self.text_mode.borrow() == enabled {
+            return;
+        }
+        info!(
+            "Icon text mode {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        *self.text_mode.borrow_mut() = enabled;
+        self.reapply_all_icons();
+    }
+
+    /// Set the per-icon override files (`theme.icons.overrides`) and the icon
+    /// size they're rendered at. Existing handles are rebuilt on a change.
+    pub fn set_overrides(&self, overrides: &HashMap<String, String>, size: u32) {
+        let paths: HashMap<String, PathBuf> = overrides
+            .iter()
+            .map(|(name, path)| (name.clone(), expand_home(path)))
+            .collect();
+        {
+            let current = self.overrides.borrow();
+            if current.paths == paths && current.size == size {
+                return;
+            }
+        }
+        info!("Icon overrides: {} icons at {}px", paths.len(), size);
+        *self.overrides.borrow_mut() = IconOverrides { paths, size };
+        self.reapply_all_icons();
+    }
+
+    /// Set the user icon name mapping (`theme.icons.map`). Existing handles
+    /// are reapplied on a change.
+    pub fn set_map(&self, map: &HashMap<String, IconMapping>) {
+        if *self.map.borrow() == *map {
+            return;
+        }
+        info!("Icon map: {} user entries", map.len());
+        *self.map.borrow_mut() = map.clone();
+        self.reapply_all_icons();
+    }
+
     /// Check if we're using the Material Symbols theme.
     pub fn uses_material(&self) -> bool {
         is_material_theme(&self.theme.borrow())
     }
 
+    /// Check if we're using the Nerd Font theme.
+    fn uses_nerd(&self) -> bool {
+        is_nerd_theme(&self.theme.borrow())
+    }
+
     /// Get the current theme name.
     #[cfg(test)]
     fn theme(&self) -> String {
@@ -1390,8 +1908,12 @@ impl IconsService {
     /// This is used both for creating new icons and for rebuilding existing
     /// icons when the theme changes.
     fn current_backend_kind(&self) -> IconBackendKind {
-        if self.material_backend_ready() {
+        if *self.text_mode.borrow() {
+            IconBackendKind::Text
+        } else if self.material_backend_ready() {
             IconBackendKind::Material
+        } else if self.uses_nerd() {
+            IconBackendKind::Nerd
         } else if self.icon_theme.borrow().is_some() {
             IconBackendKind::Gtk
         } else {
@@ -1406,9 +1928,11 @@ impl IconsService {
     ///
     /// # Backend Selection
     ///
-    /// 1. If theme is "material" and Material font is ready → Material backend
-    /// 2. Else if GTK icon theme is available → GTK backend  
-    /// 3. Else → Text fallback backend
+    /// 1. If text mode is on → Text backend
+    /// 2. Else if theme is "material" and Material font is ready → Material backend
+    /// 3. Else if theme is "nerd" → Nerd Font backend
+    /// 4. Else if GTK icon theme is available → GTK backend
+    /// 5. Else → Text fallback backend
     pub fn create_icon(&self, name: &str, css_classes: &[&str]) -> IconHandle {
         // Create stable root container - this defines the icon's bounding box
         let root = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
@@ -1432,11 +1956,24 @@ impl IconsService {
         let inner = Rc::new(IconHandleInner {
             root,
             backend: RefCell::new(backend),
+            theme_kind: Cell::new(backend_kind),
+            overrides: self.overrides.clone(),
             logical_name: RefCell::new(String::new()),
             css_classes: RefCell::new(css_classes.iter().map(|s| s.to_string()).collect()),
             dynamic_classes: RefCell::new(HashSet::new()),
         });
 
+        // Override files are rendered for the scale factor; redo them when it changes
+        let inner_weak = Rc::downgrade(&inner);
+        inner.root.connect_scale_factor_notify(move |_| {
+            if let Some(inner) = inner_weak.upgrade() {
+                let is_file = inner.backend.borrow().kind() == IconBackendKind::File;
+                if is_file {
+                    inner.reapply();
+                }
+            }
+        });
+
         // Register for live reload
         self.handles.borrow_mut().push(Rc::downgrade(&inner));
 
@@ -1552,6 +2089,48 @@ impl IconsService {
         );
     }
 
+    /// Load the CSS that sets the configured Nerd Font on Nerd icons.
+    ///
+    /// Replaces the provider from a previous call, so a font change applies
+    /// live. The font isn't checked for: if it's missing, Pango falls back
+    /// and the glyphs render as boxes.
+    fn load_nerd_css(&self) {
+        let Some(display) = gtk4::gdk::Display::default() else {
+            warn!("No display available, cannot load Nerd Font CSS");
+            return;
+        };
+
+        if let Some(old_provider) = self.nerd_css_provider.borrow_mut().take() {
+            gtk4::style_context_remove_provider_for_display(&display, &old_provider);
+        }
+
+        let font = self.font.borrow().clone();
+        let css = format!(
+            r#"
+.nerd-font-icon {{
+    font-family: '{}', '{}', monospace;
+    font-size: inherit;
+}}
+
+.nerd-font-icon.media-primary-icon {{
+    font-size: calc(var(--icon-size) * 1.35);
+}}
+"#,
+            font.replace('\'', ""),
+            NERD_FONT_FAMILY
+        );
+
+        let provider = gtk4::CssProvider::new();
+        provider.load_from_string(&css);
+        gtk4::style_context_add_provider_for_display(
+            &display,
+            &provider,
+            gtk4::STYLE_PROVIDER_PRIORITY_USER + 5,
+        );
+        *self.nerd_css_provider.borrow_mut() = Some(provider);
+        debug!("Nerd Font CSS loaded (font={})", font);
+    }
+
     /// Try to find the Material Symbols font file.
     ///
     /// Searches in order:
@@ -1665,11 +2244,40 @@ impl IconsService {
     }
 }
 
+/// Readable text for a logical icon name, shown by the text backend.
+///
+/// "battery-medium-charging" becomes "battery medium charging", so screen
+/// readers and braille displays get words rather than identifiers.
+fn text_icon_label(name: &str) -> String {
+    let name = name.strip_suffix("-symbolic").unwrap_or(name);
+    name.replace(['-', '_'], " ")
+}
+
 /// Check if a theme name refers to Material Symbols.
 fn is_material_theme(theme: &str) -> bool {
     theme.trim().eq_ignore_ascii_case("material")
 }
 
+/// Show the image file at `path` in `image`, rendered at `size` logical
+/// pixels for the widget's scale factor so SVGs stay sharp.
+fn load_icon_file(image: &Image, path: &Path, size: u32) {
+    let size = size as i32;
+    let pixels = size * image.scale_factor().max(1);
+    image.set_pixel_size(size);
+    match Pixbuf::from_file_at_scale(path, pixels, pixels, true) {
+        Ok(pixbuf) => image.set_paintable(Some(&gtk4::gdk::Texture::for_pixbuf(&pixbuf))),
+        Err(e) => {
+            warn!("Icon override {}: {}", path.display(), e);
+            image.set_icon_name(Some("image-missing"));
+        }
+    }
+}
+
+/// Check if a theme name refers to the Nerd Font backend.
+fn is_nerd_theme(theme: &str) -> bool {
+    theme.trim().eq_ignore_ascii_case("nerd")
+}
+
 /// Create a backend widget for the given kind with CSS classes applied.
 ///
 /// This is used both for initial icon creation and for rebuilding backends
@@ -1692,11 +2300,28 @@ fn create_backend_widget(kind: IconBackendKind, css_classes: &[&str]) -> IconBac
             image.add_css_class(icon::ICON);
             IconBackend::GtkImage(image)
         }
+        IconBackendKind::Nerd => {
+            let label = Label::new(None);
+            for class in css_classes {
+                label.add_css_class(class);
+            }
+            label.add_css_class(icon::NERD_FONT);
+            IconBackend::NerdLabel(label)
+        }
+        IconBackendKind::File => {
+            let image = Image::new();
+            for class in css_classes {
+                image.add_css_class(class);
+            }
+            image.add_css_class(icon::ICON);
+            IconBackend::FileImage(image)
+        }
         IconBackendKind::Text => {
             let label = Label::new(None);
             for class in css_classes {
                 label.add_css_class(class);
             }
+            label.add_css_class(icon::TEXT_LABEL);
             IconBackend::TextLabel(label)
         }
     }
@@ -1759,6 +2384,32 @@ mod tests {
         assert_eq!(material_symbol_name("wifi"), "wifi");
     }
 
+    // Nerd Font Mapping Tests
+
+    #[test]
+    fn test_nerd_font_glyph_mapping() {
+        assert_eq!(nerd_font_glyph("battery-full"), "\u{f0079}");
+        assert_eq!(nerd_font_glyph("battery-low-charging"), "\u{f0086}");
+        assert_eq!(nerd_font_glyph("bluetooth-disabled-symbolic"), "\u{f00b2}");
+        assert_eq!(nerd_font_glyph("media-playback-pause-symbolic"), "\u{f04c}");
+        // Unknown names and glyphs pass through unchanged
+        assert_eq!(nerd_font_glyph("unknown-icon"), "unknown-icon");
+        assert_eq!(nerd_font_glyph("\u{f303}"), "\u{f303}");
+    }
+
+    #[test]
+    fn test_nerd_font_glyph_covers_material_names() {
+        // Every name with a Material glyph has a Nerd Font glyph too
+        for (name, _) in MATERIAL_SYMBOLS {
+            assert_ne!(
+                nerd_font_glyph(name),
+                *name,
+                "no Nerd Font glyph for {}",
+                name
+            );
+        }
+    }
+
     // GTK Icon Mapping Tests
 
     #[test]
@@ -1869,22 +2520,32 @@ mod tests {
         let service = IconsService {
             theme: RefCell::new("material".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(false),
             css_loaded: RefCell::new(false),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
         assert!(service.uses_material());
 
         let service2 = IconsService {
             theme: RefCell::new("adwaita".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(false),
             css_loaded: RefCell::new(false),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
         assert!(!service2.uses_material());
     }
@@ -1901,11 +2562,16 @@ mod tests {
         let service = IconsService {
             theme: RefCell::new("material".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(true),
             css_loaded: RefCell::new(true),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
         assert_eq!(service.current_backend_kind(), IconBackendKind::Material);
     }
@@ -1917,11 +2583,16 @@ mod tests {
         let service = IconsService {
             theme: RefCell::new("material".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(false),
             css_loaded: RefCell::new(false),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
         assert_eq!(service.current_backend_kind(), IconBackendKind::Text);
     }
@@ -1932,11 +2603,16 @@ mod tests {
         let service = IconsService {
             theme: RefCell::new("Adwaita".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(false),
             css_loaded: RefCell::new(false),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
         assert_eq!(service.current_backend_kind(), IconBackendKind::Text);
     }
@@ -1947,11 +2623,16 @@ mod tests {
         let service = IconsService {
             theme: RefCell::new("material".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(true),
             css_loaded: RefCell::new(true),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
 
         assert_eq!(service.theme(), "material");
@@ -1959,7 +2640,7 @@ mod tests {
         assert_eq!(service.current_backend_kind(), IconBackendKind::Material);
 
         // Reconfigure to a GTK theme
-        service.reconfigure("Adwaita", 400);
+        service.reconfigure("Adwaita", 400, NERD_FONT_FAMILY);
 
         assert_eq!(service.theme(), "Adwaita");
         assert!(!service.uses_material());
@@ -1973,17 +2654,129 @@ mod tests {
         let service = IconsService {
             theme: RefCell::new("material".to_string()),
             weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
             material_ready: RefCell::new(true),
             css_loaded: RefCell::new(true),
             icon_theme: RefCell::new(None),
             handles: RefCell::new(Vec::new()),
             material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
         };
 
         // This should not change anything
-        service.reconfigure("material", 400);
+        service.reconfigure("material", 400, NERD_FONT_FAMILY);
 
         assert_eq!(service.theme(), "material");
         assert!(service.uses_material());
     }
+
+    #[test]
+    fn test_is_nerd_theme() {
+        assert!(is_nerd_theme("nerd"));
+        assert!(is_nerd_theme(" Nerd "));
+        assert!(!is_nerd_theme("material"));
+        assert!(!is_nerd_theme("gtk"));
+    }
+
+    #[test]
+    fn test_current_backend_kind_nerd_theme() {
+        // The Nerd backend needs no bundled font, so it's used whenever configured
+        let service = IconsService {
+            theme: RefCell::new("material".to_string()),
+            weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
+            material_ready: RefCell::new(true),
+            css_loaded: RefCell::new(true),
+            icon_theme: RefCell::new(None),
+            handles: RefCell::new(Vec::new()),
+            material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
+        };
+
+        service.reconfigure("nerd", 400, "JetBrainsMono Nerd Font");
+        assert_eq!(service.current_backend_kind(), IconBackendKind::Nerd);
+        assert_eq!(*service.font.borrow(), "JetBrainsMono Nerd Font");
+        assert!(!service.uses_material());
+
+        service.set_text_mode(true);
+        assert_eq!(service.current_backend_kind(), IconBackendKind::Text);
+    }
+
+    #[test]
+    fn test_set_overrides_expands_paths() {
+        let service = IconsService {
+            theme: RefCell::new("material".to_string()),
+            weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
+            material_ready: RefCell::new(true),
+            css_loaded: RefCell::new(true),
+            icon_theme: RefCell::new(None),
+            handles: RefCell::new(Vec::new()),
+            material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
+        };
+
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "battery-full-symbolic".to_string(),
+            "~/icons/battery.svg".to_string(),
+        );
+        service.set_overrides(&overrides, 16);
+
+        let current = service.overrides.borrow();
+        assert_eq!(current.size, 16);
+        assert_eq!(
+            current.paths.get("battery-full-symbolic"),
+            Some(&expand_home("~/icons/battery.svg"))
+        );
+        // Overrides don't change the theme backend itself
+        assert_eq!(service.current_backend_kind(), IconBackendKind::Material);
+    }
+
+    #[test]
+    fn test_text_mode_forces_text_backend() {
+        let service = IconsService {
+            theme: RefCell::new("material".to_string()),
+            weight: RefCell::new(400),
+            font: RefCell::new(NERD_FONT_FAMILY.to_string()),
+            material_ready: RefCell::new(true),
+            css_loaded: RefCell::new(true),
+            icon_theme: RefCell::new(None),
+            handles: RefCell::new(Vec::new()),
+            material_css_provider: RefCell::new(None),
+            nerd_css_provider: RefCell::new(None),
+            text_mode: RefCell::new(false),
+            overrides: Rc::default(),
+            map: RefCell::new(HashMap::new()),
+        };
+        assert_eq!(service.current_backend_kind(), IconBackendKind::Material);
+
+        service.set_text_mode(true);
+        assert_eq!(service.current_backend_kind(), IconBackendKind::Text);
+
+        service.set_text_mode(false);
+        assert_eq!(service.current_backend_kind(), IconBackendKind::Material);
+    }
+
+    #[test]
+    fn test_text_icon_label() {
+        assert_eq!(
+            text_icon_label("battery-medium-charging"),
+            "battery medium charging"
+        );
+        assert_eq!(
+            text_icon_label("audio-volume-high-symbolic"),
+            "audio volume high"
+        );
+        assert_eq!(text_icon_label("wifi_off"), "wifi off");
+    }
 }
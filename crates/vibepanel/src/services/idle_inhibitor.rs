@@ -7,7 +7,12 @@
 //! ## Usage
 //!
 //! The service is a singleton that can be toggled on/off. When active,
-//! it prevents the system from going idle or suspending.
+//! it prevents the system from going idle or suspending. It can also be
+//! enabled for a fixed duration with `enable_for`, after which it turns
+//! itself off again.
+//!
+//! State lives in the service rather than in widgets, so an active (or timed)
+//! inhibitor survives config reloads that rebuild the bar.
 //!
 //! ## CLI Usage
 //!
@@ -17,13 +22,21 @@
 
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use gtk4::gio;
 use gtk4::glib;
 use gtk4::prelude::*;
 use tracing::{debug, warn};
 
-use super::callbacks::Callbacks;
+use super::callbacks::{CallbackId, Callbacks};
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "screensaver";
+
+/// How often subscribers are refreshed while a timed inhibitor counts down.
+const EXPIRY_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Canonical snapshot of idle inhibitor state.
 #[derive(Debug, Clone)]
@@ -32,6 +45,8 @@ pub struct IdleInhibitorSnapshot {
     pub active: bool,
     /// Whether the inhibitor is available.
     pub available: bool,
+    /// When a timed inhibitor turns itself off (`None` = until toggled).
+    pub expires_at: Option<Instant>,
 }
 
 impl IdleInhibitorSnapshot {
@@ -40,8 +55,15 @@ impl IdleInhibitorSnapshot {
         Self {
             active: false,
             available: true,
+            expires_at: None,
         }
     }
+
+    /// Time left on a timed inhibitor, `None` if not timed.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_duration_since(Instant::now()))
+    }
 }
 
 /// Shared, process-wide idle inhibitor service.
@@ -58,6 +80,8 @@ pub struct IdleInhibitorService {
     inhibit_cookie: Cell<u32>,
     /// D-Bus proxy for org.freedesktop.ScreenSaver.
     dbus_proxy: RefCell<Option<gio::DBusProxy>>,
+    /// Countdown timer, active while a timed inhibitor is running.
+    expiry_source: RefCell<Option<glib::SourceId>>,
 }
 
 impl IdleInhibitorService {
@@ -68,6 +92,7 @@ impl IdleInhibitorService {
             callbacks: Callbacks::new(),
             inhibit_cookie: Cell::new(0),
             dbus_proxy: RefCell::new(None),
+            expiry_source: RefCell::new(None),
         });
 
         // Initialize D-Bus proxy asynchronously
@@ -116,15 +141,21 @@ impl IdleInhibitorService {
     }
 
     /// Register a callback to be invoked whenever the inhibitor state changes.
-    pub fn connect<F>(&self, callback: F)
+    pub fn connect<F>(&self, callback: F) -> CallbackId
     where
         F: Fn(&IdleInhibitorSnapshot) + 'static,
     {
-        self.callbacks.register(callback);
+        let id = self.callbacks.register(callback);
 
         // Immediately send current snapshot.
         let snapshot = self.snapshot.borrow().clone();
-        self.callbacks.notify(&snapshot);
+        self.callbacks.notify_single(id, &snapshot);
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
     }
 
     /// Return the current inhibitor snapshot.
@@ -145,16 +176,24 @@ impl IdleInhibitorService {
     }
 
     /// Toggle the inhibitor state.
-    #[allow(dead_code)] // API for potential CLI/external use
     pub fn toggle(&self) {
         let current = self.snapshot.borrow().active;
         self.set_active(!current);
     }
 
     /// Set the inhibitor state.
+    ///
+    /// Enabling clears any running timer, so the inhibitor stays on until
+    /// toggled off.
     pub fn set_active(&self, active: bool) {
+        self.cancel_expiry();
+
         let current = self.snapshot.borrow().active;
         if current == active {
+            // A timed inhibitor switched to "until toggled"
+            if self.snapshot.borrow().expires_at.is_some() {
+                self.update_expiry(None);
+            }
             return;
         }
 
@@ -165,14 +204,76 @@ impl IdleInhibitorService {
         }
     }
 
+    /// Enable the inhibitor for `duration`, then turn it off again.
+    ///
+    /// Calling this while the inhibitor is already active restarts the timer.
+    pub fn enable_for(&self, duration: Duration) {
+        self.cancel_expiry();
+
+        if !self.snapshot.borrow().active {
+            self.enable_inhibitor();
+            if !self.snapshot.borrow().active {
+                return;
+            }
+        }
+
+        self.update_expiry(Some(Instant::now() + duration));
+        self.schedule_expiry_tick();
+    }
+
     /// Stop the service and release any inhibitor.
     #[allow(dead_code)] // API for potential CLI/external use
     pub fn stop(&self) {
+        self.cancel_expiry();
         if self.snapshot.borrow().active {
             self.disable_inhibitor();
         }
     }
 
+    // Internal - expiry timer
+
+    /// Store a new expiry time and notify subscribers.
+    fn update_expiry(&self, expires_at: Option<Instant>) {
+        let snapshot = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.expires_at = expires_at;
+            snapshot.clone()
+        };
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Wake up at the next refresh (or at expiry, whichever comes first).
+    fn schedule_expiry_tick(&self) {
+        let Some(remaining) = self.snapshot.borrow().remaining() else {
+            return;
+        };
+
+        let source = glib::timeout_add_local_once(remaining.min(EXPIRY_REFRESH_INTERVAL), || {
+            let service = IdleInhibitorService::global();
+            // The source has fired; drop the handle so it isn't removed twice
+            service.expiry_source.borrow_mut().take();
+
+            let snapshot = service.snapshot();
+            match snapshot.remaining() {
+                Some(remaining) if remaining >= Duration::from_secs(1) => {
+                    service.callbacks.notify(&snapshot);
+                    service.schedule_expiry_tick();
+                }
+                _ => {
+                    debug!("IdleInhibitorService: Timed inhibitor expired");
+                    service.set_active(false);
+                }
+            }
+        });
+        *self.expiry_source.borrow_mut() = Some(source);
+    }
+
+    fn cancel_expiry(&self) {
+        if let Some(source) = self.expiry_source.borrow_mut().take() {
+            source.remove();
+        }
+    }
+
     // Internal - D-Bus ScreenSaver API
 
     fn enable_inhibitor(&self) {
@@ -185,12 +286,12 @@ impl IdleInhibitorService {
         // Call org.freedesktop.ScreenSaver.Inhibit(application_name, reason) -> cookie
         let args = ("vibepanel", "User requested idle inhibition").to_variant();
 
-        match proxy.call_sync(
+        match dbus_watchdog::call_proxy_sync(
+            WATCHDOG_SERVICE,
+            &proxy,
             "Inhibit",
             Some(&args),
-            gio::DBusCallFlags::NONE,
-            5000, // 5 second timeout
-            gio::Cancellable::NONE,
+            CallPolicy::ACTION, // 5 second timeout
         ) {
             Ok(result) => {
                 // Result is (u,) - a tuple containing the cookie
@@ -222,6 +323,7 @@ impl IdleInhibitorService {
             let mut snapshot = self.snapshot.borrow_mut();
             if snapshot.active {
                 snapshot.active = false;
+                snapshot.expires_at = None;
                 let snapshot_clone = snapshot.clone();
                 drop(snapshot);
                 self.callbacks.notify(&snapshot_clone);
@@ -234,12 +336,12 @@ impl IdleInhibitorService {
             // Call org.freedesktop.ScreenSaver.UnInhibit(cookie)
             let args = (cookie,).to_variant();
 
-            match proxy.call_sync(
+            match dbus_watchdog::call_proxy_sync(
+                WATCHDOG_SERVICE,
+                &proxy,
                 "UnInhibit",
                 Some(&args),
-                gio::DBusCallFlags::NONE,
-                5000,
-                gio::Cancellable::NONE,
+                CallPolicy::ACTION,
             ) {
                 Ok(_) => {
                     debug!("IdleInhibitorService: Disabled (cookie={})", cookie);
@@ -255,6 +357,7 @@ impl IdleInhibitorService {
 
         let mut snapshot = self.snapshot.borrow_mut();
         snapshot.active = false;
+        snapshot.expires_at = None;
         let snapshot_clone = snapshot.clone();
         drop(snapshot);
 
@@ -270,12 +373,12 @@ impl Drop for IdleInhibitorService {
             && let Some(proxy) = self.dbus_proxy.borrow().as_ref()
         {
             let args = (cookie,).to_variant();
-            let _ = proxy.call_sync(
+            let _ = dbus_watchdog::call_proxy_sync(
+                WATCHDOG_SERVICE,
+                &proxy,
                 "UnInhibit",
                 Some(&args),
-                gio::DBusCallFlags::NONE,
-                1000,
-                gio::Cancellable::NONE,
+                CallPolicy::QUICK,
             );
         }
     }
@@ -329,18 +432,20 @@ impl IdleInhibitorCli {
         );
 
         // Use call_with_unix_fd_list_sync to receive the file descriptor
-        let result = connection.call_with_unix_fd_list_sync(
-            Some("org.freedesktop.login1"),
-            "/org/freedesktop/login1",
-            "org.freedesktop.login1.Manager",
-            "Inhibit",
-            Some(&args.to_variant()),
-            Some(glib::VariantTy::new("(h)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            gio::UnixFDList::NONE,
-            gio::Cancellable::NONE,
-        );
+        let result = dbus_watchdog::run_sync("logind", CallPolicy::ACTION, |timeout_ms| {
+            connection.call_with_unix_fd_list_sync(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1",
+                "org.freedesktop.login1.Manager",
+                "Inhibit",
+                Some(&args.to_variant()),
+                Some(glib::VariantTy::new("(h)").unwrap()),
+                gio::DBusCallFlags::NONE,
+                timeout_ms,
+                gio::UnixFDList::NONE,
+                gio::Cancellable::NONE,
+            )
+        });
 
         match result {
             Ok((_reply, Some(fd_list))) => {
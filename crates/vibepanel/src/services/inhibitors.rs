@@ -0,0 +1,180 @@
+//! Query systemd-logind for active inhibitor locks.
+//!
+//! Power actions (shutdown, reboot, suspend) consult this before running so
+//! the user can see what is blocking them (e.g. a package manager holding a
+//! shutdown lock) instead of the machine powering off mid-transaction.
+//!
+//! Uses `org.freedesktop.login1.Manager.ListInhibitors` on the system bus.
+//! Failures are treated as "no blockers" so power actions never get stuck
+//! behind an unavailable logind.
+
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+use tracing::{debug, warn};
+
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "logind";
+
+const LOGIND_BUS_NAME: &str = "org.freedesktop.login1";
+
+/// Kind of operation an inhibitor lock can block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InhibitTarget {
+    /// Power off and reboot.
+    Shutdown,
+    /// Suspend and hibernate.
+    Sleep,
+}
+
+impl InhibitTarget {
+    /// The logind `what` token for this target.
+    pub fn what(&self) -> &'static str {
+        match self {
+            Self::Shutdown => "shutdown",
+            Self::Sleep => "sleep",
+        }
+    }
+}
+
+/// A single inhibitor lock as reported by logind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InhibitorLock {
+    /// Colon-separated list of inhibited operations (e.g. "shutdown:sleep").
+    pub what: String,
+    /// Human-readable name of the lock holder (e.g. "PackageKit").
+    pub who: String,
+    /// Reason given by the lock holder.
+    pub why: String,
+    /// Lock mode: "block" or "delay".
+    pub mode: String,
+    /// UID of the lock holder.
+    pub uid: u32,
+    /// PID of the lock holder.
+    pub pid: u32,
+}
+
+/// Filter locks down to those that block the given target.
+///
+/// Only `block` mode locks are returned; `delay` locks are handled by logind
+/// itself and don't prevent the action.
+pub fn blocking_locks(locks: &[InhibitorLock], target: InhibitTarget) -> Vec<InhibitorLock> {
+    locks
+        .iter()
+        .filter(|lock| lock.mode == "block")
+        .filter(|lock| lock.what.split(':').any(|w| w == target.what()))
+        .cloned()
+        .collect()
+}
+
+/// Asynchronously list the inhibitor locks that block `target`.
+///
+/// The callback runs on the GTK main loop. On any D-Bus error it receives an
+/// empty list.
+pub fn query_blocking_inhibitors<F>(target: InhibitTarget, callback: F)
+where
+    F: FnOnce(Vec<InhibitorLock>) + 'static,
+{
+    gio::bus_get(
+        gio::BusType::System,
+        None::<&gio::Cancellable>,
+        move |res| {
+            let connection = match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("Inhibitors: failed to connect to system bus: {}", e);
+                    callback(Vec::new());
+                    return;
+                }
+            };
+
+            dbus_watchdog::call(
+                WATCHDOG_SERVICE,
+                &connection,
+                LOGIND_BUS_NAME,
+                "/org/freedesktop/login1",
+                "org.freedesktop.login1.Manager",
+                "ListInhibitors",
+                None,
+                Some(glib::VariantTy::new("(a(ssssuu))").unwrap()),
+                CallPolicy::QUERY,
+                move |res| {
+                    let locks = match res {
+                        Ok(result) => parse_inhibitors(&result),
+                        Err(e) => {
+                            warn!("Inhibitors: ListInhibitors failed: {}", e);
+                            Vec::new()
+                        }
+                    };
+                    let blockers = blocking_locks(&locks, target);
+                    debug!(
+                        "Inhibitors: {} lock(s), {} blocking {}",
+                        locks.len(),
+                        blockers.len(),
+                        target.what()
+                    );
+                    callback(blockers);
+                },
+            );
+        },
+    );
+}
+
+/// Parse the `(a(ssssuu))` reply of `ListInhibitors`.
+fn parse_inhibitors(result: &glib::Variant) -> Vec<InhibitorLock> {
+    let array = result.child_value(0);
+    (0..array.n_children())
+        .filter_map(|i| {
+            let entry = array.child_value(i);
+            Some(InhibitorLock {
+                what: entry.child_value(0).get()?,
+                who: entry.child_value(1).get()?,
+                why: entry.child_value(2).get()?,
+                mode: entry.child_value(3).get()?,
+                uid: entry.child_value(4).get()?,
+                pid: entry.child_value(5).get()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(what: &str, who: &str, mode: &str) -> InhibitorLock {
+        InhibitorLock {
+            what: what.to_string(),
+            who: who.to_string(),
+            why: String::new(),
+            mode: mode.to_string(),
+            uid: 0,
+            pid: 1,
+        }
+    }
+
+    #[test]
+    fn test_blocking_locks_filters_by_target_and_mode() {
+        let locks = vec![
+            lock("shutdown:sleep", "PackageKit", "block"),
+            lock("sleep", "NetworkManager", "delay"),
+            lock("handle-lid-switch", "hypridle", "block"),
+            lock("sleep", "Steam", "block"),
+        ];
+
+        let shutdown = blocking_locks(&locks, InhibitTarget::Shutdown);
+        assert_eq!(shutdown.len(), 1);
+        assert_eq!(shutdown[0].who, "PackageKit");
+
+        let sleep = blocking_locks(&locks, InhibitTarget::Sleep);
+        let who: Vec<_> = sleep.iter().map(|l| l.who.as_str()).collect();
+        assert_eq!(who, vec!["PackageKit", "Steam"]);
+    }
+
+    #[test]
+    fn test_blocking_locks_empty() {
+        assert!(blocking_locks(&[], InhibitTarget::Shutdown).is_empty());
+    }
+}
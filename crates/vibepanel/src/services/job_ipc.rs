@@ -0,0 +1,96 @@
+//! Job messages sent from `vibepanel run` to the running bar.
+//!
+//! `vibepanel run -- <cmd>` reports when its command starts and finishes.
+//! Messages travel over the control socket as `job` commands (see
+//! `control_ipc`), and the bar forwards them to the jobs service:
+//! - `{"command":"job","action":"start","id":4242,"name":"restic backup"}` –
+//!   a job started (`id` is the runner's PID)
+//! - `{"command":"job","action":"finish","id":4242,"code":0}` – a job exited
+//!   with the given status code
+//!
+//! Like the OSD IPC, this is best-effort and fire-and-forget. If the bar
+//! isn't running, the command still runs normally.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::control_ipc::{self, ControlRequest};
+
+/// Maximum length (in characters) of a job name sent over the wire.
+const MAX_NAME_CHARS: usize = 200;
+
+/// Job IPC message types.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "action")]
+pub enum JobMessage {
+    /// A job started running.
+    #[serde(rename = "start")]
+    Started { id: u32, name: String },
+    /// A job finished with the given exit code.
+    #[serde(rename = "finish")]
+    Finished { id: u32, code: i32 },
+}
+
+/// Send a job message to the running bar (best-effort, fire-and-forget).
+pub fn send_job_message(msg: &JobMessage) -> io::Result<()> {
+    let mut msg = msg.clone();
+    if let JobMessage::Started { name, .. } = &mut msg {
+        *name = name.chars().take(MAX_NAME_CHARS).collect();
+    }
+    control_ipc::notify(&ControlRequest::Job(msg))
+}
+
+/// Convenience: send a message, logging (but otherwise ignoring) failures.
+pub fn notify_job(msg: &JobMessage) {
+    if let Err(e) = send_job_message(msg) {
+        debug!("Job IPC: failed to send {:?}: {}", msg, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_roundtrip() {
+        let cases = vec![
+            JobMessage::Started {
+                id: 4242,
+                name: "restic backup --tag nightly: home\nsecond line".to_string(),
+            },
+            JobMessage::Finished { id: 4242, code: 0 },
+            JobMessage::Finished { id: 7, code: -1 },
+        ];
+
+        for msg in cases {
+            let request = ControlRequest::Job(msg);
+            let wire = request.to_wire();
+            assert_eq!(wire.matches('\n').count(), 1);
+            assert_eq!(ControlRequest::from_wire(&wire), Ok(request));
+        }
+    }
+
+    #[test]
+    fn test_wire_format() {
+        assert_eq!(
+            ControlRequest::Job(JobMessage::Finished { id: 7, code: 2 }).to_wire(),
+            "{\"command\":\"job\",\"action\":\"finish\",\"id\":7,\"code\":2}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(
+            ControlRequest::from_wire(
+                r#"{"command":"job","action":"start","id":"abc","name":"x"}"#
+            )
+            .is_err()
+        );
+        assert!(
+            ControlRequest::from_wire(r#"{"command":"job","action":"finish","id":1}"#).is_err()
+        );
+        assert!(ControlRequest::from_wire(r#"{"command":"job","action":"volume"}"#).is_err());
+    }
+}
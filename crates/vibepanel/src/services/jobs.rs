@@ -0,0 +1,410 @@
+//! JobsService - tracks long-running commands started via `vibepanel run`.
+//!
+//! Jobs are reported over the control socket (see `job_ipc`). The service
+//! keeps running jobs plus a short history of finished ones, sends a desktop
+//! notification when a job exits, and notices runners that vanish without
+//! reporting (e.g. killed with SIGKILL).
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use gtk4::gio;
+use gtk4::glib::{self, SourceId};
+use gtk4::prelude::*;
+use tracing::{debug, warn};
+
+use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+use super::job_ipc::JobMessage;
+
+/// Number of finished jobs kept in the history.
+const MAX_FINISHED_JOBS: usize = 10;
+
+/// How often to check that running jobs' runners are still alive (seconds).
+const LIVENESS_INTERVAL_SECS: u32 = 5;
+
+/// Outcome of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    /// Still running.
+    Running,
+    /// Exited with the given status code.
+    Exited(i32),
+    /// The runner disappeared without reporting an exit status.
+    Lost,
+}
+
+/// A single tracked job.
+#[derive(Debug, Clone)]
+pub struct Job {
+    /// Job id (PID of the `vibepanel run` process).
+    pub id: u32,
+    /// Display name (command line or `--name`).
+    pub name: String,
+    /// When the job started.
+    pub started: SystemTime,
+    /// When the job finished, if it has.
+    pub ended: Option<SystemTime>,
+    /// Current state.
+    pub state: JobState,
+}
+
+impl Job {
+    /// Whether the job finished successfully.
+    pub fn succeeded(&self) -> bool {
+        self.state == JobState::Exited(0)
+    }
+
+    /// Elapsed run time (up to now for running jobs).
+    pub fn duration(&self) -> Duration {
+        let end = self.ended.unwrap_or_else(SystemTime::now);
+        end.duration_since(self.started).unwrap_or_default()
+    }
+}
+
+/// Canonical snapshot of job state.
+#[derive(Debug, Clone, Default)]
+pub struct JobsSnapshot {
+    /// Tracked jobs, newest first.
+    pub jobs: Vec<Job>,
+}
+
+impl JobsSnapshot {
+    /// Number of jobs still running.
+    pub fn running_count(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| job.state == JobState::Running)
+            .count()
+    }
+
+    /// The most recently finished job, if any.
+    pub fn last_finished(&self) -> Option<&Job> {
+        self.jobs
+            .iter()
+            .filter(|job| job.ended.is_some())
+            .max_by_key(|job| job.ended)
+    }
+}
+
+/// Format a duration compactly, e.g. "42s", "3m 05s", "1h 12m".
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Shared, process-wide jobs service.
+pub struct JobsService {
+    snapshot: RefCell<JobsSnapshot>,
+    callbacks: Callbacks<JobsSnapshot>,
+    /// Whether to send a desktop notification when a job finishes.
+    notify_on_finish: Cell<bool>,
+    /// Liveness check timer, active while jobs are running.
+    liveness_source: RefCell<Option<SourceId>>,
+}
+
+impl JobsService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(JobsSnapshot::default()),
+            callbacks: Callbacks::new(),
+            notify_on_finish: Cell::new(true),
+            liveness_source: RefCell::new(None),
+        })
+    }
+
+    /// Get the global JobsService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<JobsService> = JobsService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&JobsSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> JobsSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Enable or disable completion notifications.
+    pub fn set_notify_on_finish(&self, enabled: bool) {
+        self.notify_on_finish.set(enabled);
+    }
+
+    /// Remove all finished jobs from the history.
+    pub fn clear_finished(&self) {
+        self.snapshot
+            .borrow_mut()
+            .jobs
+            .retain(|job| job.state == JobState::Running);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply a `job` command received on the control socket.
+    pub fn handle_message(self: &Rc<Self>, msg: JobMessage) {
+        match msg {
+            JobMessage::Started { id, name } => {
+                debug!("JobsService: job {} started: {}", id, name);
+                {
+                    let mut snapshot = self.snapshot.borrow_mut();
+                    snapshot.jobs.retain(|job| job.id != id);
+                    snapshot.jobs.insert(
+                        0,
+                        Job {
+                            id,
+                            name,
+                            started: SystemTime::now(),
+                            ended: None,
+                            state: JobState::Running,
+                        },
+                    );
+                }
+                self.ensure_liveness_timer();
+            }
+            JobMessage::Finished { id, code } => {
+                debug!("JobsService: job {} finished with {}", id, code);
+                self.finish_job(id, JobState::Exited(code));
+            }
+        }
+        self.notify();
+    }
+
+    /// Mark a running job as finished and trim the history.
+    fn finish_job(&self, id: u32, state: JobState) {
+        let finished = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let Some(job) = snapshot
+                .jobs
+                .iter_mut()
+                .find(|job| job.id == id && job.state == JobState::Running)
+            else {
+                return;
+            };
+            job.state = state;
+            job.ended = Some(SystemTime::now());
+            let finished = job.clone();
+
+            trim_finished(&mut snapshot.jobs);
+            finished
+        };
+
+        if self.notify_on_finish.get() {
+            send_completion_notification(&finished);
+        }
+    }
+
+    /// Start the liveness check timer if it isn't already running.
+    fn ensure_liveness_timer(self: &Rc<Self>) {
+        if self.liveness_source.borrow().is_some() {
+            return;
+        }
+
+        let weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(LIVENESS_INTERVAL_SECS, move || {
+            let Some(this) = weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            if this.check_liveness() {
+                glib::ControlFlow::Continue
+            } else {
+                // Returning Break removes the source; just forget the ID.
+                this.liveness_source.borrow_mut().take();
+                glib::ControlFlow::Break
+            }
+        });
+        *self.liveness_source.borrow_mut() = Some(source_id);
+    }
+
+    /// Mark jobs whose runner process is gone as lost.
+    ///
+    /// Returns `true` while any job is still running.
+    fn check_liveness(&self) -> bool {
+        let lost: Vec<u32> = self
+            .snapshot
+            .borrow()
+            .jobs
+            .iter()
+            .filter(|job| job.state == JobState::Running)
+            .filter(|job| !Path::new(&format!("/proc/{}", job.id)).exists())
+            .map(|job| job.id)
+            .collect();
+
+        if !lost.is_empty() {
+            for id in lost {
+                warn!("JobsService: job {} vanished without reporting", id);
+                self.finish_job(id, JobState::Lost);
+            }
+            self.notify();
+        }
+
+        self.snapshot.borrow().running_count() > 0
+    }
+}
+
+impl Drop for JobsService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.liveness_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`].
+///
+/// Jobs are ordered newest first, so the tail holds the oldest entries.
+fn trim_finished(jobs: &mut Vec<Job>) {
+    let mut finished = 0;
+    jobs.retain(|job| {
+        if job.state == JobState::Running {
+            return true;
+        }
+        finished += 1;
+        finished <= MAX_FINISHED_JOBS
+    });
+}
+
+/// Summary and body text for a finished job's notification.
+fn completion_message(job: &Job) -> (String, String) {
+    let duration = format_duration(job.duration());
+    match job.state {
+        JobState::Exited(0) => (
+            "Job finished".to_string(),
+            format!("{} completed in {}", job.name, duration),
+        ),
+        JobState::Exited(code) => (
+            "Job failed".to_string(),
+            format!(
+                "{} exited with status {} after {}",
+                job.name, code, duration
+            ),
+        ),
+        JobState::Lost | JobState::Running => (
+            "Job interrupted".to_string(),
+            format!("{} stopped reporting after {}", job.name, duration),
+        ),
+    }
+}
+
+/// Send a desktop notification for a finished job via org.freedesktop.Notifications.
+fn send_completion_notification(job: &Job) {
+    let (summary, body) = completion_message(job);
+    let icon = if job.succeeded() {
+        "emblem-ok-symbolic"
+    } else {
+        "dialog-error-symbolic"
+    };
+
+    let params = (
+        "vibepanel",
+        0u32,
+        icon,
+        summary.as_str(),
+        body.as_str(),
+        Vec::<String>::new(),
+        HashMap::<String, glib::Variant>::new(),
+        -1i32,
+    )
+        .to_variant();
+
+    gio::bus_get(
+        gio::BusType::Session,
+        None::<&gio::Cancellable>,
+        move |res| {
+            let connection = match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("JobsService: failed to connect to session bus: {}", e);
+                    return;
+                }
+            };
+
+            dbus_watchdog::call(
+                "notifications",
+                &connection,
+                "org.freedesktop.Notifications",
+                "/org/freedesktop/Notifications",
+                "org.freedesktop.Notifications",
+                "Notify",
+                Some(&params),
+                None,
+                CallPolicy::ACTION,
+                |res| {
+                    if let Err(e) = res {
+                        debug!("JobsService: failed to send notification: {}", e);
+                    }
+                },
+            );
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: u32, state: JobState) -> Job {
+        let started = SystemTime::UNIX_EPOCH;
+        Job {
+            id,
+            name: format!("job-{}", id),
+            started,
+            ended: (state != JobState::Running).then(|| started + Duration::from_secs(id as u64)),
+            state,
+        }
+    }
+
+    #[test]
+    fn test_trim_finished_keeps_running_jobs() {
+        let mut jobs = vec![job(0, JobState::Running)];
+        jobs.extend((1..=15).map(|id| job(id, JobState::Exited(0))));
+
+        trim_finished(&mut jobs);
+
+        assert_eq!(jobs.len(), 1 + MAX_FINISHED_JOBS);
+        assert_eq!(jobs[0].state, JobState::Running);
+        assert_eq!(jobs.last().map(|j| j.id), Some(MAX_FINISHED_JOBS as u32));
+    }
+
+    #[test]
+    fn test_completion_message() {
+        let (summary, body) = completion_message(&job(65, JobState::Exited(2)));
+        assert_eq!(summary, "Job failed");
+        assert_eq!(body, "job-65 exited with status 2 after 1m 05s");
+
+        let (summary, _) = completion_message(&job(1, JobState::Exited(0)));
+        assert_eq!(summary, "Job finished");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m 05s");
+        assert_eq!(format_duration(Duration::from_secs(4320)), "1h 12m");
+    }
+}
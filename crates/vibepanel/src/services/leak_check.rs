@@ -0,0 +1,198 @@
+//! LeakCheck - debug mode for spotting objects that outlive their bar or popover.
+//!
+//! Enabled with `vibepanel --leak-check`. While enabled:
+//! - Bar widgets, bar windows, popover windows and menu handles are tracked
+//!   through weak references (tracking never keeps anything alive)
+//! - After a popover closes or a bar is removed, a report of live tracked
+//!   objects, their strong reference counts, bars, widget handles and event
+//!   bus subscribers is logged
+//! - A warning is logged when the live count after the same kind of event
+//!   grows, which is what accumulating handles look like on repeated hotplug
+//!
+//! When disabled, tracking and reporting are no-ops.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::{Rc, Weak};
+
+use gtk4::glib;
+use gtk4::prelude::*;
+use tracing::{info, warn};
+
+use super::bar_manager::BarManager;
+use super::event_bus::EventBus;
+
+/// Returns the current strong count of a tracked object (0 once it's gone).
+type StrongCount = Box<dyn Fn() -> usize>;
+
+/// Live objects and their summed strong counts for one label.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LiveCount {
+    /// Number of tracked objects still alive.
+    pub live: usize,
+    /// Sum of their strong reference counts.
+    pub strong: usize,
+}
+
+/// Shared, process-wide leak checker.
+pub struct LeakCheck {
+    enabled: Cell<bool>,
+    tracked: RefCell<Vec<(&'static str, StrongCount)>>,
+    /// Total live objects after the previous report, by report context.
+    previous: RefCell<HashMap<&'static str, usize>>,
+}
+
+impl LeakCheck {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            enabled: Cell::new(false),
+            tracked: RefCell::new(Vec::new()),
+            previous: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Get the global LeakCheck singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<LeakCheck> = LeakCheck::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Turn on tracking and reporting.
+    pub fn enable(&self) {
+        info!("Leak check enabled");
+        self.enabled.set(true);
+    }
+
+    /// Whether leak-check mode is on.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Track a GObject (widget, window) under `label`.
+    pub fn track_object(&self, label: &'static str, object: &impl IsA<glib::Object>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let weak = object.upcast_ref::<glib::Object>().downgrade();
+        // The upgrade itself holds one reference
+        self.track(
+            label,
+            Box::new(move || {
+                weak.upgrade()
+                    .map(|object| object.ref_count() as usize - 1)
+                    .unwrap_or(0)
+            }),
+        );
+    }
+
+    /// Track an `Rc`-owned value under `label`.
+    pub fn track_rc<T: 'static>(&self, label: &'static str, value: &Rc<T>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let weak: Weak<T> = Rc::downgrade(value);
+        self.track(label, Box::new(move || weak.strong_count()));
+    }
+
+    fn track(&self, label: &'static str, strong_count: StrongCount) {
+        self.tracked.borrow_mut().push((label, strong_count));
+    }
+
+    /// Live tracked objects by label. Forgets objects that are gone.
+    pub fn live_counts(&self) -> BTreeMap<&'static str, LiveCount> {
+        let mut counts: BTreeMap<&'static str, LiveCount> = BTreeMap::new();
+        self.tracked.borrow_mut().retain(|(label, strong_count)| {
+            let strong = strong_count();
+            if strong == 0 {
+                return false;
+            }
+            let count = counts.entry(*label).or_default();
+            count.live += 1;
+            count.strong += strong;
+            true
+        });
+        counts
+    }
+
+    /// Log a report once the main loop has finished tearing down `context`
+    /// (e.g. "popover closed", "bar removed").
+    pub fn report(&self, context: &'static str) {
+        if !self.is_enabled() {
+            return;
+        }
+        glib::idle_add_local_once(move || LeakCheck::global().log_report(context));
+    }
+
+    fn log_report(&self, context: &'static str) {
+        let counts = self.live_counts();
+        let manager = BarManager::global();
+        let summary: Vec<String> = counts
+            .iter()
+            .map(|(label, count)| format!("{}={} (strong {})", label, count.live, count.strong))
+            .collect();
+        info!(
+            "Leak check after {}: bars={} handles={} subscriptions={} bus_subscribers={} live: {}",
+            context,
+            manager.bar_count(),
+            manager.handle_count(),
+            manager.subscription_count(),
+            EventBus::global().total_subscriber_count(),
+            summary.join(", ")
+        );
+
+        let total: usize = counts.values().map(|count| count.live).sum();
+        if let Some(previous) = self.previous.borrow_mut().insert(context, total)
+            && total > previous
+        {
+            warn!(
+                "Leak check: live objects grew from {} to {} after {}",
+                previous, total, context
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_tracks_nothing() {
+        let check = LeakCheck::new();
+        let value = Rc::new(1);
+        check.track_rc("menu", &value);
+        assert!(check.live_counts().is_empty());
+    }
+
+    #[test]
+    fn test_live_counts_follow_rc_lifetime() {
+        let check = LeakCheck::new();
+        check.enabled.set(true);
+
+        let first = Rc::new(1);
+        let second = Rc::new(2);
+        let extra = second.clone();
+        check.track_rc("menu", &first);
+        check.track_rc("menu", &second);
+
+        assert_eq!(
+            check.live_counts().get("menu"),
+            Some(&LiveCount { live: 2, strong: 3 })
+        );
+
+        drop(first);
+        drop(extra);
+        assert_eq!(
+            check.live_counts().get("menu"),
+            Some(&LiveCount { live: 1, strong: 1 })
+        );
+
+        drop(second);
+        assert!(check.live_counts().is_empty());
+        // Dead entries are forgotten
+        assert!(check.tracked.borrow().is_empty());
+    }
+}
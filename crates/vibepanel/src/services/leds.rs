@@ -0,0 +1,215 @@
+//! Shared access to the LEDs in `/sys/class/leds`.
+//!
+//! Used by the notification LED and the mic-mute LED. Writes go through
+//! systemd-logind's `SetBrightness` (which also covers the `leds` subsystem)
+//! so no udev rules are needed, falling back to direct sysfs writes if
+//! logind is unavailable.
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use gtk4::gio;
+use gtk4::prelude::ToVariant;
+use tracing::{debug, warn};
+
+use super::brightness::logind_session_path;
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "logind";
+
+/// Logind D-Bus constants.
+const LOGIND_BUS_NAME: &str = "org.freedesktop.login1";
+const LOGIND_SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+/// Path to the LED class on Linux.
+pub const LEDS_PATH: &str = "/sys/class/leds";
+
+/// An LED found in sysfs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedDevice {
+    /// Directory name under `/sys/class/leds` (e.g. "input3::capslock").
+    pub name: String,
+    /// Path to the `brightness` file.
+    pub brightness_path: PathBuf,
+    /// Brightness used to light the LED.
+    pub max_brightness: u32,
+}
+
+impl LedDevice {
+    /// The LED `name` under `root`, if it has a `brightness` file.
+    pub fn open(root: &Path, name: &str) -> Option<Self> {
+        let dir = root.join(name);
+        let brightness_path = dir.join("brightness");
+        if !brightness_path.exists() {
+            return None;
+        }
+        let max_brightness = read_u32(&dir.join("max_brightness"))
+            .filter(|&max| max > 0)
+            .unwrap_or(1);
+
+        Some(Self {
+            name: name.to_string(),
+            brightness_path,
+            max_brightness,
+        })
+    }
+
+    /// Current brightness, if readable.
+    pub fn brightness(&self) -> Option<u32> {
+        read_u32(&self.brightness_path)
+    }
+}
+
+/// Names of the LEDs under `root`, sorted.
+pub fn led_names(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The first LED under `root` whose name ends with `suffix` (e.g. "::micmute").
+pub fn find_led_by_suffix(root: &Path, suffix: &str) -> Option<LedDevice> {
+    led_names(root)
+        .into_iter()
+        .find(|name| name.ends_with(suffix))
+        .and_then(|name| LedDevice::open(root, &name))
+}
+
+/// Sets LED brightness via logind, falling back to sysfs.
+pub struct LedWriter {
+    /// Service name used in log messages.
+    owner: &'static str,
+    /// System bus connection and logind session path, once known.
+    logind: RefCell<Option<(gio::DBusConnection, String)>>,
+}
+
+impl LedWriter {
+    /// Create a writer and start looking up the logind session.
+    ///
+    /// Writes made before the lookup finishes go straight to sysfs.
+    pub fn new(owner: &'static str) -> Rc<Self> {
+        let writer = Rc::new(Self {
+            owner,
+            logind: RefCell::new(None),
+        });
+        Self::init_logind(&writer);
+        writer
+    }
+
+    /// Set the brightness of `device`.
+    pub fn write(&self, device: LedDevice, value: u32) {
+        let Some((connection, session_path)) = self.logind.borrow().clone() else {
+            write_sysfs(self.owner, &device, value);
+            return;
+        };
+
+        let owner = self.owner;
+        dbus_watchdog::call(
+            WATCHDOG_SERVICE,
+            &connection,
+            LOGIND_BUS_NAME,
+            &session_path,
+            LOGIND_SESSION_INTERFACE,
+            "SetBrightness",
+            Some(&("leds", device.name.as_str(), value).to_variant()),
+            None,
+            CallPolicy::ACTION,
+            move |res| {
+                if let Err(e) = res {
+                    warn!(
+                        "{}: logind SetBrightness failed for {}: {}; trying sysfs",
+                        owner, device.name, e
+                    );
+                    write_sysfs(owner, &device, value);
+                }
+            },
+        );
+    }
+
+    /// Set the brightness of `device`, waiting for the call to finish (for
+    /// shutdown, when the main loop is about to stop).
+    pub fn write_sync(&self, device: &LedDevice, value: u32) {
+        let via_logind = self
+            .logind
+            .borrow()
+            .as_ref()
+            .is_some_and(|(conn, session)| {
+                dbus_watchdog::call_sync(
+                    WATCHDOG_SERVICE,
+                    conn,
+                    LOGIND_BUS_NAME,
+                    session,
+                    LOGIND_SESSION_INTERFACE,
+                    "SetBrightness",
+                    Some(&("leds", device.name.as_str(), value).to_variant()),
+                    None,
+                    CallPolicy::ACTION,
+                )
+                .is_ok()
+            });
+        if !via_logind {
+            write_sysfs(self.owner, device, value);
+        }
+    }
+
+    /// Connect to the system bus and find our logind session.
+    fn init_logind(this: &Rc<Self>) {
+        let this_weak = Rc::downgrade(this);
+        gio::bus_get(
+            gio::BusType::System,
+            None::<&gio::Cancellable>,
+            move |res| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                let connection = match res {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!(
+                            "{}: failed to connect to system bus: {}; \
+                             falling back to direct sysfs writes",
+                            this.owner, e
+                        );
+                        return;
+                    }
+                };
+
+                match logind_session_path(&connection) {
+                    Some(session_path) => {
+                        debug!("{}: using logind session {}", this.owner, session_path);
+                        *this.logind.borrow_mut() = Some((connection, session_path));
+                    }
+                    None => warn!(
+                        "{}: no usable logind session found; \
+                         falling back to direct sysfs writes",
+                        this.owner
+                    ),
+                }
+            },
+        );
+    }
+}
+
+fn read_u32(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Set the LED brightness by writing to sysfs (may need write permission).
+fn write_sysfs(owner: &str, device: &LedDevice, value: u32) {
+    if let Err(e) = fs::write(&device.brightness_path, value.to_string()) {
+        warn!(
+            "{}: failed to write {}: {}",
+            owner,
+            device.brightness_path.display(),
+            e
+        );
+    }
+}
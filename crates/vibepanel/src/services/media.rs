@@ -6,7 +6,12 @@
 //! - Playback state monitoring (Playing/Paused/Stopped)
 //! - Metadata access (title, artist, album, art URL, duration)
 //! - Playback control (play/pause, next, previous, seek, volume)
-//! - Position tracking with periodic polling when playing
+//! - Track ratings (`xesam:userRating`), with love/unlove for players that
+//!   accept ratings over an app-specific D-Bus interface
+//! - Long-form audio detection (podcasts, audiobooks) for ±30s skip controls
+//! - Position tracking: polled while playing (players aren't required to
+//!   signal `Position` changes), corrected on `Seeked`, and advanced locally
+//!   between reports
 //! - Multi-player support with automatic or manual player selection
 //!
 //! ## Architecture
@@ -25,11 +30,14 @@
 //! - Interfaces:
 //!   - `org.mpris.MediaPlayer2` - Base interface (Identity, Quit, etc.)
 //!   - `org.mpris.MediaPlayer2.Player` - Playback control and state
+//!
+//! MPRIS has no method for changing a rating, so writable ratings need a
+//! `RatingBackend` per player, probed when the player appears.
 
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use gtk4::gio;
 use gtk4::glib::{self, ControlFlow, Variant, clone};
@@ -37,6 +45,7 @@ use gtk4::prelude::*;
 use tracing::{debug, error, trace, warn};
 
 use super::callbacks::{CallbackId, Callbacks};
+use super::dbus_watchdog::{self, CallPolicy};
 
 // D-Bus constants
 const DBUS_NAME: &str = "org.freedesktop.DBus";
@@ -45,17 +54,38 @@ const DBUS_INTERFACE: &str = "org.freedesktop.DBus";
 const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
 const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
 const MPRIS_PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+/// Track id of players without a current track; SetPosition can't target it.
+const MPRIS_NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
 const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
 
+/// Service names for D-Bus health tracking. Players are tracked as one
+/// service, since any of them may come and go.
+const MPRIS_SERVICE: &str = "mpris";
+const DBUS_DAEMON_SERVICE: &str = "dbus";
+
 /// Position polling interval when playing (in milliseconds).
 const POSITION_POLL_INTERVAL_MS: u64 = 1000;
-/// Default timeout for D-Bus method calls (in milliseconds).
-const DBUS_CALL_TIMEOUT_MS: i32 = 5000;
-/// Shorter timeout for position polling queries.
-const DBUS_POLL_TIMEOUT_MS: i32 = 1000;
+
+/// How far a polled position may be from the locally advanced one before
+/// it replaces it (in microseconds).
+const POSITION_DRIFT_US: i64 = 500_000;
+
+/// Ratings at or above this (0.0-1.0, i.e. five stars) count as loved.
+const LOVED_RATING: f64 = 0.99;
+
+/// How far the skip buttons seek for long-form audio (in microseconds).
+pub const SKIP_SEEK_US: i64 = 30 * 1_000_000;
 
 // ========== Helper Functions ==========
 
+/// Advance a reported position by the playback time elapsed since, clamped
+/// to the track length when known.
+fn interpolate_position(position: i64, elapsed: Duration, rate: f64, length: Option<i64>) -> i64 {
+    let advanced = position.saturating_add((elapsed.as_micros() as f64 * rate) as i64);
+    let end = length.filter(|l| *l > 0).unwrap_or(i64::MAX);
+    advanced.clamp(0, end)
+}
+
 /// Extract player ID from MPRIS bus name (e.g., "org.mpris.MediaPlayer2.spotify" -> "spotify").
 fn player_id_from_bus_name(bus_name: &str) -> String {
     bus_name
@@ -65,6 +95,15 @@ fn player_id_from_bus_name(bus_name: &str) -> String {
         .to_string()
 }
 
+/// Position of a player in `priority`, matching its id or display name
+/// case-insensitively. Unlisted players rank after all listed ones.
+fn priority_rank(priority: &[String], player_id: &str, player_name: &str) -> usize {
+    priority
+        .iter()
+        .position(|p| p.eq_ignore_ascii_case(player_id) || p.eq_ignore_ascii_case(player_name))
+        .unwrap_or(priority.len())
+}
+
 /// Capitalize the first character of a string (e.g., "spotify" -> "Spotify").
 fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -74,6 +113,140 @@ fn capitalize_first(s: &str) -> String {
     }
 }
 
+/// Whether a `xesam:userRating` value counts as loved.
+pub fn is_loved(rating: Option<f64>) -> bool {
+    rating.is_some_and(|r| r >= LOVED_RATING)
+}
+
+/// How a player's skip buttons behave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipMode {
+    /// Seek for long-form tracks, change track otherwise.
+    Auto,
+    /// Always seek ±30 seconds.
+    Seek,
+    /// Always go to the next/previous track.
+    Track,
+}
+
+impl SkipMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(Self::Auto),
+            "seek" => Some(Self::Seek),
+            "track" => Some(Self::Track),
+            _ => None,
+        }
+    }
+}
+
+/// Rules for treating a track as long-form audio (podcast, audiobook).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongFormConfig {
+    /// Tracks at least this long count as long-form (0 = length is ignored).
+    pub min_length_us: i64,
+    /// Genres (case-insensitive substrings) that mark a track as long-form.
+    pub genres: Vec<String>,
+    /// Per-player overrides, keyed by player id or name.
+    pub players: Vec<(String, SkipMode)>,
+}
+
+impl Default for LongFormConfig {
+    fn default() -> Self {
+        Self {
+            min_length_us: 20 * 60 * 1_000_000,
+            genres: vec!["podcast".to_string(), "audiobook".to_string()],
+            players: Vec::new(),
+        }
+    }
+}
+
+impl LongFormConfig {
+    /// Whether the skip buttons should seek for this player and track.
+    fn applies(&self, player_id: &str, player_name: &str, metadata: &MediaMetadata) -> bool {
+        let mode = self
+            .players
+            .iter()
+            .find(|(name, _)| {
+                name.eq_ignore_ascii_case(player_id) || name.eq_ignore_ascii_case(player_name)
+            })
+            .map_or(SkipMode::Auto, |(_, mode)| *mode);
+
+        match mode {
+            SkipMode::Seek => true,
+            SkipMode::Track => false,
+            SkipMode::Auto => {
+                let long = self.min_length_us > 0
+                    && metadata.length.is_some_and(|l| l >= self.min_length_us);
+                let genre = metadata.genre.as_deref().is_some_and(|genre| {
+                    let genre = genre.to_lowercase();
+                    self.genres
+                        .iter()
+                        .any(|g| !g.is_empty() && genre.contains(&g.to_lowercase()))
+                });
+                long || genre
+            }
+        }
+    }
+}
+
+/// App-specific D-Bus interface for changing the current track's rating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RatingBackend {
+    /// Rhythmbox's `RhythmDB.SetEntryProperties`, rating in stars (0-5)
+    /// keyed by track URI.
+    Rhythmbox,
+}
+
+impl RatingBackend {
+    /// Backend for a player, by player ID (e.g. "rhythmbox").
+    fn for_player(player_id: &str) -> Option<Self> {
+        match player_id {
+            "rhythmbox" => Some(Self::Rhythmbox),
+            _ => None,
+        }
+    }
+
+    /// Bus name that must be owned for the backend to be usable.
+    fn bus_name(self) -> &'static str {
+        match self {
+            Self::Rhythmbox => "org.gnome.Rhythmbox3",
+        }
+    }
+
+    /// Set the rating (0.0-1.0) of the track at `url`.
+    fn set_rating(self, connection: &gio::DBusConnection, url: &str, rating: f64) {
+        let (path, interface, method, params) = match self {
+            Self::Rhythmbox => {
+                let props = HashMap::from([("rating".to_string(), (rating * 5.0).to_variant())]);
+                (
+                    "/org/gnome/Rhythmbox3/RhythmDB",
+                    "org.gnome.Rhythmbox3.RhythmDB",
+                    "SetEntryProperties",
+                    (url, props).to_variant(),
+                )
+            }
+        };
+
+        dbus_watchdog::call(
+            MPRIS_SERVICE,
+            connection,
+            self.bus_name(),
+            path,
+            interface,
+            method,
+            Some(&params),
+            None,
+            CallPolicy::ACTION,
+            move |res| {
+                if let Err(e) = res {
+                    warn!("Failed to set rating via {:?}: {}", self, e);
+                }
+            },
+        );
+    }
+}
+
 /// Playback status of the media player.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum PlaybackStatus {
@@ -104,6 +277,8 @@ pub struct MediaMetadata {
     pub artist: Option<String>,
     /// Album name (xesam:album).
     pub album: Option<String>,
+    /// Genre(s) (xesam:genre).
+    pub genre: Option<String>,
     /// Album art URL (mpris:artUrl) - can be file:// or http(s)://.
     pub art_url: Option<String>,
     /// Track URL (xesam:url) - useful for identifying web players.
@@ -112,6 +287,8 @@ pub struct MediaMetadata {
     pub length: Option<i64>,
     /// Track ID (mpris:trackid).
     pub track_id: Option<String>,
+    /// Track rating from 0.0 to 1.0 (xesam:userRating).
+    pub user_rating: Option<f64>,
 }
 
 /// Info about a single player, for the player selector UI.
@@ -152,6 +329,10 @@ pub struct MediaSnapshot {
     pub can_go_previous: bool,
     /// Whether the player can seek.
     pub can_seek: bool,
+    /// Whether the current track's rating can be changed.
+    pub can_rate: bool,
+    /// Whether the skip buttons seek ±30s instead of changing track.
+    pub long_form: bool,
 }
 
 impl Default for MediaSnapshot {
@@ -168,6 +349,8 @@ impl Default for MediaSnapshot {
             can_go_next: false,
             can_go_previous: false,
             can_seek: false,
+            can_rate: false,
+            long_form: false,
         }
     }
 }
@@ -187,15 +370,23 @@ struct MprisPlayer {
     proxy: gio::DBusProxy,
     playback_status: PlaybackStatus,
     metadata: MediaMetadata,
+    /// Last reported position (microseconds), taken at `position_updated`.
     position: i64,
+    position_updated: Instant,
+    /// Playback rate (`Rate`), 1.0 for normal speed.
+    rate: f64,
     can_play: bool,
     can_pause: bool,
     can_go_next: bool,
     can_go_previous: bool,
     can_seek: bool,
     can_control: bool,
+    /// How to change ratings, once probed.
+    rating_backend: Option<RatingBackend>,
     /// Signal subscription for PropertiesChanged (set after creation).
     _properties_subscription: Option<gio::SignalSubscription>,
+    /// Signal subscription for Seeked (set after creation).
+    _seeked_subscription: Option<gio::SignalSubscription>,
     /// Track generation for invalidating stale position polls.
     track_generation: u64,
 }
@@ -209,6 +400,25 @@ impl MprisPlayer {
             is_active,
         }
     }
+
+    /// Record a position reported by the player (or expected after a seek).
+    fn set_position(&mut self, position: i64) {
+        self.position = position;
+        self.position_updated = Instant::now();
+    }
+
+    /// Current position, advanced from the last report while playing.
+    fn current_position(&self) -> i64 {
+        if self.playback_status != PlaybackStatus::Playing {
+            return self.position;
+        }
+        interpolate_position(
+            self.position,
+            self.position_updated.elapsed(),
+            self.rate,
+            self.metadata.length,
+        )
+    }
 }
 
 /// Shared, process-wide media service with multi-player support.
@@ -223,6 +433,10 @@ pub struct MediaService {
     manual_selection: RefCell<Option<String>>,
     /// Last player that started playing (for auto-selection preference).
     last_playing: RefCell<Option<String>>,
+    /// Preferred players for auto-selection, most preferred first.
+    player_priority: RefCell<Vec<String>>,
+    /// Long-form audio detection rules.
+    long_form: RefCell<LongFormConfig>,
     /// Registered callbacks for state changes.
     callbacks: Callbacks<MediaSnapshot>,
     /// Signal subscription for NameOwnerChanged (player appear/disappear).
@@ -241,6 +455,8 @@ impl MediaService {
             active_player: RefCell::new(None),
             manual_selection: RefCell::new(None),
             last_playing: RefCell::new(None),
+            player_priority: RefCell::new(Vec::new()),
+            long_form: RefCell::new(LongFormConfig::default()),
             callbacks: Callbacks::new(),
             _name_owner_subscription: RefCell::new(None),
             position_poll_source: RefCell::new(None),
@@ -321,6 +537,38 @@ impl MediaService {
         self.manual_selection.borrow().is_none()
     }
 
+    /// Set the players auto-selection prefers, most preferred first.
+    ///
+    /// Entries match the player id (e.g. "spotify") or display name,
+    /// case-insensitively.
+    pub fn set_player_priority(self: &Rc<Self>, priority: Vec<String>) {
+        if *self.player_priority.borrow() == priority {
+            return;
+        }
+        debug!("Media player priority: {:?}", priority);
+        self.player_priority.replace(priority);
+        self.update_active_player();
+        self.notify_callbacks();
+    }
+
+    /// Set the rules for switching the skip buttons to ±30s seeking.
+    pub fn set_long_form(&self, config: LongFormConfig) {
+        if *self.long_form.borrow() == config {
+            return;
+        }
+        self.long_form.replace(config);
+        self.notify_callbacks();
+    }
+
+    /// Position of a player in the priority list (unlisted players rank last).
+    fn player_rank(&self, player: &MprisPlayer) -> usize {
+        priority_rank(
+            &self.player_priority.borrow(),
+            &player.player_id,
+            &player.player_name,
+        )
+    }
+
     /// Write current active player to state file for CLI commands.
     fn write_ipc_state(&self) {
         let active = self.active_player.borrow();
@@ -397,16 +645,16 @@ impl MediaService {
         };
 
         let this_weak = Rc::downgrade(self);
-        connection.call(
-            Some(DBUS_NAME),
+        dbus_watchdog::call(
+            DBUS_DAEMON_SERVICE,
+            &connection,
+            DBUS_NAME,
             DBUS_PATH,
             DBUS_INTERFACE,
             "ListNames",
             None,
             Some(glib::VariantTy::new("(as)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            DBUS_CALL_TIMEOUT_MS,
-            None::<&gio::Cancellable>,
+            CallPolicy::QUERY,
             move |res| {
                 let Some(this) = this_weak.upgrade() else {
                     return;
@@ -494,13 +742,17 @@ impl MediaService {
                         playback_status: PlaybackStatus::Stopped,
                         metadata: MediaMetadata::default(),
                         position: 0,
+                        position_updated: Instant::now(),
+                        rate: 1.0,
                         can_play: false,
                         can_pause: false,
                         can_go_next: false,
                         can_go_previous: false,
                         can_seek: false,
                         can_control: true,
+                        rating_backend: None,
                         _properties_subscription: None,
+                        _seeked_subscription: None,
                         track_generation: 0,
                     }));
 
@@ -517,7 +769,7 @@ impl MediaService {
                         Some(MPRIS_PATH),
                         None,
                         gio::DBusSignalFlags::NONE,
-                        move |_signal| {
+                        move |signal| {
                             let Some(player) = player_weak.upgrade() else {
                                 return;
                             };
@@ -530,6 +782,16 @@ impl MediaService {
                             let new_status = player.borrow().playback_status;
                             let status_changed = old_status != new_status;
 
+                            // The spec says Position changes aren't signalled,
+                            // but take it from players that do
+                            let changed = signal.parameters.child_value(1);
+                            if changed.is_type(glib::VariantTy::VARDICT)
+                                && let Ok(Some(position)) =
+                                    glib::VariantDict::new(Some(&changed)).lookup::<i64>("Position")
+                            {
+                                player.borrow_mut().set_position(position);
+                            }
+
                             // Track the most recently playing player
                             if new_status == PlaybackStatus::Playing
                                 && old_status != PlaybackStatus::Playing
@@ -591,7 +853,37 @@ impl MediaService {
 
                     player.borrow_mut()._properties_subscription = Some(subscription);
 
+                    // Seeked reports jumps that polling would only catch later
+                    let player_weak = Rc::downgrade(&player);
+                    let this_weak = Rc::downgrade(&this);
+                    let subscription = connection.subscribe_to_signal(
+                        Some(&bus_name_owned),
+                        Some(MPRIS_PLAYER_INTERFACE),
+                        Some("Seeked"),
+                        Some(MPRIS_PATH),
+                        None,
+                        gio::DBusSignalFlags::NONE,
+                        move |signal| {
+                            let (Some(player), Some(this)) =
+                                (player_weak.upgrade(), this_weak.upgrade())
+                            else {
+                                return;
+                            };
+                            let Some(position) = signal.parameters.child_value(0).get::<i64>()
+                            else {
+                                return;
+                            };
+
+                            trace!("{} seeked to {}", player.borrow().player_name, position);
+                            player.borrow_mut().set_position(position);
+                            this.notify_callbacks();
+                        },
+                    );
+
+                    player.borrow_mut()._seeked_subscription = Some(subscription);
+
                     debug!("Added MPRIS player: {} ({})", player_name, bus_name_owned);
+                    this.probe_rating_backend(&player);
                     this.players.borrow_mut().insert(bus_name_owned, player);
 
                     // Update active player selection
@@ -602,6 +894,52 @@ impl MediaService {
         );
     }
 
+    /// Check whether the player's rating interface is reachable.
+    fn probe_rating_backend(self: &Rc<Self>, player: &Rc<RefCell<MprisPlayer>>) {
+        let Some(backend) = RatingBackend::for_player(&player.borrow().player_id) else {
+            return;
+        };
+        let Some(connection) = self.connection.borrow().clone() else {
+            return;
+        };
+
+        let this_weak = Rc::downgrade(self);
+        let player_weak = Rc::downgrade(player);
+        dbus_watchdog::call(
+            DBUS_DAEMON_SERVICE,
+            &connection,
+            DBUS_NAME,
+            DBUS_PATH,
+            DBUS_INTERFACE,
+            "NameHasOwner",
+            Some(&(backend.bus_name(),).to_variant()),
+            Some(glib::VariantTy::new("(b)").unwrap()),
+            CallPolicy::QUERY,
+            move |res| {
+                let has_owner = match res {
+                    Ok(reply) => reply.child_value(0).get::<bool>().unwrap_or(false),
+                    Err(e) => {
+                        debug!("Rating probe for {:?} failed: {}", backend, e);
+                        false
+                    }
+                };
+                let (Some(this), Some(player)) = (this_weak.upgrade(), player_weak.upgrade())
+                else {
+                    return;
+                };
+                if has_owner {
+                    debug!(
+                        "Ratings for {} via {:?}",
+                        player.borrow().player_name,
+                        backend
+                    );
+                    player.borrow_mut().rating_backend = Some(backend);
+                    this.notify_callbacks();
+                }
+            },
+        );
+    }
+
     /// Remove a player that disappeared.
     fn remove_player(self: &Rc<Self>, bus_name: &str) {
         let removed = self.players.borrow_mut().remove(bus_name);
@@ -632,6 +970,7 @@ impl MediaService {
             can_go_previous,
             can_seek,
             can_control,
+            rate,
         ) = {
             let p = player.borrow();
             let proxy = &p.proxy;
@@ -671,6 +1010,11 @@ impl MediaService {
                 .cached_property("CanControl")
                 .and_then(|v| v.get::<bool>())
                 .unwrap_or(true);
+            let rate = proxy
+                .cached_property("Rate")
+                .and_then(|v| v.get::<f64>())
+                .filter(|r| *r > 0.0)
+                .unwrap_or(1.0);
 
             (
                 playback_status,
@@ -681,6 +1025,7 @@ impl MediaService {
                 can_go_previous,
                 can_seek,
                 can_control,
+                rate,
             )
         };
 
@@ -689,7 +1034,14 @@ impl MediaService {
         let old_track_id = p.metadata.track_id.clone();
         let old_title = p.metadata.title.clone();
 
+        // Settle the position before the speed it advances at changes
+        if p.playback_status != playback_status || p.rate != rate {
+            let position = p.current_position();
+            p.set_position(position);
+        }
+
         p.playback_status = playback_status;
+        p.rate = rate;
         p.metadata = metadata;
         p.can_play = can_play;
         p.can_pause = can_pause;
@@ -704,7 +1056,7 @@ impl MediaService {
             old_title.is_some() && p.metadata.title.is_some() && old_title != p.metadata.title;
 
         if track_id_changed || title_changed {
-            p.position = 0;
+            p.set_position(0);
             p.track_generation += 1;
             true
         } else {
@@ -739,29 +1091,26 @@ impl MediaService {
         self.select_best_player_auto(&players, &old_active);
     }
 
-    /// Auto-select the best player (last playing > other playing > current paused > other paused > any).
+    /// Auto-select the best player (playing > current paused > other paused > any).
+    ///
+    /// Among playing players the highest in `player_priority` wins, then the
+    /// one that started playing last.
     fn select_best_player_auto(
         self: &Rc<Self>,
         players: &HashMap<String, Rc<RefCell<MprisPlayer>>>,
         old_active: &Option<String>,
     ) {
-        // First, check if last_playing is still playing - prefer it
-        if let Some(ref last) = *self.last_playing.borrow()
-            && let Some(player) = players.get(last)
-            && player.borrow().playback_status == PlaybackStatus::Playing
-        {
-            if old_active.as_ref() != Some(last) {
-                debug!("Active player (auto, last playing): {}", last);
-                self.active_player.replace(Some(last.clone()));
-                self.on_active_player_changed();
-            }
-            return;
-        }
-
-        // Otherwise prefer any playing player
+        let last_playing = self.last_playing.borrow().clone();
         let playing = players
             .values()
-            .find(|p| p.borrow().playback_status == PlaybackStatus::Playing)
+            .filter(|p| p.borrow().playback_status == PlaybackStatus::Playing)
+            .min_by_key(|p| {
+                let p = p.borrow();
+                (
+                    self.player_rank(&p),
+                    last_playing.as_ref() != Some(&p.bus_name),
+                )
+            })
             .map(|p| p.borrow().bus_name.clone());
 
         if let Some(bus_name) = playing {
@@ -799,13 +1148,14 @@ impl MediaService {
             }
         }
 
-        // Find any paused player with metadata
+        // Find the preferred paused player with metadata
         let paused_with_meta = players
             .values()
-            .find(|p| {
+            .filter(|p| {
                 let p = p.borrow();
                 p.playback_status == PlaybackStatus::Paused && p.metadata.title.is_some()
             })
+            .min_by_key(|p| self.player_rank(&p.borrow()))
             .map(|p| p.borrow().bus_name.clone());
 
         if let Some(bus_name) = paused_with_meta {
@@ -824,8 +1174,11 @@ impl MediaService {
             return;
         }
 
-        // Pick any available player
-        let any = players.keys().next().cloned();
+        // Pick the preferred available player
+        let any = players
+            .values()
+            .min_by_key(|p| self.player_rank(&p.borrow()))
+            .map(|p| p.borrow().bus_name.clone());
         if any != *old_active {
             if let Some(ref bus_name) = any {
                 debug!("Active player (auto, fallback): {}", bus_name);
@@ -880,12 +1233,18 @@ impl MediaService {
                 player_id: Some(p.player_id.clone()),
                 playback_status: p.playback_status,
                 metadata: p.metadata.clone(),
-                position: p.position,
+                position: p.current_position(),
                 can_play: p.can_play,
                 can_pause: p.can_pause,
                 can_go_next: p.can_go_next,
                 can_go_previous: p.can_go_previous,
                 can_seek: p.can_seek,
+                can_rate: p.rating_backend.is_some() && p.metadata.url.is_some(),
+                long_form: self.long_form.borrow().applies(
+                    &p.player_id,
+                    &p.player_name,
+                    &p.metadata,
+                ),
             },
             None => MediaSnapshot {
                 available: !players.is_empty(),
@@ -930,12 +1289,24 @@ impl MediaService {
                 meta.url = url.get::<String>();
             }
 
+            if let Some(genre) = dict.get("xesam:genre") {
+                if let Some(genres) = genre.get::<Vec<String>>() {
+                    meta.genre = Some(genres.join(", ")).filter(|g| !g.is_empty());
+                } else if let Some(genre_str) = genre.get::<String>() {
+                    meta.genre = Some(genre_str);
+                }
+            }
+
             if let Some(length) = dict.get("mpris:length") {
                 meta.length = length
                     .get::<i64>()
                     .or_else(|| length.get::<u64>().map(|v| v as i64));
             }
 
+            if let Some(rating) = dict.get("xesam:userRating") {
+                meta.user_rating = rating.get::<f64>().map(|r| r.clamp(0.0, 1.0));
+            }
+
             if let Some(track_id) = dict.get("mpris:trackid") {
                 if let Some(id) = track_id.get::<String>() {
                     meta.track_id = Some(id);
@@ -1009,16 +1380,25 @@ impl MediaService {
 
         let cancellable = self.poll_cancellable.borrow().clone();
 
-        connection.call(
-            Some(&bus_name),
-            MPRIS_PATH,
-            PROPERTIES_INTERFACE,
-            "Get",
-            Some(&(MPRIS_PLAYER_INTERFACE, "Position").to_variant()),
-            Some(glib::VariantTy::new("(v)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            DBUS_POLL_TIMEOUT_MS,
-            Some(&cancellable),
+        // Goes through the watchdog directly so the poll stays cancellable
+        let poll_bus = bus_name.clone();
+        dbus_watchdog::run(
+            MPRIS_SERVICE,
+            CallPolicy::QUICK,
+            move |timeout_ms, reply| {
+                connection.call(
+                    Some(&poll_bus),
+                    MPRIS_PATH,
+                    PROPERTIES_INTERFACE,
+                    "Get",
+                    Some(&(MPRIS_PLAYER_INTERFACE, "Position").to_variant()),
+                    Some(glib::VariantTy::new("(v)").unwrap()),
+                    gio::DBusCallFlags::NONE,
+                    timeout_ms,
+                    Some(&cancellable),
+                    reply,
+                );
+            },
             clone!(
                 #[strong(rename_to = this)]
                 self,
@@ -1046,9 +1426,11 @@ impl MediaService {
                             if let Some(inner) = reply.child_value(0).get::<Variant>()
                                 && let Some(position) = inner.get::<i64>()
                             {
-                                let changed = player.borrow().position != position;
-                                if changed {
-                                    player.borrow_mut().position = position;
+                                // Views advance the position themselves, so
+                                // only a correction is worth notifying about
+                                let drift = (player.borrow().current_position() - position).abs();
+                                if drift > POSITION_DRIFT_US {
+                                    player.borrow_mut().set_position(position);
                                     drop(players);
                                     drop(active);
                                     this.notify_callbacks();
@@ -1080,18 +1462,83 @@ impl MediaService {
         self.call_player_method("Previous");
     }
 
+    /// Go to the previous track, or rewind 30 seconds for long-form audio.
+    pub fn skip_backward(&self) {
+        if self.build_snapshot().long_form {
+            self.seek(-SKIP_SEEK_US);
+        } else {
+            self.previous();
+        }
+    }
+
+    /// Go to the next track, or skip ahead 30 seconds for long-form audio.
+    pub fn skip_forward(&self) {
+        if self.build_snapshot().long_form {
+            self.seek(SKIP_SEEK_US);
+        } else {
+            self.next();
+        }
+    }
+
+    /// Seek relative to the current position (in microseconds).
+    pub fn seek(&self, offset_us: i64) {
+        let Some((connection, bus_name)) = self.get_active_connection() else {
+            return;
+        };
+
+        // Optimistic update, corrected by Seeked or the next position poll
+        if let Some(player) = self.players.borrow().get(&bus_name) {
+            let mut p = player.borrow_mut();
+            let end = p.metadata.length.unwrap_or(i64::MAX);
+            let position = (p.current_position() + offset_us).clamp(0, end);
+            p.set_position(position);
+        }
+        self.notify_callbacks();
+
+        dbus_watchdog::call(
+            MPRIS_SERVICE,
+            &connection,
+            &bus_name,
+            MPRIS_PATH,
+            MPRIS_PLAYER_INTERFACE,
+            "Seek",
+            Some(&(offset_us,).to_variant()),
+            None,
+            CallPolicy::ACTION,
+            |res| {
+                if let Err(e) = res {
+                    warn!("MPRIS Seek failed: {}", e);
+                }
+            },
+        );
+    }
+
+    /// Position of the active player while it's playing, advanced locally
+    /// since the player last reported it.
+    pub fn interpolated_position(&self) -> Option<i64> {
+        let players = self.players.borrow();
+        let active = self.active_player.borrow();
+        let player = active.as_ref().and_then(|bus| players.get(bus))?.borrow();
+        (player.playback_status == PlaybackStatus::Playing).then(|| player.current_position())
+    }
+
     /// Set absolute position (in microseconds).
+    ///
+    /// Players without a track id can't take SetPosition, so they get a
+    /// relative Seek from the current position instead.
     pub fn set_position(&self, position_us: i64) {
-        let track_id = {
+        let (track_id, current) = {
             let players = self.players.borrow();
             let active = self.active_player.borrow();
-            active
-                .as_ref()
-                .and_then(|bus| players.get(bus))
-                .and_then(|p| p.borrow().metadata.track_id.clone())
+            let Some(player) = active.as_ref().and_then(|bus| players.get(bus)) else {
+                return;
+            };
+            let p = player.borrow();
+            (p.metadata.track_id.clone(), p.current_position())
         };
 
-        let Some(track_id) = track_id else {
+        let Some(track_id) = track_id.filter(|id| id != MPRIS_NO_TRACK) else {
+            self.seek(position_us - current);
             return;
         };
 
@@ -1112,21 +1559,21 @@ impl MediaService {
             let players = self.players.borrow();
             let active = self.active_player.borrow();
             if let Some(player) = active.as_ref().and_then(|bus| players.get(bus)) {
-                player.borrow_mut().position = position_us;
+                player.borrow_mut().set_position(position_us);
             }
         }
         self.notify_callbacks();
 
-        connection.call(
-            Some(&bus_name),
+        dbus_watchdog::call(
+            MPRIS_SERVICE,
+            &connection,
+            &bus_name,
             MPRIS_PATH,
             MPRIS_PLAYER_INTERFACE,
             "SetPosition",
             Some(&(track_path, position_us).to_variant()),
-            None::<&glib::VariantTy>,
-            gio::DBusCallFlags::NONE,
-            DBUS_CALL_TIMEOUT_MS,
-            None::<&gio::Cancellable>,
+            None,
+            CallPolicy::ACTION,
             |res| {
                 if let Err(e) = res {
                     warn!("MPRIS SetPosition failed: {}", e);
@@ -1135,22 +1582,54 @@ impl MediaService {
         );
     }
 
+    /// Set the current track's rating (0.0-1.0, as in `xesam:userRating`).
+    pub fn set_rating(&self, rating: f64) {
+        let Some((connection, bus_name)) = self.get_active_connection() else {
+            return;
+        };
+        let Some(player) = self.players.borrow().get(&bus_name).cloned() else {
+            return;
+        };
+
+        let (backend, url) = {
+            let p = player.borrow();
+            (p.rating_backend, p.metadata.url.clone())
+        };
+        let (Some(backend), Some(url)) = (backend, url) else {
+            debug!("Player {} can't rate the current track", bus_name);
+            return;
+        };
+
+        // Optimistic update; the player confirms through Metadata
+        let rating = rating.clamp(0.0, 1.0);
+        player.borrow_mut().metadata.user_rating = Some(rating);
+        self.notify_callbacks();
+
+        backend.set_rating(&connection, &url, rating);
+    }
+
+    /// Love the current track, or clear its rating if it's already loved.
+    pub fn toggle_loved(&self) {
+        let loved = is_loved(self.build_snapshot().metadata.user_rating);
+        self.set_rating(if loved { 0.0 } else { 1.0 });
+    }
+
     fn call_player_method(&self, method: &str) {
         let Some((connection, bus_name)) = self.get_active_connection() else {
             return;
         };
 
         let method_owned = method.to_string();
-        connection.call(
-            Some(&bus_name),
+        dbus_watchdog::call(
+            MPRIS_SERVICE,
+            &connection,
+            &bus_name,
             MPRIS_PATH,
             MPRIS_PLAYER_INTERFACE,
             method,
             None,
-            None::<&glib::VariantTy>,
-            gio::DBusCallFlags::NONE,
-            DBUS_CALL_TIMEOUT_MS,
-            None::<&gio::Cancellable>,
+            None,
+            CallPolicy::ACTION,
             move |res| {
                 if let Err(e) = res {
                     warn!("MPRIS {} failed: {}", method_owned, e);
@@ -1235,16 +1714,16 @@ impl MediaCli {
 
     fn discover_players(&mut self) {
         // Call ListNames to find MPRIS players
-        let result = self.connection.call_sync(
-            Some(DBUS_NAME),
+        let result = dbus_watchdog::call_sync(
+            DBUS_DAEMON_SERVICE,
+            &self.connection,
+            DBUS_NAME,
             DBUS_PATH,
             DBUS_INTERFACE,
             "ListNames",
             None,
             Some(glib::VariantTy::new("(as)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            DBUS_CALL_TIMEOUT_MS,
-            None::<&gio::Cancellable>,
+            CallPolicy::QUERY,
         );
 
         let Ok(reply) = result else {
@@ -1295,20 +1774,18 @@ impl MediaCli {
     }
 
     fn get_playback_status(&self, bus_name: &str) -> Option<PlaybackStatus> {
-        let result = self
-            .connection
-            .call_sync(
-                Some(bus_name),
-                MPRIS_PATH,
-                PROPERTIES_INTERFACE,
-                "Get",
-                Some(&(MPRIS_PLAYER_INTERFACE, "PlaybackStatus").to_variant()),
-                Some(glib::VariantTy::new("(v)").unwrap()),
-                gio::DBusCallFlags::NONE,
-                DBUS_CALL_TIMEOUT_MS,
-                None::<&gio::Cancellable>,
-            )
-            .ok()?;
+        let result = dbus_watchdog::call_sync(
+            MPRIS_SERVICE,
+            &self.connection,
+            bus_name,
+            MPRIS_PATH,
+            PROPERTIES_INTERFACE,
+            "Get",
+            Some(&(MPRIS_PLAYER_INTERFACE, "PlaybackStatus").to_variant()),
+            Some(glib::VariantTy::new("(v)").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .ok()?;
 
         result
             .child_value(0)
@@ -1345,20 +1822,18 @@ impl MediaCli {
             .ok_or_else(|| "no media player found".to_string())?;
 
         // Get all properties at once
-        let result = self
-            .connection
-            .call_sync(
-                Some(bus_name),
-                MPRIS_PATH,
-                PROPERTIES_INTERFACE,
-                "GetAll",
-                Some(&(MPRIS_PLAYER_INTERFACE,).to_variant()),
-                Some(glib::VariantTy::new("(a{sv})").unwrap()),
-                gio::DBusCallFlags::NONE,
-                DBUS_CALL_TIMEOUT_MS,
-                None::<&gio::Cancellable>,
-            )
-            .map_err(|e| format!("failed to get player properties: {}", e))?;
+        let result = dbus_watchdog::call_sync(
+            MPRIS_SERVICE,
+            &self.connection,
+            bus_name,
+            MPRIS_PATH,
+            PROPERTIES_INTERFACE,
+            "GetAll",
+            Some(&(MPRIS_PLAYER_INTERFACE,).to_variant()),
+            Some(glib::VariantTy::new("(a{sv})").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .map_err(|e| format!("failed to get player properties: {}", e))?;
 
         // Parse properties dict
         let props_variant = result.child_value(0);
@@ -1405,19 +1880,18 @@ impl MediaCli {
             .as_ref()
             .ok_or_else(|| "no media player found".to_string())?;
 
-        self.connection
-            .call_sync(
-                Some(bus_name),
-                MPRIS_PATH,
-                MPRIS_PLAYER_INTERFACE,
-                method,
-                None,
-                None,
-                gio::DBusCallFlags::NONE,
-                DBUS_CALL_TIMEOUT_MS,
-                None::<&gio::Cancellable>,
-            )
-            .map_err(|e| format!("MPRIS {} failed: {}", method, e))?;
+        dbus_watchdog::call_sync(
+            MPRIS_SERVICE,
+            &self.connection,
+            bus_name,
+            MPRIS_PATH,
+            MPRIS_PLAYER_INTERFACE,
+            method,
+            None,
+            None,
+            CallPolicy::ACTION,
+        )
+        .map_err(|e| format!("MPRIS {} failed: {}", method, e))?;
 
         Ok(())
     }
@@ -1470,6 +1944,30 @@ impl std::fmt::Display for MediaCliStatus {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_interpolate_position() {
+        let second = Duration::from_secs(1);
+        assert_eq!(
+            interpolate_position(10_000_000, second, 1.0, None),
+            11_000_000
+        );
+        assert_eq!(
+            interpolate_position(10_000_000, second, 2.0, Some(60_000_000)),
+            12_000_000
+        );
+        // Clamped to the track length
+        assert_eq!(
+            interpolate_position(59_500_000, second, 1.0, Some(60_000_000)),
+            60_000_000
+        );
+        // Unknown length (0) doesn't clamp
+        assert_eq!(
+            interpolate_position(5_000_000, second, 1.0, Some(0)),
+            6_000_000
+        );
+        assert_eq!(interpolate_position(0, Duration::ZERO, 1.0, None), 0);
+    }
+
     #[test]
     fn test_playback_status_from_str() {
         assert_eq!("Playing".parse(), Ok(PlaybackStatus::Playing));
@@ -1487,6 +1985,66 @@ mod tests {
         assert_eq!(format_duration(-1000), "0:00");
     }
 
+    #[test]
+    fn test_parse_metadata_user_rating() {
+        let dict = HashMap::from([
+            ("xesam:title".to_string(), "Song".to_variant()),
+            ("xesam:userRating".to_string(), 1.0f64.to_variant()),
+        ]);
+        let meta = MediaService::parse_metadata(&dict.to_variant());
+        assert_eq!(meta.title.as_deref(), Some("Song"));
+        assert!(is_loved(meta.user_rating));
+
+        let dict = HashMap::from([("xesam:userRating".to_string(), 0.6f64.to_variant())]);
+        let meta = MediaService::parse_metadata(&dict.to_variant());
+        assert!(!is_loved(meta.user_rating));
+        assert!(!is_loved(None));
+    }
+
+    #[test]
+    fn test_rating_backend_for_player() {
+        assert_eq!(
+            RatingBackend::for_player("rhythmbox"),
+            Some(RatingBackend::Rhythmbox)
+        );
+        assert_eq!(RatingBackend::for_player("spotify"), None);
+    }
+
+    #[test]
+    fn test_long_form_applies() {
+        let config = LongFormConfig {
+            players: vec![
+                ("Spotify".to_string(), SkipMode::Track),
+                ("gpodder".to_string(), SkipMode::Seek),
+            ],
+            ..Default::default()
+        };
+
+        let mut meta = MediaMetadata {
+            length: Some(4 * 60 * 1_000_000),
+            ..Default::default()
+        };
+        assert!(!config.applies("mpv", "mpv", &meta));
+        assert!(config.applies("gpodder", "gPodder", &meta));
+
+        meta.genre = Some("Podcast, Technology".to_string());
+        assert!(config.applies("mpv", "mpv", &meta));
+        assert!(!config.applies("spotify", "Spotify", &meta));
+
+        meta.genre = None;
+        meta.length = Some(90 * 60 * 1_000_000);
+        assert!(config.applies("mpv", "mpv", &meta));
+    }
+
+    #[test]
+    fn test_priority_rank() {
+        let priority = vec!["Spotify".to_string(), "firefox".to_string()];
+        assert_eq!(priority_rank(&priority, "spotify", "Spotify"), 0);
+        assert_eq!(priority_rank(&priority, "firefox", "Mozilla Firefox"), 1);
+        assert_eq!(priority_rank(&priority, "vlc", "VLC media player"), 2);
+        assert_eq!(priority_rank(&[], "spotify", "Spotify"), 0);
+    }
+
     #[test]
     fn test_media_snapshot_default() {
         let snapshot = MediaSnapshot::default();
@@ -0,0 +1,594 @@
+//! MockServices - scripted fake hardware for widget and theme development.
+//!
+//! Enabled with `vibepanel --mock-services <scenario.toml>`. While enabled,
+//! the battery, network, Bluetooth and audio services skip D-Bus and
+//! PulseAudio entirely and publish snapshots from the scenario file instead,
+//! so their widgets work on machines without the hardware or daemons.
+//!
+//! A scenario is a list of steps shown one after another, looping back to
+//! the first step after the last:
+//!
+//! ```toml
+//! # Seconds each step is shown, unless the step sets `duration`
+//! interval = 5
+//!
+//! [[step]]
+//! battery = { percent = 80, state = "discharging", time_to_empty = 240 }
+//! network = { ssid = "Home", strength = 70 }
+//! bluetooth = { devices = [{ name = "Headphones", connected = true }] }
+//! audio = { volume = 40, sinks = ["Speakers", "Headphones"] }
+//!
+//! [[step]]
+//! duration = 2
+//! battery = { percent = 9 }
+//! network = { wifi_enabled = false, wired = true }
+//! ```
+//!
+//! A service left out of a step keeps its state from the previous step.
+//! Controls (volume, Wi-Fi toggle, connecting devices) do nothing while
+//! mocked.
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::glib;
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use super::audio::{AudioService, AudioSnapshot, SinkInfoSnapshot, SourceInfoSnapshot};
+use super::battery::{
+    BatteryService, BatterySnapshot, STATE_CHARGING, STATE_DISCHARGING, STATE_FULLY_CHARGED,
+};
+use super::bluetooth::{BluetoothDevice, BluetoothService, BluetoothSnapshot};
+use super::network::{NetworkService, NetworkSnapshot, WifiNetwork};
+
+/// Default seconds per step.
+const DEFAULT_INTERVAL_SECS: u32 = 5;
+
+/// A scripted sequence of service states.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Seconds each step is shown, unless the step sets its own duration.
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    /// Steps in order; playback loops.
+    #[serde(default, rename = "step")]
+    pub steps: Vec<Step>,
+}
+
+fn default_interval() -> u32 {
+    DEFAULT_INTERVAL_SECS
+}
+
+impl Scenario {
+    /// Read and parse a scenario file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| format!("invalid scenario {}: {}", path.display(), e))
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let scenario: Scenario = toml::from_str(content).map_err(|e| e.to_string())?;
+        if scenario.steps.is_empty() {
+            return Err("scenario has no [[step]] entries".to_string());
+        }
+        if scenario.interval == 0 || scenario.steps.iter().any(|step| step.duration == Some(0)) {
+            return Err("step durations must be at least 1 second".to_string());
+        }
+        Ok(scenario)
+    }
+
+    /// How long `step` is shown.
+    fn duration(&self, step: &Step) -> u32 {
+        step.duration.unwrap_or(self.interval)
+    }
+}
+
+/// One step of a scenario. Services left out keep their previous state.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// Seconds this step is shown (defaults to the scenario interval).
+    pub duration: Option<u32>,
+    pub battery: Option<MockBattery>,
+    pub network: Option<MockNetwork>,
+    pub bluetooth: Option<MockBluetooth>,
+    pub audio: Option<MockAudio>,
+}
+
+/// Battery charge state, as named in scenario files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MockBatteryState {
+    Charging,
+    #[default]
+    Discharging,
+    Empty,
+    FullyCharged,
+    PendingCharge,
+    PendingDischarge,
+}
+
+impl MockBatteryState {
+    /// The UPower state code.
+    fn code(self) -> u32 {
+        match self {
+            Self::Charging => STATE_CHARGING,
+            Self::Discharging => STATE_DISCHARGING,
+            Self::Empty => 3,
+            Self::FullyCharged => STATE_FULLY_CHARGED,
+            Self::PendingCharge => 5,
+            Self::PendingDischarge => 6,
+        }
+    }
+}
+
+/// Mocked battery state.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MockBattery {
+    pub percent: f64,
+    pub state: MockBatteryState,
+    /// Power draw in Watts.
+    pub energy_rate: Option<f64>,
+    /// Minutes until empty.
+    pub time_to_empty: Option<i64>,
+    /// Minutes until full.
+    pub time_to_full: Option<i64>,
+}
+
+impl Default for MockBattery {
+    fn default() -> Self {
+        Self {
+            percent: 100.0,
+            state: MockBatteryState::default(),
+            energy_rate: None,
+            time_to_empty: None,
+            time_to_full: None,
+        }
+    }
+}
+
+impl MockBattery {
+    fn snapshot(&self) -> BatterySnapshot {
+        BatterySnapshot {
+            available: true,
+            present: true,
+            percent: Some(self.percent.clamp(0.0, 100.0)),
+            state: Some(self.state.code()),
+            energy_rate: self.energy_rate,
+            time_to_empty: self.time_to_empty.map(|minutes| minutes * 60),
+            time_to_full: self.time_to_full.map(|minutes| minutes * 60),
+            devices: Vec::new(),
+        }
+    }
+}
+
+/// Mocked network state.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MockNetwork {
+    pub wifi_enabled: bool,
+    /// Connected Wi-Fi network, if any.
+    pub ssid: Option<String>,
+    /// Signal strength of the connected network (0-100).
+    pub strength: i32,
+    /// Whether an Ethernet connection is the primary link.
+    pub wired: bool,
+    /// Other networks in the scan results.
+    pub networks: Vec<MockWifiNetwork>,
+}
+
+impl Default for MockNetwork {
+    fn default() -> Self {
+        Self {
+            wifi_enabled: true,
+            ssid: None,
+            strength: 75,
+            wired: false,
+            networks: Vec::new(),
+        }
+    }
+}
+
+/// A Wi-Fi network in the mocked scan results.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockWifiNetwork {
+    pub ssid: String,
+    #[serde(default = "default_strength")]
+    pub strength: i32,
+    #[serde(default = "default_true")]
+    pub secured: bool,
+    #[serde(default)]
+    pub known: bool,
+}
+
+fn default_strength() -> i32 {
+    50
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl MockNetwork {
+    fn snapshot(&self) -> NetworkSnapshot {
+        let connected = self.wifi_enabled && self.ssid.is_some();
+        let ssid = self.ssid.clone().filter(|_| connected);
+
+        let mut networks: Vec<WifiNetwork> = Vec::new();
+        if self.wifi_enabled {
+            if let Some(ref ssid) = ssid {
+                networks.push(WifiNetwork {
+                    ssid: ssid.clone(),
+                    strength: self.strength,
+                    security: "secured".to_string(),
+                    active: true,
+                    known: true,
+                });
+            }
+            networks.extend(
+                self.networks
+                    .iter()
+                    .filter(|network| Some(&network.ssid) != ssid.as_ref())
+                    .map(|network| WifiNetwork {
+                        ssid: network.ssid.clone(),
+                        strength: network.strength,
+                        security: if network.secured { "secured" } else { "open" }.to_string(),
+                        active: false,
+                        known: network.known,
+                    }),
+            );
+        }
+
+        let primary_connection_type = if self.wired {
+            Some("802-3-ethernet".to_string())
+        } else if connected {
+            Some("802-11-wireless".to_string())
+        } else {
+            None
+        };
+
+        NetworkSnapshot {
+            available: true,
+            wifi_enabled: Some(self.wifi_enabled),
+            connected,
+            wired_connected: self.wired,
+            has_wifi_device: true,
+            has_ethernet_device: self.wired,
+            primary_connection_type,
+            wired_iface: self.wired.then(|| "eth0".to_string()),
+            wired_name: self.wired.then(|| "Wired connection 1".to_string()),
+            wired_speed: self.wired.then_some(1000),
+            ssid,
+            strength: if connected { self.strength } else { 0 },
+            is_ready: true,
+            networks,
+            ..NetworkSnapshot::unknown()
+        }
+    }
+}
+
+/// Mocked Bluetooth state.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MockBluetooth {
+    pub powered: bool,
+    pub devices: Vec<MockBluetoothDevice>,
+}
+
+impl Default for MockBluetooth {
+    fn default() -> Self {
+        Self {
+            powered: true,
+            devices: Vec::new(),
+        }
+    }
+}
+
+/// A device known to the mocked Bluetooth adapter.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockBluetoothDevice {
+    pub name: String,
+    #[serde(default)]
+    pub connected: bool,
+    #[serde(default = "default_true")]
+    pub paired: bool,
+    /// BlueZ icon name (e.g. "audio-headset", "input-mouse").
+    pub icon: Option<String>,
+}
+
+impl MockBluetooth {
+    fn snapshot(&self) -> BluetoothSnapshot {
+        let devices: Vec<BluetoothDevice> = self
+            .devices
+            .iter()
+            .enumerate()
+            .map(|(i, device)| {
+                let address = format!("00:00:00:00:00:{:02X}", i + 1);
+                BluetoothDevice {
+                    path: format!("/org/bluez/hci0/dev_{}", address.replace(':', "_")),
+                    name: device.name.clone(),
+                    address,
+                    connected: self.powered && device.connected,
+                    paired: device.paired,
+                    trusted: device.paired,
+                    icon: device.icon.clone(),
+                }
+            })
+            .collect();
+
+        BluetoothSnapshot {
+            has_adapter: true,
+            powered: self.powered,
+            connected_devices: devices.iter().filter(|device| device.connected).count(),
+            devices,
+            is_ready: true,
+            ..BluetoothSnapshot::empty()
+        }
+    }
+}
+
+/// Mocked audio state.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MockAudio {
+    pub volume: u32,
+    pub muted: bool,
+    /// Output devices by description; the first one is the default.
+    pub sinks: Vec<String>,
+    /// Microphone volume; no microphone when unset.
+    pub mic_volume: Option<u32>,
+    pub mic_muted: bool,
+}
+
+impl Default for MockAudio {
+    fn default() -> Self {
+        Self {
+            volume: 50,
+            muted: false,
+            sinks: vec!["Speakers".to_string()],
+            mic_volume: None,
+            mic_muted: false,
+        }
+    }
+}
+
+impl MockAudio {
+    fn snapshot(&self) -> AudioSnapshot {
+        let sinks: Vec<SinkInfoSnapshot> = self
+            .sinks
+            .iter()
+            .enumerate()
+            .map(|(i, description)| SinkInfoSnapshot {
+                name: format!("mock.sink.{}", i),
+                description: description.clone(),
+                is_default: i == 0,
+                port_available: None,
+                active_port: None,
+                jack_plugged: None,
+            })
+            .collect();
+        let sources: Vec<SourceInfoSnapshot> = self
+            .mic_volume
+            .map(|_| SourceInfoSnapshot {
+                name: "mock.source.0".to_string(),
+                description: "Microphone".to_string(),
+                is_default: true,
+                port_available: None,
+            })
+            .into_iter()
+            .collect();
+
+        AudioSnapshot {
+            volume: self.volume.min(150),
+            muted: self.muted,
+            mic_muted: self.mic_volume.map(|_| self.mic_muted),
+            mic_volume: self.mic_volume.map(|volume| volume.min(150)),
+            default_sink_name: sinks.first().map(|sink| sink.name.clone()),
+            default_source_name: sources.first().map(|source| source.name.clone()),
+            sinks,
+            sources,
+            available: true,
+            ..AudioSnapshot::default()
+        }
+    }
+}
+
+/// Shared, process-wide mock mode switch and scenario player.
+pub struct MockServices {
+    scenario: RefCell<Option<Scenario>>,
+}
+
+impl MockServices {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            scenario: RefCell::new(None),
+        })
+    }
+
+    /// Get the global MockServices singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<MockServices> = MockServices::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Turn on mock mode with `scenario`. Must be called before any mocked
+    /// service is first used.
+    pub fn enable(&self, scenario: Scenario) {
+        info!(
+            "Mock services enabled ({} scenario steps)",
+            scenario.steps.len()
+        );
+        *self.scenario.borrow_mut() = Some(scenario);
+    }
+
+    /// Whether services should publish scripted snapshots instead of
+    /// talking to the system.
+    pub fn is_enabled(&self) -> bool {
+        self.scenario.borrow().is_some()
+    }
+
+    /// Start playing the scenario (no-op unless enabled).
+    pub fn start(self: &Rc<Self>) {
+        if self.is_enabled() {
+            self.play(0);
+        }
+    }
+
+    /// Publish step `index` and schedule the next one.
+    fn play(self: &Rc<Self>, index: usize) {
+        let Some((step, duration, count)) = self.scenario.borrow().as_ref().map(|scenario| {
+            let step = scenario.steps[index].clone();
+            let duration = scenario.duration(&step);
+            (step, duration, scenario.steps.len())
+        }) else {
+            return;
+        };
+        debug!("MockServices: step {} of {}", index + 1, count);
+        publish_step(&step);
+
+        if count > 1 {
+            let this_weak = Rc::downgrade(self);
+            glib::timeout_add_local_once(Duration::from_secs(duration.into()), move || {
+                if let Some(this) = this_weak.upgrade() {
+                    this.play((index + 1) % count);
+                }
+            });
+        }
+    }
+}
+
+/// Push a step's snapshots to the mocked services.
+fn publish_step(step: &Step) {
+    if let Some(ref battery) = step.battery {
+        BatteryService::global().publish_mock(battery.snapshot());
+    }
+    if let Some(ref network) = step.network {
+        NetworkService::global().publish_mock(network.snapshot());
+    }
+    if let Some(ref bluetooth) = step.bluetooth {
+        BluetoothService::global().publish_mock(bluetooth.snapshot());
+    }
+    if let Some(ref audio) = step.audio {
+        AudioService::global().publish_mock(audio.snapshot());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scenario() {
+        let scenario = Scenario::parse(
+            r#"
+            interval = 3
+
+            [[step]]
+            battery = { percent = 80, state = "charging", time_to_full = 30 }
+            network = { ssid = "Home", networks = [{ ssid = "Cafe", secured = false }] }
+
+            [[step]]
+            duration = 1
+            audio = { volume = 40, muted = true }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.steps.len(), 2);
+        assert_eq!(scenario.duration(&scenario.steps[0]), 3);
+        assert_eq!(scenario.duration(&scenario.steps[1]), 1);
+        assert!(scenario.steps[0].bluetooth.is_none());
+
+        let battery = scenario.steps[0].battery.as_ref().unwrap().snapshot();
+        assert_eq!(battery.percent, Some(80.0));
+        assert_eq!(battery.state, Some(STATE_CHARGING));
+        assert_eq!(battery.time_to_full, Some(1800));
+    }
+
+    #[test]
+    fn test_parse_scenario_errors() {
+        assert!(Scenario::parse("interval = 5").is_err());
+        assert!(Scenario::parse("[[step]]\nduration = 0").is_err());
+        assert!(Scenario::parse("[[step]]\nbattery = { percentage = 5 }").is_err());
+        assert!(Scenario::parse("[[step]]\nbattery = { state = \"exploding\" }").is_err());
+    }
+
+    #[test]
+    fn test_network_snapshot() {
+        let wifi = MockNetwork {
+            ssid: Some("Home".to_string()),
+            networks: vec![MockWifiNetwork {
+                ssid: "Home".to_string(),
+                strength: 10,
+                secured: true,
+                known: true,
+            }],
+            ..MockNetwork::default()
+        }
+        .snapshot();
+        assert!(wifi.connected);
+        assert_eq!(wifi.ssid.as_deref(), Some("Home"));
+        assert_eq!(wifi.networks.len(), 1);
+        assert!(wifi.networks[0].active);
+
+        let off = MockNetwork {
+            wifi_enabled: false,
+            ssid: Some("Home".to_string()),
+            wired: true,
+            ..MockNetwork::default()
+        }
+        .snapshot();
+        assert!(!off.connected);
+        assert!(off.ssid.is_none());
+        assert!(off.networks.is_empty());
+        assert_eq!(
+            off.primary_connection_type.as_deref(),
+            Some("802-3-ethernet")
+        );
+    }
+
+    #[test]
+    fn test_bluetooth_and_audio_snapshots() {
+        let bluetooth = MockBluetooth {
+            powered: true,
+            devices: vec![
+                MockBluetoothDevice {
+                    name: "Headphones".to_string(),
+                    connected: true,
+                    paired: true,
+                    icon: None,
+                },
+                MockBluetoothDevice {
+                    name: "Mouse".to_string(),
+                    connected: false,
+                    paired: true,
+                    icon: None,
+                },
+            ],
+        }
+        .snapshot();
+        assert_eq!(bluetooth.connected_devices, 1);
+        assert_ne!(bluetooth.devices[0].path, bluetooth.devices[1].path);
+
+        let audio = MockAudio {
+            sinks: vec!["Speakers".to_string(), "HDMI".to_string()],
+            mic_volume: Some(60),
+            ..MockAudio::default()
+        }
+        .snapshot();
+        assert_eq!(audio.default_sink_name.as_deref(), Some("mock.sink.0"));
+        assert_eq!(audio.mic_muted, Some(false));
+        assert_eq!(audio.sources.len(), 1);
+    }
+}
@@ -0,0 +1,106 @@
+//! Messages between external "remote" modules and the bar.
+//!
+//! Modules talk to the bar over the control socket (see `control_ipc`).
+//! A module keeps its connection open, streams `module` commands for a
+//! `remote` widget slot, and receives click/scroll events back on the same
+//! connection. This allows out-of-process modules written in any language.
+//!
+//! Module → bar (`action` selects the message):
+//! - `{"command":"module","action":"update","id":"weather","label":"21°C","icon":"sunny","tooltip":"Sunny"}`
+//!   Updates the slot with the given id. Omitted fields keep their previous
+//!   value and an empty `tooltip` removes it. The first connection to update
+//!   an id owns it until it disconnects: it receives the id's events, and
+//!   updates from other connections are refused.
+//! - `{"command":"module","action":"hide","id":"weather"}` – hide the slot
+//!   without disconnecting (owner only).
+//!
+//! Like every control command, each one is answered with a response line
+//! (`{"ok":true}` or `{"ok":false,"error":"..."}`).
+//!
+//! Bar → module, between the responses (told apart by their `type`):
+//! - `{"type":"click","id":"weather","button":1}`
+//! - `{"type":"scroll","id":"weather","dx":0.0,"dy":-1.0}`
+//!
+//! When a connection closes, all slots it owned are cleared.
+
+use serde::{Deserialize, Serialize};
+
+/// Messages sent by external modules to the bar.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ModuleRequest {
+    /// Update the content of a remote widget slot.
+    Update {
+        id: String,
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default)]
+        icon: Option<String>,
+        #[serde(default)]
+        tooltip: Option<String>,
+    },
+    /// Hide a remote widget slot.
+    Hide { id: String },
+}
+
+/// Events sent by the bar back to the owning module.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModuleEvent {
+    /// A mouse button was released on the widget.
+    Click { id: String, button: u32 },
+    /// The widget was scrolled.
+    Scroll { id: String, dx: f64, dy: f64 },
+}
+
+impl ModuleEvent {
+    /// Serialize to wire format (including the trailing newline).
+    pub fn to_wire(&self) -> String {
+        let mut s = serde_json::to_string(self).unwrap_or_default();
+        s.push('\n');
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::control_ipc::ControlRequest;
+
+    #[test]
+    fn test_parse_update_partial_fields() {
+        let request = ControlRequest::from_wire(
+            r#"{"command":"module","action":"update","id":"weather","label":"21°C"}"#,
+        );
+        assert_eq!(
+            request,
+            Ok(ControlRequest::Module(ModuleRequest::Update {
+                id: "weather".to_string(),
+                label: Some("21°C".to_string()),
+                icon: None,
+                tooltip: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(ControlRequest::from_wire(r#"{"command":"module"}"#).is_err());
+        assert!(
+            ControlRequest::from_wire(r#"{"command":"module","action":"bogus","id":"x"}"#).is_err()
+        );
+        assert!(ControlRequest::from_wire(r#"{"command":"module","action":"hide"}"#).is_err());
+    }
+
+    #[test]
+    fn test_event_wire_format() {
+        let event = ModuleEvent::Click {
+            id: "weather".to_string(),
+            button: 3,
+        };
+        assert_eq!(
+            event.to_wire(),
+            "{\"type\":\"click\",\"id\":\"weather\",\"button\":3}\n"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! MuteKeysService - keeps hardware mute keys, the mic mute state and the
+//! micmute LED in agreement.
+//!
+//! - With `audio.mic_mute_key`, reads the mic-mute key, the camera access
+//!   keys and the camera lens cover switch from the `/dev/input` event
+//!   devices that report them (needs read access, usually the `input`
+//!   group). The mic-mute key toggles the default source's mute; camera
+//!   changes are published as `CameraAccessEvent`s for the OSD
+//! - Without it the key is left to the compositor (bind it to
+//!   `vibepanel ipc toggle-mic-mute` or `wpctl`), and only the resulting
+//!   mute state is followed
+//! - With `audio.mic_mute_led`, drives the first `*::micmute` LED from the
+//!   default source's mute state. The first write detaches the kernel's
+//!   `audio-micmute` trigger, which follows the ALSA capture switch and so
+//!   misses PipeWire's software mute
+//!
+//! The Quick Settings mic row and the mic OSD follow `AudioService`, so a
+//! key press, a click and an external `wpctl` all end up in one state.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::rc::Rc;
+
+use gtk4::glib;
+use tracing::{debug, info, warn};
+use vibepanel_core::config::AudioConfig;
+
+use super::audio::{AudioService, AudioSnapshot};
+use super::event_bus::EventBus;
+use super::leds::{LEDS_PATH, LedDevice, LedWriter, find_led_by_suffix};
+
+/// Input devices as seen in sysfs.
+const SYS_INPUT_PATH: &str = "/sys/class/input";
+/// Input device nodes.
+const DEV_INPUT_PATH: &str = "/dev/input";
+
+/// Event types and codes from `linux/input-event-codes.h`.
+const EV_KEY: u16 = 0x01;
+const EV_SW: u16 = 0x05;
+const KEY_MICMUTE: u16 = 248;
+const KEY_CAMERA_ACCESS_ENABLE: u16 = 0x24b;
+const KEY_CAMERA_ACCESS_DISABLE: u16 = 0x24c;
+const KEY_CAMERA_ACCESS_TOGGLE: u16 = 0x24d;
+const SW_CAMERA_LENS_COVER: u16 = 0x09;
+
+/// Keys that make an input device worth reading.
+const WATCHED_KEYS: &[u16] = &[
+    KEY_MICMUTE,
+    KEY_CAMERA_ACCESS_ENABLE,
+    KEY_CAMERA_ACCESS_DISABLE,
+    KEY_CAMERA_ACCESS_TOGGLE,
+];
+
+/// Camera access was switched with a hardware key or the lens cover.
+///
+/// Published on the `EventBus` (use `subscribe_no_replay`; this is a
+/// one-shot event, not state).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraAccessEvent {
+    Enabled,
+    Disabled,
+    /// A toggle key whose resulting state isn't reported.
+    Toggled,
+}
+
+/// An input event the service acts on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyEvent {
+    MicMute,
+    Camera(CameraAccessEvent),
+}
+
+/// Shared, process-wide mute key and LED driver.
+pub struct MuteKeysService {
+    /// Toggle the mic on the mic-mute key (`audio.mic_mute_key`).
+    handle_keys: Cell<bool>,
+    /// Drive the micmute LED (`audio.mic_mute_led`).
+    drive_led: Cell<bool>,
+    /// fd watchers of the open input devices by name; removing one closes
+    /// the device.
+    sources: RefCell<HashMap<String, glib::SourceId>>,
+    /// The micmute LED, if there is one.
+    led: RefCell<Option<LedDevice>>,
+    writer: RefCell<Option<Rc<LedWriter>>>,
+    /// Mute state last written to the LED.
+    led_muted: Cell<Option<bool>>,
+    /// Whether we follow `AudioService` yet.
+    connected: Cell<bool>,
+}
+
+impl MuteKeysService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            handle_keys: Cell::new(false),
+            drive_led: Cell::new(false),
+            sources: RefCell::new(HashMap::new()),
+            led: RefCell::new(None),
+            writer: RefCell::new(None),
+            led_muted: Cell::new(None),
+            connected: Cell::new(false),
+        })
+    }
+
+    /// Get the global MuteKeysService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<MuteKeysService> = MuteKeysService::new();
+        }
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Apply the `[audio]` mic mute settings.
+    pub fn configure(self: &Rc<Self>, config: &AudioConfig) {
+        if config.mic_mute_key != self.handle_keys.get() {
+            self.handle_keys.set(config.mic_mute_key);
+            if config.mic_mute_key {
+                self.open_devices();
+            } else {
+                for source in self.sources.take().into_values() {
+                    source.remove();
+                }
+            }
+        }
+
+        if config.mic_mute_led != self.drive_led.get() {
+            self.drive_led.set(config.mic_mute_led);
+            self.led_muted.set(None);
+            if config.mic_mute_led && self.writer.borrow().is_none() {
+                let led = find_led_by_suffix(Path::new(LEDS_PATH), "::micmute");
+                match &led {
+                    Some(led) => info!("MuteKeysService: using LED {}", led.name),
+                    None => debug!("MuteKeysService: no micmute LED in {}", LEDS_PATH),
+                }
+                if led.is_some() {
+                    *self.writer.borrow_mut() = Some(LedWriter::new("MuteKeysService"));
+                }
+                *self.led.borrow_mut() = led;
+            }
+            if config.mic_mute_led {
+                self.update_led(&AudioService::global().current());
+            }
+        }
+
+        if !self.connected.get() && self.drive_led.get() {
+            self.connected.set(true);
+            let this_weak = Rc::downgrade(self);
+            // Owned by the service, not by whatever configured it first
+            EventBus::global().unscoped(|| {
+                AudioService::global().connect(move |snapshot: &AudioSnapshot| {
+                    if let Some(this) = this_weak.upgrade() {
+                        this.update_led(snapshot);
+                    }
+                });
+            });
+        }
+    }
+
+    /// Light the LED while the mic is muted.
+    fn update_led(&self, snapshot: &AudioSnapshot) {
+        if !self.drive_led.get() {
+            return;
+        }
+        let Some(muted) = snapshot.mic_muted else {
+            return;
+        };
+        let Some(led) = self.led.borrow().clone() else {
+            return;
+        };
+        let Some(writer) = self.writer.borrow().clone() else {
+            return;
+        };
+        let previous = self.led_muted.replace(Some(muted));
+        if previous == Some(muted) {
+            return;
+        }
+
+        if previous.is_none()
+            && let Some(trigger) = active_trigger(&led)
+        {
+            // Writing 0 removes the trigger, so our writes stick
+            debug!(
+                "MuteKeysService: detaching {} trigger {}",
+                led.name, trigger
+            );
+            writer.write(led.clone(), 0);
+        }
+        debug!(
+            "MuteKeysService: {} {}",
+            if muted { "lighting" } else { "clearing" },
+            led.name
+        );
+        let value = if muted { led.max_brightness } else { 0 };
+        writer.write(led, value);
+    }
+
+    /// Open and watch every input device that reports a watched key.
+    fn open_devices(self: &Rc<Self>) {
+        let names = key_devices(Path::new(SYS_INPUT_PATH));
+        if names.is_empty() {
+            info!("MuteKeysService: no input device reports a mic-mute or camera key");
+            return;
+        }
+
+        let mut denied = Vec::new();
+        for name in names {
+            let path = Path::new(DEV_INPUT_PATH).join(&name);
+            let file = match OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_NONBLOCK | libc::O_CLOEXEC)
+                .open(&path)
+            {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    denied.push(path.display().to_string());
+                    continue;
+                }
+                Err(e) => {
+                    warn!("MuteKeysService: failed to open {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            debug!("MuteKeysService: reading keys from {}", path.display());
+            let source = self.watch_device(&name, file);
+            self.sources.borrow_mut().insert(name, source);
+        }
+
+        if !denied.is_empty() {
+            warn!(
+                "MuteKeysService: no read access to {} (add yourself to the input group, \
+                 or bind the key to `vibepanel ipc toggle-mic-mute`)",
+                denied.join(", ")
+            );
+        }
+    }
+
+    /// Read events from `file` on the main loop until it goes away.
+    fn watch_device(self: &Rc<Self>, name: &str, mut file: File) -> glib::SourceId {
+        let this_weak = Rc::downgrade(self);
+        let name = name.to_string();
+        let fd = file.as_raw_fd();
+        glib::unix_fd_add_local(
+            fd,
+            glib::IOCondition::IN | glib::IOCondition::HUP | glib::IOCondition::ERR,
+            move |_fd, _cond| {
+                let Some(this) = this_weak.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+                match read_events(&mut file) {
+                    Ok(events) => {
+                        for event in events {
+                            this.on_key_event(event);
+                        }
+                        glib::ControlFlow::Continue
+                    }
+                    Err(e) => {
+                        // The device was unplugged
+                        debug!("MuteKeysService: {} closed: {}", name, e);
+                        this.sources.borrow_mut().remove(&name);
+                        glib::ControlFlow::Break
+                    }
+                }
+            },
+        )
+    }
+
+    fn on_key_event(&self, event: KeyEvent) {
+        debug!("MuteKeysService: {:?}", event);
+        match event {
+            KeyEvent::MicMute => AudioService::global().toggle_mic_mute(),
+            KeyEvent::Camera(access) => EventBus::global().publish(&access),
+        }
+    }
+}
+
+/// Read all pending events from an evdev device.
+///
+/// Fails when the device is gone.
+fn read_events(file: &mut File) -> io::Result<Vec<KeyEvent>> {
+    let size = std::mem::size_of::<libc::input_event>();
+    let mut buf = vec![0u8; size * 64];
+    let mut events = Vec::new();
+    loop {
+        let n = match file.read(&mut buf) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(events),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for chunk in buf[..n].chunks_exact(size) {
+            // SAFETY: the kernel writes whole `input_event` structs, and
+            // read_unaligned doesn't need the buffer to be aligned
+            let raw: libc::input_event = unsafe { std::ptr::read_unaligned(chunk.as_ptr().cast()) };
+            events.extend(key_event(raw.type_, raw.code, raw.value));
+        }
+    }
+}
+
+/// The event an evdev `(type, code, value)` stands for, if any.
+///
+/// Keys count when pressed, not on release or auto-repeat.
+fn key_event(event_type: u16, code: u16, value: i32) -> Option<KeyEvent> {
+    match (event_type, code, value) {
+        (EV_KEY, KEY_MICMUTE, 1) => Some(KeyEvent::MicMute),
+        (EV_KEY, KEY_CAMERA_ACCESS_ENABLE, 1) => Some(KeyEvent::Camera(CameraAccessEvent::Enabled)),
+        (EV_KEY, KEY_CAMERA_ACCESS_DISABLE, 1) => {
+            Some(KeyEvent::Camera(CameraAccessEvent::Disabled))
+        }
+        (EV_KEY, KEY_CAMERA_ACCESS_TOGGLE, 1) => Some(KeyEvent::Camera(CameraAccessEvent::Toggled)),
+        (EV_SW, SW_CAMERA_LENS_COVER, covered) => Some(KeyEvent::Camera(if covered != 0 {
+            CameraAccessEvent::Disabled
+        } else {
+            CameraAccessEvent::Enabled
+        })),
+        _ => None,
+    }
+}
+
+/// Event device names (e.g. "event3") under `sys_root` that report a
+/// watched key or the camera lens cover switch.
+fn key_devices(sys_root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(sys_root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with("event"))
+        .filter(|name| {
+            let capabilities = sys_root.join(name).join("device/capabilities");
+            let read = |kind: &str| fs::read_to_string(capabilities.join(kind)).unwrap_or_default();
+            let keys = read("key");
+            WATCHED_KEYS.iter().any(|&key| has_capability(&keys, key))
+                || has_capability(&read("sw"), SW_CAMERA_LENS_COVER)
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Whether bit `code` is set in a sysfs capability bitmap: hex words of
+/// `long` size, most significant first (e.g. "1000000 0 0 0").
+fn has_capability(bitmap: &str, code: u16) -> bool {
+    let bits = libc::c_ulong::BITS as usize;
+    let code = code as usize;
+    bitmap
+        .split_whitespace()
+        .rev()
+        .nth(code / bits)
+        .and_then(|word| libc::c_ulong::from_str_radix(word, 16).ok())
+        .is_some_and(|word| word & (1 << (code % bits)) != 0)
+}
+
+/// The LED's active trigger other than "none", e.g. "audio-micmute".
+fn active_trigger(led: &LedDevice) -> Option<String> {
+    let dir = led.brightness_path.parent()?;
+    let triggers = fs::read_to_string(dir.join("trigger")).ok()?;
+    parse_active_trigger(&triggers).map(str::to_string)
+}
+
+/// The bracketed entry of a sysfs `trigger` list ("none [audio-micmute]
+/// ..."), unless it's "none".
+fn parse_active_trigger(triggers: &str) -> Option<&str> {
+    triggers
+        .split_whitespace()
+        .find_map(|entry| entry.strip_prefix('[')?.strip_suffix(']'))
+        .filter(|trigger| *trigger != "none")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_event() {
+        assert_eq!(key_event(EV_KEY, KEY_MICMUTE, 1), Some(KeyEvent::MicMute));
+        // Release and auto-repeat
+        assert_eq!(key_event(EV_KEY, KEY_MICMUTE, 0), None);
+        assert_eq!(key_event(EV_KEY, KEY_MICMUTE, 2), None);
+        assert_eq!(
+            key_event(EV_KEY, KEY_CAMERA_ACCESS_DISABLE, 1),
+            Some(KeyEvent::Camera(CameraAccessEvent::Disabled))
+        );
+        assert_eq!(
+            key_event(EV_SW, SW_CAMERA_LENS_COVER, 1),
+            Some(KeyEvent::Camera(CameraAccessEvent::Disabled))
+        );
+        assert_eq!(
+            key_event(EV_SW, SW_CAMERA_LENS_COVER, 0),
+            Some(KeyEvent::Camera(CameraAccessEvent::Enabled))
+        );
+        // KEY_A
+        assert_eq!(key_event(EV_KEY, 30, 1), None);
+    }
+
+    #[test]
+    fn test_has_capability() {
+        // Bit 248 is in the fourth 64-bit word (bit 56)
+        let keys = "100000000000000 0 0 0";
+        let bits = libc::c_ulong::BITS;
+        if bits == 64 {
+            assert!(has_capability(keys, KEY_MICMUTE));
+            assert!(!has_capability(keys, KEY_CAMERA_ACCESS_TOGGLE));
+        }
+        assert!(has_capability("200", SW_CAMERA_LENS_COVER));
+        assert!(!has_capability("0", SW_CAMERA_LENS_COVER));
+        assert!(!has_capability("", KEY_MICMUTE));
+    }
+
+    #[test]
+    fn test_parse_active_trigger() {
+        assert_eq!(
+            parse_active_trigger("none kbd-scrolllock [audio-micmute] timer\n"),
+            Some("audio-micmute")
+        );
+        assert_eq!(parse_active_trigger("[none] audio-micmute\n"), None);
+        assert_eq!(parse_active_trigger(""), None);
+    }
+
+    #[test]
+    fn test_key_devices() {
+        let root = std::env::temp_dir().join(format!("vibepanel-input-{}", std::process::id()));
+        let add = |name: &str, kind: &str, bitmap: &str| {
+            let dir = root.join(name).join("device/capabilities");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(kind), bitmap).unwrap();
+        };
+        add("event0", "key", "0");
+        add("event1", "sw", "200");
+        add("mouse0", "sw", "200");
+
+        let devices = key_devices(&root);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(devices, vec!["event1".to_string()]);
+    }
+}
@@ -4,6 +4,10 @@
 //! - Discovers Wi-Fi device and monitors state changes
 //! - Provides network list with signal strength, security, and known status
 //! - Supports scan, connect, disconnect, and forget operations
+//! - Lists the wired connection profiles available on the Ethernet device and
+//!   activates them on request
+//! - Reads the credentials of the connected Wi-Fi network for sharing
+//!   (NetworkManager asks polkit before handing out secrets)
 //!
 //! ## Architecture
 //!
@@ -14,7 +18,7 @@
 
 use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -24,10 +28,21 @@ use gtk4::gio::{self, prelude::*};
 use gtk4::glib::{self, Variant, VariantTy};
 use tracing::{debug, error, warn};
 
+use super::actions::{ActionKind, Actions};
 use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+use super::mock::MockServices;
+
+// Thread-local singleton storage for NetworkService
+thread_local! {
+    static NETWORK_INSTANCE: RefCell<Option<Rc<NetworkService>>> = const { RefCell::new(None) };
+}
 
 // D-Bus Constants
 
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "networkmanager";
+
 /// NetworkManager service name.
 const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
 /// NetworkManager main object path.
@@ -44,6 +59,11 @@ const IFACE_WIRED: &str = "org.freedesktop.NetworkManager.Device.Wired";
 const IFACE_AP: &str = "org.freedesktop.NetworkManager.AccessPoint";
 /// Active connection interface (for connection name/Id).
 const IFACE_ACTIVE_CONN: &str = "org.freedesktop.NetworkManager.Connection.Active";
+/// Saved connection profile interface.
+const IFACE_SETTINGS_CONN: &str = "org.freedesktop.NetworkManager.Settings.Connection";
+
+/// Setting group holding a Wi-Fi profile's key management and secrets.
+const WIFI_SECURITY_SETTING: &str = "802-11-wireless-security";
 
 /// NetworkManager device type for Ethernet (NM_DEVICE_TYPE_ETHERNET = 1).
 const ETHERNET_DEVICE_TYPE: u32 = 1;
@@ -65,6 +85,31 @@ pub struct WifiNetwork {
     pub known: bool,
 }
 
+/// A saved wired connection profile that can be activated on the Ethernet device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WiredProfile {
+    /// Profile name (e.g., "Wired connection 1", "Lab static").
+    pub name: String,
+    /// Profile UUID, used to activate it.
+    pub uuid: String,
+    /// Whether this profile is active on the device.
+    pub active: bool,
+}
+
+/// What another device needs to join a saved Wi-Fi network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WifiCredentials {
+    /// Network SSID (name).
+    pub ssid: String,
+    /// NetworkManager key management ("wpa-psk", "sae", "none" for WEP),
+    /// or None for an open network.
+    pub key_mgmt: Option<String>,
+    /// Pre-shared key or WEP key, if the network has one.
+    pub password: Option<String>,
+    /// Whether the SSID is not broadcast.
+    pub hidden: bool,
+}
+
 /// Canonical snapshot of Wi-Fi state.
 #[derive(Debug, Clone)]
 pub struct NetworkSnapshot {
@@ -90,6 +135,10 @@ pub struct NetworkSnapshot {
     pub wired_name: Option<String>,
     /// Wired link speed in Mb/s (e.g., 1000 for gigabit) when connected via Ethernet.
     pub wired_speed: Option<u32>,
+    /// Connection profiles available on the Ethernet device, sorted by name.
+    pub wired_profiles: Vec<WiredProfile>,
+    /// UUID of the wired profile currently being activated (for loading state).
+    pub activating_wired_profile: Option<String>,
     /// Current SSID if connected.
     pub ssid: Option<String>,
     /// Current signal strength if connected (0-100).
@@ -108,7 +157,7 @@ pub struct NetworkSnapshot {
 
 impl NetworkSnapshot {
     /// Create an initial "unknown" snapshot.
-    fn unknown() -> Self {
+    pub fn unknown() -> Self {
         Self {
             available: false,
             wifi_enabled: None,
@@ -120,6 +169,8 @@ impl NetworkSnapshot {
             wired_iface: None,
             wired_name: None,
             wired_speed: None,
+            wired_profiles: Vec::new(),
+            activating_wired_profile: None,
             ssid: None,
             strength: 0,
             scanning: false,
@@ -170,6 +221,10 @@ enum NetworkUpdate {
         /// Link speed in Mb/s (e.g., 1000 for gigabit).
         speed: Option<u32>,
     },
+    /// Wired connection profiles fetched.
+    WiredProfiles(Vec<WiredProfile>),
+    /// Wired profile activation finished (success or failure).
+    WiredProfileActivated,
 }
 
 /// Shared, process-wide network service for Wi-Fi state and control.
@@ -215,6 +270,11 @@ impl NetworkService {
             failed_ssid: RefCell::new(None),
         });
 
+        if MockServices::global().is_enabled() {
+            debug!("NetworkService: mocked, skipping NetworkManager");
+            return service;
+        }
+
         // Initialize D-Bus connection.
         // Background threads send updates via glib::idle_add_once() - no polling needed.
         Self::init_dbus(&service);
@@ -224,11 +284,17 @@ impl NetworkService {
 
     /// Get the global NetworkService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<NetworkService> = NetworkService::new();
-        }
+        NETWORK_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(NetworkService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        NETWORK_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked whenever the network state changes.
@@ -248,6 +314,13 @@ impl NetworkService {
         self.snapshot.borrow().clone()
     }
 
+    /// Replace the snapshot with a scripted one and notify listeners
+    /// (`--mock-services`).
+    pub fn publish_mock(&self, snapshot: NetworkSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+
     // Update Handling
 
     fn apply_update(&self, update: NetworkUpdate) {
@@ -273,6 +346,7 @@ impl NetworkService {
                     let snapshot_clone = snapshot.clone();
                     drop(snapshot);
                     self.callbacks.notify(&snapshot_clone);
+                    Self::fetch_wired_profiles();
                 }
             }
             NetworkUpdate::DeviceDiscoveryFailed => {
@@ -379,6 +453,23 @@ impl NetworkService {
                     self.callbacks.notify(&snapshot_clone);
                 }
             }
+            NetworkUpdate::WiredProfiles(profiles) => {
+                let mut snapshot = self.snapshot.borrow_mut();
+                if snapshot.wired_profiles != profiles {
+                    snapshot.wired_profiles = profiles;
+                    let snapshot_clone = snapshot.clone();
+                    drop(snapshot);
+                    self.callbacks.notify(&snapshot_clone);
+                }
+            }
+            NetworkUpdate::WiredProfileActivated => {
+                let mut snapshot = self.snapshot.borrow_mut();
+                snapshot.activating_wired_profile = None;
+                let snapshot_clone = snapshot.clone();
+                drop(snapshot);
+                self.callbacks.notify(&snapshot_clone);
+                Self::fetch_wired_profiles();
+            }
         }
     }
 
@@ -576,15 +667,14 @@ impl NetworkService {
         )
         .map_err(|e| format!("Failed to create NM proxy: {}", e))?;
 
-        let result = proxy
-            .call_sync(
-                "GetDevices",
-                None,
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .map_err(|e| format!("GetDevices failed: {}", e))?;
+        let result = dbus_watchdog::call_proxy_sync(
+            WATCHDOG_SERVICE,
+            &proxy,
+            "GetDevices",
+            None,
+            CallPolicy::QUERY,
+        )
+        .map_err(|e| format!("GetDevices failed: {}", e))?;
 
         // Result is (ao,) - array of object paths in a tuple
         let paths: Vec<String> = result
@@ -781,6 +871,142 @@ impl NetworkService {
         });
     }
 
+    /// Find the first Ethernet device path.
+    fn find_ethernet_device_sync() -> Result<Option<String>, String> {
+        let device_paths = Self::get_device_paths_sync()?;
+        Ok(device_paths.into_iter().find(|path| {
+            matches!(
+                Self::get_device_type_sync(path),
+                Ok((dtype, _)) if dtype == ETHERNET_DEVICE_TYPE
+            )
+        }))
+    }
+
+    /// Read the connection profiles NetworkManager considers available on a
+    /// device, marking the one that is active.
+    fn get_wired_profiles_sync(device_path: &str) -> Result<Vec<WiredProfile>, String> {
+        let dev_proxy = gio::DBusProxy::for_bus_sync(
+            gio::BusType::System,
+            gio::DBusProxyFlags::NONE,
+            None::<&gio::DBusInterfaceInfo>,
+            NM_SERVICE,
+            device_path,
+            IFACE_DEV,
+            None::<&gio::Cancellable>,
+        )
+        .map_err(|e| format!("Failed to create device proxy: {}", e))?;
+
+        let connection_paths: Vec<String> = dev_proxy
+            .cached_property("AvailableConnections")
+            .map(|v| v.iter().filter_map(|p| p.get::<String>()).collect())
+            .unwrap_or_default();
+
+        let active_uuid = dev_proxy
+            .cached_property("ActiveConnection")
+            .and_then(|v| v.get::<String>())
+            .filter(|p| p != "/")
+            .and_then(|path| {
+                gio::DBusProxy::for_bus_sync(
+                    gio::BusType::System,
+                    gio::DBusProxyFlags::NONE,
+                    None::<&gio::DBusInterfaceInfo>,
+                    NM_SERVICE,
+                    &path,
+                    IFACE_ACTIVE_CONN,
+                    None::<&gio::Cancellable>,
+                )
+                .ok()
+            })
+            .and_then(|proxy| proxy.cached_property("Uuid"))
+            .and_then(|v| v.get::<String>());
+
+        let mut profiles = Vec::new();
+        for path in connection_paths {
+            let proxy = match gio::DBusProxy::for_bus_sync(
+                gio::BusType::System,
+                gio::DBusProxyFlags::NONE,
+                None::<&gio::DBusInterfaceInfo>,
+                NM_SERVICE,
+                &path,
+                IFACE_SETTINGS_CONN,
+                None::<&gio::Cancellable>,
+            ) {
+                Ok(p) => p,
+                Err(e) => {
+                    debug!("Failed to create connection proxy for {}: {}", path, e);
+                    continue;
+                }
+            };
+
+            // Result is (a{sa{sv}},) - settings grouped by setting name
+            let settings = match dbus_watchdog::call_proxy_sync(
+                WATCHDOG_SERVICE,
+                &proxy,
+                "GetSettings",
+                None,
+                CallPolicy::QUERY,
+            ) {
+                Ok(result) => result.child_value(0),
+                Err(e) => {
+                    debug!("GetSettings failed for {}: {}", path, e);
+                    continue;
+                }
+            };
+
+            let Some(connection) = settings.lookup_value("connection", None) else {
+                continue;
+            };
+            let field = |key: &str| {
+                connection
+                    .lookup_value(key, None)
+                    .and_then(|v| v.get::<String>())
+            };
+            if let (Some(name), Some(uuid)) = (field("id"), field("uuid")) {
+                let active = active_uuid.as_deref() == Some(uuid.as_str());
+                profiles.push(WiredProfile { name, uuid, active });
+            }
+        }
+
+        profiles.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(profiles)
+    }
+
+    /// Fetch the wired connection profiles in a background thread.
+    fn fetch_wired_profiles() {
+        thread::spawn(move || {
+            // In debug builds, return mock data if the debug file exists
+            #[cfg(debug_assertions)]
+            if std::path::Path::new("/tmp/vibepanel-debug-wired").exists() {
+                send_network_update(NetworkUpdate::WiredProfiles(vec![
+                    WiredProfile {
+                        name: "Lab (static)".to_string(),
+                        uuid: "00000000-0000-0000-0000-000000000002".to_string(),
+                        active: false,
+                    },
+                    WiredProfile {
+                        name: "Wired connection 1".to_string(),
+                        uuid: "00000000-0000-0000-0000-000000000001".to_string(),
+                        active: true,
+                    },
+                ]));
+                return;
+            }
+
+            let profiles = match Self::find_ethernet_device_sync() {
+                Ok(Some(path)) => Self::get_wired_profiles_sync(&path).unwrap_or_else(|e| {
+                    debug!("Failed to read wired profiles: {}", e);
+                    Vec::new()
+                }),
+                Ok(None) => Vec::new(),
+                Err(e) => {
+                    warn!("Failed to get device paths for wired profiles: {}", e);
+                    Vec::new()
+                }
+            };
+            send_network_update(NetworkUpdate::WiredProfiles(profiles));
+        });
+    }
+
     /// Create wifi proxy - called from apply_update on main thread.
     fn create_wifi_proxy_from_self(&self, path: &str) {
         // Get a strong Rc to self for the callback.
@@ -899,6 +1125,11 @@ impl NetworkService {
             if wired_changed && wired_connected {
                 Self::fetch_wired_device_info();
             }
+
+            // The active profile may have changed
+            if self.snapshot.borrow().has_ethernet_device {
+                Self::fetch_wired_profiles();
+            }
         }
     }
 
@@ -1035,15 +1266,14 @@ impl NetworkService {
     }
 
     fn get_access_points_sync(wifi: &gio::DBusProxy) -> Result<Vec<String>, String> {
-        let result = wifi
-            .call_sync(
-                "GetAccessPoints",
-                None,
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .map_err(|e| format!("GetAccessPoints failed: {}", e))?;
+        let result = dbus_watchdog::call_proxy_sync(
+            WATCHDOG_SERVICE,
+            wifi,
+            "GetAccessPoints",
+            None,
+            CallPolicy::QUERY,
+        )
+        .map_err(|e| format!("GetAccessPoints failed: {}", e))?;
 
         let paths: Vec<String> = result
             .child_value(0)
@@ -1200,6 +1430,21 @@ impl NetworkService {
 
     /// Enable or disable Wi-Fi.
     pub fn set_wifi_enabled(&self, enabled: bool) {
+        let what = if enabled {
+            "enable Wi-Fi"
+        } else {
+            "disable Wi-Fi"
+        };
+        if Actions::global()
+            .permit(ActionKind::Settings, what)
+            .is_err()
+        {
+            // Re-send the unchanged state so the toggle flips back
+            let snapshot = self.snapshot.borrow().clone();
+            self.callbacks.notify(&snapshot);
+            return;
+        }
+
         let Some(nm) = self.nm_proxy.borrow().clone() else {
             return;
         };
@@ -1213,12 +1458,12 @@ impl NetworkService {
                 enabled.to_variant().to_variant(),
             ]);
 
-            if let Err(e) = nm.call_sync(
+            if let Err(e) = dbus_watchdog::call_proxy_sync(
+                WATCHDOG_SERVICE,
+                &nm,
                 "org.freedesktop.DBus.Properties.Set",
                 Some(&variant),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
+                CallPolicy::ACTION,
             ) {
                 error!("Failed to set WirelessEnabled: {}", e);
             }
@@ -1248,12 +1493,12 @@ impl NetworkService {
         let empty_dict = Variant::parse(Some(VariantTy::new("a{sv}").unwrap()), "{}").unwrap();
         let args = Variant::tuple_from_iter([empty_dict]);
 
-        wifi.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &wifi,
             "RequestScan",
             Some(&args),
-            gio::DBusCallFlags::NONE,
-            30000, // Scanning can take time
-            None::<&gio::Cancellable>,
+            CallPolicy::SLOW, // Scanning can take time
             move |_res| {
                 // Callback runs on main GLib loop - request refresh.
                 send_network_update(NetworkUpdate::RefreshNetworks);
@@ -1272,12 +1517,18 @@ impl NetworkService {
     }
 
     /// Connect to a Wi-Fi network by SSID.
-    pub fn connect_to_ssid(&self, ssid: &str, password: Option<&str>) {
+    pub fn connect_to_ssid(&self, ssid: &str, password: Option<&str>) -> Result<(), String> {
         let ssid = ssid.trim().to_string();
         if ssid.is_empty() {
-            return;
+            return Ok(());
         }
 
+        let mut args = vec!["device", "wifi", "connect", ssid.as_str()];
+        if let Some(pw) = password {
+            args.extend(["password", pw]);
+        }
+        let child = spawn_nmcli(&format!("connect to '{}'", ssid), &args)?;
+
         // Clear any previous failed state and set connecting state for UI feedback.
         *self.failed_ssid.borrow_mut() = None;
         *self.connecting_ssid.borrow_mut() = Some(ssid.clone());
@@ -1288,17 +1539,8 @@ impl NetworkService {
         drop(snapshot);
         self.callbacks.notify(&snapshot_clone);
 
-        let password = password.map(|s| s.to_string());
-
         thread::spawn(move || {
-            let mut cmd = Command::new("nmcli");
-            cmd.args(["device", "wifi", "connect", &ssid]);
-
-            if let Some(ref pw) = password {
-                cmd.args(["password", pw]);
-            }
-
-            let success = match cmd.output() {
+            let success = match child.wait_with_output() {
                 Ok(output) => {
                     if output.status.success() {
                         true
@@ -1316,7 +1558,7 @@ impl NetworkService {
                     }
                 }
                 Err(e) => {
-                    error!("Failed to run nmcli: {}", e);
+                    error!("Failed to wait for nmcli: {}", e);
                     false
                 }
             };
@@ -1324,42 +1566,220 @@ impl NetworkService {
             // Signal that connection attempt finished (success or failure).
             send_network_update(NetworkUpdate::ConnectionAttemptFinished { ssid, success });
         });
+        Ok(())
     }
 
     /// Disconnect from the current Wi-Fi network.
-    pub fn disconnect(&self) {
+    pub fn disconnect(&self) -> Result<(), String> {
         let iface = self.iface_name.borrow().clone();
         let Some(iface) = iface else {
-            return;
+            return Ok(());
         };
 
+        let child = spawn_nmcli(
+            &format!("disconnect {}", iface),
+            &["device", "disconnect", &iface],
+        )?;
+
         thread::spawn(move || {
-            if let Err(e) = Command::new("nmcli")
-                .args(["device", "disconnect", &iface])
-                .output()
-            {
+            if let Err(e) = child.wait_with_output() {
                 error!("nmcli disconnect failed: {}", e);
             }
 
             // Request refresh.
             send_network_update(NetworkUpdate::RefreshNetworks);
         });
+        Ok(())
+    }
+
+    /// Activate a wired connection profile by UUID.
+    pub fn activate_wired_profile(&self, uuid: &str) -> Result<(), String> {
+        if self.snapshot.borrow().activating_wired_profile.is_some() {
+            return Ok(());
+        }
+        let uuid = uuid.to_string();
+
+        let child = spawn_nmcli(
+            &format!("activate profile {}", uuid),
+            &["connection", "up", "uuid", &uuid],
+        )?;
+
+        let mut snapshot = self.snapshot.borrow_mut();
+        snapshot.activating_wired_profile = Some(uuid.clone());
+        let snapshot_clone = snapshot.clone();
+        drop(snapshot);
+        self.callbacks.notify(&snapshot_clone);
+
+        thread::spawn(move || {
+            match child.wait_with_output() {
+                Ok(output) if !output.status.success() => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    warn!("nmcli connection up failed for {}: {}", uuid, stderr.trim());
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to wait for nmcli: {}", e),
+            }
+
+            send_network_update(NetworkUpdate::WiredProfileActivated);
+        });
+        Ok(())
+    }
+
+    /// Read the credentials of the connected Wi-Fi network.
+    ///
+    /// NetworkManager only hands out secrets after a polkit check, so this
+    /// may show an authentication prompt; `callback` runs on the main loop
+    /// once it is answered.
+    pub fn fetch_wifi_credentials<F>(&self, callback: F)
+    where
+        F: FnOnce(Result<WifiCredentials, String>) + 'static,
+    {
+        if let Err(e) = Actions::global().permit(ActionKind::Settings, "read Wi-Fi password") {
+            callback(Err(e));
+            return;
+        }
+
+        let device_path = self
+            .wifi_proxy
+            .borrow()
+            .as_ref()
+            .map(|proxy| proxy.object_path().to_string());
+        let Some(device_path) = device_path else {
+            callback(Err("No Wi-Fi device".to_string()));
+            return;
+        };
+
+        glib::spawn_future_local(async move {
+            let result =
+                gio::spawn_blocking(move || Self::get_wifi_credentials_sync(&device_path)).await;
+            callback(result.unwrap_or_else(|_| Err("Reading credentials failed".to_string())));
+        });
+    }
+
+    /// Look up the saved profile of the device's active connection and read
+    /// its secrets.
+    fn get_wifi_credentials_sync(device_path: &str) -> Result<WifiCredentials, String> {
+        let proxy_for = |path: &str, iface: &str| {
+            gio::DBusProxy::for_bus_sync(
+                gio::BusType::System,
+                gio::DBusProxyFlags::NONE,
+                None::<&gio::DBusInterfaceInfo>,
+                NM_SERVICE,
+                path,
+                iface,
+                None::<&gio::Cancellable>,
+            )
+            .map_err(|e| format!("Failed to create proxy for {}: {}", path, e))
+        };
+
+        let active_path = proxy_for(device_path, IFACE_DEV)?
+            .cached_property("ActiveConnection")
+            .and_then(|v| v.get::<String>())
+            .filter(|p| p != "/")
+            .ok_or_else(|| "Not connected".to_string())?;
+        let settings_path = proxy_for(&active_path, IFACE_ACTIVE_CONN)?
+            .cached_property("Connection")
+            .and_then(|v| v.get::<String>())
+            .filter(|p| p != "/")
+            .ok_or_else(|| "Connection has no saved profile".to_string())?;
+        let profile = proxy_for(&settings_path, IFACE_SETTINGS_CONN)?;
+
+        // Result is (a{sa{sv}},) - settings grouped by setting name
+        let settings = dbus_watchdog::call_proxy_sync(
+            WATCHDOG_SERVICE,
+            &profile,
+            "GetSettings",
+            None,
+            CallPolicy::QUERY,
+        )
+        .map_err(|e| format!("GetSettings failed: {}", e))?
+        .child_value(0);
+
+        let wireless = settings
+            .lookup_value("802-11-wireless", None)
+            .ok_or_else(|| "Not a Wi-Fi connection".to_string())?;
+        let ssid = wireless
+            .lookup_value("ssid", None)
+            .map(|v| v.iter().filter_map(|b| b.get::<u8>()).collect::<Vec<u8>>())
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| "Profile has no SSID".to_string())?;
+        let hidden = wireless
+            .lookup_value("hidden", None)
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+
+        let security = settings.lookup_value(WIFI_SECURITY_SETTING, None);
+        let security_field = |key: &str| {
+            security
+                .as_ref()
+                .and_then(|s| s.lookup_value(key, None))
+                .and_then(|v| v.get::<String>())
+        };
+        let key_mgmt = security_field("key-mgmt");
+
+        let secret_key = match key_mgmt.as_deref() {
+            None | Some("owe") => None,
+            Some("wpa-psk") | Some("sae") => Some("psk".to_string()),
+            Some("none") => {
+                let index = security
+                    .as_ref()
+                    .and_then(|s| s.lookup_value("wep-tx-keyidx", None))
+                    .and_then(|v| v.get::<u32>())
+                    .unwrap_or(0);
+                Some(format!("wep-key{}", index))
+            }
+            Some(other) => {
+                return Err(format!("Networks using '{}' can't be shared", other));
+            }
+        };
+
+        let password = match secret_key {
+            Some(key) => {
+                // Secrets need the polkit check, so let the agent prompt
+                let params = (WIFI_SECURITY_SETTING,).to_variant();
+                let secrets =
+                    dbus_watchdog::run_sync(WATCHDOG_SERVICE, CallPolicy::SLOW, |timeout_ms| {
+                        profile.call_sync(
+                            "GetSecrets",
+                            Some(&params),
+                            gio::DBusCallFlags::ALLOW_INTERACTIVE_AUTHORIZATION,
+                            timeout_ms,
+                            None::<&gio::Cancellable>,
+                        )
+                    })
+                    .map_err(|e| format!("GetSecrets failed: {}", e))?
+                    .child_value(0);
+                secrets
+                    .lookup_value(WIFI_SECURITY_SETTING, None)
+                    .and_then(|s| s.lookup_value(&key, None))
+                    .and_then(|v| v.get::<String>())
+            }
+            None => None,
+        };
+
+        Ok(WifiCredentials {
+            ssid,
+            key_mgmt,
+            password,
+            hidden,
+        })
     }
 
     /// Forget a saved Wi-Fi network.
-    pub fn forget_network(&self, ssid: &str) {
+    pub fn forget_network(&self, ssid: &str) -> Result<(), String> {
         let ssid = ssid.trim().to_string();
         if ssid.is_empty() {
-            return;
+            return Ok(());
         }
 
+        let child = spawn_nmcli(
+            &format!("forget '{}'", ssid),
+            &["connection", "delete", "id", &ssid],
+        )?;
         let known_ssids_refresh = Arc::clone(&self.known_ssids_last_refresh);
 
         thread::spawn(move || {
-            if let Err(e) = Command::new("nmcli")
-                .args(["connection", "delete", "id", &ssid])
-                .output()
-            {
+            if let Err(e) = child.wait_with_output() {
                 error!("nmcli forget failed: {}", e);
             }
 
@@ -1371,9 +1791,24 @@ impl NetworkService {
             // Request refresh.
             send_network_update(NetworkUpdate::RefreshNetworks);
         });
+        Ok(())
     }
 }
 
+/// Start an `nmcli` command that changes network settings.
+///
+/// Goes through the action dispatcher so kiosk mode refuses it; `what`
+/// describes it for the log without exposing a password.
+fn spawn_nmcli(what: &str, args: &[&str]) -> Result<Child, String> {
+    Actions::global().permit(ActionKind::Settings, what)?;
+    Command::new("nmcli")
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run nmcli: {}", e))
+}
+
 /// Send an update to the main thread via glib::idle_add_once().
 /// This wakes the GLib main loop immediately (no polling).
 fn send_network_update(update: NetworkUpdate) {
@@ -1396,3 +1831,78 @@ fn is_wired_connected(primary_type: Option<&str>) -> bool {
 
     primary_type.is_some_and(|t| t == "802-3-ethernet")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mock::{Scenario, Step};
+
+    const REFUSED: &str = "settings change disabled in kiosk mode";
+
+    /// A service without NetworkManager, with a Wi-Fi interface and kiosk mode on.
+    fn kiosk_service() -> Rc<NetworkService> {
+        MockServices::global().enable(Scenario {
+            interval: 1,
+            steps: vec![Step::default()],
+        });
+        Actions::global().enable_kiosk();
+        let service = NetworkService::new();
+        *service.iface_name.borrow_mut() = Some("wlan0".to_string());
+        service
+    }
+
+    #[test]
+    fn test_kiosk_refuses_wifi_connect() {
+        let service = kiosk_service();
+        assert_eq!(
+            service
+                .connect_to_ssid("Home", Some("hunter2"))
+                .unwrap_err(),
+            REFUSED
+        );
+        assert_eq!(service.snapshot().connecting_ssid, None);
+        assert_eq!(service.snapshot().failed_ssid, None);
+    }
+
+    #[test]
+    fn test_kiosk_refuses_wifi_disconnect() {
+        let service = kiosk_service();
+        assert_eq!(service.disconnect().unwrap_err(), REFUSED);
+    }
+
+    #[test]
+    fn test_kiosk_refuses_forget_network() {
+        let service = kiosk_service();
+        assert_eq!(service.forget_network("Home").unwrap_err(), REFUSED);
+    }
+
+    #[test]
+    fn test_kiosk_refuses_wired_profile_activation() {
+        let service = kiosk_service();
+        assert_eq!(service.activate_wired_profile("0b1c").unwrap_err(), REFUSED);
+        assert_eq!(service.snapshot().activating_wired_profile, None);
+    }
+
+    #[test]
+    fn test_kiosk_refuses_reading_wifi_secrets() {
+        let service = kiosk_service();
+        let result = Rc::new(RefCell::new(None));
+        let result_clone = Rc::clone(&result);
+        service.fetch_wifi_credentials(move |r| {
+            *result_clone.borrow_mut() = Some(r.map(|c| c.password));
+        });
+        assert_eq!(*result.borrow(), Some(Err(REFUSED.to_string())));
+    }
+
+    #[test]
+    fn test_kiosk_resends_state_on_wifi_toggle() {
+        let service = kiosk_service();
+        let notified = Rc::new(Cell::new(0));
+        let notified_clone = Rc::clone(&notified);
+        service.connect(move |_| notified_clone.set(notified_clone.get() + 1));
+        let before = notified.get();
+
+        service.set_wifi_enabled(false);
+        assert_eq!(notified.get(), before + 1);
+    }
+}
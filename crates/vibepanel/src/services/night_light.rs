@@ -0,0 +1,328 @@
+//! NightLightService - screen color temperature that follows the sun.
+//!
+//! - Location comes from `[night_light]` coordinates, or from GeoClue
+//!   (city accuracy) when none are configured
+//! - Every minute, the temperature for the current point in the
+//!   sunrise/sunset schedule is computed and, if it changed, applied by
+//!   running the configured command on the worker pool
+//! - A manual override forces night or day until the next sunrise/sunset
+//!   transition, then the schedule takes over again. Overrides are persisted
+//!   so a restart doesn't undo them
+//!
+//! The schedule itself (sun times and ramps) lives in
+//! `vibepanel_core::night_light`.
+
+use std::cell::{Cell, RefCell};
+use std::process::Command;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use gtk4::glib;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use vibepanel_core::StateStore;
+use vibepanel_core::config::NightLightConfig;
+use vibepanel_core::night_light::Schedule;
+
+use super::callbacks::Callbacks;
+use super::event_bus::EventBus;
+use super::geoclue::{GeoClueService, GeoClueSnapshot};
+use super::worker::WorkerPool;
+
+/// State key for the manual override.
+const OVERRIDE_STATE_KEY: &str = "night_light.override";
+
+/// How often the schedule is re-evaluated (seconds).
+const TICK_INTERVAL_SECS: u32 = 60;
+
+/// How long an override lasts when the sun doesn't rise or set soon
+/// (polar day or night).
+const FALLBACK_OVERRIDE_SECS: i64 = 24 * 60 * 60;
+
+/// A manual override, active until `until` (Unix timestamp).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Override {
+    night: bool,
+    until: i64,
+}
+
+/// Canonical snapshot of night light state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NightLightSnapshot {
+    /// Whether the schedule is enabled in the config.
+    pub enabled: bool,
+    /// Whether a location is known (configured or from GeoClue).
+    pub located: bool,
+    /// Last temperature applied (Kelvin).
+    pub temperature: Option<u32>,
+    /// Whether it's currently night (past the middle of the sunset ramp),
+    /// including overrides.
+    pub night: bool,
+    /// Manual override in effect: `Some(true)` forces night, `Some(false)` day.
+    pub override_night: Option<bool>,
+}
+
+impl NightLightSnapshot {
+    fn empty() -> Self {
+        Self {
+            enabled: false,
+            located: false,
+            temperature: None,
+            night: false,
+            override_night: None,
+        }
+    }
+}
+
+/// Shared, process-wide night light service.
+pub struct NightLightService {
+    config: RefCell<Option<NightLightConfig>>,
+    /// Location from GeoClue, used when the config has no coordinates.
+    geoclue_location: Cell<Option<(f64, f64)>>,
+    override_: Cell<Option<Override>>,
+    timer_source: RefCell<Option<glib::SourceId>>,
+    /// Whether the GeoClue location is being followed.
+    watching_geoclue: Cell<bool>,
+    snapshot: RefCell<NightLightSnapshot>,
+    callbacks: Callbacks<NightLightSnapshot>,
+}
+
+impl NightLightService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            config: RefCell::new(None),
+            geoclue_location: Cell::new(None),
+            override_: Cell::new(StateStore::global().get(OVERRIDE_STATE_KEY)),
+            timer_source: RefCell::new(None),
+            watching_geoclue: Cell::new(false),
+            snapshot: RefCell::new(NightLightSnapshot::empty()),
+            callbacks: Callbacks::new(),
+        })
+    }
+
+    /// Get the global NightLightService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<NightLightService> = NightLightService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    #[allow(dead_code)]
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&NightLightSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        self.callbacks.notify(&self.snapshot.borrow());
+    }
+
+    /// Current snapshot.
+    #[allow(dead_code)]
+    pub fn snapshot(&self) -> NightLightSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Apply settings and (re)start the schedule.
+    ///
+    /// Re-applying identical settings is a no-op.
+    pub fn configure(self: &Rc<Self>, config: &NightLightConfig) {
+        if self.config.borrow().as_ref() == Some(config) {
+            return;
+        }
+        *self.config.borrow_mut() = Some(config.clone());
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        if !config.enabled {
+            // Don't leave the screen tinted
+            if self.snapshot.borrow().temperature.is_some() {
+                self.apply_temperature(config.day_temperature);
+            }
+            self.update_snapshot(|snapshot| *snapshot = NightLightSnapshot::empty());
+            return;
+        }
+
+        info!(
+            "NightLightService: {} K by day, {} K by night",
+            config.day_temperature, config.night_temperature
+        );
+
+        if config.latitude.is_none() && !self.watching_geoclue.replace(true) {
+            Self::watch_geoclue(self);
+        }
+
+        // The new config may change the command or temperatures; reapply
+        self.snapshot.borrow_mut().temperature = None;
+        self.tick();
+
+        let this_weak = Rc::downgrade(self);
+        let source_id =
+            glib::timeout_add_seconds_local(TICK_INTERVAL_SECS, move || {
+                match this_weak.upgrade() {
+                    Some(this) => {
+                        this.tick();
+                        glib::ControlFlow::Continue
+                    }
+                    None => glib::ControlFlow::Break,
+                }
+            });
+        *self.timer_source.borrow_mut() = Some(source_id);
+    }
+
+    /// Force night (`Some(true)`) or day (`Some(false)`) until the next
+    /// sunrise/sunset transition, or return to the schedule (`None`).
+    pub fn set_override(&self, night: Option<bool>) -> Result<(), String> {
+        let schedule = self
+            .schedule()
+            .ok_or_else(|| "night light is disabled or has no location".to_string())?;
+
+        let now = unix_now();
+        let override_ = night.map(|night| Override {
+            night,
+            until: schedule
+                .next_transition(now)
+                .unwrap_or(now + FALLBACK_OVERRIDE_SECS),
+        });
+        self.set_stored_override(override_);
+        self.tick();
+        Ok(())
+    }
+
+    fn set_stored_override(&self, override_: Option<Override>) {
+        self.override_.set(override_);
+        match override_ {
+            Some(override_) => StateStore::global().set(OVERRIDE_STATE_KEY, &override_),
+            None => StateStore::global().remove(OVERRIDE_STATE_KEY),
+        }
+    }
+
+    /// Schedule for the configured or discovered location.
+    fn schedule(&self) -> Option<Schedule> {
+        let config = self.config.borrow();
+        let config = config.as_ref().filter(|config| config.enabled)?;
+        let (latitude, longitude) = match (config.latitude, config.longitude) {
+            (Some(latitude), Some(longitude)) => (latitude, longitude),
+            _ => self.geoclue_location.get()?,
+        };
+        Some(Schedule::new(config, latitude, longitude))
+    }
+
+    /// Recompute the temperature and apply it if it changed.
+    fn tick(&self) {
+        let Some(schedule) = self.schedule() else {
+            let enabled = self.config.borrow().as_ref().is_some_and(|c| c.enabled);
+            self.update_snapshot(|snapshot| {
+                snapshot.enabled = enabled;
+                snapshot.located = false;
+            });
+            return;
+        };
+
+        let now = unix_now();
+        if self.override_.get().is_some_and(|o| now >= o.until) {
+            debug!("NightLightService: override expired");
+            self.set_stored_override(None);
+        }
+        let override_night = self.override_.get().map(|o| o.night);
+
+        let temperature = match override_night {
+            Some(true) => schedule.night_temperature,
+            Some(false) => schedule.day_temperature,
+            None => schedule.temperature_at(now),
+        };
+        let night = override_night.unwrap_or_else(|| schedule.night_factor(now) >= 0.5);
+
+        if self.snapshot.borrow().temperature != Some(temperature) {
+            self.apply_temperature(temperature);
+        }
+        self.update_snapshot(|snapshot| {
+            snapshot.enabled = true;
+            snapshot.located = true;
+            snapshot.temperature = Some(temperature);
+            snapshot.night = night;
+            snapshot.override_night = override_night;
+        });
+    }
+
+    /// Run the configured command for `temperature` on the worker pool.
+    fn apply_temperature(&self, temperature: u32) {
+        let Some(template) = self
+            .config
+            .borrow()
+            .as_ref()
+            .map(|config| config.command.clone())
+        else {
+            return;
+        };
+        let command = template.replace("{temperature}", &temperature.to_string());
+        debug!("NightLightService: applying {} K", temperature);
+
+        WorkerPool::global().spawn(
+            move || {
+                Command::new("sh")
+                    .args(["-c", &command])
+                    .output()
+                    .map_err(|e| format!("failed to run '{}': {}", command, e))
+                    .and_then(|output| {
+                        if output.status.success() {
+                            Ok(())
+                        } else {
+                            Err(format!(
+                                "'{}' failed: {}",
+                                command,
+                                String::from_utf8_lossy(&output.stderr).trim()
+                            ))
+                        }
+                    })
+            },
+            |result| {
+                if let Err(e) = result.unwrap_or_else(|e| Err(e.to_string())) {
+                    warn!("NightLightService: {}", e);
+                }
+            },
+        );
+    }
+
+    fn update_snapshot(&self, f: impl FnOnce(&mut NightLightSnapshot)) {
+        let snapshot = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let before = snapshot.clone();
+            f(&mut snapshot);
+            if *snapshot == before {
+                return;
+            }
+            snapshot.clone()
+        };
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Follow the GeoClue location, for when the config has no coordinates.
+    fn watch_geoclue(this: &Rc<Self>) {
+        let this_weak = Rc::downgrade(this);
+        // Owned by the service, not by whatever configured it first
+        EventBus::global().unscoped(|| {
+            GeoClueService::global().connect(move |snapshot: &GeoClueSnapshot| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                if snapshot.location.is_some()
+                    && this.geoclue_location.replace(snapshot.location) != snapshot.location
+                {
+                    this.tick();
+                }
+            });
+        });
+    }
+}
+
+/// Current time as a Unix timestamp.
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
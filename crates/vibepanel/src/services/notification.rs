@@ -12,10 +12,14 @@ use gtk4::gio::{self, prelude::*};
 use gtk4::glib::Variant;
 use tracing::{debug, error, info, warn};
 
+use super::callbacks::Callbacks;
 use super::state::{self, PersistedNotification};
 
-/// Type alias for notification service callbacks.
-type NotificationCallback = Rc<dyn Fn(&NotificationService)>;
+/// Bus event published when notification state changes.
+///
+/// Listeners read the new state from the service itself.
+#[derive(Debug, Clone)]
+struct NotificationsChanged;
 
 const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
 const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
@@ -168,7 +172,7 @@ pub struct NotificationService {
     muted: Cell<bool>,
 
     /// Callbacks for state changes
-    callbacks: RefCell<Vec<NotificationCallback>>,
+    callbacks: Callbacks<NotificationsChanged>,
     /// Whether the service is ready
     ready: Cell<bool>,
 
@@ -209,12 +213,18 @@ impl NotificationService {
             next_id: Cell::new(next_id),
             backend_available: Cell::new(false),
             muted: Cell::new(notification_state.muted),
-            callbacks: RefCell::new(Vec::new()),
+            callbacks: Callbacks::new(),
             ready: Cell::new(false),
             restored_ids: RefCell::new(restored_ids),
         });
 
-        Self::init_dbus(&service);
+        // A preview bar leaves the daemon role to the running bar
+        if crate::preview::is_enabled() {
+            debug!("NotificationService: preview mode, not acting as notification daemon");
+            service.set_ready();
+        } else {
+            Self::init_dbus(&service);
+        }
         service
     }
 
@@ -231,12 +241,13 @@ impl NotificationService {
     where
         F: Fn(&NotificationService) + 'static,
     {
-        let cb = Rc::new(callback);
-        self.callbacks.borrow_mut().push(cb.clone());
+        let id = self
+            .callbacks
+            .register(move |_: &NotificationsChanged| callback(&NotificationService::global()));
 
         // Immediately send current state if ready
         if self.ready.get() {
-            cb(self);
+            self.callbacks.notify_single(id, &NotificationsChanged);
         }
     }
 
@@ -313,6 +324,30 @@ impl NotificationService {
         self.notify_listeners();
     }
 
+    /// Close all notifications from one app.
+    pub fn close_app(&self, app_name: &str) {
+        debug!("NotificationService: close_app() called for {}", app_name);
+        let ids: Vec<u32> = self
+            .notifications
+            .borrow()
+            .values()
+            .filter(|n| n.app_name == app_name)
+            .map(|n| n.id)
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        for id in ids {
+            if self.notifications.borrow_mut().remove(&id).is_some() {
+                self.emit_notification_closed(id, CLOSE_REASON_DISMISSED);
+            }
+        }
+
+        self.save_state();
+        self.notify_listeners();
+    }
+
     /// Invoke an action on a notification.
     pub fn invoke_action(&self, id: u32, action_key: &str) {
         debug!(
@@ -755,10 +790,7 @@ impl NotificationService {
     }
 
     fn notify_listeners(&self) {
-        let callbacks: Vec<_> = self.callbacks.borrow().iter().cloned().collect();
-        for cb in callbacks {
-            cb(self);
-        }
+        self.callbacks.notify(&NotificationsChanged);
     }
 
     /// Save current notification state to disk.
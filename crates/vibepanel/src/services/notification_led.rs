@@ -0,0 +1,324 @@
+//! NotificationLedService - lights a keyboard or chassis LED while urgent
+//! notifications are unseen.
+//!
+//! - Picks the first `*::notification` LED in `/sys/class/leds`, falling
+//!   back to a capslock LED (configurable), or uses the LED named in the
+//!   notifications widget's `led` option
+//! - Turns the LED on when a critical notification arrives and off again
+//!   when the notification popover or center is opened, or when the urgent
+//!   notifications are dismissed
+//! - Restores the LED's previous brightness when clearing it, so a capslock
+//!   LED goes back to showing caps lock
+//!
+//! Writes go through logind like every LED write (see `leds`), so no udev
+//! rules are needed.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::{debug, info, warn};
+
+use super::event_bus::EventBus;
+use super::leds::{LEDS_PATH, LedDevice, LedWriter, led_names};
+use super::notification::{Notification, NotificationService, URGENCY_CRITICAL};
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings};
+
+/// Settings for the notification LED (from the notifications widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationLedSettings {
+    /// LED name under `/sys/class/leds`; `None` picks one automatically.
+    pub device: Option<String>,
+    /// Use a capslock LED when there is no notification LED.
+    pub capslock_fallback: bool,
+}
+
+impl MergeSettings for NotificationLedSettings {
+    /// There is one LED; the newest widget picks it.
+    fn merge(&mut self, other: &Self) {
+        *self = other.clone();
+    }
+}
+
+/// Shared, process-wide notification LED driver.
+pub struct NotificationLedService {
+    /// Settings of each notifications widget with an LED.
+    registered: WidgetSettings<NotificationLedSettings>,
+    /// Merged settings the LED was picked with.
+    settings: RefCell<Option<NotificationLedSettings>>,
+    device: RefCell<Option<LedDevice>>,
+    /// Writes the LED, once configured.
+    writer: RefCell<Option<Rc<LedWriter>>>,
+    /// Brightness the LED had before it was lit; `Some` while lit.
+    restore_brightness: Cell<Option<u32>>,
+    /// When the notifications were last looked at (seconds since epoch).
+    last_seen: Cell<f64>,
+}
+
+impl NotificationLedService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            device: RefCell::new(None),
+            writer: RefCell::new(None),
+            restore_brightness: Cell::new(None),
+            last_seen: Cell::new(0.0),
+        })
+    }
+
+    /// Get the global NotificationLedService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<NotificationLedService> = NotificationLedService::new();
+        }
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a notifications widget's LED choice.
+    pub fn configure(self: &Rc<Self>, settings: NotificationLedSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's LED choice; the LED is cleared with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Pick the LED for the merged settings of all widgets.
+    fn apply_settings(self: &Rc<Self>) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        // Clear the LED we were using before switching to another one
+        self.set_lit(false);
+        let Some(settings) = settings else {
+            debug!("NotificationLedService: no widgets left, releasing the LED");
+            *self.device.borrow_mut() = None;
+            *self.settings.borrow_mut() = None;
+            return;
+        };
+
+        let device = find_led(Path::new(LEDS_PATH), &settings);
+        match &device {
+            Some(device) => info!("NotificationLedService: using LED {}", device.name),
+            None => warn!(
+                "NotificationLedService: no {} LED found in {}",
+                settings.device.as_deref().unwrap_or("notification"),
+                LEDS_PATH
+            ),
+        }
+
+        *self.device.borrow_mut() = device;
+        *self.settings.borrow_mut() = Some(settings);
+
+        if self.writer.borrow().is_none() {
+            *self.writer.borrow_mut() = Some(LedWriter::new("NotificationLedService"));
+
+            // The LED is first picked while the notifications widget is built;
+            // the subscription must outlive that bar's scope
+            let this_weak = Rc::downgrade(self);
+            EventBus::global().unscoped(|| {
+                NotificationService::global().connect(move |_| {
+                    if let Some(this) = this_weak.upgrade() {
+                        this.update();
+                    }
+                });
+            });
+        }
+        self.update();
+    }
+
+    /// Mark the notifications as seen (the popover or center was opened).
+    pub fn mark_seen(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        self.last_seen.set(now);
+        self.update();
+    }
+
+    /// Put the LED back the way it was (called on shutdown).
+    pub fn shutdown(&self) {
+        let Some(value) = self.restore_brightness.take() else {
+            return;
+        };
+        let Some(device) = self.device.borrow().clone() else {
+            return;
+        };
+
+        debug!(
+            "NotificationLedService: restoring {} on shutdown",
+            device.name
+        );
+        // Synchronous: the main loop is about to stop
+        if let Some(writer) = self.writer.borrow().as_ref() {
+            writer.write_sync(&device, value);
+        }
+    }
+
+    /// Light or clear the LED for the current notifications.
+    fn update(&self) {
+        let service = NotificationService::global();
+        let pending = has_unseen_urgent(
+            &service.notifications(),
+            &service.restored_ids(),
+            self.last_seen.get(),
+        );
+        self.set_lit(pending);
+    }
+
+    fn set_lit(&self, lit: bool) {
+        let Some(device) = self.device.borrow().clone() else {
+            return;
+        };
+        if lit == self.restore_brightness.get().is_some() {
+            return;
+        }
+
+        let value = if lit {
+            let current = device.brightness().unwrap_or(0);
+            self.restore_brightness.set(Some(current));
+            device.max_brightness
+        } else {
+            self.restore_brightness.take().unwrap_or(0)
+        };
+        debug!(
+            "NotificationLedService: {} {} (brightness {})",
+            if lit { "lighting" } else { "clearing" },
+            device.name,
+            value
+        );
+        self.write(device, value);
+    }
+
+    fn write(&self, device: LedDevice, value: u32) {
+        if let Some(writer) = self.writer.borrow().as_ref() {
+            writer.write(device, value);
+        }
+    }
+}
+
+/// Whether any critical notification arrived after `last_seen`.
+///
+/// Notifications restored from a previous session don't count.
+fn has_unseen_urgent(
+    notifications: &[Notification],
+    restored: &HashSet<u32>,
+    last_seen: f64,
+) -> bool {
+    notifications.iter().any(|n| {
+        n.urgency == URGENCY_CRITICAL && !restored.contains(&n.id) && n.timestamp > last_seen
+    })
+}
+
+/// Find the LED to use under `root`: the configured one, else the first
+/// `*::notification` LED, else (if allowed) the first `*::capslock` LED.
+fn find_led(root: &Path, settings: &NotificationLedSettings) -> Option<LedDevice> {
+    let names = led_names(root);
+
+    let name = match &settings.device {
+        Some(device) => names.into_iter().find(|name| name == device),
+        None => {
+            let by_suffix =
+                |suffix: &str| names.iter().find(|name| name.ends_with(suffix)).cloned();
+            by_suffix("::notification").or_else(|| {
+                settings
+                    .capslock_fallback
+                    .then(|| by_suffix("::capslock"))
+                    .flatten()
+            })
+        }
+    }?;
+
+    LedDevice::open(root, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::notification::URGENCY_NORMAL;
+    use std::fs;
+
+    fn notification(id: u32, urgency: u8, timestamp: f64) -> Notification {
+        Notification {
+            id,
+            app_name: "test".to_string(),
+            app_icon: String::new(),
+            summary: "Summary".to_string(),
+            body: String::new(),
+            actions: Vec::new(),
+            urgency,
+            timestamp,
+            expire_timeout: -1,
+            desktop_entry: None,
+            image_path: None,
+            image_data: None,
+        }
+    }
+
+    fn write_led(root: &Path, name: &str, max_brightness: &str) {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("brightness"), "0\n").unwrap();
+        fs::write(dir.join("max_brightness"), max_brightness).unwrap();
+    }
+
+    #[test]
+    fn test_has_unseen_urgent() {
+        let restored = HashSet::from([3]);
+        let critical = notification(1, URGENCY_CRITICAL, 200.0);
+        let normal = notification(2, URGENCY_NORMAL, 200.0);
+        let restored_critical = notification(3, URGENCY_CRITICAL, 200.0);
+
+        assert!(has_unseen_urgent(&[critical.clone()], &restored, 0.0));
+        // Seen after it arrived
+        assert!(!has_unseen_urgent(&[critical], &restored, 300.0));
+        assert!(!has_unseen_urgent(&[normal], &restored, 0.0));
+        assert!(!has_unseen_urgent(&[restored_critical], &restored, 0.0));
+    }
+
+    #[test]
+    fn test_find_led() {
+        let root = std::env::temp_dir().join(format!("vibepanel-leds-{}", std::process::id()));
+        write_led(&root, "input3::capslock", "1\n");
+        write_led(&root, "input3::numlock", "1\n");
+
+        let auto = NotificationLedSettings {
+            device: None,
+            capslock_fallback: true,
+        };
+        let no_fallback = NotificationLedSettings {
+            device: None,
+            capslock_fallback: false,
+        };
+        let named = NotificationLedSettings {
+            device: Some("input3::numlock".to_string()),
+            capslock_fallback: false,
+        };
+
+        let capslock = find_led(&root, &auto).map(|led| led.name);
+        let none = find_led(&root, &no_fallback);
+        let numlock = find_led(&root, &named).map(|led| led.name);
+
+        write_led(&root, "platform::notification", "255\n");
+        let notification_led = find_led(&root, &no_fallback);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(capslock.as_deref(), Some("input3::capslock"));
+        assert!(none.is_none());
+        assert_eq!(numlock.as_deref(), Some("input3::numlock"));
+        let notification_led = notification_led.unwrap();
+        assert_eq!(notification_led.name, "platform::notification");
+        assert_eq!(notification_led.max_brightness, 255);
+    }
+}
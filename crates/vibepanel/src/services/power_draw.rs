@@ -0,0 +1,594 @@
+//! PowerDrawService - CPU power draw from RAPL energy counters.
+//!
+//! This service provides:
+//! - Periodic reads of the powercap RAPL zones
+//!   (`/sys/class/powercap/intel-rapl:*`, also used for AMD Zen CPUs), or
+//!   the `amd_energy` hwmon driver's socket counters when there are none
+//! - Watts per domain (package, cores, uncore, DRAM, platform) from the
+//!   energy difference between two reads
+//! - The package energy used since the bar started
+//!
+//! The counters are cumulative microjoules that wrap at
+//! `max_energy_range_uj`. Since CVE-2020-8694 most kernels make them
+//! readable by root only; the snapshot then carries an error saying so.
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+
+use gtk4::glib::{self, SourceId};
+use tracing::{debug, info};
+
+use super::callbacks::Callbacks;
+use super::sysfs::{read_trimmed, sorted_entries};
+use super::widget_settings::{SettingsId, WidgetSettings};
+use super::worker::WorkerPool;
+
+/// Default check interval in seconds.
+pub const DEFAULT_CHECK_INTERVAL: u64 = 2;
+
+const POWERCAP_PATH: &str = "/sys/class/powercap";
+const HWMON_PATH: &str = "/sys/class/hwmon";
+
+/// Microjoules in a watt-hour.
+const UJ_PER_WH: f64 = 3.6e9;
+
+/// What an energy counter measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Domain {
+    /// A whole CPU package (cores, caches, integrated GPU).
+    Package,
+    /// The CPU cores, part of the package.
+    Core,
+    /// The integrated GPU and uncore, part of the package.
+    Uncore,
+    /// Memory attached to a package.
+    Dram,
+    /// The whole platform (`psys`), on some Intel laptops.
+    Platform,
+}
+
+impl Domain {
+    /// Domain of a RAPL zone `name`, e.g. "package-0" or "dram".
+    fn from_zone_name(name: &str) -> Option<Self> {
+        match name {
+            n if n.starts_with("package") => Some(Domain::Package),
+            "core" => Some(Domain::Core),
+            "uncore" => Some(Domain::Uncore),
+            "dram" => Some(Domain::Dram),
+            "psys" => Some(Domain::Platform),
+            _ => None,
+        }
+    }
+
+    /// Human-readable domain name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Domain::Package => "Package",
+            Domain::Core => "Cores",
+            Domain::Uncore => "Uncore / GPU",
+            Domain::Dram => "DRAM",
+            Domain::Platform => "Platform",
+        }
+    }
+}
+
+/// One energy counter as read from sysfs.
+#[derive(Debug, Clone, PartialEq)]
+struct Counter {
+    /// Counter file, identifying the counter between reads.
+    path: PathBuf,
+    domain: Domain,
+    /// Socket the counter belongs to.
+    socket: u32,
+    /// Cumulative energy in microjoules.
+    energy_uj: u64,
+    /// Value the counter wraps at, if known.
+    max_range_uj: Option<u64>,
+}
+
+/// Power draw of one domain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainPower {
+    pub domain: Domain,
+    /// Socket the domain belongs to.
+    pub socket: u32,
+    /// Average draw since the previous read, in watts.
+    pub watts: f64,
+}
+
+/// Canonical snapshot of the power draw.
+#[derive(Debug, Clone, Default)]
+pub struct PowerDrawSnapshot {
+    /// Whether watts have been computed (needs two reads).
+    pub ready: bool,
+    /// Why no counters could be read, if so.
+    pub error: Option<String>,
+    /// Draw per domain, packages first.
+    pub domains: Vec<DomainPower>,
+    /// Package energy used since the bar started, in watt-hours.
+    pub session_wh: f64,
+}
+
+impl PowerDrawSnapshot {
+    /// Total draw of all CPU packages, in watts.
+    pub fn package_watts(&self) -> Option<f64> {
+        self.total(Domain::Package)
+    }
+
+    /// Total draw of `domain` over all sockets, `None` if not measured.
+    pub fn total(&self, domain: Domain) -> Option<f64> {
+        let mut watts = self
+            .domains
+            .iter()
+            .filter(|d| d.domain == domain)
+            .map(|d| d.watts)
+            .peekable();
+        watts.peek()?;
+        Some(watts.sum())
+    }
+}
+
+/// Shared, process-wide power draw service.
+pub struct PowerDrawService {
+    snapshot: RefCell<PowerDrawSnapshot>,
+    callbacks: Callbacks<PowerDrawSnapshot>,
+    /// Counters from the previous read and when they were taken.
+    previous: RefCell<Option<(Instant, Vec<Counter>)>>,
+    /// Package energy since the first read, in microjoules.
+    session_uj: Cell<f64>,
+    /// Read interval of each widget.
+    intervals: WidgetSettings<u64>,
+    /// Shortest interval, which the timer runs at.
+    check_interval: Cell<Option<u64>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent overlapping reads.
+    check_in_progress: Cell<bool>,
+}
+
+impl PowerDrawService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(PowerDrawSnapshot::default()),
+            callbacks: Callbacks::new(),
+            previous: RefCell::new(None),
+            session_uj: Cell::new(0.0),
+            intervals: WidgetSettings::new(),
+            check_interval: Cell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global PowerDrawService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<PowerDrawService> = PowerDrawService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&PowerDrawSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Register a widget's read interval; the shortest one is used.
+    pub fn configure(self: &Rc<Self>, check_interval: u64) -> SettingsId {
+        let id = self.intervals.add(check_interval.max(1));
+        self.apply_interval();
+        id
+    }
+
+    /// Forget a widget's interval; reads stop with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.intervals.remove(id) {
+            self.apply_interval();
+        }
+    }
+
+    /// Restart the timer at the shortest registered interval.
+    fn apply_interval(self: &Rc<Self>) {
+        let check_interval = self.intervals.merged();
+        if self.check_interval.get() == check_interval {
+            return;
+        }
+        self.check_interval.set(check_interval);
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let Some(check_interval) = check_interval else {
+            debug!("PowerDrawService: no widgets left, stopping");
+            return;
+        };
+        info!(
+            "PowerDrawService: reading energy counters every {}s",
+            check_interval
+        );
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(check_interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Trigger an immediate read of the energy counters.
+    pub fn refresh(&self) {
+        if self.check_in_progress.get() {
+            return;
+        }
+        self.check_in_progress.set(true);
+
+        WorkerPool::global().spawn(
+            || {
+                let counters = read_counters(Path::new(POWERCAP_PATH), Path::new(HWMON_PATH));
+                (Instant::now(), counters)
+            },
+            |result| {
+                let (now, counters) =
+                    result.unwrap_or_else(|e| (Instant::now(), Err(e.to_string())));
+                PowerDrawService::global().apply_counters(now, counters);
+            },
+        );
+    }
+
+    fn apply_counters(&self, now: Instant, counters: Result<Vec<Counter>, String>) {
+        self.check_in_progress.set(false);
+
+        let counters = match counters {
+            Ok(counters) => counters,
+            Err(e) => {
+                *self.previous.borrow_mut() = None;
+                let mut snapshot = self.snapshot.borrow_mut();
+                if snapshot.error.as_ref() == Some(&e) {
+                    return;
+                }
+                debug!("PowerDrawService: {}", e);
+                *snapshot = PowerDrawSnapshot {
+                    error: Some(e),
+                    ..PowerDrawSnapshot::default()
+                };
+                drop(snapshot);
+                self.notify();
+                return;
+            }
+        };
+
+        let previous = self.previous.replace(Some((now, counters.clone())));
+        let Some((then, previous)) = previous else {
+            debug!("PowerDrawService: found {} energy counters", counters.len());
+            return;
+        };
+        let seconds = now.duration_since(then).as_secs_f64();
+        let (domains, package_uj) = domain_power(&previous, &counters, seconds);
+        self.session_uj.set(self.session_uj.get() + package_uj);
+
+        *self.snapshot.borrow_mut() = PowerDrawSnapshot {
+            ready: true,
+            error: None,
+            domains,
+            session_wh: self.session_uj.get() / UJ_PER_WH,
+        };
+        self.notify();
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+impl Drop for PowerDrawService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Energy used between two reads of a counter, handling one wrap-around.
+fn energy_delta(previous: u64, current: u64, max_range: Option<u64>) -> Option<u64> {
+    if current >= previous {
+        Some(current - previous)
+    } else {
+        max_range.map(|max| max.saturating_sub(previous) + current)
+    }
+}
+
+/// Draw per domain between two reads `seconds` apart, and the package
+/// energy used in between (microjoules).
+///
+/// Counters missing from either read, or that went backwards without a
+/// known wrap value, are left out.
+fn domain_power(
+    previous: &[Counter],
+    current: &[Counter],
+    seconds: f64,
+) -> (Vec<DomainPower>, f64) {
+    let mut domains = Vec::new();
+    let mut package_uj = 0.0;
+    if seconds <= 0.0 {
+        return (domains, package_uj);
+    }
+
+    for counter in current {
+        let Some(delta) = previous
+            .iter()
+            .find(|p| p.path == counter.path)
+            .and_then(|p| energy_delta(p.energy_uj, counter.energy_uj, counter.max_range_uj))
+        else {
+            continue;
+        };
+        if counter.domain == Domain::Package {
+            package_uj += delta as f64;
+        }
+        domains.push(DomainPower {
+            domain: counter.domain,
+            socket: counter.socket,
+            watts: delta as f64 / 1e6 / seconds,
+        });
+    }
+
+    domains.sort_by_key(|d| (d.domain as u8, d.socket));
+    (domains, package_uj)
+}
+
+/// Read the RAPL powercap zones, or the `amd_energy` hwmon counters when
+/// there are none.
+fn read_counters(powercap_root: &Path, hwmon_root: &Path) -> Result<Vec<Counter>, String> {
+    let mut denied = false;
+    let mut counters = Vec::new();
+
+    for (socket, zone) in rapl_zones(powercap_root) {
+        let Some(domain) = read_trimmed(&zone.join("name"))
+            .as_deref()
+            .and_then(Domain::from_zone_name)
+        else {
+            continue;
+        };
+        let path = zone.join("energy_uj");
+        match read_u64(&path) {
+            Ok(energy_uj) => counters.push(Counter {
+                path,
+                domain,
+                socket,
+                energy_uj,
+                max_range_uj: read_u64(&zone.join("max_energy_range_uj")).ok(),
+            }),
+            Err(denied_here) => denied |= denied_here,
+        }
+    }
+
+    if counters.is_empty() {
+        for chip in sorted_entries(hwmon_root, "hwmon") {
+            if read_trimmed(&chip.join("name")).as_deref() != Some("amd_energy") {
+                continue;
+            }
+            for (path, label) in hwmon_energy_inputs(&chip) {
+                // "Esocket0" is a package; per-core "Ecore000" counters are skipped
+                let Some(socket) = label.strip_prefix("Esocket").and_then(|s| s.parse().ok())
+                else {
+                    continue;
+                };
+                match read_u64(&path) {
+                    Ok(energy_uj) => counters.push(Counter {
+                        path,
+                        domain: Domain::Package,
+                        socket,
+                        energy_uj,
+                        max_range_uj: None,
+                    }),
+                    Err(denied_here) => denied |= denied_here,
+                }
+            }
+        }
+    }
+
+    if counters.is_empty() {
+        return Err(if denied {
+            "energy counters are readable by root only (see the README for a udev rule)".to_string()
+        } else {
+            "no RAPL energy counters found".to_string()
+        });
+    }
+    Ok(counters)
+}
+
+/// Top-level `intel-rapl:<socket>` zones and their subzones
+/// (`intel-rapl:<socket>:<n>`), with the socket number.
+fn rapl_zones(powercap_root: &Path) -> Vec<(u32, PathBuf)> {
+    let mut zones: Vec<(Vec<u32>, PathBuf)> = fs::read_dir(powercap_root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let index = name
+                .strip_prefix("intel-rapl:")?
+                .split(':')
+                .map(|part| part.parse().ok())
+                .collect::<Option<Vec<u32>>>()?;
+            Some((index, entry.path()))
+        })
+        .collect();
+    zones.sort();
+    zones
+        .into_iter()
+        .map(|(index, path)| (index[0], path))
+        .collect()
+}
+
+/// `energy<n>_input` files of a hwmon chip with their labels.
+fn hwmon_energy_inputs(chip: &Path) -> Vec<(PathBuf, String)> {
+    let mut inputs: Vec<(u32, PathBuf)> = fs::read_dir(chip)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let index = name.strip_prefix("energy")?.strip_suffix("_input")?;
+            Some((index.parse().ok()?, entry.path()))
+        })
+        .collect();
+    inputs.sort_by_key(|(index, _)| *index);
+    inputs
+        .into_iter()
+        .filter_map(|(index, path)| {
+            let label = read_trimmed(&chip.join(format!("energy{}_label", index)))?;
+            Some((path, label))
+        })
+        .collect()
+}
+
+/// Read a counter; the error says whether it failed for lack of permission.
+fn read_u64(path: &Path) -> Result<u64, bool> {
+    match fs::read_to_string(path) {
+        Ok(value) => value.trim().parse().map_err(|_| false),
+        Err(e) => Err(e.kind() == ErrorKind::PermissionDenied),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(path: &str, domain: Domain, socket: u32, energy_uj: u64) -> Counter {
+        Counter {
+            path: PathBuf::from(path),
+            domain,
+            socket,
+            energy_uj,
+            max_range_uj: Some(262_143_328_850),
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_energy_delta() {
+        assert_eq!(energy_delta(100, 350, None), Some(250));
+        // Wrapped at the maximum range
+        assert_eq!(energy_delta(990, 40, Some(1000)), Some(50));
+        assert_eq!(energy_delta(990, 40, None), None);
+    }
+
+    #[test]
+    fn test_domain_power() {
+        let previous = vec![
+            counter("dram", Domain::Dram, 0, 1_000_000),
+            counter("pkg", Domain::Package, 0, 5_000_000),
+        ];
+        let current = vec![
+            counter("dram", Domain::Dram, 0, 3_000_000),
+            counter("pkg", Domain::Package, 0, 25_000_000),
+            // New since the previous read
+            counter("core", Domain::Core, 0, 7_000_000),
+        ];
+
+        let (domains, package_uj) = domain_power(&previous, &current, 2.0);
+        assert_eq!(
+            domains,
+            vec![
+                DomainPower {
+                    domain: Domain::Package,
+                    socket: 0,
+                    watts: 10.0,
+                },
+                DomainPower {
+                    domain: Domain::Dram,
+                    socket: 0,
+                    watts: 1.0,
+                },
+            ]
+        );
+        assert_eq!(package_uj, 20_000_000.0);
+    }
+
+    #[test]
+    fn test_read_counters() {
+        let root = std::env::temp_dir().join(format!("vibepanel-rapl-{}", std::process::id()));
+        let powercap = root.join("powercap");
+        let hwmon = root.join("hwmon");
+
+        write(&powercap.join("intel-rapl:0/name"), "package-0\n");
+        write(&powercap.join("intel-rapl:0/energy_uj"), "1234\n");
+        write(&powercap.join("intel-rapl:0/max_energy_range_uj"), "5000\n");
+        write(&powercap.join("intel-rapl:0:2/name"), "dram\n");
+        write(&powercap.join("intel-rapl:0:2/energy_uj"), "99\n");
+        // MMIO duplicates of the package zone are skipped
+        write(&powercap.join("intel-rapl-mmio:0/name"), "package-0\n");
+        write(&powercap.join("intel-rapl-mmio:0/energy_uj"), "1\n");
+
+        let counters = read_counters(&powercap, &hwmon).unwrap();
+        assert_eq!(counters.len(), 2);
+        assert_eq!(counters[0].domain, Domain::Package);
+        assert_eq!(counters[0].energy_uj, 1234);
+        assert_eq!(counters[0].max_range_uj, Some(5000));
+        assert_eq!(counters[1].domain, Domain::Dram);
+        assert_eq!(counters[1].max_range_uj, None);
+
+        // amd_energy socket counters when there are no RAPL zones
+        fs::remove_dir_all(&powercap).unwrap();
+        write(&hwmon.join("hwmon3/name"), "amd_energy\n");
+        write(&hwmon.join("hwmon3/energy1_input"), "500\n");
+        write(&hwmon.join("hwmon3/energy1_label"), "Ecore000\n");
+        write(&hwmon.join("hwmon3/energy17_input"), "9000\n");
+        write(&hwmon.join("hwmon3/energy17_label"), "Esocket0\n");
+        let counters = read_counters(&powercap, &hwmon).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(counters.len(), 1);
+        assert_eq!(counters[0].domain, Domain::Package);
+        assert_eq!(counters[0].energy_uj, 9000);
+
+        assert_eq!(
+            read_counters(&powercap, &hwmon).unwrap_err(),
+            "no RAPL energy counters found"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_totals() {
+        let snapshot = PowerDrawSnapshot {
+            ready: true,
+            error: None,
+            domains: vec![
+                DomainPower {
+                    domain: Domain::Package,
+                    socket: 0,
+                    watts: 12.5,
+                },
+                DomainPower {
+                    domain: Domain::Package,
+                    socket: 1,
+                    watts: 7.5,
+                },
+            ],
+            session_wh: 0.0,
+        };
+        assert_eq!(snapshot.package_watts(), Some(20.0));
+        assert_eq!(snapshot.total(Domain::Dram), None);
+    }
+}
@@ -15,7 +15,16 @@ use gtk4::prelude::ToVariant;
 use gtk4::prelude::*;
 use tracing::{error, warn};
 
-use super::callbacks::Callbacks;
+use super::callbacks::{CallbackId, Callbacks};
+use super::dbus_watchdog::{self, CallPolicy};
+
+// Thread-local singleton storage for PowerProfileService
+thread_local! {
+    static POWER_PROFILE_INSTANCE: RefCell<Option<Rc<PowerProfileService>>> = const { RefCell::new(None) };
+}
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "power-profiles";
 
 /// DBus constants for power-profiles-daemon.
 const BUS_NAME: &str = "net.hadess.PowerProfiles";
@@ -41,6 +50,45 @@ impl PowerProfileSnapshot {
             available_profiles: Vec::new(),
         }
     }
+
+    /// The profile after the current one, wrapping around (for cycling).
+    pub fn next_profile(&self) -> Option<&str> {
+        let profiles = &self.available_profiles;
+        let next = match self
+            .current_profile
+            .as_ref()
+            .and_then(|current| profiles.iter().position(|p| p == current))
+        {
+            Some(index) => (index + 1) % profiles.len(),
+            None => 0,
+        };
+        profiles.get(next).map(String::as_str)
+    }
+}
+
+/// Human-readable name for a profile ID, e.g. "power-saver" -> "Power Saver".
+pub fn profile_label(profile: &str) -> String {
+    profile
+        .split('-')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Icon name for a profile ID.
+pub fn profile_icon(profile: &str) -> &'static str {
+    match profile {
+        "performance" => "power-profile-performance-symbolic",
+        "power-saver" => "power-profile-power-saver-symbolic",
+        _ => "power-profile-balanced-symbolic",
+    }
 }
 
 /// Shared, process-wide power profile service.
@@ -67,23 +115,35 @@ impl PowerProfileService {
 
     /// Get the global PowerProfileService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<PowerProfileService> = PowerProfileService::new();
-        }
+        POWER_PROFILE_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(PowerProfileService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        POWER_PROFILE_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked whenever the power profile snapshot changes.
     /// The callback is always executed on the GLib main loop.
-    pub fn connect<F>(&self, callback: F)
+    pub fn connect<F>(&self, callback: F) -> CallbackId
     where
         F: Fn(&PowerProfileSnapshot) + 'static,
     {
-        self.callbacks.register(callback);
+        let id = self.callbacks.register(callback);
 
         let snapshot = self.snapshot.borrow().clone();
-        self.callbacks.notify(&snapshot);
+        self.callbacks.notify_single(id, &snapshot);
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
     }
 
     fn init_dbus(this: &Rc<Self>) {
@@ -250,6 +310,13 @@ impl PowerProfileService {
         self.snapshot.borrow().clone()
     }
 
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: PowerProfileSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+
     /// Request a profile change over DBus (non-blocking).
     ///
     /// Uses org.freedesktop.DBus.Properties.Set on ActiveProfile. Errors are
@@ -281,16 +348,16 @@ impl PowerProfileService {
             }
         }
 
-        connection.call(
-            Some(BUS_NAME),
+        dbus_watchdog::call(
+            WATCHDOG_SERVICE,
+            &connection,
+            BUS_NAME,
             OBJECT_PATH,
             "org.freedesktop.DBus.Properties",
             "Set",
             Some(&params),
-            None::<&glib::VariantTy>,
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            None,
+            CallPolicy::ACTION,
             move |res| {
                 if let Err(e) = res {
                     error!("Failed to set power profile '{}': {}", profile_string, e);
@@ -301,3 +368,41 @@ impl PowerProfileService {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(current: Option<&str>) -> PowerProfileSnapshot {
+        PowerProfileSnapshot {
+            available: true,
+            current_profile: current.map(str::to_string),
+            available_profiles: vec![
+                "power-saver".to_string(),
+                "balanced".to_string(),
+                "performance".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_next_profile_cycles() {
+        assert_eq!(
+            snapshot(Some("balanced")).next_profile(),
+            Some("performance")
+        );
+        assert_eq!(
+            snapshot(Some("performance")).next_profile(),
+            Some("power-saver")
+        );
+        assert_eq!(snapshot(None).next_profile(), Some("power-saver"));
+        assert_eq!(PowerProfileSnapshot::empty().next_profile(), None);
+    }
+
+    #[test]
+    fn test_profile_label() {
+        assert_eq!(profile_label("power-saver"), "Power Saver");
+        assert_eq!(profile_label("balanced"), "Balanced");
+        assert_eq!(profile_label("performance"), "Performance");
+    }
+}
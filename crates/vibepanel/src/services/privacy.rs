@@ -0,0 +1,510 @@
+//! PrivacyService - microphone, camera and screen-share usage.
+//!
+//! This service provides:
+//! - Recording and screen-cast streams from PipeWire (`pw-dump`): running
+//!   `Stream/Input/Audio` nodes are microphone users, running
+//!   `Stream/Input/Video` nodes are screen-share users unless they are
+//!   linked to a camera node
+//! - Processes holding a V4L2 camera open (`/proc/*/fd` links to
+//!   `/dev/video*`), for apps that bypass PipeWire
+//!
+//! Reads are polled on the shared worker pool; watching PipeWire for changes
+//! would mean linking libpipewire.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+use gtk4::glib::{self, SourceId};
+use serde_json::Value;
+use tracing::{debug, info};
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::widget_settings::{SettingsId, WidgetSettings};
+use super::worker::WorkerPool;
+
+/// Default check interval in seconds.
+pub const DEFAULT_CHECK_INTERVAL: u64 = 2;
+
+const PROC_PATH: &str = "/proc";
+
+/// Processes that open cameras on behalf of other apps; those apps show up
+/// as PipeWire streams instead.
+const CAMERA_BROKERS: &[&str] = &["pipewire", "wireplumber"];
+
+/// A privacy-sensitive device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Device {
+    Microphone,
+    Camera,
+    ScreenShare,
+}
+
+impl Device {
+    /// All devices, in display order.
+    pub const ALL: [Device; 3] = [Device::Microphone, Device::Camera, Device::ScreenShare];
+
+    /// Human-readable name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Device::Microphone => "Microphone",
+            Device::Camera => "Camera",
+            Device::ScreenShare => "Screen sharing",
+        }
+    }
+
+    /// Parse a config name (`microphone`, `camera`, `screen_share`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "microphone" | "mic" => Some(Device::Microphone),
+            "camera" => Some(Device::Camera),
+            "screen_share" | "screenshare" => Some(Device::ScreenShare),
+            _ => None,
+        }
+    }
+}
+
+/// An application using a device.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeviceUser {
+    /// Device in use.
+    pub device: Device,
+    /// Application name, e.g. "Firefox" or the process name.
+    pub app: String,
+    /// Process ID, when known.
+    pub pid: Option<u32>,
+}
+
+/// Canonical snapshot of device usage.
+#[derive(Debug, Clone, Default)]
+pub struct PrivacySnapshot {
+    /// Whether a read has completed.
+    pub ready: bool,
+    /// Current users, sorted by device then app.
+    pub users: Vec<DeviceUser>,
+}
+
+impl PrivacySnapshot {
+    /// Whether any application is using `device`.
+    pub fn in_use(&self, device: Device) -> bool {
+        self.users.iter().any(|u| u.device == device)
+    }
+
+    /// Applications using `device`.
+    pub fn users_of(&self, device: Device) -> impl Iterator<Item = &DeviceUser> {
+        self.users.iter().filter(move |u| u.device == device)
+    }
+}
+
+/// Shared, process-wide privacy service.
+pub struct PrivacyService {
+    snapshot: RefCell<PrivacySnapshot>,
+    callbacks: Callbacks<PrivacySnapshot>,
+    /// Check interval of each widget.
+    intervals: WidgetSettings<u64>,
+    /// Shortest interval, which the timer runs at.
+    check_interval: Cell<Option<u64>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent overlapping reads.
+    check_in_progress: Cell<bool>,
+}
+
+impl PrivacyService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(PrivacySnapshot::default()),
+            callbacks: Callbacks::new(),
+            intervals: WidgetSettings::new(),
+            check_interval: Cell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global PrivacyService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<PrivacyService> = PrivacyService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&PrivacySnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        self.callbacks
+            .notify_single(id, &self.snapshot.borrow().clone());
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> PrivacySnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Register a widget's check interval; the shortest one is used.
+    pub fn configure(self: &Rc<Self>, check_interval: u64) -> SettingsId {
+        let id = self.intervals.add(check_interval.max(1));
+        self.apply_interval();
+        id
+    }
+
+    /// Forget a widget's interval; checks stop with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.intervals.remove(id) {
+            self.apply_interval();
+        }
+    }
+
+    /// Restart the timer at the shortest registered interval.
+    fn apply_interval(self: &Rc<Self>) {
+        let check_interval = self.intervals.merged();
+        if self.check_interval.get() == check_interval {
+            return;
+        }
+        self.check_interval.set(check_interval);
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let Some(check_interval) = check_interval else {
+            debug!("PrivacyService: no widgets left, stopping");
+            return;
+        };
+        info!(
+            "PrivacyService: checking device usage every {}s",
+            check_interval
+        );
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(check_interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Trigger an immediate check of device usage.
+    pub fn refresh(&self) {
+        if self.check_in_progress.get() {
+            return;
+        }
+        self.check_in_progress.set(true);
+
+        WorkerPool::global().spawn(read_users, |users| {
+            PrivacyService::global().apply_users(users.ok())
+        });
+    }
+
+    fn apply_users(&self, users: Option<Vec<DeviceUser>>) {
+        self.check_in_progress.set(false);
+        let Some(users) = users else {
+            return;
+        };
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if snapshot.ready && snapshot.users == users {
+                return;
+            }
+            debug!("PrivacyService: {} device users", users.len());
+            snapshot.ready = true;
+            snapshot.users = users;
+        }
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+impl Drop for PrivacyService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Collect every device user from PipeWire and `/proc`.
+fn read_users() -> Vec<DeviceUser> {
+    let mut users = match pipewire_dump() {
+        Some(dump) => parse_pw_dump(&dump),
+        None => Vec::new(),
+    };
+    users.extend(camera_users(Path::new(PROC_PATH)));
+
+    // One entry per app and device, however many streams it has open
+    users.sort();
+    users.dedup_by(|a, b| a.device == b.device && a.app == b.app);
+    users
+}
+
+/// Run `pw-dump` and parse its JSON output.
+fn pipewire_dump() -> Option<Value> {
+    let output = Command::new("pw-dump")
+        .arg("--no-colors")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => serde_json::from_slice(&output.stdout).ok(),
+        Ok(output) => {
+            debug!("PrivacyService: pw-dump exited with {}", output.status);
+            None
+        }
+        Err(e) => {
+            debug!("PrivacyService: pw-dump unavailable: {}", e);
+            None
+        }
+    }
+}
+
+/// Extract microphone and screen-share users from `pw-dump` output.
+fn parse_pw_dump(dump: &Value) -> Vec<DeviceUser> {
+    let Some(objects) = dump.as_array() else {
+        return Vec::new();
+    };
+    let own_pid = std::process::id();
+
+    let mut nodes: HashMap<u64, &Value> = HashMap::new();
+    // Node IDs feeding each input node
+    let mut sources: HashMap<u64, Vec<u64>> = HashMap::new();
+    for object in objects {
+        let (Some(id), Some(kind)) = (object["id"].as_u64(), object["type"].as_str()) else {
+            continue;
+        };
+        let info = &object["info"];
+        match kind {
+            "PipeWire:Interface:Node" => {
+                nodes.insert(id, info);
+            }
+            "PipeWire:Interface:Link" => {
+                if let (Some(output), Some(input)) = (
+                    info["output-node-id"].as_u64(),
+                    info["input-node-id"].as_u64(),
+                ) {
+                    sources.entry(input).or_default().push(output);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let is_camera = |id: &u64| {
+        nodes.get(id).is_some_and(|info| {
+            let props = &info["props"];
+            props["media.class"].as_str() == Some("Video/Source")
+                && (props["device.api"].is_string() || props["media.role"] == "Camera")
+        })
+    };
+
+    let mut users = Vec::new();
+    for (id, info) in &nodes {
+        if info["state"].as_str() != Some("running") {
+            continue;
+        }
+        let props = &info["props"];
+        // Level meters (pavucontrol, our own OSD) aren't recording
+        if props["stream.monitor"].as_bool() == Some(true) {
+            continue;
+        }
+        let device = match props["media.class"].as_str() {
+            Some("Stream/Input/Audio") => Device::Microphone,
+            Some("Stream/Input/Video") => {
+                let from_camera = sources
+                    .get(id)
+                    .is_some_and(|outputs| outputs.iter().any(is_camera));
+                if from_camera {
+                    Device::Camera
+                } else {
+                    Device::ScreenShare
+                }
+            }
+            _ => continue,
+        };
+        let pid = prop_u32(&props["application.process.id"]);
+        if pid == Some(own_pid) {
+            continue;
+        }
+        let app = [
+            "application.name",
+            "application.process.binary",
+            "node.name",
+        ]
+        .iter()
+        .find_map(|key| props[*key].as_str().filter(|s| !s.is_empty()))
+        .unwrap_or("Unknown");
+        users.push(DeviceUser {
+            device,
+            app: app.to_string(),
+            pid,
+        });
+    }
+    users
+}
+
+/// PipeWire properties hold numbers either as JSON numbers or strings.
+fn prop_u32(value: &Value) -> Option<u32> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str()?.parse().ok())
+        .and_then(|v| u32::try_from(v).ok())
+}
+
+/// Processes with a `/dev/video*` device open, from `<proc_root>/*/fd`.
+///
+/// Only processes we may inspect (our own user's) are found.
+fn camera_users(proc_root: &Path) -> Vec<DeviceUser> {
+    let own_pid = std::process::id();
+    let mut pids = HashSet::new();
+
+    for entry in fs::read_dir(proc_root).into_iter().flatten().flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == own_pid {
+            continue;
+        }
+        let has_camera = fs::read_dir(entry.path().join("fd"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|fd| fs::read_link(fd.path()).ok())
+            .any(|target| target.to_string_lossy().starts_with("/dev/video"));
+        if has_camera {
+            pids.insert(pid);
+        }
+    }
+
+    pids.into_iter()
+        .filter_map(|pid| {
+            let comm = fs::read_to_string(proc_root.join(pid.to_string()).join("comm")).ok()?;
+            let app = comm.trim();
+            if CAMERA_BROKERS.contains(&app) {
+                return None;
+            }
+            Some(DeviceUser {
+                device: Device::Camera,
+                app: app.to_string(),
+                pid: Some(pid),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pw_dump() {
+        let dump = json!([
+            { "id": 40, "type": "PipeWire:Interface:Node", "info": {
+                "state": "running",
+                "props": { "media.class": "Stream/Input/Audio", "application.name": "Firefox", "application.process.id": 1234 }
+            }},
+            { "id": 41, "type": "PipeWire:Interface:Node", "info": {
+                "state": "idle",
+                "props": { "media.class": "Stream/Input/Audio", "application.name": "Discord" }
+            }},
+            { "id": 42, "type": "PipeWire:Interface:Node", "info": {
+                "state": "running",
+                "props": { "media.class": "Stream/Input/Audio", "application.name": "pavucontrol", "stream.monitor": true }
+            }},
+            { "id": 50, "type": "PipeWire:Interface:Node", "info": {
+                "state": "running",
+                "props": { "media.class": "Video/Source", "device.api": "v4l2" }
+            }},
+            { "id": 51, "type": "PipeWire:Interface:Node", "info": {
+                "state": "running",
+                "props": { "media.class": "Stream/Input/Video", "application.process.binary": "chromium", "application.process.id": "99" }
+            }},
+            { "id": 60, "type": "PipeWire:Interface:Node", "info": {
+                "state": "running",
+                "props": { "media.class": "Stream/Input/Video", "node.name": "obs" }
+            }},
+            { "id": 70, "type": "PipeWire:Interface:Link", "info": {
+                "output-node-id": 50, "input-node-id": 51
+            }}
+        ]);
+
+        let mut users = parse_pw_dump(&dump);
+        users.sort();
+        assert_eq!(
+            users,
+            vec![
+                DeviceUser {
+                    device: Device::Microphone,
+                    app: "Firefox".to_string(),
+                    pid: Some(1234),
+                },
+                DeviceUser {
+                    device: Device::Camera,
+                    app: "chromium".to_string(),
+                    pid: Some(99),
+                },
+                DeviceUser {
+                    device: Device::ScreenShare,
+                    app: "obs".to_string(),
+                    pid: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_camera_users() {
+        let root = std::env::temp_dir().join(format!("vibepanel-privacy-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (pid, comm, target) in [
+            ("100", "zoom", "/dev/video0"),
+            ("200", "pipewire", "/dev/video0"),
+            ("300", "bash", "/dev/null"),
+        ] {
+            let fd = root.join(pid).join("fd");
+            fs::create_dir_all(&fd).unwrap();
+            fs::write(root.join(pid).join("comm"), format!("{}\n", comm)).unwrap();
+            std::os::unix::fs::symlink(target, fd.join("3")).unwrap();
+        }
+
+        let users = camera_users(&root);
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(
+            users,
+            vec![DeviceUser {
+                device: Device::Camera,
+                app: "zoom".to_string(),
+                pid: Some(100),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_device_from_name() {
+        assert_eq!(Device::from_name("mic"), Some(Device::Microphone));
+        assert_eq!(Device::from_name("screen_share"), Some(Device::ScreenShare));
+        assert_eq!(Device::from_name("speaker"), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! ProxyService - switch a configured system proxy on and off.
+//!
+//! Turning the proxy on:
+//! - Sets `org.gnome.system.proxy` to manual mode with the proxy's host and
+//!   port (HTTP/HTTPS or SOCKS), which GLib/GTK apps and browsers follow live
+//! - Writes an `environment.d` drop-in with `http_proxy` and friends, picked
+//!   up by the systemd user manager for new sessions and user units
+//!
+//! Turning it off sets the GNOME mode back to `none` and removes the drop-in.
+//! When the GNOME schema isn't installed only the drop-in is managed.
+//!
+//! The state follows external changes to the GNOME settings (e.g. from
+//! GNOME Settings or `gsettings`).
+
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use gtk4::gio;
+use gtk4::prelude::*;
+use tracing::{debug, warn};
+use vibepanel_core::proxy::{ProxyKind, ProxySettings};
+
+use super::actions::{ActionKind, Actions};
+use super::callbacks::Callbacks;
+
+const PROXY_SCHEMA: &str = "org.gnome.system.proxy";
+
+/// File name of the drop-in under `~/.config/environment.d`.
+const DROP_IN_NAME: &str = "60-vibepanel-proxy.conf";
+
+/// Canonical snapshot of proxy state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxySnapshot {
+    /// Whether a manual proxy is in effect.
+    pub enabled: bool,
+    /// Active proxy as `host:port`, when enabled.
+    pub host: Option<String>,
+}
+
+/// Shared, process-wide proxy service.
+pub struct ProxyService {
+    /// GNOME proxy settings, `None` when the schema isn't installed.
+    settings: Option<gio::Settings>,
+    /// Schema and child schemas watched for external changes.
+    watched: Vec<gio::Settings>,
+    /// Proxy applied from this process (used when there is no schema).
+    applied: RefCell<Option<ProxySettings>>,
+    snapshot: RefCell<ProxySnapshot>,
+    callbacks: Callbacks<ProxySnapshot>,
+}
+
+impl ProxyService {
+    fn new() -> Rc<Self> {
+        let settings = gio::SettingsSchemaSource::default()
+            .and_then(|source| source.lookup(PROXY_SCHEMA, true))
+            .map(|_| gio::Settings::new(PROXY_SCHEMA));
+        if settings.is_none() {
+            debug!(
+                "ProxyService: {} schema not installed, managing environment.d only",
+                PROXY_SCHEMA
+            );
+        }
+
+        // Any change under the schema (mode, hosts, ports) may change the
+        // displayed state. Child schemas need their own handlers.
+        let watched = settings
+            .iter()
+            .flat_map(|settings| {
+                [
+                    settings.clone(),
+                    settings.child("http"),
+                    settings.child("socks"),
+                ]
+            })
+            .collect();
+
+        let service = Rc::new(Self {
+            settings,
+            watched,
+            applied: RefCell::new(None),
+            snapshot: RefCell::new(ProxySnapshot {
+                enabled: false,
+                host: None,
+            }),
+            callbacks: Callbacks::new(),
+        });
+
+        for settings in &service.watched {
+            let service_weak = Rc::downgrade(&service);
+            settings.connect_changed(None, move |_, _| {
+                if let Some(service) = service_weak.upgrade() {
+                    service.refresh();
+                }
+            });
+        }
+
+        service.refresh();
+        service
+    }
+
+    /// Get the global ProxyService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<ProxyService> = ProxyService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the proxy state changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&ProxySnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        self.callbacks.notify(&self.snapshot.borrow());
+    }
+
+    /// Current snapshot.
+    pub fn snapshot(&self) -> ProxySnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Switch to `proxy`, or back to a direct connection with `None`.
+    ///
+    /// In kiosk mode nothing changes and the current state is re-sent, so a
+    /// toggle that asked for the change flips back.
+    pub fn set_proxy(&self, proxy: Option<&ProxySettings>) {
+        let what = if proxy.is_some() {
+            "enable proxy"
+        } else {
+            "disable proxy"
+        };
+        if Actions::global()
+            .permit(ActionKind::Settings, what)
+            .is_err()
+        {
+            self.callbacks.notify(&self.snapshot.borrow());
+            return;
+        }
+
+        if let Some(ref settings) = self.settings
+            && let Err(e) = apply_gnome_settings(settings, proxy)
+        {
+            warn!("ProxyService: failed to update {}: {}", PROXY_SCHEMA, e);
+        }
+
+        let result = match proxy {
+            Some(proxy) => write_drop_in(&proxy.environment_d()),
+            None => remove_drop_in(),
+        };
+        if let Err(e) = result {
+            warn!(
+                "ProxyService: failed to update environment.d drop-in: {}",
+                e
+            );
+        }
+
+        *self.applied.borrow_mut() = proxy.cloned();
+        self.refresh();
+    }
+
+    /// Re-read the state and notify if it changed.
+    fn refresh(&self) {
+        let snapshot = match self.settings {
+            Some(ref settings) => read_gnome_settings(settings),
+            None => ProxySnapshot {
+                enabled: drop_in_path().is_some_and(|path| path.exists()),
+                host: self.applied.borrow().as_ref().map(|p| p.url.host_port()),
+            },
+        };
+
+        if *self.snapshot.borrow() == snapshot {
+            return;
+        }
+        debug!("ProxyService: {:?}", snapshot);
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+/// State of the GNOME proxy settings.
+fn read_gnome_settings(settings: &gio::Settings) -> ProxySnapshot {
+    if settings.string("mode") != "manual" {
+        return ProxySnapshot {
+            enabled: false,
+            host: None,
+        };
+    }
+
+    let host = ["http", "socks"].into_iter().find_map(|child| {
+        let child = settings.child(child);
+        let host = child.string("host");
+        (!host.is_empty()).then(|| format!("{}:{}", host, child.int("port")))
+    });
+    ProxySnapshot {
+        enabled: true,
+        host,
+    }
+}
+
+/// Point the GNOME proxy settings at `proxy`, or turn them off.
+fn apply_gnome_settings(
+    settings: &gio::Settings,
+    proxy: Option<&ProxySettings>,
+) -> Result<(), gtk4::glib::BoolError> {
+    let Some(proxy) = proxy else {
+        return settings.set_string("mode", "none");
+    };
+
+    let url = &proxy.url;
+    let (active, inactive): (&[&str], &[&str]) = match url.kind() {
+        ProxyKind::Http => (&["http", "https"], &["socks"]),
+        ProxyKind::Socks => (&["socks"], &["http", "https"]),
+    };
+    for child in active {
+        let child = settings.child(child);
+        child.set_string("host", &url.host)?;
+        child.set_int("port", i32::from(url.port))?;
+    }
+    // Leftover hosts from another proxy would otherwise still apply
+    for child in inactive {
+        settings.child(child).set_string("host", "")?;
+    }
+
+    let ignore_hosts: Vec<&str> = proxy.ignore_hosts.iter().map(String::as_str).collect();
+    if !ignore_hosts.is_empty() {
+        settings.set_strv("ignore-hosts", ignore_hosts.as_slice())?;
+    }
+    settings.set_string("mode", "manual")
+}
+
+/// `$XDG_CONFIG_HOME/environment.d/` drop-in path (falls back to `~/.config`).
+fn drop_in_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("environment.d").join(DROP_IN_NAME))
+}
+
+fn write_drop_in(contents: &str) -> io::Result<()> {
+    let path = drop_in_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, contents)
+}
+
+fn remove_drop_in() -> io::Result<()> {
+    let Some(path) = drop_in_path() else {
+        return Ok(());
+    };
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_kiosk_refuses_proxy_change() {
+        Actions::global().enable_kiosk();
+        let service = ProxyService::new();
+        let before = service.snapshot();
+        let notified = Rc::new(Cell::new(0));
+        let notified_clone = Rc::clone(&notified);
+        service.connect(move |_| notified_clone.set(notified_clone.get() + 1));
+        let baseline = notified.get();
+
+        service.set_proxy(None);
+        assert_eq!(service.snapshot(), before);
+        // The unchanged state is re-sent so the toggle flips back
+        assert_eq!(notified.get(), baseline + 1);
+    }
+}
@@ -0,0 +1,271 @@
+//! RemoteModulesService - shared state for out-of-process widget modules.
+//!
+//! Keeps the latest content for every remote slot id, as sent by external
+//! modules over the control socket (see `module_ipc`). `remote` widgets
+//! subscribe to snapshot updates and forward user input back to the owning
+//! module's connection.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use tracing::debug;
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::module_ipc::{ModuleEvent, ModuleRequest};
+use super::stream_server::{ClientId, Connection};
+
+/// Snapshot of a single remote widget slot.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteModuleSnapshot {
+    /// Slot id the module writes to (matches the widget's `id` option).
+    pub id: String,
+    /// Whether a module currently owns this slot and wants it shown.
+    pub visible: bool,
+    /// Label text.
+    pub label: Option<String>,
+    /// Icon name (logical or GTK icon name).
+    pub icon: Option<String>,
+    /// Tooltip text.
+    pub tooltip: Option<String>,
+}
+
+/// Internal per-slot state.
+struct Slot {
+    snapshot: RemoteModuleSnapshot,
+    owner: Option<ClientId>,
+}
+
+impl Slot {
+    /// Whether `client` may change this slot: the first client to update it
+    /// owns it until it disconnects.
+    fn accepts(&self, client: ClientId) -> bool {
+        self.owner.is_none_or(|owner| owner == client)
+    }
+
+    /// Apply an update from `client`, which becomes the owner.
+    ///
+    /// Omitted fields keep their value; an empty tooltip removes it.
+    fn update(
+        &mut self,
+        client: ClientId,
+        label: Option<String>,
+        icon: Option<String>,
+        tooltip: Option<String>,
+    ) {
+        self.owner = Some(client);
+        self.snapshot.visible = true;
+        if label.is_some() {
+            self.snapshot.label = label;
+        }
+        if icon.is_some() {
+            self.snapshot.icon = icon;
+        }
+        if let Some(tooltip) = tooltip {
+            self.snapshot.tooltip = (!tooltip.is_empty()).then_some(tooltip);
+        }
+    }
+}
+
+/// Shared, process-wide remote module service.
+pub struct RemoteModulesService {
+    slots: RefCell<HashMap<String, Slot>>,
+    callbacks: Callbacks<RemoteModuleSnapshot>,
+    /// Connections of modules owning a slot, for sending them events.
+    modules: RefCell<HashMap<ClientId, Connection>>,
+}
+
+impl RemoteModulesService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            slots: RefCell::new(HashMap::new()),
+            callbacks: Callbacks::new(),
+            modules: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Get the global RemoteModulesService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<RemoteModulesService> = RemoteModulesService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback for slot updates.
+    ///
+    /// The callback receives snapshots for every slot; widgets filter by id.
+    /// It is immediately invoked with the current state of all known slots.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&RemoteModuleSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        let snapshots: Vec<_> = self
+            .slots
+            .borrow()
+            .values()
+            .map(|slot| slot.snapshot.clone())
+            .collect();
+        for snapshot in &snapshots {
+            self.callbacks.notify_single(id, snapshot);
+        }
+        id
+    }
+
+    /// Unregister a previously connected callback.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Forward a click on a remote widget to its owning module.
+    pub fn send_click(&self, id: &str, button: u32) {
+        self.send_event(
+            id,
+            ModuleEvent::Click {
+                id: id.to_string(),
+                button,
+            },
+        );
+    }
+
+    /// Forward a scroll on a remote widget to its owning module.
+    pub fn send_scroll(&self, id: &str, dx: f64, dy: f64) {
+        self.send_event(
+            id,
+            ModuleEvent::Scroll {
+                id: id.to_string(),
+                dx,
+                dy,
+            },
+        );
+    }
+
+    fn send_event(&self, id: &str, event: ModuleEvent) {
+        let owner = self.slots.borrow().get(id).and_then(|slot| slot.owner);
+        let connection = owner.and_then(|owner| self.modules.borrow().get(&owner).cloned());
+        let Some(connection) = connection else {
+            debug!("RemoteModulesService: no module owns slot '{}'", id);
+            return;
+        };
+        connection.send(&event.to_wire());
+    }
+
+    /// Apply a `module` command received on `connection`.
+    ///
+    /// Fails if another module owns the slot.
+    pub fn handle_request(
+        &self,
+        connection: &Connection,
+        request: ModuleRequest,
+    ) -> Result<(), String> {
+        let client = connection.id();
+        let snapshot = {
+            let mut slots = self.slots.borrow_mut();
+            match request {
+                ModuleRequest::Update {
+                    id,
+                    label,
+                    icon,
+                    tooltip,
+                } => {
+                    let slot = slots.entry(id.clone()).or_insert_with(|| Slot {
+                        snapshot: RemoteModuleSnapshot {
+                            id: id.clone(),
+                            ..Default::default()
+                        },
+                        owner: None,
+                    });
+                    if !slot.accepts(client) {
+                        debug!(
+                            "RemoteModulesService: refusing update for '{}' from {:?}",
+                            id, client
+                        );
+                        return Err(format!("another module owns '{}'", id));
+                    }
+                    slot.update(client, label, icon, tooltip);
+                    self.modules
+                        .borrow_mut()
+                        .entry(client)
+                        .or_insert_with(|| connection.clone());
+                    slot.snapshot.clone()
+                }
+                ModuleRequest::Hide { id } => {
+                    let Some(slot) = slots.get_mut(&id) else {
+                        return Ok(());
+                    };
+                    if !slot.accepts(client) {
+                        debug!(
+                            "RemoteModulesService: refusing hide for '{}' from {:?}",
+                            id, client
+                        );
+                        return Err(format!("another module owns '{}'", id));
+                    }
+                    slot.snapshot.visible = false;
+                    slot.snapshot.clone()
+                }
+            }
+        };
+        self.callbacks.notify(&snapshot);
+        Ok(())
+    }
+
+    /// Release the slots owned by a control connection that closed.
+    pub fn handle_disconnect(&self, client: ClientId) {
+        if self.modules.borrow_mut().remove(&client).is_none() {
+            return;
+        }
+        let cleared: Vec<RemoteModuleSnapshot> = {
+            let mut slots = self.slots.borrow_mut();
+            let ids: Vec<String> = slots
+                .iter()
+                .filter(|(_, slot)| slot.owner == Some(client))
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| slots.remove(&id))
+                .map(|slot| RemoteModuleSnapshot {
+                    id: slot.snapshot.id,
+                    ..Default::default()
+                })
+                .collect()
+        };
+        for snapshot in &cleared {
+            debug!("RemoteModulesService: slot '{}' released", snapshot.id);
+            self.callbacks.notify(snapshot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_update() {
+        let owner = ClientId(1);
+        let other = ClientId(2);
+        let mut slot = Slot {
+            snapshot: RemoteModuleSnapshot::default(),
+            owner: None,
+        };
+        assert!(slot.accepts(other));
+
+        slot.update(
+            owner,
+            Some("21°C".to_string()),
+            None,
+            Some("Sunny".to_string()),
+        );
+        assert!(slot.accepts(owner));
+        assert!(!slot.accepts(other));
+        assert!(slot.snapshot.visible);
+        assert_eq!(slot.snapshot.tooltip.as_deref(), Some("Sunny"));
+
+        // Omitted fields are kept, an empty tooltip clears it
+        slot.update(owner, None, None, Some(String::new()));
+        assert_eq!(slot.snapshot.label.as_deref(), Some("21°C"));
+        assert_eq!(slot.snapshot.tooltip, None);
+    }
+}
@@ -0,0 +1,626 @@
+//! RemoteStatsService - CPU, memory and temperature of other machines over SSH.
+//!
+//! Widgets with `source = "ssh:<host>"` subscribe here instead of to the
+//! local `SystemService` / `TemperatureService`. Each sample runs one small
+//! shell script on the host (`ssh -o BatchMode=yes <host> sh -c '...'`) that
+//! prints `/proc/stat`, `/proc/meminfo`, `/proc/loadavg` and the hwmon /
+//! thermal zone temperatures, so the host only needs a POSIX shell and a
+//! Linux `/proc`. Authentication must work without a prompt (keys or an
+//! agent).
+//!
+//! There is one timer per host, running at the shortest interval its
+//! subscribers asked for; it stops once the last subscriber is gone (e.g.
+//! with a rebuilt bar). Snapshots are published per host, and a subscriber
+//! only hears about the host it connected to.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+use gtk4::glib::{self, SourceId};
+use tracing::{debug, trace, warn};
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::event_bus::EventBus;
+use super::system::SystemSnapshot;
+use super::temperature::{Sensor, TemperatureSnapshot};
+use super::worker::WorkerPool;
+
+/// Shortest interval between samples of one host, in seconds; every sample
+/// is an SSH round trip.
+const MIN_REMOTE_INTERVAL_SECS: u32 = 2;
+
+/// Seconds to wait for the SSH connection before giving up.
+const SSH_CONNECT_TIMEOUT: u32 = 10;
+
+/// Script run on the remote host. It must not contain single quotes, as it
+/// is passed to `sh -c '...'` through the remote login shell.
+const SAMPLE_SCRIPT: &str = r#"
+echo @stat; grep ^cpu /proc/stat
+echo @meminfo; cat /proc/meminfo
+echo @loadavg; cat /proc/loadavg
+echo @sensors
+for d in /sys/class/hwmon/hwmon*; do
+  for f in "$d"/temp*_input; do
+    [ -r "$f" ] || continue
+    i=${f##*/temp}; i=${i%_input}
+    n=$(cat "$d/name" 2>/dev/null || echo "${d##*/}")
+    l=$(cat "$d/temp${i}_label" 2>/dev/null || echo "temp$i")
+    printf "%s: %s\t%s\t%s\n" "$n" "$l" "$(cat "$f" 2>/dev/null)" "$(cat "$d/temp${i}_crit" 2>/dev/null)"
+  done
+done
+for z in /sys/class/thermal/thermal_zone*; do
+  [ -r "$z/temp" ] || continue
+  printf "%s\t%s\t\n" "$(cat "$z/type" 2>/dev/null || echo "${z##*/}")" "$(cat "$z/temp" 2>/dev/null)"
+done
+"#;
+
+/// Canonical snapshot of one remote host.
+#[derive(Debug, Clone)]
+pub struct RemoteStatsSnapshot {
+    /// SSH destination the stats come from.
+    pub host: String,
+    /// CPU, memory and load; network fields and interfaces stay empty.
+    pub system: SystemSnapshot,
+    /// Temperature sensors of the host.
+    pub temperatures: TemperatureSnapshot,
+    /// Why the last sample failed, if it did.
+    pub error: Option<String>,
+}
+
+impl RemoteStatsSnapshot {
+    fn unknown(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            system: SystemSnapshot::unknown(),
+            temperatures: TemperatureSnapshot::default(),
+            error: None,
+        }
+    }
+}
+
+/// Parse a widget's `source` option: `None` for this machine, or the SSH
+/// destination for `ssh:<host>`.
+pub fn parse_source(value: &str) -> Result<Option<String>, String> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("local") {
+        return Ok(None);
+    }
+    match value.strip_prefix("ssh:").map(str::trim) {
+        Some(host) if !host.is_empty() && !host.starts_with('-') => Ok(Some(host.to_string())),
+        _ => Err(format!(
+            "invalid source '{}', expected \"local\" or \"ssh:<host>\"",
+            value
+        )),
+    }
+}
+
+/// Sampling state of one host.
+struct RemoteHost {
+    snapshot: RefCell<RemoteStatsSnapshot>,
+    /// Subscribers and the interval each asked for.
+    subscribers: RefCell<Vec<(CallbackId, u32)>>,
+    /// Interval the timer currently runs at.
+    interval: Cell<u32>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent overlapping samples when SSH is slow.
+    sample_in_progress: Cell<bool>,
+    /// CPU times of the previous sample, for usage over the interval.
+    previous_cpu: RefCell<Option<Vec<CpuTimes>>>,
+}
+
+impl RemoteHost {
+    /// Shortest interval asked for by a live subscriber, forgetting
+    /// subscriptions dropped since; `None` when there are none left.
+    fn wanted_interval(&self) -> Option<u32> {
+        let bus = EventBus::global();
+        let mut subscribers = self.subscribers.borrow_mut();
+        subscribers.retain(|(id, _)| bus.is_subscribed(*id));
+        subscribers.iter().map(|(_, interval)| *interval).min()
+    }
+}
+
+/// Shared, process-wide remote stats service.
+pub struct RemoteStatsService {
+    hosts: RefCell<HashMap<String, Rc<RemoteHost>>>,
+    callbacks: Callbacks<RemoteStatsSnapshot>,
+}
+
+impl RemoteStatsService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            hosts: RefCell::new(HashMap::new()),
+            callbacks: Callbacks::new(),
+        })
+    }
+
+    /// Get the global RemoteStatsService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<RemoteStatsService> = RemoteStatsService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback for `host`'s stats, sampled at least every
+    /// `interval` seconds.
+    ///
+    /// Sampling starts with the first subscriber of a host. The callback is
+    /// immediately invoked with the current snapshot.
+    pub fn connect<F>(self: &Rc<Self>, host: &str, interval: u32, callback: F) -> CallbackId
+    where
+        F: Fn(&RemoteStatsSnapshot) + 'static,
+    {
+        let interval = interval.max(MIN_REMOTE_INTERVAL_SECS);
+        let id = {
+            let host = host.to_string();
+            self.callbacks
+                .register(move |snapshot: &RemoteStatsSnapshot| {
+                    if snapshot.host == host {
+                        callback(snapshot);
+                    }
+                })
+        };
+
+        let existing = self.hosts.borrow().get(host).cloned();
+        let remote = match existing {
+            Some(remote) => {
+                remote.subscribers.borrow_mut().push((id, interval));
+                if remote.wanted_interval() != Some(remote.interval.get()) {
+                    if let Some(source_id) = remote.timer_source.borrow_mut().take() {
+                        source_id.remove();
+                    }
+                    self.start_timer(host, &remote);
+                }
+                remote
+            }
+            None => {
+                debug!("RemoteStatsService: sampling {}", host);
+                let remote = Rc::new(RemoteHost {
+                    snapshot: RefCell::new(RemoteStatsSnapshot::unknown(host)),
+                    subscribers: RefCell::new(vec![(id, interval)]),
+                    interval: Cell::new(interval),
+                    timer_source: RefCell::new(None),
+                    sample_in_progress: Cell::new(false),
+                    previous_cpu: RefCell::new(None),
+                });
+                self.hosts
+                    .borrow_mut()
+                    .insert(host.to_string(), remote.clone());
+                self.sample(host, &remote);
+                self.start_timer(host, &remote);
+                remote
+            }
+        };
+
+        let snapshot = remote.snapshot.borrow().clone();
+        self.callbacks.notify_single(id, &snapshot);
+        id
+    }
+
+    /// Schedule samples of `host` at the interval its subscribers want.
+    fn start_timer(self: &Rc<Self>, host: &str, remote: &Rc<RemoteHost>) {
+        let Some(interval) = remote.wanted_interval() else {
+            return;
+        };
+        remote.interval.set(interval);
+        debug!("RemoteStatsService: sampling {} every {}s", host, interval);
+
+        let this_weak = Rc::downgrade(self);
+        let remote_weak = Rc::downgrade(remote);
+        let host = host.to_string();
+        let source_id = glib::timeout_add_seconds_local(interval, move || {
+            let (Some(this), Some(remote)) = (this_weak.upgrade(), remote_weak.upgrade()) else {
+                return glib::ControlFlow::Break;
+            };
+
+            match remote.wanted_interval() {
+                None => {
+                    debug!("RemoteStatsService: no subscribers left for {}", host);
+                    remote.timer_source.borrow_mut().take();
+                    this.hosts.borrow_mut().remove(&host);
+                    glib::ControlFlow::Break
+                }
+                Some(wanted) if wanted != interval => {
+                    // This source ends by returning Break, so just forget its ID
+                    remote.timer_source.borrow_mut().take();
+                    this.sample(&host, &remote);
+                    this.start_timer(&host, &remote);
+                    glib::ControlFlow::Break
+                }
+                Some(_) => {
+                    this.sample(&host, &remote);
+                    glib::ControlFlow::Continue
+                }
+            }
+        });
+
+        *remote.timer_source.borrow_mut() = Some(source_id);
+    }
+
+    /// Sample `host` on the worker pool.
+    fn sample(&self, host: &str, remote: &RemoteHost) {
+        if remote.sample_in_progress.replace(true) {
+            trace!(
+                "RemoteStatsService: previous sample of {} still running",
+                host
+            );
+            return;
+        }
+
+        let work_host = host.to_string();
+        let host = host.to_string();
+        WorkerPool::global().spawn(
+            move || run_remote_sample(&work_host),
+            move |result| {
+                RemoteStatsService::global()
+                    .apply_sample(&host, result.unwrap_or_else(|e| Err(e.to_string())));
+            },
+        );
+    }
+
+    /// Store a finished sample of `host` and notify its subscribers.
+    fn apply_sample(&self, host: &str, result: Result<RemoteSample, String>) {
+        // The host may have lost its last subscriber while SSH ran
+        let Some(remote) = self.hosts.borrow().get(host).cloned() else {
+            return;
+        };
+        remote.sample_in_progress.set(false);
+
+        {
+            let mut snapshot = remote.snapshot.borrow_mut();
+            match result {
+                Ok(sample) => {
+                    let mut previous = remote.previous_cpu.borrow_mut();
+                    let (system, temperatures) = build_snapshots(&sample, previous.as_deref());
+                    *previous = Some(sample.cpu);
+                    snapshot.system = system;
+                    snapshot.temperatures = temperatures;
+                    snapshot.error = None;
+                }
+                Err(err) => {
+                    if snapshot.error.as_ref() != Some(&err) {
+                        warn!("RemoteStatsService: sampling {} failed: {}", host, err);
+                    }
+                    snapshot.system.available = false;
+                    snapshot.error = Some(err);
+                }
+            }
+        }
+
+        let snapshot = remote.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+impl Drop for RemoteStatsService {
+    fn drop(&mut self) {
+        for remote in self.hosts.borrow().values() {
+            if let Some(source_id) = remote.timer_source.borrow_mut().take() {
+                source_id.remove();
+            }
+        }
+    }
+}
+
+/// Cumulative CPU time counters of one `/proc/stat` line, in jiffies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CpuTimes {
+    busy: u64,
+    total: u64,
+}
+
+impl CpuTimes {
+    /// Parse a `cpu` / `cpuN` line of `/proc/stat`.
+    fn parse(line: &str) -> Option<Self> {
+        let values: Vec<u64> = line
+            .split_whitespace()
+            .skip(1)
+            .map(|v| v.parse().ok())
+            .collect::<Option<_>>()?;
+        if values.len() < 4 {
+            return None;
+        }
+        // user nice system idle iowait irq softirq steal; guest time is
+        // already counted in user
+        let total: u64 = values.iter().take(8).sum();
+        let idle = values[3] + values.get(4).copied().unwrap_or(0);
+        Some(Self {
+            busy: total.saturating_sub(idle),
+            total,
+        })
+    }
+
+    /// Usage percentage since `previous`, or since boot without one.
+    fn usage_since(&self, previous: Option<&CpuTimes>) -> f32 {
+        let (busy, total) = match previous {
+            Some(prev) => (
+                self.busy.saturating_sub(prev.busy),
+                self.total.saturating_sub(prev.total),
+            ),
+            None => (self.busy, self.total),
+        };
+        if total == 0 {
+            return 0.0;
+        }
+        (busy as f64 / total as f64 * 100.0) as f32
+    }
+}
+
+/// One parsed run of the sample script.
+#[derive(Debug, Clone, PartialEq)]
+struct RemoteSample {
+    /// Aggregate CPU times first, then one entry per core.
+    cpu: Vec<CpuTimes>,
+    /// Total memory in bytes.
+    memory_total: u64,
+    /// Available memory in bytes.
+    memory_available: u64,
+    load_avg: (f64, f64, f64),
+    sensors: Vec<Sensor>,
+}
+
+/// Run the sample script on `host`.
+///
+/// This runs in a background thread.
+fn run_remote_sample(host: &str) -> Result<RemoteSample, String> {
+    let output = Command::new("ssh")
+        .args([
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            &format!("ConnectTimeout={}", SSH_CONNECT_TIMEOUT),
+            "--",
+            host,
+            &format!("sh -c '{}'", SAMPLE_SCRIPT),
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+
+    // The script's own status is that of its last command, which may fail
+    // harmlessly; only ssh's 255 means the host wasn't reached
+    if output.status.code() == Some(255) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or("ssh failed");
+        return Err(message.to_string());
+    }
+
+    parse_sample(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the sections printed by the sample script.
+fn parse_sample(output: &str) -> Result<RemoteSample, String> {
+    let mut cpu = Vec::new();
+    let mut memory_total = None;
+    let mut memory_available = None;
+    let mut load_avg = (0.0, 0.0, 0.0);
+    let mut sensors = Vec::new();
+
+    let mut section = "";
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix('@') {
+            section = name.trim();
+            continue;
+        }
+        match section {
+            "stat" => cpu.extend(CpuTimes::parse(line)),
+            "meminfo" => {
+                let mut parts = line.split_whitespace();
+                let key = parts.next();
+                let bytes = parts
+                    .next()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(|kb| kb * 1024);
+                match key {
+                    Some("MemTotal:") => memory_total = bytes,
+                    Some("MemAvailable:") => memory_available = bytes,
+                    _ => {}
+                }
+            }
+            "loadavg" => {
+                let values: Vec<f64> = line
+                    .split_whitespace()
+                    .take(3)
+                    .filter_map(|v| v.parse().ok())
+                    .collect();
+                if let [one, five, fifteen] = values[..] {
+                    load_avg = (one, five, fifteen);
+                }
+            }
+            "sensors" => {
+                let mut fields = line.split('\t');
+                let name = fields.next().unwrap_or("").trim();
+                let millidegrees = |field: Option<&str>| {
+                    field
+                        .and_then(|v| v.trim().parse::<f64>().ok())
+                        .map(|v| v / 1000.0)
+                };
+                if let Some(celsius) = millidegrees(fields.next())
+                    && !name.is_empty()
+                {
+                    sensors.push(Sensor {
+                        name: name.to_string(),
+                        celsius,
+                        critical: millidegrees(fields.next()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if cpu.is_empty() {
+        return Err("no CPU statistics in the output (is the host running Linux?)".to_string());
+    }
+    let (Some(memory_total), Some(memory_available)) = (memory_total, memory_available) else {
+        return Err("no memory statistics in the output".to_string());
+    };
+
+    Ok(RemoteSample {
+        cpu,
+        memory_total,
+        memory_available,
+        load_avg,
+        sensors,
+    })
+}
+
+/// Build the snapshots the widgets render from a sample, with CPU usage
+/// measured since `previous_cpu`.
+fn build_snapshots(
+    sample: &RemoteSample,
+    previous_cpu: Option<&[CpuTimes]>,
+) -> (SystemSnapshot, TemperatureSnapshot) {
+    // Core counts change with CPU hotplug; start over then
+    let previous = previous_cpu.filter(|prev| prev.len() == sample.cpu.len());
+    let usages: Vec<f32> = sample
+        .cpu
+        .iter()
+        .enumerate()
+        .map(|(i, times)| times.usage_since(previous.and_then(|prev| prev.get(i))))
+        .collect();
+
+    let temperatures = TemperatureSnapshot {
+        ready: true,
+        sensors: sample.sensors.clone(),
+    };
+
+    let memory_used = sample.memory_total.saturating_sub(sample.memory_available);
+    let memory_percent = if sample.memory_total > 0 {
+        (memory_used as f64 / sample.memory_total as f64 * 100.0) as f32
+    } else {
+        0.0
+    };
+
+    let system = SystemSnapshot {
+        available: true,
+        cpu_usage: usages[0],
+        cpu_per_core: usages[1..].to_vec(),
+        cpu_core_count: usages.len() - 1,
+        cpu_temp: temperatures.find(None).map(|s| s.celsius as f32),
+        memory_used,
+        memory_total: sample.memory_total,
+        memory_percent,
+        load_avg: sample.load_avg,
+        ..SystemSnapshot::unknown()
+    };
+
+    (system, temperatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "@stat
+cpu  100 0 100 700 100 0 0 0 50 0
+cpu0 50 0 50 400 0 0 0 0 0 0
+cpu1 50 0 50 400 0 0 0 0 0 0
+@meminfo
+MemTotal:       16000000 kB
+MemFree:         2000000 kB
+MemAvailable:    4000000 kB
+@loadavg
+0.52 0.40 0.31 2/345 6789
+@sensors
+k10temp: Tctl\t55250\t
+nvme: Composite\t41850\t84850
+drivetemp: temp1\t\t
+acpitz\t27800\t
+";
+
+    #[test]
+    fn test_parse_sample() {
+        let sample = parse_sample(OUTPUT).unwrap();
+
+        assert_eq!(sample.cpu.len(), 3);
+        assert_eq!(
+            sample.cpu[0],
+            CpuTimes {
+                busy: 200,
+                total: 1000
+            }
+        );
+        assert_eq!(sample.memory_total, 16_000_000 * 1024);
+        assert_eq!(sample.memory_available, 4_000_000 * 1024);
+        assert_eq!(sample.load_avg, (0.52, 0.40, 0.31));
+
+        let names: Vec<&str> = sample.sensors.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["k10temp: Tctl", "nvme: Composite", "acpitz"]);
+        assert_eq!(sample.sensors[0].celsius, 55.25);
+        assert_eq!(sample.sensors[0].critical, None);
+        assert_eq!(sample.sensors[1].critical, Some(84.85));
+    }
+
+    #[test]
+    fn test_parse_sample_errors() {
+        assert!(parse_sample("").is_err());
+        assert!(parse_sample("@stat\ncpu 1 2 3 4\n").is_err());
+        // A non-Linux host prints the markers but nothing else
+        assert!(
+            parse_sample("@stat\n@meminfo\n@loadavg\n@sensors\n")
+                .unwrap_err()
+                .contains("CPU")
+        );
+    }
+
+    #[test]
+    fn test_build_snapshots() {
+        let first = parse_sample(OUTPUT).unwrap();
+        let (system, temperatures) = build_snapshots(&first, None);
+
+        assert!(system.available);
+        assert_eq!(system.cpu_usage, 20.0);
+        assert_eq!(system.cpu_core_count, 2);
+        assert_eq!(system.cpu_per_core, vec![20.0, 20.0]);
+        assert_eq!(system.memory_used, 12_000_000 * 1024);
+        assert_eq!(system.memory_percent, 75.0);
+        assert_eq!(system.cpu_temp, Some(55.25));
+        assert!(temperatures.ready);
+        assert_eq!(temperatures.sensors.len(), 3);
+
+        // Usage over the interval, not since boot
+        let mut second = first.clone();
+        second.cpu = vec![
+            CpuTimes {
+                busy: 300,
+                total: 1200,
+            },
+            CpuTimes {
+                busy: 190,
+                total: 600,
+            },
+            CpuTimes {
+                busy: 110,
+                total: 600,
+            },
+        ];
+        let (system, _) = build_snapshots(&second, Some(&first.cpu));
+        assert_eq!(system.cpu_usage, 50.0);
+        assert_eq!(system.cpu_per_core, vec![90.0, 10.0]);
+
+        // A changed core count starts over
+        let (system, _) = build_snapshots(&second, Some(&first.cpu[..1]));
+        assert_eq!(system.cpu_usage, 25.0);
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(parse_source("local"), Ok(None));
+        assert_eq!(parse_source("ssh:server1"), Ok(Some("server1".to_string())));
+        assert_eq!(
+            parse_source(" ssh:admin@nas.lan "),
+            Ok(Some("admin@nas.lan".to_string()))
+        );
+        assert!(parse_source("ssh:").is_err());
+        assert!(parse_source("ssh:-oProxyCommand=x").is_err());
+        assert!(parse_source("server1").is_err());
+    }
+}
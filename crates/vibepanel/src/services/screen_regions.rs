@@ -0,0 +1,462 @@
+//! ScreenRegions - keeps floating surfaces from overlapping each other.
+//!
+//! The Quick Settings panel, notification toasts and the OSD are separate
+//! layer-shell windows that know nothing about each other. Quick Settings
+//! and the toast stack register the region they occupy while visible, and
+//! movable surfaces ask for a placement that clears everything registered
+//! before them in priority order (`Surface`): toasts move out of the way of
+//! Quick Settings, and the OSD (last in line, so it never registers) moves
+//! out of the way of both. Subscribers are notified when a region changes
+//! so they can move again.
+//!
+//! ## Coordinates
+//!
+//! Surfaces with `exclusive_zone = 0` are laid out by the compositor inside
+//! the area left over by the bar's exclusive zone, and their margins are
+//! measured from its edges. Regions use the same space: (0, 0) is the
+//! top-left corner of that work area, which the bar registers with
+//! `set_work_area`. The bar itself is therefore never overlapped.
+//!
+//! Toasts and the OSD are not bound to a monitor, so a single work area is
+//! tracked (the last bar to register it).
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk4::prelude::*;
+use gtk4_layer_shell::{Edge, LayerShell};
+
+use super::callbacks::{CallbackId, Callbacks};
+
+/// Space kept between a moved surface and the region it avoids.
+const REGION_GAP: i32 = 8;
+
+/// Floating surfaces that take part in placement, in priority order.
+///
+/// A surface only moves out of the way of surfaces that come before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Surface {
+    QuickSettings,
+    Toasts,
+    Osd,
+}
+
+/// Rectangle in work-area coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    /// Whether the two rectangles are closer than `gap` on both axes.
+    fn overlaps(&self, other: &Rect, gap: i32) -> bool {
+        self.x < other.right() + gap
+            && other.x < self.right() + gap
+            && self.y < other.bottom() + gap
+            && other.y < self.bottom() + gap
+    }
+
+    fn fits_in(&self, area: (i32, i32)) -> bool {
+        self.x >= 0 && self.y >= 0 && self.right() <= area.0 && self.bottom() <= area.1
+    }
+}
+
+/// Which edge of an axis a surface is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    /// Left or top edge.
+    Start,
+    /// Centered (not anchored on this axis).
+    Center,
+    /// Right or bottom edge.
+    End,
+}
+
+/// Layer-shell anchors, margins and size of a surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub horizontal: Anchor,
+    pub vertical: Anchor,
+    /// Margin from the anchored horizontal edge (unused when centered).
+    pub margin_x: i32,
+    /// Margin from the anchored vertical edge (unused when centered).
+    pub margin_y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Placement {
+    /// The rectangle this placement covers in a work area of `area` size.
+    pub fn rect(&self, area: (i32, i32)) -> Rect {
+        Rect {
+            x: axis_position(self.horizontal, self.margin_x, self.width, area.0),
+            y: axis_position(self.vertical, self.margin_y, self.height, area.1),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// The same surface moved to `rect`, keeping its anchors where possible.
+    ///
+    /// A centered axis that had to move is anchored to its start edge, since
+    /// layer-shell can only offset a surface from an edge it is anchored to.
+    fn moved_to(&self, rect: Rect, area: (i32, i32)) -> Placement {
+        let (horizontal, margin_x) =
+            axis_margin(self.horizontal, rect.x, self.width, area.0, self.margin_x);
+        let (vertical, margin_y) =
+            axis_margin(self.vertical, rect.y, self.height, area.1, self.margin_y);
+        Placement {
+            horizontal,
+            vertical,
+            margin_x,
+            margin_y,
+            ..*self
+        }
+    }
+}
+
+fn axis_position(anchor: Anchor, margin: i32, size: i32, extent: i32) -> i32 {
+    match anchor {
+        Anchor::Start => margin,
+        Anchor::Center => (extent - size) / 2,
+        Anchor::End => extent - size - margin,
+    }
+}
+
+fn axis_margin(
+    anchor: Anchor,
+    position: i32,
+    size: i32,
+    extent: i32,
+    margin: i32,
+) -> (Anchor, i32) {
+    match anchor {
+        Anchor::Start => (Anchor::Start, position),
+        Anchor::End => (Anchor::End, extent - size - position),
+        Anchor::Center if position == (extent - size) / 2 => (Anchor::Center, margin),
+        Anchor::Center => (Anchor::Start, position),
+    }
+}
+
+/// Direction a surface is slid in to clear a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Slide directions to try, away from the anchored edges.
+///
+/// A surface centered on both axes (never the case today) isn't moved.
+fn slide_directions(placement: &Placement) -> Vec<Direction> {
+    let mut directions = Vec::new();
+    match placement.vertical {
+        Anchor::Start => directions.push(Direction::Down),
+        Anchor::End => directions.push(Direction::Up),
+        Anchor::Center => {}
+    }
+    match placement.horizontal {
+        Anchor::Start => directions.push(Direction::Right),
+        Anchor::End => directions.push(Direction::Left),
+        Anchor::Center if placement.vertical == Anchor::Center => {}
+        // Centered on the bar's edge: either side will do
+        Anchor::Center => directions.extend([Direction::Left, Direction::Right]),
+    }
+    directions
+}
+
+/// Slide `rect` in `direction` until it clears every obstacle, or `None`
+/// if it would leave the work area first.
+fn slide(
+    mut rect: Rect,
+    direction: Direction,
+    obstacles: &[Rect],
+    area: (i32, i32),
+) -> Option<Rect> {
+    // Each step clears at least one obstacle for good
+    for _ in 0..=obstacles.len() {
+        let Some(obstacle) = obstacles.iter().find(|o| rect.overlaps(o, REGION_GAP)) else {
+            return Some(rect);
+        };
+        match direction {
+            Direction::Down => rect.y = obstacle.bottom() + REGION_GAP,
+            Direction::Up => rect.y = obstacle.y - REGION_GAP - rect.height,
+            Direction::Right => rect.x = obstacle.right() + REGION_GAP,
+            Direction::Left => rect.x = obstacle.x - REGION_GAP - rect.width,
+        }
+        if !rect.fits_in(area) {
+            return None;
+        }
+    }
+    None
+}
+
+/// Move `placement` the shortest distance that clears `obstacles`.
+///
+/// Returns the placement unchanged when nothing overlaps, or when no
+/// direction clears everything without leaving the work area.
+fn resolve_placement(placement: &Placement, obstacles: &[Rect], area: (i32, i32)) -> Placement {
+    let start = placement.rect(area);
+    if !obstacles.iter().any(|o| start.overlaps(o, REGION_GAP)) {
+        return *placement;
+    }
+
+    slide_directions(placement)
+        .into_iter()
+        .filter_map(|direction| slide(start, direction, obstacles, area))
+        .min_by_key(|rect| (rect.x - start.x).abs() + (rect.y - start.y).abs())
+        .map(|rect| placement.moved_to(rect, area))
+        .unwrap_or(*placement)
+}
+
+/// Anchor `window` and set its margins according to `placement`.
+pub fn apply_placement(window: &impl IsA<gtk4::Window>, placement: &Placement) {
+    apply_axis(
+        window,
+        Edge::Left,
+        Edge::Right,
+        placement.horizontal,
+        placement.margin_x,
+    );
+    apply_axis(
+        window,
+        Edge::Top,
+        Edge::Bottom,
+        placement.vertical,
+        placement.margin_y,
+    );
+}
+
+fn apply_axis(
+    window: &impl IsA<gtk4::Window>,
+    start: Edge,
+    end: Edge,
+    anchor: Anchor,
+    margin: i32,
+) {
+    window.set_anchor(start, anchor == Anchor::Start);
+    window.set_anchor(end, anchor == Anchor::End);
+    window.set_margin(start, if anchor == Anchor::Start { margin } else { 0 });
+    window.set_margin(end, if anchor == Anchor::End { margin } else { 0 });
+}
+
+/// Registered regions, published whenever one changes.
+#[derive(Debug, Clone, Default)]
+pub struct ScreenRegionsSnapshot {
+    /// Size of the work area, once a bar has registered it.
+    pub work_area: Option<(i32, i32)>,
+    /// Regions of the visible surfaces.
+    pub regions: Vec<(Surface, Placement)>,
+}
+
+/// Shared, process-wide screen region coordinator.
+pub struct ScreenRegions {
+    /// Work area size and registered regions.
+    snapshot: RefCell<ScreenRegionsSnapshot>,
+    /// Registered callbacks for region changes.
+    callbacks: Callbacks<ScreenRegionsSnapshot>,
+    /// Set while callbacks run, so placements they register don't recurse.
+    notifying: Cell<bool>,
+    /// A region changed while callbacks ran; notify again afterwards.
+    changed_while_notifying: Cell<bool>,
+}
+
+impl ScreenRegions {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(ScreenRegionsSnapshot::default()),
+            callbacks: Callbacks::new(),
+            notifying: Cell::new(false),
+            changed_while_notifying: Cell::new(false),
+        })
+    }
+
+    /// Get the global ScreenRegions singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<ScreenRegions> = ScreenRegions::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever a region changes.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&ScreenRegionsSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify_single(id, &snapshot);
+        id
+    }
+
+    /// Unregister a previously registered callback.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Set the size of the area left over by the bar's exclusive zone.
+    pub fn set_work_area(&self, width: i32, height: i32) {
+        let changed = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let changed = snapshot.work_area != Some((width, height));
+            snapshot.work_area = Some((width, height));
+            changed
+        };
+        if changed {
+            self.notify();
+        }
+    }
+
+    /// Record the region `surface` occupies while it is visible.
+    pub fn register(&self, surface: Surface, placement: Placement) {
+        let changed = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            match snapshot.regions.iter_mut().find(|(s, _)| *s == surface) {
+                Some((_, existing)) if *existing == placement => false,
+                Some((_, existing)) => {
+                    *existing = placement;
+                    true
+                }
+                None => {
+                    snapshot.regions.push((surface, placement));
+                    true
+                }
+            }
+        };
+        if changed {
+            self.notify();
+        }
+    }
+
+    /// Forget the region of a surface that was hidden.
+    pub fn unregister(&self, surface: Surface) {
+        let changed = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let len = snapshot.regions.len();
+            snapshot.regions.retain(|(s, _)| *s != surface);
+            snapshot.regions.len() != len
+        };
+        if changed {
+            self.notify();
+        }
+    }
+
+    /// Where `surface` should go instead of `placement` to clear the regions
+    /// of higher-priority surfaces (unchanged until a bar has registered
+    /// the work area).
+    pub fn resolve(&self, surface: Surface, placement: &Placement) -> Placement {
+        let snapshot = self.snapshot.borrow();
+        let Some(area) = snapshot.work_area else {
+            return *placement;
+        };
+        let obstacles: Vec<Rect> = snapshot
+            .regions
+            .iter()
+            .filter(|(other, _)| *other < surface)
+            .map(|(_, region)| region.rect(area))
+            .collect();
+        resolve_placement(placement, &obstacles, area)
+    }
+
+    fn notify(&self) {
+        // A surface re-placed from a callback registers its new region;
+        // publish it once the current round is done instead of recursing
+        if self.notifying.replace(true) {
+            self.changed_while_notifying.set(true);
+            return;
+        }
+        loop {
+            let snapshot = self.snapshot.borrow().clone();
+            self.callbacks.notify(&snapshot);
+            if !self.changed_while_notifying.replace(false) {
+                break;
+            }
+        }
+        self.notifying.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: (i32, i32) = (1920, 1040);
+
+    fn toasts(height: i32) -> Placement {
+        Placement {
+            horizontal: Anchor::End,
+            vertical: Anchor::Start,
+            margin_x: 8,
+            margin_y: 8,
+            width: 380,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_resolve_unchanged_without_overlap() {
+        let obstacle = Rect {
+            x: 0,
+            y: 0,
+            width: 300,
+            height: 300,
+        };
+        let placement = toasts(200);
+        assert_eq!(resolve_placement(&placement, &[obstacle], AREA), placement);
+    }
+
+    #[test]
+    fn test_resolve_takes_shortest_slide() {
+        // Short panel in the top-right corner: slide the toasts below it
+        let panel = toasts(100).rect(AREA);
+        let resolved = resolve_placement(&toasts(200), &[panel], AREA);
+        assert_eq!(resolved.vertical, Anchor::Start);
+        assert_eq!(resolved.margin_y, 8 + 100 + REGION_GAP);
+        assert_eq!(resolved.margin_x, 8);
+
+        // Tall panel: moving left is shorter than moving below it
+        let panel = toasts(900).rect(AREA);
+        let resolved = resolve_placement(&toasts(200), &[panel], AREA);
+        assert_eq!(resolved.margin_y, 8);
+        assert_eq!(resolved.margin_x, 8 + 380 + REGION_GAP);
+    }
+
+    #[test]
+    fn test_resolve_centered_surface_gets_anchored() {
+        let osd = Placement {
+            horizontal: Anchor::Center,
+            vertical: Anchor::End,
+            margin_x: 0,
+            margin_y: 48,
+            width: 300,
+            height: 60,
+        };
+        // Full-height region covering all but the left edge
+        let obstacle = Rect {
+            x: 400,
+            y: 0,
+            width: 1520,
+            height: 1040,
+        };
+        let resolved = resolve_placement(&osd, &[obstacle], AREA);
+        assert_eq!(resolved.horizontal, Anchor::Start);
+        assert_eq!(resolved.margin_x, 400 - REGION_GAP - 300);
+        assert_eq!(resolved.vertical, Anchor::End);
+        assert_eq!(resolved.margin_y, 48);
+    }
+}
@@ -1,14 +1,16 @@
 //! State persistence service for vibepanel
 //!
-//! Persists runtime state to $XDG_STATE_HOME/vibepanel/state.json
-//! This includes:
+//! Typed sections of the shared `vibepanel_core::StateStore`
+//! (`$XDG_STATE_HOME/vibepanel/state.json`). This includes:
 //! - VPN last used connection UUID
+//! - Bluetooth last connected audio device
 //! - Notification muted (DND) state
 //! - Notification history
 //! - Media window open state
+//! - Widgets hidden for the current session (restored after a compositor restart)
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use vibepanel_core::StateStore;
 
 /// Maximum number of notifications to persist to disk
 const MAX_PERSISTED_NOTIFICATIONS: usize = 50;
@@ -19,9 +21,13 @@ pub struct PersistedState {
     #[serde(default)]
     pub vpn: VpnState,
     #[serde(default)]
+    pub bluetooth: BluetoothState,
+    #[serde(default)]
     pub notifications: NotificationState,
     #[serde(default)]
     pub media: MediaState,
+    #[serde(default)]
+    pub session: SessionState,
 }
 
 /// VPN-related persisted state
@@ -31,6 +37,13 @@ pub struct VpnState {
     pub last_used_uuid: Option<String>,
 }
 
+/// Bluetooth-related persisted state
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BluetoothState {
+    /// Address of the most recently connected audio device
+    pub last_audio_device: Option<String>,
+}
+
 /// Media-related persisted state
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct MediaState {
@@ -38,6 +51,14 @@ pub struct MediaState {
     pub window_open: bool,
 }
 
+/// State of the running session, restored when the bar is restarted after
+/// the compositor went away and cleared on a normal start
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SessionState {
+    /// Config names of widgets hidden with `vibepanel ipc toggle-widget`
+    pub hidden_widgets: Vec<String>,
+}
+
 /// Notification-related persisted state
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct NotificationState {
@@ -69,81 +90,34 @@ pub struct PersistedNotification {
     // Note: image_data intentionally omitted (binary data, not suitable for JSON)
 }
 
-/// Returns the path to the state file
+/// Load persisted state from the shared state store
 ///
-/// Location: `$XDG_STATE_HOME/vibepanel/state.json`
-/// Default: `~/.local/state/vibepanel/state.json`
-fn state_file_path() -> PathBuf {
-    let state_home = std::env::var("XDG_STATE_HOME").unwrap_or_else(|_| {
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        format!("{}/.local/state", home)
-    });
-    PathBuf::from(state_home)
-        .join("vibepanel")
-        .join("state.json")
-}
-
-/// Load persisted state from disk
-///
-/// Returns `PersistedState::default()` if the file doesn't exist or is invalid.
+/// Sections that are missing or invalid fall back to their defaults.
 pub fn load() -> PersistedState {
-    let path = state_file_path();
-    match std::fs::read_to_string(&path) {
-        Ok(contents) => match serde_json::from_str(&contents) {
-            Ok(state) => {
-                tracing::debug!("Loaded state from {:?}", path);
-                state
-            }
-            Err(e) => {
-                tracing::warn!("Failed to parse state file {:?}: {}", path, e);
-                PersistedState::default()
-            }
-        },
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            tracing::debug!("No state file found at {:?}, using defaults", path);
-            PersistedState::default()
-        }
-        Err(e) => {
-            tracing::warn!("Failed to read state file {:?}: {}", path, e);
-            PersistedState::default()
-        }
+    let store = StateStore::global();
+    PersistedState {
+        vpn: store.get("vpn").unwrap_or_default(),
+        bluetooth: store.get("bluetooth").unwrap_or_default(),
+        notifications: store.get("notifications").unwrap_or_default(),
+        media: store.get("media").unwrap_or_default(),
+        session: store.get("session").unwrap_or_default(),
     }
 }
 
-/// Save persisted state to disk
+/// Save persisted state to the shared state store
 ///
-/// Creates the parent directory if it doesn't exist.
+/// The write to disk is debounced by the store.
 /// Enforces the notification history limit before saving.
 pub fn save(state: &PersistedState) {
-    let path = state_file_path();
-
-    // Ensure directory exists
-    if let Some(parent) = path.parent()
-        && let Err(e) = std::fs::create_dir_all(parent)
-    {
-        tracing::warn!("Failed to create state directory {:?}: {}", parent, e);
-        return;
-    }
+    let store = StateStore::global();
 
     // Enforce notification limit before saving
-    let mut state = state.clone();
-    if state.notifications.history.len() > MAX_PERSISTED_NOTIFICATIONS {
-        state
-            .notifications
-            .history
-            .truncate(MAX_PERSISTED_NOTIFICATIONS);
-    }
+    let mut notifications = state.notifications.clone();
+    notifications.history.truncate(MAX_PERSISTED_NOTIFICATIONS);
 
-    match serde_json::to_string_pretty(&state) {
-        Ok(json) => {
-            if let Err(e) = std::fs::write(&path, json) {
-                tracing::warn!("Failed to save state to {:?}: {}", path, e);
-            } else {
-                tracing::debug!("Saved state to {:?}", path);
-            }
-        }
-        Err(e) => {
-            tracing::warn!("Failed to serialize state: {}", e);
-        }
-    }
+    store.set("vpn", &state.vpn);
+    store.set("bluetooth", &state.bluetooth);
+    store.set("notifications", &notifications);
+    store.set("media", &state.media);
+    store.set("session", &state.session);
 }
@@ -0,0 +1,527 @@
+//! Line-based Unix stream socket server on the GTK main loop.
+//!
+//! Clients write newline-delimited lines and get one reply line for each,
+//! in order: a client's next line is only handed to the handler once the
+//! previous one is answered, so handlers may reply later (e.g. after work
+//! off the main loop). A client that stays connected can also be sent
+//! unsolicited lines between replies (events for external modules).
+//!
+//! Outgoing lines are queued per client and written as the socket drains,
+//! so a client that is slow to read never stalls the main loop or receives
+//! a partial line.
+//!
+//! The server watches the listening socket and each client with
+//! glib::unix_fd_add_local() - no threads.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::rc::{Rc, Weak};
+
+use gtk4::glib;
+use tracing::{debug, warn};
+
+/// Maximum buffered bytes for a single line before it is discarded.
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+/// Maximum queued output for a client that isn't reading; further lines
+/// are dropped until it catches up.
+const MAX_PENDING_OUTPUT: usize = 64 * 1024;
+
+/// Identifier for a connected client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientId(pub(super) u64);
+
+/// Type alias for the line handler.
+type LineHandler = Rc<dyn Fn(Connection, String)>;
+
+/// Type alias for the close handler.
+type CloseHandler = Rc<dyn Fn(ClientId)>;
+
+/// A connected client.
+struct Client {
+    stream: UnixStream,
+    /// Partial line data not yet terminated by a newline.
+    buffer: Vec<u8>,
+    /// Complete lines waiting for the previous one to be answered.
+    lines: VecDeque<String>,
+    /// Bytes not yet written to the socket.
+    outgoing: Vec<u8>,
+    /// Whether a dispatched line hasn't been answered yet.
+    awaiting_reply: bool,
+    /// Whether the line handler is running for this client.
+    dispatching: bool,
+    /// Whether the client closed its side of the connection.
+    read_closed: bool,
+    /// GLib source ID for the client fd watcher.
+    source_id: Option<glib::SourceId>,
+    /// GLib source ID for the writability watcher while output is queued.
+    write_source_id: Option<glib::SourceId>,
+}
+
+impl Client {
+    /// Whether everything the client sent is answered and written, after
+    /// it closed the connection.
+    fn is_done(&self) -> bool {
+        self.read_closed
+            && self.lines.is_empty()
+            && !self.awaiting_reply
+            && self.outgoing.is_empty()
+    }
+}
+
+/// Handle to a connected client, passed to the line handler.
+///
+/// Cloning it is cheap; sending to a client that has gone away does nothing.
+#[derive(Clone)]
+pub struct Connection {
+    server: Weak<StreamServer>,
+    id: ClientId,
+}
+
+impl Connection {
+    /// The client this handle refers to.
+    pub fn id(&self) -> ClientId {
+        self.id
+    }
+
+    /// Queue an unsolicited line (including its trailing newline) for the
+    /// client (best-effort).
+    pub fn send(&self, line: &str) {
+        if let Some(server) = self.server.upgrade() {
+            server.queue(self.id, line);
+        }
+    }
+
+    /// Answer the line the handler was given, which lets the client's next
+    /// line through.
+    pub(super) fn reply(&self, line: &str) {
+        if let Some(server) = self.server.upgrade() {
+            server.reply(self.id, line);
+        }
+    }
+}
+
+/// Server accepting line-based connections on the GTK main loop.
+pub struct StreamServer {
+    /// Log prefix, e.g. "Control IPC".
+    name: &'static str,
+    /// The bound listener (must stay alive while listening).
+    listener: UnixListener,
+    /// Path to the socket file (for cleanup on drop).
+    socket_path: PathBuf,
+    /// GLib source ID for the accept watcher.
+    source_id: RefCell<Option<glib::SourceId>>,
+    clients: RefCell<HashMap<ClientId, Client>>,
+    next_client_id: Cell<u64>,
+    on_line: LineHandler,
+    on_close: CloseHandler,
+}
+
+impl StreamServer {
+    /// Bind `path` and start accepting connections.
+    ///
+    /// `on_line` is invoked on the main loop for every non-empty line (without
+    /// its newline), and must answer it through [`Connection::reply`].
+    /// `on_close` is invoked once a client has closed the connection and
+    /// all its lines are answered.
+    pub fn bind<L, C>(
+        name: &'static str,
+        path: PathBuf,
+        on_line: L,
+        on_close: C,
+    ) -> Option<Rc<Self>>
+    where
+        L: Fn(Connection, String) + 'static,
+        C: Fn(ClientId) + 'static,
+    {
+        // Remove stale socket if it exists.
+        if path.exists() {
+            let _ = std::fs::remove_file(&path);
+        }
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(l) => l,
+            Err(e) => {
+                warn!("{}: failed to bind socket at {:?}: {}", name, path, e);
+                return None;
+            }
+        };
+
+        if let Err(e) = listener.set_nonblocking(true) {
+            warn!("{}: failed to set socket non-blocking: {}", name, e);
+            return None;
+        }
+
+        debug!("{}: listening on {:?}", name, path);
+
+        let listener_fd = listener.as_raw_fd();
+        let server = Rc::new(Self {
+            name,
+            listener,
+            socket_path: path,
+            source_id: RefCell::new(None),
+            clients: RefCell::new(HashMap::new()),
+            next_client_id: Cell::new(1),
+            on_line: Rc::new(on_line),
+            on_close: Rc::new(on_close),
+        });
+
+        let server_weak = Rc::downgrade(&server);
+        let source_id = glib::unix_fd_add_local(
+            listener_fd,
+            glib::IOCondition::IN,
+            move |_fd, _condition| {
+                let Some(server) = server_weak.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+                server.accept_pending();
+                glib::ControlFlow::Continue
+            },
+        );
+        *server.source_id.borrow_mut() = Some(source_id);
+
+        Some(server)
+    }
+
+    /// Accept all pending connections on the (non-blocking) listener.
+    fn accept_pending(self: &Rc<Self>) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _addr)) => self.add_client(stream),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    warn!("{}: accept failed: {}", self.name, e);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn add_client(self: &Rc<Self>, stream: UnixStream) {
+        if let Err(e) = stream.set_nonblocking(true) {
+            warn!("{}: failed to set client non-blocking: {}", self.name, e);
+            return;
+        }
+
+        let id = ClientId(self.next_client_id.get());
+        self.next_client_id.set(id.0 + 1);
+
+        let fd = stream.as_raw_fd();
+        let server_weak: Weak<Self> = Rc::downgrade(self);
+        let source_id = glib::unix_fd_add_local(
+            fd,
+            glib::IOCondition::IN | glib::IOCondition::HUP | glib::IOCondition::ERR,
+            move |_fd, _condition| {
+                let Some(server) = server_weak.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+                if server.read_client(id) {
+                    glib::ControlFlow::Continue
+                } else {
+                    glib::ControlFlow::Break
+                }
+            },
+        );
+
+        self.clients.borrow_mut().insert(
+            id,
+            Client {
+                stream,
+                buffer: Vec::new(),
+                lines: VecDeque::new(),
+                outgoing: Vec::new(),
+                awaiting_reply: false,
+                dispatching: false,
+                read_closed: false,
+                source_id: Some(source_id),
+                write_source_id: None,
+            },
+        );
+        debug!("{}: client {:?} connected", self.name, id);
+    }
+
+    /// Read available data from a client and dispatch complete lines.
+    ///
+    /// Returns `false` once the client closed its side (its watcher should
+    /// stop).
+    fn read_client(self: &Rc<Self>, id: ClientId) -> bool {
+        let closed = {
+            let mut clients = self.clients.borrow_mut();
+            let Some(client) = clients.get_mut(&id) else {
+                return false;
+            };
+
+            let mut closed = false;
+            let mut buf = [0u8; 4096];
+            loop {
+                match client.stream.read(&mut buf) {
+                    Ok(0) => {
+                        closed = true;
+                        break;
+                    }
+                    Ok(n) => client.buffer.extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        debug!("{}: read error from {:?}: {}", self.name, id, e);
+                        closed = true;
+                        break;
+                    }
+                }
+            }
+
+            let mut lines = split_lines(&mut client.buffer);
+            // A client may close its write side without a trailing newline
+            if closed && !client.buffer.is_empty() {
+                lines.push(String::from_utf8_lossy(&client.buffer).into_owned());
+                client.buffer.clear();
+            }
+            client
+                .lines
+                .extend(lines.into_iter().filter(|line| !line.trim().is_empty()));
+
+            if client.buffer.len() > MAX_LINE_LENGTH {
+                warn!("{}: discarding oversized line from {:?}", self.name, id);
+                client.buffer.clear();
+            }
+
+            if closed {
+                // Returning Break removes the watcher, so just forget its ID.
+                client.read_closed = true;
+                client.source_id = None;
+            }
+            closed
+        };
+
+        self.dispatch(id);
+        !closed
+    }
+
+    /// Hand the client's queued lines to the line handler, one at a time.
+    fn dispatch(self: &Rc<Self>, id: ClientId) {
+        loop {
+            let line = {
+                let mut clients = self.clients.borrow_mut();
+                let Some(client) = clients.get_mut(&id) else {
+                    return;
+                };
+                // A reply sent from inside the handler lands back in the
+                // loop below instead of recursing.
+                if client.dispatching || client.awaiting_reply {
+                    return;
+                }
+                let Some(line) = client.lines.pop_front() else {
+                    break;
+                };
+                client.awaiting_reply = true;
+                client.dispatching = true;
+                line
+            };
+
+            let connection = Connection {
+                server: Rc::downgrade(self),
+                id,
+            };
+            (self.on_line)(connection, line);
+
+            if let Some(client) = self.clients.borrow_mut().get_mut(&id) {
+                client.dispatching = false;
+            }
+        }
+        self.close_if_done(id);
+    }
+
+    fn reply(self: &Rc<Self>, id: ClientId, line: &str) {
+        match self.clients.borrow_mut().get_mut(&id) {
+            Some(client) => client.awaiting_reply = false,
+            None => return,
+        }
+        self.queue(id, line);
+        self.dispatch(id);
+    }
+
+    /// Queue a line for a client and write as much as the socket takes now.
+    ///
+    /// Lines are queued whole; they are dropped while the client has
+    /// `MAX_PENDING_OUTPUT` bytes unread.
+    fn queue(self: &Rc<Self>, id: ClientId, line: &str) {
+        let mut clients = self.clients.borrow_mut();
+        let Some(client) = clients.get_mut(&id) else {
+            return;
+        };
+        if client.outgoing.len() + line.len() > MAX_PENDING_OUTPUT {
+            debug!("{}: {:?} isn't reading, dropping {:?}", self.name, id, line);
+            return;
+        }
+        client.outgoing.extend_from_slice(line.as_bytes());
+        if client.write_source_id.is_some() {
+            // Already waiting for the socket to drain
+            return;
+        }
+
+        match write_buffered(&mut client.stream, &mut client.outgoing) {
+            Ok(true) => {}
+            Ok(false) => {
+                let server_weak = Rc::downgrade(self);
+                client.write_source_id = Some(glib::unix_fd_add_local(
+                    client.stream.as_raw_fd(),
+                    glib::IOCondition::OUT | glib::IOCondition::HUP | glib::IOCondition::ERR,
+                    move |_fd, _condition| {
+                        let Some(server) = server_weak.upgrade() else {
+                            return glib::ControlFlow::Break;
+                        };
+                        if server.flush_client(id) {
+                            glib::ControlFlow::Continue
+                        } else {
+                            glib::ControlFlow::Break
+                        }
+                    },
+                ));
+            }
+            Err(e) => {
+                // Fire-and-forget clients hang up without reading
+                debug!("{}: failed to write to {:?}: {}", self.name, id, e);
+                client.outgoing.clear();
+            }
+        }
+    }
+
+    /// Write queued output once the client's socket is writable.
+    ///
+    /// Returns `false` when nothing is left to write (its watcher should
+    /// stop).
+    fn flush_client(&self, id: ClientId) -> bool {
+        let more = {
+            let mut clients = self.clients.borrow_mut();
+            let Some(client) = clients.get_mut(&id) else {
+                return false;
+            };
+            let more = match write_buffered(&mut client.stream, &mut client.outgoing) {
+                Ok(done) => !done,
+                Err(e) => {
+                    debug!("{}: failed to write to {:?}: {}", self.name, id, e);
+                    client.outgoing.clear();
+                    false
+                }
+            };
+            if !more {
+                // Returning Break removes the watcher
+                client.write_source_id = None;
+            }
+            more
+        };
+        if !more {
+            self.close_if_done(id);
+        }
+        more
+    }
+
+    /// Forget a client once it closed the connection and has nothing left
+    /// to answer or write.
+    fn close_if_done(&self, id: ClientId) {
+        {
+            let mut clients = self.clients.borrow_mut();
+            if !clients.get(&id).is_some_and(Client::is_done) {
+                return;
+            }
+            if let Some(mut client) = clients.remove(&id)
+                && let Some(source_id) = client.write_source_id.take()
+            {
+                source_id.remove();
+            }
+        }
+        debug!("{}: client {:?} disconnected", self.name, id);
+        (self.on_close)(id);
+    }
+}
+
+impl Drop for StreamServer {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.source_id.borrow_mut().take() {
+            source_id.remove();
+        }
+        for (_, mut client) in self.clients.borrow_mut().drain() {
+            if let Some(source_id) = client.source_id.take() {
+                source_id.remove();
+            }
+            if let Some(source_id) = client.write_source_id.take() {
+                source_id.remove();
+            }
+        }
+
+        let _ = std::fs::remove_file(&self.socket_path);
+
+        debug!("{}: server stopped", self.name);
+    }
+}
+
+/// Take the complete lines (without their newline) out of `buffer`,
+/// leaving a trailing partial line in it.
+fn split_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut lines = Vec::new();
+    while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+        let line: Vec<u8> = buffer.drain(..=pos).collect();
+        lines.push(String::from_utf8_lossy(&line[..pos]).into_owned());
+    }
+    lines
+}
+
+/// Write `pending` to a non-blocking `stream`, draining what was written.
+///
+/// Returns `Ok(true)` once everything is written and `Ok(false)` if the
+/// socket is full and the rest has to wait for it to become writable.
+fn write_buffered(stream: &mut UnixStream, pending: &mut Vec<u8>) -> io::Result<bool> {
+    while !pending.is_empty() {
+        match stream.write(pending) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => {
+                pending.drain(..n);
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lines_keeps_partial_line() {
+        let mut buffer = b"{\"command\":\"state\"}\n\n{\"command\":\"rel".to_vec();
+        assert_eq!(
+            split_lines(&mut buffer),
+            vec!["{\"command\":\"state\"}".to_string(), String::new()]
+        );
+        assert_eq!(buffer, b"{\"command\":\"rel");
+    }
+
+    #[test]
+    fn test_write_buffered_waits_for_slow_reader() {
+        let (mut writer, mut reader) = UnixStream::pair().unwrap();
+        writer.set_nonblocking(true).unwrap();
+
+        // More than a socket buffer holds, so the first write can't finish
+        let data: Vec<u8> = (0..4 * 1024 * 1024).map(|i| i as u8).collect();
+        let mut pending = data.clone();
+        assert!(!write_buffered(&mut writer, &mut pending).unwrap());
+        assert!(!pending.is_empty());
+
+        let mut received = Vec::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        while !write_buffered(&mut writer, &mut pending).unwrap() {
+            let n = reader.read(&mut buf).unwrap();
+            received.extend_from_slice(&buf[..n]);
+        }
+        drop(writer);
+        reader.read_to_end(&mut received).unwrap();
+        assert_eq!(received, data);
+    }
+}
@@ -233,11 +233,12 @@ impl SurfaceStyleManager {
     }
 
     fn style_all_labels_recursive(&self, widget: &gtk4::Widget, base_font_size_px: u32) {
-        // If this widget is a Label, style it (unless it's a Material Symbol icon)
+        // If this widget is a Label, style it (unless it's a font icon)
         if let Some(label) = widget.downcast_ref::<Label>() {
-            // Skip Material Symbols icons - they use ligature-based font rendering
-            // and applying Pango attributes breaks the icon→glyph mapping
-            if !label.has_css_class(icon::MATERIAL_SYMBOL) {
+            // Skip Material Symbols and Nerd Font icons - they need their icon
+            // font, and applying Pango attributes breaks the icon→glyph mapping
+            if !label.has_css_class(icon::MATERIAL_SYMBOL) && !label.has_css_class(icon::NERD_FONT)
+            {
                 // Use CSS-computed size if available, otherwise fall back to base size.
                 // This preserves relative sizing (em values, smaller subtitles, etc.)
                 let font_size = self
@@ -0,0 +1,841 @@
+//! SyncthingService - sync status from Syncthing's local REST API.
+//!
+//! This service provides:
+//! - Per-folder state and out-of-sync item counts
+//! - Download/upload rates derived from the total transfer counters
+//! - Periodic polling in a background thread (requests are blocking)
+//! - Folder state changes from the event long-poll, between polls
+//!
+//! Endpoints used (all require the `X-API-Key` header):
+//! - `GET /rest/config/folders` - folder ids, labels and paused flags
+//! - `GET /rest/db/status?folder=<id>` - folder state and needed items; this
+//!   is expensive for Syncthing, so it's only fetched for folders we know
+//!   nothing about (first poll, new or unpaused folders, missed events)
+//! - `GET /rest/events?events=StateChanged,FolderSummary` - folder state and
+//!   summary updates, long-polled
+//! - `GET /rest/system/connections` - total bytes in/out
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Instant;
+
+use gtk4::glib::{self, SourceId};
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use super::callbacks::Callbacks;
+use super::url::encode_component;
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings};
+
+// Thread-local singleton storage for SyncthingService
+thread_local! {
+    static SYNCTHING_INSTANCE: RefCell<Option<Rc<SyncthingService>>> = const { RefCell::new(None) };
+}
+
+/// Default Syncthing GUI/REST address.
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8384";
+
+/// Default poll interval in seconds.
+pub const DEFAULT_POLL_INTERVAL: u64 = 5;
+
+/// Request timeout in seconds.
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+
+/// Events followed by the long-poll.
+const EVENT_TYPES: &str = "StateChanged,FolderSummary";
+
+/// How long Syncthing holds an events request open when nothing happens.
+const EVENTS_TIMEOUT_SECS: u64 = 60;
+
+/// Settings for the Syncthing poller (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncthingSettings {
+    /// Base URL of the REST API, without trailing slash.
+    pub url: String,
+    /// API key from Syncthing's settings.
+    pub api_key: String,
+    /// How often to poll (seconds).
+    pub poll_interval: u64,
+}
+
+impl MergeSettings for SyncthingSettings {
+    /// Poll at the shortest interval; the instance of the newest widget wins.
+    fn merge(&mut self, other: &Self) {
+        self.url = other.url.clone();
+        self.api_key = other.api_key.clone();
+        self.poll_interval = self.poll_interval.min(other.poll_interval);
+    }
+}
+
+/// Sync state of a single folder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FolderStatus {
+    /// Folder id.
+    pub id: String,
+    /// Display label (falls back to the id).
+    pub label: String,
+    /// Syncthing state string ("idle", "syncing", "scanning", "error", ...).
+    pub state: String,
+    /// Whether the folder is paused.
+    pub paused: bool,
+    /// Number of items (files, directories, deletes) still needed.
+    pub need_items: u64,
+    /// Bytes still needed.
+    pub need_bytes: u64,
+    /// Total bytes in the global state.
+    pub global_bytes: u64,
+}
+
+impl FolderStatus {
+    /// Whether the folder is fully in sync.
+    pub fn in_sync(&self) -> bool {
+        self.need_items == 0 && self.state == "idle"
+    }
+
+    /// Whether the folder is in an error state.
+    pub fn has_error(&self) -> bool {
+        self.state == "error"
+    }
+
+    /// Completion as a percentage of the global size.
+    pub fn completion(&self) -> f64 {
+        if self.global_bytes == 0 {
+            return 100.0;
+        }
+        let done = self.global_bytes.saturating_sub(self.need_bytes);
+        done as f64 / self.global_bytes as f64 * 100.0
+    }
+}
+
+/// Overall sync status across all folders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// All folders are idle and in sync.
+    UpToDate,
+    /// At least one folder is scanning or syncing.
+    Syncing,
+    /// At least one folder has errors.
+    Error,
+    /// The REST API is unreachable.
+    Disconnected,
+}
+
+/// Canonical snapshot of Syncthing state.
+#[derive(Debug, Clone, Default)]
+pub struct SyncthingSnapshot {
+    /// Whether the service has been configured.
+    pub available: bool,
+    /// Whether the last poll reached the REST API.
+    pub connected: bool,
+    /// Folder states, in Syncthing's config order.
+    pub folders: Vec<FolderStatus>,
+    /// Current download rate (bytes/second).
+    pub download_rate: u64,
+    /// Current upload rate (bytes/second).
+    pub upload_rate: u64,
+    /// Last error message, if any.
+    pub error: Option<String>,
+}
+
+impl SyncthingSnapshot {
+    /// Overall status across all folders.
+    pub fn status(&self) -> SyncStatus {
+        if !self.connected {
+            SyncStatus::Disconnected
+        } else if self.folders.iter().any(FolderStatus::has_error) {
+            SyncStatus::Error
+        } else if self
+            .folders
+            .iter()
+            .any(|folder| !folder.paused && !folder.in_sync())
+        {
+            SyncStatus::Syncing
+        } else {
+            SyncStatus::UpToDate
+        }
+    }
+
+    /// Total number of out-of-sync items across all folders.
+    pub fn out_of_sync_items(&self) -> u64 {
+        self.folders.iter().map(|folder| folder.need_items).sum()
+    }
+}
+
+/// Raw result of one poll, computed off the main thread.
+struct PollResult {
+    /// Folder configs, with the database status of those that were fetched.
+    folders: Vec<(FolderConfig, Option<DbStatus>)>,
+    in_bytes_total: u64,
+    out_bytes_total: u64,
+    at: Instant,
+}
+
+/// Shared, process-wide Syncthing service.
+pub struct SyncthingService {
+    snapshot: RefCell<SyncthingSnapshot>,
+    callbacks: Callbacks<SyncthingSnapshot>,
+    /// Settings of each Syncthing widget.
+    registered: WidgetSettings<SyncthingSettings>,
+    /// Merged settings the polls run with.
+    settings: RefCell<Option<SyncthingSettings>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Previous transfer totals, used to derive rates.
+    last_totals: Cell<Option<(u64, u64, Instant)>>,
+    /// Prevent overlapping polls.
+    poll_in_progress: Cell<bool>,
+    /// Bumped on reconfiguration; stale event requests are dropped.
+    events_generation: Cell<u64>,
+    /// Id of the last event seen, 0 until the long-poll has caught up.
+    last_event_id: Cell<u64>,
+    /// Set when events were missed; the next poll refetches every folder.
+    resync: Cell<bool>,
+}
+
+impl SyncthingService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(SyncthingSnapshot::default()),
+            callbacks: Callbacks::new(),
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            timer_source: RefCell::new(None),
+            last_totals: Cell::new(None),
+            poll_in_progress: Cell::new(false),
+            events_generation: Cell::new(0),
+            last_event_id: Cell::new(0),
+            resync: Cell::new(true),
+        })
+    }
+
+    /// Get the global SyncthingService singleton.
+    pub fn global() -> Rc<Self> {
+        SYNCTHING_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(SyncthingService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        SYNCTHING_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&SyncthingSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> SyncthingSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: SyncthingSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        self.notify();
+    }
+
+    /// Register a Syncthing widget and (re)start polling.
+    pub fn configure(self: &Rc<Self>, settings: SyncthingSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's settings; polling stops with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Restart polling with the merged settings of all widgets.
+    fn apply_settings(self: &Rc<Self>) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        // Drops the event long-poll of the old settings
+        self.events_generation.set(self.events_generation.get() + 1);
+        let Some(settings) = settings else {
+            debug!("SyncthingService: no widgets left, stopping polls");
+            *self.settings.borrow_mut() = None;
+            *self.snapshot.borrow_mut() = SyncthingSnapshot::default();
+            self.notify();
+            return;
+        };
+
+        info!(
+            "SyncthingService: polling {} every {}s",
+            settings.url, settings.poll_interval
+        );
+
+        let interval = settings.poll_interval.max(1);
+        *self.settings.borrow_mut() = Some(settings);
+        self.last_totals.set(None);
+        self.snapshot.borrow_mut().available = true;
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.poll();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.resync.set(true);
+        self.poll();
+
+        self.last_event_id.set(0);
+        self.watch_events();
+    }
+
+    /// Trigger an immediate poll.
+    pub fn poll(&self) {
+        if self.poll_in_progress.get() {
+            return;
+        }
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+
+        // Folders whose status we already follow through events, with the
+        // paused flag it was fetched with
+        let known: HashMap<String, bool> = if self.resync.replace(false) {
+            HashMap::new()
+        } else {
+            self.snapshot
+                .borrow()
+                .folders
+                .iter()
+                .map(|folder| (folder.id.clone(), folder.paused))
+                .collect()
+        };
+
+        self.poll_in_progress.set(true);
+        std::thread::spawn(move || {
+            let result = fetch_status(&settings, &known);
+            glib::idle_add_once(move || {
+                SyncthingService::global().apply_poll_result(result);
+            });
+        });
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply the result of a background poll.
+    fn apply_poll_result(&self, result: Result<PollResult, String>) {
+        self.poll_in_progress.set(false);
+        if self.settings.borrow().is_none() {
+            // Every widget went away during the poll
+            return;
+        }
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            match result {
+                Ok(poll) => {
+                    let (download_rate, upload_rate) = match self.last_totals.get() {
+                        Some((last_in, last_out, last_at)) => {
+                            let secs = poll.at.duration_since(last_at).as_secs_f64();
+                            (
+                                transfer_rate(last_in, poll.in_bytes_total, secs),
+                                transfer_rate(last_out, poll.out_bytes_total, secs),
+                            )
+                        }
+                        None => (0, 0),
+                    };
+                    self.last_totals.set(Some((
+                        poll.in_bytes_total,
+                        poll.out_bytes_total,
+                        poll.at,
+                    )));
+
+                    if !snapshot.connected {
+                        debug!("SyncthingService: connected");
+                    }
+                    snapshot.connected = true;
+                    snapshot.error = None;
+                    snapshot.folders = merge_folders(&snapshot.folders, poll.folders);
+                    snapshot.download_rate = download_rate;
+                    snapshot.upload_rate = upload_rate;
+                }
+                Err(err) => {
+                    if snapshot.connected || snapshot.error.is_none() {
+                        warn!("SyncthingService: {}", err);
+                    }
+                    self.last_totals.set(None);
+                    self.resync.set(true);
+                    snapshot.connected = false;
+                    snapshot.error = Some(err);
+                    snapshot.download_rate = 0;
+                    snapshot.upload_rate = 0;
+                }
+            }
+        }
+        self.notify();
+    }
+
+    /// Long-poll the next batch of folder events in a background thread.
+    fn watch_events(&self) {
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+        let generation = self.events_generation.get();
+        let since = self.last_event_id.get();
+
+        std::thread::spawn(move || {
+            let result = fetch_events(&settings, since);
+            glib::idle_add_once(move || {
+                SyncthingService::global().apply_events(generation, result);
+            });
+        });
+    }
+
+    /// Apply a batch of events and wait for the next one.
+    fn apply_events(&self, generation: u64, result: Result<Vec<Event>, String>) {
+        if generation != self.events_generation.get() {
+            return;
+        }
+
+        match result {
+            Ok(events) => {
+                let since = self.last_event_id.get();
+                if let Some(last) = events.last() {
+                    self.last_event_id.set(last.id);
+                }
+                // The first batch only tells where the stream is; anything
+                // before it is covered by refetching the folders
+                if since == 0 {
+                    if !events.is_empty() {
+                        self.resync.set(true);
+                    }
+                    self.watch_events();
+                    return;
+                }
+                if events.first().is_some_and(|event| event.id > since + 1) {
+                    debug!("SyncthingService: missed events, refetching folders");
+                    self.resync.set(true);
+                }
+
+                let mut changed = false;
+                {
+                    let mut snapshot = self.snapshot.borrow_mut();
+                    for event in &events {
+                        changed |= apply_event(&mut snapshot.folders, event);
+                    }
+                }
+                if changed {
+                    self.notify();
+                }
+                self.watch_events();
+            }
+            Err(err) => {
+                // Syncthing restarted or went away; event ids start over
+                debug!("SyncthingService: events: {}", err);
+                self.last_event_id.set(0);
+                self.resync.set(true);
+
+                let interval = self
+                    .settings
+                    .borrow()
+                    .as_ref()
+                    .map_or(DEFAULT_POLL_INTERVAL, |settings| settings.poll_interval)
+                    .max(1);
+                glib::timeout_add_seconds_local_once(interval as u32, move || {
+                    let this = SyncthingService::global();
+                    if this.events_generation.get() == generation {
+                        this.watch_events();
+                    }
+                });
+            }
+        }
+    }
+}
+
+impl Drop for SyncthingService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Bytes per second between two counter samples.
+///
+/// Counters reset when Syncthing restarts; treat a decrease as no traffic.
+fn transfer_rate(previous: u64, current: u64, secs: f64) -> u64 {
+    if secs <= 0.0 {
+        return 0;
+    }
+    (current.saturating_sub(previous) as f64 / secs) as u64
+}
+
+#[derive(Deserialize)]
+struct FolderConfig {
+    id: String,
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    paused: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct DbStatus {
+    state: String,
+    need_files: u64,
+    need_directories: u64,
+    need_symlinks: u64,
+    need_deletes: u64,
+    need_bytes: u64,
+    global_bytes: u64,
+}
+
+/// One entry of `/rest/events`.
+#[derive(Deserialize)]
+struct Event {
+    id: u64,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct StateChanged {
+    folder: String,
+    to: String,
+}
+
+#[derive(Deserialize)]
+struct FolderSummary {
+    folder: String,
+    summary: DbStatus,
+}
+
+#[derive(Deserialize)]
+struct Connections {
+    total: ConnectionTotals,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionTotals {
+    in_bytes_total: u64,
+    out_bytes_total: u64,
+}
+
+/// Query the REST API for folder configs and transfer totals, plus the
+/// database status of folders missing from `known` (id to paused flag).
+fn fetch_status(
+    settings: &SyncthingSettings,
+    known: &HashMap<String, bool>,
+) -> Result<PollResult, String> {
+    let folders: Vec<FolderConfig> = get_json(settings, "/rest/config/folders")?;
+
+    let mut statuses = Vec::with_capacity(folders.len());
+    for folder in folders {
+        let status = if known.get(&folder.id) == Some(&folder.paused) {
+            None
+        } else {
+            let path = format!("/rest/db/status?folder={}", encode_component(&folder.id));
+            Some(get_json(settings, &path)?)
+        };
+        statuses.push((folder, status));
+    }
+
+    let connections: Connections = get_json(settings, "/rest/system/connections")?;
+
+    Ok(PollResult {
+        folders: statuses,
+        in_bytes_total: connections.total.in_bytes_total,
+        out_bytes_total: connections.total.out_bytes_total,
+        at: Instant::now(),
+    })
+}
+
+/// Wait for events after `since`, or for the latest one when `since` is 0.
+fn fetch_events(settings: &SyncthingSettings, since: u64) -> Result<Vec<Event>, String> {
+    let mut path = format!(
+        "/rest/events?events={}&since={}&timeout={}",
+        EVENT_TYPES, since, EVENTS_TIMEOUT_SECS
+    );
+    if since == 0 {
+        path.push_str("&limit=1");
+    }
+    get_json_with_timeout(settings, &path, EVENTS_TIMEOUT_SECS + REQUEST_TIMEOUT_SECS)
+}
+
+/// Fresh folder list from a poll, keeping the known status of folders that
+/// weren't refetched.
+fn merge_folders(
+    previous: &[FolderStatus],
+    polled: Vec<(FolderConfig, Option<DbStatus>)>,
+) -> Vec<FolderStatus> {
+    polled
+        .into_iter()
+        .filter_map(|(folder, status)| match status {
+            Some(status) => Some(folder_status(folder, status)),
+            None => {
+                let mut known = previous.iter().find(|known| known.id == folder.id)?.clone();
+                known.label = folder_label(&folder);
+                Some(known)
+            }
+        })
+        .collect()
+}
+
+/// Apply one event to the folder list; returns whether anything changed.
+fn apply_event(folders: &mut [FolderStatus], event: &Event) -> bool {
+    match event.kind.as_str() {
+        "StateChanged" => {
+            let Ok(change) = serde_json::from_value::<StateChanged>(event.data.clone()) else {
+                return false;
+            };
+            let Some(folder) = followed_folder(folders, &change.folder) else {
+                return false;
+            };
+            if folder.state == change.to {
+                return false;
+            }
+            folder.state = change.to;
+            true
+        }
+        "FolderSummary" => {
+            let Ok(summary) = serde_json::from_value::<FolderSummary>(event.data.clone()) else {
+                return false;
+            };
+            let Some(folder) = followed_folder(folders, &summary.folder) else {
+                return false;
+            };
+            let status = summary.summary;
+            let updated = FolderStatus {
+                need_items: need_items(&status),
+                need_bytes: status.need_bytes,
+                global_bytes: status.global_bytes,
+                state: status.state,
+                ..folder.clone()
+            };
+            if *folder == updated {
+                return false;
+            }
+            *folder = updated;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The folder with `id`, unless paused (paused folders keep their state).
+fn followed_folder<'a>(folders: &'a mut [FolderStatus], id: &str) -> Option<&'a mut FolderStatus> {
+    folders
+        .iter_mut()
+        .find(|folder| folder.id == id && !folder.paused)
+}
+
+/// Display label of a folder (falls back to the id).
+fn folder_label(folder: &FolderConfig) -> String {
+    if folder.label.is_empty() {
+        folder.id.clone()
+    } else {
+        folder.label.clone()
+    }
+}
+
+/// Items (files, directories, symlinks, deletes) still needed.
+fn need_items(status: &DbStatus) -> u64 {
+    status.need_files + status.need_directories + status.need_symlinks + status.need_deletes
+}
+
+/// Combine a folder's config entry and database status.
+fn folder_status(folder: FolderConfig, status: DbStatus) -> FolderStatus {
+    let label = folder_label(&folder);
+    let need_items = need_items(&status);
+    FolderStatus {
+        id: folder.id,
+        label,
+        state: if folder.paused {
+            "paused".to_string()
+        } else {
+            status.state
+        },
+        paused: folder.paused,
+        need_items,
+        need_bytes: status.need_bytes,
+        global_bytes: status.global_bytes,
+    }
+}
+
+/// Perform an authenticated GET and decode the JSON body.
+fn get_json<T: serde::de::DeserializeOwned>(
+    settings: &SyncthingSettings,
+    path: &str,
+) -> Result<T, String> {
+    get_json_with_timeout(settings, path, REQUEST_TIMEOUT_SECS)
+}
+
+/// `get_json` with a custom timeout (seconds), for long-polls.
+fn get_json_with_timeout<T: serde::de::DeserializeOwned>(
+    settings: &SyncthingSettings,
+    path: &str,
+    timeout: u64,
+) -> Result<T, String> {
+    let url = format!("{}{}", settings.url, path);
+    let response = minreq::get(&url)
+        .with_header("X-API-Key", &settings.api_key)
+        .with_timeout(timeout)
+        .send()
+        .map_err(|e| format!("Cannot reach Syncthing at {}: {}", settings.url, e))?;
+
+    match response.status_code {
+        200..=299 => {}
+        401 | 403 => return Err("Syncthing rejected the API key".to_string()),
+        code => return Err(format!("Syncthing returned HTTP {} for {}", code, path)),
+    }
+
+    serde_json::from_slice(response.as_bytes())
+        .map_err(|e| format!("Unexpected response from {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(state: &str, need_items: u64) -> FolderStatus {
+        FolderStatus {
+            id: "default".to_string(),
+            label: "Default".to_string(),
+            state: state.to_string(),
+            paused: false,
+            need_items,
+            need_bytes: 0,
+            global_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_folder_status_from_api() {
+        let config: Vec<FolderConfig> =
+            serde_json::from_str(r#"[{"id": "abcd-1234", "label": "", "paused": false}]"#).unwrap();
+        let status: DbStatus = serde_json::from_str(
+            r#"{"state": "syncing", "needFiles": 3, "needDirectories": 1,
+                "needDeletes": 2, "needBytes": 500, "globalBytes": 1000}"#,
+        )
+        .unwrap();
+
+        let folder = folder_status(config.into_iter().next().unwrap(), status);
+        assert_eq!(folder.label, "abcd-1234");
+        assert_eq!(folder.need_items, 6);
+        assert_eq!(folder.completion(), 50.0);
+        assert!(!folder.in_sync());
+    }
+
+    #[test]
+    fn test_snapshot_status() {
+        let mut snapshot = SyncthingSnapshot {
+            connected: true,
+            folders: vec![folder("idle", 0)],
+            ..Default::default()
+        };
+        assert_eq!(snapshot.status(), SyncStatus::UpToDate);
+
+        snapshot.folders.push(folder("syncing", 4));
+        assert_eq!(snapshot.status(), SyncStatus::Syncing);
+        assert_eq!(snapshot.out_of_sync_items(), 4);
+
+        snapshot.folders.push(folder("error", 0));
+        assert_eq!(snapshot.status(), SyncStatus::Error);
+
+        snapshot.connected = false;
+        assert_eq!(snapshot.status(), SyncStatus::Disconnected);
+    }
+
+    #[test]
+    fn test_transfer_rate() {
+        assert_eq!(transfer_rate(1000, 3000, 2.0), 1000);
+        assert_eq!(transfer_rate(5000, 100, 2.0), 0);
+        assert_eq!(transfer_rate(0, 100, 0.0), 0);
+    }
+
+    #[test]
+    fn test_apply_event() {
+        let event = |kind: &str, data: &str| Event {
+            id: 1,
+            kind: kind.to_string(),
+            data: serde_json::from_str(data).unwrap(),
+        };
+        let mut folders = vec![folder("idle", 0)];
+
+        let scanning = event(
+            "StateChanged",
+            r#"{"folder": "default", "from": "idle", "to": "scanning"}"#,
+        );
+        assert!(apply_event(&mut folders, &scanning));
+        assert_eq!(folders[0].state, "scanning");
+        assert!(!apply_event(&mut folders, &scanning));
+
+        let summary = event(
+            "FolderSummary",
+            r#"{"folder": "default", "summary": {"state": "syncing", "needFiles": 2,
+                "needBytes": 10, "globalBytes": 100}}"#,
+        );
+        assert!(apply_event(&mut folders, &summary));
+        assert_eq!(folders[0].state, "syncing");
+        assert_eq!(folders[0].need_items, 2);
+        assert_eq!(folders[0].completion(), 90.0);
+
+        // Other folders and paused ones are left alone
+        let other = event("StateChanged", r#"{"folder": "other", "to": "error"}"#);
+        assert!(!apply_event(&mut folders, &other));
+        folders[0].paused = true;
+        assert!(!apply_event(&mut folders, &scanning));
+        assert_eq!(folders[0].state, "syncing");
+    }
+
+    #[test]
+    fn test_merge_folders_keeps_known_status() {
+        let config = |id: &str, label: &str| FolderConfig {
+            id: id.to_string(),
+            label: label.to_string(),
+            paused: false,
+        };
+        let previous = vec![folder("syncing", 4)];
+
+        let merged = merge_folders(
+            &previous,
+            vec![
+                (config("default", "Documents"), None),
+                (config("photos", ""), Some(DbStatus::default())),
+                // Not fetched and not known: dropped until the next poll
+                (config("music", ""), None),
+            ],
+        );
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].label, "Documents");
+        assert_eq!(merged[0].state, "syncing");
+        assert_eq!(merged[0].need_items, 4);
+        assert_eq!(merged[1].label, "photos");
+    }
+}
@@ -0,0 +1,28 @@
+//! Helpers for reading sysfs attribute directories (hwmon, thermal, powercap).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entries of `root` starting with `prefix`, in numeric order.
+pub fn sorted_entries(root: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut entries: Vec<(u32, PathBuf)> = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let index = name.strip_prefix(prefix)?.parse().ok()?;
+            Some((index, entry.path()))
+        })
+        .collect();
+    entries.sort_by_key(|(index, _)| *index);
+    entries.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Contents of an attribute without surrounding whitespace; `None` when
+/// unreadable or empty.
+pub fn read_trimmed(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
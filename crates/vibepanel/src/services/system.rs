@@ -1,10 +1,17 @@
 //! SystemService - shared, polling-based system resource monitoring.
 //!
 //! This service provides CPU, memory, network, and load average metrics by polling
-//! the system at a configurable interval (default: 3 seconds).
+//! the system at a configurable interval (default: 3 seconds), along with the
+//! network interfaces and their addresses.
+//!
+//! Widgets subscribe with `connect_every` and their own `interval`. There is
+//! still a single sampler and a single timer: it runs at the shortest interval
+//! any live subscriber asked for, and each subscriber only hears about samples
+//! once its own interval has passed.
 //!
 //! Uses the `sysinfo` crate for cross-platform system information gathering.
 //! The `sysinfo::System` instance is reused across polls for efficiency.
+//! Sampling reads `/proc` and `/sys`, so it runs on the shared worker pool.
 //!
 //! ## Usage
 //!
@@ -17,16 +24,27 @@
 //! ```
 
 use std::cell::{Cell, RefCell};
+use std::net::IpAddr;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use gtk4::glib::{self, SourceId};
 use sysinfo::{Components, CpuRefreshKind, MemoryRefreshKind, Networks, RefreshKind, System};
 use tracing::{debug, trace};
 
-use super::callbacks::Callbacks;
+use super::callbacks::{CallbackId, Callbacks};
+use super::event_bus::EventBus;
+use super::worker::WorkerPool;
 
 /// Default polling interval in seconds.
-const DEFAULT_POLL_INTERVAL_SECS: u32 = 3;
+pub const DEFAULT_POLL_INTERVAL_SECS: u32 = 3;
+
+/// Shortest polling interval a widget may ask for, in seconds.
+pub const MIN_POLL_INTERVAL_SECS: u32 = 1;
+
+/// Timer jitter tolerated when deciding whether a subscriber is due.
+const DUE_SLACK: Duration = Duration::from_millis(500);
 
 /// Threshold above which CPU/memory is considered "high" usage.
 pub const HIGH_USAGE_THRESHOLD: f32 = 80.0;
@@ -67,11 +85,32 @@ pub struct SystemSnapshot {
     /// Network upload speed in bytes/sec (aggregated across all interfaces).
     pub net_upload_speed: u64,
 
+    /// Network interfaces other than loopback, sorted by name.
+    pub interfaces: Vec<NetworkInterface>,
+
     // Load Average
     /// System load averages: (1 min, 5 min, 15 min).
     pub load_avg: (f64, f64, f64),
 }
 
+/// A network interface and its addresses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkInterface {
+    /// Interface name (e.g., "wlan0", "enp3s0").
+    pub name: String,
+    /// Kernel operational state (e.g., "up", "down", "dormant").
+    pub state: String,
+    /// Assigned addresses, IPv4 before IPv6.
+    pub addresses: Vec<IpAddr>,
+}
+
+impl NetworkInterface {
+    /// Whether the interface is up and can carry traffic.
+    pub fn is_up(&self) -> bool {
+        self.state == "up"
+    }
+}
+
 impl SystemSnapshot {
     /// Create an initial "unknown" snapshot before first poll.
     ///
@@ -105,17 +144,17 @@ pub struct SystemService {
     /// Timer source for periodic polling.
     timer_source: RefCell<Option<SourceId>>,
 
-    /// Reusable sysinfo System instance.
-    sys: RefCell<System>,
+    /// Reusable sysinfo state, shared with the worker doing the sampling.
+    sampler: Arc<Mutex<Sampler>>,
 
-    /// Reusable sysinfo Networks instance.
-    networks: RefCell<Networks>,
-
-    /// Reusable sysinfo Components instance for temperature sensors.
-    components: RefCell<Components>,
+    /// Prevent overlapping samples when a poll is slow.
+    sample_in_progress: Cell<bool>,
 
     /// Polling interval in seconds.
     poll_interval: Cell<u32>,
+
+    /// Intervals asked for by `connect_every` subscribers.
+    requested_intervals: RefCell<Vec<(CallbackId, u32)>>,
 }
 
 impl SystemService {
@@ -123,27 +162,14 @@ impl SystemService {
     fn new() -> Rc<Self> {
         debug!("SystemService: initializing");
 
-        // Create System with specific refresh kinds for efficiency
-        let sys = System::new_with_specifics(
-            RefreshKind::nothing()
-                .with_cpu(CpuRefreshKind::everything())
-                .with_memory(MemoryRefreshKind::everything()),
-        );
-
-        // Create Networks instance for network monitoring
-        let networks = Networks::new_with_refreshed_list();
-
-        // Create Components instance for temperature sensors
-        let components = Components::new_with_refreshed_list();
-
         let service = Rc::new(Self {
             snapshot: RefCell::new(SystemSnapshot::unknown()),
             callbacks: Callbacks::new(),
             timer_source: RefCell::new(None),
-            sys: RefCell::new(sys),
-            networks: RefCell::new(networks),
-            components: RefCell::new(components),
+            sampler: Arc::new(Mutex::new(Sampler::new())),
+            sample_in_progress: Cell::new(false),
             poll_interval: Cell::new(DEFAULT_POLL_INTERVAL_SECS),
+            requested_intervals: RefCell::new(Vec::new()),
         });
 
         // Start polling
@@ -173,54 +199,176 @@ impl SystemService {
         self.callbacks.notify(&self.snapshot.borrow());
     }
 
+    /// Register a callback that wants a fresh snapshot every `interval`
+    /// seconds.
+    ///
+    /// Polling speeds up if `interval` is shorter than the current one; the
+    /// callback skips samples that arrive before its interval has passed.
+    /// The callback is immediately invoked with the current snapshot.
+    pub fn connect_every<F>(self: &Rc<Self>, interval: u32, callback: F) -> CallbackId
+    where
+        F: Fn(&SystemSnapshot) + 'static,
+    {
+        let interval = interval.max(MIN_POLL_INTERVAL_SECS);
+        let period = Duration::from_secs(interval as u64);
+        let last_delivery: Cell<Option<Instant>> = Cell::new(None);
+
+        let id = self.callbacks.register(move |snapshot: &SystemSnapshot| {
+            let now = Instant::now();
+            if !is_due(last_delivery.get(), now, period) {
+                return;
+            }
+            last_delivery.set(Some(now));
+            callback(snapshot);
+        });
+        self.requested_intervals.borrow_mut().push((id, interval));
+
+        if self.wanted_interval() != self.poll_interval.get() {
+            if let Some(source_id) = self.timer_source.borrow_mut().take() {
+                source_id.remove();
+            }
+            Self::start_timer(self);
+        }
+
+        self.callbacks.notify_single(id, &self.snapshot.borrow());
+        id
+    }
+
+    /// Shortest interval asked for by a live subscriber, or the default.
+    ///
+    /// Subscriptions dropped since (e.g. with a rebuilt bar) are forgotten.
+    fn wanted_interval(&self) -> u32 {
+        let bus = EventBus::global();
+        let mut requested = self.requested_intervals.borrow_mut();
+        requested.retain(|(id, _)| bus.is_subscribed(*id));
+        requested
+            .iter()
+            .map(|(_, interval)| *interval)
+            .min()
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS)
+    }
+
     /// Return the current system snapshot.
     pub fn snapshot(&self) -> SystemSnapshot {
         self.snapshot.borrow().clone()
     }
 
-    /// Start the periodic polling timer.
+    /// Start polling: sample once now, then on the timer.
     fn start_polling(this: &Rc<Self>) {
         // Do an initial poll immediately
         this.poll();
 
-        // Schedule periodic polls
+        Self::start_timer(this);
+    }
+
+    /// Schedule periodic polls at the interval subscribers currently want.
+    fn start_timer(this: &Rc<Self>) {
         let this_weak = Rc::downgrade(this);
-        let interval = this.poll_interval.get();
+        let interval = this.wanted_interval();
+        this.poll_interval.set(interval);
 
         debug!("SystemService: starting polling every {}s", interval);
 
         let source_id = glib::timeout_add_seconds_local(interval, move || {
-            if let Some(this) = this_weak.upgrade() {
-                this.poll();
-                glib::ControlFlow::Continue
-            } else {
-                glib::ControlFlow::Break
+            let Some(this) = this_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            this.poll();
+
+            // Slow down again once the subscribers that wanted faster
+            // samples are gone
+            if this.wanted_interval() != interval {
+                // This source ends by returning Break, so just forget its ID
+                this.timer_source.borrow_mut().take();
+                Self::start_timer(&this);
+                return glib::ControlFlow::Break;
             }
+            glib::ControlFlow::Continue
         });
 
         *this.timer_source.borrow_mut() = Some(source_id);
     }
 
-    /// Poll system metrics and update the snapshot.
+    /// Sample system metrics on the worker pool and update the snapshot.
     fn poll(&self) {
+        if self.sample_in_progress.replace(true) {
+            trace!("SystemService: previous sample still running, skipping");
+            return;
+        }
         trace!("SystemService: polling system metrics");
 
-        let mut sys = self.sys.borrow_mut();
-        let mut networks = self.networks.borrow_mut();
-        let mut components = self.components.borrow_mut();
+        let sampler = Arc::clone(&self.sampler);
+        let poll_interval = self.poll_interval.get();
+        WorkerPool::global().spawn(
+            move || {
+                // sysinfo state stays valid even if a previous sample panicked
+                let mut sampler = sampler.lock().unwrap_or_else(|e| e.into_inner());
+                sampler.sample(poll_interval)
+            },
+            |snapshot| SystemService::global().apply_sample(snapshot.ok()),
+        );
+    }
+
+    /// Store a fresh sample and notify callbacks (`None` if sampling
+    /// panicked).
+    fn apply_sample(&self, snapshot: Option<SystemSnapshot>) {
+        self.sample_in_progress.set(false);
+        let Some(snapshot) = snapshot else {
+            return;
+        };
+        *self.snapshot.borrow_mut() = snapshot;
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+/// Whether a subscriber last served at `last` should get a sample at `now`.
+fn is_due(last: Option<Instant>, now: Instant, period: Duration) -> bool {
+    match last {
+        Some(last) => now.saturating_duration_since(last) + DUE_SLACK >= period,
+        None => true,
+    }
+}
 
+/// Reusable sysinfo state for sampling off the main thread.
+struct Sampler {
+    sys: System,
+    networks: Networks,
+    /// Temperature sensors.
+    components: Components,
+}
+
+impl Sampler {
+    fn new() -> Self {
+        Self {
+            // Create System with specific refresh kinds for efficiency
+            sys: System::new_with_specifics(
+                RefreshKind::nothing()
+                    .with_cpu(CpuRefreshKind::everything())
+                    .with_memory(MemoryRefreshKind::everything()),
+            ),
+            networks: Networks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+        }
+    }
+
+    /// Refresh all metrics and build a snapshot.
+    ///
+    /// `poll_interval` is the time since the previous sample, in seconds,
+    /// used to turn network byte counts into speeds.
+    fn sample(&mut self, poll_interval: u32) -> SystemSnapshot {
         // Refresh CPU and memory data
-        sys.refresh_cpu_all();
-        sys.refresh_memory();
+        self.sys.refresh_cpu_all();
+        self.sys.refresh_memory();
 
         // Refresh network data
-        networks.refresh(true);
+        self.networks.refresh(true);
 
         // Refresh temperature sensors
-        components.refresh(true);
+        self.components.refresh(true);
 
         // Calculate global CPU usage (average of all cores)
-        let cpus = sys.cpus();
+        let cpus = self.sys.cpus();
         let cpu_usage = if cpus.is_empty() {
             0.0
         } else {
@@ -229,11 +377,11 @@ impl SystemService {
 
         // Per-core usage
         let cpu_per_core: Vec<f32> = cpus.iter().map(|cpu| cpu.cpu_usage()).collect();
-        let cpu_core_count = sys.physical_core_count().unwrap_or(cpus.len());
+        let cpu_core_count = self.sys.physical_core_count().unwrap_or(cpus.len());
 
         // CPU temperature - find the most relevant sensor
         // Common labels: "Package id 0", "Tctl", "CPU", "Core 0", "k10temp Tctl", etc.
-        let cpu_component = components.iter().find(|c| {
+        let cpu_component = self.components.iter().find(|c| {
             let label = c.label().to_lowercase();
             label.contains("package")
                 || label.contains("tctl")
@@ -244,8 +392,8 @@ impl SystemService {
         let cpu_temp = cpu_component.and_then(|c| c.temperature());
 
         // Memory
-        let memory_total = sys.total_memory();
-        let memory_used = sys.used_memory();
+        let memory_total = self.sys.total_memory();
+        let memory_used = self.sys.used_memory();
         let memory_percent = if memory_total > 0 {
             (memory_used as f64 / memory_total as f64 * 100.0) as f32
         } else {
@@ -254,9 +402,11 @@ impl SystemService {
 
         // Network speeds (aggregate across all interfaces)
         // received() and transmitted() return bytes since last refresh
-        let poll_interval = self.poll_interval.get() as u64;
-        let (net_download, net_upload) =
-            networks.iter().fold((0u64, 0u64), |(dl, ul), (_, data)| {
+        let poll_interval = poll_interval as u64;
+        let (net_download, net_upload) = self
+            .networks
+            .iter()
+            .fold((0u64, 0u64), |(dl, ul), (_, data)| {
                 (dl + data.received(), ul + data.transmitted())
             });
         // Convert to bytes/sec
@@ -271,12 +421,19 @@ impl SystemService {
             net_upload
         };
 
+        let interfaces = collect_interfaces(
+            self.networks.iter().map(|(name, data)| {
+                let addresses = data.ip_networks().iter().map(|network| network.addr);
+                (name.as_str(), addresses.collect())
+            }),
+            interface_state,
+        );
+
         // Load average
         let load_avg = System::load_average();
         let load_avg_tuple = (load_avg.one, load_avg.five, load_avg.fifteen);
 
-        // Update snapshot
-        let new_snapshot = SystemSnapshot {
+        SystemSnapshot {
             available: true,
             cpu_usage,
             cpu_per_core,
@@ -287,15 +444,40 @@ impl SystemService {
             memory_percent,
             net_download_speed,
             net_upload_speed,
+            interfaces,
             load_avg: load_avg_tuple,
-        };
-
-        // Store and notify
-        *self.snapshot.borrow_mut() = new_snapshot;
-        self.callbacks.notify(&self.snapshot.borrow());
+        }
     }
 }
 
+/// Read an interface's operational state from sysfs.
+fn interface_state(name: &str) -> String {
+    std::fs::read_to_string(format!("/sys/class/net/{}/operstate", name))
+        .map(|state| state.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Build the interface list from `(name, addresses)` pairs, skipping loopback.
+fn collect_interfaces<'a>(
+    interfaces: impl Iterator<Item = (&'a str, Vec<IpAddr>)>,
+    state: impl Fn(&str) -> String,
+) -> Vec<NetworkInterface> {
+    let mut interfaces: Vec<NetworkInterface> = interfaces
+        .filter(|(name, _)| *name != "lo")
+        .map(|(name, mut addresses)| {
+            addresses.sort();
+            addresses.dedup();
+            NetworkInterface {
+                name: name.to_string(),
+                state: state(name),
+                addresses,
+            }
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
 impl Drop for SystemService {
     fn drop(&mut self) {
         // Cancel the timer when the service is dropped
@@ -386,6 +568,38 @@ mod tests {
         assert_eq!(format_speed(1536 * 1024), "1.5 MB/s");
     }
 
+    #[test]
+    fn test_is_due() {
+        let now = Instant::now();
+        let period = Duration::from_secs(10);
+        assert!(is_due(None, now, period));
+        assert!(!is_due(Some(now), now + Duration::from_secs(3), period));
+        // A timer firing slightly early still counts
+        assert!(is_due(Some(now), now + Duration::from_millis(9800), period));
+        assert!(is_due(Some(now), now + Duration::from_secs(12), period));
+    }
+
+    #[test]
+    fn test_collect_interfaces() {
+        let v4: IpAddr = "192.168.1.20".parse().unwrap();
+        let v6: IpAddr = "fe80::1".parse().unwrap();
+        let interfaces = collect_interfaces(
+            [
+                ("wlan0", vec![v6, v4, v4]),
+                ("lo", vec!["127.0.0.1".parse().unwrap()]),
+                ("enp3s0", vec![]),
+            ]
+            .into_iter(),
+            |name| if name == "wlan0" { "up" } else { "down" }.to_string(),
+        );
+
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["enp3s0", "wlan0"]);
+        assert!(!interfaces[0].is_up());
+        assert!(interfaces[1].is_up());
+        assert_eq!(interfaces[1].addresses, vec![v4, v6]);
+    }
+
     #[test]
     fn test_snapshot_unknown() {
         let snapshot = SystemSnapshot::unknown();
@@ -0,0 +1,631 @@
+//! TailscaleService - tailnet connection state via the `tailscale` CLI.
+//!
+//! This service provides:
+//! - Backend state (running, stopped, needs login)
+//! - The active exit node, if any
+//! - The peer list with Tailscale IPs and online state
+//! - Connect/disconnect via `tailscale up` / `tailscale down`
+//! - The login URL `tailscale up` prints when the node needs to log in
+//!
+//! State is read from `tailscale status --json` in a background thread.
+//! Changing state requires the user to be the tailscaled operator
+//! (`sudo tailscale set --operator=$USER`); failures are surfaced as errors.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::rc::Rc;
+
+use gtk4::glib::{self, SourceId};
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use super::actions::{ActionKind, Actions};
+use super::callbacks::Callbacks;
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for TailscaleService
+thread_local! {
+    static TAILSCALE_INSTANCE: RefCell<Option<Rc<TailscaleService>>> = const { RefCell::new(None) };
+}
+
+/// Default poll interval in seconds.
+pub const DEFAULT_POLL_INTERVAL: u64 = 10;
+
+/// How long `tailscale up` waits for the tailnet, including a login in the
+/// browser, before giving up (seconds).
+const UP_TIMEOUT_SECS: u64 = 120;
+
+/// Connection state of the local tailscaled backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendState {
+    /// Connected to the tailnet.
+    Running,
+    /// Disconnected (`tailscale down`).
+    Stopped,
+    /// Logged out or the node key expired.
+    NeedsLogin,
+    /// Starting up or in another transitional state.
+    Starting,
+    /// tailscaled is not reachable.
+    #[default]
+    Unavailable,
+}
+
+impl BackendState {
+    fn from_api(state: &str) -> Self {
+        match state {
+            "Running" => Self::Running,
+            "Stopped" => Self::Stopped,
+            "NeedsLogin" | "NeedsMachineAuth" => Self::NeedsLogin,
+            "NoState" => Self::Unavailable,
+            _ => Self::Starting,
+        }
+    }
+
+    /// Human-readable label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Running => "Connected",
+            Self::Stopped => "Disconnected",
+            Self::NeedsLogin => "Login required",
+            Self::Starting => "Connecting...",
+            Self::Unavailable => "Not running",
+        }
+    }
+}
+
+/// A device on the tailnet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TailscalePeer {
+    /// Host name.
+    pub name: String,
+    /// MagicDNS name without the trailing dot, if any.
+    pub dns_name: Option<String>,
+    /// Operating system reported by the peer.
+    pub os: String,
+    /// Tailscale IPs (IPv4 first).
+    pub ips: Vec<String>,
+    /// Whether the peer is currently online.
+    pub online: bool,
+    /// Whether this peer is the active exit node.
+    pub exit_node: bool,
+}
+
+impl TailscalePeer {
+    /// Primary IP address (IPv4 if available).
+    pub fn primary_ip(&self) -> Option<&str> {
+        self.ips.first().map(String::as_str)
+    }
+}
+
+/// Canonical snapshot of Tailscale state.
+#[derive(Debug, Clone, Default)]
+pub struct TailscaleSnapshot {
+    /// Whether the `tailscale` CLI was found.
+    pub available: bool,
+    /// Backend state.
+    pub state: BackendState,
+    /// This device, if known.
+    pub self_node: Option<TailscalePeer>,
+    /// Other devices, online first, then by name.
+    pub peers: Vec<TailscalePeer>,
+    /// Whether a connect/disconnect is in progress.
+    pub busy: bool,
+    /// Last error message, if any.
+    pub error: Option<String>,
+    /// Login page printed by `tailscale up`, until connected.
+    pub login_url: Option<String>,
+}
+
+impl TailscaleSnapshot {
+    /// Whether connected to the tailnet.
+    pub fn connected(&self) -> bool {
+        self.state == BackendState::Running
+    }
+
+    /// The active exit node, if any.
+    pub fn exit_node(&self) -> Option<&TailscalePeer> {
+        self.peers.iter().find(|peer| peer.exit_node)
+    }
+
+    /// Number of peers currently online.
+    pub fn online_peers(&self) -> usize {
+        self.peers.iter().filter(|peer| peer.online).count()
+    }
+}
+
+/// Parsed `tailscale status` output.
+#[derive(Debug)]
+struct StatusResult {
+    state: BackendState,
+    self_node: Option<TailscalePeer>,
+    peers: Vec<TailscalePeer>,
+}
+
+/// Shared, process-wide Tailscale service.
+pub struct TailscaleService {
+    snapshot: RefCell<TailscaleSnapshot>,
+    callbacks: Callbacks<TailscaleSnapshot>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Current poll interval (seconds), 0 until started.
+    poll_interval: Cell<u64>,
+    /// Prevent overlapping polls.
+    poll_in_progress: Cell<bool>,
+}
+
+impl TailscaleService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(TailscaleSnapshot::default()),
+            callbacks: Callbacks::new(),
+            timer_source: RefCell::new(None),
+            poll_interval: Cell::new(0),
+            poll_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global TailscaleService singleton.
+    pub fn global() -> Rc<Self> {
+        TAILSCALE_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(TailscaleService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        TAILSCALE_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&TailscaleSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> TailscaleSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: TailscaleSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        self.notify();
+    }
+
+    /// Start polling at the given interval (seconds).
+    ///
+    /// Calling again with the same interval is a no-op.
+    pub fn start(self: &Rc<Self>, interval: u64) {
+        let interval = interval.max(1);
+        if self.poll_interval.get() == interval {
+            return;
+        }
+        self.poll_interval.set(interval);
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Trigger an immediate status refresh.
+    pub fn refresh(&self) {
+        if self.poll_in_progress.get() {
+            return;
+        }
+        self.poll_in_progress.set(true);
+
+        WorkerPool::global().spawn(fetch_status, |result| {
+            TailscaleService::global()
+                .apply_status(result.unwrap_or_else(|e| Err(StatusError::Failed(e.to_string()))));
+        });
+    }
+
+    /// Connect (`tailscale up`) or disconnect (`tailscale down`).
+    pub fn set_connected(&self, connected: bool) {
+        if self.snapshot.borrow().busy {
+            return;
+        }
+        info!(
+            "TailscaleService: {}",
+            if connected {
+                "connecting"
+            } else {
+                "disconnecting"
+            }
+        );
+
+        let command = if connected { "up" } else { "down" };
+        let mut tailscale = Command::new("tailscale");
+        tailscale.arg(command);
+        if connected {
+            tailscale.arg(format!("--timeout={}s", UP_TIMEOUT_SECS));
+        }
+        tailscale
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        // Through the dispatcher so kiosk mode refuses it
+        let child = match Actions::global().spawn(ActionKind::Exec, &mut tailscale) {
+            Ok(child) => child,
+            Err(e) => {
+                let err = format!("tailscale {} failed: {}", command, e);
+                warn!("TailscaleService: {}", err);
+                self.snapshot.borrow_mut().error = Some(err);
+                self.notify();
+                return;
+            }
+        };
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.busy = true;
+            snapshot.login_url = None;
+        }
+        self.notify();
+
+        // `up` may wait minutes for a login, so not on a shared worker
+        WorkerPool::global().spawn_long(
+            "tailscale",
+            move || {
+                wait_tailscale(command, child, |url| {
+                    glib::idle_add_once(move || {
+                        TailscaleService::global().apply_login_url(url);
+                    });
+                })
+            },
+            |result| {
+                let service = TailscaleService::global();
+                {
+                    let mut snapshot = service.snapshot.borrow_mut();
+                    snapshot.busy = false;
+                    if let Err(err) = result.unwrap_or_else(|e| Err(e.to_string())) {
+                        warn!("TailscaleService: {}", err);
+                        snapshot.error = Some(err);
+                    }
+                }
+                service.notify();
+                service.refresh();
+            },
+        );
+    }
+
+    /// Open the login page printed by `tailscale up`.
+    pub fn open_login(&self) -> Result<(), String> {
+        let url = self.snapshot.borrow().login_url.clone();
+        match url {
+            Some(url) => Actions::global().open_url(&url),
+            None => Err("no login pending".to_string()),
+        }
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Apply a login URL read from a running `tailscale up`.
+    fn apply_login_url(&self, url: String) {
+        info!("TailscaleService: login required at {}", url);
+        self.snapshot.borrow_mut().login_url = Some(url);
+        self.notify();
+    }
+
+    /// Apply the result of a background status query.
+    fn apply_status(&self, result: Result<StatusResult, StatusError>) {
+        self.poll_in_progress.set(false);
+
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            match result {
+                Ok(status) => {
+                    if snapshot.state != status.state {
+                        debug!("TailscaleService: state {:?}", status.state);
+                    }
+                    snapshot.available = true;
+                    snapshot.state = status.state;
+                    if status.state == BackendState::Running {
+                        snapshot.login_url = None;
+                    }
+                    snapshot.self_node = status.self_node;
+                    snapshot.peers = status.peers;
+                    if !snapshot.busy {
+                        snapshot.error = None;
+                    }
+                }
+                Err(StatusError::NotInstalled) => {
+                    snapshot.available = false;
+                    snapshot.state = BackendState::Unavailable;
+                }
+                Err(StatusError::Failed(err)) => {
+                    if snapshot.error.as_deref() != Some(err.as_str()) {
+                        warn!("TailscaleService: {}", err);
+                    }
+                    snapshot.available = true;
+                    snapshot.state = BackendState::Unavailable;
+                    snapshot.peers.clear();
+                    snapshot.error = Some(err);
+                }
+            }
+        }
+        self.notify();
+    }
+}
+
+impl Drop for TailscaleService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Why a status query failed.
+#[derive(Debug)]
+enum StatusError {
+    /// The `tailscale` binary isn't installed.
+    NotInstalled,
+    /// The command failed or returned unexpected output.
+    Failed(String),
+}
+
+/// Run `tailscale status --json` and parse it.
+fn fetch_status() -> Result<StatusResult, StatusError> {
+    let output = Command::new("tailscale")
+        .args(["status", "--json"])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => StatusError::NotInstalled,
+            _ => StatusError::Failed(format!("Failed to run tailscale: {}", e)),
+        })?;
+
+    // `tailscale status` exits non-zero when stopped but still prints JSON,
+    // so try to parse stdout before looking at the exit status.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match parse_status(&stdout) {
+        Ok(status) => Ok(status),
+        Err(_) if !output.status.success() => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().next().unwrap_or("").trim();
+            Err(StatusError::Failed(format!("tailscale: {}", message)))
+        }
+        Err(e) => Err(StatusError::Failed(e)),
+    }
+}
+
+/// Wait for a `tailscale` subcommand started with piped stderr, passing
+/// login URLs it prints to `on_login_url` as they appear.
+fn wait_tailscale<F>(command: &str, mut child: Child, on_login_url: F) -> Result<(), String>
+where
+    F: Fn(String),
+{
+    let mut message = None;
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if let Some(url) = login_url(&line) {
+                on_login_url(url.to_string());
+            } else if message.is_none() && !line.trim().is_empty() {
+                message = Some(line.trim().to_string());
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to run tailscale: {}", e))?;
+    if !status.success() {
+        return Err(format!(
+            "tailscale {} failed: {}",
+            command,
+            message.unwrap_or_else(|| status.to_string())
+        ));
+    }
+
+    Ok(())
+}
+
+/// The login URL on a line of `tailscale up` output, which prints it
+/// indented below "To authenticate, visit:".
+fn login_url(line: &str) -> Option<&str> {
+    let line = line.trim();
+    (line.starts_with("https://") && !line.contains(char::is_whitespace)).then_some(line)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StatusJson {
+    backend_state: String,
+    #[serde(rename = "Self")]
+    self_node: Option<PeerJson>,
+    #[serde(default)]
+    peer: Option<HashMap<String, PeerJson>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PeerJson {
+    #[serde(default)]
+    host_name: String,
+    #[serde(rename = "DNSName", default)]
+    dns_name: String,
+    #[serde(rename = "OS", default)]
+    os: String,
+    #[serde(rename = "TailscaleIPs", default)]
+    tailscale_ips: Option<Vec<String>>,
+    #[serde(default)]
+    online: bool,
+    #[serde(default)]
+    exit_node: bool,
+}
+
+impl From<PeerJson> for TailscalePeer {
+    fn from(peer: PeerJson) -> Self {
+        let dns_name = peer.dns_name.trim_end_matches('.');
+        let mut ips = peer.tailscale_ips.unwrap_or_default();
+        // IPv4 addresses sort before IPv6 ones
+        ips.sort_by_key(|ip| ip.contains(':'));
+        Self {
+            name: peer.host_name,
+            dns_name: (!dns_name.is_empty()).then(|| dns_name.to_string()),
+            os: peer.os,
+            ips,
+            online: peer.online,
+            exit_node: peer.exit_node,
+        }
+    }
+}
+
+/// Parse `tailscale status --json` output.
+fn parse_status(output: &str) -> Result<StatusResult, String> {
+    let status: StatusJson =
+        serde_json::from_str(output).map_err(|e| format!("Unexpected tailscale output: {}", e))?;
+
+    let mut peers: Vec<TailscalePeer> = status
+        .peer
+        .unwrap_or_default()
+        .into_values()
+        .map(TailscalePeer::from)
+        .collect();
+    peers.sort_by(|a, b| {
+        b.online
+            .cmp(&a.online)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    Ok(StatusResult {
+        state: BackendState::from_api(&status.backend_state),
+        self_node: status.self_node.map(TailscalePeer::from),
+        peers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS_JSON: &str = r#"{
+        "Version": "1.62.0",
+        "BackendState": "Running",
+        "Self": {
+            "HostName": "laptop",
+            "DNSName": "laptop.tail1234.ts.net.",
+            "OS": "linux",
+            "TailscaleIPs": ["fd7a:115c:a1e0::1", "100.64.0.1"],
+            "Online": true
+        },
+        "Peer": {
+            "nodekey:aaa": {
+                "HostName": "nas",
+                "DNSName": "nas.tail1234.ts.net.",
+                "OS": "linux",
+                "TailscaleIPs": ["100.64.0.2"],
+                "Online": false,
+                "ExitNode": false
+            },
+            "nodekey:bbb": {
+                "HostName": "Router",
+                "DNSName": "router.tail1234.ts.net.",
+                "OS": "linux",
+                "TailscaleIPs": ["100.64.0.3"],
+                "Online": true,
+                "ExitNode": true
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_status() {
+        let status = parse_status(STATUS_JSON).unwrap();
+        assert_eq!(status.state, BackendState::Running);
+
+        let self_node = status.self_node.unwrap();
+        assert_eq!(self_node.primary_ip(), Some("100.64.0.1"));
+        assert_eq!(
+            self_node.dns_name.as_deref(),
+            Some("laptop.tail1234.ts.net")
+        );
+
+        // Online peers first
+        let names: Vec<&str> = status.peers.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Router", "nas"]);
+    }
+
+    #[test]
+    fn test_snapshot_exit_node() {
+        let status = parse_status(STATUS_JSON).unwrap();
+        let snapshot = TailscaleSnapshot {
+            available: true,
+            state: status.state,
+            self_node: status.self_node,
+            peers: status.peers,
+            ..Default::default()
+        };
+        assert!(snapshot.connected());
+        assert_eq!(
+            snapshot.exit_node().map(|p| p.name.as_str()),
+            Some("Router")
+        );
+        assert_eq!(snapshot.online_peers(), 1);
+    }
+
+    #[test]
+    fn test_parse_stopped_status() {
+        let status =
+            parse_status(r#"{"BackendState": "Stopped", "Self": null, "Peer": null}"#).unwrap();
+        assert_eq!(status.state, BackendState::Stopped);
+        assert!(status.peers.is_empty());
+    }
+
+    #[test]
+    fn test_login_url() {
+        assert_eq!(
+            login_url("\thttps://login.tailscale.com/a/1b2c3d"),
+            Some("https://login.tailscale.com/a/1b2c3d")
+        );
+        assert_eq!(login_url("To authenticate, visit:"), None);
+        assert_eq!(login_url("see https://tailscale.com/s/up"), None);
+    }
+
+    #[test]
+    fn test_wait_tailscale_reports_login_url() {
+        let child = Command::new("sh")
+            .args([
+                "-c",
+                "printf 'To authenticate, visit:\\n\\n\\thttps://login.tailscale.com/a/xyz\\n' >&2; exit 1",
+            ])
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let urls = RefCell::new(Vec::new());
+        let result = wait_tailscale("up", child, |url| urls.borrow_mut().push(url));
+        assert_eq!(*urls.borrow(), ["https://login.tailscale.com/a/xyz"]);
+        assert_eq!(
+            result.unwrap_err(),
+            "tailscale up failed: To authenticate, visit:"
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! TemperatureService - hardware temperature sensors from sysfs.
+//!
+//! This service provides:
+//! - Periodic reads of every `hwmon` temperature input
+//!   (`/sys/class/hwmon/hwmon*/temp*_input`, named after the chip and label)
+//! - Thermal zones (`/sys/class/thermal/thermal_zone*/temp`, named by type)
+//!
+//! Reads run on the shared worker pool; some drivers (NVMe, drivetemp) are
+//! slow enough to stall the main loop.
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use gtk4::glib::{self, SourceId};
+use tracing::{debug, info};
+
+use super::callbacks::Callbacks;
+use super::sysfs::{read_trimmed, sorted_entries};
+use super::widget_settings::{SettingsId, WidgetSettings};
+use super::worker::WorkerPool;
+
+/// Default check interval in seconds.
+pub const DEFAULT_CHECK_INTERVAL: u64 = 5;
+
+const HWMON_PATH: &str = "/sys/class/hwmon";
+const THERMAL_PATH: &str = "/sys/class/thermal";
+
+/// Chips / zone types that usually report the CPU package temperature,
+/// in order of preference, for when no sensor is configured.
+const PREFERRED_SENSORS: &[&str] = &[
+    "coretemp",
+    "k10temp",
+    "zenpower",
+    "cpu_thermal",
+    "x86_pkg_temp",
+    "acpitz",
+];
+
+/// A single temperature reading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensor {
+    /// Display name, e.g. "coretemp: Package id 0" or "x86_pkg_temp".
+    pub name: String,
+    /// Current temperature in °C.
+    pub celsius: f64,
+    /// Critical temperature reported by the driver, if any.
+    pub critical: Option<f64>,
+}
+
+/// Canonical snapshot of all sensors.
+#[derive(Debug, Clone, Default)]
+pub struct TemperatureSnapshot {
+    /// Whether a read has completed.
+    pub ready: bool,
+    /// All detected sensors (hwmon first, then thermal zones).
+    pub sensors: Vec<Sensor>,
+}
+
+impl TemperatureSnapshot {
+    /// The sensor matching `query`, or the likely CPU sensor when `None`.
+    pub fn find(&self, query: Option<&str>) -> Option<&Sensor> {
+        find_sensor(&self.sensors, query)
+    }
+}
+
+/// Shared, process-wide temperature service.
+pub struct TemperatureService {
+    snapshot: RefCell<TemperatureSnapshot>,
+    callbacks: Callbacks<TemperatureSnapshot>,
+    /// Read interval of each widget.
+    intervals: WidgetSettings<u64>,
+    /// Shortest interval, which the timer runs at.
+    check_interval: Cell<Option<u64>>,
+    timer_source: RefCell<Option<SourceId>>,
+    /// Prevent overlapping reads.
+    check_in_progress: Cell<bool>,
+}
+
+impl TemperatureService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(TemperatureSnapshot::default()),
+            callbacks: Callbacks::new(),
+            intervals: WidgetSettings::new(),
+            check_interval: Cell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global TemperatureService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<TemperatureService> = TemperatureService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&TemperatureSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> TemperatureSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Register a widget's read interval; the shortest one is used.
+    pub fn configure(self: &Rc<Self>, check_interval: u64) -> SettingsId {
+        let id = self.intervals.add(check_interval.max(1));
+        self.apply_interval();
+        id
+    }
+
+    /// Forget a widget's interval; reads stop with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.intervals.remove(id) {
+            self.apply_interval();
+        }
+    }
+
+    /// Restart the timer at the shortest registered interval.
+    fn apply_interval(self: &Rc<Self>) {
+        let check_interval = self.intervals.merged();
+        if self.check_interval.get() == check_interval {
+            return;
+        }
+        self.check_interval.set(check_interval);
+
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+        let Some(check_interval) = check_interval else {
+            debug!("TemperatureService: no widgets left, stopping");
+            return;
+        };
+        info!(
+            "TemperatureService: reading sensors every {}s",
+            check_interval
+        );
+
+        let this_weak = Rc::downgrade(self);
+        let source_id = glib::timeout_add_seconds_local(check_interval as u32, move || {
+            if let Some(this) = this_weak.upgrade() {
+                this.refresh();
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+        *self.timer_source.borrow_mut() = Some(source_id);
+
+        self.refresh();
+    }
+
+    /// Trigger an immediate read of all sensors.
+    pub fn refresh(&self) {
+        if self.check_in_progress.get() {
+            return;
+        }
+        self.check_in_progress.set(true);
+
+        WorkerPool::global().spawn(
+            || read_sensors(Path::new(HWMON_PATH), Path::new(THERMAL_PATH)),
+            |sensors| TemperatureService::global().apply_sensors(sensors.ok()),
+        );
+    }
+
+    fn apply_sensors(&self, sensors: Option<Vec<Sensor>>) {
+        self.check_in_progress.set(false);
+        let Some(sensors) = sensors else {
+            return;
+        };
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if snapshot.ready && snapshot.sensors == sensors {
+                return;
+            }
+            if !snapshot.ready {
+                debug!("TemperatureService: found {} sensors", sensors.len());
+            }
+            snapshot.ready = true;
+            snapshot.sensors = sensors;
+        }
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+impl Drop for TemperatureService {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.timer_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+}
+
+/// Read all hwmon and thermal zone sensors under the given sysfs roots.
+fn read_sensors(hwmon_root: &Path, thermal_root: &Path) -> Vec<Sensor> {
+    let mut sensors = Vec::new();
+
+    for chip in sorted_entries(hwmon_root, "hwmon") {
+        let chip_name = read_trimmed(&chip.join("name")).unwrap_or_else(|| file_name(&chip));
+        let mut inputs: Vec<(u32, PathBuf)> = fs::read_dir(&chip)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                let index = name.strip_prefix("temp")?.strip_suffix("_input")?;
+                Some((index.parse().ok()?, entry.path()))
+            })
+            .collect();
+        inputs.sort_by_key(|(index, _)| *index);
+
+        for (index, input) in inputs {
+            let Some(celsius) = read_millidegrees(&input) else {
+                continue;
+            };
+            let label = read_trimmed(&chip.join(format!("temp{}_label", index)))
+                .unwrap_or_else(|| format!("temp{}", index));
+            sensors.push(Sensor {
+                name: format!("{}: {}", chip_name, label),
+                celsius,
+                critical: read_millidegrees(&chip.join(format!("temp{}_crit", index))),
+            });
+        }
+    }
+
+    for zone in sorted_entries(thermal_root, "thermal_zone") {
+        let Some(celsius) = read_millidegrees(&zone.join("temp")) else {
+            continue;
+        };
+        sensors.push(Sensor {
+            name: read_trimmed(&zone.join("type")).unwrap_or_else(|| file_name(&zone)),
+            celsius,
+            critical: None,
+        });
+    }
+
+    sensors
+}
+
+/// Find the sensor matching `query` (exact name first, then substring, both
+/// case-insensitive), or the preferred CPU sensor when no query is given.
+fn find_sensor<'a>(sensors: &'a [Sensor], query: Option<&str>) -> Option<&'a Sensor> {
+    match query {
+        Some(query) => {
+            let query = query.to_lowercase();
+            sensors
+                .iter()
+                .find(|s| s.name.to_lowercase() == query)
+                .or_else(|| {
+                    sensors
+                        .iter()
+                        .find(|s| s.name.to_lowercase().contains(&query))
+                })
+        }
+        None => PREFERRED_SENSORS
+            .iter()
+            .find_map(|chip| sensors.iter().find(|s| s.name.starts_with(chip)))
+            .or_else(|| sensors.first()),
+    }
+}
+
+/// Read a sysfs temperature in millidegrees Celsius.
+fn read_millidegrees(path: &Path) -> Option<f64> {
+    let value: i64 = read_trimmed(path)?.parse().ok()?;
+    Some(value as f64 / 1000.0)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor(name: &str, celsius: f64) -> Sensor {
+        Sensor {
+            name: name.to_string(),
+            celsius,
+            critical: None,
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_read_sensors() {
+        let root = std::env::temp_dir().join(format!("vibepanel-sensors-{}", std::process::id()));
+        let hwmon = root.join("hwmon");
+        let thermal = root.join("thermal");
+
+        write(&hwmon.join("hwmon1/name"), "coretemp\n");
+        write(&hwmon.join("hwmon1/temp2_input"), "48000\n");
+        write(&hwmon.join("hwmon1/temp1_input"), "52500\n");
+        write(&hwmon.join("hwmon1/temp1_label"), "Package id 0\n");
+        write(&hwmon.join("hwmon1/temp1_crit"), "100000\n");
+        // Unreadable inputs are skipped
+        write(&hwmon.join("hwmon0/name"), "nvme\n");
+        write(&hwmon.join("hwmon0/temp1_input"), "\n");
+        write(&thermal.join("thermal_zone0/type"), "acpitz\n");
+        write(&thermal.join("thermal_zone0/temp"), "27800\n");
+
+        let sensors = read_sensors(&hwmon, &thermal);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            sensors,
+            vec![
+                Sensor {
+                    name: "coretemp: Package id 0".to_string(),
+                    celsius: 52.5,
+                    critical: Some(100.0),
+                },
+                sensor("coretemp: temp2", 48.0),
+                sensor("acpitz", 27.8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_sensor() {
+        let sensors = vec![
+            sensor("nvme: Composite", 40.0),
+            sensor("k10temp: Tctl", 55.0),
+            sensor("amdgpu: edge", 45.0),
+        ];
+        // Default prefers the CPU package sensor
+        assert_eq!(find_sensor(&sensors, None).unwrap().name, "k10temp: Tctl");
+        assert_eq!(
+            find_sensor(&sensors, Some("AMDGPU: edge")).unwrap().name,
+            "amdgpu: edge"
+        );
+        assert_eq!(
+            find_sensor(&sensors, Some("composite")).unwrap().name,
+            "nvme: Composite"
+        );
+        assert!(find_sensor(&sensors, Some("coretemp")).is_none());
+        assert_eq!(
+            find_sensor(&[sensor("acpitz", 30.0)], None).unwrap().name,
+            "acpitz"
+        );
+    }
+}
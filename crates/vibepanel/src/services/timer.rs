@@ -0,0 +1,475 @@
+//! TimerService - pomodoro / focus timer.
+//!
+//! This service provides:
+//! - Work / short break / long break phases with a long break every N
+//!   work sessions
+//! - Start, pause/resume, skip and reset
+//! - A desktop notification when an interval completes
+//!
+//! The timer runs in the service rather than in the widget, so a running
+//! interval survives bar rebuilds (monitor hotplug, config reloads).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use gtk4::gio;
+use gtk4::glib::{self, SourceId};
+use gtk4::prelude::*;
+use tracing::{debug, info, warn};
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Timer phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerPhase {
+    /// Focused work interval.
+    Work,
+    /// Short break between work intervals.
+    ShortBreak,
+    /// Long break after several work intervals.
+    LongBreak,
+}
+
+impl TimerPhase {
+    /// Display name of the phase.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Work => "Focus",
+            Self::ShortBreak => "Short break",
+            Self::LongBreak => "Long break",
+        }
+    }
+
+    /// Whether this is a break phase.
+    pub fn is_break(&self) -> bool {
+        !matches!(self, Self::Work)
+    }
+}
+
+/// Whether the timer is counting down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerState {
+    /// Not started (or reset); shows the full interval.
+    Idle,
+    /// Counting down.
+    Running,
+    /// Paused part-way through an interval.
+    Paused,
+}
+
+/// Interval lengths (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerSettings {
+    /// Work interval length.
+    pub work: Duration,
+    /// Short break length.
+    pub short_break: Duration,
+    /// Long break length.
+    pub long_break: Duration,
+    /// Take a long break after this many work intervals (0 = never).
+    pub long_break_every: u32,
+    /// Start the next interval automatically when one completes.
+    pub auto_start: bool,
+}
+
+impl TimerSettings {
+    /// Settings for a named preset: "pomodoro" (25/5/15), "52-17" or "90-20".
+    pub fn preset(name: &str) -> Option<Self> {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        let (work, short_break, long_break, long_break_every) = match name {
+            "pomodoro" => (25, 5, 15, 4),
+            "52-17" => (52, 17, 17, 0),
+            "90-20" => (90, 20, 20, 0),
+            _ => return None,
+        };
+        Some(Self {
+            work: minutes(work),
+            short_break: minutes(short_break),
+            long_break: minutes(long_break),
+            long_break_every,
+            auto_start: false,
+        })
+    }
+
+    /// Length of an interval in the given phase.
+    pub fn duration(&self, phase: TimerPhase) -> Duration {
+        match phase {
+            TimerPhase::Work => self.work,
+            TimerPhase::ShortBreak => self.short_break,
+            TimerPhase::LongBreak => self.long_break,
+        }
+    }
+
+    /// Phase that follows `phase`, given the number of completed work intervals.
+    fn next_phase(&self, phase: TimerPhase, completed: u32) -> TimerPhase {
+        match phase {
+            TimerPhase::Work
+                if self.long_break_every > 0
+                    && completed > 0
+                    && completed.is_multiple_of(self.long_break_every) =>
+            {
+                TimerPhase::LongBreak
+            }
+            TimerPhase::Work => TimerPhase::ShortBreak,
+            TimerPhase::ShortBreak | TimerPhase::LongBreak => TimerPhase::Work,
+        }
+    }
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self {
+        Self::preset("pomodoro").expect("built-in preset")
+    }
+}
+
+/// Canonical snapshot of timer state.
+#[derive(Debug, Clone)]
+pub struct TimerSnapshot {
+    /// Current phase.
+    pub phase: TimerPhase,
+    /// Whether the timer is idle, running or paused.
+    pub state: TimerState,
+    /// Time left in the current interval.
+    pub remaining: Duration,
+    /// Work intervals completed since the last reset.
+    pub completed: u32,
+}
+
+/// Shared, process-wide timer service.
+pub struct TimerService {
+    snapshot: RefCell<TimerSnapshot>,
+    callbacks: Callbacks<TimerSnapshot>,
+    settings: RefCell<TimerSettings>,
+    /// When the running interval ends.
+    deadline: RefCell<Option<Instant>>,
+    /// Once-per-second countdown tick, active while running.
+    tick_source: RefCell<Option<SourceId>>,
+}
+
+impl TimerService {
+    fn new() -> Rc<Self> {
+        let settings = TimerSettings::default();
+        Rc::new(Self {
+            snapshot: RefCell::new(TimerSnapshot {
+                phase: TimerPhase::Work,
+                state: TimerState::Idle,
+                remaining: settings.work,
+                completed: 0,
+            }),
+            callbacks: Callbacks::new(),
+            settings: RefCell::new(settings),
+            deadline: RefCell::new(None),
+            tick_source: RefCell::new(None),
+        })
+    }
+
+    /// Get the global TimerService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<TimerService> = TimerService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&TimerSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify_single(id, &snapshot);
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Apply interval settings.
+    ///
+    /// Re-applying identical settings (e.g. when the bar is rebuilt) is a
+    /// no-op, so a running interval keeps going. New lengths take effect
+    /// right away only while idle; otherwise from the next interval.
+    pub fn configure(&self, settings: TimerSettings) {
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        debug!("TimerService: settings changed to {:?}", settings);
+        let idle_remaining = {
+            let snapshot = self.snapshot.borrow();
+            (snapshot.state == TimerState::Idle).then(|| settings.duration(snapshot.phase))
+        };
+        *self.settings.borrow_mut() = settings;
+
+        if let Some(remaining) = idle_remaining {
+            self.snapshot.borrow_mut().remaining = remaining;
+            self.notify();
+        }
+    }
+
+    /// Start when idle, otherwise pause or resume.
+    pub fn toggle(&self) {
+        let state = self.snapshot.borrow().state;
+        match state {
+            TimerState::Idle | TimerState::Paused => self.start(),
+            TimerState::Running => self.pause(),
+        }
+    }
+
+    /// Start (or resume) the current interval.
+    pub fn start(&self) {
+        let remaining = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if snapshot.state == TimerState::Running {
+                return;
+            }
+            snapshot.state = TimerState::Running;
+            snapshot.remaining
+        };
+
+        *self.deadline.borrow_mut() = Some(Instant::now() + remaining);
+        self.start_ticking();
+        self.notify();
+    }
+
+    /// Pause the running interval.
+    pub fn pause(&self) {
+        if self.snapshot.borrow().state != TimerState::Running {
+            return;
+        }
+
+        self.stop_ticking();
+        let remaining = self.remaining_until_deadline();
+        *self.deadline.borrow_mut() = None;
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.state = TimerState::Paused;
+            snapshot.remaining = remaining;
+        }
+        self.notify();
+    }
+
+    /// End the current interval early and move to the next phase.
+    pub fn skip(&self) {
+        self.advance(false);
+    }
+
+    /// Stop the timer and go back to an idle work interval.
+    pub fn reset(&self) {
+        self.stop_ticking();
+        *self.deadline.borrow_mut() = None;
+        let work = self.settings.borrow().work;
+        *self.snapshot.borrow_mut() = TimerSnapshot {
+            phase: TimerPhase::Work,
+            state: TimerState::Idle,
+            remaining: work,
+            completed: 0,
+        };
+        self.notify();
+    }
+
+    fn notify(&self) {
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    fn remaining_until_deadline(&self) -> Duration {
+        self.deadline
+            .borrow()
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+            .unwrap_or_default()
+    }
+
+    fn start_ticking(&self) {
+        self.stop_ticking();
+        let source_id = glib::timeout_add_seconds_local(1, || {
+            TimerService::global().tick();
+            glib::ControlFlow::Continue
+        });
+        *self.tick_source.borrow_mut() = Some(source_id);
+    }
+
+    fn stop_ticking(&self) {
+        if let Some(source_id) = self.tick_source.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+
+    /// Update the countdown; completes the interval once the deadline passes.
+    fn tick(&self) {
+        let remaining = self.remaining_until_deadline();
+        if remaining.is_zero() {
+            self.advance(true);
+            return;
+        }
+
+        self.snapshot.borrow_mut().remaining = remaining;
+        self.notify();
+    }
+
+    /// Move to the next phase.
+    ///
+    /// `completed` is true when the interval ran out (rather than being
+    /// skipped): only then is it counted and announced.
+    fn advance(&self, completed: bool) {
+        self.stop_ticking();
+        *self.deadline.borrow_mut() = None;
+
+        let finished = {
+            let settings = self.settings.borrow();
+            let mut snapshot = self.snapshot.borrow_mut();
+            let finished = snapshot.phase;
+            if completed && finished == TimerPhase::Work {
+                snapshot.completed += 1;
+            }
+            snapshot.phase = settings.next_phase(finished, snapshot.completed);
+            snapshot.remaining = settings.duration(snapshot.phase);
+            snapshot.state = TimerState::Idle;
+            finished
+        };
+
+        if completed {
+            let next = self.snapshot.borrow().phase;
+            info!(
+                "TimerService: {} finished, next: {}",
+                finished.label(),
+                next.label()
+            );
+            send_completion_notification(finished, next);
+        }
+
+        if self.settings.borrow().auto_start {
+            self.start();
+        } else {
+            self.notify();
+        }
+    }
+}
+
+/// Notification text for a finished interval.
+fn completion_message(finished: TimerPhase, next: TimerPhase) -> (String, String) {
+    match finished {
+        TimerPhase::Work => (
+            "Focus session complete".to_string(),
+            format!("Time for a {}", next.label().to_lowercase()),
+        ),
+        TimerPhase::ShortBreak | TimerPhase::LongBreak => (
+            format!("{} over", finished.label()),
+            "Time to focus".to_string(),
+        ),
+    }
+}
+
+/// Send a desktop notification for a finished interval via org.freedesktop.Notifications.
+fn send_completion_notification(finished: TimerPhase, next: TimerPhase) {
+    let (summary, body) = completion_message(finished, next);
+
+    let params = (
+        "vibepanel",
+        0u32,
+        "alarm-symbolic",
+        summary.as_str(),
+        body.as_str(),
+        Vec::<String>::new(),
+        HashMap::<String, glib::Variant>::new(),
+        -1i32,
+    )
+        .to_variant();
+
+    gio::bus_get(
+        gio::BusType::Session,
+        None::<&gio::Cancellable>,
+        move |res| {
+            let connection = match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("TimerService: failed to connect to session bus: {}", e);
+                    return;
+                }
+            };
+
+            dbus_watchdog::call(
+                "notifications",
+                &connection,
+                "org.freedesktop.Notifications",
+                "/org/freedesktop/Notifications",
+                "org.freedesktop.Notifications",
+                "Notify",
+                Some(&params),
+                None,
+                CallPolicy::ACTION,
+                |res| {
+                    if let Err(e) = res {
+                        debug!("TimerService: failed to send notification: {}", e);
+                    }
+                },
+            );
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let pomodoro = TimerSettings::preset("pomodoro").unwrap();
+        assert_eq!(pomodoro.work, Duration::from_secs(25 * 60));
+        assert_eq!(
+            pomodoro.duration(TimerPhase::ShortBreak),
+            Duration::from_secs(5 * 60)
+        );
+        assert_eq!(pomodoro.long_break_every, 4);
+        assert!(TimerSettings::preset("52-17").is_some());
+        assert!(TimerSettings::preset("tomato").is_none());
+    }
+
+    #[test]
+    fn test_next_phase() {
+        let settings = TimerSettings::default();
+        assert_eq!(
+            settings.next_phase(TimerPhase::Work, 1),
+            TimerPhase::ShortBreak
+        );
+        assert_eq!(
+            settings.next_phase(TimerPhase::Work, 4),
+            TimerPhase::LongBreak
+        );
+        assert_eq!(
+            settings.next_phase(TimerPhase::Work, 0),
+            TimerPhase::ShortBreak
+        );
+        assert_eq!(
+            settings.next_phase(TimerPhase::LongBreak, 4),
+            TimerPhase::Work
+        );
+
+        let never = TimerSettings {
+            long_break_every: 0,
+            ..settings
+        };
+        assert_eq!(
+            never.next_phase(TimerPhase::Work, 4),
+            TimerPhase::ShortBreak
+        );
+    }
+
+    #[test]
+    fn test_completion_message() {
+        let (summary, body) = completion_message(TimerPhase::Work, TimerPhase::LongBreak);
+        assert_eq!(summary, "Focus session complete");
+        assert_eq!(body, "Time for a long break");
+
+        let (summary, _) = completion_message(TimerPhase::ShortBreak, TimerPhase::Work);
+        assert_eq!(summary, "Short break over");
+    }
+}
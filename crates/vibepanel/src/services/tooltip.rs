@@ -20,6 +20,7 @@ use vibepanel_core::SurfaceStyles;
 
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::styles::tooltip;
+use crate::widgets::layer_shell_popover::{anchor_to_bar_edge, bar_edge};
 
 // Thread-local singleton storage for TooltipManager
 thread_local! {
@@ -64,9 +65,9 @@ struct TooltipWindow {
 /// Positioning mode for tooltips.
 #[derive(Clone, Copy)]
 enum TooltipAnchor {
-    /// Anchor from top-left, use left margin for X position
+    /// Anchor from the left, use left margin for X position
     Left,
-    /// Anchor from top-right, use right margin for X position
+    /// Anchor from the right, use right margin for X position
     Right,
 }
 
@@ -179,7 +180,9 @@ impl TooltipWindow {
             }
         }
 
-        self.window.set_margin(Edge::Top, y);
+        // Open next to the bar, on whichever edge it sits
+        anchor_to_bar_edge(&self.window);
+        self.window.set_margin(bar_edge(), y);
         self.window.present();
     }
 
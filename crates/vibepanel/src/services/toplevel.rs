@@ -0,0 +1,486 @@
+//! ToplevelService - open windows via wlr-foreign-toplevel-management.
+//!
+//! Tracks every toplevel the compositor announces (title, app_id, outputs,
+//! activated/minimized state) and forwards activate/close requests from the
+//! taskbar widget. Hyprland, Niri and MangoWC all implement the protocol, so
+//! a single Wayland client covers every supported compositor without going
+//! through the per-compositor IPC backends.
+//!
+//! The client runs on its own Wayland connection whose fd is watched from the
+//! GLib main loop. Handle events are double-buffered: changes are published
+//! on the handle's `done` event.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::os::fd::{AsFd, AsRawFd};
+use std::rc::Rc;
+
+use gtk4::glib;
+use tracing::{debug, error, warn};
+use wayland_backend::client::ObjectId;
+use wayland_client::protocol::wl_output::{self, WlOutput};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::protocol::wl_seat::{self, WlSeat};
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle, event_created_child};
+
+use super::callbacks::{CallbackId, Callbacks};
+use super::compositor::foreign_toplevel::{
+    State, ZwlrForeignToplevelHandleV1, ZwlrForeignToplevelManagerV1,
+    zwlr_foreign_toplevel_handle_v1, zwlr_foreign_toplevel_manager_v1,
+};
+
+const MANAGER_INTERFACE: &str = "zwlr_foreign_toplevel_manager_v1";
+
+/// An open window.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Toplevel {
+    /// Stable identifier for activate/close requests.
+    pub id: u64,
+    /// Window title (may be empty).
+    pub title: String,
+    /// Application ID (e.g., "firefox", "org.gnome.Nautilus").
+    pub app_id: String,
+    /// Connector names of the outputs the window is visible on.
+    pub outputs: Vec<String>,
+    /// Whether the window has keyboard focus.
+    pub activated: bool,
+    /// Whether the window is minimized.
+    pub minimized: bool,
+}
+
+/// Canonical snapshot of the open windows.
+#[derive(Debug, Clone, Default)]
+pub struct ToplevelSnapshot {
+    /// Whether the compositor supports the protocol.
+    pub available: bool,
+    /// Open windows in the order the compositor announced them.
+    pub toplevels: Vec<Toplevel>,
+}
+
+/// A toplevel handle and its latest state.
+struct TrackedToplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    toplevel: Toplevel,
+    /// wl_outputs the window is on (names are resolved at snapshot time).
+    outputs: Vec<ObjectId>,
+    /// Whether the initial `done` was received.
+    ready: bool,
+}
+
+/// Wayland-side state, owned by the event queue dispatch.
+#[derive(Default)]
+struct WaylandState {
+    manager: Option<ZwlrForeignToplevelManagerV1>,
+    seat: Option<WlSeat>,
+    /// Output names keyed by wl_output (None until the `name` event).
+    outputs: HashMap<ObjectId, Option<String>>,
+    toplevels: Vec<TrackedToplevel>,
+    next_id: u64,
+    /// Set when a change should be published.
+    dirty: bool,
+}
+
+impl WaylandState {
+    fn toplevel_mut(
+        &mut self,
+        handle: &ZwlrForeignToplevelHandleV1,
+    ) -> Option<&mut TrackedToplevel> {
+        let id = handle.id();
+        self.toplevels.iter_mut().find(|t| t.handle.id() == id)
+    }
+
+    fn handle(&self, id: u64) -> Option<&ZwlrForeignToplevelHandleV1> {
+        self.toplevels
+            .iter()
+            .find(|t| t.toplevel.id == id)
+            .map(|t| &t.handle)
+    }
+
+    fn snapshot(&self) -> ToplevelSnapshot {
+        let toplevels = self
+            .toplevels
+            .iter()
+            .filter(|t| t.ready)
+            .map(|t| Toplevel {
+                outputs: t
+                    .outputs
+                    .iter()
+                    .filter_map(|id| self.outputs.get(id).cloned().flatten())
+                    .collect(),
+                ..t.toplevel.clone()
+            })
+            .collect();
+        ToplevelSnapshot {
+            available: self.manager.is_some(),
+            toplevels,
+        }
+    }
+}
+
+/// Decode a `state` array into `(activated, minimized)`.
+fn parse_states(states: &[u8]) -> (bool, bool) {
+    let mut activated = false;
+    let mut minimized = false;
+    for chunk in states.chunks_exact(4) {
+        let value = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        if value == State::Activated as u32 {
+            activated = true;
+        } else if value == State::Minimized as u32 {
+            minimized = true;
+        }
+    }
+    (activated, minimized)
+}
+
+impl Dispatch<WlRegistry, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            match interface.as_str() {
+                MANAGER_INTERFACE => {
+                    debug!("Found foreign toplevel manager v{}", version);
+                    state.manager = Some(registry.bind(name, version.min(3), qh, ()));
+                }
+                "wl_seat" if state.seat.is_none() => {
+                    state.seat = Some(registry.bind(name, version.min(1), qh, ()));
+                }
+                "wl_output" => {
+                    // v4 is needed for the connector name
+                    let output: WlOutput = registry.bind(name, version.min(4), qh, ());
+                    state.outputs.insert(output.id(), None);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for WaylandState {
+    fn event(
+        _state: &mut Self,
+        _seat: &WlSeat,
+        _event: wl_seat::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlOutput, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        output: &WlOutput,
+        event: wl_output::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event {
+            state.outputs.insert(output.id(), Some(name));
+            state.dirty = true;
+        }
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _manager: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                state.next_id += 1;
+                state.toplevels.push(TrackedToplevel {
+                    handle: toplevel,
+                    toplevel: Toplevel {
+                        id: state.next_id,
+                        ..Default::default()
+                    },
+                    outputs: Vec::new(),
+                    ready: false,
+                });
+            }
+            zwlr_foreign_toplevel_manager_v1::Event::Finished => {
+                warn!("ToplevelService: compositor finished the toplevel manager");
+                state.manager = None;
+                state.dirty = true;
+            }
+        }
+    }
+
+    event_created_child!(WaylandState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_handle_v1::Event::Closed = event {
+            let id = handle.id();
+            state.toplevels.retain(|t| t.handle.id() != id);
+            handle.destroy();
+            state.dirty = true;
+            return;
+        }
+
+        let Some(tracked) = state.toplevel_mut(handle) else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                tracked.toplevel.title = title;
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                tracked.toplevel.app_id = app_id;
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output } => {
+                if !tracked.outputs.contains(&output.id()) {
+                    tracked.outputs.push(output.id());
+                }
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                let id = output.id();
+                tracked.outputs.retain(|o| *o != id);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::State { state: states } => {
+                let (activated, minimized) = parse_states(&states);
+                tracked.toplevel.activated = activated;
+                tracked.toplevel.minimized = minimized;
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Done => {
+                tracked.ready = true;
+                state.dirty = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The Wayland connection's event queue and its state.
+struct Client {
+    queue: EventQueue<WaylandState>,
+    state: WaylandState,
+}
+
+/// Shared, process-wide open window list.
+pub struct ToplevelService {
+    snapshot: RefCell<ToplevelSnapshot>,
+    callbacks: Callbacks<ToplevelSnapshot>,
+    client: RefCell<Option<Client>>,
+    source: RefCell<Option<glib::SourceId>>,
+}
+
+impl ToplevelService {
+    fn new() -> Rc<Self> {
+        let service = Rc::new(Self {
+            snapshot: RefCell::new(ToplevelSnapshot::default()),
+            callbacks: Callbacks::new(),
+            client: RefCell::new(None),
+            source: RefCell::new(None),
+        });
+        service.start();
+        service
+    }
+
+    /// Get the global ToplevelService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<ToplevelService> = ToplevelService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the window list changes.
+    pub fn connect<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(&ToplevelSnapshot) + 'static,
+    {
+        let id = self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        self.callbacks
+            .notify_single(id, &self.snapshot.borrow().clone());
+        id
+    }
+
+    /// Unregister a callback registered with `connect`.
+    pub fn disconnect(&self, id: CallbackId) {
+        self.callbacks.unregister(id);
+    }
+
+    /// Return the current window list.
+    pub fn snapshot(&self) -> ToplevelSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Focus a window (unminimizing it if needed).
+    pub fn activate(&self, id: u64) {
+        self.request(id, |handle, state| {
+            if let Some(ref seat) = state.seat {
+                handle.activate(seat);
+            }
+        });
+    }
+
+    /// Ask a window to close.
+    pub fn close(&self, id: u64) {
+        self.request(id, |handle, _| handle.close());
+    }
+
+    fn request(&self, id: u64, send: impl FnOnce(&ZwlrForeignToplevelHandleV1, &WaylandState)) {
+        let client = self.client.borrow();
+        let Some(client) = client.as_ref() else {
+            return;
+        };
+        let Some(handle) = client.state.handle(id) else {
+            debug!("ToplevelService: window {} is gone", id);
+            return;
+        };
+        send(handle, &client.state);
+        if let Err(e) = client.queue.flush() {
+            warn!("ToplevelService: failed to send request: {}", e);
+        }
+    }
+
+    /// Connect to the compositor and watch the connection from the main loop.
+    fn start(self: &Rc<Self>) {
+        let connection = match Connection::connect_to_env() {
+            Ok(connection) => connection,
+            Err(e) => {
+                error!(
+                    "ToplevelService: failed to connect to Wayland display: {}",
+                    e
+                );
+                return;
+            }
+        };
+        let mut queue: EventQueue<WaylandState> = connection.new_event_queue();
+        let qh = queue.handle();
+        let _registry = connection.display().get_registry(&qh, ());
+
+        let mut state = WaylandState::default();
+        // First roundtrip binds the globals, the second receives the
+        // initial toplevels and output names
+        for _ in 0..2 {
+            if let Err(e) = queue.roundtrip(&mut state) {
+                error!("ToplevelService: Wayland roundtrip failed: {}", e);
+                return;
+            }
+        }
+        if state.manager.is_none() {
+            warn!(
+                "ToplevelService: compositor does not support {}, taskbar stays empty",
+                MANAGER_INTERFACE
+            );
+            return;
+        }
+        state.dirty = false;
+        *self.snapshot.borrow_mut() = state.snapshot();
+
+        let fd = queue.as_fd().as_raw_fd();
+        *self.client.borrow_mut() = Some(Client { queue, state });
+
+        let this_weak = Rc::downgrade(self);
+        let source = glib::unix_fd_add_local(fd, glib::IOCondition::IN, move |_fd, _condition| {
+            match this_weak.upgrade() {
+                Some(this) => this.dispatch(),
+                None => glib::ControlFlow::Break,
+            }
+        });
+        *self.source.borrow_mut() = Some(source);
+        debug!("ToplevelService started");
+    }
+
+    /// Read and dispatch pending Wayland events, publishing any changes.
+    fn dispatch(&self) -> glib::ControlFlow {
+        let snapshot = {
+            let mut client = self.client.borrow_mut();
+            let Some(Client { queue, state }) = client.as_mut() else {
+                return glib::ControlFlow::Break;
+            };
+
+            if let Some(guard) = queue.prepare_read() {
+                match guard.read() {
+                    Ok(_) => {}
+                    Err(wayland_client::backend::WaylandError::Io(e))
+                        if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(e) => {
+                        error!("ToplevelService: Wayland read error: {}", e);
+                        *self.source.borrow_mut() = None;
+                        *client = None;
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+            if let Err(e) = queue.dispatch_pending(state) {
+                error!("ToplevelService: Wayland dispatch error: {}", e);
+                *self.source.borrow_mut() = None;
+                *client = None;
+                return glib::ControlFlow::Break;
+            }
+            if !std::mem::take(&mut state.dirty) {
+                return glib::ControlFlow::Continue;
+            }
+            state.snapshot()
+        };
+
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+        glib::ControlFlow::Continue
+    }
+}
+
+impl Drop for ToplevelService {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.borrow_mut().take() {
+            source.remove();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(states: &[State]) -> Vec<u8> {
+        states
+            .iter()
+            .flat_map(|s| (*s as u32).to_ne_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_states() {
+        assert_eq!(parse_states(&[]), (false, false));
+        assert_eq!(
+            parse_states(&encode(&[State::Maximized, State::Activated])),
+            (true, false)
+        );
+        assert_eq!(parse_states(&encode(&[State::Minimized])), (false, true));
+        // Trailing partial entries are ignored
+        assert_eq!(parse_states(&[2, 0]), (false, false));
+    }
+}
@@ -0,0 +1,248 @@
+//! TouchpadService - switch the touchpad off while typing with an external mouse.
+//!
+//! Two ways to reach the touchpad, tried in order:
+//! - The compositor's IPC, for backends that can toggle input devices
+//!   (Hyprland's `device[<name>]:enabled` keyword)
+//! - KWin's `org.kde.KWin.InputDevice` D-Bus objects, whose `enabled`
+//!   property is the libinput "send events" setting
+//!
+//! Niri and the other backends have no IPC for input devices, so the service
+//! reports itself unavailable there.
+//!
+//! Hyprland can't be asked whether a device is enabled, so the state there
+//! is what the service last set; it starts out enabled. KWin's state follows
+//! external changes (e.g. from System Settings).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::gio;
+use gtk4::glib::{self, Variant};
+use gtk4::prelude::*;
+use tracing::{debug, error, warn};
+
+use super::callbacks::Callbacks;
+use super::compositor::CompositorManager;
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "kwin";
+
+const KWIN_BUS_NAME: &str = "org.kde.KWin";
+const KWIN_INPUT_PATH: &str = "/org/kde/KWin/InputDevice";
+const KWIN_MANAGER_IFACE: &str = "org.kde.KWin.InputDeviceManager";
+const KWIN_DEVICE_IFACE: &str = "org.kde.KWin.InputDevice";
+
+/// Canonical snapshot of touchpad state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchpadSnapshot {
+    /// Whether a touchpad was found that can be toggled.
+    pub available: bool,
+    /// Whether the touchpad is enabled.
+    pub enabled: bool,
+}
+
+/// How the touchpads are reached.
+enum Backend {
+    /// Touchpad names, toggled through the compositor's IPC.
+    Compositor(Vec<String>),
+    /// KWin D-Bus proxies of the touchpad devices.
+    Kwin(Vec<gio::DBusProxy>),
+    /// No way to toggle a touchpad.
+    None,
+}
+
+/// Shared, process-wide touchpad service.
+pub struct TouchpadService {
+    backend: Backend,
+    snapshot: RefCell<TouchpadSnapshot>,
+    callbacks: Callbacks<TouchpadSnapshot>,
+}
+
+impl TouchpadService {
+    fn new() -> Rc<Self> {
+        let backend = find_backend();
+        let available = !matches!(backend, Backend::None);
+        if !available {
+            debug!("TouchpadService: no touchpad the compositor can toggle");
+        }
+
+        let service = Rc::new(Self {
+            backend,
+            snapshot: RefCell::new(TouchpadSnapshot {
+                available,
+                enabled: true,
+            }),
+            callbacks: Callbacks::new(),
+        });
+
+        if let Backend::Kwin(ref devices) = service.backend {
+            for device in devices {
+                let service_weak = Rc::downgrade(&service);
+                device.connect_g_properties_changed(move |_, _, _| {
+                    if let Some(service) = service_weak.upgrade() {
+                        service.refresh();
+                    }
+                });
+            }
+            service.refresh();
+        }
+
+        service
+    }
+
+    /// Get the global TouchpadService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<TouchpadService> = TouchpadService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Register a callback to be invoked whenever the touchpad state changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&TouchpadSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        self.callbacks.notify(&self.snapshot.borrow());
+    }
+
+    /// Current snapshot.
+    pub fn snapshot(&self) -> TouchpadSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Enable or disable every touchpad.
+    pub fn set_enabled(self: &Rc<Self>, enabled: bool) {
+        match self.backend {
+            Backend::Compositor(ref names) => {
+                let compositor = CompositorManager::global();
+                let mut applied = false;
+                for name in names {
+                    applied |= compositor.set_input_device_enabled(name, enabled);
+                }
+                if applied {
+                    self.update(enabled);
+                } else {
+                    // Put the toggle back
+                    self.callbacks.notify(&self.snapshot.borrow());
+                }
+            }
+            Backend::Kwin(ref devices) => {
+                for device in devices {
+                    let params = Variant::tuple_from_iter([
+                        KWIN_DEVICE_IFACE.to_variant(),
+                        "enabled".to_variant(),
+                        glib::Variant::from_variant(&enabled.to_variant()),
+                    ]);
+                    let service_weak = Rc::downgrade(self);
+                    let device_for_cache = device.clone();
+                    dbus_watchdog::call_proxy(
+                        WATCHDOG_SERVICE,
+                        device,
+                        "org.freedesktop.DBus.Properties.Set",
+                        Some(&params),
+                        CallPolicy::ACTION,
+                        move |res| {
+                            match res {
+                                Ok(_) => device_for_cache
+                                    .set_cached_property("enabled", Some(&enabled.to_variant())),
+                                Err(e) => error!("TouchpadService: set enabled failed: {}", e),
+                            }
+                            if let Some(service) = service_weak.upgrade() {
+                                service.refresh();
+                            }
+                        },
+                    );
+                }
+            }
+            Backend::None => {}
+        }
+    }
+
+    /// Re-read the KWin device state.
+    fn refresh(&self) {
+        let Backend::Kwin(ref devices) = self.backend else {
+            return;
+        };
+        // Enabled when any touchpad is, so the toggle turns them all off
+        let enabled = devices
+            .iter()
+            .any(|device| cached_bool(device, "enabled").unwrap_or(true));
+        self.update(enabled);
+    }
+
+    /// Store the state and notify if it changed.
+    fn update(&self, enabled: bool) {
+        if self.snapshot.borrow().enabled == enabled {
+            return;
+        }
+        debug!("TouchpadService: enabled={}", enabled);
+        self.snapshot.borrow_mut().enabled = enabled;
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+}
+
+/// Find the touchpads, through the compositor first and KWin second.
+fn find_backend() -> Backend {
+    let names = CompositorManager::global().list_touchpads();
+    if !names.is_empty() {
+        debug!("TouchpadService: toggling {:?} via compositor IPC", names);
+        return Backend::Compositor(names);
+    }
+
+    let devices = kwin_touchpads();
+    if !devices.is_empty() {
+        debug!("TouchpadService: toggling {} KWin device(s)", devices.len());
+        return Backend::Kwin(devices);
+    }
+
+    Backend::None
+}
+
+/// Proxies of the touchpads KWin knows about, empty when KWin isn't running.
+fn kwin_touchpads() -> Vec<gio::DBusProxy> {
+    let proxy = |path: &str, iface: &str| {
+        gio::DBusProxy::for_bus_sync(
+            gio::BusType::Session,
+            gio::DBusProxyFlags::DO_NOT_AUTO_START,
+            None,
+            KWIN_BUS_NAME,
+            path,
+            iface,
+            gio::Cancellable::NONE,
+        )
+        .map_err(|e| warn!("TouchpadService: KWin proxy for {}: {}", path, e))
+        .ok()
+    };
+
+    let Some(manager) = proxy(KWIN_INPUT_PATH, KWIN_MANAGER_IFACE) else {
+        return Vec::new();
+    };
+    // No owner means KWin isn't the compositor; its cached properties are empty
+    let Some(sys_names) = manager
+        .cached_property("devicesSysNames")
+        .and_then(|v| v.get::<Vec<String>>())
+    else {
+        return Vec::new();
+    };
+
+    sys_names
+        .iter()
+        .filter_map(|sys_name| {
+            proxy(
+                &format!("{}/{}", KWIN_INPUT_PATH, sys_name),
+                KWIN_DEVICE_IFACE,
+            )
+        })
+        .filter(|device| cached_bool(device, "touchpad") == Some(true))
+        .collect()
+}
+
+/// Read a cached boolean property.
+fn cached_bool(proxy: &gio::DBusProxy, name: &str) -> Option<bool> {
+    proxy.cached_property(name).and_then(|v| v.get::<bool>())
+}
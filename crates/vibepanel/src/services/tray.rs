@@ -15,8 +15,17 @@ use gtk4::glib::{self, Variant};
 use sha2::{Digest, Sha256};
 use tracing::{debug, error, info, warn};
 
-/// Type alias for tray service callbacks.
-type TrayCallback = Rc<dyn Fn(&TrayService)>;
+use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+
+/// Bus event published when tray state changes.
+///
+/// Listeners read the new state from the service itself.
+#[derive(Debug, Clone)]
+struct TrayChanged;
+
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "tray";
 
 const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
 const WATCHER_PATH: &str = "/StatusNotifierWatcher";
@@ -143,7 +152,7 @@ pub struct TrayService {
     pending_proxies: RefCell<HashSet<String>>,
 
     // Callbacks and readiness
-    callbacks: RefCell<Vec<TrayCallback>>,
+    callbacks: Callbacks<TrayChanged>,
     ready: Cell<bool>,
 
     /// D-Bus signal subscriptions for external watcher signals (kept alive for service lifetime).
@@ -168,7 +177,7 @@ impl TrayService {
             pending_updates: RefCell::new(HashMap::new()),
             debounce_timers: RefCell::new(HashMap::new()),
             pending_proxies: RefCell::new(HashSet::new()),
-            callbacks: RefCell::new(Vec::new()),
+            callbacks: Callbacks::new(),
             ready: Cell::new(false),
             _watcher_signal_subscriptions: RefCell::new(Vec::new()),
         });
@@ -190,12 +199,13 @@ impl TrayService {
     where
         F: Fn(&TrayService) + 'static,
     {
-        let cb = Rc::new(callback);
-        self.callbacks.borrow_mut().push(cb.clone());
+        let id = self
+            .callbacks
+            .register(move |_: &TrayChanged| callback(&TrayService::global()));
 
-        // Immediately send current state if ready.
+        // Immediately send current state if ready
         if self.ready.get() {
-            cb(self);
+            self.callbacks.notify_single(id, &TrayChanged);
         }
     }
 
@@ -223,12 +233,12 @@ impl TrayService {
             return;
         };
 
-        proxy.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &proxy,
             "Activate",
             Some(&(x, y).to_variant()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
             |result| {
                 if let Err(e) = result {
                     debug!("Activate call failed: {}", e);
@@ -315,12 +325,12 @@ impl TrayService {
         let menu_proxy_clone = menu_proxy.clone();
 
         // First call AboutToShow async, then GetLayout
-        menu_proxy.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &menu_proxy,
             "AboutToShow",
             Some(&(0i32,).to_variant()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
             move |_result| {
                 // Ignore AboutToShow result - some apps don't implement it
                 // Now call GetLayout
@@ -334,12 +344,12 @@ impl TrayService {
                     "children-display",
                 ];
 
-                menu_proxy_clone.call(
+                dbus_watchdog::call_proxy(
+                    WATCHDOG_SERVICE,
+                    &menu_proxy_clone,
                     "GetLayout",
                     Some(&(0i32, -1i32, properties).to_variant()),
-                    gio::DBusCallFlags::NONE,
-                    5000,
-                    None::<&gio::Cancellable>,
+                    CallPolicy::QUERY,
                     move |result| {
                         let entries = match result {
                             Ok(r) => {
@@ -375,12 +385,12 @@ impl TrayService {
         let data_variant = "".to_variant();
         let params = (menu_id, event, data_variant, timestamp).to_variant();
 
-        menu_proxy.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            &menu_proxy,
             "Event",
             Some(&params),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
             |result| {
                 if let Err(e) = result {
                     debug!("Menu Event call failed: {}", e);
@@ -732,12 +742,12 @@ impl TrayService {
 
                 // Register as host
                 let host_id = this.host_id.clone();
-                watcher.call(
+                dbus_watchdog::call_proxy(
+                    WATCHDOG_SERVICE,
+                    &watcher,
                     "RegisterStatusNotifierHost",
                     Some(&(host_id.as_str(),).to_variant()),
-                    gio::DBusCallFlags::NONE,
-                    5000,
-                    None::<&gio::Cancellable>,
+                    CallPolicy::ACTION,
                     |result| {
                         if let Err(e) = result {
                             error!("Host registration failed: {}", e);
@@ -1045,12 +1055,12 @@ impl TrayService {
     fn fetch_proxy_properties_async(&self, identifier: &str, proxy: &gio::DBusProxy) {
         let identifier_owned = identifier.to_string();
 
-        proxy.call(
+        dbus_watchdog::call_proxy(
+            WATCHDOG_SERVICE,
+            proxy,
             "org.freedesktop.DBus.Properties.GetAll",
             Some(&(ITEM_INTERFACE,).to_variant()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::QUERY,
             move |result| {
                 let this = TrayService::global();
 
@@ -1086,7 +1096,9 @@ impl TrayService {
                 };
 
                 let overrides = this.parse_properties_result(&variant);
-                if let Some(snapshot) = this.snapshot_from_proxy(&identifier_owned, &proxy, overrides.as_ref()) {
+                if let Some(snapshot) =
+                    this.snapshot_from_proxy(&identifier_owned, &proxy, overrides.as_ref())
+                {
                     this.items.borrow_mut().insert(identifier_owned, snapshot);
                     this.notify_listeners();
                 }
@@ -1485,10 +1497,7 @@ impl TrayService {
     }
 
     fn notify_listeners(&self) {
-        let callbacks: Vec<_> = self.callbacks.borrow().iter().cloned().collect();
-        for cb in callbacks {
-            cb(self);
-        }
+        self.callbacks.notify(&TrayChanged);
     }
 }
 
@@ -3,30 +3,75 @@
 //! This service provides:
 //! - Auto-detection of package managers (dnf, pacman, paru)
 //! - Periodic checking for available updates
+//! - Event-driven refreshes on network reconnect, resume from suspend, and
+//!   package database changes (e.g. after an upgrade)
 //! - Background thread execution to avoid blocking the UI
 //! - Grouped updates by repository
+//! - Flatpak updates, tracked separately and applied in-place
+//! - Remote machines checked over SSH, each with its own pending count
+//! - Pending reboots: a newer kernel or systemd installed than the one
+//!   running, with an optional desktop notification
 //!
 //! Supports:
 //! - Fedora: dnf
 //! - Arch Linux: pacman (official repos), paru (official + AUR)
+//! - Flatpak (alongside or instead of a system package manager)
 
 use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
 use std::rc::Rc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
+use gtk4::gio;
 use gtk4::glib::{self, SourceId};
+use gtk4::prelude::*;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
 use tracing::{debug, info, warn};
 
+use super::actions::{ActionKind, Actions};
 use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
+use super::event_bus::EventBus;
+use super::network::{NetworkService, NetworkSnapshot};
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for UpdatesService
+thread_local! {
+    static UPDATES_INSTANCE: RefCell<Option<Rc<UpdatesService>>> = const { RefCell::new(None) };
+}
 
 /// Default check interval in seconds (1 hour).
 const DEFAULT_CHECK_INTERVAL: u64 = 3600;
 
 /// Minimum check interval to prevent abuse (5 minutes).
-const MIN_CHECK_INTERVAL: u64 = 300;
+pub const MIN_CHECK_INTERVAL: u64 = 300;
+
+/// Path used to detect a Flatpak installation.
+const FLATPAK_BIN: &str = "/usr/bin/flatpak";
+
+/// Delay before an event-triggered refresh runs (seconds).
+///
+/// Collapses bursts of triggers (e.g. resume followed by network reconnect)
+/// into a single check and gives the network time to come up.
+const TRIGGER_DELAY_SECS: u32 = 5;
+
+/// Debounce for package database file events.
+const DB_WATCH_DEBOUNCE_MS: u64 = 2000;
+
+/// SSH connect timeout for remote checks (seconds).
+const SSH_CONNECT_TIMEOUT: u32 = 10;
+
+/// Release of the running kernel (same as `uname -r`).
+const KERNEL_RELEASE_PATH: &str = "/proc/sys/kernel/osrelease";
+
+/// Installed kernels, one directory per release.
+const MODULES_DIR: &str = "/usr/lib/modules";
 
 /// Supported package managers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -48,6 +93,14 @@ impl PackageManager {
             Self::Paru => "paru -Syu",
         }
     }
+
+    /// Directories whose contents change when packages are installed or removed.
+    pub fn database_paths(&self) -> &'static [&'static str] {
+        match self {
+            Self::Dnf => &["/var/lib/rpm"],
+            Self::Pacman | Self::Paru => &["/var/lib/pacman/local"],
+        }
+    }
 }
 
 /// Information about a single package update.
@@ -57,6 +110,44 @@ pub struct UpdateInfo {
     pub name: String,
 }
 
+/// A remote machine whose updates are checked over SSH.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    /// Display name.
+    pub name: String,
+    /// SSH destination (`[user@]host` or a `~/.ssh/config` alias).
+    pub host: String,
+    /// Command run on the host; each non-empty output line is one update.
+    pub command: String,
+}
+
+/// Update state of a remote machine.
+#[derive(Debug, Clone)]
+pub struct RemoteUpdates {
+    /// The machine being checked.
+    pub target: RemoteTarget,
+    /// Whether a check is currently in progress.
+    pub checking: bool,
+    /// Pending updates as of the last successful check.
+    pub update_count: usize,
+    /// Error from the last check, if it failed.
+    pub error: Option<String>,
+    /// Time of the last successful check.
+    pub last_check: Option<SystemTime>,
+}
+
+impl RemoteUpdates {
+    fn new(target: RemoteTarget) -> Self {
+        Self {
+            target,
+            checking: false,
+            update_count: 0,
+            error: None,
+            last_check: None,
+        }
+    }
+}
+
 /// Canonical snapshot of update state.
 #[derive(Debug, Clone)]
 pub struct UpdatesSnapshot {
@@ -76,6 +167,18 @@ pub struct UpdatesSnapshot {
     pub last_check: Option<SystemTime>,
     /// Detected package manager.
     pub package_manager: Option<PackageManager>,
+    /// Whether Flatpak is installed.
+    pub flatpak_available: bool,
+    /// Pending Flatpak application and runtime updates.
+    pub flatpak_updates: Vec<UpdateInfo>,
+    /// Progress of a running Flatpak update (0.0 to 1.0), `None` when idle.
+    pub flatpak_progress: Option<f64>,
+    /// Error from the last Flatpak update, if it failed.
+    pub flatpak_error: Option<String>,
+    /// Remote machines, in configuration order.
+    pub remotes: Vec<RemoteUpdates>,
+    /// Why a reboot is needed, e.g. a newer kernel than the running one.
+    pub reboot_required: Option<String>,
 }
 
 impl UpdatesSnapshot {
@@ -90,8 +193,29 @@ impl UpdatesSnapshot {
             updates_by_repo: HashMap::new(),
             last_check: None,
             package_manager: None,
+            flatpak_available: false,
+            flatpak_updates: Vec::new(),
+            flatpak_progress: None,
+            flatpak_error: None,
+            remotes: Vec::new(),
+            reboot_required: None,
         }
     }
+
+    /// Total pending updates: system packages plus Flatpaks.
+    pub fn total_update_count(&self) -> usize {
+        self.update_count + self.flatpak_updates.len()
+    }
+
+    /// Pending updates across all remote machines.
+    pub fn remote_update_count(&self) -> usize {
+        self.remotes.iter().map(|r| r.update_count).sum()
+    }
+
+    /// Whether any remote machine failed its last check.
+    pub fn has_remote_error(&self) -> bool {
+        self.remotes.iter().any(|r| r.error.is_some())
+    }
 }
 
 /// Result of a background update check.
@@ -109,6 +233,16 @@ pub struct UpdatesService {
     timer_source: RefCell<Option<SourceId>>,
     /// Prevent concurrent checks.
     check_in_progress: Cell<bool>,
+    /// Pending event-triggered refresh.
+    trigger_source: RefCell<Option<SourceId>>,
+    /// Last seen network connectivity, for detecting reconnects.
+    network_online: Cell<Option<bool>>,
+    /// logind PrepareForSleep subscription (kept alive for resume detection).
+    sleep_subscription: RefCell<Option<gio::SignalSubscription>>,
+    /// Package database watcher (kept alive while the service exists).
+    db_watcher: RefCell<Option<Debouncer<RecommendedWatcher>>>,
+    /// Send a desktop notification when a reboot becomes necessary.
+    notify_on_reboot: Cell<bool>,
 }
 
 impl UpdatesService {
@@ -119,20 +253,31 @@ impl UpdatesService {
             check_interval: Cell::new(DEFAULT_CHECK_INTERVAL),
             timer_source: RefCell::new(None),
             check_in_progress: Cell::new(false),
+            trigger_source: RefCell::new(None),
+            network_online: Cell::new(None),
+            sleep_subscription: RefCell::new(None),
+            db_watcher: RefCell::new(None),
+            notify_on_reboot: Cell::new(false),
         });
 
         // Detect package manager
         let pm = detect_package_manager();
+        let flatpak = Path::new(FLATPAK_BIN).exists();
         {
             let mut snapshot = service.snapshot.borrow_mut();
             snapshot.package_manager = pm;
-            snapshot.available = pm.is_some();
+            snapshot.flatpak_available = flatpak;
+            snapshot.available = pm.is_some() || flatpak;
         }
 
-        if pm.is_some() {
-            info!("UpdatesService: detected package manager {:?}", pm);
+        if pm.is_some() || flatpak {
+            info!(
+                "UpdatesService: detected package manager {:?} (flatpak: {})",
+                pm, flatpak
+            );
             // Start initial check and periodic timer
             Self::start_periodic_checks(&service);
+            Self::start_refresh_triggers(&service, pm);
         } else {
             info!("UpdatesService: no supported package manager detected");
             let mut snapshot = service.snapshot.borrow_mut();
@@ -144,11 +289,17 @@ impl UpdatesService {
 
     /// Get the global UpdatesService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<UpdatesService> = UpdatesService::new();
-        }
+        UPDATES_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(UpdatesService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        UPDATES_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked whenever the snapshot changes.
@@ -166,14 +317,57 @@ impl UpdatesService {
         self.snapshot.borrow().clone()
     }
 
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: UpdatesSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+
     /// Trigger an immediate update check.
     pub fn refresh(&self) {
-        if !self.snapshot.borrow().available {
-            return;
+        {
+            let snapshot = self.snapshot.borrow();
+            if !snapshot.available && snapshot.remotes.is_empty() {
+                return;
+            }
         }
         self.check_updates_async();
     }
 
+    /// Set the remote machines to check over SSH.
+    ///
+    /// A changed list replaces the previous results and is checked right
+    /// away; the same list again is a no-op.
+    pub fn set_remotes(self: &Rc<Self>, remotes: Vec<RemoteTarget>) {
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if snapshot
+                .remotes
+                .iter()
+                .map(|r| &r.target)
+                .eq(remotes.iter())
+            {
+                return;
+            }
+            snapshot.remotes = remotes.into_iter().map(RemoteUpdates::new).collect();
+        }
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+
+        if self.timer_source.borrow().is_none() {
+            // No local package manager, so nothing is checking periodically yet
+            Self::start_periodic_checks(self);
+        } else {
+            self.check_remotes_async();
+        }
+    }
+
+    /// Enable or disable the notification sent when a reboot becomes necessary.
+    pub fn set_notify_on_reboot(&self, enabled: bool) {
+        self.notify_on_reboot.set(enabled);
+    }
+
     /// Set the check interval in seconds.
     ///
     /// Takes effect on the next timer cycle.
@@ -204,18 +398,154 @@ impl UpdatesService {
         *this.timer_source.borrow_mut() = Some(source_id);
     }
 
+    /// Start event-driven refresh triggers alongside the periodic timer.
+    fn start_refresh_triggers(this: &Rc<Self>, pm: Option<PackageManager>) {
+        Self::watch_network(this);
+        Self::watch_resume(this);
+        if let Some(pm) = pm {
+            this.watch_package_database(pm);
+        }
+    }
+
+    /// Refresh when the network comes back after being offline.
+    fn watch_network(this: &Rc<Self>) {
+        let this_weak = Rc::downgrade(this);
+        // Owned by the service, not by the bar that first configured it
+        EventBus::global().unscoped(|| {
+            NetworkService::global().connect(move |snapshot: &NetworkSnapshot| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                if !snapshot.available || !snapshot.is_ready {
+                    return;
+                }
+                let online = snapshot.connected || snapshot.wired_connected;
+                let previous = this.network_online.replace(Some(online));
+                if previous == Some(false) && online {
+                    this.schedule_triggered_refresh("network reconnected");
+                }
+            });
+        });
+    }
+
+    /// Refresh after resume from suspend via logind's PrepareForSleep signal.
+    fn watch_resume(this: &Rc<Self>) {
+        let this_weak = Rc::downgrade(this);
+        gio::bus_get(
+            gio::BusType::System,
+            None::<&gio::Cancellable>,
+            move |res| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                let connection = match res {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        debug!("UpdatesService: no system bus for resume detection: {}", e);
+                        return;
+                    }
+                };
+
+                let this_weak = Rc::downgrade(&this);
+                let subscription = connection.subscribe_to_signal(
+                    Some("org.freedesktop.login1"),
+                    Some("org.freedesktop.login1.Manager"),
+                    Some("PrepareForSleep"),
+                    Some("/org/freedesktop/login1"),
+                    None,
+                    gio::DBusSignalFlags::NONE,
+                    move |signal| {
+                        // PrepareForSleep(false) is emitted after resume.
+                        let going_to_sleep = signal.parameters.child_value(0).get::<bool>();
+                        if going_to_sleep == Some(false)
+                            && let Some(this) = this_weak.upgrade()
+                        {
+                            this.schedule_triggered_refresh("resumed from suspend");
+                        }
+                    },
+                );
+                this.sleep_subscription.replace(Some(subscription));
+            },
+        );
+    }
+
+    /// Refresh when the package database changes (install, upgrade, removal).
+    fn watch_package_database(&self, pm: PackageManager) {
+        let debounce = Duration::from_millis(DB_WATCH_DEBOUNCE_MS);
+        let mut debouncer = match new_debouncer(debounce, |res: DebounceEventResult| match res {
+            Ok(events) if !events.is_empty() => {
+                glib::idle_add_once(|| {
+                    UpdatesService::global().schedule_triggered_refresh("package database changed");
+                });
+            }
+            Ok(_) => {}
+            Err(e) => debug!("UpdatesService: package database watcher error: {}", e),
+        }) {
+            Ok(d) => d,
+            Err(e) => {
+                warn!(
+                    "UpdatesService: failed to create package database watcher: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        let mut watching = false;
+        for path in pm.database_paths() {
+            match debouncer
+                .watcher()
+                .watch(Path::new(path), RecursiveMode::NonRecursive)
+            {
+                Ok(()) => {
+                    debug!("UpdatesService: watching {} for changes", path);
+                    watching = true;
+                }
+                Err(e) => debug!("UpdatesService: cannot watch {}: {}", path, e),
+            }
+        }
+
+        if watching {
+            *self.db_watcher.borrow_mut() = Some(debouncer);
+        }
+    }
+
+    /// Schedule a refresh shortly after a trigger event.
+    ///
+    /// Repeated triggers within [`TRIGGER_DELAY_SECS`] restart the delay.
+    fn schedule_triggered_refresh(&self, reason: &str) {
+        debug!("UpdatesService: refresh triggered ({})", reason);
+
+        if let Some(source_id) = self.trigger_source.borrow_mut().take() {
+            source_id.remove();
+        }
+
+        let source_id = glib::timeout_add_seconds_local_once(TRIGGER_DELAY_SECS, || {
+            let service = UpdatesService::global();
+            // The source has fired; forget its ID so it isn't removed twice.
+            service.trigger_source.borrow_mut().take();
+            service.refresh();
+        });
+        *self.trigger_source.borrow_mut() = Some(source_id);
+    }
+
     /// Perform an async update check in a background thread.
     fn check_updates_async(&self) {
+        self.check_remotes_async();
+
         // Prevent concurrent checks
         if self.check_in_progress.get() {
             debug!("UpdatesService: check already in progress, skipping");
             return;
         }
 
-        let pm = match self.snapshot.borrow().package_manager {
-            Some(pm) => pm,
-            None => return,
+        let (pm, flatpak) = {
+            let snapshot = self.snapshot.borrow();
+            (snapshot.package_manager, snapshot.flatpak_available)
         };
+        if pm.is_none() && !flatpak {
+            return;
+        }
 
         self.check_in_progress.set(true);
 
@@ -228,27 +558,141 @@ impl UpdatesService {
             self.callbacks.notify(&snapshot_clone);
         }
 
-        debug!("UpdatesService: starting update check with {:?}", pm);
+        debug!(
+            "UpdatesService: starting update check with {:?} (flatpak: {})",
+            pm, flatpak
+        );
 
-        // Spawn background thread
-        std::thread::spawn(move || {
-            let result = run_update_check(pm);
+        // Package manager checks can take minutes; keep them off the pool
+        WorkerPool::global().spawn_long(
+            "update-check",
+            move || {
+                let result = match pm {
+                    Some(pm) => run_update_check(pm),
+                    None => CheckResult {
+                        updates_by_repo: HashMap::new(),
+                        error: None,
+                    },
+                };
+                let flatpak_updates = if flatpak {
+                    check_flatpak_updates()
+                } else {
+                    None
+                };
+                (result, flatpak_updates, detect_pending_reboot())
+            },
+            |result| {
+                let service = UpdatesService::global();
+                let (result, flatpak_updates, reboot_required) = result.unwrap_or_else(|e| {
+                    // Report the failure, keeping the previous reboot state
+                    let reboot_required = service.snapshot.borrow().reboot_required.clone();
+                    let result = CheckResult {
+                        updates_by_repo: HashMap::new(),
+                        error: Some(e.to_string()),
+                    };
+                    (result, None, reboot_required)
+                });
+                service.apply_check_result(result, flatpak_updates, reboot_required);
+            },
+        );
+    }
 
-            // Send result back to main thread
-            glib::idle_add_once(move || {
-                UpdatesService::global().apply_check_result(result);
-            });
-        });
+    /// Check every remote machine that isn't already being checked.
+    ///
+    /// Each host gets a thread of its own so a slow host doesn't hold up
+    /// the others or the worker pool.
+    fn check_remotes_async(&self) {
+        let targets: Vec<RemoteTarget> = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot
+                .remotes
+                .iter_mut()
+                .filter(|r| !r.checking)
+                .map(|r| {
+                    r.checking = true;
+                    r.target.clone()
+                })
+                .collect()
+        };
+        if targets.is_empty() {
+            return;
+        }
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+
+        for target in targets {
+            debug!("UpdatesService: checking {} over ssh", target.host);
+            let work_target = target.clone();
+            WorkerPool::global().spawn_long(
+                "remote-update-check",
+                move || run_remote_check(&work_target),
+                move |result| {
+                    UpdatesService::global().apply_remote_result(
+                        &target,
+                        result.unwrap_or_else(|e| Err(e.to_string())),
+                    );
+                },
+            );
+        }
+    }
+
+    /// Apply the result of a remote check.
+    fn apply_remote_result(&self, target: &RemoteTarget, result: Result<usize, String>) {
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            // The remotes may have been reconfigured while the check ran
+            let Some(remote) = snapshot.remotes.iter_mut().find(|r| r.target == *target) else {
+                return;
+            };
+            remote.checking = false;
+            match result {
+                Ok(count) => {
+                    debug!("UpdatesService: {} has {} updates", target.name, count);
+                    remote.update_count = count;
+                    remote.error = None;
+                    remote.last_check = Some(SystemTime::now());
+                }
+                Err(err) => {
+                    warn!("UpdatesService: check on {} failed: {}", target.name, err);
+                    // Keep the previous count on error
+                    remote.error = Some(err);
+                }
+            }
+        }
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
     }
 
     /// Apply the result of a background check.
-    fn apply_check_result(&self, result: CheckResult) {
+    ///
+    /// `flatpak_updates` is `None` when Flatpak wasn't checked or the check
+    /// failed, in which case the previous Flatpak list is kept.
+    fn apply_check_result(
+        &self,
+        result: CheckResult,
+        flatpak_updates: Option<Vec<UpdateInfo>>,
+        reboot_required: Option<String>,
+    ) {
         self.check_in_progress.set(false);
 
         let mut snapshot = self.snapshot.borrow_mut();
         snapshot.checking = false;
         snapshot.is_ready = true;
 
+        if let Some(ref reason) = reboot_required
+            && snapshot.reboot_required.is_none()
+        {
+            info!("UpdatesService: reboot required: {}", reason);
+            if self.notify_on_reboot.get() {
+                send_reboot_notification(reason);
+            }
+        }
+        snapshot.reboot_required = reboot_required;
+
+        if let Some(flatpak_updates) = flatpak_updates {
+            snapshot.flatpak_updates = flatpak_updates;
+        }
+
         if let Some(err) = result.error {
             warn!("UpdatesService: check failed: {}", err);
             snapshot.error = Some(err);
@@ -270,6 +714,82 @@ impl UpdatesService {
         drop(snapshot);
         self.callbacks.notify(&snapshot_clone);
     }
+
+    /// Update all installed Flatpaks in a background thread.
+    ///
+    /// Progress is published via `flatpak_progress`; a fresh check runs once
+    /// the update finishes.
+    pub fn update_flatpaks(&self) {
+        let total = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if !snapshot.flatpak_available || snapshot.flatpak_progress.is_some() {
+                return;
+            }
+            snapshot.flatpak_error = None;
+            match start_flatpak_update() {
+                Ok(child) => {
+                    snapshot.flatpak_progress = Some(0.0);
+                    Some((child, snapshot.flatpak_updates.len()))
+                }
+                Err(err) => {
+                    warn!("UpdatesService: could not start Flatpak update: {}", err);
+                    snapshot.flatpak_error = Some(err);
+                    None
+                }
+            }
+        };
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+
+        let Some((child, total)) = total else {
+            return;
+        };
+        info!("UpdatesService: updating {} Flatpak ref(s)", total);
+
+        std::thread::spawn(move || {
+            let result = run_flatpak_update(child, total, |progress| {
+                glib::idle_add_once(move || {
+                    UpdatesService::global().apply_flatpak_progress(progress);
+                });
+            });
+
+            glib::idle_add_once(move || {
+                UpdatesService::global().finish_flatpak_update(result);
+            });
+        });
+    }
+
+    /// Apply a progress report from a running Flatpak update.
+    fn apply_flatpak_progress(&self, progress: f64) {
+        let mut snapshot = self.snapshot.borrow_mut();
+        if snapshot.flatpak_progress.is_none() {
+            return;
+        }
+        snapshot.flatpak_progress = Some(progress);
+
+        let snapshot_clone = snapshot.clone();
+        drop(snapshot);
+        self.callbacks.notify(&snapshot_clone);
+    }
+
+    /// Apply the outcome of a Flatpak update and re-check.
+    fn finish_flatpak_update(&self, result: Result<(), String>) {
+        {
+            let mut snapshot = self.snapshot.borrow_mut();
+            snapshot.flatpak_progress = None;
+            match result {
+                Ok(()) => info!("UpdatesService: Flatpak update finished"),
+                Err(err) => {
+                    warn!("UpdatesService: Flatpak update failed: {}", err);
+                    snapshot.flatpak_error = Some(err);
+                }
+            }
+        }
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+
+        self.refresh();
+    }
 }
 
 impl Drop for UpdatesService {
@@ -277,6 +797,9 @@ impl Drop for UpdatesService {
         if let Some(source_id) = self.timer_source.borrow_mut().take() {
             source_id.remove();
         }
+        if let Some(source_id) = self.trigger_source.borrow_mut().take() {
+            source_id.remove();
+        }
     }
 }
 
@@ -305,6 +828,323 @@ fn detect_package_manager() -> Option<PackageManager> {
     None
 }
 
+/// Check for Flatpak updates across all installations.
+///
+/// Returns `None` if the check failed (e.g. offline), so callers can keep
+/// the previous result.
+fn check_flatpak_updates() -> Option<Vec<UpdateInfo>> {
+    let output = Command::new("flatpak")
+        .args(["remote-ls", "--updates", "--columns=application"])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Some(parse_flatpak_remote_ls_output(&stdout))
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            debug!("flatpak remote-ls failed: {}", stderr.trim());
+            None
+        }
+        Err(e) => {
+            debug!("Failed to run flatpak: {}", e);
+            None
+        }
+    }
+}
+
+/// Parse `flatpak remote-ls --updates --columns=application` output.
+///
+/// One application/runtime ID per line. The same ID can appear once per
+/// installation (user and system), so duplicates are dropped.
+fn parse_flatpak_remote_ls_output(output: &str) -> Vec<UpdateInfo> {
+    let mut updates: Vec<UpdateInfo> = Vec::new();
+
+    for line in output.lines() {
+        let name = line.trim();
+        // Header is only printed on a TTY, but skip it defensively
+        if name.is_empty() || name == "Application ID" {
+            continue;
+        }
+        if !updates.iter().any(|u| u.name == name) {
+            updates.push(UpdateInfo {
+                name: name.to_string(),
+            });
+        }
+    }
+
+    updates
+}
+
+/// Run `flatpak update` non-interactively, reporting progress.
+///
+/// This runs in a background thread. `on_progress` receives the fraction
+/// of operations started so far, based on the expected `total`.
+/// Start `flatpak update` through the action dispatcher, so kiosk mode
+/// refuses it.
+fn start_flatpak_update() -> Result<Child, String> {
+    let mut command = Command::new("flatpak");
+    command
+        .args(["update", "--noninteractive", "-y"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    Actions::global()
+        .spawn(ActionKind::Exec, &mut command)
+        .map_err(|e| format!("Failed to run flatpak: {}", e))
+}
+
+/// Follow a running `flatpak update`, reporting progress until it exits.
+fn run_flatpak_update<F>(mut child: Child, total: usize, on_progress: F) -> Result<(), String>
+where
+    F: Fn(f64),
+{
+    if let Some(stdout) = child.stdout.take() {
+        let mut started = 0usize;
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if is_flatpak_operation_line(&line) {
+                started += 1;
+                on_progress(flatpak_progress_fraction(started, total));
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for flatpak: {}", e))?;
+    if status.success() {
+        on_progress(1.0);
+        Ok(())
+    } else {
+        Err(format!("flatpak update exited with {}", status))
+    }
+}
+
+/// Whether a line of `flatpak update --noninteractive` output starts an operation.
+fn is_flatpak_operation_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("Updating ") || line.starts_with("Installing ")
+}
+
+/// Fraction of a Flatpak update that has completed.
+///
+/// The operation that just started is counted as half done so the bar
+/// never reaches 100% before the process exits.
+fn flatpak_progress_fraction(started: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let done = started as f64 - 0.5;
+    (done / total as f64).clamp(0.0, 0.99)
+}
+
+/// Run a remote target's command over SSH and count its output lines.
+///
+/// This runs in a background thread. `BatchMode` makes ssh fail instead of
+/// prompting for a password, so hosts need key-based authentication.
+fn run_remote_check(target: &RemoteTarget) -> Result<usize, String> {
+    let output = Command::new("ssh")
+        .args([
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            &format!("ConnectTimeout={}", SSH_CONNECT_TIMEOUT),
+            "--",
+            &target.host,
+            &target.command,
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+
+    // ssh exits with 255 on its own errors; any other status is the
+    // command's, and tools like `checkupdates` use those to mean "none"
+    if output.status.code() == Some(255) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or("ssh failed");
+        return Err(message.to_string());
+    }
+
+    Ok(count_update_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Count the updates in a remote command's output (one per non-empty line).
+fn count_update_lines(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+}
+
+/// Why a reboot is needed, if a newer kernel or systemd is installed than
+/// the one running.
+///
+/// This runs in a background thread.
+fn detect_pending_reboot() -> Option<String> {
+    let kernel = fs::read_to_string(KERNEL_RELEASE_PATH)
+        .ok()
+        .and_then(|running| {
+            pending_kernel(running.trim(), &installed_kernels(Path::new(MODULES_DIR)))
+        });
+    kernel.or_else(|| {
+        let running = command_stdout(
+            "busctl",
+            &[
+                "get-property",
+                "org.freedesktop.systemd1",
+                "/org/freedesktop/systemd1",
+                "org.freedesktop.systemd1.Manager",
+                "Version",
+            ],
+        )?;
+        let installed = command_stdout("systemctl", &["--version"])?;
+        pending_systemd(&running, &installed)
+    })
+}
+
+/// Run a command and return its stdout if it succeeded.
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Kernel releases with modules installed under `modules_dir`.
+///
+/// Directories left behind by a removed kernel (e.g. stray DKMS modules)
+/// don't count.
+fn installed_kernels(modules_dir: &Path) -> Vec<String> {
+    fs::read_dir(modules_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            path.join("vmlinuz").exists() || path.join("modules.dep").exists()
+        })
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
+
+/// Compare the running kernel against the installed ones.
+///
+/// Only kernels of the same flavor are compared, so an installed `linux`
+/// doesn't flag a running `linux-lts`.
+fn pending_kernel(running: &str, installed: &[String]) -> Option<String> {
+    // Nothing installed where we look (e.g. a container): can't tell
+    if installed.is_empty() {
+        return None;
+    }
+
+    let flavor = kernel_flavor(running);
+    let newest = installed
+        .iter()
+        .filter(|release| kernel_flavor(release) == flavor)
+        .max_by(|a, b| compare_versions(a, b));
+
+    if !installed.iter().any(|release| release == running) {
+        // Rolling distros remove the running kernel's modules on upgrade
+        return Some(match newest {
+            Some(newest) => format!("Kernel {} installed (running {})", newest, running),
+            None => format!("Running kernel {} is no longer installed", running),
+        });
+    }
+    match newest {
+        Some(newest) if compare_versions(newest, running) == Ordering::Greater => {
+            Some(format!("Kernel {} installed (running {})", newest, running))
+        }
+        _ => None,
+    }
+}
+
+/// A kernel release with its version numbers removed, e.g. "..-arch-" for
+/// "6.9.1-arch1-1" and "..--lts" for "6.6.30-1-lts".
+fn kernel_flavor(release: &str) -> String {
+    release.chars().filter(|c| !c.is_ascii_digit()).collect()
+}
+
+/// Compare two version strings by their numeric components.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|part| part.parse().ok())
+            .collect()
+    };
+    numbers(a).cmp(&numbers(b))
+}
+
+/// Compare the running systemd (`busctl get-property` output, e.g.
+/// `s "256.1-1-arch"`) against the installed one (`systemctl --version`,
+/// e.g. `systemd 256 (256.2-1-arch)`).
+fn pending_systemd(running: &str, installed: &str) -> Option<String> {
+    let running = running.trim().strip_prefix("s ")?.trim_matches('"');
+    let first_line = installed.lines().next()?;
+    let installed = match (first_line.find('('), first_line.rfind(')')) {
+        (Some(start), Some(end)) if start < end => &first_line[start + 1..end],
+        _ => first_line.split_whitespace().nth(1)?,
+    };
+    (!running.is_empty() && running != installed)
+        .then(|| format!("systemd {} installed (running {})", installed, running))
+}
+
+/// Send a desktop notification that a reboot is needed.
+fn send_reboot_notification(reason: &str) {
+    let params = (
+        "vibepanel",
+        0u32,
+        "system-reboot-symbolic",
+        "Reboot required",
+        reason,
+        Vec::<String>::new(),
+        HashMap::<String, glib::Variant>::new(),
+        -1i32,
+    )
+        .to_variant();
+
+    gio::bus_get(
+        gio::BusType::Session,
+        None::<&gio::Cancellable>,
+        move |res| {
+            let connection = match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("UpdatesService: failed to connect to session bus: {}", e);
+                    return;
+                }
+            };
+
+            dbus_watchdog::call(
+                "notifications",
+                &connection,
+                "org.freedesktop.Notifications",
+                "/org/freedesktop/Notifications",
+                "org.freedesktop.Notifications",
+                "Notify",
+                Some(&params),
+                None,
+                CallPolicy::ACTION,
+                |res| {
+                    if let Err(e) = res {
+                        debug!("UpdatesService: failed to send notification: {}", e);
+                    }
+                },
+            );
+        },
+    );
+}
+
 /// Run the update check for the given package manager.
 ///
 /// This runs in a background thread and should not touch any GTK state.
@@ -637,4 +1477,85 @@ firefox 119.0-1 -> 120.0-1
         assert_eq!(PackageManager::Pacman.upgrade_command(), "sudo pacman -Syu");
         assert_eq!(PackageManager::Paru.upgrade_command(), "paru -Syu");
     }
+
+    #[test]
+    fn test_parse_flatpak_remote_ls_output() {
+        let output = "org.mozilla.firefox\norg.freedesktop.Platform\n\norg.mozilla.firefox\n";
+        let updates = parse_flatpak_remote_ls_output(output);
+        let names: Vec<_> = updates.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["org.mozilla.firefox", "org.freedesktop.Platform"]
+        );
+    }
+
+    #[test]
+    fn test_count_update_lines() {
+        assert_eq!(count_update_lines(""), 0);
+        assert_eq!(
+            count_update_lines("linux 6.9.1-1 -> 6.9.2-1\n\n  \nmesa 24.1.0-1 -> 24.1.1-1\n"),
+            2
+        );
+    }
+
+    #[test]
+    fn test_pending_kernel() {
+        let installed =
+            |releases: &[&str]| -> Vec<String> { releases.iter().map(|r| r.to_string()).collect() };
+
+        // Arch: the running kernel's modules were replaced
+        assert_eq!(
+            pending_kernel("6.9.1-arch1-1", &installed(&["6.9.2-arch1-1"])).as_deref(),
+            Some("Kernel 6.9.2-arch1-1 installed (running 6.9.1-arch1-1)")
+        );
+        // Fedora: several kernels installed side by side
+        assert!(
+            pending_kernel(
+                "6.9.9-200.fc40.x86_64",
+                &installed(&["6.9.9-200.fc40.x86_64", "6.10.3-200.fc40.x86_64"])
+            )
+            .is_some()
+        );
+        // Running the newest; a newer kernel of another flavor doesn't count
+        assert_eq!(
+            pending_kernel(
+                "6.6.30-1-lts",
+                &installed(&["6.6.30-1-lts", "6.9.2-arch1-1"])
+            ),
+            None
+        );
+        assert_eq!(pending_kernel("6.9.1-arch1-1", &[]), None);
+    }
+
+    #[test]
+    fn test_pending_systemd() {
+        let installed = "systemd 256 (256.2-1-arch)\n+PAM +AUDIT +SELINUX\n";
+        assert_eq!(
+            pending_systemd("s \"256.1-1-arch\"\n", installed).as_deref(),
+            Some("systemd 256.2-1-arch installed (running 256.1-1-arch)")
+        );
+        assert_eq!(pending_systemd("s \"256.2-1-arch\"", installed), None);
+        assert_eq!(pending_systemd("s \"255\"", "systemd 255\n"), None);
+    }
+
+    #[test]
+    fn test_flatpak_progress() {
+        assert!(is_flatpak_operation_line(
+            "Updating app/org.mozilla.firefox/x86_64/stable"
+        ));
+        assert!(!is_flatpak_operation_line("Looking for updates…"));
+
+        assert_eq!(flatpak_progress_fraction(0, 0), 0.0);
+        assert_eq!(flatpak_progress_fraction(1, 2), 0.25);
+        assert_eq!(flatpak_progress_fraction(5, 2), 0.99);
+    }
+
+    #[test]
+    fn test_flatpak_update_refused_in_kiosk_mode() {
+        Actions::global().enable_kiosk();
+        assert_eq!(
+            start_flatpak_update().unwrap_err(),
+            "Failed to run flatpak: command disabled in kiosk mode"
+        );
+    }
 }
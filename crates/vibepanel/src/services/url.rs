@@ -0,0 +1,27 @@
+//! URL helpers shared by the services talking to HTTP APIs.
+
+/// Percent-encode a URL path segment or query value.
+pub fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_component() {
+        assert_eq!(encode_component("abcd-1234"), "abcd-1234");
+        assert_eq!(encode_component("group/project"), "group%2Fproject");
+        assert_eq!(encode_component("my docs&x"), "my%20docs%26x");
+    }
+}
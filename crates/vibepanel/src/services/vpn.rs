@@ -21,8 +21,12 @@ use gtk4::glib::{self, Variant};
 use tracing::{debug, error, warn};
 
 use super::callbacks::Callbacks;
+use super::dbus_watchdog::{self, CallPolicy};
 use super::state;
 
+/// Service name for D-Bus health tracking.
+const WATCHDOG_SERVICE: &str = "networkmanager";
+
 /// NetworkManager service name.
 const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
 /// NetworkManager main object path.
@@ -262,16 +266,16 @@ impl VpnService {
     /// secret agents (polkit) for connections that require authentication.
     fn activate_connection_dbus(connection: &gio::DBusConnection, uuid: &str) {
         // First, get the connection object path from UUID
-        let conn_path = match connection.call_sync(
-            Some(NM_SERVICE),
+        let conn_path = match dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
             NM_SETTINGS_PATH,
             NM_SETTINGS_IFACE,
             "GetConnectionByUuid",
             Some(&(uuid,).to_variant()),
             Some(glib::VariantTy::new("(o)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::QUERY,
         ) {
             Ok(v) => {
                 let path_variant = v.child_value(0);
@@ -302,16 +306,16 @@ impl VpnService {
             glib::variant::ObjectPath::try_from("/").unwrap(),
         );
 
-        match connection.call_sync(
-            Some(NM_SERVICE),
+        match dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
             NM_PATH,
             NM_IFACE,
             "ActivateConnection",
             Some(&args.to_variant()),
             Some(glib::VariantTy::new("(o)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            30000, // 30s timeout for auth dialogs
-            None::<&gio::Cancellable>,
+            CallPolicy::SLOW, // 30s timeout for auth dialogs
         ) {
             Ok(_) => debug!("VPN: Connection {} activation initiated", uuid),
             Err(e) => warn!("VPN: Failed to activate connection {}: {}", uuid, e),
@@ -336,16 +340,16 @@ impl VpnService {
 
         let args = (glib::variant::ObjectPath::try_from(active_path.as_str()).unwrap(),);
 
-        match connection.call_sync(
-            Some(NM_SERVICE),
+        match dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
             NM_PATH,
             NM_IFACE,
             "DeactivateConnection",
             Some(&args.to_variant()),
             None,
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::ACTION,
         ) {
             Ok(_) => debug!("VPN: Connection {} deactivated", uuid),
             Err(e) => warn!("VPN: Failed to deactivate connection {}: {}", uuid, e),
@@ -355,19 +359,18 @@ impl VpnService {
     /// Find the active connection object path for a given UUID.
     fn find_active_connection_path(connection: &gio::DBusConnection, uuid: &str) -> Option<String> {
         // Get ActiveConnections property
-        let active_conns = connection
-            .call_sync(
-                Some(NM_SERVICE),
-                NM_PATH,
-                IFACE_PROPS,
-                "Get",
-                Some(&("org.freedesktop.NetworkManager", "ActiveConnections").to_variant()),
-                Some(glib::VariantTy::new("(v)").unwrap()),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .ok()?;
+        let active_conns = dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
+            NM_PATH,
+            IFACE_PROPS,
+            "Get",
+            Some(&("org.freedesktop.NetworkManager", "ActiveConnections").to_variant()),
+            Some(glib::VariantTy::new("(v)").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .ok()?;
 
         let inner = active_conns.child_value(0);
         let paths_variant = inner.child_value(0);
@@ -702,16 +705,16 @@ impl VpnService {
         let mut result = Vec::new();
 
         // Get list of connection paths from Settings.
-        let conn_paths = match connection.call_sync(
-            Some(NM_SERVICE),
+        let conn_paths = match dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
             NM_SETTINGS_PATH,
             NM_SETTINGS_IFACE,
             "ListConnections",
             None,
             Some(glib::VariantTy::new("(ao)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::QUERY,
         ) {
             Ok(v) => v,
             Err(e) => {
@@ -734,16 +737,16 @@ impl VpnService {
             };
 
             // Get connection settings.
-            let settings = match connection.call_sync(
-                Some(NM_SERVICE),
+            let settings = match dbus_watchdog::call_sync(
+                WATCHDOG_SERVICE,
+                connection,
+                NM_SERVICE,
                 path,
                 IFACE_CONNECTION,
                 "GetSettings",
                 None,
                 Some(glib::VariantTy::new("(a{sa{sv}})").unwrap()),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
+                CallPolicy::QUERY,
             ) {
                 Ok(v) => v,
                 Err(e) => {
@@ -810,16 +813,16 @@ impl VpnService {
         let mut vpn_paths: Vec<String> = Vec::new();
 
         // Get ActiveConnections property.
-        let active_conns = match connection.call_sync(
-            Some(NM_SERVICE),
+        let active_conns = match dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
             NM_PATH,
             IFACE_PROPS,
             "Get",
             Some(&("org.freedesktop.NetworkManager", "ActiveConnections").to_variant()),
             Some(glib::VariantTy::new("(v)").unwrap()),
-            gio::DBusCallFlags::NONE,
-            5000,
-            None::<&gio::Cancellable>,
+            CallPolicy::QUERY,
         ) {
             Ok(v) => v,
             Err(e) => {
@@ -870,19 +873,18 @@ impl VpnService {
         interface: &str,
         property: &str,
     ) -> Option<String> {
-        let result = connection
-            .call_sync(
-                Some(NM_SERVICE),
-                path,
-                IFACE_PROPS,
-                "Get",
-                Some(&(interface, property).to_variant()),
-                Some(glib::VariantTy::new("(v)").unwrap()),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .ok()?;
+        let result = dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
+            path,
+            IFACE_PROPS,
+            "Get",
+            Some(&(interface, property).to_variant()),
+            Some(glib::VariantTy::new("(v)").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .ok()?;
 
         let inner = result.child_value(0);
         let value = inner.child_value(0);
@@ -896,19 +898,18 @@ impl VpnService {
         interface: &str,
         property: &str,
     ) -> Option<u32> {
-        let result = connection
-            .call_sync(
-                Some(NM_SERVICE),
-                path,
-                IFACE_PROPS,
-                "Get",
-                Some(&(interface, property).to_variant()),
-                Some(glib::VariantTy::new("(v)").unwrap()),
-                gio::DBusCallFlags::NONE,
-                5000,
-                None::<&gio::Cancellable>,
-            )
-            .ok()?;
+        let result = dbus_watchdog::call_sync(
+            WATCHDOG_SERVICE,
+            connection,
+            NM_SERVICE,
+            path,
+            IFACE_PROPS,
+            "Get",
+            Some(&(interface, property).to_variant()),
+            Some(glib::VariantTy::new("(v)").unwrap()),
+            CallPolicy::QUERY,
+        )
+        .ok()?;
 
         let inner = result.child_value(0);
         let value = inner.child_value(0);
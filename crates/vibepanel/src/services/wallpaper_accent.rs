@@ -0,0 +1,236 @@
+//! WallpaperAccentService - accent color sampled from the wallpaper.
+//!
+//! With `theme.accent = "wallpaper"`, the wallpaper (`theme.wallpaper`, or
+//! the first one `hyprctl hyprpaper listactive` or `swww query` reports) is
+//! decoded at thumbnail size on the worker pool and its dominant color
+//! becomes the accent. The color is handed to the config manager, which
+//! rebuilds the theme CSS with it through `ThemePalette::from_config`.
+//!
+//! Neither hyprpaper nor swww announces wallpaper changes, so the service
+//! polls: the wallpaper is looked up again every few seconds and only
+//! re-sampled when its path or modification time changed.
+
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use gtk4::gdk_pixbuf::Pixbuf;
+use gtk4::glib;
+use tracing::{debug, warn};
+use vibepanel_core::config::ThemeConfig;
+use vibepanel_core::wallpaper;
+
+use super::config_manager::ConfigManager;
+use super::worker::WorkerPool;
+
+/// How often the wallpaper is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Size the wallpaper is scaled down to before sampling.
+const SAMPLE_SIZE: i32 = 64;
+
+/// A wallpaper file as last seen: its path and modification time.
+type WallpaperKey = (PathBuf, SystemTime);
+
+/// Outcome of a wallpaper check on the worker pool.
+enum Sample {
+    /// Same file as last time.
+    Unchanged,
+    /// A new or modified wallpaper and its dominant color, if it has one.
+    Sampled(WallpaperKey, Option<String>),
+}
+
+/// Shared, process-wide wallpaper accent service.
+pub struct WallpaperAccentService {
+    /// `theme.wallpaper`, or `None` to ask the wallpaper tools.
+    configured: RefCell<Option<String>>,
+    /// Wallpaper the current color was sampled from.
+    sampled: RefCell<Option<WallpaperKey>>,
+    /// Dominant color of the wallpaper (`None` = theme default).
+    color: RefCell<Option<String>>,
+    /// Last error logged, so a missing wallpaper isn't logged every poll.
+    last_error: RefCell<Option<String>>,
+    /// Poll timer while `theme.accent = "wallpaper"`.
+    timer_source: RefCell<Option<glib::SourceId>>,
+    check_in_progress: Cell<bool>,
+}
+
+impl WallpaperAccentService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            configured: RefCell::new(None),
+            sampled: RefCell::new(None),
+            color: RefCell::new(None),
+            last_error: RefCell::new(None),
+            timer_source: RefCell::new(None),
+            check_in_progress: Cell::new(false),
+        })
+    }
+
+    /// Get the global WallpaperAccentService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<WallpaperAccentService> = WallpaperAccentService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Start or stop following the wallpaper (also after a config reload).
+    pub fn configure(self: &Rc<Self>, theme: &ThemeConfig) {
+        if theme.accent.as_deref() != Some("wallpaper") {
+            if let Some(source_id) = self.timer_source.borrow_mut().take() {
+                source_id.remove();
+                debug!("WallpaperAccentService: stopped");
+            }
+            *self.sampled.borrow_mut() = None;
+            *self.color.borrow_mut() = None;
+            return;
+        }
+
+        if *self.configured.borrow() != theme.wallpaper {
+            *self.configured.borrow_mut() = theme.wallpaper.clone();
+            *self.sampled.borrow_mut() = None;
+        }
+
+        if self.timer_source.borrow().is_none() {
+            let this_weak = Rc::downgrade(self);
+            let source_id = glib::timeout_add_local(POLL_INTERVAL, move || {
+                if let Some(this) = this_weak.upgrade() {
+                    this.refresh();
+                    glib::ControlFlow::Continue
+                } else {
+                    glib::ControlFlow::Break
+                }
+            });
+            *self.timer_source.borrow_mut() = Some(source_id);
+            debug!("WallpaperAccentService: following the wallpaper");
+        }
+
+        self.refresh();
+    }
+
+    /// Dominant color of the current wallpaper, once sampled.
+    pub fn color(&self) -> Option<String> {
+        self.color.borrow().clone()
+    }
+
+    /// Look the wallpaper up and sample it if it changed.
+    fn refresh(&self) {
+        if self.check_in_progress.replace(true) {
+            return;
+        }
+
+        let configured = self.configured.borrow().clone();
+        let sampled = self.sampled.borrow().clone();
+        WorkerPool::global().spawn(
+            move || sample(configured.as_deref(), sampled.as_ref()),
+            |result| {
+                WallpaperAccentService::global()
+                    .apply_sample(result.unwrap_or_else(|e| Err(e.to_string())));
+            },
+        );
+    }
+
+    fn apply_sample(&self, result: Result<Sample, String>) {
+        self.check_in_progress.set(false);
+        if self.timer_source.borrow().is_none() {
+            // Stopped while the check was running
+            return;
+        }
+
+        let (key, color) = match result {
+            Ok(Sample::Unchanged) => return,
+            Ok(Sample::Sampled(key, color)) => (key, color),
+            Err(e) => {
+                if self.last_error.borrow().as_ref() != Some(&e) {
+                    warn!("WallpaperAccentService: {}", e);
+                    *self.last_error.borrow_mut() = Some(e);
+                }
+                return;
+            }
+        };
+        *self.last_error.borrow_mut() = None;
+
+        if color.is_none() {
+            debug!(
+                "WallpaperAccentService: {} has no dominant color",
+                key.0.display()
+            );
+        }
+        *self.sampled.borrow_mut() = Some(key);
+        if *self.color.borrow() == color {
+            return;
+        }
+
+        debug!("WallpaperAccentService: accent {:?}", color);
+        *self.color.borrow_mut() = color;
+        ConfigManager::global().refresh_wallpaper_accent();
+    }
+}
+
+/// Find the wallpaper and sample it unless it's the one sampled last.
+fn sample(configured: Option<&str>, sampled: Option<&WallpaperKey>) -> Result<Sample, String> {
+    let path = match configured {
+        Some(path) => wallpaper::wallpaper_path(path),
+        None => current_wallpaper().ok_or_else(|| {
+            "no wallpaper reported by hyprpaper or swww; set theme.wallpaper".to_string()
+        })?,
+    };
+    let modified = std::fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+    let key = (path, modified);
+    if sampled == Some(&key) {
+        return Ok(Sample::Unchanged);
+    }
+
+    let color = dominant_color(&key.0)?;
+    Ok(Sample::Sampled(key, color))
+}
+
+/// Wallpaper shown by hyprpaper or swww, whichever answers.
+fn current_wallpaper() -> Option<PathBuf> {
+    run_tool("hyprctl", &["hyprpaper", "listactive"])
+        .and_then(|output| wallpaper::parse_hyprpaper_active(&output))
+        .or_else(|| {
+            run_tool("swww", &["query"]).and_then(|output| wallpaper::parse_swww_query(&output))
+        })
+}
+
+/// Run a wallpaper tool and return its stdout, `None` if it isn't installed
+/// or fails.
+fn run_tool(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Decode `path` at thumbnail size and pick its dominant color.
+fn dominant_color(path: &Path) -> Result<Option<String>, String> {
+    let pixbuf = Pixbuf::from_file_at_scale(path, SAMPLE_SIZE, SAMPLE_SIZE, true)
+        .map_err(|e| format!("failed to load {}: {}", path.display(), e))?;
+
+    // Drop the row padding
+    let channels = pixbuf.n_channels() as usize;
+    let width = pixbuf.width() as usize;
+    let rowstride = pixbuf.rowstride() as usize;
+    let bytes = pixbuf.read_pixel_bytes();
+    let pixels: Vec<u8> = (0..pixbuf.height() as usize)
+        .filter_map(|row| bytes.get(row * rowstride..row * rowstride + width * channels))
+        .flatten()
+        .copied()
+        .collect();
+
+    Ok(wallpaper::dominant_color(&pixels, channels))
+}
@@ -0,0 +1,951 @@
+//! WeatherService - current conditions and forecast from a weather provider.
+//!
+//! This service provides:
+//! - Current temperature and condition, an hourly forecast for the next
+//!   hours and a daily forecast for the week
+//! - Periodic fetches on the worker pool, keeping the last forecast on errors
+//! - Any number of named locations, each with its own update interval;
+//!   locations come from coordinates, a place name (geocoded once by the
+//!   provider), or GeoClue when neither is configured
+//!
+//! Providers implement [`WeatherProvider`]; Open-Meteo is the default and
+//! needs no API key.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use gtk4::glib::{self, SourceId};
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use super::callbacks::Callbacks;
+use super::event_bus::EventBus;
+use super::geoclue::{GeoClueService, GeoClueSnapshot};
+use super::url::encode_component;
+use super::widget_settings::{MergeSettings, SettingsId, WidgetSettings};
+use super::worker::WorkerPool;
+
+// Thread-local singleton storage for WeatherService
+thread_local! {
+    static WEATHER_INSTANCE: RefCell<Option<Rc<WeatherService>>> = const { RefCell::new(None) };
+}
+
+/// Default update interval in seconds.
+pub const DEFAULT_UPDATE_INTERVAL: u64 = 900;
+
+/// Minimum update interval in seconds (providers ask for modest polling).
+pub const MIN_UPDATE_INTERVAL: u64 = 300;
+
+/// Request timeout in seconds.
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Number of hours in the hourly forecast, starting with the current hour.
+const HOURLY_COUNT: usize = 12;
+
+/// Number of days in the daily forecast, starting today.
+const DAILY_COUNT: usize = 7;
+
+/// Unit system for temperatures and wind speed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Units {
+    /// °C and km/h.
+    #[default]
+    Metric,
+    /// °F and mph.
+    Imperial,
+}
+
+impl Units {
+    /// Parse a config value ("metric" / "imperial").
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "metric" | "celsius" => Some(Self::Metric),
+            "imperial" | "fahrenheit" => Some(Self::Imperial),
+            _ => None,
+        }
+    }
+
+    /// Temperature unit symbol, e.g. "°C".
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self {
+            Self::Metric => "°C",
+            Self::Imperial => "°F",
+        }
+    }
+
+    /// Wind speed unit, e.g. "km/h".
+    pub fn wind_speed_unit(&self) -> &'static str {
+        match self {
+            Self::Metric => "km/h",
+            Self::Imperial => "mph",
+        }
+    }
+}
+
+/// Provider-independent weather condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    FreezingRain,
+    Showers,
+    Snow,
+    Thunderstorm,
+}
+
+impl Condition {
+    /// Map a WMO weather interpretation code (as used by Open-Meteo).
+    pub fn from_wmo(code: u8) -> Self {
+        match code {
+            0 => Self::Clear,
+            1 | 2 => Self::PartlyCloudy,
+            3 => Self::Cloudy,
+            45 | 48 => Self::Fog,
+            51 | 53 | 55 => Self::Drizzle,
+            56 | 57 | 66 | 67 => Self::FreezingRain,
+            61 | 63 | 65 => Self::Rain,
+            80..=82 => Self::Showers,
+            71 | 73 | 75 | 77 | 85 | 86 => Self::Snow,
+            95 | 96 | 99 => Self::Thunderstorm,
+            _ => Self::Cloudy,
+        }
+    }
+
+    /// Human-readable description.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Clear => "Clear",
+            Self::PartlyCloudy => "Partly cloudy",
+            Self::Cloudy => "Cloudy",
+            Self::Fog => "Fog",
+            Self::Drizzle => "Drizzle",
+            Self::Rain => "Rain",
+            Self::FreezingRain => "Freezing rain",
+            Self::Showers => "Showers",
+            Self::Snow => "Snow",
+            Self::Thunderstorm => "Thunderstorm",
+        }
+    }
+
+    /// Icon name for the condition by day or night.
+    pub fn icon(&self, is_day: bool) -> &'static str {
+        match (self, is_day) {
+            (Self::Clear, true) => "weather-clear-symbolic",
+            (Self::Clear, false) => "weather-clear-night-symbolic",
+            (Self::PartlyCloudy, true) => "weather-few-clouds-symbolic",
+            (Self::PartlyCloudy, false) => "weather-few-clouds-night-symbolic",
+            (Self::Cloudy, _) => "weather-overcast-symbolic",
+            (Self::Fog, _) => "weather-fog-symbolic",
+            (Self::Drizzle, _) => "weather-showers-scattered-symbolic",
+            (Self::Rain | Self::FreezingRain | Self::Showers, _) => "weather-showers-symbolic",
+            (Self::Snow, _) => "weather-snow-symbolic",
+            (Self::Thunderstorm, _) => "weather-storm-symbolic",
+        }
+    }
+}
+
+/// Current conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentWeather {
+    pub temperature: f64,
+    pub apparent_temperature: Option<f64>,
+    /// Relative humidity in percent.
+    pub humidity: Option<u8>,
+    pub wind_speed: Option<f64>,
+    pub condition: Condition,
+    pub is_day: bool,
+}
+
+/// One hour of the hourly forecast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HourlyWeather {
+    /// Local time at the location.
+    pub time: NaiveDateTime,
+    pub temperature: f64,
+    pub condition: Condition,
+    pub is_day: bool,
+    /// Chance of precipitation in percent.
+    pub precipitation_probability: Option<u8>,
+}
+
+/// One day of the daily forecast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyWeather {
+    /// Local date at the location.
+    pub date: NaiveDate,
+    pub min: f64,
+    pub max: f64,
+    pub condition: Condition,
+    /// Highest chance of precipitation in percent.
+    pub precipitation_probability: Option<u8>,
+}
+
+/// Current conditions plus forecast, in the requested units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    pub current: CurrentWeather,
+    pub hourly: Vec<HourlyWeather>,
+    pub daily: Vec<DailyWeather>,
+}
+
+/// A geocoded place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+    /// Display name, e.g. "Berlin, Germany".
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A weather data source.
+///
+/// Methods block on the network and run on the worker pool.
+pub trait WeatherProvider: Sync {
+    /// Resolve a place name to coordinates.
+    fn geocode(&self, query: &str) -> Result<Place, String>;
+
+    /// Fetch current conditions and forecast for a location.
+    fn forecast(&self, latitude: f64, longitude: f64, units: Units) -> Result<Forecast, String>;
+}
+
+/// Available providers, selected by name in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// [Open-Meteo](https://open-meteo.com) (no API key).
+    #[default]
+    OpenMeteo,
+}
+
+impl ProviderKind {
+    /// Parse a config value (e.g. "open-meteo").
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().replace('_', "-").as_str() {
+            "open-meteo" | "openmeteo" => Some(Self::OpenMeteo),
+            _ => None,
+        }
+    }
+
+    fn provider(self) -> &'static dyn WeatherProvider {
+        match self {
+            Self::OpenMeteo => &OpenMeteo,
+        }
+    }
+}
+
+/// Where to get the weather for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeatherLocation {
+    /// Fixed coordinates.
+    Coordinates { latitude: f64, longitude: f64 },
+    /// A place name, geocoded by the provider.
+    Place(String),
+    /// The GeoClue location.
+    Auto,
+}
+
+/// One location to track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocationSettings {
+    /// Display name (defaults to the place name or coordinates).
+    pub name: Option<String>,
+    pub location: WeatherLocation,
+    /// Update interval (seconds).
+    pub update_interval: u64,
+}
+
+/// Settings for the weather service (from the widget config).
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherSettings {
+    /// Tracked locations, in display order.
+    pub locations: Vec<LocationSettings>,
+    pub units: Units,
+    pub provider: ProviderKind,
+}
+
+impl MergeSettings for WeatherSettings {
+    /// Track every widget's locations, each at its shortest interval; the
+    /// units and provider of the newest widget win.
+    fn merge(&mut self, other: &Self) {
+        for location in &other.locations {
+            match self
+                .locations
+                .iter_mut()
+                .find(|l| l.name == location.name && l.location == location.location)
+            {
+                Some(existing) => {
+                    existing.update_interval =
+                        existing.update_interval.min(location.update_interval);
+                }
+                None => self.locations.push(location.clone()),
+            }
+        }
+        self.units = other.units;
+        self.provider = other.provider;
+    }
+}
+
+/// Weather state for one tracked location.
+#[derive(Debug, Clone, Default)]
+pub struct LocationWeather {
+    /// Configured display name.
+    pub name: Option<String>,
+    /// Whether a fetch has completed (successfully or not).
+    pub ready: bool,
+    /// Display name of the location, if known.
+    pub place: Option<String>,
+    /// Latest forecast (kept when later fetches fail).
+    pub forecast: Option<Forecast>,
+    /// Error from the last fetch, or why no fetch could be made.
+    pub error: Option<String>,
+    /// When the forecast was last fetched.
+    pub updated: Option<SystemTime>,
+}
+
+impl LocationWeather {
+    /// Configured name, else the place name.
+    pub fn title(&self) -> Option<&str> {
+        self.name.as_deref().or(self.place.as_deref())
+    }
+}
+
+/// Canonical snapshot of weather state.
+#[derive(Debug, Clone, Default)]
+pub struct WeatherSnapshot {
+    /// Units of the forecasts.
+    pub units: Units,
+    /// One entry per configured location, in the same order.
+    pub locations: Vec<LocationWeather>,
+}
+
+/// Internal per-location fetch state.
+#[derive(Default)]
+struct TrackedLocation {
+    timer_source: Option<SourceId>,
+    /// Prevent overlapping fetches.
+    fetch_in_progress: bool,
+    /// Geocoded place for a `WeatherLocation::Place`.
+    geocoded: Option<Place>,
+}
+
+/// Shared, process-wide weather service.
+pub struct WeatherService {
+    snapshot: RefCell<WeatherSnapshot>,
+    callbacks: Callbacks<WeatherSnapshot>,
+    /// Settings of each weather widget.
+    registered: WidgetSettings<WeatherSettings>,
+    /// Merged settings the fetches run with.
+    settings: RefCell<Option<WeatherSettings>>,
+    /// Bumped on every configure so results for old settings are dropped.
+    generation: Cell<u64>,
+    /// Fetch state, indexed like `settings.locations`.
+    tracked: RefCell<Vec<TrackedLocation>>,
+    /// Location from GeoClue, for `WeatherLocation::Auto`.
+    geoclue_location: Cell<Option<(f64, f64)>>,
+    /// Whether the GeoClue location is being followed.
+    watching_geoclue: Cell<bool>,
+}
+
+impl WeatherService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            snapshot: RefCell::new(WeatherSnapshot::default()),
+            callbacks: Callbacks::new(),
+            registered: WidgetSettings::new(),
+            settings: RefCell::new(None),
+            generation: Cell::new(0),
+            tracked: RefCell::new(Vec::new()),
+            geoclue_location: Cell::new(None),
+            watching_geoclue: Cell::new(false),
+        })
+    }
+
+    /// Get the global WeatherService singleton.
+    pub fn global() -> Rc<Self> {
+        WEATHER_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(WeatherService::new)
+                .clone()
+        })
+    }
+
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        WEATHER_INSTANCE.with(RefCell::take);
+    }
+
+    /// Register a callback to be invoked whenever the snapshot changes.
+    pub fn connect<F>(&self, callback: F)
+    where
+        F: Fn(&WeatherSnapshot) + 'static,
+    {
+        self.callbacks.register(callback);
+        // Immediately notify with current snapshot
+        let snapshot = self.snapshot.borrow().clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Return the current snapshot.
+    pub fn snapshot(&self) -> WeatherSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Replace the snapshot with a canned one and notify listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, snapshot: WeatherSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot.clone();
+        self.callbacks.notify(&snapshot);
+    }
+
+    /// Register a weather widget's locations and fetch them.
+    pub fn configure(self: &Rc<Self>, settings: WeatherSettings) -> SettingsId {
+        let id = self.registered.add(settings);
+        self.apply_settings();
+        id
+    }
+
+    /// Forget a widget's locations; updates stop with the last one.
+    pub fn release(self: &Rc<Self>, id: SettingsId) {
+        if self.registered.remove(id) {
+            self.apply_settings();
+        }
+    }
+
+    /// Restart updates with the merged settings of all widgets.
+    fn apply_settings(self: &Rc<Self>) {
+        let settings = self.registered.merged();
+        if *self.settings.borrow() == settings {
+            return;
+        }
+
+        self.stop_timers();
+        self.generation.set(self.generation.get() + 1);
+        let Some(settings) = settings else {
+            debug!("WeatherService: no widgets left, stopping updates");
+            self.tracked.borrow_mut().clear();
+            *self.settings.borrow_mut() = None;
+            *self.snapshot.borrow_mut() = WeatherSnapshot::default();
+            self.callbacks.notify(&WeatherSnapshot::default());
+            return;
+        };
+
+        info!(
+            "WeatherService: {} location(s) via {:?}",
+            settings.locations.len(),
+            settings.provider
+        );
+
+        // Forget data fetched with the old settings
+        *self.snapshot.borrow_mut() = WeatherSnapshot {
+            units: settings.units,
+            locations: settings
+                .locations
+                .iter()
+                .map(|location| LocationWeather {
+                    name: location.name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+
+        let mut tracked = Vec::with_capacity(settings.locations.len());
+        for (index, location) in settings.locations.iter().enumerate() {
+            let update_interval = location.update_interval.max(MIN_UPDATE_INTERVAL);
+            debug!(
+                "WeatherService: {:?}, updating every {}s",
+                location.location, update_interval
+            );
+            let this_weak = Rc::downgrade(self);
+            let source_id = glib::timeout_add_seconds_local(update_interval as u32, move || {
+                if let Some(this) = this_weak.upgrade() {
+                    this.refresh_location(index);
+                    glib::ControlFlow::Continue
+                } else {
+                    glib::ControlFlow::Break
+                }
+            });
+            tracked.push(TrackedLocation {
+                timer_source: Some(source_id),
+                ..Default::default()
+            });
+        }
+        *self.tracked.borrow_mut() = tracked;
+
+        let watch_geoclue = settings
+            .locations
+            .iter()
+            .any(|location| location.location == WeatherLocation::Auto);
+        *self.settings.borrow_mut() = Some(settings);
+
+        if watch_geoclue && !self.watching_geoclue.replace(true) {
+            Self::watch_geoclue(self);
+        }
+        self.refresh();
+    }
+
+    /// Fetch the weather for every location now.
+    pub fn refresh(&self) {
+        let count = self.tracked.borrow().len();
+        for index in 0..count {
+            self.refresh_location(index);
+        }
+    }
+
+    /// Fetch the weather for one location now.
+    fn refresh_location(&self, index: usize) {
+        let Some(settings) = self.settings.borrow().clone() else {
+            return;
+        };
+        let Some(location) = settings.locations.get(index) else {
+            return;
+        };
+
+        // Coordinates to fetch for, or a place name to geocode first
+        let (coordinates, query) = {
+            let tracked = self.tracked.borrow();
+            let Some(tracked) = tracked.get(index) else {
+                return;
+            };
+            if tracked.fetch_in_progress {
+                return;
+            }
+            match location.location {
+                WeatherLocation::Coordinates {
+                    latitude,
+                    longitude,
+                } => (Some((latitude, longitude)), None),
+                WeatherLocation::Place(ref query) => match tracked.geocoded {
+                    Some(ref place) => (Some((place.latitude, place.longitude)), None),
+                    None => (None, Some(query.clone())),
+                },
+                WeatherLocation::Auto => (self.geoclue_location.get(), None),
+            }
+        };
+        if coordinates.is_none() && query.is_none() {
+            self.update_location(index, |weather| {
+                weather.error =
+                    Some("Waiting for location (set `location` or enable GeoClue)".to_string())
+            });
+            return;
+        }
+
+        if let Some(tracked) = self.tracked.borrow_mut().get_mut(index) {
+            tracked.fetch_in_progress = true;
+        }
+        let generation = self.generation.get();
+        let provider = settings.provider.provider();
+        let units = settings.units;
+        WorkerPool::global().spawn(
+            move || -> Result<(Option<Place>, Forecast), String> {
+                let place = query.map(|query| provider.geocode(&query)).transpose()?;
+                let (latitude, longitude) = match place {
+                    Some(ref place) => (place.latitude, place.longitude),
+                    None => coordinates.unwrap_or_default(),
+                };
+                let forecast = provider.forecast(latitude, longitude, units)?;
+                Ok((place, forecast))
+            },
+            move |result| {
+                WeatherService::global().apply_result(
+                    generation,
+                    index,
+                    result.unwrap_or_else(|e| Err(e.to_string())),
+                );
+            },
+        );
+    }
+
+    fn apply_result(
+        &self,
+        generation: u64,
+        index: usize,
+        result: Result<(Option<Place>, Forecast), String>,
+    ) {
+        if generation != self.generation.get() {
+            // Settings changed mid-fetch; configure already fetched again
+            return;
+        }
+        let Some(location) = self
+            .settings
+            .borrow()
+            .as_ref()
+            .and_then(|settings| settings.locations.get(index).cloned())
+        else {
+            return;
+        };
+
+        let geocoded = {
+            let mut tracked = self.tracked.borrow_mut();
+            let Some(tracked) = tracked.get_mut(index) else {
+                return;
+            };
+            tracked.fetch_in_progress = false;
+            if let Ok((Some(ref place), _)) = result {
+                tracked.geocoded = Some(place.clone());
+            }
+            tracked.geocoded.clone()
+        };
+
+        match result {
+            Ok((_, forecast)) => {
+                let place = match location.location {
+                    WeatherLocation::Place(ref query) => geocoded.map(|place| {
+                        debug!(
+                            "WeatherService: '{}' is {} ({:.2}, {:.2})",
+                            query, place.name, place.latitude, place.longitude
+                        );
+                        place.name
+                    }),
+                    WeatherLocation::Coordinates {
+                        latitude,
+                        longitude,
+                    } => Some(format!("{:.2}, {:.2}", latitude, longitude)),
+                    WeatherLocation::Auto => Some("Current location".to_string()),
+                };
+                self.update_location(index, |weather| {
+                    weather.place = place;
+                    weather.forecast = Some(forecast);
+                    weather.error = None;
+                    weather.updated = Some(SystemTime::now());
+                });
+            }
+            Err(e) => {
+                let unchanged = self
+                    .snapshot
+                    .borrow()
+                    .locations
+                    .get(index)
+                    .is_some_and(|weather| weather.error.as_deref() == Some(e.as_str()));
+                if !unchanged {
+                    warn!("WeatherService: {:?}: {}", location.location, e);
+                }
+                // Keep the last forecast on transient errors
+                self.update_location(index, |weather| weather.error = Some(e));
+            }
+        }
+    }
+
+    fn update_location(&self, index: usize, f: impl FnOnce(&mut LocationWeather)) {
+        let snapshot = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let Some(weather) = snapshot.locations.get_mut(index) else {
+                return;
+            };
+            f(weather);
+            weather.ready = true;
+            snapshot.clone()
+        };
+        self.callbacks.notify(&snapshot);
+    }
+
+    fn stop_timers(&self) {
+        for tracked in self.tracked.borrow_mut().iter_mut() {
+            if let Some(source_id) = tracked.timer_source.take() {
+                source_id.remove();
+            }
+        }
+    }
+
+    /// Follow the GeoClue location, for `WeatherLocation::Auto`.
+    fn watch_geoclue(this: &Rc<Self>) {
+        let this_weak = Rc::downgrade(this);
+        // Owned by the service, not by the bar that first configured it
+        EventBus::global().unscoped(|| {
+            GeoClueService::global().connect(move |snapshot: &GeoClueSnapshot| {
+                let Some(this) = this_weak.upgrade() else {
+                    return;
+                };
+                let previous = this.geoclue_location.replace(snapshot.location);
+                // Refetch on the first fix; later updates wait for the timers
+                if previous.is_none() && snapshot.location.is_some() {
+                    let auto: Vec<usize> = this
+                        .settings
+                        .borrow()
+                        .iter()
+                        .flat_map(|settings| settings.locations.iter().enumerate())
+                        .filter(|(_, location)| location.location == WeatherLocation::Auto)
+                        .map(|(index, _)| index)
+                        .collect();
+                    for index in auto {
+                        this.refresh_location(index);
+                    }
+                }
+            });
+        });
+    }
+}
+
+impl Drop for WeatherService {
+    fn drop(&mut self) {
+        self.stop_timers();
+    }
+}
+
+/// Send a GET request and return the body.
+fn get(url: &str) -> Result<String, String> {
+    let response = minreq::get(url)
+        .with_header("User-Agent", "vibepanel")
+        .with_timeout(REQUEST_TIMEOUT_SECS)
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+    match response.status_code {
+        200..=299 => {}
+        429 => return Err("Rate limited".to_string()),
+        code => return Err(format!("HTTP {}", code)),
+    }
+    response
+        .as_str()
+        .map(str::to_string)
+        .map_err(|e| format!("Unexpected response: {}", e))
+}
+
+// Open-Meteo
+
+/// Open-Meteo forecast and geocoding APIs.
+struct OpenMeteo;
+
+const OPEN_METEO_FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const OPEN_METEO_GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+
+impl WeatherProvider for OpenMeteo {
+    fn geocode(&self, query: &str) -> Result<Place, String> {
+        let url = format!(
+            "{}?name={}&count=1&format=json",
+            OPEN_METEO_GEOCODING_URL,
+            encode_component(query)
+        );
+        let response: OpenMeteoGeocoding =
+            serde_json::from_str(&get(&url)?).map_err(|e| format!("Unexpected response: {}", e))?;
+        let result = response
+            .results
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Location '{}' not found", query))?;
+        let name = match result.country {
+            Some(country) => format!("{}, {}", result.name, country),
+            None => result.name,
+        };
+        Ok(Place {
+            name,
+            latitude: result.latitude,
+            longitude: result.longitude,
+        })
+    }
+
+    fn forecast(&self, latitude: f64, longitude: f64, units: Units) -> Result<Forecast, String> {
+        let mut url = format!(
+            "{}?latitude={:.4}&longitude={:.4}\
+             &current=temperature_2m,apparent_temperature,relative_humidity_2m,weather_code,is_day,wind_speed_10m\
+             &hourly=temperature_2m,weather_code,precipitation_probability,is_day\
+             &daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_probability_max\
+             &timezone=auto&timeformat=unixtime&forecast_days={}",
+            OPEN_METEO_FORECAST_URL, latitude, longitude, DAILY_COUNT
+        );
+        if units == Units::Imperial {
+            url.push_str("&temperature_unit=fahrenheit&wind_speed_unit=mph");
+        }
+        parse_open_meteo(&get(&url)?)
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoGeocoding {
+    #[serde(default)]
+    results: Vec<OpenMeteoPlace>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoPlace {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    country: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoResponse {
+    #[serde(default)]
+    utc_offset_seconds: i64,
+    current: OpenMeteoCurrent,
+    hourly: OpenMeteoHourly,
+    daily: OpenMeteoDaily,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoCurrent {
+    time: i64,
+    temperature_2m: f64,
+    apparent_temperature: Option<f64>,
+    relative_humidity_2m: Option<f64>,
+    weather_code: Option<u8>,
+    is_day: Option<u8>,
+    wind_speed_10m: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<i64>,
+    temperature_2m: Vec<Option<f64>>,
+    weather_code: Vec<Option<u8>>,
+    precipitation_probability: Vec<Option<f64>>,
+    is_day: Vec<Option<u8>>,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoDaily {
+    time: Vec<i64>,
+    weather_code: Vec<Option<u8>>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_probability_max: Vec<Option<f64>>,
+}
+
+/// Parse an Open-Meteo forecast response (requested with
+/// `timeformat=unixtime`).
+fn parse_open_meteo(body: &str) -> Result<Forecast, String> {
+    let response: OpenMeteoResponse =
+        serde_json::from_str(body).map_err(|e| format!("Unexpected response: {}", e))?;
+    let offset = response.utc_offset_seconds;
+    let local_time =
+        |unix: i64| DateTime::from_timestamp(unix + offset, 0).map(|time| time.naive_utc());
+    let percent = |value: Option<f64>| value.map(|v| v.round().clamp(0.0, 100.0) as u8);
+
+    let current = &response.current;
+    let current_weather = CurrentWeather {
+        temperature: current.temperature_2m,
+        apparent_temperature: current.apparent_temperature,
+        humidity: percent(current.relative_humidity_2m),
+        wind_speed: current.wind_speed_10m,
+        condition: Condition::from_wmo(current.weather_code.unwrap_or(0)),
+        is_day: current.is_day != Some(0),
+    };
+
+    // Start at the current hour
+    let hourly_data = &response.hourly;
+    let hourly = hourly_data
+        .time
+        .iter()
+        .enumerate()
+        .filter(|&(_, &time)| time + 3600 > current.time)
+        .filter_map(|(i, &time)| {
+            Some(HourlyWeather {
+                time: local_time(time)?,
+                temperature: (*hourly_data.temperature_2m.get(i)?)?,
+                condition: Condition::from_wmo(hourly_data.weather_code.get(i).copied()??),
+                is_day: hourly_data.is_day.get(i).copied().flatten() != Some(0),
+                precipitation_probability: percent(
+                    hourly_data
+                        .precipitation_probability
+                        .get(i)
+                        .copied()
+                        .flatten(),
+                ),
+            })
+        })
+        .take(HOURLY_COUNT)
+        .collect();
+
+    let daily_data = &response.daily;
+    let daily = daily_data
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &time)| {
+            Some(DailyWeather {
+                date: local_time(time)?.date(),
+                min: (*daily_data.temperature_2m_min.get(i)?)?,
+                max: (*daily_data.temperature_2m_max.get(i)?)?,
+                condition: Condition::from_wmo(daily_data.weather_code.get(i).copied()??),
+                precipitation_probability: percent(
+                    daily_data
+                        .precipitation_probability_max
+                        .get(i)
+                        .copied()
+                        .flatten(),
+                ),
+            })
+        })
+        .take(DAILY_COUNT)
+        .collect();
+
+    Ok(Forecast {
+        current: current_weather,
+        hourly,
+        daily,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "utc_offset_seconds": 7200,
+        "current": {
+            "time": 1760608800, "temperature_2m": 12.4, "apparent_temperature": 10.1,
+            "relative_humidity_2m": 81, "weather_code": 3, "is_day": 1, "wind_speed_10m": 14.2
+        },
+        "hourly": {
+            "time": [1760601600, 1760605200, 1760608800, 1760612400],
+            "temperature_2m": [11.0, 11.8, 12.4, null],
+            "weather_code": [2, 3, 3, 61],
+            "precipitation_probability": [0, 5, 10, 60],
+            "is_day": [1, 1, 1, 1]
+        },
+        "daily": {
+            "time": [1760565600, 1760652000],
+            "weather_code": [61, 0],
+            "temperature_2m_max": [13.9, 15.2],
+            "temperature_2m_min": [7.5, 6.1],
+            "precipitation_probability_max": [80, null]
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_open_meteo() {
+        let forecast = parse_open_meteo(RESPONSE).unwrap();
+
+        assert_eq!(forecast.current.temperature, 12.4);
+        assert_eq!(forecast.current.humidity, Some(81));
+        assert_eq!(forecast.current.condition, Condition::Cloudy);
+        assert!(forecast.current.is_day);
+
+        // Past hours are skipped, hours with missing values dropped
+        assert_eq!(forecast.hourly.len(), 1);
+        assert_eq!(
+            forecast.hourly[0].time.format("%Y-%m-%d %H:%M").to_string(),
+            "2025-10-16 12:00"
+        );
+        assert_eq!(forecast.hourly[0].precipitation_probability, Some(10));
+
+        // Daily times are local midnight
+        assert_eq!(forecast.daily.len(), 2);
+        assert_eq!(
+            forecast.daily[0].date,
+            NaiveDate::from_ymd_opt(2025, 10, 16).unwrap()
+        );
+        assert_eq!(forecast.daily[0].condition, Condition::Rain);
+        assert_eq!(forecast.daily[0].precipitation_probability, Some(80));
+        assert_eq!(forecast.daily[1].precipitation_probability, None);
+
+        assert!(parse_open_meteo("{}").is_err());
+    }
+
+    #[test]
+    fn test_condition_from_wmo() {
+        assert_eq!(Condition::from_wmo(0), Condition::Clear);
+        assert_eq!(Condition::from_wmo(2), Condition::PartlyCloudy);
+        assert_eq!(Condition::from_wmo(48), Condition::Fog);
+        assert_eq!(Condition::from_wmo(66), Condition::FreezingRain);
+        assert_eq!(Condition::from_wmo(81), Condition::Showers);
+        assert_eq!(Condition::from_wmo(86), Condition::Snow);
+        assert_eq!(Condition::from_wmo(99), Condition::Thunderstorm);
+        assert_eq!(Condition::Clear.icon(false), "weather-clear-night-symbolic");
+    }
+}
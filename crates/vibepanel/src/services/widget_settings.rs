@@ -0,0 +1,162 @@
+//! Settings registered by the widgets sharing a service.
+//!
+//! A service like git or weather is a singleton, but every widget instance
+//! (one per monitor, or several with different options) brings its own
+//! settings. `WidgetSettings<T>` keeps one entry per widget until the widget
+//! releases it, and merges them into the settings the service runs with.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! impl MyService {
+//!     pub fn configure(self: &Rc<Self>, settings: MySettings) -> SettingsId {
+//!         let id = self.registered.add(settings);
+//!         self.apply_settings();
+//!         id
+//!     }
+//!
+//!     pub fn release(self: &Rc<Self>, id: SettingsId) {
+//!         if self.registered.remove(id) {
+//!             self.apply_settings();
+//!         }
+//!     }
+//!
+//!     fn apply_settings(self: &Rc<Self>) {
+//!         let settings = self.registered.merged();
+//!         if *self.settings.borrow() == settings {
+//!             return;
+//!         }
+//!         // (re)start with `settings`, or stop when `None`
+//!     }
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+
+/// Identifier for one widget's registered settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SettingsId(u64);
+
+/// Settings that can be combined across widgets.
+pub trait MergeSettings: Clone {
+    /// Fold in `other`, registered after `self`.
+    fn merge(&mut self, other: &Self);
+}
+
+/// Check intervals (seconds) merge to the shortest one.
+impl MergeSettings for u64 {
+    fn merge(&mut self, other: &Self) {
+        *self = (*self).min(*other);
+    }
+}
+
+/// Settings registered by each widget using a service.
+pub struct WidgetSettings<T> {
+    entries: RefCell<Vec<(SettingsId, T)>>,
+    next_id: Cell<u64>,
+}
+
+impl<T: MergeSettings> WidgetSettings<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: RefCell::new(Vec::new()),
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Register a widget's settings.
+    pub fn add(&self, settings: T) -> SettingsId {
+        let id = SettingsId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.entries.borrow_mut().push((id, settings));
+        id
+    }
+
+    /// Remove a widget's settings. Returns `false` if they were already gone.
+    pub fn remove(&self, id: SettingsId) -> bool {
+        let mut entries = self.entries.borrow_mut();
+        let len = entries.len();
+        entries.retain(|(entry_id, _)| *entry_id != id);
+        entries.len() != len
+    }
+
+    /// All registered settings merged in registration order; `None` when no
+    /// widget is registered.
+    pub fn merged(&self) -> Option<T> {
+        let entries = self.entries.borrow();
+        let mut iter = entries.iter().map(|(_, settings)| settings);
+        let mut merged = iter.next()?.clone();
+        for settings in iter {
+            merged.merge(settings);
+        }
+        Some(merged)
+    }
+}
+
+impl<T: MergeSettings> Default for WidgetSettings<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Append the items of `other` missing from `items`, keeping their order.
+pub fn merge_unique<T: Clone + PartialEq>(items: &mut Vec<T>, other: &[T]) {
+    for item in other {
+        if !items.contains(item) {
+            items.push(item.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Settings {
+        repos: Vec<&'static str>,
+        interval: u64,
+    }
+
+    impl MergeSettings for Settings {
+        fn merge(&mut self, other: &Self) {
+            merge_unique(&mut self.repos, &other.repos);
+            self.interval = self.interval.min(other.interval);
+        }
+    }
+
+    #[test]
+    fn test_merged_combines_registrations() {
+        let registered = WidgetSettings::new();
+        assert_eq!(registered.merged(), None);
+
+        let first = registered.add(Settings {
+            repos: vec!["a", "b"],
+            interval: 60,
+        });
+        let second = registered.add(Settings {
+            repos: vec!["b", "c"],
+            interval: 30,
+        });
+        assert_eq!(
+            registered.merged(),
+            Some(Settings {
+                repos: vec!["a", "b", "c"],
+                interval: 30,
+            })
+        );
+
+        assert!(registered.remove(second));
+        assert!(!registered.remove(second));
+        assert_eq!(
+            registered.merged(),
+            Some(Settings {
+                repos: vec!["a", "b"],
+                interval: 60,
+            })
+        );
+
+        assert!(registered.remove(first));
+        assert_eq!(registered.merged(), None);
+    }
+}
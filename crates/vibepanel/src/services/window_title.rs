@@ -12,6 +12,12 @@ use tracing::debug;
 
 use super::callbacks::Callbacks;
 use super::compositor::{CompositorManager, WindowInfo};
+use super::event_bus::EventBus;
+
+// Thread-local singleton storage for WindowTitleService
+thread_local! {
+    static WINDOW_TITLE_INSTANCE: RefCell<Option<Rc<WindowTitleService>>> = const { RefCell::new(None) };
+}
 
 /// Snapshot of window title service state for callbacks.
 #[derive(Debug, Clone, Default)]
@@ -22,6 +28,8 @@ pub struct WindowTitleSnapshot {
     pub app_id: String,
     /// Output/monitor name (if available).
     pub output: Option<String>,
+    /// Whether the focused window is fullscreen.
+    pub fullscreen: bool,
 }
 
 impl From<WindowInfo> for WindowTitleSnapshot {
@@ -30,6 +38,7 @@ impl From<WindowInfo> for WindowTitleSnapshot {
             title: info.title,
             app_id: info.app_id,
             output: info.output,
+            fullscreen: info.fullscreen,
         }
     }
 }
@@ -40,6 +49,7 @@ impl From<&WindowInfo> for WindowTitleSnapshot {
             title: info.title.clone(),
             app_id: info.app_id.clone(),
             output: info.output.clone(),
+            fullscreen: info.fullscreen,
         }
     }
 }
@@ -83,11 +93,17 @@ impl WindowTitleService {
 
     /// Get the global WindowTitleService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<WindowTitleService> = WindowTitleService::new();
-        }
+        WINDOW_TITLE_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(WindowTitleService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        WINDOW_TITLE_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked when window title changes.
@@ -110,6 +126,11 @@ impl WindowTitleService {
         self.callbacks.register(move |snapshot| cb_clone(snapshot));
     }
 
+    /// Return the current focused window snapshot.
+    pub fn snapshot(&self) -> WindowTitleSnapshot {
+        self.current.borrow().clone()
+    }
+
     fn handle_update(&self, window_info: &WindowInfo) {
         // Update stored state
         let snapshot: WindowTitleSnapshot = window_info.into();
@@ -123,10 +144,13 @@ impl WindowTitleService {
     fn register_with_manager(this: &Rc<Self>, manager: &Rc<CompositorManager>) {
         // Create callback that handles updates
         let service_weak = Rc::downgrade(this);
-        manager.register_window_callback(move |window_info| {
-            if let Some(service) = service_weak.upgrade() {
-                service.handle_update(window_info);
-            }
+        // Owned by the service, not by the bar that created it
+        EventBus::global().unscoped(|| {
+            manager.register_window_callback(move |window_info| {
+                if let Some(service) = service_weak.upgrade() {
+                    service.handle_update(window_info);
+                }
+            });
         });
     }
 }
@@ -0,0 +1,235 @@
+//! WorkerPool - shared background threads for blocking I/O.
+//!
+//! Services that shell out, read files or sample the system hand that work
+//! to this pool instead of running it on the GTK main loop (which stalls the
+//! UI) or spawning a fresh thread per request.
+//!
+//! - `spawn(work, deliver)` runs `work` on a worker thread and calls
+//!   `deliver` with its result back on the main loop
+//! - `deliver` never leaves the main thread, so it may capture `Rc`s and
+//!   GTK objects; only `work` and its result need to be `Send`
+//! - A panicking job is logged and its `deliver` gets `Err(JobPanicked)`,
+//!   so callers can clear their in-progress state
+//!
+//! Jobs should be short-lived. Work that can take minutes (package manager
+//! checks) goes through `spawn_long`, which gives it a thread of its own
+//! with the same delivery; work that streams progress keeps its own thread.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use gtk4::glib;
+use tracing::{debug, error};
+
+/// Number of worker threads.
+const WORKER_THREADS: usize = 4;
+
+/// Work sent to a worker thread.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Result of a job, type-erased for the trip back to the main loop.
+type JobResult = Box<dyn Any + Send>;
+
+/// Main-thread continuation for a job; `None` if the job panicked.
+type Delivery = Box<dyn FnOnce(Option<JobResult>)>;
+
+/// A job that panicked instead of returning a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobPanicked;
+
+impl fmt::Display for JobPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("background job panicked")
+    }
+}
+
+/// Shared, process-wide worker pool.
+pub struct WorkerPool {
+    sender: Sender<Job>,
+    /// Continuations waiting for their job's result, by job ID.
+    pending: RefCell<HashMap<u64, Delivery>>,
+    next_id: Cell<u64>,
+}
+
+impl WorkerPool {
+    fn new() -> Rc<Self> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..WORKER_THREADS {
+            let receiver = Arc::clone(&receiver);
+            let spawned = std::thread::Builder::new()
+                .name(format!("vibepanel-worker-{}", index))
+                .spawn(move || worker_loop(&receiver));
+            if let Err(e) = spawned {
+                error!("WorkerPool: failed to start worker thread: {}", e);
+            }
+        }
+        debug!("WorkerPool: started {} worker threads", WORKER_THREADS);
+
+        Rc::new(Self {
+            sender,
+            pending: RefCell::new(HashMap::new()),
+            next_id: Cell::new(1),
+        })
+    }
+
+    /// Get the global WorkerPool singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<WorkerPool> = WorkerPool::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Run `work` on a worker thread and pass its result to `deliver` on
+    /// the main loop.
+    ///
+    /// `deliver` always runs, with `Err(JobPanicked)` if `work` panicked.
+    pub fn spawn<T, W, D>(&self, work: W, deliver: D)
+    where
+        T: Send + 'static,
+        W: FnOnce() -> T + Send + 'static,
+        D: FnOnce(Result<T, JobPanicked>) + 'static,
+    {
+        let (id, job) = self.prepare(work, deliver);
+        if self.sender.send(job).is_err() {
+            error!("WorkerPool: all worker threads have exited");
+            self.pending.borrow_mut().remove(&id);
+        }
+    }
+
+    /// Like [`spawn`](Self::spawn), but run `work` on a thread of its own.
+    ///
+    /// For jobs that can take minutes (package manager checks), which would
+    /// otherwise hold a worker and starve the short jobs behind them.
+    pub fn spawn_long<T, W, D>(&self, name: &str, work: W, deliver: D)
+    where
+        T: Send + 'static,
+        W: FnOnce() -> T + Send + 'static,
+        D: FnOnce(Result<T, JobPanicked>) + 'static,
+    {
+        let (id, job) = self.prepare(work, deliver);
+        let spawned = std::thread::Builder::new()
+            .name(format!("vibepanel-{}", name))
+            .spawn(job);
+        if let Err(e) = spawned {
+            error!("WorkerPool: failed to start thread for {}: {}", name, e);
+            self.pending.borrow_mut().remove(&id);
+        }
+    }
+
+    /// Register `deliver` and wrap `work` into a job that hands its result
+    /// back to the main loop.
+    fn prepare<T, W, D>(&self, work: W, deliver: D) -> (u64, Job)
+    where
+        T: Send + 'static,
+        W: FnOnce() -> T + Send + 'static,
+        D: FnOnce(Result<T, JobPanicked>) + 'static,
+    {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        self.pending.borrow_mut().insert(
+            id,
+            Box::new(move |result: Option<JobResult>| match result {
+                Some(result) => match result.downcast::<T>() {
+                    Ok(result) => deliver(Ok(*result)),
+                    Err(_) => error!("WorkerPool: job {} returned an unexpected type", id),
+                },
+                None => deliver(Err(JobPanicked)),
+            }),
+        );
+
+        let job: Job = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(work))
+                .map(|result| Box::new(result) as JobResult);
+            glib::idle_add_once(move || WorkerPool::global().complete(id, result.ok()));
+        });
+        (id, job)
+    }
+
+    /// Number of jobs submitted but not yet delivered.
+    #[allow(dead_code)]
+    pub fn pending_count(&self) -> usize {
+        self.pending.borrow().len()
+    }
+
+    /// Hand a finished job's result to its continuation.
+    fn complete(&self, id: u64, result: Option<JobResult>) {
+        // Release the borrow before delivering; continuations may spawn jobs
+        let delivery = self.pending.borrow_mut().remove(&id);
+        let Some(deliver) = delivery else {
+            debug!("WorkerPool: no continuation for job {}", id);
+            return;
+        };
+        if result.is_none() {
+            error!("WorkerPool: job {} panicked", id);
+        }
+        deliver(result);
+    }
+}
+
+/// Run jobs until the pool's sender is dropped.
+fn worker_loop(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // Hold the lock only while waiting for the next job
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run the default main context until `done` returns true.
+    fn run_until(done: impl Fn() -> bool) {
+        let context = glib::MainContext::default();
+        while !done() {
+            context.iteration(true);
+        }
+    }
+
+    #[test]
+    fn test_deliver_runs_for_every_job() {
+        let pool = WorkerPool::global();
+        let results = Rc::new(RefCell::new(Vec::new()));
+
+        let results_clone = results.clone();
+        pool.spawn(
+            || 2 + 2,
+            move |result| results_clone.borrow_mut().push(result),
+        );
+        let results_clone = results.clone();
+        pool.spawn(
+            || -> i32 { panic!("job failed") },
+            move |result| results_clone.borrow_mut().push(result),
+        );
+        let results_clone = results.clone();
+        pool.spawn_long(
+            "test-long-job",
+            || 7,
+            move |result| results_clone.borrow_mut().push(result),
+        );
+
+        run_until(|| results.borrow().len() == 3);
+        let mut results = results.borrow().clone();
+        results.sort_by_key(|result| (result.is_err(), *result.as_ref().unwrap_or(&0)));
+        assert_eq!(results, vec![Ok(4), Ok(7), Err(JobPanicked)]);
+        assert_eq!(pool.pending_count(), 0);
+    }
+}
@@ -12,7 +12,13 @@ use std::rc::Rc;
 use tracing::debug;
 
 use super::callbacks::Callbacks;
-use super::compositor::{CompositorManager, WorkspaceMeta, WorkspaceSnapshot};
+use super::compositor::{CompositorManager, WorkspaceGrid, WorkspaceMeta, WorkspaceSnapshot};
+use super::event_bus::EventBus;
+
+// Thread-local singleton storage for WorkspaceService
+thread_local! {
+    static WORKSPACE_INSTANCE: RefCell<Option<Rc<WorkspaceService>>> = const { RefCell::new(None) };
+}
 
 /// Enriched workspace object for widget consumption.
 ///
@@ -127,6 +133,8 @@ pub struct WorkspaceServiceSnapshot {
     /// Per-output workspace state for multi-monitor setups.
     /// Key is the output/monitor connector name (e.g., "eDP-1", "DP-1").
     pub per_output: HashMap<String, PerOutputWorkspaces>,
+    /// Workspace grid size, for compositors with a grid layout (Wayfire).
+    pub grid: Option<WorkspaceGrid>,
 }
 
 /// Shared, process-wide workspace service.
@@ -172,11 +180,17 @@ impl WorkspaceService {
 
     /// Get the global WorkspaceService singleton.
     pub fn global() -> Rc<Self> {
-        thread_local! {
-            static INSTANCE: Rc<WorkspaceService> = WorkspaceService::new();
-        }
+        WORKSPACE_INSTANCE.with(|cell| {
+            cell.borrow_mut()
+                .get_or_insert_with(WorkspaceService::new)
+                .clone()
+        })
+    }
 
-        INSTANCE.with(|s| s.clone())
+    /// Drop the singleton, so the next `global()` builds a fresh service.
+    #[cfg(test)]
+    pub fn reset_for_test() {
+        WORKSPACE_INSTANCE.with(RefCell::take);
     }
 
     /// Register a callback to be invoked when workspace state changes.
@@ -199,6 +213,22 @@ impl WorkspaceService {
         self.manager.switch_workspace(workspace_id);
     }
 
+    /// Request the compositor to show or hide a workspace alongside the
+    /// visible ones (switches on compositors without multi-tag views).
+    pub fn toggle_workspace(&self, workspace_id: i32) {
+        self.manager.toggle_workspace(workspace_id);
+    }
+
+    /// Replace the workspaces and their state with canned ones and notify
+    /// listeners.
+    #[cfg(test)]
+    pub fn publish_for_test(&self, workspaces: Vec<WorkspaceMeta>, snapshot: WorkspaceSnapshot) {
+        *self.snapshot.borrow_mut() = snapshot;
+        *self.workspaces.borrow_mut() = workspaces;
+        *self.ready.borrow_mut() = true;
+        self.callbacks.notify(&self.build_snapshot());
+    }
+
     fn handle_update(&self, snapshot: WorkspaceSnapshot) {
         // Update stored snapshot
         *self.snapshot.borrow_mut() = snapshot;
@@ -215,10 +245,13 @@ impl WorkspaceService {
     fn register_with_manager(this: &Rc<Self>) {
         // Create callback that handles updates
         let service_weak = Rc::downgrade(this);
-        this.manager.register_workspace_callback(move |snapshot| {
-            if let Some(service) = service_weak.upgrade() {
-                service.handle_update(snapshot.clone());
-            }
+        // Owned by the service, not by the bar that created it
+        EventBus::global().unscoped(|| {
+            this.manager.register_workspace_callback(move |snapshot| {
+                if let Some(service) = service_weak.upgrade() {
+                    service.handle_update(snapshot.clone());
+                }
+            });
         });
     }
 
@@ -263,6 +296,7 @@ impl WorkspaceService {
             window_counts: snapshot.window_counts.clone(),
             workspaces,
             per_output,
+            grid: snapshot.grid,
         }
     }
 }
@@ -0,0 +1,128 @@
+//! WorkspaceAccentService - accent color that follows the focused workspace.
+//!
+//! `[workspace.colors]` maps workspaces (by name or number) to accent colors.
+//! While a workspace with a color is focused, a CSS provider above the theme
+//! CSS overrides `--color-accent-primary` and `--color-accent-subtle`, so the
+//! bar, workspace pills and popovers pick it up live on workspace events.
+//! Other workspaces fall back to `theme.accent`.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use tracing::debug;
+use vibepanel_core::ThemePalette;
+use vibepanel_core::config::WorkspaceConfig;
+
+use super::event_bus::EventBus;
+use super::workspace::{WorkspaceService, WorkspaceServiceSnapshot};
+
+/// Above the theme CSS (USER), below the user's style.css (USER + 100).
+const ACCENT_CSS_PRIORITY: u32 = gtk4::STYLE_PROVIDER_PRIORITY_USER + 1;
+
+/// Shared, process-wide workspace accent service.
+pub struct WorkspaceAccentService {
+    /// Workspace colors from the config.
+    config: RefCell<WorkspaceConfig>,
+    /// Focused workspaces (id, name) from the last workspace update.
+    active: RefCell<Vec<(i32, String)>>,
+    /// Accent color currently applied (`None` = theme accent).
+    applied: RefCell<Option<String>>,
+    /// Provider holding the override, added to the display on first use.
+    provider: RefCell<Option<gtk4::CssProvider>>,
+    /// Whether workspace updates are subscribed to.
+    subscribed: Cell<bool>,
+}
+
+impl WorkspaceAccentService {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            config: RefCell::new(WorkspaceConfig::default()),
+            active: RefCell::new(Vec::new()),
+            applied: RefCell::new(None),
+            provider: RefCell::new(None),
+            subscribed: Cell::new(false),
+        })
+    }
+
+    /// Get the global WorkspaceAccentService singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<WorkspaceAccentService> = WorkspaceAccentService::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Apply (or re-apply after a config reload) the workspace colors.
+    ///
+    /// Workspace events are only subscribed to once colors are configured.
+    pub fn configure(self: &Rc<Self>, config: &WorkspaceConfig) {
+        *self.config.borrow_mut() = config.clone();
+
+        if !config.colors.is_empty() && !self.subscribed.replace(true) {
+            // Subscribing delivers the current workspaces right away; the
+            // subscription is owned by the service, not by any bar
+            let this_weak = Rc::downgrade(self);
+            EventBus::global().unscoped(|| {
+                WorkspaceService::global().connect(move |snapshot: &WorkspaceServiceSnapshot| {
+                    if let Some(this) = this_weak.upgrade() {
+                        this.on_workspaces_changed(snapshot);
+                    }
+                });
+            });
+        }
+
+        self.apply();
+    }
+
+    fn on_workspaces_changed(&self, snapshot: &WorkspaceServiceSnapshot) {
+        let mut active: Vec<(i32, String)> = snapshot
+            .workspaces
+            .iter()
+            .filter(|ws| ws.active)
+            .map(|ws| (ws.id, ws.name.clone()))
+            .collect();
+        active.sort();
+        *self.active.borrow_mut() = active;
+        self.apply();
+    }
+
+    /// Load the focused workspace's color, or clear the override.
+    fn apply(&self) {
+        let color = {
+            let config = self.config.borrow();
+            self.active
+                .borrow()
+                .iter()
+                .find_map(|(id, name)| config.color_for(*id, name))
+                .map(str::to_string)
+        };
+        if *self.applied.borrow() == color {
+            return;
+        }
+
+        let Some(provider) = self.provider() else {
+            return;
+        };
+        let css = color
+            .as_deref()
+            .map(ThemePalette::accent_override_css)
+            .unwrap_or_default();
+        provider.load_from_string(&css);
+        debug!("Workspace accent: {:?}", color);
+        *self.applied.borrow_mut() = color;
+    }
+
+    /// The override provider, added to the default display on first use.
+    fn provider(&self) -> Option<gtk4::CssProvider> {
+        if let Some(provider) = self.provider.borrow().as_ref() {
+            return Some(provider.clone());
+        }
+
+        let display = gtk4::gdk::Display::default()?;
+        let provider = gtk4::CssProvider::new();
+        gtk4::style_context_add_provider_for_display(&display, &provider, ACCENT_CSS_PRIORITY);
+        *self.provider.borrow_mut() = Some(provider.clone());
+        Some(provider)
+    }
+}
@@ -0,0 +1,304 @@
+//! Moving a personalized bar between machines (`export-setup` / `import-setup`).
+//!
+//! A setup bundle is a gzipped tarball holding:
+//! - `config.toml`, the config file in use
+//! - `style.css`, from next to the config file, if there is one
+//! - `state.json`, the persisted widget state, if there is one
+//! - `assets/`, images the theme points at (bar and widget background
+//!   images, `theme.wallpaper`, icon override files)
+//! - `manifest.json`, the bundle version and where each asset belongs
+//!
+//! Asset paths under the home directory are stored as `~/...`, so they land
+//! in the importing user's home. Archiving is left to the system `tar`.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use vibepanel_core::Config;
+use vibepanel_core::state::default_state_path;
+
+use crate::widgets::expand_home;
+
+/// Bundle format version written to the manifest.
+const BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "config.toml";
+const STYLE_FILE: &str = "style.css";
+const STATE_FILE: &str = "state.json";
+const ASSETS_DIR: &str = "assets";
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+/// A theme asset in the bundle.
+#[derive(Debug, Serialize, Deserialize)]
+struct Asset {
+    /// File in the bundle, e.g. "assets/0-bar.png".
+    file: String,
+    /// Where it's installed, as written in the config (`~` allowed).
+    path: String,
+}
+
+/// Write a setup bundle of the config at `config_path` (or the one found
+/// by the usual lookup) to `output`.
+///
+/// Returns the files that went into the bundle.
+pub fn export(config_path: Option<&Path>, output: &Path) -> Result<Vec<PathBuf>, String> {
+    let loaded = Config::find_and_load(config_path).map_err(|e| e.to_string())?;
+    let config_file = loaded
+        .source
+        .ok_or("no config file found (the built-in defaults need no export)")?;
+
+    let staging = Staging::new("export")?;
+    let mut included = Vec::new();
+    let mut include = |source: &Path, name: &str| -> Result<(), String> {
+        copy_file(source, &staging.path.join(name))?;
+        included.push(source.to_path_buf());
+        Ok(())
+    };
+
+    include(&config_file, CONFIG_FILE)?;
+    let style = config_file.with_file_name(STYLE_FILE);
+    if style.is_file() {
+        include(&style, STYLE_FILE)?;
+    }
+    let state = default_state_path();
+    if state.is_file() {
+        include(&state, STATE_FILE)?;
+    }
+
+    let mut manifest = Manifest {
+        version: BUNDLE_VERSION,
+        assets: Vec::new(),
+    };
+    for path in theme_assets(&loaded.config) {
+        let source = expand_home(&path);
+        if !source.is_file() {
+            warn!("Skipping missing theme asset {}", source.display());
+            continue;
+        }
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file = format!("{}/{}-{}", ASSETS_DIR, manifest.assets.len(), name);
+        include(&source, &file)?;
+        manifest.assets.push(Asset {
+            file,
+            path: portable_path(&path),
+        });
+    }
+
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(staging.path.join(MANIFEST_FILE), json)
+        .map_err(|e| format!("failed to write manifest: {}", e))?;
+
+    run_tar(&[
+        "-czf".as_ref(),
+        output.as_os_str(),
+        "-C".as_ref(),
+        staging.path.as_os_str(),
+        ".".as_ref(),
+    ])?;
+    Ok(included)
+}
+
+/// Install the setup bundle at `bundle`, writing the config to
+/// `config_path` (or the XDG config location).
+///
+/// Nothing is written if the bundled config doesn't load, or if any file
+/// would be replaced and `force` isn't set. Returns the files written.
+pub fn import(
+    bundle: &Path,
+    config_path: Option<&Path>,
+    force: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let staging = Staging::new("import")?;
+    run_tar(&[
+        "-xzf".as_ref(),
+        bundle.as_os_str(),
+        "-C".as_ref(),
+        staging.path.as_os_str(),
+    ])?;
+
+    let manifest: Manifest = fs::read_to_string(staging.path.join(MANIFEST_FILE))
+        .map_err(|e| format!("not a vibepanel setup bundle: {}", e))
+        .and_then(|json| {
+            serde_json::from_str(&json).map_err(|e| format!("invalid manifest: {}", e))
+        })?;
+    if manifest.version != BUNDLE_VERSION {
+        return Err(format!(
+            "unsupported bundle version {} (expected {})",
+            manifest.version, BUNDLE_VERSION
+        ));
+    }
+
+    let bundled_config = staging.path.join(CONFIG_FILE);
+    Config::load(&bundled_config)
+        .map_err(|e| e.to_string())
+        .and_then(|config| config.validate().map_err(|e| e.to_string()))
+        .map_err(|e| format!("bundled config is invalid: {}", e))?;
+
+    let config_target = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => Config::config_search_paths()
+            .into_iter()
+            .next()
+            .ok_or("no config location")?,
+    };
+    // (file in the bundle, destination)
+    let mut files = vec![
+        (bundled_config, config_target.clone()),
+        (
+            staging.path.join(STYLE_FILE),
+            config_target.with_file_name(STYLE_FILE),
+        ),
+        (staging.path.join(STATE_FILE), default_state_path()),
+    ];
+    for asset in &manifest.assets {
+        if !is_bundle_relative(&asset.file) {
+            return Err(format!("invalid asset path '{}'", asset.file));
+        }
+        files.push((staging.path.join(&asset.file), expand_home(&asset.path)));
+    }
+    files.retain(|(source, _)| source.is_file());
+
+    let existing: Vec<String> = files
+        .iter()
+        .filter(|(_, target)| target.exists())
+        .map(|(_, target)| target.display().to_string())
+        .collect();
+    if !existing.is_empty() && !force {
+        return Err(format!(
+            "would overwrite {} (use --force to replace)",
+            existing.join(", ")
+        ));
+    }
+
+    let mut written = Vec::new();
+    for (source, target) in files {
+        copy_file(&source, &target)?;
+        written.push(target);
+    }
+    Ok(written)
+}
+
+/// Image paths the theme refers to, as written in the config.
+fn theme_assets(config: &Config) -> Vec<String> {
+    let mut paths: Vec<String> = [
+        &config.theme.bar_background,
+        &config.theme.widget_background,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|background| background.image.clone())
+    .chain(config.theme.wallpaper.clone())
+    .chain(config.theme.icons.overrides.values().cloned())
+    .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// `path` with the home directory written as `~`, so it resolves on another
+/// machine.
+fn portable_path(path: &str) -> String {
+    let Ok(home) = std::env::var("HOME") else {
+        return path.to_string();
+    };
+    match Path::new(path).strip_prefix(&home) {
+        Ok(rest) if !home.is_empty() => format!("~/{}", rest.display()),
+        _ => path.to_string(),
+    }
+}
+
+/// Whether `file` is a plain relative path inside the bundle.
+fn is_bundle_relative(file: &str) -> bool {
+    let path = Path::new(file);
+    path.starts_with(ASSETS_DIR)
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn copy_file(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::copy(source, target)
+        .map(|_| ())
+        .map_err(|e| format!("failed to copy {}: {}", source.display(), e))
+}
+
+fn run_tar(args: &[&std::ffi::OsStr]) -> Result<(), String> {
+    let output = Command::new("tar")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("failed to run tar: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "tar failed: {}",
+            stderr.lines().last().unwrap_or("").trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Temporary directory a bundle is assembled or unpacked in, removed on drop.
+struct Staging {
+    path: PathBuf,
+}
+
+impl Staging {
+    fn new(purpose: &str) -> Result<Self, String> {
+        let path =
+            std::env::temp_dir().join(format!("vibepanel-{}-{}", purpose, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_path() {
+        assert_eq!(portable_path("~/Pictures/bar.png"), "~/Pictures/bar.png");
+        assert_eq!(portable_path("/usr/share/bg.png"), "/usr/share/bg.png");
+        if let Ok(home) = std::env::var("HOME") {
+            assert_eq!(
+                portable_path(&format!("{}/walls/a.jpg", home)),
+                "~/walls/a.jpg"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_bundle_relative() {
+        assert!(is_bundle_relative("assets/0-bar.png"));
+        assert!(!is_bundle_relative("assets/../../.bashrc"));
+        assert!(!is_bundle_relative("/etc/passwd"));
+        assert!(!is_bundle_relative("config.toml"));
+    }
+}
@@ -27,6 +27,13 @@ pub mod class {
     /// Applied to shared island containers that hold multiple grouped widgets.
     pub const WIDGET_GROUP: &str = "widget-group";
 
+    /// Widget group whose members share one popover (`.shared-popover`).
+    /// The whole island is the hover and click target.
+    pub const SHARED_POPOVER: &str = "shared-popover";
+
+    /// Placeholder holding a widget's slot until it's built (`.widget-placeholder`).
+    pub const WIDGET_PLACEHOLDER: &str = "widget-placeholder";
+
     /// Widget content inner box (`.content`).
     pub const CONTENT: &str = "content";
 
@@ -36,12 +43,25 @@ pub mod class {
     /// Bar window class (`.bar-window`).
     pub const BAR_WINDOW: &str = "bar-window";
 
+    /// Bar window in text-only mode (`.text-mode`).
+    pub const BAR_TEXT_MODE: &str = "text-mode";
+
+    /// Bar window of a `--preview` instance (`.preview`).
+    pub const BAR_PREVIEW: &str = "preview";
+
+    /// "Preview" label at the start of a preview bar (`.bar-preview-label`).
+    pub const BAR_PREVIEW_LABEL: &str = "bar-preview-label";
+
     /// Bar shell class (`.bar-shell`).
     pub const BAR_SHELL: &str = "bar-shell";
 
     /// Bar shell inner class (`.bar-shell-inner`).
     pub const BAR_SHELL_INNER: &str = "bar-shell-inner";
 
+    /// Invisible strip along the screen edge that summons an on-demand bar
+    /// (`.bar-hot-edge`).
+    pub const BAR_HOT_EDGE: &str = "bar-hot-edge";
+
     /// Bar margin spacer (`.bar-margin-spacer`).
     pub const BAR_MARGIN_SPACER: &str = "bar-margin-spacer";
 
@@ -51,6 +71,24 @@ pub mod class {
     /// Sectioned bar widget CSS name (`sectioned-bar`).
     pub const SECTIONED_BAR: &str = "sectioned-bar";
 
+    /// Count or dot badge on a widget icon (`.widget-badge`).
+    pub const BADGE: &str = "widget-badge";
+
+    /// Badge shown as a dot without text (`.widget-badge-dot`).
+    pub const BADGE_DOT: &str = "widget-badge-dot";
+
+    /// Badge in the icon's top-right corner (`.widget-badge-top-right`).
+    pub const BADGE_TOP_RIGHT: &str = "widget-badge-top-right";
+
+    /// Badge in the icon's top-left corner (`.widget-badge-top-left`).
+    pub const BADGE_TOP_LEFT: &str = "widget-badge-top-left";
+
+    /// Badge in the icon's bottom-right corner (`.widget-badge-bottom-right`).
+    pub const BADGE_BOTTOM_RIGHT: &str = "widget-badge-bottom-right";
+
+    /// Badge in the icon's bottom-left corner (`.widget-badge-bottom-left`).
+    pub const BADGE_BOTTOM_LEFT: &str = "widget-badge-bottom-left";
+
     /// Click catcher overlay (`.vp-click-catcher`).
     pub const CLICK_CATCHER: &str = "vp-click-catcher";
 
@@ -202,6 +240,9 @@ pub mod state {
     /// Clickable element (`.clickable`).
     pub const CLICKABLE: &str = "clickable";
 
+    /// Widget highlighted in bar keyboard focus mode (`.keyboard-focus`).
+    pub const KEYBOARD_FOCUS: &str = "keyboard-focus";
+
     /// Occupied workspace state (`.occupied`).
     pub const OCCUPIED: &str = "occupied";
 
@@ -230,6 +271,15 @@ pub mod qs {
     /// Idle inhibitor toggle card (`.qs-idle-inhibitor`).
     pub const IDLE_INHIBITOR: &str = "qs-idle-inhibitor";
 
+    /// Proxy toggle card (`.qs-proxy`).
+    pub const PROXY: &str = "qs-proxy";
+
+    /// Touchpad toggle card (`.qs-touchpad`).
+    pub const TOUCHPAD: &str = "qs-touchpad";
+
+    /// Power profile toggle card (`.qs-power-profile`).
+    pub const POWER_PROFILE: &str = "qs-power-profile";
+
     // Slider row identifiers (for per-row CSS targeting)
     /// Audio output slider row (`.qs-audio-output`).
     pub const AUDIO_OUTPUT: &str = "qs-audio-output";
@@ -363,6 +413,19 @@ pub mod qs {
     /// Updates error row (`.qs-updates-error`).
     pub const UPDATES_ERROR: &str = "qs-updates-error";
 
+    /// Flatpak update controls container (`.qs-updates-flatpak`).
+    pub const UPDATES_FLATPAK: &str = "qs-updates-flatpak";
+
+    /// Flatpak update progress bar (`.qs-updates-flatpak-progress`).
+    pub const UPDATES_FLATPAK_PROGRESS: &str = "qs-updates-flatpak-progress";
+
+    // Power profile card
+    /// Power profile list (`.qs-power-profile-list`).
+    pub const POWER_PROFILE_LIST: &str = "qs-power-profile-list";
+
+    /// Power profile row (`.qs-power-profile-row`).
+    pub const POWER_PROFILE_ROW: &str = "qs-power-profile-row";
+
     // Wi-Fi card
     /// Wi-Fi network row (`.qs-wifi-row`).
     pub const WIFI_ROW: &str = "qs-wifi-row";
@@ -373,6 +436,12 @@ pub mod qs {
     /// Wi-Fi overlay signal icon (highlighted, actual bars) (`.qs-wifi-overlay`).
     pub const WIFI_OVERLAY: &str = "qs-wifi-overlay";
 
+    /// Wi-Fi share popover content (`.qs-wifi-share`).
+    pub const WIFI_SHARE: &str = "qs-wifi-share";
+
+    /// Wi-Fi share QR code (`.qs-wifi-share-code`).
+    pub const WIFI_SHARE_CODE: &str = "qs-wifi-share-code";
+
     /// Row menu content container (`.qs-row-menu-content`).
     pub const ROW_MENU_CONTENT: &str = "qs-row-menu-content";
 
@@ -418,6 +487,9 @@ pub mod qs {
 
     /// Power details container (`.qs-power-details`).
     pub const POWER_DETAILS: &str = "qs-power-details";
+
+    /// Inhibitor lock confirmation popover content (`.qs-power-inhibitors`).
+    pub const POWER_INHIBITORS: &str = "qs-power-inhibitors";
 }
 
 /// Widget-specific CSS classes.
@@ -453,6 +525,12 @@ pub mod widget {
     /// Workspace separator (`.workspace-separator`).
     pub const WORKSPACE_SEPARATOR: &str = "workspace-separator";
 
+    /// Grid of workspace indicators (`.workspace-grid`).
+    pub const WORKSPACE_GRID: &str = "workspace-grid";
+
+    /// Speaker badge on a workspace playing sound (`.workspace-audio`).
+    pub const WORKSPACE_AUDIO: &str = "workspace-audio";
+
     /// Active workspace (`.active`).
     pub const ACTIVE: &str = "active";
 
@@ -504,9 +582,6 @@ pub mod widget {
     /// Notification badge container (`.notification-badge`).
     pub const NOTIFICATION_BADGE: &str = "notification-badge";
 
-    /// Notification badge dot (`.notification-badge-dot`).
-    pub const NOTIFICATION_BADGE_DOT: &str = "notification-badge-dot";
-
     // Window title
     /// Window title widget (`.window-title`).
     pub const WINDOW_TITLE: &str = "window-title";
@@ -517,6 +592,25 @@ pub mod widget {
     /// Window title app icon (`.window-title-app-icon`).
     pub const WINDOW_TITLE_APP_ICON: &str = "window-title-app-icon";
 
+    // Taskbar
+    /// Taskbar widget (`.taskbar`).
+    pub const TASKBAR: &str = "taskbar";
+
+    /// Taskbar window button (`.taskbar-item`).
+    pub const TASKBAR_ITEM: &str = "taskbar-item";
+
+    /// Taskbar app icon (`.taskbar-item-icon`).
+    pub const TASKBAR_ITEM_ICON: &str = "taskbar-item-icon";
+
+    /// Taskbar window title (`.taskbar-item-label`).
+    pub const TASKBAR_ITEM_LABEL: &str = "taskbar-item-label";
+
+    /// Minimized window (`.taskbar-item-minimized`).
+    pub const TASKBAR_ITEM_MINIMIZED: &str = "taskbar-item-minimized";
+
+    /// Speaker badge on a window playing sound (`.taskbar-item-audio`).
+    pub const TASKBAR_ITEM_AUDIO: &str = "taskbar-item-audio";
+
     // Updates
     /// Updates widget (`.updates`).
     pub const UPDATES: &str = "updates";
@@ -533,6 +627,15 @@ pub mod widget {
     /// Updates checking state (`.updates-checking`).
     pub const UPDATES_CHECKING: &str = "updates-checking";
 
+    /// Reboot required (`.updates-reboot`).
+    pub const UPDATES_REBOOT: &str = "updates-reboot";
+
+    /// Update count badge on the icon (`.updates-badge`).
+    pub const UPDATES_BADGE: &str = "updates-badge";
+
+    /// Reboot required badge (`.updates-reboot-icon`).
+    pub const UPDATES_REBOOT_ICON: &str = "updates-reboot-icon";
+
     // Quick Settings bar widget
     /// Quick Settings bar widget (`.quick-settings`).
     pub const QUICK_SETTINGS: &str = "quick-settings";
@@ -562,6 +665,263 @@ pub mod widget {
 
     /// Memory high usage state (`.memory-high`).
     pub const MEMORY_HIGH: &str = "memory-high";
+
+    // Remote module
+    /// Remote module widget (`.remote`).
+    pub const REMOTE: &str = "remote";
+
+    /// Remote module icon (`.remote-icon`).
+    pub const REMOTE_ICON: &str = "remote-icon";
+
+    /// Remote module label (`.remote-label`).
+    pub const REMOTE_LABEL: &str = "remote-label";
+
+    // Custom command
+    /// Custom command widget (`.custom`).
+    pub const CUSTOM: &str = "custom";
+
+    /// Custom command icon (`.custom-icon`).
+    pub const CUSTOM_ICON: &str = "custom-icon";
+
+    /// Custom command label (`.custom-label`).
+    pub const CUSTOM_LABEL: &str = "custom-label";
+
+    /// Custom widget icon badge (`.custom-badge`).
+    pub const CUSTOM_BADGE: &str = "custom-badge";
+
+    /// Custom command failed; last output is stale (`.custom-error`).
+    pub const CUSTOM_ERROR: &str = "custom-error";
+
+    // Jobs
+    /// Jobs widget (`.jobs`).
+    pub const JOBS: &str = "jobs";
+
+    /// Jobs spinner (`.jobs-spinner`).
+    pub const JOBS_SPINNER: &str = "jobs-spinner";
+
+    /// Jobs status icon (`.jobs-icon`).
+    pub const JOBS_ICON: &str = "jobs-icon";
+
+    /// Jobs running count label (`.jobs-label`).
+    pub const JOBS_LABEL: &str = "jobs-label";
+
+    /// Jobs failed state (`.jobs-failed`).
+    pub const JOBS_FAILED: &str = "jobs-failed";
+
+    // Backup
+    /// Backup widget (`.backup`).
+    pub const BACKUP: &str = "backup";
+
+    /// Backup icon (`.backup-icon`).
+    pub const BACKUP_ICON: &str = "backup-icon";
+
+    /// Backup age label (`.backup-age`).
+    pub const BACKUP_AGE: &str = "backup-age";
+
+    /// Backup overdue state (`.backup-overdue`).
+    pub const BACKUP_OVERDUE: &str = "backup-overdue";
+
+    /// Backup check error state (`.backup-error`).
+    pub const BACKUP_ERROR: &str = "backup-error";
+
+    /// Backup running state (`.backup-running`).
+    pub const BACKUP_RUNNING: &str = "backup-running";
+
+    // Syncthing
+    /// Syncthing widget (`.syncthing`).
+    pub const SYNCTHING: &str = "syncthing";
+
+    /// Syncthing status icon (`.syncthing-icon`).
+    pub const SYNCTHING_ICON: &str = "syncthing-icon";
+
+    /// Syncthing out-of-sync count label (`.syncthing-label`).
+    pub const SYNCTHING_LABEL: &str = "syncthing-label";
+
+    /// Syncthing syncing state (`.syncthing-syncing`).
+    pub const SYNCTHING_SYNCING: &str = "syncthing-syncing";
+
+    /// Syncthing folder error state (`.syncthing-error`).
+    pub const SYNCTHING_ERROR: &str = "syncthing-error";
+
+    /// Syncthing unreachable state (`.syncthing-disconnected`).
+    pub const SYNCTHING_DISCONNECTED: &str = "syncthing-disconnected";
+
+    // Network
+    /// Network widget (`.network`).
+    pub const NETWORK: &str = "network";
+
+    /// Network status icon (`.network-icon`).
+    pub const NETWORK_ICON: &str = "network-icon";
+
+    /// Network SSID label (`.network-label`).
+    pub const NETWORK_LABEL: &str = "network-label";
+
+    /// Network disconnected state (`.network-disconnected`).
+    pub const NETWORK_DISCONNECTED: &str = "network-disconnected";
+
+    // Bluetooth
+    /// Bluetooth widget (`.bluetooth`).
+    pub const BLUETOOTH: &str = "bluetooth";
+
+    /// Bluetooth status icon (`.bluetooth-icon`).
+    pub const BLUETOOTH_ICON: &str = "bluetooth-icon";
+
+    /// Connected device count badge (`.bluetooth-badge`).
+    pub const BLUETOOTH_BADGE: &str = "bluetooth-badge";
+
+    /// Bluetooth powered off or unavailable (`.bluetooth-off`).
+    pub const BLUETOOTH_OFF: &str = "bluetooth-off";
+
+    /// At least one device connected (`.bluetooth-connected`).
+    pub const BLUETOOTH_CONNECTED: &str = "bluetooth-connected";
+
+    // Tailscale
+    /// Tailscale widget (`.tailscale`).
+    pub const TAILSCALE: &str = "tailscale";
+
+    /// Tailscale status icon (`.tailscale-icon`).
+    pub const TAILSCALE_ICON: &str = "tailscale-icon";
+
+    /// Tailscale exit node label (`.tailscale-label`).
+    pub const TAILSCALE_LABEL: &str = "tailscale-label";
+
+    /// Tailscale connected state (`.tailscale-connected`).
+    pub const TAILSCALE_CONNECTED: &str = "tailscale-connected";
+
+    /// Tailscale login required state (`.tailscale-error`).
+    pub const TAILSCALE_ERROR: &str = "tailscale-error";
+
+    // Git
+    /// Git widget (`.git`).
+    pub const GIT: &str = "git";
+
+    /// Git icon (`.git-icon`).
+    pub const GIT_ICON: &str = "git-icon";
+
+    /// Git pending repository count label (`.git-label`).
+    pub const GIT_LABEL: &str = "git-label";
+
+    /// Git pending work state (`.git-dirty`).
+    pub const GIT_DIRTY: &str = "git-dirty";
+
+    /// Git repository error state (`.git-error`).
+    pub const GIT_ERROR: &str = "git-error";
+
+    // CI
+    /// CI widget (`.ci`).
+    pub const CI: &str = "ci";
+
+    /// CI status icon (`.ci-icon`).
+    pub const CI_ICON: &str = "ci-icon";
+
+    /// CI failing count label (`.ci-label`).
+    pub const CI_LABEL: &str = "ci-label";
+
+    /// CI all-passing state (`.ci-passing`).
+    pub const CI_PASSING: &str = "ci-passing";
+
+    /// CI failing state (`.ci-failing`).
+    pub const CI_FAILING: &str = "ci-failing";
+
+    /// CI running state (`.ci-running`).
+    pub const CI_RUNNING: &str = "ci-running";
+
+    // Hosts
+    /// Hosts widget (`.hosts`).
+    pub const HOSTS: &str = "hosts";
+
+    /// Container for the per-host dots (`.hosts-dots`).
+    pub const HOSTS_DOTS: &str = "hosts-dots";
+
+    /// At least one host is down (`.hosts-down`).
+    pub const HOSTS_DOWN: &str = "hosts-down";
+
+    /// Idle inhibitor widget (`.idle-inhibitor`).
+    pub const IDLE_INHIBITOR: &str = "idle-inhibitor";
+
+    /// Idle inhibitor icon (`.idle-inhibitor-icon`).
+    pub const IDLE_INHIBITOR_ICON: &str = "idle-inhibitor-icon";
+
+    /// Time left on a timed inhibitor (`.idle-inhibitor-label`).
+    pub const IDLE_INHIBITOR_LABEL: &str = "idle-inhibitor-label";
+
+    /// Idle is inhibited (`.idle-inhibitor-active`).
+    pub const IDLE_INHIBITOR_ACTIVE: &str = "idle-inhibitor-active";
+
+    /// Power draw widget (`.power-draw`).
+    pub const POWER_DRAW: &str = "power-draw";
+
+    /// Power draw icon (`.power-draw-icon`).
+    pub const POWER_DRAW_ICON: &str = "power-draw-icon";
+
+    /// Power draw label (`.power-draw-label`).
+    pub const POWER_DRAW_LABEL: &str = "power-draw-label";
+
+    /// Power profile widget (`.power-profile`).
+    pub const POWER_PROFILE: &str = "power-profile";
+
+    /// Power profile icon (`.power-profile-icon`).
+    pub const POWER_PROFILE_ICON: &str = "power-profile-icon";
+
+    /// Power profile name label (`.power-profile-label`).
+    pub const POWER_PROFILE_LABEL: &str = "power-profile-label";
+
+    /// Performance profile active (`.power-profile-performance`).
+    pub const POWER_PROFILE_PERFORMANCE: &str = "power-profile-performance";
+
+    /// Power saver profile active (`.power-profile-power-saver`).
+    pub const POWER_PROFILE_POWER_SAVER: &str = "power-profile-power-saver";
+
+    /// Privacy widget (`.privacy`).
+    pub const PRIVACY: &str = "privacy";
+
+    /// Device-in-use icon (`.privacy-icon`).
+    pub const PRIVACY_ICON: &str = "privacy-icon";
+
+    /// Temperature widget (`.temperature`).
+    pub const TEMPERATURE: &str = "temperature";
+
+    /// Temperature icon (`.temperature-icon`).
+    pub const TEMPERATURE_ICON: &str = "temperature-icon";
+
+    /// Temperature label (`.temperature-label`).
+    pub const TEMPERATURE_LABEL: &str = "temperature-label";
+
+    /// Above the warning threshold (`.temperature-warning`).
+    pub const TEMPERATURE_WARNING: &str = "temperature-warning";
+
+    /// Above the urgent threshold (`.temperature-urgent`).
+    pub const TEMPERATURE_URGENT: &str = "temperature-urgent";
+
+    /// Focus timer widget (`.timer`).
+    pub const TIMER: &str = "timer";
+
+    /// Focus timer icon (`.timer-icon`).
+    pub const TIMER_ICON: &str = "timer-icon";
+
+    /// Time left label (`.timer-label`).
+    pub const TIMER_LABEL: &str = "timer-label";
+
+    /// Timer counting down (`.timer-running`).
+    pub const TIMER_RUNNING: &str = "timer-running";
+
+    /// Timer paused (`.timer-paused`).
+    pub const TIMER_PAUSED: &str = "timer-paused";
+
+    /// In a break interval (`.timer-break`).
+    pub const TIMER_BREAK: &str = "timer-break";
+
+    /// Weather widget container (`.weather`).
+    pub const WEATHER: &str = "weather";
+
+    /// Weather condition icon (`.weather-icon`).
+    pub const WEATHER_ICON: &str = "weather-icon";
+
+    /// Weather temperature label (`.weather-label`).
+    pub const WEATHER_LABEL: &str = "weather-label";
+
+    /// Last update failed (`.weather-stale`).
+    pub const WEATHER_STALE: &str = "weather-stale";
 }
 
 /// Surface and popover classes.
@@ -606,8 +966,14 @@ pub mod icon {
     /// Material symbol (`.material-symbol`).
     pub const MATERIAL_SYMBOL: &str = "material-symbol";
 
+    /// Nerd Font glyph (`.nerd-font-icon`).
+    pub const NERD_FONT: &str = "nerd-font-icon";
+
     /// Generic icon class (`.icon`).
     pub const ICON: &str = "icon";
+
+    /// Icon shown as a text label (`.icon-text-label`).
+    pub const TEXT_LABEL: &str = "icon-text-label";
 }
 
 /// Notification popover and toast classes.
@@ -619,6 +985,31 @@ pub mod notification {
     /// Notification list container (`.notification-list`).
     pub const LIST: &str = "notification-list";
 
+    // Notification center panel
+    /// Notification center window (`.notification-center-window`).
+    pub const CENTER_WINDOW: &str = "notification-center-window";
+
+    /// Notification center content (`.notification-center`).
+    pub const CENTER: &str = "notification-center";
+
+    /// Notification center search entry (`.notification-center-search`).
+    pub const CENTER_SEARCH: &str = "notification-center-search";
+
+    /// Do Not Disturb label in the header (`.notification-center-dnd-label`).
+    pub const CENTER_DND_LABEL: &str = "notification-center-dnd-label";
+
+    /// Per-app group (`.notification-group`).
+    pub const GROUP: &str = "notification-group";
+
+    /// Group header row (`.notification-group-header`).
+    pub const GROUP_HEADER: &str = "notification-group-header";
+
+    /// Group app name (`.notification-group-title`).
+    pub const GROUP_TITLE: &str = "notification-group-title";
+
+    /// Group notification count (`.notification-group-count`).
+    pub const GROUP_COUNT: &str = "notification-group-count";
+
     /// Scrollable area (`.notification-scroll`).
     pub const SCROLL: &str = "notification-scroll";
 
@@ -739,6 +1130,33 @@ pub mod notification {
     pub const TOAST_CLICKABLE: &str = "notification-toast-clickable";
 }
 
+/// Command palette classes.
+pub mod command_palette {
+    /// Palette window (`.command-palette-window`).
+    pub const WINDOW: &str = "command-palette-window";
+
+    /// Palette content (`.command-palette`).
+    pub const PALETTE: &str = "command-palette";
+
+    /// Search entry (`.command-palette-search`).
+    pub const SEARCH: &str = "command-palette-search";
+
+    /// Action list (`.command-palette-list`).
+    pub const LIST: &str = "command-palette-list";
+
+    /// Action row (`.command-palette-row`).
+    pub const ROW: &str = "command-palette-row";
+
+    /// Row picked by Enter (`.command-palette-row-selected`).
+    pub const ROW_SELECTED: &str = "command-palette-row-selected";
+
+    /// Action category label (`.command-palette-category`).
+    pub const CATEGORY: &str = "command-palette-category";
+
+    /// No matching actions label (`.command-palette-empty`).
+    pub const EMPTY: &str = "command-palette-empty";
+}
+
 /// On-Screen Display (OSD) classes.
 pub mod osd {
     /// OSD window (`.osd-window`).
@@ -775,6 +1193,306 @@ pub mod osd {
     pub const HORIZONTAL: &str = "osd-horizontal";
 }
 
+/// Jobs popover classes.
+pub mod jobs {
+    /// Jobs popover container (`.jobs-popover`).
+    pub const POPOVER: &str = "jobs-popover";
+
+    /// Job row (`.jobs-row`).
+    pub const ROW: &str = "jobs-row";
+
+    /// Failed job row (`.jobs-row-failed`).
+    pub const ROW_FAILED: &str = "jobs-row-failed";
+
+    /// Job name label (`.jobs-row-name`).
+    pub const ROW_NAME: &str = "jobs-row-name";
+
+    /// Job status label (`.jobs-row-status`).
+    pub const ROW_STATUS: &str = "jobs-row-status";
+}
+
+/// Syncthing popover classes.
+pub mod syncthing {
+    /// Syncthing popover container (`.syncthing-popover`).
+    pub const POPOVER: &str = "syncthing-popover";
+
+    /// Transfer rates label (`.syncthing-rates`).
+    pub const RATES: &str = "syncthing-rates";
+
+    /// Folder row (`.syncthing-row`).
+    pub const ROW: &str = "syncthing-row";
+
+    /// Folder row that is syncing (`.syncthing-row-syncing`).
+    pub const ROW_SYNCING: &str = "syncthing-row-syncing";
+
+    /// Folder row with errors (`.syncthing-row-error`).
+    pub const ROW_ERROR: &str = "syncthing-row-error";
+
+    /// Folder name label (`.syncthing-row-name`).
+    pub const ROW_NAME: &str = "syncthing-row-name";
+
+    /// Folder status label (`.syncthing-row-status`).
+    pub const ROW_STATUS: &str = "syncthing-row-status";
+}
+
+/// Network popover classes.
+pub mod network {
+    /// Network popover container (`.network-popover`).
+    pub const POPOVER: &str = "network-popover";
+
+    /// Connection name label (`.network-name`).
+    pub const NAME: &str = "network-name";
+
+    /// Detail value label (`.network-detail-value`).
+    pub const DETAIL_VALUE: &str = "network-detail-value";
+}
+
+/// Bluetooth popover classes.
+pub mod bluetooth {
+    /// Bluetooth popover container (`.bluetooth-popover`).
+    pub const POPOVER: &str = "bluetooth-popover";
+}
+
+/// Tailscale popover classes.
+pub mod tailscale {
+    /// Section title (`.vp-section-title`).
+    pub const SECTION_TITLE: &str = "vp-section-title";
+
+    /// Tailscale popover container (`.tailscale-popover`).
+    pub const POPOVER: &str = "tailscale-popover";
+
+    /// Error message label (`.tailscale-popover-error`).
+    pub const ERROR: &str = "tailscale-popover-error";
+
+    /// Exit node label (`.tailscale-exit-node`).
+    pub const EXIT_NODE: &str = "tailscale-exit-node";
+
+    /// Peer row (`.tailscale-peer`).
+    pub const PEER_ROW: &str = "tailscale-peer";
+
+    /// Offline peer row (`.tailscale-peer-offline`).
+    pub const PEER_OFFLINE: &str = "tailscale-peer-offline";
+
+    /// Peer name label (`.tailscale-peer-name`).
+    pub const PEER_NAME: &str = "tailscale-peer-name";
+
+    /// Peer OS/status label (`.tailscale-peer-detail`).
+    pub const PEER_DETAIL: &str = "tailscale-peer-detail";
+
+    /// Peer IP label (`.tailscale-peer-ip`).
+    pub const PEER_IP: &str = "tailscale-peer-ip";
+}
+
+/// Git popover classes.
+pub mod git {
+    /// Git popover container (`.git-popover`).
+    pub const POPOVER: &str = "git-popover";
+
+    /// Repository row (`.git-row`).
+    pub const ROW: &str = "git-row";
+
+    /// Repository row with pending work (`.git-row-dirty`).
+    pub const ROW_DIRTY: &str = "git-row-dirty";
+
+    /// Repository row that failed to load (`.git-row-error`).
+    pub const ROW_ERROR: &str = "git-row-error";
+
+    /// Repository name label (`.git-row-name`).
+    pub const ROW_NAME: &str = "git-row-name";
+
+    /// Repository status label (`.git-row-status`).
+    pub const ROW_STATUS: &str = "git-row-status";
+}
+
+/// CI popover classes.
+pub mod ci {
+    /// CI popover container (`.ci-popover`).
+    pub const POPOVER: &str = "ci-popover";
+
+    /// Repository row button (`.ci-row`).
+    pub const ROW: &str = "ci-row";
+
+    /// Passing repository row (`.ci-row-success`).
+    pub const ROW_SUCCESS: &str = "ci-row-success";
+
+    /// Failing repository row (`.ci-row-failure`).
+    pub const ROW_FAILURE: &str = "ci-row-failure";
+
+    /// Repository row with a run in progress (`.ci-row-running`).
+    pub const ROW_RUNNING: &str = "ci-row-running";
+
+    /// Repository name label (`.ci-row-name`).
+    pub const ROW_NAME: &str = "ci-row-name";
+
+    /// Run status label (`.ci-row-status`).
+    pub const ROW_STATUS: &str = "ci-row-status";
+}
+
+/// Hosts widget and popover classes.
+pub mod hosts {
+    /// Hosts popover container (`.hosts-popover`).
+    pub const POPOVER: &str = "hosts-popover";
+
+    /// Reachability dot (`.hosts-dot`).
+    pub const DOT: &str = "hosts-dot";
+
+    /// Reachable host (`.hosts-dot-up`).
+    pub const UP: &str = "hosts-dot-up";
+
+    /// Unreachable host (`.hosts-dot-down`).
+    pub const DOWN: &str = "hosts-dot-down";
+
+    /// Host row button (`.hosts-row`).
+    pub const ROW: &str = "hosts-row";
+
+    /// Host name label (`.hosts-row-name`).
+    pub const ROW_NAME: &str = "hosts-row-name";
+
+    /// Host status label (`.hosts-row-status`).
+    pub const ROW_STATUS: &str = "hosts-row-status";
+}
+
+/// Idle inhibitor popover classes.
+pub mod idle_inhibitor {
+    /// Idle inhibitor popover container (`.idle-inhibitor-popover`).
+    pub const POPOVER: &str = "idle-inhibitor-popover";
+
+    /// Duration option button (`.idle-inhibitor-duration`).
+    pub const DURATION_BUTTON: &str = "idle-inhibitor-duration";
+}
+
+/// Privacy popover classes.
+pub mod privacy {
+    /// Privacy popover container (`.privacy-popover`).
+    pub const POPOVER: &str = "privacy-popover";
+
+    /// Device heading (`.privacy-section-title`).
+    pub const SECTION_TITLE: &str = "privacy-section-title";
+
+    /// Application row (`.privacy-row`).
+    pub const ROW: &str = "privacy-row";
+
+    /// Application name label (`.privacy-row-app`).
+    pub const ROW_APP: &str = "privacy-row-app";
+}
+
+/// Updates popover classes (shown when remote machines are configured).
+pub mod updates {
+    /// Updates popover container (`.updates-popover`).
+    pub const POPOVER: &str = "updates-popover";
+
+    /// Machine row (`.updates-row`).
+    pub const ROW: &str = "updates-row";
+
+    /// Machine name label (`.updates-row-name`).
+    pub const ROW_NAME: &str = "updates-row-name";
+
+    /// Machine status label (`.updates-row-status`).
+    pub const ROW_STATUS: &str = "updates-row-status";
+
+    /// Machine with pending updates (`.updates-row-pending`).
+    pub const ROW_PENDING: &str = "updates-row-pending";
+
+    /// Pending reboot notice (`.updates-popover-reboot`).
+    pub const REBOOT: &str = "updates-popover-reboot";
+}
+
+/// Temperature popover classes.
+pub mod temperature {
+    /// Temperature popover container (`.temperature-popover`).
+    pub const POPOVER: &str = "temperature-popover";
+
+    /// Sensor row (`.temperature-row`).
+    pub const ROW: &str = "temperature-row";
+
+    /// Sensor name label (`.temperature-row-name`).
+    pub const ROW_NAME: &str = "temperature-row-name";
+
+    /// Sensor value label (`.temperature-row-value`).
+    pub const ROW_VALUE: &str = "temperature-row-value";
+
+    /// Sensor shown in the bar (`.temperature-row-selected`).
+    pub const ROW_SELECTED: &str = "temperature-row-selected";
+
+    /// Sensor above the warning threshold (`.temperature-row-warning`).
+    pub const ROW_WARNING: &str = "temperature-row-warning";
+
+    /// Sensor above the urgent threshold (`.temperature-row-urgent`).
+    pub const ROW_URGENT: &str = "temperature-row-urgent";
+}
+
+/// Power draw popover classes.
+pub mod power_draw {
+    /// Power draw popover container (`.power-draw-popover`).
+    pub const POPOVER: &str = "power-draw-popover";
+
+    /// Domain or total row (`.power-draw-row`).
+    pub const ROW: &str = "power-draw-row";
+
+    /// Row name label (`.power-draw-row-name`).
+    pub const ROW_NAME: &str = "power-draw-row-name";
+
+    /// Row value label (`.power-draw-row-value`).
+    pub const ROW_VALUE: &str = "power-draw-row-value";
+
+    /// Domain counted in the package total (`.power-draw-row-part`).
+    pub const ROW_PART: &str = "power-draw-row-part";
+}
+
+/// Weather popover classes.
+pub mod weather {
+    /// Section title (`.vp-section-title`).
+    pub const SECTION_TITLE: &str = "vp-section-title";
+
+    /// Weather popover container (`.weather-popover`).
+    pub const POPOVER: &str = "weather-popover";
+
+    /// Location switcher row (`.weather-switcher`).
+    pub const SWITCHER: &str = "weather-switcher";
+
+    /// Location switcher button (`.weather-switcher-button`).
+    pub const SWITCHER_BUTTON: &str = "weather-switcher-button";
+
+    /// Selected location in the switcher (`.weather-switcher-selected`).
+    pub const SWITCHER_SELECTED: &str = "weather-switcher-selected";
+
+    /// Current conditions row (`.weather-current`).
+    pub const CURRENT: &str = "weather-current";
+
+    /// Current condition icon (`.weather-current-icon`).
+    pub const CURRENT_ICON: &str = "weather-current-icon";
+
+    /// Current temperature label (`.weather-current-temperature`).
+    pub const CURRENT_TEMPERATURE: &str = "weather-current-temperature";
+
+    /// Hourly forecast strip (`.weather-hourly`).
+    pub const HOURLY: &str = "weather-hourly";
+
+    /// Hourly forecast column (`.weather-hour`).
+    pub const HOUR: &str = "weather-hour";
+
+    /// Hourly forecast icon (`.weather-hour-icon`).
+    pub const HOUR_ICON: &str = "weather-hour-icon";
+
+    /// Daily forecast row (`.weather-day`).
+    pub const DAY: &str = "weather-day";
+
+    /// Daily forecast day name (`.weather-day-name`).
+    pub const DAY_NAME: &str = "weather-day-name";
+
+    /// Daily forecast icon (`.weather-day-icon`).
+    pub const DAY_ICON: &str = "weather-day-icon";
+
+    /// Daily min/max temperatures (`.weather-day-range`).
+    pub const DAY_RANGE: &str = "weather-day-range";
+
+    /// Chance of precipitation (`.weather-precipitation`).
+    pub const PRECIPITATION: &str = "weather-precipitation";
+
+    /// Last update / error line (`.weather-footer`).
+    pub const FOOTER: &str = "weather-footer";
+}
+
 /// Battery popover classes.
 pub mod battery {
     /// Section title (`.vp-section-title`).
@@ -806,6 +1524,15 @@ pub mod battery {
 
     /// Popover separator (`.battery-popover-separator`).
     pub const POPOVER_SEPARATOR: &str = "battery-popover-separator";
+
+    /// Device row (`.battery-popover-device`).
+    pub const POPOVER_DEVICE: &str = "battery-popover-device";
+
+    /// Device state label (`.battery-popover-device-state`).
+    pub const POPOVER_DEVICE_STATE: &str = "battery-popover-device-state";
+
+    /// Device percentage label (`.battery-popover-device-percent`).
+    pub const POPOVER_DEVICE_PERCENT: &str = "battery-popover-device-percent";
 }
 
 /// Calendar popover classes.
@@ -827,6 +1554,63 @@ pub mod calendar {
 
     /// Show today state (`.show-today`).
     pub const SHOW_TODAY: &str = "show-today";
+
+    /// Month/year picker button in the header (`.calendar-header-button`).
+    pub const HEADER_BUTTON: &str = "calendar-header-button";
+
+    /// Jump-to-today button (`.calendar-today-button`).
+    pub const TODAY_BUTTON: &str = "calendar-today-button";
+
+    /// Selected day moved with the keyboard, so highlight it (`.keyboard-nav`).
+    pub const KEYBOARD_NAV: &str = "keyboard-nav";
+
+    /// Year view container (`.calendar-year-view`).
+    pub const YEAR_VIEW: &str = "calendar-year-view";
+
+    /// Month button in the year view (`.calendar-year-month`).
+    pub const YEAR_MONTH: &str = "calendar-year-month";
+
+    /// The current month in the year view (`.current`).
+    pub const YEAR_MONTH_CURRENT: &str = "current";
+
+    /// Month name in the year view (`.calendar-year-month-label`).
+    pub const YEAR_MONTH_LABEL: &str = "calendar-year-month-label";
+
+    /// Day cell in the year view (`.calendar-year-day`).
+    pub const YEAR_DAY: &str = "calendar-year-day";
+
+    /// Day that has passed (`.past`).
+    pub const YEAR_DAY_PAST: &str = "past";
+
+    /// Today (`.today`).
+    pub const YEAR_DAY_TODAY: &str = "today";
+
+    /// Saturday or Sunday (`.weekend`).
+    pub const YEAR_DAY_WEEKEND: &str = "weekend";
+
+    /// Event list under the month view (`.calendar-events`).
+    pub const EVENTS: &str = "calendar-events";
+
+    /// Event list heading (`.calendar-events-title`).
+    pub const EVENTS_TITLE: &str = "calendar-events-title";
+
+    /// One event in the list (`.calendar-event`).
+    pub const EVENT_ROW: &str = "calendar-event";
+
+    /// Event start time or "All day" (`.calendar-event-time`).
+    pub const EVENT_TIME: &str = "calendar-event-time";
+
+    /// Event title (`.calendar-event-title`).
+    pub const EVENT_TITLE: &str = "calendar-event-title";
+
+    /// Other timezones under the calendar (`.calendar-world-clocks`).
+    pub const WORLD_CLOCKS: &str = "calendar-world-clocks";
+
+    /// One timezone row (`.calendar-world-clock`).
+    pub const WORLD_CLOCK_ROW: &str = "calendar-world-clock";
+
+    /// Time in that timezone (`.calendar-world-clock-time`).
+    pub const WORLD_CLOCK_TIME: &str = "calendar-world-clock-time";
 }
 
 /// Tooltip classes.
@@ -926,6 +1710,12 @@ pub mod media {
     /// Player name label (`.media-player-name`).
     pub const PLAYER_NAME: &str = "media-player-name";
 
+    /// Love (rating) button (`.media-love-btn`).
+    pub const LOVE_BTN: &str = "media-love-btn";
+
+    /// Loved track state on the love button (`.media-loved`).
+    pub const LOVED: &str = "media-loved";
+
     // Pop-out window
     /// Pop-out window (`.media-window`).
     pub const WINDOW: &str = "media-window";
@@ -1040,4 +1830,10 @@ pub mod system_popover {
 
     /// Network speed icon (`.system-network-icon`).
     pub const NETWORK_ICON: &str = "system-network-icon";
+
+    /// Network interface with its addresses (`.system-interface`).
+    pub const INTERFACE: &str = "system-interface";
+
+    /// Interface address row with copy button (`.system-address-row`).
+    pub const ADDRESS_ROW: &str = "system-address-row";
 }
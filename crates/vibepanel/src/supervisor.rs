@@ -0,0 +1,250 @@
+//! Restarting the bar when the Wayland compositor goes away.
+//!
+//! GTK ends the process as soon as its Wayland connection breaks, so a
+//! compositor crash or restart can't be handled from inside the bar. Instead
+//! `main` runs the bar as a child of a small supervisor process. When the
+//! child exits because the display disappeared, the supervisor waits for the
+//! compositor to come back, retrying with backoff, and starts a new bar. The
+//! new bar creates fresh layer surfaces and restores widget state saved in
+//! the state store (see [`crate::services::bar_manager::BarManager::restore_session`]).
+//!
+//! Any other exit of the bar is passed through unchanged.
+
+use std::ffi::OsString;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, ExitStatus};
+use std::time::{Duration, Instant};
+
+use tracing::{error, info, warn};
+use wayland_client::Connection;
+
+/// Set in the environment of the bar process run by the supervisor.
+const SUPERVISED_ENV: &str = "VIBEPANEL_SUPERVISED";
+
+/// Set in the environment of a bar restarted after the display came back.
+const RESTORED_ENV: &str = "VIBEPANEL_RESTORED";
+
+/// First delay between attempts to reach the compositor again.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Longest delay between attempts to reach the compositor again.
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// How long to wait for the compositor to come back before giving up.
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A bar that ran at least this long counts as having started fine.
+const MIN_UPTIME: Duration = Duration::from_secs(30);
+
+/// Give up after this many restarts in a row that each ended before
+/// [`MIN_UPTIME`], so a bar that can't start doesn't loop forever.
+const MAX_QUICK_RESTARTS: u32 = 5;
+
+/// Identity of the Wayland display socket (device and inode).
+///
+/// A compositor that restarts binds a new socket, so a different identity
+/// means the display the bar was connected to is gone.
+type SocketId = (u64, u64);
+
+/// Whether this process is the bar run by the supervisor.
+pub fn is_supervised() -> bool {
+    std::env::var_os(SUPERVISED_ENV).is_some()
+}
+
+/// Whether this bar was restarted after the compositor went away, and should
+/// restore the previous session's state.
+pub fn is_restored() -> bool {
+    std::env::var_os(RESTORED_ENV).is_some()
+}
+
+/// Run the bar as a child process and restart it whenever it exits because
+/// the Wayland display went away.
+///
+/// Returns the exit code of the last bar process.
+pub fn run() -> ExitCode {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            error!(
+                "Cannot restart on compositor loss, running without supervisor: {}",
+                e
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let args: Vec<OsString> = std::env::args_os().skip(1).collect();
+
+    let mut restored = false;
+    let mut quick_restarts = 0;
+    loop {
+        let socket = display_socket_id();
+        let started = Instant::now();
+        let status = match spawn_bar(&exe, &args, restored) {
+            Ok(status) => status,
+            Err(e) => {
+                error!("Failed to start bar process: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        if status.success() || (!display_lost(socket, display_socket_id()) && display_available()) {
+            return exit_code(status);
+        }
+
+        quick_restarts = if started.elapsed() < MIN_UPTIME {
+            quick_restarts + 1
+        } else {
+            0
+        };
+        if quick_restarts > MAX_QUICK_RESTARTS {
+            error!(
+                "Bar exited {} times in a row shortly after starting, giving up",
+                quick_restarts
+            );
+            return exit_code(status);
+        }
+
+        warn!(
+            "Wayland display lost ({}), waiting for the compositor",
+            status
+        );
+        if !wait_for_display() {
+            error!(
+                "Compositor did not come back within {}s, exiting",
+                RECONNECT_TIMEOUT.as_secs()
+            );
+            return exit_code(status);
+        }
+        info!("Wayland display is back, restarting bar");
+        restored = true;
+    }
+}
+
+/// Start the bar with the same arguments and wait for it to exit.
+fn spawn_bar(exe: &Path, args: &[OsString], restored: bool) -> std::io::Result<ExitStatus> {
+    let mut command = Command::new(exe);
+    command.args(args).env(SUPERVISED_ENV, "1");
+    if restored {
+        command.env(RESTORED_ENV, "1");
+    } else {
+        command.env_remove(RESTORED_ENV);
+    }
+
+    let supervisor = std::process::id() as libc::pid_t;
+    // SAFETY: prctl and getppid are async-signal-safe and the closure
+    // doesn't allocate.
+    unsafe {
+        command.pre_exec(move || {
+            // Don't outlive the supervisor when it is stopped
+            if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::getppid() != supervisor {
+                return Err(std::io::Error::from_raw_os_error(libc::ESRCH));
+            }
+            Ok(())
+        });
+    }
+
+    command.spawn()?.wait()
+}
+
+/// Path of the Wayland display socket from `WAYLAND_DISPLAY`, resolved
+/// against `XDG_RUNTIME_DIR` like libwayland does.
+fn display_socket_path() -> Option<PathBuf> {
+    let display = std::env::var_os("WAYLAND_DISPLAY").unwrap_or_else(|| "wayland-0".into());
+    let display = PathBuf::from(display);
+    if display.is_absolute() {
+        return Some(display);
+    }
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+    Some(PathBuf::from(runtime_dir).join(display))
+}
+
+/// Identity of the current Wayland display socket, if it exists.
+fn display_socket_id() -> Option<SocketId> {
+    let metadata = std::fs::metadata(display_socket_path()?).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Whether the display socket the bar started with is gone or was replaced.
+fn display_lost(before: Option<SocketId>, after: Option<SocketId>) -> bool {
+    before.is_some() && before != after
+}
+
+/// Whether a compositor accepts connections on the display socket.
+fn display_available() -> bool {
+    Connection::connect_to_env().is_ok()
+}
+
+/// Delay before reconnect attempt `attempt` (starting at 0): doubles from
+/// [`INITIAL_BACKOFF`] up to [`MAX_BACKOFF`].
+fn backoff_delay(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+        .min(MAX_BACKOFF)
+}
+
+/// Wait with backoff until the compositor accepts connections again.
+///
+/// Returns false if it doesn't within [`RECONNECT_TIMEOUT`].
+fn wait_for_display() -> bool {
+    let deadline = Instant::now() + RECONNECT_TIMEOUT;
+    let mut attempt = 0;
+    loop {
+        if display_available() {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        std::thread::sleep(backoff_delay(attempt).min(deadline - now));
+        attempt += 1;
+    }
+}
+
+/// Exit code to pass on for a finished bar process.
+fn exit_code(status: ExitStatus) -> ExitCode {
+    match status.code() {
+        Some(code) => ExitCode::from(code as u8),
+        // Killed by a signal: report it like a shell would
+        None => ExitCode::from(128u8.wrapping_add(status.signal().unwrap_or(0) as u8)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_doubles_up_to_max() {
+        assert_eq!(backoff_delay(0), Duration::from_millis(250));
+        assert_eq!(backoff_delay(1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(3), Duration::from_secs(2));
+        assert_eq!(backoff_delay(5), MAX_BACKOFF);
+        assert_eq!(backoff_delay(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_display_lost() {
+        // Socket removed or replaced by a new compositor
+        assert!(display_lost(Some((1, 10)), None));
+        assert!(display_lost(Some((1, 10)), Some((1, 11))));
+        // Same socket still there
+        assert!(!display_lost(Some((1, 10)), Some((1, 10))));
+        // No socket to compare against
+        assert!(!display_lost(None, Some((1, 10))));
+    }
+
+    #[test]
+    fn test_exit_code_from_signal() {
+        let status = ExitStatus::from_raw(libc::SIGTERM);
+        assert_eq!(
+            format!("{:?}", exit_code(status)),
+            format!("{:?}", ExitCode::from(128 + libc::SIGTERM as u8))
+        );
+    }
+}
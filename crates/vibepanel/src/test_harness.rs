@@ -0,0 +1,221 @@
+//! Widget snapshot testing on a headless display.
+//!
+//! Widget tests build a widget, push canned service snapshots into it and
+//! check the resulting widget tree, dumped as text, for expected lines:
+//!
+//! ```ignore
+//! test_harness::run(|| {
+//!     let widget = BatteryWidget::new(BatteryConfig::default());
+//!     BatteryService::global().publish_mock(snapshot);
+//!     test_harness::assert_rendered(
+//!         &test_harness::render(widget.widget()),
+//!         &["icon.battery-icon.battery-low", r#"label.battery-percentage.vcenter-caps "15%""#],
+//!     );
+//! });
+//! ```
+//!
+//! GTK needs a display, so tests run on one dedicated GTK thread. In CI that
+//! display is a headless Broadway server (`gtk4-broadwayd`, with
+//! `GDK_BACKEND=broadway`). Without any display the tests are skipped,
+//! unless `VIBEPANEL_REQUIRE_DISPLAY` is set, in which case they fail.
+//!
+//! The GLib main loop never runs on the test thread, so services can't
+//! replace the canned snapshots with real D-Bus or worker results. The
+//! compositor manager runs without a backend; compositor-driven widgets get
+//! their state from `CompositorManager::handle_*_update` or the service's
+//! `publish_for_test`.
+//!
+//! Every test starts from fresh service singletons and a fresh compositor
+//! manager, since all tests share the one GTK thread.
+
+use std::fmt::Write as _;
+use std::panic;
+use std::sync::OnceLock;
+
+use gtk4::glib;
+use gtk4::prelude::*;
+use vibepanel_core::Config;
+
+use crate::services::audio::AudioService;
+use crate::services::audio_windows::AudioWindowsService;
+use crate::services::backup::BackupService;
+use crate::services::battery::BatteryService;
+use crate::services::bluetooth::BluetoothService;
+use crate::services::calendar_events::CalendarEventsService;
+use crate::services::ci::CiService;
+use crate::services::compositor::CompositorManager;
+use crate::services::config_manager::ConfigManager;
+use crate::services::custom::CustomService;
+use crate::services::git::GitService;
+use crate::services::hosts::HostsService;
+use crate::services::network::NetworkService;
+use crate::services::power_profile::PowerProfileService;
+use crate::services::syncthing::SyncthingService;
+use crate::services::tailscale::TailscaleService;
+use crate::services::updates::UpdatesService;
+use crate::services::weather::WeatherService;
+use crate::services::window_title::WindowTitleService;
+use crate::services::workspace::WorkspaceService;
+use crate::styles::icon;
+
+/// Set to fail instead of skipping when no display is available.
+const REQUIRE_DISPLAY_ENV: &str = "VIBEPANEL_REQUIRE_DISPLAY";
+
+/// The GTK thread, and whether GTK initialized on it.
+struct GtkThread {
+    pool: glib::ThreadPool,
+    ready: bool,
+}
+
+fn gtk_thread() -> &'static GtkThread {
+    static GTK_THREAD: OnceLock<GtkThread> = OnceLock::new();
+    GTK_THREAD.get_or_init(|| {
+        let pool = glib::ThreadPool::exclusive(1).expect("failed to create the GTK test thread");
+        let ready = pool
+            .push(|| {
+                let ready = gtk4::init().is_ok();
+                if ready {
+                    ConfigManager::init_global(Config::default(), None);
+                }
+                ready
+            })
+            .expect("failed to schedule GTK initialization")
+            .join()
+            .unwrap_or(false);
+        GtkThread { pool, ready }
+    })
+}
+
+/// Run a widget test on the GTK thread.
+///
+/// Panics from `test` are propagated to the calling test. Skips `test` when
+/// GTK can't open a display (see the module docs).
+pub fn run<F>(test: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let thread = gtk_thread();
+    if !thread.ready {
+        if std::env::var_os(REQUIRE_DISPLAY_ENV).is_some() {
+            panic!("GTK could not open a display and {REQUIRE_DISPLAY_ENV} is set");
+        }
+        eprintln!("skipping widget test: GTK could not open a display");
+        return;
+    }
+
+    let result = thread
+        .pool
+        .push(move || {
+            reset_services();
+            test();
+        })
+        .expect("failed to schedule widget test")
+        .join();
+    if let Err(panic) = result {
+        panic::resume_unwind(panic);
+    }
+}
+
+/// Replace the services widget tests drive with fresh instances.
+fn reset_services() {
+    AudioService::reset_for_test();
+    AudioWindowsService::reset_for_test();
+    BackupService::reset_for_test();
+    BatteryService::reset_for_test();
+    BluetoothService::reset_for_test();
+    CalendarEventsService::reset_for_test();
+    CiService::reset_for_test();
+    CustomService::reset_for_test();
+    GitService::reset_for_test();
+    HostsService::reset_for_test();
+    NetworkService::reset_for_test();
+    PowerProfileService::reset_for_test();
+    SyncthingService::reset_for_test();
+    TailscaleService::reset_for_test();
+    UpdatesService::reset_for_test();
+    WeatherService::reset_for_test();
+    WindowTitleService::reset_for_test();
+    WorkspaceService::reset_for_test();
+    CompositorManager::init_for_test();
+}
+
+/// Dump the visible widget tree under `widget`, one widget per line.
+///
+/// Each line is the CSS node name followed by the widget's CSS classes in
+/// alphabetical order, plus the text of labels. Hidden widgets (and their
+/// children) are left out. Icons are collapsed to one `icon` line with their
+/// classes, since their content depends on the icon backend available.
+pub fn render(widget: &impl IsA<gtk4::Widget>) -> String {
+    let mut out = String::new();
+    render_node(widget.as_ref(), 0, &mut out);
+    out
+}
+
+/// Assert that `rendered` contains each of `lines` (ignoring indentation),
+/// in order.
+#[track_caller]
+pub fn assert_rendered(rendered: &str, lines: &[&str]) {
+    let mut remaining = rendered.lines().map(str::trim);
+    for line in lines {
+        assert!(
+            remaining.any(|l| l == *line),
+            "missing or out of order: {line:?}\nrendered tree:\n{rendered}"
+        );
+    }
+}
+
+fn render_node(widget: &gtk4::Widget, depth: usize, out: &mut String) {
+    if !widget.is_visible() {
+        return;
+    }
+    out.push_str(&"  ".repeat(depth));
+
+    if widget.has_css_class(icon::ROOT) {
+        let mut classes = sorted_classes(widget);
+        if let Some(backend) = widget.first_child() {
+            classes.extend(sorted_classes(&backend));
+        }
+        classes.retain(|class| {
+            ![
+                icon::ROOT,
+                icon::TEXT,
+                icon::MATERIAL_SYMBOL,
+                icon::NERD_FONT,
+                icon::ICON,
+            ]
+            .contains(&class.as_str())
+        });
+        classes.sort();
+        classes.dedup();
+        let _ = writeln!(out, "icon{}", dotted(&classes));
+        return;
+    }
+
+    out.push_str(&widget.css_name());
+    out.push_str(&dotted(&sorted_classes(widget)));
+    if let Some(label) = widget.downcast_ref::<gtk4::Label>() {
+        let _ = write!(out, " {:?}", label.text().as_str());
+    }
+    out.push('\n');
+
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        render_node(&current, depth + 1, out);
+        child = current.next_sibling();
+    }
+}
+
+/// CSS classes in alphabetical order (GTK doesn't keep insertion order).
+fn sorted_classes(widget: &gtk4::Widget) -> Vec<String> {
+    let mut classes: Vec<String> = widget
+        .css_classes()
+        .iter()
+        .map(|class| class.to_string())
+        .collect();
+    classes.sort();
+    classes
+}
+
+fn dotted(classes: &[String]) -> String {
+    classes.iter().map(|class| format!(".{}", class)).collect()
+}
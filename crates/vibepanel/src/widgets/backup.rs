@@ -0,0 +1,374 @@
+//! Backup widget - shows the age of the last successful backup.
+//!
+//! This widget:
+//! - Shows an icon and the age of the newest backup (e.g. "5h", "3d")
+//! - Warns (`.backup-overdue`) when the backup is older than `overdue_hours`
+//! - Shows "!" when the check fails
+//! - Runs `backup_command` on click, tracked by the jobs widget
+//!
+//! Configuration options:
+//! - `tool`: Backup tool to query: "borg", "restic" or "timeshift" (required)
+//! - `repository`: Repository for borg/restic, or snapshot directory for timeshift
+//! - `overdue_hours`: Age in hours after which the backup is overdue (default: 24)
+//! - `check_interval`: How often to check, in seconds (default: 900)
+//! - `backup_command`: Shell command for "run backup now" (optional)
+
+use std::time::Duration;
+
+use gtk4::prelude::*;
+use gtk4::{GestureClick, Label};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::backup::{
+    BackupService, BackupSettings, BackupSnapshot, BackupTool, DEFAULT_CHECK_INTERVAL,
+    DEFAULT_OVERDUE_HOURS,
+};
+use crate::services::icons::IconHandle;
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{class, state, widget};
+use crate::widgets::base::BaseWidget;
+use crate::widgets::updates_common::format_last_check;
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+/// Configuration for the backup widget.
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    /// Backup tool to query (`None` if missing or unknown).
+    pub tool: Option<BackupTool>,
+    /// Repository (borg/restic) or snapshot directory (timeshift).
+    pub repository: Option<String>,
+    /// Age in hours after which the backup is overdue.
+    pub overdue_hours: u64,
+    /// How often to check (seconds).
+    pub check_interval: u64,
+    /// Shell command for "run backup now".
+    pub backup_command: Option<String>,
+}
+
+impl WidgetConfig for BackupConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "backup",
+            entry,
+            &[
+                "tool",
+                "repository",
+                "overdue_hours",
+                "check_interval",
+                "backup_command",
+            ],
+        );
+
+        let tool = match entry.options.get("tool").and_then(|v| v.as_str()) {
+            Some(name) => {
+                let tool = BackupTool::from_name(name);
+                if tool.is_none() {
+                    warn!(
+                        "backup widget: unknown tool '{}' (expected borg, restic or timeshift)",
+                        name
+                    );
+                }
+                tool
+            }
+            None => {
+                warn!("backup widget has no `tool` configured");
+                None
+            }
+        };
+
+        let repository = entry
+            .options
+            .get("repository")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        let overdue_hours = entry
+            .options
+            .get("overdue_hours")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_OVERDUE_HOURS);
+
+        let check_interval = entry
+            .options
+            .get("check_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(0) as u64)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+
+        let backup_command = entry
+            .options
+            .get("backup_command")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(String::from);
+
+        Self {
+            tool,
+            repository,
+            overdue_hours,
+            check_interval,
+            backup_command,
+        }
+    }
+}
+
+/// Backup widget that displays the age of the last backup.
+pub struct BackupWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// This widget's settings registered with the service.
+    settings_id: Option<SettingsId>,
+}
+
+impl BackupWidget {
+    /// Create a new backup widget with the given configuration.
+    pub fn new(config: BackupConfig) -> Self {
+        let base = BaseWidget::new(&[widget::BACKUP]);
+        base.set_tooltip("Backup: checking...");
+
+        let icon_handle = base.add_icon("backup", &[widget::BACKUP_ICON]);
+        let age_label = base.add_label(None, &[widget::BACKUP_AGE, class::VCENTER_CAPS]);
+
+        let service = BackupService::global();
+
+        let Some(tool) = config.tool else {
+            base.widget().set_visible(false);
+            return Self {
+                base,
+                settings_id: None,
+            };
+        };
+
+        if config.backup_command.is_some() {
+            base.widget().add_css_class(state::CLICKABLE);
+            let click = GestureClick::new();
+            click.connect_released(|_, _, _, _| {
+                BackupService::global().run_backup_now();
+            });
+            base.widget().add_controller(click);
+        }
+
+        let overdue_hours = config.overdue_hours;
+        let settings_id = service.configure(BackupSettings {
+            tool,
+            repository: config.repository,
+            overdue_after: Duration::from_secs(overdue_hours * 3600),
+            check_interval: config.check_interval,
+            backup_command: config.backup_command,
+        });
+
+        {
+            let container = base.widget().clone();
+            service.connect(move |snapshot: &BackupSnapshot| {
+                update_widget_from_snapshot(
+                    &container,
+                    &icon_handle,
+                    &age_label,
+                    overdue_hours,
+                    snapshot,
+                );
+            });
+        }
+
+        Self {
+            base,
+            settings_id: Some(settings_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl Drop for BackupWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.settings_id.take() {
+            BackupService::global().release(settings_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    age_label: &Label,
+    overdue_hours: u64,
+    snapshot: &BackupSnapshot,
+) {
+    container.remove_css_class(widget::BACKUP_OVERDUE);
+    container.remove_css_class(widget::BACKUP_ERROR);
+    container.remove_css_class(widget::BACKUP_RUNNING);
+
+    if snapshot.error.is_some() {
+        container.add_css_class(widget::BACKUP_ERROR);
+    } else if snapshot.overdue {
+        container.add_css_class(widget::BACKUP_OVERDUE);
+    }
+    if snapshot.running {
+        container.add_css_class(widget::BACKUP_RUNNING);
+    }
+
+    icon_handle.set_icon(if snapshot.running {
+        "process-working-symbolic"
+    } else {
+        "backup"
+    });
+
+    let text = if snapshot.error.is_some() {
+        "!".to_string()
+    } else {
+        snapshot
+            .last_backup
+            .and_then(|time| time.elapsed().ok())
+            .map(format_age)
+            .unwrap_or_else(|| "--".to_string())
+    };
+    update::set_label_text(age_label, &text);
+
+    TooltipManager::global()
+        .set_styled_tooltip(container, &format_tooltip(snapshot, overdue_hours));
+}
+
+/// Format an age compactly: "12m", "5h", "3d".
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86400)
+    }
+}
+
+/// Format the tooltip for a snapshot.
+fn format_tooltip(snapshot: &BackupSnapshot, overdue_hours: u64) -> String {
+    let tool = snapshot.tool.map(|t| t.name()).unwrap_or("backup");
+    let mut lines = Vec::new();
+
+    if snapshot.last_backup.is_some() {
+        lines.push(format!(
+            "Last backup: {} ({})",
+            format_last_check(snapshot.last_backup),
+            tool
+        ));
+    } else if snapshot.checking {
+        lines.push("Checking backups...".to_string());
+    } else {
+        lines.push(format!("No backups found ({})", tool));
+    }
+
+    if let Some(ref err) = snapshot.error {
+        lines.push(format!("Error: {}", err));
+    } else if snapshot.overdue {
+        lines.push(format!("Overdue (older than {}h)", overdue_hours));
+    }
+
+    if snapshot.running {
+        lines.push("Backup running...".to_string());
+    } else if snapshot.can_run {
+        lines.push(String::new());
+        lines.push("Click to run a backup now".to_string());
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use std::collections::HashMap;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_backup_config_parsing() {
+        let mut options = HashMap::new();
+        options.insert(
+            "tool".to_string(),
+            toml::Value::String("restic".to_string()),
+        );
+        options.insert("overdue_hours".to_string(), toml::Value::Integer(48));
+        options.insert(
+            "backup_command".to_string(),
+            toml::Value::String("systemctl --user start restic-backup".to_string()),
+        );
+        let entry = WidgetEntry {
+            name: "backup".to_string(),
+            options,
+        };
+        let config = BackupConfig::from_entry(&entry);
+
+        assert_eq!(config.tool, Some(BackupTool::Restic));
+        assert_eq!(config.overdue_hours, 48);
+        assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
+        assert!(config.repository.is_none());
+        assert!(config.backup_command.is_some());
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(125)), "2m");
+        assert_eq!(format_age(Duration::from_secs(5 * 3600 + 10)), "5h");
+        assert_eq!(format_age(Duration::from_secs(3 * 86400)), "3d");
+    }
+
+    #[test]
+    fn test_backup_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = BackupWidget::new(BackupConfig {
+                tool: Some(BackupTool::Timeshift),
+                repository: Some("/nonexistent/timeshift".to_string()),
+                overdue_hours: DEFAULT_OVERDUE_HOURS,
+                check_interval: DEFAULT_CHECK_INTERVAL,
+                backup_command: None,
+            });
+            let service = BackupService::global();
+            let snapshot = |age_hours: u64, overdue: bool| BackupSnapshot {
+                available: true,
+                tool: Some(BackupTool::Timeshift),
+                last_backup: SystemTime::now().checked_sub(Duration::from_secs(age_hours * 3600)),
+                overdue,
+                ..BackupSnapshot::default()
+            };
+
+            service.publish_for_test(snapshot(5, false));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.backup.widget.widget-item",
+                    "icon.backup-icon",
+                    r#"label.backup-age.vcenter-caps "5h""#,
+                ],
+            );
+
+            service.publish_for_test(snapshot(50, true));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.backup.backup-overdue.widget.widget-item",
+                    r#"label.backup-age.vcenter-caps "2d""#,
+                ],
+            );
+
+            // Failed check: flagged as an error rather than overdue
+            service.publish_for_test(BackupSnapshot {
+                error: Some("repository not found".to_string()),
+                ..snapshot(50, true)
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.backup.backup-error.widget.widget-item",
+                    r#"label.backup-age.vcenter-caps "!""#,
+                ],
+            );
+        });
+    }
+}
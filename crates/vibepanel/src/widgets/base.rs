@@ -3,18 +3,26 @@
 //! Provides a thin, reusable wrapper around a root `gtk4::Box` with
 //! common CSS classes and helpers for labels, icons, and tooltips.
 
+use gtk4::gio;
 use gtk4::prelude::*;
-use gtk4::{Align, Box as GtkBox, GestureClick, Label, Orientation, Popover, PositionType};
+use gtk4::{
+    Align, Box as GtkBox, GestureClick, Label, Orientation, Overlay, Popover, PositionType,
+    Separator,
+};
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use crate::popover_tracker::{PopoverId, PopoverTracker};
 use crate::services::config_manager::ConfigManager;
 use crate::services::icons::{IconHandle, IconsService};
+use crate::services::leak_check::LeakCheck;
 use crate::services::tooltip::TooltipManager;
 use crate::styles::{class, state, surface};
 use crate::widgets::layer_shell_popover::{Dismissible, LayerShellPopover};
-use tracing::debug;
+use crate::widgets::update;
+use tracing::{debug, warn};
+use vibepanel_core::config::WidgetEntry;
+use vibepanel_core::theme::parse_color;
 
 /// Configure a GTK popover with standard settings.
 ///
@@ -25,7 +33,7 @@ use tracing::debug;
 /// - No arrow
 /// - Autohide enabled
 /// - `widget-menu` CSS class
-/// - Bottom position
+/// - Opens away from the bar (below a top bar, above a bottom bar)
 /// - Center alignment
 /// - Configurable vertical offset from config
 pub fn configure_popover(popover: &Popover) {
@@ -33,12 +41,18 @@ pub fn configure_popover(popover: &Popover) {
     popover.set_autohide(true);
     popover.add_css_class(surface::WIDGET_MENU);
     popover.add_css_class(surface::NO_FOCUS);
-    popover.set_position(PositionType::Bottom);
     popover.set_halign(Align::Center);
 
     // Get the popover offset from config (defaults to 1 if not set)
-    let offset = ConfigManager::global().popover_offset() as i32;
-    popover.set_offset(0, offset);
+    let config_mgr = ConfigManager::global();
+    let offset = config_mgr.popover_offset() as i32;
+    if config_mgr.bar_at_bottom() {
+        popover.set_position(PositionType::Top);
+        popover.set_offset(0, -offset);
+    } else {
+        popover.set_position(PositionType::Bottom);
+        popover.set_offset(0, offset);
+    }
 }
 
 /// Handle for managing a widget menu popover.
@@ -69,13 +83,15 @@ impl MenuHandle {
     where
         F: Fn() -> gtk4::Widget + 'static,
     {
-        Rc::new(Self {
+        let handle = Rc::new(Self {
             popover: RefCell::new(None),
             builder: Rc::new(builder),
             widget_name,
             parent,
             tracker_id: Cell::new(None),
-        })
+        });
+        LeakCheck::global().track_rc("menu handle", &handle);
+        handle
     }
 
     /// Ensure the popover is created, creating it lazily if needed.
@@ -216,6 +232,325 @@ impl Dismissible for MenuHandle {
     }
 }
 
+/// Whether a click at (`x`, `y`) landed inside an interactive child that
+/// handles its own clicks.
+///
+/// Currently only checks for Button; if bar widgets gain other interactive
+/// children (Switch, Entry, etc.), add them here.
+fn click_on_button(gesture: &GestureClick, x: f64, y: f64) -> bool {
+    let Some(target) = gesture
+        .widget()
+        .and_then(|widget| widget.pick(x, y, gtk4::PickFlags::DEFAULT))
+    else {
+        return false;
+    };
+    // Walk up from target to find if it's inside a button
+    let mut current = Some(target);
+    while let Some(w) = current {
+        if w.downcast_ref::<gtk4::Button>().is_some() {
+            return true;
+        }
+        current = w.parent();
+    }
+    false
+}
+
+/// Open `menu`, or close it if it's the one already open.
+fn toggle_menu(menu: &MenuHandle) {
+    // Check if the menu is visible before dismissing
+    let was_visible = menu.is_visible();
+
+    // Dismiss any active popup (enables seamless transitions)
+    PopoverTracker::global().dismiss_active();
+
+    // If the menu was already open, we just closed it - don't re-open
+    if !was_visible {
+        debug!("Opening menu from click");
+        menu.show();
+    } else {
+        debug!("Closed own menu from click");
+    }
+}
+
+/// Detailed action name of a bar widget's default action, what a left click
+/// does. Bar keyboard focus mode activates it on Enter.
+pub const DEFAULT_ACTION: &str = "widget.activate";
+
+/// Register `action` as `widget`'s [`DEFAULT_ACTION`].
+fn install_default_action<F>(widget: &GtkBox, action: F)
+where
+    F: Fn() + 'static,
+{
+    let activate = gio::SimpleAction::new("activate", None);
+    activate.connect_activate(move |_, _| action());
+    let group = gio::SimpleActionGroup::new();
+    group.add_action(&activate);
+    widget.insert_action_group("widget", Some(&group));
+}
+
+/// A widget's popover content, built on demand for a group's shared popover.
+#[derive(Clone)]
+pub struct PopoverSection {
+    /// Sections with the same key show the same content; a shared popover
+    /// only shows the first of them.
+    key: String,
+    menu: Rc<RefCell<Option<Rc<MenuHandle>>>>,
+}
+
+impl PopoverSection {
+    /// Use `key` to detect sections showing the same content.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+
+    /// Build the section's content, or `None` if the widget has no popover.
+    pub fn build(&self) -> Option<gtk4::Widget> {
+        let builder = self.menu.borrow().as_ref()?.builder.clone();
+        Some(builder())
+    }
+}
+
+/// Bar widgets built on a `BaseWidget`.
+///
+/// Lets the widget factory collect popover sections from different widget
+/// types uniformly.
+pub trait BarWidget {
+    /// Get a reference to the base widget.
+    fn base(&self) -> &BaseWidget;
+
+    /// This widget's popover content, for a group's shared popover.
+    fn popover_section(&self) -> PopoverSection {
+        self.base().popover_section()
+    }
+}
+
+/// Open one popover stacking every member's section when `island` is
+/// clicked, in place of the members' own menus.
+///
+/// Sections with the same key are shown once. Does nothing if no member has
+/// a popover section.
+pub fn bind_shared_popover(island: &GtkBox, sections: Vec<PopoverSection>) {
+    let mut unique: Vec<PopoverSection> = Vec::new();
+    for section in sections {
+        if !unique.iter().any(|s| s.key == section.key) {
+            unique.push(section);
+        }
+    }
+    if unique.is_empty() {
+        return;
+    }
+
+    island.add_css_class(state::CLICKABLE);
+    island.add_css_class(class::SHARED_POPOVER);
+
+    let menu = MenuHandle::new(
+        class::WIDGET_GROUP.to_string(),
+        move || {
+            let container = GtkBox::new(Orientation::Vertical, 0);
+            for content in unique.iter().filter_map(PopoverSection::build) {
+                if container.first_child().is_some() {
+                    container.append(&Separator::new(Orientation::Horizontal));
+                }
+                container.append(&content);
+            }
+            container.upcast()
+        },
+        island.clone(),
+    );
+
+    // Capture phase, so the group claims the click before a member's own
+    // gesture would open its menu
+    let gesture_click = GestureClick::new();
+    gesture_click.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    gesture_click.connect_pressed(|gesture, _, x, y| {
+        if gesture.current_button() == 1 && !click_on_button(gesture, x, y) {
+            gesture.set_state(gtk4::EventSequenceState::Claimed);
+        }
+    });
+    let menu_weak = Rc::downgrade(&menu);
+    install_default_action(island, move || {
+        if let Some(menu) = menu_weak.upgrade() {
+            toggle_menu(&menu);
+        }
+    });
+    gesture_click.connect_released(move |gesture, _, x, y| {
+        if gesture.current_button() == 1 && !click_on_button(gesture, x, y) {
+            toggle_menu(&menu);
+        }
+    });
+    island.add_controller(gesture_click);
+}
+
+/// Widget options read by `BadgeConfig::from_entry`.
+///
+/// Widgets with a badge append these to their `warn_unknown_options` list.
+pub const BADGE_OPTIONS: &[&str] = &["badge_position", "badge_color", "badge_text_color"];
+
+/// Counts above this are shown as "99+".
+const BADGE_MAX_COUNT: usize = 99;
+
+/// Corner of the icon a badge sits in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BadgePosition {
+    #[default]
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
+}
+
+impl BadgePosition {
+    /// Parse a config value like "top-right" (underscores are accepted too).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().replace('_', "-").as_str() {
+            "top-right" => Some(Self::TopRight),
+            "top-left" => Some(Self::TopLeft),
+            "bottom-right" => Some(Self::BottomRight),
+            "bottom-left" => Some(Self::BottomLeft),
+            _ => None,
+        }
+    }
+
+    /// Horizontal and vertical alignment inside the icon overlay.
+    fn align(self) -> (Align, Align) {
+        match self {
+            Self::TopRight => (Align::End, Align::Start),
+            Self::TopLeft => (Align::Start, Align::Start),
+            Self::BottomRight => (Align::End, Align::End),
+            Self::BottomLeft => (Align::Start, Align::End),
+        }
+    }
+
+    fn css_class(self) -> &'static str {
+        match self {
+            Self::TopRight => class::BADGE_TOP_RIGHT,
+            Self::TopLeft => class::BADGE_TOP_LEFT,
+            Self::BottomRight => class::BADGE_BOTTOM_RIGHT,
+            Self::BottomLeft => class::BADGE_BOTTOM_LEFT,
+        }
+    }
+}
+
+/// Badge placement and colors, from the `badge_*` widget options.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BadgeConfig {
+    /// Corner of the icon the badge sits in.
+    pub position: BadgePosition,
+    /// Badge background color (default: accent color).
+    pub color: Option<String>,
+    /// Count text color (default: accent text color).
+    pub text_color: Option<String>,
+}
+
+impl BadgeConfig {
+    /// Read `badge_position`, `badge_color` and `badge_text_color`.
+    ///
+    /// Colors accept anything the theme config does, plus `var(--...)`
+    /// references to theme variables. Invalid values are logged and ignored.
+    pub fn from_entry(widget_name: &str, entry: &WidgetEntry) -> Self {
+        let option = |key: &str| entry.options.get(key).and_then(|v| v.as_str());
+
+        let position = match option("badge_position") {
+            None => BadgePosition::default(),
+            Some(value) => BadgePosition::parse(value).unwrap_or_else(|| {
+                warn!(
+                    "{} widget: unknown badge_position '{}' (expected top-right, \
+                     top-left, bottom-right or bottom-left)",
+                    widget_name, value
+                );
+                BadgePosition::default()
+            }),
+        };
+
+        let color = |key: &str| {
+            let value = option(key)?.trim();
+            if value.starts_with("var(--") {
+                return Some(value.to_string());
+            }
+            match parse_color(value) {
+                Some(color) => Some(color.to_css()),
+                None => {
+                    warn!("{} widget: invalid {} '{}'", widget_name, key, value);
+                    None
+                }
+            }
+        };
+
+        Self {
+            position,
+            color: color("badge_color"),
+            text_color: color("badge_text_color"),
+        }
+    }
+
+    /// Badge-scoped CSS for the configured colors, if any.
+    fn css(&self) -> Option<String> {
+        let mut rules = Vec::new();
+        if let Some(color) = &self.color {
+            rules.push(format!("background-color: {};", color));
+        }
+        if let Some(color) = &self.text_color {
+            rules.push(format!("color: {};", color));
+        }
+        if rules.is_empty() {
+            return None;
+        }
+        Some(format!(".{} {{ {} }}", class::BADGE, rules.join(" ")))
+    }
+}
+
+/// Text for a badge count, capped at "99+".
+fn badge_text(count: usize) -> String {
+    if count > BADGE_MAX_COUNT {
+        format!("{}+", BADGE_MAX_COUNT)
+    } else {
+        count.to_string()
+    }
+}
+
+/// Handle for a badge created with `BaseWidget::add_badge`.
+///
+/// The badge starts hidden. It shows either a count or a plain dot.
+#[derive(Clone)]
+pub struct BadgeHandle {
+    root: GtkBox,
+    label: Label,
+}
+
+impl BadgeHandle {
+    /// Show `count` on the badge, or hide it when the count is 0.
+    pub fn set_count(&self, count: usize) {
+        if count == 0 {
+            self.root.set_visible(false);
+            return;
+        }
+        self.root.remove_css_class(class::BADGE_DOT);
+        self.root.set_size_request(-1, -1);
+        update::set_label_text(&self.label, &badge_text(count));
+        self.label.set_visible(true);
+        self.root.set_visible(true);
+    }
+
+    /// Show or hide the badge as a dot without text.
+    pub fn set_dot(&self, visible: bool) {
+        if !visible {
+            self.root.set_visible(false);
+            return;
+        }
+        // A fixed-size box keeps the dot round regardless of font metrics
+        self.root.add_css_class(class::BADGE_DOT);
+        self.root.set_size_request(8, 8);
+        self.label.set_visible(false);
+        self.root.set_visible(true);
+    }
+
+    /// Hide the badge.
+    pub fn hide(&self) {
+        self.root.set_visible(false);
+    }
+}
+
 /// Shared base widget container.
 ///
 /// Each widget owns a `BaseWidget` instance and exposes the underlying
@@ -231,6 +566,8 @@ pub struct BaseWidget {
     menu: Rc<RefCell<Option<Rc<MenuHandle>>>>,
     /// Widget name for CSS class-based styling of popovers (e.g., "clock")
     widget_name: String,
+    /// Replaces toggling the menu as the widget's default action.
+    default_action: Rc<RefCell<Option<Rc<dyn Fn()>>>>,
     _gesture_click: GestureClick,
 }
 
@@ -283,63 +620,62 @@ impl BaseWidget {
                     gesture.current_button()
                 );
 
-                // Check if the click target is inside an interactive widget that should
-                // handle its own clicks. Currently only checks for Button; if bar widgets
-                // gain other interactive children (Switch, Entry, etc.), add them here.
-                if let Some(widget) = gesture.widget()
-                    && let Some(target) = widget.pick(x, y, gtk4::PickFlags::DEFAULT)
-                {
-                    // Walk up from target to find if it's inside a button
-                    let mut current: Option<gtk4::Widget> = Some(target);
-                    while let Some(w) = current {
-                        if w.downcast_ref::<gtk4::Button>().is_some() {
-                            debug!("BaseWidget click: target is a Button, skipping popover toggle");
-                            return;
-                        }
-                        current = w.parent();
-                    }
+                if click_on_button(gesture, x, y) {
+                    debug!("BaseWidget click: target is a Button, skipping popover toggle");
+                    return;
                 }
 
                 // Process every click regardless of n_press count
                 // (we don't use double-click, so treat them all as single clicks)
                 if gesture.current_button() == 1 {
-                    // Check if our own menu is visible before dismissing
-                    let my_menu_was_visible = menu_for_cb
-                        .borrow()
-                        .as_ref()
-                        .map(|m| m.is_visible())
-                        .unwrap_or(false);
-
-                    // Dismiss any active popup (enables seamless transitions)
-                    PopoverTracker::global().dismiss_active();
-
-                    if let Some(ref menu) = *menu_for_cb.borrow() {
-                        // If our menu was already open, we just closed it - don't re-open
-                        // If it wasn't open, open it now
-                        if !my_menu_was_visible {
-                            debug!("Opening menu from BaseWidget click");
-                            menu.show();
-                        } else {
-                            debug!("Closed own menu from BaseWidget click");
+                    match *menu_for_cb.borrow() {
+                        Some(ref menu) => toggle_menu(menu),
+                        None => {
+                            // Still dismiss any active popup, as a click elsewhere would
+                            PopoverTracker::global().dismiss_active();
+                            debug!("BaseWidget click: no menu registered");
                         }
-                    } else {
-                        debug!("BaseWidget click: no menu registered");
                     }
                 }
             });
         }
 
         container.add_controller(gesture_click.clone());
+        LeakCheck::global().track_object("bar widget", &container);
+
+        let default_action: Rc<RefCell<Option<Rc<dyn Fn()>>>> = Rc::new(RefCell::new(None));
+        {
+            let menu = menu.clone();
+            let default_action = default_action.clone();
+            install_default_action(&container, move || {
+                let action = default_action.borrow().clone();
+                if let Some(action) = action {
+                    action();
+                } else if let Some(menu) = menu.borrow().clone() {
+                    toggle_menu(&menu);
+                }
+            });
+        }
 
         Self {
             container,
             content,
             menu,
             widget_name,
+            default_action,
             _gesture_click: gesture_click,
         }
     }
 
+    /// Run `action` when the widget is activated from the keyboard, for
+    /// widgets whose left click does something other than open the menu.
+    pub fn set_default_action<F>(&self, action: F)
+    where
+        F: Fn() + 'static,
+    {
+        *self.default_action.borrow_mut() = Some(Rc::new(action));
+    }
+
     /// Get the root GTK container for this widget.
     ///
     /// This is the outermost box with the `widget` CSS class.
@@ -390,12 +726,75 @@ impl BaseWidget {
         label
     }
 
+    /// Overlay a badge on a corner of `icon`.
+    ///
+    /// The icon is moved into an overlay at the same position in the content
+    /// box. The badge gets the `widget-badge` class, a position class and any
+    /// `css_classes`; configured colors are scoped to the badge itself.
+    pub fn add_badge(
+        &self,
+        icon: &IconHandle,
+        config: &BadgeConfig,
+        css_classes: &[&str],
+    ) -> BadgeHandle {
+        let icon_widget = icon.widget();
+        let overlay = Overlay::new();
+        overlay.set_valign(Align::Center);
+        if icon_widget.parent().as_ref() == Some(self.content.upcast_ref::<gtk4::Widget>()) {
+            let previous = icon_widget.prev_sibling();
+            self.content.remove(&icon_widget);
+            self.content.insert_child_after(&overlay, previous.as_ref());
+        } else {
+            self.content.append(&overlay);
+        }
+        overlay.set_child(Some(&icon_widget));
+
+        let root = GtkBox::new(Orientation::Horizontal, 0);
+        root.add_css_class(class::BADGE);
+        root.add_css_class(config.position.css_class());
+        for class in css_classes {
+            root.add_css_class(class);
+        }
+        let (halign, valign) = config.position.align();
+        root.set_halign(halign);
+        root.set_valign(valign);
+        root.set_visible(false);
+
+        let label = Label::new(None);
+        label.set_hexpand(true);
+        root.append(&label);
+        overlay.add_overlay(&root);
+
+        if let Some(css) = config.css() {
+            let provider = gtk4::CssProvider::new();
+            provider.load_from_string(&css);
+            // Widget-scoped provider, as for surfaces: a display-wide one
+            // would need a unique class per badge
+            #[allow(deprecated)]
+            root.style_context()
+                .add_provider(&provider, gtk4::STYLE_PROVIDER_PRIORITY_USER);
+        }
+
+        BadgeHandle { root, label }
+    }
+
     /// Set a styled tooltip on the root container using `TooltipManager`.
     pub fn set_tooltip(&self, text: &str) {
         let tooltip_manager = TooltipManager::global();
         tooltip_manager.set_styled_tooltip(&self.container, text);
     }
 
+    /// This widget's popover content, for a group's shared popover.
+    ///
+    /// The section reads the menu when it's built, so it also covers a menu
+    /// created after this call.
+    pub fn popover_section(&self) -> PopoverSection {
+        PopoverSection {
+            key: self.widget_name.clone(),
+            menu: self.menu.clone(),
+        }
+    }
+
     /// Create a menu popover for this widget.
     ///
     /// This creates a layer-shell popover with proper keyboard focus handling,
@@ -419,3 +818,67 @@ impl BaseWidget {
         handle
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_badge_config() {
+        let mut options = HashMap::new();
+        options.insert(
+            "badge_position".to_string(),
+            toml::Value::String("bottom_left".to_string()),
+        );
+        options.insert(
+            "badge_color".to_string(),
+            toml::Value::String("rgb(255, 0, 0)".to_string()),
+        );
+        options.insert(
+            "badge_text_color".to_string(),
+            toml::Value::String("var(--color-foreground-primary)".to_string()),
+        );
+        let config = BadgeConfig::from_entry(
+            "notifications",
+            &WidgetEntry {
+                name: "notifications".to_string(),
+                options,
+            },
+        );
+        assert_eq!(config.position, BadgePosition::BottomLeft);
+        assert_eq!(config.color.as_deref(), Some("#ff0000"));
+        assert_eq!(
+            config.css().as_deref(),
+            Some(
+                ".widget-badge { background-color: #ff0000; color: var(--color-foreground-primary); }"
+            )
+        );
+
+        let mut options = HashMap::new();
+        options.insert(
+            "badge_position".to_string(),
+            toml::Value::String("middle".to_string()),
+        );
+        options.insert(
+            "badge_color".to_string(),
+            toml::Value::String("not-a-color".to_string()),
+        );
+        let config = BadgeConfig::from_entry(
+            "notifications",
+            &WidgetEntry {
+                name: "notifications".to_string(),
+                options,
+            },
+        );
+        assert_eq!(config, BadgeConfig::default());
+        assert!(config.css().is_none());
+    }
+
+    #[test]
+    fn test_badge_text() {
+        assert_eq!(badge_text(1), "1");
+        assert_eq!(badge_text(99), "99");
+        assert_eq!(badge_text(250), "99+");
+    }
+}
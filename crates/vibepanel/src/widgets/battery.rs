@@ -24,10 +24,11 @@ use std::rc::Rc;
 use crate::services::power_profile::{PowerProfileService, PowerProfileSnapshot};
 use crate::services::tooltip::TooltipManager;
 use crate::widgets::WidgetConfig;
-use crate::widgets::base::BaseWidget;
+use crate::widgets::base::{BarWidget, BaseWidget};
 use crate::widgets::battery_popover::{
     BatteryPopoverController, build_battery_popover_with_controller,
 };
+use crate::widgets::update;
 use crate::widgets::warn_unknown_options;
 
 const DEFAULT_SHOW_PERCENTAGE: bool = true;
@@ -139,6 +140,8 @@ impl BatteryWidget {
             let controller_for_cb = widget.popover_controller.clone();
 
             battery_service.connect(move |snapshot: &BatterySnapshot| {
+                // Hidden on machines without a system battery or UPS
+                container.set_visible(snapshot.present);
                 update_widgets_from_state_impl(
                     &container,
                     &icon_handle,
@@ -198,6 +201,12 @@ impl BatteryWidget {
     }
 }
 
+impl BarWidget for BatteryWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
 /// Update the visual widget state given canonical battery info.
 ///
 /// Uses `IconHandle` for icon updates, ensuring all theme mapping goes through
@@ -227,7 +236,7 @@ fn update_widgets_from_state_impl(
         }
 
         if show_percentage {
-            percentage_label.set_label("?");
+            update::set_label_text(percentage_label, "?");
             percentage_label.set_visible(true);
         } else {
             percentage_label.set_visible(false);
@@ -276,7 +285,7 @@ fn update_widgets_from_state_impl(
             Some(pct) => readable_pct(pct),
             None => "?".to_string(),
         };
-        percentage_label.set_label(&text);
+        update::set_label_text(percentage_label, &text);
         percentage_label.set_visible(true);
     } else {
         percentage_label.set_visible(false);
@@ -361,6 +370,8 @@ pub fn battery_icon_name(percent: u8, charging: bool) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::battery::STATE_DISCHARGING;
+    use crate::test_harness;
 
     #[test]
     fn test_rounded_pct_value_basic() {
@@ -417,4 +428,58 @@ mod tests {
         assert!(config.show_percentage);
         assert!(config.show_icon);
     }
+
+    fn battery(percent: f64, state: u32) -> BatterySnapshot {
+        BatterySnapshot {
+            available: true,
+            present: true,
+            percent: Some(percent),
+            state: Some(state),
+            ..BatterySnapshot::unknown()
+        }
+    }
+
+    #[test]
+    fn test_battery_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = BatteryWidget::new(BatteryConfig::default());
+            let service = BatteryService::global();
+
+            service.publish_mock(battery(15.0, STATE_DISCHARGING));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "icon.battery-icon.battery-low",
+                    r#"label.battery-percentage.vcenter-caps "15%""#,
+                ],
+            );
+
+            service.publish_mock(battery(80.4, STATE_CHARGING));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "icon.battery-charging.battery-icon",
+                    r#"label.battery-percentage.vcenter-caps "80%""#,
+                ],
+            );
+
+            // UPower gone on a laptop: still shown, as unknown
+            service.publish_mock(BatterySnapshot {
+                present: true,
+                ..BatterySnapshot::unknown()
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.battery.clickable.service-unavailable.widget.widget-item",
+                    "icon.battery-icon",
+                    r#"label.battery-percentage.vcenter-caps "?""#,
+                ],
+            );
+
+            // Nothing to show (no battery or UPS)
+            service.publish_mock(BatterySnapshot::unknown());
+            assert_eq!(test_harness::render(widget.widget()), "");
+        });
+    }
 }
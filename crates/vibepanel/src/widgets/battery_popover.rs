@@ -6,9 +6,10 @@ use gtk4::prelude::*;
 use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Separator, Widget};
 
 use crate::services::battery::{
-    BatteryService, BatterySnapshot, STATE_CHARGING, STATE_FULLY_CHARGED,
+    BatteryDevice, BatteryService, BatterySnapshot, STATE_CHARGING, STATE_DISCHARGING,
+    STATE_FULLY_CHARGED,
 };
-use crate::services::power_profile::{PowerProfileService, PowerProfileSnapshot};
+use crate::services::power_profile::{PowerProfileService, PowerProfileSnapshot, profile_label};
 use crate::styles::{battery as bat, button, color, surface};
 
 fn format_time(seconds: i64) -> String {
@@ -43,7 +44,7 @@ fn format_power(watts: Option<f64>) -> String {
 fn state_text(state: Option<u32>) -> String {
     match state {
         Some(STATE_CHARGING) => "Charging".to_string(),
-        Some(2) => "Discharging".to_string(),
+        Some(STATE_DISCHARGING) => "Discharging".to_string(),
         Some(3) => "Empty".to_string(),
         Some(STATE_FULLY_CHARGED) => "Full".to_string(),
         Some(5) => "Pending charge".to_string(),
@@ -59,6 +60,9 @@ pub struct BatteryPopoverController {
     state_label: Label,
     time_label: Label,
     power_label: Label,
+    /// Per-device section, hidden when it would only repeat the summary.
+    devices_section: GtkBox,
+    devices_list: GtkBox,
     profile_buttons: RefCell<Vec<(Button, String)>>,
 }
 
@@ -68,12 +72,16 @@ impl BatteryPopoverController {
         state_label: &Label,
         time_label: &Label,
         power_label: &Label,
+        devices_section: &GtkBox,
+        devices_list: &GtkBox,
     ) -> Self {
         Self {
             percent_label: percent_label.clone(),
             state_label: state_label.clone(),
             time_label: time_label.clone(),
             power_label: power_label.clone(),
+            devices_section: devices_section.clone(),
+            devices_list: devices_list.clone(),
             profile_buttons: RefCell::new(Vec::new()),
         }
     }
@@ -106,7 +114,7 @@ impl BatteryPopoverController {
         button_box.set_homogeneous(true);
 
         for profile in profiles {
-            let label_text = title_case(&profile.replace('-', " "));
+            let label_text = profile_label(profile);
             let btn = Button::with_label(&label_text);
             btn.add_css_class(bat::POPOVER_PROFILE_BUTTON);
             btn.set_hexpand(true);
@@ -175,7 +183,7 @@ impl BatteryPopoverController {
                 } else {
                     self.time_label.set_label("Time until full: Unknown");
                 }
-            } else if state == 2 {
+            } else if state == STATE_DISCHARGING {
                 if let Some(tte) = battery_snapshot.time_to_empty {
                     self.time_label
                         .set_label(&format!("Time remaining: {}", format_time(tte)));
@@ -195,22 +203,47 @@ impl BatteryPopoverController {
             format_power(battery_snapshot.energy_rate)
         ));
 
+        self.refresh_devices(battery_snapshot);
         self.refresh_profile_buttons(power_snapshot);
     }
+
+    /// Rebuild the device rows.
+    fn refresh_devices(&self, battery_snapshot: &BatterySnapshot) {
+        self.devices_section
+            .set_visible(battery_snapshot.has_device_details());
+
+        while let Some(child) = self.devices_list.first_child() {
+            self.devices_list.remove(&child);
+        }
+        for device in &battery_snapshot.devices {
+            self.devices_list.append(&build_device_row(device));
+        }
+    }
 }
 
-/// Title-case a string (capitalize first letter of each word).
-fn title_case(s: &str) -> String {
-    s.split_whitespace()
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-                None => String::new(),
-            }
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
+/// Build one device row: name, state and percentage.
+fn build_device_row(device: &BatteryDevice) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class(bat::POPOVER_DEVICE);
+
+    let name = Label::new(Some(&device.name()));
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    row.append(&name);
+
+    if device.state.is_some() {
+        let state = Label::new(Some(&state_text(device.state)));
+        state.add_css_class(bat::POPOVER_DEVICE_STATE);
+        state.add_css_class(color::MUTED);
+        row.append(&state);
+    }
+
+    let percent = Label::new(Some(&format!("{:.0}%", device.percent.clamp(0.0, 100.0))));
+    percent.add_css_class(bat::POPOVER_DEVICE_PERCENT);
+    row.append(&percent);
+
+    row
 }
 
 /// Build a battery popover content widget bound to global services.
@@ -261,14 +294,31 @@ pub fn build_battery_popover_with_controller() -> (Widget, BatteryPopoverControl
 
     container.append(&info_section);
 
+    // Individual batteries, UPSes and peripherals
+    let devices_section = GtkBox::new(Orientation::Vertical, 8);
+    let devices_title = Label::new(Some("Devices"));
+    devices_title.add_css_class(surface::POPOVER_TITLE);
+    devices_title.set_halign(Align::Start);
+    devices_section.append(&devices_title);
+
+    let devices_list = GtkBox::new(Orientation::Vertical, 4);
+    devices_section.append(&devices_list);
+    container.append(&devices_section);
+
     // Separator
     let separator = Separator::new(Orientation::Horizontal);
     separator.add_css_class(bat::POPOVER_SEPARATOR);
     container.append(&separator);
 
     // Initialise controller and profile section
-    let controller =
-        BatteryPopoverController::new(&percent_label, &state_label, &time_label, &power_label);
+    let controller = BatteryPopoverController::new(
+        &percent_label,
+        &state_label,
+        &time_label,
+        &power_label,
+        &devices_section,
+        &devices_list,
+    );
 
     let profile_section = controller.build_profile_section(&power_snapshot);
     container.append(&profile_section);
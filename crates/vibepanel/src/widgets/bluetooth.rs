@@ -0,0 +1,270 @@
+//! Bluetooth widget - adapter and connected device indicator.
+//!
+//! This widget:
+//! - Shows a Bluetooth icon reflecting powered / connected state from
+//!   `BluetoothService` snapshots
+//! - Shows a badge with the number of connected devices
+//! - Opens a popover with a power switch and the Quick Settings Bluetooth
+//!   device list (connect, disconnect, pair, forget)
+//!
+//! Configuration options:
+//! - `show_count`: Show the connected device count badge (default: true)
+//! - `badge_position`, `badge_color`, `badge_text_color`: Badge placement
+//!   and colors (see `BadgeConfig`)
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Label, Orientation, Switch, Widget};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::bluetooth::{BluetoothService, BluetoothSnapshot};
+use crate::services::icons::IconHandle;
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{bluetooth, surface, widget};
+use crate::widgets::base::{
+    BADGE_OPTIONS, BadgeConfig, BadgeHandle, BarWidget, BaseWidget, MenuHandle,
+};
+use crate::widgets::quick_settings::bluetooth_card::{
+    self, BluetoothCardState, bt_icon_name, build_bluetooth_details,
+};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_SHOW_COUNT: bool = true;
+
+/// Configuration for the Bluetooth widget.
+#[derive(Debug, Clone)]
+pub struct BluetoothConfig {
+    /// Show the connected device count badge.
+    pub show_count: bool,
+    /// Badge placement and colors.
+    pub badge: BadgeConfig,
+}
+
+impl WidgetConfig for BluetoothConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        let known: Vec<&str> = ["show_count"]
+            .into_iter()
+            .chain(BADGE_OPTIONS.iter().copied())
+            .collect();
+        warn_unknown_options("bluetooth", entry, &known);
+
+        let show_count = entry
+            .options
+            .get("show_count")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_COUNT);
+
+        Self {
+            show_count,
+            badge: BadgeConfig::from_entry("bluetooth", entry),
+        }
+    }
+}
+
+impl Default for BluetoothConfig {
+    fn default() -> Self {
+        Self {
+            show_count: DEFAULT_SHOW_COUNT,
+            badge: BadgeConfig::default(),
+        }
+    }
+}
+
+/// Bluetooth widget that displays adapter state and connected devices.
+pub struct BluetoothWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover with the device list.
+    _menu: Rc<MenuHandle>,
+}
+
+impl BluetoothWidget {
+    /// Create a new Bluetooth widget with the given configuration.
+    pub fn new(config: BluetoothConfig) -> Self {
+        let base = BaseWidget::new(&[widget::BLUETOOTH]);
+        base.set_tooltip("Bluetooth");
+
+        let icon_handle = base.add_icon("bluetooth-symbolic", &[widget::BLUETOOTH_ICON]);
+        let badge = base.add_badge(&icon_handle, &config.badge, &[widget::BLUETOOTH_BADGE]);
+
+        // Device list state, updated by the Quick Settings card logic
+        let card_state = Rc::new(BluetoothCardState::new());
+        let power_switch: Rc<RefCell<Option<Switch>>> = Rc::new(RefCell::new(None));
+
+        let menu = {
+            let card_state = Rc::clone(&card_state);
+            let power_switch = Rc::clone(&power_switch);
+            base.create_menu(move || build_bluetooth_popover(&card_state, &power_switch))
+        };
+
+        {
+            let container = base.widget().clone();
+            let show_count = config.show_count;
+            BluetoothService::global().connect(move |snapshot: &BluetoothSnapshot| {
+                update_widget_from_snapshot(&container, &icon_handle, &badge, show_count, snapshot);
+
+                if let Some(switch) = power_switch.borrow().as_ref() {
+                    card_state.updating_toggle.set(true);
+                    switch.set_active(snapshot.powered && snapshot.has_adapter);
+                    switch.set_sensitive(snapshot.has_adapter);
+                    card_state.updating_toggle.set(false);
+                }
+                bluetooth_card::on_bluetooth_changed(&card_state, snapshot);
+            });
+        }
+
+        Self { base, _menu: menu }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for BluetoothWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+/// Tooltip text for a snapshot.
+fn tooltip_text(snapshot: &BluetoothSnapshot) -> String {
+    if !snapshot.has_adapter {
+        return "Bluetooth unavailable".to_string();
+    }
+    if !snapshot.powered {
+        return "Bluetooth off".to_string();
+    }
+
+    let connected: Vec<&str> = snapshot
+        .devices
+        .iter()
+        .filter(|d| d.connected)
+        .map(|d| {
+            if d.name.is_empty() {
+                d.address.as_str()
+            } else {
+                d.name.as_str()
+            }
+        })
+        .collect();
+    if connected.is_empty() {
+        "Bluetooth on".to_string()
+    } else {
+        format!("Connected: {}", connected.join(", "))
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    badge: &BadgeHandle,
+    show_count: bool,
+    snapshot: &BluetoothSnapshot,
+) {
+    let powered = snapshot.powered && snapshot.has_adapter;
+    let connected = if powered {
+        snapshot.connected_devices
+    } else {
+        0
+    };
+
+    update::set_class(container, widget::BLUETOOTH_OFF, !powered);
+    update::set_class(container, widget::BLUETOOTH_CONNECTED, connected > 0);
+    icon_handle.set_icon(bt_icon_name(powered, connected));
+
+    badge.set_count(if show_count { connected } else { 0 });
+
+    TooltipManager::global().set_styled_tooltip(container, &tooltip_text(snapshot));
+}
+
+/// Build the popover content: title, power switch and device list.
+fn build_bluetooth_popover(
+    card_state: &Rc<BluetoothCardState>,
+    power_switch: &RefCell<Option<Switch>>,
+) -> Widget {
+    let snapshot = BluetoothService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(bluetooth::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("Bluetooth"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let switch = Switch::new();
+    switch.set_valign(Align::Center);
+    switch.set_active(snapshot.powered && snapshot.has_adapter);
+    switch.set_sensitive(snapshot.has_adapter);
+    {
+        let card_state = Rc::clone(card_state);
+        switch.connect_active_notify(move |switch| {
+            // Skip programmatic updates from service snapshots
+            if card_state.updating_toggle.get() {
+                return;
+            }
+            BluetoothService::global().set_powered(switch.is_active());
+        });
+    }
+    header.append(&switch);
+    container.append(&header);
+    *power_switch.borrow_mut() = Some(switch);
+
+    let details = build_bluetooth_details(card_state);
+    details.scan_button.set_visible(snapshot.powered);
+    container.append(&details.container);
+
+    // Later snapshots update the list in place through the card state
+    *card_state.base.list_box.borrow_mut() = Some(details.list_box);
+    *card_state.scan_button.borrow_mut() = Some(details.scan_button);
+
+    container.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::bluetooth::BluetoothDevice;
+
+    fn device(name: &str, connected: bool) -> BluetoothDevice {
+        BluetoothDevice {
+            path: format!("/org/bluez/hci0/dev_{}", name),
+            name: name.to_string(),
+            address: "00:11:22:33:44:55".to_string(),
+            connected,
+            paired: true,
+            trusted: true,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_bluetooth_config_defaults() {
+        let entry = WidgetEntry {
+            name: "bluetooth".to_string(),
+            options: Default::default(),
+        };
+        assert!(BluetoothConfig::from_entry(&entry).show_count);
+    }
+
+    #[test]
+    fn test_tooltip_text() {
+        let mut snapshot = BluetoothSnapshot::empty();
+        assert_eq!(tooltip_text(&snapshot), "Bluetooth unavailable");
+
+        snapshot.has_adapter = true;
+        assert_eq!(tooltip_text(&snapshot), "Bluetooth off");
+
+        snapshot.powered = true;
+        snapshot.devices = vec![device("Headphones", true), device("Mouse", false)];
+        snapshot.connected_devices = 1;
+        assert_eq!(tooltip_text(&snapshot), "Connected: Headphones");
+    }
+}
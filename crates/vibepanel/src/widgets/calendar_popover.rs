@@ -1,227 +1,690 @@
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use gtk4::gdk::{self, ModifierType};
+use gtk4::glib::Propagation;
+use gtk4::pango::EllipsizeMode;
 use gtk4::prelude::*;
-use gtk4::{Align, Box as GtkBox, Button, Calendar, Label, Orientation, Overlay, Widget};
-
-use crate::styles::{calendar as cal, surface};
+use gtk4::{
+    Align, Box as GtkBox, Button, Calendar, EventControllerKey, Grid, Label, Orientation, Overlay,
+    PropagationPhase, Stack, StackTransitionType, Widget,
+};
+
+use crate::services::calendar_events::{
+    CalendarEvent, CalendarEventsService, CalendarEventsSnapshot, EventTime, events_between,
+};
+use crate::services::callbacks::CallbackId;
+use crate::styles::{button, calendar as cal, color, surface};
+
+/// Months per row in the year view.
+const YEAR_VIEW_COLUMNS: i32 = 4;
+
+/// A keyboard navigation step within the month grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Days(i64),
+    Months(i32),
+}
 
 /// Build a calendar popover for the clock widget.
 ///
 /// Shows a month view calendar with custom previous/next navigation and a
-/// header label. Toggles a `show-today` CSS class when the currently viewed
-/// month matches the real current month.
-pub fn build_clock_calendar_popover(show_week_numbers: bool) -> Widget {
-    // Today and tracked month/year (always using day = 1 so that
-    // month arithmetic is simpler and avoids invalid dates like 31 Feb).
-    let today: NaiveDate = Local::now().date_naive();
-    let current_date = Rc::new(RefCell::new(today));
-    // Flag to prevent signal handler from interfering during programmatic updates
-    let updating = Rc::new(Cell::new(false));
-
-    // Main container
-    let container = GtkBox::new(Orientation::Vertical, 0);
-    container.add_css_class(cal::POPOVER);
-
-    // Header with navigation
-
-    let header_box = GtkBox::new(Orientation::Horizontal, 8);
-    header_box.set_halign(Align::Center);
-
-    // Month/year label - initial text is updated below via helper.
-    let header_label = Label::new(None);
-    header_label.add_css_class(surface::POPOVER_TITLE);
-    header_label.set_valign(Align::Start);
-
-    header_box.append(&header_label);
-    container.append(&header_box);
-
-    // Calendar widget
-    let calendar = Calendar::new();
-    calendar.set_show_heading(false);
-    calendar.set_show_week_numbers(show_week_numbers);
-    calendar.add_css_class(cal::WIDGET);
-    calendar.add_css_class(cal::GRID);
-    calendar.set_halign(Align::Fill); // Fill the wrapper so left alignment works relative to it
-    // Initially show today styling since we start in the current month
-    calendar.add_css_class(cal::SHOW_TODAY);
-
-    // Wrapper to center the calendar+overlay in the popover
-    let wrapper = GtkBox::new(Orientation::Vertical, 0);
-    wrapper.set_halign(Align::Center);
-
-    if show_week_numbers {
-        // Week number header "w"
-        // We use an Overlay to position the "w" label precisely over the top-left corner
-        // of the calendar, aligning it with the week number column.
-        let overlay = Overlay::new();
-        overlay.set_child(Some(&calendar));
-
-        let w_label = Label::new(Some("w"));
-        w_label.add_css_class("week-number-header");
-        w_label.set_halign(Align::Start);
-        w_label.set_valign(Align::Start);
-
-        overlay.add_overlay(&w_label);
-        wrapper.append(&overlay);
-    } else {
-        // No week numbers, just append calendar directly
-        wrapper.append(&calendar);
-    }
-
-    container.append(&wrapper);
-
-    // Helper closures --------------------------------------------------------
-
-    // Update header label text from a NaiveDate (Month YYYY).
-    let update_header = {
-        let header_label = header_label.clone();
-        move |date: NaiveDate| {
-            header_label.set_label(&date.format("%B %Y").to_string());
+/// header button that switches to a year view for picking a month. Arrow
+/// keys move the selected day, PageUp/PageDown change the month (with Shift,
+/// the year) and Home returns to today. Toggles a `show-today` CSS class
+/// when the currently viewed month matches the real current month.
+///
+/// With `show_events`, days with events from `CalendarEventsService` are
+/// marked and the selected day's events (today: the upcoming ones) are
+/// listed under the month view.
+pub fn build_clock_calendar_popover(
+    show_week_numbers: bool,
+    show_events: bool,
+    world_clocks: &[(String, String)],
+) -> Widget {
+    let (popover, container) = CalendarPopover::new(show_week_numbers, show_events);
+    popover.render();
+    popover.connect_signals(&container);
+
+    if !world_clocks.is_empty() {
+        container.append(&world_clocks_box(world_clocks));
+    }
+
+    // Keep the state alive as long as the content. Signal handlers only hold
+    // weak references, and the state doesn't reference the container, so
+    // nothing keeps the content alive once the popover window is destroyed.
+    // SAFETY: The key is private to this module and always holds this type.
+    unsafe {
+        container.set_data("vibepanel-calendar-popover", popover);
+    }
+
+    container.upcast::<Widget>()
+}
+
+/// Widgets and view state of one calendar popover.
+///
+/// Owned by the content container (see [`build_clock_calendar_popover`]),
+/// so it must not hold the container itself.
+struct CalendarPopover {
+    today: NaiveDate,
+    /// Selected day; the month view shows its month.
+    date: Cell<NaiveDate>,
+    /// Year shown while the year view is open.
+    year_view: Cell<Option<i32>>,
+    /// Flag to prevent signal handler from interfering during programmatic updates
+    updating: Cell<bool>,
+    header_label: Label,
+    header_button: Button,
+    prev_button: Button,
+    next_button: Button,
+    today_button: Button,
+    stack: Stack,
+    calendar: Calendar,
+    year_grid: GtkBox,
+    /// Event list under the month view (`None` without event sources).
+    events_box: Option<GtkBox>,
+    /// Events from the last service snapshot (recurrences not expanded).
+    events: RefCell<Vec<CalendarEvent>>,
+    /// CalendarEventsService callback registration.
+    events_callback: Cell<Option<CallbackId>>,
+}
+
+impl CalendarPopover {
+    fn new(show_week_numbers: bool, show_events: bool) -> (Rc<Self>, GtkBox) {
+        let today: NaiveDate = Local::now().date_naive();
+
+        // Main container
+        let container = GtkBox::new(Orientation::Vertical, 0);
+        container.add_css_class(cal::POPOVER);
+
+        // Header with navigation and a month/year picker button
+        let header_box = GtkBox::new(Orientation::Horizontal, 8);
+        header_box.add_css_class(cal::HEADER);
+        header_box.set_halign(Align::Center);
+
+        let prev_button = nav_button("go-previous-symbolic");
+
+        let header_label = Label::new(None);
+        header_label.add_css_class(surface::POPOVER_TITLE);
+        let header_button = Button::new();
+        header_button.set_child(Some(&header_label));
+        header_button.add_css_class(button::GHOST);
+        header_button.add_css_class(cal::HEADER_BUTTON);
+        header_button.set_valign(Align::Start);
+        header_button.set_tooltip_text(Some("Pick a month"));
+
+        let next_button = nav_button("go-next-symbolic");
+
+        let today_button = Button::with_label("Today");
+        today_button.add_css_class(button::GHOST);
+        today_button.add_css_class(cal::TODAY_BUTTON);
+        today_button.set_valign(Align::Start);
+
+        header_box.append(&prev_button);
+        header_box.append(&header_button);
+        header_box.append(&next_button);
+        header_box.append(&today_button);
+        container.append(&header_box);
+
+        // Calendar widget
+        let calendar = Calendar::new();
+        calendar.set_show_heading(false);
+        calendar.set_show_week_numbers(show_week_numbers);
+        calendar.add_css_class(cal::WIDGET);
+        calendar.add_css_class(cal::GRID);
+        calendar.set_halign(Align::Fill); // Fill the wrapper so left alignment works relative to it
+
+        // Wrapper to center the calendar+overlay in the popover
+        let wrapper = GtkBox::new(Orientation::Vertical, 0);
+        wrapper.set_halign(Align::Center);
+
+        if show_week_numbers {
+            // Week number header "w"
+            // We use an Overlay to position the "w" label precisely over the top-left corner
+            // of the calendar, aligning it with the week number column.
+            let overlay = Overlay::new();
+            overlay.set_child(Some(&calendar));
+
+            let w_label = Label::new(Some("w"));
+            w_label.add_css_class("week-number-header");
+            w_label.set_halign(Align::Start);
+            w_label.set_valign(Align::Start);
+
+            overlay.add_overlay(&w_label);
+            wrapper.append(&overlay);
+        } else {
+            // No week numbers, just append calendar directly
+            wrapper.append(&calendar);
         }
-    };
 
-    // Sync the GtkCalendar display and the `show-today` CSS class based on
-    // the logical date representing the visible month.
-    let update_calendar = {
-        let calendar = calendar.clone();
-        let updating = updating.clone();
-        move |today: NaiveDate, date: NaiveDate| {
-            let is_current_month = date.month() == today.month() && date.year() == today.year();
-
-            // Set flag to prevent signal handler from interfering
-            updating.set(true);
-
-            // Always set day to 1 first to avoid invalid intermediate states (e.g., Feb 30).
-            // This ensures month/year changes never fail due to the current day being invalid.
-            calendar.set_day(1);
-            calendar.set_year(date.year());
-            // GtkCalendar expects month in the 0-11 range (i32)
-            calendar.set_month(date.month0() as i32);
-            // Now set the actual day (today's day if current month, otherwise keep at 1)
-            if is_current_month {
-                calendar.set_day(today.day() as i32);
+        let year_grid = GtkBox::new(Orientation::Vertical, 0);
+        year_grid.add_css_class(cal::YEAR_VIEW);
+        year_grid.set_halign(Align::Center);
+        year_grid.set_valign(Align::Center);
+
+        let stack = Stack::new();
+        stack.set_transition_type(StackTransitionType::Crossfade);
+        stack.add_named(&wrapper, Some("month"));
+        stack.add_named(&year_grid, Some("year"));
+        container.append(&stack);
+
+        let events_box = show_events.then(|| {
+            let events_box = GtkBox::new(Orientation::Vertical, 2);
+            events_box.add_css_class(cal::EVENTS);
+            container.append(&events_box);
+            events_box
+        });
+
+        let popover = Rc::new(Self {
+            today,
+            date: Cell::new(today),
+            year_view: Cell::new(None),
+            updating: Cell::new(false),
+            header_label,
+            header_button,
+            prev_button,
+            next_button,
+            today_button,
+            stack,
+            calendar,
+            year_grid,
+            events_box,
+            events: RefCell::new(Vec::new()),
+            events_callback: Cell::new(None),
+        });
+        (popover, container)
+    }
+
+    fn connect_signals(self: &Rc<Self>, container: &GtkBox) {
+        let weak = Rc::downgrade(self);
+        self.prev_button.connect_clicked(move |_| {
+            if let Some(this) = weak.upgrade() {
+                this.page(-1);
+            }
+        });
+
+        let weak = Rc::downgrade(self);
+        self.next_button.connect_clicked(move |_| {
+            if let Some(this) = weak.upgrade() {
+                this.page(1);
+            }
+        });
+
+        let weak = Rc::downgrade(self);
+        self.header_button.connect_clicked(move |_| {
+            if let Some(this) = weak.upgrade() {
+                match this.year_view.get() {
+                    Some(_) => this.year_view.set(None),
+                    None => this.year_view.set(Some(this.date.get().year())),
+                }
+                this.render();
+            }
+        });
+
+        let weak = Rc::downgrade(self);
+        self.today_button.connect_clicked(move |_| {
+            if let Some(this) = weak.upgrade() {
+                this.show_date(this.today);
             }
+        });
 
-            updating.set(false);
+        // Calendar internal navigation (e.g., selecting a day that moves between
+        // months) should also keep `date` and the header / CSS in sync.
+        let weak = Rc::downgrade(self);
+        self.calendar
+            .connect_day_selected(move |calendar: &Calendar| {
+                let Some(this) = weak.upgrade() else {
+                    return;
+                };
+                // Skip if we're in a programmatic update
+                if this.updating.get() {
+                    return;
+                }
 
-            if is_current_month {
-                calendar.add_css_class(cal::SHOW_TODAY);
+                // GtkCalendar months are 0-11
+                if let Some(date) = NaiveDate::from_ymd_opt(
+                    calendar.year(),
+                    (calendar.month() + 1) as u32,
+                    calendar.day() as u32,
+                ) && date != this.date.get()
+                {
+                    this.show_date(date);
+                }
+            });
+
+        // Keyboard navigation. Capture phase so GtkCalendar's own (focus-only)
+        // arrow key handling doesn't run as well.
+        let key_controller = EventControllerKey::new();
+        key_controller.set_propagation_phase(PropagationPhase::Capture);
+        let weak = Rc::downgrade(self);
+        key_controller.connect_key_pressed(move |_, keyval, _, modifiers| {
+            let Some(this) = weak.upgrade() else {
+                return Propagation::Proceed;
+            };
+            if this.handle_key(keyval, modifiers) {
+                Propagation::Stop
             } else {
-                calendar.remove_css_class(cal::SHOW_TODAY);
+                Propagation::Proceed
             }
+        });
+        container.add_controller(key_controller);
+
+        if self.events_box.is_some() {
+            let service = CalendarEventsService::global();
+            let weak = Rc::downgrade(self);
+            let id = service.connect(move |snapshot: &CalendarEventsSnapshot| {
+                if let Some(this) = weak.upgrade() {
+                    *this.events.borrow_mut() = snapshot.events.clone();
+                    if this.year_view.get().is_none() {
+                        this.render();
+                    }
+                }
+            });
+            self.events_callback.set(Some(id));
+            // Pick up calendar edits made since the last periodic reload
+            service.refresh();
         }
-    };
+    }
 
-    // Initial sync to today's month.
-    {
-        let date = *current_date.borrow();
-        update_header(date);
-        update_calendar(today, date);
+    /// Handle a navigation key; returns whether it was consumed.
+    fn handle_key(self: &Rc<Self>, keyval: gdk::Key, modifiers: ModifierType) -> bool {
+        let shift = modifiers.contains(ModifierType::SHIFT_MASK);
+
+        if self.year_view.get().is_some() {
+            match keyval {
+                gdk::Key::Left | gdk::Key::Page_Up => self.page(-1),
+                gdk::Key::Right | gdk::Key::Page_Down => self.page(1),
+                gdk::Key::Home => self.show_date(self.today),
+                _ => return false,
+            }
+            return true;
+        }
+
+        let step = match keyval {
+            gdk::Key::Left => Step::Days(-1),
+            gdk::Key::Right => Step::Days(1),
+            gdk::Key::Up => Step::Days(-7),
+            gdk::Key::Down => Step::Days(7),
+            gdk::Key::Page_Up => Step::Months(if shift { -12 } else { -1 }),
+            gdk::Key::Page_Down => Step::Months(if shift { 12 } else { 1 }),
+            gdk::Key::Home => {
+                self.show_date(self.today);
+                return true;
+            }
+            _ => return false,
+        };
+        if let Some(date) = step_date(self.date.get(), step) {
+            self.calendar.add_css_class(cal::KEYBOARD_NAV);
+            self.show_date(date);
+        }
+        true
     }
 
-    // Navigation buttons (prev/next) ----------------------------------------
+    /// Previous (-1) / next (1) month, or year while the year view is open.
+    fn page(self: &Rc<Self>, delta: i32) {
+        if let Some(year) = self.year_view.get() {
+            self.year_view.set(Some(year + delta));
+            self.render();
+            return;
+        }
 
-    let prev_button = Button::from_icon_name("go-previous-symbolic");
-    prev_button.add_css_class(surface::POPOVER_ICON_BTN);
-    prev_button.set_valign(Align::Start);
-    if let Some(child) = prev_button.child() {
-        child.set_halign(gtk4::Align::Center);
-        child.set_valign(gtk4::Align::Center);
+        // Land on today in the current month, otherwise on the 1st.
+        let Some(first) = step_date(
+            self.date.get().with_day(1).unwrap_or(self.today),
+            Step::Months(delta),
+        ) else {
+            return;
+        };
+        self.show_date(if same_month(first, self.today) {
+            self.today
+        } else {
+            first
+        });
+    }
+
+    /// Close the year view (if open) and select `date`.
+    fn show_date(self: &Rc<Self>, date: NaiveDate) {
+        self.date.set(date);
+        self.year_view.set(None);
+        self.render();
+    }
+
+    fn render(self: &Rc<Self>) {
+        if let Some(year) = self.year_view.get() {
+            self.header_label.set_label(&year.to_string());
+            self.today_button.set_sensitive(year != self.today.year());
+            self.prev_button.set_tooltip_text(Some("Previous year"));
+            self.next_button.set_tooltip_text(Some("Next year"));
+
+            while let Some(child) = self.year_grid.first_child() {
+                self.year_grid.remove(&child);
+            }
+            self.year_grid.append(&self.build_year_grid(year));
+            self.stack.set_visible_child_name("year");
+            if let Some(events_box) = &self.events_box {
+                events_box.set_visible(false);
+            }
+            return;
+        }
+
+        let date = self.date.get();
+        self.header_label
+            .set_label(&date.format("%B %Y").to_string());
+        self.today_button.set_sensitive(date != self.today);
+        self.prev_button.set_tooltip_text(Some("Previous month"));
+        self.next_button.set_tooltip_text(Some("Next month"));
+
+        // Set flag to prevent signal handler from interfering
+        self.updating.set(true);
+        // Always set day to 1 first to avoid invalid intermediate states (e.g., Feb 30).
+        // This ensures month/year changes never fail due to the current day being invalid.
+        self.calendar.set_day(1);
+        self.calendar.set_year(date.year());
+        // GtkCalendar expects month in the 0-11 range (i32)
+        self.calendar.set_month(date.month0() as i32);
+        self.calendar.set_day(date.day() as i32);
+        self.updating.set(false);
+
+        if same_month(date, self.today) {
+            self.calendar.add_css_class(cal::SHOW_TODAY);
+        } else {
+            self.calendar.remove_css_class(cal::SHOW_TODAY);
+        }
+        self.stack.set_visible_child_name("month");
+
+        if let Some(events_box) = &self.events_box {
+            self.mark_event_days(date);
+            self.render_events(events_box, date);
+            events_box.set_visible(true);
+        }
     }
 
-    {
-        let current_date = current_date.clone();
-        let update_header = update_header.clone();
-        let update_calendar = update_calendar.clone();
-        prev_button.connect_clicked(move |_| {
-            let new_date = {
-                let date = current_date.borrow();
-                let mut year = date.year();
-                let mut month = date.month();
-                if month == 1 {
-                    month = 12;
-                    year -= 1;
-                } else {
-                    month -= 1;
+    /// Mark the days of `date`'s month that have events.
+    fn mark_event_days(&self, date: NaiveDate) {
+        self.calendar.clear_marks();
+        let Some(first) = date.with_day(1) else {
+            return;
+        };
+        let Some(next) = first.checked_add_months(Months::new(1)) else {
+            return;
+        };
+
+        for event in events_between(&self.events.borrow(), first, next) {
+            for day in first.iter_days().take_while(|day| *day < next) {
+                if event.is_on(day) {
+                    self.calendar.mark_day(day.day());
                 }
-                NaiveDate::from_ymd_opt(year, month, 1)
+            }
+        }
+    }
+
+    /// List the events on `date` (only the upcoming ones for today).
+    fn render_events(&self, events_box: &GtkBox, date: NaiveDate) {
+        while let Some(child) = events_box.first_child() {
+            events_box.remove(&child);
+        }
+
+        let is_today = date == self.today;
+        let title = Label::new(Some(&if is_today {
+            "Today".to_string()
+        } else {
+            date.format("%A, %B %-d").to_string()
+        }));
+        title.add_css_class(cal::EVENTS_TITLE);
+        title.set_halign(Align::Start);
+        events_box.append(&title);
+
+        let now = Local::now().naive_local();
+        let events: Vec<CalendarEvent> =
+            events_between(&self.events.borrow(), date, date + Days::new(1))
+                .into_iter()
+                .filter(|event| !is_today || event.is_upcoming(now))
+                .collect();
+
+        if events.is_empty() {
+            let empty = Label::new(Some(if is_today {
+                "No more events today"
+            } else {
+                "No events"
+            }));
+            empty.add_css_class(color::MUTED);
+            empty.set_halign(Align::Start);
+            events_box.append(&empty);
+            return;
+        }
+
+        for event in &events {
+            events_box.append(&event_row(event, date));
+        }
+    }
+
+    /// Build the year view: one compact month per cell, days shaded by
+    /// whether they've passed. Clicking a month opens it.
+    fn build_year_grid(self: &Rc<Self>, year: i32) -> Grid {
+        let grid = Grid::new();
+        grid.set_row_spacing(6);
+        grid.set_column_spacing(6);
+
+        for month in 1..=12u32 {
+            let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+                continue;
             };
-            if let Some(new_date) = new_date {
-                *current_date.borrow_mut() = new_date;
-                update_header(new_date);
-                update_calendar(today, new_date);
+
+            let content = GtkBox::new(Orientation::Vertical, 2);
+            let name = Label::new(Some(&first.format("%b").to_string()));
+            name.add_css_class(cal::YEAR_MONTH_LABEL);
+            name.set_halign(Align::Start);
+            content.append(&name);
+
+            let days = Grid::new();
+            days.set_row_spacing(1);
+            days.set_column_spacing(1);
+            for (index, cell) in month_cells(first).into_iter().enumerate() {
+                let Some(day) = cell else {
+                    continue;
+                };
+                let dot = GtkBox::new(Orientation::Horizontal, 0);
+                dot.add_css_class(cal::YEAR_DAY);
+                if day == self.today {
+                    dot.add_css_class(cal::YEAR_DAY_TODAY);
+                } else if day < self.today {
+                    dot.add_css_class(cal::YEAR_DAY_PAST);
+                }
+                if matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+                    dot.add_css_class(cal::YEAR_DAY_WEEKEND);
+                }
+                days.attach(&dot, (index % 7) as i32, (index / 7) as i32, 1, 1);
             }
-        });
+            content.append(&days);
+
+            let month_button = Button::new();
+            month_button.set_child(Some(&content));
+            month_button.add_css_class(button::GHOST);
+            month_button.add_css_class(cal::YEAR_MONTH);
+            if same_month(first, self.today) {
+                month_button.add_css_class(cal::YEAR_MONTH_CURRENT);
+            }
+            month_button.set_tooltip_text(Some(&first.format("%B %Y").to_string()));
+
+            let weak = Rc::downgrade(self);
+            month_button.connect_clicked(move |_| {
+                if let Some(this) = weak.upgrade() {
+                    this.show_date(if same_month(first, this.today) {
+                        this.today
+                    } else {
+                        first
+                    });
+                }
+            });
+
+            let index = month as i32 - 1;
+            grid.attach(
+                &month_button,
+                index % YEAR_VIEW_COLUMNS,
+                index / YEAR_VIEW_COLUMNS,
+                1,
+                1,
+            );
+        }
+
+        grid
+    }
+}
+
+impl Drop for CalendarPopover {
+    fn drop(&mut self) {
+        if let Some(id) = self.events_callback.take() {
+            CalendarEventsService::global().disconnect(id);
+        }
+    }
+}
+
+/// One event in the list: start time (or "All day") and title.
+fn event_row(event: &CalendarEvent, date: NaiveDate) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class(cal::EVENT_ROW);
+
+    let time = Label::new(Some(&event_time_label(event, date)));
+    time.add_css_class(cal::EVENT_TIME);
+    time.add_css_class(color::MUTED);
+    time.set_xalign(0.0);
+    row.append(&time);
+
+    let title = Label::new(Some(&event.summary));
+    title.add_css_class(cal::EVENT_TITLE);
+    title.set_xalign(0.0);
+    title.set_hexpand(true);
+    title.set_ellipsize(EllipsizeMode::End);
+    title.set_max_width_chars(28);
+    row.append(&title);
+
+    let tooltip = match &event.location {
+        Some(location) => format!("{}\n{}", event.summary, location),
+        None => event.summary.clone(),
+    };
+    row.set_tooltip_text(Some(&tooltip));
+    row
+}
+
+/// Other timezones (label, time) as rows under the calendar.
+fn world_clocks_box(world_clocks: &[(String, String)]) -> GtkBox {
+    let world_box = GtkBox::new(Orientation::Vertical, 2);
+    world_box.add_css_class(cal::WORLD_CLOCKS);
+
+    for (label, time) in world_clocks {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        row.add_css_class(cal::WORLD_CLOCK_ROW);
+
+        let name = Label::new(Some(label));
+        name.set_xalign(0.0);
+        name.set_hexpand(true);
+        name.set_ellipsize(EllipsizeMode::End);
+        row.append(&name);
+
+        let time = Label::new(Some(time));
+        time.add_css_class(cal::WORLD_CLOCK_TIME);
+        time.add_css_class(color::MUTED);
+        time.set_xalign(1.0);
+        row.append(&time);
+
+        world_box.append(&row);
     }
+    world_box
+}
 
-    let next_button = Button::from_icon_name("go-next-symbolic");
-    next_button.add_css_class(surface::POPOVER_ICON_BTN);
-    next_button.set_valign(Align::Start);
-    if let Some(child) = next_button.child() {
+/// Time column text: "All day", the start time, or "Ongoing" for an event
+/// that started on an earlier day.
+fn event_time_label(event: &CalendarEvent, date: NaiveDate) -> String {
+    match event.start {
+        EventTime::Date(_) => "All day".to_string(),
+        EventTime::DateTime(start) if start.date() < date => "Ongoing".to_string(),
+        EventTime::DateTime(start) => start.format("%H:%M").to_string(),
+    }
+}
+
+fn nav_button(icon_name: &str) -> Button {
+    let nav = Button::from_icon_name(icon_name);
+    nav.add_css_class(surface::POPOVER_ICON_BTN);
+    nav.set_valign(Align::Start);
+    if let Some(child) = nav.child() {
         child.set_halign(gtk4::Align::Center);
         child.set_valign(gtk4::Align::Center);
     }
+    nav
+}
 
-    {
-        let current_date = current_date.clone();
-        let update_header = update_header.clone();
-        let update_calendar = update_calendar.clone();
-        next_button.connect_clicked(move |_| {
-            let new_date = {
-                let date = current_date.borrow();
-                let mut year = date.year();
-                let mut month = date.month();
-                if month == 12 {
-                    month = 1;
-                    year += 1;
-                } else {
-                    month += 1;
-                }
-                NaiveDate::from_ymd_opt(year, month, 1)
-            };
-            if let Some(new_date) = new_date {
-                *current_date.borrow_mut() = new_date;
-                update_header(new_date);
-                update_calendar(today, new_date);
-            }
-        });
+fn same_month(a: NaiveDate, b: NaiveDate) -> bool {
+    a.year() == b.year() && a.month() == b.month()
+}
+
+/// Apply a navigation step. Month steps clamp the day to the target month's
+/// length (Jan 31 + 1 month = Feb 28/29).
+fn step_date(date: NaiveDate, step: Step) -> Option<NaiveDate> {
+    match step {
+        Step::Days(days) if days < 0 => date.checked_sub_days(Days::new(days.unsigned_abs())),
+        Step::Days(days) => date.checked_add_days(Days::new(days as u64)),
+        Step::Months(months) if months < 0 => {
+            date.checked_sub_months(Months::new(months.unsigned_abs()))
+        }
+        Step::Months(months) => date.checked_add_months(Months::new(months as u32)),
     }
+}
 
-    // Insert buttons around the header label.
-    header_box.prepend(&prev_button);
-    header_box.append(&next_button);
-
-    // Calendar internal navigation (e.g., selecting a day that moves between
-    // months) should also keep `current_date` and the header / CSS in sync.
-    {
-        let current_date = current_date.clone();
-        let update_header = update_header.clone();
-        let update_calendar = update_calendar.clone();
-        let updating = updating.clone();
-        calendar.connect_day_selected(move |cal: &Calendar| {
-            // Skip if we're in a programmatic update
-            if updating.get() {
-                return;
-            }
+/// Cells of a Monday-first month grid starting at `first`, with `None`
+/// padding before the 1st.
+fn month_cells(first: NaiveDate) -> Vec<Option<NaiveDate>> {
+    let padding = first.weekday().num_days_from_monday() as usize;
+    let mut cells = vec![None; padding];
+    cells.extend(
+        first
+            .iter_days()
+            .take_while(|day| day.month() == first.month())
+            .map(Some),
+    );
+    cells
+}
 
-            let year = cal.year();
-            // GtkCalendar months are 0-11
-            let month = (cal.month() + 1) as u32;
-            let current = *current_date.borrow();
-
-            // Only update if the calendar's month/year differs from our tracked state
-            // (i.e., user clicked a day in a different month)
-            if (month != current.month() || year != current.year())
-                && let Some(date) = NaiveDate::from_ymd_opt(year, month, 1)
-            {
-                *current_date.borrow_mut() = date;
-                update_header(date);
-                update_calendar(today, date);
-            }
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
     }
 
-    container.upcast::<Widget>()
+    #[test]
+    fn test_step_date() {
+        assert_eq!(
+            step_date(date(2026, 3, 1), Step::Days(-1)),
+            Some(date(2026, 2, 28))
+        );
+        assert_eq!(
+            step_date(date(2026, 12, 29), Step::Days(7)),
+            Some(date(2027, 1, 5))
+        );
+        // Month steps clamp to the end of shorter months
+        assert_eq!(
+            step_date(date(2026, 1, 31), Step::Months(1)),
+            Some(date(2026, 2, 28))
+        );
+        assert_eq!(
+            step_date(date(2024, 2, 29), Step::Months(-12)),
+            Some(date(2023, 2, 28))
+        );
+    }
+
+    #[test]
+    fn test_month_cells() {
+        // October 2026 starts on a Thursday
+        let cells = month_cells(date(2026, 10, 1));
+        assert_eq!(cells.len(), 3 + 31);
+        assert!(cells[..3].iter().all(Option::is_none));
+        assert_eq!(cells[3], Some(date(2026, 10, 1)));
+        assert_eq!(cells.last(), Some(&Some(date(2026, 10, 31))));
+
+        // February 2027 starts on a Monday
+        assert_eq!(month_cells(date(2027, 2, 1))[0], Some(date(2027, 2, 1)));
+        assert_eq!(month_cells(date(2027, 2, 1)).len(), 28);
+    }
 }
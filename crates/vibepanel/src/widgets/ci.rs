@@ -0,0 +1,430 @@
+//! CI widget - latest build status for GitHub Actions and GitLab repositories.
+//!
+//! This widget:
+//! - Shows a green/red/running icon summarizing all watched repositories
+//! - Shows the number of failing repositories
+//! - Opens a popover with one row per repository; clicking a row opens the
+//!   latest run in the browser
+//!
+//! Configuration options:
+//! - `repos`: List of `github:owner/repo` or `gitlab:group/project` specs,
+//!   optionally suffixed with `@branch` (required)
+//! - `secrets_file`: TOML file with `github_token` / `gitlab_token`
+//!   (default: "~/.config/vibepanel/ci-secrets.toml")
+//! - `gitlab_url`: GitLab instance URL (default: "https://gitlab.com")
+//! - `poll_interval`: How often to poll, in seconds (default: 300, minimum 60)
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Widget};
+use tracing::{debug, warn};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::actions::Actions;
+use crate::services::ci::{
+    CiRepo, CiRepoStatus, CiService, CiSettings, CiSnapshot, CiState, DEFAULT_GITLAB_URL,
+    DEFAULT_POLL_INTERVAL,
+};
+use crate::services::icons::IconHandle;
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{button, ci, class, color, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, expand_home, update, warn_unknown_options};
+
+/// Default location of the token file.
+const DEFAULT_SECRETS_FILE: &str = "~/.config/vibepanel/ci-secrets.toml";
+
+/// Configuration for the CI widget.
+#[derive(Debug, Clone)]
+pub struct CiConfig {
+    /// Repositories to watch.
+    pub repos: Vec<CiRepo>,
+    /// Token file path.
+    pub secrets_file: PathBuf,
+    /// GitLab instance URL.
+    pub gitlab_url: String,
+    /// Base poll interval (seconds).
+    pub poll_interval: u64,
+}
+
+impl WidgetConfig for CiConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "ci",
+            entry,
+            &["repos", "secrets_file", "gitlab_url", "poll_interval"],
+        );
+
+        let repos: Vec<CiRepo> = entry
+            .options
+            .get("repos")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .filter_map(|spec| {
+                        let repo = CiRepo::parse(spec);
+                        if repo.is_none() {
+                            warn!(
+                                "ci widget: invalid repo '{}' (expected github:owner/repo or gitlab:group/project)",
+                                spec
+                            );
+                        }
+                        repo
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if repos.is_empty() {
+            warn!("ci widget has no `repos` configured");
+        }
+
+        let secrets_file = expand_home(
+            entry
+                .options
+                .get("secrets_file")
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_SECRETS_FILE),
+        );
+
+        let gitlab_url = entry
+            .options
+            .get("gitlab_url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| DEFAULT_GITLAB_URL.to_string());
+
+        let poll_interval = entry
+            .options
+            .get("poll_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(0) as u64)
+            .unwrap_or(DEFAULT_POLL_INTERVAL);
+
+        Self {
+            repos,
+            secrets_file,
+            gitlab_url,
+            poll_interval,
+        }
+    }
+}
+
+/// CI widget that displays build status across repositories.
+pub struct CiWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover listing repositories.
+    _menu: Option<Rc<MenuHandle>>,
+    /// This widget's settings registered with the service.
+    settings_id: Option<SettingsId>,
+}
+
+impl CiWidget {
+    /// Create a new CI widget with the given configuration.
+    pub fn new(config: CiConfig) -> Self {
+        let base = BaseWidget::new(&[widget::CI]);
+        base.set_tooltip("CI: checking...");
+
+        let icon_handle = base.add_icon("emblem-ok-symbolic", &[widget::CI_ICON]);
+        let label = base.add_label(None, &[widget::CI_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+
+        if config.repos.is_empty() {
+            base.widget().set_visible(false);
+            return Self {
+                base,
+                _menu: None,
+                settings_id: None,
+            };
+        }
+
+        let menu = base.create_menu(build_ci_popover);
+
+        let service = CiService::global();
+        let settings_id = service.configure(CiSettings {
+            repos: config.repos,
+            secrets_file: Some(config.secrets_file),
+            gitlab_url: config.gitlab_url,
+            poll_interval: config.poll_interval,
+        });
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            service.connect(move |snapshot: &CiSnapshot| {
+                update_widget_from_snapshot(&container, &icon_handle, &label, snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self {
+            base,
+            _menu: Some(menu),
+            settings_id: Some(settings_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for CiWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for CiWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.settings_id.take() {
+            CiService::global().release(settings_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    snapshot: &CiSnapshot,
+) {
+    container.remove_css_class(widget::CI_FAILING);
+    container.remove_css_class(widget::CI_RUNNING);
+    container.remove_css_class(widget::CI_PASSING);
+
+    let failing = snapshot.failing();
+    let icon = if failing > 0 {
+        container.add_css_class(widget::CI_FAILING);
+        "dialog-error-symbolic"
+    } else if snapshot.running() > 0 {
+        container.add_css_class(widget::CI_RUNNING);
+        "process-working-symbolic"
+    } else {
+        if snapshot
+            .repos
+            .iter()
+            .any(|r| r.state == Some(CiState::Success))
+        {
+            container.add_css_class(widget::CI_PASSING);
+        }
+        "emblem-ok-symbolic"
+    };
+    icon_handle.set_icon(icon);
+
+    update::set_label_text(label, &failing.to_string());
+    label.set_visible(failing > 0);
+
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(snapshot));
+}
+
+/// Status text for one repository, e.g. "Failing · CI" or "Rate limited".
+pub fn repo_status_text(status: &CiRepoStatus) -> String {
+    let state = match status.state {
+        Some(state) => state.label(),
+        None if status.error.is_some() => return status.error.clone().unwrap_or_default(),
+        None => "Checking...",
+    };
+    match (&status.name, &status.error) {
+        (_, Some(err)) => format!("{} · {}", state, err),
+        (Some(name), None) => format!("{} · {}", state, name),
+        (None, None) => state.to_string(),
+    }
+}
+
+/// Format the tooltip for a snapshot.
+fn format_tooltip(snapshot: &CiSnapshot) -> String {
+    let mut lines = vec![match snapshot.failing() {
+        0 => "CI: all passing".to_string(),
+        n => format!("CI: {} failing", n),
+    }];
+    for status in &snapshot.repos {
+        lines.push(format!(
+            "{}: {}",
+            status.repo.display_name(),
+            repo_status_text(status)
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Open a URL in the default browser.
+fn open_url(url: &str) {
+    debug!("Opening {}", url);
+    if let Err(e) = Actions::global().open_url(url) {
+        warn!("Failed to open {}: {}", url, e);
+    }
+}
+
+/// Build the popover content listing repositories.
+fn build_ci_popover() -> Widget {
+    let snapshot = CiService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(ci::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("CI"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let refresh = Button::with_label("Refresh");
+    refresh.add_css_class(button::GHOST);
+    refresh.connect_clicked(|_| CiService::global().poll());
+    header.append(&refresh);
+    container.append(&header);
+
+    for status in &snapshot.repos {
+        container.append(&build_repo_row(status));
+    }
+
+    container.upcast()
+}
+
+/// Build a repository row; clicking it opens the latest run.
+fn build_repo_row(status: &CiRepoStatus) -> Button {
+    let row = GtkBox::new(Orientation::Horizontal, 12);
+
+    let name = Label::new(Some(&status.repo.display_name()));
+    name.add_css_class(ci::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(EllipsizeMode::Middle);
+    name.set_max_width_chars(32);
+    row.append(&name);
+
+    let state = Label::new(Some(&repo_status_text(status)));
+    state.add_css_class(ci::ROW_STATUS);
+    state.set_halign(Align::End);
+    state.set_ellipsize(EllipsizeMode::End);
+    state.set_max_width_chars(24);
+    row.append(&state);
+
+    let row_button = Button::new();
+    row_button.add_css_class(button::RESET);
+    row_button.add_css_class(ci::ROW);
+    row_button.add_css_class(match status.state {
+        Some(CiState::Success) => ci::ROW_SUCCESS,
+        Some(CiState::Failure) => ci::ROW_FAILURE,
+        Some(CiState::Running) => ci::ROW_RUNNING,
+        _ => color::MUTED,
+    });
+    row_button.set_child(Some(&row));
+
+    match status.url.clone() {
+        Some(url) => {
+            row_button.connect_clicked(move |_| open_url(&url));
+        }
+        None => row_button.set_sensitive(false),
+    }
+
+    row_button
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_ci_config_parsing() {
+        let mut options = HashMap::new();
+        options.insert(
+            "repos".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("github:owner/repo@main".to_string()),
+                toml::Value::String("not-a-repo".to_string()),
+            ]),
+        );
+        options.insert(
+            "secrets_file".to_string(),
+            toml::Value::String("/etc/vibepanel/ci.toml".to_string()),
+        );
+        let entry = WidgetEntry {
+            name: "ci".to_string(),
+            options,
+        };
+        let config = CiConfig::from_entry(&entry);
+
+        assert_eq!(config.repos.len(), 1);
+        assert_eq!(config.repos[0].display_name(), "owner/repo@main");
+        assert_eq!(config.secrets_file, PathBuf::from("/etc/vibepanel/ci.toml"));
+        assert_eq!(config.gitlab_url, DEFAULT_GITLAB_URL);
+    }
+
+    #[test]
+    fn test_repo_status_text() {
+        let mut status = CiRepoStatus {
+            repo: CiRepo::parse("gitlab:group/project").unwrap(),
+            state: None,
+            name: None,
+            url: None,
+            error: None,
+        };
+        assert_eq!(repo_status_text(&status), "Checking...");
+
+        status.state = Some(CiState::Failure);
+        status.name = Some("main".to_string());
+        assert_eq!(repo_status_text(&status), "Failing · main");
+
+        status.error = Some("Rate limited".to_string());
+        assert_eq!(repo_status_text(&status), "Failing · Rate limited");
+    }
+
+    #[test]
+    fn test_ci_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let repo = CiRepo::parse("gitlab:group/project").unwrap();
+            let widget = CiWidget::new(CiConfig {
+                repos: vec![repo.clone()],
+                secrets_file: PathBuf::from("/nonexistent/ci-secrets.toml"),
+                // Keep the service's own poll off the network
+                gitlab_url: "http://127.0.0.1:9".to_string(),
+                poll_interval: DEFAULT_POLL_INTERVAL,
+            });
+            let service = CiService::global();
+            let snapshot = |state| CiSnapshot {
+                available: true,
+                repos: vec![CiRepoStatus {
+                    repo: repo.clone(),
+                    state: Some(state),
+                    name: Some("pipeline".to_string()),
+                    url: None,
+                    error: None,
+                }],
+                backoff_until: None,
+            };
+
+            service.publish_for_test(snapshot(CiState::Failure));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.ci.ci-failing.clickable.widget.widget-item",
+                    "icon.ci-icon",
+                    r#"label.ci-label.vcenter-caps "1""#,
+                ],
+            );
+
+            // All passing: the failure count is hidden
+            service.publish_for_test(snapshot(CiState::Success));
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &["box.ci.ci-passing.clickable.widget.widget-item"],
+            );
+            assert!(!rendered.contains("ci-label"), "rendered tree:\n{rendered}");
+        });
+    }
+}
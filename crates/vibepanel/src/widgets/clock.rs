@@ -1,38 +1,167 @@
 //! Clock widget - displays the current time.
 //!
 //! Updates on minute boundaries to minimize CPU usage.
+//!
+//! Configuration options:
+//! - `format`: strftime format string (default: "%a %d %H:%M")
+//! - `show_week_numbers`: Show week numbers in the calendar (default: true)
+//! - `on_click`: What a click does: "calendar" (default) opens the calendar
+//!   popover, "popover:none" disables it, and "exec:<command>" runs a
+//!   command instead, e.g. "exec:gnome-calendar"
+//! - `events_eds`: Show events from Evolution Data Server calendars in the
+//!   calendar popover (default: false)
+//! - `events_ics`: `.ics` files, or directories of them, whose events are
+//!   shown in the calendar popover (default: none)
+//! - `timezones`: Other timezones shown in the tooltip and the calendar
+//!   popover, as IANA names (`"America/New_York"`) or tables with a label
+//!   (`{ tz = "Asia/Tokyo", label = "Tokyo" }`) (default: none)
+//! - `timezone_format`: strftime format for the other timezones
+//!   (default: "%H:%M")
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use chrono::Timelike;
-use gtk4::Label;
+use chrono::{DateTime, FixedOffset, Timelike, Utc};
+use gtk4::gdk::BUTTON_PRIMARY;
 use gtk4::glib::{self, SourceId};
-use tracing::debug;
+use gtk4::prelude::*;
+use gtk4::{GestureClick, Label};
+use tracing::{debug, warn};
 use vibepanel_core::config::WidgetEntry;
 
+use crate::services::actions::{ActionKind, Actions};
+use crate::services::calendar_events::{CalendarEventsService, CalendarSources};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::state;
 use crate::styles::widget as wgt;
 use crate::widgets::WidgetConfig;
-use crate::widgets::base::BaseWidget;
+use crate::widgets::base::{BarWidget, BaseWidget};
 use crate::widgets::calendar_popover::build_clock_calendar_popover;
-use crate::widgets::warn_unknown_options;
+use crate::widgets::update;
+use crate::widgets::{expand_home, warn_unknown_options};
 
 /// Default format string for the clock display.
 const DEFAULT_FORMAT: &str = "%a %d %H:%M";
 
-/// Configuration for the clock widget.
+/// Default format string for the other timezones.
+const DEFAULT_TIMEZONE_FORMAT: &str = "%H:%M";
+
+/// Another timezone shown next to the local time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldClock {
+    /// Short name shown before the time (e.g. "NYC").
+    pub label: String,
+    /// IANA timezone name (e.g. "America/New_York").
+    pub timezone: String,
+}
+
+impl WorldClock {
+    /// Parse a `timezones` entry: an IANA name or `{ tz, label }`.
+    fn from_value(value: &toml::Value) -> Option<Self> {
+        let (timezone, label) = match value {
+            toml::Value::String(timezone) => (timezone.as_str(), None),
+            toml::Value::Table(table) => (
+                table.get("tz")?.as_str()?,
+                table.get("label").and_then(|v| v.as_str()),
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            label: label.map_or_else(|| timezone_label(timezone), str::to_string),
+            timezone: timezone.to_string(),
+        })
+    }
+
+    /// Current time in this timezone, from the system timezone database.
+    ///
+    /// Returns None if the timezone is unknown.
+    pub fn now(&self) -> Option<DateTime<FixedOffset>> {
+        let timezone = glib::TimeZone::from_identifier(Some(&self.timezone))?;
+        let offset = glib::DateTime::now(&timezone).ok()?.utc_offset();
+        let offset = FixedOffset::east_opt(offset.as_seconds() as i32)?;
+        Some(Utc::now().with_timezone(&offset))
+    }
+}
+
+/// Default label for a timezone: its city, e.g. "New York" for
+/// "America/New_York".
+fn timezone_label(timezone: &str) -> String {
+    timezone
+        .rsplit('/')
+        .next()
+        .unwrap_or(timezone)
+        .replace('_', " ")
+}
+
+/// Tooltip line with the other timezones, e.g. "NYC 09:12 · Tokyo 22:12".
+fn world_clock_tooltip(times: &[(String, String)]) -> String {
+    times
+        .iter()
+        .map(|(label, time)| format!("{} {}", label, time))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
 
+/// What clicking the clock does.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ClockClickAction {
+    /// Open the built-in calendar popover.
+    #[default]
+    Calendar,
+    /// Do nothing.
+    None,
+    /// Run a command line (e.g. launch a calendar application).
+    Exec(String),
+}
+
+impl ClockClickAction {
+    /// Parse an `on_click` value: "calendar", "popover:none" or "exec:<command>".
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(command) = value.strip_prefix("exec:") {
+            let command = command.trim();
+            return (!command.is_empty()).then(|| Self::Exec(command.to_string()));
+        }
+        match value {
+            "calendar" | "popover:calendar" => Some(Self::Calendar),
+            "none" | "popover:none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// Configuration for the clock widget.
 #[derive(Debug, Clone)]
 pub struct ClockConfig {
     /// strftime format string for the clock display.
     pub format: String,
     /// Whether to show week numbers in the calendar popover.
     pub show_week_numbers: bool,
+    /// What clicking the clock does.
+    pub on_click: ClockClickAction,
+    /// Where calendar popover events come from.
+    pub events: CalendarSources,
+    /// Other timezones shown in the tooltip and calendar popover.
+    pub timezones: Vec<WorldClock>,
+    /// strftime format string for the other timezones.
+    pub timezone_format: String,
 }
 
 impl WidgetConfig for ClockConfig {
     fn from_entry(entry: &WidgetEntry) -> Self {
-        warn_unknown_options("clock", entry, &["format", "show_week_numbers"]);
+        warn_unknown_options(
+            "clock",
+            entry,
+            &[
+                "format",
+                "show_week_numbers",
+                "on_click",
+                "events_eds",
+                "events_ics",
+                "timezones",
+                "timezone_format",
+            ],
+        );
 
         let format = entry
             .options
@@ -47,9 +176,75 @@ impl WidgetConfig for ClockConfig {
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
+        let on_click = match entry.options.get("on_click").and_then(|v| v.as_str()) {
+            Some(value) => ClockClickAction::parse(value).unwrap_or_else(|| {
+                warn!(
+                    "clock widget: invalid on_click '{}' (expected \"calendar\", \"popover:none\" or \"exec:<command>\"), using calendar",
+                    value
+                );
+                ClockClickAction::Calendar
+            }),
+            None => ClockClickAction::Calendar,
+        };
+
+        let events_eds = entry
+            .options
+            .get("events_eds")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let events_ics = match entry.options.get("events_ics") {
+            Some(toml::Value::String(path)) => vec![expand_home(path)],
+            Some(toml::Value::Array(paths)) => paths
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(expand_home)
+                .collect(),
+            Some(_) => {
+                warn!("clock widget: `events_ics` must be a path or a list of paths");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        let timezones = match entry.options.get("timezones") {
+            Some(toml::Value::Array(items)) => items
+                .iter()
+                .filter_map(|item| {
+                    let clock = WorldClock::from_value(item);
+                    if clock.is_none() {
+                        warn!(
+                            "clock widget: ignoring timezone {} (expected a name like \"Asia/Tokyo\" or {{ tz, label }})",
+                            item
+                        );
+                    }
+                    clock
+                })
+                .collect(),
+            Some(_) => {
+                warn!("clock widget: `timezones` must be a list");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        let timezone_format = entry
+            .options
+            .get("timezone_format")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_TIMEZONE_FORMAT)
+            .to_string();
+
         Self {
             format,
             show_week_numbers,
+            on_click,
+            events: CalendarSources {
+                eds: events_eds,
+                ics: events_ics,
+            },
+            timezones,
+            timezone_format,
         }
     }
 }
@@ -59,18 +254,61 @@ impl Default for ClockConfig {
         Self {
             format: DEFAULT_FORMAT.to_string(),
             show_week_numbers: true,
+            on_click: ClockClickAction::Calendar,
+            events: CalendarSources::default(),
+            timezones: Vec::new(),
+            timezone_format: DEFAULT_TIMEZONE_FORMAT.to_string(),
         }
     }
 }
 
-/// Clock widget that displays and updates the current time.
-pub struct ClockWidget {
-    /// Shared base widget container.
-    base: BaseWidget,
+/// What the clock shows, re-rendered on every tick.
+struct ClockDisplay {
+    /// Root container, for the tooltip.
+    container: gtk4::Box,
     /// The label displaying the time.
     label: Label,
     /// The format string for strftime.
     format: String,
+    /// Other timezones shown in the tooltip.
+    timezones: Vec<WorldClock>,
+    /// The format string for the other timezones.
+    timezone_format: String,
+}
+
+impl ClockDisplay {
+    /// Update the displayed time and the other timezones.
+    fn update(&self) {
+        let now = chrono::Local::now();
+        let text = now.format(&self.format).to_string();
+        update::set_label_text(&self.label, &text);
+        debug!("Clock updated: {}", text);
+
+        if !self.timezones.is_empty() {
+            let times = world_clock_times(&self.timezones, &self.timezone_format);
+            TooltipManager::global()
+                .set_styled_tooltip(&self.container, &world_clock_tooltip(&times));
+        }
+    }
+}
+
+/// Label and formatted current time for each known timezone.
+fn world_clock_times(timezones: &[WorldClock], format: &str) -> Vec<(String, String)> {
+    timezones
+        .iter()
+        .filter_map(|clock| {
+            let now = clock.now()?;
+            Some((clock.label.clone(), now.format(format).to_string()))
+        })
+        .collect()
+}
+
+/// Clock widget that displays and updates the current time.
+pub struct ClockWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Label and format, shared with the timer callbacks.
+    display: Rc<ClockDisplay>,
     /// Active timer source ID for cancellation on drop.
     /// The Rc<RefCell<>> allows the closure to update the ID when
     /// it transitions from the one-shot to the repeating timer.
@@ -84,19 +322,56 @@ impl ClockWidget {
 
         let label = base.add_label(Some("--:--"), &[wgt::CLOCK_LABEL]);
 
-        let show_week_numbers = config.show_week_numbers;
-        base.create_menu(move || build_clock_calendar_popover(show_week_numbers));
+        // Events are only loaded while the calendar popover can show them
+        let show_events =
+            config.on_click == ClockClickAction::Calendar && !config.events.is_empty();
+        CalendarEventsService::global().configure(if show_events {
+            config.events
+        } else {
+            CalendarSources::default()
+        });
+
+        for clock in &config.timezones {
+            if clock.now().is_none() {
+                warn!(
+                    "clock widget: unknown timezone '{}' (expected an IANA name like \"Asia/Tokyo\")",
+                    clock.timezone
+                );
+            }
+        }
 
-        let timer_source = Rc::new(RefCell::new(None));
+        match config.on_click {
+            ClockClickAction::Calendar => {
+                let show_week_numbers = config.show_week_numbers;
+                let timezones = config.timezones.clone();
+                let timezone_format = config.timezone_format.clone();
+                base.create_menu(move || {
+                    build_clock_calendar_popover(
+                        show_week_numbers,
+                        show_events,
+                        &world_clock_times(&timezones, &timezone_format),
+                    )
+                });
+            }
+            ClockClickAction::None => {}
+            ClockClickAction::Exec(command) => connect_exec(&base, command),
+        }
 
-        let widget = Self {
-            base,
+        let display = Rc::new(ClockDisplay {
+            container: base.widget().clone(),
             label,
             format: config.format,
-            timer_source,
+            timezones: config.timezones,
+            timezone_format: config.timezone_format,
+        });
+
+        let widget = Self {
+            base,
+            display,
+            timer_source: Rc::new(RefCell::new(None)),
         };
 
-        widget.update_time();
+        widget.display.update();
         widget.schedule_minute_tick();
 
         widget
@@ -107,35 +382,21 @@ impl ClockWidget {
         self.base.widget()
     }
 
-    /// Update the displayed time.
-    fn update_time(&self) {
-        let now = chrono::Local::now();
-        let text = now.format(&self.format).to_string();
-        self.label.set_label(&text);
-        debug!("Clock updated: {}", text);
-    }
-
     /// Schedule the next tick on the next minute boundary.
     fn schedule_minute_tick(&self) {
         let now = chrono::Local::now();
         let delay_seconds = 60 - now.second();
 
-        let label = self.label.clone();
-        let format = self.format.clone();
+        let display = Rc::clone(&self.display);
         let timer_source = Rc::clone(&self.timer_source);
 
         let source_id = glib::timeout_add_seconds_local_once(delay_seconds, move || {
-            let now = chrono::Local::now();
-            let text = now.format(&format).to_string();
-            label.set_label(&text);
+            display.update();
 
-            let label_clone = label.clone();
-            let format_clone = format.clone();
+            let display_clone = Rc::clone(&display);
             let timer_source_clone = Rc::clone(&timer_source);
             let repeating_id = glib::timeout_add_seconds_local(60, move || {
-                let now = chrono::Local::now();
-                let text = now.format(&format_clone).to_string();
-                label_clone.set_label(&text);
+                display_clone.update();
                 glib::ControlFlow::Continue
             });
 
@@ -148,6 +409,27 @@ impl ClockWidget {
     }
 }
 
+impl BarWidget for ClockWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+/// Run `command` when the clock is clicked.
+fn connect_exec(base: &BaseWidget, command: String) {
+    base.widget().add_css_class(state::CLICKABLE);
+
+    let gesture = GestureClick::new();
+    gesture.set_button(BUTTON_PRIMARY);
+    gesture.connect_released(move |_, _, _, _| {
+        debug!("Clock clicked, running '{}'", command);
+        if let Err(e) = Actions::global().spawn_command_line(ActionKind::Exec, &command) {
+            warn!("clock widget: failed to run '{}': {}", command, e);
+        }
+    });
+    base.widget().add_controller(gesture);
+}
+
 impl Drop for ClockWidget {
     fn drop(&mut self) {
         // Cancel any active timer to prevent callbacks after widget is dropped
@@ -161,6 +443,7 @@ impl Drop for ClockWidget {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_harness;
     use std::collections::HashMap;
     use toml::Value;
 
@@ -201,5 +484,125 @@ mod tests {
     fn test_clock_config_default_impl() {
         let config = ClockConfig::default();
         assert_eq!(config.format, "%a %d %H:%M");
+        assert_eq!(config.on_click, ClockClickAction::Calendar);
+    }
+
+    #[test]
+    fn test_clock_click_action_parse() {
+        assert_eq!(
+            ClockClickAction::parse("calendar"),
+            Some(ClockClickAction::Calendar)
+        );
+        assert_eq!(
+            ClockClickAction::parse("popover:none"),
+            Some(ClockClickAction::None)
+        );
+        assert_eq!(
+            ClockClickAction::parse("exec: gnome-calendar"),
+            Some(ClockClickAction::Exec("gnome-calendar".to_string()))
+        );
+        assert_eq!(ClockClickAction::parse("exec:"), None);
+        assert_eq!(ClockClickAction::parse("browser"), None);
+    }
+
+    #[test]
+    fn test_clock_config_invalid_on_click_falls_back() {
+        let mut options = HashMap::new();
+        options.insert("on_click".to_string(), Value::String("nope".to_string()));
+        let entry = make_widget_entry("clock", options);
+        let config = ClockConfig::from_entry(&entry);
+        assert_eq!(config.on_click, ClockClickAction::Calendar);
+    }
+
+    #[test]
+    fn test_clock_config_events() {
+        let entry = make_widget_entry("clock", HashMap::new());
+        assert!(ClockConfig::from_entry(&entry).events.is_empty());
+
+        let mut options = HashMap::new();
+        options.insert("events_eds".to_string(), Value::Boolean(true));
+        options.insert(
+            "events_ics".to_string(),
+            Value::String("/srv/calendars/work.ics".to_string()),
+        );
+        let entry = make_widget_entry("clock", options);
+        let events = ClockConfig::from_entry(&entry).events;
+        assert!(events.eds);
+        assert_eq!(
+            events.ics,
+            vec![std::path::PathBuf::from("/srv/calendars/work.ics")]
+        );
+    }
+
+    #[test]
+    fn test_clock_config_timezones() {
+        let mut tokyo = toml::map::Map::new();
+        tokyo.insert("tz".to_string(), Value::String("Asia/Tokyo".to_string()));
+        tokyo.insert("label".to_string(), Value::String("Tokyo".to_string()));
+
+        let mut options = HashMap::new();
+        options.insert(
+            "timezones".to_string(),
+            Value::Array(vec![
+                Value::String("America/New_York".to_string()),
+                Value::Table(tokyo),
+                Value::Integer(5),
+            ]),
+        );
+        let entry = make_widget_entry("clock", options);
+        let config = ClockConfig::from_entry(&entry);
+        assert_eq!(
+            config.timezones,
+            vec![
+                WorldClock {
+                    label: "New York".to_string(),
+                    timezone: "America/New_York".to_string(),
+                },
+                WorldClock {
+                    label: "Tokyo".to_string(),
+                    timezone: "Asia/Tokyo".to_string(),
+                },
+            ]
+        );
+        assert_eq!(config.timezone_format, "%H:%M");
+    }
+
+    #[test]
+    fn test_timezone_label() {
+        assert_eq!(timezone_label("America/New_York"), "New York");
+        assert_eq!(
+            timezone_label("America/Argentina/Buenos_Aires"),
+            "Buenos Aires"
+        );
+        assert_eq!(timezone_label("UTC"), "UTC");
+    }
+
+    #[test]
+    fn test_world_clock_tooltip() {
+        let times = vec![
+            ("NYC".to_string(), "09:12".to_string()),
+            ("Tokyo".to_string(), "22:12".to_string()),
+        ];
+        assert_eq!(world_clock_tooltip(&times), "NYC 09:12 · Tokyo 22:12");
+        assert_eq!(world_clock_tooltip(&[]), "");
+    }
+
+    #[test]
+    fn test_clock_widget_renders_time() {
+        test_harness::run(|| {
+            let widget = ClockWidget::new(ClockConfig {
+                format: "%Y".to_string(),
+                on_click: ClockClickAction::None,
+                ..ClockConfig::default()
+            });
+            let year = chrono::Local::now().format("%Y").to_string();
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clock.widget.widget-item",
+                    &format!("label.clock-label {year:?}"),
+                ],
+            );
+        });
     }
 }
@@ -0,0 +1,739 @@
+//! Command palette - fuzzy-searchable list of bar actions.
+//!
+//! A layer-shell window centered below (or above) the bar, opened with
+//! `vibepanel ipc toggle-command-palette` (e.g. from a compositor keybind):
+//! - Type to fuzzy-filter actions; Up/Down move the selection, Enter runs it
+//! - Actions: reload, Quick Settings, notification center, Do Not Disturb,
+//!   showing/hiding each widget on the bar, power profiles, idle inhibitor,
+//!   night light, the focus timer and media playback
+//!
+//! Only actions that apply right now are listed (e.g. power profiles need
+//! power-profiles-daemon, timer actions need a timer widget on the bar).
+//! Like the notification center, the window is created on each open and
+//! destroyed on close.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk4::gdk::{self, Monitor};
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, EventControllerKey, Label,
+    Orientation, PropagationPhase, SearchEntry,
+};
+use gtk4_layer_shell::{Layer, LayerShell};
+use tracing::{debug, warn};
+
+use crate::popover_tracker::{PopoverId, PopoverTracker};
+use crate::services::actions::{ActionKind, Actions};
+use crate::services::bar_manager::BarManager;
+use crate::services::config_manager::ConfigManager;
+use crate::services::idle_inhibitor::IdleInhibitorService;
+use crate::services::media::MediaService;
+use crate::services::night_light::NightLightService;
+use crate::services::notification::NotificationService;
+use crate::services::power_profile::{PowerProfileService, profile_label};
+use crate::services::surfaces::SurfaceStyleManager;
+use crate::services::timer::TimerService;
+use crate::styles::{color, command_palette as palette, surface};
+use crate::widgets::layer_shell_popover::{
+    Dismissible, bar_edge, calculate_bar_exclusive_zone, calculate_popover_bar_margin,
+    create_click_catcher, popover_keyboard_mode, setup_esc_handler,
+};
+
+/// Palette content width.
+const PALETTE_WIDTH: i32 = 480;
+/// Maximum number of matching actions shown at once.
+const MAX_RESULTS: usize = 10;
+
+/// Something the palette can do.
+#[derive(Debug, Clone, PartialEq)]
+enum PaletteAction {
+    Reload,
+    OpenQuickSettings,
+    ToggleNotificationCenter,
+    ToggleDoNotDisturb,
+    ToggleWidget(String),
+    SetPowerProfile(String),
+    ToggleIdleInhibitor,
+    NightLight(Option<bool>),
+    TimerToggle,
+    TimerSkip,
+    TimerReset,
+    MediaPlayPause,
+    MediaNext,
+    MediaPrevious,
+}
+
+impl PaletteAction {
+    /// Run the action; `output` is the monitor the palette was opened on.
+    fn run(&self, output: Option<&str>) -> Result<(), String> {
+        // Same commands as the mutating `vibepanel ipc` ones, refused in
+        // kiosk mode
+        if matches!(
+            self,
+            Self::Reload | Self::ToggleWidget(_) | Self::NightLight(_)
+        ) {
+            Actions::global().permit(ActionKind::Control, &format!("{:?}", self))?;
+        }
+
+        match self {
+            Self::Reload => ConfigManager::global().reload(|result| {
+                if let Err(e) = result {
+                    warn!("Command palette: reload failed: {}", e);
+                }
+            }),
+            Self::OpenQuickSettings => return BarManager::global().open_quick_settings(output),
+            Self::ToggleNotificationCenter => {
+                return BarManager::global().toggle_notification_center(output);
+            }
+            Self::ToggleDoNotDisturb => NotificationService::global().toggle_muted(),
+            Self::ToggleWidget(name) => {
+                BarManager::global().toggle_widget(name);
+            }
+            Self::SetPowerProfile(profile) => {
+                if !PowerProfileService::global().set_profile(profile) {
+                    return Err(format!("could not switch to power profile '{}'", profile));
+                }
+            }
+            Self::ToggleIdleInhibitor => IdleInhibitorService::global().toggle(),
+            Self::NightLight(night) => return NightLightService::global().set_override(*night),
+            Self::TimerToggle => TimerService::global().toggle(),
+            Self::TimerSkip => TimerService::global().skip(),
+            Self::TimerReset => TimerService::global().reset(),
+            Self::MediaPlayPause => MediaService::global().play_pause(),
+            Self::MediaNext => MediaService::global().next(),
+            Self::MediaPrevious => MediaService::global().previous(),
+        }
+        Ok(())
+    }
+}
+
+/// A listed action.
+#[derive(Debug, Clone, PartialEq)]
+struct PaletteEntry {
+    title: String,
+    category: &'static str,
+    action: PaletteAction,
+}
+
+impl PaletteEntry {
+    fn new(title: impl Into<String>, category: &'static str, action: PaletteAction) -> Self {
+        Self {
+            title: title.into(),
+            category,
+            action,
+        }
+    }
+}
+
+/// What's available when the palette opens, used to pick the actions.
+#[derive(Debug, Clone, Default)]
+struct PaletteContext {
+    /// Config names of the widgets on the palette's bar.
+    widget_names: Vec<String>,
+    /// Power profiles offered by power-profiles-daemon.
+    power_profiles: Vec<String>,
+    idle_inhibitor: bool,
+    night_light: bool,
+    media: bool,
+}
+
+impl PaletteContext {
+    fn current(widget_names: Vec<String>) -> Self {
+        let power = PowerProfileService::global().snapshot();
+        let night_light = NightLightService::global().snapshot();
+        Self {
+            widget_names,
+            power_profiles: if power.available {
+                power.available_profiles
+            } else {
+                Vec::new()
+            },
+            idle_inhibitor: IdleInhibitorService::global().available(),
+            night_light: night_light.enabled && night_light.located,
+            media: MediaService::global().snapshot().available,
+        }
+    }
+
+    fn has_widget(&self, name: &str) -> bool {
+        self.widget_names.iter().any(|n| n == name)
+    }
+}
+
+/// All actions that apply in `ctx`, in display order.
+fn palette_entries(ctx: &PaletteContext) -> Vec<PaletteEntry> {
+    use PaletteAction as A;
+
+    let mut entries = vec![PaletteEntry::new("Reload config", "Bar", A::Reload)];
+    if ctx.has_widget("quick_settings") {
+        entries.push(PaletteEntry::new(
+            "Open Quick Settings",
+            "Bar",
+            A::OpenQuickSettings,
+        ));
+    }
+    entries.push(PaletteEntry::new(
+        "Toggle notification center",
+        "Notifications",
+        A::ToggleNotificationCenter,
+    ));
+    entries.push(PaletteEntry::new(
+        "Toggle Do Not Disturb",
+        "Notifications",
+        A::ToggleDoNotDisturb,
+    ));
+
+    for name in &ctx.widget_names {
+        entries.push(PaletteEntry::new(
+            format!("Show/hide {}", name),
+            "Widget",
+            A::ToggleWidget(name.clone()),
+        ));
+    }
+
+    for profile in &ctx.power_profiles {
+        entries.push(PaletteEntry::new(
+            format!("Power profile: {}", profile_label(profile)),
+            "Power",
+            A::SetPowerProfile(profile.clone()),
+        ));
+    }
+    if ctx.idle_inhibitor {
+        entries.push(PaletteEntry::new(
+            "Toggle idle inhibitor",
+            "Power",
+            A::ToggleIdleInhibitor,
+        ));
+    }
+
+    if ctx.night_light {
+        entries.push(PaletteEntry::new(
+            "Night light: on",
+            "Display",
+            A::NightLight(Some(true)),
+        ));
+        entries.push(PaletteEntry::new(
+            "Night light: off",
+            "Display",
+            A::NightLight(Some(false)),
+        ));
+        entries.push(PaletteEntry::new(
+            "Night light: follow schedule",
+            "Display",
+            A::NightLight(None),
+        ));
+    }
+
+    if ctx.has_widget("timer") {
+        entries.push(PaletteEntry::new(
+            "Timer: start/pause",
+            "Timer",
+            A::TimerToggle,
+        ));
+        entries.push(PaletteEntry::new(
+            "Timer: skip interval",
+            "Timer",
+            A::TimerSkip,
+        ));
+        entries.push(PaletteEntry::new("Timer: reset", "Timer", A::TimerReset));
+    }
+
+    if ctx.media {
+        entries.push(PaletteEntry::new(
+            "Media: play/pause",
+            "Media",
+            A::MediaPlayPause,
+        ));
+        entries.push(PaletteEntry::new(
+            "Media: next track",
+            "Media",
+            A::MediaNext,
+        ));
+        entries.push(PaletteEntry::new(
+            "Media: previous track",
+            "Media",
+            A::MediaPrevious,
+        ));
+    }
+
+    entries
+}
+
+/// Score `text` against `query` as a case-insensitive subsequence match.
+///
+/// Returns `None` when the query's characters don't all appear in order.
+/// Matches at word starts and runs of consecutive characters score higher;
+/// gaps score lower. Whitespace in the query is ignored.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (i, &c) in text.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if c != query[matched] {
+            continue;
+        }
+
+        score += 1;
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        match previous {
+            Some(p) if p + 1 == i => score += 5,
+            Some(p) => score -= (i - p - 1).min(3) as i32,
+            None => score -= i.min(3) as i32,
+        }
+        previous = Some(i);
+        matched += 1;
+    }
+
+    (matched == query.len()).then_some(score)
+}
+
+/// Entries matching `query`, best match first (ties keep display order).
+fn filter_entries<'a>(entries: &'a [PaletteEntry], query: &str) -> Vec<&'a PaletteEntry> {
+    let mut scored: Vec<(i32, &PaletteEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let title = fuzzy_score(query, &entry.title);
+            let category = fuzzy_score(query, entry.category).map(|s| s - 4);
+            title.max(category).map(|score| (score, entry))
+        })
+        .collect();
+    scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// The open palette and its click-catcher.
+struct PalettePanel {
+    window: ApplicationWindow,
+    click_catcher: ApplicationWindow,
+    search: SearchEntry,
+    list: GtkBox,
+    entries: Vec<PaletteEntry>,
+    /// Actions currently listed, in row order.
+    shown: RefCell<Vec<(Button, PaletteAction)>>,
+    selected: Cell<usize>,
+    /// Connector of the monitor the palette was opened on.
+    output: Option<String>,
+}
+
+impl PalettePanel {
+    fn new(
+        app: &Application,
+        monitor: &Monitor,
+        output: Option<String>,
+        entries: Vec<PaletteEntry>,
+    ) -> Rc<Self> {
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title("vibepanel command palette")
+            .decorated(false)
+            .resizable(false)
+            .build();
+        window.add_css_class(palette::WINDOW);
+
+        // Anchored to the bar edge only: horizontally centered
+        window.init_layer_shell();
+        window.set_layer(Layer::Top);
+        window.set_exclusive_zone(0);
+        window.set_anchor(bar_edge(), true);
+        window.set_margin(bar_edge(), calculate_popover_bar_margin());
+        window.set_keyboard_mode(popover_keyboard_mode());
+        window.set_monitor(Some(monitor));
+
+        let outer = GtkBox::new(Orientation::Vertical, 0);
+        outer.add_css_class(palette::PALETTE);
+        outer.add_css_class(surface::POPOVER);
+        outer.set_size_request(PALETTE_WIDTH, -1);
+        SurfaceStyleManager::global().apply_surface_styles(&outer, true);
+
+        let search = SearchEntry::new();
+        search.set_placeholder_text(Some("Run an action"));
+        search.add_css_class(palette::SEARCH);
+        outer.append(&search);
+
+        let list = GtkBox::new(Orientation::Vertical, 0);
+        list.add_css_class(palette::LIST);
+        outer.append(&list);
+
+        window.set_child(Some(&outer));
+
+        let click_catcher = create_click_catcher(app, calculate_bar_exclusive_zone(), || {
+            CommandPalette::global().close();
+        });
+        click_catcher.set_monitor(Some(monitor));
+
+        let panel = Rc::new(Self {
+            window,
+            click_catcher,
+            search,
+            list,
+            entries,
+            shown: RefCell::new(Vec::new()),
+            selected: Cell::new(0),
+            output,
+        });
+
+        setup_esc_handler(&panel.window, || CommandPalette::global().close());
+
+        // Escape in the search entry clears it first, then closes the palette
+        panel.search.connect_stop_search(|entry| {
+            if entry.text().is_empty() {
+                CommandPalette::global().close();
+            } else {
+                entry.set_text("");
+            }
+        });
+
+        {
+            let panel_weak = Rc::downgrade(&panel);
+            panel.search.connect_search_changed(move |_| {
+                if let Some(panel) = panel_weak.upgrade() {
+                    panel.selected.set(0);
+                    panel.refresh();
+                }
+            });
+        }
+
+        {
+            let panel_weak = Rc::downgrade(&panel);
+            panel.search.connect_activate(move |_| {
+                if let Some(panel) = panel_weak.upgrade() {
+                    panel.run_selected();
+                }
+            });
+        }
+
+        // Arrow keys move the selection while typing continues in the entry
+        let key_controller = EventControllerKey::new();
+        key_controller.set_propagation_phase(PropagationPhase::Capture);
+        let panel_weak = Rc::downgrade(&panel);
+        key_controller.connect_key_pressed(move |_, keyval, _, _| {
+            let Some(panel) = panel_weak.upgrade() else {
+                return glib::Propagation::Proceed;
+            };
+            match keyval {
+                gdk::Key::Up => panel.move_selection(-1),
+                gdk::Key::Down => panel.move_selection(1),
+                _ => return glib::Propagation::Proceed,
+            }
+            glib::Propagation::Stop
+        });
+        panel.search.add_controller(key_controller);
+
+        panel.refresh();
+        panel
+    }
+
+    fn show(&self) {
+        self.click_catcher.set_visible(true);
+        self.window.present();
+        self.search.grab_focus();
+    }
+
+    /// Rebuild the rows for the current query.
+    fn refresh(self: &Rc<Self>) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        let query = self.search.text();
+        let matches = filter_entries(&self.entries, &query);
+        let mut shown = Vec::new();
+        for entry in matches.into_iter().take(MAX_RESULTS) {
+            let row = build_row(entry);
+            let panel_weak = Rc::downgrade(self);
+            let action = entry.action.clone();
+            row.connect_clicked(move |_| {
+                if let Some(panel) = panel_weak.upgrade() {
+                    panel.run(action.clone());
+                }
+            });
+            self.list.append(&row);
+            shown.push((row, entry.action.clone()));
+        }
+
+        if shown.is_empty() {
+            let empty = Label::new(Some("No matching actions"));
+            empty.add_css_class(palette::EMPTY);
+            empty.add_css_class(color::MUTED);
+            self.list.append(&empty);
+        }
+
+        *self.shown.borrow_mut() = shown;
+        SurfaceStyleManager::global().apply_pango_attrs_all(&self.list);
+        self.update_selection();
+    }
+
+    fn move_selection(&self, delta: isize) {
+        let count = self.shown.borrow().len();
+        if count == 0 {
+            return;
+        }
+        let selected = (self.selected.get() as isize + delta).rem_euclid(count as isize);
+        self.selected.set(selected as usize);
+        self.update_selection();
+    }
+
+    fn update_selection(&self) {
+        for (i, (row, _)) in self.shown.borrow().iter().enumerate() {
+            if i == self.selected.get() {
+                row.add_css_class(palette::ROW_SELECTED);
+            } else {
+                row.remove_css_class(palette::ROW_SELECTED);
+            }
+        }
+    }
+
+    fn run_selected(&self) {
+        let action = self
+            .shown
+            .borrow()
+            .get(self.selected.get())
+            .map(|(_, action)| action.clone());
+        if let Some(action) = action {
+            self.run(action);
+        }
+    }
+
+    /// Close the palette, then run `action` once the window is gone so
+    /// panels it opens aren't dismissed along with it.
+    fn run(&self, action: PaletteAction) {
+        let output = self.output.clone();
+        CommandPalette::global().close();
+        glib::idle_add_local_once(move || {
+            debug!("Command palette: running {:?}", action);
+            if let Err(e) = action.run(output.as_deref()) {
+                warn!("Command palette: {:?} failed: {}", action, e);
+            }
+        });
+    }
+
+    fn destroy(&self) {
+        self.click_catcher.close();
+        self.window.close();
+    }
+}
+
+/// Build a clickable row: action title and its category.
+fn build_row(entry: &PaletteEntry) -> Button {
+    let content = GtkBox::new(Orientation::Horizontal, 8);
+
+    let title = Label::new(Some(&entry.title));
+    title.set_hexpand(true);
+    title.set_xalign(0.0);
+    title.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    content.append(&title);
+
+    let category = Label::new(Some(entry.category));
+    category.add_css_class(palette::CATEGORY);
+    category.add_css_class(color::MUTED);
+    category.set_valign(Align::Center);
+    content.append(&category);
+
+    let row = Button::new();
+    row.set_has_frame(false);
+    row.set_focusable(false);
+    row.set_focus_on_click(false);
+    row.add_css_class(palette::ROW);
+    row.set_child(Some(&content));
+    row
+}
+
+/// Process-wide command palette.
+pub struct CommandPalette {
+    panel: RefCell<Option<Rc<PalettePanel>>>,
+    /// ID returned from PopoverTracker while the palette is open.
+    tracker_id: Cell<Option<PopoverId>>,
+}
+
+impl CommandPalette {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            panel: RefCell::new(None),
+            tracker_id: Cell::new(None),
+        })
+    }
+
+    /// Get the global CommandPalette singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<CommandPalette> = CommandPalette::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Whether the palette is currently shown.
+    pub fn is_open(&self) -> bool {
+        self.panel.borrow().is_some()
+    }
+
+    /// Toggle the palette on `monitor`, listing actions for the bar there
+    /// (`output` is its connector and `widget_names` its widgets).
+    pub fn toggle(
+        &self,
+        app: &Application,
+        monitor: &Monitor,
+        output: Option<String>,
+        widget_names: Vec<String>,
+    ) {
+        if self.is_open() {
+            self.close();
+            return;
+        }
+
+        // Dismiss any other active popup before opening
+        PopoverTracker::global().dismiss_active();
+
+        let entries = palette_entries(&PaletteContext::current(widget_names));
+        let panel = PalettePanel::new(app, monitor, output, entries);
+        panel.show();
+        *self.panel.borrow_mut() = Some(panel);
+
+        let id = PopoverTracker::global().set_active(Rc::new(CommandPaletteDismissible));
+        self.tracker_id.set(Some(id));
+    }
+
+    /// Close the palette if it is open.
+    pub fn close(&self) {
+        self.hide_panel();
+        if let Some(id) = self.tracker_id.take() {
+            PopoverTracker::global().clear_if_active(id);
+        }
+    }
+
+    fn hide_panel(&self) {
+        // Release the borrow before destroying: closing the window may
+        // re-enter through the click-catcher or ESC handlers.
+        let panel = self.panel.borrow_mut().take();
+        if let Some(panel) = panel {
+            panel.destroy();
+        }
+    }
+}
+
+/// Adapter so other popups can dismiss the command palette when opening.
+struct CommandPaletteDismissible;
+
+impl Dismissible for CommandPaletteDismissible {
+    fn dismiss(&self) {
+        let palette = CommandPalette::global();
+        palette.hide_panel();
+        palette.tracker_id.set(None);
+    }
+
+    fn is_visible(&self) -> bool {
+        CommandPalette::global().is_open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles<'a>(entries: &[&'a PaletteEntry]) -> Vec<&'a str> {
+        entries.iter().map(|e| e.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Reload config"), Some(0));
+        assert!(fuzzy_score("rld", "Reload config").is_some());
+        assert!(fuzzy_score("RELOAD", "Reload config").is_some());
+        assert_eq!(fuzzy_score("xyz", "Reload config"), None);
+        // Order matters
+        assert_eq!(fuzzy_score("dr", "Reload"), None);
+
+        // Word starts and consecutive runs beat scattered matches
+        let initials = fuzzy_score("tnc", "Toggle notification center").unwrap();
+        let scattered = fuzzy_score("tnc", "Stationary cat").unwrap();
+        assert!(initials > scattered);
+        let prefix = fuzzy_score("rel", "Reload config").unwrap();
+        let spread = fuzzy_score("rel", "Run media player").unwrap();
+        assert!(prefix > spread);
+    }
+
+    #[test]
+    fn test_palette_entries_follow_context() {
+        let bare = palette_entries(&PaletteContext::default());
+        assert_eq!(
+            bare.iter().map(|e| &e.action).collect::<Vec<_>>(),
+            vec![
+                &PaletteAction::Reload,
+                &PaletteAction::ToggleNotificationCenter,
+                &PaletteAction::ToggleDoNotDisturb,
+            ]
+        );
+
+        let ctx = PaletteContext {
+            widget_names: vec!["quick_settings".to_string(), "timer".to_string()],
+            power_profiles: vec!["power-saver".to_string(), "balanced".to_string()],
+            ..PaletteContext::default()
+        };
+        let entries = palette_entries(&ctx);
+        let find = |title: &str| entries.iter().find(|e| e.title == title).map(|e| &e.action);
+        assert_eq!(
+            find("Open Quick Settings"),
+            Some(&PaletteAction::OpenQuickSettings)
+        );
+        assert_eq!(
+            find("Show/hide timer"),
+            Some(&PaletteAction::ToggleWidget("timer".to_string()))
+        );
+        assert_eq!(
+            find("Power profile: Power Saver"),
+            Some(&PaletteAction::SetPowerProfile("power-saver".to_string()))
+        );
+        assert_eq!(
+            find("Timer: skip interval"),
+            Some(&PaletteAction::TimerSkip)
+        );
+        assert_eq!(find("Media: next track"), None);
+    }
+
+    #[test]
+    fn test_filter_entries_ranks_matches() {
+        let ctx = PaletteContext {
+            widget_names: vec!["cpu".to_string(), "clock".to_string()],
+            media: true,
+            ..PaletteContext::default()
+        };
+        let entries = palette_entries(&ctx);
+
+        assert_eq!(filter_entries(&entries, "").len(), entries.len());
+        assert_eq!(
+            titles(&filter_entries(&entries, "hide cpu")),
+            vec!["Show/hide cpu"]
+        );
+        // Category matches count too, below title matches
+        assert_eq!(
+            titles(&filter_entries(&entries, "media")),
+            vec![
+                "Media: play/pause",
+                "Media: next track",
+                "Media: previous track"
+            ]
+        );
+        assert_eq!(
+            titles(&filter_entries(&entries, "next"))[0],
+            "Media: next track"
+        );
+        assert!(filter_entries(&entries, "qqq").is_empty());
+    }
+}
@@ -4,6 +4,13 @@
 //! canonical snapshots; this widget subscribes to those snapshots and renders
 //! icon/text/CSS/tooltip accordingly.
 //!
+//! Configuration options:
+//! - `show_icon`: Whether to show the icon (default: true)
+//! - `show_percentage`: Whether to show the usage percentage (default: true)
+//! - `interval`: Seconds between updates (default: 3, minimum 1)
+//! - `source`: "local", or "ssh:<host>" to show another machine's CPU via
+//!   `RemoteStatsService` (default: "local")
+//!
 //! Uses:
 //! - `IconsService` (via BaseWidget) for themed CPU icon
 //! - `TooltipManager` for styled tooltips
@@ -14,12 +21,17 @@ use gtk4::prelude::*;
 use vibepanel_core::config::WidgetEntry;
 
 use crate::services::icons::IconHandle;
-use crate::services::system::{SystemService, SystemSnapshot};
+use crate::services::remote_stats::{RemoteStatsService, RemoteStatsSnapshot};
+use crate::services::system::{
+    DEFAULT_POLL_INTERVAL_SECS, MIN_POLL_INTERVAL_SECS, SystemService, SystemSnapshot,
+};
 use crate::services::tooltip::TooltipManager;
 use crate::styles::{class, widget};
-use crate::widgets::base::BaseWidget;
+use crate::widgets::base::{BarWidget, BaseWidget, PopoverSection};
 use crate::widgets::system_popover::SystemPopoverBinding;
-use crate::widgets::{WidgetConfig, warn_unknown_options};
+use crate::widgets::{
+    WidgetConfig, parse_interval, parse_stats_source, stats_title, update, warn_unknown_options,
+};
 
 /// Default configuration values
 const DEFAULT_SHOW_ICON: bool = true;
@@ -32,11 +44,19 @@ pub struct CpuConfig {
     pub show_icon: bool,
     /// Whether to show the CPU usage percentage.
     pub show_percentage: bool,
+    /// Seconds between updates.
+    pub interval: u32,
+    /// Host to read stats from over SSH; `None` for this machine.
+    pub source: Option<String>,
 }
 
 impl WidgetConfig for CpuConfig {
     fn from_entry(entry: &WidgetEntry) -> Self {
-        warn_unknown_options("cpu", entry, &["show_icon", "show_percentage"]);
+        warn_unknown_options(
+            "cpu",
+            entry,
+            &["show_icon", "show_percentage", "interval", "source"],
+        );
 
         let show_icon = entry
             .options
@@ -50,9 +70,21 @@ impl WidgetConfig for CpuConfig {
             .and_then(|v| v.as_bool())
             .unwrap_or(DEFAULT_SHOW_PERCENTAGE);
 
+        let interval = parse_interval(
+            "cpu",
+            |key| entry.options.get(key),
+            None,
+            DEFAULT_POLL_INTERVAL_SECS as u64,
+            MIN_POLL_INTERVAL_SECS as u64,
+        ) as u32;
+
+        let source = parse_stats_source("cpu", entry);
+
         Self {
             show_icon,
             show_percentage,
+            interval,
+            source,
         }
     }
 }
@@ -62,6 +94,8 @@ impl Default for CpuConfig {
         Self {
             show_icon: DEFAULT_SHOW_ICON,
             show_percentage: DEFAULT_SHOW_PERCENTAGE,
+            interval: DEFAULT_POLL_INTERVAL_SECS,
+            source: None,
         }
     }
 }
@@ -110,7 +144,6 @@ impl CpuWidget {
             .percentage_label
             .set_visible(widget.config.show_percentage);
 
-        let system_service = SystemService::global();
         {
             let container = widget.base.widget().clone();
             let icon_handle = widget.icon_handle.clone();
@@ -118,19 +151,44 @@ impl CpuWidget {
             let show_icon = widget.config.show_icon;
             let show_percentage = widget.config.show_percentage;
             let popover_binding = widget.popover_binding.clone();
-
-            system_service.connect(move |snapshot: &SystemSnapshot| {
-                update_cpu_widget(
-                    &container,
-                    &icon_handle,
-                    &percentage_label,
-                    show_icon,
-                    show_percentage,
-                    snapshot,
-                );
-
-                popover_binding.update_if_open(snapshot);
-            });
+            let title = stats_title("CPU", widget.config.source.as_deref());
+
+            let update = {
+                let container = container.clone();
+                let title = title.clone();
+                move |snapshot: &SystemSnapshot| {
+                    update_cpu_widget(
+                        &container,
+                        &icon_handle,
+                        &percentage_label,
+                        show_icon,
+                        show_percentage,
+                        &title,
+                        snapshot,
+                    );
+
+                    popover_binding.update_if_open(snapshot);
+                }
+            };
+
+            match widget.config.source {
+                Some(ref host) => {
+                    RemoteStatsService::global().connect(
+                        host,
+                        widget.config.interval,
+                        move |remote: &RemoteStatsSnapshot| {
+                            update(&remote.system);
+                            if let Some(ref err) = remote.error {
+                                TooltipManager::global()
+                                    .set_styled_tooltip(&container, &format!("{}: {}", title, err));
+                            }
+                        },
+                    );
+                }
+                None => {
+                    SystemService::global().connect_every(widget.config.interval, update);
+                }
+            }
         }
 
         widget
@@ -142,6 +200,20 @@ impl CpuWidget {
     }
 }
 
+impl BarWidget for CpuWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+
+    fn popover_section(&self) -> PopoverSection {
+        // CPU and memory widgets open the same system popover
+        self.base.popover_section().with_key(format!(
+            "system:{}",
+            self.config.source.as_deref().unwrap_or("local")
+        ))
+    }
+}
+
 /// Update the CPU widget visuals from a system snapshot.
 fn update_cpu_widget(
     container: &gtk4::Box,
@@ -149,6 +221,7 @@ fn update_cpu_widget(
     percentage_label: &Label,
     show_icon: bool,
     show_percentage: bool,
+    title: &str,
     snapshot: &SystemSnapshot,
 ) {
     if !snapshot.available {
@@ -156,20 +229,20 @@ fn update_cpu_widget(
             icon_handle.widget().set_visible(true);
         }
         if show_percentage {
-            percentage_label.set_label("?");
+            update::set_label_text(percentage_label, "?");
             percentage_label.set_visible(true);
         }
 
         let tooltip_manager = TooltipManager::global();
-        tooltip_manager.set_styled_tooltip(container, "CPU: Service unavailable");
+        tooltip_manager.set_styled_tooltip(container, &format!("{}: Service unavailable", title));
         return;
     }
 
     if snapshot.is_cpu_high() {
-        container.add_css_class(widget::CPU_HIGH);
+        update::set_class(container, widget::CPU_HIGH, true);
         icon_handle.add_css_class(widget::CPU_HIGH);
     } else {
-        container.remove_css_class(widget::CPU_HIGH);
+        update::set_class(container, widget::CPU_HIGH, false);
         icon_handle.remove_css_class(widget::CPU_HIGH);
     }
 
@@ -181,15 +254,15 @@ fn update_cpu_widget(
 
     if show_percentage {
         let text = format!("{:.0}%", snapshot.cpu_usage);
-        percentage_label.set_label(&text);
+        update::set_label_text(percentage_label, &text);
         percentage_label.set_visible(true);
     } else {
         percentage_label.set_visible(false);
     }
 
     let tooltip = format!(
-        "CPU: {:.1}%\nCores: {}",
-        snapshot.cpu_usage, snapshot.cpu_core_count
+        "{}: {:.1}%\nCores: {}",
+        title, snapshot.cpu_usage, snapshot.cpu_core_count
     );
     let tooltip_manager = TooltipManager::global();
     tooltip_manager.set_styled_tooltip(container, &tooltip);
@@ -208,6 +281,8 @@ mod tests {
         let config = CpuConfig::from_entry(&entry);
         assert!(config.show_icon);
         assert!(config.show_percentage);
+        assert_eq!(config.interval, DEFAULT_POLL_INTERVAL_SECS);
+        assert_eq!(config.source, None);
     }
 
     #[test]
@@ -224,4 +299,56 @@ mod tests {
         assert!(!config.show_icon);
         assert!(config.show_percentage);
     }
+
+    #[test]
+    fn test_cpu_config_interval() {
+        let mut options = std::collections::HashMap::new();
+        options.insert("interval".to_string(), toml::Value::Integer(10));
+        let entry = WidgetEntry {
+            name: "cpu".to_string(),
+            options,
+        };
+        assert_eq!(CpuConfig::from_entry(&entry).interval, 10);
+
+        // Below the minimum is raised to it
+        let mut options = std::collections::HashMap::new();
+        options.insert("interval".to_string(), toml::Value::Integer(0));
+        let entry = WidgetEntry {
+            name: "cpu".to_string(),
+            options,
+        };
+        assert_eq!(
+            CpuConfig::from_entry(&entry).interval,
+            MIN_POLL_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn test_cpu_config_source() {
+        let mut options = std::collections::HashMap::new();
+        options.insert(
+            "source".to_string(),
+            toml::Value::String("ssh:server1".to_string()),
+        );
+        let entry = WidgetEntry {
+            name: "cpu".to_string(),
+            options,
+        };
+        assert_eq!(
+            CpuConfig::from_entry(&entry).source.as_deref(),
+            Some("server1")
+        );
+
+        // An invalid source falls back to local stats
+        let mut options = std::collections::HashMap::new();
+        options.insert(
+            "source".to_string(),
+            toml::Value::String("server1".to_string()),
+        );
+        let entry = WidgetEntry {
+            name: "cpu".to_string(),
+            options,
+        };
+        assert_eq!(CpuConfig::from_entry(&entry).source, None);
+    }
 }
@@ -0,0 +1,14 @@
+//! Backup widget CSS.
+
+/// Return backup CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== BACKUP ===== */
+
+.backup.backup-overdue .backup-icon,
+.backup.backup-overdue .backup-age,
+.backup.backup-error .backup-icon {
+    color: var(--color-state-urgent);
+}
+"#
+}
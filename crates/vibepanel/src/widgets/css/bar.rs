@@ -3,11 +3,18 @@
 //! Note: This module requires config values for screen_margin and spacing,
 //! so it returns a formatted String rather than a static str.
 
+use vibepanel_core::config::BarConfig;
+
 use super::WIDGET_BG_WITH_OPACITY;
 
 /// Return bar CSS with config values interpolated.
-pub fn css(screen_margin: u32, spacing: u32) -> String {
+pub fn css(bar: &BarConfig) -> String {
     let widget_bg = WIDGET_BG_WITH_OPACITY;
+    let screen_margin = bar.screen_margin;
+    let left_spacing = bar.section_widget_spacing("left");
+    let right_spacing = bar.section_widget_spacing("right");
+    // Group items overlap their 10px side paddings by default
+    let group_item_offset = bar.group_spacing as i64 - 20;
     format!(
         r#"
 /* ===== BAR ===== */
@@ -20,7 +27,8 @@ pub fn css(screen_margin: u32, spacing: u32) -> String {
 /* Shell containers transparent */
 .bar-shell,
 .bar-shell-inner,
-.bar-margin-spacer {{
+.bar-margin-spacer,
+.bar-hot-edge {{
     background: transparent;
 }}
 
@@ -55,6 +63,12 @@ sectioned-bar.bar {{
     padding: 0;
 }}
 
+/* Placeholder shown while the bar hydrates - a faint empty island */
+.widget.widget-placeholder {{
+    min-width: var(--widget-height);
+    opacity: 0.5;
+}}
+
 /* Widget hover state - standalone clickable widgets */
 .widget.clickable:not(.widget-group):hover {{
     background-image: linear-gradient(var(--color-card-overlay-hover), var(--color-card-overlay-hover));
@@ -65,9 +79,10 @@ sectioned-bar.bar {{
     padding: var(--widget-padding-y) 10px;
 }}
 
-/* Pull non-first items left to overlap with previous item's right padding */
+/* Pull non-first items left to overlap with previous item's right padding,
+   leaving bar.group_spacing between them */
 .widget-group > .content > .widget-item:not(:first-child) {{
-    margin-left: -20px;
+    margin-left: {group_item_offset}px;
 }}
 
 /* Widget items inside groups - individual clickable hover targets */
@@ -76,6 +91,24 @@ sectioned-bar.bar {{
     border-radius: var(--radius-widget);
 }}
 
+/* Groups with a shared popover - the whole island is one hover target */
+.widget.widget-group.shared-popover:hover {{
+    background-image: linear-gradient(var(--color-card-overlay-hover), var(--color-card-overlay-hover));
+}}
+
+.widget-group.shared-popover > .content > .widget-item.clickable:hover {{
+    background-image: none;
+}}
+
+/* Widget highlighted in keyboard focus mode */
+.widget-item.keyboard-focus,
+.widget.widget-group.shared-popover.keyboard-focus {{
+    background-image: linear-gradient(var(--color-card-overlay-hover), var(--color-card-overlay-hover));
+    border-radius: var(--radius-widget);
+    outline: 2px solid var(--color-accent-primary);
+    outline-offset: -2px;
+}}
+
 /* Spacing between items inside widgets */
 .widget > .content > *:not(:last-child),
 .widget-group > .content .content > *:not(:last-child) {{
@@ -83,9 +116,12 @@ sectioned-bar.bar {{
 }}
 
 /* Section widget spacing via margins (Box spacing=0 to allow spacer to have no gaps) */
-.bar-section--left > *:not(:last-child):not(.spacer),
+.bar-section--left > *:not(:last-child):not(.spacer) {{
+    margin-right: {left_spacing}px;
+}}
+
 .bar-section--right > *:not(:last-child):not(.spacer) {{
-    margin-right: {spacing}px;
+    margin-right: {right_spacing}px;
 }}
 
 /* Spacer widget - no margins so it doesn't create extra gaps */
@@ -108,6 +144,17 @@ sectioned-bar.bar {{
     background-image: linear-gradient(var(--color-card-overlay-hover), var(--color-card-overlay-hover));
 }}
 
+/* Workspace grid (Wayfire) - compact numbered cells stacked in rows */
+.workspace-grid {{
+    font-size: 0.6em;
+}}
+
+.workspace-grid .workspace-indicator {{
+    padding: 0 2px;
+    min-height: 0;
+    margin: 1px;
+}}
+
 .workspace-indicator-minimal {{
     background-color: var(--color-foreground-faint);
 }}
@@ -116,6 +163,49 @@ sectioned-bar.bar {{
     color: var(--color-accent-text, #fff);
     background-color: var(--color-accent-primary);
 }}
+
+/* Workspace with a window playing sound */
+.icon-root.workspace-audio {{
+    font-size: 8px;
+    -gtk-icon-size: 8px;
+    color: var(--color-accent-primary);
+    margin-top: -3px;
+    margin-right: -3px;
+}}
+
+/* ===== PREVIEW (vibepanel --preview) ===== */
+
+.bar-window.preview sectioned-bar.bar {{
+    box-shadow: inset 0 0 0 2px var(--color-accent-primary);
+}}
+
+.bar-preview-label {{
+    margin-right: 6px;
+    padding: 2px 8px;
+    border-radius: var(--radius-widget);
+    background: var(--color-accent-primary);
+    color: var(--color-accent-text, #fff);
+    font-weight: bold;
+}}
+
+/* ===== TEXT MODE (bar.mode = "text") ===== */
+
+/* Wider widgets and full-contrast text instead of muted/faint shades */
+.bar-window.text-mode .widget {{
+    padding: var(--widget-padding-y) 14px;
+}}
+
+.bar-window.text-mode label {{
+    color: var(--color-foreground-primary);
+}}
+
+.bar-window.text-mode .icon-text-label {{
+    font-weight: bold;
+}}
+
+.bar-window.text-mode .workspace-indicator.active label {{
+    color: var(--color-accent-text, #fff);
+}}
 "#
     )
 }
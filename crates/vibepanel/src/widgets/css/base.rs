@@ -35,6 +35,45 @@ window.layer-shell-click-catcher {{
     font-size: var(--icon-size);
 }}
 
+/* ===== WIDGET BADGES ===== */
+/* Count or dot overlaid on a widget icon (BaseWidget::add_badge) */
+.widget-badge {{
+    min-width: 12px;
+    min-height: 12px;
+    padding: 0 2px;
+    border-radius: var(--radius-round);
+    font-size: 9px;
+    font-weight: 700;
+    color: var(--color-accent-text, #fff);
+    background-color: var(--color-accent-primary);
+}}
+
+.widget-badge-dot {{
+    min-width: 8px;
+    min-height: 8px;
+    padding: 0;
+}}
+
+.widget-badge-top-right {{
+    margin-top: -2px;
+    margin-right: -4px;
+}}
+
+.widget-badge-top-left {{
+    margin-top: -2px;
+    margin-left: -4px;
+}}
+
+.widget-badge-bottom-right {{
+    margin-bottom: -2px;
+    margin-right: -4px;
+}}
+
+.widget-badge-bottom-left {{
+    margin-bottom: -2px;
+    margin-left: -4px;
+}}
+
 /* ===== NATIVE GTK TOOLTIPS ===== */
 /* Style GTK's native tooltips (used in popovers/windows where layer-shell tooltips don't work) */
 tooltip,
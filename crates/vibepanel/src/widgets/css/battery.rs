@@ -29,6 +29,14 @@ pub fn css() -> &'static str {
     font-size: var(--font-size-sm);
 }
 
+.battery-popover-device-state {
+    font-size: var(--font-size-sm);
+}
+
+.battery-popover-device-percent {
+    font-weight: 500;
+}
+
 .battery-popover-profile-button {
     font-size: var(--font-size-sm);
     border-radius: var(--radius-widget);
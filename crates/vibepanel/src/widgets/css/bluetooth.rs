@@ -0,0 +1,21 @@
+//! Bluetooth widget CSS.
+
+/// Return Bluetooth CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== BLUETOOTH ===== */
+
+.bluetooth.bluetooth-off .bluetooth-icon {
+    color: var(--color-foreground-disabled);
+}
+
+.bluetooth.bluetooth-connected .bluetooth-icon {
+    color: var(--color-accent-primary);
+}
+
+/* Bluetooth popover */
+.bluetooth-popover {
+    min-width: 300px;
+}
+"#
+}
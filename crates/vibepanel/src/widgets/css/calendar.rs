@@ -43,6 +43,13 @@ calendar.view grid *:selected:not(.today) {
     box-shadow: none;
 }
 
+/* Selected day is only highlighted once moved with the keyboard */
+calendar.view.keyboard-nav grid label.day-number:selected:not(.today) {
+    background: transparent;
+    box-shadow: inset 0 0 0 1px var(--color-accent-primary);
+    border-radius: var(--radius-widget);
+}
+
 calendar.view grid label.day-number {
     margin: 1px 2px;
     min-width: calc(var(--font-size) * 1.5);
@@ -55,5 +62,96 @@ calendar.view grid label.day-number {
     margin-left: 20px; /* Align with week numbers column */
     margin-top: 16px; /* Align vertically with day headers (M T W...) */
 }
+
+/* Header: month/year picker and today button, aligned with the nav buttons */
+.calendar-header-button,
+.calendar-today-button {
+    min-height: 28px;
+    padding: 0 8px;
+    margin-top: -8px;
+}
+
+.calendar-today-button {
+    font-size: var(--font-size-sm);
+    color: var(--color-foreground-muted);
+}
+
+.calendar-today-button:disabled {
+    opacity: 0.4;
+}
+
+/* Year view: compact month grids, days shaded once they've passed */
+.calendar-year-view {
+    margin-top: 8px;
+}
+
+.calendar-year-month {
+    padding: 4px;
+}
+
+.calendar-year-month.current .calendar-year-month-label {
+    color: var(--color-accent-primary);
+}
+
+.calendar-year-month-label {
+    font-size: var(--font-size-xs);
+    color: var(--color-foreground-muted);
+}
+
+.calendar-year-day {
+    min-width: 5px;
+    min-height: 5px;
+    border-radius: 1px;
+    background: var(--color-card-overlay);
+}
+
+.calendar-year-day.weekend {
+    opacity: 0.6;
+}
+
+.calendar-year-day.past {
+    background: color-mix(in srgb, var(--color-accent-primary) 45%, transparent);
+}
+
+.calendar-year-day.today {
+    background: var(--color-accent-primary);
+}
+
+/* Days with events (marked days get :checked) show a dot under the number */
+calendar.view grid label.day-number:checked {
+    background-image: radial-gradient(circle at 50% 88%, var(--color-accent-primary) 1.5px, transparent 2px);
+}
+
+calendar.view grid label.today:checked {
+    background-image: radial-gradient(circle at 50% 88%, var(--color-accent-text, #fff) 1.5px, transparent 2px);
+}
+
+/* Event list under the month view */
+.calendar-events {
+    margin-top: 8px;
+    padding-top: 8px;
+    border-top: 1px solid var(--color-card-overlay);
+}
+
+.calendar-events-title {
+    font-weight: 600;
+    margin-bottom: 2px;
+}
+
+.calendar-event-time {
+    min-width: 56px;
+    font-size: var(--font-size-sm);
+}
+
+/* Other timezones at the bottom */
+.calendar-world-clocks {
+    margin-top: 8px;
+    padding-top: 8px;
+    border-top: 1px solid var(--color-card-overlay);
+}
+
+.calendar-world-clock-time {
+    font-feature-settings: "tnum";
+}
 "#
 }
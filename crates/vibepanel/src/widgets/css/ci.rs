@@ -0,0 +1,47 @@
+//! CI widget CSS.
+
+/// Return CI CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== CI ===== */
+
+.ci.ci-passing .ci-icon {
+    color: var(--color-state-success);
+}
+
+.ci.ci-failing .ci-icon,
+.ci.ci-failing .ci-label {
+    color: var(--color-state-urgent);
+}
+
+/* CI popover */
+.ci-popover {
+    min-width: 320px;
+}
+
+.ci-row {
+    padding: 4px 6px;
+    border-radius: var(--radius-widget);
+}
+
+.ci-row:hover {
+    background-color: color-mix(in srgb, var(--color-foreground-primary) 8%, transparent);
+}
+
+.ci-row-status {
+    font-size: var(--font-size-sm);
+}
+
+.ci-row-success .ci-row-status {
+    color: var(--color-state-success);
+}
+
+.ci-row-failure .ci-row-status {
+    color: var(--color-state-urgent);
+}
+
+.ci-row-running .ci-row-status {
+    color: var(--color-state-warning);
+}
+"#
+}
@@ -0,0 +1,34 @@
+//! Command palette CSS.
+
+/// Return command palette CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Command Palette ===== */
+
+window.command-palette-window {
+    background: transparent;
+}
+
+.command-palette-list {
+    margin-top: 4px;
+}
+
+.command-palette-row {
+    padding: 6px 8px;
+    border-radius: var(--radius-widget);
+}
+
+.command-palette-row:hover,
+.command-palette-row-selected {
+    background: var(--color-card-overlay-hover);
+}
+
+.command-palette-category {
+    font-size: var(--font-size-sm);
+}
+
+.command-palette-empty {
+    padding: 12px 8px;
+}
+"#
+}
@@ -0,0 +1,14 @@
+//! Custom command widget CSS.
+
+/// Return custom widget CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Custom ===== */
+
+/* Last run failed; the previous output is still shown */
+.custom.custom-error .custom-icon,
+.custom.custom-error .custom-label {
+    color: var(--color-state-warning);
+}
+"#
+}
@@ -0,0 +1,38 @@
+//! Git widget CSS.
+
+/// Return git CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== GIT ===== */
+
+.git.git-dirty .git-icon,
+.git.git-dirty .git-label {
+    color: var(--color-state-warning);
+}
+
+.git.git-error .git-icon {
+    color: var(--color-state-urgent);
+}
+
+/* Git popover */
+.git-popover {
+    min-width: 300px;
+}
+
+.git-row {
+    padding: 4px 0;
+}
+
+.git-row-status {
+    font-size: var(--font-size-sm);
+}
+
+.git-row-dirty .git-row-name {
+    color: var(--color-state-warning);
+}
+
+.git-row-error .git-row-status {
+    color: var(--color-state-urgent);
+}
+"#
+}
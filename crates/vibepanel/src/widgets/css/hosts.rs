@@ -0,0 +1,41 @@
+//! Hosts widget CSS.
+
+/// Return hosts CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Hosts ===== */
+
+.hosts-dot {
+    min-width: 8px;
+    min-height: 8px;
+    border-radius: 50%;
+    background-color: color-mix(in srgb, var(--color-foreground-primary) 35%, transparent);
+}
+
+.hosts-dot.hosts-dot-up {
+    background-color: var(--color-state-success);
+}
+
+.hosts-dot.hosts-dot-down {
+    background-color: var(--color-state-urgent);
+}
+
+/* Hosts popover */
+.hosts-popover {
+    min-width: 300px;
+}
+
+.hosts-row {
+    padding: 4px 6px;
+    border-radius: var(--radius-widget);
+}
+
+.hosts-row:hover {
+    background-color: color-mix(in srgb, var(--color-foreground-primary) 8%, transparent);
+}
+
+.hosts-row-status {
+    font-size: var(--font-size-sm);
+}
+"#
+}
@@ -0,0 +1,25 @@
+//! Idle inhibitor widget CSS.
+
+/// Return idle inhibitor CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Idle Inhibitor ===== */
+
+.idle-inhibitor.idle-inhibitor-active .idle-inhibitor-icon {
+    color: var(--color-accent-primary);
+}
+
+.idle-inhibitor.service-unavailable .idle-inhibitor-icon {
+    color: var(--color-foreground-disabled);
+}
+
+/* Idle inhibitor popover */
+.idle-inhibitor-popover {
+    min-width: 260px;
+}
+
+.idle-inhibitor-duration {
+    padding: 6px 8px;
+}
+"#
+}
@@ -0,0 +1,29 @@
+//! Jobs widget CSS.
+
+/// Return jobs CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== JOBS ===== */
+
+.jobs.jobs-failed .jobs-icon {
+    color: var(--color-state-urgent);
+}
+
+/* Jobs popover */
+.jobs-popover {
+    min-width: 280px;
+}
+
+.jobs-row {
+    padding: 4px 0;
+}
+
+.jobs-row-status {
+    font-size: var(--font-size-sm);
+}
+
+.jobs-row-failed .jobs-row-status {
+    color: var(--color-state-urgent);
+}
+"#
+}
@@ -70,7 +70,8 @@ pub fn css() -> &'static str {
 
 /* Override base popover icon button size for denser media layout */
 .media-popout-btn,
-.media-player-selector-btn {
+.media-player-selector-btn,
+.media-popover-header .media-love-btn {
     min-width: 20px;
     min-height: 20px;
     margin-top: 0;
@@ -81,6 +82,15 @@ pub fn css() -> &'static str {
     margin-top: 2px;
 }
 
+/* Love button: filled accent heart when the track is loved */
+.media-love-btn.media-loved {
+    color: var(--color-accent-primary);
+}
+
+.media-love-btn.media-loved .material-symbol {
+    font-variation-settings: 'FILL' 1;
+}
+
 /* Player selector menu - extends qs-row-menu-content */
 .media-player-menu {
     font-family: var(--font-family);
@@ -12,25 +12,65 @@
 //! - `calendar` - Calendar widget styles
 //! - `quick_settings` - Quick settings panel, cards, rows
 //! - `battery` - Battery widget and popover
+//! - `jobs` - Jobs widget and popover
+//! - `backup` - Backup age widget states
+//! - `syncthing` - Syncthing widget and popover
+//! - `network` - Network status widget and popover
+//! - `bluetooth` - Bluetooth widget and device popover
+//! - `tailscale` - Tailscale widget and peer popover
+//! - `git` - Git repository status widget and popover
+//! - `ci` - CI build status widget and popover
+//! - `custom` - Custom command widget states
+//! - `taskbar` - Taskbar window buttons
+//! - `hosts` - Host reachability dots and popover
+//! - `updates` - Updates reboot badge and remote machines popover
+//! - `idle_inhibitor` - Idle inhibitor toggle and duration popover
+//! - `power_draw` - Power draw widget and domain popover
+//! - `power_profile` - Power profile indicator
+//! - `privacy` - Device-in-use indicators and popover
+//! - `temperature` - Temperature widget states and sensor popover
+//! - `timer` - Focus timer states
+//! - `weather` - Weather widget and forecast popover
 //! - `notifications` - Notification rows and toasts
 //! - `osd` - On-screen display overlays
+//! - `command_palette` - Command palette window and action rows
 //! - `media` - Media player widget
 //! - `system` - System info popover
 
 /// Widget background with opacity applied via `color-mix()`.
 pub const WIDGET_BG_WITH_OPACITY: &str = "color-mix(in srgb, var(--widget-background-color) var(--widget-background-opacity), transparent)";
 
+mod backup;
 mod bar;
 mod base;
 mod battery;
+mod bluetooth;
 mod buttons;
 mod calendar;
+mod ci;
+mod command_palette;
+mod custom;
+mod git;
+mod hosts;
+mod idle_inhibitor;
+mod jobs;
 mod media;
+mod network;
 mod notifications;
 mod osd;
+mod power_draw;
+mod power_profile;
+mod privacy;
 mod quick_settings;
+mod syncthing;
 mod system;
+mod tailscale;
+mod taskbar;
+mod temperature;
+mod timer;
 mod tray;
+mod updates;
+mod weather;
 
 use vibepanel_core::Config;
 
@@ -44,22 +84,39 @@ pub fn utility_css() -> String {
 
 /// Generate all widget CSS.
 pub fn widget_css(config: &Config) -> String {
-    let screen_margin = config.bar.screen_margin;
-    let spacing = config.bar.spacing;
-
     // Collect all CSS from submodules
-    let bar_css = bar::css(screen_margin, spacing);
+    let bar_css = bar::css(&config.bar);
     let tray_css = tray::css();
     let buttons_css = buttons::css();
     let calendar_css = calendar::css();
     let quick_settings_css = quick_settings::css();
     let battery_css = battery::css();
+    let jobs_css = jobs::css();
+    let backup_css = backup::css();
+    let syncthing_css = syncthing::css();
+    let network_css = network::css();
+    let bluetooth_css = bluetooth::css();
+    let tailscale_css = tailscale::css();
+    let git_css = git::css();
+    let ci_css = ci::css();
+    let custom_css = custom::css();
+    let taskbar_css = taskbar::css();
+    let hosts_css = hosts::css();
+    let updates_css = updates::css();
+    let idle_inhibitor_css = idle_inhibitor::css();
+    let power_draw_css = power_draw::css();
+    let power_profile_css = power_profile::css();
+    let privacy_css = privacy::css();
+    let temperature_css = temperature::css();
+    let timer_css = timer::css();
+    let weather_css = weather::css();
     let notifications_css = notifications::css();
     let osd_css = osd::css();
+    let command_palette_css = command_palette::css();
     let media_css = media::css();
     let system_css = system::css();
 
     format!(
-        "{bar_css}\n{tray_css}\n{buttons_css}\n{calendar_css}\n{quick_settings_css}\n{battery_css}\n{notifications_css}\n{osd_css}\n{media_css}\n{system_css}"
+        "{bar_css}\n{tray_css}\n{buttons_css}\n{calendar_css}\n{quick_settings_css}\n{battery_css}\n{jobs_css}\n{backup_css}\n{syncthing_css}\n{network_css}\n{bluetooth_css}\n{tailscale_css}\n{git_css}\n{ci_css}\n{custom_css}\n{taskbar_css}\n{hosts_css}\n{updates_css}\n{idle_inhibitor_css}\n{power_draw_css}\n{power_profile_css}\n{privacy_css}\n{temperature_css}\n{timer_css}\n{weather_css}\n{notifications_css}\n{osd_css}\n{command_palette_css}\n{media_css}\n{system_css}"
     )
 }
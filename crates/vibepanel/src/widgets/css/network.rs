@@ -0,0 +1,25 @@
+//! Network widget CSS.
+
+/// Return network CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== NETWORK ===== */
+
+.network.network-disconnected .network-icon {
+    color: var(--color-foreground-disabled);
+}
+
+/* Network popover */
+.network-popover {
+    min-width: 240px;
+}
+
+.network-name {
+    font-weight: 600;
+}
+
+.network-detail-value {
+    font-size: var(--font-size-sm);
+}
+"#
+}
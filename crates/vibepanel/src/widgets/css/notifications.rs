@@ -15,20 +15,12 @@ pub fn css() -> &'static str {
     color: var(--color-foreground-disabled);
 }
 
-/* Badge indicator dot */
-.notification-badge {
+/* Badge dot sits inside the bell's corner rather than overhanging it */
+.notification-badge.widget-badge-dot.widget-badge-top-right {
     margin-right: 2px;
     margin-top: 3px;
 }
 
-.notification-badge-dot {
-    min-width: 8px;
-    min-height: 8px;
-    padding: 0;
-    border-radius: var(--radius-round);
-    background-color: var(--color-accent-primary);
-}
-
 /* Shared icon styling (row + toast) */
 .notification-row-icon,
 .notification-toast-icon {
@@ -137,6 +129,36 @@ pub fn css() -> &'static str {
     padding: 8px 0 0 0;
 }
 
+/* === Notification center panel === */
+
+window.notification-center-window {
+    background: transparent;
+}
+
+.notification-center .notification-header {
+    padding: 0;
+}
+
+.notification-center-dnd-label {
+    font-size: var(--font-size-sm);
+}
+
+.notification-group {
+    margin-bottom: 8px;
+}
+
+.notification-group-header {
+    padding: 0 2px 4px 2px;
+}
+
+.notification-group-title {
+    font-weight: bold;
+}
+
+.notification-group-count {
+    font-size: var(--font-size-sm);
+}
+
 /* Empty state */
 .notification-empty {
     padding: 32px 16px;
@@ -0,0 +1,31 @@
+//! Power draw widget CSS.
+
+/// Return power draw CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Power draw ===== */
+
+.power-draw-label {
+    font-feature-settings: "tnum";
+}
+
+/* Power draw popover */
+.power-draw-popover {
+    min-width: 240px;
+}
+
+.power-draw-row {
+    padding: 4px 0;
+}
+
+.power-draw-row-value {
+    font-feature-settings: "tnum";
+}
+
+/* Cores and uncore are part of the package figure */
+.power-draw-row-part .power-draw-row-name {
+    padding-left: 12px;
+    opacity: 0.75;
+}
+"#
+}
@@ -0,0 +1,16 @@
+//! Power profile widget CSS.
+
+/// Return power profile CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Power Profile ===== */
+
+.power-profile.power-profile-performance .power-profile-icon {
+    color: var(--color-state-warning);
+}
+
+.power-profile.power-profile-power-saver .power-profile-icon {
+    color: var(--color-state-success);
+}
+"#
+}
@@ -0,0 +1,26 @@
+//! Privacy widget CSS.
+
+/// Return privacy CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Privacy ===== */
+
+.privacy .privacy-icon {
+    color: var(--color-state-urgent);
+}
+
+/* Privacy popover */
+.privacy-popover {
+    min-width: 260px;
+}
+
+.privacy-section-title {
+    font-weight: bold;
+    margin-top: 4px;
+}
+
+.privacy-row {
+    padding: 2px 0;
+}
+"#
+}
@@ -204,6 +204,19 @@ window.quick-settings-window {
     border-radius: var(--radius-pill);
 }
 
+/* Flatpak update progress - accent fill like the system popover bars */
+.qs-updates-flatpak-progress trough {
+    background-color: var(--color-slider-track);
+    border-radius: var(--slider-radius);
+    min-height: var(--slider-height);
+}
+
+.qs-updates-flatpak-progress trough progress {
+    background-color: var(--color-accent-slider, var(--color-accent-primary));
+    border-radius: var(--slider-radius);
+    min-height: var(--slider-height);
+}
+
 /* Row menu content */
 .qs-row-menu-content {
     font-family: var(--font-family);
@@ -211,6 +224,15 @@ window.quick-settings-window {
     border-radius: var(--radius-surface);
 }
 
+/* Wi-Fi share popover */
+.qs-wifi-share {
+    padding: 12px;
+}
+
+.qs-wifi-share-code {
+    border-radius: var(--radius-widget);
+}
+
 /* Row hamburger menu button */
 .qs-row-menu-button {
     min-width: calc(var(--icon-size) * 2);
@@ -372,6 +394,11 @@ window.quick-settings-window {
     border-radius: var(--radius-widget);
 }
 
+/* Inhibitor lock confirmation popover */
+.qs-power-inhibitors button {
+    padding: 4px 10px;
+}
+
 /* ===== BLUETOOTH AUTH PROMPT ===== */
 
 /* Auth prompt container - inline under device row */
@@ -0,0 +1,41 @@
+//! Syncthing widget CSS.
+
+/// Return Syncthing CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== SYNCTHING ===== */
+
+.syncthing.syncthing-syncing .syncthing-icon {
+    color: var(--color-accent-primary);
+}
+
+.syncthing.syncthing-error .syncthing-icon {
+    color: var(--color-state-urgent);
+}
+
+.syncthing.syncthing-disconnected .syncthing-icon {
+    opacity: 0.5;
+}
+
+/* Syncthing popover */
+.syncthing-popover {
+    min-width: 280px;
+}
+
+.syncthing-rates {
+    font-size: var(--font-size-sm);
+}
+
+.syncthing-row {
+    padding: 4px 0;
+}
+
+.syncthing-row-status {
+    font-size: var(--font-size-sm);
+}
+
+.syncthing-row-error .syncthing-row-status {
+    color: var(--color-state-urgent);
+}
+"#
+}
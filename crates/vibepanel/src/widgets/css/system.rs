@@ -64,5 +64,9 @@ pub fn css() -> &'static str {
 .system-network-icon {
     font-size: 0.9em;
 }
+
+.system-address-row {
+    margin-left: 8px;
+}
 "#
 }
@@ -0,0 +1,39 @@
+//! Tailscale widget CSS.
+
+/// Return Tailscale CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== TAILSCALE ===== */
+
+.tailscale.tailscale-connected .tailscale-icon {
+    color: var(--color-accent-primary);
+}
+
+.tailscale.tailscale-error .tailscale-icon {
+    color: var(--color-state-urgent);
+}
+
+/* Tailscale popover */
+.tailscale-popover {
+    min-width: 300px;
+}
+
+.tailscale-popover-error {
+    color: var(--color-state-urgent);
+    font-size: var(--font-size-sm);
+}
+
+.tailscale-peer {
+    padding: 4px 0;
+}
+
+.tailscale-peer-detail,
+.tailscale-peer-ip {
+    font-size: var(--font-size-sm);
+}
+
+.tailscale-peer-offline .tailscale-peer-name {
+    opacity: 0.6;
+}
+"#
+}
@@ -0,0 +1,35 @@
+//! Taskbar widget CSS.
+
+/// Return taskbar CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Taskbar ===== */
+
+.taskbar-item {
+    padding: 0 4px;
+    border-radius: var(--radius-pill);
+}
+
+.taskbar-item.clickable:hover {
+    background-image: linear-gradient(var(--color-card-overlay-hover), var(--color-card-overlay-hover));
+}
+
+/* Focused window */
+.taskbar-item.active {
+    background-color: color-mix(in srgb, var(--color-accent-primary) 30%, transparent);
+}
+
+.taskbar-item.taskbar-item-minimized {
+    opacity: 0.5;
+}
+
+/* Window playing sound */
+.icon-root.taskbar-item-audio {
+    font-size: 9px;
+    -gtk-icon-size: 9px;
+    color: var(--color-accent-primary);
+    margin-top: -2px;
+    margin-right: -4px;
+}
+"#
+}
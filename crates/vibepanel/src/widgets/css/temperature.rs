@@ -0,0 +1,43 @@
+//! Temperature widget CSS.
+
+/// Return temperature CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Temperature ===== */
+
+.temperature.temperature-warning .temperature-icon,
+.temperature.temperature-warning .temperature-label {
+    color: var(--color-state-warning);
+}
+
+.temperature.temperature-urgent .temperature-icon,
+.temperature.temperature-urgent .temperature-label {
+    color: var(--color-state-urgent);
+}
+
+/* Temperature popover */
+.temperature-popover {
+    min-width: 280px;
+}
+
+.temperature-row {
+    padding: 4px 0;
+}
+
+.temperature-row-value {
+    font-feature-settings: "tnum";
+}
+
+.temperature-row-selected .temperature-row-name {
+    font-weight: bold;
+}
+
+.temperature-row-warning .temperature-row-value {
+    color: var(--color-state-warning);
+}
+
+.temperature-row-urgent .temperature-row-value {
+    color: var(--color-state-urgent);
+}
+"#
+}
@@ -0,0 +1,24 @@
+//! Focus timer widget CSS.
+
+/// Return focus timer CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Timer ===== */
+
+.timer .timer-label {
+    font-feature-settings: "tnum";
+}
+
+.timer.timer-running .timer-icon {
+    color: var(--color-accent-primary);
+}
+
+.timer.timer-running.timer-break .timer-icon {
+    color: var(--color-state-success);
+}
+
+.timer.timer-paused .timer-label {
+    color: var(--color-foreground-muted);
+}
+"#
+}
@@ -0,0 +1,40 @@
+//! Updates widget CSS.
+
+/// Return updates CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Updates ===== */
+
+.updates .updates-reboot-icon {
+    color: var(--color-state-warning);
+}
+
+/* Updates popover (remote machines) */
+.updates-popover {
+    min-width: 280px;
+}
+
+.updates-row {
+    padding: 4px 6px;
+    border-radius: var(--radius-widget);
+}
+
+button.updates-row:hover {
+    background-color: color-mix(in srgb, var(--color-foreground-primary) 8%, transparent);
+}
+
+.updates-row-status {
+    font-size: var(--font-size-sm);
+}
+
+.updates-row-pending .updates-row-status {
+    color: var(--color-accent-primary);
+}
+
+.updates-popover-reboot {
+    color: var(--color-state-warning);
+    font-size: var(--font-size-sm);
+    padding: 4px 6px;
+}
+"#
+}
@@ -0,0 +1,72 @@
+//! Weather widget CSS.
+
+/// Return weather CSS.
+pub fn css() -> &'static str {
+    r#"
+/* ===== Weather ===== */
+
+.weather.weather-stale .weather-icon,
+.weather.weather-stale .weather-label {
+    opacity: 0.6;
+}
+
+/* Weather popover */
+.weather-popover {
+    min-width: 340px;
+}
+
+.weather-switcher-button {
+    padding: 2px 8px;
+}
+
+.weather-switcher-button.weather-switcher-selected {
+    background: color-mix(in srgb, var(--color-accent-primary) 25%, transparent);
+}
+
+.weather-current {
+    padding: 4px 0;
+}
+
+.weather-current-icon {
+    font-size: calc(var(--icon-size) * 2.5);
+}
+
+.weather-current-temperature {
+    font-size: 1.6em;
+    font-weight: bold;
+}
+
+.weather-hourly {
+    padding: 2px 0 4px 0;
+}
+
+.weather-hour {
+    padding: 0 2px;
+    font-feature-settings: "tnum";
+}
+
+.weather-day {
+    padding: 3px 0;
+}
+
+.weather-day-name {
+    min-width: 48px;
+}
+
+.weather-precipitation {
+    color: var(--color-accent-primary);
+    font-size: 0.85em;
+    font-feature-settings: "tnum";
+}
+
+.weather-day-range {
+    min-width: 72px;
+    font-feature-settings: "tnum";
+}
+
+.weather-footer {
+    font-size: 0.85em;
+    padding-top: 4px;
+}
+"#
+}
@@ -0,0 +1,548 @@
+//! Custom widget - shows the output of a user command.
+//!
+//! Configure each instance in its own section and place it as
+//! `"custom:<name>"`:
+//!
+//! ```toml
+//! [widgets."custom:uptime"]
+//! exec = "uptime -p"
+//! interval = 60
+//! ```
+//!
+//! Configuration options:
+//! - `exec`: Shell command to run (required)
+//! - `interval`: Seconds between runs (default: 10)
+//! - `follow`: Run `exec` once and treat each stdout line as an update
+//!   (default: false)
+//! - `output`: "text" (text, tooltip and classes on separate lines) or
+//!   "json" (`text`, `tooltip`, `class`, `percentage`, `badge`)
+//!   (default: "text")
+//! - `icon`: Icon shown before the text
+//! - `icons`: Icons picked by `percentage`, lowest first (overrides `icon`)
+//! - `on_click`, `on_click_middle`, `on_click_right`, `on_scroll_up`,
+//!   `on_scroll_down`: Shell commands run on mouse input; the command is
+//!   re-run afterwards in interval mode
+//! - `badge_position`, `badge_color`, `badge_text_color`: Placement and
+//!   colors of the icon badge (see `BadgeConfig`)
+//!
+//! In JSON output, `badge` puts a count (`3`) or a dot (`true`) on the icon,
+//! e.g. for unread mail or failed systemd units:
+//!
+//! ```toml
+//! [widgets."custom:failed-units"]
+//! exec = 'echo "{\"badge\": $(systemctl --failed --no-legend | wc -l)}"'
+//! output = "json"
+//! icon = "dialog-warning-symbolic"
+//! interval = 60
+//! ```
+//!
+//! The widget hides while the command prints no text and no icon is set.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::gdk::{BUTTON_MIDDLE, BUTTON_PRIMARY, BUTTON_SECONDARY};
+use gtk4::prelude::*;
+use gtk4::{EventControllerScroll, EventControllerScrollFlags, GestureClick, Label, glib};
+use tracing::{debug, warn};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::actions::{ActionKind, Actions};
+use crate::services::callbacks::CallbackId;
+use crate::services::custom::{
+    CustomBadge, CustomService, CustomSettings, CustomSnapshot, DEFAULT_INTERVAL, DEFAULT_TIMEOUT,
+    OutputFormat, RunMode,
+};
+use crate::services::icons::IconHandle;
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{class, state, widget};
+use crate::widgets::base::{BADGE_OPTIONS, BadgeConfig, BadgeHandle, BaseWidget};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+/// Commands run on mouse input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CustomActions {
+    pub click: Option<String>,
+    pub click_middle: Option<String>,
+    pub click_right: Option<String>,
+    pub scroll_up: Option<String>,
+    pub scroll_down: Option<String>,
+}
+
+impl CustomActions {
+    fn has_click(&self) -> bool {
+        self.click.is_some() || self.click_middle.is_some() || self.click_right.is_some()
+    }
+
+    fn has_scroll(&self) -> bool {
+        self.scroll_up.is_some() || self.scroll_down.is_some()
+    }
+}
+
+/// Configuration for the custom widget.
+#[derive(Debug, Clone)]
+pub struct CustomConfig {
+    /// Instance name (from `"custom:<name>"`).
+    pub id: String,
+    /// How to run the command.
+    pub settings: CustomSettings,
+    /// Static icon.
+    pub icon: Option<String>,
+    /// Icons indexed by percentage.
+    pub icons: Vec<String>,
+    /// Mouse actions.
+    pub actions: CustomActions,
+    /// Icon badge placement and colors.
+    pub badge: BadgeConfig,
+}
+
+impl WidgetConfig for CustomConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        let known: Vec<&str> = [
+            "id",
+            "exec",
+            "interval",
+            "timeout",
+            "follow",
+            "output",
+            "icon",
+            "icons",
+            "on_click",
+            "on_click_middle",
+            "on_click_right",
+            "on_scroll_up",
+            "on_scroll_down",
+        ]
+        .into_iter()
+        .chain(BADGE_OPTIONS.iter().copied())
+        .collect();
+        warn_unknown_options("custom", entry, &known);
+
+        let string = |key: &str| {
+            entry
+                .options
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let id = string("id").unwrap_or_default();
+        if id.is_empty() {
+            warn!("custom widget has no name; place it as \"custom:<name>\"");
+        }
+
+        let exec = string("exec").unwrap_or_default();
+        if exec.is_empty() {
+            warn!("custom:{}: no `exec` command configured", id);
+        }
+
+        let follow = entry
+            .options
+            .get("follow")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let interval = entry
+            .options
+            .get("interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_INTERVAL);
+        let timeout = entry
+            .options
+            .get("timeout")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_TIMEOUT);
+
+        let format = match string("output") {
+            Some(value) => OutputFormat::parse(&value).unwrap_or_else(|| {
+                warn!("custom:{}: unknown output '{}', using text", id, value);
+                OutputFormat::Text
+            }),
+            None => OutputFormat::Text,
+        };
+
+        let icons = entry
+            .options
+            .get("icons")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let actions = CustomActions {
+            click: string("on_click"),
+            click_middle: string("on_click_middle"),
+            click_right: string("on_click_right"),
+            scroll_up: string("on_scroll_up"),
+            scroll_down: string("on_scroll_down"),
+        };
+
+        Self {
+            id,
+            settings: CustomSettings {
+                exec,
+                mode: if follow {
+                    RunMode::Follow
+                } else {
+                    RunMode::Interval(interval)
+                },
+                format,
+                timeout,
+            },
+            icon: string("icon"),
+            icons,
+            actions,
+            badge: BadgeConfig::from_entry("custom", entry),
+        }
+    }
+}
+
+/// Custom widget rendering a user command's output.
+pub struct CustomWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Instance name, used to release the command on drop.
+    id: String,
+    /// Callback ID for CustomService updates (None without a command).
+    callback_id: Option<CallbackId>,
+}
+
+impl CustomWidget {
+    /// Create a new custom widget with the given configuration.
+    pub fn new(config: CustomConfig) -> Self {
+        let base = BaseWidget::new(&[widget::CUSTOM]);
+        if !config.id.is_empty() {
+            base.widget()
+                .add_css_class(&format!("custom-{}", css_name(&config.id)));
+        }
+        base.widget().set_visible(false);
+
+        let icon_handle = base.add_icon(
+            config.icon.as_deref().unwrap_or_default(),
+            &[widget::CUSTOM_ICON],
+        );
+        icon_handle.widget().set_visible(false);
+        let label = base.add_label(None, &[widget::CUSTOM_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+        // Only JSON output can carry a badge
+        let badge = (config.settings.format == OutputFormat::Json)
+            .then(|| base.add_badge(&icon_handle, &config.badge, &[widget::CUSTOM_BADGE]));
+
+        let id = config.id.clone();
+        if config.id.is_empty() || config.settings.exec.is_empty() {
+            return Self {
+                base,
+                id,
+                callback_id: None,
+            };
+        }
+
+        connect_actions(&base, &config.id, &config.actions);
+
+        let service = CustomService::global();
+        service.start(&config.id, config.settings);
+
+        let callback_id = {
+            let container = base.widget().clone();
+            let applied_classes = RefCell::new(Vec::new());
+            let icon = config.icon;
+            let icons = config.icons;
+            service.connect(move |snapshot: &CustomSnapshot| {
+                if snapshot.id != id {
+                    return;
+                }
+                update_custom_widget(
+                    &container,
+                    &icon_handle,
+                    &label,
+                    badge.as_ref(),
+                    &applied_classes,
+                    icon.as_deref(),
+                    &icons,
+                    snapshot,
+                );
+            })
+        };
+
+        Self {
+            base,
+            id: config.id,
+            callback_id: Some(callback_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl Drop for CustomWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.callback_id.take() {
+            let service = CustomService::global();
+            service.disconnect(callback_id);
+            service.release(&self.id);
+        }
+    }
+}
+
+/// Hook up click and scroll actions.
+fn connect_actions(base: &BaseWidget, id: &str, actions: &CustomActions) {
+    if actions.has_click() {
+        base.widget().add_css_class(state::CLICKABLE);
+
+        // No menu is registered on the base widget, so its own click gesture
+        // is a no-op.
+        let click = GestureClick::new();
+        click.set_button(0);
+        let id = id.to_string();
+        let actions = actions.clone();
+        click.connect_released(move |gesture, _n_press, _x, _y| {
+            let command = match gesture.current_button() {
+                BUTTON_PRIMARY => actions.click.as_deref(),
+                BUTTON_MIDDLE => actions.click_middle.as_deref(),
+                BUTTON_SECONDARY => actions.click_right.as_deref(),
+                _ => None,
+            };
+            if let Some(command) = command {
+                run_action(&id, command);
+            }
+        });
+        base.widget().add_controller(click);
+
+        if let Some(command) = actions.click.clone() {
+            let id = id.to_string();
+            base.set_default_action(move || run_action(&id, &command));
+        }
+    }
+
+    if actions.has_scroll() {
+        let scroll = EventControllerScroll::new(EventControllerScrollFlags::VERTICAL);
+        let id = id.to_string();
+        let actions = actions.clone();
+        scroll.connect_scroll(move |_controller, _dx, dy| {
+            let command = if dy < 0.0 {
+                actions.scroll_up.as_deref()
+            } else if dy > 0.0 {
+                actions.scroll_down.as_deref()
+            } else {
+                None
+            };
+            if let Some(command) = command {
+                run_action(&id, command);
+            }
+            glib::Propagation::Stop
+        });
+        base.widget().add_controller(scroll);
+    }
+}
+
+/// Run an action command, then refresh the widget's output.
+fn run_action(id: &str, command: &str) {
+    debug!("custom:{}: running '{}'", id, command);
+    let command_line = format!("sh -c {}", glib::shell_quote(command).to_string_lossy());
+    if let Err(e) = Actions::global().spawn_command_line(ActionKind::Exec, &command_line) {
+        warn!("custom:{}: failed to run '{}': {}", id, command, e);
+        return;
+    }
+    CustomService::global().refresh(id);
+}
+
+/// Update the widget visuals from a command snapshot.
+fn update_custom_widget(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    badge: Option<&BadgeHandle>,
+    applied_classes: &RefCell<Vec<String>>,
+    icon: Option<&str>,
+    icons: &[String],
+    snapshot: &CustomSnapshot,
+) {
+    let output = snapshot.output.clone().unwrap_or_default();
+
+    let icon = match output.percentage {
+        Some(percentage) => icon_for_percentage(icons, percentage).or(icon),
+        None => icon,
+    };
+    match icon {
+        Some(icon) => {
+            icon_handle.set_icon(icon);
+            icon_handle.widget().set_visible(true);
+        }
+        None => icon_handle.widget().set_visible(false),
+    }
+
+    if let Some(badge) = badge {
+        match output.badge {
+            Some(CustomBadge::Count(count)) => badge.set_count(count),
+            Some(CustomBadge::Dot) => badge.set_dot(true),
+            None => badge.hide(),
+        }
+    }
+
+    update::set_label_text(label, &output.text);
+    label.set_visible(!output.text.is_empty());
+    container.set_visible(icon.is_some() || !output.text.is_empty());
+
+    // Replace the classes set by the previous output
+    let mut applied = applied_classes.borrow_mut();
+    for class in applied.drain(..) {
+        container.remove_css_class(&class);
+    }
+    for class in &output.classes {
+        container.add_css_class(class);
+        applied.push(class.clone());
+    }
+    update::set_class(container, widget::CUSTOM_ERROR, snapshot.error.is_some());
+
+    let tooltip = match (&output.tooltip, &snapshot.error) {
+        (Some(tooltip), Some(error)) => format!("{}\nError: {}", tooltip, error),
+        (None, Some(error)) => format!("Error: {}", error),
+        (Some(tooltip), None) => tooltip.clone(),
+        (None, None) => String::new(),
+    };
+    TooltipManager::global().set_styled_tooltip(container, &tooltip);
+}
+
+/// Pick the icon for `percentage` from a list ordered lowest first.
+fn icon_for_percentage(icons: &[String], percentage: u8) -> Option<&str> {
+    if icons.is_empty() {
+        return None;
+    }
+    let index = (percentage.min(100) as usize * icons.len() / 101).min(icons.len() - 1);
+    Some(icons[index].as_str())
+}
+
+/// Turn an instance name into a CSS class suffix.
+fn css_name(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::custom::CustomOutput;
+    use crate::test_harness;
+    use crate::widgets::test_entry;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_custom_config() {
+        let mut options = HashMap::new();
+        options.insert("id".to_string(), toml::Value::String("mail".to_string()));
+        options.insert(
+            "exec".to_string(),
+            toml::Value::String("mail-count --json".to_string()),
+        );
+        options.insert("follow".to_string(), toml::Value::Boolean(true));
+        options.insert(
+            "output".to_string(),
+            toml::Value::String("json".to_string()),
+        );
+        options.insert(
+            "on_click".to_string(),
+            toml::Value::String("thunderbird".to_string()),
+        );
+        let config = CustomConfig::from_entry(&test_entry("custom", options));
+
+        assert_eq!(config.id, "mail");
+        assert_eq!(config.settings.exec, "mail-count --json");
+        assert_eq!(config.settings.mode, RunMode::Follow);
+        assert_eq!(config.settings.format, OutputFormat::Json);
+        assert_eq!(config.actions.click.as_deref(), Some("thunderbird"));
+        assert!(config.actions.scroll_up.is_none());
+
+        let config = CustomConfig::from_entry(&test_entry("custom", HashMap::new()));
+        assert_eq!(config.settings.mode, RunMode::Interval(DEFAULT_INTERVAL));
+        assert_eq!(config.settings.timeout, DEFAULT_TIMEOUT);
+        assert_eq!(config.settings.format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_icon_for_percentage() {
+        let icons: Vec<String> = ["low", "mid", "high"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(icon_for_percentage(&icons, 0), Some("low"));
+        assert_eq!(icon_for_percentage(&icons, 50), Some("mid"));
+        assert_eq!(icon_for_percentage(&icons, 100), Some("high"));
+        assert_eq!(icon_for_percentage(&[], 50), None);
+    }
+
+    #[test]
+    fn test_css_name() {
+        assert_eq!(css_name("disk_usage"), "disk-usage");
+        assert_eq!(css_name("vpn2"), "vpn2");
+    }
+
+    #[test]
+    fn test_custom_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = CustomWidget::new(CustomConfig {
+                id: "uptime".to_string(),
+                settings: CustomSettings {
+                    exec: "true".to_string(),
+                    mode: RunMode::Interval(DEFAULT_INTERVAL),
+                    format: OutputFormat::Text,
+                    timeout: DEFAULT_TIMEOUT,
+                },
+                icon: None,
+                icons: Vec::new(),
+                actions: CustomActions::default(),
+                badge: BadgeConfig::default(),
+            });
+            let service = CustomService::global();
+            let snapshot = |text: &str, classes: &[&str], error: Option<&str>| CustomSnapshot {
+                id: "uptime".to_string(),
+                output: Some(CustomOutput {
+                    text: text.to_string(),
+                    classes: classes.iter().map(|class| class.to_string()).collect(),
+                    ..CustomOutput::default()
+                }),
+                error: error.map(str::to_string),
+            };
+
+            service.publish_for_test(snapshot("up 3 hours", &["busy"], None));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.busy.custom.custom-uptime.widget.widget-item",
+                    r#"label.custom-label.vcenter-caps "up 3 hours""#,
+                ],
+            );
+
+            // Another command's output is ignored
+            service.publish_for_test(CustomSnapshot {
+                id: "other".to_string(),
+                ..snapshot("elsewhere", &[], None)
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[r#"label.custom-label.vcenter-caps "up 3 hours""#],
+            );
+
+            // Failed run: the output's classes are replaced by the error flag
+            service.publish_for_test(snapshot("up 3 hours", &[], Some("exit status 1")));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &["box.custom.custom-error.custom-uptime.widget.widget-item"],
+            );
+
+            // No text and no icon: hidden
+            service.publish_for_test(snapshot("", &[], None));
+            assert_eq!(test_harness::render(widget.widget()), "");
+        });
+    }
+}
@@ -0,0 +1,407 @@
+//! Git widget - reminds about uncommitted and unpushed work.
+//!
+//! This widget:
+//! - Shows an icon and the number of repositories with pending work
+//! - Highlights (`.git-dirty`) when any repository needs attention
+//! - Opens a popover listing each repository's branch, changes and
+//!   ahead/behind counts on click
+//! - Adds pending repositories to the shutdown/reboot confirmation
+//!
+//! Configuration options:
+//! - `repos`: List of repository paths; `~` expands to the home directory (required)
+//! - `check_interval`: How often to check, in seconds (default: 60)
+//! - `hide_when_clean`: Hide the widget when nothing is pending (default: false)
+
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::git::{
+    DEFAULT_CHECK_INTERVAL, GitService, GitSettings, GitSnapshot, RepoStatus,
+};
+use crate::services::icons::IconHandle;
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{button, class, color, git, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, expand_home, update, warn_unknown_options};
+
+const DEFAULT_HIDE_WHEN_CLEAN: bool = false;
+
+/// Configuration for the git widget.
+#[derive(Debug, Clone)]
+pub struct GitConfig {
+    /// Repository working tree paths.
+    pub repos: Vec<PathBuf>,
+    /// How often to check (seconds).
+    pub check_interval: u64,
+    /// Hide the widget when nothing is pending.
+    pub hide_when_clean: bool,
+}
+
+impl WidgetConfig for GitConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "git",
+            entry,
+            &["repos", "check_interval", "hide_when_clean"],
+        );
+
+        let repos: Vec<PathBuf> = entry
+            .options
+            .get("repos")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(expand_home)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if repos.is_empty() {
+            warn!("git widget has no `repos` configured");
+        }
+
+        let check_interval = entry
+            .options
+            .get("check_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(0) as u64)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+
+        let hide_when_clean = entry
+            .options
+            .get("hide_when_clean")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_HIDE_WHEN_CLEAN);
+
+        Self {
+            repos,
+            check_interval,
+            hide_when_clean,
+        }
+    }
+}
+
+/// Git widget that displays repositories with pending work.
+pub struct GitWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover listing repositories.
+    _menu: Option<Rc<MenuHandle>>,
+    /// This widget's settings registered with the service.
+    settings_id: Option<SettingsId>,
+}
+
+impl GitWidget {
+    /// Create a new git widget with the given configuration.
+    pub fn new(config: GitConfig) -> Self {
+        let base = BaseWidget::new(&[widget::GIT]);
+        base.set_tooltip("Git: checking...");
+
+        let icon_handle = base.add_icon("vcs-branch-symbolic", &[widget::GIT_ICON]);
+        let label = base.add_label(None, &[widget::GIT_LABEL, class::VCENTER_CAPS]);
+
+        if config.repos.is_empty() {
+            base.widget().set_visible(false);
+            return Self {
+                base,
+                _menu: None,
+                settings_id: None,
+            };
+        }
+
+        let menu = base.create_menu(build_git_popover);
+
+        let service = GitService::global();
+        let settings_id = service.configure(GitSettings {
+            repos: config.repos,
+            check_interval: config.check_interval,
+        });
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            let hide_when_clean = config.hide_when_clean;
+            service.connect(move |snapshot: &GitSnapshot| {
+                update_widget_from_snapshot(
+                    &container,
+                    &icon_handle,
+                    &label,
+                    hide_when_clean,
+                    snapshot,
+                );
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self {
+            base,
+            _menu: Some(menu),
+            settings_id: Some(settings_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for GitWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for GitWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.settings_id.take() {
+            GitService::global().release(settings_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    hide_when_clean: bool,
+    snapshot: &GitSnapshot,
+) {
+    let pending = snapshot.attention_repos().count();
+    let errors = snapshot.repos.iter().filter(|r| r.error.is_some()).count();
+
+    container.remove_css_class(widget::GIT_DIRTY);
+    container.remove_css_class(widget::GIT_ERROR);
+    if pending > 0 {
+        container.add_css_class(widget::GIT_DIRTY);
+    } else if errors > 0 {
+        container.add_css_class(widget::GIT_ERROR);
+    }
+    icon_handle.set_icon(if pending == 0 && errors > 0 {
+        "dialog-error-symbolic"
+    } else {
+        "vcs-branch-symbolic"
+    });
+
+    update::set_label_text(label, &pending.to_string());
+    label.set_visible(pending > 0);
+    container.set_visible(!(hide_when_clean && pending == 0 && errors == 0));
+
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(snapshot));
+}
+
+/// Short summary for a repository, e.g. "main · 3 changed · ↑2 ↓1".
+pub fn repo_summary(repo: &RepoStatus) -> String {
+    if let Some(ref err) = repo.error {
+        return err.clone();
+    }
+
+    let mut parts = vec![
+        repo.branch
+            .clone()
+            .unwrap_or_else(|| "detached".to_string()),
+    ];
+    if repo.changed > 0 {
+        parts.push(format!("{} changed", repo.changed));
+    }
+    if repo.untracked > 0 {
+        parts.push(format!("{} untracked", repo.untracked));
+    }
+    match (repo.ahead, repo.behind) {
+        (0, 0) => {}
+        (ahead, 0) => parts.push(format!("↑{}", ahead)),
+        (0, behind) => parts.push(format!("↓{}", behind)),
+        (ahead, behind) => parts.push(format!("↑{} ↓{}", ahead, behind)),
+    }
+    if !repo.unpushed_branches.is_empty() {
+        let n = repo.unpushed_branches.len();
+        let s = if n == 1 { "" } else { "es" };
+        parts.push(format!("{} unpushed branch{}", n, s));
+    }
+    parts.join(" · ")
+}
+
+/// Format the tooltip for a snapshot.
+fn format_tooltip(snapshot: &GitSnapshot) -> String {
+    let pending: Vec<&RepoStatus> = snapshot.attention_repos().collect();
+    if pending.is_empty() {
+        if snapshot.repos.is_empty() && snapshot.checking {
+            return "Git: checking...".to_string();
+        }
+        return "Git: all repositories clean and pushed".to_string();
+    }
+
+    let mut lines = vec!["Git: pending work".to_string()];
+    for repo in pending {
+        lines.push(format!("{}: {}", repo.name, repo_summary(repo)));
+    }
+    lines.join("\n")
+}
+
+/// Build the popover content listing repositories.
+fn build_git_popover() -> Widget {
+    let snapshot = GitService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(git::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("Repositories"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let refresh = Button::with_label(if snapshot.checking {
+        "Checking..."
+    } else {
+        "Refresh"
+    });
+    refresh.add_css_class(button::GHOST);
+    refresh.set_sensitive(!snapshot.checking);
+    refresh.connect_clicked(|_| GitService::global().refresh());
+    header.append(&refresh);
+    container.append(&header);
+
+    for repo in &snapshot.repos {
+        container.append(&build_repo_row(repo));
+    }
+
+    container.upcast()
+}
+
+/// Build a repository row: name and path on top, summary below.
+fn build_repo_row(repo: &RepoStatus) -> GtkBox {
+    let row = GtkBox::new(Orientation::Vertical, 2);
+    row.add_css_class(git::ROW);
+    if repo.error.is_some() {
+        row.add_css_class(git::ROW_ERROR);
+    } else if repo.needs_attention() {
+        row.add_css_class(git::ROW_DIRTY);
+    }
+
+    let name = Label::new(Some(&repo.name));
+    name.add_css_class(git::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_xalign(0.0);
+    row.append(&name);
+
+    let summary = Label::new(Some(&repo_summary(repo)));
+    summary.add_css_class(git::ROW_STATUS);
+    summary.add_css_class(color::MUTED);
+    summary.set_halign(Align::Start);
+    summary.set_xalign(0.0);
+    summary.set_ellipsize(EllipsizeMode::End);
+    summary.set_max_width_chars(48);
+    row.append(&summary);
+
+    if !repo.unpushed_branches.is_empty() {
+        let branches = Label::new(Some(&format!(
+            "Unpushed: {}",
+            repo.unpushed_branches.join(", ")
+        )));
+        branches.add_css_class(git::ROW_STATUS);
+        branches.add_css_class(color::MUTED);
+        branches.set_halign(Align::Start);
+        branches.set_xalign(0.0);
+        branches.set_wrap(true);
+        branches.set_max_width_chars(48);
+        row.append(&branches);
+    }
+
+    TooltipManager::global().set_styled_tooltip(&row, &repo.path.display().to_string());
+
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_git_config_parsing() {
+        let mut options = HashMap::new();
+        options.insert(
+            "repos".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("/srv/project".to_string()),
+                toml::Value::Integer(3),
+            ]),
+        );
+        let entry = WidgetEntry {
+            name: "git".to_string(),
+            options,
+        };
+        let config = GitConfig::from_entry(&entry);
+
+        assert_eq!(config.repos, vec![PathBuf::from("/srv/project")]);
+        assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
+        assert!(!config.hide_when_clean);
+    }
+
+    #[test]
+    fn test_repo_summary() {
+        let repo = RepoStatus {
+            name: "vibepanel".to_string(),
+            branch: Some("main".to_string()),
+            changed: 3,
+            ahead: 2,
+            behind: 1,
+            unpushed_branches: vec!["wip".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            repo_summary(&repo),
+            "main · 3 changed · ↑2 ↓1 · 1 unpushed branch"
+        );
+    }
+
+    #[test]
+    fn test_git_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let path = PathBuf::from("/nonexistent/vibepanel");
+            let widget = GitWidget::new(GitConfig {
+                repos: vec![path.clone()],
+                check_interval: DEFAULT_CHECK_INTERVAL,
+                hide_when_clean: true,
+            });
+            let service = GitService::global();
+            let snapshot = |changed| GitSnapshot {
+                available: true,
+                checking: false,
+                repos: vec![RepoStatus {
+                    path: path.clone(),
+                    name: "vibepanel".to_string(),
+                    branch: Some("main".to_string()),
+                    changed,
+                    ..Default::default()
+                }],
+            };
+
+            service.publish_for_test(snapshot(3));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.git.git-dirty.widget.widget-item",
+                    "icon.git-icon",
+                    r#"label.git-label.vcenter-caps "1""#,
+                ],
+            );
+
+            // Clean and pushed: hidden
+            service.publish_for_test(snapshot(0));
+            assert_eq!(test_harness::render(widget.widget()), "");
+        });
+    }
+}
@@ -0,0 +1,478 @@
+//! Hosts widget - reachability of remote hosts.
+//!
+//! This widget:
+//! - Shows one dot per configured host, colored by up/down state
+//! - Lists hosts with latency or failure reason in a popover
+//! - Opens an SSH session in a terminal when a host row is clicked
+//!
+//! Configuration options:
+//! - `hosts`: List of `[user@]host[:port]` strings or tables with `host`
+//!   and optional `name`, `port`, `user` (required)
+//! - `probe`: "tcp" (connect only) or "ssh" (also check the SSH banner)
+//!   (default: "ssh")
+//! - `check_interval`: How often to check, in seconds (default: 60)
+//! - `terminal`: Terminal emulator for SSH sessions (default: auto-detect)
+
+use std::process::Stdio;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Widget};
+use tracing::{debug, warn};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::actions::{ActionKind, Actions};
+use crate::services::hosts::{
+    DEFAULT_CHECK_INTERVAL, DEFAULT_TIMEOUT, HostSpec, HostState, HostStatus, HostsService,
+    HostsSettings, HostsSnapshot, ProbeKind,
+};
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{button, color, hosts, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::updates_common::{detect_terminal, terminal_command};
+use crate::widgets::{WidgetConfig, warn_unknown_options};
+
+/// Configuration for the hosts widget.
+#[derive(Debug, Clone)]
+pub struct HostsConfig {
+    /// Hosts to check.
+    pub hosts: Vec<HostSpec>,
+    /// How hosts are probed.
+    pub probe: ProbeKind,
+    /// How often to check (seconds).
+    pub check_interval: u64,
+    /// Terminal override for SSH sessions.
+    pub terminal: Option<String>,
+}
+
+impl WidgetConfig for HostsConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "hosts",
+            entry,
+            &["hosts", "probe", "check_interval", "terminal"],
+        );
+
+        let hosts: Vec<HostSpec> = entry
+            .options
+            .get("hosts")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|value| {
+                        let spec = parse_host_value(value);
+                        if spec.is_none() {
+                            warn!("hosts widget: invalid host entry {}", value);
+                        }
+                        spec
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if hosts.is_empty() {
+            warn!("hosts widget has no `hosts` configured");
+        }
+
+        let probe = match entry.options.get("probe").and_then(|v| v.as_str()) {
+            Some(name) => ProbeKind::from_name(name).unwrap_or_else(|| {
+                warn!("hosts widget: unknown probe '{}', using ssh", name);
+                ProbeKind::Ssh
+            }),
+            None => ProbeKind::Ssh,
+        };
+
+        let check_interval = entry
+            .options
+            .get("check_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(0) as u64)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+
+        let terminal = entry
+            .options
+            .get("terminal")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        Self {
+            hosts,
+            probe,
+            check_interval,
+            terminal,
+        }
+    }
+}
+
+/// Parse a host entry given as a string or an inline table.
+fn parse_host_value(value: &toml::Value) -> Option<HostSpec> {
+    if let Some(spec) = value.as_str() {
+        return HostSpec::parse(spec);
+    }
+
+    let table = value.as_table()?;
+    let mut spec = HostSpec::parse(table.get("host")?.as_str()?)?;
+    if let Some(port) = table.get("port").and_then(|v| v.as_integer()) {
+        spec.port = u16::try_from(port).ok()?;
+    }
+    if let Some(user) = table.get("user").and_then(|v| v.as_str()) {
+        spec.user = Some(user.to_string());
+    }
+    if let Some(name) = table.get("name").and_then(|v| v.as_str()) {
+        spec.name = name.to_string();
+    }
+    Some(spec)
+}
+
+/// Hosts widget that displays reachability dots.
+pub struct HostsWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover listing hosts.
+    _menu: Option<Rc<MenuHandle>>,
+    /// This widget's settings registered with the service.
+    settings_id: Option<SettingsId>,
+}
+
+impl HostsWidget {
+    /// Create a new hosts widget with the given configuration.
+    pub fn new(config: HostsConfig) -> Self {
+        let base = BaseWidget::new(&[widget::HOSTS]);
+        base.set_tooltip("Hosts: checking...");
+
+        let dots = GtkBox::new(Orientation::Horizontal, 4);
+        dots.add_css_class(widget::HOSTS_DOTS);
+        dots.set_valign(Align::Center);
+        base.content().append(&dots);
+
+        if config.hosts.is_empty() {
+            base.widget().set_visible(false);
+            return Self {
+                base,
+                _menu: None,
+                settings_id: None,
+            };
+        }
+
+        let terminal = Rc::new(config.terminal);
+        let menu = base.create_menu(move || build_hosts_popover(&terminal));
+
+        let service = HostsService::global();
+        let settings_id = service.configure(HostsSettings {
+            hosts: config.hosts,
+            probe: config.probe,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            check_interval: config.check_interval,
+        });
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            service.connect(move |snapshot: &HostsSnapshot| {
+                update_widget_from_snapshot(&container, &dots, snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self {
+            base,
+            _menu: Some(menu),
+            settings_id: Some(settings_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for HostsWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for HostsWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.settings_id.take() {
+            HostsService::global().release(settings_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(container: &gtk4::Box, dots: &GtkBox, snapshot: &HostsSnapshot) {
+    while let Some(child) = dots.first_child() {
+        dots.remove(&child);
+    }
+    for status in &snapshot.hosts {
+        let dot = GtkBox::new(Orientation::Horizontal, 0);
+        dot.add_css_class(hosts::DOT);
+        if let Some(class) = dot_class(status.state) {
+            dot.add_css_class(class);
+        }
+        dot.set_valign(Align::Center);
+        dots.append(&dot);
+    }
+
+    if snapshot.down_count() > 0 {
+        container.add_css_class(widget::HOSTS_DOWN);
+    } else {
+        container.remove_css_class(widget::HOSTS_DOWN);
+    }
+
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(snapshot));
+}
+
+/// State class for a host dot or row.
+fn dot_class(state: HostState) -> Option<&'static str> {
+    match state {
+        HostState::Up => Some(hosts::UP),
+        HostState::Down => Some(hosts::DOWN),
+        HostState::Unknown => None,
+    }
+}
+
+/// Status text for one host, e.g. "Up · 12 ms" or "Down · Connection refused".
+pub fn host_status_text(status: &HostStatus) -> String {
+    match status.state {
+        HostState::Unknown => "Checking...".to_string(),
+        HostState::Up => match status.latency {
+            Some(latency) => format!("Up · {} ms", latency.as_millis()),
+            None => "Up".to_string(),
+        },
+        HostState::Down => match status.error {
+            Some(ref err) => format!("Down · {}", err),
+            None => "Down".to_string(),
+        },
+    }
+}
+
+/// Format the tooltip for a snapshot.
+fn format_tooltip(snapshot: &HostsSnapshot) -> String {
+    let mut lines = vec![match snapshot.down_count() {
+        0 => "Hosts".to_string(),
+        n => format!("Hosts: {} down", n),
+    }];
+    for status in &snapshot.hosts {
+        lines.push(format!(
+            "{}: {}",
+            status.spec.name,
+            host_status_text(status)
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Open an SSH session to `spec` in a terminal.
+fn open_ssh_session(spec: &HostSpec, terminal: Option<&str>) {
+    let Some(terminal) = terminal.map(str::to_string).or_else(detect_terminal) else {
+        warn!("hosts widget: no terminal emulator found for SSH session");
+        return;
+    };
+
+    let port = spec.port.to_string();
+    let destination = spec.ssh_destination();
+    debug!("Opening SSH session to {} in {}", destination, terminal);
+
+    let mut command = terminal_command(&terminal, &["ssh", "-p", &port, &destination]);
+    command.stdout(Stdio::null()).stderr(Stdio::null());
+    let result = Actions::global().spawn(ActionKind::Exec, &mut command);
+    if let Err(e) = result {
+        warn!("Failed to launch {}: {}", terminal, e);
+    }
+}
+
+/// Build the popover content listing hosts.
+fn build_hosts_popover(terminal: &Rc<Option<String>>) -> Widget {
+    let snapshot = HostsService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(hosts::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("Hosts"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let refresh = Button::with_label("Refresh");
+    refresh.add_css_class(button::GHOST);
+    refresh.connect_clicked(|_| HostsService::global().refresh());
+    header.append(&refresh);
+    container.append(&header);
+
+    for status in &snapshot.hosts {
+        container.append(&build_host_row(status, terminal));
+    }
+
+    container.upcast()
+}
+
+/// Build a host row; clicking it opens an SSH session.
+fn build_host_row(status: &HostStatus, terminal: &Rc<Option<String>>) -> Button {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+
+    let dot = GtkBox::new(Orientation::Horizontal, 0);
+    dot.add_css_class(hosts::DOT);
+    if let Some(class) = dot_class(status.state) {
+        dot.add_css_class(class);
+    }
+    dot.set_valign(Align::Center);
+    row.append(&dot);
+
+    let name = Label::new(Some(&status.spec.name));
+    name.add_css_class(hosts::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(EllipsizeMode::End);
+    name.set_max_width_chars(24);
+    row.append(&name);
+
+    let state = Label::new(Some(&host_status_text(status)));
+    state.add_css_class(hosts::ROW_STATUS);
+    state.add_css_class(color::MUTED);
+    state.set_halign(Align::End);
+    state.set_ellipsize(EllipsizeMode::End);
+    state.set_max_width_chars(28);
+    row.append(&state);
+
+    let row_button = Button::new();
+    row_button.add_css_class(button::RESET);
+    row_button.add_css_class(hosts::ROW);
+    row_button.set_child(Some(&row));
+    TooltipManager::global().set_styled_tooltip(
+        &row_button,
+        &format!(
+            "ssh -p {} {}",
+            status.spec.port,
+            status.spec.ssh_destination()
+        ),
+    );
+
+    let spec = status.spec.clone();
+    let terminal = terminal.clone();
+    row_button.connect_clicked(move |_| open_ssh_session(&spec, terminal.as_deref()));
+
+    row_button
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_hosts_config_parsing() {
+        let mut table = toml::map::Map::new();
+        table.insert("name".to_string(), toml::Value::String("NAS".to_string()));
+        table.insert(
+            "host".to_string(),
+            toml::Value::String("nas.lan".to_string()),
+        );
+        table.insert("port".to_string(), toml::Value::Integer(2222));
+
+        let mut options = HashMap::new();
+        options.insert(
+            "hosts".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("root@build01".to_string()),
+                toml::Value::Table(table),
+                toml::Value::Integer(1),
+            ]),
+        );
+        options.insert("probe".to_string(), toml::Value::String("tcp".to_string()));
+        let entry = WidgetEntry {
+            name: "hosts".to_string(),
+            options,
+        };
+        let config = HostsConfig::from_entry(&entry);
+
+        assert_eq!(config.hosts.len(), 2);
+        assert_eq!(config.hosts[0].ssh_destination(), "root@build01");
+        assert_eq!(config.hosts[1].name, "NAS");
+        assert_eq!(config.hosts[1].port, 2222);
+        assert_eq!(config.probe, ProbeKind::Tcp);
+        assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
+        assert_eq!(config.terminal, None);
+    }
+
+    #[test]
+    fn test_host_status_text() {
+        let mut status = HostStatus {
+            spec: HostSpec::parse("nas.lan").unwrap(),
+            state: HostState::Unknown,
+            latency: None,
+            error: None,
+        };
+        assert_eq!(host_status_text(&status), "Checking...");
+
+        status.state = HostState::Up;
+        status.latency = Some(Duration::from_millis(12));
+        assert_eq!(host_status_text(&status), "Up · 12 ms");
+
+        status.state = HostState::Down;
+        status.error = Some("Connection refused".to_string());
+        assert_eq!(host_status_text(&status), "Down · Connection refused");
+    }
+
+    #[test]
+    fn test_hosts_widget_renders_snapshots() {
+        test_harness::run(|| {
+            // Discard ports on loopback, so the service's own probes stay local
+            let specs: Vec<HostSpec> = ["127.0.0.1:9", "[::1]:9"]
+                .into_iter()
+                .filter_map(HostSpec::parse)
+                .collect();
+            let widget = HostsWidget::new(HostsConfig {
+                hosts: specs.clone(),
+                probe: ProbeKind::Tcp,
+                check_interval: DEFAULT_CHECK_INTERVAL,
+                terminal: None,
+            });
+            let service = HostsService::global();
+            let snapshot = |states: [HostState; 2]| HostsSnapshot {
+                hosts: specs
+                    .iter()
+                    .zip(states)
+                    .map(|(spec, state)| HostStatus {
+                        spec: spec.clone(),
+                        state,
+                        latency: None,
+                        error: None,
+                    })
+                    .collect(),
+            };
+
+            service.publish_for_test(snapshot([HostState::Up, HostState::Down]));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.hosts.hosts-down.widget.widget-item",
+                    "box.hosts-dots",
+                    "box.hosts-dot.hosts-dot-up",
+                    "box.hosts-dot.hosts-dot-down",
+                ],
+            );
+
+            // Still checking: plain dots, nothing flagged
+            service.publish_for_test(snapshot([HostState::Unknown, HostState::Unknown]));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.hosts.widget.widget-item",
+                    "box.hosts-dot",
+                    "box.hosts-dot",
+                ],
+            );
+        });
+    }
+}
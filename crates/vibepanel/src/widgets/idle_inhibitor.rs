@@ -0,0 +1,363 @@
+//! Idle inhibitor widget - "caffeine" toggle that keeps the system awake.
+//!
+//! This widget:
+//! - Shows a coffee cup icon, highlighted while idle is inhibited
+//! - Shows the time left when the inhibitor was enabled for a fixed duration
+//! - Opens a popover with an on/off switch and timed durations
+//!   (e.g. 30 minutes, 1 hour) on click
+//! - Toggles the inhibitor on right click (or on any click when no
+//!   durations are configured)
+//!
+//! The inhibitor state lives in `IdleInhibitorService`, so it is shared with
+//! the Quick Settings card and survives config reloads.
+//!
+//! Configuration options:
+//! - `durations`: Timed durations offered in the popover, in minutes
+//!   (default: [30, 60]; an empty list makes the widget a plain toggle)
+//! - `show_remaining`: Show the time left next to the icon (default: true)
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, GestureClick, Label, Orientation, Switch, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::callbacks::CallbackId;
+use crate::services::icons::IconHandle;
+use crate::services::idle_inhibitor::{IdleInhibitorService, IdleInhibitorSnapshot};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{button, class, color, idle_inhibitor, state, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::quick_settings::idle_inhibitor_card::{format_remaining, idle_subtitle};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_DURATIONS: &[u64] = &[30, 60];
+const DEFAULT_SHOW_REMAINING: bool = true;
+
+/// Configuration for the idle inhibitor widget.
+#[derive(Debug, Clone)]
+pub struct IdleInhibitorConfig {
+    /// Timed durations offered in the popover, in minutes.
+    pub durations: Vec<u64>,
+    /// Show the time left on a timed inhibitor.
+    pub show_remaining: bool,
+}
+
+impl WidgetConfig for IdleInhibitorConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("idle_inhibitor", entry, &["durations", "show_remaining"]);
+
+        let durations = match entry.options.get("durations") {
+            Some(value) => match value.as_array() {
+                Some(items) => items
+                    .iter()
+                    .filter_map(|item| match item.as_integer() {
+                        Some(minutes) if minutes > 0 => Some(minutes as u64),
+                        _ => {
+                            warn!(
+                                "idle_inhibitor widget: ignoring invalid duration {} \
+                                 (expected a positive number of minutes)",
+                                item
+                            );
+                            None
+                        }
+                    })
+                    .collect(),
+                None => {
+                    warn!("idle_inhibitor widget: `durations` must be a list of minutes");
+                    DEFAULT_DURATIONS.to_vec()
+                }
+            },
+            None => DEFAULT_DURATIONS.to_vec(),
+        };
+
+        let show_remaining = entry
+            .options
+            .get("show_remaining")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_REMAINING);
+
+        Self {
+            durations,
+            show_remaining,
+        }
+    }
+}
+
+impl Default for IdleInhibitorConfig {
+    fn default() -> Self {
+        Self {
+            durations: DEFAULT_DURATIONS.to_vec(),
+            show_remaining: DEFAULT_SHOW_REMAINING,
+        }
+    }
+}
+
+/// Popover widgets updated from service snapshots while the popover is open.
+#[derive(Default)]
+struct PopoverRefs {
+    switch: Option<Switch>,
+    status: Option<Label>,
+}
+
+/// Idle inhibitor widget with a toggle and timed durations.
+pub struct IdleInhibitorWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover with the switch and durations (`None` for a plain toggle).
+    _menu: Option<Rc<MenuHandle>>,
+    /// IdleInhibitorService callback registration.
+    callback_id: Option<CallbackId>,
+}
+
+impl IdleInhibitorWidget {
+    /// Create a new idle inhibitor widget with the given configuration.
+    pub fn new(config: IdleInhibitorConfig) -> Self {
+        let base = BaseWidget::new(&[widget::IDLE_INHIBITOR]);
+        base.widget().add_css_class(state::CLICKABLE);
+
+        let icon_handle = base.add_icon(
+            "caffeine-cup-empty-symbolic",
+            &[widget::IDLE_INHIBITOR_ICON],
+        );
+        let label = base.add_label(None, &[widget::IDLE_INHIBITOR_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+
+        let popover_refs = Rc::new(RefCell::new(PopoverRefs::default()));
+
+        let menu = if config.durations.is_empty() {
+            let click = GestureClick::new();
+            click.connect_released(|_, _, _, _| {
+                IdleInhibitorService::global().toggle();
+            });
+            base.widget().add_controller(click);
+            None
+        } else {
+            let secondary = GestureClick::new();
+            secondary.set_button(3); // GDK_BUTTON_SECONDARY
+            secondary.connect_released(|_, _, _, _| {
+                IdleInhibitorService::global().toggle();
+            });
+            base.widget().add_controller(secondary);
+
+            let durations = config.durations.clone();
+            let popover_refs = Rc::clone(&popover_refs);
+            Some(base.create_menu(move || build_idle_inhibitor_popover(&durations, &popover_refs)))
+        };
+
+        let callback_id = {
+            let container = base.widget().clone();
+            let show_remaining = config.show_remaining;
+            IdleInhibitorService::global().connect(move |snapshot: &IdleInhibitorSnapshot| {
+                update_widget_from_snapshot(
+                    &container,
+                    &icon_handle,
+                    &label,
+                    show_remaining,
+                    snapshot,
+                );
+
+                let refs = popover_refs.borrow();
+                if let Some(switch) = refs.switch.as_ref()
+                    && switch.is_active() != snapshot.active
+                {
+                    switch.set_active(snapshot.active);
+                }
+                if let Some(status) = refs.status.as_ref() {
+                    update::set_label_text(status, &status_text(snapshot));
+                }
+            })
+        };
+
+        Self {
+            base,
+            _menu: menu,
+            callback_id: Some(callback_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for IdleInhibitorWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for IdleInhibitorWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.callback_id.take() {
+            IdleInhibitorService::global().disconnect(callback_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    show_remaining: bool,
+    snapshot: &IdleInhibitorSnapshot,
+) {
+    update::set_class(container, widget::IDLE_INHIBITOR_ACTIVE, snapshot.active);
+    update::set_class(container, state::SERVICE_UNAVAILABLE, !snapshot.available);
+    icon_handle.set_icon(if snapshot.active {
+        "caffeine-cup-full-symbolic"
+    } else {
+        "caffeine-cup-empty-symbolic"
+    });
+
+    match snapshot.remaining() {
+        Some(remaining) if show_remaining => {
+            update::set_label_text(label, &format_remaining(remaining));
+            label.set_visible(true);
+        }
+        _ => label.set_visible(false),
+    }
+
+    TooltipManager::global().set_styled_tooltip(container, &status_text(snapshot));
+}
+
+/// Status line used for the tooltip and the popover.
+fn status_text(snapshot: &IdleInhibitorSnapshot) -> String {
+    if !snapshot.available {
+        return "Idle inhibitor unavailable".to_string();
+    }
+    match (snapshot.active, snapshot.remaining()) {
+        (false, _) => "Idle allowed".to_string(),
+        (true, None) => "Staying awake until turned off".to_string(),
+        (true, Some(_)) => format!("Staying awake: {}", idle_subtitle(snapshot)),
+    }
+}
+
+/// Human-readable label for a duration in minutes, e.g. "30 minutes", "1 hour".
+fn duration_label(minutes: u64) -> String {
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    let plural = |n: u64, unit: &str| {
+        if n == 1 {
+            format!("1 {}", unit)
+        } else {
+            format!("{} {}s", n, unit)
+        }
+    };
+    match (hours, minutes) {
+        (0, m) => plural(m, "minute"),
+        (h, 0) => plural(h, "hour"),
+        (h, m) => format!("{} {}", plural(h, "hour"), plural(m, "minute")),
+    }
+}
+
+/// Build the popover content: title, on/off switch and duration buttons.
+fn build_idle_inhibitor_popover(durations: &[u64], refs: &RefCell<PopoverRefs>) -> Widget {
+    let snapshot = IdleInhibitorService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(idle_inhibitor::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("Idle Inhibitor"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let switch = Switch::new();
+    switch.set_valign(Align::Center);
+    switch.set_active(snapshot.active);
+    switch.set_sensitive(snapshot.available);
+    switch.connect_active_notify(|switch| {
+        // Skip programmatic syncs so a running timer isn't cleared
+        let service = IdleInhibitorService::global();
+        if service.snapshot().active != switch.is_active() {
+            service.set_active(switch.is_active());
+        }
+    });
+    header.append(&switch);
+    container.append(&header);
+
+    let status = Label::new(Some(&status_text(&snapshot)));
+    status.add_css_class(color::MUTED);
+    status.set_halign(Align::Start);
+    container.append(&status);
+
+    let options = GtkBox::new(Orientation::Vertical, 2);
+    for &minutes in durations {
+        let button = Button::with_label(&format!("Keep awake for {}", duration_label(minutes)));
+        button.add_css_class(button::GHOST);
+        button.add_css_class(idle_inhibitor::DURATION_BUTTON);
+        if let Some(label) = button.child().and_downcast::<Label>() {
+            label.set_xalign(0.0);
+        }
+        button.set_sensitive(snapshot.available);
+        button.connect_clicked(move |_| {
+            IdleInhibitorService::global().enable_for(Duration::from_secs(minutes * 60));
+        });
+        options.append(&button);
+    }
+
+    let until_toggled = Button::with_label("Keep awake until turned off");
+    until_toggled.add_css_class(button::GHOST);
+    until_toggled.add_css_class(idle_inhibitor::DURATION_BUTTON);
+    if let Some(label) = until_toggled.child().and_downcast::<Label>() {
+        label.set_xalign(0.0);
+    }
+    until_toggled.set_sensitive(snapshot.available);
+    until_toggled.connect_clicked(|_| {
+        IdleInhibitorService::global().set_active(true);
+    });
+    options.append(&until_toggled);
+    container.append(&options);
+
+    let mut refs = refs.borrow_mut();
+    refs.switch = Some(switch);
+    refs.status = Some(status);
+
+    container.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_idle_inhibitor_config() {
+        let config = IdleInhibitorConfig::from_entry(&WidgetEntry {
+            name: "idle_inhibitor".to_string(),
+            options: HashMap::new(),
+        });
+        assert_eq!(config.durations, vec![30, 60]);
+        assert!(config.show_remaining);
+
+        let mut options = HashMap::new();
+        options.insert(
+            "durations".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::Integer(15),
+                toml::Value::Integer(-5),
+                toml::Value::Integer(120),
+            ]),
+        );
+        let config = IdleInhibitorConfig::from_entry(&WidgetEntry {
+            name: "idle_inhibitor".to_string(),
+            options,
+        });
+        assert_eq!(config.durations, vec![15, 120]);
+    }
+
+    #[test]
+    fn test_duration_label() {
+        assert_eq!(duration_label(1), "1 minute");
+        assert_eq!(duration_label(30), "30 minutes");
+        assert_eq!(duration_label(60), "1 hour");
+        assert_eq!(duration_label(150), "2 hours 30 minutes");
+    }
+}
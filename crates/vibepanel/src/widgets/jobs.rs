@@ -0,0 +1,264 @@
+//! Jobs widget - monitors long-running commands started via `vibepanel run`.
+//!
+//! This widget:
+//! - Shows a spinner and the number of running jobs
+//! - Shows the exit status of the most recent job once everything finished
+//! - Hides itself when no jobs have been tracked
+//! - Opens a popover listing recent jobs with their durations on click
+//!
+//! Configuration options:
+//! - `notify`: Send a desktop notification when a job finishes (default: true)
+
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Spinner, Widget};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::icons::IconHandle;
+use crate::services::jobs::{Job, JobState, JobsService, JobsSnapshot, format_duration};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{button, class, color, jobs, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_NOTIFY: bool = true;
+
+/// Configuration for the jobs widget.
+#[derive(Debug, Clone)]
+pub struct JobsConfig {
+    /// Send a desktop notification when a job finishes.
+    pub notify: bool,
+}
+
+impl WidgetConfig for JobsConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("jobs", entry, &["notify"]);
+
+        let notify = entry
+            .options
+            .get("notify")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_NOTIFY);
+
+        Self { notify }
+    }
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            notify: DEFAULT_NOTIFY,
+        }
+    }
+}
+
+/// Jobs widget that displays running and recently finished jobs.
+pub struct JobsWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover listing recent jobs.
+    _menu: Rc<MenuHandle>,
+}
+
+impl JobsWidget {
+    /// Create a new jobs widget with the given configuration.
+    pub fn new(config: JobsConfig) -> Self {
+        let base = BaseWidget::new(&[widget::JOBS]);
+        base.widget().set_visible(false);
+
+        let spinner = Spinner::new();
+        spinner.add_css_class(widget::JOBS_SPINNER);
+        spinner.set_valign(Align::Center);
+        base.content().append(&spinner);
+
+        let icon_handle = base.add_icon("emblem-ok-symbolic", &[widget::JOBS_ICON]);
+        let label = base.add_label(None, &[widget::JOBS_LABEL, class::VCENTER_CAPS]);
+
+        let menu = base.create_menu(build_jobs_popover);
+
+        let service = JobsService::global();
+        service.set_notify_on_finish(config.notify);
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            service.connect(move |snapshot: &JobsSnapshot| {
+                update_widget_from_snapshot(&container, &spinner, &icon_handle, &label, snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self { base, _menu: menu }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for JobsWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    spinner: &Spinner,
+    icon_handle: &IconHandle,
+    label: &Label,
+    snapshot: &JobsSnapshot,
+) {
+    if snapshot.jobs.is_empty() {
+        spinner.stop();
+        container.set_visible(false);
+        return;
+    }
+    container.set_visible(true);
+
+    container.remove_css_class(widget::JOBS_FAILED);
+    let running = snapshot.running_count();
+
+    let tooltip = if running > 0 {
+        spinner.set_visible(true);
+        spinner.start();
+        icon_handle.widget().set_visible(false);
+        update::set_label_text(label, &running.to_string());
+        label.set_visible(true);
+
+        let s = if running == 1 { "" } else { "s" };
+        format!("{} job{} running", running, s)
+    } else if let Some(job) = snapshot.last_finished() {
+        spinner.stop();
+        spinner.set_visible(false);
+        label.set_visible(false);
+
+        let icon = if job.succeeded() {
+            "emblem-ok-symbolic"
+        } else {
+            container.add_css_class(widget::JOBS_FAILED);
+            "dialog-error-symbolic"
+        };
+        icon_handle.set_icon(icon);
+        icon_handle.widget().set_visible(true);
+
+        format!("{}\n{}", job.name, status_text(job))
+    } else {
+        String::new()
+    };
+
+    TooltipManager::global().set_styled_tooltip(container, &tooltip);
+}
+
+/// Human-readable status with duration, e.g. "Running · 3m 05s".
+pub fn status_text(job: &Job) -> String {
+    let duration = format_duration(job.duration());
+    match job.state {
+        JobState::Running => format!("Running · {}", duration),
+        JobState::Exited(0) => format!("Done · {}", duration),
+        JobState::Exited(code) => format!("Exit {} · {}", code, duration),
+        JobState::Lost => format!("Interrupted · {}", duration),
+    }
+}
+
+/// Build the popover content listing recent jobs.
+fn build_jobs_popover() -> Widget {
+    let snapshot = JobsService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(jobs::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("Jobs"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    if snapshot
+        .jobs
+        .iter()
+        .any(|job| job.state != JobState::Running)
+    {
+        let clear = Button::with_label("Clear");
+        clear.add_css_class(button::GHOST);
+        clear.connect_clicked(|_| JobsService::global().clear_finished());
+        header.append(&clear);
+    }
+    container.append(&header);
+
+    if snapshot.jobs.is_empty() {
+        let empty = Label::new(Some("No recent jobs"));
+        empty.add_css_class(color::MUTED);
+        empty.set_halign(Align::Start);
+        container.append(&empty);
+    }
+
+    for job in &snapshot.jobs {
+        container.append(&build_job_row(job));
+    }
+
+    container.upcast()
+}
+
+/// Build a single job row: name on the left, status on the right.
+fn build_job_row(job: &Job) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 12);
+    row.add_css_class(jobs::ROW);
+    if matches!(job.state, JobState::Exited(code) if code != 0) || job.state == JobState::Lost {
+        row.add_css_class(jobs::ROW_FAILED);
+    }
+
+    let name = Label::new(Some(&job.name));
+    name.add_css_class(jobs::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(EllipsizeMode::Middle);
+    name.set_max_width_chars(40);
+    row.append(&name);
+
+    let status = Label::new(Some(&status_text(job)));
+    status.add_css_class(jobs::ROW_STATUS);
+    status.add_css_class(color::MUTED);
+    status.set_halign(Align::End);
+    row.append(&status);
+
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_jobs_config_defaults() {
+        let entry = WidgetEntry {
+            name: "jobs".to_string(),
+            options: Default::default(),
+        };
+        let config = JobsConfig::from_entry(&entry);
+        assert!(config.notify);
+    }
+
+    #[test]
+    fn test_status_text() {
+        let started = SystemTime::UNIX_EPOCH;
+        let mut job = Job {
+            id: 1,
+            name: "make".to_string(),
+            started,
+            ended: Some(started + Duration::from_secs(185)),
+            state: JobState::Exited(0),
+        };
+        assert_eq!(status_text(&job), "Done · 3m 05s");
+
+        job.state = JobState::Exited(2);
+        assert_eq!(status_text(&job), "Exit 2 · 3m 05s");
+    }
+}
@@ -19,6 +19,8 @@ use std::rc::Rc;
 
 use crate::services::compositor::CompositorManager;
 use crate::services::config_manager::ConfigManager;
+use crate::services::event_bus::{EventBus, SubscriptionScope};
+use crate::services::leak_check::LeakCheck;
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::styles::{class, surface};
 
@@ -36,14 +38,51 @@ const POPOVER_DEFAULT_WIDTH_ESTIMATE: i32 = 320;
 
 const POPOVER_MIN_VALID_WIDTH: i32 = 20;
 
-/// Calculate the top margin for a popover based on bar configuration.
+/// Screen edge the bar is anchored to (`bar.position`).
+pub fn bar_edge() -> Edge {
+    if ConfigManager::global().bar_at_bottom() {
+        Edge::Bottom
+    } else {
+        Edge::Top
+    }
+}
+
+/// Anchor a popover-like surface to the bar's screen edge, leaving the
+/// opposite edge free so the surface keeps its natural height.
+pub fn anchor_to_bar_edge(window: &impl IsA<gtk4::Window>) {
+    let edge = bar_edge();
+    let opposite = if edge == Edge::Top {
+        Edge::Bottom
+    } else {
+        Edge::Top
+    };
+    window.set_anchor(edge, true);
+    window.set_anchor(opposite, false);
+}
+
+/// Apply shadow margins around popover content, with no margin on the side
+/// facing the bar so the popover sits flush against its offset.
+pub fn set_shadow_margins(outer: &GtkBox, margin: i32) {
+    let (top, bottom) = if bar_edge() == Edge::Bottom {
+        (margin, 0)
+    } else {
+        (0, margin)
+    };
+    outer.set_margin_top(top);
+    outer.set_margin_bottom(bottom);
+    outer.set_margin_start(margin);
+    outer.set_margin_end(margin);
+}
+
+/// Calculate the bar-side margin for a popover based on bar configuration.
 ///
+/// This is the top margin for a top bar and the bottom margin for a bottom bar.
 /// When the bar has a visible background (opacity > 0), the popover needs to
 /// account for bar padding in its positioning. This ensures consistent visual
 /// spacing regardless of bar transparency settings.
 ///
 /// Used by both `LayerShellPopover` and Quick Settings for consistent positioning.
-pub fn calculate_popover_top_margin() -> i32 {
+pub fn calculate_popover_bar_margin() -> i32 {
     let config_mgr = ConfigManager::global();
     let bar_padding = config_mgr.bar_padding() as i32;
     let bar_opacity = config_mgr.bar_background_opacity();
@@ -133,13 +172,13 @@ pub fn calculate_bar_exclusive_zone() -> i32 {
 /// Create a click-catcher layer-shell surface.
 ///
 /// The click-catcher is a fullscreen transparent surface that sits behind popovers
-/// and captures clicks outside the popover to dismiss it. It has a top margin
-/// equal to the bar's exclusive zone so clicks on the bar pass through.
+/// and captures clicks outside the popover to dismiss it. It has a margin on the
+/// bar's edge equal to the bar's exclusive zone so clicks on the bar pass through.
 ///
 /// # Arguments
 ///
 /// * `app` - The GTK application
-/// * `bar_zone` - Height of the bar's exclusive zone (margin at top to leave bar uncovered)
+/// * `bar_zone` - Height of the bar's exclusive zone (margin on the bar edge to leave bar uncovered)
 /// * `on_dismiss` - Callback invoked when the catcher is clicked
 ///
 /// # Returns
@@ -173,7 +212,7 @@ where
     catcher.set_keyboard_mode(KeyboardMode::None);
 
     // Leave the bar area uncovered so clicks/hovers pass through to bar widgets.
-    catcher.set_margin(Edge::Top, bar_zone);
+    catcher.set_margin(bar_edge(), bar_zone);
 
     // Content - add CSS class to the child widget for background styling
     let overlay = GtkBox::new(Orientation::Vertical, 0);
@@ -228,6 +267,8 @@ pub struct LayerShellPopover {
     builder: Rc<dyn Fn() -> gtk4::Widget>,
     window: RefCell<Option<ApplicationWindow>>,
     click_catcher: RefCell<Option<ApplicationWindow>>,
+    /// Service subscriptions made while building the current content.
+    content_scope: RefCell<Option<SubscriptionScope>>,
     /// Anchor X coordinate (widget center) in monitor coordinates.
     anchor_x: Cell<i32>,
     anchor_monitor: RefCell<Option<Monitor>>,
@@ -251,6 +292,7 @@ impl LayerShellPopover {
             builder: Rc::new(builder),
             window: RefCell::new(None),
             click_catcher: RefCell::new(None),
+            content_scope: RefCell::new(None),
             anchor_x: Cell::new(0),
             anchor_monitor: RefCell::new(None),
         })
@@ -281,8 +323,13 @@ impl LayerShellPopover {
         }
 
         // Destroy main window
-        if let Some(window) = self.window.borrow_mut().take() {
+        let window = self.window.borrow_mut().take();
+        if let Some(window) = window {
             window.close();
+            // Content callbacks would otherwise keep the closed content alive
+            let scope = self.content_scope.borrow_mut().take();
+            drop(scope);
+            LeakCheck::global().report("popover closed");
         }
     }
 
@@ -353,14 +400,19 @@ impl LayerShellPopover {
         window.init_layer_shell();
         window.set_layer(Layer::Top);
         window.set_exclusive_zone(0);
-        window.set_anchor(Edge::Top, true);
+        anchor_to_bar_edge(&window);
         window.set_anchor(Edge::Right, true);
-        window.set_anchor(Edge::Bottom, false);
         window.set_anchor(Edge::Left, false);
         window.set_keyboard_mode(popover_keyboard_mode());
 
-        // Build content
-        let content = (self.builder)();
+        // Build content, recording the service subscriptions it makes
+        let scope = EventBus::global().scope();
+        let content = {
+            let _scope = scope.enter();
+            (self.builder)()
+        };
+        *self.content_scope.borrow_mut() = Some(scope);
+        LeakCheck::global().track_object("popover window", &window);
         content.add_css_class(surface::POPOVER);
         let popover_class = format!("{}-popover", self.widget_name);
         content.add_css_class(&popover_class);
@@ -369,10 +421,7 @@ impl LayerShellPopover {
         let outer = GtkBox::new(Orientation::Vertical, 0);
         outer.add_css_class(surface::WIDGET_MENU);
         outer.add_css_class(surface::NO_FOCUS);
-        outer.set_margin_top(0);
-        outer.set_margin_bottom(POPOVER_SHADOW_MARGIN);
-        outer.set_margin_start(POPOVER_SHADOW_MARGIN);
-        outer.set_margin_end(POPOVER_SHADOW_MARGIN);
+        set_shadow_margins(&outer, POPOVER_SHADOW_MARGIN);
         outer.append(&content);
 
         // Apply surface styles (background, shadow, font) to the content
@@ -420,8 +469,8 @@ impl LayerShellPopover {
 
         let geom = monitor.geometry();
 
-        // Set top margin
-        window.set_margin(Edge::Top, calculate_popover_top_margin());
+        // Offset from the bar
+        window.set_margin(bar_edge(), calculate_popover_bar_margin());
 
         // Calculate horizontal position (center on anchor_x)
         if anchor_x > 0 {
@@ -4,11 +4,13 @@
 //! - Compact bar display with album art thumbnail (or play/pause icon fallback)
 //! - Hides completely when no MPRIS player is available
 //! - Click opens a popover with full playback controls
+//! - Middle-click toggles play/pause, scrolling skips to the next/previous track
 //! - Pop-out button to open a standalone draggable window
+//! - Podcasts and audiobooks get ±30s skip buttons in the popover and window
 
-use gtk4::Image;
 use gtk4::gio;
 use gtk4::prelude::*;
+use gtk4::{EventControllerScroll, EventControllerScrollFlags, GestureClick, Image, glib};
 use std::cell::RefCell;
 use std::rc::Rc;
 use tracing::{debug, warn};
@@ -17,11 +19,13 @@ use vibepanel_core::config::WidgetEntry;
 use crate::services::callbacks::CallbackId;
 use crate::services::config_manager::ConfigManager;
 use crate::services::icons::{IconHandle, resolve_app_icon_name, set_image_from_app_id};
-use crate::services::media::{MediaService, MediaSnapshot, PlaybackStatus};
+use crate::services::media::{
+    LongFormConfig, MediaService, MediaSnapshot, PlaybackStatus, SkipMode,
+};
 use crate::services::state;
 use crate::services::tooltip::TooltipManager;
 use crate::styles::media;
-use crate::widgets::base::{BaseWidget, MenuHandle};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
 use crate::widgets::marquee_label::MarqueeLabel;
 use crate::widgets::media_components::{ArtState, load_art_from_url};
 use crate::widgets::media_popover::{MediaPopoverController, build_media_popover_with_controller};
@@ -48,14 +52,22 @@ const ART_DISPLAY_SCALE: f64 = 0.75;
 /// Configuration for the media widget.
 #[derive(Debug, Clone)]
 pub struct MediaConfig {
-    /// Template string for rendering.
+    /// Template string for rendering (`format` is accepted as an alias).
     /// Widget tokens: {art}, {player_icon}, {icon}, {controls}
     /// Text tokens: {title}, {artist}, {album}
     pub template: String,
     /// Text to show when no player is available (empty = hide widget).
     pub empty_text: String,
-    /// Maximum text length (0 = unlimited).
+    /// Maximum text length (0 = unlimited; `max_length` is accepted as an alias).
+    /// Longer text scrolls.
     pub max_chars: usize,
+    /// Players to prefer when several are available, most preferred first.
+    /// Matches the player id (e.g. "spotify") or name, case-insensitively.
+    pub player_priority: Vec<String>,
+    /// When the popover's skip buttons seek ±30s instead of changing track.
+    /// Options: `long_form_minutes` (0 = ignore length), `long_form_genres`,
+    /// and `skip_mode`, a table of player to "auto", "seek" or "track".
+    pub long_form: LongFormConfig,
     /// Opacity for the pop-out window (0.0 = fully transparent, 1.0 = fully opaque).
     ///
     /// Note: This field is parsed for config validation but read dynamically from
@@ -69,12 +81,24 @@ impl WidgetConfig for MediaConfig {
         warn_unknown_options(
             "media",
             entry,
-            &["template", "empty_text", "max_chars", "popout_opacity"],
+            &[
+                "template",
+                "format",
+                "empty_text",
+                "max_chars",
+                "max_length",
+                "player_priority",
+                "long_form_minutes",
+                "long_form_genres",
+                "skip_mode",
+                "popout_opacity",
+            ],
         );
 
         let template = entry
             .options
             .get("template")
+            .or_else(|| entry.options.get("format"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
@@ -89,10 +113,55 @@ impl WidgetConfig for MediaConfig {
         let max_chars = entry
             .options
             .get("max_chars")
+            .or_else(|| entry.options.get("max_length"))
             .and_then(|v| v.as_integer())
             .map(|v| v.max(0) as usize)
             .unwrap_or(DEFAULT_MAX_CHARS);
 
+        let player_priority = entry
+            .options
+            .get("player_priority")
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut long_form = LongFormConfig::default();
+        if let Some(minutes) = entry
+            .options
+            .get("long_form_minutes")
+            .and_then(|v| v.as_integer())
+        {
+            long_form.min_length_us = minutes.max(0) * 60 * 1_000_000;
+        }
+        if let Some(genres) = entry
+            .options
+            .get("long_form_genres")
+            .and_then(|v| v.as_array())
+        {
+            long_form.genres = genres
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect();
+        }
+        if let Some(players) = entry.options.get("skip_mode").and_then(|v| v.as_table()) {
+            for (player, mode) in players {
+                match mode.as_str().and_then(SkipMode::parse) {
+                    Some(mode) => long_form.players.push((player.clone(), mode)),
+                    None => warn!(
+                        "Invalid media skip_mode for '{}': {} (expected \"auto\", \"seek\" or \"track\")",
+                        player, mode
+                    ),
+                }
+            }
+        }
+
         let popout_opacity = entry
             .options
             .get("popout_opacity")
@@ -104,6 +173,8 @@ impl WidgetConfig for MediaConfig {
             template,
             empty_text,
             max_chars,
+            player_priority,
+            long_form,
             popout_opacity,
         }
     }
@@ -115,6 +186,8 @@ impl Default for MediaConfig {
             template: DEFAULT_TEMPLATE.to_string(),
             empty_text: String::new(),
             max_chars: DEFAULT_MAX_CHARS,
+            player_priority: Vec::new(),
+            long_form: LongFormConfig::default(),
             popout_opacity: 1.0,
         }
     }
@@ -709,6 +782,31 @@ impl MediaWidget {
         }
 
         let media_service = MediaService::global();
+        media_service.set_player_priority(config.player_priority.clone());
+        media_service.set_long_form(config.long_form.clone());
+
+        // Middle-click toggles playback; the left button opens the popover.
+        let middle_click = GestureClick::new();
+        middle_click.set_button(2);
+        middle_click.connect_released(|_, _, _, _| {
+            MediaService::global().play_pause();
+        });
+        base.widget().add_controller(middle_click);
+
+        // Discrete so touchpads don't skip several tracks per swipe.
+        let scroll = EventControllerScroll::new(
+            EventControllerScrollFlags::VERTICAL | EventControllerScrollFlags::DISCRETE,
+        );
+        scroll.connect_scroll(|_, _, dy| {
+            if dy < 0.0 {
+                MediaService::global().next();
+            } else if dy > 0.0 {
+                MediaService::global().previous();
+            }
+            glib::Propagation::Stop
+        });
+        base.widget().add_controller(scroll);
+
         let template_elements = template_elements.clone();
         let art_state = Rc::new(RefCell::new(ArtState::default()));
 
@@ -761,6 +859,12 @@ impl MediaWidget {
     }
 }
 
+impl BarWidget for MediaWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
 impl Drop for MediaWidget {
     fn drop(&mut self) {
         MediaService::global().disconnect(self.media_callback_id);
@@ -1037,6 +1141,7 @@ fn build_tooltip(snapshot: &MediaSnapshot) -> String {
 mod tests {
     use super::*;
     use crate::services::media::MediaMetadata;
+    use std::collections::HashMap;
 
     #[test]
     fn test_media_config_defaults() {
@@ -1048,6 +1153,59 @@ mod tests {
         assert_eq!(config.template, "{art}{artist} - {title}{controls}");
         assert_eq!(config.empty_text, "");
         assert_eq!(config.max_chars, 20);
+        assert!(config.player_priority.is_empty());
+    }
+
+    #[test]
+    fn test_media_config_aliases_and_priority() {
+        let mut options = HashMap::new();
+        options.insert(
+            "format".to_string(),
+            toml::Value::String("{title}".to_string()),
+        );
+        options.insert("max_length".to_string(), toml::Value::Integer(30));
+        options.insert(
+            "player_priority".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("spotify".to_string()),
+                toml::Value::String("mpv".to_string()),
+            ]),
+        );
+        let entry = WidgetEntry {
+            name: "media".to_string(),
+            options,
+        };
+        let config = MediaConfig::from_entry(&entry);
+        assert_eq!(config.template, "{title}");
+        assert_eq!(config.max_chars, 30);
+        assert_eq!(config.player_priority, vec!["spotify", "mpv"]);
+    }
+
+    #[test]
+    fn test_media_config_long_form() {
+        let mut skip_mode = toml::Table::new();
+        skip_mode.insert(
+            "spotify".to_string(),
+            toml::Value::String("track".to_string()),
+        );
+        skip_mode.insert(
+            "gpodder".to_string(),
+            toml::Value::String("rewind".to_string()),
+        );
+        let mut options = HashMap::new();
+        options.insert("long_form_minutes".to_string(), toml::Value::Integer(0));
+        options.insert("skip_mode".to_string(), toml::Value::Table(skip_mode));
+        let entry = WidgetEntry {
+            name: "media".to_string(),
+            options,
+        };
+        let config = MediaConfig::from_entry(&entry);
+        assert_eq!(config.long_form.min_length_us, 0);
+        // Invalid modes are skipped
+        assert_eq!(
+            config.long_form.players,
+            vec![("spotify".to_string(), SkipMode::Track)]
+        );
     }
 
     #[test]
@@ -2,6 +2,7 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
 
 use gtk4::gdk_pixbuf::Pixbuf;
 use gtk4::gio;
@@ -12,11 +13,16 @@ use tracing::debug;
 
 use crate::services::config_manager::ConfigManager;
 use crate::services::icons::{IconHandle, IconsService};
-use crate::services::media::{MediaService, MediaSnapshot, PlaybackStatus, format_duration};
+use crate::services::media::{
+    MediaService, MediaSnapshot, PlaybackStatus, format_duration, is_loved,
+};
 use crate::styles::{button, color, icon, media};
 use crate::widgets::marquee_label::MarqueeLabel;
 use crate::widgets::rounded_picture::RoundedPicture;
 
+/// How often a visible seek bar advances between position reports (in milliseconds).
+const SEEK_TICK_MS: u64 = 250;
+
 // ============================================================================
 // Shared Controller
 // ============================================================================
@@ -36,7 +42,11 @@ pub struct MediaViewController {
     pub play_pause_btn: Button,
     pub play_pause_icon: IconHandle,
     pub prev_btn: Button,
+    pub prev_icon: IconHandle,
     pub next_btn: Button,
+    pub next_icon: IconHandle,
+    pub love_btn: Button,
+    pub love_icon: IconHandle,
     pub seek_scale: Scale,
     pub position_label: Label,
     pub duration_label: Label,
@@ -61,11 +71,12 @@ impl MediaViewController {
         update_playback_controls(
             &self.play_pause_icon,
             &self.play_pause_btn,
-            &self.prev_btn,
-            &self.next_btn,
+            (&self.prev_btn, &self.prev_icon),
+            (&self.next_btn, &self.next_icon),
             &self.seek_scale,
             snapshot,
         );
+        update_love_button(&self.love_btn, &self.love_icon, snapshot);
         update_seek_position(
             &self.seek_scale,
             &self.position_label,
@@ -136,10 +147,20 @@ where
 // ============================================================================
 
 /// Build media control buttons (prev, play/pause, next).
-/// Returns (container, prev_btn, play_pause_btn, play_pause_icon, next_btn)
+///
+/// Prev/next seek ±30s instead for long-form audio (see `MediaSnapshot::long_form`).
+/// Returns (container, prev_btn, prev_icon, play_pause_btn, play_pause_icon, next_btn, next_icon)
 pub fn build_media_controls(
     extra_classes: &[&str],
-) -> (GtkBox, Button, Button, IconHandle, Button) {
+) -> (
+    GtkBox,
+    Button,
+    IconHandle,
+    Button,
+    IconHandle,
+    Button,
+    IconHandle,
+) {
     let icons = IconsService::global();
 
     let container = GtkBox::new(Orientation::Horizontal, 8);
@@ -159,7 +180,7 @@ pub fn build_media_controls(
     }
     prev_btn.set_tooltip_text(Some("Previous"));
     prev_btn.set_valign(Align::Center);
-    prev_btn.connect_clicked(|_| MediaService::global().previous());
+    prev_btn.connect_clicked(|_| MediaService::global().skip_backward());
     container.append(&prev_btn);
 
     // Play/pause button
@@ -193,18 +214,44 @@ pub fn build_media_controls(
     }
     next_btn.set_tooltip_text(Some("Next"));
     next_btn.set_valign(Align::Center);
-    next_btn.connect_clicked(|_| MediaService::global().next());
+    next_btn.connect_clicked(|_| MediaService::global().skip_forward());
     container.append(&next_btn);
 
     (
         container,
         prev_btn,
+        prev_icon,
         play_pause_btn,
         play_pause_icon,
         next_btn,
+        next_icon,
     )
 }
 
+/// Build the love button, which toggles the current track between loved
+/// (full rating) and unrated.
+/// Returns (button, icon). Hidden until the track has a rating or can be rated.
+pub fn build_love_button(classes: &[&str]) -> (Button, IconHandle) {
+    let icons = IconsService::global();
+
+    let love_icon = icons.create_icon("non-starred-symbolic", &[icon::ICON]);
+    love_icon.widget().set_halign(Align::Center);
+    love_icon.widget().set_valign(Align::Center);
+    let love_btn = Button::new();
+    love_btn.set_has_frame(false);
+    love_btn.set_focusable(false);
+    love_btn.set_child(Some(&love_icon.widget()));
+    love_btn.add_css_class(media::LOVE_BTN);
+    for class in classes {
+        love_btn.add_css_class(class);
+    }
+    love_btn.set_valign(Align::Center);
+    love_btn.set_visible(false);
+    love_btn.connect_clicked(|_| MediaService::global().toggle_loved());
+
+    (love_btn, love_icon)
+}
+
 /// Build seek bar with time labels.
 /// Returns (container, scale, position_label, duration_label, is_seeking)
 pub fn build_seek_section(
@@ -259,10 +306,9 @@ pub fn build_seek_section(
                     if let Some(position) = pending_seek.borrow_mut().take() {
                         MediaService::global().set_position(position);
                         let is_seeking = is_seeking.clone();
-                        glib::timeout_add_local_once(
-                            std::time::Duration::from_millis(150),
-                            move || *is_seeking.borrow_mut() = false,
-                        );
+                        glib::timeout_add_local_once(Duration::from_millis(150), move || {
+                            *is_seeking.borrow_mut() = false
+                        });
                     }
                     glib::Propagation::Proceed
                 }
@@ -272,6 +318,42 @@ pub fn build_seek_section(
     }
     scale.add_controller(legacy_controller);
 
+    // Advance the bar locally while it's on screen; players report their
+    // position only when polled or after a seek
+    let tick_source = Rc::new(RefCell::new(None::<glib::SourceId>));
+    {
+        let tick_source = tick_source.clone();
+        let is_seeking = is_seeking.clone();
+        let position_label = position_label.clone();
+        scale.connect_map(move |scale| {
+            let scale_weak = scale.downgrade();
+            let is_seeking = is_seeking.clone();
+            let position_label = position_label.clone();
+            let source = glib::timeout_add_local(Duration::from_millis(SEEK_TICK_MS), move || {
+                let Some(scale) = scale_weak.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+                // A range of 0..1 means the track length is unknown
+                if !*is_seeking.borrow()
+                    && scale.adjustment().upper() > 1.0
+                    && let Some(position) = MediaService::global().interpolated_position()
+                {
+                    scale.set_value(position as f64);
+                    position_label.set_label(&format_duration(position));
+                }
+                glib::ControlFlow::Continue
+            });
+            if let Some(old) = tick_source.replace(Some(source)) {
+                old.remove();
+            }
+        });
+    }
+    scale.connect_unmap(move |_| {
+        if let Some(source) = tick_source.take() {
+            source.remove();
+        }
+    });
+
     {
         let is_pressed = is_pressed.clone();
         let is_seeking = is_seeking.clone();
@@ -405,8 +487,8 @@ pub fn update_track_info(
 pub fn update_playback_controls(
     play_pause_icon: &IconHandle,
     play_pause_btn: &Button,
-    prev_btn: &Button,
-    next_btn: &Button,
+    (prev_btn, prev_icon): (&Button, &IconHandle),
+    (next_btn, next_icon): (&Button, &IconHandle),
     seek_scale: &Scale,
     snapshot: &MediaSnapshot,
 ) {
@@ -415,9 +497,51 @@ pub fn update_playback_controls(
         PlaybackStatus::Paused | PlaybackStatus::Stopped => "media-playback-start",
     });
     play_pause_btn.set_sensitive(snapshot.can_play || snapshot.can_pause);
-    prev_btn.set_sensitive(snapshot.can_go_previous);
-    next_btn.set_sensitive(snapshot.can_go_next);
     seek_scale.set_sensitive(snapshot.can_seek);
+
+    if snapshot.long_form {
+        prev_icon.set_icon("media-rewind-30-symbolic");
+        next_icon.set_icon("media-forward-30-symbolic");
+        prev_btn.set_tooltip_text(Some("Back 30 seconds"));
+        next_btn.set_tooltip_text(Some("Forward 30 seconds"));
+        prev_btn.set_sensitive(snapshot.can_seek);
+        next_btn.set_sensitive(snapshot.can_seek);
+    } else {
+        prev_icon.set_icon("skip_previous");
+        next_icon.set_icon("skip_next");
+        prev_btn.set_tooltip_text(Some("Previous"));
+        next_btn.set_tooltip_text(Some("Next"));
+        prev_btn.set_sensitive(snapshot.can_go_previous);
+        next_btn.set_sensitive(snapshot.can_go_next);
+    }
+}
+
+/// Update the love button from a media snapshot.
+///
+/// Players that only report `xesam:userRating` show the state without
+/// letting it be changed.
+pub fn update_love_button(love_btn: &Button, love_icon: &IconHandle, snapshot: &MediaSnapshot) {
+    let rating = snapshot.metadata.user_rating;
+    love_btn.set_visible(snapshot.can_rate || rating.is_some());
+    love_btn.set_sensitive(snapshot.can_rate);
+
+    let loved = is_loved(rating);
+    love_icon.set_icon(if loved {
+        "starred-symbolic"
+    } else {
+        "non-starred-symbolic"
+    });
+    if loved {
+        love_btn.add_css_class(media::LOVED);
+    } else {
+        love_btn.remove_css_class(media::LOVED);
+    }
+    love_btn.set_tooltip_text(Some(match (loved, snapshot.can_rate) {
+        (true, true) => "Unlove",
+        (false, true) => "Love",
+        (true, false) => "Loved",
+        (false, false) => "Not rated",
+    }));
 }
 
 /// Update seek bar position from a media snapshot.
@@ -10,8 +10,8 @@ use crate::services::tooltip::TooltipManager;
 use crate::styles::{button, color, icon, media, qs, surface};
 use crate::widgets::base::configure_popover;
 use crate::widgets::media_components::{
-    MediaViewController, build_album_art, build_media_controls, build_seek_section,
-    build_track_info,
+    MediaViewController, build_album_art, build_love_button, build_media_controls,
+    build_seek_section, build_track_info,
 };
 
 const POPOVER_ART_SIZE: i32 = 140;
@@ -52,6 +52,10 @@ where
     buttons_row.set_valign(Align::Start);
     buttons_row.add_css_class(media::HEADER);
 
+    // Love button (only shown for players with ratings)
+    let (love_btn, love_icon) = build_love_button(&[surface::POPOVER_ICON_BTN]);
+    buttons_row.append(&love_btn);
+
     // Player selector button
     let player_btn = Button::new();
     player_btn.set_has_frame(false);
@@ -101,8 +105,15 @@ where
     info_spacer.set_vexpand(true);
     info_section.append(&info_spacer);
 
-    let (controls_container, prev_btn, play_pause_btn, play_pause_icon, next_btn) =
-        build_media_controls(&[]);
+    let (
+        controls_container,
+        prev_btn,
+        prev_icon,
+        play_pause_btn,
+        play_pause_icon,
+        next_btn,
+        next_icon,
+    ) = build_media_controls(&[]);
     info_section.append(&controls_container);
 
     main_row.append(&info_section);
@@ -123,7 +134,11 @@ where
         play_pause_btn,
         play_pause_icon,
         prev_btn,
+        prev_icon,
         next_btn,
+        next_icon,
+        love_btn,
+        love_icon,
         seek_scale,
         position_label,
         duration_label,
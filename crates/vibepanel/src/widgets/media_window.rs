@@ -13,8 +13,8 @@ use crate::services::media::MediaService;
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::styles::media;
 use crate::widgets::media_components::{
-    MediaViewController, build_album_art, build_media_controls, build_seek_section,
-    build_track_info,
+    MediaViewController, build_album_art, build_love_button, build_media_controls,
+    build_seek_section, build_track_info,
 };
 
 const WINDOW_ART_SIZE: i32 = 100;
@@ -151,8 +151,17 @@ where
     track_info_container.set_margin_bottom(4);
     info_section.append(&track_info_container);
 
-    let (controls_container, prev_btn, play_pause_btn, play_pause_icon, next_btn) =
-        build_media_controls(&[media::WINDOW_CONTROL_BTN]);
+    let (
+        controls_container,
+        prev_btn,
+        prev_icon,
+        play_pause_btn,
+        play_pause_icon,
+        next_btn,
+        next_icon,
+    ) = build_media_controls(&[media::WINDOW_CONTROL_BTN]);
+    let (love_btn, love_icon) = build_love_button(&[media::CONTROL_BTN, media::WINDOW_CONTROL_BTN]);
+    controls_container.append(&love_btn);
     info_section.append(&controls_container);
 
     content_row.append(&info_section);
@@ -176,7 +185,11 @@ where
         play_pause_btn,
         play_pause_icon,
         prev_btn,
+        prev_icon,
         next_btn,
+        next_icon,
+        love_btn,
+        love_icon,
         seek_scale,
         position_label,
         duration_label,
@@ -4,6 +4,13 @@
 //! canonical snapshots; this widget subscribes to those snapshots and renders
 //! icon/text/CSS/tooltip accordingly.
 //!
+//! Configuration options:
+//! - `show_icon`: Whether to show the icon (default: true)
+//! - `format`: "percentage", "absolute" or "both" (default: "percentage")
+//! - `interval`: Seconds between updates (default: 3, minimum 1)
+//! - `source`: "local", or "ssh:<host>" to show another machine's memory via
+//!   `RemoteStatsService` (default: "local")
+//!
 //! Uses:
 //! - `IconsService` (via BaseWidget) for themed memory icon
 //! - `TooltipManager` for styled tooltips
@@ -14,12 +21,18 @@ use gtk4::prelude::*;
 use vibepanel_core::config::WidgetEntry;
 
 use crate::services::icons::IconHandle;
-use crate::services::system::{SystemService, SystemSnapshot, format_bytes, format_bytes_long};
+use crate::services::remote_stats::{RemoteStatsService, RemoteStatsSnapshot};
+use crate::services::system::{
+    DEFAULT_POLL_INTERVAL_SECS, MIN_POLL_INTERVAL_SECS, SystemService, SystemSnapshot,
+    format_bytes, format_bytes_long,
+};
 use crate::services::tooltip::TooltipManager;
 use crate::styles::{class, widget};
-use crate::widgets::base::BaseWidget;
+use crate::widgets::base::{BarWidget, BaseWidget, PopoverSection};
 use crate::widgets::system_popover::SystemPopoverBinding;
-use crate::widgets::{WidgetConfig, warn_unknown_options};
+use crate::widgets::{
+    WidgetConfig, parse_interval, parse_stats_source, stats_title, update, warn_unknown_options,
+};
 
 /// Default configuration values
 const DEFAULT_SHOW_ICON: bool = true;
@@ -54,11 +67,19 @@ pub struct MemoryConfig {
     pub show_icon: bool,
     /// Display format for memory usage.
     pub format: MemoryFormat,
+    /// Seconds between updates.
+    pub interval: u32,
+    /// Host to read stats from over SSH; `None` for this machine.
+    pub source: Option<String>,
 }
 
 impl WidgetConfig for MemoryConfig {
     fn from_entry(entry: &WidgetEntry) -> Self {
-        warn_unknown_options("memory", entry, &["show_icon", "format"]);
+        warn_unknown_options(
+            "memory",
+            entry,
+            &["show_icon", "format", "interval", "source"],
+        );
 
         let show_icon = entry
             .options
@@ -73,7 +94,22 @@ impl WidgetConfig for MemoryConfig {
             .map(MemoryFormat::from_str)
             .unwrap_or_default();
 
-        Self { show_icon, format }
+        let interval = parse_interval(
+            "memory",
+            |key| entry.options.get(key),
+            None,
+            DEFAULT_POLL_INTERVAL_SECS as u64,
+            MIN_POLL_INTERVAL_SECS as u64,
+        ) as u32;
+
+        let source = parse_stats_source("memory", entry);
+
+        Self {
+            show_icon,
+            format,
+            interval,
+            source,
+        }
     }
 }
 
@@ -82,6 +118,8 @@ impl Default for MemoryConfig {
         Self {
             show_icon: DEFAULT_SHOW_ICON,
             format: MemoryFormat::default(),
+            interval: DEFAULT_POLL_INTERVAL_SECS,
+            source: None,
         }
     }
 }
@@ -127,7 +165,6 @@ impl MemoryWidget {
             .widget()
             .set_visible(widget.config.show_icon);
 
-        let system_service = SystemService::global();
         {
             let container = widget.base.widget().clone();
             let icon_handle = widget.icon_handle.clone();
@@ -135,19 +172,44 @@ impl MemoryWidget {
             let show_icon = widget.config.show_icon;
             let format = widget.config.format.clone();
             let popover_binding = widget.popover_binding.clone();
-
-            system_service.connect(move |snapshot: &SystemSnapshot| {
-                update_memory_widget(
-                    &container,
-                    &icon_handle,
-                    &memory_label,
-                    show_icon,
-                    &format,
-                    snapshot,
-                );
-
-                popover_binding.update_if_open(snapshot);
-            });
+            let title = stats_title("Memory", widget.config.source.as_deref());
+
+            let update = {
+                let container = container.clone();
+                let title = title.clone();
+                move |snapshot: &SystemSnapshot| {
+                    update_memory_widget(
+                        &container,
+                        &icon_handle,
+                        &memory_label,
+                        show_icon,
+                        &format,
+                        &title,
+                        snapshot,
+                    );
+
+                    popover_binding.update_if_open(snapshot);
+                }
+            };
+
+            match widget.config.source {
+                Some(ref host) => {
+                    RemoteStatsService::global().connect(
+                        host,
+                        widget.config.interval,
+                        move |remote: &RemoteStatsSnapshot| {
+                            update(&remote.system);
+                            if let Some(ref err) = remote.error {
+                                TooltipManager::global()
+                                    .set_styled_tooltip(&container, &format!("{}: {}", title, err));
+                            }
+                        },
+                    );
+                }
+                None => {
+                    SystemService::global().connect_every(widget.config.interval, update);
+                }
+            }
         }
 
         widget
@@ -159,6 +221,20 @@ impl MemoryWidget {
     }
 }
 
+impl BarWidget for MemoryWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+
+    fn popover_section(&self) -> PopoverSection {
+        // CPU and memory widgets open the same system popover
+        self.base.popover_section().with_key(format!(
+            "system:{}",
+            self.config.source.as_deref().unwrap_or("local")
+        ))
+    }
+}
+
 /// Format memory usage according to the selected format.
 fn format_memory(snapshot: &SystemSnapshot, format: &MemoryFormat) -> String {
     match format {
@@ -179,25 +255,26 @@ fn update_memory_widget(
     memory_label: &Label,
     show_icon: bool,
     format: &MemoryFormat,
+    title: &str,
     snapshot: &SystemSnapshot,
 ) {
     if !snapshot.available {
         if show_icon {
             icon_handle.widget().set_visible(true);
         }
-        memory_label.set_label("?");
+        update::set_label_text(memory_label, "?");
         memory_label.set_visible(true);
 
         let tooltip_manager = TooltipManager::global();
-        tooltip_manager.set_styled_tooltip(container, "Memory: Service unavailable");
+        tooltip_manager.set_styled_tooltip(container, &format!("{}: Service unavailable", title));
         return;
     }
 
     if snapshot.is_memory_high() {
-        container.add_css_class(widget::MEMORY_HIGH);
+        update::set_class(container, widget::MEMORY_HIGH, true);
         icon_handle.add_css_class(widget::MEMORY_HIGH);
     } else {
-        container.remove_css_class(widget::MEMORY_HIGH);
+        update::set_class(container, widget::MEMORY_HIGH, false);
         icon_handle.remove_css_class(widget::MEMORY_HIGH);
     }
 
@@ -208,11 +285,12 @@ fn update_memory_widget(
     }
 
     let text = format_memory(snapshot, format);
-    memory_label.set_label(&text);
+    update::set_label_text(memory_label, &text);
     memory_label.set_visible(true);
 
     let tooltip = format!(
-        "Memory: {:.1}%\n{} / {}",
+        "{}: {:.1}%\n{} / {}",
+        title,
         snapshot.memory_percent,
         format_bytes_long(snapshot.memory_used),
         format_bytes_long(snapshot.memory_total)
@@ -252,6 +330,25 @@ mod tests {
         let config = MemoryConfig::from_entry(&entry);
         assert!(!config.show_icon);
         assert_eq!(config.format, MemoryFormat::Absolute);
+        assert_eq!(config.interval, DEFAULT_POLL_INTERVAL_SECS);
+        assert_eq!(config.source, None);
+    }
+
+    #[test]
+    fn test_memory_config_source() {
+        let mut options = std::collections::HashMap::new();
+        options.insert(
+            "source".to_string(),
+            toml::Value::String("ssh:admin@nas".to_string()),
+        );
+        let entry = WidgetEntry {
+            name: "memory".to_string(),
+            options,
+        };
+        assert_eq!(
+            MemoryConfig::from_entry(&entry).source.as_deref(),
+            Some("admin@nas")
+        );
     }
 
     #[test]
@@ -13,12 +13,21 @@
 //! identity for per-widget styling (e.g., `[widgets.clock].background_color`).
 //! This class is also used to generate popover class names like `clock-popover`.
 
+mod backup;
 mod base;
 mod battery;
 mod battery_popover;
+mod bluetooth;
 mod calendar_popover;
+mod ci;
 mod clock;
+mod command_palette;
 mod cpu;
+mod custom;
+mod git;
+mod hosts;
+mod idle_inhibitor;
+mod jobs;
 pub mod layer_shell_popover;
 mod marquee_label;
 mod media;
@@ -26,17 +35,32 @@ mod media_components;
 mod media_popover;
 mod media_window;
 mod memory;
+mod network;
+mod notification_center;
 mod notifications;
 mod notifications_common;
 mod notifications_popover;
 mod notifications_toast;
 mod osd;
+mod osd_queue;
+mod power_draw;
+mod power_profile;
+mod privacy;
+mod remote;
 mod rounded_picture;
+pub mod show_if;
 mod spacer;
+mod syncthing;
 mod system_popover;
+mod tailscale;
+mod taskbar;
+mod temperature;
+mod timer;
 mod tray;
+pub mod update;
 mod updates;
 mod updates_common;
+mod weather;
 mod window_title;
 mod workspaces;
 
@@ -44,30 +68,53 @@ pub mod css;
 
 pub mod quick_settings;
 
-pub use base::BaseWidget;
+pub use backup::{BackupConfig, BackupWidget};
+pub use base::{BarWidget, BaseWidget, DEFAULT_ACTION, PopoverSection, bind_shared_popover};
 pub use battery::{BatteryConfig, BatteryWidget};
+pub use bluetooth::{BluetoothConfig, BluetoothWidget};
+pub use ci::{CiConfig, CiWidget};
 pub use clock::{ClockConfig, ClockWidget};
+pub use command_palette::CommandPalette;
+pub use git::{GitConfig, GitWidget};
+pub use hosts::{HostsConfig, HostsWidget};
+pub use idle_inhibitor::{IdleInhibitorConfig, IdleInhibitorWidget};
+pub use jobs::{JobsConfig, JobsWidget};
 pub use media::{MediaConfig, MediaWidget};
+pub use network::{NetworkConfig, NetworkWidget};
+pub use notification_center::NotificationCenter;
 pub use notifications::{NotificationsConfig, NotificationsWidget};
 pub use osd::OsdOverlay;
+pub use power_draw::{PowerDrawConfig, PowerDrawWidget};
+pub use power_profile::{PowerProfileConfig, PowerProfileWidget};
+pub use privacy::{PrivacyConfig, PrivacyWidget};
 pub use quick_settings::QuickSettingsWindowHandle;
 pub use quick_settings::{QuickSettingsConfig, QuickSettingsWidget};
+pub use remote::{RemoteConfig, RemoteWidget};
 pub use spacer::{SpacerConfig, SpacerWidget};
+pub use syncthing::{SyncthingConfig, SyncthingWidget};
+pub use tailscale::{TailscaleConfig, TailscaleWidget};
+pub use taskbar::{TaskbarConfig, TaskbarWidget};
+pub use temperature::{TemperatureConfig, TemperatureWidget};
+pub use timer::{TimerConfig, TimerWidget};
 pub use tray::{TrayConfig, TrayWidget};
 pub use updates::{UpdatesConfig, UpdatesWidget};
+pub use weather::{WeatherConfig, WeatherWidget};
 pub use window_title::{WindowTitleConfig, WindowTitleWidget};
 pub use workspaces::{WorkspacesConfig, WorkspacesWidget};
 
 pub use cpu::{CpuConfig, CpuWidget};
+pub use custom::{CustomConfig, CustomWidget};
 pub use memory::{MemoryConfig, MemoryWidget};
 
 use gtk4::Widget;
 use gtk4::prelude::*;
 use std::any::Any;
-use tracing::{debug, warn};
+use std::path::PathBuf;
+use tracing::warn;
 use vibepanel_core::config::WidgetEntry;
 
-use crate::services::battery::BatteryService;
+use crate::services::event_bus::{EventBus, ScopeGuard, SubscriptionScope};
+use crate::services::remote_stats;
 
 /// Trait for widget configuration types.
 ///
@@ -108,9 +155,12 @@ pub trait WidgetConfig: Sized + Default {
     fn from_entry(entry: &WidgetEntry) -> Self;
 }
 
+/// Options every widget accepts, handled by the bar rather than the widget.
+const COMMON_OPTIONS: &[&str] = &["show_if"];
+
 /// Log warnings for unknown options in a widget entry.
 ///
-/// Call this at the start of `from_entry()` implementations to warn users
+/// Options in `COMMON_OPTIONS` are always known. Call this at the start of `from_entry()` implementations to warn users
 /// about potential typos in their configuration.
 ///
 /// # Example
@@ -125,7 +175,7 @@ pub trait WidgetConfig: Sized + Default {
 /// ```
 pub fn warn_unknown_options(widget_name: &str, entry: &WidgetEntry, known_keys: &[&str]) {
     for key in entry.options.keys() {
-        if !known_keys.contains(&key.as_str()) {
+        if !known_keys.contains(&key.as_str()) && !COMMON_OPTIONS.contains(&key.as_str()) {
             warn!(
                 "Unknown option '{}' for widget '{}' - possible typo?",
                 key, widget_name
@@ -134,12 +184,90 @@ pub fn warn_unknown_options(widget_name: &str, entry: &WidgetEntry, known_keys:
     }
 }
 
+/// Read a polling widget's `interval` option, in seconds.
+///
+/// `get` looks up an option by key (in the widget entry, or a nested table
+/// such as a weather location). `legacy_key` is the widget's older name for
+/// the option (e.g. `check_interval`), still accepted when `interval` isn't
+/// set. Values below `min` would hammer the system or a remote API, so they
+/// are raised to `min` with a warning.
+pub fn parse_interval<'a>(
+    widget_name: &str,
+    get: impl Fn(&str) -> Option<&'a toml::Value>,
+    legacy_key: Option<&str>,
+    default: u64,
+    min: u64,
+) -> u64 {
+    let value = get("interval")
+        .or_else(|| legacy_key.and_then(&get))
+        .and_then(|v| v.as_integer());
+    let Some(value) = value else {
+        return default;
+    };
+    if value < min as i64 {
+        warn!(
+            "Widget '{}': interval {}s is below the minimum of {}s, using {}s",
+            widget_name, value, min, min
+        );
+        return min;
+    }
+    value as u64
+}
+
+/// Read a stats widget's `source` option: `None` for this machine, or the
+/// SSH destination to read stats from (`source = "ssh:<host>"`).
+///
+/// An invalid value is ignored with a warning.
+pub fn parse_stats_source(widget_name: &str, entry: &WidgetEntry) -> Option<String> {
+    let value = entry.options.get("source")?.as_str()?;
+    match remote_stats::parse_source(value) {
+        Ok(source) => source,
+        Err(e) => {
+            warn!("Widget '{}': {}, using local stats", widget_name, e);
+            None
+        }
+    }
+}
+
+/// Tooltip title of a stats widget, naming the host for remote stats,
+/// e.g. "CPU (server1)".
+pub fn stats_title(title: &str, source: Option<&str>) -> String {
+    match source {
+        Some(host) => format!("{} ({})", title, host),
+        None => title.to_string(),
+    }
+}
+
+/// Expand a leading `~` in a configured path to `$HOME`.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{}{}", home, rest))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Config entry for widget `name` with `options`, for config parsing tests.
+#[cfg(test)]
+pub fn test_entry(
+    name: &str,
+    options: std::collections::HashMap<String, toml::Value>,
+) -> WidgetEntry {
+    WidgetEntry {
+        name: name.to_string(),
+        options,
+    }
+}
+
 /// A built widget with its GTK widget and ownership handle.
 pub struct BuiltWidget {
     /// The GTK widget to add to the container.
     pub widget: Widget,
     /// Opaque handle to keep the Rust-side state alive (timers, callbacks, etc.).
     pub handle: Box<dyn Any>,
+    /// The widget's popover content, for groups with a shared popover.
+    pub popover_section: Option<PopoverSection>,
 }
 
 /// Factory for constructing widgets from configuration entries.
@@ -151,7 +279,7 @@ impl WidgetFactory {
     /// Returns `None` if the widget type is not recognized.
     ///
     /// The `output_id` parameter is the monitor connector name (e.g., "eDP-1")
-    /// used for per-monitor filtering in widgets like window_title.
+    /// used for per-monitor filtering in widgets like window_title and taskbar.
     pub fn build(
         entry: &WidgetEntry,
         qs_handle: Option<&QuickSettingsWindowHandle>,
@@ -162,22 +290,22 @@ impl WidgetFactory {
                 let cfg = ClockConfig::from_entry(entry);
                 let clock = ClockWidget::new(cfg);
                 let root = clock.widget().clone().upcast::<Widget>();
+                let popover_section = clock.popover_section();
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(clock),
+                    popover_section: Some(popover_section),
                 })
             }
             "battery" => {
-                if !BatteryService::global().snapshot().available {
-                    debug!("Skipping battery widget: no battery available");
-                    return None;
-                }
                 let cfg = BatteryConfig::from_entry(entry);
                 let battery = BatteryWidget::new(cfg);
                 let root = battery.widget().clone().upcast::<Widget>();
+                let popover_section = battery.popover_section();
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(battery),
+                    popover_section: Some(popover_section),
                 })
             }
             "workspaces" => {
@@ -187,6 +315,7 @@ impl WidgetFactory {
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(workspaces),
+                    popover_section: None,
                 })
             }
             "window_title" => {
@@ -196,6 +325,17 @@ impl WidgetFactory {
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(window_title),
+                    popover_section: None,
+                })
+            }
+            "taskbar" => {
+                let cfg = TaskbarConfig::from_entry(entry);
+                let taskbar = TaskbarWidget::new(cfg, output_id.map(|s| s.to_string()));
+                let root = taskbar.widget().clone().upcast::<Widget>();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(taskbar),
+                    popover_section: None,
                 })
             }
             "tray" => {
@@ -205,6 +345,7 @@ impl WidgetFactory {
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(tray),
+                    popover_section: None,
                 })
             }
             "notifications" => {
@@ -214,6 +355,7 @@ impl WidgetFactory {
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(notifications),
+                    popover_section: None,
                 })
             }
             "quick_settings" => {
@@ -234,42 +376,244 @@ impl WidgetFactory {
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(widget),
+                    popover_section: None,
+                })
+            }
+            "network" => {
+                let cfg = NetworkConfig::from_entry(entry);
+                let network = NetworkWidget::new(cfg, qs_handle.cloned());
+                let root = network.widget().clone().upcast::<Widget>();
+                let popover_section = network.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(network),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "bluetooth" => {
+                let cfg = BluetoothConfig::from_entry(entry);
+                let bluetooth = BluetoothWidget::new(cfg);
+                let root = bluetooth.widget().clone().upcast::<Widget>();
+                let popover_section = bluetooth.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(bluetooth),
+                    popover_section: Some(popover_section),
                 })
             }
             "updates" => {
                 let cfg = UpdatesConfig::from_entry(entry);
                 let updates = UpdatesWidget::new(cfg);
                 let root = updates.widget().clone().upcast::<Widget>();
+                let popover_section = updates.popover_section();
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(updates),
+                    popover_section: Some(popover_section),
                 })
             }
             "cpu" => {
                 let cfg = CpuConfig::from_entry(entry);
                 let cpu = CpuWidget::new(cfg);
                 let root = cpu.widget().clone().upcast::<Widget>();
+                let popover_section = cpu.popover_section();
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(cpu),
+                    popover_section: Some(popover_section),
                 })
             }
             "memory" => {
                 let cfg = MemoryConfig::from_entry(entry);
                 let memory = MemoryWidget::new(cfg);
                 let root = memory.widget().clone().upcast::<Widget>();
+                let popover_section = memory.popover_section();
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(memory),
+                    popover_section: Some(popover_section),
                 })
             }
             "media" => {
                 let cfg = MediaConfig::from_entry(entry);
                 let media = MediaWidget::new(cfg);
                 let root = media.widget().clone().upcast::<Widget>();
+                let popover_section = media.popover_section();
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(media),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "backup" => {
+                let cfg = BackupConfig::from_entry(entry);
+                let backup = BackupWidget::new(cfg);
+                let root = backup.widget().clone().upcast::<Widget>();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(backup),
+                    popover_section: None,
+                })
+            }
+            "syncthing" => {
+                let cfg = SyncthingConfig::from_entry(entry);
+                let syncthing = SyncthingWidget::new(cfg);
+                let root = syncthing.widget().clone().upcast::<Widget>();
+                let popover_section = syncthing.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(syncthing),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "tailscale" => {
+                let cfg = TailscaleConfig::from_entry(entry);
+                let tailscale = TailscaleWidget::new(cfg);
+                let root = tailscale.widget().clone().upcast::<Widget>();
+                let popover_section = tailscale.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(tailscale),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "git" => {
+                let cfg = GitConfig::from_entry(entry);
+                let git = GitWidget::new(cfg);
+                let root = git.widget().clone().upcast::<Widget>();
+                let popover_section = git.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(git),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "ci" => {
+                let cfg = CiConfig::from_entry(entry);
+                let ci = CiWidget::new(cfg);
+                let root = ci.widget().clone().upcast::<Widget>();
+                let popover_section = ci.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(ci),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "hosts" => {
+                let cfg = HostsConfig::from_entry(entry);
+                let hosts = HostsWidget::new(cfg);
+                let root = hosts.widget().clone().upcast::<Widget>();
+                let popover_section = hosts.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(hosts),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "idle_inhibitor" => {
+                let cfg = IdleInhibitorConfig::from_entry(entry);
+                let idle_inhibitor = IdleInhibitorWidget::new(cfg);
+                let root = idle_inhibitor.widget().clone().upcast::<Widget>();
+                let popover_section = idle_inhibitor.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(idle_inhibitor),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "power_draw" => {
+                let cfg = PowerDrawConfig::from_entry(entry);
+                let power_draw = PowerDrawWidget::new(cfg);
+                let root = power_draw.widget().clone().upcast::<Widget>();
+                let popover_section = power_draw.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(power_draw),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "power_profile" => {
+                let cfg = PowerProfileConfig::from_entry(entry);
+                let power_profile = PowerProfileWidget::new(cfg);
+                let root = power_profile.widget().clone().upcast::<Widget>();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(power_profile),
+                    popover_section: None,
+                })
+            }
+            "privacy" => {
+                let cfg = PrivacyConfig::from_entry(entry);
+                let privacy = PrivacyWidget::new(cfg);
+                let root = privacy.widget().clone().upcast::<Widget>();
+                let popover_section = privacy.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(privacy),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "temperature" => {
+                let cfg = TemperatureConfig::from_entry(entry);
+                let temperature = TemperatureWidget::new(cfg);
+                let root = temperature.widget().clone().upcast::<Widget>();
+                let popover_section = temperature.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(temperature),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "timer" => {
+                let cfg = TimerConfig::from_entry(entry);
+                let timer = TimerWidget::new(cfg);
+                let root = timer.widget().clone().upcast::<Widget>();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(timer),
+                    popover_section: None,
+                })
+            }
+            "weather" => {
+                let cfg = WeatherConfig::from_entry(entry);
+                let weather = WeatherWidget::new(cfg);
+                let root = weather.widget().clone().upcast::<Widget>();
+                let popover_section = weather.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(weather),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "jobs" => {
+                let cfg = JobsConfig::from_entry(entry);
+                let jobs = JobsWidget::new(cfg);
+                let root = jobs.widget().clone().upcast::<Widget>();
+                let popover_section = jobs.popover_section();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(jobs),
+                    popover_section: Some(popover_section),
+                })
+            }
+            "remote" => {
+                let cfg = RemoteConfig::from_entry(entry);
+                let remote = RemoteWidget::new(cfg);
+                let root = remote.widget().clone().upcast::<Widget>();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(remote),
+                    popover_section: None,
+                })
+            }
+            "custom" => {
+                let cfg = CustomConfig::from_entry(entry);
+                let custom = CustomWidget::new(cfg);
+                let root = custom.widget().clone().upcast::<Widget>();
+                Some(BuiltWidget {
+                    widget: root,
+                    handle: Box::new(custom),
+                    popover_section: None,
                 })
             }
             "spacer" => {
@@ -279,6 +623,7 @@ impl WidgetFactory {
                 Some(BuiltWidget {
                     widget: root,
                     handle: Box::new(spacer),
+                    popover_section: None,
                 })
             }
             name => {
@@ -292,10 +637,18 @@ impl WidgetFactory {
 /// Holds widget handles to keep them alive for the lifetime of the bar.
 ///
 /// When widgets are created, their Rust-side state (timers, callbacks, etc.)
-/// must be kept alive. This struct owns those handles.
+/// must be kept alive. This struct owns those handles, along with the service
+/// subscriptions made while the bar was built, so dropping it (on monitor
+/// removal or reload) releases everything the bar's widgets registered.
 pub struct BarState {
     /// Widget handles that must be kept alive.
     widget_handles: Vec<Box<dyn Any>>,
+    /// Root widgets by config name, for runtime control over IPC.
+    named_widgets: Vec<(String, Widget)>,
+    /// This bar's Quick Settings window handle.
+    quick_settings: Option<QuickSettingsWindowHandle>,
+    /// Service subscriptions made while building the bar.
+    subscriptions: SubscriptionScope,
 }
 
 impl BarState {
@@ -303,18 +656,86 @@ impl BarState {
     pub fn new() -> Self {
         Self {
             widget_handles: Vec::new(),
+            named_widgets: Vec::new(),
+            quick_settings: None,
+            subscriptions: EventBus::global().scope(),
         }
     }
 
+    /// Record service subscriptions in this bar's scope until the guard is
+    /// dropped. Enter it while building the bar's widgets.
+    pub fn enter_scope(&self) -> ScopeGuard {
+        self.subscriptions.enter()
+    }
+
     /// Add a widget handle to be kept alive.
     pub fn add_handle(&mut self, handle: Box<dyn Any>) {
         self.widget_handles.push(handle);
     }
 
+    /// Record a built widget's root under its config name.
+    pub fn add_widget(&mut self, name: &str, widget: &Widget) {
+        self.named_widgets.push((name.to_string(), widget.clone()));
+    }
+
+    /// Toggle the visibility of every widget named `name`.
+    ///
+    /// Returns the number of widgets toggled.
+    pub fn toggle_widget(&self, name: &str) -> usize {
+        let mut count = 0;
+        for (_, widget) in self.named_widgets.iter().filter(|(n, _)| n == name) {
+            widget.set_visible(!widget.is_visible());
+            count += 1;
+        }
+        count
+    }
+
+    /// Config names of the widgets on this bar, in layout order.
+    pub fn widget_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (name, _) in &self.named_widgets {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+
+    /// Set this bar's Quick Settings window handle.
+    pub fn set_quick_settings(&mut self, handle: QuickSettingsWindowHandle) {
+        self.quick_settings = Some(handle);
+    }
+
+    /// This bar's Quick Settings window handle, if set.
+    pub fn quick_settings(&self) -> Option<&QuickSettingsWindowHandle> {
+        self.quick_settings.as_ref()
+    }
+
+    /// Open Quick Settings anchored to this bar's `quick_settings` widget.
+    pub fn open_quick_settings(&self) -> Result<(), String> {
+        let (Some(handle), Some((_, anchor))) = (
+            &self.quick_settings,
+            self.named_widgets
+                .iter()
+                .find(|(name, _)| name == "quick_settings"),
+        ) else {
+            return Err("no quick_settings widget on this bar".to_string());
+        };
+        if !handle.is_open() {
+            handle.toggle_from(anchor);
+        }
+        Ok(())
+    }
+
     /// Get the number of widget handles being held.
     pub fn handle_count(&self) -> usize {
         self.widget_handles.len()
     }
+
+    /// Get the number of service subscriptions owned by this bar.
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
 }
 
 impl Default for BarState {
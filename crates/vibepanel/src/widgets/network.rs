@@ -0,0 +1,355 @@
+//! Network widget - Wi-Fi / Ethernet connection indicator.
+//!
+//! This widget:
+//! - Shows a Wi-Fi signal strength, Ethernet or disconnected icon from
+//!   `NetworkService` snapshots
+//! - Optionally shows the SSID (or "Ethernet") next to the icon
+//! - Opens a popover with the connection name, signal strength, IP address and
+//!   a button that opens Quick Settings for switching networks
+//!
+//! Configuration options:
+//! - `show_label`: Show the SSID / "Ethernet" next to the icon (default: false)
+
+use std::net::{IpAddr, UdpSocket};
+use std::rc::Rc;
+
+use gtk4::glib::{self, WeakRef};
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Widget};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::icons::IconHandle;
+use crate::services::network::{NetworkService, NetworkSnapshot};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{button, class, color, network, surface, widget};
+use crate::widgets::QuickSettingsWindowHandle;
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::quick_settings::wifi_card::{wifi_icon_name, wifi_strength_icon};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_SHOW_LABEL: bool = false;
+
+/// Configuration for the network widget.
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    /// Show the SSID / "Ethernet" next to the icon.
+    pub show_label: bool,
+}
+
+impl WidgetConfig for NetworkConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("network", entry, &["show_label"]);
+
+        let show_label = entry
+            .options
+            .get("show_label")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_LABEL);
+
+        Self { show_label }
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            show_label: DEFAULT_SHOW_LABEL,
+        }
+    }
+}
+
+/// Network widget that displays the current connection.
+pub struct NetworkWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover with connection details.
+    _menu: Rc<MenuHandle>,
+}
+
+impl NetworkWidget {
+    /// Create a new network widget with the given configuration.
+    ///
+    /// `qs_handle` is this bar's Quick Settings window, opened from the popover.
+    pub fn new(config: NetworkConfig, qs_handle: Option<QuickSettingsWindowHandle>) -> Self {
+        let base = BaseWidget::new(&[widget::NETWORK]);
+        base.set_tooltip("Network");
+
+        let icon_handle =
+            base.add_icon("network-wireless-offline-symbolic", &[widget::NETWORK_ICON]);
+        let label = base.add_label(None, &[widget::NETWORK_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+
+        // Weak: the menu is owned through the widget's click handler
+        let root = base.widget().downgrade();
+        let menu = base.create_menu(move || build_network_popover(&root, qs_handle.as_ref()));
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            let show_label = config.show_label;
+            NetworkService::global().connect(move |snapshot: &NetworkSnapshot| {
+                update_widget_from_snapshot(&container, &icon_handle, &label, show_label, snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self { base, _menu: menu }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for NetworkWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+/// Connection state shown by the widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionKind {
+    Wired,
+    Wifi,
+    Disconnected,
+}
+
+impl ConnectionKind {
+    fn from_snapshot(snapshot: &NetworkSnapshot) -> Self {
+        if snapshot.wired_connected {
+            Self::Wired
+        } else if snapshot.connected {
+            Self::Wifi
+        } else {
+            Self::Disconnected
+        }
+    }
+}
+
+/// Icon for a snapshot: signal strength when on Wi-Fi.
+fn icon_for_snapshot(snapshot: &NetworkSnapshot) -> &'static str {
+    match ConnectionKind::from_snapshot(snapshot) {
+        ConnectionKind::Wifi => wifi_strength_icon(snapshot.strength),
+        _ => wifi_icon_name(
+            snapshot.available,
+            snapshot.connected,
+            snapshot.wifi_enabled.unwrap_or(false),
+            snapshot.wired_connected,
+            snapshot.has_wifi_device,
+        ),
+    }
+}
+
+/// Short connection name: SSID, "Ethernet" or "Disconnected".
+fn connection_name(snapshot: &NetworkSnapshot) -> String {
+    match ConnectionKind::from_snapshot(snapshot) {
+        ConnectionKind::Wired => "Ethernet".to_string(),
+        ConnectionKind::Wifi => snapshot.ssid.clone().unwrap_or_else(|| "Wi-Fi".to_string()),
+        ConnectionKind::Disconnected if !snapshot.available => "Unavailable".to_string(),
+        ConnectionKind::Disconnected if snapshot.wifi_enabled == Some(false) => {
+            "Wi-Fi off".to_string()
+        }
+        ConnectionKind::Disconnected => "Disconnected".to_string(),
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    show_label: bool,
+    snapshot: &NetworkSnapshot,
+) {
+    let kind = ConnectionKind::from_snapshot(snapshot);
+    update::set_class(
+        container,
+        widget::NETWORK_DISCONNECTED,
+        kind == ConnectionKind::Disconnected,
+    );
+    icon_handle.set_icon(icon_for_snapshot(snapshot));
+
+    let name = connection_name(snapshot);
+    if show_label && kind != ConnectionKind::Disconnected {
+        update::set_label_text(label, &name);
+        label.set_visible(true);
+    } else {
+        label.set_visible(false);
+    }
+
+    let tooltip = match kind {
+        ConnectionKind::Wifi => format!("{} ({}%)", name, snapshot.strength),
+        ConnectionKind::Wired => match snapshot.wired_name {
+            Some(ref conn) => format!("Ethernet: {}", conn),
+            None => name,
+        },
+        ConnectionKind::Disconnected => name,
+    };
+    TooltipManager::global().set_styled_tooltip(container, &tooltip);
+}
+
+/// Local address of the default route.
+///
+/// Connecting a UDP socket only selects a route; no packets are sent.
+fn primary_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:9").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified()).then_some(ip)
+}
+
+/// Build the popover content: connection name, details and a Quick Settings link.
+fn build_network_popover(
+    root: &WeakRef<gtk4::Box>,
+    qs_handle: Option<&QuickSettingsWindowHandle>,
+) -> Widget {
+    let snapshot = NetworkService::global().snapshot();
+    let kind = ConnectionKind::from_snapshot(&snapshot);
+
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(network::POPOVER);
+
+    let title = Label::new(Some("Network"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    container.append(&title);
+
+    let name = Label::new(Some(&connection_name(&snapshot)));
+    name.add_css_class(network::NAME);
+    name.set_halign(Align::Start);
+    container.append(&name);
+
+    let mut details: Vec<(&str, String)> = Vec::new();
+    match kind {
+        ConnectionKind::Wifi => {
+            details.push(("Signal", format!("{}%", snapshot.strength)));
+        }
+        ConnectionKind::Wired => {
+            if let Some(ref conn) = snapshot.wired_name {
+                details.push(("Profile", conn.clone()));
+            }
+            if let Some(ref iface) = snapshot.wired_iface {
+                details.push(("Interface", iface.clone()));
+            }
+            if let Some(speed) = snapshot.wired_speed {
+                details.push(("Speed", format!("{} Mb/s", speed)));
+            }
+        }
+        ConnectionKind::Disconnected => {}
+    }
+    if kind != ConnectionKind::Disconnected
+        && let Some(ip) = primary_ip()
+    {
+        details.push(("IP address", ip.to_string()));
+    }
+
+    for (key, value) in details {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        let key_label = Label::new(Some(key));
+        key_label.add_css_class(color::MUTED);
+        key_label.set_halign(Align::Start);
+        key_label.set_hexpand(true);
+        row.append(&key_label);
+
+        let value_label = Label::new(Some(&value));
+        value_label.add_css_class(network::DETAIL_VALUE);
+        value_label.set_selectable(true);
+        value_label.set_halign(Align::End);
+        row.append(&value_label);
+        container.append(&row);
+    }
+
+    if let Some(qs_handle) = qs_handle {
+        let open_qs = Button::with_label("Network settings");
+        open_qs.add_css_class(button::GHOST);
+        open_qs.set_halign(Align::End);
+        let qs_handle = qs_handle.clone();
+        let root = root.clone();
+        open_qs.connect_clicked(move |_| {
+            // Opening Quick Settings dismisses this popover; defer so the
+            // click finishes before the popover window goes away.
+            let qs_handle = qs_handle.clone();
+            let root = root.clone();
+            glib::idle_add_local_once(move || {
+                if let Some(root) = root.upgrade() {
+                    qs_handle.toggle_from(&root);
+                }
+            });
+        });
+        container.append(&open_qs);
+    }
+
+    container.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+
+    fn snapshot() -> NetworkSnapshot {
+        let mut snapshot = NetworkSnapshot::unknown();
+        snapshot.available = true;
+        snapshot.has_wifi_device = true;
+        snapshot.wifi_enabled = Some(true);
+        snapshot
+    }
+
+    #[test]
+    fn test_network_config_defaults() {
+        let entry = WidgetEntry {
+            name: "network".to_string(),
+            options: Default::default(),
+        };
+        assert!(!NetworkConfig::from_entry(&entry).show_label);
+    }
+
+    #[test]
+    fn test_connection_name() {
+        let mut snap = snapshot();
+        assert_eq!(connection_name(&snap), "Disconnected");
+
+        snap.connected = true;
+        snap.ssid = Some("Home".to_string());
+        assert_eq!(connection_name(&snap), "Home");
+
+        // Ethernet wins when both are up
+        snap.wired_connected = true;
+        assert_eq!(connection_name(&snap), "Ethernet");
+    }
+
+    #[test]
+    fn test_network_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = NetworkWidget::new(NetworkConfig { show_label: true }, None);
+            let service = NetworkService::global();
+
+            let mut wifi = snapshot();
+            wifi.connected = true;
+            wifi.ssid = Some("Home".to_string());
+            wifi.strength = 80;
+            service.publish_mock(wifi);
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.network.widget.widget-item",
+                    r#"label.network-label.vcenter-caps "Home""#,
+                ],
+            );
+
+            // Disconnected: flagged, and the label is hidden
+            service.publish_mock(snapshot());
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &["box.clickable.network.network-disconnected.widget.widget-item"],
+            );
+            assert!(
+                !rendered.contains("network-label"),
+                "rendered tree:\n{rendered}"
+            );
+        });
+    }
+}
@@ -0,0 +1,511 @@
+//! Notification center - full-height notification history panel.
+//!
+//! A layer-shell panel along the right screen edge, opened from the
+//! notifications widget or with `vibepanel notify panel`:
+//! - Header with a Do Not Disturb switch and a clear-all button
+//! - Search over app name, summary and body
+//! - Notifications grouped by app (most recent app first), each group with
+//!   its own clear button
+//!
+//! Like Quick Settings, the window is created on each open and destroyed on
+//! close, since layer-shell surfaces don't reliably re-show after being hidden.
+//! There is one panel at a time, shown on the monitor it was opened from.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk4::gdk::Monitor;
+use gtk4::glib;
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, Label, Orientation, PolicyType,
+    ScrolledWindow, SearchEntry, Switch,
+};
+use gtk4_layer_shell::{Edge, Layer, LayerShell};
+use tracing::debug;
+
+use crate::popover_tracker::{PopoverId, PopoverTracker};
+use crate::services::event_bus::{EventBus, SubscriptionScope};
+use crate::services::icons::IconsService;
+use crate::services::notification::{Notification, NotificationService};
+use crate::services::surfaces::SurfaceStyleManager;
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{button, color, notification as notif, surface};
+use crate::widgets::layer_shell_popover::{
+    Dismissible, bar_edge, calculate_bar_exclusive_zone, calculate_popover_bar_margin,
+    create_click_catcher, popover_keyboard_mode, setup_esc_handler,
+};
+
+use super::notifications_popover::{ClosePopoverCallback, add_empty_state, build_notification_row};
+
+/// Panel content width.
+const CENTER_WIDTH: i32 = 420;
+/// Gap between the panel and the screen edges it doesn't share with the bar.
+const CENTER_EDGE_MARGIN: i32 = 8;
+
+/// Bus event published when the notification center opens, so widgets can
+/// mark their notifications as seen.
+#[derive(Debug, Clone)]
+pub struct NotificationCenterOpened;
+
+/// The open panel and its click-catcher.
+struct CenterPanel {
+    window: ApplicationWindow,
+    click_catcher: ApplicationWindow,
+    list: GtkBox,
+    search: SearchEntry,
+    dnd_switch: Switch,
+    clear_button: Button,
+    /// Service subscriptions made while the panel is open.
+    subscriptions: RefCell<Option<SubscriptionScope>>,
+}
+
+impl CenterPanel {
+    fn new(app: &Application, monitor: Option<&Monitor>) -> Rc<Self> {
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title("vibepanel notification center")
+            .decorated(false)
+            .resizable(false)
+            .build();
+        window.add_css_class(notif::CENTER_WINDOW);
+
+        // Anchored to the bar edge, the opposite edge and the right: full
+        // height below (or above) the bar.
+        window.init_layer_shell();
+        window.set_layer(Layer::Top);
+        window.set_exclusive_zone(0);
+        window.set_anchor(Edge::Top, true);
+        window.set_anchor(Edge::Bottom, true);
+        window.set_anchor(Edge::Right, true);
+        window.set_anchor(Edge::Left, false);
+        let far_edge = if bar_edge() == Edge::Top {
+            Edge::Bottom
+        } else {
+            Edge::Top
+        };
+        window.set_margin(bar_edge(), calculate_popover_bar_margin());
+        window.set_margin(far_edge, CENTER_EDGE_MARGIN);
+        window.set_margin(Edge::Right, CENTER_EDGE_MARGIN);
+        window.set_keyboard_mode(popover_keyboard_mode());
+        if let Some(monitor) = monitor {
+            window.set_monitor(Some(monitor));
+        }
+
+        let outer = GtkBox::new(Orientation::Vertical, 8);
+        outer.add_css_class(notif::CENTER);
+        outer.add_css_class(surface::POPOVER);
+        outer.set_size_request(CENTER_WIDTH, -1);
+        SurfaceStyleManager::global().apply_surface_styles(&outer, true);
+
+        let (header, dnd_switch, clear_button) = build_header();
+        outer.append(&header);
+
+        let search = SearchEntry::new();
+        search.set_placeholder_text(Some("Search notifications"));
+        search.add_css_class(notif::CENTER_SEARCH);
+        outer.append(&search);
+
+        let list = GtkBox::new(Orientation::Vertical, 0);
+        list.add_css_class(notif::LIST);
+
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_policy(PolicyType::Never, PolicyType::Automatic);
+        scrolled.set_vexpand(true);
+        scrolled.add_css_class(notif::SCROLL);
+        scrolled.set_child(Some(&list));
+        outer.append(&scrolled);
+
+        SurfaceStyleManager::global().apply_pango_attrs_all(&outer);
+        window.set_child(Some(&outer));
+
+        let click_catcher = create_click_catcher(app, calculate_bar_exclusive_zone(), || {
+            NotificationCenter::global().close();
+        });
+        if let Some(monitor) = monitor {
+            click_catcher.set_monitor(Some(monitor));
+        }
+
+        let panel = Rc::new(Self {
+            window,
+            click_catcher,
+            list,
+            search,
+            dnd_switch,
+            clear_button,
+            subscriptions: RefCell::new(None),
+        });
+
+        setup_esc_handler(&panel.window, || NotificationCenter::global().close());
+
+        // Escape in the search entry clears it first, then closes the panel
+        panel.search.connect_stop_search(|entry| {
+            if entry.text().is_empty() {
+                NotificationCenter::global().close();
+            } else {
+                entry.set_text("");
+            }
+        });
+
+        {
+            let panel_weak = Rc::downgrade(&panel);
+            panel.search.connect_search_changed(move |_| {
+                if let Some(panel) = panel_weak.upgrade() {
+                    panel.refresh();
+                }
+            });
+        }
+
+        let scope = EventBus::global().scope();
+        {
+            let _scope = scope.enter();
+            let panel_weak = Rc::downgrade(&panel);
+            NotificationService::global().connect(move |_| {
+                if let Some(panel) = panel_weak.upgrade() {
+                    panel.refresh();
+                }
+            });
+        }
+        *panel.subscriptions.borrow_mut() = Some(scope);
+
+        panel.refresh();
+        panel
+    }
+
+    fn show(&self) {
+        self.click_catcher.set_visible(true);
+        self.window.present();
+        self.search.grab_focus();
+    }
+
+    /// Rebuild the list and sync the header with the service.
+    fn refresh(&self) {
+        let service = NotificationService::global();
+        if self.dnd_switch.is_active() != service.is_muted() {
+            self.dnd_switch.set_active(service.is_muted());
+        }
+        self.clear_button.set_sensitive(service.count() > 0);
+
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        if !service.backend_available() {
+            add_empty_state(
+                &self.list,
+                "Another notification daemon is running.\nDisable it to use this notification center.",
+            );
+            return;
+        }
+
+        let query = self.search.text();
+        let groups = group_by_app(service.notifications(), &query);
+        if groups.is_empty() {
+            add_empty_state(
+                &self.list,
+                if query.trim().is_empty() {
+                    "No notifications"
+                } else {
+                    "No matching notifications"
+                },
+            );
+            return;
+        }
+
+        let on_close: ClosePopoverCallback = Rc::new(|| NotificationCenter::global().close());
+        for (app_name, notifications) in groups {
+            self.list
+                .append(&build_group(&app_name, &notifications, &on_close));
+        }
+    }
+
+    fn destroy(&self) {
+        self.subscriptions.borrow_mut().take();
+        self.click_catcher.close();
+        self.window.close();
+    }
+}
+
+/// Build the header: title, Do Not Disturb switch and clear-all button.
+fn build_header() -> (GtkBox, Switch, Button) {
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    header.add_css_class(notif::HEADER);
+
+    let title = Label::new(Some("Notifications"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_hexpand(true);
+    title.set_xalign(0.0);
+    header.append(&title);
+
+    let dnd_label = Label::new(Some("Do Not Disturb"));
+    dnd_label.add_css_class(notif::CENTER_DND_LABEL);
+    dnd_label.add_css_class(color::MUTED);
+    header.append(&dnd_label);
+
+    let dnd_switch = Switch::new();
+    dnd_switch.set_valign(Align::Center);
+    dnd_switch.set_focus_on_click(false);
+    dnd_switch.set_active(NotificationService::global().is_muted());
+    dnd_switch.connect_state_set(|_, muted| {
+        NotificationService::global().set_muted(muted);
+        glib::Propagation::Proceed
+    });
+    header.append(&dnd_switch);
+
+    let clear_button = Button::new();
+    clear_button.set_has_frame(false);
+    clear_button.set_focusable(false);
+    clear_button.set_focus_on_click(false);
+    clear_button.add_css_class(surface::POPOVER_ICON_BTN);
+    let clear_icon = IconsService::global()
+        .create_icon("user-trash-symbolic", &[color::PRIMARY, notif::HEADER_ICON]);
+    clear_button.set_child(Some(&clear_icon.widget()));
+    TooltipManager::global().set_styled_tooltip(&clear_button, "Clear all notifications");
+    clear_button.connect_clicked(|_| NotificationService::global().close_all());
+    header.append(&clear_button);
+
+    (header, dnd_switch, clear_button)
+}
+
+/// Build one app's group: header with count and clear button, then rows.
+fn build_group(
+    app_name: &str,
+    notifications: &[Notification],
+    on_close: &ClosePopoverCallback,
+) -> GtkBox {
+    let group = GtkBox::new(Orientation::Vertical, 0);
+    group.add_css_class(notif::GROUP);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    header.add_css_class(notif::GROUP_HEADER);
+
+    let title = Label::new(Some(app_name));
+    title.add_css_class(notif::GROUP_TITLE);
+    title.set_xalign(0.0);
+    title.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    header.append(&title);
+
+    let count = Label::new(Some(&notifications.len().to_string()));
+    count.add_css_class(notif::GROUP_COUNT);
+    count.add_css_class(color::MUTED);
+    count.set_hexpand(true);
+    count.set_xalign(0.0);
+    header.append(&count);
+
+    let clear = Button::with_label("Clear");
+    clear.add_css_class(button::GHOST);
+    clear.set_focus_on_click(false);
+    let app = app_name.to_string();
+    clear.connect_clicked(move |_| {
+        debug!("Notification center: clearing notifications from {}", app);
+        NotificationService::global().close_app(&app);
+    });
+    header.append(&clear);
+    group.append(&header);
+
+    for notification in notifications {
+        group.append(&build_notification_row(
+            notification,
+            Some(on_close.clone()),
+        ));
+    }
+
+    group
+}
+
+/// Notifications matching `query` (case-insensitive, against app name,
+/// summary and body), grouped by app. The app with the most recent
+/// notification comes first, and each group is newest first.
+fn group_by_app(
+    mut notifications: Vec<Notification>,
+    query: &str,
+) -> Vec<(String, Vec<Notification>)> {
+    let query = query.trim().to_lowercase();
+    notifications.retain(|n| {
+        query.is_empty()
+            || n.app_name.to_lowercase().contains(&query)
+            || n.summary.to_lowercase().contains(&query)
+            || n.body.to_lowercase().contains(&query)
+    });
+    notifications.sort_by(|a, b| b.timestamp.total_cmp(&a.timestamp));
+
+    let mut groups: Vec<(String, Vec<Notification>)> = Vec::new();
+    for notification in notifications {
+        match groups
+            .iter_mut()
+            .find(|(app, _)| *app == notification.app_name)
+        {
+            Some((_, group)) => group.push(notification),
+            None => groups.push((notification.app_name.clone(), vec![notification])),
+        }
+    }
+    groups
+}
+
+/// Process-wide notification center.
+pub struct NotificationCenter {
+    panel: RefCell<Option<Rc<CenterPanel>>>,
+    /// ID returned from PopoverTracker while the panel is open.
+    tracker_id: Cell<Option<PopoverId>>,
+}
+
+impl NotificationCenter {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            panel: RefCell::new(None),
+            tracker_id: Cell::new(None),
+        })
+    }
+
+    /// Get the global NotificationCenter singleton.
+    pub fn global() -> Rc<Self> {
+        thread_local! {
+            static INSTANCE: Rc<NotificationCenter> = NotificationCenter::new();
+        }
+
+        INSTANCE.with(|s| s.clone())
+    }
+
+    /// Whether the panel is currently shown.
+    pub fn is_open(&self) -> bool {
+        self.panel.borrow().is_some()
+    }
+
+    /// Toggle the panel on the monitor of `anchor` (a bar widget).
+    pub fn toggle_from(&self, anchor: &impl IsA<gtk4::Widget>) {
+        let anchor = anchor.as_ref();
+        let Some(app) = anchor
+            .root()
+            .and_then(|root| root.downcast::<gtk4::Window>().ok())
+            .and_then(|window| window.application())
+        else {
+            return;
+        };
+        let monitor = anchor
+            .native()
+            .and_then(|native| native.surface())
+            .and_then(|surface| surface.display().monitor_at_surface(&surface));
+        self.toggle(&app, monitor.as_ref());
+    }
+
+    /// Toggle the panel on `monitor` (or the compositor's choice).
+    pub fn toggle(&self, app: &Application, monitor: Option<&Monitor>) {
+        if self.is_open() {
+            self.close();
+            return;
+        }
+
+        // Dismiss any other active popup before opening
+        PopoverTracker::global().dismiss_active();
+
+        let panel = CenterPanel::new(app, monitor);
+        panel.show();
+        *self.panel.borrow_mut() = Some(panel);
+
+        let id = PopoverTracker::global().set_active(Rc::new(NotificationCenterDismissible));
+        self.tracker_id.set(Some(id));
+
+        EventBus::global().publish(&NotificationCenterOpened);
+    }
+
+    /// Close the panel if it is open.
+    pub fn close(&self) {
+        self.hide_panel();
+        if let Some(id) = self.tracker_id.take() {
+            PopoverTracker::global().clear_if_active(id);
+        }
+    }
+
+    fn hide_panel(&self) {
+        // Release the borrow before destroying: closing the window may
+        // re-enter through the click-catcher or ESC handlers.
+        let panel = self.panel.borrow_mut().take();
+        if let Some(panel) = panel {
+            panel.destroy();
+        }
+    }
+}
+
+/// Adapter so other popups can dismiss the notification center when opening.
+struct NotificationCenterDismissible;
+
+impl Dismissible for NotificationCenterDismissible {
+    fn dismiss(&self) {
+        let center = NotificationCenter::global();
+        center.hide_panel();
+        center.tracker_id.set(None);
+    }
+
+    fn is_visible(&self) -> bool {
+        NotificationCenter::global().is_open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(id: u32, app_name: &str, summary: &str, timestamp: f64) -> Notification {
+        Notification {
+            id,
+            app_name: app_name.to_string(),
+            app_icon: String::new(),
+            summary: summary.to_string(),
+            body: String::new(),
+            actions: Vec::new(),
+            urgency: 1,
+            timestamp,
+            expire_timeout: -1,
+            desktop_entry: None,
+            image_path: None,
+            image_data: None,
+        }
+    }
+
+    fn ids(groups: &[(String, Vec<Notification>)]) -> Vec<(&str, Vec<u32>)> {
+        groups
+            .iter()
+            .map(|(app, group)| (app.as_str(), group.iter().map(|n| n.id).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_app_orders_by_recency() {
+        let notifications = vec![
+            notification(1, "Mail", "Inbox", 100.0),
+            notification(2, "Chat", "Alice", 300.0),
+            notification(3, "Mail", "Invoice", 200.0),
+            notification(4, "Chat", "Bob", 150.0),
+        ];
+        let groups = group_by_app(notifications, "");
+        assert_eq!(
+            ids(&groups),
+            vec![("Chat", vec![2, 4]), ("Mail", vec![3, 1])]
+        );
+    }
+
+    #[test]
+    fn test_group_by_app_search() {
+        let mut with_body = notification(3, "Calendar", "Standup", 50.0);
+        with_body.body = "Room 4B".to_string();
+        let notifications = vec![
+            notification(1, "Mail", "Invoice from ACME", 100.0),
+            notification(2, "Chat", "invoice question", 200.0),
+            with_body,
+        ];
+
+        assert_eq!(
+            ids(&group_by_app(notifications.clone(), "  INVOICE ")),
+            vec![("Chat", vec![2]), ("Mail", vec![1])]
+        );
+        assert_eq!(
+            ids(&group_by_app(notifications.clone(), "room 4b")),
+            vec![("Calendar", vec![3])]
+        );
+        assert_eq!(
+            ids(&group_by_app(notifications.clone(), "mail")),
+            vec![("Mail", vec![1])]
+        );
+        assert!(group_by_app(notifications, "nothing").is_empty());
+    }
+}
@@ -3,42 +3,134 @@
 //! Features:
 //! - Bell icon with unread notification badge
 //! - CSS states: has-notifications, has-critical, backend-unavailable
-//! - Popover with scrollable notification list and dismiss controls
+//! - Click opens the notification center panel, or a popover with the
+//!   notification list and dismiss controls
 //! - Toast overlay windows for new notifications (top-right stacked)
 //!
+//! Configuration options:
+//! - `display`: Where the notification history opens, "panel" for the
+//!   full-height notification center or "popover" (default: "panel")
+//! - `badge_count`: Show the unread count on the badge instead of a dot
+//!   (default: false)
+//! - `badge_position`, `badge_color`, `badge_text_color`: Badge placement
+//!   and colors (see `BadgeConfig`)
+//! - `led`: Light an LED while critical notifications are unseen: `true`
+//!   picks a `*::notification` LED, or a name under `/sys/class/leds`
+//!   (e.g. "input3::capslock") uses that one (default: false)
+//! - `led_capslock_fallback`: With `led = true`, use the capslock LED when
+//!   there is no notification LED (default: true)
+//!
 //! This module is split into several files for maintainability:
 //! - `notifications.rs` (this file): Widget implementation and badge logic
 //! - `notifications_toast.rs`: Toast window management and queue
 //! - `notifications_popover.rs`: Popover content and notification list
+//! - `notification_center.rs`: Full-height notification center panel
 //! - `notifications_common.rs`: Shared constants and helper functions
 
+use gtk4::gdk::BUTTON_PRIMARY;
 use gtk4::glib;
 use gtk4::prelude::*;
-use gtk4::{Align, Application, Box as GtkBox, Orientation, Overlay, Widget};
+use gtk4::{Application, Box as GtkBox, GestureClick};
 use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tracing::debug;
+use tracing::{debug, warn};
 use vibepanel_core::config::WidgetEntry;
 
+use crate::services::event_bus::EventBus;
 use crate::services::icons::IconHandle;
 use crate::services::notification::{NotificationService, URGENCY_CRITICAL};
+use crate::services::notification_led::{NotificationLedService, NotificationLedSettings};
 use crate::services::tooltip::TooltipManager;
-use crate::styles::widget;
-use crate::widgets::base::MenuHandle;
-use crate::widgets::{BaseWidget, WidgetConfig};
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{state, widget};
+use crate::widgets::base::{BADGE_OPTIONS, BadgeConfig, BadgeHandle, MenuHandle};
+use crate::widgets::{BaseWidget, WidgetConfig, warn_unknown_options};
 
+use super::notification_center::{NotificationCenter, NotificationCenterOpened};
 use super::notifications_popover::{ClosePopoverCallback, build_popover_content};
 use super::notifications_toast::NotificationToastManager;
 
+/// Where the notification history opens when the bell is clicked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotificationsDisplay {
+    /// Full-height notification center panel.
+    #[default]
+    Panel,
+    /// Popover anchored to the bell.
+    Popover,
+}
+
 /// Configuration for the notification widget.
 #[derive(Debug, Clone, Default)]
-pub struct NotificationsConfig {}
+pub struct NotificationsConfig {
+    /// Where the notification history opens.
+    pub display: NotificationsDisplay,
+    /// Show the unread count on the badge instead of a dot.
+    pub badge_count: bool,
+    /// Badge placement and colors.
+    pub badge: BadgeConfig,
+    /// Notification LED to drive, if enabled.
+    pub led: Option<NotificationLedSettings>,
+}
 
 impl WidgetConfig for NotificationsConfig {
-    fn from_entry(_entry: &WidgetEntry) -> Self {
-        Self {}
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        let known: Vec<&str> = ["display", "badge_count", "led", "led_capslock_fallback"]
+            .into_iter()
+            .chain(BADGE_OPTIONS.iter().copied())
+            .collect();
+        warn_unknown_options("notifications", entry, &known);
+
+        let display = match entry.options.get("display").and_then(|v| v.as_str()) {
+            None | Some("panel") => NotificationsDisplay::Panel,
+            Some("popover") => NotificationsDisplay::Popover,
+            Some(other) => {
+                warn!(
+                    "notifications widget: unknown display '{}', using 'panel'",
+                    other
+                );
+                NotificationsDisplay::Panel
+            }
+        };
+
+        let badge_count = entry
+            .options
+            .get("badge_count")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let capslock_fallback = entry
+            .options
+            .get("led_capslock_fallback")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let led = match entry.options.get("led") {
+            None => None,
+            Some(toml::Value::Boolean(enabled)) => enabled.then_some(NotificationLedSettings {
+                device: None,
+                capslock_fallback,
+            }),
+            Some(toml::Value::String(device)) => Some(NotificationLedSettings {
+                device: Some(device.clone()),
+                capslock_fallback,
+            }),
+            Some(other) => {
+                warn!(
+                    "notifications widget: 'led' must be true, false or an LED name, got {}",
+                    other
+                );
+                None
+            }
+        };
+
+        Self {
+            display,
+            badge_count,
+            badge: BadgeConfig::from_entry("notifications", entry),
+            led,
+        }
     }
 }
 
@@ -47,7 +139,8 @@ impl WidgetConfig for NotificationsConfig {
 /// This is wrapped in Rc<RefCell<...>> to allow safe sharing with callbacks.
 struct NotificationsWidgetInner {
     icon_handle: IconHandle,
-    badge: Widget,
+    badge: BadgeHandle,
+    badge_count: bool,
     container: GtkBox,
     known_ids: RefCell<HashSet<u32>>,
     toast_manager: RefCell<Option<Rc<NotificationToastManager>>>,
@@ -68,13 +161,13 @@ impl NotificationsWidgetInner {
         self.show_new_toasts(service);
 
         // Update badge: unread since last popover open
-        // Badge is shown as a simple dot (no text), count is only in tooltip
+        // By default the badge is a dot and the count is only in the tooltip
         let unread = self.calculate_unread_count(service);
         debug!("NotificationsWidget: unread count = {}", unread);
-        if unread > 0 {
-            self.badge.set_visible(true);
+        if self.badge_count {
+            self.badge.set_count(unread);
         } else {
-            self.badge.set_visible(false);
+            self.badge.set_dot(unread > 0);
         }
 
         // Check for critical notifications
@@ -247,57 +340,42 @@ impl NotificationsWidgetInner {
         Some(app)
     }
 
-    /// Mark notifications as seen (called when popover opens).
+    /// Mark notifications as seen (called when the popover or panel opens).
     fn mark_as_seen(&self) {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_secs_f64())
             .unwrap_or(0.0);
         self.last_seen_timestamp.set(now);
+        NotificationLedService::global().mark_seen();
     }
 }
 
-/// Notification bell widget with popover showing notification list.
+/// Notification bell widget opening the notification center or a popover.
 pub struct NotificationsWidget {
     base: BaseWidget,
     inner: Rc<NotificationsWidgetInner>,
+    /// LED settings registered with the notification LED service.
+    led_settings_id: Option<SettingsId>,
 }
 
 impl NotificationsWidget {
     /// Create a new notification widget.
-    pub fn new(_config: NotificationsConfig) -> Self {
+    pub fn new(config: NotificationsConfig) -> Self {
         let base = BaseWidget::new(&[widget::NOTIFICATIONS]);
 
-        // Create an overlay for badge on top of icon
-        let overlay = Overlay::new();
-        overlay.set_valign(Align::Center);
-
         // Bell icon - use logical name that maps to Material "notifications" or GTK equivalent
         let icon_handle = base.add_icon("notifications", &[widget::NOTIFICATION_ICON]);
 
-        // Remove icon from content box and put in overlay instead
-        base.content().remove(&icon_handle.widget());
-        overlay.set_child(Some(&icon_handle.widget()));
-
-        // Badge indicator dot (hidden by default)
-        // Use a fixed-size Box instead of Label to avoid text metric issues
-        let badge = GtkBox::new(Orientation::Horizontal, 0);
-        badge.add_css_class(widget::NOTIFICATION_BADGE);
-        badge.add_css_class(widget::NOTIFICATION_BADGE_DOT);
-        badge.set_visible(false);
-        badge.set_halign(Align::End);
-        badge.set_valign(Align::Start);
-        // Set explicit size request to ensure square shape
-        badge.set_size_request(8, 8);
-        overlay.add_overlay(&badge);
-
-        base.content().append(&overlay);
+        // Unread badge (hidden by default)
+        let badge = base.add_badge(&icon_handle, &config.badge, &[widget::NOTIFICATION_BADGE]);
 
         base.set_tooltip("Notifications");
 
         let inner = Rc::new(NotificationsWidgetInner {
             icon_handle,
-            badge: badge.upcast(),
+            badge,
+            badge_count: config.badge_count,
             container: base.widget().clone(),
             known_ids: RefCell::new(HashSet::new()),
             toast_manager: RefCell::new(None),
@@ -306,13 +384,24 @@ impl NotificationsWidget {
             menu_handle: RefCell::new(None),
         });
 
-        let widget = Self { base, inner };
+        let mut widget = Self {
+            base,
+            inner,
+            led_settings_id: None,
+        };
 
-        widget.build_menu();
+        match config.display {
+            NotificationsDisplay::Panel => widget.connect_panel(),
+            NotificationsDisplay::Popover => widget.build_menu(),
+        }
 
         // Connect to notification service (using safe Rc pattern)
         widget.bind_service();
 
+        widget.led_settings_id = config
+            .led
+            .map(|led| NotificationLedService::global().configure(led));
+
         widget
     }
 
@@ -321,6 +410,31 @@ impl NotificationsWidget {
         self.base.widget()
     }
 
+    fn connect_panel(&self) {
+        // Ensure the root box is clickable.
+        self.base.widget().add_css_class(state::CLICKABLE);
+
+        // Gesture to toggle the notification center when clicked.
+        let gesture = GestureClick::new();
+        gesture.set_button(BUTTON_PRIMARY);
+        // Run in capture phase to handle click before BaseWidget's gesture
+        gesture.set_propagation_phase(gtk4::PropagationPhase::Capture);
+
+        let root = self.base.widget().clone();
+        gesture.connect_released(move |gesture, _n_press, _x, _y| {
+            if gesture.current_button() != BUTTON_PRIMARY {
+                return;
+            }
+
+            // Claim the gesture sequence to prevent BaseWidget's handler from firing
+            gesture.set_state(gtk4::EventSequenceState::Claimed);
+
+            NotificationCenter::global().toggle_from(&root);
+        });
+
+        self.base.widget().add_controller(gesture);
+    }
+
     fn build_menu(&self) {
         let inner = Rc::clone(&self.inner);
 
@@ -389,6 +503,22 @@ impl NotificationsWidget {
         service.connect(move |svc| {
             inner.on_service_update(svc);
         });
+
+        // The notification center is shared by all bars (and can be opened
+        // over IPC), so clear the badge whenever it opens.
+        let inner = Rc::clone(&self.inner);
+        EventBus::global().subscribe_no_replay(move |_: &NotificationCenterOpened| {
+            inner.mark_as_seen();
+            inner.on_service_update(&NotificationService::global());
+        });
+    }
+}
+
+impl Drop for NotificationsWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.led_settings_id.take() {
+            NotificationLedService::global().release(settings_id);
+        }
     }
 }
 
@@ -397,3 +527,69 @@ impl Default for NotificationsWidget {
         Self::new(NotificationsConfig::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn entry(display: Option<&str>) -> WidgetEntry {
+        let mut options = HashMap::new();
+        if let Some(display) = display {
+            options.insert(
+                "display".to_string(),
+                toml::Value::String(display.to_string()),
+            );
+        }
+        WidgetEntry {
+            name: "notifications".to_string(),
+            options,
+        }
+    }
+
+    #[test]
+    fn test_notifications_config_display() {
+        let display = |value| NotificationsConfig::from_entry(&entry(value)).display;
+        assert_eq!(display(None), NotificationsDisplay::Panel);
+        assert_eq!(display(Some("popover")), NotificationsDisplay::Popover);
+        assert_eq!(display(Some("sidebar")), NotificationsDisplay::Panel);
+    }
+
+    #[test]
+    fn test_notifications_config_led() {
+        let led = |value: Option<toml::Value>, fallback: Option<bool>| {
+            let mut entry = entry(None);
+            if let Some(value) = value {
+                entry.options.insert("led".to_string(), value);
+            }
+            if let Some(fallback) = fallback {
+                entry.options.insert(
+                    "led_capslock_fallback".to_string(),
+                    toml::Value::Boolean(fallback),
+                );
+            }
+            NotificationsConfig::from_entry(&entry).led
+        };
+
+        assert_eq!(led(None, None), None);
+        assert_eq!(led(Some(toml::Value::Boolean(false)), None), None);
+        assert_eq!(
+            led(Some(toml::Value::Boolean(true)), Some(false)),
+            Some(NotificationLedSettings {
+                device: None,
+                capslock_fallback: false,
+            })
+        );
+        assert_eq!(
+            led(
+                Some(toml::Value::String("input3::capslock".to_string())),
+                None
+            ),
+            Some(NotificationLedSettings {
+                device: Some("input3::capslock".to_string()),
+                capslock_fallback: true,
+            })
+        );
+        assert_eq!(led(Some(toml::Value::Integer(1)), None), None);
+    }
+}
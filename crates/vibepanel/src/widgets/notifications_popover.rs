@@ -10,6 +10,8 @@ use gtk4::{
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
+use crate::popover_tracker::remember_scroll_position;
+use crate::services::actions::Actions;
 use crate::services::icons::IconsService;
 use crate::services::notification::{
     Notification, NotificationService, URGENCY_CRITICAL, URGENCY_LOW,
@@ -83,6 +85,7 @@ pub(super) fn build_popover_content(on_close: Option<ClosePopoverCallback>) -> g
     scrolled.set_min_content_height(content_height);
     scrolled.set_max_content_height(max_height);
     scrolled.add_css_class(notif::SCROLL);
+    remember_scroll_position(&scrolled, "notifications.list");
 
     scrolled.set_child(Some(&notification_list));
     root.append(&scrolled);
@@ -227,7 +230,7 @@ fn populate_notification_list(list: &GtkBox, on_close: Option<ClosePopoverCallba
     }
 }
 
-fn add_empty_state(list: &GtkBox, message: &str) {
+pub(super) fn add_empty_state(list: &GtkBox, message: &str) {
     let empty = GtkBox::new(Orientation::Vertical, 8);
     empty.add_css_class(notif::EMPTY);
     empty.set_valign(Align::Center);
@@ -254,7 +257,7 @@ fn add_empty_state(list: &GtkBox, message: &str) {
     list.append(&empty);
 }
 
-fn build_notification_row(
+pub(super) fn build_notification_row(
     notification: &Notification,
     on_close: Option<ClosePopoverCallback>,
 ) -> GtkBox {
@@ -355,11 +358,10 @@ fn build_notification_row(
         // interaction that conflicts with the layer shell surface state.
         let on_close_link = on_close.clone();
         body_label.connect_activate_link(move |_, uri| {
-            // Use xdg-open via spawn_command_line_async for a detached process
-            let cmd = format!("xdg-open '{}'", uri.replace("'", "'\\''"));
-            // We ignore the result here because this is a fire-and-forget operation
-            // and we can't do much if xdg-open fails to launch from here anyway.
-            let _ = glib::spawn_command_line_async(&cmd);
+            // xdg-open runs as a detached process. We ignore the result here
+            // because this is a fire-and-forget operation and we can't do much
+            // if xdg-open fails to launch (or kiosk mode refuses it) anyway.
+            let _ = Actions::global().open_url(uri);
 
             // Close popover when user navigates away via link
             if let Some(ref close_cb) = on_close_link {
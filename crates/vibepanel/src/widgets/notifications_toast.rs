@@ -2,7 +2,8 @@
 //!
 //! This module handles floating toast windows that appear when new notifications
 //! arrive. Toasts stack vertically in the top-right corner and auto-dismiss
-//! after a timeout (except for critical notifications).
+//! after a timeout (except for critical notifications). The stack moves
+//! out of the way of an open Quick Settings panel (see `ScreenRegions`).
 
 use gtk4::glib::{self, SourceId};
 use gtk4::prelude::*;
@@ -13,7 +14,9 @@ use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use tracing::debug;
 
+use crate::services::callbacks::CallbackId;
 use crate::services::notification::{Notification, URGENCY_CRITICAL, URGENCY_LOW};
+use crate::services::screen_regions::{Anchor, Placement, ScreenRegions, Surface};
 
 /// Type alias for toast notification callbacks.
 type ToastCallback = Rc<dyn Fn(u32)>;
@@ -322,6 +325,11 @@ impl NotificationToast {
         self.window.present();
     }
 
+    /// Move the toast horizontally (no animation).
+    pub fn set_margin_right(&self, margin: i32) {
+        self.window.set_margin(Edge::Right, margin);
+    }
+
     /// Get the measured height of this toast (or estimated if not yet measured)
     pub fn height(&self) -> i32 {
         self.height.get()
@@ -401,6 +409,8 @@ pub(super) struct NotificationToastManager {
     toast_order: RefCell<Vec<u32>>,
     on_action: ToastActionCallback,
     on_toast_removed: Rc<dyn Fn()>,
+    /// ScreenRegions callback registration.
+    regions_callback: Cell<Option<CallbackId>>,
 }
 
 impl NotificationToastManager {
@@ -408,12 +418,50 @@ impl NotificationToastManager {
         on_action: impl Fn(u32, &str) + 'static,
         on_toast_removed: impl Fn() + 'static,
     ) -> Rc<Self> {
-        Rc::new(Self {
+        let manager = Rc::new(Self {
             toasts: RefCell::new(HashMap::new()),
             toast_order: RefCell::new(Vec::new()),
             on_action: Rc::new(on_action),
             on_toast_removed: Rc::new(on_toast_removed),
-        })
+            regions_callback: Cell::new(None),
+        });
+
+        // Restack when Quick Settings opens, closes or changes size
+        let manager_weak = Rc::downgrade(&manager);
+        let id = ScreenRegions::global().connect(move |_| {
+            if let Some(manager) = manager_weak.upgrade() {
+                manager.reposition_toasts();
+            }
+        });
+        manager.regions_callback.set(Some(id));
+
+        manager
+    }
+
+    /// Where the stack goes, for a stack `height` tall: the top-right
+    /// corner, or clear of the regions of higher-priority surfaces.
+    fn stack_placement(height: i32) -> Placement {
+        ScreenRegions::global().resolve(
+            Surface::Toasts,
+            &Placement {
+                horizontal: Anchor::End,
+                vertical: Anchor::Start,
+                margin_x: TOAST_MARGIN_RIGHT,
+                margin_y: TOAST_MARGIN_TOP,
+                width: POPOVER_WIDTH,
+                height,
+            },
+        )
+    }
+
+    /// Heights of the stacked toasts, top to bottom.
+    fn toast_heights(&self) -> Vec<i32> {
+        let toasts = self.toasts.borrow();
+        self.toast_order
+            .borrow()
+            .iter()
+            .filter_map(|id| toasts.get(id).map(|toast| toast.height()))
+            .collect()
     }
 
     pub fn show(self: &Rc<Self>, app: &Application, notification: &Notification) {
@@ -423,17 +471,10 @@ impl NotificationToastManager {
         }
 
         // Calculate initial margin from existing toasts
-        let initial_margin = {
-            let order = self.toast_order.borrow();
-            let toasts = self.toasts.borrow();
-            let mut y_offset = TOAST_MARGIN_TOP;
-            for &id in order.iter() {
-                if let Some(toast) = toasts.get(&id) {
-                    y_offset += toast.height() + TOAST_GAP;
-                }
-            }
-            y_offset
-        };
+        let heights = self.toast_heights();
+        let y_offset: i32 = heights.iter().map(|height| height + TOAST_GAP).sum();
+        let placement = Self::stack_placement(y_offset + TOAST_ESTIMATED_HEIGHT);
+        let initial_margin = placement.margin_y + y_offset;
 
         let manager = Rc::clone(self);
         let on_dismiss: Rc<dyn Fn(u32)> = Rc::new(move |id| {
@@ -466,6 +507,7 @@ impl NotificationToastManager {
             .borrow_mut()
             .insert(notification.id, Rc::clone(&toast));
         self.toast_order.borrow_mut().push(notification.id);
+        toast.set_margin_right(placement.margin_x);
         toast.present();
     }
 
@@ -488,18 +530,40 @@ impl NotificationToastManager {
     }
 
     fn reposition_toasts(&self) {
-        let order = self.toast_order.borrow();
-        let toasts = self.toasts.borrow();
-        let mut y_offset = TOAST_MARGIN_TOP;
-        for &id in order.iter() {
-            if let Some(toast) = toasts.get(&id) {
-                toast.update_margin_top(y_offset, true);
-                y_offset += toast.height() + TOAST_GAP;
+        let heights = self.toast_heights();
+        if heights.is_empty() {
+            ScreenRegions::global().unregister(Surface::Toasts);
+            return;
+        }
+
+        let stack_height = heights.iter().sum::<i32>() + TOAST_GAP * (heights.len() as i32 - 1);
+        let placement = Self::stack_placement(stack_height);
+        {
+            let order = self.toast_order.borrow();
+            let toasts = self.toasts.borrow();
+            let mut y_offset = placement.margin_y;
+            for &id in order.iter() {
+                if let Some(toast) = toasts.get(&id) {
+                    toast.update_margin_top(y_offset, true);
+                    toast.set_margin_right(placement.margin_x);
+                    y_offset += toast.height() + TOAST_GAP;
+                }
             }
         }
+
+        // Let the OSD move out of the stack's way
+        ScreenRegions::global().register(Surface::Toasts, placement);
     }
 
     pub fn active_ids(&self) -> HashSet<u32> {
         self.toasts.borrow().keys().cloned().collect()
     }
 }
+
+impl Drop for NotificationToastManager {
+    fn drop(&mut self) {
+        if let Some(id) = self.regions_callback.take() {
+            ScreenRegions::global().disconnect(id);
+        }
+    }
+}
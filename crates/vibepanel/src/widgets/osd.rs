@@ -3,34 +3,65 @@
 //! - Small overlay window with icon + slider
 //! - Layer-shell OVERLAY, non-intrusive, auto-hiding
 //! - Reacts to `BrightnessService` and `AudioService` changes, ignoring the initial sync
+//! - Volume changes from other tools (pamixer, compositor keybinds) arrive as
+//!   PulseAudio sink events; the echo of a change `vibepanel volume` already
+//!   announced over IPC is dropped, whichever of the two arrives first
+//! - Announces headphone jack plug/unplug (`JackEvent`s from `AudioService`)
+//! - Announces mic mute changes, whichever way they were made, and camera
+//!   access keys (`CameraAccessEvent`s from `MuteKeysService`)
+//! - Shows overdrive and safe-listening warnings (`VolumeWarning`s)
+//! - Queues events by priority instead of replacing what's on screen, and
+//!   animates the slider when rapid volume changes coalesce (see `osd_queue`)
+//! - Optionally stays hidden while a fullscreen window is focused or a
+//!   configured app is running, sending a notification instead
+//! - Moves out of the way of an open Quick Settings panel and notification
+//!   toasts (see `ScreenRegions`)
 
 use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::services::audio::AudioService;
+use crate::services::audio::{AudioService, JackEvent, VolumeWarning};
 use crate::services::brightness::BrightnessService;
+use crate::services::dbus_watchdog::{self, CallPolicy};
 use crate::styles::{color, osd};
 
 use gtk4::gdk;
+use gtk4::gio;
 use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{Align, Application, Box as GtkBox, Image, Label, Orientation, Scale};
-use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use gtk4_layer_shell::{KeyboardMode, Layer, LayerShell};
 use tracing::{debug, warn};
 
 use vibepanel_core::config::OsdConfig;
 
 use crate::services::audio::AudioSnapshot;
 use crate::services::brightness::BrightnessSnapshot;
+use crate::services::event_bus::EventBus;
 use crate::services::icons::IconsService;
-use crate::services::osd_ipc::{OsdIpcListener, OsdMessage};
+use crate::services::mute_keys::CameraAccessEvent;
+use crate::services::osd_ipc::OsdMessage;
+use crate::services::screen_regions::{
+    Anchor, Placement, ScreenRegions, ScreenRegionsSnapshot, Surface, apply_placement,
+};
 use crate::services::surfaces::SurfaceStyleManager;
+use crate::services::window_title::{WindowTitleService, WindowTitleSnapshot};
+use crate::widgets::osd_queue::{OsdAction, OsdEvent, OsdPriority, OsdQueue, OsdValueKind};
 
 /// Valid OSD positions for anchoring.
 const VALID_POSITIONS: &[&str] = &["bottom", "left", "right", "top"];
 const DEFAULT_POSITION: &str = "bottom";
 
+/// Duration of the slider animation between coalesced values.
+const VALUE_ANIMATION: Duration = Duration::from_millis(150);
+/// Frame interval for the slider animation (~60 FPS).
+const ANIMATION_FRAME_MS: u64 = 16;
+/// How long after a PulseAudio-driven volume OSD an identical IPC message is
+/// treated as its echo rather than a new key press.
+const IPC_ECHO_WINDOW: Duration = Duration::from_millis(500);
+
 fn normalize_position(position: &str) -> String {
     if VALID_POSITIONS.contains(&position) {
         position.to_string()
@@ -57,6 +88,8 @@ pub struct OsdWidget {
     unavailable_content: GtkBox,
     unavailable_icon: Image,
     unavailable_label: Label,
+    /// Running slider animation, if any.
+    animation: Rc<RefCell<Option<glib::SourceId>>>,
 }
 
 impl OsdWidget {
@@ -121,6 +154,7 @@ impl OsdWidget {
             unavailable_content,
             unavailable_icon,
             unavailable_label,
+            animation: Rc::new(RefCell::new(None)),
         }
     }
 
@@ -129,6 +163,7 @@ impl OsdWidget {
     }
 
     pub fn set_value(&self, value: u32) {
+        self.stop_animation();
         let v = value.clamp(0, 100) as f64;
         self.scale.set_value(v);
         // Show normal content, hide unavailable
@@ -136,8 +171,41 @@ impl OsdWidget {
         self.unavailable_content.set_visible(false);
     }
 
-    /// Set the widget to "unavailable" state with icon and message.
-    pub fn set_unavailable(&self, icon_name: &str, message: &str) {
+    /// Slide to `value` from the current position instead of jumping.
+    pub fn animate_value(&self, value: u32) {
+        self.stop_animation();
+        self.normal_content.set_visible(true);
+        self.unavailable_content.set_visible(false);
+
+        let from = self.scale.value();
+        let to = value.clamp(0, 100) as f64;
+        let start = Instant::now();
+        let scale = self.scale.clone();
+        let animation = self.animation.clone();
+        let source_id =
+            glib::timeout_add_local(Duration::from_millis(ANIMATION_FRAME_MS), move || {
+                let t = (start.elapsed().as_secs_f64() / VALUE_ANIMATION.as_secs_f64()).min(1.0);
+                // Ease out (cubic)
+                let eased = 1.0 - (1.0 - t).powi(3);
+                scale.set_value(from + (to - from) * eased);
+                if t < 1.0 {
+                    return glib::ControlFlow::Continue;
+                }
+                *animation.borrow_mut() = None;
+                glib::ControlFlow::Break
+            });
+        *self.animation.borrow_mut() = Some(source_id);
+    }
+
+    fn stop_animation(&self) {
+        if let Some(source_id) = self.animation.borrow_mut().take() {
+            source_id.remove();
+        }
+    }
+
+    /// Show a centered icon and message instead of the slider (e.g. volume
+    /// unavailable, headphones unplugged).
+    pub fn set_message(&self, icon_name: &str, message: &str) {
         // Update unavailable content
         self.unavailable_icon.set_icon_name(Some(icon_name));
         self.unavailable_label.set_text(message);
@@ -165,13 +233,25 @@ impl OsdWidget {
 /// - Appears above other windows (OVERLAY layer)
 /// - Does not take keyboard focus
 /// - Does not reserve screen space (exclusive_zone = 0)
-/// - Auto-hides after a timeout
+/// - Auto-hides after a timeout, then shows the next queued event
 /// - Listens for IPC messages from CLI commands
 pub struct OsdOverlay {
     window: gtk4::Window,
     osd_widget: OsdWidget,
+    /// Anchors and margins for the configured position (size filled in
+    /// when shown).
+    placement: Placement,
     timeout_ms: u32,
     hide_source: RefCell<Option<glib::SourceId>>,
+    /// Event on screen and events waiting for it to time out.
+    queue: RefCell<OsdQueue>,
+
+    // Suppression (fullscreen / gaming).
+    suppress_fullscreen: bool,
+    suppress_apps: Vec<String>,
+    notify_when_suppressed: bool,
+    /// ID of the last notification sent instead of the OSD, replaced by the next.
+    notification_id: Rc<Cell<u32>>,
 
     // Brightness state tracking.
     brightness_baseline_seen: Cell<bool>,
@@ -181,9 +261,9 @@ pub struct OsdOverlay {
     audio_baseline_seen: Cell<bool>,
     last_volume: Cell<u32>,
     last_muted: Cell<bool>,
-
-    // IPC listener for CLI commands (kept alive for the lifetime of the overlay).
-    _ipc_listener: RefCell<Option<Rc<RefCell<OsdIpcListener>>>>,
+    last_mic_muted: Cell<Option<bool>>,
+    /// When the last volume OSD driven by a PulseAudio event was shown.
+    last_audio_shown: Cell<Option<Instant>>,
 }
 
 impl OsdOverlay {
@@ -241,33 +321,69 @@ impl OsdOverlay {
         SurfaceStyleManager::global().apply_pango_attrs_all(&container);
 
         // Anchor window according to position.
-        Self::apply_position(&window, &position);
+        let placement = Self::position_placement(&position);
+        apply_placement(&window, &placement);
 
         let overlay = Rc::new(Self {
             window,
             osd_widget,
+            placement,
             timeout_ms,
             hide_source: RefCell::new(None),
+            queue: RefCell::new(OsdQueue::new()),
+            suppress_fullscreen: osd_config.suppress_fullscreen,
+            suppress_apps: osd_config.suppress_apps.clone(),
+            notify_when_suppressed: osd_config.notify_when_suppressed,
+            notification_id: Rc::new(Cell::new(0)),
             brightness_baseline_seen: Cell::new(false),
             last_brightness: Cell::new(0),
             audio_baseline_seen: Cell::new(false),
             last_volume: Cell::new(0),
             last_muted: Cell::new(false),
-            _ipc_listener: RefCell::new(None),
+            last_mic_muted: Cell::new(None),
+            last_audio_shown: Cell::new(None),
         });
 
         overlay.connect_brightness();
         overlay.connect_audio();
         overlay.connect_ipc();
+        overlay.connect_screen_regions();
 
         overlay
     }
 
-    /// Show the overlay with a specific icon + value.
-    pub fn show_value(self: &Rc<Self>, icon_name: &str, value: u32) {
-        self.osd_widget.set_icon(icon_name);
-        self.osd_widget.set_value(value);
+    /// Queue an event, showing it now if nothing more important is on screen.
+    fn present(self: &Rc<Self>, event: OsdEvent) {
+        if self.timeout_ms == 0 {
+            // The OSD never hides, so nothing would ever leave the queue
+            self.queue.borrow_mut().clear();
+        }
+
+        let action = self.queue.borrow_mut().push(event);
+        match action {
+            OsdAction::Show(event) => self.display(&event, false),
+            OsdAction::Update(event) => self.display(&event, true),
+            OsdAction::Queued => {}
+        }
+    }
 
+    /// Put an event on screen and restart the hide timer.
+    fn display(self: &Rc<Self>, event: &OsdEvent, animate: bool) {
+        match event {
+            OsdEvent::Value { icon, value, .. } => {
+                self.osd_widget.set_icon(icon);
+                if animate && self.window.is_visible() {
+                    self.osd_widget.animate_value(*value);
+                } else {
+                    self.osd_widget.set_value(*value);
+                }
+            }
+            OsdEvent::Message { icon, message, .. } => {
+                self.osd_widget.set_message(icon, message);
+            }
+        }
+
+        self.place();
         self.window.set_visible(true);
         self.reset_hide_timer();
     }
@@ -283,7 +399,14 @@ impl OsdOverlay {
         } else {
             "display-brightness-high-symbolic"
         };
-        self.show_value(icon, value);
+        if self.suppress(icon, &format!("Brightness {}%", value)) {
+            return;
+        }
+        self.present(OsdEvent::Value {
+            kind: OsdValueKind::Brightness,
+            icon,
+            value,
+        });
     }
 
     /// Volume-specific helper: compute icon from volume/mute state and show.
@@ -297,17 +420,115 @@ impl OsdOverlay {
         } else {
             "audio-volume-high-symbolic"
         };
+        let summary = if muted {
+            "Volume muted".to_string()
+        } else {
+            format!("Volume {}%", volume)
+        };
+        if self.suppress(icon, &summary) {
+            return;
+        }
         // Clamp to 100 for display, even though we allow overdrive internally.
-        self.show_value(icon, volume.min(100));
+        self.present(OsdEvent::Value {
+            kind: OsdValueKind::Volume,
+            icon,
+            value: volume.min(100),
+        });
     }
 
     /// Show OSD indicating volume control is unavailable (device not ready).
     pub fn show_volume_unavailable(self: &Rc<Self>) {
-        self.osd_widget
-            .set_unavailable("audio-volume-muted-symbolic", "Play audio to enable");
+        if self.suppress("audio-volume-muted-symbolic", "Volume unavailable") {
+            return;
+        }
+        self.present(OsdEvent::Message {
+            icon: "audio-volume-muted-symbolic",
+            message: "Play audio to enable".to_string(),
+            priority: OsdPriority::Interactive,
+        });
+    }
 
-        self.window.set_visible(true);
-        self.reset_hide_timer();
+    /// Announce a headphone jack being plugged in or unplugged.
+    pub fn show_jack(self: &Rc<Self>, event: &JackEvent) {
+        // The volume may follow the port switch (and mute on unplug arrives
+        // as a separate update); don't replace this message with it.
+        let snapshot = AudioService::global().current();
+        self.last_volume.set(snapshot.volume);
+        self.last_muted.set(event.muted);
+
+        let (icon, message) = jack_message(event);
+        if self.suppress(icon, &message) {
+            return;
+        }
+        self.present(OsdEvent::Message {
+            icon,
+            message,
+            priority: OsdPriority::Info,
+        });
+    }
+
+    /// Announce the microphone being muted or unmuted.
+    pub fn show_mic_mute(self: &Rc<Self>, muted: bool) {
+        let (icon, message) = mic_mute_message(muted);
+        if self.suppress(icon, message) {
+            return;
+        }
+        self.present(OsdEvent::Message {
+            icon,
+            message: message.to_string(),
+            priority: OsdPriority::Interactive,
+        });
+    }
+
+    /// Announce a camera access key or the lens cover.
+    pub fn show_camera_access(self: &Rc<Self>, event: CameraAccessEvent) {
+        let (icon, message) = camera_access_message(event);
+        if self.suppress(icon, message) {
+            return;
+        }
+        self.present(OsdEvent::Message {
+            icon,
+            message: message.to_string(),
+            priority: OsdPriority::Interactive,
+        });
+    }
+
+    /// Warn about overdrive or long loud headphone listening.
+    pub fn show_volume_warning(self: &Rc<Self>, warning: &VolumeWarning) {
+        let (icon, message) = volume_warning_message(warning);
+        if self.suppress(icon, &message) {
+            return;
+        }
+        self.present(OsdEvent::Message {
+            icon,
+            message,
+            priority: OsdPriority::Warning,
+        });
+    }
+
+    // Internal: suppression
+
+    /// Returns true if the OSD should stay hidden right now, sending
+    /// `summary` as a notification instead when configured.
+    fn suppress(&self, icon_name: &str, summary: &str) -> bool {
+        if !self.suppress_fullscreen && self.suppress_apps.is_empty() {
+            return false;
+        }
+        let focused = WindowTitleService::global().snapshot();
+        let Some(reason) = suppression_reason(
+            self.suppress_fullscreen,
+            &self.suppress_apps,
+            &focused,
+            running_process_names,
+        ) else {
+            return false;
+        };
+
+        debug!("OSD suppressed ({}): {}", reason, summary);
+        if self.notify_when_suppressed {
+            send_value_notification(&self.notification_id, icon_name, summary);
+        }
+        true
     }
 
     // Internal: layer shell
@@ -326,33 +547,50 @@ impl OsdOverlay {
         }
     }
 
-    fn apply_position(window: &gtk4::Window, position: &str) {
-        for edge in [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
-            window.set_anchor(edge, false);
-        }
-
-        match position {
-            "bottom" => {
-                window.set_anchor(Edge::Bottom, true);
-                window.set_margin(Edge::Bottom, 48);
-            }
-            "top" => {
-                window.set_anchor(Edge::Top, true);
-                window.set_margin(Edge::Top, 48);
-            }
-            "left" => {
-                window.set_anchor(Edge::Left, true);
-                window.set_margin(Edge::Left, 24);
-            }
-            "right" => {
-                window.set_anchor(Edge::Right, true);
-                window.set_margin(Edge::Right, 24);
-            }
+    fn position_placement(position: &str) -> Placement {
+        let (horizontal, vertical, margin_x, margin_y) = match position {
+            "bottom" => (Anchor::Center, Anchor::End, 0, 48),
+            "top" => (Anchor::Center, Anchor::Start, 0, 48),
+            "left" => (Anchor::Start, Anchor::Center, 24, 0),
+            "right" => (Anchor::End, Anchor::Center, 24, 0),
             // normalize_position guarantees only valid values, but match must be exhaustive
             _ => unreachable!("Invalid position after normalization"),
+        };
+        Placement {
+            horizontal,
+            vertical,
+            margin_x,
+            margin_y,
+            width: 0,
+            height: 0,
         }
     }
 
+    /// Anchor the window at its configured position, or clear of Quick
+    /// Settings and notification toasts when they are in the way.
+    fn place(&self) {
+        let (_, natural) = self.window.preferred_size();
+        let placement = Placement {
+            width: natural.width(),
+            height: natural.height(),
+            ..self.placement
+        };
+        let placement = ScreenRegions::global().resolve(Surface::Osd, &placement);
+        apply_placement(&self.window, &placement);
+    }
+
+    /// Re-place the OSD while it is on screen and a region changes.
+    fn connect_screen_regions(self: &Rc<Self>) {
+        let this_weak = Rc::downgrade(self);
+        ScreenRegions::global().connect(move |_: &ScreenRegionsSnapshot| {
+            if let Some(this) = this_weak.upgrade()
+                && this.window.is_visible()
+            {
+                this.place();
+            }
+        });
+    }
+
     fn reset_hide_timer(self: &Rc<Self>) {
         if self.timeout_ms == 0 {
             return;
@@ -367,8 +605,12 @@ impl OsdOverlay {
 
         let source_id = glib::timeout_add_local(Duration::from_millis(timeout as u64), move || {
             if let Some(this) = this_weak.upgrade() {
-                this.window.set_visible(false);
                 *this.hide_source.borrow_mut() = None;
+                let next = this.queue.borrow_mut().advance();
+                match next {
+                    Some(event) => this.display(&event, false),
+                    None => this.window.set_visible(false),
+                }
             }
             glib::ControlFlow::Break
         });
@@ -435,6 +677,27 @@ impl OsdOverlay {
                 this.on_audio_changed(snapshot);
             }
         });
+
+        let this_weak = Rc::downgrade(self);
+        EventBus::global().subscribe_no_replay(move |event: &JackEvent| {
+            if let Some(this) = this_weak.upgrade() {
+                this.show_jack(event);
+            }
+        });
+
+        let this_weak = Rc::downgrade(self);
+        EventBus::global().subscribe_no_replay(move |warning: &VolumeWarning| {
+            if let Some(this) = this_weak.upgrade() {
+                this.show_volume_warning(warning);
+            }
+        });
+
+        let this_weak = Rc::downgrade(self);
+        EventBus::global().subscribe_no_replay(move |event: &CameraAccessEvent| {
+            if let Some(this) = this_weak.upgrade() {
+                this.show_camera_access(*event);
+            }
+        });
     }
 
     fn on_audio_changed(self: &Rc<Self>, snapshot: &AudioSnapshot) {
@@ -461,6 +724,7 @@ impl OsdOverlay {
             self.audio_baseline_seen.set(true);
             self.last_volume.set(volume);
             self.last_muted.set(muted);
+            self.last_mic_muted.set(snapshot.mic_muted);
             return;
         }
 
@@ -470,9 +734,18 @@ impl OsdOverlay {
             self.audio_baseline_seen.set(true);
             self.last_volume.set(volume);
             self.last_muted.set(muted);
+            self.last_mic_muted.set(snapshot.mic_muted);
             return;
         }
 
+        // Mic mute (hardware key, Quick Settings, wpctl) has its own message
+        let previous_mic = self.last_mic_muted.replace(snapshot.mic_muted);
+        if let (Some(before), Some(mic_muted)) = (previous_mic, snapshot.mic_muted)
+            && before != mic_muted
+        {
+            self.show_mic_mute(mic_muted);
+        }
+
         // Check if anything changed from our tracked baseline.
         if self.last_volume.get() == volume && self.last_muted.get() == muted {
             return;
@@ -487,28 +760,23 @@ impl OsdOverlay {
             return;
         }
 
+        self.last_audio_shown.set(Some(Instant::now()));
         self.show_volume(volume, muted);
     }
 
     // Internal: IPC integration (for CLI commands)
 
+    /// Show OSDs requested over the control socket (`show-osd`).
     fn connect_ipc(self: &Rc<Self>) {
-        let listener = match OsdIpcListener::new() {
-            Some(l) => l,
-            None => {
-                debug!("OSD IPC listener not available (non-fatal)");
-                return;
-            }
-        };
-
         let this_weak = Rc::downgrade(self);
 
-        listener.borrow().connect(move |msg| {
+        // No replay: a message is a one-shot request, not state
+        EventBus::global().subscribe_no_replay(move |msg: &OsdMessage| {
             let Some(this) = this_weak.upgrade() else {
                 return;
             };
 
-            match msg {
+            match *msg {
                 OsdMessage::Volume { percent, muted } => {
                     debug!("OSD IPC: received volume {}% muted={}", percent, muted);
                     // Notify AudioService of the external volume request so
@@ -516,6 +784,21 @@ impl OsdOverlay {
                     let audio = AudioService::global();
                     audio.note_external_volume_request(percent);
 
+                    // The sink event for this change may already have shown it
+                    if is_ipc_echo(
+                        (this.last_volume.get(), this.last_muted.get()),
+                        this.last_audio_shown.get(),
+                        (percent, muted),
+                        Instant::now(),
+                    ) {
+                        debug!("OSD IPC: volume already shown from sink event");
+                        return;
+                    }
+                    // ...and if it hasn't arrived yet, it now matches the
+                    // baseline and is dropped
+                    this.last_volume.set(percent);
+                    this.last_muted.set(muted);
+
                     // Check if control is available before showing normal volume OSD
                     let snapshot = audio.current();
                     if snapshot.available && !snapshot.control_available {
@@ -535,9 +818,302 @@ impl OsdOverlay {
                 }
             }
         });
+        debug!("OSD IPC subscription connected");
+    }
+}
+
+/// Whether an IPC volume message only repeats a value a sink event showed
+/// moments ago.
+fn is_ipc_echo(
+    last: (u32, bool),
+    last_shown: Option<Instant>,
+    message: (u32, bool),
+    now: Instant,
+) -> bool {
+    last == message
+        && last_shown.is_some_and(|shown| now.saturating_duration_since(shown) < IPC_ECHO_WINDOW)
+}
+
+/// Icon and message for a jack event.
+fn jack_message(event: &JackEvent) -> (&'static str, String) {
+    if event.plugged {
+        let port = event.port.as_ref().filter(|port| port.kind.is_jack());
+        let icon = port.map_or("audio-headphones-symbolic", |port| port.kind.icon_name());
+        let label = port.map_or("Headphones", |port| port.label());
+        (icon, format!("{} plugged in", label))
+    } else if event.muted {
+        (
+            "audio-volume-muted-symbolic",
+            "Headphones unplugged, muted".to_string(),
+        )
+    } else {
+        (
+            "audio-speakers-symbolic",
+            "Headphones unplugged".to_string(),
+        )
+    }
+}
+
+/// Icon and message for a mic mute change.
+fn mic_mute_message(muted: bool) -> (&'static str, &'static str) {
+    if muted {
+        ("microphone-sensitivity-muted-symbolic", "Microphone muted")
+    } else {
+        ("audio-input-microphone-symbolic", "Microphone on")
+    }
+}
+
+/// Icon and message for a camera access change.
+fn camera_access_message(event: CameraAccessEvent) -> (&'static str, &'static str) {
+    match event {
+        CameraAccessEvent::Enabled => ("camera-web-symbolic", "Camera on"),
+        CameraAccessEvent::Disabled => ("camera-disabled-symbolic", "Camera off"),
+        CameraAccessEvent::Toggled => ("camera-web-symbolic", "Camera access toggled"),
+    }
+}
+
+/// Icon and message for a volume warning.
+fn volume_warning_message(warning: &VolumeWarning) -> (&'static str, String) {
+    match *warning {
+        VolumeWarning::Overdrive { volume } => (
+            "audio-volume-overamplified-symbolic",
+            format!("Volume {}% may distort", volume),
+        ),
+        VolumeWarning::LoudListening { minutes } => {
+            let duration = if minutes >= 60 && minutes % 60 == 0 {
+                let hours = minutes / 60;
+                format!("{} hour{}", hours, if hours == 1 { "" } else { "s" })
+            } else {
+                format!("{} min", minutes)
+            };
+            (
+                "audio-headphones-symbolic",
+                format!("Loud for {}, consider a break", duration),
+            )
+        }
+    }
+}
+
+/// Why the OSD should be suppressed, if it should.
+///
+/// `running` is only called when the focused window doesn't already decide
+/// the outcome, since it scans the process table.
+fn suppression_reason(
+    suppress_fullscreen: bool,
+    suppress_apps: &[String],
+    focused: &WindowTitleSnapshot,
+    running: impl FnOnce() -> HashSet<String>,
+) -> Option<String> {
+    if suppress_fullscreen && focused.fullscreen {
+        return Some("fullscreen window".to_string());
+    }
+    if suppress_apps.is_empty() {
+        return None;
+    }
+    if let Some(app) = suppress_apps
+        .iter()
+        .find(|app| app.eq_ignore_ascii_case(&focused.app_id))
+    {
+        return Some(format!("{} focused", app));
+    }
+    let processes = running();
+    suppress_apps
+        .iter()
+        .find(|app| processes.contains(&process_name_key(app)))
+        .map(|app| format!("{} running", app))
+}
+
+/// Normalize a name for comparison with `/proc/<pid>/comm`, which the
+/// kernel truncates to 15 bytes.
+fn process_name_key(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let mut end = name.len().min(15);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    name[..end].to_string()
+}
+
+/// Names of all running processes, normalized with `process_name_key`.
+fn running_process_names() -> HashSet<String> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return HashSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("comm")).ok())
+        .map(|comm| process_name_key(&comm))
+        .collect()
+}
+
+/// Send (or replace) a transient desktop notification with an OSD value.
+fn send_value_notification(notification_id: &Rc<Cell<u32>>, icon_name: &str, summary: &str) {
+    let mut hints = HashMap::<String, glib::Variant>::new();
+    hints.insert("transient".to_string(), true.to_variant());
+    hints.insert("urgency".to_string(), 0u8.to_variant());
+
+    let params = (
+        "vibepanel",
+        notification_id.get(),
+        icon_name,
+        summary,
+        "",
+        Vec::<String>::new(),
+        hints,
+        -1i32,
+    )
+        .to_variant();
+
+    let notification_id = notification_id.clone();
+    gio::bus_get(
+        gio::BusType::Session,
+        None::<&gio::Cancellable>,
+        move |res| {
+            let connection = match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("OSD: failed to connect to session bus: {}", e);
+                    return;
+                }
+            };
+
+            dbus_watchdog::call(
+                "notifications",
+                &connection,
+                "org.freedesktop.Notifications",
+                "/org/freedesktop/Notifications",
+                "org.freedesktop.Notifications",
+                "Notify",
+                Some(&params),
+                None,
+                CallPolicy::ACTION,
+                move |res| match res {
+                    Ok(reply) => {
+                        if let Some((id,)) = reply.get::<(u32,)>() {
+                            notification_id.set(id);
+                        }
+                    }
+                    Err(e) => debug!("OSD: failed to send notification: {}", e),
+                },
+            );
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn focused(app_id: &str, fullscreen: bool) -> WindowTitleSnapshot {
+        WindowTitleSnapshot {
+            app_id: app_id.to_string(),
+            fullscreen,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_jack_message() {
+        use crate::services::audio::{PortKind, SinkPort};
+
+        let mut event = JackEvent {
+            plugged: true,
+            sink_description: "Built-in Audio".to_string(),
+            port: Some(SinkPort {
+                name: "analog-output-headphones".to_string(),
+                description: "Headphones".to_string(),
+                kind: PortKind::Headphones,
+            }),
+            muted: false,
+        };
+        assert_eq!(
+            jack_message(&event),
+            (
+                "audio-headphones-symbolic",
+                "Headphones plugged in".to_string()
+            )
+        );
+
+        event.plugged = false;
+        event.muted = true;
+        assert_eq!(jack_message(&event).1, "Headphones unplugged, muted");
+    }
+
+    #[test]
+    fn test_is_ipc_echo() {
+        let shown = Instant::now();
+        let soon = shown + Duration::from_millis(50);
+        assert!(is_ipc_echo((40, false), Some(shown), (40, false), soon));
+        // A different value, or the same one much later, is a new key press
+        assert!(!is_ipc_echo((40, false), Some(shown), (45, false), soon));
+        assert!(!is_ipc_echo((40, false), Some(shown), (40, true), soon));
+        assert!(!is_ipc_echo(
+            (40, false),
+            Some(shown),
+            (40, false),
+            shown + IPC_ECHO_WINDOW
+        ));
+        assert!(!is_ipc_echo((40, false), None, (40, false), soon));
+    }
+
+    #[test]
+    fn test_mic_and_camera_messages() {
+        assert_eq!(mic_mute_message(true).1, "Microphone muted");
+        assert_eq!(mic_mute_message(false).1, "Microphone on");
+        assert_eq!(
+            camera_access_message(CameraAccessEvent::Disabled),
+            ("camera-disabled-symbolic", "Camera off")
+        );
+    }
+
+    #[test]
+    fn test_volume_warning_message() {
+        let message = |warning| volume_warning_message(&warning).1;
+        assert_eq!(
+            message(VolumeWarning::Overdrive { volume: 110 }),
+            "Volume 110% may distort"
+        );
+        assert_eq!(
+            message(VolumeWarning::LoudListening { minutes: 45 }),
+            "Loud for 45 min, consider a break"
+        );
+        assert_eq!(
+            message(VolumeWarning::LoudListening { minutes: 120 }),
+            "Loud for 2 hours, consider a break"
+        );
+    }
+
+    #[test]
+    fn test_suppression_reason() {
+        let apps = vec!["steam".to_string(), "Gamescope".to_string()];
+        let no_processes = || -> HashSet<String> { panic!("process scan not needed") };
+
+        assert!(suppression_reason(true, &[], &focused("mpv", true), no_processes).is_some());
+        assert!(suppression_reason(false, &[], &focused("mpv", true), no_processes).is_none());
+        assert_eq!(
+            suppression_reason(false, &apps, &focused("Steam", false), no_processes),
+            Some("steam focused".to_string())
+        );
+
+        let running = || HashSet::from(["gamescope".to_string()]);
+        assert_eq!(
+            suppression_reason(false, &apps, &focused("firefox", false), running),
+            Some("Gamescope running".to_string())
+        );
+        assert!(
+            suppression_reason(false, &apps, &focused("firefox", false), HashSet::new).is_none()
+        );
+    }
 
-        // Store the listener to keep it alive.
-        *self._ipc_listener.borrow_mut() = Some(listener);
-        debug!("OSD IPC listener connected");
+    #[test]
+    fn test_process_name_key_truncates_like_comm() {
+        assert_eq!(process_name_key("SteamWebHelperProcess"), "steamwebhelperp");
+        assert_eq!(process_name_key("gamescope\n"), "gamescope");
     }
 }
@@ -0,0 +1,245 @@
+//! Event queue for the OSD overlay.
+//!
+//! The overlay shows one event at a time. New events either:
+//! - Coalesce with the event on screen (rapid volume key presses update the
+//!   slider in place instead of restarting the OSD)
+//! - Preempt it, when they have a higher priority (a volume change while a
+//!   hotplug message is showing); a preempted message is shown again later.
+//!   Feedback for user input always shows the latest key press.
+//! - Wait in a queue until the current event times out
+//!
+//! This module only decides what to show; `OsdOverlay` does the showing.
+
+use std::collections::VecDeque;
+
+/// Maximum queued events; the oldest lowest-priority one is dropped beyond this.
+const MAX_PENDING: usize = 8;
+
+/// How urgently an event should be shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OsdPriority {
+    /// Device hotplug and other informational messages.
+    Info,
+    /// Warnings about the current state (e.g. volume overdrive).
+    Warning,
+    /// Direct feedback for user input (volume / brightness keys).
+    Interactive,
+}
+
+/// Which slider a value event drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdValueKind {
+    Volume,
+    Brightness,
+}
+
+/// Something for the OSD to show.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OsdEvent {
+    /// Icon + slider.
+    Value {
+        kind: OsdValueKind,
+        icon: &'static str,
+        value: u32,
+    },
+    /// Centered icon + message.
+    Message {
+        icon: &'static str,
+        message: String,
+        priority: OsdPriority,
+    },
+}
+
+impl OsdEvent {
+    pub fn priority(&self) -> OsdPriority {
+        match self {
+            Self::Value { .. } => OsdPriority::Interactive,
+            Self::Message { priority, .. } => *priority,
+        }
+    }
+
+    /// Whether `other` replaces this event instead of being shown after it.
+    fn coalesces_with(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Value { kind: a, .. }, Self::Value { kind: b, .. }) => a == b,
+            (Self::Message { message: a, .. }, Self::Message { message: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// What the overlay should do after an event is pushed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OsdAction {
+    /// Show this event, replacing whatever is on screen.
+    Show(OsdEvent),
+    /// Update the event on screen in place and restart its timeout.
+    Update(OsdEvent),
+    /// Nothing to do until the current event times out.
+    Queued,
+}
+
+/// Current and pending OSD events.
+#[derive(Debug, Default)]
+pub struct OsdQueue {
+    current: Option<OsdEvent>,
+    /// Pending events, highest priority first (FIFO within a priority).
+    pending: VecDeque<OsdEvent>,
+}
+
+impl OsdQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an event and decide how to present it.
+    pub fn push(&mut self, event: OsdEvent) -> OsdAction {
+        let Some(current) = self.current.as_ref() else {
+            self.current = Some(event.clone());
+            return OsdAction::Show(event);
+        };
+
+        if current.coalesces_with(&event) {
+            self.current = Some(event.clone());
+            return OsdAction::Update(event);
+        }
+
+        let preempts =
+            event.priority() > current.priority() || event.priority() == OsdPriority::Interactive;
+        if preempts {
+            // Messages aren't stale once interrupted; show them again later.
+            // An interrupted value is, so drop it.
+            if let Some(preempted) = self.current.replace(event.clone())
+                && matches!(preempted, OsdEvent::Message { .. })
+            {
+                self.pending.push_front(preempted);
+            }
+            self.pending
+                .retain(|pending| !pending.coalesces_with(&event));
+            return OsdAction::Show(event);
+        }
+
+        self.enqueue(event);
+        OsdAction::Queued
+    }
+
+    /// The current event timed out; return the next one to show, if any.
+    pub fn advance(&mut self) -> Option<OsdEvent> {
+        self.current = self.pending.pop_front();
+        self.current.clone()
+    }
+
+    /// Forget the current and pending events.
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.pending.clear();
+    }
+
+    fn enqueue(&mut self, event: OsdEvent) {
+        if let Some(existing) = self.pending.iter_mut().find(|p| p.coalesces_with(&event)) {
+            *existing = event;
+            return;
+        }
+
+        let index = self
+            .pending
+            .iter()
+            .position(|p| p.priority() < event.priority())
+            .unwrap_or(self.pending.len());
+        self.pending.insert(index, event);
+
+        if self.pending.len() > MAX_PENDING {
+            // Lowest priority sits at the back; drop the oldest of those
+            let lowest = self.pending.back().map(OsdEvent::priority);
+            if let Some(index) = self
+                .pending
+                .iter()
+                .position(|p| Some(p.priority()) == lowest)
+            {
+                self.pending.remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(value: u32) -> OsdEvent {
+        OsdEvent::Value {
+            kind: OsdValueKind::Volume,
+            icon: "audio-volume-medium-symbolic",
+            value,
+        }
+    }
+
+    fn message(message: &str, priority: OsdPriority) -> OsdEvent {
+        OsdEvent::Message {
+            icon: "audio-headphones-symbolic",
+            message: message.to_string(),
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_volume_changes_coalesce() {
+        let mut queue = OsdQueue::new();
+        assert_eq!(queue.push(volume(40)), OsdAction::Show(volume(40)));
+        assert_eq!(queue.push(volume(45)), OsdAction::Update(volume(45)));
+
+        // Other input feedback replaces it right away
+        let brightness = OsdEvent::Value {
+            kind: OsdValueKind::Brightness,
+            icon: "display-brightness-medium-symbolic",
+            value: 50,
+        };
+        assert_eq!(queue.push(brightness.clone()), OsdAction::Show(brightness));
+        assert_eq!(queue.advance(), None);
+    }
+
+    #[test]
+    fn test_hotplug_queued_behind_volume() {
+        let mut queue = OsdQueue::new();
+        let plugged = message("Headphones plugged in", OsdPriority::Info);
+        queue.push(volume(40));
+        assert_eq!(queue.push(plugged.clone()), OsdAction::Queued);
+        assert_eq!(queue.push(volume(45)), OsdAction::Update(volume(45)));
+        assert_eq!(queue.advance(), Some(plugged));
+        assert_eq!(queue.advance(), None);
+    }
+
+    #[test]
+    fn test_volume_preempts_message_which_returns() {
+        let mut queue = OsdQueue::new();
+        let unplugged = message("Headphones unplugged", OsdPriority::Info);
+        let warning = message("Volume 110% may distort", OsdPriority::Warning);
+        queue.push(unplugged.clone());
+        assert_eq!(queue.push(volume(110)), OsdAction::Show(volume(110)));
+        assert_eq!(queue.push(warning.clone()), OsdAction::Queued);
+        // Higher priority first, then the interrupted message
+        assert_eq!(queue.advance(), Some(warning));
+        assert_eq!(queue.advance(), Some(unplugged));
+    }
+
+    #[test]
+    fn test_pending_is_bounded() {
+        let mut queue = OsdQueue::new();
+        queue.push(volume(40));
+        for i in 0..MAX_PENDING + 2 {
+            queue.push(message(&format!("Device {}", i), OsdPriority::Info));
+        }
+        let mut shown = Vec::new();
+        while let Some(event) = queue.advance() {
+            shown.push(event);
+        }
+        assert_eq!(shown.len(), MAX_PENDING);
+        assert_eq!(
+            shown.last(),
+            Some(&message(
+                &format!("Device {}", MAX_PENDING + 1),
+                OsdPriority::Info
+            ))
+        );
+    }
+}
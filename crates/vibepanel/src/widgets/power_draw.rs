@@ -0,0 +1,337 @@
+//! Power draw widget - CPU power from RAPL energy counters.
+//!
+//! This widget:
+//! - Shows the current CPU package draw in watts
+//! - Keeps the package energy used since the bar started
+//! - Breaks the draw down by domain (package, cores, uncore, DRAM) in a
+//!   popover, next to the battery discharge rate on laptops
+//!
+//! Configuration options:
+//! - `check_interval`: How often to read the counters, in seconds (default: 2)
+//! - `show_icon`: Whether to show the bolt icon (default: true)
+//! - `show_session`: Whether to show the session energy next to the watts
+//!   (default: false)
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Label, Orientation, Widget};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::battery::{BatteryService, BatterySnapshot, STATE_DISCHARGING};
+use crate::services::icons::IconHandle;
+use crate::services::power_draw::{
+    DEFAULT_CHECK_INTERVAL, Domain, PowerDrawService, PowerDrawSnapshot,
+};
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{class, color, power_draw, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_SHOW_ICON: bool = true;
+const DEFAULT_SHOW_SESSION: bool = false;
+
+/// Configuration for the power draw widget.
+#[derive(Debug, Clone)]
+pub struct PowerDrawConfig {
+    /// How often to read the energy counters (seconds).
+    pub check_interval: u64,
+    /// Whether to show the icon.
+    pub show_icon: bool,
+    /// Whether to show the session energy in the bar.
+    pub show_session: bool,
+}
+
+impl WidgetConfig for PowerDrawConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "power_draw",
+            entry,
+            &["check_interval", "show_icon", "show_session"],
+        );
+
+        let check_interval = entry
+            .options
+            .get("check_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+
+        let show_icon = entry
+            .options
+            .get("show_icon")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_ICON);
+
+        let show_session = entry
+            .options
+            .get("show_session")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_SESSION);
+
+        Self {
+            check_interval,
+            show_icon,
+            show_session,
+        }
+    }
+}
+
+/// Power draw widget that displays the CPU package watts.
+pub struct PowerDrawWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Bolt icon handle from IconsService.
+    _icon_handle: IconHandle,
+    /// Popover with the per-domain breakdown.
+    _menu: Rc<MenuHandle>,
+    /// Read interval registered with the service.
+    settings_id: SettingsId,
+}
+
+impl PowerDrawWidget {
+    /// Create a new power draw widget with the given configuration.
+    pub fn new(config: PowerDrawConfig) -> Self {
+        let base = BaseWidget::new(&[widget::POWER_DRAW]);
+        base.set_tooltip("Power draw: reading energy counters...");
+
+        let icon_handle = base.add_icon("power-draw-symbolic", &[widget::POWER_DRAW_ICON]);
+        icon_handle.widget().set_visible(config.show_icon);
+        let label = base.add_label(None, &[widget::POWER_DRAW_LABEL, class::VCENTER_CAPS]);
+
+        // Last snapshot shown, which the popover breaks down
+        let latest: Rc<RefCell<PowerDrawSnapshot>> = Rc::default();
+        let menu = {
+            let latest = latest.clone();
+            base.create_menu(move || {
+                build_power_draw_popover(&latest.borrow(), &BatteryService::global().snapshot())
+            })
+        };
+
+        let container = base.widget().clone();
+        let service = PowerDrawService::global();
+        let settings_id = service.configure(config.check_interval);
+        {
+            let menu = menu.clone();
+            service.connect(move |snapshot: &PowerDrawSnapshot| {
+                *latest.borrow_mut() = snapshot.clone();
+                update_widget_from_snapshot(&container, &label, &config, snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self {
+            base,
+            _icon_handle: icon_handle,
+            _menu: menu,
+            settings_id,
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for PowerDrawWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for PowerDrawWidget {
+    fn drop(&mut self) {
+        PowerDrawService::global().release(self.settings_id);
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    label: &Label,
+    config: &PowerDrawConfig,
+    snapshot: &PowerDrawSnapshot,
+) {
+    if let Some(ref error) = snapshot.error {
+        update::set_label_text(label, "?");
+        TooltipManager::global().set_styled_tooltip(container, &format!("Power draw: {}", error));
+        return;
+    }
+    let Some(watts) = snapshot.package_watts() else {
+        return;
+    };
+
+    let text = if config.show_session {
+        format!(
+            "{} · {}",
+            format_watts(watts),
+            format_wh(snapshot.session_wh)
+        )
+    } else {
+        format_watts(watts)
+    };
+    update::set_label_text(label, &text);
+
+    TooltipManager::global().set_styled_tooltip(
+        container,
+        &format!(
+            "CPU package: {}\nSince start: {}",
+            format_watts(watts),
+            format_wh(snapshot.session_wh)
+        ),
+    );
+}
+
+/// Format a draw for display, e.g. "8.4 W" or "23 W".
+fn format_watts(watts: f64) -> String {
+    if watts < 10.0 {
+        format!("{:.1} W", watts)
+    } else {
+        format!("{:.0} W", watts)
+    }
+}
+
+/// Format an energy total for display, e.g. "1.25 Wh".
+fn format_wh(wh: f64) -> String {
+    if wh < 10.0 {
+        format!("{:.2} Wh", wh)
+    } else {
+        format!("{:.1} Wh", wh)
+    }
+}
+
+/// Battery discharge rate, if the system runs on battery.
+fn battery_discharge(battery: &BatterySnapshot) -> Option<f64> {
+    if battery.present && battery.state == Some(STATE_DISCHARGING) {
+        battery.energy_rate.filter(|rate| *rate > 0.0)
+    } else {
+        None
+    }
+}
+
+/// Add a name / value row to the popover.
+fn append_row(container: &GtkBox, name: &str, value: &str, css_class: Option<&str>) {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class(power_draw::ROW);
+    if let Some(css_class) = css_class {
+        row.add_css_class(css_class);
+    }
+
+    let name = Label::new(Some(name));
+    name.add_css_class(power_draw::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    row.append(&name);
+
+    let value = Label::new(Some(value));
+    value.add_css_class(power_draw::ROW_VALUE);
+    value.set_halign(Align::End);
+    row.append(&value);
+
+    container.append(&row);
+}
+
+/// Build the popover content breaking `snapshot` down by domain.
+fn build_power_draw_popover(snapshot: &PowerDrawSnapshot, battery: &BatterySnapshot) -> Widget {
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(power_draw::POPOVER);
+
+    let title = Label::new(Some("Power draw"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    container.append(&title);
+
+    if !snapshot.ready {
+        let empty = Label::new(Some(
+            snapshot
+                .error
+                .as_deref()
+                .unwrap_or("Reading energy counters..."),
+        ));
+        empty.add_css_class(color::MUTED);
+        empty.set_halign(Align::Start);
+        empty.set_wrap(true);
+        empty.set_max_width_chars(36);
+        container.append(&empty);
+        return container.upcast();
+    }
+
+    let multi_socket = snapshot.domains.iter().any(|d| d.socket > 0);
+    for domain in &snapshot.domains {
+        let name = if multi_socket {
+            format!("{} (socket {})", domain.domain.label(), domain.socket)
+        } else {
+            domain.domain.label().to_string()
+        };
+        // Cores and uncore are part of the package figure
+        let part =
+            matches!(domain.domain, Domain::Core | Domain::Uncore).then_some(power_draw::ROW_PART);
+        append_row(&container, &name, &format_watts(domain.watts), part);
+    }
+    append_row(
+        &container,
+        "Since start",
+        &format_wh(snapshot.session_wh),
+        None,
+    );
+
+    if let Some(discharge) = battery_discharge(battery) {
+        let cpu =
+            snapshot.package_watts().unwrap_or(0.0) + snapshot.total(Domain::Dram).unwrap_or(0.0);
+        append_row(&container, "Battery", &format_watts(discharge), None);
+        append_row(
+            &container,
+            "Rest of system",
+            &format_watts((discharge - cpu).max(0.0)),
+            None,
+        );
+    }
+
+    container.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::test_entry;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_power_draw_config() {
+        let config = PowerDrawConfig::from_entry(&test_entry("power_draw", HashMap::new()));
+        assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
+        assert!(config.show_icon);
+        assert!(!config.show_session);
+
+        let mut options = HashMap::new();
+        options.insert("check_interval".to_string(), toml::Value::Integer(0));
+        options.insert("show_session".to_string(), toml::Value::Boolean(true));
+        let config = PowerDrawConfig::from_entry(&test_entry("power_draw", options));
+        assert_eq!(config.check_interval, 1);
+        assert!(config.show_session);
+    }
+
+    #[test]
+    fn test_format_power() {
+        assert_eq!(format_watts(4.26), "4.3 W");
+        assert_eq!(format_watts(23.4), "23 W");
+        assert_eq!(format_wh(0.123), "0.12 Wh");
+        assert_eq!(format_wh(12.34), "12.3 Wh");
+    }
+
+    #[test]
+    fn test_battery_discharge() {
+        let mut battery = BatterySnapshot::unknown();
+        battery.present = true;
+        battery.energy_rate = Some(14.5);
+        battery.state = Some(STATE_DISCHARGING);
+        assert_eq!(battery_discharge(&battery), Some(14.5));
+
+        battery.state = Some(crate::services::battery::STATE_CHARGING);
+        assert_eq!(battery_discharge(&battery), None);
+    }
+}
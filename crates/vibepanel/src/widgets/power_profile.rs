@@ -0,0 +1,237 @@
+//! Power profile widget - shows and switches the power-profiles-daemon profile.
+//!
+//! This widget:
+//! - Shows an icon for the active profile (performance, balanced, power saver)
+//! - Cycles to the next profile on click
+//! - Hides itself when power-profiles-daemon is not running
+//!
+//! Configuration options:
+//! - `show_label`: Show the profile name next to the icon (default: false)
+
+use gtk4::prelude::*;
+use gtk4::{GestureClick, Label};
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::callbacks::CallbackId;
+use crate::services::icons::IconHandle;
+use crate::services::power_profile::{
+    PowerProfileService, PowerProfileSnapshot, profile_icon, profile_label,
+};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{class, state, widget};
+use crate::widgets::base::BaseWidget;
+use crate::widgets::{WidgetConfig, warn_unknown_options};
+
+/// Configuration for the power profile widget.
+#[derive(Debug, Clone)]
+pub struct PowerProfileConfig {
+    /// Whether to show the profile name next to the icon.
+    pub show_label: bool,
+}
+
+impl WidgetConfig for PowerProfileConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("power_profile", entry, &["show_label"]);
+
+        let show_label = entry
+            .options
+            .get("show_label")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        Self { show_label }
+    }
+}
+
+/// Power profile widget showing the active profile.
+pub struct PowerProfileWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// PowerProfileService callback registration.
+    callback_id: Option<CallbackId>,
+}
+
+impl PowerProfileWidget {
+    /// Create a new power profile widget with the given configuration.
+    pub fn new(config: PowerProfileConfig) -> Self {
+        let base = BaseWidget::new(&[widget::POWER_PROFILE]);
+        base.widget().set_visible(false);
+        base.widget().add_css_class(state::CLICKABLE);
+
+        let icon_handle = base.add_icon(
+            "power-profile-balanced-symbolic",
+            &[widget::POWER_PROFILE_ICON],
+        );
+        let label = base.add_label(None, &[widget::POWER_PROFILE_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(config.show_label);
+
+        let click = GestureClick::new();
+        click.connect_released(|_, _, _, _| {
+            let service = PowerProfileService::global();
+            let snapshot = service.snapshot();
+            if let Some(next) = snapshot.next_profile() {
+                service.set_profile(next);
+            }
+        });
+        base.widget().add_controller(click);
+
+        let callback_id = {
+            let container = base.widget().clone();
+            PowerProfileService::global().connect(move |snapshot: &PowerProfileSnapshot| {
+                update_widget_from_snapshot(&container, &icon_handle, &label, snapshot);
+            })
+        };
+
+        Self {
+            base,
+            callback_id: Some(callback_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl Drop for PowerProfileWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.callback_id.take() {
+            PowerProfileService::global().disconnect(callback_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    snapshot: &PowerProfileSnapshot,
+) {
+    let profile = match snapshot.current_profile.as_deref() {
+        Some(profile) if snapshot.available => profile,
+        _ => {
+            container.set_visible(false);
+            return;
+        }
+    };
+    container.set_visible(true);
+
+    container.remove_css_class(widget::POWER_PROFILE_PERFORMANCE);
+    container.remove_css_class(widget::POWER_PROFILE_POWER_SAVER);
+    match profile {
+        "performance" => container.add_css_class(widget::POWER_PROFILE_PERFORMANCE),
+        "power-saver" => container.add_css_class(widget::POWER_PROFILE_POWER_SAVER),
+        _ => {}
+    }
+
+    let name = profile_label(profile);
+    icon_handle.set_icon(profile_icon(profile));
+    label.set_label(&name);
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(&name, snapshot));
+}
+
+/// Format the tooltip, naming the profile a click switches to.
+fn format_tooltip(name: &str, snapshot: &PowerProfileSnapshot) -> String {
+    match snapshot.next_profile() {
+        Some(next) if snapshot.available_profiles.len() > 1 => format!(
+            "Power profile: {}\nClick to switch to {}",
+            name,
+            profile_label(next)
+        ),
+        _ => format!("Power profile: {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_power_profile_config() {
+        let mut options = HashMap::new();
+        let config = PowerProfileConfig::from_entry(&WidgetEntry {
+            name: "power_profile".to_string(),
+            options: options.clone(),
+        });
+        assert!(!config.show_label);
+
+        options.insert("show_label".to_string(), toml::Value::Boolean(true));
+        let config = PowerProfileConfig::from_entry(&WidgetEntry {
+            name: "power_profile".to_string(),
+            options,
+        });
+        assert!(config.show_label);
+    }
+
+    #[test]
+    fn test_format_tooltip() {
+        let snapshot = PowerProfileSnapshot {
+            available: true,
+            current_profile: Some("balanced".to_string()),
+            available_profiles: vec!["balanced".to_string(), "power-saver".to_string()],
+        };
+        assert_eq!(
+            format_tooltip("Balanced", &snapshot),
+            "Power profile: Balanced\nClick to switch to Power Saver"
+        );
+
+        let single = PowerProfileSnapshot {
+            available_profiles: vec!["balanced".to_string()],
+            ..snapshot
+        };
+        assert_eq!(
+            format_tooltip("Balanced", &single),
+            "Power profile: Balanced"
+        );
+    }
+
+    #[test]
+    fn test_power_profile_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = PowerProfileWidget::new(PowerProfileConfig { show_label: true });
+            let service = PowerProfileService::global();
+            let profiles = vec![
+                "power-saver".to_string(),
+                "balanced".to_string(),
+                "performance".to_string(),
+            ];
+
+            service.publish_for_test(PowerProfileSnapshot {
+                available: true,
+                current_profile: Some("performance".to_string()),
+                available_profiles: profiles.clone(),
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.power-profile.power-profile-performance.widget.widget-item",
+                    "icon.power-profile-icon",
+                    r#"label.power-profile-label.vcenter-caps "Performance""#,
+                ],
+            );
+
+            service.publish_for_test(PowerProfileSnapshot {
+                available: true,
+                current_profile: Some("balanced".to_string()),
+                available_profiles: profiles,
+            });
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &[
+                    "box.clickable.power-profile.widget.widget-item",
+                    r#"label.power-profile-label.vcenter-caps "Balanced""#,
+                ],
+            );
+            assert!(!rendered.contains("power-profile-performance"));
+
+            // Hidden while power-profiles-daemon isn't running
+            service.publish_for_test(PowerProfileSnapshot::empty());
+            assert_eq!(test_harness::render(widget.widget()), "");
+        });
+    }
+}
@@ -0,0 +1,310 @@
+//! Privacy widget - microphone, camera and screen-share indicators.
+//!
+//! This widget:
+//! - Shows an icon for each device an application is currently using
+//! - Hides itself when nothing is in use
+//! - Lists which applications hold each device in a popover
+//!
+//! Usage comes from PipeWire streams and open `/dev/video*` devices (see
+//! `PrivacyService`).
+//!
+//! Configuration options:
+//! - `devices`: Devices to watch, any of "microphone", "camera" and
+//!   "screen_share" (default: all)
+//! - `check_interval`: How often to check, in seconds (default: 2)
+
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Label, Orientation, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::callbacks::CallbackId;
+use crate::services::icons::IconHandle;
+use crate::services::privacy::{DEFAULT_CHECK_INTERVAL, Device, PrivacyService, PrivacySnapshot};
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{color, privacy, surface, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, warn_unknown_options};
+
+/// Configuration for the privacy widget.
+#[derive(Debug, Clone)]
+pub struct PrivacyConfig {
+    /// Devices to show indicators for, in display order.
+    pub devices: Vec<Device>,
+    /// How often to check device usage (seconds).
+    pub check_interval: u64,
+}
+
+impl WidgetConfig for PrivacyConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("privacy", entry, &["devices", "check_interval"]);
+
+        let devices = match entry.options.get("devices").and_then(|v| v.as_array()) {
+            Some(names) => {
+                let mut devices: Vec<Device> = names
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .filter_map(|name| {
+                        let device = Device::from_name(name);
+                        if device.is_none() {
+                            warn!("privacy widget: unknown device '{}'", name);
+                        }
+                        device
+                    })
+                    .collect();
+                devices.sort();
+                devices.dedup();
+                devices
+            }
+            None => Device::ALL.to_vec(),
+        };
+
+        let check_interval = entry
+            .options
+            .get("check_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+
+        Self {
+            devices,
+            check_interval,
+        }
+    }
+}
+
+/// Privacy widget showing in-use device indicators.
+pub struct PrivacyWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover listing device users.
+    _menu: Rc<MenuHandle>,
+    /// PrivacyService callback registration.
+    callback_id: Option<CallbackId>,
+    /// Check interval registered with the service.
+    settings_id: SettingsId,
+}
+
+impl PrivacyWidget {
+    /// Create a new privacy widget with the given configuration.
+    pub fn new(config: PrivacyConfig) -> Self {
+        let base = BaseWidget::new(&[widget::PRIVACY]);
+        base.widget().set_visible(false);
+
+        let icons: Vec<(Device, IconHandle)> = config
+            .devices
+            .iter()
+            .map(|&device| {
+                let handle = base.add_icon(icon_name(device), &[widget::PRIVACY_ICON]);
+                handle.widget().set_visible(false);
+                (device, handle)
+            })
+            .collect();
+
+        let devices = Rc::new(config.devices);
+        let menu = {
+            let devices = devices.clone();
+            base.create_menu(move || build_privacy_popover(&devices))
+        };
+
+        let service = PrivacyService::global();
+        let settings_id = service.configure(config.check_interval);
+
+        let callback_id = {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            service.connect(move |snapshot: &PrivacySnapshot| {
+                update_widget_from_snapshot(&container, &icons, snapshot);
+                menu.refresh_if_visible();
+            })
+        };
+
+        Self {
+            base,
+            _menu: menu,
+            callback_id: Some(callback_id),
+            settings_id,
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for PrivacyWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for PrivacyWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.callback_id.take() {
+            let service = PrivacyService::global();
+            service.disconnect(callback_id);
+            service.release(self.settings_id);
+        }
+    }
+}
+
+/// Icon name for a device indicator.
+fn icon_name(device: Device) -> &'static str {
+    match device {
+        Device::Microphone => "audio-input-microphone-symbolic",
+        Device::Camera => "camera-web-symbolic",
+        Device::ScreenShare => "screen-shared-symbolic",
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icons: &[(Device, IconHandle)],
+    snapshot: &PrivacySnapshot,
+) {
+    let mut any_in_use = false;
+    for (device, handle) in icons {
+        let in_use = snapshot.in_use(*device);
+        handle.widget().set_visible(in_use);
+        any_in_use |= in_use;
+    }
+    container.set_visible(any_in_use);
+
+    if any_in_use {
+        let devices: Vec<Device> = icons.iter().map(|(device, _)| *device).collect();
+        TooltipManager::global().set_styled_tooltip(container, &format_tooltip(&devices, snapshot));
+    }
+}
+
+/// Format the tooltip, e.g. "Microphone: Firefox, Discord".
+fn format_tooltip(devices: &[Device], snapshot: &PrivacySnapshot) -> String {
+    devices
+        .iter()
+        .filter(|device| snapshot.in_use(**device))
+        .map(|&device| {
+            let apps: Vec<&str> = snapshot
+                .users_of(device)
+                .map(|user| user.app.as_str())
+                .collect();
+            format!("{}: {}", device.label(), apps.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build the popover content listing the applications using each device.
+fn build_privacy_popover(devices: &[Device]) -> Widget {
+    let snapshot = PrivacyService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(privacy::POPOVER);
+
+    let title = Label::new(Some("Privacy"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    container.append(&title);
+
+    let in_use: Vec<Device> = devices
+        .iter()
+        .copied()
+        .filter(|device| snapshot.in_use(*device))
+        .collect();
+    if in_use.is_empty() {
+        let empty = Label::new(Some("No devices in use"));
+        empty.add_css_class(color::MUTED);
+        empty.set_halign(Align::Start);
+        container.append(&empty);
+    }
+
+    for device in in_use {
+        let section = Label::new(Some(device.label()));
+        section.add_css_class(privacy::SECTION_TITLE);
+        section.set_halign(Align::Start);
+        container.append(&section);
+
+        for user in snapshot.users_of(device) {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            row.add_css_class(privacy::ROW);
+
+            let app = Label::new(Some(&user.app));
+            app.add_css_class(privacy::ROW_APP);
+            app.set_halign(Align::Start);
+            app.set_hexpand(true);
+            app.set_xalign(0.0);
+            app.set_ellipsize(EllipsizeMode::End);
+            app.set_max_width_chars(28);
+            row.append(&app);
+
+            if let Some(pid) = user.pid {
+                let pid = Label::new(Some(&format!("PID {}", pid)));
+                pid.add_css_class(color::MUTED);
+                pid.set_halign(Align::End);
+                row.append(&pid);
+            }
+
+            container.append(&row);
+        }
+    }
+
+    container.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::privacy::DeviceUser;
+    use crate::widgets::test_entry;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_privacy_config() {
+        let config = PrivacyConfig::from_entry(&test_entry("privacy", HashMap::new()));
+        assert_eq!(config.devices, Device::ALL.to_vec());
+        assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
+
+        let mut options = HashMap::new();
+        options.insert(
+            "devices".to_string(),
+            toml::Value::Array(vec![
+                toml::Value::String("screen_share".to_string()),
+                toml::Value::String("speaker".to_string()),
+                toml::Value::String("microphone".to_string()),
+            ]),
+        );
+        let config = PrivacyConfig::from_entry(&test_entry("privacy", options));
+        assert_eq!(
+            config.devices,
+            vec![Device::Microphone, Device::ScreenShare]
+        );
+    }
+
+    #[test]
+    fn test_format_tooltip() {
+        let user = |device, app: &str| DeviceUser {
+            device,
+            app: app.to_string(),
+            pid: None,
+        };
+        let snapshot = PrivacySnapshot {
+            ready: true,
+            users: vec![
+                user(Device::Microphone, "Discord"),
+                user(Device::Microphone, "Firefox"),
+                user(Device::Camera, "zoom"),
+            ],
+        };
+
+        assert_eq!(
+            format_tooltip(&Device::ALL, &snapshot),
+            "Microphone: Discord, Firefox\nCamera: zoom"
+        );
+        // Unwatched devices are left out
+        assert_eq!(format_tooltip(&[Device::Camera], &snapshot), "Camera: zoom");
+    }
+}
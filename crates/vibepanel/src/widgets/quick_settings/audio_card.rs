@@ -3,7 +3,7 @@
 //! This module contains:
 //! - Audio icon helpers (volume_icon_name)
 //! - Audio row building (mute button, slider, expander)
-//! - Audio details (sink list)
+//! - Audio details (sink list, with each sink's active port)
 //! - State change handling
 
 use std::cell::{Cell, RefCell};
@@ -184,10 +184,13 @@ pub fn build_audio_hint_label() -> Label {
 /// - `is_default`: Whether this sink is the current default.
 /// - `port_available`: Whether the sink's port is available (e.g., headphones plugged in).
 ///   `None` means no jack detection, `Some(false)` means unavailable.
+/// - `port`: Label of the active port ("Speakers", "Headphones", "HDMI"),
+///   shown below the description.
 pub fn create_sink_row(
     description: &str,
     is_default: bool,
     port_available: Option<bool>,
+    port: Option<&str>,
 ) -> ListBoxRow {
     let list_row = ListBoxRow::new();
     list_row.add_css_class(row::QS);
@@ -209,7 +212,23 @@ pub fn create_sink_row(
     label.set_max_width_chars(22);
     label.add_css_class(row::QS_TITLE);
     label.add_css_class(color::PRIMARY);
-    hbox.append(&label);
+
+    match port.filter(|port| *port != description) {
+        Some(port) => {
+            let text_box = GtkBox::new(Orientation::Vertical, 0);
+            text_box.set_hexpand(true);
+            text_box.set_valign(Align::Center);
+            text_box.append(&label);
+
+            let port_label = Label::new(Some(port));
+            port_label.set_xalign(0.0);
+            port_label.add_css_class(row::QS_SUBTITLE);
+            port_label.add_css_class(color::MUTED);
+            text_box.append(&port_label);
+            hbox.append(&text_box);
+        }
+        None => hbox.append(&label),
+    }
 
     // Selection indicator
     if is_default {
@@ -289,7 +308,12 @@ pub fn populate_audio_sink_list(list_box: &ListBox, snapshot: &AudioSnapshot) {
             continue;
         }
 
-        let row = create_sink_row(&sink.description, sink.is_default, sink.port_available);
+        let row = create_sink_row(
+            &sink.description,
+            sink.is_default,
+            sink.port_available,
+            sink.active_port.as_ref().map(|port| port.label()),
+        );
         list_box.append(&row);
     }
 }
@@ -8,7 +8,7 @@
 use gtk4::gdk::BUTTON_PRIMARY;
 use gtk4::prelude::*;
 use gtk4::{Box as GtkBox, GestureClick};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::QuickSettingsWindowHandle;
 use super::audio_card::volume_icon_name;
@@ -17,7 +17,6 @@ use super::vpn_card::vpn_icon_name;
 use super::wifi_card::wifi_icon_name;
 use crate::services::audio::{AudioService, AudioSnapshot};
 use crate::services::bluetooth::{BluetoothService, BluetoothSnapshot};
-use crate::services::config_manager::ConfigManager;
 use crate::services::network::{NetworkService, NetworkSnapshot};
 use crate::services::tooltip::TooltipManager;
 use crate::services::vpn::{VpnService, VpnSnapshot};
@@ -26,6 +25,77 @@ use crate::widgets::BaseWidget;
 use crate::widgets::WidgetConfig;
 use crate::widgets::warn_unknown_options;
 use vibepanel_core::config::WidgetEntry;
+use vibepanel_core::proxy::{ProxySettings, ProxyUrl};
+
+/// Default number of toggle cards per row.
+const DEFAULT_COLUMNS: usize = 2;
+
+/// Widest toggle grid that still fits the panel's fixed content width.
+const MAX_COLUMNS: usize = 4;
+
+/// A card in the Quick Settings panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickSettingsCard {
+    Wifi,
+    Bluetooth,
+    Vpn,
+    IdleInhibitor,
+    PowerProfile,
+    Proxy,
+    Touchpad,
+    Updates,
+    Power,
+    Audio,
+    Mic,
+    Brightness,
+}
+
+impl QuickSettingsCard {
+    /// All cards in their default display order.
+    pub const ALL: [Self; 12] = [
+        Self::Wifi,
+        Self::Bluetooth,
+        Self::Vpn,
+        Self::IdleInhibitor,
+        Self::PowerProfile,
+        Self::Proxy,
+        Self::Touchpad,
+        Self::Updates,
+        Self::Power,
+        Self::Audio,
+        Self::Mic,
+        Self::Brightness,
+    ];
+
+    /// Config name of the card (also its per-card enable option).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wifi => "wifi",
+            Self::Bluetooth => "bluetooth",
+            Self::Vpn => "vpn",
+            Self::IdleInhibitor => "idle_inhibitor",
+            Self::PowerProfile => "power_profile",
+            Self::Proxy => "proxy",
+            Self::Touchpad => "touchpad",
+            Self::Updates => "updates",
+            Self::Power => "power",
+            Self::Audio => "audio",
+            Self::Mic => "mic",
+            Self::Brightness => "brightness",
+        }
+    }
+
+    /// Parse a config name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|card| card.name() == name)
+    }
+
+    /// Whether the card sits in the toggle grid (as opposed to the slider
+    /// sections below it).
+    pub fn is_toggle(self) -> bool {
+        !matches!(self, Self::Audio | Self::Mic | Self::Brightness)
+    }
+}
 
 /// Configuration for which cards are shown in Quick Settings.
 ///
@@ -38,12 +108,36 @@ use vibepanel_core::config::WidgetEntry;
 /// idle_inhibitor = false
 /// vpn_close_on_connect = true  # close panel when VPN connects successfully
 /// ```
+///
+/// The proxy card is only shown when a proxy is configured:
+///
+/// ```toml
+/// [widgets.quick_settings]
+/// proxy_url = "http://proxy.example.com:3128"
+/// proxy_ignore_hosts = ["localhost", "127.0.0.0/8", ".corp.example.com"]
+/// ```
+///
+/// The touchpad card is only shown when the compositor can toggle a
+/// touchpad (Hyprland or KDE Plasma).
+///
+/// Or list the cards to show, in display order. Toggle cards fill the grid
+/// in list order and sliders follow in list order. When `cards` is set the
+/// per-card booleans are ignored:
+///
+/// ```toml
+/// [widgets.quick_settings]
+/// cards = ["wifi", "bluetooth", "power", "audio", "brightness"]
+/// columns = 3  # toggle cards per row (1-4, default 2)
+/// ```
 #[derive(Debug, Clone)]
 pub struct QuickSettingsCardsConfig {
     pub wifi: bool,
     pub bluetooth: bool,
     pub vpn: bool,
     pub idle_inhibitor: bool,
+    pub power_profile: bool,
+    pub proxy: bool,
+    pub touchpad: bool,
     pub updates: bool,
     pub audio: bool,
     pub mic: bool,
@@ -52,6 +146,24 @@ pub struct QuickSettingsCardsConfig {
     /// Close the Quick Settings panel when a VPN connection succeeds.
     /// Defaults to `true`. Useful when VPN connections trigger password prompts.
     pub vpn_close_on_connect: bool,
+    /// Proxy switched on by the proxy card.
+    pub proxy_settings: Option<ProxySettings>,
+    /// Enabled cards in display order.
+    pub order: Vec<QuickSettingsCard>,
+    /// Toggle cards per grid row.
+    pub columns: usize,
+}
+
+impl QuickSettingsCardsConfig {
+    /// Enabled toggle-grid cards in display order.
+    pub fn toggle_cards(&self) -> impl Iterator<Item = QuickSettingsCard> + '_ {
+        self.order.iter().copied().filter(|card| card.is_toggle())
+    }
+
+    /// Enabled slider sections in display order.
+    pub fn slider_cards(&self) -> impl Iterator<Item = QuickSettingsCard> + '_ {
+        self.order.iter().copied().filter(|card| !card.is_toggle())
+    }
 }
 
 impl Default for QuickSettingsCardsConfig {
@@ -61,12 +173,18 @@ impl Default for QuickSettingsCardsConfig {
             bluetooth: true,
             vpn: true,
             idle_inhibitor: true,
+            power_profile: true,
+            proxy: false,
+            touchpad: true,
             updates: true,
             audio: true,
             mic: true,
             brightness: true,
             power: true,
             vpn_close_on_connect: true,
+            proxy_settings: None,
+            order: QuickSettingsCard::ALL.to_vec(),
+            columns: DEFAULT_COLUMNS,
         }
     }
 }
@@ -85,12 +203,19 @@ impl WidgetConfig for QuickSettingsConfig {
             "bluetooth",
             "vpn",
             "idle_inhibitor",
+            "power_profile",
+            "proxy",
+            "touchpad",
             "updates",
             "audio",
             "mic",
             "brightness",
             "power",
             "vpn_close_on_connect",
+            "proxy_url",
+            "proxy_ignore_hosts",
+            "cards",
+            "columns",
         ];
         warn_unknown_options("quick_settings", entry, known_options);
 
@@ -102,23 +227,101 @@ impl WidgetConfig for QuickSettingsConfig {
                 .unwrap_or(true) // default to true (shown)
         };
 
+        let order: Vec<QuickSettingsCard> = match entry.options.get("cards") {
+            Some(value) => parse_card_list(value),
+            None => QuickSettingsCard::ALL
+                .into_iter()
+                .filter(|card| get_bool(card.name()))
+                .collect(),
+        };
+
+        let columns = match entry.options.get("columns").and_then(|v| v.as_integer()) {
+            Some(columns) if (1..=MAX_COLUMNS as i64).contains(&columns) => columns as usize,
+            Some(columns) => {
+                warn!(
+                    "quick_settings: columns = {} is out of range (1-{}), using {}",
+                    columns, MAX_COLUMNS, DEFAULT_COLUMNS
+                );
+                DEFAULT_COLUMNS
+            }
+            None => DEFAULT_COLUMNS,
+        };
+
+        let proxy_settings = parse_proxy_settings(entry);
+
+        let enabled = |card: QuickSettingsCard| order.contains(&card);
         Self {
             cards: QuickSettingsCardsConfig {
-                wifi: get_bool("wifi"),
-                bluetooth: get_bool("bluetooth"),
-                vpn: get_bool("vpn"),
-                idle_inhibitor: get_bool("idle_inhibitor"),
-                updates: get_bool("updates"),
-                audio: get_bool("audio"),
-                mic: get_bool("mic"),
-                brightness: get_bool("brightness"),
-                power: get_bool("power"),
+                wifi: enabled(QuickSettingsCard::Wifi),
+                bluetooth: enabled(QuickSettingsCard::Bluetooth),
+                vpn: enabled(QuickSettingsCard::Vpn),
+                idle_inhibitor: enabled(QuickSettingsCard::IdleInhibitor),
+                power_profile: enabled(QuickSettingsCard::PowerProfile),
+                proxy: enabled(QuickSettingsCard::Proxy) && proxy_settings.is_some(),
+                touchpad: enabled(QuickSettingsCard::Touchpad),
+                updates: enabled(QuickSettingsCard::Updates),
+                audio: enabled(QuickSettingsCard::Audio),
+                mic: enabled(QuickSettingsCard::Mic),
+                brightness: enabled(QuickSettingsCard::Brightness),
+                power: enabled(QuickSettingsCard::Power),
                 vpn_close_on_connect: get_bool("vpn_close_on_connect"),
+                proxy_settings,
+                order,
+                columns,
             },
         }
     }
 }
 
+/// Parse the `cards` option, skipping unknown names and duplicates.
+fn parse_card_list(value: &toml::Value) -> Vec<QuickSettingsCard> {
+    let Some(values) = value.as_array() else {
+        warn!("quick_settings: `cards` should be a list of card names");
+        return QuickSettingsCard::ALL.to_vec();
+    };
+
+    let mut order = Vec::new();
+    for name in values.iter().filter_map(|v| v.as_str()) {
+        match QuickSettingsCard::from_name(name) {
+            Some(card) if !order.contains(&card) => order.push(card),
+            Some(_) => warn!("quick_settings: card '{}' is listed twice", name),
+            None => warn!("quick_settings: unknown card '{}' in `cards`", name),
+        }
+    }
+    order
+}
+
+/// Parse `proxy_url` and `proxy_ignore_hosts`. Returns `None` (hiding the
+/// proxy card) when no valid proxy is configured.
+fn parse_proxy_settings(entry: &WidgetEntry) -> Option<ProxySettings> {
+    let url = entry.options.get("proxy_url")?.as_str().or_else(|| {
+        warn!("quick_settings: `proxy_url` should be a string");
+        None
+    })?;
+    let url = match ProxyUrl::parse(url) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("quick_settings: {}", e);
+            return None;
+        }
+    };
+
+    let ignore_hosts = entry
+        .options
+        .get("proxy_ignore_hosts")
+        .and_then(|v| v.as_array())
+        .map(|hosts| {
+            hosts
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(ProxySettings { url, ignore_hosts })
+}
+
 /// Bar-side Quick Settings indicator.
 pub struct QuickSettingsWidget {
     base: BaseWidget,
@@ -382,6 +585,17 @@ impl QuickSettingsWidget {
         // Ensure the root box is clickable.
         base.widget().add_css_class(state::CLICKABLE);
 
+        // Same from the keyboard (bar focus mode)
+        {
+            let root = base.widget().downgrade();
+            let qs_window = qs_window.clone();
+            base.set_default_action(move || {
+                if let Some(root) = root.upgrade() {
+                    qs_window.toggle_from(&root);
+                }
+            });
+        }
+
         // Gesture to toggle the Quick Settings window when clicked.
         let gesture = GestureClick::new();
         gesture.set_button(BUTTON_PRIMARY);
@@ -389,7 +603,6 @@ impl QuickSettingsWidget {
         gesture.set_propagation_phase(gtk4::PropagationPhase::Capture);
 
         {
-            let qs_window_handle = qs_window.clone();
             let root = base.widget().clone();
             // Use connect_released for immediate response without double-click delay
             gesture.connect_released(move |gesture, _n_press, _x, _y| {
@@ -406,30 +619,7 @@ impl QuickSettingsWidget {
                 // Claim the gesture sequence to prevent BaseWidget's handler from firing
                 gesture.set_state(gtk4::EventSequenceState::Claimed);
 
-                if let Some(native) = root.native() {
-                    let surface = native.surface();
-                    let monitor = surface.as_ref().map(|s| {
-                        let display = s.display();
-                        display.monitor_at_surface(s)
-                    });
-
-                    // Compute widget bounds relative to the native window
-                    if let Some(bounds) = root.compute_bounds(&native) {
-                        // Widget bounds are relative to the bar window's (0,0).
-                        // Only anchor_x is used for horizontal positioning of QS window.
-                        let screen_margin = ConfigManager::global().screen_margin() as i32;
-                        let widget_center_x =
-                            (bounds.x() + bounds.width() / 2.0) as i32 + screen_margin;
-
-                        let monitor = monitor.flatten();
-                        qs_window_handle.toggle_at(widget_center_x, monitor);
-                    } else {
-                        // Fallback: toggle without positioning
-                        qs_window_handle.toggle_at(0, None);
-                    }
-                } else {
-                    qs_window_handle.toggle_at(0, None);
-                }
+                qs_window.toggle_from(&root);
             });
         }
 
@@ -443,3 +633,128 @@ impl QuickSettingsWidget {
         self.base.widget()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use crate::widgets::test_entry;
+    use gtk4::Application;
+    use std::collections::HashMap;
+
+    fn card_list(names: &[&str]) -> toml::Value {
+        toml::Value::Array(
+            names
+                .iter()
+                .map(|name| toml::Value::String(name.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_cards_default_from_booleans() {
+        let mut options = HashMap::new();
+        options.insert("vpn".to_string(), toml::Value::Boolean(false));
+        let cards = QuickSettingsConfig::from_entry(&test_entry("quick_settings", options)).cards;
+
+        assert!(!cards.vpn);
+        assert!(cards.wifi);
+        assert_eq!(cards.columns, DEFAULT_COLUMNS);
+        assert_eq!(cards.order.len(), QuickSettingsCard::ALL.len() - 1);
+        assert!(!cards.order.contains(&QuickSettingsCard::Vpn));
+    }
+
+    #[test]
+    fn test_cards_list_sets_order_and_enablement() {
+        let mut options = HashMap::new();
+        options.insert(
+            "cards".to_string(),
+            card_list(&["audio", "power", "bogus", "wifi", "power", "brightness"]),
+        );
+        // Ignored when `cards` is set
+        options.insert("bluetooth".to_string(), toml::Value::Boolean(true));
+        options.insert("columns".to_string(), toml::Value::Integer(3));
+        let cards = QuickSettingsConfig::from_entry(&test_entry("quick_settings", options)).cards;
+
+        assert_eq!(
+            cards.order,
+            vec![
+                QuickSettingsCard::Audio,
+                QuickSettingsCard::Power,
+                QuickSettingsCard::Wifi,
+                QuickSettingsCard::Brightness,
+            ]
+        );
+        assert!(!cards.bluetooth);
+        assert!(cards.power);
+        assert_eq!(cards.columns, 3);
+        assert_eq!(
+            cards.toggle_cards().collect::<Vec<_>>(),
+            vec![QuickSettingsCard::Power, QuickSettingsCard::Wifi]
+        );
+        assert_eq!(
+            cards.slider_cards().collect::<Vec<_>>(),
+            vec![QuickSettingsCard::Audio, QuickSettingsCard::Brightness]
+        );
+    }
+
+    #[test]
+    fn test_proxy_card_requires_proxy_url() {
+        let cards =
+            QuickSettingsConfig::from_entry(&test_entry("quick_settings", HashMap::new())).cards;
+        assert!(!cards.proxy);
+        assert!(cards.proxy_settings.is_none());
+
+        let mut options = HashMap::new();
+        options.insert(
+            "proxy_url".to_string(),
+            toml::Value::String("http://proxy.corp:3128".to_string()),
+        );
+        options.insert("proxy_ignore_hosts".to_string(), card_list(&["localhost"]));
+        let cards = QuickSettingsConfig::from_entry(&test_entry("quick_settings", options)).cards;
+        assert!(cards.proxy);
+        let settings = cards.proxy_settings.unwrap();
+        assert_eq!(settings.url.host_port(), "proxy.corp:3128");
+        assert_eq!(settings.ignore_hosts, vec!["localhost".to_string()]);
+    }
+
+    #[test]
+    fn test_columns_out_of_range_uses_default() {
+        let mut options = HashMap::new();
+        options.insert("columns".to_string(), toml::Value::Integer(0));
+        let cards = QuickSettingsConfig::from_entry(&test_entry("quick_settings", options)).cards;
+        assert_eq!(cards.columns, DEFAULT_COLUMNS);
+    }
+
+    #[test]
+    fn test_audio_indicator_renders_snapshots() {
+        test_harness::run(|| {
+            let cards = QuickSettingsCardsConfig {
+                wifi: false,
+                bluetooth: false,
+                vpn: false,
+                audio: true,
+                ..QuickSettingsCardsConfig::default()
+            };
+            let window =
+                QuickSettingsWindowHandle::new(Application::builder().build(), cards.clone());
+            let widget = QuickSettingsWidget::new(QuickSettingsConfig { cards }, window);
+            let service = AudioService::global();
+
+            service.publish_mock(AudioSnapshot {
+                available: true,
+                control_available: true,
+                volume: 40,
+                ..AudioSnapshot::default()
+            });
+            test_harness::assert_rendered(&test_harness::render(widget.widget()), &["icon"]);
+
+            // No sound server
+            service.publish_mock(AudioSnapshot::default());
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &["icon.service-unavailable"],
+            );
+        });
+    }
+}
@@ -4,6 +4,7 @@
 //! - Idle inhibitor state handling (simple toggle card, no expander)
 
 use std::cell::RefCell;
+use std::time::Duration;
 
 use gtk4::prelude::*;
 use gtk4::{Label, ToggleButton};
@@ -39,6 +40,27 @@ impl Default for IdleInhibitorCardState {
     }
 }
 
+/// Format the time left on a timed inhibitor, rounded up to whole minutes.
+pub fn format_remaining(remaining: Duration) -> String {
+    let minutes = remaining.as_secs().div_ceil(60);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Subtitle text for the card: off, on until toggled, or time left.
+pub fn idle_subtitle(snapshot: &IdleInhibitorSnapshot) -> String {
+    if !snapshot.active {
+        return "Disabled".to_string();
+    }
+    match snapshot.remaining() {
+        Some(remaining) => format!("{} left", format_remaining(remaining)),
+        None => "Enabled".to_string(),
+    }
+}
+
 /// Handle Idle Inhibitor state changes from IdleInhibitorService.
 pub fn on_idle_inhibitor_changed(state: &IdleInhibitorCardState, snapshot: &IdleInhibitorSnapshot) {
     // Update toggle state
@@ -56,12 +78,20 @@ pub fn on_idle_inhibitor_changed(state: &IdleInhibitorCardState, snapshot: &Idle
 
     // Update subtitle
     if let Some(label) = state.subtitle.borrow().as_ref() {
-        let subtitle = if snapshot.active {
-            "Enabled"
-        } else {
-            "Disabled"
-        };
-        label.set_label(subtitle);
+        label.set_label(&idle_subtitle(snapshot));
         set_subtitle_active(label, snapshot.active);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(1)), "1m");
+        assert_eq!(format_remaining(Duration::from_secs(29 * 60 + 30)), "30m");
+        assert_eq!(format_remaining(Duration::from_secs(3600)), "1h 00m");
+        assert_eq!(format_remaining(Duration::from_secs(95 * 60)), "1h 35m");
+    }
+}
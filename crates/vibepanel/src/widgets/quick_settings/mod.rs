@@ -6,12 +6,16 @@
 //! - `ui_helpers` - Shared UI builders (cards, rows, etc.)
 //! - `components` - Reusable component builders (SliderRowBuilder, etc.)
 //! - `wifi_card` - Wi-Fi panel logic and icon helpers
+//! - `wifi_share` - Wi-Fi sharing as a QR code
 //! - `bluetooth_card` - Bluetooth panel logic and icon helpers
 //! - `vpn_card` - VPN panel logic and icon helpers
 //! - `audio_card` - Audio panel logic (volume, sinks)
 //! - `mic_card` - Microphone panel logic (input volume, sources)
 //! - `brightness_card` - Brightness slider
 //! - `idle_inhibitor_card` - Idle inhibitor toggle
+//! - `power_profile_card` - Power profile selector (power-profiles-daemon)
+//! - `proxy_card` - System proxy toggle
+//! - `touchpad_card` - Touchpad toggle
 //! - `updates_card` - System updates panel
 //! - `power_card` - Power menu (shutdown, reboot, etc.)
 
@@ -23,10 +27,14 @@ pub mod components;
 pub mod idle_inhibitor_card;
 pub mod mic_card;
 pub mod power_card;
+pub mod power_profile_card;
+pub mod proxy_card;
+pub mod touchpad_card;
 pub mod ui_helpers;
 pub mod updates_card;
 pub mod vpn_card;
 pub mod wifi_card;
+pub mod wifi_share;
 pub mod window;
 
 pub use bar_widget::{QuickSettingsConfig, QuickSettingsWidget};
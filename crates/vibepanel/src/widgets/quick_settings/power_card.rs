@@ -6,16 +6,25 @@
 //! - Chevron: Click to expand/collapse action list
 //! - Action rows: Hold for [`HOLD_DURATION_MS`] to execute
 //!
+//! Shutdown, reboot and suspend first check logind for blocking inhibitor
+//! locks (e.g. a package manager mid-transaction). If any are held, a
+//! confirmation popover lists the blockers instead of acting immediately;
+//! proceeding from it asks systemd to ignore the inhibitors.
+//! Shutdown and reboot also list repositories with uncommitted or unpushed
+//! work when the git widget is configured.
+//!
 //! Two UI variants for prototyping:
 //! - Popover: Actions appear in a popover menu
 //! - Expander: Actions appear as ListRows in accordion
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::process::Command;
 use std::rc::Rc;
 use std::time::Duration;
 
 use gtk4::gdk::BUTTON_PRIMARY;
+use gtk4::gio;
 use gtk4::glib::{self, SourceId};
 use gtk4::prelude::*;
 use gtk4::{
@@ -24,10 +33,15 @@ use gtk4::{
 };
 use tracing::{debug, warn};
 
+use crate::services::actions::{ActionKind, Actions};
 use crate::services::compositor::CompositorManager;
+use crate::services::dbus_watchdog::{self, CallPolicy};
+use crate::services::git::GitService;
 use crate::services::icons::{IconHandle, IconsService};
+use crate::services::inhibitors::{InhibitTarget, InhibitorLock, query_blocking_inhibitors};
 use crate::styles::{button, card, color, qs, row};
 use crate::widgets::base::configure_popover;
+use crate::widgets::git::repo_summary;
 
 use super::components::{CardLabel, ToggleCard};
 use super::ui_helpers::{ExpandableCard, ExpandableCardBase, create_qs_list_box};
@@ -50,6 +64,8 @@ struct PowerAction {
     /// Command to execute (first element is program, rest are args).
     /// Empty slice means special handling (e.g., logout via compositor IPC).
     command: &'static [&'static str],
+    /// Inhibitor lock kind that can block this action, if any.
+    inhibit: Option<InhibitTarget>,
 }
 
 /// Available power actions.
@@ -59,24 +75,28 @@ const POWER_ACTIONS: &[PowerAction] = &[
         label: "Shutdown",
         icon: "system-shutdown-symbolic",
         command: &["systemctl", "poweroff"],
+        inhibit: Some(InhibitTarget::Shutdown),
     },
     PowerAction {
         id: "reboot",
         label: "Reboot",
         icon: "system-reboot-symbolic",
         command: &["systemctl", "reboot"],
+        inhibit: Some(InhibitTarget::Shutdown),
     },
     PowerAction {
         id: "suspend",
         label: "Suspend",
         icon: "system-suspend-symbolic",
         command: &["systemctl", "suspend"],
+        inhibit: Some(InhibitTarget::Sleep),
     },
     PowerAction {
         id: "lock",
         label: "Lock",
         icon: "system-lock-screen-symbolic",
         command: &["loginctl", "lock-session"],
+        inhibit: None,
     },
     PowerAction {
         id: "logout",
@@ -84,13 +104,22 @@ const POWER_ACTIONS: &[PowerAction] = &[
         icon: "system-log-out-symbolic",
         // Empty command - handled specially via compositor IPC
         command: &[],
+        inhibit: None,
     },
 ];
 
 /// Execute a power action command.
-fn execute_power_action(action: &PowerAction) {
+///
+/// With `ignore_inhibitors`, systemctl is told to skip logind's inhibitor
+/// check; otherwise a held `block` lock makes logind refuse the request.
+fn execute_power_action(action: &PowerAction, ignore_inhibitors: bool) {
+    let actions = Actions::global();
+
     // Special handling for logout - use compositor IPC
     if action.id == "logout" {
+        if actions.permit(ActionKind::Power, action.id).is_err() {
+            return;
+        }
         debug!("Executing logout via compositor IPC");
         CompositorManager::global().quit_compositor();
         return;
@@ -103,15 +132,220 @@ fn execute_power_action(action: &PowerAction) {
 
     debug!("Executing power action {}: {:?}", action.id, action.command);
 
-    match Command::new(action.command[0])
-        .args(&action.command[1..])
-        .spawn()
-    {
+    let mut command = Command::new(action.command[0]);
+    command.args(&action.command[1..]);
+    if ignore_inhibitors && action.command[0] == "systemctl" {
+        command.arg("--check-inhibitors=no");
+    }
+    match actions.spawn(ActionKind::Power, &mut command) {
         Ok(_) => debug!("Power action {} spawned successfully", action.id),
         Err(e) => warn!("Failed to execute power action {}: {}", action.id, e),
     }
 }
 
+/// Run a power action, checking for blocking inhibitor locks first.
+///
+/// If logind reports `block` locks for the action, or (for shutdown and
+/// reboot) a watched git repository has pending work, a confirmation
+/// popover anchored to `anchor` lists them and lets the user proceed anyway.
+fn request_power_action(action: &'static PowerAction, anchor: &gtk4::Widget) {
+    let Some(target) = action.inhibit else {
+        execute_power_action(action, false);
+        return;
+    };
+
+    let anchor_weak = anchor.downgrade();
+    query_blocking_inhibitors(target, move |blockers| {
+        let pending_work = if target == InhibitTarget::Shutdown {
+            pending_git_work()
+        } else {
+            Vec::new()
+        };
+        if blockers.is_empty() && pending_work.is_empty() {
+            execute_power_action(action, false);
+            return;
+        }
+
+        match anchor_weak.upgrade() {
+            Some(anchor) => show_power_confirmation(&anchor, action, &blockers, &pending_work),
+            None => send_blocked_notification(action, &blockers, &pending_work),
+        }
+    });
+}
+
+/// Tell the user `action` was not run when there is no widget left to anchor
+/// the confirmation popover to (e.g. the bar was rebuilt while logind answered).
+fn send_blocked_notification(
+    action: &PowerAction,
+    blockers: &[InhibitorLock],
+    pending_work: &[String],
+) {
+    warn!(
+        "Power action {} blocked ({} inhibitors, {} repositories with pending work)",
+        action.id,
+        blockers.len(),
+        pending_work.len()
+    );
+
+    let summary = format!("{} was not performed", action.label);
+    let body = blockers
+        .iter()
+        .map(|lock| lock.who.clone())
+        .chain(pending_work.iter().cloned())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let params = (
+        "vibepanel",
+        0u32,
+        "dialog-warning-symbolic",
+        summary.as_str(),
+        body.as_str(),
+        Vec::<String>::new(),
+        HashMap::<String, glib::Variant>::new(),
+        -1i32,
+    )
+        .to_variant();
+
+    gio::bus_get(
+        gio::BusType::Session,
+        None::<&gio::Cancellable>,
+        move |res| {
+            let connection = match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("Power card: failed to connect to session bus: {}", e);
+                    return;
+                }
+            };
+
+            dbus_watchdog::call(
+                "notifications",
+                &connection,
+                "org.freedesktop.Notifications",
+                "/org/freedesktop/Notifications",
+                "org.freedesktop.Notifications",
+                "Notify",
+                Some(&params),
+                None,
+                CallPolicy::ACTION,
+                |res| {
+                    if let Err(e) = res {
+                        debug!("Power card: failed to send notification: {}", e);
+                    }
+                },
+            );
+        },
+    );
+}
+
+/// Describe watched git repositories with uncommitted or unpushed work.
+fn pending_git_work() -> Vec<String> {
+    GitService::global()
+        .snapshot()
+        .attention_repos()
+        .map(|repo| format!("{} – {}", repo.name, repo_summary(repo)))
+        .collect()
+}
+
+/// Add a wrapped, muted label for one confirmation entry.
+fn append_confirmation_line(content: &GtkBox, text: &str) {
+    let label = Label::new(Some(text));
+    label.set_xalign(0.0);
+    label.set_wrap(true);
+    label.set_max_width_chars(40);
+    label.add_css_class(color::MUTED);
+    content.append(&label);
+}
+
+/// Show a popover listing the inhibitor locks and pending work blocking `action`.
+fn show_power_confirmation(
+    parent: &gtk4::Widget,
+    action: &'static PowerAction,
+    blockers: &[InhibitorLock],
+    pending_work: &[String],
+) {
+    let popover = Popover::new();
+    configure_popover(&popover);
+
+    let content = GtkBox::new(Orientation::Vertical, 4);
+    content.add_css_class(qs::ROW_MENU_CONTENT);
+    content.add_css_class(qs::POWER_INHIBITORS);
+    content.set_margin_top(8);
+    content.set_margin_bottom(8);
+    content.set_margin_start(8);
+    content.set_margin_end(8);
+
+    if !blockers.is_empty() {
+        let title = Label::new(Some(&format!("{} is blocked by:", action.label)));
+        title.set_xalign(0.0);
+        title.add_css_class(color::PRIMARY);
+        content.append(&title);
+
+        for lock in blockers {
+            let text = if lock.why.is_empty() {
+                lock.who.clone()
+            } else {
+                format!("{} – {}", lock.who, lock.why)
+            };
+            append_confirmation_line(&content, &text);
+        }
+    }
+
+    if !pending_work.is_empty() {
+        let title = Label::new(Some("Uncommitted or unpushed work:"));
+        title.set_xalign(0.0);
+        title.add_css_class(color::PRIMARY);
+        content.append(&title);
+
+        for text in pending_work {
+            append_confirmation_line(&content, text);
+        }
+    }
+
+    let buttons = GtkBox::new(Orientation::Horizontal, 6);
+    buttons.set_halign(Align::End);
+    buttons.set_margin_top(4);
+
+    let cancel = Button::with_label("Cancel");
+    cancel.add_css_class(button::GHOST);
+    {
+        let popover_weak = popover.downgrade();
+        cancel.connect_clicked(move |_| {
+            if let Some(popover) = popover_weak.upgrade() {
+                popover.popdown();
+            }
+        });
+    }
+    buttons.append(&cancel);
+
+    let proceed = Button::with_label(&format!("{} anyway", action.label));
+    proceed.add_css_class(button::ACCENT);
+    {
+        let popover_weak = popover.downgrade();
+        // Pending git work alone does not involve logind; only skip its
+        // check when there were inhibitors to override.
+        let ignore_inhibitors = !blockers.is_empty();
+        proceed.connect_clicked(move |_| {
+            if let Some(popover) = popover_weak.upgrade() {
+                popover.popdown();
+            }
+            execute_power_action(action, ignore_inhibitors);
+        });
+    }
+    buttons.append(&proceed);
+
+    content.append(&buttons);
+
+    popover.set_child(Some(&content));
+    popover.set_parent(parent);
+    popover.popup();
+
+    // Unparent popover when closed
+    popover.connect_closed(|p| {
+        p.unparent();
+    });
+}
+
 /// State for managing hold-to-confirm gesture.
 struct HoldToConfirmState {
     /// Timer ID for the animation completion callback.
@@ -532,8 +766,11 @@ fn create_power_popover_action(action: &'static PowerAction) -> Overlay {
     overlay.add_overlay(&btn);
 
     // Set up hold-to-confirm
+    let btn_weak = btn.downgrade();
     setup_hold_to_confirm(&btn, &btn, &progress, move || {
-        execute_power_action(action);
+        if let Some(btn) = btn_weak.upgrade() {
+            request_power_action(action, btn.upcast_ref());
+        }
     });
 
     overlay
@@ -601,8 +838,11 @@ pub fn build_power_card_expander() -> (GtkBox, Revealer, Rc<PowerCardExpanderSta
     // Gesture on toggle button, but width calculated from card_overlay (full card width)
     {
         let shutdown_action = &POWER_ACTIONS[0]; // Shutdown action (index 0)
+        let overlay_weak = card_overlay.downgrade();
         setup_hold_to_confirm(&card.toggle, &card_overlay, &progress, move || {
-            execute_power_action(shutdown_action);
+            if let Some(overlay) = overlay_weak.upgrade() {
+                request_power_action(shutdown_action, overlay.upcast_ref());
+            }
         });
     }
 
@@ -692,8 +932,11 @@ fn build_power_action_row(action: &'static PowerAction) -> ListBoxRow {
     list_row.set_child(Some(&overlay));
 
     // Set up hold-to-confirm on the row
+    let row_weak = list_row.downgrade();
     setup_hold_to_confirm(&list_row, &list_row, &progress, move || {
-        execute_power_action(action);
+        if let Some(list_row) = row_weak.upgrade() {
+            request_power_action(action, list_row.upcast_ref());
+        }
     });
 
     list_row
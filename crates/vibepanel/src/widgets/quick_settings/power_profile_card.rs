@@ -0,0 +1,233 @@
+//! Power profile card for Quick Settings panel.
+//!
+//! This module contains:
+//! - Power profile card state
+//! - Card building with expandable profile list
+//! - Profile selection (row activation) and cycling (toggle click)
+//! - State change handling
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gtk4::prelude::*;
+use gtk4::{
+    Align, Box as GtkBox, Button, ListBox, ListBoxRow, Orientation, Overlay, Revealer,
+    RevealerTransitionType,
+};
+
+use super::components::{ListRow, ToggleCard};
+use super::ui_helpers::{
+    ExpandableCard, ExpandableCardBase, add_placeholder_row, clear_list_box, create_qs_list_box,
+    set_icon_active, set_subtitle_active,
+};
+use crate::services::icons::IconsService;
+use crate::services::power_profile::{
+    PowerProfileService, PowerProfileSnapshot, profile_icon, profile_label,
+};
+use crate::services::surfaces::SurfaceStyleManager;
+use crate::styles::{icon, qs, row};
+
+/// State for the Power Profile card in the Quick Settings panel.
+pub struct PowerProfileCardState {
+    pub base: ExpandableCardBase,
+    /// Set while the toggle is updated programmatically (prevents cycling).
+    pub updating_toggle: Cell<bool>,
+}
+
+impl PowerProfileCardState {
+    pub fn new() -> Self {
+        Self {
+            base: ExpandableCardBase::new(),
+            updating_toggle: Cell::new(false),
+        }
+    }
+}
+
+impl Default for PowerProfileCardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExpandableCard for PowerProfileCardState {
+    fn base(&self) -> &ExpandableCardBase {
+        &self.base
+    }
+}
+
+/// Whether a snapshot should highlight the card (any profile but balanced).
+fn is_highlighted(snapshot: &PowerProfileSnapshot) -> bool {
+    snapshot
+        .current_profile
+        .as_deref()
+        .is_some_and(|profile| profile != "balanced")
+}
+
+/// Subtitle text for the card.
+fn card_subtitle(snapshot: &PowerProfileSnapshot) -> String {
+    match snapshot.current_profile.as_deref() {
+        Some(profile) if snapshot.available => profile_label(profile),
+        _ => "Unavailable".to_string(),
+    }
+}
+
+/// Build the Power Profile card and revealer for the Quick Settings panel.
+///
+/// Clicking the card cycles to the next profile; the expander lists all
+/// profiles. Returns `(card, revealer, expander_button)` - caller is
+/// responsible for accordion registration via `AccordionManager::setup_expander`.
+pub fn build_power_profile_card(
+    state: &Rc<PowerProfileCardState>,
+) -> (GtkBox, Revealer, Option<Button>) {
+    let snapshot = PowerProfileService::global().snapshot();
+    let highlighted = is_highlighted(&snapshot);
+
+    let card = ToggleCard::builder()
+        .icon(profile_icon(
+            snapshot.current_profile.as_deref().unwrap_or_default(),
+        ))
+        .label("Power Profile")
+        .subtitle(&card_subtitle(&snapshot))
+        .active(highlighted)
+        .sensitive(snapshot.available)
+        .icon_active(highlighted)
+        .with_expander(true)
+        .build();
+
+    // Add card identifier for CSS targeting
+    card.card.add_css_class(qs::POWER_PROFILE);
+
+    // Store references
+    *state.base.toggle.borrow_mut() = Some(card.toggle.clone());
+    *state.base.card_icon.borrow_mut() = Some(card.icon_handle.clone());
+    *state.base.subtitle.borrow_mut() = card.subtitle.clone();
+    *state.base.arrow.borrow_mut() = card.expander_icon.clone();
+
+    {
+        let state = Rc::clone(state);
+        card.toggle.connect_toggled(move |_| {
+            if state.updating_toggle.get() {
+                return;
+            }
+            let service = PowerProfileService::global();
+            let snapshot = service.snapshot();
+            if let Some(next) = snapshot.next_profile() {
+                service.set_profile(next);
+            }
+        });
+    }
+
+    let revealer = Revealer::new();
+    revealer.set_reveal_child(false);
+    revealer.set_transition_type(RevealerTransitionType::SlideDown);
+
+    let list_box = create_qs_list_box();
+    list_box.add_css_class(qs::POWER_PROFILE_LIST);
+    list_box.connect_row_activated(|_, row| {
+        let service = PowerProfileService::global();
+        let snapshot = service.snapshot();
+        if let Some(profile) = usize::try_from(row.index())
+            .ok()
+            .and_then(|index| snapshot.available_profiles.get(index))
+        {
+            service.set_profile(profile);
+        }
+    });
+    populate_profile_list(&list_box, &snapshot);
+    revealer.set_child(Some(&list_box));
+
+    *state.base.list_box.borrow_mut() = Some(list_box);
+    *state.base.revealer.borrow_mut() = Some(revealer.clone());
+
+    (card.card, revealer, card.expander_button)
+}
+
+/// Handle power profile changes from PowerProfileService.
+pub fn on_power_profile_changed(state: &PowerProfileCardState, snapshot: &PowerProfileSnapshot) {
+    let highlighted = is_highlighted(snapshot);
+
+    if let Some(toggle) = state.base.toggle.borrow().as_ref() {
+        state.updating_toggle.set(true);
+        toggle.set_active(highlighted);
+        toggle.set_sensitive(snapshot.available);
+        state.updating_toggle.set(false);
+    }
+
+    if let Some(icon_handle) = state.base.card_icon.borrow().as_ref() {
+        icon_handle.set_icon(profile_icon(
+            snapshot.current_profile.as_deref().unwrap_or_default(),
+        ));
+        set_icon_active(icon_handle, highlighted);
+    }
+
+    if let Some(label) = state.base.subtitle.borrow().as_ref() {
+        label.set_label(&card_subtitle(snapshot));
+        set_subtitle_active(label, highlighted);
+    }
+
+    if let Some(list_box) = state.base.list_box.borrow().as_ref() {
+        populate_profile_list(list_box, snapshot);
+        // Apply Pango font attrs to dynamically created list rows
+        SurfaceStyleManager::global().apply_pango_attrs_all(list_box);
+    }
+}
+
+/// Populate the profile list, one row per available profile.
+///
+/// Rows are appended in `available_profiles` order so the row index maps
+/// back to the profile on activation.
+fn populate_profile_list(list_box: &ListBox, snapshot: &PowerProfileSnapshot) {
+    clear_list_box(list_box);
+
+    if !snapshot.available || snapshot.available_profiles.is_empty() {
+        add_placeholder_row(list_box, "Power profiles not available");
+        return;
+    }
+
+    let current = snapshot.current_profile.as_deref();
+    for profile in &snapshot.available_profiles {
+        let row = create_profile_row(profile, Some(profile.as_str()) == current);
+        list_box.append(&row);
+    }
+}
+
+/// Create a profile row with its icon and a selection indicator.
+fn create_profile_row(profile: &str, selected: bool) -> ListBoxRow {
+    let icons = IconsService::global();
+    let leading = icons.create_icon(profile_icon(profile), &[icon::TEXT, row::QS_ICON]);
+
+    let result = ListRow::builder()
+        .title(&profile_label(profile))
+        .leading_widget(leading.widget())
+        .trailing_widget(selection_indicator(selected))
+        .css_class(qs::POWER_PROFILE_ROW)
+        .build();
+    result.row
+}
+
+/// Checkmark for the selected profile, an empty radio circle otherwise.
+fn selection_indicator(selected: bool) -> gtk4::Widget {
+    if selected {
+        // Overlay: background box + checkmark icon floating on top
+        let overlay = Overlay::new();
+        overlay.set_valign(Align::Center);
+
+        let bg = GtkBox::new(Orientation::Horizontal, 0);
+        bg.add_css_class(row::QS_INDICATOR_BG);
+        overlay.set_child(Some(&bg));
+
+        let icons = IconsService::global();
+        let indicator = icons.create_icon("object-select-symbolic", &[row::QS_INDICATOR]);
+        indicator.widget().set_halign(Align::Center);
+        indicator.widget().set_valign(Align::Center);
+        overlay.add_overlay(&indicator.widget());
+
+        overlay.upcast()
+    } else {
+        // CSS-styled box for unselected (respects --radius-pill)
+        let indicator = GtkBox::new(Orientation::Horizontal, 0);
+        indicator.add_css_class(row::QS_RADIO_INDICATOR);
+        indicator.set_valign(Align::Center);
+        indicator.upcast()
+    }
+}
@@ -0,0 +1,70 @@
+//! Proxy card for Quick Settings panel.
+//!
+//! This module contains:
+//! - Proxy state handling (simple toggle card, no expander)
+
+use std::cell::RefCell;
+
+use gtk4::prelude::*;
+use gtk4::{Label, ToggleButton};
+
+use crate::services::icons::IconHandle;
+use crate::services::proxy::ProxySnapshot;
+
+use super::ui_helpers::{set_icon_active, set_subtitle_active};
+
+/// State for the Proxy card in the Quick Settings panel.
+pub struct ProxyCardState {
+    /// Proxy toggle button.
+    pub toggle: RefCell<Option<ToggleButton>>,
+    /// Proxy card icon handle.
+    pub card_icon: RefCell<Option<IconHandle>>,
+    /// Proxy subtitle label.
+    pub subtitle: RefCell<Option<Label>>,
+}
+
+impl ProxyCardState {
+    pub fn new() -> Self {
+        Self {
+            toggle: RefCell::new(None),
+            card_icon: RefCell::new(None),
+            subtitle: RefCell::new(None),
+        }
+    }
+}
+
+impl Default for ProxyCardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Subtitle for the proxy card: the active proxy host when on.
+pub fn proxy_subtitle(snapshot: &ProxySnapshot) -> String {
+    match (snapshot.enabled, &snapshot.host) {
+        (true, Some(host)) => host.clone(),
+        (true, None) => "Enabled".to_string(),
+        (false, _) => "Direct".to_string(),
+    }
+}
+
+/// Handle proxy state changes from ProxyService.
+pub fn on_proxy_changed(state: &ProxyCardState, snapshot: &ProxySnapshot) {
+    // Update toggle state
+    if let Some(toggle) = state.toggle.borrow().as_ref()
+        && toggle.is_active() != snapshot.enabled
+    {
+        toggle.set_active(snapshot.enabled);
+    }
+
+    // Update icon active state
+    if let Some(icon_handle) = state.card_icon.borrow().as_ref() {
+        set_icon_active(icon_handle, snapshot.enabled);
+    }
+
+    // Update subtitle
+    if let Some(label) = state.subtitle.borrow().as_ref() {
+        label.set_label(&proxy_subtitle(snapshot));
+        set_subtitle_active(label, snapshot.enabled);
+    }
+}
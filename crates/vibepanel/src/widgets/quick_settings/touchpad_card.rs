@@ -0,0 +1,70 @@
+//! Touchpad card for Quick Settings panel.
+//!
+//! This module contains:
+//! - Touchpad state handling (simple toggle card, no expander)
+
+use std::cell::RefCell;
+
+use gtk4::prelude::*;
+use gtk4::{Label, ToggleButton};
+
+use crate::services::icons::IconHandle;
+use crate::services::touchpad::TouchpadSnapshot;
+
+use super::ui_helpers::{set_icon_active, set_subtitle_active};
+
+/// State for the Touchpad card in the Quick Settings panel.
+pub struct TouchpadCardState {
+    /// Touchpad toggle button.
+    pub toggle: RefCell<Option<ToggleButton>>,
+    /// Touchpad card icon handle.
+    pub card_icon: RefCell<Option<IconHandle>>,
+    /// Touchpad subtitle label.
+    pub subtitle: RefCell<Option<Label>>,
+}
+
+impl TouchpadCardState {
+    pub fn new() -> Self {
+        Self {
+            toggle: RefCell::new(None),
+            card_icon: RefCell::new(None),
+            subtitle: RefCell::new(None),
+        }
+    }
+}
+
+impl Default for TouchpadCardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Subtitle for the touchpad card.
+pub fn touchpad_subtitle(snapshot: &TouchpadSnapshot) -> &'static str {
+    if snapshot.enabled {
+        "Enabled"
+    } else {
+        "Disabled"
+    }
+}
+
+/// Handle touchpad state changes from TouchpadService.
+pub fn on_touchpad_changed(state: &TouchpadCardState, snapshot: &TouchpadSnapshot) {
+    // Update toggle state
+    if let Some(toggle) = state.toggle.borrow().as_ref()
+        && toggle.is_active() != snapshot.enabled
+    {
+        toggle.set_active(snapshot.enabled);
+    }
+
+    // Update icon active state
+    if let Some(icon_handle) = state.card_icon.borrow().as_ref() {
+        set_icon_active(icon_handle, snapshot.enabled);
+    }
+
+    // Update subtitle
+    if let Some(label) = state.subtitle.borrow().as_ref() {
+        label.set_label(touchpad_subtitle(snapshot));
+        set_subtitle_active(label, snapshot.enabled);
+    }
+}
@@ -5,6 +5,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::popover_tracker::PopoverTracker;
 use crate::services::icons::{IconHandle, IconsService};
 use crate::styles::{button, color, qs, row, state};
 use gtk4::prelude::*;
@@ -332,6 +333,36 @@ pub fn collapse_revealer_instant(revealer: &Revealer) {
     }
 }
 
+/// Restore and track a revealer's expanded state for the session.
+///
+/// Quick Settings is rebuilt on every open; this re-expands the section
+/// remembered under `key` instantly (no animation) and records every later
+/// change, including accordion collapses.
+///
+/// Returns `true` if the section was restored expanded.
+pub fn remember_expanded(
+    key: &'static str,
+    revealer: &Revealer,
+    arrow: Option<&IconHandle>,
+) -> bool {
+    let expanded = PopoverTracker::global().is_expanded(key);
+    if expanded {
+        let old_dur = revealer.transition_duration();
+        revealer.set_transition_duration(0);
+        revealer.set_reveal_child(true);
+        revealer.set_transition_duration(old_dur);
+        if let Some(arrow) = arrow {
+            arrow.widget().add_css_class(state::EXPANDED);
+        }
+    }
+
+    revealer.connect_reveal_child_notify(move |revealer| {
+        PopoverTracker::global().set_expanded(key, revealer.reveals_child());
+    });
+
+    expanded
+}
+
 /// Clear all children from a ListBox.
 pub fn clear_list_box(list_box: &ListBox) {
     while let Some(child) = list_box.first_child() {
@@ -5,13 +5,16 @@
 //! - Card building with expandable details
 //! - Update list population
 //! - Refresh and upgrade button handlers
+//! - Flatpak "update all" action with progress
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use gtk4::pango::{EllipsizeMode, WrapMode};
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Button, Label, Orientation, PolicyType, Revealer, ScrolledWindow};
+use gtk4::{
+    Box as GtkBox, Button, Label, Orientation, PolicyType, ProgressBar, Revealer, ScrolledWindow,
+};
 use tracing::debug;
 
 use super::components::ToggleCard;
@@ -22,7 +25,7 @@ use super::ui_helpers::{
 use super::window::current_quick_settings_window;
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::services::updates::{UpdatesService, UpdatesSnapshot};
-use crate::styles::{color, qs, row};
+use crate::styles::{button, color, qs, row};
 use crate::widgets::updates_common::{
     format_last_check, format_repo_summary, icon_for_state, spawn_upgrade_terminal,
 };
@@ -35,6 +38,14 @@ pub struct UpdatesCardState {
     pub refresh_button: RefCell<Option<Rc<ScanButton>>>,
     /// Last check label in the details.
     pub last_check_label: RefCell<Option<Label>>,
+    /// Container for the Flatpak update controls.
+    pub flatpak_box: RefCell<Option<GtkBox>>,
+    /// "Update all Flatpaks" button.
+    pub flatpak_button: RefCell<Option<Button>>,
+    /// Progress bar shown while Flatpaks are updating.
+    pub flatpak_progress: RefCell<Option<ProgressBar>>,
+    /// Status label for Flatpak update errors.
+    pub flatpak_status: RefCell<Option<Label>>,
 }
 
 impl UpdatesCardState {
@@ -44,6 +55,10 @@ impl UpdatesCardState {
             card_box: RefCell::new(None),
             refresh_button: RefCell::new(None),
             last_check_label: RefCell::new(None),
+            flatpak_box: RefCell::new(None),
+            flatpak_button: RefCell::new(None),
+            flatpak_progress: RefCell::new(None),
+            flatpak_status: RefCell::new(None),
         }
     }
 }
@@ -69,7 +84,7 @@ pub fn build_updates_card(state: &Rc<UpdatesCardState>) -> (GtkBox, Revealer, Op
 
     let subtitle_text = format_repo_summary(&snapshot);
     let icon_name = icon_for_state(&snapshot);
-    let has_updates = snapshot.update_count > 0;
+    let has_updates = snapshot.total_update_count() > 0;
 
     let card = ToggleCard::builder()
         .icon(icon_name)
@@ -176,9 +191,43 @@ pub fn build_updates_details(state: &Rc<UpdatesCardState>) -> UpdatesDetailsResu
 
     *state.base.list_box.borrow_mut() = Some(list_box.clone());
 
+    // Flatpak controls: applied in-place, separate from the system upgrade
+    let flatpak_box = GtkBox::new(Orientation::Vertical, 4);
+    flatpak_box.add_css_class(qs::UPDATES_FLATPAK);
+
+    let flatpak_button = Button::with_label("Update all Flatpaks");
+    flatpak_button.add_css_class(button::CARD);
+    flatpak_button.connect_clicked(|_| {
+        debug!("Updates: update all Flatpaks clicked");
+        UpdatesService::global().update_flatpaks();
+    });
+    flatpak_box.append(&flatpak_button);
+
+    let flatpak_progress = ProgressBar::new();
+    flatpak_progress.add_css_class(qs::UPDATES_FLATPAK_PROGRESS);
+    flatpak_progress.set_visible(false);
+    flatpak_box.append(&flatpak_progress);
+
+    let flatpak_status = Label::new(None);
+    flatpak_status.add_css_class(row::QS_SUBTITLE);
+    flatpak_status.add_css_class(color::MUTED);
+    flatpak_status.set_xalign(0.0);
+    flatpak_status.set_wrap(true);
+    flatpak_status.set_wrap_mode(WrapMode::WordChar);
+    flatpak_status.set_visible(false);
+    flatpak_box.append(&flatpak_status);
+
+    container.append(&flatpak_box);
+
+    *state.flatpak_box.borrow_mut() = Some(flatpak_box);
+    *state.flatpak_button.borrow_mut() = Some(flatpak_button);
+    *state.flatpak_progress.borrow_mut() = Some(flatpak_progress);
+    *state.flatpak_status.borrow_mut() = Some(flatpak_status);
+
     // Populate initial state
     let snapshot = UpdatesService::global().snapshot();
     populate_updates_list(state, &snapshot);
+    update_flatpak_ui(state, &snapshot);
 
     UpdatesDetailsResult { container }
 }
@@ -189,7 +238,7 @@ pub fn on_updates_changed(state: &UpdatesCardState, snapshot: &UpdatesSnapshot)
     if let Some(icon) = state.base.card_icon.borrow().as_ref() {
         let icon_name = icon_for_state(snapshot);
         icon.set_icon(icon_name);
-        set_icon_active(icon, snapshot.update_count > 0);
+        set_icon_active(icon, snapshot.total_update_count() > 0);
     }
 
     // Update subtitle
@@ -197,11 +246,11 @@ pub fn on_updates_changed(state: &UpdatesCardState, snapshot: &UpdatesSnapshot)
         let text = format_repo_summary(snapshot);
         subtitle.set_label(&text);
         subtitle.set_visible(!text.is_empty());
-        set_subtitle_active(subtitle, snapshot.update_count > 0);
+        set_subtitle_active(subtitle, snapshot.total_update_count() > 0);
     }
 
-    // Update toggle sensitivity
-    let is_actionable = snapshot.available && snapshot.update_count > 0;
+    // Update toggle sensitivity (the toggle runs the system upgrade only)
+    let is_actionable = snapshot.package_manager.is_some() && snapshot.update_count > 0;
     if let Some(toggle) = state.base.toggle.borrow().as_ref() {
         toggle.set_sensitive(is_actionable);
         toggle.set_active(false);
@@ -218,6 +267,9 @@ pub fn on_updates_changed(state: &UpdatesCardState, snapshot: &UpdatesSnapshot)
     // Update refresh button label and animation
     update_refresh_ui(state, snapshot);
 
+    // Update Flatpak controls
+    update_flatpak_ui(state, snapshot);
+
     // Update last check label
     if let Some(label) = state.last_check_label.borrow().as_ref() {
         let text = format!("Last check: {}", format_last_check(snapshot.last_check));
@@ -240,6 +292,43 @@ fn update_refresh_ui(state: &UpdatesCardState, snapshot: &UpdatesSnapshot) {
     }
 }
 
+/// Update the Flatpak button, progress bar and status from a snapshot.
+fn update_flatpak_ui(state: &UpdatesCardState, snapshot: &UpdatesSnapshot) {
+    let updating = snapshot.flatpak_progress.is_some();
+
+    if let Some(flatpak_box) = state.flatpak_box.borrow().as_ref() {
+        let show = snapshot.flatpak_available
+            && (!snapshot.flatpak_updates.is_empty()
+                || updating
+                || snapshot.flatpak_error.is_some());
+        flatpak_box.set_visible(show);
+    }
+
+    if let Some(button) = state.flatpak_button.borrow().as_ref() {
+        button.set_sensitive(!updating && !snapshot.flatpak_updates.is_empty());
+        button.set_label(if updating {
+            "Updating Flatpaks..."
+        } else {
+            "Update all Flatpaks"
+        });
+    }
+
+    if let Some(progress) = state.flatpak_progress.borrow().as_ref() {
+        progress.set_visible(updating);
+        progress.set_fraction(snapshot.flatpak_progress.unwrap_or(0.0));
+    }
+
+    if let Some(status) = state.flatpak_status.borrow().as_ref() {
+        match snapshot.flatpak_error.as_deref() {
+            Some(err) if !updating => {
+                status.set_label(&format!("Flatpak update failed: {}", err));
+                status.set_visible(true);
+            }
+            _ => status.set_visible(false),
+        }
+    }
+}
+
 /// Populate the updates list from a snapshot.
 fn populate_updates_list(state: &UpdatesCardState, snapshot: &UpdatesSnapshot) {
     let Some(list_box) = state.base.list_box.borrow().as_ref().cloned() else {
@@ -257,14 +346,14 @@ fn populate_updates_list(state: &UpdatesCardState, snapshot: &UpdatesSnapshot) {
     }
 
     // Handle checking state
-    if snapshot.checking && snapshot.update_count == 0 {
+    if snapshot.checking && snapshot.total_update_count() == 0 {
         let row = create_message_row("Checking for updates...");
         list_box.append(&row);
         return;
     }
 
     // Handle no updates
-    if snapshot.update_count == 0 {
+    if snapshot.total_update_count() == 0 {
         let row = create_message_row("System is up to date");
         list_box.append(&row);
         return;
@@ -284,6 +373,18 @@ fn populate_updates_list(state: &UpdatesCardState, snapshot: &UpdatesSnapshot) {
         let row = create_updates_row(&title, &pkg_list);
         list_box.append(&row);
     }
+
+    // Flatpaks are listed separately from system repos
+    if !snapshot.flatpak_updates.is_empty() {
+        let names: Vec<&str> = snapshot
+            .flatpak_updates
+            .iter()
+            .map(|u| u.name.as_str())
+            .collect();
+        let title = format!("Flatpak ({})", names.len());
+        let row = create_updates_row(&title, &names.join("\n"));
+        list_box.append(&row);
+    }
 }
 
 /// Create a simple message row.
@@ -23,9 +23,11 @@ use super::ui_helpers::{
     clear_list_box, create_qs_list_box, create_row_action_label, create_row_menu_action,
     create_row_menu_button, set_icon_active,
 };
+use super::wifi_share::share_connected_network;
 use super::window::current_quick_settings_window;
+use crate::popover_tracker::remember_scroll_position;
 use crate::services::icons::IconsService;
-use crate::services::network::{NetworkService, NetworkSnapshot, WifiNetwork};
+use crate::services::network::{NetworkService, NetworkSnapshot, WifiNetwork, WiredProfile};
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::styles::{button, color, icon, qs, row, state, surface};
 use crate::widgets::base::configure_popover;
@@ -223,6 +225,8 @@ pub struct WifiCardState {
     pub wifi_switch: RefCell<Option<Switch>>,
     /// Ethernet row container (shown above Wi-Fi controls when connected).
     pub ethernet_row: RefCell<Option<GtkBox>>,
+    /// What the Ethernet row currently shows.
+    pub ethernet_view: RefCell<Option<EthernetView>>,
 }
 
 impl WifiCardState {
@@ -246,6 +250,7 @@ impl WifiCardState {
             wifi_label: RefCell::new(None),
             wifi_switch: RefCell::new(None),
             ethernet_row: RefCell::new(None),
+            ethernet_view: RefCell::new(None),
         }
     }
 }
@@ -291,12 +296,15 @@ pub fn build_wifi_details(
     // Get current network state for initial values
     let snapshot = NetworkService::global().snapshot();
 
-    // Ethernet row (above Wi-Fi controls, shown only when connected)
-    let ethernet_row = build_ethernet_row(&snapshot);
+    // Ethernet row (above Wi-Fi controls, shown when connected or when
+    // there is a wired profile to switch to)
+    let ethernet_view = EthernetView::from_snapshot(&snapshot);
+    let ethernet_row = build_ethernet_row(&ethernet_view);
     container.append(&ethernet_row);
 
     // Store ethernet row reference for dynamic updates
     *state.ethernet_row.borrow_mut() = Some(ethernet_row);
+    *state.ethernet_view.borrow_mut() = Some(ethernet_view);
 
     // Wi-Fi switch row: "Wi-Fi" label + switch + scan button
     // The label+switch are only visible when ethernet device present, but scan button always visible
@@ -340,6 +348,7 @@ pub fn build_wifi_details(
     scroller.set_child(Some(&list_box));
     scroller.set_max_content_height(360);
     scroller.set_propagate_natural_height(true);
+    remember_scroll_position(&scroller, "qs.wifi.list");
 
     container.append(&scroller);
 
@@ -510,15 +519,57 @@ fn add_wifi_disabled_placeholder(list_box: &ListBox) {
     list_box.append(&row);
 }
 
+/// The parts of the network snapshot shown in the Ethernet section.
+///
+/// Compared on each update so the section is only rebuilt when it changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EthernetView {
+    connected: bool,
+    name: Option<String>,
+    iface: Option<String>,
+    speed: Option<u32>,
+    profiles: Vec<WiredProfile>,
+    activating: Option<String>,
+}
+
+impl EthernetView {
+    fn from_snapshot(snapshot: &NetworkSnapshot) -> Self {
+        Self {
+            connected: snapshot.wired_connected,
+            name: snapshot.wired_name.clone(),
+            iface: snapshot.wired_iface.clone(),
+            speed: snapshot.wired_speed,
+            profiles: snapshot.wired_profiles.clone(),
+            activating: snapshot.activating_wired_profile.clone(),
+        }
+    }
+
+    /// Profiles that can be switched to (all but the active one).
+    fn switchable_profiles(&self) -> impl Iterator<Item = &WiredProfile> {
+        self.profiles.iter().filter(|p| !p.active)
+    }
+
+    /// The section is shown while connected, or when there is a profile
+    /// to bring the link up with.
+    fn visible(&self) -> bool {
+        self.connected || self.switchable_profiles().next().is_some()
+    }
+}
+
 /// Build a standalone Ethernet section widget (not in a ListBox).
-/// Includes a header label and connection details row.
+/// Includes a header label, the connection details row and a row for each
+/// other wired profile that can be activated.
 /// Returns a GtkBox that can be shown/hidden based on connection state.
-fn build_ethernet_row(snapshot: &NetworkSnapshot) -> GtkBox {
-    let icons = IconsService::global();
-
+fn build_ethernet_row(view: &EthernetView) -> GtkBox {
     // Main container for the entire Ethernet section
     let container = GtkBox::new(Orientation::Vertical, 0);
     container.add_css_class(qs::ETHERNET_ROW_CONTAINER);
+    populate_ethernet_row(&container, view);
+    container
+}
+
+fn populate_ethernet_row(container: &GtkBox, view: &EthernetView) {
+    let icons = IconsService::global();
 
     // Header row with "Ethernet" label (matches Wi-Fi header style)
     let header_row = GtkBox::new(Orientation::Horizontal, 8);
@@ -532,77 +583,133 @@ fn build_ethernet_row(snapshot: &NetworkSnapshot) -> GtkBox {
 
     container.append(&header_row);
 
-    // Create ethernet icon with accent color (always connected when shown)
-    let icon_handle = icons.create_icon(
-        "network-wired-symbolic",
-        &[icon::TEXT, row::QS_ICON, color::ACCENT],
-    );
+    if view.connected {
+        // Create ethernet icon with accent color (connected)
+        let icon_handle = icons.create_icon(
+            "network-wired-symbolic",
+            &[icon::TEXT, row::QS_ICON, color::ACCENT],
+        );
+
+        // Get connection name for title, fallback to interface name, then generic
+        let title = view
+            .name
+            .as_deref()
+            .or(view.iface.as_deref())
+            .unwrap_or("Wired Connection");
 
-    // Get connection name for title, fallback to interface name, then generic
-    let title = snapshot
-        .wired_name
-        .as_deref()
-        .or(snapshot.wired_iface.as_deref())
-        .unwrap_or("Wired Connection");
-
-    // Build subtitle extra parts: interface name, speed
-    let mut extra_parts: Vec<String> = Vec::new();
-    if let Some(ref iface) = snapshot.wired_iface {
-        extra_parts.push(iface.clone());
-    }
-    if let Some(speed) = snapshot.wired_speed {
-        if speed >= 1000 {
-            let gbps = speed as f64 / 1000.0;
-            if gbps.fract() == 0.0 {
-                extra_parts.push(format!("{} Gbps", speed / 1000));
+        // Build subtitle extra parts: interface name, speed
+        let mut extra_parts: Vec<String> = Vec::new();
+        if let Some(ref iface) = view.iface {
+            extra_parts.push(iface.clone());
+        }
+        if let Some(speed) = view.speed {
+            if speed >= 1000 {
+                let gbps = speed as f64 / 1000.0;
+                if gbps.fract() == 0.0 {
+                    extra_parts.push(format!("{} Gbps", speed / 1000));
+                } else {
+                    extra_parts.push(format!("{:.1} Gbps", gbps));
+                }
             } else {
-                extra_parts.push(format!("{:.1} Gbps", gbps));
+                extra_parts.push(format!("{} Mbps", speed));
             }
-        } else {
-            extra_parts.push(format!("{} Mbps", speed));
         }
+
+        // Build connected subtitle widget with accent "Connected" and muted extra parts
+        let extra_refs: Vec<&str> = extra_parts.iter().map(|s| s.as_str()).collect();
+        let subtitle_widget = build_accent_subtitle("Connected", &extra_refs);
+
+        // Connection details row with connection name as title
+        let row_result = ListRow::builder()
+            .title(title)
+            .subtitle_widget(subtitle_widget.upcast())
+            .leading_widget(icon_handle.widget())
+            .css_class(qs::WIFI_ROW)
+            .build();
+
+        container.append(&ethernet_connection_row(&row_result.row));
     }
 
-    // Build connected subtitle widget with accent "Connected" and muted extra parts
-    let extra_refs: Vec<&str> = extra_parts.iter().map(|s| s.as_str()).collect();
-    let subtitle_widget = build_accent_subtitle("Connected", &extra_refs);
+    // Other wired profiles, one click to activate
+    for profile in view.switchable_profiles() {
+        let is_activating = view.activating.as_deref() == Some(profile.uuid.as_str());
+
+        let icon_handle = icons.create_icon(
+            "network-wired-symbolic",
+            &[icon::TEXT, row::QS_ICON, color::PRIMARY],
+        );
+
+        let trailing: gtk4::Widget = if is_activating {
+            let connecting_label = Label::new(Some("..."));
+            connecting_label.add_css_class(color::MUTED);
+            connecting_label.upcast()
+        } else {
+            let action_label = create_row_action_label("Connect");
+            action_label.set_sensitive(view.activating.is_none());
+            let uuid = profile.uuid.clone();
+            action_label.connect_clicked(move |_| {
+                debug!("wired_profile_activate uuid={}", uuid);
+                if let Err(e) = NetworkService::global().activate_wired_profile(&uuid) {
+                    debug!("wired_profile_activate refused: {}", e);
+                }
+            });
+            action_label.upcast()
+        };
+
+        let subtitle = if is_activating {
+            "Connecting..."
+        } else {
+            "Saved profile"
+        };
+
+        let row_result = ListRow::builder()
+            .title(&profile.name)
+            .subtitle(subtitle)
+            .leading_widget(icon_handle.widget())
+            .trailing_widget(trailing)
+            .css_class(qs::WIFI_ROW)
+            .build();
+
+        container.append(&ethernet_connection_row(&row_result.row));
+    }
 
-    // Connection details row with connection name as title
-    let row_result = ListRow::builder()
-        .title(title)
-        .subtitle_widget(subtitle_widget.upcast())
-        .leading_widget(icon_handle.widget())
-        .css_class(qs::WIFI_ROW)
-        .build();
+    container.set_visible(view.visible());
+}
 
+/// Move a list row's content into a standalone box styled like a list row,
+/// for use outside a ListBox.
+fn ethernet_connection_row(list_row: &ListBoxRow) -> GtkBox {
     // Connection row container with background styling
     let connection_row = GtkBox::new(Orientation::Vertical, 0);
     connection_row.add_css_class(row::QS);
     connection_row.add_css_class(qs::ETHERNET_CONNECTION_ROW);
 
     // Extract the row's child and put it in our container
-    if let Some(child) = row_result.row.child() {
-        row_result.row.set_child(None::<&gtk4::Widget>);
+    if let Some(child) = list_row.child() {
+        list_row.set_child(None::<&gtk4::Widget>);
         connection_row.append(&child);
     }
 
-    container.append(&connection_row);
-
-    // Initially visible only if wired is connected
-    container.set_visible(snapshot.wired_connected);
-
-    container
+    connection_row
 }
 
-/// Update the Ethernet row visibility and content based on connection state.
+/// Update the Ethernet section based on connection state and wired profiles.
+///
+/// The section is rebuilt only when what it shows has changed.
 pub fn update_ethernet_row(state: &WifiCardState, snapshot: &NetworkSnapshot) {
-    if let Some(ethernet_row) = state.ethernet_row.borrow().as_ref() {
-        ethernet_row.set_visible(snapshot.wired_connected);
+    let view = EthernetView::from_snapshot(snapshot);
+    if state.ethernet_view.borrow().as_ref() == Some(&view) {
+        return;
+    }
 
-        // If connected and row is visible, we might want to update the subtitle
-        // For now, the subtitle is static after creation. If we need dynamic updates,
-        // we'd need to store subtitle label reference and update it here.
+    if let Some(ethernet_row) = state.ethernet_row.borrow().as_ref() {
+        while let Some(child) = ethernet_row.first_child() {
+            ethernet_row.remove(&child);
+        }
+        populate_ethernet_row(ethernet_row, &view);
+        SurfaceStyleManager::global().apply_pango_attrs_all(ethernet_row);
     }
+    *state.ethernet_view.borrow_mut() = Some(view);
 }
 
 /// Populate the Wi-Fi list with network data from snapshot.
@@ -758,10 +865,15 @@ pub fn populate_wifi_list(state: &WifiCardState, list_box: &ListBox, snapshot: &
             let active = net.active;
             row_result.row.connect_activate(move |_| {
                 let service = NetworkService::global();
-                if active {
-                    service.disconnect();
+                let result = if active {
+                    service.disconnect()
                 } else if security == "open" || known {
-                    service.connect_to_ssid(&ssid, None);
+                    service.connect_to_ssid(&ssid, None)
+                } else {
+                    Ok(())
+                };
+                if let Err(e) = result {
+                    debug!("wifi_row_activate ssid={} refused: {}", ssid, e);
                 }
                 // Secured, unknown networks: handled by the "Connect" button gesture
             });
@@ -823,7 +935,9 @@ fn create_network_action_widget(net: &WifiNetwork) -> gtk4::Widget {
             } else {
                 // Open network: connect directly without password
                 let network = NetworkService::global();
-                network.connect_to_ssid(&ssid_clone, None);
+                if let Err(e) = network.connect_to_ssid(&ssid_clone, None) {
+                    debug!("wifi_connect ssid={} refused: {}", ssid_clone, e);
+                }
             }
         });
         return action_label.upcast();
@@ -857,7 +971,21 @@ fn create_network_action_widget(net: &WifiNetwork) -> gtk4::Widget {
                 }
                 let network = NetworkService::global();
                 debug!("wifi_disconnect_from_menu ssid={}", ssid_clone);
-                network.disconnect();
+                if let Err(e) = network.disconnect() {
+                    debug!("wifi_disconnect_from_menu refused: {}", e);
+                }
+            });
+            content_box.append(&action);
+
+            let popover_weak = popover.downgrade();
+            let anchor_weak = btn.downgrade();
+            let action = create_row_menu_action("Share network", move || {
+                if let Some(p) = popover_weak.upgrade() {
+                    p.popdown();
+                }
+                if let Some(anchor) = anchor_weak.upgrade() {
+                    share_connected_network(&anchor);
+                }
             });
             content_box.append(&action);
         } else {
@@ -871,7 +999,9 @@ fn create_network_action_widget(net: &WifiNetwork) -> gtk4::Widget {
                 let network = NetworkService::global();
                 debug!("wifi_connect_from_menu ssid={}", ssid_clone);
                 // Known networks connect without password prompt
-                network.connect_to_ssid(&ssid_clone, None);
+                if let Err(e) = network.connect_to_ssid(&ssid_clone, None) {
+                    debug!("wifi_connect_from_menu refused: {}", e);
+                }
             });
             content_box.append(&action);
         }
@@ -887,7 +1017,9 @@ fn create_network_action_widget(net: &WifiNetwork) -> gtk4::Widget {
                 }
                 let network = NetworkService::global();
                 debug!("wifi_forget_from_menu ssid={}", ssid_clone);
-                network.forget_network(&ssid_clone);
+                if let Err(e) = network.forget_network(&ssid_clone) {
+                    debug!("wifi_forget_from_menu refused: {}", e);
+                }
             });
             content_box.append(&action);
         }
@@ -1010,7 +1142,13 @@ fn on_password_connect_clicked(state: &WifiCardState, window: WeakRef<Applicatio
     set_password_connecting_state(state, true, Some(window));
 
     let service = NetworkService::global();
-    service.connect_to_ssid(&ssid, Some(&password));
+    if let Err(e) = service.connect_to_ssid(&ssid, Some(&password)) {
+        set_password_connecting_state(state, false, None);
+        if let Some(error_label) = state.password_error_label.borrow().as_ref() {
+            error_label.add_css_class(color::ERROR);
+            error_label.set_label(&e);
+        }
+    }
 }
 
 /// Set the password dialog to connecting/idle state.
@@ -1234,7 +1372,7 @@ pub fn on_network_changed(
     // Update Wi-Fi subtitle
     update_subtitle(state, snapshot);
 
-    // Update Ethernet row visibility
+    // Update Ethernet row visibility and wired profiles
     update_ethernet_row(state, snapshot);
 
     // Update scan button UI (label + animation)
@@ -1405,6 +1543,8 @@ mod tests {
             wired_iface: None,
             wired_name: None,
             wired_speed: None,
+            wired_profiles: Vec::new(),
+            activating_wired_profile: None,
             ssid: None,
             strength: 0,
             scanning: false,
@@ -1415,6 +1555,40 @@ mod tests {
         }
     }
 
+    fn wired_profile(name: &str, active: bool) -> WiredProfile {
+        WiredProfile {
+            name: name.to_string(),
+            uuid: format!("uuid-{}", name),
+            active,
+        }
+    }
+
+    #[test]
+    fn test_ethernet_view_visible_with_switchable_profile() {
+        let mut snapshot = test_snapshot();
+        assert!(!EthernetView::from_snapshot(&snapshot).visible());
+
+        // Only the active profile: nothing to switch to, hidden until connected
+        snapshot.wired_profiles = vec![wired_profile("Home", true)];
+        assert!(!EthernetView::from_snapshot(&snapshot).visible());
+
+        snapshot.wired_profiles.push(wired_profile("Lab", false));
+        let view = EthernetView::from_snapshot(&snapshot);
+        assert!(view.visible());
+        let names: Vec<&str> = view
+            .switchable_profiles()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["Lab"]);
+    }
+
+    #[test]
+    fn test_ethernet_view_visible_when_connected() {
+        let mut snapshot = test_snapshot();
+        snapshot.wired_connected = true;
+        assert!(EthernetView::from_snapshot(&snapshot).visible());
+    }
+
     // Tests for get_network_subtitle_text()
 
     #[test]
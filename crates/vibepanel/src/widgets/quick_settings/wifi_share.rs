@@ -0,0 +1,224 @@
+//! Wi-Fi sharing for the Quick Settings Wi-Fi card.
+//!
+//! The connected network's row menu offers "Share network", which reads the
+//! network's credentials from NetworkManager and shows them as a `WIFI:` QR
+//! code that phone cameras offer to join.
+
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, DrawingArea, Label, Orientation, Popover};
+use qrcodegen::{QrCode, QrCodeEcc};
+use tracing::{debug, warn};
+
+use crate::services::network::{NetworkService, WifiCredentials};
+use crate::services::surfaces::SurfaceStyleManager;
+use crate::styles::{color, qs, surface};
+use crate::widgets::base::configure_popover;
+
+/// Light border around the code, in modules; scanners need it to find the code.
+const QUIET_ZONE: i32 = 4;
+
+/// Width and height of the rendered code, in pixels.
+const CODE_SIZE: i32 = 200;
+
+/// Escape a field of a `WIFI:` payload.
+fn escape_field(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Authentication type of a `WIFI:` payload for a NetworkManager key management.
+fn auth_type(key_mgmt: Option<&str>) -> &'static str {
+    match key_mgmt {
+        Some("wpa-psk") | Some("sae") => "WPA",
+        Some("none") => "WEP",
+        _ => "nopass",
+    }
+}
+
+/// Build the `WIFI:` payload phones understand for joining a network.
+pub fn qr_payload(credentials: &WifiCredentials) -> String {
+    let auth = auth_type(credentials.key_mgmt.as_deref());
+    let mut payload = format!("WIFI:T:{};S:{};", auth, escape_field(&credentials.ssid));
+    if auth != "nopass"
+        && let Some(password) = &credentials.password
+    {
+        payload.push_str(&format!("P:{};", escape_field(password)));
+    }
+    if credentials.hidden {
+        payload.push_str("H:true;");
+    }
+    payload.push(';');
+    payload
+}
+
+/// Create a widget drawing `payload` as a QR code.
+fn build_qr_code(payload: &str) -> Option<DrawingArea> {
+    let code = match QrCode::encode_text(payload, QrCodeEcc::Medium) {
+        Ok(code) => code,
+        Err(e) => {
+            warn!("Failed to encode Wi-Fi QR code: {:?}", e);
+            return None;
+        }
+    };
+
+    let area = DrawingArea::new();
+    area.set_content_width(CODE_SIZE);
+    area.set_content_height(CODE_SIZE);
+    area.set_halign(gtk4::Align::Center);
+    area.add_css_class(qs::WIFI_SHARE_CODE);
+    area.set_draw_func(move |_, cr, width, height| {
+        let modules = code.size() + 2 * QUIET_ZONE;
+        let module = (width.min(height) / modules).max(1) as f64;
+        let offset_x = (width as f64 - module * modules as f64) / 2.0;
+        let offset_y = (height as f64 - module * modules as f64) / 2.0;
+
+        // Always dark on light, whatever the theme: inverted codes don't scan
+        // on every phone
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        cr.rectangle(
+            offset_x,
+            offset_y,
+            module * modules as f64,
+            module * modules as f64,
+        );
+        let _ = cr.fill();
+
+        cr.set_source_rgb(0.0, 0.0, 0.0);
+        for y in 0..code.size() {
+            for x in 0..code.size() {
+                if code.get_module(x, y) {
+                    cr.rectangle(
+                        offset_x + (x + QUIET_ZONE) as f64 * module,
+                        offset_y + (y + QUIET_ZONE) as f64 * module,
+                        module,
+                        module,
+                    );
+                }
+            }
+        }
+        let _ = cr.fill();
+    });
+
+    Some(area)
+}
+
+/// Build the popover content for a fetch result.
+fn build_share_content(result: &Result<WifiCredentials, String>) -> GtkBox {
+    let content = GtkBox::new(Orientation::Vertical, 8);
+    content.add_css_class(qs::ROW_MENU_CONTENT);
+    content.add_css_class(qs::WIFI_SHARE);
+
+    let message = match result {
+        Ok(credentials) => {
+            let ssid = Label::new(Some(&credentials.ssid));
+            ssid.add_css_class(color::PRIMARY);
+            content.append(&ssid);
+
+            match build_qr_code(&qr_payload(credentials)) {
+                Some(code) => {
+                    content.append(&code);
+                    "Scan with a phone camera to join".to_string()
+                }
+                None => "Couldn't create a QR code".to_string(),
+            }
+        }
+        Err(e) => format!("Couldn't read the network password: {}", e),
+    };
+
+    let hint = Label::new(Some(&message));
+    hint.set_wrap(true);
+    hint.set_max_width_chars(28);
+    hint.set_justify(gtk4::Justification::Center);
+    hint.add_css_class(color::MUTED);
+    content.append(&hint);
+
+    content
+}
+
+/// Fetch the connected network's credentials and show them as a QR code in
+/// a popover on `anchor`.
+///
+/// The fetch may wait on a polkit prompt; if the row was rebuilt in the
+/// meantime the result is dropped.
+pub fn share_connected_network(anchor: &Button) {
+    let anchor_weak = anchor.downgrade();
+    NetworkService::global().fetch_wifi_credentials(move |result| {
+        let Some(anchor) = anchor_weak.upgrade() else {
+            return;
+        };
+        if anchor.root().is_none() {
+            debug!("Wi-Fi row went away before the credentials arrived");
+            return;
+        }
+
+        let popover = Popover::new();
+        configure_popover(&popover);
+
+        let panel = GtkBox::new(Orientation::Vertical, 0);
+        panel.add_css_class(surface::WIDGET_MENU_CONTENT);
+        let content = build_share_content(&result);
+        panel.append(&content);
+
+        let style_mgr = SurfaceStyleManager::global();
+        style_mgr.apply_surface_styles(&panel, true);
+        style_mgr.apply_pango_attrs_all(&content);
+
+        popover.set_child(Some(&panel));
+        popover.set_parent(&anchor);
+        popover.popup();
+
+        // Unparent popover when closed to avoid "still has children" warning
+        // when the button is destroyed during list refresh
+        popover.connect_closed(|p| {
+            p.unparent();
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(ssid: &str, key_mgmt: Option<&str>, password: Option<&str>) -> WifiCredentials {
+        WifiCredentials {
+            ssid: ssid.to_string(),
+            key_mgmt: key_mgmt.map(str::to_string),
+            password: password.map(str::to_string),
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn test_qr_payload_wpa() {
+        let creds = credentials("Home", Some("wpa-psk"), Some("hunter22"));
+        assert_eq!(qr_payload(&creds), "WIFI:T:WPA;S:Home;P:hunter22;;");
+
+        let creds = credentials("Home", Some("sae"), Some("hunter22"));
+        assert_eq!(qr_payload(&creds), "WIFI:T:WPA;S:Home;P:hunter22;;");
+    }
+
+    #[test]
+    fn test_qr_payload_open_and_hidden() {
+        let creds = credentials("Cafe", None, None);
+        assert_eq!(qr_payload(&creds), "WIFI:T:nopass;S:Cafe;;");
+
+        let mut creds = credentials("Lab", Some("none"), Some("abcde"));
+        creds.hidden = true;
+        assert_eq!(qr_payload(&creds), "WIFI:T:WEP;S:Lab;P:abcde;H:true;;");
+    }
+
+    #[test]
+    fn test_qr_payload_escapes_special_characters() {
+        let creds = credentials("My;Net", Some("wpa-psk"), Some(r#"a:b,c\d"e"#));
+        assert_eq!(
+            qr_payload(&creds),
+            r#"WIFI:T:WPA;S:My\;Net;P:a\:b\,c\\d\"e;;"#
+        );
+    }
+}
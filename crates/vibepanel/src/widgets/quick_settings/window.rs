@@ -23,26 +23,35 @@ use crate::services::brightness::BrightnessService;
 use crate::services::config_manager::ConfigManager;
 use crate::services::idle_inhibitor::IdleInhibitorService;
 use crate::services::network::NetworkService;
+use crate::services::power_profile::PowerProfileService;
+use crate::services::proxy::ProxyService;
+use crate::services::screen_regions::{Anchor, Placement, ScreenRegions, Surface};
 use crate::services::surfaces::SurfaceStyleManager;
+use crate::services::touchpad::TouchpadService;
 use crate::services::updates::UpdatesService;
 use crate::services::vpn::VpnService;
 use crate::styles::{qs, state, surface};
 use crate::widgets::layer_shell_popover::{
-    Dismissible, calculate_bar_exclusive_zone, calculate_popover_right_margin,
-    calculate_popover_top_margin, create_click_catcher, popover_keyboard_mode, setup_esc_handler,
+    Dismissible, anchor_to_bar_edge, bar_edge, calculate_bar_exclusive_zone,
+    calculate_popover_bar_margin, calculate_popover_right_margin, create_click_catcher,
+    popover_keyboard_mode, set_shadow_margins, setup_esc_handler,
 };
+use vibepanel_core::proxy::ProxySettings;
 
 use super::audio_card::{
     self, AudioCardState, build_audio_details, build_audio_hint_label, build_audio_row,
 };
-use super::bar_widget::QuickSettingsCardsConfig;
+use super::bar_widget::{QuickSettingsCard, QuickSettingsCardsConfig};
 use super::bluetooth_card::{self, BluetoothCardState, bt_icon_name, build_bluetooth_details};
 use super::brightness_card::{self, BrightnessCardState, build_brightness_row};
 use super::components::ToggleCard;
 use super::idle_inhibitor_card::{self, IdleInhibitorCardState};
 use super::mic_card::{self, MicCardState, build_mic_details, build_mic_hint_label, build_mic_row};
 use super::power_card::{self, PowerCardBuildResult};
-use super::ui_helpers::{AccordionManager, ExpandableCard};
+use super::power_profile_card::{self, PowerProfileCardState, build_power_profile_card};
+use super::proxy_card::{self, ProxyCardState, proxy_subtitle};
+use super::touchpad_card::{self, TouchpadCardState, touchpad_subtitle};
+use super::ui_helpers::{AccordionManager, ExpandableCard, remember_expanded};
 use super::updates_card::{self, UpdatesCardState, build_updates_card};
 use super::vpn_card::{self, VpnCardState, build_vpn_details, vpn_icon_name};
 use super::wifi_card::{
@@ -106,6 +115,9 @@ pub struct QuickSettingsWindow {
     pub bluetooth: Rc<BluetoothCardState>,
     pub vpn: Rc<VpnCardState>,
     pub idle_inhibitor: Rc<IdleInhibitorCardState>,
+    pub power_profile: Rc<PowerProfileCardState>,
+    pub proxy: Rc<ProxyCardState>,
+    pub touchpad: Rc<TouchpadCardState>,
     pub audio: Rc<AudioCardState>,
     pub mic: Rc<MicCardState>,
     pub brightness: Rc<BrightnessCardState>,
@@ -130,11 +142,10 @@ impl QuickSettingsWindow {
         window.init_layer_shell();
         window.set_layer(Layer::Top);
         window.set_exclusive_zone(0);
-        window.set_anchor(Edge::Top, true);
+        anchor_to_bar_edge(&window);
         window.set_anchor(Edge::Right, true);
-        window.set_anchor(Edge::Bottom, false);
         window.set_anchor(Edge::Left, false);
-        window.set_margin(Edge::Top, 0);
+        window.set_margin(bar_edge(), 0);
         window.set_margin(Edge::Right, 8);
         window.set_keyboard_mode(popover_keyboard_mode());
 
@@ -158,6 +169,9 @@ impl QuickSettingsWindow {
             bluetooth: Rc::new(BluetoothCardState::new()),
             vpn: Rc::new(VpnCardState::new()),
             idle_inhibitor: Rc::new(IdleInhibitorCardState::new()),
+            power_profile: Rc::new(PowerProfileCardState::new()),
+            proxy: Rc::new(ProxyCardState::new()),
+            touchpad: Rc::new(TouchpadCardState::new()),
             audio: Rc::new(AudioCardState::new()),
             mic: Rc::new(MicCardState::new()),
             brightness: Rc::new(BrightnessCardState::new()),
@@ -191,6 +205,17 @@ impl QuickSettingsWindow {
             });
         }
 
+        // Keep the registered region in step with the panel's height as
+        // cards expand and collapse
+        {
+            let qs_weak = Rc::downgrade(&qs);
+            qs.scroll_container.vadjustment().connect_changed(move |_| {
+                if let Some(qs) = qs_weak.upgrade() {
+                    qs.register_region();
+                }
+            });
+        }
+
         // Subscribe to services
         Self::subscribe_to_services(&qs);
 
@@ -247,6 +272,33 @@ impl QuickSettingsWindow {
             });
         }
 
+        if cfg.power_profile {
+            let qs_weak = Rc::downgrade(qs);
+            PowerProfileService::global().connect(move |snapshot| {
+                if let Some(qs) = qs_weak.upgrade() {
+                    power_profile_card::on_power_profile_changed(&qs.power_profile, snapshot);
+                }
+            });
+        }
+
+        if cfg.proxy {
+            let qs_weak = Rc::downgrade(qs);
+            ProxyService::global().connect(move |snapshot| {
+                if let Some(qs) = qs_weak.upgrade() {
+                    proxy_card::on_proxy_changed(&qs.proxy, snapshot);
+                }
+            });
+        }
+
+        if cfg.touchpad {
+            let qs_weak = Rc::downgrade(qs);
+            TouchpadService::global().connect(move |snapshot| {
+                if let Some(qs) = qs_weak.upgrade() {
+                    touchpad_card::on_touchpad_changed(&qs.touchpad, snapshot);
+                }
+            });
+        }
+
         if cfg.audio {
             let qs_weak = Rc::downgrade(qs);
             AudioService::global().connect(move |snapshot| {
@@ -289,10 +341,7 @@ impl QuickSettingsWindow {
         let outer = GtkBox::new(Orientation::Vertical, 0);
         outer.add_css_class(qs::WINDOW_CONTAINER);
         outer.add_css_class(surface::NO_FOCUS);
-        outer.set_margin_top(0);
-        outer.set_margin_bottom(QUICK_SETTINGS_OUTER_MARGIN);
-        outer.set_margin_start(QUICK_SETTINGS_OUTER_MARGIN);
-        outer.set_margin_end(QUICK_SETTINGS_OUTER_MARGIN);
+        set_shadow_margins(&outer, QUICK_SETTINGS_OUTER_MARGIN);
 
         // Apply surface styles - background now controlled via CSS variables
         outer.add_css_class("quick-settings-popover");
@@ -307,12 +356,15 @@ impl QuickSettingsWindow {
         let cfg = &qs.cards_config;
 
         // Collect toggle cards and their revealers.
-        // These are the cards that appear in the 2-per-row grid.
+        // These are the cards that appear in the `columns`-per-row grid.
         //
         // Cards with expandable state store a trait object for uniform accordion
         // registration. Cards that need custom expand/collapse behavior (e.g.,
         // Power card updating its subtitle) provide an on_toggle callback.
         struct ToggleCardInfo {
+            kind: QuickSettingsCard,
+            /// Key for remembering the expanded state across opens.
+            key: &'static str,
             card: GtkBox,
             revealer: Option<Revealer>,
             expander_button: Option<Button>,
@@ -329,6 +381,8 @@ impl QuickSettingsWindow {
         if cfg.wifi {
             let (card, revealer, expander_button) = Self::build_wifi_card(qs);
             toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::Wifi,
+                key: "qs.wifi",
                 card,
                 revealer: Some(revealer),
                 expander_button,
@@ -339,6 +393,8 @@ impl QuickSettingsWindow {
         if cfg.bluetooth {
             let (card, revealer, expander_button) = Self::build_bluetooth_card(qs);
             toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::Bluetooth,
+                key: "qs.bluetooth",
                 card,
                 revealer: Some(revealer),
                 expander_button,
@@ -349,6 +405,8 @@ impl QuickSettingsWindow {
         if cfg.vpn {
             let (card, revealer, expander_button) = Self::build_vpn_card(qs);
             toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::Vpn,
+                key: "qs.vpn",
                 card,
                 revealer: Some(revealer),
                 expander_button,
@@ -359,6 +417,44 @@ impl QuickSettingsWindow {
         if cfg.idle_inhibitor {
             let card = Self::build_idle_inhibitor_card(qs);
             toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::IdleInhibitor,
+                key: "qs.idle_inhibitor",
+                card,
+                revealer: None,
+                expander_button: None,
+                expandable: None,
+                on_toggle: None,
+            });
+        }
+        if cfg.power_profile {
+            let (card, revealer, expander_button) = build_power_profile_card(&qs.power_profile);
+            toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::PowerProfile,
+                key: "qs.power_profile",
+                card,
+                revealer: Some(revealer),
+                expander_button,
+                expandable: Some(Rc::clone(&qs.power_profile) as Rc<dyn ExpandableCard>),
+                on_toggle: None,
+            });
+        }
+        if let Some(proxy) = cfg.proxy_settings.as_ref().filter(|_| cfg.proxy) {
+            let card = Self::build_proxy_card(qs, proxy);
+            toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::Proxy,
+                key: "qs.proxy",
+                card,
+                revealer: None,
+                expander_button: None,
+                expandable: None,
+                on_toggle: None,
+            });
+        }
+        if cfg.touchpad && TouchpadService::global().snapshot().available {
+            let card = Self::build_touchpad_card(qs);
+            toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::Touchpad,
+                key: "qs.touchpad",
                 card,
                 revealer: None,
                 expander_button: None,
@@ -369,6 +465,8 @@ impl QuickSettingsWindow {
         if cfg.updates {
             let (card, revealer, expander_button) = build_updates_card(&qs.updates);
             toggle_cards.push(ToggleCardInfo {
+                kind: QuickSettingsCard::Updates,
+                key: "qs.updates",
                 card,
                 revealer: Some(revealer),
                 expander_button,
@@ -376,11 +474,12 @@ impl QuickSettingsWindow {
                 on_toggle: None,
             });
         }
-        // Power card (always last in the grid)
         if cfg.power {
             match power_card::build_power_card() {
                 PowerCardBuildResult::Popover { card, state: _ } => {
                     toggle_cards.push(ToggleCardInfo {
+                        kind: QuickSettingsCard::Power,
+                        key: "qs.power",
                         card,
                         revealer: None,
                         expander_button: None,
@@ -399,6 +498,8 @@ impl QuickSettingsWindow {
                     // subtitle might be set after callback creation.
                     let state_clone = Rc::clone(&state);
                     toggle_cards.push(ToggleCardInfo {
+                        kind: QuickSettingsCard::Power,
+                        key: "qs.power",
                         card,
                         revealer: Some(revealer),
                         expander_button,
@@ -417,9 +518,12 @@ impl QuickSettingsWindow {
             }
         }
 
+        // Lay cards out in the configured order
+        toggle_cards.sort_by_key(|tc| cfg.order.iter().position(|kind| *kind == tc.kind));
+
         // Build rows dynamically with per-row accordion managers
         let mut is_first_row = true;
-        for chunk in toggle_cards.chunks(2) {
+        for chunk in toggle_cards.chunks(cfg.columns) {
             let row = GtkBox::new(Orientation::Horizontal, CARD_ROW_GAP);
             row.add_css_class(qs::CARDS_ROW);
             row.set_homogeneous(true);
@@ -451,40 +555,66 @@ impl QuickSettingsWindow {
                 }
             }
 
-            // If odd number of cards in this row, add placeholder for consistent sizing
-            if chunk.len() == 1 {
+            // Pad a short last row with placeholders for consistent sizing
+            for _ in chunk.len()..cfg.columns {
                 let placeholder = GtkBox::new(Orientation::Horizontal, 0);
                 row.append(&placeholder);
             }
 
             content.append(&row);
 
-            // Add revealers after the row (they expand below the cards)
+            // Add revealers after the row (they expand below the cards),
+            // restoring whichever was left expanded last time
             for tc in chunk {
                 if let Some(ref revealer) = tc.revealer {
                     content.append(revealer);
+
+                    let arrow = tc
+                        .expandable
+                        .as_ref()
+                        .and_then(|card| card.base().arrow.borrow().clone());
+                    if remember_expanded(tc.key, revealer, arrow.as_ref())
+                        && let Some(ref callback) = tc.on_toggle
+                    {
+                        callback(true);
+                    }
                 }
             }
         }
 
-        if cfg.audio {
-            let (audio_row, audio_revealer, audio_hint_label) = Self::build_audio_section(qs);
-            audio_row.set_margin_top(AUDIO_SECTION_TOP_MARGIN);
-            content.append(&audio_row);
-            content.append(&audio_hint_label);
-            content.append(&audio_revealer);
-        }
-
-        if cfg.mic {
-            let (mic_row, mic_revealer, mic_hint_label) = Self::build_mic_section(qs);
-            content.append(&mic_row);
-            content.append(&mic_hint_label);
-            content.append(&mic_revealer);
-        }
-
-        if cfg.brightness && BrightnessService::global().current().available {
-            let brightness_row = Self::build_brightness_section(qs);
-            content.append(&brightness_row);
+        // Slider sections below the grid, in the configured order. The first
+        // one is spaced away from the grid.
+        let mut is_first_slider = true;
+        for kind in cfg.slider_cards() {
+            let first_row = match kind {
+                QuickSettingsCard::Audio => {
+                    let (audio_row, audio_revealer, audio_hint_label) =
+                        Self::build_audio_section(qs);
+                    content.append(&audio_row);
+                    content.append(&audio_hint_label);
+                    content.append(&audio_revealer);
+                    audio_row
+                }
+                QuickSettingsCard::Mic => {
+                    let (mic_row, mic_revealer, mic_hint_label) = Self::build_mic_section(qs);
+                    content.append(&mic_row);
+                    content.append(&mic_hint_label);
+                    content.append(&mic_revealer);
+                    mic_row
+                }
+                QuickSettingsCard::Brightness
+                    if BrightnessService::global().current().available =>
+                {
+                    let brightness_row = Self::build_brightness_section(qs);
+                    content.append(&brightness_row);
+                    brightness_row
+                }
+                _ => continue,
+            };
+            if is_first_slider {
+                first_row.set_margin_top(AUDIO_SECTION_TOP_MARGIN);
+                is_first_slider = false;
+            }
         }
 
         // Wrap content in the scroll container for height limiting
@@ -781,16 +911,10 @@ impl QuickSettingsWindow {
         let idle_active = idle_snapshot.active;
         let idle_available = idle_snapshot.available;
 
-        let idle_subtitle_text = if idle_active {
-            "Enabled".to_string()
-        } else {
-            "Disabled".to_string()
-        };
-
         let idle_card = ToggleCard::builder()
             .icon("night-light-symbolic")
             .label("Idle Inhibitor")
-            .subtitle(&idle_subtitle_text)
+            .subtitle(&idle_inhibitor_card::idle_subtitle(&idle_snapshot))
             .active(idle_active)
             .sensitive(idle_available)
             .icon_active(idle_active)
@@ -803,7 +927,11 @@ impl QuickSettingsWindow {
         {
             let toggle = idle_card.toggle.clone();
             toggle.connect_toggled(move |toggle| {
-                IdleInhibitorService::global().set_active(toggle.is_active());
+                // Skip programmatic syncs so a running timer isn't cleared
+                let service = IdleInhibitorService::global();
+                if service.snapshot().active != toggle.is_active() {
+                    service.set_active(toggle.is_active());
+                }
             });
         }
 
@@ -815,6 +943,75 @@ impl QuickSettingsWindow {
         idle_card.card
     }
 
+    /// Build the Proxy card (no revealer needed).
+    fn build_proxy_card(qs: &Rc<Self>, proxy: &ProxySettings) -> GtkBox {
+        let snapshot = ProxyService::global().snapshot();
+
+        let proxy_card = ToggleCard::builder()
+            .icon("network-proxy-symbolic")
+            .label("Proxy")
+            .subtitle(&proxy_subtitle(&snapshot))
+            .active(snapshot.enabled)
+            .icon_active(snapshot.enabled)
+            .with_expander(false)
+            .build();
+
+        // Add card identifier for CSS targeting
+        proxy_card.card.add_css_class(qs::PROXY);
+
+        {
+            let proxy = proxy.clone();
+            proxy_card.toggle.connect_toggled(move |toggle| {
+                let service = ProxyService::global();
+                // Ignore toggles driven by service updates
+                if toggle.is_active() == service.snapshot().enabled {
+                    return;
+                }
+                service.set_proxy(toggle.is_active().then_some(&proxy));
+            });
+        }
+
+        // Store references
+        *qs.proxy.toggle.borrow_mut() = Some(proxy_card.toggle.clone());
+        *qs.proxy.card_icon.borrow_mut() = Some(proxy_card.icon_handle.clone());
+        *qs.proxy.subtitle.borrow_mut() = proxy_card.subtitle.clone();
+
+        proxy_card.card
+    }
+
+    /// Build the Touchpad card (no revealer needed).
+    fn build_touchpad_card(qs: &Rc<Self>) -> GtkBox {
+        let snapshot = TouchpadService::global().snapshot();
+
+        let touchpad_card = ToggleCard::builder()
+            .icon("input-touchpad-symbolic")
+            .label("Touchpad")
+            .subtitle(touchpad_subtitle(&snapshot))
+            .active(snapshot.enabled)
+            .icon_active(snapshot.enabled)
+            .with_expander(false)
+            .build();
+
+        // Add card identifier for CSS targeting
+        touchpad_card.card.add_css_class(qs::TOUCHPAD);
+
+        touchpad_card.toggle.connect_toggled(|toggle| {
+            let service = TouchpadService::global();
+            // Ignore toggles driven by service updates
+            if toggle.is_active() == service.snapshot().enabled {
+                return;
+            }
+            service.set_enabled(toggle.is_active());
+        });
+
+        // Store references
+        *qs.touchpad.toggle.borrow_mut() = Some(touchpad_card.toggle.clone());
+        *qs.touchpad.card_icon.borrow_mut() = Some(touchpad_card.icon_handle.clone());
+        *qs.touchpad.subtitle.borrow_mut() = touchpad_card.subtitle.clone();
+
+        touchpad_card.card
+    }
+
     /// Build the audio section (row, revealer, hint label).
     fn build_audio_section(qs: &Rc<Self>) -> (GtkBox, Revealer, Label) {
         let audio_widgets = build_audio_row();
@@ -828,6 +1025,13 @@ impl QuickSettingsWindow {
         let audio_service = AudioService::global();
         let audio_snapshot = audio_service.current();
 
+        // The slider stops at audio.max_volume when it's below 100%
+        let max_volume = audio_service.max_volume();
+        if max_volume < 100 {
+            audio_widgets.slider.set_fill_level(max_volume as f64);
+            audio_widgets.slider.set_restrict_to_fill_level(true);
+            audio_widgets.slider.set_show_fill_level(true);
+        }
         audio_widgets.slider.set_value(audio_snapshot.volume as f64);
 
         let vol_icon = audio_card::volume_icon_name(audio_snapshot.volume, audio_snapshot.muted);
@@ -891,6 +1095,12 @@ impl QuickSettingsWindow {
         *qs.audio.row.borrow_mut() = Some(audio_widgets.row.clone());
         *qs.audio.hint_label.borrow_mut() = Some(audio_hint_label.clone());
 
+        remember_expanded(
+            "qs.audio",
+            &audio_details.revealer,
+            Some(&audio_widgets.arrow_handle),
+        );
+
         // Wire up expander button for audio sink list
         {
             let revealer = audio_details.revealer.clone();
@@ -988,6 +1198,12 @@ impl QuickSettingsWindow {
         *qs.mic.row.borrow_mut() = Some(mic_widgets.row.clone());
         *qs.mic.hint_label.borrow_mut() = Some(mic_hint_label.clone());
 
+        remember_expanded(
+            "qs.mic",
+            &mic_details.revealer,
+            Some(&mic_widgets.arrow_handle),
+        );
+
         // Wire up expander button for mic source list
         {
             let revealer = mic_details.revealer.clone();
@@ -1093,14 +1309,14 @@ impl QuickSettingsWindow {
             bar_size + 2 * screen_margin + popover_offset
         };
 
-        // Set top margin using shared helper
-        let top_margin = calculate_popover_top_margin();
-        self.window.set_margin(Edge::Top, top_margin);
+        // Offset from the bar using shared helper
+        let bar_margin = calculate_popover_bar_margin();
+        self.window.set_margin(bar_edge(), bar_margin);
 
         // Max height: screen minus bar zone, margins, and container padding
         let max_height = geom.height()
             - bar_exclusive_zone
-            - top_margin
+            - bar_margin
             - QUICK_SETTINGS_CONTAINER_PADDING
             - QUICK_SETTINGS_BOTTOM_MARGIN;
 
@@ -1129,6 +1345,31 @@ impl QuickSettingsWindow {
             self.window
                 .set_margin(Edge::Right, QUICK_SETTINGS_DEFAULT_RIGHT_MARGIN);
         }
+
+        self.register_region();
+    }
+
+    /// Register the panel's region so toasts and the OSD move out of its way.
+    fn register_region(&self) {
+        if !self.window.is_visible() {
+            return;
+        }
+        let edge = bar_edge();
+        ScreenRegions::global().register(
+            Surface::QuickSettings,
+            Placement {
+                horizontal: Anchor::End,
+                vertical: if edge == Edge::Bottom {
+                    Anchor::End
+                } else {
+                    Anchor::Start
+                },
+                margin_x: self.window.margin(Edge::Right),
+                margin_y: self.window.margin(edge),
+                width: self.window.width(),
+                height: self.window.height(),
+            },
+        );
     }
 
     /// Show the panel and associated click-catcher.
@@ -1197,6 +1438,8 @@ impl QuickSettingsWindow {
         // Clear the global QS window reference
         clear_current_qs_window();
 
+        ScreenRegions::global().unregister(Surface::QuickSettings);
+
         // Destroy click-catcher
         if let Some(catcher) = self.click_catcher.borrow_mut().take() {
             catcher.close();
@@ -1261,15 +1504,42 @@ impl QuickSettingsWindowHandle {
         }
     }
 
-    pub fn toggle_at(&self, x: i32, monitor: Option<Monitor>) {
-        // Check if window exists and is visible
-        let is_visible = self
-            .window
+    /// Whether the Quick Settings window is currently shown.
+    pub fn is_open(&self) -> bool {
+        self.window
             .borrow()
             .as_ref()
-            .is_some_and(|w| w.window.is_visible());
+            .is_some_and(|w| w.window.is_visible())
+    }
 
-        if is_visible {
+    /// Toggle the window, positioned under `anchor` (the bar widget).
+    pub fn toggle_from(&self, anchor: &impl IsA<gtk4::Widget>) {
+        let anchor = anchor.as_ref();
+        let Some(native) = anchor.native() else {
+            self.toggle_at(0, None);
+            return;
+        };
+
+        let monitor = native
+            .surface()
+            .and_then(|surface| surface.display().monitor_at_surface(&surface));
+
+        // Compute widget bounds relative to the native window
+        match anchor.compute_bounds(&native) {
+            Some(bounds) => {
+                // Widget bounds are relative to the bar window's (0,0).
+                // Only anchor_x is used for horizontal positioning of QS window.
+                let screen_margin = ConfigManager::global().screen_margin() as i32;
+                let widget_center_x = (bounds.x() + bounds.width() / 2.0) as i32 + screen_margin;
+                self.toggle_at(widget_center_x, monitor);
+            }
+            // Fallback: toggle without positioning
+            None => self.toggle_at(0, None),
+        }
+    }
+
+    pub fn toggle_at(&self, x: i32, monitor: Option<Monitor>) {
+        if self.is_open() {
             // Window is visible - close and destroy it
             if let Some(qs) = self.window.borrow_mut().take() {
                 qs.hide_panel();
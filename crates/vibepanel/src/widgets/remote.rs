@@ -0,0 +1,167 @@
+//! Remote widget - displays content streamed by an external module.
+//!
+//! External processes connect to the module IPC socket and push
+//! label/icon/tooltip updates for a slot id. This widget renders the slot
+//! and sends click/scroll events back to the owning module.
+//!
+//! Configure the slot id inline (`"remote:weather"`) or via the `id` option.
+//! The widget stays hidden until a module publishes content for its id.
+
+use gtk4::prelude::*;
+use gtk4::{EventControllerScroll, EventControllerScrollFlags, GestureClick, Label, glib};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::callbacks::CallbackId;
+use crate::services::icons::IconHandle;
+use crate::services::remote_modules::{RemoteModuleSnapshot, RemoteModulesService};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{class, state, widget};
+use crate::widgets::base::BaseWidget;
+use crate::widgets::{WidgetConfig, warn_unknown_options};
+
+/// Configuration for the remote widget.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteConfig {
+    /// Slot id the external module publishes to.
+    pub id: String,
+}
+
+impl WidgetConfig for RemoteConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("remote", entry, &["id"]);
+
+        let id = entry
+            .options
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if id.is_empty() {
+            warn!("remote widget has no id; use \"remote:<id>\" or set `id`");
+        }
+
+        Self { id }
+    }
+}
+
+/// Remote widget rendering an externally-driven slot.
+pub struct RemoteWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Callback ID for RemoteModulesService updates.
+    callback_id: CallbackId,
+}
+
+impl RemoteWidget {
+    /// Create a new remote widget with the given configuration.
+    pub fn new(config: RemoteConfig) -> Self {
+        let base = BaseWidget::new(&[widget::REMOTE]);
+        base.widget().add_css_class(state::CLICKABLE);
+        base.widget().set_visible(false);
+
+        let icon_handle = base.add_icon("", &[widget::REMOTE_ICON]);
+        icon_handle.widget().set_visible(false);
+        let label = base.add_label(None, &[widget::REMOTE_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+
+        // Forward every mouse button to the module (no menu is registered on
+        // the base widget, so its own click gesture is a no-op).
+        let click = GestureClick::new();
+        click.set_button(0);
+        {
+            let id = config.id.clone();
+            click.connect_released(move |gesture, _n_press, _x, _y| {
+                RemoteModulesService::global().send_click(&id, gesture.current_button());
+            });
+        }
+        base.widget().add_controller(click);
+
+        let scroll = EventControllerScroll::new(EventControllerScrollFlags::BOTH_AXES);
+        {
+            let id = config.id.clone();
+            scroll.connect_scroll(move |_controller, dx, dy| {
+                RemoteModulesService::global().send_scroll(&id, dx, dy);
+                glib::Propagation::Stop
+            });
+        }
+        base.widget().add_controller(scroll);
+
+        let callback_id = {
+            let container = base.widget().clone();
+            let id = config.id.clone();
+            RemoteModulesService::global().connect(move |snapshot| {
+                if snapshot.id == id {
+                    update_remote_widget(&container, &icon_handle, &label, snapshot);
+                }
+            })
+        };
+
+        Self { base, callback_id }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl Drop for RemoteWidget {
+    fn drop(&mut self) {
+        RemoteModulesService::global().disconnect(self.callback_id);
+    }
+}
+
+/// Update the widget visuals from a slot snapshot.
+fn update_remote_widget(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    snapshot: &RemoteModuleSnapshot,
+) {
+    container.set_visible(snapshot.visible);
+    if !snapshot.visible {
+        return;
+    }
+
+    match snapshot.icon.as_deref() {
+        Some(icon) if !icon.is_empty() => {
+            icon_handle.set_icon(icon);
+            icon_handle.widget().set_visible(true);
+        }
+        _ => icon_handle.widget().set_visible(false),
+    }
+
+    match snapshot.label.as_deref() {
+        Some(text) if !text.is_empty() => {
+            label.set_label(text);
+            label.set_visible(true);
+        }
+        _ => label.set_visible(false),
+    }
+
+    if let Some(tooltip) = snapshot.tooltip.as_deref()
+        && !tooltip.is_empty()
+    {
+        TooltipManager::global().set_styled_tooltip(container, tooltip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_remote_config_id() {
+        let mut options = HashMap::new();
+        options.insert("id".to_string(), toml::Value::String("weather".to_string()));
+        let entry = WidgetEntry {
+            name: "remote".to_string(),
+            options,
+        };
+        let config = RemoteConfig::from_entry(&entry);
+        assert_eq!(config.id, "weather");
+    }
+}
@@ -0,0 +1,141 @@
+//! `show_if`: showing a widget only while a condition on service state holds.
+//!
+//! The condition's variables (see `vibepanel_core::condition`) are read from
+//! service snapshots. Only the services a condition mentions are subscribed
+//! to; the subscriptions are made while the bar's scope is entered, so they
+//! go away with the bar.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gtk4::Widget;
+use gtk4::prelude::*;
+use tracing::{debug, warn};
+use vibepanel_core::condition::Condition;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::battery::{BatteryService, BatterySnapshot, STATE_CHARGING};
+use crate::services::bluetooth::{BluetoothService, BluetoothSnapshot};
+use crate::services::media::{MediaService, MediaSnapshot, PlaybackStatus};
+use crate::services::network::{NetworkService, NetworkSnapshot};
+use crate::services::updates::{UpdatesService, UpdatesSnapshot};
+
+/// Known variable values by name; a missing entry is an unknown value.
+type Values = Rc<RefCell<HashMap<&'static str, f64>>>;
+
+fn flag(value: bool) -> f64 {
+    if value { 1.0 } else { 0.0 }
+}
+
+/// Parse the entry's `show_if` option, if set.
+///
+/// Conditions are checked when the config is validated; one that still
+/// doesn't parse is ignored with a warning.
+pub fn condition_for(entry: &WidgetEntry) -> Option<Condition> {
+    let text = entry.options.get("show_if")?.as_str()?;
+    match Condition::parse(text) {
+        Ok(condition) => Some(condition),
+        Err(e) => {
+            warn!("Ignoring show_if for widget '{}': {}", entry.name, e);
+            None
+        }
+    }
+}
+
+/// Show `widget` only while `condition` holds, re-evaluating it whenever a
+/// service it refers to publishes a new snapshot.
+pub fn bind(widget: &Widget, condition: Condition) {
+    let services = condition_services(&condition);
+    let values: Values = Rc::new(RefCell::new(HashMap::new()));
+    let update: Rc<dyn Fn()> = {
+        let widget = widget.downgrade();
+        let values = values.clone();
+        Rc::new(move || {
+            let Some(widget) = widget.upgrade() else {
+                return;
+            };
+            let visible = {
+                let values = values.borrow();
+                condition.evaluate(|name| values.get(name).copied())
+            };
+            if widget.is_visible() != visible {
+                debug!(
+                    "show_if: {} widget",
+                    if visible { "showing" } else { "hiding" }
+                );
+                widget.set_visible(visible);
+            }
+        })
+    };
+
+    // Unknown values are false, so the widget starts out hidden unless the
+    // condition is negated; the services fill the values in as they connect
+    update();
+
+    for service in services {
+        let values = values.clone();
+        let update = update.clone();
+        match service.as_str() {
+            "battery" => BatteryService::global().connect(move |s: &BatterySnapshot| {
+                let mut v = values.borrow_mut();
+                v.insert("battery.present", flag(s.present));
+                v.insert("battery.charging", flag(s.state == Some(STATE_CHARGING)));
+                match s.percent {
+                    Some(percent) => v.insert("battery.percent", percent),
+                    None => v.remove("battery.percent"),
+                };
+                drop(v);
+                update();
+            }),
+            "updates" => UpdatesService::global().connect(move |s: &UpdatesSnapshot| {
+                if s.is_ready {
+                    values
+                        .borrow_mut()
+                        .insert("updates.count", s.update_count as f64);
+                    update();
+                }
+            }),
+            "media" => {
+                // The subscription lives as long as the bar's scope
+                let _ = MediaService::global().connect(move |s: &MediaSnapshot| {
+                    let mut v = values.borrow_mut();
+                    v.insert("media.active", flag(s.player_name.is_some()));
+                    v.insert(
+                        "media.playing",
+                        flag(s.playback_status == PlaybackStatus::Playing),
+                    );
+                    drop(v);
+                    update();
+                });
+            }
+            "network" => NetworkService::global().connect(move |s: &NetworkSnapshot| {
+                let mut v = values.borrow_mut();
+                v.insert("network.connected", flag(s.connected || s.wired_connected));
+                v.insert("network.wifi", flag(s.connected));
+                drop(v);
+                update();
+            }),
+            "bluetooth" => BluetoothService::global().connect(move |s: &BluetoothSnapshot| {
+                let mut v = values.borrow_mut();
+                v.insert("bluetooth.powered", flag(s.powered));
+                v.insert("bluetooth.connected", s.connected_devices as f64);
+                drop(v);
+                update();
+            }),
+            _ => {}
+        }
+    }
+}
+
+/// Services whose snapshots the condition's variables come from.
+fn condition_services(condition: &Condition) -> Vec<String> {
+    let mut services: Vec<String> = Vec::new();
+    for variable in condition.variables() {
+        let service = variable.split('.').next().unwrap_or(variable);
+        if !services.iter().any(|s| s == service) {
+            services.push(service.to_string());
+        }
+    }
+    services
+}
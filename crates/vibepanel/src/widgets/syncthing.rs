@@ -0,0 +1,416 @@
+//! Syncthing widget - shows sync status from Syncthing's REST API.
+//!
+//! This widget:
+//! - Shows an icon reflecting the overall status (up to date, syncing, error, offline)
+//! - Shows the number of out-of-sync items while folders are syncing
+//! - Opens a popover with transfer rates and per-folder status on click
+//!
+//! Configuration options:
+//! - `api_key`: API key from Syncthing's GUI settings (required)
+//! - `url`: REST API address (default: "http://127.0.0.1:8384")
+//! - `poll_interval`: How often to poll, in seconds (default: 5)
+
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Label, Orientation, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::icons::IconHandle;
+use crate::services::syncthing::{
+    DEFAULT_POLL_INTERVAL, DEFAULT_URL, FolderStatus, SyncStatus, SyncthingService,
+    SyncthingSettings, SyncthingSnapshot,
+};
+use crate::services::system::format_speed;
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{class, color, surface, syncthing, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+/// Configuration for the Syncthing widget.
+#[derive(Debug, Clone)]
+pub struct SyncthingConfig {
+    /// REST API address.
+    pub url: String,
+    /// API key (`None` if not configured).
+    pub api_key: Option<String>,
+    /// How often to poll (seconds).
+    pub poll_interval: u64,
+}
+
+impl WidgetConfig for SyncthingConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("syncthing", entry, &["api_key", "url", "poll_interval"]);
+
+        let url = entry
+            .options
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| DEFAULT_URL.to_string());
+
+        let api_key = entry
+            .options
+            .get("api_key")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.trim().to_string());
+        if api_key.is_none() {
+            warn!("syncthing widget has no `api_key` configured");
+        }
+
+        let poll_interval = entry
+            .options
+            .get("poll_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_POLL_INTERVAL);
+
+        Self {
+            url,
+            api_key,
+            poll_interval,
+        }
+    }
+}
+
+/// Syncthing widget that displays overall sync status.
+pub struct SyncthingWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover with per-folder status.
+    _menu: Option<Rc<MenuHandle>>,
+    /// This widget's settings registered with the service.
+    settings_id: Option<SettingsId>,
+}
+
+impl SyncthingWidget {
+    /// Create a new Syncthing widget with the given configuration.
+    pub fn new(config: SyncthingConfig) -> Self {
+        let base = BaseWidget::new(&[widget::SYNCTHING]);
+        base.set_tooltip("Syncthing: connecting...");
+
+        let icon_handle = base.add_icon("emblem-synchronizing-symbolic", &[widget::SYNCTHING_ICON]);
+        let label = base.add_label(None, &[widget::SYNCTHING_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+
+        let Some(api_key) = config.api_key else {
+            base.widget().set_visible(false);
+            return Self {
+                base,
+                _menu: None,
+                settings_id: None,
+            };
+        };
+
+        let menu = base.create_menu(build_syncthing_popover);
+
+        let service = SyncthingService::global();
+        let settings_id = service.configure(SyncthingSettings {
+            url: config.url,
+            api_key,
+            poll_interval: config.poll_interval,
+        });
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            service.connect(move |snapshot: &SyncthingSnapshot| {
+                update_widget_from_snapshot(&container, &icon_handle, &label, snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self {
+            base,
+            _menu: Some(menu),
+            settings_id: Some(settings_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for SyncthingWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for SyncthingWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.settings_id.take() {
+            SyncthingService::global().release(settings_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    snapshot: &SyncthingSnapshot,
+) {
+    container.remove_css_class(widget::SYNCTHING_SYNCING);
+    container.remove_css_class(widget::SYNCTHING_ERROR);
+    container.remove_css_class(widget::SYNCTHING_DISCONNECTED);
+
+    let status = snapshot.status();
+    let icon = match status {
+        SyncStatus::UpToDate => "emblem-synchronizing-symbolic",
+        SyncStatus::Syncing => {
+            container.add_css_class(widget::SYNCTHING_SYNCING);
+            "emblem-synchronizing-symbolic"
+        }
+        SyncStatus::Error => {
+            container.add_css_class(widget::SYNCTHING_ERROR);
+            "sync-problem-symbolic"
+        }
+        SyncStatus::Disconnected => {
+            container.add_css_class(widget::SYNCTHING_DISCONNECTED);
+            "sync-disabled-symbolic"
+        }
+    };
+    icon_handle.set_icon(icon);
+
+    let out_of_sync = snapshot.out_of_sync_items();
+    if status == SyncStatus::Syncing && out_of_sync > 0 {
+        update::set_label_text(label, &out_of_sync.to_string());
+        label.set_visible(true);
+    } else {
+        label.set_visible(false);
+    }
+
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(snapshot));
+}
+
+/// One-line summary of the overall status.
+fn status_summary(snapshot: &SyncthingSnapshot) -> String {
+    match snapshot.status() {
+        SyncStatus::UpToDate => "Up to date".to_string(),
+        SyncStatus::Syncing => {
+            let items = snapshot.out_of_sync_items();
+            let s = if items == 1 { "" } else { "s" };
+            format!("Syncing · {} item{} out of sync", items, s)
+        }
+        SyncStatus::Error => "Folder errors".to_string(),
+        SyncStatus::Disconnected => "Not running".to_string(),
+    }
+}
+
+/// Human-readable status for a single folder, e.g. "Syncing 42% · 12 items".
+pub fn folder_status_text(folder: &FolderStatus) -> String {
+    match folder.state.as_str() {
+        "paused" => "Paused".to_string(),
+        "error" => "Error".to_string(),
+        "scanning" | "scan-waiting" => "Scanning".to_string(),
+        _ if folder.in_sync() => "Up to date".to_string(),
+        _ if folder.need_items > 0 => {
+            let s = if folder.need_items == 1 { "" } else { "s" };
+            format!(
+                "Syncing {:.0}% · {} item{}",
+                folder.completion().floor(),
+                folder.need_items,
+                s
+            )
+        }
+        _ => "Syncing".to_string(),
+    }
+}
+
+/// Download/upload rates, e.g. "↓ 1.5 MB/s  ↑ 12 KB/s".
+fn format_rates(snapshot: &SyncthingSnapshot) -> String {
+    format!(
+        "↓ {}  ↑ {}",
+        format_speed(snapshot.download_rate),
+        format_speed(snapshot.upload_rate)
+    )
+}
+
+/// Format the tooltip for a snapshot.
+fn format_tooltip(snapshot: &SyncthingSnapshot) -> String {
+    let mut lines = vec![format!("Syncthing: {}", status_summary(snapshot))];
+    if snapshot.connected {
+        lines.push(format_rates(snapshot));
+    } else if let Some(ref err) = snapshot.error {
+        lines.push(err.clone());
+    }
+    lines.join("\n")
+}
+
+/// Build the popover content: rates plus one row per folder.
+fn build_syncthing_popover() -> Widget {
+    let snapshot = SyncthingService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(syncthing::POPOVER);
+
+    let title = Label::new(Some("Syncthing"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    container.append(&title);
+
+    let summary = Label::new(Some(&status_summary(&snapshot)));
+    summary.add_css_class(color::MUTED);
+    summary.set_halign(Align::Start);
+    container.append(&summary);
+
+    if !snapshot.connected {
+        if let Some(ref err) = snapshot.error {
+            let error = Label::new(Some(err));
+            error.add_css_class(color::MUTED);
+            error.set_halign(Align::Start);
+            error.set_wrap(true);
+            error.set_max_width_chars(40);
+            container.append(&error);
+        }
+        return container.upcast();
+    }
+
+    let rates = Label::new(Some(&format_rates(&snapshot)));
+    rates.add_css_class(syncthing::RATES);
+    rates.set_halign(Align::Start);
+    container.append(&rates);
+
+    for folder in &snapshot.folders {
+        container.append(&build_folder_row(folder));
+    }
+
+    container.upcast()
+}
+
+/// Build a single folder row: label on the left, status on the right.
+fn build_folder_row(folder: &FolderStatus) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 12);
+    row.add_css_class(syncthing::ROW);
+    if folder.has_error() {
+        row.add_css_class(syncthing::ROW_ERROR);
+    } else if !folder.paused && !folder.in_sync() {
+        row.add_css_class(syncthing::ROW_SYNCING);
+    }
+
+    let name = Label::new(Some(&folder.label));
+    name.add_css_class(syncthing::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(EllipsizeMode::End);
+    name.set_max_width_chars(30);
+    row.append(&name);
+
+    let status = Label::new(Some(&folder_status_text(folder)));
+    status.add_css_class(syncthing::ROW_STATUS);
+    status.add_css_class(color::MUTED);
+    status.set_halign(Align::End);
+    row.append(&status);
+
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_syncthing_config_parsing() {
+        let mut options = HashMap::new();
+        options.insert(
+            "api_key".to_string(),
+            toml::Value::String("abc123".to_string()),
+        );
+        options.insert(
+            "url".to_string(),
+            toml::Value::String("http://localhost:8385/".to_string()),
+        );
+        let entry = WidgetEntry {
+            name: "syncthing".to_string(),
+            options,
+        };
+        let config = SyncthingConfig::from_entry(&entry);
+
+        assert_eq!(config.api_key.as_deref(), Some("abc123"));
+        assert_eq!(config.url, "http://localhost:8385");
+        assert_eq!(config.poll_interval, DEFAULT_POLL_INTERVAL);
+    }
+
+    #[test]
+    fn test_folder_status_text() {
+        let mut folder = FolderStatus {
+            id: "docs".to_string(),
+            label: "Documents".to_string(),
+            state: "syncing".to_string(),
+            paused: false,
+            need_items: 12,
+            need_bytes: 580,
+            global_bytes: 1000,
+        };
+        assert_eq!(folder_status_text(&folder), "Syncing 42% · 12 items");
+
+        folder.state = "idle".to_string();
+        folder.need_items = 0;
+        assert_eq!(folder_status_text(&folder), "Up to date");
+
+        folder.state = "paused".to_string();
+        assert_eq!(folder_status_text(&folder), "Paused");
+    }
+
+    #[test]
+    fn test_syncthing_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = SyncthingWidget::new(SyncthingConfig {
+                // Keep the service's own poll off the network
+                url: "http://127.0.0.1:9".to_string(),
+                api_key: Some("key".to_string()),
+                poll_interval: DEFAULT_POLL_INTERVAL,
+            });
+            let service = SyncthingService::global();
+
+            service.publish_for_test(SyncthingSnapshot {
+                available: true,
+                connected: true,
+                folders: vec![FolderStatus {
+                    id: "docs".to_string(),
+                    label: "Documents".to_string(),
+                    state: "syncing".to_string(),
+                    paused: false,
+                    need_items: 12,
+                    need_bytes: 4096,
+                    global_bytes: 8192,
+                }],
+                ..SyncthingSnapshot::default()
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.syncthing.syncthing-syncing.widget.widget-item",
+                    "icon.syncthing-icon",
+                    r#"label.syncthing-label.vcenter-caps "12""#,
+                ],
+            );
+
+            // REST API unreachable: flagged, no item count
+            service.publish_for_test(SyncthingSnapshot {
+                available: true,
+                ..SyncthingSnapshot::default()
+            });
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &["box.clickable.syncthing.syncthing-disconnected.widget.widget-item"],
+            );
+            assert!(
+                !rendered.contains("syncthing-label"),
+                "rendered tree:\n{rendered}"
+            );
+        });
+    }
+}
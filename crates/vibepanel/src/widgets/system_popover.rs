@@ -13,21 +13,31 @@
 //! │ ┌───────────┐ ┌───────────┐ │
 //! │ │  Load     │ │  Network  │ │
 //! │ └───────────┘ └───────────┘ │
+//! ├─────────────────────────────┤
+//! │ ┌─────────────────────────┐ │
+//! │ │  Interfaces             │ │
+//! │ └─────────────────────────┘ │
 //! └─────────────────────────────┘
 //! ```
 //!
 //! The CPU section has an expandable per-core breakdown that spans full width.
+//! The interfaces section lists each network interface with its state and
+//! addresses, each with a button copying it to the clipboard.
 
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use gtk4::prelude::*;
 use gtk4::{
-    Align, Box as GtkBox, Label, Orientation, ProgressBar, Revealer, RevealerTransitionType, Widget,
+    Align, Box as GtkBox, Button, Label, Orientation, ProgressBar, Revealer,
+    RevealerTransitionType, Widget,
 };
 
 use crate::services::icons::{IconHandle, IconsService};
-use crate::services::system::{SystemService, SystemSnapshot, format_bytes_long, format_speed};
+use crate::services::system::{
+    NetworkInterface, SystemService, SystemSnapshot, format_bytes_long, format_speed,
+};
+use crate::services::tooltip::TooltipManager;
 use crate::styles::{button, card, color, icon, surface, system_popover as sp};
 
 /// A single pre-allocated per-core row with its updatable widgets.
@@ -64,6 +74,10 @@ pub struct SystemPopoverController {
     load_1_label: Label,
     load_5_label: Label,
     load_15_label: Label,
+
+    // Interfaces section
+    interfaces_box: GtkBox,
+    shown_interfaces: Rc<RefCell<Vec<NetworkInterface>>>,
 }
 
 impl SystemPopoverController {
@@ -109,6 +123,8 @@ impl SystemPopoverController {
         self.load_1_label.set_label(&format!("{:.2}", one));
         self.load_5_label.set_label(&format!("{:.2}", five));
         self.load_15_label.set_label(&format!("{:.2}", fifteen));
+
+        self.update_interfaces(snapshot);
     }
 
     /// Toggle the cores expander visibility.
@@ -171,6 +187,94 @@ impl SystemPopoverController {
             }
         }
     }
+
+    /// Rebuild the interface rows if the interfaces or their addresses changed.
+    fn update_interfaces(&self, snapshot: &SystemSnapshot) {
+        let mut shown = self.shown_interfaces.borrow_mut();
+        if *shown == snapshot.interfaces && self.interfaces_box.first_child().is_some() {
+            return;
+        }
+        *shown = snapshot.interfaces.clone();
+
+        while let Some(child) = self.interfaces_box.first_child() {
+            self.interfaces_box.remove(&child);
+        }
+
+        if snapshot.interfaces.is_empty() {
+            let label = Label::new(Some("No network interfaces"));
+            label.add_css_class(color::MUTED);
+            label.set_halign(Align::Start);
+            self.interfaces_box.append(&label);
+            return;
+        }
+
+        for interface in &snapshot.interfaces {
+            self.interfaces_box.append(&interface_row(interface));
+        }
+    }
+}
+
+/// Create the rows for one interface: its name and state, then its addresses.
+fn interface_row(interface: &NetworkInterface) -> GtkBox {
+    let container = GtkBox::new(Orientation::Vertical, 2);
+    container.add_css_class(sp::INTERFACE);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let name = Label::new(Some(&interface.name));
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    header.append(&name);
+
+    let state = Label::new(Some(&interface.state));
+    state.add_css_class(if interface.is_up() {
+        color::PRIMARY
+    } else {
+        color::MUTED
+    });
+    header.append(&state);
+    container.append(&header);
+
+    for address in &interface.addresses {
+        container.append(&address_row(&address.to_string()));
+    }
+
+    container
+}
+
+/// Create an address row with a button copying the address.
+fn address_row(address: &str) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class(sp::ADDRESS_ROW);
+
+    let label = Label::new(Some(address));
+    label.add_css_class(color::MUTED);
+    label.set_halign(Align::Start);
+    label.set_hexpand(true);
+    label.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
+    row.append(&label);
+
+    let copy_btn = Button::new();
+    copy_btn.set_has_frame(false);
+    copy_btn.set_focusable(false);
+    copy_btn.set_focus_on_click(false);
+    copy_btn.add_css_class(surface::POPOVER_ICON_BTN);
+    copy_btn.set_valign(Align::Center);
+
+    let copy_icon = IconsService::global().create_icon("edit-copy-symbolic", &[color::PRIMARY]);
+    let copy_icon_widget = copy_icon.widget();
+    copy_icon_widget.set_halign(Align::Center);
+    copy_icon_widget.set_valign(Align::Center);
+    copy_btn.set_child(Some(&copy_icon_widget));
+    TooltipManager::global().set_styled_tooltip(&copy_btn, "Copy address");
+
+    let address = address.to_string();
+    copy_btn.connect_clicked(move |btn| {
+        btn.clipboard().set_text(&address);
+        TooltipManager::global().set_styled_tooltip(btn, "Copied");
+    });
+    row.append(&copy_btn);
+
+    row
 }
 
 /// Create a section title with icon and label.
@@ -229,10 +333,10 @@ fn stat_row(label_text: &str, value_width_chars: i32) -> (GtkBox, Label) {
     (row, value)
 }
 
-/// Build a system resource popover content widget.
-pub fn build_system_popover_with_controller() -> (Widget, SystemPopoverController) {
-    let system_service = SystemService::global();
-    let snapshot = system_service.snapshot();
+/// Build a system resource popover content widget showing `snapshot`.
+pub fn build_system_popover_with_controller(
+    snapshot: &SystemSnapshot,
+) -> (Widget, SystemPopoverController) {
     let icons = IconsService::global();
 
     let container = GtkBox::new(Orientation::Vertical, 0);
@@ -423,6 +527,19 @@ pub fn build_system_popover_with_controller() -> (Widget, SystemPopoverControlle
     bottom_row.append(&network_card);
     container.append(&bottom_row);
 
+    let interfaces_card = GtkBox::new(Orientation::Vertical, 0);
+    interfaces_card.add_css_class(card::BASE);
+    interfaces_card.add_css_class(sp::SECTION_CARD);
+    interfaces_card.set_margin_top(8);
+
+    let interfaces_section = GtkBox::new(Orientation::Vertical, 8);
+    interfaces_section.append(&section_title("settings_ethernet", "Interfaces", &icons));
+
+    let interfaces_box = GtkBox::new(Orientation::Vertical, 8);
+    interfaces_section.append(&interfaces_box);
+    interfaces_card.append(&interfaces_section);
+    container.append(&interfaces_card);
+
     let controller = SystemPopoverController {
         cpu_usage_label,
         cpu_temp_label,
@@ -441,6 +558,8 @@ pub fn build_system_popover_with_controller() -> (Widget, SystemPopoverControlle
         load_1_label,
         load_5_label,
         load_15_label,
+        interfaces_box,
+        shown_interfaces: Rc::new(RefCell::new(Vec::new())),
     };
 
     let controller_clone = controller.clone();
@@ -448,7 +567,7 @@ pub fn build_system_popover_with_controller() -> (Widget, SystemPopoverControlle
         controller_clone.toggle_cores();
     });
 
-    controller.update_from_snapshot(&snapshot);
+    controller.update_from_snapshot(snapshot);
 
     (container.upcast::<Widget>(), controller)
 }
@@ -457,6 +576,8 @@ pub fn build_system_popover_with_controller() -> (Widget, SystemPopoverControlle
 #[derive(Clone)]
 pub struct SystemPopoverBinding {
     controller: Rc<RefCell<Option<SystemPopoverController>>>,
+    /// Last snapshot the widget showed, which may be a remote host's.
+    last_snapshot: Rc<RefCell<Option<SystemSnapshot>>>,
 }
 
 impl SystemPopoverBinding {
@@ -464,18 +585,29 @@ impl SystemPopoverBinding {
     pub fn new(base: &crate::widgets::base::BaseWidget) -> Self {
         let controller: Rc<RefCell<Option<SystemPopoverController>>> = Rc::new(RefCell::new(None));
         let controller_for_builder = controller.clone();
+        let last_snapshot: Rc<RefCell<Option<SystemSnapshot>>> = Rc::new(RefCell::new(None));
+        let last_for_builder = last_snapshot.clone();
 
         base.create_menu(move || {
-            let (widget, ctrl) = build_system_popover_with_controller();
+            let snapshot = last_for_builder
+                .borrow()
+                .clone()
+                .unwrap_or_else(|| SystemService::global().snapshot());
+            let (widget, ctrl) = build_system_popover_with_controller(&snapshot);
             *controller_for_builder.borrow_mut() = Some(ctrl);
             widget
         });
 
-        Self { controller }
+        Self {
+            controller,
+            last_snapshot,
+        }
     }
 
-    /// Update the popover if it's currently open.
+    /// Remember the widget's latest snapshot and update the popover if it's
+    /// currently open.
     pub fn update_if_open(&self, snapshot: &SystemSnapshot) {
+        *self.last_snapshot.borrow_mut() = Some(snapshot.clone());
         if let Some(controller) = self.controller.borrow().as_ref() {
             controller.update_from_snapshot(snapshot);
         }
@@ -0,0 +1,446 @@
+//! Tailscale widget - tailnet connection state, exit node and peers.
+//!
+//! This widget:
+//! - Shows a VPN icon reflecting the connection state
+//! - Shows the active exit node name next to the icon
+//! - Opens a popover with a connect/disconnect switch and the peer list,
+//!   where each peer's IP can be copied to the clipboard
+//! - Hides itself when the `tailscale` CLI isn't installed
+//!
+//! Configuration options:
+//! - `poll_interval`: How often to poll `tailscale status`, in seconds (default: 10)
+//! - `show_exit_node`: Show the exit node name in the bar (default: true)
+
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, ScrolledWindow, Switch, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::icons::{IconHandle, IconsService};
+use crate::services::tailscale::{
+    BackendState, DEFAULT_POLL_INTERVAL, TailscalePeer, TailscaleService, TailscaleSnapshot,
+};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{class, color, surface, tailscale, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_SHOW_EXIT_NODE: bool = true;
+
+/// Maximum height of the peer list before it scrolls (pixels).
+const PEER_LIST_MAX_HEIGHT: i32 = 320;
+
+/// Configuration for the Tailscale widget.
+#[derive(Debug, Clone)]
+pub struct TailscaleConfig {
+    /// How often to poll (seconds).
+    pub poll_interval: u64,
+    /// Show the exit node name in the bar.
+    pub show_exit_node: bool,
+}
+
+impl WidgetConfig for TailscaleConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options("tailscale", entry, &["poll_interval", "show_exit_node"]);
+
+        let poll_interval = entry
+            .options
+            .get("poll_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_POLL_INTERVAL);
+
+        let show_exit_node = entry
+            .options
+            .get("show_exit_node")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_EXIT_NODE);
+
+        Self {
+            poll_interval,
+            show_exit_node,
+        }
+    }
+}
+
+impl Default for TailscaleConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            show_exit_node: DEFAULT_SHOW_EXIT_NODE,
+        }
+    }
+}
+
+/// Tailscale widget that displays tailnet connection state.
+pub struct TailscaleWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Popover with the connection switch and peers.
+    _menu: Rc<MenuHandle>,
+}
+
+impl TailscaleWidget {
+    /// Create a new Tailscale widget with the given configuration.
+    pub fn new(config: TailscaleConfig) -> Self {
+        let base = BaseWidget::new(&[widget::TAILSCALE]);
+        base.set_tooltip("Tailscale");
+
+        let icon_handle = base.add_icon(
+            "network-vpn-disconnected-symbolic",
+            &[widget::TAILSCALE_ICON],
+        );
+        let label = base.add_label(None, &[widget::TAILSCALE_LABEL, class::VCENTER_CAPS]);
+        label.set_visible(false);
+
+        let menu = base.create_menu(build_tailscale_popover);
+
+        let service = TailscaleService::global();
+        service.start(config.poll_interval);
+
+        {
+            let container = base.widget().clone();
+            let menu = menu.clone();
+            let show_exit_node = config.show_exit_node;
+            service.connect(move |snapshot: &TailscaleSnapshot| {
+                update_widget_from_snapshot(
+                    &container,
+                    &icon_handle,
+                    &label,
+                    show_exit_node,
+                    snapshot,
+                );
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self { base, _menu: menu }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for TailscaleWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    show_exit_node: bool,
+    snapshot: &TailscaleSnapshot,
+) {
+    container.set_visible(snapshot.available);
+
+    container.remove_css_class(widget::TAILSCALE_CONNECTED);
+    container.remove_css_class(widget::TAILSCALE_ERROR);
+
+    let icon = match snapshot.state {
+        BackendState::Running => {
+            container.add_css_class(widget::TAILSCALE_CONNECTED);
+            "network-vpn-connected-symbolic"
+        }
+        BackendState::Starting => "network-vpn-acquiring-symbolic",
+        BackendState::NeedsLogin => {
+            container.add_css_class(widget::TAILSCALE_ERROR);
+            "network-vpn-disconnected-symbolic"
+        }
+        BackendState::Stopped | BackendState::Unavailable => "network-vpn-disconnected-symbolic",
+    };
+    icon_handle.set_icon(icon);
+
+    match snapshot.exit_node().filter(|_| show_exit_node) {
+        Some(exit_node) => {
+            update::set_label_text(label, &exit_node.name);
+            label.set_visible(true);
+        }
+        None => label.set_visible(false),
+    }
+
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(snapshot));
+}
+
+/// Format the tooltip for a snapshot.
+fn format_tooltip(snapshot: &TailscaleSnapshot) -> String {
+    let mut lines = vec![format!("Tailscale: {}", snapshot.state.label())];
+
+    if snapshot.connected() {
+        if let Some(ip) = snapshot.self_node.as_ref().and_then(|n| n.primary_ip()) {
+            lines.push(ip.to_string());
+        }
+        if let Some(exit_node) = snapshot.exit_node() {
+            lines.push(format!("Exit node: {}", exit_node.name));
+        }
+        lines.push(format!(
+            "{} of {} peers online",
+            snapshot.online_peers(),
+            snapshot.peers.len()
+        ));
+    }
+
+    if let Some(ref err) = snapshot.error {
+        lines.push(err.clone());
+    }
+
+    lines.join("\n")
+}
+
+/// Build the popover content: header with switch, status and peer list.
+fn build_tailscale_popover() -> Widget {
+    let snapshot = TailscaleService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(tailscale::POPOVER);
+
+    // Header: title + connect switch
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    header.set_baseline_position(gtk4::BaselinePosition::Center);
+    let title = Label::new(Some("Tailscale"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let switch = Switch::new();
+    switch.set_valign(Align::Center);
+    switch.set_active(snapshot.connected() || snapshot.state == BackendState::Starting);
+    switch.set_sensitive(
+        !snapshot.busy
+            && matches!(
+                snapshot.state,
+                BackendState::Running | BackendState::Stopped | BackendState::Starting
+            ),
+    );
+    switch.connect_state_set(|_, enabled| {
+        TailscaleService::global().set_connected(enabled);
+        gtk4::glib::Propagation::Proceed
+    });
+    header.append(&switch);
+    container.append(&header);
+
+    let status = Label::new(Some(snapshot.state.label()));
+    status.add_css_class(color::MUTED);
+    status.set_halign(Align::Start);
+    container.append(&status);
+
+    if let Some(ref err) = snapshot.error {
+        let error = Label::new(Some(err));
+        error.add_css_class(tailscale::ERROR);
+        error.set_halign(Align::Start);
+        error.set_xalign(0.0);
+        error.set_wrap(true);
+        error.set_max_width_chars(40);
+        container.append(&error);
+    }
+
+    if snapshot.login_url.is_some() {
+        let login = Button::with_label("Log in");
+        login.set_halign(Align::Start);
+        login.connect_clicked(|_| {
+            if let Err(e) = TailscaleService::global().open_login() {
+                warn!("Tailscale: can't open the login page: {}", e);
+            }
+        });
+        container.append(&login);
+    }
+
+    if !snapshot.connected() {
+        return container.upcast();
+    }
+
+    if let Some(ref self_node) = snapshot.self_node {
+        container.append(&build_peer_row(self_node, Some("This device")));
+    }
+
+    if let Some(exit_node) = snapshot.exit_node() {
+        let exit = Label::new(Some(&format!("Exit node: {}", exit_node.name)));
+        exit.add_css_class(tailscale::EXIT_NODE);
+        exit.set_halign(Align::Start);
+        container.append(&exit);
+    }
+
+    let section = Label::new(Some("Peers"));
+    section.add_css_class(tailscale::SECTION_TITLE);
+    section.set_halign(Align::Start);
+    container.append(&section);
+
+    if snapshot.peers.is_empty() {
+        let empty = Label::new(Some("No peers"));
+        empty.add_css_class(color::MUTED);
+        empty.set_halign(Align::Start);
+        container.append(&empty);
+        return container.upcast();
+    }
+
+    let list = GtkBox::new(Orientation::Vertical, 0);
+    for peer in &snapshot.peers {
+        list.append(&build_peer_row(peer, None));
+    }
+
+    let scroller = ScrolledWindow::new();
+    scroller.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
+    scroller.set_propagate_natural_height(true);
+    scroller.set_max_content_height(PEER_LIST_MAX_HEIGHT);
+    scroller.set_child(Some(&list));
+    container.append(&scroller);
+
+    container.upcast()
+}
+
+/// Build a peer row: name (and OS) on the left, IP with copy button on the right.
+fn build_peer_row(peer: &TailscalePeer, subtitle: Option<&str>) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    row.add_css_class(tailscale::PEER_ROW);
+    if !peer.online {
+        row.add_css_class(tailscale::PEER_OFFLINE);
+    }
+
+    let text = GtkBox::new(Orientation::Vertical, 0);
+    text.set_hexpand(true);
+
+    let name = Label::new(Some(&peer.name));
+    name.add_css_class(tailscale::PEER_NAME);
+    name.set_halign(Align::Start);
+    name.set_xalign(0.0);
+    name.set_ellipsize(EllipsizeMode::End);
+    name.set_max_width_chars(24);
+    text.append(&name);
+
+    let detail = subtitle.map(String::from).unwrap_or_else(|| {
+        if peer.online {
+            peer.os.clone()
+        } else {
+            format!("{} · offline", peer.os)
+        }
+    });
+    if !detail.is_empty() {
+        let detail_label = Label::new(Some(&detail));
+        detail_label.add_css_class(tailscale::PEER_DETAIL);
+        detail_label.add_css_class(color::MUTED);
+        detail_label.set_halign(Align::Start);
+        text.append(&detail_label);
+    }
+    row.append(&text);
+
+    if let Some(ip) = peer.primary_ip() {
+        let ip_label = Label::new(Some(ip));
+        ip_label.add_css_class(tailscale::PEER_IP);
+        ip_label.add_css_class(color::MUTED);
+        ip_label.set_valign(Align::Center);
+        row.append(&ip_label);
+
+        let copy_btn = Button::new();
+        copy_btn.set_has_frame(false);
+        copy_btn.set_focusable(false);
+        copy_btn.set_focus_on_click(false);
+        copy_btn.add_css_class(surface::POPOVER_ICON_BTN);
+        copy_btn.set_valign(Align::Center);
+
+        let copy_icon = IconsService::global().create_icon("edit-copy-symbolic", &[color::PRIMARY]);
+        let copy_icon_widget = copy_icon.widget();
+        copy_icon_widget.set_halign(Align::Center);
+        copy_icon_widget.set_valign(Align::Center);
+        copy_btn.set_child(Some(&copy_icon_widget));
+        TooltipManager::global().set_styled_tooltip(&copy_btn, "Copy IP address");
+
+        let ip = ip.to_string();
+        copy_btn.connect_clicked(move |btn| {
+            btn.clipboard().set_text(&ip);
+            TooltipManager::global().set_styled_tooltip(btn, "Copied");
+        });
+        row.append(&copy_btn);
+    }
+
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_harness;
+
+    #[test]
+    fn test_tailscale_config_defaults() {
+        let entry = WidgetEntry {
+            name: "tailscale".to_string(),
+            options: Default::default(),
+        };
+        let config = TailscaleConfig::from_entry(&entry);
+        assert_eq!(config.poll_interval, DEFAULT_POLL_INTERVAL);
+        assert!(config.show_exit_node);
+    }
+
+    #[test]
+    fn test_format_tooltip_disconnected() {
+        let snapshot = TailscaleSnapshot {
+            available: true,
+            state: BackendState::Stopped,
+            ..Default::default()
+        };
+        assert_eq!(format_tooltip(&snapshot), "Tailscale: Disconnected");
+    }
+
+    #[test]
+    fn test_tailscale_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = TailscaleWidget::new(TailscaleConfig {
+                poll_interval: DEFAULT_POLL_INTERVAL,
+                show_exit_node: true,
+            });
+            let service = TailscaleService::global();
+            let exit_node = TailscalePeer {
+                name: "amsterdam".to_string(),
+                dns_name: None,
+                os: "linux".to_string(),
+                ips: vec!["100.64.0.2".to_string()],
+                online: true,
+                exit_node: true,
+            };
+
+            service.publish_for_test(TailscaleSnapshot {
+                available: true,
+                state: BackendState::Running,
+                peers: vec![exit_node],
+                ..TailscaleSnapshot::default()
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.tailscale.tailscale-connected.widget.widget-item",
+                    "icon.tailscale-icon",
+                    r#"label.tailscale-label.vcenter-caps "amsterdam""#,
+                ],
+            );
+
+            // Logged out: flagged, no exit node
+            service.publish_for_test(TailscaleSnapshot {
+                available: true,
+                state: BackendState::NeedsLogin,
+                ..TailscaleSnapshot::default()
+            });
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &["box.clickable.tailscale.tailscale-error.widget.widget-item"],
+            );
+            assert!(
+                !rendered.contains("tailscale-label"),
+                "rendered tree:\n{rendered}"
+            );
+
+            // CLI not installed: hidden
+            service.publish_for_test(TailscaleSnapshot::default());
+            assert_eq!(test_harness::render(widget.widget()), "");
+        });
+    }
+}
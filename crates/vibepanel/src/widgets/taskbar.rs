@@ -0,0 +1,371 @@
+//! Taskbar widget - open windows as app icons.
+//!
+//! This widget:
+//! - Shows one button per open window, in the compositor's order
+//! - Highlights the focused window and dims minimized ones
+//! - Focuses a window on left click and closes it on middle click
+//! - Lists only the windows on the bar's monitor, like `window_title`
+//! - Badges windows that are playing sound with a small speaker (see
+//!   `AudioWindowsService`; Hyprland and Niri only)
+//!
+//! Windows come from the wlr-foreign-toplevel-management protocol (see
+//! `ToplevelService`); the widget hides itself when there are none.
+//!
+//! Configuration options:
+//! - `all_outputs`: List windows from every monitor (default: false)
+//! - `show_title`: Show the window title next to the icon (default: false)
+//! - `max_chars`: Title width in characters when `show_title` is set
+//!   (default: 16)
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gtk4::gdk::{BUTTON_MIDDLE, BUTTON_PRIMARY};
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, GestureClick, Image, Label, Orientation, Overlay, Widget};
+use tracing::debug;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::audio_windows::{AudioWindowsService, AudioWindowsSnapshot};
+use crate::services::callbacks::CallbackId;
+use crate::services::config_manager::ConfigManager;
+use crate::services::icons::{IconHandle, IconsService, get_app_icon_name};
+use crate::services::tooltip::TooltipManager;
+use crate::services::toplevel::{Toplevel, ToplevelService, ToplevelSnapshot};
+use crate::styles::{icon, state, widget};
+use crate::widgets::base::BaseWidget;
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_ALL_OUTPUTS: bool = false;
+const DEFAULT_SHOW_TITLE: bool = false;
+const DEFAULT_MAX_CHARS: i32 = 16;
+
+/// Icon for windows without a resolvable app icon.
+const FALLBACK_ICON: &str = "application-x-executable";
+
+/// Configuration for the taskbar widget.
+#[derive(Debug, Clone)]
+pub struct TaskbarConfig {
+    /// List windows from every monitor instead of only the bar's.
+    pub all_outputs: bool,
+    /// Whether to show window titles.
+    pub show_title: bool,
+    /// Maximum title width in characters.
+    pub max_chars: i32,
+}
+
+impl WidgetConfig for TaskbarConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "taskbar",
+            entry,
+            &["all_outputs", "show_title", "max_chars"],
+        );
+
+        let all_outputs = entry
+            .options
+            .get("all_outputs")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_ALL_OUTPUTS);
+
+        let show_title = entry
+            .options
+            .get("show_title")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_TITLE);
+
+        let max_chars = entry
+            .options
+            .get("max_chars")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as i32)
+            .unwrap_or(DEFAULT_MAX_CHARS);
+
+        Self {
+            all_outputs,
+            show_title,
+            max_chars,
+        }
+    }
+}
+
+/// Widgets for one window button.
+struct TaskbarItem {
+    root: GtkBox,
+    image: Image,
+    label: Option<Label>,
+    /// Speaker badge, shown while the window plays sound.
+    audio: IconHandle,
+    /// App ID and title, for matching against windows playing sound.
+    window: (String, String),
+}
+
+/// Taskbar widget listing open windows.
+pub struct TaskbarWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// ToplevelService callback registration.
+    callback_id: Option<CallbackId>,
+    /// AudioWindowsService callback registration.
+    audio_callback: Option<CallbackId>,
+}
+
+impl TaskbarWidget {
+    /// Create a new taskbar widget with the given configuration.
+    ///
+    /// The `output_id` parameter is the monitor connector name (e.g., "eDP-1")
+    /// used to list only the windows on this monitor. If `None` (or with
+    /// `all_outputs`), every window is listed.
+    pub fn new(config: TaskbarConfig, output_id: Option<String>) -> Self {
+        let base = BaseWidget::new(&[widget::TASKBAR]);
+        base.widget().set_visible(false);
+
+        let output_id = output_id.filter(|_| !config.all_outputs);
+        let root = base.widget().clone();
+        let container = base.content().clone();
+        let items: Rc<RefCell<HashMap<u64, TaskbarItem>>> = Rc::new(RefCell::new(HashMap::new()));
+        let audio = Rc::new(RefCell::new(AudioWindowsSnapshot::default()));
+
+        let items_for_toplevels = Rc::clone(&items);
+        let audio_for_toplevels = Rc::clone(&audio);
+        let callback_id = ToplevelService::global().connect(move |snapshot: &ToplevelSnapshot| {
+            update_items(
+                &root,
+                &container,
+                &items_for_toplevels,
+                &config,
+                output_id.as_deref(),
+                snapshot,
+                &audio_for_toplevels.borrow(),
+            );
+        });
+
+        let audio_callback =
+            AudioWindowsService::global().connect(move |snapshot: &AudioWindowsSnapshot| {
+                *audio.borrow_mut() = snapshot.clone();
+                for item in items.borrow().values() {
+                    update_audio_badge(item, snapshot);
+                }
+            });
+
+        debug!("TaskbarWidget created");
+        Self {
+            base,
+            callback_id: Some(callback_id),
+            audio_callback: Some(audio_callback),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl Drop for TaskbarWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.callback_id.take() {
+            ToplevelService::global().disconnect(callback_id);
+        }
+        if let Some(callback_id) = self.audio_callback.take() {
+            AudioWindowsService::global().disconnect(callback_id);
+        }
+    }
+}
+
+/// Whether a window belongs on the bar for `output_id`.
+///
+/// Windows the compositor reports no output for (e.g. minimized ones on
+/// some compositors) are shown on every bar rather than lost.
+fn on_output(toplevel: &Toplevel, output_id: Option<&str>) -> bool {
+    match output_id {
+        Some(output) if !toplevel.outputs.is_empty() => {
+            toplevel.outputs.iter().any(|o| o == output)
+        }
+        _ => true,
+    }
+}
+
+/// Sync the window buttons with a snapshot.
+fn update_items(
+    root: &GtkBox,
+    container: &GtkBox,
+    items: &RefCell<HashMap<u64, TaskbarItem>>,
+    config: &TaskbarConfig,
+    output_id: Option<&str>,
+    snapshot: &ToplevelSnapshot,
+    audio: &AudioWindowsSnapshot,
+) {
+    let toplevels: Vec<&Toplevel> = snapshot
+        .toplevels
+        .iter()
+        .filter(|t| on_output(t, output_id))
+        .collect();
+
+    let mut items = items.borrow_mut();
+    items.retain(|id, item| {
+        let keep = toplevels.iter().any(|t| t.id == *id);
+        if !keep {
+            container.remove(&item.root);
+        }
+        keep
+    });
+
+    let mut previous: Option<Widget> = None;
+    for toplevel in toplevels {
+        let item = items
+            .entry(toplevel.id)
+            .or_insert_with(|| create_item(container, config, toplevel.id));
+        update_item(item, toplevel);
+        update_audio_badge(item, audio);
+        // Keep the compositor's order
+        container.reorder_child_after(&item.root, previous.as_ref());
+        previous = Some(item.root.clone().upcast());
+    }
+
+    root.set_visible(!items.is_empty());
+}
+
+fn create_item(container: &GtkBox, config: &TaskbarConfig, id: u64) -> TaskbarItem {
+    let root = GtkBox::new(Orientation::Horizontal, 4);
+    root.add_css_class(widget::TASKBAR_ITEM);
+    root.add_css_class(state::CLICKABLE);
+
+    let image = Image::new();
+    image.add_css_class(widget::TASKBAR_ITEM_ICON);
+    image.set_pixel_size(ConfigManager::global().theme_sizes().pixmap_icon_size as i32);
+
+    // Wrap in icon-root container for consistent sizing with other icons
+    let icon_root = GtkBox::new(Orientation::Horizontal, 0);
+    icon_root.add_css_class(icon::ROOT);
+    icon_root.append(&image);
+
+    let audio = IconsService::global()
+        .create_icon("audio-volume-high-symbolic", &[widget::TASKBAR_ITEM_AUDIO]);
+    let audio_widget = audio.widget();
+    audio_widget.set_halign(Align::End);
+    audio_widget.set_valign(Align::Start);
+    audio_widget.set_can_target(false);
+    audio_widget.set_visible(false);
+
+    let overlay = Overlay::new();
+    overlay.set_valign(Align::Center);
+    overlay.set_child(Some(&icon_root));
+    overlay.add_overlay(&audio_widget);
+    root.append(&overlay);
+
+    let label = config.show_title.then(|| {
+        let label = Label::new(None);
+        label.add_css_class(widget::TASKBAR_ITEM_LABEL);
+        label.set_ellipsize(EllipsizeMode::End);
+        label.set_single_line_mode(true);
+        label.set_max_width_chars(config.max_chars);
+        label.set_xalign(0.0);
+        root.append(&label);
+        label
+    });
+
+    let gesture = GestureClick::new();
+    gesture.set_button(0);
+    gesture.connect_released(
+        move |gesture, _n_press, _x, _y| match gesture.current_button() {
+            BUTTON_PRIMARY => ToplevelService::global().activate(id),
+            BUTTON_MIDDLE => ToplevelService::global().close(id),
+            _ => {}
+        },
+    );
+    root.add_controller(gesture);
+
+    container.append(&root);
+    TaskbarItem {
+        root,
+        image,
+        label,
+        audio,
+        window: (String::new(), String::new()),
+    }
+}
+
+fn update_item(item: &mut TaskbarItem, toplevel: &Toplevel) {
+    item.window = (toplevel.app_id.clone(), toplevel.title.clone());
+
+    update::set_class(&item.root, widget::ACTIVE, toplevel.activated);
+    update::set_class(
+        &item.root,
+        widget::TASKBAR_ITEM_MINIMIZED,
+        toplevel.minimized,
+    );
+
+    // Same lookup as window_title: desktop entry icon, then the app_id itself
+    let icon_name = match get_app_icon_name(&toplevel.app_id) {
+        name if !name.is_empty() => name,
+        _ if !toplevel.app_id.is_empty() => toplevel.app_id.to_lowercase(),
+        _ => FALLBACK_ICON.to_string(),
+    };
+    if item.image.icon_name().as_deref() != Some(icon_name.as_str()) {
+        item.image.set_icon_name(Some(&icon_name));
+    }
+
+    let title = if toplevel.title.is_empty() {
+        &toplevel.app_id
+    } else {
+        &toplevel.title
+    };
+    if let Some(ref label) = item.label {
+        update::set_label_text(label, title);
+    }
+    TooltipManager::global().set_styled_tooltip(&item.root, title);
+}
+
+/// Show the speaker badge while the window plays sound.
+///
+/// Toplevels carry no PID, so windows are matched by app ID and title.
+fn update_audio_badge(item: &TaskbarItem, audio: &AudioWindowsSnapshot) {
+    let (app_id, title) = &item.window;
+    item.audio
+        .widget()
+        .set_visible(audio.window_playing(app_id, title));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taskbar_config() {
+        let entry = WidgetEntry {
+            name: "taskbar".to_string(),
+            options: HashMap::new(),
+        };
+        let config = TaskbarConfig::from_entry(&entry);
+        assert!(!config.all_outputs);
+        assert!(!config.show_title);
+        assert_eq!(config.max_chars, DEFAULT_MAX_CHARS);
+
+        let mut options = HashMap::new();
+        options.insert("show_title".to_string(), toml::Value::Boolean(true));
+        options.insert("max_chars".to_string(), toml::Value::Integer(0));
+        let config = TaskbarConfig::from_entry(&WidgetEntry {
+            name: "taskbar".to_string(),
+            options,
+        });
+        assert!(config.show_title);
+        assert_eq!(config.max_chars, 1);
+    }
+
+    #[test]
+    fn test_on_output() {
+        let toplevel = Toplevel {
+            outputs: vec!["DP-1".to_string()],
+            ..Default::default()
+        };
+        assert!(on_output(&toplevel, Some("DP-1")));
+        assert!(!on_output(&toplevel, Some("eDP-1")));
+        assert!(on_output(&toplevel, None));
+
+        // No output reported: shown everywhere
+        assert!(on_output(&Toplevel::default(), Some("eDP-1")));
+    }
+}
@@ -0,0 +1,437 @@
+//! Temperature widget - hardware sensor temperature.
+//!
+//! This widget:
+//! - Shows the temperature of one sensor (the CPU package by default)
+//! - Turns warning / urgent colored above the configured thresholds
+//! - Lists every detected hwmon sensor and thermal zone in a popover
+//!
+//! Configuration options:
+//! - `sensor`: Sensor name as shown in the popover, e.g. "k10temp: Tctl";
+//!   matched case-insensitively, falling back to a substring match
+//!   (default: auto-detect the CPU sensor)
+//! - `warning`: Warning threshold in °C (default: 70)
+//! - `urgent`: Urgent threshold in °C (default: 85)
+//! - `check_interval`: How often to read sensors, in seconds (default: 5)
+//! - `show_icon`: Whether to show the thermometer icon (default: true)
+//! - `source`: "local", or "ssh:<host>" to show another machine's sensors via
+//!   `RemoteStatsService` (default: "local")
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::pango::EllipsizeMode;
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Label, Orientation, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::icons::IconHandle;
+use crate::services::remote_stats::{RemoteStatsService, RemoteStatsSnapshot};
+use crate::services::temperature::{
+    DEFAULT_CHECK_INTERVAL, Sensor, TemperatureService, TemperatureSnapshot,
+};
+use crate::services::tooltip::TooltipManager;
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{class, color, surface, temperature, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, parse_stats_source, update, warn_unknown_options};
+
+const DEFAULT_WARNING: f64 = 70.0;
+const DEFAULT_URGENT: f64 = 85.0;
+const DEFAULT_SHOW_ICON: bool = true;
+
+/// Configuration for the temperature widget.
+#[derive(Debug, Clone)]
+pub struct TemperatureConfig {
+    /// Sensor to show; `None` picks the CPU sensor.
+    pub sensor: Option<String>,
+    /// Warning threshold (°C).
+    pub warning: f64,
+    /// Urgent threshold (°C).
+    pub urgent: f64,
+    /// How often to read sensors (seconds).
+    pub check_interval: u64,
+    /// Whether to show the icon.
+    pub show_icon: bool,
+    /// Host to read sensors from over SSH; `None` for this machine.
+    pub source: Option<String>,
+}
+
+impl WidgetConfig for TemperatureConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "temperature",
+            entry,
+            &[
+                "sensor",
+                "warning",
+                "urgent",
+                "check_interval",
+                "show_icon",
+                "source",
+            ],
+        );
+
+        let sensor = entry
+            .options
+            .get("sensor")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        // Accept both `warning = 70` and `warning = 72.5`
+        let threshold = |key: &str, default: f64| {
+            entry
+                .options
+                .get(key)
+                .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+                .unwrap_or(default)
+        };
+        let warning = threshold("warning", DEFAULT_WARNING);
+        let mut urgent = threshold("urgent", DEFAULT_URGENT);
+        if urgent < warning {
+            warn!(
+                "temperature widget: urgent ({}) is below warning ({}), using warning",
+                urgent, warning
+            );
+            urgent = warning;
+        }
+
+        let check_interval = entry
+            .options
+            .get("check_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(1) as u64)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+
+        let show_icon = entry
+            .options
+            .get("show_icon")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_ICON);
+
+        let source = parse_stats_source("temperature", entry);
+
+        Self {
+            sensor,
+            warning,
+            urgent,
+            check_interval,
+            show_icon,
+            source,
+        }
+    }
+}
+
+/// Temperature level relative to the configured thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Normal,
+    Warning,
+    Urgent,
+}
+
+impl TemperatureConfig {
+    fn level(&self, celsius: f64) -> Level {
+        if celsius >= self.urgent {
+            Level::Urgent
+        } else if celsius >= self.warning {
+            Level::Warning
+        } else {
+            Level::Normal
+        }
+    }
+}
+
+/// Temperature widget that displays one sensor reading.
+pub struct TemperatureWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Thermometer icon handle from IconsService.
+    _icon_handle: IconHandle,
+    /// Popover listing all sensors.
+    _menu: Rc<MenuHandle>,
+    /// Read interval registered with the local service.
+    settings_id: Option<SettingsId>,
+}
+
+impl TemperatureWidget {
+    /// Create a new temperature widget with the given configuration.
+    pub fn new(config: TemperatureConfig) -> Self {
+        let base = BaseWidget::new(&[widget::TEMPERATURE]);
+        base.set_tooltip("Temperature: reading sensors...");
+
+        let icon_handle = base.add_icon("device_thermostat", &[widget::TEMPERATURE_ICON]);
+        icon_handle.widget().set_visible(config.show_icon);
+        let label = base.add_label(None, &[widget::TEMPERATURE_LABEL, class::VCENTER_CAPS]);
+
+        let config = Rc::new(config);
+        // Last snapshot shown, which the popover lists
+        let latest: Rc<RefCell<TemperatureSnapshot>> = Rc::default();
+        let menu = {
+            let config = config.clone();
+            let latest = latest.clone();
+            base.create_menu(move || build_temperature_popover(&config, &latest.borrow()))
+        };
+
+        let container = base.widget().clone();
+        let show = {
+            let container = container.clone();
+            let label = label.clone();
+            let menu = menu.clone();
+            let config = config.clone();
+            move |snapshot: &TemperatureSnapshot| {
+                *latest.borrow_mut() = snapshot.clone();
+                update_widget_from_snapshot(&container, &label, &config, snapshot);
+                menu.refresh_if_visible();
+            }
+        };
+
+        let settings_id = match config.source {
+            Some(ref host) => {
+                RemoteStatsService::global().connect(
+                    host,
+                    config.check_interval as u32,
+                    move |remote: &RemoteStatsSnapshot| match remote.error {
+                        Some(ref err) => {
+                            update::set_label_text(&label, "?");
+                            TooltipManager::global().set_styled_tooltip(
+                                &container,
+                                &format!("{}: {}", remote.host, err),
+                            );
+                        }
+                        None => show(&remote.temperatures),
+                    },
+                );
+                None
+            }
+            None => {
+                let service = TemperatureService::global();
+                let settings_id = service.configure(config.check_interval);
+                service.connect(show);
+                Some(settings_id)
+            }
+        };
+
+        Self {
+            base,
+            _icon_handle: icon_handle,
+            _menu: menu,
+            settings_id,
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for TemperatureWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for TemperatureWidget {
+    fn drop(&mut self) {
+        if let Some(settings_id) = self.settings_id.take() {
+            TemperatureService::global().release(settings_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    label: &Label,
+    config: &TemperatureConfig,
+    snapshot: &TemperatureSnapshot,
+) {
+    if !snapshot.ready {
+        return;
+    }
+
+    let Some(sensor) = snapshot.find(config.sensor.as_deref()) else {
+        update::set_label_text(label, "?");
+        update::set_class(container, widget::TEMPERATURE_WARNING, false);
+        update::set_class(container, widget::TEMPERATURE_URGENT, false);
+        let tooltip = match config.sensor {
+            Some(ref name) => format!("Temperature: sensor '{}' not found", name),
+            None => "Temperature: no sensors found".to_string(),
+        };
+        TooltipManager::global().set_styled_tooltip(container, &with_host(config, tooltip));
+        return;
+    };
+
+    update::set_label_text(label, &format_celsius(sensor.celsius));
+
+    let level = config.level(sensor.celsius);
+    update::set_class(
+        container,
+        widget::TEMPERATURE_WARNING,
+        level == Level::Warning,
+    );
+    update::set_class(
+        container,
+        widget::TEMPERATURE_URGENT,
+        level == Level::Urgent,
+    );
+
+    TooltipManager::global()
+        .set_styled_tooltip(container, &with_host(config, format_tooltip(sensor)));
+}
+
+/// Put the host the sensors are read from above a tooltip, for remote stats.
+fn with_host(config: &TemperatureConfig, tooltip: String) -> String {
+    match config.source {
+        Some(ref host) => format!("{}\n{}", host, tooltip),
+        None => tooltip,
+    }
+}
+
+/// Format a temperature for display, e.g. "52°C".
+fn format_celsius(celsius: f64) -> String {
+    format!("{:.0}°C", celsius)
+}
+
+/// Format the tooltip for the shown sensor.
+fn format_tooltip(sensor: &Sensor) -> String {
+    match sensor.critical {
+        Some(critical) => format!(
+            "{}: {:.1}°C\nCritical: {}",
+            sensor.name,
+            sensor.celsius,
+            format_celsius(critical)
+        ),
+        None => format!("{}: {:.1}°C", sensor.name, sensor.celsius),
+    }
+}
+
+/// Build the popover content listing the sensors of `snapshot`.
+fn build_temperature_popover(config: &TemperatureConfig, snapshot: &TemperatureSnapshot) -> Widget {
+    let selected = snapshot
+        .find(config.sensor.as_deref())
+        .map(|s| s.name.clone());
+
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(temperature::POPOVER);
+
+    let title = Label::new(Some(&match config.source {
+        Some(ref host) => format!("Sensors on {}", host),
+        None => "Sensors".to_string(),
+    }));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    container.append(&title);
+
+    if snapshot.sensors.is_empty() {
+        let empty = Label::new(Some(if snapshot.ready {
+            "No temperature sensors found"
+        } else {
+            "Reading sensors..."
+        }));
+        empty.add_css_class(color::MUTED);
+        empty.set_halign(Align::Start);
+        container.append(&empty);
+    }
+
+    for sensor in &snapshot.sensors {
+        let row = GtkBox::new(Orientation::Horizontal, 8);
+        row.add_css_class(temperature::ROW);
+        match config.level(sensor.celsius) {
+            Level::Warning => row.add_css_class(temperature::ROW_WARNING),
+            Level::Urgent => row.add_css_class(temperature::ROW_URGENT),
+            Level::Normal => {}
+        }
+        if selected.as_deref() == Some(sensor.name.as_str()) {
+            row.add_css_class(temperature::ROW_SELECTED);
+        }
+
+        let name = Label::new(Some(&sensor.name));
+        name.add_css_class(temperature::ROW_NAME);
+        name.set_halign(Align::Start);
+        name.set_hexpand(true);
+        name.set_xalign(0.0);
+        name.set_ellipsize(EllipsizeMode::End);
+        name.set_max_width_chars(28);
+        row.append(&name);
+
+        let value = Label::new(Some(&format_celsius(sensor.celsius)));
+        value.add_css_class(temperature::ROW_VALUE);
+        value.set_halign(Align::End);
+        row.append(&value);
+
+        container.append(&row);
+    }
+
+    container.upcast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::test_entry;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_temperature_config_defaults() {
+        let config = TemperatureConfig::from_entry(&test_entry("temperature", HashMap::new()));
+        assert_eq!(config.sensor, None);
+        assert_eq!(config.warning, DEFAULT_WARNING);
+        assert_eq!(config.urgent, DEFAULT_URGENT);
+        assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
+        assert!(config.show_icon);
+        assert_eq!(config.source, None);
+    }
+
+    #[test]
+    fn test_temperature_config_source() {
+        let mut options = HashMap::new();
+        options.insert(
+            "source".to_string(),
+            toml::Value::String("ssh:server1".to_string()),
+        );
+        let config = TemperatureConfig::from_entry(&test_entry("temperature", options));
+        assert_eq!(config.source.as_deref(), Some("server1"));
+
+        let mut options = HashMap::new();
+        options.insert(
+            "source".to_string(),
+            toml::Value::String("local".to_string()),
+        );
+        let config = TemperatureConfig::from_entry(&test_entry("temperature", options));
+        assert_eq!(config.source, None);
+    }
+
+    #[test]
+    fn test_temperature_config_thresholds() {
+        let mut options = HashMap::new();
+        options.insert(
+            "sensor".to_string(),
+            toml::Value::String("amdgpu: edge".to_string()),
+        );
+        options.insert("warning".to_string(), toml::Value::Integer(60));
+        options.insert("urgent".to_string(), toml::Value::Float(80.5));
+        let config = TemperatureConfig::from_entry(&test_entry("temperature", options));
+
+        assert_eq!(config.sensor.as_deref(), Some("amdgpu: edge"));
+        assert_eq!(config.level(59.9), Level::Normal);
+        assert_eq!(config.level(60.0), Level::Warning);
+        assert_eq!(config.level(80.5), Level::Urgent);
+
+        // Urgent below warning is clamped up
+        let mut options = HashMap::new();
+        options.insert("warning".to_string(), toml::Value::Integer(90));
+        options.insert("urgent".to_string(), toml::Value::Integer(80));
+        let config = TemperatureConfig::from_entry(&test_entry("temperature", options));
+        assert_eq!(config.urgent, 90.0);
+    }
+
+    #[test]
+    fn test_format_celsius() {
+        assert_eq!(format_celsius(52.4), "52°C");
+        assert_eq!(format_celsius(52.6), "53°C");
+        assert_eq!(format_celsius(99.9), "100°C");
+    }
+}
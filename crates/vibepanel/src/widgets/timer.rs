@@ -0,0 +1,275 @@
+//! Timer widget - pomodoro / focus timer.
+//!
+//! This widget:
+//! - Shows the time left in the current interval (e.g. "24:13")
+//! - Starts, pauses and resumes on click
+//! - Skips to the next interval on right click, resets on middle click
+//! - Marks running, paused and break states with CSS classes
+//!
+//! The countdown runs in `TimerService`, so it survives bar rebuilds, and a
+//! notification is sent when an interval completes.
+//!
+//! Configuration options:
+//! - `preset`: "pomodoro" (25/5/15 min, long break every 4), "52-17" or "90-20"
+//!   (default: "pomodoro")
+//! - `work`, `short_break`, `long_break`: Override interval lengths, in minutes
+//! - `long_break_every`: Work intervals before a long break (0 = never)
+//! - `auto_start`: Start the next interval automatically (default: false)
+
+use std::time::Duration;
+
+use gtk4::gdk::{BUTTON_MIDDLE, BUTTON_PRIMARY, BUTTON_SECONDARY};
+use gtk4::prelude::*;
+use gtk4::{GestureClick, Label};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::callbacks::CallbackId;
+use crate::services::icons::IconHandle;
+use crate::services::timer::{TimerService, TimerSettings, TimerSnapshot, TimerState};
+use crate::services::tooltip::TooltipManager;
+use crate::styles::{class, state, widget};
+use crate::widgets::base::BaseWidget;
+use crate::widgets::{WidgetConfig, update, warn_unknown_options};
+
+const DEFAULT_PRESET: &str = "pomodoro";
+
+/// Configuration for the timer widget.
+#[derive(Debug, Clone, Default)]
+pub struct TimerConfig {
+    /// Interval lengths after applying the preset and overrides.
+    pub settings: TimerSettings,
+}
+
+impl WidgetConfig for TimerConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "timer",
+            entry,
+            &[
+                "preset",
+                "work",
+                "short_break",
+                "long_break",
+                "long_break_every",
+                "auto_start",
+            ],
+        );
+
+        let preset = entry
+            .options
+            .get("preset")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_PRESET);
+        let mut settings = TimerSettings::preset(preset).unwrap_or_else(|| {
+            warn!(
+                "timer widget: unknown preset '{}' (expected pomodoro, 52-17 or 90-20)",
+                preset
+            );
+            TimerSettings::default()
+        });
+
+        let minutes = |key: &str| {
+            entry
+                .options
+                .get(key)
+                .and_then(|v| v.as_integer())
+                .filter(|&m| m > 0)
+                .map(|m| Duration::from_secs(m as u64 * 60))
+        };
+        if let Some(work) = minutes("work") {
+            settings.work = work;
+        }
+        if let Some(short_break) = minutes("short_break") {
+            settings.short_break = short_break;
+        }
+        if let Some(long_break) = minutes("long_break") {
+            settings.long_break = long_break;
+        }
+        if let Some(every) = entry
+            .options
+            .get("long_break_every")
+            .and_then(|v| v.as_integer())
+            .filter(|&n| n >= 0)
+        {
+            settings.long_break_every = every as u32;
+        }
+        if let Some(auto_start) = entry.options.get("auto_start").and_then(|v| v.as_bool()) {
+            settings.auto_start = auto_start;
+        }
+
+        Self { settings }
+    }
+}
+
+/// Pomodoro / focus timer widget.
+pub struct TimerWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// TimerService callback registration.
+    callback_id: Option<CallbackId>,
+}
+
+impl TimerWidget {
+    /// Create a new timer widget with the given configuration.
+    pub fn new(config: TimerConfig) -> Self {
+        let base = BaseWidget::new(&[widget::TIMER]);
+        base.widget().add_css_class(state::CLICKABLE);
+
+        let icon_handle = base.add_icon("alarm-symbolic", &[widget::TIMER_ICON]);
+        let label = base.add_label(None, &[widget::TIMER_LABEL, class::VCENTER_CAPS]);
+
+        let service = TimerService::global();
+        service.configure(config.settings);
+
+        // No menu is registered on the base widget, so its own click gesture
+        // is a no-op.
+        let click = GestureClick::new();
+        click.set_button(0);
+        click.connect_released(|gesture, _n_press, _x, _y| {
+            let service = TimerService::global();
+            match gesture.current_button() {
+                BUTTON_PRIMARY => service.toggle(),
+                BUTTON_SECONDARY => service.skip(),
+                BUTTON_MIDDLE => service.reset(),
+                _ => {}
+            }
+        });
+        base.widget().add_controller(click);
+
+        let callback_id = {
+            let container = base.widget().clone();
+            service.connect(move |snapshot: &TimerSnapshot| {
+                update_widget_from_snapshot(&container, &icon_handle, &label, snapshot);
+            })
+        };
+
+        Self {
+            base,
+            callback_id: Some(callback_id),
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl Drop for TimerWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.callback_id.take() {
+            TimerService::global().disconnect(callback_id);
+        }
+    }
+}
+
+/// Update the widget's visual state from a snapshot.
+fn update_widget_from_snapshot(
+    container: &gtk4::Box,
+    icon_handle: &IconHandle,
+    label: &Label,
+    snapshot: &TimerSnapshot,
+) {
+    update::set_class(
+        container,
+        widget::TIMER_RUNNING,
+        snapshot.state == TimerState::Running,
+    );
+    update::set_class(
+        container,
+        widget::TIMER_PAUSED,
+        snapshot.state == TimerState::Paused,
+    );
+    update::set_class(container, widget::TIMER_BREAK, snapshot.phase.is_break());
+
+    icon_handle.set_icon(match snapshot.state {
+        TimerState::Paused => "media-playback-pause-symbolic",
+        _ if snapshot.phase.is_break() => "preferences-system-time-symbolic",
+        _ => "alarm-symbolic",
+    });
+    update::set_label_text(label, &format_remaining(snapshot.remaining));
+
+    TooltipManager::global().set_styled_tooltip(container, &format_tooltip(snapshot));
+}
+
+/// Format the time left as "mm:ss", or "h:mm:ss" from an hour up.
+fn format_remaining(remaining: Duration) -> String {
+    // Round up so the display reaches 0:00 only when the interval ends
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Tooltip with the phase, state, completed count and click hints.
+fn format_tooltip(snapshot: &TimerSnapshot) -> String {
+    let state = match snapshot.state {
+        TimerState::Idle => "ready",
+        TimerState::Running => "running",
+        TimerState::Paused => "paused",
+    };
+    let action = match snapshot.state {
+        TimerState::Idle => "start",
+        TimerState::Running => "pause",
+        TimerState::Paused => "resume",
+    };
+
+    format!(
+        "{} ({}) - {} left\nCompleted: {}\nClick to {}, right-click to skip, middle-click to reset",
+        snapshot.phase.label(),
+        state,
+        format_remaining(snapshot.remaining),
+        snapshot.completed,
+        action
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::timer::TimerPhase;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_timer_config_overrides() {
+        let mut options = HashMap::new();
+        options.insert(
+            "preset".to_string(),
+            toml::Value::String("52-17".to_string()),
+        );
+        options.insert("short_break".to_string(), toml::Value::Integer(10));
+        options.insert("auto_start".to_string(), toml::Value::Boolean(true));
+        let config = TimerConfig::from_entry(&WidgetEntry {
+            name: "timer".to_string(),
+            options,
+        });
+        assert_eq!(config.settings.work, Duration::from_secs(52 * 60));
+        assert_eq!(config.settings.short_break, Duration::from_secs(10 * 60));
+        assert!(config.settings.auto_start);
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(25 * 60)), "25:00");
+        assert_eq!(format_remaining(Duration::from_millis(59_400)), "01:00");
+        assert_eq!(format_remaining(Duration::ZERO), "00:00");
+        assert_eq!(format_remaining(Duration::from_secs(90 * 60)), "1:30:00");
+    }
+
+    #[test]
+    fn test_format_tooltip() {
+        let snapshot = TimerSnapshot {
+            phase: TimerPhase::Work,
+            state: TimerState::Paused,
+            remaining: Duration::from_secs(754),
+            completed: 2,
+        };
+        assert_eq!(
+            format_tooltip(&snapshot),
+            "Focus (paused) - 12:34 left\nCompleted: 2\nClick to resume, right-click to skip, middle-click to reset"
+        );
+    }
+}
@@ -11,7 +11,8 @@ use gtk4::gdk_pixbuf::{Colorspace, Pixbuf};
 use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{
-    Box as GtkBox, Button, GestureClick, Image, Label, Orientation, Popover, Separator, Widget,
+    Box as GtkBox, Button, GestureClick, IconTheme, Image, Label, Orientation, Popover, Separator,
+    Widget,
 };
 use tracing::debug;
 use vibepanel_core::config::WidgetEntry;
@@ -19,6 +20,7 @@ use vibepanel_core::{parse_hex_color, theme::relative_luminance};
 
 use crate::services::callbacks::CallbackId;
 use crate::services::config_manager::ConfigManager;
+use crate::services::icons::mapped_gtk_icon_name;
 use crate::services::surfaces::SurfaceStyleManager;
 use crate::services::tooltip::TooltipManager;
 use crate::services::tray::{TrayItem, TrayMenuEntry, TrayPixmap, TrayService};
@@ -100,6 +102,9 @@ struct WidgetState {
     config: TrayConfig,
     buttons: HashMap<String, Button>,
     pixmap_cache: HashMap<String, gdk::Texture>,
+    /// Icon themes of items with an `IconThemePath`, by identifier, with
+    /// the path each was built for.
+    item_themes: HashMap<String, (String, IconTheme)>,
     menu: Option<MenuState>,
     /// Track the current button order to avoid unnecessary rebuilds.
     /// This prevents menu flickering when animated icons update rapidly.
@@ -143,6 +148,7 @@ impl TrayWidget {
             config,
             buttons: HashMap::new(),
             pixmap_cache: HashMap::new(),
+            item_themes: HashMap::new(),
             menu: None,
             button_order: Vec::new(),
             contrast_params: compute_contrast_params(),
@@ -187,6 +193,8 @@ impl TrayWidget {
                     let mut st = state.borrow_mut();
                     st.contrast_params = compute_contrast_params();
                     st.pixmap_cache.clear();
+                    // Rebuilt on the next update, following a new icon theme
+                    st.item_themes.clear();
                 }
                 let state = state.clone();
                 let content = content.clone();
@@ -245,6 +253,7 @@ fn sync_items(state: &Rc<RefCell<WidgetState>>, container: &GtkBox, root: &GtkBo
         let mut menu_to_close: Option<Popover> = None;
 
         for identifier in to_remove {
+            st.item_themes.remove(&identifier);
             if let Some(button) = st.buttons.remove(&identifier) {
                 // If menu is parented to this button, mark it for cleanup
                 if let Some(ref menu) = st.menu
@@ -392,6 +401,12 @@ fn update_button(state: &Rc<RefCell<WidgetState>>, button: &Button, snapshot: &T
         snapshot.icon_name.as_ref()
     };
 
+    // An icon name mapped in `theme.icons.map` wins
+    if let Some(mapped) = icon_name.and_then(|name| mapped_gtk_icon_name(name)) {
+        image.set_icon_name(Some(&mapped));
+        return;
+    }
+
     // Try pixmap first, then icon name, then fallback
     if let Some(pixmap) = pixmap
         && let Some(texture) = get_cached_texture(state, pixmap)
@@ -400,15 +415,31 @@ fn update_button(state: &Rc<RefCell<WidgetState>>, button: &Button, snapshot: &T
         return;
     }
 
-    // Try loading from custom icon theme path if provided
+    // Try the item's own icon directory if provided, as a theme (with
+    // hicolor-style subdirectories) and then as plain files
     if let Some(name) = icon_name
         && !name.is_empty()
         && let Some(theme_path) = &snapshot.icon_theme_path
         && !theme_path.is_empty()
-        && let Some(texture) = load_icon_from_theme_path(theme_path, name)
     {
-        image.set_paintable(Some(&texture));
-        return;
+        if let Some(theme) = item_icon_theme(state, &snapshot.identifier, theme_path)
+            && theme.has_icon(name)
+        {
+            let paintable = theme.lookup_icon(
+                name,
+                &[],
+                image.pixel_size(),
+                image.scale_factor(),
+                gtk4::TextDirection::None,
+                gtk4::IconLookupFlags::empty(),
+            );
+            image.set_paintable(Some(&paintable));
+            return;
+        }
+        if let Some(texture) = load_icon_from_theme_path(theme_path, name) {
+            image.set_paintable(Some(&texture));
+            return;
+        }
     }
 
     if let Some(name) = icon_name
@@ -671,6 +702,45 @@ fn calculate_contrast_ratio(lum1: f64, lum2: f64) -> f64 {
 /// Load an icon from a custom theme path provided by the application.
 ///
 /// Tries common image extensions (.png, .svg, .xpm) to find the icon file.
+/// Icon theme for an item's `IconThemePath`: the current theme with the
+/// item's directory searched first.
+///
+/// Built once per item and path; `None` if the directory doesn't exist.
+fn item_icon_theme(
+    state: &Rc<RefCell<WidgetState>>,
+    identifier: &str,
+    theme_path: &str,
+) -> Option<IconTheme> {
+    if let Some((path, theme)) = state.borrow().item_themes.get(identifier)
+        && path == theme_path
+    {
+        return Some(theme.clone());
+    }
+
+    if !std::path::Path::new(theme_path).is_dir() {
+        return None;
+    }
+    let display = gdk::Display::default()?;
+    let display_theme = IconTheme::for_display(&display);
+
+    let theme = IconTheme::new();
+    theme.set_theme_name(Some(&display_theme.theme_name()));
+    let mut search_path = vec![std::path::PathBuf::from(theme_path)];
+    search_path.extend(display_theme.search_path());
+    let search_path: Vec<&std::path::Path> = search_path.iter().map(|p| p.as_path()).collect();
+    theme.set_search_path(&search_path);
+    debug!(
+        "Tray item {} uses icon theme path {}",
+        identifier, theme_path
+    );
+
+    state.borrow_mut().item_themes.insert(
+        identifier.to_string(),
+        (theme_path.to_string(), theme.clone()),
+    );
+    Some(theme)
+}
+
 fn load_icon_from_theme_path(theme_path: &str, icon_name: &str) -> Option<gdk::Texture> {
     use std::path::Path;
 
@@ -0,0 +1,38 @@
+//! Change-checked setters for bar widget updates.
+//!
+//! Polling services (system, clock, battery, ...) notify on every tick even
+//! when the displayed values haven't changed. Setting a label's text, even
+//! to the same string, makes GTK recompute its layout and queue a resize and
+//! redraw of the bar. These helpers compare against what is displayed first
+//! and leave the widget untouched when nothing changed, so a static bar
+//! stays idle.
+
+use gtk4::Label;
+use gtk4::prelude::*;
+
+/// Set a label's text if it differs from what is displayed.
+///
+/// Returns `true` if the label was updated.
+pub fn set_label_text(label: &Label, text: &str) -> bool {
+    if label.label() == text {
+        return false;
+    }
+    label.set_label(text);
+    true
+}
+
+/// Add or remove a CSS class if it isn't already in that state.
+///
+/// Returns `true` if the widget's classes changed.
+pub fn set_class(widget: &impl IsA<gtk4::Widget>, class: &str, enabled: bool) -> bool {
+    let widget = widget.as_ref();
+    if widget.has_css_class(class) == enabled {
+        return false;
+    }
+    if enabled {
+        widget.add_css_class(class);
+    } else {
+        widget.remove_css_class(class);
+    }
+    true
+}
@@ -1,28 +1,65 @@
 //! Updates widget - displays available package updates in the bar.
 //!
 //! This widget:
-//! - Shows an icon and count when updates are available
+//! - Shows an icon and count when updates are available (system + Flatpak)
 //! - Hides itself when there are no updates (and no errors)
 //! - Shows "!" when there's an error checking for updates
+//! - Shows a reboot badge when a newer kernel or systemd is installed than
+//!   the one running
 //! - Opens a terminal with the upgrade command on click
+//! - With remote machines configured, counts their updates too and opens a
+//!   popover with one row per machine instead
 //!
 //! Configuration options:
-//! - `check_interval`: How often to check for updates (seconds, default: 3600)
+//! - `interval`: How often to check for updates (seconds, default: 3600,
+//!   minimum 300); `check_interval` is accepted as an older name
 //! - `terminal`: Override terminal emulator detection
+//! - `notify_reboot`: Send a desktop notification when a reboot becomes
+//!   necessary (default: false)
+//! - `count_badge`: Show the update count as a badge on the icon instead of
+//!   a label next to it (default: false)
+//! - `badge_position`, `badge_color`, `badge_text_color`: Badge placement
+//!   and colors (see `BadgeConfig`)
+//! - `remotes`: Table of remote machines checked over SSH, keyed by display
+//!   name. Each entry has a `command` printing one line per pending update
+//!   and an optional `host` (SSH destination, defaults to the name). SSH
+//!   must work without a password prompt (keys or an agent).
+//!
+//! ```toml
+//! [widgets.updates.remotes]
+//! web-1 = { host = "admin@web-1.example.com", command = "checkupdates" }
+//! db-1 = { command = "apt list --upgradable 2>/dev/null | tail -n +2" }
+//! ```
+
+use std::rc::Rc;
 
+use gtk4::pango::EllipsizeMode;
 use gtk4::prelude::*;
-use gtk4::{GestureClick, Label};
+use gtk4::{Align, Box as GtkBox, Button, GestureClick, Label, Orientation, Widget};
+use tracing::warn;
 use vibepanel_core::config::WidgetEntry;
 
 use crate::services::icons::IconHandle;
 use crate::services::tooltip::TooltipManager;
-use crate::services::updates::{UpdatesService, UpdatesSnapshot};
-use crate::styles::{class, state, widget};
-use crate::widgets::base::BaseWidget;
-use crate::widgets::updates_common::{format_tooltip, icon_for_state, spawn_upgrade_terminal};
-use crate::widgets::{WidgetConfig, warn_unknown_options};
+use crate::services::updates::{
+    MIN_CHECK_INTERVAL, RemoteTarget, RemoteUpdates, UpdatesService, UpdatesSnapshot,
+};
+use crate::styles::{button, class, color, state, surface, updates, widget};
+use crate::widgets::base::{
+    BADGE_OPTIONS, BadgeConfig, BadgeHandle, BarWidget, BaseWidget, MenuHandle,
+};
+use crate::widgets::updates_common::{
+    format_last_check, format_remote_summary, format_repo_summary, format_tooltip, icon_for_state,
+    spawn_upgrade_terminal,
+};
+use crate::widgets::{WidgetConfig, parse_interval, update, warn_unknown_options};
 
 const DEFAULT_CHECK_INTERVAL: u64 = 3600;
+const DEFAULT_NOTIFY_REBOOT: bool = false;
+const DEFAULT_COUNT_BADGE: bool = false;
+
+/// Options accepted by each `remotes` entry.
+const REMOTE_OPTIONS: &[&str] = &["host", "command"];
 
 /// Configuration for the updates widget.
 #[derive(Debug, Clone)]
@@ -31,18 +68,38 @@ pub struct UpdatesConfig {
     pub check_interval: u64,
     /// Override terminal emulator detection.
     pub terminal: Option<String>,
+    /// Remote machines checked over SSH.
+    pub remotes: Vec<RemoteTarget>,
+    /// Notify when a reboot becomes necessary.
+    pub notify_reboot: bool,
+    /// Show the count as a badge on the icon.
+    pub count_badge: bool,
+    /// Badge placement and colors.
+    pub badge: BadgeConfig,
 }
 
 impl WidgetConfig for UpdatesConfig {
     fn from_entry(entry: &WidgetEntry) -> Self {
-        warn_unknown_options("updates", entry, &["check_interval", "terminal"]);
-
-        let check_interval = entry
-            .options
-            .get("check_interval")
-            .and_then(|v| v.as_integer())
-            .map(|v| v as u64)
-            .unwrap_or(DEFAULT_CHECK_INTERVAL);
+        let known: Vec<&str> = [
+            "interval",
+            "check_interval",
+            "terminal",
+            "remotes",
+            "notify_reboot",
+            "count_badge",
+        ]
+        .into_iter()
+        .chain(BADGE_OPTIONS.iter().copied())
+        .collect();
+        warn_unknown_options("updates", entry, &known);
+
+        let check_interval = parse_interval(
+            "updates",
+            |key| entry.options.get(key),
+            Some("check_interval"),
+            DEFAULT_CHECK_INTERVAL,
+            MIN_CHECK_INTERVAL,
+        );
 
         let terminal = entry
             .options
@@ -50,11 +107,67 @@ impl WidgetConfig for UpdatesConfig {
             .and_then(|v| v.as_str())
             .map(String::from);
 
+        let remotes = match entry.options.get("remotes") {
+            Some(toml::Value::Table(table)) => table
+                .iter()
+                .filter_map(|(name, value)| parse_remote(name, value))
+                .collect(),
+            Some(_) => {
+                warn!("updates widget: 'remotes' must be a table of remote machines");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        let notify_reboot = entry
+            .options
+            .get("notify_reboot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_NOTIFY_REBOOT);
+
+        let count_badge = entry
+            .options
+            .get("count_badge")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_COUNT_BADGE);
+
         Self {
             check_interval,
             terminal,
+            remotes,
+            notify_reboot,
+            count_badge,
+            badge: BadgeConfig::from_entry("updates", entry),
+        }
+    }
+}
+
+/// Parse one `remotes` entry; entries without a command are skipped.
+fn parse_remote(name: &str, value: &toml::Value) -> Option<RemoteTarget> {
+    let Some(table) = value.as_table() else {
+        warn!("updates widget: remote '{}' must be a table", name);
+        return None;
+    };
+    for key in table.keys() {
+        if !REMOTE_OPTIONS.contains(&key.as_str()) {
+            warn!(
+                "Unknown option '{}' for updates remote '{}' - possible typo?",
+                key, name
+            );
         }
     }
+
+    let Some(command) = table.get("command").and_then(|v| v.as_str()) else {
+        warn!("updates widget: remote '{}' has no command, skipping", name);
+        return None;
+    };
+    let host = table.get("host").and_then(|v| v.as_str()).unwrap_or(name);
+
+    Some(RemoteTarget {
+        name: name.to_string(),
+        host: host.to_string(),
+        command: command.to_string(),
+    })
 }
 
 impl Default for UpdatesConfig {
@@ -62,6 +175,10 @@ impl Default for UpdatesConfig {
         Self {
             check_interval: DEFAULT_CHECK_INTERVAL,
             terminal: None,
+            remotes: Vec::new(),
+            notify_reboot: DEFAULT_NOTIFY_REBOOT,
+            count_badge: DEFAULT_COUNT_BADGE,
+            badge: BadgeConfig::default(),
         }
     }
 }
@@ -74,8 +191,14 @@ pub struct UpdatesWidget {
     icon_handle: IconHandle,
     /// Label showing update count or "!".
     count_label: Label,
+    /// Count badge on the icon, replacing the label when `count_badge` is set.
+    count_badge: Option<BadgeHandle>,
+    /// Badge shown when a reboot is required.
+    reboot_handle: IconHandle,
     /// Terminal override from config.
     terminal: Option<String>,
+    /// Popover listing machines, when remotes are configured.
+    _menu: Option<Rc<MenuHandle>>,
 }
 
 impl UpdatesWidget {
@@ -84,25 +207,42 @@ impl UpdatesWidget {
         let base = BaseWidget::new(&[widget::UPDATES]);
         base.set_tooltip("Updates: checking...");
 
-        // Mark as clickable since we have a custom click handler
-        base.widget().add_css_class(state::CLICKABLE);
-
         let icon_handle = base.add_icon("software-update-available", &[widget::UPDATES_ICON]);
         let count_label = base.add_label(None, &[widget::UPDATES_COUNT, class::VCENTER_CAPS]);
-
-        // Configure the service with our interval
+        let count_badge = config.count_badge.then(|| {
+            count_label.set_visible(false);
+            base.add_badge(&icon_handle, &config.badge, &[widget::UPDATES_BADGE])
+        });
+        let reboot_handle = base.add_icon("system-reboot-symbolic", &[widget::UPDATES_REBOOT_ICON]);
+        reboot_handle.widget().set_visible(false);
+
+        // Configure the service with our interval and remotes
         let service = UpdatesService::global();
         service.set_check_interval(config.check_interval);
+        service.set_notify_on_reboot(config.notify_reboot);
+        let has_remotes = !config.remotes.is_empty();
+        service.set_remotes(config.remotes);
+
+        let menu = has_remotes.then(|| {
+            let terminal = Rc::new(config.terminal.clone());
+            base.create_menu(move || build_updates_popover(&terminal))
+        });
 
         let widget = Self {
             base,
             icon_handle,
             count_label,
+            count_badge,
+            reboot_handle,
             terminal: config.terminal,
+            _menu: menu.clone(),
         };
 
-        // Set up click handler to spawn terminal
-        {
+        // Without remotes, a click goes straight to the upgrade terminal
+        if !has_remotes {
+            // Mark as clickable since we have a custom click handler
+            widget.base.widget().add_css_class(state::CLICKABLE);
+
             let terminal = widget.terminal.clone();
             let container = widget.base.widget().clone();
 
@@ -123,9 +263,21 @@ impl UpdatesWidget {
             let container = widget.base.widget().clone();
             let icon_handle = widget.icon_handle.clone();
             let count_label = widget.count_label.clone();
+            let count_badge = widget.count_badge.clone();
+            let reboot_handle = widget.reboot_handle.clone();
 
             service.connect(move |snapshot: &UpdatesSnapshot| {
-                update_widget_from_snapshot(&container, &icon_handle, &count_label, snapshot);
+                update_widget_from_snapshot(
+                    &container,
+                    &icon_handle,
+                    &count_label,
+                    count_badge.as_ref(),
+                    &reboot_handle,
+                    snapshot,
+                );
+                if let Some(ref menu) = menu {
+                    menu.refresh_if_visible();
+                }
             });
         }
 
@@ -138,33 +290,48 @@ impl UpdatesWidget {
     }
 }
 
+impl BarWidget for UpdatesWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
 /// Update the widget's visual state from a snapshot.
 fn update_widget_from_snapshot(
     container: &gtk4::Box,
     icon_handle: &IconHandle,
     count_label: &Label,
+    count_badge: Option<&BadgeHandle>,
+    reboot_handle: &IconHandle,
     snapshot: &UpdatesSnapshot,
 ) {
-    // Handle unavailable state (no package manager)
-    if !snapshot.available {
+    // Handle unavailable state (no package manager and no remotes)
+    if !snapshot.available && snapshot.remotes.is_empty() {
         container.set_visible(false);
         return;
     }
 
-    // Determine visibility: show only if updates available OR error
-    let should_show = snapshot.update_count > 0 || snapshot.error.is_some();
+    // Determine visibility: show only if updates available, error, or a
+    // reboot is pending (usually right after upgrading, with no updates left)
+    let count = snapshot.total_update_count() + snapshot.remote_update_count();
+    let has_error = snapshot.error.is_some() || snapshot.has_remote_error();
+    let reboot_required = snapshot.reboot_required.is_some();
+    let should_show = count > 0 || has_error || reboot_required;
     container.set_visible(should_show);
 
     if !should_show {
         return;
     }
 
+    update::set_class(container, widget::UPDATES_REBOOT, reboot_required);
+    reboot_handle.widget().set_visible(reboot_required);
+
     // Update CSS classes
     container.remove_css_class(widget::UPDATES_ERROR);
     container.remove_css_class(widget::UPDATES_CHECKING);
     icon_handle.remove_css_class(widget::UPDATES_ERROR);
 
-    if snapshot.error.is_some() {
+    if has_error {
         container.add_css_class(widget::UPDATES_ERROR);
         icon_handle.add_css_class(widget::UPDATES_ERROR);
     } else if snapshot.checking {
@@ -175,11 +342,29 @@ fn update_widget_from_snapshot(
     let icon_name = icon_for_state(snapshot);
     icon_handle.set_icon(icon_name);
 
-    // Update label: show "!" for error, count otherwise
-    if snapshot.error.is_some() {
-        count_label.set_label("!");
+    // Only the reboot badge when there's nothing else to report
+    let show_count = count > 0 || has_error;
+    icon_handle.widget().set_visible(show_count);
+
+    // Update label: show "!" for a local error, count otherwise (an
+    // unreachable remote shouldn't hide the others' counts). The badge
+    // shows a dot instead of "!".
+    let show_error = snapshot.error.is_some() || count == 0;
+    if let Some(badge) = count_badge {
+        if !show_count {
+            badge.hide();
+        } else if show_error {
+            badge.set_dot(true);
+        } else {
+            badge.set_count(count);
+        }
     } else {
-        count_label.set_label(&snapshot.update_count.to_string());
+        count_label.set_visible(show_count);
+        if show_error {
+            update::set_label_text(count_label, "!");
+        } else {
+            update::set_label_text(count_label, &count.to_string());
+        }
     }
 
     // Update tooltip
@@ -188,9 +373,114 @@ fn update_widget_from_snapshot(
     tooltip_manager.set_styled_tooltip(container, &tooltip);
 }
 
+/// Build the popover content listing this machine and the remotes.
+fn build_updates_popover(terminal: &Rc<Option<String>>) -> Widget {
+    let snapshot = UpdatesService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 4);
+    container.add_css_class(updates::POPOVER);
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    let title = Label::new(Some("Updates"));
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    title.set_hexpand(true);
+    header.append(&title);
+
+    let refresh = Button::with_label("Refresh");
+    refresh.add_css_class(button::GHOST);
+    refresh.connect_clicked(|_| UpdatesService::global().refresh());
+    header.append(&refresh);
+    container.append(&header);
+
+    if snapshot.available {
+        let row = build_row(
+            "This machine",
+            &format_repo_summary(&snapshot),
+            snapshot.error.is_some(),
+            snapshot.total_update_count() > 0,
+        );
+
+        // Clicking the local row opens the upgrade terminal
+        let row_button = Button::new();
+        row_button.add_css_class(button::RESET);
+        row_button.add_css_class(updates::ROW);
+        row_button.set_child(Some(&row));
+        if let Some(pm) = snapshot.package_manager {
+            TooltipManager::global().set_styled_tooltip(&row_button, "Upgrade in a terminal");
+            let terminal = terminal.clone();
+            row_button.connect_clicked(move |_| {
+                if let Err(e) = spawn_upgrade_terminal(pm, terminal.as_deref()) {
+                    tracing::error!("Failed to spawn upgrade terminal: {}", e);
+                }
+            });
+        }
+        container.append(&row_button);
+    }
+
+    if let Some(ref reason) = snapshot.reboot_required {
+        let reboot = Label::new(Some(&format!("Reboot required: {}", reason)));
+        reboot.add_css_class(updates::REBOOT);
+        reboot.set_halign(Align::Start);
+        reboot.set_xalign(0.0);
+        reboot.set_wrap(true);
+        container.append(&reboot);
+    }
+
+    for remote in &snapshot.remotes {
+        let row = build_row(
+            &remote.target.name,
+            &format_remote_summary(remote),
+            remote.error.is_some(),
+            remote.update_count > 0,
+        );
+        row.add_css_class(updates::ROW);
+        TooltipManager::global().set_styled_tooltip(&row, &remote_tooltip(remote));
+        container.append(&row);
+    }
+
+    container.upcast()
+}
+
+/// Build a machine row with its name and status.
+fn build_row(name: &str, status: &str, error: bool, pending: bool) -> GtkBox {
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    update::set_class(&row, updates::ROW_PENDING, pending && !error);
+
+    let name = Label::new(Some(name));
+    name.add_css_class(updates::ROW_NAME);
+    name.set_halign(Align::Start);
+    name.set_hexpand(true);
+    name.set_xalign(0.0);
+    name.set_ellipsize(EllipsizeMode::End);
+    name.set_max_width_chars(24);
+    row.append(&name);
+
+    let state = Label::new(Some(status));
+    state.add_css_class(updates::ROW_STATUS);
+    state.add_css_class(if error { color::ERROR } else { color::MUTED });
+    state.set_halign(Align::End);
+    row.append(&state);
+
+    row
+}
+
+/// Tooltip for a remote row: destination, last check and any error.
+fn remote_tooltip(remote: &RemoteUpdates) -> String {
+    let mut lines = vec![
+        remote.target.host.clone(),
+        format!("Last check: {}", format_last_check(remote.last_check)),
+    ];
+    if let Some(ref err) = remote.error {
+        lines.push(format!("Error: {}", err));
+    }
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_harness;
 
     #[test]
     fn test_updates_config_defaults() {
@@ -202,6 +492,9 @@ mod tests {
 
         assert_eq!(config.check_interval, DEFAULT_CHECK_INTERVAL);
         assert!(config.terminal.is_none());
+        assert!(config.remotes.is_empty());
+        assert!(!config.notify_reboot);
+        assert!(!config.count_badge);
     }
 
     #[test]
@@ -222,4 +515,132 @@ mod tests {
         assert_eq!(config.check_interval, 1800);
         assert_eq!(config.terminal, Some("ghostty".to_string()));
     }
+
+    #[test]
+    fn test_updates_config_interval() {
+        let mut options = std::collections::HashMap::new();
+        options.insert("interval".to_string(), toml::Value::Integer(7200));
+        options.insert("check_interval".to_string(), toml::Value::Integer(1800));
+        let entry = WidgetEntry {
+            name: "updates".to_string(),
+            options,
+        };
+        // `interval` wins over the older name
+        assert_eq!(UpdatesConfig::from_entry(&entry).check_interval, 7200);
+
+        let mut options = std::collections::HashMap::new();
+        options.insert("interval".to_string(), toml::Value::Integer(10));
+        let entry = WidgetEntry {
+            name: "updates".to_string(),
+            options,
+        };
+        assert_eq!(
+            UpdatesConfig::from_entry(&entry).check_interval,
+            MIN_CHECK_INTERVAL
+        );
+    }
+
+    #[test]
+    fn test_updates_config_remotes() {
+        let remotes: toml::Value = toml::from_str(
+            r#"
+            web-1 = { host = "admin@web-1.lan", command = "checkupdates" }
+            db-1 = { command = "apt list --upgradable" }
+            broken = { host = "broken.lan" }
+            "#,
+        )
+        .unwrap();
+
+        let mut options = std::collections::HashMap::new();
+        options.insert("remotes".to_string(), remotes);
+        let config = UpdatesConfig::from_entry(&WidgetEntry {
+            name: "updates".to_string(),
+            options,
+        });
+
+        // Entries without a command are skipped; host defaults to the name
+        assert_eq!(
+            config.remotes,
+            vec![
+                RemoteTarget {
+                    name: "db-1".to_string(),
+                    host: "db-1".to_string(),
+                    command: "apt list --upgradable".to_string(),
+                },
+                RemoteTarget {
+                    name: "web-1".to_string(),
+                    host: "admin@web-1.lan".to_string(),
+                    command: "checkupdates".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_updates_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = UpdatesWidget::new(UpdatesConfig {
+                check_interval: DEFAULT_CHECK_INTERVAL,
+                terminal: None,
+                remotes: Vec::new(),
+                notify_reboot: DEFAULT_NOTIFY_REBOOT,
+                count_badge: DEFAULT_COUNT_BADGE,
+                badge: BadgeConfig::default(),
+            });
+            let service = UpdatesService::global();
+            let ready = UpdatesSnapshot {
+                available: true,
+                is_ready: true,
+                ..UpdatesSnapshot::unknown()
+            };
+
+            service.publish_for_test(UpdatesSnapshot {
+                update_count: 4,
+                ..ready.clone()
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.updates.widget.widget-item",
+                    "icon.updates-icon",
+                    r#"label.updates-count.vcenter-caps "4""#,
+                ],
+            );
+
+            service.publish_for_test(UpdatesSnapshot {
+                error: Some("checkupdates failed".to_string()),
+                ..ready.clone()
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.updates.updates-error.widget.widget-item",
+                    "icon.updates-error.updates-icon",
+                    r#"label.updates-count.vcenter-caps "!""#,
+                ],
+            );
+
+            // Only a pending reboot: the reboot icon alone
+            service.publish_for_test(UpdatesSnapshot {
+                reboot_required: Some("Kernel 6.12 installed".to_string()),
+                ..ready.clone()
+            });
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &[
+                    "box.clickable.updates.updates-reboot.widget.widget-item",
+                    "icon.updates-reboot-icon",
+                ],
+            );
+            assert!(
+                !rendered.contains("icon.updates-icon"),
+                "rendered tree:\n{rendered}"
+            );
+
+            // Nothing pending: hidden
+            service.publish_for_test(ready);
+            assert_eq!(test_harness::render(widget.widget()), "");
+        });
+    }
 }
@@ -13,7 +13,8 @@ use std::time::SystemTime;
 use gtk4::glib;
 use tracing::{debug, error, warn};
 
-use crate::services::updates::{PackageManager, UpdatesService, UpdatesSnapshot};
+use crate::services::actions::{ActionKind, Actions};
+use crate::services::updates::{PackageManager, RemoteUpdates, UpdatesService, UpdatesSnapshot};
 
 /// Get the appropriate icon name based on snapshot state.
 pub fn icon_for_state(snapshot: &UpdatesSnapshot) -> &'static str {
@@ -28,16 +29,54 @@ pub fn icon_for_state(snapshot: &UpdatesSnapshot) -> &'static str {
 ///
 /// Example output:
 /// ```text
-/// 12 updates available
+/// 14 updates available
 /// core: 3
 /// extra: 5
 /// aur: 4
+/// flatpak: 2
 ///
 /// Last check: 5 minutes ago
+///
+/// Reboot required
+/// Kernel 6.9.2-arch1-1 installed (running 6.9.1-arch1-1)
+///
+/// web-1: 3 updates
+/// db-1: Unreachable
 /// ```
 pub fn format_tooltip(snapshot: &UpdatesSnapshot) -> String {
     let mut lines = Vec::new();
 
+    if snapshot.available {
+        format_local_tooltip(snapshot, &mut lines);
+    }
+
+    if let Some(ref reason) = snapshot.reboot_required {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push("Reboot required".to_string());
+        lines.push(reason.clone());
+    }
+
+    // One line per remote machine
+    if !snapshot.remotes.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        for remote in &snapshot.remotes {
+            lines.push(format!(
+                "{}: {}",
+                remote.target.name,
+                format_remote_summary(remote)
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Append the local machine's section of the tooltip.
+fn format_local_tooltip(snapshot: &UpdatesSnapshot, lines: &mut Vec<String>) {
     // Header
     if let Some(ref err) = snapshot.error {
         lines.push("Update check failed".to_string());
@@ -45,15 +84,16 @@ pub fn format_tooltip(snapshot: &UpdatesSnapshot) -> String {
         lines.push(format!("Error: {}", err));
     } else if snapshot.checking {
         lines.push("Checking for updates...".to_string());
-    } else if snapshot.update_count == 0 {
+    } else if snapshot.total_update_count() == 0 {
         lines.push("System is up to date".to_string());
     } else {
-        let s = if snapshot.update_count == 1 { "" } else { "s" };
-        lines.push(format!("{} update{} available", snapshot.update_count, s));
+        let count = snapshot.total_update_count();
+        let s = if count == 1 { "" } else { "s" };
+        lines.push(format!("{} update{} available", count, s));
     }
 
     // Repo breakdown (only if updates are available)
-    if snapshot.update_count > 0 && snapshot.error.is_none() {
+    if snapshot.total_update_count() > 0 && snapshot.error.is_none() {
         // Sort repos for consistent display
         let mut repos: Vec<_> = snapshot.updates_by_repo.iter().collect();
         repos.sort_by_key(|(name, _)| *name);
@@ -61,6 +101,11 @@ pub fn format_tooltip(snapshot: &UpdatesSnapshot) -> String {
         for (repo, updates) in repos {
             lines.push(format!("{}: {}", repo, updates.len()));
         }
+
+        // Flatpaks are listed after system repos
+        if !snapshot.flatpak_updates.is_empty() {
+            lines.push(format!("flatpak: {}", snapshot.flatpak_updates.len()));
+        }
     }
 
     // Last check time
@@ -77,8 +122,6 @@ pub fn format_tooltip(snapshot: &UpdatesSnapshot) -> String {
             format_last_check(snapshot.last_check)
         ));
     }
-
-    lines.join("\n")
 }
 
 /// Format a repo summary for subtitle text.
@@ -93,12 +136,32 @@ pub fn format_repo_summary(snapshot: &UpdatesSnapshot) -> String {
         return "Checking...".to_string();
     }
 
-    if snapshot.update_count == 0 {
+    let count = snapshot.total_update_count();
+    if count == 0 {
         return "Up to date".to_string();
     }
 
-    let s = if snapshot.update_count == 1 { "" } else { "s" };
-    format!("{} update{}", snapshot.update_count, s)
+    let s = if count == 1 { "" } else { "s" };
+    format!("{} update{}", count, s)
+}
+
+/// Format a remote machine's status for subtitle text.
+///
+/// Example: "3 updates", "Up to date" or "Unreachable"
+pub fn format_remote_summary(remote: &RemoteUpdates) -> String {
+    if remote.error.is_some() {
+        return "Unreachable".to_string();
+    }
+
+    if remote.checking && remote.last_check.is_none() {
+        return "Checking...".to_string();
+    }
+
+    match remote.update_count {
+        0 => "Up to date".to_string(),
+        1 => "1 update".to_string(),
+        count => format!("{} updates", count),
+    }
 }
 
 /// Format a human-readable "last checked" string.
@@ -179,6 +242,34 @@ fn which_exists(cmd: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Build a command that runs `args` inside `terminal`.
+///
+/// Handles the differing "execute" syntax of common terminal emulators.
+pub fn terminal_command(terminal: &str, args: &[&str]) -> Command {
+    // Get the terminal name (without path)
+    let term_name = terminal.split('/').next_back().unwrap_or(terminal);
+
+    let mut command = Command::new(terminal);
+    match term_name {
+        // Terminals that don't need -e flag
+        "foot" | "kitty" => {}
+        // gnome-terminal uses -- instead of -e
+        "gnome-terminal" => {
+            command.arg("--");
+        }
+        // wezterm has its own syntax
+        "wezterm" => {
+            command.args(["start", "--"]);
+        }
+        // Most terminals use -e
+        _ => {
+            command.arg("-e");
+        }
+    }
+    command.args(args);
+    command
+}
+
 /// Spawn a terminal with the upgrade command.
 ///
 /// Returns an error message if spawning fails.
@@ -192,6 +283,7 @@ pub fn spawn_upgrade_terminal(
         .ok_or_else(|| "No terminal emulator found".to_string())?;
 
     let upgrade_cmd = package_manager.upgrade_command();
+    Actions::global().permit(ActionKind::Exec, upgrade_cmd)?;
 
     // Build the shell command that runs upgrade and waits for user input
     let shell_cmd = format!(
@@ -204,39 +296,10 @@ pub fn spawn_upgrade_terminal(
         terminal, upgrade_cmd
     );
 
-    // Get the terminal name (without path)
-    let term_name = terminal.split('/').next_back().unwrap_or(&terminal);
-
-    // Build command based on terminal type
-    let result = match term_name {
-        // Terminals that don't need -e flag
-        "foot" | "kitty" => Command::new(&terminal)
-            .args(["sh", "-c", &shell_cmd])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn(),
-
-        // gnome-terminal uses -- instead of -e
-        "gnome-terminal" => Command::new(&terminal)
-            .args(["--", "sh", "-c", &shell_cmd])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn(),
-
-        // wezterm has its own syntax
-        "wezterm" => Command::new(&terminal)
-            .args(["start", "--", "sh", "-c", &shell_cmd])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn(),
-
-        // Most terminals use -e
-        _ => Command::new(&terminal)
-            .args(["-e", "sh", "-c", &shell_cmd])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn(),
-    };
+    let result = terminal_command(&terminal, &["sh", "-c", &shell_cmd])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
 
     match result {
         Ok(mut child) => {
@@ -297,6 +360,12 @@ mod tests {
             updates_by_repo: by_repo,
             last_check: Some(SystemTime::now()),
             package_manager: Some(PackageManager::Paru),
+            flatpak_available: false,
+            flatpak_updates: Vec::new(),
+            flatpak_progress: None,
+            flatpak_error: None,
+            remotes: Vec::new(),
+            reboot_required: None,
         }
     }
 
@@ -326,6 +395,12 @@ mod tests {
             updates_by_repo: HashMap::new(),
             last_check: Some(SystemTime::now()),
             package_manager: Some(PackageManager::Paru),
+            flatpak_available: false,
+            flatpak_updates: Vec::new(),
+            flatpak_progress: None,
+            flatpak_error: None,
+            remotes: Vec::new(),
+            reboot_required: None,
         };
 
         let tooltip = format_tooltip(&snapshot);
@@ -343,6 +418,12 @@ mod tests {
             updates_by_repo: HashMap::new(),
             last_check: None,
             package_manager: Some(PackageManager::Paru),
+            flatpak_available: false,
+            flatpak_updates: Vec::new(),
+            flatpak_progress: None,
+            flatpak_error: None,
+            remotes: Vec::new(),
+            reboot_required: None,
         };
 
         let tooltip = format_tooltip(&snapshot);
@@ -361,6 +442,18 @@ mod tests {
         assert_eq!(summary, "3 updates");
     }
 
+    #[test]
+    fn test_format_summary_includes_flatpaks() {
+        let mut snapshot = make_snapshot(vec![("official", vec!["linux"])]);
+        snapshot.flatpak_available = true;
+        snapshot.flatpak_updates = vec![crate::services::updates::UpdateInfo {
+            name: "org.mozilla.firefox".to_string(),
+        }];
+
+        assert_eq!(format_repo_summary(&snapshot), "2 updates");
+        assert!(format_tooltip(&snapshot).contains("flatpak: 1"));
+    }
+
     #[test]
     fn test_format_repo_summary_single_repo() {
         let snapshot = make_snapshot(vec![("official", vec!["linux", "firefox"])]);
@@ -377,6 +470,46 @@ mod tests {
         assert_eq!(summary, "1 update");
     }
 
+    #[test]
+    fn test_format_tooltip_remotes() {
+        use crate::services::updates::RemoteTarget;
+
+        let remote = |name: &str, count, error: Option<&str>| RemoteUpdates {
+            target: RemoteTarget {
+                name: name.to_string(),
+                host: name.to_string(),
+                command: "checkupdates".to_string(),
+            },
+            checking: false,
+            update_count: count,
+            error: error.map(String::from),
+            last_check: Some(SystemTime::now()),
+        };
+
+        let mut snapshot = UpdatesSnapshot::unknown();
+        snapshot.remotes = vec![
+            remote("web-1", 3, None),
+            remote("db-1", 0, Some("Connection timed out")),
+        ];
+
+        // No local package manager: only the remote lines
+        assert_eq!(
+            format_tooltip(&snapshot),
+            "web-1: 3 updates\ndb-1: Unreachable"
+        );
+        assert_eq!(format_remote_summary(&snapshot.remotes[0]), "3 updates");
+    }
+
+    #[test]
+    fn test_format_tooltip_reboot_required() {
+        let mut snapshot = make_snapshot(vec![]);
+        snapshot.reboot_required = Some("Kernel 6.9.2 installed (running 6.9.1)".to_string());
+
+        let tooltip = format_tooltip(&snapshot);
+        assert!(tooltip.starts_with("System is up to date"));
+        assert!(tooltip.ends_with("Reboot required\nKernel 6.9.2 installed (running 6.9.1)"));
+    }
+
     #[test]
     fn test_format_last_check() {
         assert_eq!(format_last_check(None), "Never");
@@ -0,0 +1,818 @@
+//! Weather widget - current conditions and forecast.
+//!
+//! This widget:
+//! - Shows the current temperature with a condition icon
+//! - Opens a popover with current details, an hourly forecast and a daily
+//!   forecast for the week
+//! - Dims when the last update failed (the previous forecast stays shown)
+//! - Tracks several named locations, switchable in the popover and
+//!   optionally rotated through on the bar
+//!
+//! Configuration options:
+//! - `location`: Place name to look up, e.g. "Berlin" (default: GeoClue)
+//! - `latitude` / `longitude`: Coordinates; take precedence over `location`
+//! - `locations`: List of locations instead of the single one above, each a
+//!   table with `name`, `location` or `latitude`/`longitude`, and optionally
+//!   its own `interval`
+//! - `units`: "metric" or "imperial" (default: "metric")
+//! - `provider`: Weather provider (default: "open-meteo")
+//! - `interval`: How often to update, in seconds (default: 900, minimum
+//!   300); `update_interval` is accepted as an older name
+//! - `rotate_interval`: Cycle the bar through the locations every N seconds
+//!   (default: 0, disabled)
+//! - `show_icon`: Whether to show the condition icon (default: true)
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use chrono::{DateTime, Local};
+use gtk4::glib::{self, SourceId};
+use gtk4::prelude::*;
+use gtk4::{Align, Box as GtkBox, Button, Label, Orientation, Widget};
+use tracing::warn;
+use vibepanel_core::config::WidgetEntry;
+
+use crate::services::icons::{IconHandle, IconsService};
+use crate::services::tooltip::TooltipManager;
+use crate::services::weather::{
+    DEFAULT_UPDATE_INTERVAL, Forecast, LocationSettings, LocationWeather, MIN_UPDATE_INTERVAL,
+    ProviderKind, Units, WeatherLocation, WeatherService, WeatherSettings, WeatherSnapshot,
+};
+use crate::services::widget_settings::SettingsId;
+use crate::styles::{button, class, color, surface, weather, widget};
+use crate::widgets::base::{BarWidget, BaseWidget, MenuHandle};
+use crate::widgets::{WidgetConfig, parse_interval, update, warn_unknown_options};
+
+const DEFAULT_SHOW_ICON: bool = true;
+
+/// Options of a `locations` entry.
+const LOCATION_OPTIONS: &[&str] = &[
+    "name",
+    "location",
+    "latitude",
+    "longitude",
+    "interval",
+    "update_interval",
+];
+
+/// Configuration for the weather widget.
+#[derive(Debug, Clone)]
+pub struct WeatherConfig {
+    /// Locations to track (at least one).
+    pub locations: Vec<LocationSettings>,
+    /// Unit system.
+    pub units: Units,
+    /// Weather provider.
+    pub provider: ProviderKind,
+    /// Seconds between bar rotations (0 = off).
+    pub rotate_interval: u64,
+    /// Whether to show the icon.
+    pub show_icon: bool,
+}
+
+impl WidgetConfig for WeatherConfig {
+    fn from_entry(entry: &WidgetEntry) -> Self {
+        warn_unknown_options(
+            "weather",
+            entry,
+            &[
+                "location",
+                "latitude",
+                "longitude",
+                "locations",
+                "units",
+                "provider",
+                "interval",
+                "update_interval",
+                "rotate_interval",
+                "show_icon",
+            ],
+        );
+
+        let update_interval = parse_interval(
+            "weather",
+            |key| entry.options.get(key),
+            Some("update_interval"),
+            DEFAULT_UPDATE_INTERVAL,
+            MIN_UPDATE_INTERVAL,
+        );
+
+        let mut locations = Vec::new();
+        if let Some(values) = entry.options.get("locations").and_then(|v| v.as_array()) {
+            for value in values {
+                let Some(table) = value.as_table() else {
+                    warn!("weather widget: `locations` entries must be tables");
+                    continue;
+                };
+                for key in table.keys() {
+                    if !LOCATION_OPTIONS.contains(&key.as_str()) {
+                        warn!(
+                            "weather widget: unknown option '{}' in `locations` - possible typo?",
+                            key
+                        );
+                    }
+                }
+                locations.push(LocationSettings {
+                    name: table
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty()),
+                    location: parse_location(|key| table.get(key)),
+                    update_interval: parse_interval(
+                        "weather",
+                        |key| table.get(key),
+                        Some("update_interval"),
+                        update_interval,
+                        MIN_UPDATE_INTERVAL,
+                    ),
+                });
+            }
+        }
+        if locations.is_empty() {
+            locations.push(LocationSettings {
+                name: None,
+                location: parse_location(|key| entry.options.get(key)),
+                update_interval,
+            });
+        }
+
+        let units = match entry.options.get("units").and_then(|v| v.as_str()) {
+            Some(value) => Units::parse(value).unwrap_or_else(|| {
+                warn!("weather widget: unknown units '{}', using metric", value);
+                Units::Metric
+            }),
+            None => Units::Metric,
+        };
+
+        let provider = match entry.options.get("provider").and_then(|v| v.as_str()) {
+            Some(value) => ProviderKind::parse(value).unwrap_or_else(|| {
+                warn!(
+                    "weather widget: unknown provider '{}', using open-meteo",
+                    value
+                );
+                ProviderKind::default()
+            }),
+            None => ProviderKind::default(),
+        };
+
+        let rotate_interval = entry
+            .options
+            .get("rotate_interval")
+            .and_then(|v| v.as_integer())
+            .map(|v| v.max(0) as u64)
+            .unwrap_or(0);
+
+        let show_icon = entry
+            .options
+            .get("show_icon")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(DEFAULT_SHOW_ICON);
+
+        Self {
+            locations,
+            units,
+            provider,
+            rotate_interval,
+            show_icon,
+        }
+    }
+}
+
+/// Read a location from `location` or `latitude`/`longitude` options.
+fn parse_location<'a>(get: impl Fn(&str) -> Option<&'a toml::Value>) -> WeatherLocation {
+    // Accept both `latitude = 52` and `latitude = 52.52`
+    let coordinate = |key: &str| {
+        get(key).and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+    };
+    let place = get("location")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    match (coordinate("latitude"), coordinate("longitude")) {
+        (Some(latitude), Some(longitude)) => WeatherLocation::Coordinates {
+            latitude,
+            longitude,
+        },
+        (latitude, longitude) => {
+            if latitude.is_some() || longitude.is_some() {
+                warn!("weather widget: set both `latitude` and `longitude`");
+            }
+            match place {
+                Some(place) => WeatherLocation::Place(place),
+                None => WeatherLocation::Auto,
+            }
+        }
+    }
+}
+
+/// Bar display state shared by the service callback, the rotation timer and
+/// the popover switcher.
+struct WeatherView {
+    container: gtk4::Box,
+    icon_handle: IconHandle,
+    label: Label,
+    /// Index of the location shown on the bar and in the popover.
+    selected: Cell<usize>,
+}
+
+impl WeatherView {
+    /// Show location `index`, wrapping around to the first one.
+    fn select(&self, index: usize, snapshot: &WeatherSnapshot) {
+        let index = if index < snapshot.locations.len() {
+            index
+        } else {
+            0
+        };
+        self.selected.set(index);
+        self.update(snapshot);
+    }
+
+    /// Update the bar from a snapshot.
+    fn update(&self, snapshot: &WeatherSnapshot) {
+        let Some(location) = snapshot.locations.get(self.selected.get()) else {
+            return;
+        };
+
+        update::set_class(
+            &self.container,
+            widget::WEATHER_STALE,
+            location.error.is_some(),
+        );
+
+        let Some(ref forecast) = location.forecast else {
+            update::set_label_text(&self.label, "--");
+            let tooltip = match location.error {
+                Some(ref error) => format!("Weather: {}", error),
+                None => "Weather: updating...".to_string(),
+            };
+            TooltipManager::global().set_styled_tooltip(&self.container, &tooltip);
+            return;
+        };
+
+        let current = &forecast.current;
+        self.icon_handle
+            .set_icon(current.condition.icon(current.is_day));
+        let degrees = format_degrees(current.temperature);
+        // Name the location when there are several to tell apart
+        let text = match location.name {
+            Some(ref name) if snapshot.locations.len() > 1 => format!("{} {}", name, degrees),
+            _ => degrees,
+        };
+        update::set_label_text(&self.label, &text);
+        TooltipManager::global().set_styled_tooltip(
+            &self.container,
+            &format_tooltip(location, forecast, snapshot.units),
+        );
+    }
+}
+
+/// Weather widget that displays the current temperature.
+pub struct WeatherWidget {
+    /// Shared base widget container.
+    base: BaseWidget,
+    /// Forecast popover.
+    _menu: Rc<MenuHandle>,
+    /// Timer cycling the bar through the locations.
+    rotate_source: Option<SourceId>,
+    /// This widget's settings registered with the service.
+    settings_id: SettingsId,
+}
+
+impl WeatherWidget {
+    /// Create a new weather widget with the given configuration.
+    pub fn new(config: WeatherConfig) -> Self {
+        let base = BaseWidget::new(&[widget::WEATHER]);
+        base.set_tooltip("Weather: updating...");
+
+        let icon_handle = base.add_icon("weather-few-clouds-symbolic", &[widget::WEATHER_ICON]);
+        icon_handle.widget().set_visible(config.show_icon);
+        let label = base.add_label(Some("--"), &[widget::WEATHER_LABEL, class::VCENTER_CAPS]);
+
+        let view = Rc::new(WeatherView {
+            container: base.widget().clone(),
+            icon_handle,
+            label,
+            selected: Cell::new(0),
+        });
+
+        let menu = {
+            let view = Rc::clone(&view);
+            base.create_menu(move || build_weather_popover(&view))
+        };
+
+        let rotate_source = (config.rotate_interval > 0 && config.locations.len() > 1).then(|| {
+            let view = Rc::clone(&view);
+            let menu = Rc::clone(&menu);
+            glib::timeout_add_seconds_local(config.rotate_interval as u32, move || {
+                // Don't switch while the user is looking at the popover
+                if !menu.is_visible() {
+                    let snapshot = WeatherService::global().snapshot();
+                    view.select(view.selected.get() + 1, &snapshot);
+                }
+                glib::ControlFlow::Continue
+            })
+        });
+
+        let service = WeatherService::global();
+        let settings_id = service.configure(WeatherSettings {
+            locations: config.locations,
+            units: config.units,
+            provider: config.provider,
+        });
+
+        {
+            let view = Rc::clone(&view);
+            let menu = Rc::clone(&menu);
+            service.connect(move |snapshot: &WeatherSnapshot| {
+                view.select(view.selected.get(), snapshot);
+                menu.refresh_if_visible();
+            });
+        }
+
+        Self {
+            base,
+            _menu: menu,
+            rotate_source,
+            settings_id,
+        }
+    }
+
+    /// Get the root GTK widget for embedding in the bar.
+    pub fn widget(&self) -> &gtk4::Box {
+        self.base.widget()
+    }
+}
+
+impl BarWidget for WeatherWidget {
+    fn base(&self) -> &BaseWidget {
+        &self.base
+    }
+}
+
+impl Drop for WeatherWidget {
+    fn drop(&mut self) {
+        if let Some(source_id) = self.rotate_source.take() {
+            source_id.remove();
+        }
+        WeatherService::global().release(self.settings_id);
+    }
+}
+
+/// Format a temperature for the bar, e.g. "12°".
+fn format_degrees(temperature: f64) -> String {
+    // Avoid "-0°" for values just below zero
+    let rounded = temperature.round();
+    format!("{}°", if rounded == 0.0 { 0.0 } else { rounded })
+}
+
+/// Format a temperature with its unit, e.g. "12°C".
+fn format_temperature(temperature: f64, units: Units) -> String {
+    format!(
+        "{}{}",
+        format_degrees(temperature).trim_end_matches('°'),
+        units.temperature_symbol()
+    )
+}
+
+/// Format the tooltip for the current conditions.
+fn format_tooltip(location: &LocationWeather, forecast: &Forecast, units: Units) -> String {
+    let current = &forecast.current;
+    let mut lines = vec![format!(
+        "{}, {}",
+        current.condition.description(),
+        format_temperature(current.temperature, units)
+    )];
+    if let Some(apparent) = current.apparent_temperature {
+        lines.push(format!(
+            "Feels like {}",
+            format_temperature(apparent, units)
+        ));
+    }
+    if let Some(title) = location.title() {
+        lines.push(title.to_string());
+    }
+    if let Some(ref error) = location.error {
+        lines.push(format!("Update failed: {}", error));
+    }
+    lines.join("\n")
+}
+
+/// Build the forecast popover content.
+fn build_weather_popover(view: &Rc<WeatherView>) -> Widget {
+    let snapshot = WeatherService::global().snapshot();
+
+    let container = GtkBox::new(Orientation::Vertical, 8);
+    container.add_css_class(weather::POPOVER);
+
+    let title = Label::new(None);
+    title.add_css_class(surface::POPOVER_TITLE);
+    title.set_halign(Align::Start);
+    container.append(&title);
+
+    // Location switcher; picking a location also shows it on the bar
+    let switcher = GtkBox::new(Orientation::Horizontal, 4);
+    switcher.add_css_class(weather::SWITCHER);
+    switcher.set_visible(snapshot.locations.len() > 1);
+    container.append(&switcher);
+
+    let body = GtkBox::new(Orientation::Vertical, 8);
+    container.append(&body);
+
+    let buttons: Vec<Button> = snapshot
+        .locations
+        .iter()
+        .map(|location| {
+            let button = Button::with_label(location.title().unwrap_or("Weather"));
+            button.add_css_class(button::GHOST);
+            button.add_css_class(weather::SWITCHER_BUTTON);
+            button.set_focus_on_click(false);
+            switcher.append(&button);
+            button
+        })
+        .collect();
+
+    let show: Rc<dyn Fn(usize)> = {
+        // Weak refs: the buttons' click handlers own this closure
+        let buttons: Vec<_> = buttons.iter().map(|b| b.downgrade()).collect();
+        let view = Rc::clone(view);
+        Rc::new(move |index| {
+            let snapshot = WeatherService::global().snapshot();
+            view.select(index, &snapshot);
+            let selected = view.selected.get();
+            for (i, button) in buttons.iter().enumerate() {
+                if let Some(button) = button.upgrade() {
+                    update::set_class(&button, weather::SWITCHER_SELECTED, i == selected);
+                }
+            }
+
+            let location = snapshot
+                .locations
+                .get(selected)
+                .cloned()
+                .unwrap_or_default();
+            title.set_label(location.title().unwrap_or("Weather"));
+            while let Some(child) = body.first_child() {
+                body.remove(&child);
+            }
+            build_location_body(&body, &location, snapshot.units);
+        })
+    };
+    for (index, button) in buttons.iter().enumerate() {
+        let show = Rc::clone(&show);
+        button.connect_clicked(move |_| show(index));
+    }
+    show(view.selected.get());
+
+    container.upcast()
+}
+
+/// Fill `container` with the conditions and forecast for one location.
+fn build_location_body(container: &GtkBox, location: &LocationWeather, units: Units) {
+    let icons = IconsService::global();
+
+    let Some(ref forecast) = location.forecast else {
+        let empty = Label::new(Some(location.error.as_deref().unwrap_or("Updating...")));
+        empty.add_css_class(color::MUTED);
+        empty.set_halign(Align::Start);
+        empty.set_wrap(true);
+        container.append(&empty);
+        return;
+    };
+
+    // Current conditions
+    let current = &forecast.current;
+    let current_box = GtkBox::new(Orientation::Horizontal, 12);
+    current_box.add_css_class(weather::CURRENT);
+    let current_icon = icons.create_icon(
+        current.condition.icon(current.is_day),
+        &[weather::CURRENT_ICON],
+    );
+    current_icon.widget().set_valign(Align::Center);
+    current_box.append(&current_icon.widget());
+
+    let current_text = GtkBox::new(Orientation::Vertical, 2);
+    let temperature = Label::new(Some(&format_temperature(current.temperature, units)));
+    temperature.add_css_class(weather::CURRENT_TEMPERATURE);
+    temperature.set_halign(Align::Start);
+    current_text.append(&temperature);
+
+    let mut details = vec![current.condition.description().to_string()];
+    if let Some(apparent) = current.apparent_temperature {
+        details.push(format!(
+            "feels like {}",
+            format_temperature(apparent, units)
+        ));
+    }
+    let condition = Label::new(Some(&details.join(", ")));
+    condition.set_halign(Align::Start);
+    current_text.append(&condition);
+
+    let mut extra = Vec::new();
+    if let Some(humidity) = current.humidity {
+        extra.push(format!("Humidity {}%", humidity));
+    }
+    if let Some(wind_speed) = current.wind_speed {
+        extra.push(format!(
+            "Wind {:.0} {}",
+            wind_speed,
+            units.wind_speed_unit()
+        ));
+    }
+    if !extra.is_empty() {
+        let extra = Label::new(Some(&extra.join(" · ")));
+        extra.add_css_class(color::MUTED);
+        extra.set_halign(Align::Start);
+        current_text.append(&extra);
+    }
+    current_box.append(&current_text);
+    container.append(&current_box);
+
+    // Hourly forecast
+    if !forecast.hourly.is_empty() {
+        container.append(&section_title("Hourly"));
+        let hours = GtkBox::new(Orientation::Horizontal, 0);
+        hours.add_css_class(weather::HOURLY);
+        hours.set_homogeneous(true);
+        for hour in &forecast.hourly {
+            let column = GtkBox::new(Orientation::Vertical, 2);
+            column.add_css_class(weather::HOUR);
+
+            let time = Label::new(Some(&hour.time.format("%H").to_string()));
+            time.add_css_class(color::MUTED);
+            column.append(&time);
+
+            let icon = icons.create_icon(hour.condition.icon(hour.is_day), &[weather::HOUR_ICON]);
+            icon.widget().set_halign(Align::Center);
+            column.append(&icon.widget());
+
+            let temperature = Label::new(Some(&format_degrees(hour.temperature)));
+            column.append(&temperature);
+
+            let precipitation =
+                Label::new(Some(&format_precipitation(hour.precipitation_probability)));
+            precipitation.add_css_class(weather::PRECIPITATION);
+            column.append(&precipitation);
+
+            hours.append(&column);
+        }
+        container.append(&hours);
+    }
+
+    // Daily forecast
+    if !forecast.daily.is_empty() {
+        container.append(&section_title("This week"));
+        let today = Local::now().date_naive();
+        for day in &forecast.daily {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            row.add_css_class(weather::DAY);
+
+            let name = if day.date == today {
+                "Today".to_string()
+            } else {
+                day.date.format("%a").to_string()
+            };
+            let name = Label::new(Some(&name));
+            name.add_css_class(weather::DAY_NAME);
+            name.set_xalign(0.0);
+            row.append(&name);
+
+            let icon = icons.create_icon(day.condition.icon(true), &[weather::DAY_ICON]);
+            row.append(&icon.widget());
+
+            let description = Label::new(Some(day.condition.description()));
+            description.add_css_class(color::MUTED);
+            description.set_hexpand(true);
+            description.set_xalign(0.0);
+            row.append(&description);
+
+            let precipitation =
+                Label::new(Some(&format_precipitation(day.precipitation_probability)));
+            precipitation.add_css_class(weather::PRECIPITATION);
+            row.append(&precipitation);
+
+            let range = Label::new(Some(&format!(
+                "{} / {}",
+                format_degrees(day.min),
+                format_degrees(day.max)
+            )));
+            range.add_css_class(weather::DAY_RANGE);
+            range.set_xalign(1.0);
+            row.append(&range);
+
+            container.append(&row);
+        }
+    }
+
+    // Last update / error
+    let footer = match (&location.error, location.updated) {
+        (Some(error), _) => format!("Update failed: {}", error),
+        (None, Some(updated)) => format!(
+            "Updated {}",
+            DateTime::<Local>::from(updated).format("%H:%M")
+        ),
+        (None, None) => String::new(),
+    };
+    if !footer.is_empty() {
+        let footer_label = Label::new(Some(&footer));
+        footer_label.add_css_class(weather::FOOTER);
+        footer_label.add_css_class(if location.error.is_some() {
+            color::ERROR
+        } else {
+            color::MUTED
+        });
+        footer_label.set_halign(Align::Start);
+        footer_label.set_wrap(true);
+        container.append(&footer_label);
+    }
+}
+
+fn section_title(text: &str) -> Label {
+    let label = Label::new(Some(text));
+    label.add_css_class(weather::SECTION_TITLE);
+    label.set_halign(Align::Start);
+    label
+}
+
+/// Chance of precipitation, hidden below 10% to keep the forecast calm.
+fn format_precipitation(probability: Option<u8>) -> String {
+    match probability {
+        Some(probability) if probability >= 10 => format!("{}%", probability),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::weather::{Condition, CurrentWeather};
+    use crate::test_harness;
+    use crate::widgets::test_entry;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_weather_config_location() {
+        let config = WeatherConfig::from_entry(&test_entry("weather", HashMap::new()));
+        assert_eq!(config.locations.len(), 1);
+        assert_eq!(config.locations[0].location, WeatherLocation::Auto);
+        assert_eq!(config.locations[0].update_interval, DEFAULT_UPDATE_INTERVAL);
+        assert_eq!(config.units, Units::Metric);
+
+        let mut options = HashMap::new();
+        options.insert(
+            "location".to_string(),
+            toml::Value::String("Berlin".to_string()),
+        );
+        options.insert(
+            "units".to_string(),
+            toml::Value::String("imperial".to_string()),
+        );
+        let config = WeatherConfig::from_entry(&test_entry("weather", options.clone()));
+        assert_eq!(
+            config.locations[0].location,
+            WeatherLocation::Place("Berlin".to_string())
+        );
+        assert_eq!(config.units, Units::Imperial);
+
+        // Coordinates take precedence over the place name
+        options.insert("latitude".to_string(), toml::Value::Float(52.52));
+        options.insert("longitude".to_string(), toml::Value::Integer(13));
+        let config = WeatherConfig::from_entry(&test_entry("weather", options));
+        assert_eq!(
+            config.locations[0].location,
+            WeatherLocation::Coordinates {
+                latitude: 52.52,
+                longitude: 13.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_weather_config_locations() {
+        let options: HashMap<String, toml::Value> = toml::from_str(
+            r#"
+            update_interval = 1200
+            rotate_interval = 30
+            locations = [
+                { name = "Home", location = "Berlin" },
+                { name = "NYC", latitude = 40.71, longitude = -74.01, update_interval = 3600 },
+            ]
+            "#,
+        )
+        .unwrap();
+        let config = WeatherConfig::from_entry(&test_entry("weather", options));
+
+        assert_eq!(config.rotate_interval, 30);
+        assert_eq!(config.locations.len(), 2);
+        assert_eq!(config.locations[0].name.as_deref(), Some("Home"));
+        assert_eq!(
+            config.locations[0].location,
+            WeatherLocation::Place("Berlin".to_string())
+        );
+        assert_eq!(config.locations[0].update_interval, 1200);
+        assert_eq!(
+            config.locations[1].location,
+            WeatherLocation::Coordinates {
+                latitude: 40.71,
+                longitude: -74.01
+            }
+        );
+        assert_eq!(config.locations[1].update_interval, 3600);
+    }
+
+    #[test]
+    fn test_weather_config_interval() {
+        let options: HashMap<String, toml::Value> = toml::from_str(
+            r#"
+            interval = 1800
+            locations = [
+                { name = "Home", location = "Berlin" },
+                { name = "NYC", location = "New York", interval = 60 },
+            ]
+            "#,
+        )
+        .unwrap();
+        let config = WeatherConfig::from_entry(&test_entry("weather", options));
+
+        assert_eq!(config.locations[0].update_interval, 1800);
+        // Below the provider-friendly minimum is raised to it
+        assert_eq!(config.locations[1].update_interval, MIN_UPDATE_INTERVAL);
+    }
+
+    #[test]
+    fn test_format_degrees() {
+        assert_eq!(format_degrees(12.4), "12°");
+        assert_eq!(format_degrees(-0.3), "0°");
+        assert_eq!(format_degrees(-4.6), "-5°");
+        assert_eq!(format_temperature(21.5, Units::Imperial), "22°F");
+    }
+
+    #[test]
+    fn test_weather_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = WeatherWidget::new(WeatherConfig {
+                // Fetches wait for a GeoClue fix, so the service stays offline
+                locations: vec![LocationSettings {
+                    name: None,
+                    location: WeatherLocation::Auto,
+                    update_interval: DEFAULT_UPDATE_INTERVAL,
+                }],
+                units: Units::Metric,
+                provider: ProviderKind::OpenMeteo,
+                rotate_interval: 0,
+                show_icon: true,
+            });
+            let service = WeatherService::global();
+            let forecast = Forecast {
+                current: CurrentWeather {
+                    temperature: 12.4,
+                    apparent_temperature: None,
+                    humidity: None,
+                    wind_speed: None,
+                    condition: Condition::Clear,
+                    is_day: true,
+                },
+                hourly: Vec::new(),
+                daily: Vec::new(),
+            };
+            let snapshot = |forecast: Option<Forecast>, error: Option<&str>| WeatherSnapshot {
+                units: Units::Metric,
+                locations: vec![LocationWeather {
+                    name: None,
+                    ready: true,
+                    place: Some("Berlin, Germany".to_string()),
+                    forecast,
+                    error: error.map(str::to_string),
+                    updated: None,
+                }],
+            };
+
+            service.publish_for_test(snapshot(Some(forecast.clone()), None));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.weather.widget.widget-item",
+                    "icon.weather-icon",
+                    r#"label.vcenter-caps.weather-label "12°""#,
+                ],
+            );
+
+            // Failed refresh: the last forecast stays, marked stale
+            service.publish_for_test(snapshot(Some(forecast), Some("timed out")));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[
+                    "box.clickable.weather.weather-stale.widget.widget-item",
+                    r#"label.vcenter-caps.weather-label "12°""#,
+                ],
+            );
+
+            // No forecast at all
+            service.publish_for_test(snapshot(None, Some("timed out")));
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[r#"label.vcenter-caps.weather-label "--""#],
+            );
+        });
+    }
+}
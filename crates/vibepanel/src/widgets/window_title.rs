@@ -491,6 +491,8 @@ fn titlecase(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::compositor::{CompositorManager, WindowInfo};
+    use crate::test_harness;
     use std::collections::HashMap;
     use toml::Value;
 
@@ -651,4 +653,37 @@ mod tests {
         let cleaned = clean_title("Firefox — SoMe WeIrD CaSe", "Firefox");
         assert_eq!(cleaned, "SoMe WeIrD CaSe");
     }
+
+    #[test]
+    fn test_window_title_widget_renders_focused_window() {
+        test_harness::run(|| {
+            let widget = WindowTitleWidget::new(
+                WindowTitleConfig {
+                    template: "{title}".to_string(),
+                    show_app_fallback: false,
+                    show_icon: false,
+                    uppercase: false,
+                    ..WindowTitleConfig::default()
+                },
+                None,
+            );
+            let manager = CompositorManager::global();
+
+            manager.handle_window_update(WindowInfo {
+                title: "Inbox".to_string(),
+                ..WindowInfo::default()
+            });
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[r#"label.window-title-label "Inbox""#],
+            );
+
+            // No focused window
+            manager.handle_window_update(WindowInfo::default());
+            test_harness::assert_rendered(
+                &test_harness::render(widget.widget()),
+                &[&format!("label.window-title-label {DEFAULT_EMPTY_TEXT:?}")],
+            );
+        });
+    }
 }
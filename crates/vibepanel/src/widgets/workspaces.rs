@@ -2,18 +2,28 @@
 //!
 //! Shows occupied/active workspaces with visual indicators and CSS classes.
 //! Clicking on a workspace indicator switches to that workspace.
+//! Workspaces with a window playing sound get a small speaker badge (see
+//! `AudioWindowsService`).
+//!
+//! With `layout = "grid"`, compositors that arrange workspaces in a grid
+//! (Wayfire) get every cell shown as a numbered indicator, in the same rows
+//! and columns as on screen. Other compositors keep the row layout.
 
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-use gtk4::gdk::BUTTON_PRIMARY;
+use gtk4::gdk::{BUTTON_PRIMARY, BUTTON_SECONDARY};
 use gtk4::pango::EllipsizeMode;
 use gtk4::prelude::*;
-use gtk4::{Align, Box as GtkBox, GestureClick, Label};
+use gtk4::{Align, Box as GtkBox, GestureClick, Grid, Label, Overlay};
 use tracing::{debug, trace};
 use vibepanel_core::config::WidgetEntry;
 
+use crate::services::audio_windows::{AudioWindowsService, AudioWindowsSnapshot};
+use crate::services::callbacks::CallbackId;
+use crate::services::compositor::WorkspaceGrid;
+use crate::services::icons::{IconHandle, IconsService};
 use crate::services::tooltip::TooltipManager;
 use crate::services::workspace::{Workspace, WorkspaceService, WorkspaceServiceSnapshot};
 use crate::styles::{state, widget};
@@ -43,8 +53,28 @@ impl LabelType {
     }
 }
 
+/// How workspace indicators are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One row of occupied and active workspaces.
+    Row,
+    /// Every workspace as a numbered cell of the compositor's grid.
+    /// Falls back to `Row` when the compositor has no grid.
+    Grid,
+}
+
+impl Layout {
+    fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "grid" => Layout::Grid,
+            _ => Layout::Row,
+        }
+    }
+}
+
 const DEFAULT_LABEL_TYPE: LabelType = LabelType::None;
 const DEFAULT_SEPARATOR: &str = "";
+const DEFAULT_LAYOUT: Layout = Layout::Row;
 
 /// Configuration for the workspaces widget.
 #[derive(Debug, Clone)]
@@ -53,11 +83,13 @@ pub struct WorkspacesConfig {
     pub label_type: LabelType,
     /// Separator string between workspace indicators.
     pub separator: String,
+    /// How to arrange workspace indicators.
+    pub layout: Layout,
 }
 
 impl WidgetConfig for WorkspacesConfig {
     fn from_entry(entry: &WidgetEntry) -> Self {
-        warn_unknown_options("workspaces", entry, &["label_type", "separator"]);
+        warn_unknown_options("workspaces", entry, &["label_type", "separator", "layout"]);
 
         let label_type = entry
             .options
@@ -73,9 +105,17 @@ impl WidgetConfig for WorkspacesConfig {
             .unwrap_or(DEFAULT_SEPARATOR)
             .to_string();
 
+        let layout = entry
+            .options
+            .get("layout")
+            .and_then(|v| v.as_str())
+            .map(Layout::from_str)
+            .unwrap_or(DEFAULT_LAYOUT);
+
         Self {
             label_type,
             separator,
+            layout,
         }
     }
 }
@@ -85,14 +125,24 @@ impl Default for WorkspacesConfig {
         Self {
             label_type: DEFAULT_LABEL_TYPE,
             separator: DEFAULT_SEPARATOR.to_string(),
+            layout: DEFAULT_LAYOUT,
         }
     }
 }
 
+/// Widgets for one workspace indicator.
+struct Indicator {
+    label: Label,
+    /// Speaker badge, shown while a window on the workspace plays sound.
+    audio: IconHandle,
+}
+
 /// Workspaces widget that displays workspace indicators.
 pub struct WorkspacesWidget {
     /// Shared base widget container.
     base: BaseWidget,
+    /// AudioWindowsService callback registration.
+    audio_callback: Option<CallbackId>,
 }
 
 impl WorkspacesWidget {
@@ -111,34 +161,54 @@ impl WorkspacesWidget {
         // Use the content box provided by BaseWidget
         let workspace_container = base.content().clone();
 
-        // State shared with the callback (callback owns these via Rc).
-        let workspace_labels = Rc::new(RefCell::new(HashMap::new()));
+        // State shared with the callbacks (callbacks own these via Rc).
+        let workspace_indicators = Rc::new(RefCell::new(HashMap::new()));
         let current_ids = Rc::new(RefCell::new(Vec::new()));
+        let audio = Rc::new(RefCell::new(AudioWindowsSnapshot::default()));
         let label_type = config.label_type;
         let separator = config.separator;
+        let layout = config.layout;
 
         // Clone output_id for the debug message
         let output_id_debug = output_id.clone();
 
         // Connect to workspace service.
         // The callback owns its own Rc clones of the state.
+        let indicators = Rc::clone(&workspace_indicators);
+        let audio_for_workspaces = Rc::clone(&audio);
         WorkspaceService::global().connect(move |snapshot| {
             update_indicators(
                 &workspace_container,
-                &workspace_labels,
+                &indicators,
                 &current_ids,
                 label_type,
                 &separator,
+                layout,
                 snapshot,
                 output_id.as_deref(),
+                &audio_for_workspaces.borrow(),
             );
         });
 
+        let audio_callback =
+            AudioWindowsService::global().connect(move |snapshot: &AudioWindowsSnapshot| {
+                *audio.borrow_mut() = snapshot.clone();
+                for (id, indicator) in workspace_indicators.borrow().iter() {
+                    indicator
+                        .audio
+                        .widget()
+                        .set_visible(snapshot.workspace_playing(*id));
+                }
+            });
+
         debug!(
             "WorkspacesWidget created (output_id: {:?})",
             output_id_debug
         );
-        Self { base }
+        Self {
+            base,
+            audio_callback: Some(audio_callback),
+        }
     }
 
     /// Get the root GTK widget for embedding in the bar.
@@ -147,6 +217,14 @@ impl WorkspacesWidget {
     }
 }
 
+impl Drop for WorkspacesWidget {
+    fn drop(&mut self) {
+        if let Some(callback_id) = self.audio_callback.take() {
+            AudioWindowsService::global().disconnect(callback_id);
+        }
+    }
+}
+
 /// Icon glyphs for workspace indicators.
 const ICON_OCCUPIED: &str = "●";
 const ICON_EMPTY: &str = "○";
@@ -155,30 +233,42 @@ const ICON_ACTIVE: &str = "◆";
 /// Clear all workspace indicator widgets from the container.
 fn clear_indicators(
     container: &GtkBox,
-    labels: &Rc<RefCell<HashMap<i32, Label>>>,
+    indicators: &Rc<RefCell<HashMap<i32, Indicator>>>,
     ids: &Rc<RefCell<Vec<i32>>>,
 ) {
     while let Some(child) = container.first_child() {
         container.remove(&child);
     }
-    labels.borrow_mut().clear();
+    indicators.borrow_mut().clear();
     ids.borrow_mut().clear();
 }
 
 /// Create workspace indicator labels for the given workspaces.
 fn create_indicators(
     container: &GtkBox,
-    labels_cell: &Rc<RefCell<HashMap<i32, Label>>>,
+    indicators_cell: &Rc<RefCell<HashMap<i32, Indicator>>>,
     ids_cell: &Rc<RefCell<Vec<i32>>>,
     label_type: LabelType,
     separator: &str,
+    grid: Option<WorkspaceGrid>,
     workspaces: &[Workspace],
 ) {
-    clear_indicators(container, labels_cell, ids_cell);
+    clear_indicators(container, indicators_cell, ids_cell);
 
-    let mut labels = labels_cell.borrow_mut();
+    let mut indicators = indicators_cell.borrow_mut();
     let mut ids = ids_cell.borrow_mut();
 
+    // Grid cells are placed at their position in the compositor's grid
+    let cells = grid.map(|grid| {
+        let cells = Grid::new();
+        cells.add_css_class(widget::WORKSPACE_GRID);
+        cells.set_valign(Align::Center);
+        cells.set_row_homogeneous(true);
+        cells.set_column_homogeneous(true);
+        container.append(&cells);
+        (cells, grid.columns as i32)
+    });
+
     for (i, workspace) in workspaces.iter().enumerate() {
         let label_text = match label_type {
             LabelType::Icons => ICON_EMPTY,
@@ -198,23 +288,49 @@ fn create_indicators(
             label.add_css_class(widget::WORKSPACE_INDICATOR_MINIMAL);
         }
 
-        // Add click handler to switch workspace
+        // Left click switches workspace, right click toggles it alongside
+        // the visible ones (River/DWL-style tags)
         let workspace_id = workspace.id;
         let gesture = GestureClick::new();
-        gesture.set_button(BUTTON_PRIMARY);
-        gesture.connect_released(move |gesture, _n_press, _x, _y| {
-            if gesture.current_button() != BUTTON_PRIMARY {
-                return;
-            }
-            debug!("Switching to workspace {}", workspace_id);
-            WorkspaceService::global().switch_workspace(workspace_id);
-        });
+        gesture.set_button(0);
+        gesture.connect_released(
+            move |gesture, _n_press, _x, _y| match gesture.current_button() {
+                BUTTON_PRIMARY => {
+                    debug!("Switching to workspace {}", workspace_id);
+                    WorkspaceService::global().switch_workspace(workspace_id);
+                }
+                BUTTON_SECONDARY => {
+                    debug!("Toggling workspace {}", workspace_id);
+                    WorkspaceService::global().toggle_workspace(workspace_id);
+                }
+                _ => {}
+            },
+        );
         label.add_controller(gesture);
 
-        labels.insert(workspace.id, label.clone());
-        container.append(&label);
+        let audio = IconsService::global()
+            .create_icon("audio-volume-high-symbolic", &[widget::WORKSPACE_AUDIO]);
+        let audio_widget = audio.widget();
+        audio_widget.set_halign(Align::End);
+        audio_widget.set_valign(Align::Start);
+        audio_widget.set_can_target(false);
+        audio_widget.set_visible(false);
+
+        let overlay = Overlay::new();
+        overlay.set_valign(Align::Center);
+        overlay.set_child(Some(&label));
+        overlay.add_overlay(&audio_widget);
+
+        indicators.insert(workspace.id, Indicator { label, audio });
         ids.push(workspace.id);
 
+        if let Some((cells, columns)) = &cells {
+            let index = workspace.id - 1;
+            cells.attach(&overlay, index % columns, index / columns, 1, 1);
+            continue;
+        }
+        container.append(&overlay);
+
         // Add separator if not the last workspace
         if i < workspaces.len() - 1 && !separator.is_empty() {
             let sep = Label::new(Some(separator));
@@ -231,14 +347,20 @@ fn create_indicators(
 /// - Uses per-output workspace data if available.
 /// - For Niri: shows only workspaces belonging to this output.
 /// - For MangoWC: shows all workspaces with per-output window counts.
+///
+/// With the grid layout and a compositor that reports a grid, every cell is
+/// shown, labeled with its number.
+#[allow(clippy::too_many_arguments)]
 fn update_indicators(
     container: &GtkBox,
-    labels_cell: &Rc<RefCell<HashMap<i32, Label>>>,
+    indicators_cell: &Rc<RefCell<HashMap<i32, Indicator>>>,
     ids_cell: &Rc<RefCell<Vec<i32>>>,
     label_type: LabelType,
     separator: &str,
+    layout: Layout,
     snapshot: &WorkspaceServiceSnapshot,
     output_id: Option<&str>,
+    audio: &AudioWindowsSnapshot,
 ) {
     // Get the workspace list to use - either per-output or global
     let (workspaces, active_workspaces, source): (&[Workspace], &HashSet<i32>, &str) = if let Some(
@@ -291,6 +413,15 @@ fn update_indicators(
     // Add all active workspaces to display (supports multi-tag view)
     display_ids.extend(active_workspaces.iter());
 
+    // The grid layout shows every cell, numbered
+    let grid = snapshot.grid.filter(|_| layout == Layout::Grid);
+    let label_type = if let Some(grid) = grid {
+        display_ids.extend(1..=(grid.columns * grid.rows) as i32);
+        LabelType::Numbers
+    } else {
+        label_type
+    };
+
     // Filter to only display relevant workspaces
     let display_workspaces: Vec<_> = workspaces
         .iter()
@@ -311,7 +442,7 @@ fn update_indicators(
         let current_ids = ids_cell.borrow();
         if !current_ids.is_empty() {
             drop(current_ids);
-            clear_indicators(container, labels_cell, ids_cell);
+            clear_indicators(container, indicators_cell, ids_cell);
         }
         return;
     }
@@ -321,20 +452,26 @@ fn update_indicators(
     if new_ids != *ids_cell.borrow() {
         create_indicators(
             container,
-            labels_cell,
+            indicators_cell,
             ids_cell,
             label_type,
             separator,
+            grid,
             &display_workspaces,
         );
     }
 
     // Update indicator styling
-    let labels = labels_cell.borrow();
+    let indicators = indicators_cell.borrow();
     for workspace in &display_workspaces {
-        let Some(label) = labels.get(&workspace.id) else {
+        let Some(indicator) = indicators.get(&workspace.id) else {
             continue;
         };
+        let label = &indicator.label;
+        indicator
+            .audio
+            .widget()
+            .set_visible(audio.workspace_playing(workspace.id));
 
         // Remove existing state classes
         label.remove_css_class(widget::ACTIVE);
@@ -407,6 +544,8 @@ fn build_tooltip(workspace: &Workspace) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::compositor::{WorkspaceMeta, WorkspaceSnapshot};
+    use crate::test_harness;
     use std::collections::HashMap;
     use toml::Value;
 
@@ -448,6 +587,18 @@ mod tests {
         assert_eq!(config.label_type, LabelType::None);
     }
 
+    #[test]
+    fn test_workspace_config_layout() {
+        let entry = make_widget_entry("workspaces", HashMap::new());
+        assert_eq!(WorkspacesConfig::from_entry(&entry).layout, Layout::Row);
+
+        let mut options = HashMap::new();
+        options.insert("layout".to_string(), Value::String("Grid".to_string()));
+        let entry = make_widget_entry("workspaces", options);
+        assert_eq!(WorkspacesConfig::from_entry(&entry).layout, Layout::Grid);
+        assert_eq!(Layout::from_str("unknown"), Layout::Row);
+    }
+
     #[test]
     fn test_label_type_from_str() {
         assert_eq!(LabelType::from_str("icons"), LabelType::Icons);
@@ -456,4 +607,43 @@ mod tests {
         assert_eq!(LabelType::from_str("none"), LabelType::None);
         assert_eq!(LabelType::from_str("unknown"), LabelType::Icons); // default
     }
+
+    #[test]
+    fn test_workspaces_widget_renders_snapshots() {
+        test_harness::run(|| {
+            let widget = WorkspacesWidget::new(
+                WorkspacesConfig {
+                    label_type: LabelType::Numbers,
+                    ..WorkspacesConfig::default()
+                },
+                None,
+            );
+            let metas: Vec<WorkspaceMeta> = (1..=3)
+                .map(|id| WorkspaceMeta {
+                    id,
+                    name: id.to_string(),
+                    output: None,
+                })
+                .collect();
+
+            WorkspaceService::global().publish_for_test(
+                metas,
+                WorkspaceSnapshot {
+                    active_workspace: HashSet::from([2]),
+                    occupied_workspaces: HashSet::from([1, 2]),
+                    ..WorkspaceSnapshot::default()
+                },
+            );
+            let rendered = test_harness::render(widget.widget());
+            test_harness::assert_rendered(
+                &rendered,
+                &[
+                    r#"label.clickable.occupied.workspace-indicator "1""#,
+                    r#"label.active.clickable.workspace-indicator "2""#,
+                ],
+            );
+            // Empty and inactive: not shown
+            assert!(!rendered.contains(r#""3""#), "rendered tree:\n{rendered}");
+        });
+    }
 }